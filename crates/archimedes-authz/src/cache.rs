@@ -1,6 +1,7 @@
 //! Decision caching for authorization.
 //!
 //! Caches policy decisions to avoid re-evaluating the same requests.
+//! Obligations are cached with the decision they came with.
 
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
@@ -8,8 +9,11 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::RwLock;
 use std::time::{Duration, Instant};
 
+use archimedes_core::Obligations;
 use themis_platform_types::{PolicyDecision, PolicyInput};
 
+use crate::evaluator::Decision;
+
 /// Configuration for the decision cache.
 #[derive(Debug, Clone)]
 pub struct CacheConfig {
@@ -92,14 +96,14 @@ impl CacheKey {
 /// Cached decision entry.
 #[derive(Debug, Clone)]
 struct CacheEntry {
-    /// The cached decision.
-    decision: PolicyDecision,
+    /// The cached decision and its obligations.
+    decision: Decision,
     /// When the entry was created.
     created_at: Instant,
 }
 
 impl CacheEntry {
-    fn new(decision: PolicyDecision) -> Self {
+    fn new(decision: Decision) -> Self {
         Self {
             decision,
             created_at: Instant::now(),
@@ -153,6 +157,12 @@ impl DecisionCache {
 
     /// Get a cached decision for the given input.
     pub fn get(&self, input: &PolicyInput) -> Option<PolicyDecision> {
+        self.get_with_obligations(input)
+            .map(|decision| decision.decision)
+    }
+
+    /// Get a cached decision for the given input with its obligations.
+    pub fn get_with_obligations(&self, input: &PolicyInput) -> Option<Decision> {
         if self.config.max_entries == 0 {
             self.misses.fetch_add(1, Ordering::Relaxed);
             return None;
@@ -174,6 +184,17 @@ impl DecisionCache {
 
     /// Insert a decision into the cache.
     pub fn insert(&self, input: &PolicyInput, decision: &PolicyDecision) {
+        self.insert_with_obligations(
+            input,
+            &Decision {
+                decision: decision.clone(),
+                obligations: Obligations::default(),
+            },
+        );
+    }
+
+    /// Insert a decision and its obligations into the cache.
+    pub fn insert_with_obligations(&self, input: &PolicyInput, decision: &Decision) {
         if self.config.max_entries == 0 {
             return;
         }
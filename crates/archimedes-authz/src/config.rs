@@ -11,6 +11,8 @@ pub struct EvaluatorConfig {
     pub default_policy_version: String,
     /// Query path for the allow decision.
     pub allow_query: String,
+    /// Query path for the obligations attached to the decision.
+    pub obligations_query: String,
     /// Whether to enable strict mode for Rego evaluation.
     pub strict_mode: bool,
     /// Maximum evaluation time in milliseconds.
@@ -25,6 +27,7 @@ impl Default for EvaluatorConfig {
             default_policy_id: "authz".to_string(),
             default_policy_version: "1.0.0".to_string(),
             allow_query: "data.authz.allow".to_string(),
+            obligations_query: "data.authz.obligations".to_string(),
             strict_mode: false,
             max_eval_time_ms: 100,
             cache_config: CacheConfig::default(),
@@ -56,6 +59,12 @@ impl EvaluatorConfig {
         self
     }
 
+    /// Set the obligations query path.
+    pub fn with_obligations_query(mut self, query: impl Into<String>) -> Self {
+        self.obligations_query = query.into();
+        self
+    }
+
     /// Enable or disable strict mode.
     pub fn with_strict_mode(mut self, strict: bool) -> Self {
         self.strict_mode = strict;
@@ -80,6 +89,7 @@ impl EvaluatorConfig {
             default_policy_id: "authz".to_string(),
             default_policy_version: "1.0.0".to_string(),
             allow_query: "data.authz.allow".to_string(),
+            obligations_query: "data.authz.obligations".to_string(),
             strict_mode: true,
             max_eval_time_ms: 50,
             cache_config: CacheConfig::production(),
@@ -92,6 +102,7 @@ impl EvaluatorConfig {
             default_policy_id: "authz".to_string(),
             default_policy_version: "dev".to_string(),
             allow_query: "data.authz.allow".to_string(),
+            obligations_query: "data.authz.obligations".to_string(),
             strict_mode: false,
             max_eval_time_ms: 500,
             cache_config: CacheConfig::development(),
@@ -108,6 +119,7 @@ mod tests {
         let config = EvaluatorConfig::default();
         assert_eq!(config.default_policy_id, "authz");
         assert_eq!(config.allow_query, "data.authz.allow");
+        assert_eq!(config.obligations_query, "data.authz.obligations");
         assert!(!config.strict_mode);
     }
 
@@ -2,18 +2,43 @@
 //!
 //! This module provides the core policy evaluation logic using the `regorus`
 //! crate, a pure Rust implementation of OPA.
+//!
+//! Policies often consult a `data` document, such as role assignments or
+//! feature flags, that changes independently of the policy code. It can be
+//! replaced with [`PolicyEvaluator::set_data`] or updated in place with
+//! [`PolicyEvaluator::merge_data`] without reloading the bundle.
+//!
+//! List endpoints can ask for the conditions under which the policy allows
+//! access with [`PolicyEvaluator::partial`] instead of checking each item;
+//! see [`crate::partial`] for the shape of the result.
+//!
+//! Besides allowing or denying, a policy can attach obligations at the
+//! obligations query (`data.authz.obligations` by default).
+//! [`PolicyEvaluator::evaluate_with_obligations`] returns them with the
+//! decision; every key the policy sets is kept.
 
 use std::path::Path;
 use std::time::Instant;
 
+use archimedes_core::Obligations;
 use regorus::Engine;
-use serde_json::Value;
+use serde_json::{Map, Value};
 use themis_platform_types::{PolicyDecision, PolicyInput};
 use tracing::{debug, info, instrument, warn};
 
 use crate::bundle::{Bundle, BundleLoader, BundleMetadata};
 use crate::config::EvaluatorConfig;
 use crate::error::{AuthzError, AuthzResult};
+use crate::partial::{self, PartialResult};
+
+/// A policy decision together with the obligations the policy attached.
+#[derive(Debug, Clone)]
+pub struct Decision {
+    /// Whether the request is allowed, and why not.
+    pub decision: PolicyDecision,
+    /// Obligations the service should honor when acting on the decision.
+    pub obligations: Obligations,
+}
 
 /// OPA/Rego policy evaluator.
 ///
@@ -26,6 +51,10 @@ pub struct PolicyEvaluator {
     config: EvaluatorConfig,
     /// Currently loaded bundle metadata.
     bundle_metadata: Option<BundleMetadata>,
+    /// The `data` document loaded into the engine.
+    data: Value,
+    /// Sources of the loaded policies, read by partial evaluation.
+    policies: Vec<String>,
 }
 
 impl PolicyEvaluator {
@@ -42,6 +71,8 @@ impl PolicyEvaluator {
             engine,
             config,
             bundle_metadata: None,
+            data: Value::Object(Map::new()),
+            policies: Vec::new(),
         })
     }
 
@@ -97,17 +128,21 @@ impl PolicyEvaluator {
         }
 
         // Load all data
+        let mut data = Value::Object(Map::new());
         for (path, content) in &bundle.data {
             debug!(path, "adding data");
             let regorus_value: regorus::Value = content.clone().into();
             engine.add_data(regorus_value).map_err(|e| {
                 AuthzError::Evaluation(format!("failed to load data {}: {}", path, e))
             })?;
+            merge_value(&mut data, content.clone());
         }
 
         let metadata = bundle.metadata.clone();
         self.engine = engine;
         self.bundle_metadata = Some(metadata.clone());
+        self.data = data;
+        self.policies = bundle.policies.into_values().collect();
 
         Ok(metadata)
     }
@@ -117,25 +152,81 @@ impl PolicyEvaluator {
         self.engine
             .add_policy(name.to_string(), source.to_string())
             .map_err(|e| AuthzError::Evaluation(format!("failed to add policy {}: {}", name, e)))?;
+        self.policies.push(source.to_string());
         Ok(())
     }
 
     /// Add data for policy evaluation.
     pub fn add_data(&mut self, data: Value) -> AuthzResult<()> {
-        let regorus_value: regorus::Value = data.into();
+        let regorus_value: regorus::Value = data.clone().into();
         self.engine
             .add_data(regorus_value)
             .map_err(|e| AuthzError::Evaluation(format!("failed to add data: {}", e)))?;
+        merge_value(&mut self.data, data);
         Ok(())
     }
 
+    /// Replace the `data` document without reloading the policies.
+    ///
+    /// The document is loaded into a copy of the engine that replaces the
+    /// current one only once loading succeeds. Each evaluation works on its
+    /// own copy of the engine, so it sees either the old document or the new
+    /// one, never a mix.
+    pub fn set_data(&mut self, data: Value) -> AuthzResult<()> {
+        if !data.is_object() {
+            return Err(AuthzError::InvalidInput(
+                "data document must be a JSON object".to_string(),
+            ));
+        }
+
+        let mut engine = self.engine.clone();
+        engine.clear_data();
+        engine
+            .add_data(data.clone().into())
+            .map_err(|e| AuthzError::Evaluation(format!("failed to set data: {}", e)))?;
+
+        self.engine = engine;
+        self.data = data;
+        Ok(())
+    }
+
+    /// Merge `value` into the `data` document at `path`.
+    ///
+    /// The path is slash-separated like OPA's data API, so `roles/admins`
+    /// addresses `data.roles.admins`; missing parents are created. Objects
+    /// are merged key by key and any other value replaces what was there.
+    pub fn merge_data(&mut self, path: &str, value: Value) -> AuthzResult<()> {
+        let mut data = self.data.clone();
+        let target = path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .fold(&mut data, child);
+        merge_value(target, value);
+        self.set_data(data)
+    }
+
+    /// Get the current `data` document.
+    pub fn data(&self) -> &Value {
+        &self.data
+    }
+
     /// Evaluate a policy decision for the given input.
+    pub fn evaluate(&self, input: &PolicyInput) -> AuthzResult<PolicyDecision> {
+        self.evaluate_with_obligations(input)
+            .map(|decision| decision.decision)
+    }
+
+    /// Evaluate a policy decision along with its obligations.
+    ///
+    /// Obligations are read from the obligations query only when the
+    /// request is allowed; a policy that does not define them, or defines
+    /// something other than an object, yields none.
     #[instrument(skip(self, input), fields(
         service = %input.service,
         operation_id = %input.operation_id,
         method = %input.method
     ))]
-    pub fn evaluate(&self, input: &PolicyInput) -> AuthzResult<PolicyDecision> {
+    pub fn evaluate_with_obligations(&self, input: &PolicyInput) -> AuthzResult<Decision> {
         let start = Instant::now();
 
         // Convert input to JSON for OPA
@@ -178,16 +269,77 @@ impl PolicyEvaluator {
         );
 
         let decision = if allowed {
-            PolicyDecision::allow(policy_id, policy_version).with_evaluation_time(elapsed_ns)
+            Decision {
+                decision: PolicyDecision::allow(policy_id, policy_version)
+                    .with_evaluation_time(elapsed_ns),
+                obligations: self.extract_obligations(&mut engine),
+            }
         } else {
             // Try to extract a denial reason
             let reason = self.extract_denial_reason(&mut engine);
-            PolicyDecision::deny(policy_id, policy_version, reason).with_evaluation_time(elapsed_ns)
+            Decision {
+                decision: PolicyDecision::deny(policy_id, policy_version, reason)
+                    .with_evaluation_time(elapsed_ns),
+                obligations: Obligations::default(),
+            }
         };
 
         Ok(decision)
     }
 
+    /// Partially evaluate the allow rule with the `unknowns` left open.
+    ///
+    /// `unknowns` are references into the input, such as `input.resource`,
+    /// whose values are not known yet, typically the rows a list endpoint
+    /// is about to fetch. The result is [`PartialResult::Allowed`] or
+    /// [`PartialResult::Denied`] if the decision does not depend on them,
+    /// and otherwise the conditions they must meet, for the handler to
+    /// turn into a query filter.
+    ///
+    /// Only a subset of Rego can be partially evaluated; see
+    /// [`crate::partial`].
+    #[instrument(skip(self, input), fields(
+        service = %input.service,
+        operation_id = %input.operation_id
+    ))]
+    pub fn partial(&self, input: &PolicyInput, unknowns: &[&str]) -> AuthzResult<PartialResult> {
+        let (package, rule) = partial::split_query(&self.config.allow_query).ok_or_else(|| {
+            AuthzError::Config(format!(
+                "allow query {} is not a rule reference",
+                self.config.allow_query
+            ))
+        })?;
+        let bodies: Vec<_> = self
+            .policies
+            .iter()
+            .flat_map(|source| partial::rule_bodies(source, package, rule))
+            .collect();
+        if bodies.is_empty() {
+            return Err(AuthzError::PolicyNotFound(self.config.allow_query.clone()));
+        }
+
+        let input_json = serde_json::to_value(input)
+            .map_err(|e| AuthzError::InvalidInput(format!("failed to serialize input: {}", e)))?;
+        let mut engine = self.engine.clone();
+        engine.set_input(input_json.into());
+
+        let mut queries = Vec::new();
+        for body in &bodies {
+            match partial::residual(&mut engine, body, unknowns)? {
+                Some(conditions) if conditions.is_empty() => return Ok(PartialResult::Allowed),
+                Some(conditions) => queries.push(conditions),
+                None => {}
+            }
+        }
+
+        debug!(queries = queries.len(), "partial evaluation complete");
+        if queries.is_empty() {
+            Ok(PartialResult::Denied)
+        } else {
+            Ok(PartialResult::Conditional(queries))
+        }
+    }
+
     /// Get the currently loaded bundle metadata.
     pub fn bundle_metadata(&self) -> Option<&BundleMetadata> {
         self.bundle_metadata.as_ref()
@@ -224,6 +376,19 @@ impl PolicyEvaluator {
         }
         "access denied by policy".to_string()
     }
+
+    fn extract_obligations(&self, engine: &mut Engine) -> Obligations {
+        let Ok(result) = engine.eval_query(self.config.obligations_query.clone(), false) else {
+            return Obligations::default();
+        };
+        result
+            .result
+            .iter()
+            .flat_map(|r| &r.expressions)
+            .find_map(|expr| serde_json::to_value(&expr.value).ok())
+            .map(Obligations::from_value)
+            .unwrap_or_default()
+    }
 }
 
 impl Clone for PolicyEvaluator {
@@ -232,10 +397,35 @@ impl Clone for PolicyEvaluator {
             engine: self.engine.clone(),
             config: self.config.clone(),
             bundle_metadata: self.bundle_metadata.clone(),
+            data: self.data.clone(),
+            policies: self.policies.clone(),
         }
     }
 }
 
+/// Get the member `key` of `node`, turning `node` into an object if needed.
+fn child<'a>(node: &'a mut Value, key: &str) -> &'a mut Value {
+    if !node.is_object() {
+        *node = Value::Object(Map::new());
+    }
+    match node {
+        Value::Object(map) => map.entry(key).or_insert(Value::Null),
+        _ => unreachable!("node was made an object above"),
+    }
+}
+
+/// Deep-merge `value` into `target`.
+fn merge_value(target: &mut Value, value: Value) {
+    match (target, value) {
+        (Value::Object(target), Value::Object(value)) => {
+            for (key, value) in value {
+                merge_value(target.entry(key).or_insert(Value::Null), value);
+            }
+        }
+        (target, value) => *target = value,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -314,12 +504,191 @@ mod tests {
         assert!(decision.allowed);
     }
 
+    #[test]
+    fn test_evaluate_with_obligations() {
+        let mut evaluator = PolicyEvaluator::with_defaults().unwrap();
+        evaluator
+            .add_policy(
+                "authz.rego",
+                r#"
+                package authz
+                allow if {
+                    input.method == "GET"
+                }
+                obligations := {"mask_fields": ["ssn"], "x-retention-days": 30}
+                "#,
+            )
+            .unwrap();
+
+        let input = create_test_input();
+        let decision = evaluator.evaluate_with_obligations(&input).unwrap();
+        assert!(decision.decision.allowed);
+        assert_eq!(
+            decision.obligations.get_as::<Vec<String>>("mask_fields"),
+            Some(vec!["ssn".to_string()])
+        );
+        assert_eq!(
+            decision.obligations.get("x-retention-days"),
+            Some(&serde_json::json!(30))
+        );
+
+        let mut evaluator = PolicyEvaluator::with_defaults().unwrap();
+        evaluator
+            .add_policy("authz.rego", "package authz\nallow = true")
+            .unwrap();
+        let decision = evaluator.evaluate_with_obligations(&input).unwrap();
+        assert!(decision.decision.allowed);
+        assert!(decision.obligations.is_empty());
+    }
+
     #[test]
     fn test_has_policy() {
         let evaluator = PolicyEvaluator::with_defaults().unwrap();
         assert!(!evaluator.has_policy());
     }
 
+    const METHOD_POLICY: &str = r#"
+        package authz
+        allow if {
+            data.open_methods[_] == input.method
+        }
+    "#;
+
+    #[test]
+    fn test_set_data_flips_decision() {
+        let mut evaluator = PolicyEvaluator::with_defaults().unwrap();
+        evaluator.add_policy("authz.rego", METHOD_POLICY).unwrap();
+        evaluator
+            .add_data(serde_json::json!({"open_methods": ["POST"]}))
+            .unwrap();
+
+        let input = create_test_input();
+        assert!(!evaluator.evaluate(&input).unwrap().allowed);
+
+        evaluator
+            .set_data(serde_json::json!({"open_methods": ["GET", "POST"]}))
+            .unwrap();
+        assert!(evaluator.evaluate(&input).unwrap().allowed);
+
+        evaluator.set_data(serde_json::json!({})).unwrap();
+        assert!(!evaluator.evaluate(&input).unwrap().allowed);
+        assert!(evaluator.set_data(serde_json::json!([1])).is_err());
+    }
+
+    #[test]
+    fn test_merge_data() {
+        let mut evaluator = PolicyEvaluator::with_defaults().unwrap();
+        evaluator.add_policy("authz.rego", METHOD_POLICY).unwrap();
+        evaluator
+            .add_data(serde_json::json!({"flags": {"beta": true}}))
+            .unwrap();
+
+        let input = create_test_input();
+        assert!(!evaluator.evaluate(&input).unwrap().allowed);
+
+        evaluator
+            .merge_data("open_methods", serde_json::json!(["GET"]))
+            .unwrap();
+        evaluator
+            .merge_data("flags/roles/admins", serde_json::json!(["alice"]))
+            .unwrap();
+
+        assert!(evaluator.evaluate(&input).unwrap().allowed);
+        assert_eq!(
+            evaluator.data(),
+            &serde_json::json!({
+                "open_methods": ["GET"],
+                "flags": {"beta": true, "roles": {"admins": ["alice"]}}
+            })
+        );
+    }
+
+    const OWNER_POLICY: &str = r#"
+        package authz
+
+        default allow := false
+
+        # Anyone may create
+        allow if {
+            input.method == "POST"
+        }
+
+        # Callers read their own resources
+        allow if {
+            input.method == "GET"
+            input.resource.owner == input.caller.user_id
+        }
+    "#;
+
+    fn input_with_method(method: &str) -> PolicyInput {
+        PolicyInput::builder()
+            .caller(CallerIdentity::user("user-123", "user@example.com"))
+            .service("test-service")
+            .operation_id("listDocuments")
+            .method(method)
+            .path("/documents")
+            .request_id(RequestId::new())
+            .try_build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_partial_residual() {
+        use crate::partial::{Comparison, Condition, Term};
+
+        let mut evaluator = PolicyEvaluator::with_defaults().unwrap();
+        evaluator.add_policy("authz.rego", OWNER_POLICY).unwrap();
+
+        let result = evaluator
+            .partial(&input_with_method("GET"), &["input.resource"])
+            .unwrap();
+        assert_eq!(
+            result,
+            PartialResult::Conditional(vec![vec![Condition {
+                left: Term::Unknown("input.resource.owner".to_string()),
+                op: Comparison::Eq,
+                right: Term::Value(serde_json::json!("user-123")),
+            }]])
+        );
+        assert_eq!(
+            serde_json::to_value(&result).unwrap(),
+            serde_json::json!({
+                "result": "conditional",
+                "queries": [[{
+                    "left": {"unknown": "input.resource.owner"},
+                    "op": "eq",
+                    "right": {"value": "user-123"}
+                }]]
+            })
+        );
+    }
+
+    #[test]
+    fn test_partial_shortcuts() {
+        let mut evaluator = PolicyEvaluator::with_defaults().unwrap();
+        evaluator.add_policy("authz.rego", OWNER_POLICY).unwrap();
+
+        let allowed = evaluator
+            .partial(&input_with_method("POST"), &["input.resource"])
+            .unwrap();
+        assert_eq!(allowed, PartialResult::Allowed);
+
+        let denied = evaluator
+            .partial(&input_with_method("DELETE"), &["input.resource"])
+            .unwrap();
+        assert_eq!(denied, PartialResult::Denied);
+        assert_eq!(
+            serde_json::to_value(&denied).unwrap(),
+            serde_json::json!({"result": "denied"})
+        );
+
+        let empty = PolicyEvaluator::with_defaults().unwrap();
+        assert!(matches!(
+            empty.partial(&input_with_method("GET"), &["input.resource"]),
+            Err(AuthzError::PolicyNotFound(_))
+        ));
+    }
+
     #[test]
     fn test_bundle_metadata() {
         let evaluator = PolicyEvaluator::with_defaults().unwrap();
@@ -9,6 +9,8 @@
 //! - Load and cache policy bundles from the registry
 //! - Evaluate authorization decisions using OPA/Rego
 //! - Cache decisions for performance
+//! - Pass the obligations a policy attaches to its decision on to handlers
+//! - Turn policies into filter conditions for list endpoints
 //! - Provide audit logging for compliance
 //!
 //! # Architecture
@@ -67,13 +69,15 @@ pub mod cache;
 pub mod config;
 pub mod error;
 pub mod evaluator;
+pub mod partial;
 
 // Re-exports for convenience
 pub use bundle::{Bundle, BundleLoader, BundleMetadata};
 pub use cache::{CacheConfig, DecisionCache};
 pub use config::EvaluatorConfig;
 pub use error::{AuthzError, AuthzResult};
-pub use evaluator::PolicyEvaluator;
+pub use evaluator::{Decision, PolicyEvaluator};
+pub use partial::{Comparison, Condition, PartialResult, Term};
 
 /// Main authorization service for Archimedes.
 ///
@@ -121,6 +125,24 @@ impl Authorizer {
         Ok(())
     }
 
+    /// Replace the policy `data` document.
+    ///
+    /// Cached decisions do not record which parts of the document they
+    /// read, so all of them are dropped.
+    pub fn set_data(&mut self, data: serde_json::Value) -> AuthzResult<()> {
+        self.evaluator.set_data(data)?;
+        self.cache.clear();
+        Ok(())
+    }
+
+    /// Merge a value into the policy `data` document at a slash-separated
+    /// path, dropping cached decisions as [`Authorizer::set_data`] does.
+    pub fn merge_data(&mut self, path: &str, value: serde_json::Value) -> AuthzResult<()> {
+        self.evaluator.merge_data(path, value)?;
+        self.cache.clear();
+        Ok(())
+    }
+
     /// Evaluate an authorization request.
     ///
     /// First checks the cache, then evaluates against the loaded policy.
@@ -128,8 +150,19 @@ impl Authorizer {
         &self,
         input: &themis_platform_types::PolicyInput,
     ) -> AuthzResult<themis_platform_types::PolicyDecision> {
+        self.authorize_with_obligations(input)
+            .await
+            .map(|decision| decision.decision)
+    }
+
+    /// Evaluate an authorization request, keeping the obligations the
+    /// policy attached to the decision.
+    pub async fn authorize_with_obligations(
+        &self,
+        input: &themis_platform_types::PolicyInput,
+    ) -> AuthzResult<Decision> {
         // Check cache first
-        if let Some(decision) = self.cache.get(input) {
+        if let Some(decision) = self.cache.get_with_obligations(input) {
             tracing::debug!(
                 operation_id = %input.operation_id,
                 cached = true,
@@ -139,11 +172,11 @@ impl Authorizer {
         }
 
         // Evaluate policy
-        let decision = self.evaluator.evaluate(input)?;
+        let decision = self.evaluator.evaluate_with_obligations(input)?;
 
         // Cache the decision
-        if self.cache.should_cache(&decision) {
-            self.cache.insert(input, &decision);
+        if self.cache.should_cache(&decision.decision) {
+            self.cache.insert_with_obligations(input, &decision);
         }
 
         Ok(decision)
@@ -184,4 +217,43 @@ mod tests {
         assert_eq!(stats.hits, 0);
         assert_eq!(stats.misses, 0);
     }
+
+    #[tokio::test]
+    async fn test_data_update_invalidates_cached_decision() {
+        use themis_platform_types::{CallerIdentity, PolicyInput, RequestId};
+
+        let mut evaluator = PolicyEvaluator::with_defaults().unwrap();
+        evaluator
+            .add_policy(
+                "authz.rego",
+                "package authz\nallow if { data.admins[_] == input.operation_id }",
+            )
+            .unwrap();
+        let mut authorizer = Authorizer::new(
+            evaluator,
+            DecisionCache::new(cache::CacheConfig::development()),
+        );
+        let input = PolicyInput::builder()
+            .caller(CallerIdentity::user("user-123", "user@example.com"))
+            .service("test-service")
+            .operation_id("deleteUser")
+            .method("DELETE")
+            .path("/users/1")
+            .request_id(RequestId::new())
+            .try_build()
+            .unwrap();
+
+        assert!(!authorizer.authorize(&input).await.unwrap().allowed);
+        assert!(!authorizer.authorize(&input).await.unwrap().allowed);
+        assert_eq!(authorizer.cache_stats().hits, 1);
+
+        authorizer
+            .merge_data("admins", serde_json::json!(["deleteUser"]))
+            .unwrap();
+        assert_eq!(authorizer.cache_stats().size, 0);
+        assert!(authorizer.authorize(&input).await.unwrap().allowed);
+
+        authorizer.set_data(serde_json::json!({})).unwrap();
+        assert!(!authorizer.authorize(&input).await.unwrap().allowed);
+    }
 }
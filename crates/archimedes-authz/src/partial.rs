@@ -0,0 +1,418 @@
+//! Partial evaluation of the allow rule.
+//!
+//! A list endpoint cannot ask the policy about every row it might return.
+//! Instead, [`PolicyEvaluator::partial`](crate::PolicyEvaluator::partial)
+//! evaluates the allow rule with parts of the input left unknown, such as
+//! `input.resource`, and returns the conditions the unknown parts must meet.
+//! The handler translates those into a database filter.
+//!
+//! # Residual Shape
+//!
+//! Each `allow` rule body is evaluated on its own. Expressions that only
+//! read known input and data are evaluated; a body with a false expression
+//! is dropped. Expressions that read an unknown must be comparisons, and
+//! their known side is replaced by its value. What is left is a list of
+//! queries, any of which allows the request, each a list of conditions that
+//! must all hold:
+//!
+//! ```json
+//! {
+//!   "result": "conditional",
+//!   "queries": [
+//!     [
+//!       {
+//!         "left": { "unknown": "input.resource.owner" },
+//!         "op": "eq",
+//!         "right": { "value": "user-123" }
+//!       }
+//!     ]
+//!   ]
+//! }
+//! ```
+//!
+//! Two shortcuts skip the filter entirely: `{"result": "allowed"}` when a
+//! body holds without reading any unknown, and `{"result": "denied"}` when
+//! every body fails on the known input.
+//!
+//! # Supported Policies
+//!
+//! Rules are read from the policy source, so partial evaluation supports a
+//! subset of Rego: boolean `allow` rules in the package of the allow query,
+//! with or without `if` and braces. Variables bound in one expression are
+//! not visible to expressions that read unknowns, and an unknown may only
+//! appear as a plain reference on one side of `==`, `=`, `!=`, `<`, `<=`,
+//! `>` or `>=`. Anything else is reported as an evaluation error rather
+//! than guessed at.
+
+use regorus::Engine;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::{AuthzError, AuthzResult};
+
+/// Outcome of partially evaluating the allow rule.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "result", content = "queries", rename_all = "snake_case")]
+pub enum PartialResult {
+    /// The request is allowed whatever the unknowns are.
+    Allowed,
+    /// The request is denied whatever the unknowns are.
+    Denied,
+    /// The request is allowed if all conditions of any query hold.
+    Conditional(Vec<Vec<Condition>>),
+}
+
+/// A comparison the unknown parts of the input must satisfy.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Condition {
+    /// Left-hand side.
+    pub left: Term,
+    /// Comparison operator.
+    pub op: Comparison,
+    /// Right-hand side.
+    pub right: Term,
+}
+
+/// One side of a [`Condition`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Term {
+    /// A reference into an unknown, as written in the policy.
+    Unknown(String),
+    /// A value computed from the known input and data.
+    Value(Value),
+}
+
+/// Comparison operator of a [`Condition`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Comparison {
+    /// `==` or `=`.
+    Eq,
+    /// `!=`.
+    Ne,
+    /// `<`.
+    Lt,
+    /// `<=`.
+    Le,
+    /// `>`.
+    Gt,
+    /// `>=`.
+    Ge,
+}
+
+/// Split an allow query such as `data.authz.allow` into its package and
+/// rule name.
+pub(crate) fn split_query(query: &str) -> Option<(&str, &str)> {
+    query.strip_prefix("data.")?.rsplit_once('.')
+}
+
+/// Collect the bodies of the boolean rule `rule` in `package`, each as its
+/// list of expressions. Rules that always hold have an empty body.
+pub(crate) fn rule_bodies(source: &str, package: &str, rule: &str) -> Vec<Vec<String>> {
+    let statements = statements(&strip_comments(source));
+    let in_package = statements
+        .iter()
+        .find_map(|s| s.strip_prefix("package "))
+        .is_some_and(|name| name.trim() == package);
+    if !in_package {
+        return Vec::new();
+    }
+
+    statements
+        .iter()
+        .filter_map(|statement| rule_body(statement, rule))
+        .collect()
+}
+
+/// Partially evaluate one rule body.
+///
+/// Returns `None` if the body fails on the known input, otherwise the
+/// conditions left on the unknowns.
+pub(crate) fn residual(
+    engine: &mut Engine,
+    body: &[String],
+    unknowns: &[&str],
+) -> AuthzResult<Option<Vec<Condition>>> {
+    let (open, known): (Vec<&String>, Vec<&String>) = body
+        .iter()
+        .partition(|expr| references_unknown(expr, unknowns));
+
+    // Known expressions are evaluated together so they can share variables
+    if !known.is_empty() {
+        let query = known
+            .iter()
+            .map(|expr| expr.as_str())
+            .collect::<Vec<_>>()
+            .join("; ");
+        if !holds(engine, &query)? {
+            return Ok(None);
+        }
+    }
+
+    let mut conditions = Vec::with_capacity(open.len());
+    for expr in open {
+        let (left, op, right) = split_comparison(expr).ok_or_else(|| {
+            AuthzError::Evaluation(format!(
+                "cannot partially evaluate `{}`: unknowns may only be compared",
+                expr
+            ))
+        })?;
+        let (Some(left), Some(right)) = (
+            term(engine, left, unknowns)?,
+            term(engine, right, unknowns)?,
+        ) else {
+            return Ok(None);
+        };
+        conditions.push(Condition { left, op, right });
+    }
+    Ok(Some(conditions))
+}
+
+/// Evaluate one side of a comparison, or `None` if it is undefined.
+fn term(engine: &mut Engine, expr: &str, unknowns: &[&str]) -> AuthzResult<Option<Term>> {
+    if references_unknown(expr, unknowns) {
+        let is_ref = expr
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '.' | '[' | ']' | '"'));
+        if !is_ref {
+            return Err(AuthzError::Evaluation(format!(
+                "cannot partially evaluate `{}`: unknowns must be plain references",
+                expr
+            )));
+        }
+        return Ok(Some(Term::Unknown(expr.to_string())));
+    }
+
+    let results = engine
+        .eval_query(expr.to_string(), false)
+        .map_err(|e| AuthzError::Evaluation(format!("failed to evaluate `{}`: {}", expr, e)))?;
+    let value = results
+        .result
+        .first()
+        .and_then(|r| r.expressions.first())
+        .map(|e| &e.value)
+        .filter(|value| **value != regorus::Value::Undefined);
+    value
+        .map(|value| serde_json::to_value(value).map(Term::Value))
+        .transpose()
+        .map_err(Into::into)
+}
+
+/// Whether a query over the known input holds.
+fn holds(engine: &mut Engine, query: &str) -> AuthzResult<bool> {
+    let results = engine
+        .eval_query(query.to_string(), false)
+        .map_err(|e| AuthzError::Evaluation(format!("failed to evaluate `{}`: {}", query, e)))?;
+    Ok(results.result.iter().any(|r| {
+        r.expressions
+            .iter()
+            .all(|e| e.value != regorus::Value::Bool(false))
+    }))
+}
+
+/// Whether `expr` reads one of the unknowns.
+fn references_unknown(expr: &str, unknowns: &[&str]) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    unknowns.iter().any(|unknown| {
+        expr.match_indices(unknown).any(|(start, _)| {
+            let before = expr[..start].chars().next_back();
+            let after = expr[start + unknown.len()..].chars().next();
+            !before.is_some_and(|c| is_ident(c) || c == '.') && !after.is_some_and(is_ident)
+        })
+    })
+}
+
+/// Split a comparison at its top-level operator.
+fn split_comparison(expr: &str) -> Option<(&str, Comparison, &str)> {
+    let bytes = expr.as_bytes();
+    let mut depth = 0_i32;
+    let mut in_string = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        let next = bytes.get(i + 1).copied();
+        match bytes[i] {
+            b'"' if i == 0 || bytes[i - 1] != b'\\' => in_string = !in_string,
+            _ if in_string => {}
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => depth -= 1,
+            c @ (b'=' | b'!' | b'<' | b'>') if depth == 0 => {
+                let (op, len) = match (c, next) {
+                    (b'=', Some(b'=')) => (Comparison::Eq, 2),
+                    (b'!', Some(b'=')) => (Comparison::Ne, 2),
+                    (b'<', Some(b'=')) => (Comparison::Le, 2),
+                    (b'>', Some(b'=')) => (Comparison::Ge, 2),
+                    (b'<', _) => (Comparison::Lt, 1),
+                    (b'>', _) => (Comparison::Gt, 1),
+                    // `:=` declares a variable rather than comparing
+                    (b'=', _) if i > 0 && bytes[i - 1] != b':' => (Comparison::Eq, 1),
+                    _ => return None,
+                };
+                return Some((expr[..i].trim(), op, expr[i + len..].trim()));
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Parse the body of `statement` if it defines the boolean rule `rule`.
+fn rule_body(statement: &str, rule: &str) -> Option<Vec<String>> {
+    let rest = statement.strip_prefix(rule)?;
+    if rest.starts_with(|c: char| c.is_alphanumeric() || c == '_') {
+        return None;
+    }
+
+    // Braces after an assignment hold a value, not a body
+    let braces = rest.find('{').zip(rest.rfind('}')).filter(|(open, _)| {
+        let head = rest[..*open].trim_end();
+        head.ends_with("if") || !head.contains('=')
+    });
+    let (head, body) = match braces {
+        Some((open, close)) => (&rest[..open], Some(&rest[open + 1..close])),
+        None => (rest, None),
+    };
+
+    // The head may assign a value, which must be `true`
+    let mut head = head.trim();
+    if let Some(value) = head.strip_prefix(":=").or_else(|| head.strip_prefix('=')) {
+        let value = value.trim_start();
+        let end = value.find(" if").unwrap_or(value.len());
+        if value[..end].trim() != "true" {
+            return None;
+        }
+        head = value[end..].trim();
+    }
+    let head = head.strip_prefix("if").map_or(head, str::trim);
+
+    let body = body.unwrap_or(head);
+    Some(expressions(body))
+}
+
+/// Split a rule body into its expressions.
+fn expressions(body: &str) -> Vec<String> {
+    let mut expressions = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0_i32;
+    let mut in_string = false;
+    for c in body.chars() {
+        match c {
+            '"' => in_string = !in_string,
+            '(' | '[' | '{' if !in_string => depth += 1,
+            ')' | ']' | '}' if !in_string => depth -= 1,
+            '\n' | ';' if !in_string && depth == 0 => {
+                expressions.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    expressions.push(current);
+    expressions
+        .into_iter()
+        .map(|expr| expr.trim().to_string())
+        .filter(|expr| !expr.is_empty())
+        .collect()
+}
+
+/// Split source into top-level statements, one per rule or declaration.
+fn statements(source: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0_usize;
+    for line in source.lines() {
+        if depth == 0 && !current.trim().is_empty() && !line.trim().is_empty() {
+            statements.push(std::mem::take(&mut current));
+        }
+        for c in line.chars() {
+            match c {
+                '{' => depth += 1,
+                '}' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+        current.push_str(line);
+        current.push('\n');
+    }
+    statements.push(current);
+    statements
+        .into_iter()
+        .map(|statement| statement.trim().to_string())
+        .filter(|statement| !statement.is_empty())
+        .collect()
+}
+
+/// Remove `#` comments, leaving string literals alone.
+fn strip_comments(source: &str) -> String {
+    source
+        .lines()
+        .map(|line| {
+            let mut in_string = false;
+            for (i, c) in line.char_indices() {
+                match c {
+                    '"' => in_string = !in_string,
+                    '#' if !in_string => return &line[..i],
+                    _ => {}
+                }
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rule_bodies() {
+        let source = r#"
+            package authz
+
+            default allow := false
+
+            # Owners only
+            allow if {
+                input.method == "GET"; input.resource.owner == input.user
+            }
+
+            allow = true if input.admin
+            allow { input.public }
+            allow = false
+            allowed if { true }
+        "#;
+
+        let bodies = rule_bodies(source, "authz", "allow");
+        assert_eq!(
+            bodies,
+            vec![
+                vec![
+                    r#"input.method == "GET""#.to_string(),
+                    "input.resource.owner == input.user".to_string(),
+                ],
+                vec!["input.admin".to_string()],
+                vec!["input.public".to_string()],
+            ]
+        );
+        assert!(rule_bodies(source, "other", "allow").is_empty());
+    }
+
+    #[test]
+    fn test_split_comparison() {
+        let (left, op, right) = split_comparison(r#"input.resource["owner"] != "a==b""#).unwrap();
+        assert_eq!(left, r#"input.resource["owner"]"#);
+        assert_eq!(op, Comparison::Ne);
+        assert_eq!(right, r#""a==b""#);
+
+        assert_eq!(split_comparison("x := 1"), None);
+        assert_eq!(split_comparison("count(x) >= 2").unwrap().1, Comparison::Ge);
+        assert!(references_unknown(
+            "input.resource.owner",
+            &["input.resource"]
+        ));
+        assert!(!references_unknown("input.resources", &["input.resource"]));
+    }
+}
@@ -73,6 +73,7 @@ impl ArchimedesConfig {
     /// - Server address is invalid
     /// - Metrics address is invalid
     /// - Sampling ratio is not in 0.0..=1.0
+    /// - Connection settings are zero or outside HTTP/2 protocol bounds
     /// - Required fields are missing when features are enabled
     pub fn validate(&self) -> Result<(), crate::ConfigError> {
         // Validate server address format
@@ -88,6 +89,8 @@ impl ArchimedesConfig {
             ));
         }
 
+        self.validate_connection_settings()?;
+
         // Validate metrics address if enabled
         if self.telemetry.metrics.enabled
             && self
@@ -125,6 +128,67 @@ impl ArchimedesConfig {
         Ok(())
     }
 
+    /// Validates server connection tuning against protocol bounds.
+    fn validate_connection_settings(&self) -> Result<(), crate::ConfigError> {
+        // RFC 9113 §6.9.1: flow control windows cannot exceed 2^31 - 1
+        const MAX_WINDOW_SIZE: u32 = (1 << 31) - 1;
+
+        let server = &self.server;
+        let positive = [
+            (
+                "server.max_requests_per_connection",
+                server.max_requests_per_connection,
+            ),
+            (
+                "server.http2_max_concurrent_streams",
+                server.http2_max_concurrent_streams.map(u64::from),
+            ),
+            (
+                "server.http2_keep_alive_interval_secs",
+                server.http2_keep_alive_interval_secs,
+            ),
+            (
+                "server.http2_keep_alive_timeout_secs",
+                server.http2_keep_alive_timeout_secs,
+            ),
+            (
+                "server.http2_max_header_list_size",
+                server.http2_max_header_list_size.map(u64::from),
+            ),
+        ];
+        for (field, value) in positive {
+            if value == Some(0) {
+                return Err(crate::ConfigError::invalid_value(
+                    field,
+                    "must be greater than 0",
+                ));
+            }
+        }
+
+        let windows = [
+            (
+                "server.http2_initial_stream_window_size",
+                server.http2_initial_stream_window_size,
+            ),
+            (
+                "server.http2_initial_connection_window_size",
+                server.http2_initial_connection_window_size,
+            ),
+        ];
+        for (field, value) in windows {
+            if let Some(size) = value {
+                if size == 0 || size > MAX_WINDOW_SIZE {
+                    return Err(crate::ConfigError::invalid_value(
+                        field,
+                        format!("must be between 1 and {MAX_WINDOW_SIZE}, got {size}"),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Create a development configuration preset.
     ///
     /// This preset is optimized for local development with:
@@ -437,6 +501,39 @@ mod tests {
         assert!(config.contract.validate_responses);
     }
 
+    #[test]
+    fn test_validate_connection_settings() {
+        let config = ArchimedesConfig::builder()
+            .server(ServerConfig {
+                max_requests_per_connection: Some(1000),
+                http2_max_concurrent_streams: Some(256),
+                http2_initial_stream_window_size: Some(1 << 20),
+                http2_initial_connection_window_size: Some((1 << 31) - 1),
+                ..Default::default()
+            })
+            .build();
+        assert!(config.validate().is_ok());
+
+        let config = ArchimedesConfig::builder()
+            .server(ServerConfig {
+                http2_initial_stream_window_size: Some(1 << 31),
+                ..Default::default()
+            })
+            .build();
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("server.http2_initial_stream_window_size"));
+        assert!(err.contains("2147483647"));
+
+        let config = ArchimedesConfig::builder()
+            .server(ServerConfig {
+                max_requests_per_connection: Some(0),
+                ..Default::default()
+            })
+            .build();
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("server.max_requests_per_connection"));
+    }
+
     #[test]
     fn test_build_validated_success() {
         let result = ArchimedesConfig::builder().build_validated();
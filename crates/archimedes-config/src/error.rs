@@ -31,6 +31,10 @@ pub enum ConfigError {
     #[error("failed to parse JSON configuration: {0}")]
     JsonError(#[from] serde_json::Error),
 
+    /// YAML parsing error.
+    #[error("failed to parse YAML configuration: {0}")]
+    YamlError(#[from] serde_yaml::Error),
+
     /// Unknown field in configuration (strict mode).
     #[error("unknown configuration field: {field} in section {section}")]
     UnknownField {
@@ -2,7 +2,7 @@
 //!
 //! This crate provides a strongly-typed configuration system for Archimedes servers
 //! with support for:
-//! - TOML and JSON configuration files
+//! - TOML, JSON and YAML configuration files
 //! - Environment variable overrides
 //! - Strict validation (fails on unknown fields)
 //! - Layered configuration (defaults → file → env)
@@ -44,6 +44,9 @@
 //! max_connections = 10000
 //! request_timeout_ms = 30000
 //!
+//! [server.admin]
+//! enabled = false
+//!
 //! [telemetry]
 //! service_name = "my-service"
 //! service_version = "1.0.0"
@@ -53,6 +56,9 @@
 //! enabled = true
 //! addr = "0.0.0.0:9090"
 //!
+//! [telemetry.metrics.auth]
+//! bearer_token = "change-me"
+//!
 //! [telemetry.tracing]
 //! enabled = true
 //! otlp_endpoint = "http://localhost:4317"
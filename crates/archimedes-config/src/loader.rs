@@ -3,10 +3,11 @@
 //! This module provides the [`ConfigLoader`] for loading configuration from
 //! multiple sources: defaults, files, and environment variables.
 
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::env;
 use std::fs;
 use std::path::Path;
+use std::str::FromStr;
 
 use crate::{ArchimedesConfig, ConfigError};
 
@@ -15,9 +16,35 @@ use crate::{ArchimedesConfig, ConfigError};
 /// The loader applies configuration in layers, with later layers overriding
 /// earlier ones:
 /// 1. Default values (built into the code)
-/// 2. Configuration file (TOML or JSON)
+/// 2. Configuration file (TOML, JSON or YAML)
 /// 3. Environment variables
 ///
+/// # Environment Variables
+///
+/// With a prefix of `ARCHIMEDES`, the variable `ARCHIMEDES__A__B__C` sets the
+/// field `a.b.c`: sections and keys are separated by a double underscore and
+/// written in upper case, while single underscores are part of the key name.
+/// For example `ARCHIMEDES__TELEMETRY__METRICS__PROCESS_METRICS=true` sets
+/// `telemetry.metrics.process_metrics`.
+///
+/// - **Arrays** are written one element per variable with a trailing index:
+///   `ARCHIMEDES__AUTHORIZATION__ALLOW_ANONYMOUS__0=healthCheck` and
+///   `..._ALLOW_ANONYMOUS__1=readiness`. Indices must be contiguous from 0,
+///   and the elements replace the whole array from the defaults or file.
+/// - **Optional values** are cleared by an empty variable.
+/// - **Booleans** accept `true`/`false`, `1`/`0`, `yes`/`no` and `on`/`off`.
+///   A value that does not parse as the field's type fails loading with
+///   [`ConfigError::EnvParseError`] naming the variable, the expected type
+///   and the value.
+/// - **Unknown keys** under the prefix are ignored.
+///
+/// Environment variables are applied last, in [`ConfigLoader::load`], so
+/// they override the defaults and any file regardless of the order in which
+/// the loader methods were called. A missing file is an error with
+/// [`ConfigLoader::with_file`] and skipped with
+/// [`ConfigLoader::with_optional_file`]; [`ConfigLoader::from_env`] reads no
+/// file at all.
+///
 /// # Example
 ///
 /// ```no_run
@@ -64,6 +91,35 @@ impl ConfigLoader {
         }
     }
 
+    /// Build a configuration from defaults and environment variables only.
+    ///
+    /// This is the bootstrap for deployments configured entirely through
+    /// the environment. No file is read, so every non-default value must
+    /// come from a `PREFIX__...` variable; see
+    /// [Environment Variables](Self#environment-variables) for how
+    /// variables map to fields.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError` if an environment variable cannot be parsed or
+    /// the resulting configuration is invalid.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use archimedes_config::ConfigLoader;
+    ///
+    /// # fn main() -> Result<(), archimedes_config::ConfigError> {
+    /// // ARCHIMEDES__SERVER__HTTP_ADDR=0.0.0.0:9000
+    /// // ARCHIMEDES__AUTHORIZATION__ALLOW_ANONYMOUS__0=healthCheck
+    /// let config = ConfigLoader::from_env("ARCHIMEDES")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_env(prefix: &str) -> Result<ArchimedesConfig, ConfigError> {
+        Self::new().with_env_prefix(prefix).load()
+    }
+
     /// Start with default configuration values.
     ///
     /// This is called automatically by `new()`, but can be chained for clarity.
@@ -123,7 +179,7 @@ impl ConfigLoader {
 
     /// Load configuration from a file.
     ///
-    /// Supports TOML (.toml) and JSON (.json) formats.
+    /// Supports TOML (.toml), JSON (.json) and YAML (.yaml, .yml) formats.
     /// The file format is determined by the file extension.
     ///
     /// # Errors
@@ -131,7 +187,7 @@ impl ConfigLoader {
     /// Returns `ConfigError` if:
     /// - The file does not exist
     /// - The file cannot be read
-    /// - The file contains invalid TOML/JSON
+    /// - The file contains invalid TOML/JSON/YAML
     /// - The file contains unknown fields (strict mode)
     ///
     /// # Example
@@ -170,7 +226,7 @@ impl ConfigLoader {
     ///
     /// Returns `ConfigError` if the file exists but:
     /// - Cannot be read
-    /// - Contains invalid TOML/JSON
+    /// - Contains invalid TOML/JSON/YAML
     /// - Contains unknown fields
     ///
     /// # Example
@@ -197,7 +253,7 @@ impl ConfigLoader {
     /// # Arguments
     ///
     /// * `content` - Configuration content as a string
-    /// * `format` - File format ("toml" or "json")
+    /// * `format` - File format ("toml", "json", "yaml" or "yml")
     ///
     /// # Errors
     ///
@@ -225,6 +281,7 @@ impl ConfigLoader {
         let file_config = match format.to_lowercase().as_str() {
             "toml" => toml::from_str(content)?,
             "json" => serde_json::from_str(content)?,
+            "yaml" | "yml" => serde_yaml::from_str(content)?,
             _ => {
                 return Err(ConfigError::validation_error(format!(
                     "unsupported configuration format: {format}"
@@ -242,6 +299,10 @@ impl ConfigLoader {
     /// For example, with prefix "ARCHIMEDES":
     /// - `ARCHIMEDES__SERVER__HTTP_ADDR=0.0.0.0:9000`
     /// - `ARCHIMEDES__TELEMETRY__SERVICE_NAME=my-service`
+    /// - `ARCHIMEDES__AUTHORIZATION__ALLOW_ANONYMOUS__0=healthCheck`
+    ///
+    /// See [Environment Variables](Self#environment-variables) for nested
+    /// keys, arrays and precedence.
     ///
     /// # Example
     ///
@@ -347,6 +408,7 @@ impl ConfigLoader {
         match extension.as_deref() {
             Some("toml") => Ok(toml::from_str(content)?),
             Some("json") => Ok(serde_json::from_str(content)?),
+            Some("yaml" | "yml") => Ok(serde_yaml::from_str(content)?),
             _ => Err(ConfigError::validation_error(format!(
                 "unsupported configuration file format: {}",
                 path.display()
@@ -363,11 +425,80 @@ impl ConfigLoader {
 
     // Apply environment variable overrides
     fn apply_env_overrides(&mut self, prefix: &str) -> Result<(), ConfigError> {
-        let env_vars: HashMap<String, String> =
+        // Sorted so that overrides apply in a stable order
+        let env_vars: BTreeMap<String, String> =
             env::vars().filter(|(k, _)| k.starts_with(prefix)).collect();
 
+        // Indexed keys (`PREFIX__X__0`) are collected per array and applied
+        // together, replacing the whole array
+        let mut arrays: BTreeMap<String, BTreeMap<usize, (String, String)>> = BTreeMap::new();
         for (key, value) in env_vars {
-            self.apply_env_var(&key, &value, prefix)?;
+            match key.rsplit_once("__") {
+                Some((base, index))
+                    if !index.is_empty() && index.bytes().all(|b| b.is_ascii_digit()) =>
+                {
+                    let index = index.parse().map_err(|_| {
+                        ConfigError::env_parse_error(&key, "array index out of range")
+                    })?;
+                    arrays
+                        .entry(base.to_string())
+                        .or_default()
+                        .insert(index, (key, value));
+                }
+                _ => self.apply_env_var(&key, &value, prefix)?,
+            }
+        }
+
+        for (base, elements) in arrays {
+            for (expected, (index, (key, _))) in elements.iter().enumerate() {
+                if *index != expected {
+                    return Err(ConfigError::env_parse_error(
+                        key.as_str(),
+                        format!(
+                            "array indices must be contiguous from 0, missing {base}__{expected}"
+                        ),
+                    ));
+                }
+            }
+            let elements: Vec<(String, String)> = elements.into_values().collect();
+            self.apply_env_array(&base, &elements, prefix)?;
+        }
+
+        Ok(())
+    }
+
+    // Apply an array from indexed environment variables
+    fn apply_env_array(
+        &mut self,
+        base: &str,
+        elements: &[(String, String)],
+        prefix: &str,
+    ) -> Result<(), ConfigError> {
+        let Some(parts) = env_key_parts(base, prefix) else {
+            let key = elements.first().map_or(base, |(key, _)| key.as_str());
+            return Err(ConfigError::env_parse_error(key, "invalid key format"));
+        };
+        let strings = || elements.iter().map(|(_, value)| value.clone()).collect();
+
+        match parts.as_slice() {
+            ["TELEMETRY", "METRICS", "HISTOGRAM_BUCKETS"] => {
+                self.config.telemetry.metrics.histogram_buckets = elements
+                    .iter()
+                    .map(|(key, value)| parse_env(key, value, "float"))
+                    .collect::<Result<_, _>>()?;
+            }
+            ["TELEMETRY", "METRICS", "AUTH", "ALLOWED_CLIENT_SUBJECTS"] => {
+                self.config.telemetry.metrics.auth.allowed_client_subjects = strings();
+            }
+            ["SERVER", "ADMIN", "AUTH", "ALLOWED_CLIENT_SUBJECTS"] => {
+                self.config.server.admin.auth.allowed_client_subjects = strings();
+            }
+            ["AUTHORIZATION", "ALLOW_ANONYMOUS"] => {
+                self.config.authorization.allow_anonymous = strings();
+            }
+
+            // Unknown key - ignore, as for scalars
+            _ => {}
         }
 
         Ok(())
@@ -375,46 +506,72 @@ impl ConfigLoader {
 
     // Apply a single environment variable
     fn apply_env_var(&mut self, key: &str, value: &str, prefix: &str) -> Result<(), ConfigError> {
-        // Remove prefix and split by double underscore
-        let key_without_prefix = key
-            .strip_prefix(prefix)
-            .and_then(|k| k.strip_prefix("__"))
+        let parts = env_key_parts(key, prefix)
             .ok_or_else(|| ConfigError::env_parse_error(key, "invalid key format"))?;
 
-        let parts: Vec<&str> = key_without_prefix.split("__").collect();
-
         match parts.as_slice() {
             // Server section
             ["SERVER", "HTTP_ADDR"] => {
                 self.config.server.http_addr = value.to_string();
             }
             ["SERVER", "SHUTDOWN_TIMEOUT_SECS"] => {
-                self.config.server.shutdown_timeout_secs = value
-                    .parse()
-                    .map_err(|_| ConfigError::env_parse_error(key, "expected integer"))?;
+                self.config.server.shutdown_timeout_secs = parse_env(key, value, "integer")?;
             }
             ["SERVER", "MAX_CONNECTIONS"] => {
-                self.config.server.max_connections = value
-                    .parse()
-                    .map_err(|_| ConfigError::env_parse_error(key, "expected integer"))?;
+                self.config.server.max_connections = parse_env(key, value, "integer")?;
             }
             ["SERVER", "REQUEST_TIMEOUT_MS"] => {
-                self.config.server.request_timeout_ms = value
-                    .parse()
-                    .map_err(|_| ConfigError::env_parse_error(key, "expected integer"))?;
+                self.config.server.request_timeout_ms = parse_env(key, value, "integer")?;
             }
             ["SERVER", "KEEP_ALIVE_SECS"] => {
                 self.config.server.keep_alive_secs = if value.eq_ignore_ascii_case("none") {
                     None
                 } else {
-                    Some(value.parse().map_err(|_| {
-                        ConfigError::env_parse_error(key, "expected integer or 'none'")
-                    })?)
+                    Some(parse_env(key, value, "integer or 'none'")?)
                 };
             }
             ["SERVER", "HTTP2_ENABLED"] => {
-                self.config.server.http2_enabled = parse_bool(value)
-                    .ok_or_else(|| ConfigError::env_parse_error(key, "expected boolean"))?;
+                self.config.server.http2_enabled = parse_env_bool(key, value)?;
+            }
+            ["SERVER", "MAX_REQUESTS_PER_CONNECTION"] => {
+                self.config.server.max_requests_per_connection =
+                    Some(parse_env(key, value, "integer")?);
+            }
+            ["SERVER", "HTTP2_MAX_CONCURRENT_STREAMS"] => {
+                self.config.server.http2_max_concurrent_streams =
+                    Some(parse_env(key, value, "integer")?);
+            }
+            ["SERVER", "HTTP2_INITIAL_STREAM_WINDOW_SIZE"] => {
+                self.config.server.http2_initial_stream_window_size =
+                    Some(parse_env(key, value, "integer")?);
+            }
+            ["SERVER", "HTTP2_INITIAL_CONNECTION_WINDOW_SIZE"] => {
+                self.config.server.http2_initial_connection_window_size =
+                    Some(parse_env(key, value, "integer")?);
+            }
+            ["SERVER", "HTTP2_KEEP_ALIVE_INTERVAL_SECS"] => {
+                self.config.server.http2_keep_alive_interval_secs =
+                    Some(parse_env(key, value, "integer")?);
+            }
+            ["SERVER", "HTTP2_KEEP_ALIVE_TIMEOUT_SECS"] => {
+                self.config.server.http2_keep_alive_timeout_secs =
+                    Some(parse_env(key, value, "integer")?);
+            }
+            ["SERVER", "HTTP2_MAX_HEADER_LIST_SIZE"] => {
+                self.config.server.http2_max_header_list_size =
+                    Some(parse_env(key, value, "integer")?);
+            }
+            ["SERVER", "ADMIN", "ENABLED"] => {
+                self.config.server.admin.enabled = parse_env_bool(key, value)?;
+            }
+            ["SERVER", "ADMIN", "AUTH", "BEARER_TOKEN"] => {
+                self.config.server.admin.auth.bearer_token = non_empty(value).map(Into::into);
+            }
+            ["SERVER", "ADMIN", "AUTH", "REQUIRE_CLIENT_CERT"] => {
+                self.config.server.admin.auth.require_client_cert = parse_env_bool(key, value)?;
+            }
+            ["SERVER", "ADMIN", "AUTH", "CLIENT_CERT_HEADER"] => {
+                self.config.server.admin.auth.client_cert_header = value.to_string();
             }
 
             // Telemetry section
@@ -422,11 +579,7 @@ impl ConfigLoader {
                 self.config.telemetry.service_name = value.to_string();
             }
             ["TELEMETRY", "SERVICE_VERSION"] => {
-                self.config.telemetry.service_version = if value.is_empty() {
-                    None
-                } else {
-                    Some(value.to_string())
-                };
+                self.config.telemetry.service_version = non_empty(value);
             }
             ["TELEMETRY", "ENVIRONMENT"] => {
                 self.config.telemetry.environment = value.to_string();
@@ -434,35 +587,42 @@ impl ConfigLoader {
 
             // Telemetry metrics
             ["TELEMETRY", "METRICS", "ENABLED"] => {
-                self.config.telemetry.metrics.enabled = parse_bool(value)
-                    .ok_or_else(|| ConfigError::env_parse_error(key, "expected boolean"))?;
+                self.config.telemetry.metrics.enabled = parse_env_bool(key, value)?;
             }
             ["TELEMETRY", "METRICS", "ADDR"] => {
                 self.config.telemetry.metrics.addr = value.to_string();
             }
+            ["TELEMETRY", "METRICS", "PROCESS_METRICS"] => {
+                self.config.telemetry.metrics.process_metrics = parse_env_bool(key, value)?;
+            }
+            ["TELEMETRY", "METRICS", "DETAILED_TIMING"] => {
+                self.config.telemetry.metrics.detailed_timing = parse_env_bool(key, value)?;
+            }
+            ["TELEMETRY", "METRICS", "AUTH", "BEARER_TOKEN"] => {
+                self.config.telemetry.metrics.auth.bearer_token = non_empty(value).map(Into::into);
+            }
+            ["TELEMETRY", "METRICS", "AUTH", "REQUIRE_CLIENT_CERT"] => {
+                self.config.telemetry.metrics.auth.require_client_cert =
+                    parse_env_bool(key, value)?;
+            }
+            ["TELEMETRY", "METRICS", "AUTH", "CLIENT_CERT_HEADER"] => {
+                self.config.telemetry.metrics.auth.client_cert_header = value.to_string();
+            }
 
             // Telemetry tracing
             ["TELEMETRY", "TRACING", "ENABLED"] => {
-                self.config.telemetry.tracing.enabled = parse_bool(value)
-                    .ok_or_else(|| ConfigError::env_parse_error(key, "expected boolean"))?;
+                self.config.telemetry.tracing.enabled = parse_env_bool(key, value)?;
             }
             ["TELEMETRY", "TRACING", "OTLP_ENDPOINT"] => {
-                self.config.telemetry.tracing.otlp_endpoint = if value.is_empty() {
-                    None
-                } else {
-                    Some(value.to_string())
-                };
+                self.config.telemetry.tracing.otlp_endpoint = non_empty(value);
             }
             ["TELEMETRY", "TRACING", "SAMPLING_RATIO"] => {
-                self.config.telemetry.tracing.sampling_ratio = value
-                    .parse()
-                    .map_err(|_| ConfigError::env_parse_error(key, "expected float"))?;
+                self.config.telemetry.tracing.sampling_ratio = parse_env(key, value, "float")?;
             }
 
             // Telemetry logging
             ["TELEMETRY", "LOGGING", "ENABLED"] => {
-                self.config.telemetry.logging.enabled = parse_bool(value)
-                    .ok_or_else(|| ConfigError::env_parse_error(key, "expected boolean"))?;
+                self.config.telemetry.logging.enabled = parse_env_bool(key, value)?;
             }
             ["TELEMETRY", "LOGGING", "LEVEL"] => {
                 self.config.telemetry.logging.level = value.to_string();
@@ -474,20 +634,18 @@ impl ConfigLoader {
                     _ => {
                         return Err(ConfigError::env_parse_error(
                             key,
-                            "expected 'json' or 'pretty'",
+                            format!("expected 'json' or 'pretty', got '{value}'"),
                         ))
                     }
                 };
             }
             ["TELEMETRY", "LOGGING", "ANSI_ENABLED"] => {
-                self.config.telemetry.logging.ansi_enabled = parse_bool(value)
-                    .ok_or_else(|| ConfigError::env_parse_error(key, "expected boolean"))?;
+                self.config.telemetry.logging.ansi_enabled = parse_env_bool(key, value)?;
             }
 
             // Authorization section
             ["AUTHORIZATION", "ENABLED"] => {
-                self.config.authorization.enabled = parse_bool(value)
-                    .ok_or_else(|| ConfigError::env_parse_error(key, "expected boolean"))?;
+                self.config.authorization.enabled = parse_env_bool(key, value)?;
             }
             ["AUTHORIZATION", "MODE"] => {
                 self.config.authorization.mode = match value.to_lowercase().as_str() {
@@ -498,38 +656,29 @@ impl ConfigLoader {
                     _ => {
                         return Err(ConfigError::env_parse_error(
                             key,
-                            "expected 'allow_all', 'deny_all', 'rbac', or 'opa'",
+                            format!(
+                                "expected 'allow_all', 'deny_all', 'rbac', or 'opa', got '{value}'"
+                            ),
                         ))
                     }
                 };
             }
             ["AUTHORIZATION", "OPA_ENDPOINT"] => {
-                self.config.authorization.opa_endpoint = if value.is_empty() {
-                    None
-                } else {
-                    Some(value.to_string())
-                };
+                self.config.authorization.opa_endpoint = non_empty(value);
             }
 
             // Contract section
             ["CONTRACT", "ENABLED"] => {
-                self.config.contract.enabled = parse_bool(value)
-                    .ok_or_else(|| ConfigError::env_parse_error(key, "expected boolean"))?;
+                self.config.contract.enabled = parse_env_bool(key, value)?;
             }
             ["CONTRACT", "STRICT_VALIDATION"] => {
-                self.config.contract.strict_validation = parse_bool(value)
-                    .ok_or_else(|| ConfigError::env_parse_error(key, "expected boolean"))?;
+                self.config.contract.strict_validation = parse_env_bool(key, value)?;
             }
             ["CONTRACT", "CONTRACT_PATH"] => {
-                self.config.contract.contract_path = if value.is_empty() {
-                    None
-                } else {
-                    Some(value.to_string())
-                };
+                self.config.contract.contract_path = non_empty(value);
             }
             ["CONTRACT", "VALIDATE_RESPONSES"] => {
-                self.config.contract.validate_responses = parse_bool(value)
-                    .ok_or_else(|| ConfigError::env_parse_error(key, "expected boolean"))?;
+                self.config.contract.validate_responses = parse_env_bool(key, value)?;
             }
 
             // Unknown key - ignore (could also warn)
@@ -540,6 +689,35 @@ impl ConfigLoader {
     }
 }
 
+/// Split `PREFIX__A__B` into `["A", "B"]`.
+fn env_key_parts<'a>(key: &'a str, prefix: &str) -> Option<Vec<&'a str>> {
+    key.strip_prefix(prefix)
+        .and_then(|k| k.strip_prefix("__"))
+        .map(|k| k.split("__").collect())
+}
+
+/// Parse an environment value, naming the expected type on failure.
+fn parse_env<T: FromStr>(key: &str, value: &str, expected: &str) -> Result<T, ConfigError> {
+    value.trim().parse().map_err(|_| {
+        ConfigError::env_parse_error(key, format!("expected {expected}, got '{value}'"))
+    })
+}
+
+/// Parse a boolean environment value.
+fn parse_env_bool(key: &str, value: &str) -> Result<bool, ConfigError> {
+    parse_bool(value).ok_or_else(|| {
+        ConfigError::env_parse_error(
+            key,
+            format!("expected boolean (true/false, 1/0, yes/no, on/off), got '{value}'"),
+        )
+    })
+}
+
+/// An empty value clears an optional field.
+fn non_empty(value: &str) -> Option<String> {
+    (!value.is_empty()).then(|| value.to_string())
+}
+
 /// Parse a boolean from a string.
 fn parse_bool(s: &str) -> Option<bool> {
     match s.to_lowercase().as_str() {
@@ -552,6 +730,7 @@ fn parse_bool(s: &str) -> Option<bool> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::Secret;
 
     #[test]
     fn test_loader_new() {
@@ -680,6 +859,39 @@ mod tests {
         assert_eq!(loader.config.telemetry.logging.level, "debug");
     }
 
+    #[test]
+    fn test_apply_env_var_metrics_token() {
+        let mut loader = ConfigLoader::new();
+        loader
+            .apply_env_var(
+                "TEST__TELEMETRY__METRICS__AUTH__BEARER_TOKEN",
+                "scrape-token",
+                "TEST",
+            )
+            .unwrap();
+        let token = loader.config.telemetry.metrics.auth.bearer_token.as_ref();
+        assert_eq!(token.map(Secret::expose), Some("scrape-token"));
+    }
+
+    #[test]
+    fn test_apply_env_var_admin() {
+        let mut loader = ConfigLoader::new();
+        assert!(!loader.config.server.admin.enabled);
+        loader
+            .apply_env_var("TEST__SERVER__ADMIN__ENABLED", "true", "TEST")
+            .unwrap();
+        loader
+            .apply_env_var(
+                "TEST__SERVER__ADMIN__AUTH__BEARER_TOKEN",
+                "admin-token",
+                "TEST",
+            )
+            .unwrap();
+        assert!(loader.config.server.admin.enabled);
+        let token = loader.config.server.admin.auth.bearer_token.as_ref();
+        assert_eq!(token.map(Secret::expose), Some("admin-token"));
+    }
+
     #[test]
     fn test_apply_env_var_boolean() {
         let mut loader = ConfigLoader::new();
@@ -689,6 +901,26 @@ mod tests {
         assert!(!loader.config.server.http2_enabled);
     }
 
+    #[test]
+    fn test_apply_env_var_connection_settings() {
+        let mut loader = ConfigLoader::new();
+        loader
+            .apply_env_var("TEST__SERVER__MAX_REQUESTS_PER_CONNECTION", "100", "TEST")
+            .unwrap();
+        loader
+            .apply_env_var("TEST__SERVER__HTTP2_MAX_CONCURRENT_STREAMS", "64", "TEST")
+            .unwrap();
+        assert_eq!(loader.config.server.max_requests_per_connection, Some(100));
+        assert_eq!(loader.config.server.http2_max_concurrent_streams, Some(64));
+
+        let result = loader.apply_env_var(
+            "TEST__SERVER__HTTP2_INITIAL_STREAM_WINDOW_SIZE",
+            "-1",
+            "TEST",
+        );
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_apply_env_var_invalid_integer() {
         let mut loader = ConfigLoader::new();
@@ -696,6 +928,121 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_apply_env_var_type_mismatch_is_precise() {
+        let mut loader = ConfigLoader::new();
+        let err = loader
+            .apply_env_var("TEST__TELEMETRY__METRICS__ENABLED", "enabled", "TEST")
+            .unwrap_err();
+        assert!(matches!(
+            &err,
+            ConfigError::EnvParseError { var, .. } if var == "TEST__TELEMETRY__METRICS__ENABLED"
+        ));
+        assert!(err.to_string().contains("expected boolean"));
+        assert!(err.to_string().contains("got 'enabled'"));
+
+        let err = loader
+            .apply_env_var("TEST__SERVER__REQUEST_TIMEOUT_MS", "30s", "TEST")
+            .unwrap_err();
+        assert!(err.to_string().contains("expected integer, got '30s'"));
+    }
+
+    #[test]
+    fn test_from_env_nested_overrides() {
+        env::set_var(
+            "ARCHIMEDES_FROM_ENV_TEST__SERVER__HTTP_ADDR",
+            "127.0.0.1:9100",
+        );
+        env::set_var(
+            "ARCHIMEDES_FROM_ENV_TEST__TELEMETRY__METRICS__PROCESS_METRICS",
+            "yes",
+        );
+        env::set_var(
+            "ARCHIMEDES_FROM_ENV_TEST__TELEMETRY__TRACING__SAMPLING_RATIO",
+            "0.25",
+        );
+
+        let config = ConfigLoader::from_env("ARCHIMEDES_FROM_ENV_TEST").unwrap();
+        for key in [
+            "SERVER__HTTP_ADDR",
+            "TELEMETRY__METRICS__PROCESS_METRICS",
+            "TELEMETRY__TRACING__SAMPLING_RATIO",
+        ] {
+            env::remove_var(format!("ARCHIMEDES_FROM_ENV_TEST__{key}"));
+        }
+
+        assert_eq!(config.server.http_addr, "127.0.0.1:9100");
+        assert!(config.telemetry.metrics.process_metrics);
+        assert!((config.telemetry.tracing.sampling_ratio - 0.25).abs() < f64::EPSILON);
+        // Everything else keeps its default
+        assert_eq!(config.telemetry.metrics.addr, "0.0.0.0:9090");
+    }
+
+    #[test]
+    fn test_env_array_from_indexed_vars() {
+        let prefix = "ARCHIMEDES_ARRAY_TEST";
+        let vars = [
+            ("AUTHORIZATION__ALLOW_ANONYMOUS__1", "readiness"),
+            ("AUTHORIZATION__ALLOW_ANONYMOUS__0", "healthCheck"),
+            ("TELEMETRY__METRICS__HISTOGRAM_BUCKETS__0", "0.05"),
+            ("TELEMETRY__METRICS__HISTOGRAM_BUCKETS__1", "0.5"),
+        ];
+        for (key, value) in vars {
+            env::set_var(format!("{prefix}__{key}"), value);
+        }
+
+        let toml = "[authorization]\nallow_anonymous = [\"fromFile\", \"other\", \"third\"]\n";
+        let config = ConfigLoader::new()
+            .with_string(toml, "toml")
+            .unwrap()
+            .with_env_prefix(prefix)
+            .load();
+        for (key, _) in vars {
+            env::remove_var(format!("{prefix}__{key}"));
+        }
+
+        let config = config.unwrap();
+        assert_eq!(
+            config.authorization.allow_anonymous,
+            vec!["healthCheck", "readiness"]
+        );
+        assert_eq!(config.telemetry.metrics.histogram_buckets, vec![0.05, 0.5]);
+    }
+
+    #[test]
+    fn test_env_array_gap_rejected() {
+        let prefix = "ARCHIMEDES_ARRAY_GAP_TEST";
+        env::set_var(format!("{prefix}__AUTHORIZATION__ALLOW_ANONYMOUS__0"), "a");
+        env::set_var(format!("{prefix}__AUTHORIZATION__ALLOW_ANONYMOUS__2"), "c");
+
+        let result = ConfigLoader::from_env(prefix);
+        env::remove_var(format!("{prefix}__AUTHORIZATION__ALLOW_ANONYMOUS__0"));
+        env::remove_var(format!("{prefix}__AUTHORIZATION__ALLOW_ANONYMOUS__2"));
+
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("ALLOW_ANONYMOUS__1"));
+    }
+
+    #[test]
+    fn test_env_array_element_type_mismatch() {
+        let mut loader = ConfigLoader::new();
+        let elements = vec![(
+            "TEST__TELEMETRY__METRICS__HISTOGRAM_BUCKETS__0".to_string(),
+            "fast".to_string(),
+        )];
+        let err = loader
+            .apply_env_array(
+                "TEST__TELEMETRY__METRICS__HISTOGRAM_BUCKETS",
+                &elements,
+                "TEST",
+            )
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("TEST__TELEMETRY__METRICS__HISTOGRAM_BUCKETS__0"));
+        assert!(err.to_string().contains("expected float, got 'fast'"));
+    }
+
     #[test]
     fn test_apply_env_var_authorization_mode() {
         let mut loader = ConfigLoader::new();
@@ -750,6 +1097,8 @@ mod tests {
             [telemetry.metrics]
             enabled = true
             addr = "0.0.0.0:9090"
+            process_metrics = true
+            detailed_timing = true
 
             [telemetry.tracing]
             enabled = true
@@ -784,6 +1133,8 @@ mod tests {
         assert_eq!(config.server.http_addr, "0.0.0.0:8080");
         assert_eq!(config.server.shutdown_timeout_secs, 60);
         assert_eq!(config.telemetry.service_name, "example-service");
+        assert!(config.telemetry.metrics.process_metrics);
+        assert!(config.telemetry.metrics.detailed_timing);
         assert_eq!(
             config.telemetry.tracing.otlp_endpoint,
             Some("http://jaeger:4317".to_string())
@@ -799,4 +1150,114 @@ mod tests {
             Some("/etc/contracts/api.json".to_string())
         );
     }
+
+    const EQUIVALENT_TOML: &str = r#"
+        [server]
+        http_addr = "127.0.0.1:8000"
+        http2_enabled = false
+
+        [telemetry]
+        service_name = "orders"
+        environment = "staging"
+
+        [telemetry.metrics]
+        histogram_buckets = [0.01, 0.1, 1.0]
+
+        [telemetry.logging]
+        level = "debug"
+        format = "pretty"
+
+        [authorization]
+        mode = "rbac"
+        allow_anonymous = ["healthCheck"]
+    "#;
+
+    const EQUIVALENT_JSON: &str = r#"{
+        "server": {"http_addr": "127.0.0.1:8000", "http2_enabled": false},
+        "telemetry": {
+            "service_name": "orders",
+            "environment": "staging",
+            "metrics": {"histogram_buckets": [0.01, 0.1, 1.0]},
+            "logging": {"level": "debug", "format": "pretty"}
+        },
+        "authorization": {"mode": "rbac", "allow_anonymous": ["healthCheck"]}
+    }"#;
+
+    const EQUIVALENT_YAML: &str = "
+server:
+  http_addr: 127.0.0.1:8000
+  http2_enabled: false
+telemetry:
+  service_name: orders
+  environment: staging
+  metrics:
+    histogram_buckets: [0.01, 0.1, 1.0]
+  logging:
+    level: debug
+    format: pretty
+authorization:
+  mode: rbac
+  allow_anonymous:
+    - healthCheck
+";
+
+    fn load_file(name: &str, content: &str) -> Result<ArchimedesConfig, ConfigError> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(name);
+        fs::write(&path, content).unwrap();
+        ConfigLoader::new().with_file(&path)?.load()
+    }
+
+    #[test]
+    fn test_yaml_toml_json_files_are_equivalent() {
+        let toml = load_file("config.toml", EQUIVALENT_TOML).unwrap();
+        let json = load_file("config.json", EQUIVALENT_JSON).unwrap();
+        let yaml = load_file("config.yaml", EQUIVALENT_YAML).unwrap();
+        let yml = load_file("config.yml", EQUIVALENT_YAML).unwrap();
+
+        assert_eq!(toml.server.http_addr, "127.0.0.1:8000");
+        assert_eq!(toml.telemetry.logging.format, crate::LogFormat::Pretty);
+        assert_eq!(json, toml);
+        assert_eq!(yaml, toml);
+        assert_eq!(yml, toml);
+    }
+
+    #[test]
+    fn test_yaml_unknown_field_rejected() {
+        let yaml = "server:\n  http_addr: 127.0.0.1:8000\n  unknown_field: value\n";
+
+        let result = load_file("config.yaml", yaml);
+        assert!(matches!(result, Err(ConfigError::YamlError(_))));
+        assert!(result.unwrap_err().to_string().contains("unknown_field"));
+    }
+
+    #[test]
+    fn test_yaml_env_overrides() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.yml");
+        fs::write(&path, EQUIVALENT_YAML).unwrap();
+        env::set_var("ARCHIMEDES_YAML_TEST__SERVER__HTTP_ADDR", "127.0.0.1:9000");
+
+        let config = ConfigLoader::new()
+            .with_file(&path)
+            .unwrap()
+            .with_env_prefix("ARCHIMEDES_YAML_TEST")
+            .load()
+            .unwrap();
+        env::remove_var("ARCHIMEDES_YAML_TEST__SERVER__HTTP_ADDR");
+
+        assert_eq!(config.server.http_addr, "127.0.0.1:9000");
+        assert_eq!(config.telemetry.service_name, "orders");
+    }
+
+    #[test]
+    fn test_loader_with_string_yaml() {
+        let config = ConfigLoader::new()
+            .with_string("server:\n  http_addr: 127.0.0.1:3000\n", "yaml")
+            .unwrap()
+            .load()
+            .unwrap();
+
+        assert_eq!(config.server.http_addr, "127.0.0.1:3000");
+    }
 }
@@ -4,6 +4,9 @@
 
 use serde::{Deserialize, Serialize};
 
+pub use archimedes_core::Secret;
+pub use archimedes_telemetry::{AdminConfig, MetricsAuthConfig};
+
 /// Server configuration section.
 ///
 /// Controls the HTTP server behavior including bind address, timeouts,
@@ -21,6 +24,8 @@ use serde::{Deserialize, Serialize};
 ///     request_timeout_ms: 30000,
 ///     keep_alive_secs: Some(60),
 ///     http2_enabled: true,
+///     max_requests_per_connection: Some(1000),
+///     ..Default::default()
 /// };
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -49,6 +54,39 @@ pub struct ServerConfig {
     /// Enable HTTP/2 support.
     #[serde(default = "default_http2_enabled")]
     pub http2_enabled: bool,
+
+    /// Requests served on one HTTP/1 connection before it is closed with
+    /// `Connection: close`. None keeps connections open indefinitely.
+    #[serde(default)]
+    pub max_requests_per_connection: Option<u64>,
+
+    /// Maximum concurrent HTTP/2 streams per connection.
+    #[serde(default)]
+    pub http2_max_concurrent_streams: Option<u32>,
+
+    /// Initial HTTP/2 stream-level flow control window in bytes.
+    #[serde(default)]
+    pub http2_initial_stream_window_size: Option<u32>,
+
+    /// Initial HTTP/2 connection-level flow control window in bytes.
+    #[serde(default)]
+    pub http2_initial_connection_window_size: Option<u32>,
+
+    /// Interval between HTTP/2 keep-alive pings in seconds. None disables pings.
+    #[serde(default)]
+    pub http2_keep_alive_interval_secs: Option<u64>,
+
+    /// Seconds to wait for an HTTP/2 keep-alive ping acknowledgement.
+    #[serde(default)]
+    pub http2_keep_alive_timeout_secs: Option<u64>,
+
+    /// Maximum size of the HTTP/2 header list in bytes.
+    #[serde(default)]
+    pub http2_max_header_list_size: Option<u32>,
+
+    /// Admin API served on the metrics listener. Disabled by default.
+    #[serde(default)]
+    pub admin: AdminConfig,
 }
 
 impl Default for ServerConfig {
@@ -60,6 +98,14 @@ impl Default for ServerConfig {
             request_timeout_ms: default_request_timeout(),
             keep_alive_secs: default_keep_alive(),
             http2_enabled: default_http2_enabled(),
+            max_requests_per_connection: None,
+            http2_max_concurrent_streams: None,
+            http2_initial_stream_window_size: None,
+            http2_initial_connection_window_size: None,
+            http2_keep_alive_interval_secs: None,
+            http2_keep_alive_timeout_secs: None,
+            http2_max_header_list_size: None,
+            admin: AdminConfig::default(),
         }
     }
 }
@@ -104,6 +150,22 @@ pub struct MetricsConfig {
     /// Histogram bucket boundaries for request duration.
     #[serde(default = "default_histogram_buckets")]
     pub histogram_buckets: Vec<f64>,
+
+    /// Expose process (memory, CPU, file descriptors) and Tokio runtime
+    /// metrics.
+    #[serde(default)]
+    pub process_metrics: bool,
+
+    /// Record per-request phase timings and body sizes, exposed as the
+    /// `archimedes_request_phase_duration_seconds` histogram and on the
+    /// request log. Off by default because of its overhead and cardinality.
+    #[serde(default)]
+    pub detailed_timing: bool,
+
+    /// Credentials required to scrape the metrics endpoint. `/health` on
+    /// the same listener stays open.
+    #[serde(default)]
+    pub auth: MetricsAuthConfig,
 }
 
 impl Default for MetricsConfig {
@@ -112,6 +174,9 @@ impl Default for MetricsConfig {
             enabled: true,
             addr: default_metrics_addr(),
             histogram_buckets: default_histogram_buckets(),
+            process_metrics: false,
+            detailed_timing: false,
+            auth: MetricsAuthConfig::default(),
         }
     }
 }
@@ -362,6 +427,24 @@ mod tests {
         assert_eq!(config.request_timeout_ms, 30000);
         assert_eq!(config.keep_alive_secs, Some(60));
         assert!(config.http2_enabled);
+        assert!(config.max_requests_per_connection.is_none());
+        assert!(config.http2_max_concurrent_streams.is_none());
+    }
+
+    #[test]
+    fn test_server_config_deserialize_connection_settings() {
+        let toml = r#"
+            max_requests_per_connection = 500
+            http2_max_concurrent_streams = 128
+            http2_initial_stream_window_size = 1048576
+            http2_keep_alive_interval_secs = 20
+        "#;
+        let config: ServerConfig = toml::from_str(toml).unwrap();
+        assert_eq!(config.max_requests_per_connection, Some(500));
+        assert_eq!(config.http2_max_concurrent_streams, Some(128));
+        assert_eq!(config.http2_initial_stream_window_size, Some(1_048_576));
+        assert_eq!(config.http2_keep_alive_interval_secs, Some(20));
+        assert!(config.http2_keep_alive_timeout_secs.is_none());
     }
 
     #[test]
@@ -393,6 +476,42 @@ mod tests {
         assert!(config.enabled);
         assert_eq!(config.addr, "0.0.0.0:9090");
         assert!(!config.histogram_buckets.is_empty());
+        assert!(!config.auth.is_enabled());
+    }
+
+    #[test]
+    fn test_metrics_auth_deserialize() {
+        let toml = r#"
+            [auth]
+            bearer_token = "scrape-token"
+            require_client_cert = true
+            allowed_client_subjects = ["CN=prometheus"]
+        "#;
+        let config: MetricsConfig = toml::from_str(toml).unwrap();
+        let token = config.auth.bearer_token.as_ref().unwrap();
+        assert_eq!(token.expose(), "scrape-token");
+        assert!(config.auth.require_client_cert);
+        assert_eq!(config.auth.allowed_client_subjects, ["CN=prometheus"]);
+
+        let serialized = toml::to_string(&config).unwrap();
+        assert!(!serialized.contains("scrape-token"));
+    }
+
+    #[test]
+    fn test_admin_disabled_by_default() {
+        let config: ServerConfig = toml::from_str("").unwrap();
+        assert!(!config.admin.enabled);
+
+        let toml = r#"
+            [admin]
+            enabled = true
+
+            [admin.auth]
+            bearer_token = "admin-token"
+        "#;
+        let config: ServerConfig = toml::from_str(toml).unwrap();
+        assert!(config.admin.enabled);
+        assert!(config.admin.auth.is_enabled());
     }
 
     #[test]
@@ -2,7 +2,7 @@
 //!
 //! This module provides the [`FileWatcher`] for monitoring configuration files
 //! and triggering reloads when they change. This enables hot-reload of:
-//! - Configuration files (config.toml, config.json)
+//! - Configuration files (config.toml, config.json, config.yaml)
 //! - Contract files (*.json)
 //! - OPA policy bundles (*.tar.gz, *.rego)
 //!
@@ -7,6 +7,7 @@
 use std::collections::HashSet;
 
 use crate::handler::BoxedHandler;
+use crate::operations::ContractOperation;
 
 /// Error type for handler binding operations.
 #[derive(Debug, Clone)]
@@ -99,6 +100,18 @@ impl HandlerBinder {
         }
     }
 
+    /// Create a handler binder requiring every operation of a generated
+    /// operation enum.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let binder = HandlerBinder::for_operations::<ops::Operation>();
+    /// ```
+    pub fn for_operations<O: ContractOperation>() -> Self {
+        Self::new(O::ids())
+    }
+
     /// Register a handler for an operation.
     ///
     /// # Arguments
@@ -184,10 +197,8 @@ mod tests {
 
     fn create_test_handler() -> BoxedHandler {
         Box::new(|_ctx| {
-            Box::pin(async { Ok(Bytes::new()) })
-                as std::pin::Pin<
-                    Box<dyn std::future::Future<Output = Result<Bytes, crate::ThemisError>> + Send>,
-                >
+            Box::pin(async { Ok(http::Response::new(Bytes::new())) })
+                as crate::handler::BoxedFuture<crate::handler::HandlerOutput>
         })
     }
 
@@ -198,6 +209,47 @@ mod tests {
         assert_eq!(binder.handler_count(), 0);
     }
 
+    #[test]
+    fn test_binder_for_operations() {
+        #[derive(Debug, Clone, Copy)]
+        enum Operation {
+            GetUser,
+            DeleteUser,
+        }
+
+        impl ContractOperation for Operation {
+            const ALL: &'static [Self] = &[Self::GetUser, Self::DeleteUser];
+
+            fn as_str(self) -> &'static str {
+                match self {
+                    Self::GetUser => "getUser",
+                    Self::DeleteUser => "deleteUser",
+                }
+            }
+
+            fn method(self) -> &'static str {
+                match self {
+                    Self::GetUser => "GET",
+                    Self::DeleteUser => "DELETE",
+                }
+            }
+
+            fn path(self) -> &'static str {
+                "/users/{userId}"
+            }
+        }
+
+        let mut binder = HandlerBinder::for_operations::<Operation>();
+        assert_eq!(binder.required_count(), 2);
+        binder
+            .register(Operation::GetUser.as_str(), create_test_handler())
+            .unwrap();
+        assert!(matches!(
+            binder.validate(),
+            Err(BinderError::MissingHandler(op)) if op == "deleteUser"
+        ));
+    }
+
     #[test]
     fn test_binder_register_unknown_operation() {
         let mut binder = HandlerBinder::new(vec!["op1"]);
@@ -0,0 +1,319 @@
+//! HTTP caching policies.
+//!
+//! A [`CachePolicy`] is the typed form of a `Cache-Control` header. The
+//! contract can declare one per operation, in which case the server applies
+//! it to the operation's successful responses; handlers can also build one
+//! and apply it themselves.
+//!
+//! ```rust
+//! use archimedes_core::CachePolicy;
+//!
+//! let policy = CachePolicy::public().max_age(300).stale_while_revalidate(60);
+//! assert_eq!(
+//!     policy.to_string(),
+//!     "public, max-age=300, stale-while-revalidate=60"
+//! );
+//!
+//! let mut headers = http::HeaderMap::new();
+//! policy.apply(&mut headers);
+//! assert_eq!(
+//!     headers["cache-control"],
+//!     "public, max-age=300, stale-while-revalidate=60"
+//! );
+//! assert!(headers.contains_key("expires"));
+//! ```
+
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+use http::header::{CACHE_CONTROL, EXPIRES};
+use http::{HeaderMap, HeaderValue};
+
+/// Furthest ahead `Expires` is set, one year.
+const MAX_EXPIRES_SECS: u64 = 365 * 24 * 60 * 60;
+
+/// Who may store a response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheScope {
+    /// Shared caches, such as CDNs and proxies, may store the response.
+    Public,
+    /// Only the client's own cache may store the response.
+    Private,
+}
+
+/// The directives of a `Cache-Control` response header.
+///
+/// Directives are serialized in a fixed order, so equal policies always
+/// produce the same header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CachePolicy {
+    scope: Option<CacheScope>,
+    no_cache: bool,
+    no_store: bool,
+    max_age: Option<u64>,
+    s_maxage: Option<u64>,
+    must_revalidate: bool,
+    immutable: bool,
+    stale_while_revalidate: Option<u64>,
+    stale_if_error: Option<u64>,
+}
+
+impl CachePolicy {
+    /// Creates a policy without directives, for building on.
+    const fn empty() -> Self {
+        Self {
+            scope: None,
+            no_cache: false,
+            no_store: false,
+            max_age: None,
+            s_maxage: None,
+            must_revalidate: false,
+            immutable: false,
+            stale_while_revalidate: None,
+            stale_if_error: None,
+        }
+    }
+
+    /// Creates a policy any cache may store responses under (`public`).
+    #[must_use]
+    pub fn public() -> Self {
+        Self {
+            scope: Some(CacheScope::Public),
+            ..Self::empty()
+        }
+    }
+
+    /// Creates a policy only the client's cache may store responses under
+    /// (`private`).
+    #[must_use]
+    pub fn private() -> Self {
+        Self {
+            scope: Some(CacheScope::Private),
+            ..Self::empty()
+        }
+    }
+
+    /// Creates a policy forbidding caches to store responses (`no-store`).
+    #[must_use]
+    pub fn no_store() -> Self {
+        Self {
+            no_store: true,
+            ..Self::empty()
+        }
+    }
+
+    /// Creates a policy requiring caches to revalidate responses before
+    /// every use (`no-cache`).
+    #[must_use]
+    pub fn no_cache() -> Self {
+        Self {
+            no_cache: true,
+            ..Self::empty()
+        }
+    }
+
+    /// Sets how many seconds a response stays fresh (`max-age`).
+    #[must_use]
+    pub fn max_age(mut self, seconds: u64) -> Self {
+        self.max_age = Some(seconds);
+        self
+    }
+
+    /// Sets how many seconds a response stays fresh in shared caches,
+    /// overriding `max-age` there (`s-maxage`).
+    #[must_use]
+    pub fn s_maxage(mut self, seconds: u64) -> Self {
+        self.s_maxage = Some(seconds);
+        self
+    }
+
+    /// Forbids caches to serve a stale response without revalidating it
+    /// (`must-revalidate`).
+    #[must_use]
+    pub fn must_revalidate(mut self) -> Self {
+        self.must_revalidate = true;
+        self
+    }
+
+    /// Marks the response as never changing while fresh (`immutable`).
+    #[must_use]
+    pub fn immutable(mut self) -> Self {
+        self.immutable = true;
+        self
+    }
+
+    /// Lets caches serve a stale response for this many seconds while they
+    /// revalidate it in the background (`stale-while-revalidate`).
+    #[must_use]
+    pub fn stale_while_revalidate(mut self, seconds: u64) -> Self {
+        self.stale_while_revalidate = Some(seconds);
+        self
+    }
+
+    /// Lets caches serve a stale response for this many seconds when
+    /// revalidating it fails (`stale-if-error`).
+    #[must_use]
+    pub fn stale_if_error(mut self, seconds: u64) -> Self {
+        self.stale_if_error = Some(seconds);
+        self
+    }
+
+    /// Returns who may store responses, if the policy says.
+    #[must_use]
+    pub fn scope(&self) -> Option<CacheScope> {
+        self.scope
+    }
+
+    /// Returns the freshness lifetime in seconds, if set.
+    #[must_use]
+    pub fn max_age_secs(&self) -> Option<u64> {
+        self.max_age
+    }
+
+    /// Returns `true` if shared caches may reuse responses without asking
+    /// the server.
+    #[must_use]
+    pub fn is_shareable(&self) -> bool {
+        !matches!(self.scope, Some(CacheScope::Private)) && !self.no_store && !self.no_cache
+    }
+
+    /// Returns the `Cache-Control` header value.
+    #[must_use]
+    pub fn header_value(&self) -> HeaderValue {
+        HeaderValue::try_from(self.to_string())
+            .unwrap_or_else(|_| HeaderValue::from_static("no-store"))
+    }
+
+    /// Sets `Cache-Control`, and `Expires` for HTTP/1.0 caches, replacing
+    /// any already set.
+    ///
+    /// `Expires` is the end of the freshness lifetime for a shareable
+    /// policy with a `max-age`, and `0` (already expired) for one shared
+    /// caches must not reuse, since HTTP/1.0 caches ignore `private`,
+    /// `no-store` and `no-cache`.
+    pub fn apply(&self, headers: &mut HeaderMap) {
+        self.apply_at(headers, SystemTime::now());
+    }
+
+    /// Sets the headers as of `now`; see [`apply`](Self::apply).
+    fn apply_at(&self, headers: &mut HeaderMap, now: SystemTime) {
+        headers.insert(CACHE_CONTROL, self.header_value());
+        if !self.is_shareable() {
+            headers.insert(EXPIRES, HeaderValue::from_static("0"));
+        } else if let Some(max_age) = self.max_age {
+            // HTTP/1.1 servers should not send `Expires` more than a year
+            // ahead (RFC 2616, section 14.21)
+            let expires = now + Duration::from_secs(max_age.min(MAX_EXPIRES_SECS));
+            if let Ok(value) = HeaderValue::try_from(httpdate::fmt_http_date(expires)) {
+                headers.insert(EXPIRES, value);
+            }
+        } else {
+            headers.remove(EXPIRES);
+        }
+    }
+}
+
+impl fmt::Display for CachePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut directives = Vec::new();
+        match self.scope {
+            Some(CacheScope::Public) => directives.push("public".to_string()),
+            Some(CacheScope::Private) => directives.push("private".to_string()),
+            None => {}
+        }
+        if self.no_cache {
+            directives.push("no-cache".to_string());
+        }
+        if self.no_store {
+            directives.push("no-store".to_string());
+        }
+        if let Some(seconds) = self.max_age {
+            directives.push(format!("max-age={seconds}"));
+        }
+        if let Some(seconds) = self.s_maxage {
+            directives.push(format!("s-maxage={seconds}"));
+        }
+        if self.must_revalidate {
+            directives.push("must-revalidate".to_string());
+        }
+        if self.immutable {
+            directives.push("immutable".to_string());
+        }
+        if let Some(seconds) = self.stale_while_revalidate {
+            directives.push(format!("stale-while-revalidate={seconds}"));
+        }
+        if let Some(seconds) = self.stale_if_error {
+            directives.push(format!("stale-if-error={seconds}"));
+        }
+        f.write_str(&directives.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directive_formatting() {
+        let cases = [
+            (CachePolicy::public(), "public"),
+            (CachePolicy::private(), "private"),
+            (CachePolicy::no_store(), "no-store"),
+            (CachePolicy::no_cache(), "no-cache"),
+            (CachePolicy::public().max_age(300), "public, max-age=300"),
+            (
+                CachePolicy::private().max_age(60).must_revalidate(),
+                "private, max-age=60, must-revalidate",
+            ),
+            (
+                CachePolicy::public().max_age(31_536_000).immutable(),
+                "public, max-age=31536000, immutable",
+            ),
+            (
+                CachePolicy::public().s_maxage(600).max_age(60),
+                "public, max-age=60, s-maxage=600",
+            ),
+            (
+                CachePolicy::public()
+                    .max_age(300)
+                    .stale_while_revalidate(60)
+                    .stale_if_error(86_400),
+                "public, max-age=300, stale-while-revalidate=60, stale-if-error=86400",
+            ),
+            (CachePolicy::no_cache().max_age(0), "no-cache, max-age=0"),
+        ];
+        for (policy, expected) in cases {
+            assert_eq!(policy.to_string(), expected);
+            assert_eq!(policy.header_value(), expected);
+        }
+    }
+
+    #[test]
+    fn test_expires_follows_max_age() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(784_111_777);
+        let mut headers = HeaderMap::new();
+
+        CachePolicy::public()
+            .max_age(60)
+            .apply_at(&mut headers, now);
+        assert_eq!(headers[CACHE_CONTROL], "public, max-age=60");
+        assert_eq!(headers[EXPIRES], "Sun, 06 Nov 1994 08:50:37 GMT");
+
+        CachePolicy::private()
+            .max_age(60)
+            .apply_at(&mut headers, now);
+        assert_eq!(headers[EXPIRES], "0");
+
+        CachePolicy::public()
+            .max_age(u64::MAX)
+            .apply_at(&mut headers, now);
+        assert_eq!(headers[EXPIRES], "Mon, 06 Nov 1995 08:49:37 GMT");
+
+        CachePolicy::public().apply_at(&mut headers, now);
+        assert!(!headers.contains_key(EXPIRES));
+
+        CachePolicy::no_store().apply_at(&mut headers, now);
+        assert_eq!(headers[CACHE_CONTROL], "no-store");
+        assert_eq!(headers[EXPIRES], "0");
+    }
+}
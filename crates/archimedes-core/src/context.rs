@@ -3,8 +3,14 @@
 //! The [`RequestContext`] carries all per-request state through the middleware
 //! pipeline and into handlers.
 
+use std::sync::Arc;
 use std::time::Instant;
 
+use archimedes_router::{UrlForError, UrlGenerator};
+
+use crate::di::Container;
+use crate::obligations::Obligations;
+
 // Re-export from shared platform types
 pub use themis_platform_types::{CallerIdentity, RequestId};
 
@@ -42,6 +48,21 @@ pub struct RequestContext {
     /// The operation ID from the contract (e.g., "getUser").
     operation_id: Option<String>,
 
+    /// The tenant the request was made on behalf of.
+    tenant_id: Option<String>,
+
+    /// The locale negotiated from `Accept-Language`.
+    locale: Option<String>,
+
+    /// Generator for URLs of other operations.
+    url_generator: Option<UrlGenerator>,
+
+    /// Services of the app serving the request.
+    container: Option<Arc<Container>>,
+
+    /// Obligations attached to the authorization decision.
+    obligations: Obligations,
+
     /// When the request started processing.
     #[allow(dead_code)]
     started_at: Instant,
@@ -59,6 +80,11 @@ impl RequestContext {
             trace_id: None,
             span_id: None,
             operation_id: None,
+            tenant_id: None,
+            locale: None,
+            url_generator: None,
+            container: None,
+            obligations: Obligations::default(),
             started_at: Instant::now(),
         }
     }
@@ -72,6 +98,11 @@ impl RequestContext {
             trace_id: None,
             span_id: None,
             operation_id: None,
+            tenant_id: None,
+            locale: None,
+            url_generator: None,
+            container: None,
+            obligations: Obligations::default(),
             started_at: Instant::now(),
         }
     }
@@ -169,6 +200,162 @@ impl RequestContext {
         self
     }
 
+    /// Returns the tenant ID if set.
+    #[must_use]
+    pub fn tenant_id(&self) -> Option<&str> {
+        self.tenant_id.as_deref()
+    }
+
+    /// Sets the tenant the request was made on behalf of.
+    pub fn set_tenant_id(&mut self, tenant_id: impl Into<String>) {
+        self.tenant_id = Some(tenant_id.into());
+    }
+
+    /// Returns a new context with the specified tenant ID.
+    #[must_use]
+    pub fn with_tenant_id(mut self, tenant_id: impl Into<String>) -> Self {
+        self.tenant_id = Some(tenant_id.into());
+        self
+    }
+
+    /// Returns the locale negotiated for the request, if a message catalog
+    /// is configured.
+    ///
+    /// This is the locale error messages are rendered in, so handlers can
+    /// answer in the same language.
+    #[must_use]
+    pub fn locale(&self) -> Option<&str> {
+        self.locale.as_deref()
+    }
+
+    /// Sets the locale negotiated for the request.
+    pub fn set_locale(&mut self, locale: impl Into<String>) {
+        self.locale = Some(locale.into());
+    }
+
+    /// Returns a new context with the specified locale.
+    #[must_use]
+    pub fn with_locale(mut self, locale: impl Into<String>) -> Self {
+        self.locale = Some(locale.into());
+        self
+    }
+
+    /// Sets the generator used by [`url_for`](Self::url_for).
+    pub fn set_url_generator(&mut self, url_generator: UrlGenerator) {
+        self.url_generator = Some(url_generator);
+    }
+
+    /// Returns a new context with the specified URL generator.
+    #[must_use]
+    pub fn with_url_generator(mut self, url_generator: UrlGenerator) -> Self {
+        self.url_generator = Some(url_generator);
+        self
+    }
+
+    /// Returns a new context resolving services from `container`.
+    #[must_use]
+    pub fn with_container(mut self, container: Arc<Container>) -> Self {
+        self.container = Some(container);
+        self
+    }
+
+    /// Returns the DI container of the app serving the request, if any.
+    #[must_use]
+    pub fn container(&self) -> Option<&Container> {
+        self.container.as_deref()
+    }
+
+    /// Returns the obligations the authorization policy attached to the
+    /// request. Empty when the policy returned none.
+    #[must_use]
+    pub const fn obligations(&self) -> &Obligations {
+        &self.obligations
+    }
+
+    /// Sets the obligations of the authorization decision.
+    pub fn set_obligations(&mut self, obligations: Obligations) {
+        self.obligations = obligations;
+    }
+
+    /// Returns a new context with the specified obligations.
+    #[must_use]
+    pub fn with_obligations(mut self, obligations: Obligations) -> Self {
+        self.obligations = obligations;
+        self
+    }
+
+    /// Builds the URL of another operation, including the server base path.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UrlForError::Unavailable`] if the context has no URL
+    /// generator, or the router's error if the URL cannot be built.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use archimedes_core::RequestContext;
+    /// use archimedes_router::{MethodRouter, Router, UrlGenerator};
+    ///
+    /// let mut router = Router::new();
+    /// router.insert("/users/{id}", MethodRouter::new().get("getUser"));
+    ///
+    /// let ctx = RequestContext::new().with_url_generator(UrlGenerator::new(router));
+    /// assert_eq!(ctx.url_for("getUser", &[("id", "42")]).unwrap(), "/users/42");
+    /// ```
+    pub fn url_for(
+        &self,
+        operation_id: &str,
+        params: &[(&str, &str)],
+    ) -> Result<String, UrlForError> {
+        self.url_for_with_query(operation_id, params, &[])
+    }
+
+    /// Builds the URL of another operation with query parameters appended.
+    ///
+    /// # Errors
+    ///
+    /// See [`url_for`](Self::url_for).
+    pub fn url_for_with_query(
+        &self,
+        operation_id: &str,
+        params: &[(&str, &str)],
+        query: &[(&str, &str)],
+    ) -> Result<String, UrlForError> {
+        self.url_generator
+            .as_ref()
+            .ok_or(UrlForError::Unavailable)?
+            .url_for_with_query(operation_id, params, query)
+    }
+
+    /// Records a business field, such as a tenant or order ID, on the
+    /// request span.
+    ///
+    /// The field is exported with the span and included in the request log
+    /// written by the telemetry stage. Values may be strings, integers or
+    /// booleans. Does nothing when the tracing stage is not running.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use archimedes_core::RequestContext;
+    ///
+    /// let ctx = RequestContext::mock();
+    /// ctx.record("order_id", 42);
+    /// ctx.record("tenant_id", "acme");
+    /// ```
+    pub fn record(&self, key: &str, value: impl Into<crate::span_fields::FieldValue>) {
+        crate::span_fields::record(key, value);
+    }
+
+    /// Returns this request's response to the caller without redacting the
+    /// fields the contract reserves for callers holding a scope.
+    ///
+    /// Does nothing when the redaction stage is not running.
+    pub fn skip_redaction(&self) {
+        crate::redaction::skip();
+    }
+
     /// Returns the elapsed time since the request started.
     #[must_use]
     pub fn elapsed(&self) -> std::time::Duration {
@@ -238,6 +425,29 @@ mod tests {
         assert_eq!(ctx.operation_id(), Some("getUser"));
     }
 
+    #[test]
+    fn test_request_context_locale() {
+        let ctx = RequestContext::new();
+        assert!(ctx.locale().is_none());
+        assert_eq!(ctx.with_locale("de-CH").locale(), Some("de-CH"));
+    }
+
+    #[test]
+    fn test_request_context_url_for() {
+        use archimedes_router::{MethodRouter, Router};
+
+        let ctx = RequestContext::new();
+        assert_eq!(ctx.url_for("getUser", &[]), Err(UrlForError::Unavailable));
+
+        let mut router = Router::new();
+        router.insert("/users/{id}", MethodRouter::new().get("getUser"));
+        let ctx = ctx.with_url_generator(UrlGenerator::new(router).base_path("/api"));
+        assert_eq!(
+            ctx.url_for("getUser", &[("id", "a b")]).unwrap(),
+            "/api/users/a%20b"
+        );
+    }
+
     #[test]
     fn test_request_context_elapsed() {
         let ctx = RequestContext::new();
@@ -245,4 +455,18 @@ mod tests {
         let elapsed = ctx.elapsed();
         assert!(elapsed >= std::time::Duration::from_millis(10));
     }
+
+    #[test]
+    fn test_request_context_container() {
+        struct Greeting(&'static str);
+
+        let ctx = RequestContext::new();
+        assert!(ctx.container().is_none());
+
+        let mut container = Container::new();
+        container.register(Arc::new(Greeting("hello")));
+        let ctx = ctx.with_container(Arc::new(container));
+        let greeting = ctx.container().unwrap().resolve::<Greeting>().unwrap();
+        assert_eq!(greeting.0, "hello");
+    }
 }
@@ -237,6 +237,9 @@ pub struct Operation {
     /// Whether this operation requires authentication.
     #[serde(default = "default_true")]
     requires_auth: bool,
+    /// Whether this operation is deprecated.
+    #[serde(default)]
+    deprecated: bool,
 }
 
 const fn default_true() -> bool {
@@ -315,6 +318,12 @@ impl Operation {
         self.requires_auth
     }
 
+    /// Returns whether this operation is deprecated.
+    #[must_use]
+    pub const fn is_deprecated(&self) -> bool {
+        self.deprecated
+    }
+
     /// Attempts to match a request path against this operation's path pattern.
     ///
     /// Returns the extracted path parameters if the path matches.
@@ -383,6 +392,7 @@ pub struct OperationBuilder {
     description: Option<String>,
     tags: Vec<String>,
     requires_auth: bool,
+    deprecated: bool,
 }
 
 impl OperationBuilder {
@@ -398,6 +408,7 @@ impl OperationBuilder {
             description: None,
             tags: Vec::new(),
             requires_auth: true,
+            deprecated: false,
         }
     }
 
@@ -457,6 +468,13 @@ impl OperationBuilder {
         self
     }
 
+    /// Marks this operation as deprecated.
+    #[must_use]
+    pub const fn deprecated(mut self) -> Self {
+        self.deprecated = true;
+        self
+    }
+
     /// Builds the operation.
     #[must_use]
     pub fn build(self) -> Operation {
@@ -471,6 +489,7 @@ impl OperationBuilder {
             description: self.description,
             tags: self.tags,
             requires_auth: self.requires_auth,
+            deprecated: self.deprecated,
         }
     }
 }
@@ -1,7 +1,8 @@
 //! Test fixtures for Archimedes development and testing.
 //!
 //! This module provides pre-built mock contracts and operations that can be used
-//! in tests across the Archimedes codebase.
+//! in tests across the Archimedes codebase, and a [`FixtureBuilder`] for
+//! contracts with the features a test needs switched on or off.
 //!
 //! # Example
 //!
@@ -192,6 +193,274 @@ pub fn order_service_contract() -> Contract {
         .build()
 }
 
+/// Builds contracts with toggleable features for edge-case tests.
+///
+/// Add operations one at a time or as CRUD sets with
+/// [`resource`](Self::resource), then switch features on or off:
+///
+/// - [`auth`](Self::auth): operations require authentication (default on)
+/// - [`pagination`](Self::pagination): list operations respond with a
+///   [`page_schema`] envelope instead of a bare array (default off)
+/// - [`error_schemas`](Self::error_schemas): error responses are described
+///   by [`error_schema`] (default off)
+/// - [`deprecate`](Self::deprecate): marks an operation as deprecated
+///
+/// `archimedes_sentinel::fixtures::artifact` turns the same builder into a
+/// matching `LoadedArtifact` for Sentinel tests.
+///
+/// # Example
+///
+/// ```
+/// use archimedes_core::fixtures::FixtureBuilder;
+///
+/// let contract = FixtureBuilder::new("widget-service")
+///     .resource("widget")
+///     .auth(false)
+///     .deprecate("deleteWidget")
+///     .build();
+///
+/// assert_eq!(contract.operations().len(), 5);
+/// let op = contract.get_operation("deleteWidget").unwrap();
+/// assert!(op.is_deprecated());
+/// assert!(!op.requires_auth());
+/// ```
+#[derive(Debug, Clone)]
+pub struct FixtureBuilder {
+    name: String,
+    version: String,
+    operations: Vec<FixtureOperation>,
+    auth: bool,
+    pagination: bool,
+    error_schemas: bool,
+    deprecated: Vec<String>,
+}
+
+/// An operation added to a [`FixtureBuilder`].
+#[derive(Debug, Clone)]
+struct FixtureOperation {
+    operation: Operation,
+    /// Item schema, for list operations.
+    list_item: Option<MockSchema>,
+}
+
+impl FixtureBuilder {
+    /// Creates a builder for a contract with no operations.
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            version: "1.0.0".to_string(),
+            operations: Vec::new(),
+            auth: true,
+            pagination: false,
+            error_schemas: false,
+            deprecated: Vec::new(),
+        }
+    }
+
+    /// Sets the contract version.
+    #[must_use]
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = version.into();
+        self
+    }
+
+    /// Adds an operation.
+    #[must_use]
+    pub fn operation(mut self, operation: Operation) -> Self {
+        self.operations.push(FixtureOperation {
+            operation,
+            list_item: None,
+        });
+        self
+    }
+
+    /// Adds a list operation responding with a collection of `item`.
+    ///
+    /// The response schema is set when the contract is built, depending on
+    /// [`pagination`](Self::pagination).
+    #[must_use]
+    pub fn list_operation(mut self, operation: Operation, item: MockSchema) -> Self {
+        self.operations.push(FixtureOperation {
+            operation,
+            list_item: Some(item),
+        });
+        self
+    }
+
+    /// Adds CRUD operations for a resource.
+    ///
+    /// For `"widget"` this adds `listWidgets` and `createWidget` on
+    /// `/widgets`, and `getWidget`, `updateWidget`, and `deleteWidget` on
+    /// `/widgets/{widgetId}`. `createWidget` requires a body with a `name`;
+    /// `updateWidget` takes an optional one.
+    #[must_use]
+    pub fn resource(self, name: &str) -> Self {
+        let title = capitalize(name);
+        let collection = format!("/{name}s");
+        let item = format!("{collection}/{{{name}Id}}");
+        let schema = MockSchema::object(vec![
+            ("id", MockSchema::string().required()),
+            ("name", MockSchema::string().required()),
+        ]);
+
+        self.list_operation(
+            Operation::builder(format!("list{title}s"))
+                .method(Method::GET)
+                .path(&collection)
+                .tag(name)
+                .build(),
+            schema.clone(),
+        )
+        .operation(
+            Operation::builder(format!("get{title}"))
+                .method(Method::GET)
+                .path(&item)
+                .tag(name)
+                .response_schema(schema.clone())
+                .build(),
+        )
+        .operation(
+            Operation::builder(format!("create{title}"))
+                .method(Method::POST)
+                .path(collection)
+                .tag(name)
+                .request_schema(
+                    MockSchema::object(vec![("name", MockSchema::string().required())]).required(),
+                )
+                .response_schema(schema.clone())
+                .build(),
+        )
+        .operation(
+            Operation::builder(format!("update{title}"))
+                .method(Method::PUT)
+                .path(&item)
+                .tag(name)
+                .request_schema(MockSchema::object(vec![("name", MockSchema::string())]))
+                .response_schema(schema)
+                .build(),
+        )
+        .operation(
+            Operation::builder(format!("delete{title}"))
+                .method(Method::DELETE)
+                .path(item)
+                .tag(name)
+                .build(),
+        )
+    }
+
+    /// Sets whether operations require authentication.
+    ///
+    /// When off, every operation is built without authentication. When on
+    /// (the default), operations keep their own setting.
+    #[must_use]
+    pub fn auth(mut self, enabled: bool) -> Self {
+        self.auth = enabled;
+        self
+    }
+
+    /// Sets whether list operations respond with a [`page_schema`].
+    #[must_use]
+    pub fn pagination(mut self, enabled: bool) -> Self {
+        self.pagination = enabled;
+        self
+    }
+
+    /// Sets whether error responses are described by [`error_schema`].
+    ///
+    /// Contracts have a single response schema per operation, so this only
+    /// affects artifacts built from the fixture.
+    #[must_use]
+    pub fn error_schemas(mut self, enabled: bool) -> Self {
+        self.error_schemas = enabled;
+        self
+    }
+
+    /// Marks an operation as deprecated.
+    #[must_use]
+    pub fn deprecate(mut self, operation_id: impl Into<String>) -> Self {
+        self.deprecated.push(operation_id.into());
+        self
+    }
+
+    /// Returns whether error responses are described by [`error_schema`].
+    #[must_use]
+    pub const fn has_error_schemas(&self) -> bool {
+        self.error_schemas
+    }
+
+    /// Builds the contract.
+    #[must_use]
+    pub fn build(&self) -> Contract {
+        Contract::builder(&self.name)
+            .version(&self.version)
+            .operations(self.operations.iter().map(|op| self.build_operation(op)))
+            .build()
+    }
+
+    fn build_operation(&self, fixture: &FixtureOperation) -> Operation {
+        let op = &fixture.operation;
+        let mut builder = Operation::builder(op.operation_id())
+            .method(op.method().clone())
+            .path(op.path())
+            .requires_auth(self.auth && op.requires_auth());
+        if let Some(description) = op.description() {
+            builder = builder.description(description);
+        }
+        for tag in op.tags() {
+            builder = builder.tag(tag);
+        }
+        if let Some(schema) = op.request_schema() {
+            builder = builder.request_schema(schema.clone());
+        }
+        let response = match &fixture.list_item {
+            Some(item) if self.pagination => Some(page_schema(item.clone())),
+            Some(item) => Some(MockSchema::array(item.clone())),
+            None => op.response_schema().cloned(),
+        };
+        if let Some(schema) = response {
+            builder = builder.response_schema(schema);
+        }
+        if op.is_deprecated() || self.deprecated.iter().any(|id| id == op.operation_id()) {
+            builder = builder.deprecated();
+        }
+        builder.build()
+    }
+}
+
+/// Upper-cases the first character of `name`.
+fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_uppercase().chain(chars).collect()
+    })
+}
+
+/// Returns a page envelope schema around `item`.
+#[must_use]
+pub fn page_schema(item: MockSchema) -> MockSchema {
+    MockSchema::object(vec![
+        ("items", MockSchema::array(item).required()),
+        ("total", MockSchema::integer().required()),
+        ("page", MockSchema::integer().required()),
+        ("pageSize", MockSchema::integer().required()),
+    ])
+}
+
+/// Returns the error envelope schema.
+#[must_use]
+pub fn error_schema() -> MockSchema {
+    MockSchema::object(vec![(
+        "error",
+        MockSchema::object(vec![
+            ("code", MockSchema::string().required()),
+            ("message", MockSchema::string().required()),
+            ("details", MockSchema::any()),
+        ])
+        .required(),
+    )])
+}
+
 /// Returns a user schema for testing.
 #[must_use]
 pub fn user_schema() -> MockSchema {
@@ -407,6 +676,81 @@ mod tests {
         assert_eq!(op.operation_id(), "addOrderItem");
     }
 
+    #[test]
+    fn test_fixture_builder_deprecated_and_required_body() {
+        let contract = FixtureBuilder::new("ledger")
+            .version("2.0.0")
+            .operation(
+                Operation::builder("postEntry")
+                    .method(Method::POST)
+                    .path("/entries")
+                    .request_schema(
+                        MockSchema::object(vec![("amount", MockSchema::integer().required())])
+                            .required(),
+                    )
+                    .build(),
+            )
+            .resource("account")
+            .deprecate("getAccount")
+            .build();
+
+        assert_eq!(contract.version(), "2.0.0");
+        assert_eq!(contract.operations().len(), 6);
+
+        // Deprecated operations still resolve, flagged as deprecated
+        let (op, params) = contract
+            .match_operation(&Method::GET, "/accounts/acc-1")
+            .unwrap();
+        assert_eq!(op.operation_id(), "getAccount");
+        assert!(op.is_deprecated());
+        assert_eq!(params.get("accountId"), Some(&"acc-1".to_string()));
+        let (op, _) = contract.match_operation(&Method::GET, "/accounts").unwrap();
+        assert!(!op.is_deprecated());
+
+        // The required body rejects a missing or incomplete body
+        let (op, _) = contract.match_operation(&Method::POST, "/entries").unwrap();
+        let schema = op.request_schema().unwrap();
+        assert!(schema.validate(&json!({"amount": 5})).is_ok());
+        assert!(schema.validate(&json!({})).is_err());
+        assert!(schema.validate(&json!(null)).is_err());
+
+        // The update body is optional
+        let update = contract.get_operation("updateAccount").unwrap();
+        assert!(update
+            .request_schema()
+            .unwrap()
+            .validate(&json!(null))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_fixture_builder_toggles() {
+        let plain = FixtureBuilder::new("widgets").resource("widget");
+        let list = plain.build().get_operation("listWidgets").unwrap().clone();
+        assert!(list.requires_auth());
+        assert!(list
+            .response_schema()
+            .unwrap()
+            .validate(&json!([{"id": "w-1", "name": "Widget"}]))
+            .is_ok());
+        assert!(!plain.has_error_schemas());
+
+        let toggled = plain.auth(false).pagination(true).error_schemas(true);
+        let contract = toggled.build();
+        assert!(contract.operations().iter().all(|op| !op.requires_auth()));
+        assert!(toggled.has_error_schemas());
+
+        let schema = contract
+            .get_operation("listWidgets")
+            .unwrap()
+            .response_schema()
+            .unwrap();
+        assert!(schema
+            .validate(&json!({"items": [], "total": 0, "page": 1, "pageSize": 20}))
+            .is_ok());
+        assert!(schema.validate(&json!([])).is_err());
+    }
+
     #[test]
     fn test_create_order_validation() {
         let schema = create_order_schema();
@@ -15,14 +15,19 @@ pub type BoxedFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
 ///
 /// This is the type-erased handler signature used by the macro-generated code.
 /// Handlers receive an [`InvocationContext`] containing all HTTP request details
-/// and middleware context.
-pub type BoxedHandler =
-    Box<dyn Fn(InvocationContext) -> BoxedFuture<Result<Bytes, ThemisError>> + Send + Sync>;
+/// and middleware context, and produce the full response so that status codes
+/// and headers chosen by the handler reach the client. See
+/// [`IntoResponse`](crate::response::IntoResponse) for the accepted return types.
+pub type BoxedHandler = Box<dyn Fn(InvocationContext) -> BoxedFuture<HandlerOutput> + Send + Sync>;
+
+/// The output of a [`BoxedHandler`]: the response, or an error for the server
+/// to render.
+pub type HandlerOutput = Result<http::Response<Bytes>, ThemisError>;
 
 /// Converts a handler result into a response body.
 ///
-/// This function is used by the `#[handler]` macro to convert the handler's
-/// return type into a serialized `Bytes` response.
+/// Serializes the value as JSON. The `#[handler]` macro keeps this encoding
+/// for handlers returning `Result<T: Serialize, ThemisError>`.
 ///
 /// # Errors
 ///
@@ -9,6 +9,12 @@
 //! - [`CallerIdentity`] - Authenticated caller identity (from `themis-platform-types`)
 //! - [`ThemisError`] - Standard error types
 //! - [`Handler`] - Core handler trait
+//! - [`IntoResponse`] - Conversion of handler return values into responses
+//! - [`StartupError`] - Startup failures mapped to process exit codes
+//! - [`Obligations`] - Obligations a policy attached to its decision
+//! - [`CachePolicy`] - Typed `Cache-Control` directives
+//! - [`RequestKey`] - Canonical request keys for caching and single-flight
+//! - [`ContractOperation`] - Typed operation IDs generated from a contract
 //! - [`Contract`] - Mock contract type for parallel development
 //! - [`Operation`] - API operation definition
 //! - [`MockSchema`] - Request/response schema validation
@@ -64,6 +70,12 @@
 //!
 //! // Pre-built health check contract (no auth required)
 //! let health = fixtures::health_contract();
+//!
+//! // Parameterized contract with a deprecated operation
+//! let widgets = fixtures::FixtureBuilder::new("widget-service")
+//!     .resource("widget")
+//!     .deprecate("deleteWidget")
+//!     .build();
 //! ```
 
 #![doc(html_root_url = "https://docs.rs/archimedes-core/0.1.0")]
@@ -71,6 +83,7 @@
 #![forbid(unsafe_code)]
 
 pub mod binder;
+pub mod cache;
 mod context;
 pub mod contract;
 pub mod di;
@@ -79,6 +92,16 @@ pub mod fixtures;
 pub mod handler;
 mod identity;
 mod invocation;
+pub mod obligations;
+pub mod operations;
+pub mod redaction;
+pub mod request_key;
+pub mod response;
+pub mod secret;
+pub mod span_fields;
+pub mod startup;
+mod stream;
+pub mod timing;
 
 // Re-export shared types from themis-platform-types
 pub use themis_platform_types::{
@@ -90,11 +113,22 @@ pub use themis_platform_types::{
 
 // Re-export local types
 pub use binder::{BinderError, BinderResult, HandlerBinder};
+pub use cache::{CachePolicy, CacheScope};
 pub use context::RequestContext;
 pub use contract::{Contract, MockSchema, Operation, ValidationError};
 pub use error::{ErrorCategory, ErrorDetail, ErrorEnvelope, ThemisError, ThemisResult};
 pub use handler::Handler;
 pub use invocation::{InvocationContext, InvocationContextBuilder};
+pub use obligations::Obligations;
+pub use operations::{ContractOperation, UnknownOperation};
+pub use request_key::{RequestKey, RequestKeyBuilder};
+pub use response::{IntoResponse, Trailers};
+pub use secret::Secret;
+pub use startup::{StartupCategory, StartupError};
+pub use stream::{StreamOutcome, StreamStatus, ERROR_MESSAGE_TRAILER, STREAM_STATUS_TRAILER};
+
+// Re-export reverse routing types used by `RequestContext::url_for`
+pub use archimedes_router::{UrlForError, UrlGenerator};
 
 // Keep local identity module for Archimedes-specific extensions
 pub use identity::CallerIdentityExt;
@@ -0,0 +1,153 @@
+//! Authorization obligations.
+//!
+//! Beyond allowing or denying a request, a policy can attach obligations
+//! the service is expected to honor, such as masking a field or recording
+//! the access. [`Obligations`] is the map of them, keyed by obligation
+//! name. Archimedes does not interpret the keys: every key the policy
+//! returns is passed through, so handlers and response filters can act on
+//! obligations the framework does not know about.
+//!
+//! With OPA, obligations are the object at `data.authz.obligations`:
+//!
+//! ```rego
+//! package authz
+//!
+//! allow if input.caller.roles[_] == "support"
+//!
+//! obligations := {"mask_fields": ["ssn"], "audit": true}
+//! ```
+//!
+//! Handlers read them from the context:
+//!
+//! ```rust
+//! use archimedes_core::{Obligations, RequestContext};
+//!
+//! let ctx = RequestContext::new().with_obligations(Obligations::from_value(
+//!     serde_json::json!({"mask_fields": ["ssn"]}),
+//! ));
+//! let masked: Vec<String> = ctx.obligations().get_as("mask_fields").unwrap_or_default();
+//! assert_eq!(masked, ["ssn"]);
+//! ```
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Obligations attached to an authorization decision, keyed by name.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Obligations(Map<String, Value>);
+
+impl Obligations {
+    /// Creates an empty set of obligations.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates obligations from a policy result.
+    ///
+    /// Anything but a JSON object carries no obligations.
+    #[must_use]
+    pub fn from_value(value: Value) -> Self {
+        match value {
+            Value::Object(map) => Self(map),
+            _ => Self::default(),
+        }
+    }
+
+    /// Adds an obligation, replacing any with the same key.
+    #[must_use]
+    pub fn with(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.insert(key, value);
+        self
+    }
+
+    /// Adds an obligation, replacing any with the same key.
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<Value>) {
+        self.0.insert(key.into(), value.into());
+    }
+
+    /// Returns the obligation under `key`.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.0.get(key)
+    }
+
+    /// Returns the obligation under `key` deserialized as `T`, or `None` if
+    /// it is absent or has another shape.
+    #[must_use]
+    pub fn get_as<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        self.0.get(key).and_then(|value| T::deserialize(value).ok())
+    }
+
+    /// Returns `true` if the obligation `key` is present.
+    #[must_use]
+    pub fn contains(&self, key: &str) -> bool {
+        self.0.contains_key(key)
+    }
+
+    /// Returns `true` if there are no obligations.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the number of obligations.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Iterates over the obligations by key.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.0.iter().map(|(key, value)| (key.as_str(), value))
+    }
+
+    /// Returns the obligations as a JSON object.
+    #[must_use]
+    pub fn as_map(&self) -> &Map<String, Value> {
+        &self.0
+    }
+}
+
+impl From<Map<String, Value>> for Obligations {
+    fn from(map: Map<String, Value>) -> Self {
+        Self(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_unknown_keys_pass_through() {
+        let obligations = Obligations::from_value(json!({
+            "mask_fields": ["ssn"],
+            "x-vendor-retention": {"days": 30},
+        }));
+        assert_eq!(obligations.len(), 2);
+        assert_eq!(
+            obligations.get("x-vendor-retention"),
+            Some(&json!({"days": 30}))
+        );
+        assert_eq!(
+            obligations.get_as::<Vec<String>>("mask_fields"),
+            Some(vec!["ssn".to_string()])
+        );
+        assert_eq!(obligations.get_as::<bool>("mask_fields"), None);
+        assert_eq!(
+            serde_json::to_value(&obligations).unwrap(),
+            json!({"mask_fields": ["ssn"], "x-vendor-retention": {"days": 30}})
+        );
+    }
+
+    #[test]
+    fn test_non_object_is_empty() {
+        assert!(Obligations::from_value(json!(["audit"])).is_empty());
+        assert!(Obligations::from_value(Value::Null).is_empty());
+        assert!(Obligations::new().with("audit", true).contains("audit"));
+    }
+}
@@ -0,0 +1,78 @@
+//! Typed operation IDs.
+//!
+//! Operation IDs are plain strings at runtime, so a typo in
+//! `registry.register("getUsr", ...)` only shows up when the operation is
+//! called. `archimedes_sentinel::codegen` generates, from the contract, a
+//! constant per operation and an enum with one variant per operation that
+//! implements [`ContractOperation`]. Using the constants turns a typo into a
+//! compile error, and matching on the enum makes the compiler point at every
+//! place that must change when the contract gains an operation.
+//!
+//! ```rust
+//! use archimedes_core::ContractOperation;
+//!
+//! #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+//! enum Operation {
+//!     GetUser,
+//!     DeleteUser,
+//! }
+//!
+//! impl ContractOperation for Operation {
+//!     const ALL: &'static [Self] = &[Self::GetUser, Self::DeleteUser];
+//!
+//!     fn as_str(self) -> &'static str {
+//!         match self {
+//!             Self::GetUser => "getUser",
+//!             Self::DeleteUser => "deleteUser",
+//!         }
+//!     }
+//!
+//!     fn method(self) -> &'static str {
+//!         match self {
+//!             Self::GetUser => "GET",
+//!             Self::DeleteUser => "DELETE",
+//!         }
+//!     }
+//!
+//!     fn path(self) -> &'static str {
+//!         "/users/{userId}"
+//!     }
+//! }
+//!
+//! let ids: Vec<_> = Operation::ALL.iter().map(|op| op.as_str()).collect();
+//! assert_eq!(ids, ["getUser", "deleteUser"]);
+//! ```
+
+use std::fmt;
+
+/// The operations of a contract, usually generated from it.
+pub trait ContractOperation: Copy + fmt::Debug + Send + Sync + 'static {
+    /// Every operation of the contract, in contract order.
+    const ALL: &'static [Self];
+
+    /// Returns the operation ID.
+    fn as_str(self) -> &'static str;
+
+    /// Returns the HTTP method of the operation, in uppercase.
+    fn method(self) -> &'static str;
+
+    /// Returns the path template of the operation.
+    fn path(self) -> &'static str;
+
+    /// Returns the IDs of every operation, in contract order.
+    fn ids() -> Vec<&'static str> {
+        Self::ALL.iter().map(|op| op.as_str()).collect()
+    }
+}
+
+/// Error returned when parsing an operation ID the contract does not declare.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownOperation(pub String);
+
+impl fmt::Display for UnknownOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown operation: {}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownOperation {}
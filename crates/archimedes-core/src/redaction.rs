@@ -0,0 +1,90 @@
+//! Per-request opt-out of response redaction.
+//!
+//! The redaction stage runs each request inside a [`scope`]. A handler
+//! returning a response that must reach the caller unredacted, for example
+//! an export already filtered by the handler itself, calls
+//! [`RequestContext::skip_redaction`](crate::RequestContext::skip_redaction)
+//! or [`skip`], and the stage leaves the response alone.
+//!
+//! Outside a scope, for example when the redaction stage is not in the
+//! pipeline, skipping is a no-op.
+//!
+//! # Example
+//!
+//! ```rust
+//! use archimedes_core::redaction::{self, RedactionOptOut};
+//!
+//! # tokio_test::block_on(async {
+//! let opt_out = RedactionOptOut::new();
+//! redaction::scope(opt_out.clone(), async {
+//!     redaction::skip();
+//! })
+//! .await;
+//!
+//! assert!(opt_out.is_skipped());
+//! # });
+//! ```
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+tokio::task_local! {
+    static OPT_OUT: RedactionOptOut;
+}
+
+/// Shared flag recording whether a request opted out of redaction.
+///
+/// Cloning the handle shares the underlying flag.
+#[derive(Debug, Clone, Default)]
+pub struct RedactionOptOut(Arc<AtomicBool>);
+
+impl RedactionOptOut {
+    /// Creates a flag that is not set.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Opts the request out of redaction.
+    pub fn skip(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if the request opted out of redaction.
+    #[must_use]
+    pub fn is_skipped(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Runs `future` with `opt_out` recording whether it skips redaction.
+pub async fn scope<F: Future>(opt_out: RedactionOptOut, future: F) -> F::Output {
+    OPT_OUT.scope(opt_out, future).await
+}
+
+/// Opts the current request out of redaction, if redaction is running.
+pub fn skip() {
+    // Outside a scope there is nothing to opt out of
+    let _ = OPT_OUT.try_with(RedactionOptOut::skip);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_skip_outside_scope_is_noop() {
+        skip();
+    }
+
+    #[tokio::test]
+    async fn test_scope_records_skip() {
+        let opt_out = RedactionOptOut::new();
+        scope(opt_out.clone(), async {}).await;
+        assert!(!opt_out.is_skipped());
+
+        scope(opt_out.clone(), async { skip() }).await;
+        assert!(opt_out.is_skipped());
+    }
+}
@@ -0,0 +1,413 @@
+//! Canonical request keys for caching and single-flight.
+//!
+//! Requests that ask for the same thing should share a cache entry, or a
+//! single in-flight upstream call, even when they spell it differently.
+//! A [`RequestKey`] is built from a canonical form of the request:
+//!
+//! - the method, uppercased
+//! - the path, with repeated and trailing slashes removed, `.` and `..`
+//!   segments resolved, and percent-encoding normalized
+//! - the query parameters, decoded and sorted by name; repeated values of a
+//!   parameter keep their order, since it may matter
+//! - the values of the `Vary` headers, by lowercase name
+//!
+//! Each operation configures its own [`RequestKeyBuilder`] with the query
+//! parameters and headers that select its response, and reuses it for
+//! every request.
+//!
+//! ```rust
+//! use archimedes_core::RequestKey;
+//! use http::{header, HeaderMap, Method, Uri};
+//!
+//! let keys = RequestKey::builder()
+//!     .ignore_query_params(["utm_source"])
+//!     .vary(header::ACCEPT_LANGUAGE);
+//!
+//! let mut headers = HeaderMap::new();
+//! headers.insert(header::ACCEPT_LANGUAGE, "de".parse().unwrap());
+//!
+//! let a = keys.build(&Method::GET, &Uri::from_static("/items?b=2&a=1"), &headers);
+//! let b = keys.build(
+//!     &Method::GET,
+//!     &Uri::from_static("/items/?a=1&utm_source=mail&b=2"),
+//!     &headers,
+//! );
+//! assert_eq!(a, b);
+//! assert_eq!(a.digest(), b.digest());
+//! ```
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use http::{HeaderMap, HeaderName, Method, Uri};
+
+/// FNV-1a 64-bit offset basis.
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// FNV-1a 64-bit prime.
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// The canonical key of a request.
+///
+/// Keys are equal when their canonical forms are; the [`digest`] is a
+/// stable hash of the canonical form, the same across processes and
+/// releases, for use as a compact cache key.
+///
+/// [`digest`]: RequestKey::digest
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RequestKey {
+    canonical: String,
+}
+
+impl RequestKey {
+    /// Creates a builder keying on every query parameter and no headers.
+    #[must_use]
+    pub fn builder() -> RequestKeyBuilder {
+        RequestKeyBuilder::new()
+    }
+
+    /// Returns the canonical form of the request.
+    #[must_use]
+    pub fn canonical(&self) -> &str {
+        &self.canonical
+    }
+
+    /// Returns the stable 64-bit hash of the canonical form.
+    #[must_use]
+    pub fn digest(&self) -> u64 {
+        self.canonical.bytes().fold(FNV_OFFSET, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+        })
+    }
+}
+
+/// Formats the digest as 16 hex digits.
+impl fmt::Display for RequestKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.digest())
+    }
+}
+
+/// Which query parameters take part in a key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum QueryParams {
+    /// Every parameter.
+    All,
+    /// Only the named parameters.
+    Only(BTreeSet<String>),
+    /// Every parameter but the named ones.
+    Except(BTreeSet<String>),
+}
+
+impl QueryParams {
+    fn includes(&self, name: &str) -> bool {
+        match self {
+            Self::All => true,
+            Self::Only(names) => names.contains(name),
+            Self::Except(names) => !names.contains(name),
+        }
+    }
+}
+
+/// Builds the [`RequestKey`] of requests to an operation.
+///
+/// The builder holds which query parameters and headers participate, so
+/// one builder serves every request to the operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestKeyBuilder {
+    query: QueryParams,
+    vary: Vec<HeaderName>,
+}
+
+impl Default for RequestKeyBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RequestKeyBuilder {
+    /// Creates a builder keying on every query parameter and no headers.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            query: QueryParams::All,
+            vary: Vec::new(),
+        }
+    }
+
+    /// Keys only on the named query parameters.
+    #[must_use]
+    pub fn only_query_params<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.query = QueryParams::Only(names.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Keys on every query parameter but the named ones, such as tracking
+    /// parameters.
+    #[must_use]
+    pub fn ignore_query_params<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.query = QueryParams::Except(names.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Keys on a request header, as a `Vary` header would.
+    #[must_use]
+    pub fn vary(mut self, name: HeaderName) -> Self {
+        if !self.vary.contains(&name) {
+            self.vary.push(name);
+            self.vary.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        }
+        self
+    }
+
+    /// Returns the headers the key varies on, sorted by name.
+    #[must_use]
+    pub fn vary_headers(&self) -> &[HeaderName] {
+        &self.vary
+    }
+
+    /// Builds the key of a request.
+    #[must_use]
+    pub fn build(&self, method: &Method, uri: &Uri, headers: &HeaderMap) -> RequestKey {
+        let mut canonical = method.as_str().to_ascii_uppercase();
+        canonical.push('\n');
+        canonical.push_str(&normalize_path(uri.path()));
+        canonical.push('\n');
+        canonical.push_str(&self.canonical_query(uri.query().unwrap_or_default()));
+
+        for name in &self.vary {
+            canonical.push('\n');
+            canonical.push_str(name.as_str());
+            // An absent header keys differently from an empty one
+            let values: Vec<&str> = headers
+                .get_all(name)
+                .iter()
+                .map(|value| value.to_str().unwrap_or_default().trim())
+                .collect();
+            if !values.is_empty() {
+                canonical.push(':');
+                canonical.push_str(&values.join(","));
+            }
+        }
+
+        RequestKey { canonical }
+    }
+
+    /// Returns the included query parameters, decoded, sorted by name and
+    /// re-encoded.
+    fn canonical_query(&self, query: &str) -> String {
+        let mut params: Vec<(String, String)> = query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (decode(name, true), decode(value, true))
+            })
+            .filter(|(name, _)| self.query.includes(name))
+            .collect();
+        // Stable, so repeated values keep their order
+        params.sort_by(|a, b| a.0.cmp(&b.0));
+        params
+            .iter()
+            .map(|(name, value)| format!("{}={}", encode(name), encode(value)))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+}
+
+/// Normalizes a path: repeated and trailing slashes are removed, `.` and
+/// `..` segments resolved, and percent-encoding made canonical.
+fn normalize_path(path: &str) -> String {
+    let mut segments: Vec<String> = Vec::new();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(encode(&decode(segment, false))),
+        }
+    }
+    format!("/{}", segments.join("/"))
+}
+
+/// Percent-decodes a string, reading `+` as a space in query strings.
+///
+/// Malformed escapes are kept as they are.
+fn decode(input: &str, query: bool) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = bytes
+                    .get(i + 1..i + 3)
+                    .and_then(|hex| std::str::from_utf8(hex).ok())
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                if let Some(byte) = hex {
+                    decoded.push(byte);
+                    i += 3;
+                    continue;
+                }
+                decoded.push(b'%');
+            }
+            b'+' if query => decoded.push(b' '),
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Percent-encodes every byte but the unreserved characters of RFC 3986.
+fn encode(input: &str) -> String {
+    let mut encoded = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(char::from(byte));
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::header::{ACCEPT_ENCODING, ACCEPT_LANGUAGE};
+    use http::HeaderValue;
+
+    fn key(builder: &RequestKeyBuilder, uri: &str, headers: &HeaderMap) -> RequestKey {
+        builder.build(&Method::GET, &uri.parse().unwrap(), headers)
+    }
+
+    fn language(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT_LANGUAGE, HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn test_reordered_query_params_share_key() {
+        let builder = RequestKey::builder();
+        let headers = HeaderMap::new();
+
+        let a = key(&builder, "/items?sort=name&page=2&q=red%20shoes", &headers);
+        let b = key(&builder, "/items?q=red+shoes&page=2&sort=name", &headers);
+        assert_eq!(a, b);
+        assert_eq!(a.digest(), b.digest());
+        assert_eq!(a.canonical(), "GET\n/items\npage=2&q=red%20shoes&sort=name");
+
+        // Repeated values keep their order
+        let c = key(&builder, "/items?id=2&id=1", &headers);
+        let d = key(&builder, "/items?id=1&id=2", &headers);
+        assert_ne!(c, d);
+    }
+
+    #[test]
+    fn test_varying_header_changes_key() {
+        let builder = RequestKey::builder().vary(ACCEPT_LANGUAGE);
+
+        let en = key(&builder, "/items", &language("en"));
+        let de = key(&builder, "/items", &language("de"));
+        assert_ne!(en, de);
+        assert_ne!(en.digest(), de.digest());
+        assert_eq!(en, key(&builder, "/items", &language(" en ")));
+
+        // Absent and empty headers differ
+        let absent = key(&builder, "/items", &HeaderMap::new());
+        assert_ne!(absent, key(&builder, "/items", &language("")));
+
+        // Headers outside the vary set do not participate
+        let mut gzip = language("en");
+        gzip.insert(ACCEPT_ENCODING, HeaderValue::from_static("gzip"));
+        assert_eq!(en, key(&builder, "/items", &gzip));
+    }
+
+    #[test]
+    fn test_vary_order_does_not_matter() {
+        let a = RequestKey::builder()
+            .vary(ACCEPT_LANGUAGE)
+            .vary(ACCEPT_ENCODING);
+        let b = RequestKey::builder()
+            .vary(ACCEPT_ENCODING)
+            .vary(ACCEPT_LANGUAGE)
+            .vary(ACCEPT_ENCODING);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_path_normalization() {
+        let builder = RequestKey::builder();
+        let headers = HeaderMap::new();
+        let canonical = key(&builder, "/users/42/orders", &headers);
+
+        for uri in [
+            "/users/42/orders/",
+            "//users//42/orders",
+            "/users/./42/orders",
+            "/users/7/../42/orders",
+            "/users/%34%32/orders",
+        ] {
+            assert_eq!(key(&builder, uri, &headers), canonical, "{uri}");
+        }
+        assert_eq!(key(&builder, "/", &headers).canonical(), "GET\n/\n");
+        assert_ne!(key(&builder, "/users/43/orders", &headers), canonical);
+    }
+
+    #[test]
+    fn test_query_param_selection() {
+        let headers = HeaderMap::new();
+
+        let only = RequestKey::builder().only_query_params(["page"]);
+        assert_eq!(
+            key(&only, "/items?page=2&session=a", &headers),
+            key(&only, "/items?session=b&page=2", &headers)
+        );
+        assert_ne!(
+            key(&only, "/items?page=2", &headers),
+            key(&only, "/items?page=3", &headers)
+        );
+
+        let ignore = RequestKey::builder().ignore_query_params(["utm_source"]);
+        assert_eq!(
+            key(&ignore, "/items?utm_source=mail", &headers),
+            key(&ignore, "/items", &headers)
+        );
+        assert_ne!(
+            key(&ignore, "/items?page=2", &headers),
+            key(&ignore, "/items", &headers)
+        );
+    }
+
+    #[test]
+    fn test_digest_is_stable() {
+        let key = key(&RequestKey::builder(), "/items?a=1", &HeaderMap::new());
+        assert_eq!(key.to_string(), format!("{:016x}", key.digest()));
+        // FNV-1a of the canonical form, fixed across processes
+        assert_eq!(
+            RequestKey {
+                canonical: String::new()
+            }
+            .digest(),
+            FNV_OFFSET
+        );
+
+        let post = RequestKey::builder().build(
+            &Method::POST,
+            &Uri::from_static("/items?a=1"),
+            &HeaderMap::new(),
+        );
+        assert_ne!(key, post);
+    }
+}
@@ -0,0 +1,464 @@
+//! Converting handler return values into HTTP responses.
+//!
+//! [`IntoResponse`] is the return-type contract shared by every way of
+//! registering a handler: the `#[handler]` macro, the [`HandlerBinder`] and
+//! the server's `HandlerRegistry`. Anything that sets a status, headers and
+//! a body can be returned.
+//!
+//! | Return type | Status | `Content-Type` |
+//! |-------------|--------|----------------|
+//! | `String`, `&'static str` | 200 | `text/plain; charset=utf-8` |
+//! | `StatusCode` | as given | none, empty body |
+//! | `(StatusCode, R)` | as given | from `R` |
+//! | `(StatusCode, HeaderMap, R)` | as given | from `R`, unless the map sets it |
+//! | `Result<R, E>` with `E: Into<ThemisError>` | from `R`, or the error's | from `R`, or JSON |
+//! | [`ThemisError`] | the error's | `application/json` |
+//! | `http::Response<Bytes>` | as given | as given |
+//!
+//! The extractor crate implements the trait for `Json<T>`, `JsonResponse`,
+//! `NoContent`, `Redirect` and its other response types.
+//!
+//! Responses are buffered. Bodies that are produced over time, such as an
+//! `SseStream` from `archimedes-sse`, are sent from a streaming handler
+//! instead (`HandlerRegistry::register_streaming` with the headers from
+//! `archimedes_sse::sse_response`).
+//!
+//! # Trailers
+//!
+//! Pairing a response with [`Trailers`] sends those fields after the body,
+//! as gRPC does with `grpc-status`. Trailers reach the client over HTTP/2,
+//! and over HTTP/1.1 when the client sent `TE: trailers` and the body is
+//! chunked. Since the body is buffered, the server sends the fields as
+//! ordinary headers to clients that cannot receive trailers, so they are
+//! never lost. Middleware and telemetry find them in the response
+//! extensions.
+//!
+//! ```rust
+//! use archimedes_core::response::{IntoResponse, Trailers, GRPC_STATUS_TRAILER};
+//! use http::HeaderValue;
+//!
+//! let trailers = Trailers::new().with(GRPC_STATUS_TRAILER, HeaderValue::from_static("0"));
+//! let response = ("done", trailers).into_response();
+//!
+//! let sent = response.extensions().get::<Trailers>().unwrap();
+//! assert_eq!(sent.get(GRPC_STATUS_TRAILER).unwrap(), "0");
+//! ```
+//!
+//! Handlers returning `Result<T, ThemisError>` with `T: Serialize` keep
+//! their existing behavior: `T` is serialized as a JSON `200` response.
+//!
+//! [`HandlerBinder`]: crate::binder::HandlerBinder
+//!
+//! # Example
+//!
+//! ```rust
+//! use archimedes_core::response::IntoResponse;
+//! use http::{header, HeaderMap, HeaderValue, StatusCode};
+//!
+//! let mut headers = HeaderMap::new();
+//! headers.insert(header::LOCATION, HeaderValue::from_static("/orders/42"));
+//!
+//! let response = (StatusCode::CREATED, headers, "created").into_response();
+//! assert_eq!(response.status(), StatusCode::CREATED);
+//! assert_eq!(response.headers()[header::LOCATION], "/orders/42");
+//! assert_eq!(response.body().as_ref(), b"created");
+//! ```
+
+use bytes::Bytes;
+use http::header::{self, HeaderName};
+use http::{HeaderMap, HeaderValue, Response, StatusCode};
+use serde::Serialize;
+
+use crate::{ThemisError, STREAM_STATUS_TRAILER};
+
+/// `Content-Type` of plain text responses.
+pub const TEXT_PLAIN: &str = "text/plain; charset=utf-8";
+
+/// `Content-Type` of JSON responses.
+pub const APPLICATION_JSON: &str = "application/json";
+
+/// Trailer carrying a gRPC status code; `0` means success.
+pub const GRPC_STATUS_TRAILER: &str = "grpc-status";
+
+/// A value that can be turned into an HTTP response.
+pub trait IntoResponse {
+    /// Builds the response.
+    fn into_response(self) -> Response<Bytes>;
+}
+
+impl IntoResponse for Response<Bytes> {
+    fn into_response(self) -> Response<Bytes> {
+        self
+    }
+}
+
+impl IntoResponse for String {
+    fn into_response(self) -> Response<Bytes> {
+        with_content_type(Bytes::from(self), TEXT_PLAIN)
+    }
+}
+
+impl IntoResponse for &'static str {
+    fn into_response(self) -> Response<Bytes> {
+        with_content_type(Bytes::from_static(self.as_bytes()), TEXT_PLAIN)
+    }
+}
+
+impl IntoResponse for StatusCode {
+    fn into_response(self) -> Response<Bytes> {
+        let mut response = Response::new(Bytes::new());
+        *response.status_mut() = self;
+        response
+    }
+}
+
+impl IntoResponse for ThemisError {
+    fn into_response(self) -> Response<Bytes> {
+        let body = serde_json::to_vec(&self.to_envelope(None)).unwrap_or_default();
+        let mut response = with_content_type(Bytes::from(body), APPLICATION_JSON);
+        *response.status_mut() = self.status_code();
+        response
+    }
+}
+
+impl<R: IntoResponse> IntoResponse for (StatusCode, R) {
+    fn into_response(self) -> Response<Bytes> {
+        let (status, inner) = self;
+        let mut response = inner.into_response();
+        *response.status_mut() = status;
+        response
+    }
+}
+
+impl<R: IntoResponse> IntoResponse for (StatusCode, HeaderMap, R) {
+    fn into_response(self) -> Response<Bytes> {
+        let (status, headers, inner) = self;
+        let mut response = inner.into_response();
+        *response.status_mut() = status;
+        // Headers given explicitly win over those set by the body type
+        for name in headers.keys() {
+            response.headers_mut().remove(name);
+        }
+        response.headers_mut().extend(headers);
+        response
+    }
+}
+
+impl<R, E> IntoResponse for Result<R, E>
+where
+    R: IntoResponse,
+    E: Into<ThemisError>,
+{
+    fn into_response(self) -> Response<Bytes> {
+        match self {
+            Ok(value) => value.into_response(),
+            Err(error) => error.into().into_response(),
+        }
+    }
+}
+
+impl<R: IntoResponse> IntoResponse for (R, Trailers) {
+    fn into_response(self) -> Response<Bytes> {
+        let (inner, trailers) = self;
+        let mut response = inner.into_response();
+        response.extensions_mut().insert(trailers);
+        response
+    }
+}
+
+/// Fields sent after a buffered response body.
+///
+/// Attach them by returning `(response, trailers)` from a handler. See the
+/// [module documentation](self#trailers) for how they are delivered.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Trailers(HeaderMap);
+
+impl Trailers {
+    /// Creates an empty set of trailers.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a trailer, replacing any previous value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is not a valid header name.
+    #[must_use]
+    pub fn with(mut self, name: &str, value: HeaderValue) -> Self {
+        self.insert(
+            HeaderName::try_from(name).expect("invalid trailer name"),
+            value,
+        );
+        self
+    }
+
+    /// Adds a trailer, replacing any previous value.
+    pub fn insert(&mut self, name: HeaderName, value: HeaderValue) {
+        self.0.insert(name, value);
+    }
+
+    /// Returns the value of a trailer.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&HeaderValue> {
+        self.0.get(name)
+    }
+
+    /// Returns `true` if no trailers are set.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the trailers as a header map.
+    #[must_use]
+    pub fn headers(&self) -> &HeaderMap {
+        &self.0
+    }
+
+    /// Consumes the trailers, returning the header map.
+    #[must_use]
+    pub fn into_headers(self) -> HeaderMap {
+        self.0
+    }
+
+    /// Returns the value of the `Trailer` header declaring these trailers,
+    /// or `None` if there are none.
+    #[must_use]
+    pub fn declaration(&self) -> Option<HeaderValue> {
+        let names: Vec<&str> = self.0.keys().map(HeaderName::as_str).collect();
+        if names.is_empty() {
+            return None;
+        }
+        HeaderValue::from_str(&names.join(", ")).ok()
+    }
+
+    /// Returns `true` if the trailers report a failure: a non-zero
+    /// `grpc-status`, or an `x-stream-status` of `error`.
+    #[must_use]
+    pub fn is_failure(&self) -> bool {
+        let grpc_failed = self
+            .get(GRPC_STATUS_TRAILER)
+            .is_some_and(|status| status.as_bytes() != b"0");
+        let stream_failed = self
+            .get(STREAM_STATUS_TRAILER)
+            .is_some_and(|status| status.as_bytes() == b"error");
+        grpc_failed || stream_failed
+    }
+}
+
+impl From<HeaderMap> for Trailers {
+    fn from(headers: HeaderMap) -> Self {
+        Self(headers)
+    }
+}
+
+/// Serializes `value` as a `200` JSON response.
+///
+/// # Errors
+///
+/// Returns `ThemisError::Internal` if serialization fails.
+pub fn json<T: Serialize>(value: &T) -> Result<Response<Bytes>, ThemisError> {
+    let body = serde_json::to_vec(value)
+        .map_err(|e| ThemisError::internal(format!("Failed to serialize response: {e}")))?;
+    Ok(with_content_type(Bytes::from(body), APPLICATION_JSON))
+}
+
+fn with_content_type(body: Bytes, content_type: &'static str) -> Response<Bytes> {
+    let mut response = Response::new(body);
+    response
+        .headers_mut()
+        .insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+    response
+}
+
+/// Support for the `#[handler]` macro. Not public API.
+///
+/// The macro converts a handler's return value with
+/// `(&&&HandlerReturn::new(value)).into_handler_response()`. Method
+/// resolution picks the first of these that applies:
+///
+/// 1. `Result<T: Serialize, ThemisError>`: `T` as JSON, as before
+///    [`IntoResponse`] existed.
+/// 2. `Result<R: IntoResponse, E: Into<ThemisError>>`: the response, with
+///    the error passed on to the server's error handling.
+/// 3. Any other `R: IntoResponse`.
+#[doc(hidden)]
+pub mod __private {
+    use std::cell::Cell;
+
+    use bytes::Bytes;
+    use http::Response;
+    use serde::Serialize;
+
+    use super::IntoResponse;
+    use crate::ThemisError;
+
+    /// A handler's return value awaiting conversion.
+    pub struct HandlerReturn<T>(Cell<Option<T>>);
+
+    impl<T> HandlerReturn<T> {
+        /// Wraps a return value.
+        #[must_use]
+        pub fn new(value: T) -> Self {
+            Self(Cell::new(Some(value)))
+        }
+
+        fn take(&self) -> T {
+            self.0.take().expect("handler return value converted twice")
+        }
+    }
+
+    /// Converts `Result<T: Serialize, ThemisError>` as JSON.
+    pub trait ViaSerialize {
+        /// Converts the wrapped value.
+        ///
+        /// # Errors
+        ///
+        /// Returns the handler's error, or a serialization failure.
+        fn into_handler_response(&self) -> Result<Response<Bytes>, ThemisError>;
+    }
+
+    impl<T: Serialize> ViaSerialize for &&HandlerReturn<Result<T, ThemisError>> {
+        fn into_handler_response(&self) -> Result<Response<Bytes>, ThemisError> {
+            self.take().and_then(|value| super::json(&value))
+        }
+    }
+
+    /// Converts `Result<R: IntoResponse, E: Into<ThemisError>>`.
+    pub trait ViaResult {
+        /// Converts the wrapped value.
+        ///
+        /// # Errors
+        ///
+        /// Returns the handler's error.
+        fn into_handler_response(&self) -> Result<Response<Bytes>, ThemisError>;
+    }
+
+    impl<R, E> ViaResult for &HandlerReturn<Result<R, E>>
+    where
+        R: IntoResponse,
+        E: Into<ThemisError>,
+    {
+        fn into_handler_response(&self) -> Result<Response<Bytes>, ThemisError> {
+            self.take()
+                .map(IntoResponse::into_response)
+                .map_err(Into::into)
+        }
+    }
+
+    /// Converts any other `R: IntoResponse`.
+    pub trait ViaIntoResponse {
+        /// Converts the wrapped value.
+        ///
+        /// # Errors
+        ///
+        /// Never fails; the signature matches the other conversions.
+        fn into_handler_response(&self) -> Result<Response<Bytes>, ThemisError>;
+    }
+
+    impl<R: IntoResponse> ViaIntoResponse for HandlerReturn<R> {
+        fn into_handler_response(&self) -> Result<Response<Bytes>, ThemisError> {
+            Ok(self.take().into_response())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::__private::{HandlerReturn, ViaIntoResponse, ViaResult, ViaSerialize};
+    use super::*;
+
+    #[test]
+    fn test_text() {
+        let response = "hello".into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], TEXT_PLAIN);
+        assert_eq!(response.body().as_ref(), b"hello");
+
+        let response = String::from("owned").into_response();
+        assert_eq!(response.body().as_ref(), b"owned");
+    }
+
+    #[test]
+    fn test_status_tuples() {
+        let response = StatusCode::ACCEPTED.into_response();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        assert!(response.body().is_empty());
+
+        let response = (StatusCode::CREATED, "made").into_response();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], TEXT_PLAIN);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("text/csv"));
+        headers.insert("x-total", HeaderValue::from_static("2"));
+        let response = (StatusCode::OK, headers, "a,b\n").into_response();
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "text/csv");
+        assert_eq!(
+            response
+                .headers()
+                .get_all(header::CONTENT_TYPE)
+                .iter()
+                .count(),
+            1
+        );
+        assert_eq!(response.headers()["x-total"], "2");
+    }
+
+    #[test]
+    fn test_result() {
+        let ok: Result<&'static str, ThemisError> = Ok("fine");
+        assert_eq!(ok.into_response().status(), StatusCode::OK);
+
+        let err: Result<&'static str, ThemisError> = Err(ThemisError::not_found("no order"));
+        let response = err.into_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], APPLICATION_JSON);
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["error"]["code"], "NOT_FOUND");
+    }
+
+    #[test]
+    fn test_trailers() {
+        let trailers = Trailers::new()
+            .with(GRPC_STATUS_TRAILER, HeaderValue::from_static("0"))
+            .with("x-checksum", HeaderValue::from_static("abc"));
+        assert!(!trailers.is_failure());
+        assert_eq!(trailers.declaration().unwrap(), "grpc-status, x-checksum");
+
+        let response = ((StatusCode::ACCEPTED, "queued"), trailers.clone()).into_response();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        assert_eq!(response.body().as_ref(), b"queued");
+        assert_eq!(response.extensions().get::<Trailers>(), Some(&trailers));
+
+        let failed = Trailers::new().with(GRPC_STATUS_TRAILER, HeaderValue::from_static("13"));
+        assert!(failed.is_failure());
+        assert!(Trailers::new().declaration().is_none());
+    }
+
+    #[test]
+    fn test_handler_return_resolution() {
+        // Serializable results keep their JSON encoding, even for strings
+        let value: Result<String, ThemisError> = Ok("id".to_string());
+        let response = (&&&HandlerReturn::new(value))
+            .into_handler_response()
+            .unwrap();
+        assert_eq!(response.headers()[header::CONTENT_TYPE], APPLICATION_JSON);
+        assert_eq!(response.body().as_ref(), b"\"id\"");
+
+        let value: Result<(StatusCode, &'static str), ThemisError> =
+            Err(ThemisError::conflict("taken"));
+        let error = (&&&HandlerReturn::new(value))
+            .into_handler_response()
+            .unwrap_err();
+        assert_eq!(error.status_code(), StatusCode::CONFLICT);
+
+        let response = (&&&HandlerReturn::new((StatusCode::CREATED, "made")))
+            .into_handler_response()
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], TEXT_PLAIN);
+    }
+}
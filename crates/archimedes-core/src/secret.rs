@@ -0,0 +1,102 @@
+//! Secret configuration values.
+//!
+//! [`Secret`] wraps a credential such as a bearer token so that it never
+//! appears in logs or configuration dumps: `Debug`, `Display` and
+//! serialization all print [`REDACTED`]. The value is only reachable
+//! through [`Secret::expose`].
+//!
+//! # Example
+//!
+//! ```rust
+//! use archimedes_core::Secret;
+//!
+//! let token: Secret = serde_json::from_str(r#""s3cr3t""#).unwrap();
+//! assert_eq!(token.expose(), "s3cr3t");
+//! assert_eq!(format!("{token:?}"), "[REDACTED]");
+//! assert_eq!(serde_json::to_string(&token).unwrap(), r#""[REDACTED]""#);
+//! ```
+
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Text shown in place of a secret value.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// A configuration value that must not be logged.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret(String);
+
+impl Secret {
+    /// Wraps a secret value.
+    #[must_use]
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    /// Returns the secret value.
+    #[must_use]
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns `true` if the value is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl fmt::Display for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl Serialize for Secret {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(REDACTED)
+    }
+}
+
+impl<'de> Deserialize<'de> for Secret {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_is_redacted() {
+        let secret = Secret::new("hunter2");
+        assert_eq!(secret.expose(), "hunter2");
+        assert_eq!(secret.to_string(), REDACTED);
+        assert_eq!(format!("{secret:?}"), REDACTED);
+        assert_eq!(
+            serde_json::to_value(&secret).unwrap(),
+            serde_json::json!(REDACTED)
+        );
+        assert!(!secret.is_empty());
+    }
+}
@@ -0,0 +1,199 @@
+//! Business context recorded on the request span.
+//!
+//! The tracing stage runs each request inside a [`scope`]. Handlers attach
+//! fields such as a tenant or order ID with
+//! [`RequestContext::record`](crate::RequestContext::record) or [`record`],
+//! and the tracing and telemetry stages read the collected [`SpanFields`]
+//! back to put them on the exported span and the request log.
+//!
+//! Each field is also recorded on the current `tracing` span, which keeps
+//! it if the span declared a field of that name.
+//!
+//! Outside a scope, for example when the tracing stage is not in the
+//! pipeline, recording is a no-op.
+//!
+//! # Example
+//!
+//! ```rust
+//! use archimedes_core::span_fields::{self, FieldValue, SpanFields};
+//!
+//! # tokio_test::block_on(async {
+//! let fields = SpanFields::new();
+//! span_fields::scope(fields.clone(), async {
+//!     span_fields::record("tenant_id", "acme");
+//!     span_fields::record("order_id", 42);
+//! })
+//! .await;
+//!
+//! assert_eq!(fields.get("order_id"), Some(FieldValue::Int(42)));
+//! # });
+//! ```
+
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex, PoisonError};
+
+tokio::task_local! {
+    static FIELDS: SpanFields;
+}
+
+/// Value of a recorded field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldValue {
+    /// A string.
+    Str(String),
+    /// An integer.
+    Int(i64),
+    /// A boolean.
+    Bool(bool),
+}
+
+impl fmt::Display for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Str(value) => f.write_str(value),
+            Self::Int(value) => write!(f, "{value}"),
+            Self::Bool(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+impl From<&str> for FieldValue {
+    fn from(value: &str) -> Self {
+        Self::Str(value.to_string())
+    }
+}
+
+impl From<String> for FieldValue {
+    fn from(value: String) -> Self {
+        Self::Str(value)
+    }
+}
+
+impl From<i64> for FieldValue {
+    fn from(value: i64) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<i32> for FieldValue {
+    fn from(value: i32) -> Self {
+        Self::Int(value.into())
+    }
+}
+
+impl From<u32> for FieldValue {
+    fn from(value: u32) -> Self {
+        Self::Int(value.into())
+    }
+}
+
+impl From<bool> for FieldValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+/// Shared handle to the fields recorded for a request.
+///
+/// Cloning the handle shares the underlying fields. Recording a key again
+/// replaces its value but keeps its original position.
+#[derive(Debug, Clone, Default)]
+pub struct SpanFields(Arc<Mutex<Vec<(String, FieldValue)>>>);
+
+impl SpanFields {
+    /// Creates an empty set of fields.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets `key` to `value`.
+    pub fn record(&self, key: impl Into<String>, value: impl Into<FieldValue>) {
+        let (key, value) = (key.into(), value.into());
+        let mut fields = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        match fields.iter_mut().find(|(name, _)| *name == key) {
+            Some((_, existing)) => *existing = value,
+            None => fields.push((key, value)),
+        }
+    }
+
+    /// Returns the value of `key`, if recorded.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<FieldValue> {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.clone())
+    }
+
+    /// Returns a copy of the fields in the order they were first recorded.
+    #[must_use]
+    pub fn snapshot(&self) -> Vec<(String, FieldValue)> {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+/// Runs `future` with `fields` collecting the fields recorded inside it.
+pub async fn scope<F: Future>(fields: SpanFields, future: F) -> F::Output {
+    FIELDS.scope(fields, future).await
+}
+
+/// Returns the fields of the current request, if recording is on.
+#[must_use]
+pub fn current() -> Option<SpanFields> {
+    FIELDS.try_with(Clone::clone).ok()
+}
+
+/// Sets `key` to `value` on the current request, if any.
+pub fn record(key: &str, value: impl Into<FieldValue>) {
+    let value = value.into();
+    let span = tracing::Span::current();
+    match &value {
+        FieldValue::Str(text) => span.record(key, text.as_str()),
+        FieldValue::Int(number) => span.record(key, *number),
+        FieldValue::Bool(flag) => span.record(key, *flag),
+    };
+    // Outside a scope there is nothing to record into
+    let _ = FIELDS.try_with(|fields| fields.record(key, value));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_record_outside_scope_is_noop() {
+        assert!(current().is_none());
+        record("tenant_id", "acme");
+        record("retry", true);
+    }
+
+    #[tokio::test]
+    async fn test_scope_collects_fields_in_order() {
+        let fields = SpanFields::new();
+        scope(fields.clone(), async {
+            record("tenant_id", "acme");
+            record("order_id", 7);
+            record("express", false);
+            record("order_id", 8_i64);
+        })
+        .await;
+
+        assert_eq!(
+            fields.snapshot(),
+            vec![
+                ("tenant_id".to_string(), FieldValue::Str("acme".to_string())),
+                ("order_id".to_string(), FieldValue::Int(8)),
+                ("express".to_string(), FieldValue::Bool(false)),
+            ]
+        );
+        assert_eq!(fields.get("missing"), None);
+        assert_eq!(FieldValue::Int(8).to_string(), "8");
+    }
+}
@@ -0,0 +1,274 @@
+//! Startup failures and process exit codes.
+//!
+//! A service that cannot start reports a [`StartupError`] whose
+//! [`StartupCategory`] says which part of startup failed. Each category maps
+//! to its own process exit code, so an orchestrator can tell a broken
+//! configuration from a port that is already taken without parsing logs:
+//!
+//! | Category | Exit code | Meaning |
+//! |---|---|---|
+//! | `ConfigInvalid` | 10 | The configuration could not be read or is invalid |
+//! | `ContractLoad` | 11 | The contract artifact could not be loaded |
+//! | `PolicyLoad` | 12 | The policy bundle could not be loaded |
+//! | `Bind` | 13 | The listen address could not be bound |
+//! | `TlsSetup` | 14 | Certificates or the TLS backend could not be set up |
+//! | `DependencyProbe` | 15 | A required dependency did not respond |
+//! | any, transient | 75 | Retrying may succeed, e.g. a registry fetch timed out |
+//!
+//! Transient failures all exit with [`EXIT_TRANSIENT`] (`EX_TEMPFAIL`), so an
+//! init-container wrapper can retry exactly those and give up on the rest.
+//!
+//! # Example
+//!
+//! ```rust
+//! use archimedes_core::startup::{StartupCategory, StartupError, EXIT_TRANSIENT};
+//!
+//! let err = StartupError::contract_load("no such file: contract.json");
+//! assert_eq!(err.category(), StartupCategory::ContractLoad);
+//! assert_eq!(err.exit_code(), 11);
+//!
+//! let err = StartupError::contract_load("registry fetch timed out").transient();
+//! assert_eq!(err.exit_code(), EXIT_TRANSIENT);
+//! ```
+
+use std::fmt;
+
+/// Exit code for startup failures that may succeed on retry (`EX_TEMPFAIL`).
+pub const EXIT_TRANSIENT: i32 = 75;
+
+/// The part of startup that failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StartupCategory {
+    /// The configuration could not be read or failed validation.
+    ConfigInvalid,
+    /// The contract artifact could not be loaded.
+    ContractLoad,
+    /// The policy bundle could not be loaded.
+    PolicyLoad,
+    /// The listen address could not be bound.
+    Bind,
+    /// Certificates or the TLS backend could not be set up.
+    TlsSetup,
+    /// A required dependency did not respond.
+    DependencyProbe,
+}
+
+impl StartupCategory {
+    /// Returns the `snake_case` name used in logs.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::ConfigInvalid => "config_invalid",
+            Self::ContractLoad => "contract_load",
+            Self::PolicyLoad => "policy_load",
+            Self::Bind => "bind",
+            Self::TlsSetup => "tls_setup",
+            Self::DependencyProbe => "dependency_probe",
+        }
+    }
+
+    /// Returns the process exit code for a permanent failure.
+    #[must_use]
+    pub const fn exit_code(self) -> i32 {
+        match self {
+            Self::ConfigInvalid => 10,
+            Self::ContractLoad => 11,
+            Self::PolicyLoad => 12,
+            Self::Bind => 13,
+            Self::TlsSetup => 14,
+            Self::DependencyProbe => 15,
+        }
+    }
+
+    /// Returns a hint for the operator on how to fix the failure.
+    #[must_use]
+    pub const fn remediation(self) -> &'static str {
+        match self {
+            Self::ConfigInvalid => "check the configuration file and environment variables",
+            Self::ContractLoad => "check that the contract path exists and holds a valid artifact",
+            Self::PolicyLoad => "check that the policy bundle path exists and the bundle compiles",
+            Self::Bind => "check the listen address and that the port is not already in use",
+            Self::TlsSetup => "check the certificate, key and CA paths and their permissions",
+            Self::DependencyProbe => "check that the dependency is running and reachable",
+        }
+    }
+}
+
+impl fmt::Display for StartupCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A failure that stopped a service from starting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StartupError {
+    category: StartupCategory,
+    detail: String,
+    transient: bool,
+}
+
+impl StartupError {
+    /// Creates a permanent startup error.
+    #[must_use]
+    pub fn new(category: StartupCategory, detail: impl Into<String>) -> Self {
+        Self {
+            category,
+            detail: detail.into(),
+            transient: false,
+        }
+    }
+
+    /// Creates a [`StartupCategory::ConfigInvalid`] error.
+    #[must_use]
+    pub fn config_invalid(detail: impl Into<String>) -> Self {
+        Self::new(StartupCategory::ConfigInvalid, detail)
+    }
+
+    /// Creates a [`StartupCategory::ContractLoad`] error.
+    #[must_use]
+    pub fn contract_load(detail: impl Into<String>) -> Self {
+        Self::new(StartupCategory::ContractLoad, detail)
+    }
+
+    /// Creates a [`StartupCategory::PolicyLoad`] error.
+    #[must_use]
+    pub fn policy_load(detail: impl Into<String>) -> Self {
+        Self::new(StartupCategory::PolicyLoad, detail)
+    }
+
+    /// Creates a [`StartupCategory::Bind`] error.
+    #[must_use]
+    pub fn bind(detail: impl Into<String>) -> Self {
+        Self::new(StartupCategory::Bind, detail)
+    }
+
+    /// Creates a [`StartupCategory::TlsSetup`] error.
+    #[must_use]
+    pub fn tls_setup(detail: impl Into<String>) -> Self {
+        Self::new(StartupCategory::TlsSetup, detail)
+    }
+
+    /// Creates a [`StartupCategory::DependencyProbe`] error.
+    #[must_use]
+    pub fn dependency_probe(detail: impl Into<String>) -> Self {
+        Self::new(StartupCategory::DependencyProbe, detail)
+    }
+
+    /// Marks the error as transient, meaning a retry may succeed.
+    #[must_use]
+    pub fn transient(mut self) -> Self {
+        self.transient = true;
+        self
+    }
+
+    /// Returns the category.
+    #[must_use]
+    pub fn category(&self) -> StartupCategory {
+        self.category
+    }
+
+    /// Returns what went wrong.
+    #[must_use]
+    pub fn detail(&self) -> &str {
+        &self.detail
+    }
+
+    /// Returns `true` if a retry may succeed.
+    #[must_use]
+    pub fn is_transient(&self) -> bool {
+        self.transient
+    }
+
+    /// Returns the process exit code: [`EXIT_TRANSIENT`] for transient
+    /// errors, otherwise the category's code.
+    #[must_use]
+    pub fn exit_code(&self) -> i32 {
+        if self.transient {
+            EXIT_TRANSIENT
+        } else {
+            self.category.exit_code()
+        }
+    }
+
+    /// Returns a hint for the operator on how to fix the failure.
+    #[must_use]
+    pub fn remediation(&self) -> &'static str {
+        self.category.remediation()
+    }
+
+    /// Logs the error as the final structured record before exiting.
+    pub fn log(&self) {
+        tracing::error!(
+            category = self.category.as_str(),
+            exit_code = self.exit_code(),
+            transient = self.transient,
+            detail = %self.detail,
+            remediation = self.remediation(),
+            "startup failed"
+        );
+    }
+
+    /// Logs the error and exits the process with its exit code.
+    pub fn exit(&self) -> ! {
+        self.log();
+        std::process::exit(self.exit_code())
+    }
+}
+
+impl fmt::Display for StartupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.category, self.detail)
+    }
+}
+
+impl std::error::Error for StartupError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    const CATEGORIES: [StartupCategory; 6] = [
+        StartupCategory::ConfigInvalid,
+        StartupCategory::ContractLoad,
+        StartupCategory::PolicyLoad,
+        StartupCategory::Bind,
+        StartupCategory::TlsSetup,
+        StartupCategory::DependencyProbe,
+    ];
+
+    #[test]
+    fn test_exit_codes_are_distinct() {
+        let codes: HashSet<i32> = CATEGORIES.iter().map(|c| c.exit_code()).collect();
+        assert_eq!(codes.len(), CATEGORIES.len());
+        assert!(!codes.contains(&EXIT_TRANSIENT));
+        assert!(!codes.contains(&0) && !codes.contains(&1));
+    }
+
+    #[test]
+    fn test_constructors_map_to_categories() {
+        let cases = [
+            (StartupError::config_invalid("x"), 10),
+            (StartupError::contract_load("x"), 11),
+            (StartupError::policy_load("x"), 12),
+            (StartupError::bind("x"), 13),
+            (StartupError::tls_setup("x"), 14),
+            (StartupError::dependency_probe("x"), 15),
+        ];
+        for (err, code) in cases {
+            assert_eq!(err.exit_code(), code);
+            assert!(!err.is_transient());
+            assert!(!err.remediation().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_transient_errors_share_an_exit_code() {
+        let err = StartupError::policy_load("registry fetch timed out").transient();
+        assert!(err.is_transient());
+        assert_eq!(err.category(), StartupCategory::PolicyLoad);
+        assert_eq!(err.exit_code(), EXIT_TRANSIENT);
+        assert_eq!(err.to_string(), "policy_load: registry fetch timed out");
+    }
+}
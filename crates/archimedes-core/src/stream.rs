@@ -0,0 +1,147 @@
+//! Completion status for streaming responses.
+//!
+//! A streaming response commits to its status line before the body is
+//! produced, so a failure halfway through cannot change the `200` the
+//! client already received. The [`StreamOutcome`] handle records how the
+//! stream actually ended. The producing task resolves it, the response
+//! body reports it as trailers, and telemetry reads it back to label the
+//! request with its effective outcome.
+//!
+//! # Example
+//!
+//! ```rust
+//! use archimedes_core::{StreamOutcome, StreamStatus};
+//!
+//! let outcome = StreamOutcome::new();
+//! let observer = outcome.clone();
+//!
+//! assert!(!observer.is_complete());
+//! outcome.set(StreamStatus::error("upstream cursor expired"));
+//!
+//! assert!(observer.is_error());
+//! assert_eq!(observer.get().unwrap().as_str(), "error");
+//! ```
+
+use std::sync::{Arc, OnceLock};
+
+/// Trailer carrying the final [`StreamStatus`] of a streaming response.
+pub const STREAM_STATUS_TRAILER: &str = "x-stream-status";
+
+/// Trailer carrying the error message of a failed streaming response.
+pub const ERROR_MESSAGE_TRAILER: &str = "x-error-message";
+
+/// How a streaming response ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamStatus {
+    /// The stream was produced completely.
+    Ok,
+    /// The stream was aborted part-way through.
+    Error {
+        /// Description of the failure.
+        message: String,
+    },
+}
+
+impl StreamStatus {
+    /// Creates an error status with the given message.
+    #[must_use]
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::Error {
+            message: message.into(),
+        }
+    }
+
+    /// Returns `true` if the stream failed.
+    #[must_use]
+    pub fn is_error(&self) -> bool {
+        matches!(self, Self::Error { .. })
+    }
+
+    /// Returns the value used for the `x-stream-status` trailer.
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::Error { .. } => "error",
+        }
+    }
+
+    /// Returns the error message, if the stream failed.
+    #[must_use]
+    pub fn message(&self) -> Option<&str> {
+        match self {
+            Self::Ok => None,
+            Self::Error { message } => Some(message),
+        }
+    }
+}
+
+/// Shared handle to the outcome of a streaming response.
+///
+/// Clones observe the same outcome. The first status set wins, so a
+/// producer that reports an error is not overwritten by a later clean-up
+/// path reporting success.
+#[derive(Debug, Clone, Default)]
+pub struct StreamOutcome {
+    status: Arc<OnceLock<StreamStatus>>,
+}
+
+impl StreamOutcome {
+    /// Creates an unresolved outcome.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolves the outcome.
+    ///
+    /// Returns `false` if the outcome had already been resolved.
+    pub fn set(&self, status: StreamStatus) -> bool {
+        self.status.set(status).is_ok()
+    }
+
+    /// Returns the status, or `None` while the stream is still running.
+    #[must_use]
+    pub fn get(&self) -> Option<&StreamStatus> {
+        self.status.get()
+    }
+
+    /// Returns `true` once the stream has ended.
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.status.get().is_some()
+    }
+
+    /// Returns `true` if the stream ended with an error.
+    #[must_use]
+    pub fn is_error(&self) -> bool {
+        self.status.get().is_some_and(StreamStatus::is_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_status_wins() {
+        let outcome = StreamOutcome::new();
+        assert!(outcome.set(StreamStatus::error("boom")));
+        assert!(!outcome.set(StreamStatus::Ok));
+
+        assert!(outcome.is_error());
+        assert_eq!(outcome.get().and_then(StreamStatus::message), Some("boom"));
+    }
+
+    #[test]
+    fn test_clones_share_status() {
+        let outcome = StreamOutcome::new();
+        let observer = outcome.clone();
+        assert!(!observer.is_complete());
+
+        outcome.set(StreamStatus::Ok);
+        assert!(observer.is_complete());
+        assert!(!observer.is_error());
+        assert_eq!(observer.get().map(StreamStatus::as_str), Some("ok"));
+    }
+}
@@ -0,0 +1,281 @@
+//! Per-request phase timing and resource accounting.
+//!
+//! When detailed timing is enabled, the server runs each request inside a
+//! timing [`scope`]. Code on the request path records how long its phase
+//! took with [`record`] or [`time`], and the server reads the collected
+//! [`PhaseTimings`] back to log them and feed the
+//! `archimedes_request_phase_duration_seconds` histogram.
+//!
+//! Outside a scope every function here is a no-op that only checks a task
+//! local, so instrumented code costs next to nothing when the flag is off.
+//!
+//! # Phases
+//!
+//! | Phase | Covers |
+//! |-------|--------|
+//! | `validation` | Request and response schema validation |
+//! | `deserialization` | Decoding the request body into the handler's type |
+//! | `handler` | Running the handler future, from first poll to completion |
+//! | `serialization` | Encoding the handler's response |
+//!
+//! # Example
+//!
+//! ```rust
+//! use archimedes_core::timing::{self, RequestTiming};
+//! use std::time::Duration;
+//!
+//! # tokio_test::block_on(async {
+//! let timing = RequestTiming::new();
+//! timing::scope(timing.clone(), async {
+//!     timing::record(timing::VALIDATION, Duration::from_millis(2));
+//!     timing::time(timing::SERIALIZATION, || serde_json::to_vec(&42));
+//! })
+//! .await;
+//!
+//! let timings = timing.snapshot();
+//! assert_eq!(timings.phase(timing::VALIDATION), Some(Duration::from_millis(2)));
+//! assert!(timings.phase(timing::SERIALIZATION).is_some());
+//! # });
+//! ```
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+/// Request and response schema validation.
+pub const VALIDATION: &str = "validation";
+
+/// Decoding the request body into the handler's request type.
+pub const DESERIALIZATION: &str = "deserialization";
+
+/// Running the handler future.
+pub const HANDLER: &str = "handler";
+
+/// Encoding the handler's response.
+pub const SERIALIZATION: &str = "serialization";
+
+tokio::task_local! {
+    static TIMING: RequestTiming;
+}
+
+/// Timings and sizes collected for one request.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PhaseTimings {
+    phases: Vec<(&'static str, Duration)>,
+    request_bytes: u64,
+    response_bytes: u64,
+    handler_busy: Duration,
+}
+
+impl PhaseTimings {
+    /// Returns the total time spent in a phase, if it was recorded.
+    #[must_use]
+    pub fn phase(&self, name: &str) -> Option<Duration> {
+        self.phases
+            .iter()
+            .find(|(phase, _)| *phase == name)
+            .map(|(_, duration)| *duration)
+    }
+
+    /// Returns the recorded phases in the order they first ran.
+    pub fn phases(&self) -> impl Iterator<Item = (&'static str, Duration)> + '_ {
+        self.phases.iter().copied()
+    }
+
+    /// Returns the size of the buffered request body in bytes.
+    #[must_use]
+    pub fn request_bytes(&self) -> u64 {
+        self.request_bytes
+    }
+
+    /// Returns the size of the response body in bytes.
+    #[must_use]
+    pub fn response_bytes(&self) -> u64 {
+        self.response_bytes
+    }
+
+    /// Returns the time the handler future spent inside `poll`.
+    ///
+    /// Unlike the `handler` phase this excludes time spent waiting on I/O
+    /// or timers, so it approximates the CPU time the handler used.
+    #[must_use]
+    pub fn handler_busy(&self) -> Duration {
+        self.handler_busy
+    }
+
+    fn add(&mut self, name: &'static str, duration: Duration) {
+        match self.phases.iter_mut().find(|(phase, _)| *phase == name) {
+            Some((_, total)) => *total += duration,
+            None => self.phases.push((name, duration)),
+        }
+    }
+}
+
+/// Shared handle to the timings of a request in progress.
+///
+/// Cloning the handle shares the underlying timings.
+#[derive(Debug, Clone, Default)]
+pub struct RequestTiming(Arc<Mutex<PhaseTimings>>);
+
+impl RequestTiming {
+    /// Creates an empty set of timings.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `duration` to the total of `phase`.
+    pub fn record(&self, phase: &'static str, duration: Duration) {
+        self.with(|timings| timings.add(phase, duration));
+    }
+
+    /// Sets the size of the buffered request body.
+    pub fn set_request_bytes(&self, bytes: u64) {
+        self.with(|timings| timings.request_bytes = bytes);
+    }
+
+    /// Sets the size of the response body.
+    pub fn set_response_bytes(&self, bytes: u64) {
+        self.with(|timings| timings.response_bytes = bytes);
+    }
+
+    /// Adds to the time the handler future spent inside `poll`.
+    pub fn add_handler_busy(&self, duration: Duration) {
+        self.with(|timings| timings.handler_busy += duration);
+    }
+
+    /// Returns a copy of the timings collected so far.
+    #[must_use]
+    pub fn snapshot(&self) -> PhaseTimings {
+        self.with(|timings| timings.clone())
+    }
+
+    fn with<T>(&self, f: impl FnOnce(&mut PhaseTimings) -> T) -> T {
+        f(&mut self.0.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+/// Runs `future` with `timing` collecting the phases recorded inside it.
+pub async fn scope<F: Future>(timing: RequestTiming, future: F) -> F::Output {
+    TIMING.scope(timing, future).await
+}
+
+/// Returns the timing of the current request, if detailed timing is on.
+#[must_use]
+pub fn current() -> Option<RequestTiming> {
+    TIMING.try_with(Clone::clone).ok()
+}
+
+/// Returns `true` when running inside a timing [`scope`].
+#[must_use]
+pub fn is_enabled() -> bool {
+    TIMING.try_with(|_| ()).is_ok()
+}
+
+/// Adds `duration` to `phase` of the current request, if any.
+pub fn record(phase: &'static str, duration: Duration) {
+    // Outside a scope there is nothing to record into
+    let _ = TIMING.try_with(|timing| timing.record(phase, duration));
+}
+
+/// Runs `f`, recording its duration as `phase` of the current request.
+pub fn time<T>(phase: &'static str, f: impl FnOnce() -> T) -> T {
+    if !is_enabled() {
+        return f();
+    }
+    let start = Instant::now();
+    let output = f();
+    record(phase, start.elapsed());
+    output
+}
+
+/// Awaits a handler future, recording its wall time as the `handler` phase
+/// and its time inside `poll` as [`PhaseTimings::handler_busy`].
+pub async fn time_handler<F: Future>(future: F) -> F::Output {
+    let Some(timing) = current() else {
+        return future.await;
+    };
+    let start = Instant::now();
+    let mut polled = PollTimed {
+        future: Box::pin(future),
+        busy: Duration::ZERO,
+    };
+    let output = (&mut polled).await;
+    timing.record(HANDLER, start.elapsed());
+    timing.add_handler_busy(polled.busy);
+    output
+}
+
+/// Future wrapper that sums the time spent polling the inner future.
+struct PollTimed<F> {
+    future: Pin<Box<F>>,
+    busy: Duration,
+}
+
+impl<F: Future> Future for PollTimed<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let start = Instant::now();
+        let poll = self.future.as_mut().poll(cx);
+        self.busy += start.elapsed();
+        poll
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_record_outside_scope_is_noop() {
+        assert!(!is_enabled());
+        assert!(current().is_none());
+        record(VALIDATION, Duration::from_millis(1));
+        assert_eq!(time(SERIALIZATION, || 7), 7);
+        assert_eq!(time_handler(async { 8 }).await, 8);
+    }
+
+    #[tokio::test]
+    async fn test_scope_accumulates_phases_in_order() {
+        let timing = RequestTiming::new();
+        scope(timing.clone(), async {
+            assert!(is_enabled());
+            record(VALIDATION, Duration::from_millis(2));
+            record(DESERIALIZATION, Duration::from_millis(1));
+            record(VALIDATION, Duration::from_millis(3));
+        })
+        .await;
+        timing.set_request_bytes(12);
+        timing.set_response_bytes(34);
+
+        let timings = timing.snapshot();
+        assert_eq!(timings.phase(VALIDATION), Some(Duration::from_millis(5)));
+        assert_eq!(timings.phase(HANDLER), None);
+        assert_eq!(
+            timings.phases().map(|(name, _)| name).collect::<Vec<_>>(),
+            vec![VALIDATION, DESERIALIZATION]
+        );
+        assert_eq!(timings.request_bytes(), 12);
+        assert_eq!(timings.response_bytes(), 34);
+    }
+
+    #[tokio::test]
+    async fn test_handler_busy_excludes_waiting() {
+        let timing = RequestTiming::new();
+        scope(
+            timing.clone(),
+            time_handler(async {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }),
+        )
+        .await;
+
+        let timings = timing.snapshot();
+        let handler = timings.phase(HANDLER).unwrap();
+        assert!(handler >= Duration::from_millis(20));
+        assert!(timings.handler_busy() < handler);
+    }
+}
@@ -10,9 +10,9 @@
 
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use archimedes_sentinel::{LoadedArtifact, LoadedOperation};
+use archimedes_sentinel::{LoadedArtifact, LoadedOperation, Sentinel};
 use themis_core::Schema as ThemisSchema;
 
 use crate::error::{DocsError, DocsResult};
@@ -559,6 +559,7 @@ pub struct OpenApiGenerator {
     license: Option<License>,
     external_docs: Option<ExternalDocumentation>,
     security_schemes: IndexMap<String, SecurityScheme>,
+    hidden_operations: HashSet<String>,
 }
 
 impl Default for OpenApiGenerator {
@@ -580,6 +581,7 @@ impl OpenApiGenerator {
             license: None,
             external_docs: None,
             security_schemes: IndexMap::new(),
+            hidden_operations: HashSet::new(),
         }
     }
 
@@ -671,6 +673,21 @@ impl OpenApiGenerator {
         self
     }
 
+    /// Leave operations out of the generated spec.
+    ///
+    /// Use this for operations that are not publicly available yet, such
+    /// as those closed by an operation gate.
+    #[must_use]
+    pub fn hide_operations<I, S>(mut self, operation_ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.hidden_operations
+            .extend(operation_ids.into_iter().map(Into::into));
+        self
+    }
+
     /// Generate an OpenAPI spec from a loaded artifact.
     pub fn generate(&self, artifact: &LoadedArtifact) -> DocsResult<OpenApi> {
         let info = Info {
@@ -692,6 +709,9 @@ impl OpenApiGenerator {
         let mut tags_set: std::collections::HashSet<String> = std::collections::HashSet::new();
 
         for operation in &artifact.operations {
+            if self.hidden_operations.contains(&operation.id) {
+                continue;
+            }
             let path_item = paths.entry(operation.path.clone()).or_default();
             let openapi_op = self.convert_operation(operation)?;
 
@@ -776,24 +796,22 @@ impl OpenApiGenerator {
                 },
             );
         } else {
-            for (status, schema_ref) in &op.response_schemas {
-                let mut content = IndexMap::new();
-                content.insert(
-                    "application/json".to_string(),
+            // One response per status, with a content entry per media type
+            let mut schemas: Vec<_> = op.response_schemas.iter().collect();
+            schemas.sort_by(|(a, _), (b, _)| a.cmp(b));
+            for ((status, media_type), schema_ref) in schemas {
+                let response = responses.entry(status.clone()).or_insert_with(|| Response {
+                    description: format!("{} response", status),
+                    headers: IndexMap::new(),
+                    content: IndexMap::new(),
+                });
+                response.content.insert(
+                    media_type.clone(),
                     MediaType {
                         schema: Some(Schema::reference(&schema_ref.reference)),
                         example: None,
                     },
                 );
-
-                responses.insert(
-                    status.clone(),
-                    Response {
-                        description: format!("{} response", status),
-                        headers: IndexMap::new(),
-                        content,
-                    },
-                );
             }
         }
 
@@ -832,7 +850,9 @@ impl OpenApiGenerator {
         Ok(Operation {
             operation_id: op.id.clone(),
             summary: op.summary.clone(),
-            description: None,
+            description: op.cache_policy.map(|policy| {
+                format!("Successful responses are cached: `Cache-Control: {policy}`.")
+            }),
             tags: op.tags.clone(),
             deprecated: op.deprecated,
             parameters,
@@ -842,6 +862,21 @@ impl OpenApiGenerator {
         })
     }
 
+    /// Generate one OpenAPI spec per contract version served by a Sentinel.
+    ///
+    /// Specs are keyed by version label in load order. Each spec's
+    /// `info.version` is its artifact's version, ignoring any override.
+    pub fn generate_versions(&self, sentinel: &Sentinel) -> DocsResult<IndexMap<String, OpenApi>> {
+        sentinel
+            .artifacts()
+            .map(|(label, artifact)| {
+                let mut spec = self.generate(artifact)?;
+                spec.info.version = artifact.version.clone();
+                Ok((label.to_string(), spec))
+            })
+            .collect()
+    }
+
     /// Generate the OpenAPI spec as JSON.
     pub fn generate_json(&self, artifact: &LoadedArtifact) -> DocsResult<String> {
         let spec = self.generate(artifact)?;
@@ -1109,4 +1144,197 @@ mod tests {
         assert!(json.contains("3.1.0"));
         assert!(json.contains("Test API"));
     }
+
+    #[test]
+    fn test_generate_versions_emits_spec_per_version() {
+        use archimedes_sentinel::{SentinelConfig, VersionSelector};
+
+        let artifact = |version: &str, op: &str| LoadedArtifact {
+            service: "users".to_string(),
+            version: version.to_string(),
+            format: "openapi".to_string(),
+            operations: vec![LoadedOperation {
+                id: op.to_string(),
+                method: "GET".to_string(),
+                path: "/users/{userId}".to_string(),
+                summary: None,
+                deprecated: false,
+                security: vec![],
+                request_schema: None,
+                response_schemas: HashMap::new(),
+                tags: vec![],
+                header_params: Vec::new(),
+                event_schemas: HashMap::new(),
+                timeout: None,
+                cache_policy: None,
+                allow_reserved: Vec::new(),
+            }],
+            schemas: IndexMap::new(),
+            stats: Default::default(),
+        };
+        let sentinel = Sentinel::versioned(
+            [
+                ("v1", artifact("1.0.0", "getUser")),
+                ("v2", artifact("2.0.0", "getUserV2")),
+            ],
+            VersionSelector::new(),
+            SentinelConfig::default(),
+        )
+        .unwrap();
+
+        let specs = OpenApiGenerator::new()
+            .version("ignored")
+            .generate_versions(&sentinel)
+            .unwrap();
+
+        assert_eq!(specs.keys().collect::<Vec<_>>(), vec!["v1", "v2"]);
+        assert_eq!(specs["v1"].info.version, "1.0.0");
+        assert_eq!(specs["v2"].info.version, "2.0.0");
+        let v2_get = specs["v2"].paths["/users/{userId}"].get.as_ref().unwrap();
+        assert_eq!(v2_get.operation_id, "getUserV2");
+    }
+
+    #[test]
+    fn test_hidden_operations_are_omitted() {
+        let operation = |id: &str, path: &str| LoadedOperation {
+            id: id.to_string(),
+            method: "POST".to_string(),
+            path: path.to_string(),
+            summary: None,
+            deprecated: false,
+            security: vec![],
+            request_schema: None,
+            response_schemas: HashMap::new(),
+            tags: vec![],
+            header_params: Vec::new(),
+            event_schemas: HashMap::new(),
+            timeout: None,
+            cache_policy: None,
+            allow_reserved: Vec::new(),
+        };
+        let artifact = LoadedArtifact {
+            service: "shop".to_string(),
+            version: "1.0.0".to_string(),
+            format: "openapi".to_string(),
+            operations: vec![
+                operation("checkout", "/checkout"),
+                operation("newCheckout", "/checkout/v2"),
+            ],
+            schemas: IndexMap::new(),
+            stats: Default::default(),
+        };
+
+        let spec = OpenApiGenerator::new()
+            .hide_operations(["newCheckout"])
+            .generate(&artifact)
+            .unwrap();
+
+        assert!(spec.paths.contains_key("/checkout"));
+        assert!(!spec.paths.contains_key("/checkout/v2"));
+    }
+
+    #[test]
+    fn test_cache_policy_in_description() {
+        use archimedes_core::CachePolicy;
+
+        let operation = |id: &str, cache_policy| LoadedOperation {
+            id: id.to_string(),
+            method: "GET".to_string(),
+            path: format!("/{id}"),
+            summary: None,
+            deprecated: false,
+            security: vec![],
+            request_schema: None,
+            response_schemas: HashMap::new(),
+            tags: vec![],
+            header_params: Vec::new(),
+            event_schemas: HashMap::new(),
+            timeout: None,
+            cache_policy,
+        };
+        let artifact = LoadedArtifact {
+            service: "shop".to_string(),
+            version: "1.0.0".to_string(),
+            format: "openapi".to_string(),
+            operations: vec![
+                operation("catalog", Some(CachePolicy::public().max_age(300))),
+                operation("cart", None),
+            ],
+            schemas: IndexMap::new(),
+            stats: Default::default(),
+        };
+
+        let spec = OpenApiGenerator::new().generate(&artifact).unwrap();
+
+        let catalog = spec.paths["/catalog"].get.as_ref().unwrap();
+        assert_eq!(
+            catalog.description.as_deref(),
+            Some("Successful responses are cached: `Cache-Control: public, max-age=300`.")
+        );
+        assert_eq!(spec.paths["/cart"].get.as_ref().unwrap().description, None);
+    }
+
+    #[test]
+    fn test_response_content_per_media_type() {
+        use archimedes_sentinel::SchemaRef;
+
+        let schema = |reference: &str, schema_type: &str| SchemaRef {
+            reference: reference.to_string(),
+            schema_type: schema_type.to_string(),
+            required: vec![],
+            properties: HashMap::new(),
+            property_schemas: HashMap::new(),
+            required_scopes: HashMap::new(),
+            items: None,
+            additional_properties: None,
+        };
+        let artifact = LoadedArtifact {
+            service: "reports".to_string(),
+            version: "1.0.0".to_string(),
+            format: "openapi".to_string(),
+            operations: vec![LoadedOperation {
+                id: "getReport".to_string(),
+                method: "GET".to_string(),
+                path: "/reports/{id}".to_string(),
+                summary: None,
+                deprecated: false,
+                security: vec![],
+                request_schema: None,
+                response_schemas: HashMap::from([
+                    (
+                        ("200".to_string(), "application/json".to_string()),
+                        schema("#/components/schemas/Report", "object"),
+                    ),
+                    (
+                        ("200".to_string(), "text/csv".to_string()),
+                        schema("#/components/schemas/ReportCsv", "string"),
+                    ),
+                    (
+                        ("404".to_string(), "application/json".to_string()),
+                        schema("#/components/schemas/Error", "object"),
+                    ),
+                ]),
+                tags: vec![],
+                header_params: Vec::new(),
+                event_schemas: HashMap::new(),
+                timeout: None,
+                cache_policy: None,
+                allow_reserved: Vec::new(),
+            }],
+            schemas: IndexMap::new(),
+            stats: Default::default(),
+        };
+
+        let spec = OpenApiGenerator::new().generate(&artifact).unwrap();
+
+        let get = spec.paths["/reports/{id}"].get.as_ref().unwrap();
+        assert_eq!(get.responses.keys().collect::<Vec<_>>(), vec!["200", "404"]);
+        let ok = &get.responses["200"];
+        assert_eq!(
+            ok.content.keys().collect::<Vec<_>>(),
+            vec!["application/json", "text/csv"]
+        );
+        let json = serde_json::to_value(&ok.content["text/csv"]).unwrap();
+        assert_eq!(json["schema"]["$ref"], "#/components/schemas/ReportCsv");
+    }
 }
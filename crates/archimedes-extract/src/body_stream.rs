@@ -0,0 +1,499 @@
+//! Request bodies consumed as a stream.
+//!
+//! Handlers registered with `HandlerRegistry::register_body_stream` receive
+//! the request body as a [`BodyStream`] instead of a deserialized value. The
+//! body is read from the connection only as the handler polls it, so a slow
+//! consumer applies backpressure all the way to the client's socket and
+//! memory stays bounded however large the body is.
+//!
+//! [`BodyStream::ndjson`] frames the body as newline-delimited JSON and
+//! yields one item per line. A line that fails to parse, or to validate
+//! against the contract's item schema, is reported as an
+//! [`NdjsonError::Item`] and the stream moves on to the next line; a body
+//! that cannot be read ends the stream with an [`NdjsonError::Body`].
+//!
+//! # Example
+//!
+//! ```rust
+//! use archimedes_extract::body_stream::{BodyStream, NdjsonError};
+//! use bytes::Bytes;
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Event {
+//!     kind: String,
+//! }
+//!
+//! # async fn ingest() {
+//! let body = BodyStream::from_bytes(Bytes::from("{\"kind\":\"a\"}\nnot json\n{\"kind\":\"b\"}\n"));
+//! let mut events = body.ndjson::<Event>();
+//!
+//! let mut kinds = Vec::new();
+//! let mut bad_lines = Vec::new();
+//! while let Some(item) = events.next_item().await {
+//!     match item {
+//!         Ok(event) => kinds.push(event.kind),
+//!         Err(NdjsonError::Item { line, .. }) => bad_lines.push(line),
+//!         Err(e) => panic!("{e}"),
+//!     }
+//! }
+//! assert_eq!(kinds, ["a", "b"]);
+//! assert_eq!(bad_lines, [2]);
+//! # }
+//! ```
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use bytes::{Bytes, BytesMut};
+use futures_core::Stream;
+use futures_util::stream::BoxStream;
+use futures_util::StreamExt;
+use http_body::Body;
+use serde::de::DeserializeOwned;
+
+use crate::error::{ExtractionError, ExtractionSource};
+use crate::json::from_json;
+
+/// Default limit on the length of one NDJSON line (1 MiB).
+pub const DEFAULT_MAX_LINE_BYTES: usize = 1024 * 1024;
+
+/// Validates one streamed item, such as an NDJSON line, before it is
+/// deserialized.
+pub type ItemValidator = Arc<dyn Fn(&[u8]) -> Result<(), ExtractionError> + Send + Sync>;
+
+/// A request body read incrementally.
+///
+/// Yields the body's data chunks as they arrive. Read errors, including the
+/// client disconnecting mid-body, are yielded as an [`ExtractionError`].
+pub struct BodyStream {
+    inner: BoxStream<'static, Result<Bytes, ExtractionError>>,
+    item_validator: Option<ItemValidator>,
+}
+
+impl BodyStream {
+    /// Creates a body stream over an HTTP body, such as hyper's `Incoming`.
+    ///
+    /// Trailers are skipped.
+    pub fn from_body<B>(body: B) -> Self
+    where
+        B: Body<Data = Bytes> + Send + 'static,
+        B::Error: fmt::Display,
+    {
+        let stream = futures_util::stream::unfold(Box::pin(body), |mut body| async move {
+            loop {
+                match std::future::poll_fn(|cx| body.as_mut().poll_frame(cx)).await? {
+                    Ok(frame) => {
+                        if let Ok(data) = frame.into_data() {
+                            return Some((Ok(data), body));
+                        }
+                    }
+                    Err(e) => return Some((Err(read_error(&e)), body)),
+                }
+            }
+        });
+        Self::from_stream(stream)
+    }
+
+    /// Creates a body stream over a stream of chunks.
+    pub fn from_stream<S, E>(stream: S) -> Self
+    where
+        S: Stream<Item = Result<Bytes, E>> + Send + 'static,
+        E: fmt::Display,
+    {
+        Self {
+            inner: stream
+                .map(|chunk| chunk.map_err(|e| read_error(&e)))
+                .boxed(),
+            item_validator: None,
+        }
+    }
+
+    /// Creates a body stream over a buffered body.
+    #[must_use]
+    pub fn from_bytes(body: Bytes) -> Self {
+        let chunk = (!body.is_empty()).then_some(Ok::<_, ExtractionError>(body));
+        Self {
+            inner: futures_util::stream::iter(chunk).boxed(),
+            item_validator: None,
+        }
+    }
+
+    /// Sets the validator applied to each item framed from the body.
+    #[must_use]
+    pub fn with_item_validator(mut self, validator: ItemValidator) -> Self {
+        self.item_validator = Some(validator);
+        self
+    }
+
+    /// Returns `true` if items framed from the body are validated.
+    #[must_use]
+    pub fn validates_items(&self) -> bool {
+        self.item_validator.is_some()
+    }
+
+    /// Reads the next chunk of the body.
+    ///
+    /// Returns `None` once the body is complete.
+    pub async fn next_chunk(&mut self) -> Option<Result<Bytes, ExtractionError>> {
+        self.inner.next().await
+    }
+
+    /// Frames the body as newline-delimited JSON, yielding one `T` per line.
+    ///
+    /// Blank lines are skipped, and a final line without a trailing newline
+    /// is still read.
+    #[must_use]
+    pub fn ndjson<T: DeserializeOwned>(self) -> NdjsonStream<T> {
+        NdjsonStream {
+            body: self,
+            buffer: BytesMut::new(),
+            scanned: 0,
+            line: 0,
+            max_line_bytes: DEFAULT_MAX_LINE_BYTES,
+            stop_on_item_error: false,
+            done: false,
+            _item: PhantomData,
+        }
+    }
+}
+
+impl Stream for BodyStream {
+    type Item = Result<Bytes, ExtractionError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.poll_next_unpin(cx)
+    }
+}
+
+impl fmt::Debug for BodyStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BodyStream")
+            .field("validates_items", &self.validates_items())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Error reading a body that could not be read to its end.
+fn read_error(error: &dyn fmt::Display) -> ExtractionError {
+    ExtractionError::custom(
+        ExtractionSource::Body,
+        "",
+        format!("failed to read request body: {error}"),
+    )
+}
+
+/// Error yielded by an [`NdjsonStream`].
+#[derive(Debug)]
+pub enum NdjsonError {
+    /// A line failed to parse or validate. The stream continues with the
+    /// next line unless it stops on item errors.
+    Item {
+        /// Line number, starting at 1.
+        line: usize,
+        /// Why the line was rejected.
+        error: ExtractionError,
+    },
+    /// The body could not be read, or a line exceeded the length limit.
+    /// The stream ends.
+    Body(ExtractionError),
+}
+
+impl NdjsonError {
+    /// Returns `true` for an error confined to one line.
+    #[must_use]
+    pub fn is_item(&self) -> bool {
+        matches!(self, Self::Item { .. })
+    }
+
+    /// Returns the line number of an item error.
+    #[must_use]
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Self::Item { line, .. } => Some(*line),
+            Self::Body(_) => None,
+        }
+    }
+
+    /// Returns the underlying extraction error.
+    #[must_use]
+    pub fn extraction_error(&self) -> &ExtractionError {
+        match self {
+            Self::Item { error, .. } | Self::Body(error) => error,
+        }
+    }
+}
+
+impl fmt::Display for NdjsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Item { line, error } => write!(f, "line {line}: {error}"),
+            Self::Body(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for NdjsonError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.extraction_error())
+    }
+}
+
+/// Newline-delimited JSON items framed from a [`BodyStream`].
+///
+/// Created by [`BodyStream::ndjson`]. Only the current, incomplete line is
+/// buffered, up to [`max_line_bytes`](Self::max_line_bytes).
+pub struct NdjsonStream<T> {
+    body: BodyStream,
+    buffer: BytesMut,
+    /// Bytes of `buffer` already searched for a newline
+    scanned: usize,
+    line: usize,
+    max_line_bytes: usize,
+    stop_on_item_error: bool,
+    done: bool,
+    _item: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned> NdjsonStream<T> {
+    /// Sets the longest line accepted, in bytes.
+    ///
+    /// A longer line ends the stream with a `413`-mapped
+    /// [`NdjsonError::Body`]. Defaults to [`DEFAULT_MAX_LINE_BYTES`].
+    #[must_use]
+    pub fn max_line_bytes(mut self, max: usize) -> Self {
+        self.max_line_bytes = max;
+        self
+    }
+
+    /// Ends the stream after the first item error instead of moving on to
+    /// the next line.
+    #[must_use]
+    pub fn stop_on_item_error(mut self, stop: bool) -> Self {
+        self.stop_on_item_error = stop;
+        self
+    }
+
+    /// Returns the number of lines read so far, including blank and
+    /// rejected lines.
+    #[must_use]
+    pub fn lines_read(&self) -> usize {
+        self.line
+    }
+
+    /// Reads the next item.
+    ///
+    /// Returns `None` once the body is complete, or after an error that
+    /// ends the stream.
+    pub async fn next_item(&mut self) -> Option<Result<T, NdjsonError>> {
+        self.next().await
+    }
+
+    /// Parses one line, returning `None` for a blank line.
+    fn parse_line(&mut self, line: &[u8]) -> Option<Result<T, NdjsonError>> {
+        self.line += 1;
+        let line = trim_whitespace(line);
+        if line.is_empty() {
+            return None;
+        }
+
+        let item = self
+            .body
+            .item_validator
+            .as_ref()
+            .map_or(Ok(()), |validate| validate(line))
+            .and_then(|()| from_json(line));
+        if item.is_err() && self.stop_on_item_error {
+            self.done = true;
+        }
+        Some(item.map_err(|error| NdjsonError::Item {
+            line: self.line,
+            error,
+        }))
+    }
+
+    /// Ends the stream with an error.
+    fn fail(&mut self, error: ExtractionError) -> Option<Result<T, NdjsonError>> {
+        self.done = true;
+        self.buffer.clear();
+        Some(Err(NdjsonError::Body(error)))
+    }
+}
+
+impl<T: DeserializeOwned> Stream for NdjsonStream<T> {
+    type Item = Result<T, NdjsonError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if this.done {
+                return Poll::Ready(None);
+            }
+
+            if let Some(offset) = this.buffer[this.scanned..].iter().position(|&b| b == b'\n') {
+                let line = this.buffer.split_to(this.scanned + offset + 1);
+                this.scanned = 0;
+                if let Some(item) = this.parse_line(&line[..line.len() - 1]) {
+                    return Poll::Ready(Some(item));
+                }
+                continue;
+            }
+            this.scanned = this.buffer.len();
+
+            if this.buffer.len() > this.max_line_bytes {
+                let error =
+                    ExtractionError::payload_too_large(this.max_line_bytes, this.buffer.len());
+                return Poll::Ready(this.fail(error));
+            }
+
+            match this.body.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(chunk))) => this.buffer.extend_from_slice(&chunk),
+                Poll::Ready(Some(Err(error))) => return Poll::Ready(this.fail(error)),
+                Poll::Ready(None) => {
+                    this.done = true;
+                    let rest = this.buffer.split();
+                    return Poll::Ready(this.parse_line(&rest));
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl<T> fmt::Debug for NdjsonStream<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NdjsonStream")
+            .field("line", &self.line)
+            .field("buffered", &self.buffer.len())
+            .field("max_line_bytes", &self.max_line_bytes)
+            .field("stop_on_item_error", &self.stop_on_item_error)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Trims ASCII whitespace, including a `\r` before the newline.
+fn trim_whitespace(line: &[u8]) -> &[u8] {
+    let start = line
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(line.len());
+    let end = line
+        .iter()
+        .rposition(|b| !b.is_ascii_whitespace())
+        .map_or(start, |end| end + 1);
+    &line[start..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::convert::Infallible;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Event {
+        id: u64,
+    }
+
+    /// A body of `lines` NDJSON events, produced lazily in small chunks.
+    fn lazy_body(lines: u64) -> BodyStream {
+        let chunks = futures_util::stream::iter(0..lines).chunks(100).map(|ids| {
+            let chunk: String = ids.iter().map(|id| format!("{{\"id\":{id}}}\n")).collect();
+            Ok::<_, Infallible>(Bytes::from(chunk))
+        });
+        BodyStream::from_stream(chunks)
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_large_body_bounded_memory() {
+        let mut events = lazy_body(100_000).ndjson::<Event>();
+
+        let mut count = 0;
+        let mut max_capacity = 0;
+        while let Some(event) = events.next_item().await {
+            assert_eq!(event.unwrap().id, count);
+            count += 1;
+            max_capacity = max_capacity.max(events.buffer.capacity());
+        }
+
+        assert_eq!(count, 100_000);
+        // Roughly one chunk of ~1.6 KB is buffered at a time, not the ~1.6 MB body
+        assert!(
+            max_capacity < 16 * 1024,
+            "buffered up to {max_capacity} bytes"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_malformed_line_is_item_error() {
+        let body = BodyStream::from_bytes(Bytes::from("{\"id\":1}\n{\"id\":\n\n{\"id\":3}"));
+        let mut events = body.ndjson::<Event>();
+
+        assert_eq!(events.next_item().await.unwrap().unwrap(), Event { id: 1 });
+        let error = events.next_item().await.unwrap().unwrap_err();
+        assert!(error.is_item());
+        assert_eq!(error.line(), Some(2));
+        // The blank line is skipped and the unterminated last line is read
+        assert_eq!(events.next_item().await.unwrap().unwrap(), Event { id: 3 });
+        assert!(events.next_item().await.is_none());
+        assert_eq!(events.lines_read(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_stop_on_item_error() {
+        let body = BodyStream::from_bytes(Bytes::from("oops\n{\"id\":2}\n"));
+        let mut events = body.ndjson::<Event>().stop_on_item_error(true);
+
+        assert!(events.next_item().await.unwrap().unwrap_err().is_item());
+        assert!(events.next_item().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_item_validator_rejects_line() {
+        let validator: ItemValidator = Arc::new(|line: &[u8]| {
+            if line.windows(4).any(|w| w == b"\"id\"") {
+                Ok(())
+            } else {
+                Err(ExtractionError::missing(ExtractionSource::Body, "id"))
+            }
+        });
+        let body = BodyStream::from_bytes(Bytes::from("{\"name\":\"x\"}\n{\"id\":2}\n"))
+            .with_item_validator(validator);
+        let mut events = body.ndjson::<Event>();
+
+        let error = events.next_item().await.unwrap().unwrap_err();
+        assert_eq!(error.extraction_error().issues()[0].field, "id");
+        assert_eq!(events.next_item().await.unwrap().unwrap(), Event { id: 2 });
+    }
+
+    #[tokio::test]
+    async fn test_read_error_ends_stream() {
+        let chunks = futures_util::stream::iter([
+            Ok(Bytes::from("{\"id\":1}\n{\"id\"")),
+            Err("connection reset"),
+        ]);
+        let mut events = BodyStream::from_stream(chunks).ndjson::<Event>();
+
+        assert_eq!(events.next_item().await.unwrap().unwrap(), Event { id: 1 });
+        let error = events.next_item().await.unwrap().unwrap_err();
+        assert!(!error.is_item());
+        assert!(error.to_string().contains("connection reset"));
+        assert!(events.next_item().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_line_too_long_ends_stream() {
+        let body = BodyStream::from_bytes(Bytes::from(format!("\"{}\"\n", "x".repeat(64))));
+        let mut events = body.ndjson::<String>().max_line_bytes(16);
+
+        let error = events.next_item().await.unwrap().unwrap_err();
+        assert_eq!(
+            error.extraction_error().status_code(),
+            http::StatusCode::PAYLOAD_TOO_LARGE
+        );
+        assert!(events.next_item().await.is_none());
+    }
+}
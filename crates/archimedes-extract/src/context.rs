@@ -7,7 +7,7 @@ use archimedes_core::di::Container;
 use archimedes_core::InvocationContext;
 use archimedes_router::Params;
 use bytes::Bytes;
-use http::{HeaderMap, Method, Uri};
+use http::{HeaderMap, HeaderName, Method, Uri};
 use std::sync::Arc;
 
 /// Context providing access to all parts of an HTTP request.
@@ -187,6 +187,8 @@ impl ExtractionContext {
     }
 
     /// Returns a specific header value as a string.
+    ///
+    /// Header names are case-insensitive.
     #[must_use]
     pub fn header(&self, name: &str) -> Option<&str> {
         self.headers.get(name).and_then(|v| v.to_str().ok())
@@ -254,9 +256,11 @@ impl ExtractionContextBuilder {
     }
 
     /// Adds a single header.
+    ///
+    /// The name may use any case. Invalid names or values are ignored.
     #[must_use]
-    pub fn header(mut self, name: &'static str, value: &str) -> Self {
-        if let Ok(value) = value.parse() {
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        if let (Ok(name), Ok(value)) = (HeaderName::from_bytes(name.as_bytes()), value.parse()) {
             self.headers.insert(name, value);
         }
         self
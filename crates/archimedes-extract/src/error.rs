@@ -2,12 +2,22 @@
 //!
 //! This module provides error types for extraction failures,
 //! including information about the source of the error.
+//!
+//! Every error carries a list of [`FieldIssue`]s naming the field that
+//! failed. For `Json`, `Query`, `Form` and `Path` the field is the full path
+//! to the offending value, such as `items[2].price`. Handlers generated by
+//! `#[handler]` run every extractor and report all issues at once with
+//! [`ExtractionError::aggregate`].
 
+use archimedes_core::{FieldErrors, ThemisError};
 use http::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use std::fmt;
 
 /// Source of extraction (where data was being extracted from).
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum ExtractionSource {
     /// Path parameters (e.g., `/users/{id}`)
     Path,
@@ -36,6 +46,62 @@ impl fmt::Display for ExtractionSource {
     }
 }
 
+/// A single problem with one field of the request.
+///
+/// # Example
+///
+/// ```rust
+/// use archimedes_extract::{ExtractionError, ExtractionSource};
+///
+/// let err = ExtractionError::invalid_type(ExtractionSource::Query, "limit", "expected integer");
+/// let issue = &err.issues()[0];
+/// assert_eq!(issue.source, ExtractionSource::Query);
+/// assert_eq!(issue.field, "limit");
+/// assert_eq!(issue.location(), "query.limit");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldIssue {
+    /// Where the field was read from.
+    pub source: ExtractionSource,
+    /// Path to the field, such as `address.zip` or `items[0]`. Empty when
+    /// the issue concerns the source as a whole, like a malformed body.
+    pub field: String,
+    /// What the field should have been, when known.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub expected: String,
+    /// What went wrong.
+    pub message: String,
+}
+
+impl FieldIssue {
+    /// Creates a field issue.
+    #[must_use]
+    pub fn new(
+        source: ExtractionSource,
+        field: impl Into<String>,
+        expected: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            source,
+            field: field.into(),
+            expected: expected.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Returns the source and field as `source.field`, or just the source
+    /// when there is no field.
+    #[must_use]
+    pub fn location(&self) -> String {
+        if self.field.is_empty() {
+            self.source.to_string()
+        } else {
+            format!("{}.{}", self.source, self.field)
+        }
+    }
+}
+
 /// Error that occurs during extraction.
 ///
 /// Contains information about the source of the error and what went wrong.
@@ -58,6 +124,7 @@ pub struct ExtractionError {
     kind: ExtractionErrorKind,
     field: Option<String>,
     message: String,
+    issues: Vec<FieldIssue>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -76,6 +143,8 @@ enum ExtractionErrorKind {
     UnsupportedMediaType,
     /// Custom error (e.g., DI failure)
     Custom,
+    /// Several extractors failed
+    Multiple,
 }
 
 impl ExtractionError {
@@ -83,12 +152,12 @@ impl ExtractionError {
     #[must_use]
     pub fn missing(source: ExtractionSource, field: impl Into<String>) -> Self {
         let field = field.into();
-        Self {
-            extraction_source: source,
-            kind: ExtractionErrorKind::Missing,
-            message: format!("missing required {source} parameter: {field}"),
-            field: Some(field),
-        }
+        Self::with_issue(
+            source,
+            ExtractionErrorKind::Missing,
+            format!("missing required {source} parameter: {field}"),
+            FieldIssue::new(source, field, "a value", "missing required value"),
+        )
     }
 
     /// Creates an error for an invalid type or format.
@@ -100,12 +169,12 @@ impl ExtractionError {
     ) -> Self {
         let field = field.into();
         let details = details.into();
-        Self {
-            extraction_source: source,
-            kind: ExtractionErrorKind::InvalidType,
-            message: format!("invalid {source} parameter '{field}': {details}"),
-            field: Some(field),
-        }
+        Self::with_issue(
+            source,
+            ExtractionErrorKind::InvalidType,
+            format!("invalid {source} parameter '{field}': {details}"),
+            FieldIssue::new(source, field, expected_from(&details), details),
+        )
     }
 
     /// Creates an error for a validation failure.
@@ -117,69 +186,105 @@ impl ExtractionError {
     ) -> Self {
         let field = field.into();
         let details = details.into();
-        Self {
-            extraction_source: source,
-            kind: ExtractionErrorKind::ValidationFailed,
-            message: format!("validation failed for {source} parameter '{field}': {details}"),
-            field: Some(field),
-        }
+        Self::with_issue(
+            source,
+            ExtractionErrorKind::ValidationFailed,
+            format!("validation failed for {source} parameter '{field}': {details}"),
+            FieldIssue::new(source, field, "", details),
+        )
     }
 
     /// Creates an error for deserialization failure.
     #[must_use]
     pub fn deserialization_failed(source: ExtractionSource, error: impl Into<String>) -> Self {
         let error = error.into();
-        Self {
-            extraction_source: source,
-            kind: ExtractionErrorKind::DeserializationFailed,
-            message: format!("failed to deserialize {source}: {error}"),
-            field: None,
-        }
+        Self::with_issue(
+            source,
+            ExtractionErrorKind::DeserializationFailed,
+            format!("failed to deserialize {source}: {error}"),
+            FieldIssue::new(source, "", "", error),
+        )
     }
 
     /// Creates an error for a payload that's too large.
     #[must_use]
     pub fn payload_too_large(max_size: usize, actual_size: usize) -> Self {
-        Self {
-            extraction_source: ExtractionSource::Body,
-            kind: ExtractionErrorKind::PayloadTooLarge,
-            message: format!("payload too large: max {max_size} bytes, got {actual_size} bytes"),
-            field: None,
-        }
+        let message = format!("payload too large: max {max_size} bytes, got {actual_size} bytes");
+        Self::with_issue(
+            ExtractionSource::Body,
+            ExtractionErrorKind::PayloadTooLarge,
+            message.clone(),
+            FieldIssue::new(ExtractionSource::Body, "", "", message),
+        )
+    }
+
+    /// Creates an error for a single field that's too large.
+    #[must_use]
+    pub fn field_too_large(
+        source: ExtractionSource,
+        field: impl Into<String>,
+        max_size: usize,
+        actual_size: usize,
+    ) -> Self {
+        let field = field.into();
+        Self::with_issue(
+            source,
+            ExtractionErrorKind::PayloadTooLarge,
+            format!(
+                "{source} field '{field}' too large: max {max_size} bytes, got {actual_size} bytes"
+            ),
+            FieldIssue::new(
+                source,
+                field,
+                format!("at most {max_size} bytes"),
+                format!("got {actual_size} bytes"),
+            ),
+        )
     }
 
     /// Creates an error for unsupported content type.
     #[must_use]
     pub fn unsupported_media_type(expected: &str, actual: Option<&str>) -> Self {
         let actual_str = actual.unwrap_or("none");
-        Self {
-            extraction_source: ExtractionSource::ContentType,
-            kind: ExtractionErrorKind::UnsupportedMediaType,
-            message: format!("unsupported content type: expected '{expected}', got '{actual_str}'"),
-            field: None,
-        }
+        Self::with_issue(
+            ExtractionSource::ContentType,
+            ExtractionErrorKind::UnsupportedMediaType,
+            format!("unsupported content type: expected '{expected}', got '{actual_str}'"),
+            FieldIssue::new(
+                ExtractionSource::ContentType,
+                "",
+                expected,
+                format!("got '{actual_str}'"),
+            ),
+        )
     }
 
     /// Creates an error for missing Content-Type header.
     #[must_use]
     pub fn missing_content_type(expected: &str) -> Self {
-        Self {
-            extraction_source: ExtractionSource::ContentType,
-            kind: ExtractionErrorKind::UnsupportedMediaType,
-            message: format!("missing Content-Type header, expected '{expected}'"),
-            field: None,
-        }
+        Self::with_issue(
+            ExtractionSource::ContentType,
+            ExtractionErrorKind::UnsupportedMediaType,
+            format!("missing Content-Type header, expected '{expected}'"),
+            FieldIssue::new(
+                ExtractionSource::ContentType,
+                "",
+                expected,
+                "missing Content-Type header",
+            ),
+        )
     }
 
     /// Creates an error for invalid Content-Type header.
     #[must_use]
     pub fn invalid_content_type(details: impl Into<String>) -> Self {
-        Self {
-            extraction_source: ExtractionSource::ContentType,
-            kind: ExtractionErrorKind::UnsupportedMediaType,
-            message: format!("invalid Content-Type header: {}", details.into()),
-            field: None,
-        }
+        let details = details.into();
+        Self::with_issue(
+            ExtractionSource::ContentType,
+            ExtractionErrorKind::UnsupportedMediaType,
+            format!("invalid Content-Type header: {details}"),
+            FieldIssue::new(ExtractionSource::ContentType, "", "", details),
+        )
     }
 
     /// Creates a custom error.
@@ -193,11 +298,102 @@ impl ExtractionError {
         message: impl Into<String>,
     ) -> Self {
         let field = field.into();
+        let message = message.into();
+        Self::with_issue(
+            source,
+            ExtractionErrorKind::Custom,
+            message.clone(),
+            FieldIssue::new(source, field, "", message),
+        )
+    }
+
+    /// Combines the failures of several extractors into one error listing
+    /// every issue.
+    ///
+    /// Returns `None` when there are no errors and the error itself when
+    /// there is exactly one. Several errors combine into a `400 Bad Request`
+    /// with the code `INVALID_REQUEST`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use archimedes_extract::{ExtractionError, ExtractionSource};
+    /// use http::StatusCode;
+    ///
+    /// let err = ExtractionError::aggregate([
+    ///     ExtractionError::invalid_type(ExtractionSource::Path, "user_id", "expected u64"),
+    ///     ExtractionError::missing(ExtractionSource::Query, "limit"),
+    /// ])
+    /// .unwrap();
+    ///
+    /// assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+    /// assert_eq!(err.issues().len(), 2);
+    /// ```
+    #[must_use]
+    pub fn aggregate(errors: impl IntoIterator<Item = Self>) -> Option<Self> {
+        let mut errors: Vec<Self> = errors.into_iter().collect();
+        if errors.len() <= 1 {
+            return errors.pop();
+        }
+
+        let message = errors
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; ");
+        Some(Self {
+            extraction_source: ExtractionSource::Other,
+            kind: ExtractionErrorKind::Multiple,
+            field: None,
+            message: format!("{} request issues: {message}", errors.len()),
+            issues: errors.into_iter().flat_map(|error| error.issues).collect(),
+        })
+    }
+
+    /// Creates an error from a failure to deserialize `source`, naming the
+    /// field the deserializer had reached.
+    pub(crate) fn from_path_error(
+        source: ExtractionSource,
+        path: &serde_path_to_error::Path,
+        details: &str,
+    ) -> Self {
+        let mut field = if path.iter().next().is_none() {
+            String::new()
+        } else {
+            path.to_string()
+        };
+
+        // Missing fields are reported at their parent, so name them here
+        if let Some(name) = details
+            .strip_prefix("missing field `")
+            .and_then(|rest| rest.strip_suffix('`'))
+        {
+            if !field.is_empty() {
+                field.push('.');
+            }
+            field.push_str(name);
+            return Self::missing(source, field);
+        }
+
+        if field.is_empty() {
+            Self::deserialization_failed(source, details)
+        } else {
+            Self::invalid_type(source, field, details)
+        }
+    }
+
+    fn with_issue(
+        source: ExtractionSource,
+        kind: ExtractionErrorKind,
+        message: String,
+        issue: FieldIssue,
+    ) -> Self {
         Self {
             extraction_source: source,
-            kind: ExtractionErrorKind::Custom,
-            message: message.into(),
-            field: Some(field),
+            kind,
+            field: (!issue.field.is_empty()).then(|| issue.field.clone()),
+            message,
+            issues: vec![issue],
         }
     }
 
@@ -219,19 +415,38 @@ impl ExtractionError {
         self.field.as_deref()
     }
 
+    /// Returns every issue this error reports.
+    #[must_use]
+    pub fn issues(&self) -> &[FieldIssue] {
+        &self.issues
+    }
+
+    /// Returns the issues as error envelope details:
+    /// `{"issues": [{"source", "field", "expected", "message"}]}`.
+    #[must_use]
+    pub fn details(&self) -> serde_json::Value {
+        serde_json::json!({ "issues": self.issues })
+    }
+
+    /// Returns `true` if the request body parsed but did not match the
+    /// expected shape, such as a missing field or a wrong type.
+    #[must_use]
+    pub fn is_body_schema_issue(&self) -> bool {
+        self.extraction_source == ExtractionSource::Body
+            && matches!(
+                self.kind,
+                ExtractionErrorKind::Missing | ExtractionErrorKind::InvalidType
+            )
+    }
+
     /// Returns the appropriate HTTP status code for this error.
+    ///
+    /// Uses the default [`ExtractionStatusMap`]; see
+    /// [`ExtractionStatusMap::resolve`] to answer body schema issues with
+    /// `422 Unprocessable Entity` instead.
     #[must_use]
-    #[allow(clippy::match_same_arms)]
     pub fn status_code(&self) -> StatusCode {
-        match self.kind {
-            ExtractionErrorKind::Missing => StatusCode::BAD_REQUEST,
-            ExtractionErrorKind::InvalidType => StatusCode::BAD_REQUEST,
-            ExtractionErrorKind::ValidationFailed => StatusCode::UNPROCESSABLE_ENTITY,
-            ExtractionErrorKind::DeserializationFailed => StatusCode::BAD_REQUEST,
-            ExtractionErrorKind::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
-            ExtractionErrorKind::UnsupportedMediaType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
-            ExtractionErrorKind::Custom => StatusCode::INTERNAL_SERVER_ERROR,
-        }
+        ExtractionStatusMap::default().resolve(self)
     }
 
     /// Returns the error code suitable for error envelopes.
@@ -245,6 +460,84 @@ impl ExtractionError {
             ExtractionErrorKind::PayloadTooLarge => "PAYLOAD_TOO_LARGE",
             ExtractionErrorKind::UnsupportedMediaType => "UNSUPPORTED_MEDIA_TYPE",
             ExtractionErrorKind::Custom => "EXTRACTION_FAILED",
+            ExtractionErrorKind::Multiple => "INVALID_REQUEST",
+        }
+    }
+}
+
+/// Status codes used for extraction errors.
+///
+/// Body schema issues, where the body parsed but a field is missing or has
+/// the wrong type, are answered with `400 Bad Request` by default. Some APIs
+/// prefer `422 Unprocessable Entity` for these, keeping 400 for malformed
+/// requests.
+///
+/// # Example
+///
+/// ```rust
+/// use archimedes_extract::{ExtractionError, ExtractionSource, ExtractionStatusMap};
+/// use http::StatusCode;
+///
+/// let map = ExtractionStatusMap::new().body_schema(StatusCode::UNPROCESSABLE_ENTITY);
+///
+/// let err = ExtractionError::missing(ExtractionSource::Body, "email");
+/// assert_eq!(map.resolve(&err), StatusCode::UNPROCESSABLE_ENTITY);
+///
+/// let err = ExtractionError::deserialization_failed(ExtractionSource::Body, "EOF");
+/// assert_eq!(map.resolve(&err), StatusCode::BAD_REQUEST);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtractionStatusMap {
+    body_schema: StatusCode,
+    validation: StatusCode,
+}
+
+impl Default for ExtractionStatusMap {
+    fn default() -> Self {
+        Self {
+            body_schema: StatusCode::BAD_REQUEST,
+            validation: StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+}
+
+impl ExtractionStatusMap {
+    /// Creates the default mapping.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the status for body schema issues (default 400).
+    #[must_use]
+    pub fn body_schema(mut self, status: StatusCode) -> Self {
+        self.body_schema = status;
+        self
+    }
+
+    /// Sets the status for failed validation (default 422).
+    #[must_use]
+    pub fn validation(mut self, status: StatusCode) -> Self {
+        self.validation = status;
+        self
+    }
+
+    /// Returns the status code for an error.
+    #[must_use]
+    #[allow(clippy::match_same_arms)]
+    pub fn resolve(&self, error: &ExtractionError) -> StatusCode {
+        if error.is_body_schema_issue() {
+            return self.body_schema;
+        }
+        match error.kind {
+            ExtractionErrorKind::Missing => StatusCode::BAD_REQUEST,
+            ExtractionErrorKind::InvalidType => StatusCode::BAD_REQUEST,
+            ExtractionErrorKind::ValidationFailed => self.validation,
+            ExtractionErrorKind::DeserializationFailed => StatusCode::BAD_REQUEST,
+            ExtractionErrorKind::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            ExtractionErrorKind::UnsupportedMediaType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ExtractionErrorKind::Custom => StatusCode::INTERNAL_SERVER_ERROR,
+            ExtractionErrorKind::Multiple => StatusCode::BAD_REQUEST,
         }
     }
 }
@@ -257,6 +550,36 @@ impl fmt::Display for ExtractionError {
 
 impl std::error::Error for ExtractionError {}
 
+impl From<ExtractionError> for ThemisError {
+    /// Converts to a validation error with one field error per issue, keyed
+    /// by [`FieldIssue::location`].
+    fn from(error: ExtractionError) -> Self {
+        let mut fields = FieldErrors::new();
+        for issue in &error.issues {
+            fields.add(issue.location(), issue.message.clone());
+        }
+        ThemisError::validation_with_fields(error.message, fields)
+    }
+}
+
+/// Deserializes URL-encoded `input`, tracking the path to a failing field.
+pub(crate) fn from_urlencoded<T: DeserializeOwned>(
+    source: ExtractionSource,
+    input: &str,
+) -> Result<T, ExtractionError> {
+    let deserializer =
+        serde_urlencoded::Deserializer::new(form_urlencoded::parse(input.as_bytes()));
+    serde_path_to_error::deserialize(deserializer)
+        .map_err(|e| ExtractionError::from_path_error(source, e.path(), &e.inner().to_string()))
+}
+
+/// Returns what a serde error message says was expected, if anything.
+fn expected_from(details: &str) -> &str {
+    details
+        .split_once("expected ")
+        .map_or("", |(_, expected)| expected)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -335,6 +658,63 @@ mod tests {
         assert!(err.to_string().contains("text/plain"));
     }
 
+    #[test]
+    fn test_aggregate_lists_every_issue() {
+        assert!(ExtractionError::aggregate([]).is_none());
+
+        let single =
+            ExtractionError::aggregate([ExtractionError::payload_too_large(1, 2)]).unwrap();
+        assert_eq!(single.error_code(), "PAYLOAD_TOO_LARGE");
+
+        let err = ExtractionError::aggregate([
+            ExtractionError::invalid_type(ExtractionSource::Path, "user_id", "expected u64"),
+            ExtractionError::missing(ExtractionSource::Query, "limit"),
+            ExtractionError::missing(ExtractionSource::Body, "email"),
+        ])
+        .unwrap();
+
+        assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+        assert_eq!(err.error_code(), "INVALID_REQUEST");
+        assert!(err.to_string().starts_with("3 request issues: "));
+        let locations: Vec<_> = err.issues().iter().map(FieldIssue::location).collect();
+        assert_eq!(locations, ["path.user_id", "query.limit", "body.email"]);
+        assert_eq!(err.issues()[0].expected, "u64");
+
+        let details = err.details();
+        assert_eq!(details["issues"][1]["source"], "query");
+        assert_eq!(details["issues"][1]["field"], "limit");
+    }
+
+    #[test]
+    fn test_status_map_body_schema() {
+        let map = ExtractionStatusMap::new().body_schema(StatusCode::UNPROCESSABLE_ENTITY);
+
+        let body = ExtractionError::invalid_type(ExtractionSource::Body, "age", "expected u8");
+        assert_eq!(body.status_code(), StatusCode::BAD_REQUEST);
+        assert_eq!(map.resolve(&body), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let query = ExtractionError::invalid_type(ExtractionSource::Query, "age", "expected u8");
+        assert_eq!(map.resolve(&query), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn test_into_themis_error() {
+        let err = ExtractionError::aggregate([
+            ExtractionError::missing(ExtractionSource::Query, "limit"),
+            ExtractionError::deserialization_failed(ExtractionSource::Body, "EOF"),
+        ])
+        .unwrap();
+
+        let themis = ThemisError::from(err);
+        assert_eq!(themis.status_code(), StatusCode::BAD_REQUEST);
+        let details = themis.to_envelope(None).error.details.unwrap();
+        assert_eq!(
+            details["fields"]["query.limit"][0],
+            "missing required value"
+        );
+        assert_eq!(details["fields"]["body"][0], "EOF");
+    }
+
     #[test]
     fn test_extraction_source_display() {
         assert_eq!(ExtractionSource::Path.to_string(), "path");
@@ -1,14 +1,66 @@
 //! Form data extractor.
 //!
 //! The [`Form`] extractor deserializes URL-encoded form data from request bodies.
+//!
+//! Requests must have Content-Type `application/x-www-form-urlencoded`, or
+//! extraction fails with `415 Unsupported Media Type`. Size limits are
+//! checked before the body is parsed: the total body size, and the encoded
+//! size of each field's value (see [`FormConfig`]).
+//!
+//! A key given more than once, as in `tags=a&tags=b`, deserializes into a
+//! sequence such as `Vec<String>`. A key given once deserializes into a
+//! sequence of one.
 
 use crate::{ExtractionContext, ExtractionError, ExtractionSource, FromRequest};
-use serde::de::DeserializeOwned;
+use serde::de::{self, value, DeserializeOwned, IntoDeserializer, Visitor};
 use std::ops::Deref;
 
 /// Default maximum body size for form extraction (1 MB).
 const DEFAULT_MAX_BODY_SIZE: usize = 1024 * 1024;
 
+/// The media type of URL-encoded forms.
+const FORM_MEDIA_TYPE: &str = "application/x-www-form-urlencoded";
+
+/// Size limits for form extraction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormConfig {
+    /// Maximum total body size in bytes.
+    pub max_body_size: usize,
+    /// Maximum encoded size of a single field value in bytes.
+    pub max_field_size: usize,
+}
+
+impl Default for FormConfig {
+    fn default() -> Self {
+        Self {
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+            max_field_size: DEFAULT_MAX_BODY_SIZE,
+        }
+    }
+}
+
+impl FormConfig {
+    /// Create a new configuration with default values.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum body size.
+    #[must_use]
+    pub fn max_body_size(mut self, size: usize) -> Self {
+        self.max_body_size = size;
+        self
+    }
+
+    /// Set the maximum field size.
+    #[must_use]
+    pub fn max_field_size(mut self, size: usize) -> Self {
+        self.max_field_size = size;
+        self
+    }
+}
+
 /// Extractor for URL-encoded form data.
 ///
 /// `Form<T>` deserializes the request body as URL-encoded form data into the
@@ -31,11 +83,13 @@ const DEFAULT_MAX_BODY_SIZE: usize = 1024 * 1024;
 /// }
 ///
 /// let body = b"username=alice&password=secret123";
+/// let mut headers = HeaderMap::new();
+/// headers.insert("content-type", "application/x-www-form-urlencoded".parse().unwrap());
 ///
 /// let ctx = ExtractionContext::new(
 ///     Method::POST,
 ///     Uri::from_static("/login"),
-///     HeaderMap::new(),
+///     headers,
 ///     Bytes::from_static(body),
 ///     Params::new(),
 /// );
@@ -63,11 +117,13 @@ const DEFAULT_MAX_BODY_SIZE: usize = 1024 * 1024;
 ///
 /// // "hello world" is encoded as "hello+world" or "hello%20world"
 /// let body = b"query=hello+world";
+/// let mut headers = HeaderMap::new();
+/// headers.insert("content-type", "application/x-www-form-urlencoded".parse().unwrap());
 ///
 /// let ctx = ExtractionContext::new(
 ///     Method::POST,
 ///     Uri::from_static("/search"),
-///     HeaderMap::new(),
+///     headers,
 ///     Bytes::from_static(body),
 ///     Params::new(),
 /// );
@@ -75,6 +131,46 @@ const DEFAULT_MAX_BODY_SIZE: usize = 1024 * 1024;
 /// let Form(form) = Form::<SearchForm>::from_request(&ctx).unwrap();
 /// assert_eq!(form.query, "hello world");
 /// ```
+///
+/// # Type Coercion
+///
+/// Values arrive as strings and are converted to the field's declared type,
+/// following the same rules as the Sentinel's coercion mode: `"30"` for an
+/// integer, `"3.5"` for a float and `"true"`/`"false"` for a bool. Lossy
+/// conversions such as `"3.7"` into an integer fail, and `String` fields
+/// keep the raw value.
+///
+/// # Repeated Keys
+///
+/// Repeated keys, as sent by multi-select inputs and checkbox groups,
+/// deserialize into sequences:
+///
+/// ```rust
+/// use archimedes_extract::{Form, FromRequest, ExtractionContext};
+/// use archimedes_router::Params;
+/// use http::{Method, Uri, HeaderMap};
+/// use bytes::Bytes;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct TagsForm {
+///     tags: Vec<String>,
+/// }
+///
+/// let mut headers = HeaderMap::new();
+/// headers.insert("content-type", "application/x-www-form-urlencoded".parse().unwrap());
+///
+/// let ctx = ExtractionContext::new(
+///     Method::POST,
+///     Uri::from_static("/posts"),
+///     headers,
+///     Bytes::from_static(b"tags=rust&tags=http"),
+///     Params::new(),
+/// );
+///
+/// let Form(form) = Form::<TagsForm>::from_request(&ctx).unwrap();
+/// assert_eq!(form.tags, ["rust", "http"]);
+/// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Form<T>(pub T);
 
@@ -86,6 +182,22 @@ impl<T> Form<T> {
     }
 }
 
+impl<T: DeserializeOwned> Form<T> {
+    /// Extracts form data with custom size limits.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the Content-Type is not
+    /// `application/x-www-form-urlencoded`, a limit is exceeded, or the
+    /// body does not deserialize into `T`.
+    pub fn from_request_with(
+        ctx: &ExtractionContext,
+        config: &FormConfig,
+    ) -> Result<Self, ExtractionError> {
+        parse_form(ctx, config).map(Form)
+    }
+}
+
 impl<T> Deref for Form<T> {
     type Target = T;
 
@@ -96,48 +208,26 @@ impl<T> Deref for Form<T> {
 
 impl<T: DeserializeOwned> FromRequest for Form<T> {
     fn from_request(ctx: &ExtractionContext) -> Result<Self, ExtractionError> {
-        let body = ctx.body();
-
-        // Check body size
-        if body.len() > DEFAULT_MAX_BODY_SIZE {
-            return Err(ExtractionError::payload_too_large(
-                DEFAULT_MAX_BODY_SIZE,
-                body.len(),
-            ));
-        }
-
-        // Handle empty body
-        if body.is_empty() {
-            return Err(ExtractionError::deserialization_failed(
-                ExtractionSource::Body,
-                "empty request body",
-            ));
-        }
-
-        // Parse as UTF-8
-        let body_str = std::str::from_utf8(body).map_err(|e| {
-            ExtractionError::deserialization_failed(
-                ExtractionSource::Body,
-                format!("invalid UTF-8: {e}"),
-            )
-        })?;
-
-        // Deserialize form data
-        let value: T = serde_urlencoded::from_str(body_str).map_err(|e| {
-            ExtractionError::deserialization_failed(ExtractionSource::Body, e.to_string())
-        })?;
-
-        Ok(Form(value))
+        Self::from_request_with(ctx, &FormConfig::default())
     }
 }
 
-/// Form extractor with configurable size limit.
+/// Form extractor with configurable size limits.
 ///
-/// Use this when you need to accept form bodies larger than the default 1 MB limit.
+/// Use this when you need to accept form bodies larger than the default 1 MB
+/// limit, or to limit the size of each field. `FIELD_LIMIT` defaults to no
+/// limit beyond `LIMIT`.
+///
+/// ```rust,ignore
+/// // At most 64 KiB in total, and 4 KiB per field
+/// async fn comment(FormWithLimit(form): FormWithLimit<CommentForm, 65536, 4096>) -> Response {
+///     // ...
+/// }
+/// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct FormWithLimit<T, const LIMIT: usize>(pub T);
+pub struct FormWithLimit<T, const LIMIT: usize, const FIELD_LIMIT: usize = { usize::MAX }>(pub T);
 
-impl<T, const LIMIT: usize> FormWithLimit<T, LIMIT> {
+impl<T, const LIMIT: usize, const FIELD_LIMIT: usize> FormWithLimit<T, LIMIT, FIELD_LIMIT> {
     /// Consumes the `FormWithLimit` and returns the inner value.
     #[must_use]
     pub fn into_inner(self) -> T {
@@ -145,7 +235,9 @@ impl<T, const LIMIT: usize> FormWithLimit<T, LIMIT> {
     }
 }
 
-impl<T, const LIMIT: usize> Deref for FormWithLimit<T, LIMIT> {
+impl<T, const LIMIT: usize, const FIELD_LIMIT: usize> Deref
+    for FormWithLimit<T, LIMIT, FIELD_LIMIT>
+{
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
@@ -153,37 +245,215 @@ impl<T, const LIMIT: usize> Deref for FormWithLimit<T, LIMIT> {
     }
 }
 
-impl<T: DeserializeOwned, const LIMIT: usize> FromRequest for FormWithLimit<T, LIMIT> {
+impl<T: DeserializeOwned, const LIMIT: usize, const FIELD_LIMIT: usize> FromRequest
+    for FormWithLimit<T, LIMIT, FIELD_LIMIT>
+{
     fn from_request(ctx: &ExtractionContext) -> Result<Self, ExtractionError> {
-        let body = ctx.body();
+        let config = FormConfig::new()
+            .max_body_size(LIMIT)
+            .max_field_size(FIELD_LIMIT);
+        parse_form(ctx, &config).map(FormWithLimit)
+    }
+}
 
-        // Check body size against custom limit
-        if body.len() > LIMIT {
-            return Err(ExtractionError::payload_too_large(LIMIT, body.len()));
-        }
+/// Checks the content type and limits of a form body, then deserializes it.
+fn parse_form<T: DeserializeOwned>(
+    ctx: &ExtractionContext,
+    config: &FormConfig,
+) -> Result<T, ExtractionError> {
+    check_content_type(ctx)?;
+
+    let body = ctx.body();
+
+    // Check body size
+    if body.len() > config.max_body_size {
+        return Err(ExtractionError::payload_too_large(
+            config.max_body_size,
+            body.len(),
+        ));
+    }
+
+    // Handle empty body
+    if body.is_empty() {
+        return Err(ExtractionError::deserialization_failed(
+            ExtractionSource::Body,
+            "empty request body",
+        ));
+    }
 
-        // Handle empty body
-        if body.is_empty() {
-            return Err(ExtractionError::deserialization_failed(
+    // Check field sizes before decoding anything
+    for pair in body.split(|&b| b == b'&') {
+        let (name, value) = match pair.iter().position(|&b| b == b'=') {
+            Some(i) => (&pair[..i], &pair[i + 1..]),
+            None => (pair, &[][..]),
+        };
+        if value.len() > config.max_field_size {
+            return Err(ExtractionError::field_too_large(
                 ExtractionSource::Body,
-                "empty request body",
+                String::from_utf8_lossy(name),
+                config.max_field_size,
+                value.len(),
             ));
         }
+    }
 
-        // Parse as UTF-8
-        let body_str = std::str::from_utf8(body).map_err(|e| {
-            ExtractionError::deserialization_failed(
-                ExtractionSource::Body,
-                format!("invalid UTF-8: {e}"),
-            )
-        })?;
+    // Parse as UTF-8
+    let body_str = std::str::from_utf8(body).map_err(|e| {
+        ExtractionError::deserialization_failed(
+            ExtractionSource::Body,
+            format!("invalid UTF-8: {e}"),
+        )
+    })?;
+
+    // Group values by key, keeping keys in first-seen order
+    let pairs: Vec<(String, String)> = serde_urlencoded::from_str(body_str).map_err(|e| {
+        ExtractionError::deserialization_failed(ExtractionSource::Body, e.to_string())
+    })?;
+    let mut fields: Vec<(String, FieldValues)> = Vec::new();
+    for (name, value) in pairs {
+        match fields.iter_mut().find(|(field, _)| *field == name) {
+            Some((_, values)) => values.0.push(value),
+            None => fields.push((name, FieldValues(vec![value]))),
+        }
+    }
+
+    // Deserialize form data, naming the field that failed
+    serde_path_to_error::deserialize(value::MapDeserializer::<_, value::Error>::new(
+        fields.into_iter(),
+    ))
+    .map_err(|e| {
+        ExtractionError::from_path_error(ExtractionSource::Body, e.path(), &e.inner().to_string())
+    })
+}
+
+/// Checks that the request declares a URL-encoded form body.
+fn check_content_type(ctx: &ExtractionContext) -> Result<(), ExtractionError> {
+    let content_type = ctx
+        .content_type()
+        .ok_or_else(|| ExtractionError::missing_content_type(FORM_MEDIA_TYPE))?;
+    let mime: mime::Mime = content_type
+        .parse()
+        .map_err(|_| ExtractionError::invalid_content_type(content_type))?;
+    if mime.essence_str() == FORM_MEDIA_TYPE {
+        Ok(())
+    } else {
+        Err(ExtractionError::unsupported_media_type(
+            FORM_MEDIA_TYPE,
+            Some(content_type),
+        ))
+    }
+}
+
+/// The values of one form field, one per occurrence of its key.
+///
+/// Deserializes as a sequence when a sequence is expected, and as the
+/// single value otherwise. Scalars are parsed from their string form.
+struct FieldValues(Vec<String>);
+
+impl FieldValues {
+    fn single(self) -> Result<String, value::Error> {
+        let mut values = self.0;
+        if values.len() == 1 {
+            Ok(values.remove(0))
+        } else {
+            Err(de::Error::custom(format!(
+                "expected a single value, got {}",
+                values.len()
+            )))
+        }
+    }
+}
 
-        // Deserialize form data
-        let value: T = serde_urlencoded::from_str(body_str).map_err(|e| {
-            ExtractionError::deserialization_failed(ExtractionSource::Body, e.to_string())
-        })?;
+impl<'de> IntoDeserializer<'de, value::Error> for FieldValues {
+    type Deserializer = Self;
 
-        Ok(FormWithLimit(value))
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+/// Deserializes a scalar by parsing the single value.
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                let value = self.single()?;
+                let parsed = value.parse().map_err(|e| {
+                    de::Error::custom(format!("invalid value '{value}': {e}"))
+                })?;
+                visitor.$visit(parsed)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for FieldValues {
+    type Error = value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.0.len() == 1 {
+            visitor.visit_string(self.single()?)
+        } else {
+            self.deserialize_seq(visitor)
+        }
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let values = self.0.into_iter().map(|value| FieldValues(vec![value]));
+        visitor.visit_seq(value::SeqDeserializer::new(values))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_enum(self.single()?.into_deserializer())
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    deserialize_parsed! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    serde::forward_to_deserialize_any! {
+        i128 u128 str string bytes byte_buf unit_struct tuple_struct map struct
+        identifier ignored_any
     }
 }
 
@@ -215,10 +485,15 @@ mod tests {
     }
 
     fn make_ctx(body: &[u8]) -> ExtractionContext {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "content-type",
+            "application/x-www-form-urlencoded".parse().unwrap(),
+        );
         ExtractionContext::new(
             Method::POST,
             Uri::from_static("/"),
-            HeaderMap::new(),
+            headers,
             Bytes::from(body.to_vec()),
             Params::new(),
         )
@@ -269,7 +544,6 @@ mod tests {
 
     #[test]
     fn test_array_form() {
-        // Note: serde_urlencoded doesn't support repeated keys for arrays.
         // Arrays default to empty when not provided.
         let body = b"";
         let ctx = make_ctx(body);
@@ -279,6 +553,77 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_repeated_keys_into_vec() {
+        #[derive(Debug, Deserialize)]
+        struct TagsForm {
+            title: String,
+            tags: Vec<String>,
+            #[serde(default)]
+            scores: Vec<u32>,
+        }
+
+        let ctx = make_ctx(b"tags=rust&title=Hello&tags=web+dev&scores=1&scores=2");
+        let Form(form) = Form::<TagsForm>::from_request(&ctx).unwrap();
+        assert_eq!(form.title, "Hello");
+        assert_eq!(form.tags, ["rust", "web dev"]);
+        assert_eq!(form.scores, [1, 2]);
+
+        // A key given once is a sequence of one
+        let ctx = make_ctx(b"title=Hello&tags=rust");
+        let Form(form) = Form::<TagsForm>::from_request(&ctx).unwrap();
+        assert_eq!(form.tags, ["rust"]);
+        assert!(form.scores.is_empty());
+
+        // A scalar field given more than once is ambiguous
+        let ctx = make_ctx(b"title=Hello&title=World&tags=rust");
+        assert!(Form::<TagsForm>::from_request(&ctx).is_err());
+    }
+
+    #[test]
+    fn test_wrong_content_type() {
+        let ctx_with = |content_type: Option<&'static str>| {
+            let mut headers = HeaderMap::new();
+            if let Some(content_type) = content_type {
+                headers.insert("content-type", content_type.parse().unwrap());
+            }
+            ExtractionContext::new(
+                Method::POST,
+                Uri::from_static("/"),
+                headers,
+                Bytes::from_static(b"username=alice&password=secret"),
+                Params::new(),
+            )
+        };
+
+        let err = Form::<LoginForm>::from_request(&ctx_with(Some("application/json"))).unwrap_err();
+        assert_eq!(err.status_code(), http::StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        let err = Form::<LoginForm>::from_request(&ctx_with(None)).unwrap_err();
+        assert_eq!(err.status_code(), http::StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        // Parameters are allowed
+        let ctx = ctx_with(Some("application/x-www-form-urlencoded; charset=UTF-8"));
+        assert!(Form::<LoginForm>::from_request(&ctx).is_ok());
+    }
+
+    #[test]
+    fn test_field_too_large() {
+        let body = format!("username=alice&password={}", "A".repeat(64));
+        let ctx = make_ctx(body.as_bytes());
+
+        let config = FormConfig::new().max_field_size(32);
+        let err = Form::<LoginForm>::from_request_with(&ctx, &config).unwrap_err();
+        assert_eq!(err.error_code(), "PAYLOAD_TOO_LARGE");
+        assert_eq!(err.field(), Some("password"));
+
+        let err = FormWithLimit::<LoginForm, 1024, 32>::from_request(&ctx).unwrap_err();
+        assert_eq!(err.field(), Some("password"));
+
+        assert!(FormWithLimit::<LoginForm, 1024, 64>::from_request(&ctx).is_ok());
+        assert!(FormWithLimit::<LoginForm, 1024>::from_request(&ctx).is_ok());
+    }
+
     #[test]
     fn test_empty_body() {
         let ctx = make_ctx(b"");
@@ -299,6 +644,25 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_form_coercion() {
+        #[derive(Debug, Deserialize)]
+        struct ProfileForm {
+            age: u32,
+            subscribed: bool,
+            phone: String,
+        }
+
+        let ctx = make_ctx(b"age=30&subscribed=true&phone=0123");
+        let Form(form) = Form::<ProfileForm>::from_request(&ctx).unwrap();
+        assert_eq!(form.age, 30);
+        assert!(form.subscribed);
+        assert_eq!(form.phone, "0123");
+
+        let ctx = make_ctx(b"age=3.7&subscribed=true&phone=0123");
+        assert!(Form::<ProfileForm>::from_request(&ctx).is_err());
+    }
+
     #[test]
     fn test_deref() {
         let body = b"username=alice&password=secret";
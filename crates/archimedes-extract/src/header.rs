@@ -1,9 +1,30 @@
 //! Header extractors.
 //!
 //! This module provides extractors for HTTP headers.
+//!
+//! # Typed Headers
+//!
+//! [`ExtractTypedHeader<T>`] extracts any [`TypedHeader`]. Besides the
+//! simple wrappers ([`ContentType`], [`Accept`], [`Authorization`],
+//! [`UserAgent`]), this module parses the conditional and negotiation
+//! headers services handle most often:
+//!
+//! | Type | Header |
+//! |------|--------|
+//! | [`IfMatch`] | `If-Match` |
+//! | [`IfNoneMatch`] | `If-None-Match` |
+//! | [`Range`] | `Range` |
+//! | [`Forwarded`] | `Forwarded` |
+//! | [`IdempotencyKey`] | `Idempotency-Key` |
+//! | [`AcceptLanguage`] | `Accept-Language` |
+//!
+//! Application-specific headers can be declared with the
+//! [`typed_header!`](crate::typed_header) macro.
 
 use crate::{ExtractionContext, ExtractionError, ExtractionSource, FromRequest};
 use http::HeaderMap;
+use std::borrow::Cow;
+use std::fmt;
 use std::ops::Deref;
 
 /// Extractor for a single header value by name.
@@ -87,8 +108,26 @@ pub trait TypedHeader: Sized {
     /// The header name (lowercase).
     const NAME: &'static str;
 
+    /// Whether the header is a comma-separated list.
+    ///
+    /// List headers may be split across several header lines; when this is
+    /// `true` the lines are joined with `", "` before parsing.
+    const MULTI_VALUE: bool = false;
+
     /// Parses the header value into this type.
     fn parse(value: &str) -> Option<Self>;
+
+    /// Parses the header value, describing why it is invalid on failure.
+    ///
+    /// The default implementation calls [`parse`](Self::parse) and reports
+    /// a generic reason. Override it to tell clients what is wrong.
+    ///
+    /// # Errors
+    ///
+    /// Returns the reason the value could not be parsed.
+    fn try_parse(value: &str) -> Result<Self, String> {
+        Self::parse(value).ok_or_else(|| "failed to parse header value".to_string())
+    }
 }
 
 /// Extract a typed header from the request.
@@ -119,16 +158,21 @@ impl<T: TypedHeader> Deref for ExtractTypedHeader<T> {
 
 impl<T: TypedHeader> FromRequest for ExtractTypedHeader<T> {
     fn from_request(ctx: &ExtractionContext) -> Result<Self, ExtractionError> {
-        let value = ctx
-            .header(T::NAME)
-            .ok_or_else(|| ExtractionError::missing(ExtractionSource::Header, T::NAME))?;
-
-        let parsed = T::parse(value).ok_or_else(|| {
-            ExtractionError::invalid_type(
-                ExtractionSource::Header,
-                T::NAME,
-                "failed to parse header value",
-            )
+        let value = if T::MULTI_VALUE {
+            let values: Vec<&str> = ctx
+                .headers()
+                .get_all(T::NAME)
+                .iter()
+                .filter_map(|v| v.to_str().ok())
+                .collect();
+            (!values.is_empty()).then(|| Cow::Owned(values.join(", ")))
+        } else {
+            ctx.header(T::NAME).map(Cow::Borrowed)
+        }
+        .ok_or_else(|| ExtractionError::missing(ExtractionSource::Header, T::NAME))?;
+
+        let parsed = T::try_parse(&value).map_err(|reason| {
+            ExtractionError::invalid_type(ExtractionSource::Header, T::NAME, reason)
         })?;
 
         Ok(ExtractTypedHeader(parsed))
@@ -179,7 +223,7 @@ impl Headers {
         self.0
     }
 
-    /// Gets a header value by name.
+    /// Gets a header value by name, ignoring case.
     #[must_use]
     pub fn get(&self, name: &str) -> Option<&str> {
         self.0.get(name).and_then(|v| v.to_str().ok())
@@ -314,6 +358,695 @@ impl TypedHeader for UserAgent {
     }
 }
 
+/// Declares a [`TypedHeader`] newtype.
+///
+/// The parser receives the raw header value and returns the inner value or
+/// a reason the value is invalid. When extracted with
+/// [`ExtractTypedHeader`], a failure becomes an [`ExtractionError`] naming
+/// the header and carrying the reason. The generated type derives `Debug`
+/// and `Clone` and derefs to its inner value; further attributes are passed
+/// through.
+///
+/// # Example
+///
+/// ```rust
+/// use archimedes_extract::{typed_header, TypedHeader};
+///
+/// typed_header! {
+///     /// Tenant the request is made on behalf of.
+///     #[derive(PartialEq, Eq)]
+///     name = "x-tenant-id",
+///     type = pub TenantId(String),
+///     parse = |value| {
+///         if value.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+///             Ok(value.to_string())
+///         } else {
+///             Err("tenant IDs may only contain letters, digits and '-'".to_string())
+///         }
+///     },
+/// }
+///
+/// assert_eq!(TenantId::NAME, "x-tenant-id");
+/// assert_eq!(TenantId::parse("acme-eu"), Some(TenantId("acme-eu".to_string())));
+/// assert!(TenantId::try_parse("acme eu").unwrap_err().contains("letters"));
+/// ```
+#[macro_export]
+macro_rules! typed_header {
+    (
+        $(#[$meta:meta])*
+        name = $name:literal,
+        type = $vis:vis $ty:ident($inner:ty),
+        parse = $parse:expr $(,)?
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone)]
+        $vis struct $ty(pub $inner);
+
+        impl $crate::TypedHeader for $ty {
+            const NAME: &'static str = $name;
+
+            fn parse(value: &str) -> ::std::option::Option<Self> {
+                <Self as $crate::TypedHeader>::try_parse(value).ok()
+            }
+
+            fn try_parse(value: &str) -> ::std::result::Result<Self, ::std::string::String> {
+                let parse: fn(&str) -> ::std::result::Result<$inner, ::std::string::String> =
+                    $parse;
+                parse(value).map($ty)
+            }
+        }
+
+        impl ::std::ops::Deref for $ty {
+            type Target = $inner;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+    };
+}
+
+/// Splits a header value on `separator`, ignoring separators inside quoted
+/// strings. Parts are trimmed.
+fn split_quoted(value: &str, separator: char) -> Result<Vec<&str>, String> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut in_quotes = false;
+    let mut escaped = false;
+
+    for (i, c) in value.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if in_quotes && c == '\\' {
+            escaped = true;
+        } else if c == '"' {
+            in_quotes = !in_quotes;
+        } else if c == separator && !in_quotes {
+            parts.push(value[start..i].trim());
+            start = i + c.len_utf8();
+        }
+    }
+
+    if in_quotes {
+        return Err("unterminated quoted string".to_string());
+    }
+    parts.push(value[start..].trim());
+    Ok(parts)
+}
+
+/// Returns `true` if `value` is a non-empty RFC 9110 token.
+fn is_token(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
+
+/// An entity tag, as used by `ETag`, `If-Match` and `If-None-Match`.
+///
+/// Displays in header form, e.g. `W/"v1"`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EntityTag {
+    weak: bool,
+    tag: String,
+}
+
+impl EntityTag {
+    /// Creates a strong entity tag.
+    #[must_use]
+    pub fn strong(tag: impl Into<String>) -> Self {
+        Self {
+            weak: false,
+            tag: tag.into(),
+        }
+    }
+
+    /// Creates a weak entity tag.
+    #[must_use]
+    pub fn weak(tag: impl Into<String>) -> Self {
+        Self {
+            weak: true,
+            tag: tag.into(),
+        }
+    }
+
+    /// Parses a single entity tag such as `"v1"` or `W/"v1"`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the reason the value is not a valid entity tag.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        let (weak, quoted) = match value.strip_prefix("W/") {
+            Some(rest) => (true, rest),
+            None => (false, value),
+        };
+
+        let tag = quoted
+            .strip_prefix('"')
+            .and_then(|rest| rest.strip_suffix('"'))
+            .ok_or_else(|| format!("entity tag {value} must be a quoted string"))?;
+        if let Some(c) = tag
+            .chars()
+            .find(|&c| c == '"' || c.is_ascii_control() || c == ' ')
+        {
+            return Err(format!(
+                "entity tag {value} contains invalid character {c:?}"
+            ));
+        }
+
+        Ok(Self {
+            weak,
+            tag: tag.to_string(),
+        })
+    }
+
+    /// Returns `true` if this is a weak tag.
+    #[must_use]
+    pub fn is_weak(&self) -> bool {
+        self.weak
+    }
+
+    /// Returns the opaque tag, without quotes or weakness indicator.
+    #[must_use]
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// Strong comparison: both tags are strong and identical.
+    #[must_use]
+    pub fn strong_eq(&self, other: &Self) -> bool {
+        !self.weak && !other.weak && self.tag == other.tag
+    }
+
+    /// Weak comparison: the opaque tags are identical, ignoring weakness.
+    #[must_use]
+    pub fn weak_eq(&self, other: &Self) -> bool {
+        self.tag == other.tag
+    }
+}
+
+impl fmt::Display for EntityTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.weak {
+            write!(f, "W/\"{}\"", self.tag)
+        } else {
+            write!(f, "\"{}\"", self.tag)
+        }
+    }
+}
+
+/// Parses `*` or a list of entity tags. `None` stands for `*`.
+///
+/// Entity tags may contain commas, so the list is split outside quotes.
+fn parse_entity_tags(value: &str) -> Result<Option<Vec<EntityTag>>, String> {
+    if value.trim() == "*" {
+        return Ok(None);
+    }
+
+    let mut tags = Vec::new();
+    for part in split_quoted(value, ',')? {
+        if !part.is_empty() {
+            tags.push(EntityTag::parse(part)?);
+        }
+    }
+    if tags.is_empty() {
+        return Err("expected '*' or at least one entity tag".to_string());
+    }
+    Ok(Some(tags))
+}
+
+/// If-Match header.
+///
+/// The precondition holds when the current representation's entity tag
+/// strongly matches one of the listed tags, or for `*` when a current
+/// representation exists.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IfMatch {
+    /// `*`: matches any current representation.
+    Any,
+    /// The listed entity tags.
+    Tags(Vec<EntityTag>),
+}
+
+impl IfMatch {
+    /// Evaluates the precondition against the current entity tag.
+    ///
+    /// `current` is `None` when the resource has no current representation.
+    #[must_use]
+    pub fn matches(&self, current: Option<&EntityTag>) -> bool {
+        match (self, current) {
+            (_, None) => false,
+            (Self::Any, Some(_)) => true,
+            (Self::Tags(tags), Some(current)) => tags.iter().any(|t| t.strong_eq(current)),
+        }
+    }
+}
+
+impl TypedHeader for IfMatch {
+    const NAME: &'static str = "if-match";
+    const MULTI_VALUE: bool = true;
+
+    fn parse(value: &str) -> Option<Self> {
+        Self::try_parse(value).ok()
+    }
+
+    fn try_parse(value: &str) -> Result<Self, String> {
+        Ok(parse_entity_tags(value)?.map_or(Self::Any, Self::Tags))
+    }
+}
+
+/// If-None-Match header.
+///
+/// The precondition fails (typically answered with `304 Not Modified`)
+/// when the current entity tag weakly matches one of the listed tags, or
+/// for `*` when a current representation exists.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IfNoneMatch {
+    /// `*`: matches any current representation.
+    Any,
+    /// The listed entity tags.
+    Tags(Vec<EntityTag>),
+}
+
+impl IfNoneMatch {
+    /// Returns `true` if the current entity tag matches the header, meaning
+    /// the precondition fails.
+    ///
+    /// `current` is `None` when the resource has no current representation.
+    #[must_use]
+    pub fn matches(&self, current: Option<&EntityTag>) -> bool {
+        match (self, current) {
+            (_, None) => false,
+            (Self::Any, Some(_)) => true,
+            (Self::Tags(tags), Some(current)) => tags.iter().any(|t| t.weak_eq(current)),
+        }
+    }
+}
+
+impl TypedHeader for IfNoneMatch {
+    const NAME: &'static str = "if-none-match";
+    const MULTI_VALUE: bool = true;
+
+    fn parse(value: &str) -> Option<Self> {
+        Self::try_parse(value).ok()
+    }
+
+    fn try_parse(value: &str) -> Result<Self, String> {
+        Ok(parse_entity_tags(value)?.map_or(Self::Any, Self::Tags))
+    }
+}
+
+/// A single range of a `Range: bytes=...` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteRange {
+    /// `first-last`, both inclusive.
+    Bounded {
+        /// First byte position.
+        first: u64,
+        /// Last byte position (inclusive).
+        last: u64,
+    },
+    /// `first-`: from `first` to the end.
+    From(u64),
+    /// `-length`: the final `length` bytes.
+    Suffix(u64),
+}
+
+impl ByteRange {
+    /// Resolves the range against a representation of `len` bytes.
+    ///
+    /// Returns the inclusive `(first, last)` byte positions, or `None` if
+    /// the range is not satisfiable.
+    #[must_use]
+    pub fn resolve(&self, len: u64) -> Option<(u64, u64)> {
+        if len == 0 {
+            return None;
+        }
+        match *self {
+            Self::Bounded { first, last } if first < len => Some((first, last.min(len - 1))),
+            Self::From(first) if first < len => Some((first, len - 1)),
+            Self::Suffix(length) if length > 0 => Some((len.saturating_sub(length), len - 1)),
+            _ => None,
+        }
+    }
+}
+
+/// Range header (byte ranges only).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Range(pub Vec<ByteRange>);
+
+impl Range {
+    /// Returns the requested ranges in header order.
+    #[must_use]
+    pub fn ranges(&self) -> &[ByteRange] {
+        &self.0
+    }
+
+    /// Resolves every satisfiable range against a representation of `len`
+    /// bytes.
+    #[must_use]
+    pub fn satisfiable(&self, len: u64) -> Vec<(u64, u64)> {
+        self.0.iter().filter_map(|r| r.resolve(len)).collect()
+    }
+}
+
+impl TypedHeader for Range {
+    const NAME: &'static str = "range";
+
+    fn parse(value: &str) -> Option<Self> {
+        Self::try_parse(value).ok()
+    }
+
+    fn try_parse(value: &str) -> Result<Self, String> {
+        let (unit, specs) = value
+            .split_once('=')
+            .ok_or_else(|| "expected '<unit>=<ranges>'".to_string())?;
+        if !unit.trim().eq_ignore_ascii_case("bytes") {
+            return Err(format!("unsupported range unit '{}'", unit.trim()));
+        }
+
+        let parse_pos = |pos: &str| {
+            if pos.is_empty() || !pos.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(format!("invalid byte position '{pos}'"));
+            }
+            pos.parse::<u64>()
+                .map_err(|_| format!("byte position '{pos}' is too large"))
+        };
+
+        let mut ranges = Vec::new();
+        for spec in specs.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let (first, last) = spec
+                .split_once('-')
+                .ok_or_else(|| format!("range '{spec}' is missing '-'"))?;
+            let range = match (first, last) {
+                ("", length) => ByteRange::Suffix(parse_pos(length)?),
+                (first, "") => ByteRange::From(parse_pos(first)?),
+                (first, last) => {
+                    let (first, last) = (parse_pos(first)?, parse_pos(last)?);
+                    if first > last {
+                        return Err(format!("range '{spec}' ends before it starts"));
+                    }
+                    ByteRange::Bounded { first, last }
+                }
+            };
+            ranges.push(range);
+        }
+
+        if ranges.is_empty() {
+            return Err("expected at least one byte range".to_string());
+        }
+        Ok(Self(ranges))
+    }
+}
+
+/// One element (hop) of a `Forwarded` header.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ForwardedElement {
+    /// The `for` parameter: the client that made the request to the proxy.
+    pub forwarded_for: Option<String>,
+    /// The `by` parameter: the proxy interface that received the request.
+    pub by: Option<String>,
+    /// The `host` parameter: the original `Host` header.
+    pub host: Option<String>,
+    /// The `proto` parameter: the original scheme.
+    pub proto: Option<String>,
+    /// Any other parameters, with lowercase names.
+    pub extensions: Vec<(String, String)>,
+}
+
+impl ForwardedElement {
+    /// Parses a single `;`-separated element.
+    fn parse(element: &str) -> Result<Self, String> {
+        let mut parsed = Self::default();
+        let mut seen = Vec::new();
+
+        for pair in split_quoted(element, ';')? {
+            let (name, value) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("parameter '{pair}' is missing '='"))?;
+            let name = name.trim().to_ascii_lowercase();
+            if !is_token(&name) {
+                return Err(format!("invalid parameter name in '{pair}'"));
+            }
+            if seen.contains(&name) {
+                return Err(format!("parameter '{name}' appears more than once"));
+            }
+
+            let value = unquote(value.trim())
+                .ok_or_else(|| format!("invalid value for parameter '{name}'"))?;
+            match name.as_str() {
+                "for" => parsed.forwarded_for = Some(value),
+                "by" => parsed.by = Some(value),
+                "host" => parsed.host = Some(value),
+                "proto" => parsed.proto = Some(value),
+                _ => parsed.extensions.push((name.clone(), value)),
+            }
+            seen.push(name);
+        }
+
+        Ok(parsed)
+    }
+}
+
+/// Returns a token or the content of a quoted string, unescaped.
+fn unquote(value: &str) -> Option<String> {
+    let Some(inner) = value.strip_prefix('"') else {
+        return is_token(value).then(|| value.to_string());
+    };
+    let inner = inner.strip_suffix('"')?;
+
+    let mut unescaped = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.push(chars.next()?),
+            '"' => return None,
+            c => unescaped.push(c),
+        }
+    }
+    Some(unescaped)
+}
+
+/// Forwarded header (RFC 7239).
+///
+/// Elements are in header order: the first element was added by the proxy
+/// closest to the client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Forwarded(pub Vec<ForwardedElement>);
+
+impl Forwarded {
+    /// Returns the elements in header order.
+    #[must_use]
+    pub fn elements(&self) -> &[ForwardedElement] {
+        &self.0
+    }
+
+    /// Returns the `for` parameter of the first element, i.e. the original
+    /// client as reported by the first proxy.
+    #[must_use]
+    pub fn client(&self) -> Option<&str> {
+        self.0.first()?.forwarded_for.as_deref()
+    }
+}
+
+impl TypedHeader for Forwarded {
+    const NAME: &'static str = "forwarded";
+    const MULTI_VALUE: bool = true;
+
+    fn parse(value: &str) -> Option<Self> {
+        Self::try_parse(value).ok()
+    }
+
+    fn try_parse(value: &str) -> Result<Self, String> {
+        let elements = split_quoted(value, ',')?
+            .into_iter()
+            .filter(|e| !e.is_empty())
+            .map(ForwardedElement::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if elements.is_empty() {
+            return Err("expected at least one forwarded element".to_string());
+        }
+        Ok(Self(elements))
+    }
+}
+
+typed_header! {
+    /// Idempotency-Key header.
+    ///
+    /// Accepts the key bare or as a quoted string, as in the IETF
+    /// `Idempotency-Key` draft. Keys must be 1 to 255 visible ASCII
+    /// characters.
+    #[derive(PartialEq, Eq, Hash)]
+    name = "idempotency-key",
+    type = pub IdempotencyKey(String),
+    parse = |value| {
+        let value = value.trim();
+        let key = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .unwrap_or(value);
+        if key.is_empty() {
+            return Err("idempotency key must not be empty".to_string());
+        }
+        if key.len() > 255 {
+            return Err("idempotency key must be at most 255 characters".to_string());
+        }
+        if !key.bytes().all(|b| b.is_ascii_graphic() && b != b'"') {
+            return Err("idempotency key must contain only visible ASCII characters".to_string());
+        }
+        Ok(key.to_string())
+    },
+}
+
+/// A language range from an `Accept-Language` header, with its weight.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguageRange {
+    /// The language range, e.g. `en-GB`, `fr` or `*`.
+    pub range: String,
+    /// The `q` weight in thousandths (`1000` when absent, `0` means "not
+    /// acceptable").
+    pub quality: u16,
+}
+
+impl LanguageRange {
+    /// Returns `true` for the `*` range.
+    #[must_use]
+    pub fn is_wildcard(&self) -> bool {
+        self.range == "*"
+    }
+
+    /// Returns `true` if the range matches `tag` (RFC 4647 basic
+    /// filtering): the range equals the tag or is a prefix of it ending at
+    /// a `-`, ignoring case. `*` matches every tag.
+    #[must_use]
+    pub fn matches(&self, tag: &str) -> bool {
+        if self.is_wildcard() {
+            return true;
+        }
+        let range = self.range.as_bytes();
+        let tag = tag.as_bytes();
+        tag.len() >= range.len()
+            && tag[..range.len()].eq_ignore_ascii_case(range)
+            && (tag.len() == range.len() || tag[range.len()] == b'-')
+    }
+}
+
+/// Parses a `q` weight into thousandths.
+fn parse_quality(value: &str) -> Result<u16, String> {
+    let invalid = || format!("invalid quality value '{value}'");
+    let (int, frac) = value.split_once('.').unwrap_or((value, ""));
+    if frac.len() > 3 || !frac.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+
+    let thousandths = frac
+        .bytes()
+        .chain(std::iter::repeat(b'0'))
+        .take(3)
+        .fold(0u16, |acc, b| acc * 10 + u16::from(b - b'0'));
+    match int {
+        "0" => Ok(thousandths),
+        "1" if thousandths == 0 => Ok(1000),
+        _ => Err(invalid()),
+    }
+}
+
+/// Accept-Language header.
+///
+/// Ranges are ordered by descending weight; ranges with equal weight keep
+/// their header order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AcceptLanguage(pub Vec<LanguageRange>);
+
+impl AcceptLanguage {
+    /// Returns the ranges, most preferred first.
+    #[must_use]
+    pub fn ranges(&self) -> &[LanguageRange] {
+        &self.0
+    }
+
+    /// Returns the most preferred acceptable range.
+    #[must_use]
+    pub fn preferred(&self) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|r| r.quality > 0)
+            .map(|r| r.range.as_str())
+    }
+
+    /// Picks the best of the `available` language tags.
+    ///
+    /// Ranges are tried in preference order; for each, the first matching
+    /// available tag is chosen unless a `q=0` range explicitly excludes it.
+    #[must_use]
+    pub fn negotiate<'a>(&self, available: &[&'a str]) -> Option<&'a str> {
+        let excluded = |tag: &str| {
+            self.0
+                .iter()
+                .any(|r| r.quality == 0 && !r.is_wildcard() && r.matches(tag))
+        };
+
+        self.0
+            .iter()
+            .filter(|r| r.quality > 0)
+            .find_map(|r| {
+                available
+                    .iter()
+                    .find(|tag| r.matches(tag) && !excluded(tag))
+            })
+            .copied()
+    }
+}
+
+impl TypedHeader for AcceptLanguage {
+    const NAME: &'static str = "accept-language";
+    const MULTI_VALUE: bool = true;
+
+    fn parse(value: &str) -> Option<Self> {
+        Self::try_parse(value).ok()
+    }
+
+    fn try_parse(value: &str) -> Result<Self, String> {
+        let mut ranges = Vec::new();
+
+        for item in value.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let mut params = item.split(';').map(str::trim);
+            let range = params.next().unwrap_or_default();
+            let valid = range == "*"
+                || range.split('-').all(|part| {
+                    (1..=8).contains(&part.len()) && part.bytes().all(|b| b.is_ascii_alphanumeric())
+                });
+            if !valid {
+                return Err(format!("invalid language range '{range}'"));
+            }
+
+            let mut quality = 1000;
+            for param in params {
+                match param.split_once('=') {
+                    Some((name, q)) if name.trim().eq_ignore_ascii_case("q") => {
+                        quality = parse_quality(q.trim())?;
+                    }
+                    _ => return Err(format!("unexpected parameter '{param}' for '{range}'")),
+                }
+            }
+
+            ranges.push(LanguageRange {
+                range: range.to_string(),
+                quality,
+            });
+        }
+
+        if ranges.is_empty() {
+            return Err("expected at least one language range".to_string());
+        }
+        // Stable, so equal weights keep their header order
+        ranges.sort_by(|a, b| b.quality.cmp(&a.quality));
+        Ok(Self(ranges))
+    }
+}
+
 /// Helper function to extract a header by name.
 ///
 /// # Example
@@ -352,6 +1085,7 @@ pub fn header_opt<'a>(ctx: &'a ExtractionContext, name: &str) -> Option<&'a str>
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::context::ExtractionContextBuilder;
     use archimedes_router::Params;
     use bytes::Bytes;
     use http::{HeaderMap, Method, Uri};
@@ -391,6 +1125,26 @@ mod tests {
         assert_eq!(h.get("missing"), None);
     }
 
+    #[test]
+    fn test_header_lookups_ignore_case() {
+        let ctx = ExtractionContextBuilder::new()
+            .method(Method::GET)
+            .uri(Uri::from_static("/"))
+            .header("X-Tenant-Id", "acme")
+            .header("Content-Type", "application/json")
+            .build();
+
+        let h = Headers::from_request(&ctx).unwrap();
+        assert_eq!(h.get("x-tenant-id"), Some("acme"));
+        assert_eq!(h.get("X-TENANT-ID"), Some("acme"));
+        assert!(h.contains("X-Tenant-Id"));
+
+        assert_eq!(header(&ctx, "X-Tenant-ID").unwrap(), "acme");
+        assert_eq!(header_opt(&ctx, "CONTENT-TYPE"), Some("application/json"));
+        let ExtractTypedHeader(ct) = ExtractTypedHeader::<ContentType>::from_request(&ctx).unwrap();
+        assert!(ct.is_json());
+    }
+
     #[test]
     fn test_typed_header_content_type() {
         let mut headers = HeaderMap::new();
@@ -495,4 +1249,202 @@ mod tests {
         assert_eq!(basic.bearer_token(), None);
         assert_eq!(basic.basic_credentials(), Some("dXNlcjpwYXNz"));
     }
+
+    #[test]
+    fn test_entity_tag_parsing() {
+        let strong = EntityTag::parse("\"v1\"").unwrap();
+        let weak = EntityTag::parse("W/\"v1\"").unwrap();
+        assert!(!strong.is_weak());
+        assert!(weak.is_weak());
+        assert_eq!(weak.tag(), "v1");
+        assert_eq!(weak.to_string(), "W/\"v1\"");
+
+        assert!(strong.weak_eq(&weak));
+        assert!(!strong.strong_eq(&weak));
+        assert!(strong.strong_eq(&EntityTag::strong("v1")));
+
+        assert!(EntityTag::parse("v1").is_err());
+        assert!(EntityTag::parse("w/\"v1\"").is_err());
+        assert!(EntityTag::parse("\"v 1\"").is_err());
+    }
+
+    #[test]
+    fn test_if_match_uses_strong_comparison() {
+        let header = IfMatch::try_parse("W/\"a\", \"b,c\"").unwrap();
+        assert_eq!(
+            header,
+            IfMatch::Tags(vec![EntityTag::weak("a"), EntityTag::strong("b,c")])
+        );
+
+        assert!(header.matches(Some(&EntityTag::strong("b,c"))));
+        assert!(!header.matches(Some(&EntityTag::strong("a"))));
+        assert!(!header.matches(None));
+
+        assert_eq!(IfMatch::try_parse(" * ").unwrap(), IfMatch::Any);
+        assert!(IfMatch::Any.matches(Some(&EntityTag::weak("x"))));
+        assert!(!IfMatch::Any.matches(None));
+    }
+
+    #[test]
+    fn test_if_none_match_uses_weak_comparison() {
+        let header = IfNoneMatch::try_parse("W/\"a\"").unwrap();
+        assert!(header.matches(Some(&EntityTag::strong("a"))));
+        assert!(!header.matches(Some(&EntityTag::strong("b"))));
+
+        assert!(IfNoneMatch::try_parse("").is_err());
+        assert!(IfNoneMatch::try_parse("\"unterminated").is_err());
+    }
+
+    #[test]
+    fn test_multi_value_headers_are_joined() {
+        let mut headers = HeaderMap::new();
+        headers.append("if-none-match", "\"a\"".parse().unwrap());
+        headers.append("if-none-match", "W/\"b\"".parse().unwrap());
+
+        let ctx = make_ctx(headers);
+        let ExtractTypedHeader(header) =
+            ExtractTypedHeader::<IfNoneMatch>::from_request(&ctx).unwrap();
+
+        assert_eq!(
+            header,
+            IfNoneMatch::Tags(vec![EntityTag::strong("a"), EntityTag::weak("b")])
+        );
+    }
+
+    #[test]
+    fn test_range_parsing() {
+        let range = Range::try_parse("bytes=0-99, 200-, -50").unwrap();
+        assert_eq!(
+            range.ranges(),
+            &[
+                ByteRange::Bounded { first: 0, last: 99 },
+                ByteRange::From(200),
+                ByteRange::Suffix(50),
+            ]
+        );
+        assert_eq!(range.satisfiable(150), vec![(0, 99), (100, 149)]);
+        assert_eq!(ByteRange::Suffix(500).resolve(100), Some((0, 99)));
+        assert_eq!(
+            ByteRange::Bounded {
+                first: 50,
+                last: 500
+            }
+            .resolve(100),
+            Some((50, 99))
+        );
+        assert_eq!(ByteRange::Suffix(0).resolve(100), None);
+
+        assert!(Range::try_parse("items=0-1").unwrap_err().contains("unit"));
+        assert!(Range::try_parse("bytes=5-1")
+            .unwrap_err()
+            .contains("ends before"));
+        assert!(Range::try_parse("bytes=-").is_err());
+        assert!(Range::try_parse("bytes=a-1").is_err());
+        assert!(Range::try_parse("bytes=").is_err());
+        assert!(Range::try_parse("0-1").is_err());
+    }
+
+    #[test]
+    fn test_forwarded_parsing() {
+        let header = Forwarded::try_parse(
+            "for=192.0.2.60;proto=http;by=203.0.113.43, For=\"[2001:db8:cafe::17]:4711\";secret=\"a;b\"",
+        )
+        .unwrap();
+
+        assert_eq!(header.elements().len(), 2);
+        assert_eq!(header.client(), Some("192.0.2.60"));
+        assert_eq!(header.elements()[0].proto.as_deref(), Some("http"));
+        assert_eq!(header.elements()[0].by.as_deref(), Some("203.0.113.43"));
+        assert_eq!(
+            header.elements()[1].forwarded_for.as_deref(),
+            Some("[2001:db8:cafe::17]:4711")
+        );
+        assert_eq!(
+            header.elements()[1].extensions,
+            vec![("secret".to_string(), "a;b".to_string())]
+        );
+
+        let escaped = Forwarded::try_parse(r#"for="\"quoted\"""#).unwrap();
+        assert_eq!(escaped.client(), Some("\"quoted\""));
+    }
+
+    #[test]
+    fn test_forwarded_rejects_malformed_pairs() {
+        assert!(Forwarded::try_parse("for")
+            .unwrap_err()
+            .contains("missing '='"));
+        assert!(Forwarded::try_parse("=1.2.3.4").is_err());
+        assert!(Forwarded::try_parse("for=[2001:db8::1]").is_err());
+        assert!(Forwarded::try_parse("for=\"1.2.3.4").is_err());
+        assert!(Forwarded::try_parse("for=1.2.3.4;for=5.6.7.8")
+            .unwrap_err()
+            .contains("more than once"));
+        assert!(Forwarded::try_parse(" , ").is_err());
+    }
+
+    #[test]
+    fn test_idempotency_key() {
+        assert_eq!(
+            IdempotencyKey::try_parse("\"8e03978e-40d5-43e8-bc93-6894a57f9324\"").unwrap(),
+            IdempotencyKey("8e03978e-40d5-43e8-bc93-6894a57f9324".to_string())
+        );
+        assert_eq!(
+            IdempotencyKey::parse("abc").map(|k| k.0),
+            Some("abc".to_string())
+        );
+        assert!(IdempotencyKey::try_parse("\"\"").is_err());
+        assert!(IdempotencyKey::try_parse(&"k".repeat(256)).is_err());
+        assert!(IdempotencyKey::try_parse("has space").is_err());
+    }
+
+    #[test]
+    fn test_typed_header_error_names_header_and_reason() {
+        let mut headers = HeaderMap::new();
+        headers.insert("idempotency-key", "\"\"".parse().unwrap());
+
+        let ctx = make_ctx(headers);
+        let err = ExtractTypedHeader::<IdempotencyKey>::from_request(&ctx).unwrap_err();
+
+        assert_eq!(err.source(), ExtractionSource::Header);
+        assert_eq!(err.field(), Some("idempotency-key"));
+        assert!(err
+            .to_string()
+            .contains("idempotency key must not be empty"));
+    }
+
+    #[test]
+    fn test_accept_language_orders_by_quality() {
+        let header = AcceptLanguage::try_parse("fr;q=0.5, en-GB, de;q=0.8, en;q=0.8").unwrap();
+        let ranges: Vec<&str> = header.ranges().iter().map(|r| r.range.as_str()).collect();
+        assert_eq!(ranges, vec!["en-GB", "de", "en", "fr"]);
+        assert_eq!(header.ranges()[3].quality, 500);
+        assert_eq!(header.preferred(), Some("en-GB"));
+
+        assert_eq!(header.negotiate(&["fr", "en-US"]), Some("en-US"));
+        assert_eq!(header.negotiate(&["fr"]), Some("fr"));
+        assert_eq!(header.negotiate(&["es"]), None);
+    }
+
+    #[test]
+    fn test_accept_language_wildcard() {
+        let header = AcceptLanguage::try_parse("de, *;q=0.1, en;q=0").unwrap();
+        assert!(header.ranges()[1].is_wildcard());
+
+        assert_eq!(header.negotiate(&["de-AT"]), Some("de-AT"));
+        assert_eq!(header.negotiate(&["en", "es"]), Some("es"));
+        assert_eq!(header.negotiate(&["en-US"]), None);
+    }
+
+    #[test]
+    fn test_accept_language_rejects_invalid_values() {
+        assert!(AcceptLanguage::try_parse("en;q=1.5").is_err());
+        assert!(AcceptLanguage::try_parse("en;q=0.1234").is_err());
+        assert!(AcceptLanguage::try_parse("en;level=1").is_err());
+        assert!(AcceptLanguage::try_parse("toolongsubtag").is_err());
+        assert!(AcceptLanguage::try_parse("").is_err());
+        assert_eq!(
+            AcceptLanguage::try_parse("en;q=1.").unwrap().ranges()[0].quality,
+            1000
+        );
+    }
 }
@@ -2,8 +2,14 @@
 //!
 //! The [`Json`] extractor deserializes JSON request bodies into typed structs.
 
+use crate::response::JsonResponse;
 use crate::{ExtractionContext, ExtractionError, ExtractionSource, FromRequest};
+use archimedes_core::IntoResponse;
+use bytes::Bytes;
+use http::Response;
 use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::error::Category;
 use std::ops::Deref;
 
 /// Default maximum body size for JSON extraction (1 MB).
@@ -99,6 +105,16 @@ impl<T> Deref for Json<T> {
     }
 }
 
+/// Returned from a handler, `Json<T>` is serialized as an `application/json`
+/// response with [`JsonResponse`] and the global [`JsonConfig`].
+///
+/// [`JsonConfig`]: crate::response::JsonConfig
+impl<T: Serialize> IntoResponse for Json<T> {
+    fn into_response(self) -> Response<Bytes> {
+        JsonResponse::new(self.0).into_response()
+    }
+}
+
 impl<T: DeserializeOwned> FromRequest for Json<T> {
     fn from_request(ctx: &ExtractionContext) -> Result<Self, ExtractionError> {
         let body = ctx.body();
@@ -120,9 +136,7 @@ impl<T: DeserializeOwned> FromRequest for Json<T> {
         }
 
         // Deserialize JSON
-        let value: T = serde_json::from_slice(body).map_err(|e| {
-            ExtractionError::deserialization_failed(ExtractionSource::Body, e.to_string())
-        })?;
+        let value: T = from_json(body)?;
 
         Ok(Json(value))
     }
@@ -186,14 +200,35 @@ impl<T: DeserializeOwned, const LIMIT: usize> FromRequest for JsonWithLimit<T, L
         }
 
         // Deserialize JSON
-        let value: T = serde_json::from_slice(body).map_err(|e| {
-            ExtractionError::deserialization_failed(ExtractionSource::Body, e.to_string())
-        })?;
+        let value: T = from_json(body)?;
 
         Ok(JsonWithLimit(value))
     }
 }
 
+/// Deserializes a JSON body, naming the field that failed.
+///
+/// Syntax errors fail the body as a whole; a body that parses but does not
+/// match `T` reports the path to the offending value.
+pub(crate) fn from_json<T: DeserializeOwned>(body: &[u8]) -> Result<T, ExtractionError> {
+    let mut deserializer = serde_json::Deserializer::from_slice(body);
+    let value = serde_path_to_error::deserialize(&mut deserializer).map_err(|e| {
+        let inner = e.inner();
+        if inner.classify() == Category::Data {
+            // The position is noise once the field is named
+            let details = inner.to_string();
+            let details = details.split(" at line ").next().unwrap_or_default();
+            ExtractionError::from_path_error(ExtractionSource::Body, e.path(), details)
+        } else {
+            ExtractionError::deserialization_failed(ExtractionSource::Body, inner.to_string())
+        }
+    })?;
+    deserializer.end().map_err(|e| {
+        ExtractionError::deserialization_failed(ExtractionSource::Body, e.to_string())
+    })?;
+    Ok(value)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -295,6 +330,9 @@ mod tests {
         let result = Json::<CreateUser>::from_request(&ctx);
 
         assert!(result.is_err());
+        let err = result.unwrap_err();
+        assert_eq!(err.field(), Some("email"));
+        assert!(err.is_body_schema_issue());
     }
 
     #[test]
@@ -305,6 +343,29 @@ mod tests {
         let result = Json::<CreateUser>::from_request(&ctx);
 
         assert!(result.is_err());
+        let issue = &result.unwrap_err().issues()[0];
+        assert_eq!(issue.field, "name");
+        assert_eq!(issue.expected, "a string");
+        assert!(!issue.message.contains("line"));
+    }
+
+    #[test]
+    fn test_nested_field_path() {
+        let body = br#"{"user": {"name": "Alice"}, "tags": ["a", 2]}"#;
+        let err = Json::<NestedData>::from_request(&make_ctx(body)).unwrap_err();
+        assert_eq!(err.field(), Some("user.email"));
+
+        let body = br#"{"user": {"name": "Alice", "email": "a@b.c"}, "tags": ["a", 2]}"#;
+        let err = Json::<NestedData>::from_request(&make_ctx(body)).unwrap_err();
+        assert_eq!(err.field(), Some("tags[1]"));
+    }
+
+    #[test]
+    fn test_syntax_error_has_no_field() {
+        let body = br#"{"name": "Alice"} trailing"#;
+        let err = Json::<CreateUser>::from_request(&make_ctx(body)).unwrap_err();
+        assert_eq!(err.field(), None);
+        assert!(!err.is_body_schema_issue());
     }
 
     #[test]
@@ -374,4 +435,28 @@ mod tests {
         assert!(result.is_some());
         assert_eq!(result.unwrap().name, "Alice");
     }
+
+    #[test]
+    fn test_json_into_response() {
+        #[derive(Serialize)]
+        struct Created {
+            id: u64,
+        }
+
+        let response = Json(Created { id: 7 }).into_response();
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert_eq!(
+            response.headers()[http::header::CONTENT_TYPE],
+            "application/json"
+        );
+        assert_eq!(response.body().as_ref(), br#"{"id":7}"#);
+
+        let response = (http::StatusCode::CREATED, Json(Created { id: 8 })).into_response();
+        assert_eq!(response.status(), http::StatusCode::CREATED);
+        assert_eq!(
+            response.headers()[http::header::CONTENT_TYPE],
+            "application/json"
+        );
+        assert_eq!(response.body().as_ref(), br#"{"id":8}"#);
+    }
 }
@@ -13,12 +13,15 @@
 //! | Extractor | Source | Description |
 //! |-----------|--------|-------------|
 //! | [`Path<T>`] | URL path | Extract typed parameters from path segments |
+//! | [`RawPath`] | URL path | Access matched path parameters as strings |
 //! | [`Query<T>`] | Query string | Parse URL query parameters |
 //! | [`Json<T>`] | Request body | Deserialize JSON body |
 //! | [`Form<T>`] | Request body | Parse URL-encoded form data |
 //! | [`Header<T>`] | Headers | Extract a typed header value |
 //! | [`Headers`] | Headers | Access all request headers |
 //! | [`RawBody`] | Request body | Access raw request bytes |
+//! | [`BodyStream`] | Request body | Read the body incrementally, e.g. as NDJSON |
+//! | [`Precondition`] | Headers | Check `If-Match` and friends against the current resource |
 //!
 //! ## Example
 //!
@@ -80,7 +83,9 @@
 //!
 //! - Source location (path, query, body, header)
 //! - Detailed error message
-//! - Automatic conversion to HTTP 400/422 responses
+//! - A [`FieldIssue`] per failing field, with its full path
+//! - Automatic conversion to HTTP 400/422 responses, configurable with
+//!   [`ExtractionStatusMap`]
 //!
 //! ```rust
 //! use archimedes_extract::{ExtractionError, ExtractionSource};
@@ -100,6 +105,7 @@
 #![forbid(unsafe_code)]
 
 mod body;
+pub mod body_stream;
 mod context;
 pub mod cookie;
 mod error;
@@ -109,24 +115,34 @@ mod header;
 mod inject;
 mod json;
 pub mod multipart;
+pub mod naming;
 mod path;
+pub mod precondition;
 mod query;
 pub mod response;
+pub mod streaming;
 
 // Re-export main types
 pub use body::{BodyString, RawBody};
+pub use body_stream::{BodyStream, NdjsonError, NdjsonStream};
 pub use context::ExtractionContext;
 pub use cookie::{Cookie, Cookies, SameSite, SetCookie};
-pub use error::{ExtractionError, ExtractionSource};
+pub use error::{ExtractionError, ExtractionSource, ExtractionStatusMap, FieldIssue};
 pub use extractor::FromRequest;
-pub use form::{Form, FormWithLimit};
+pub use form::{Form, FormConfig, FormWithLimit};
 pub use header::{header, header_opt, ExtractTypedHeader, Header, Headers, TypedHeader};
 pub use header::{Accept, Authorization, ContentType, UserAgent};
+pub use header::{
+    AcceptLanguage, ByteRange, EntityTag, Forwarded, ForwardedElement, IdempotencyKey, IfMatch,
+    IfNoneMatch, LanguageRange, Range,
+};
 pub use inject::Inject;
 pub use json::{Json, JsonWithLimit};
-pub use multipart::{Field, Multipart, MultipartConfig, UploadedFile};
-pub use path::{path_param, Path};
+pub use multipart::{Field, Multipart, MultipartConfig, SavedFile, UploadLimits, UploadedFile};
+pub use path::{path_param, Path, RawPath};
+pub use precondition::{Precondition, PreconditionOutcome};
 pub use query::{Query, RawQuery};
+pub use streaming::{StreamError, StreamWriter, StreamingBody, StreamingResponse};
 
 // Re-export useful types from dependencies
 pub use archimedes_router::Params;
@@ -20,10 +20,29 @@
 //!     Ok(Response::no_content())
 //! }
 //! ```
+//!
+//! # Saving Uploads to Disk
+//!
+//! [`Multipart::save_to_dir`] streams each file to its own temporary file
+//! instead of holding it in memory, enforcing [`UploadLimits`] as it goes.
+//! Combined with [`Multipart::from_stream`], large uploads never have to fit
+//! in memory:
+//!
+//! ```rust,ignore
+//! let limits = UploadLimits::new().max_file_size(100 * 1024 * 1024).max_files(4);
+//! for file in multipart.save_to_dir("/var/tmp/uploads", &limits).await? {
+//!     println!("{:?} saved to {}", file.file_name, file.path.display());
+//! }
+//! ```
 
 use bytes::Bytes;
+use futures_core::Stream;
 use http::{header, HeaderMap};
 use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::io::AsyncWriteExt;
 
 use crate::{ExtractionError, ExtractionSource};
 
@@ -118,24 +137,7 @@ impl Multipart {
         body: Bytes,
         config: MultipartConfig,
     ) -> Result<Self, ExtractionError> {
-        // Extract boundary from Content-Type header
-        let content_type = headers
-            .get(header::CONTENT_TYPE)
-            .ok_or_else(|| {
-                ExtractionError::missing_content_type("multipart/form-data")
-            })?
-            .to_str()
-            .map_err(|_| {
-                ExtractionError::invalid_content_type(
-                    "invalid UTF-8 in Content-Type header",
-                )
-            })?;
-
-        let boundary = multer::parse_boundary(content_type).map_err(|_| {
-            ExtractionError::invalid_content_type(
-                "missing or invalid boundary in multipart Content-Type",
-            )
-        })?;
+        let boundary = Self::boundary(headers)?;
 
         // Check body size
         if body.len() > config.max_body_size {
@@ -159,6 +161,57 @@ impl Multipart {
         })
     }
 
+    /// Create a Multipart extractor over a streamed body, so fields can be
+    /// read as the body arrives instead of after buffering it.
+    ///
+    /// `config.max_body_size` is enforced while the stream is read.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the Content-Type header is missing or invalid.
+    pub fn from_stream<S, E>(
+        headers: &HeaderMap,
+        stream: S,
+        config: MultipartConfig,
+    ) -> Result<Self, ExtractionError>
+    where
+        S: Stream<Item = Result<Bytes, E>> + Send + 'static,
+        E: Into<Box<dyn std::error::Error + Send + Sync>> + 'static,
+    {
+        let boundary = Self::boundary(headers)?;
+        let constraints = multer::Constraints::new()
+            .size_limit(multer::SizeLimit::new().whole_stream(config.max_body_size as u64));
+        let inner = multer::Multipart::with_constraints(stream, boundary, constraints);
+
+        Ok(Self {
+            inner,
+            config,
+            field_count: 0,
+        })
+    }
+
+    /// Extract the multipart boundary from the Content-Type header.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the Content-Type header is missing or has no
+    /// valid boundary.
+    pub fn boundary(headers: &HeaderMap) -> Result<String, ExtractionError> {
+        let content_type = headers
+            .get(header::CONTENT_TYPE)
+            .ok_or_else(|| ExtractionError::missing_content_type("multipart/form-data"))?
+            .to_str()
+            .map_err(|_| {
+                ExtractionError::invalid_content_type("invalid UTF-8 in Content-Type header")
+            })?;
+
+        multer::parse_boundary(content_type).map_err(|_| {
+            ExtractionError::invalid_content_type(
+                "missing or invalid boundary in multipart Content-Type",
+            )
+        })
+    }
+
     /// Create with default configuration.
     pub fn from_request_default(
         headers: &HeaderMap,
@@ -220,6 +273,125 @@ impl Multipart {
 
         Ok(files)
     }
+
+    /// Stream every file field to its own temporary file in `dir`.
+    ///
+    /// Files are written chunk by chunk and non-file fields are skipped.
+    /// The limits are enforced while streaming: as soon as a file grows past
+    /// `max_file_size`, the files together grow past `max_total_size`, or
+    /// there are more than `max_files` files, the upload is aborted and every
+    /// file this call wrote, including the partial one, is removed.
+    ///
+    /// The saved files belong to the caller, who should move or delete them
+    /// once done.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a limit is exceeded, the multipart data is
+    /// malformed, or a file cannot be written.
+    pub async fn save_to_dir(
+        &mut self,
+        dir: impl AsRef<Path>,
+        limits: &UploadLimits,
+    ) -> Result<Vec<SavedFile>, ExtractionError> {
+        let mut saved = Vec::new();
+        if let Err(e) = self.save_files(dir.as_ref(), limits, &mut saved).await {
+            for file in &saved {
+                let _ = tokio::fs::remove_file(&file.path).await;
+            }
+            return Err(e);
+        }
+        Ok(saved)
+    }
+
+    /// Write the file fields to `dir`, recording each file in `saved` as soon
+    /// as it is created so it can be removed on failure.
+    async fn save_files(
+        &mut self,
+        dir: &Path,
+        limits: &UploadLimits,
+        saved: &mut Vec<SavedFile>,
+    ) -> Result<(), ExtractionError> {
+        let mut total_size = 0;
+
+        while let Some(mut field) = self.next_field().await? {
+            if field.file_name().is_none() {
+                continue;
+            }
+            if saved.len() >= limits.max_files {
+                return Err(ExtractionError::validation_failed(
+                    ExtractionSource::Body,
+                    "multipart",
+                    format!("too many files (max {})", limits.max_files),
+                ));
+            }
+
+            let path = temp_upload_path(dir);
+            let mut file = tokio::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+                .await
+                .map_err(|e| write_error(&path, &e))?;
+            let name = field.name().map(String::from);
+            saved.push(SavedFile {
+                name: name.clone(),
+                file_name: field.file_name().map(String::from),
+                content_type: field.content_type().map(ToString::to_string),
+                size: 0,
+                path: path.clone(),
+            });
+
+            let mut size = 0;
+            while let Some(chunk) = field.chunk().await? {
+                size += chunk.len();
+                total_size += chunk.len();
+                if size > limits.max_file_size {
+                    return Err(ExtractionError::field_too_large(
+                        ExtractionSource::Body,
+                        name.unwrap_or_default(),
+                        limits.max_file_size,
+                        size,
+                    ));
+                }
+                if total_size > limits.max_total_size {
+                    return Err(ExtractionError::payload_too_large(
+                        limits.max_total_size,
+                        total_size,
+                    ));
+                }
+                file.write_all(&chunk)
+                    .await
+                    .map_err(|e| write_error(&path, &e))?;
+            }
+            file.flush().await.map_err(|e| write_error(&path, &e))?;
+
+            if let Some(saved_file) = saved.last_mut() {
+                saved_file.size = size;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Build a path for a new temporary upload file in `dir`.
+fn temp_upload_path(dir: &Path) -> PathBuf {
+    static NEXT_UPLOAD: AtomicU64 = AtomicU64::new(0);
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.subsec_nanos());
+    let n = NEXT_UPLOAD.fetch_add(1, Ordering::Relaxed);
+    dir.join(format!("upload-{}-{nanos}-{n}.tmp", std::process::id()))
+}
+
+fn write_error(path: &Path, e: &io::Error) -> ExtractionError {
+    ExtractionError::custom(
+        ExtractionSource::Body,
+        "multipart",
+        format!("failed to write upload to {}: {e}", path.display()),
+    )
 }
 
 impl std::fmt::Debug for Multipart {
@@ -267,6 +439,13 @@ impl Field {
         self.inner.content_type()
     }
 
+    /// Get the headers of this field's part, such as
+    /// `Content-Disposition` and `Content-Type`.
+    #[must_use]
+    pub fn headers(&self) -> &HeaderMap {
+        self.inner.headers()
+    }
+
     /// Read the entire field as bytes.
     ///
     /// # Errors
@@ -306,6 +485,24 @@ impl Field {
         })
     }
 
+    /// Read the next chunk of the field, or `None` once it is exhausted.
+    ///
+    /// Reading chunk by chunk streams the field without holding it in
+    /// memory; the field size limit is left to the caller.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the multipart data is malformed or the body
+    /// exceeds its size limit.
+    pub async fn chunk(&mut self) -> Result<Option<Bytes>, ExtractionError> {
+        self.inner.chunk().await.map_err(|e| {
+            ExtractionError::deserialization_failed(
+                ExtractionSource::Body,
+                format!("failed to read field: {e}"),
+            )
+        })
+    }
+
     /// Convert this field into an [`UploadedFile`].
     ///
     /// # Errors
@@ -451,6 +648,71 @@ impl UploadedFile {
     }
 }
 
+/// Limits for [`Multipart::save_to_dir`].
+#[derive(Debug, Clone)]
+pub struct UploadLimits {
+    /// Maximum size of a single file in bytes.
+    pub max_file_size: usize,
+    /// Maximum size of all files together in bytes.
+    pub max_total_size: usize,
+    /// Maximum number of files.
+    pub max_files: usize,
+}
+
+impl Default for UploadLimits {
+    fn default() -> Self {
+        Self {
+            max_file_size: DEFAULT_MAX_FIELD_SIZE,
+            max_total_size: DEFAULT_MAX_BODY_SIZE,
+            max_files: 10,
+        }
+    }
+}
+
+impl UploadLimits {
+    /// Create limits with default values.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum size of a single file.
+    #[must_use]
+    pub fn max_file_size(mut self, size: usize) -> Self {
+        self.max_file_size = size;
+        self
+    }
+
+    /// Set the maximum size of all files together.
+    #[must_use]
+    pub fn max_total_size(mut self, size: usize) -> Self {
+        self.max_total_size = size;
+        self
+    }
+
+    /// Set the maximum number of files.
+    #[must_use]
+    pub fn max_files(mut self, count: usize) -> Self {
+        self.max_files = count;
+        self
+    }
+}
+
+/// A file that [`Multipart::save_to_dir`] wrote to disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SavedFile {
+    /// The form field name.
+    pub name: Option<String>,
+    /// The original file name from the client.
+    pub file_name: Option<String>,
+    /// The MIME type of the file.
+    pub content_type: Option<String>,
+    /// The file size in bytes.
+    pub size: usize,
+    /// Where the file was written.
+    pub path: PathBuf,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -728,4 +990,108 @@ mod tests {
         assert!(empty.is_empty());
         assert!(!non_empty.is_empty());
     }
+
+    fn multipart_headers(boundary: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={boundary}")
+                .parse()
+                .unwrap(),
+        );
+        headers
+    }
+
+    /// A fresh directory for a test's uploads.
+    fn upload_dir(test: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("archimedes-uploads-{}-{test}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn dir_entries(dir: &Path) -> usize {
+        std::fs::read_dir(dir).unwrap().count()
+    }
+
+    #[tokio::test]
+    async fn test_multipart_save_to_dir() {
+        let boundary = "----boundary";
+        let body = create_multipart_body(
+            boundary,
+            &[
+                ("title", "text/plain", None, b"holiday"),
+                ("photo", "image/png", Some("beach.png"), b"PNG_DATA"),
+                ("notes", "text/plain", Some("notes.txt"), b"sunny all week"),
+            ],
+        );
+        // Deliver the body in small chunks, splitting files across them
+        let chunks: Vec<Result<Bytes, io::Error>> = body
+            .chunks(7)
+            .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+            .collect();
+        let stream = futures_util::stream::iter(chunks);
+
+        let dir = upload_dir("save");
+        let limits = UploadLimits::new().max_file_size(16).max_files(2);
+        let mut multipart =
+            Multipart::from_stream(&multipart_headers(boundary), stream, MultipartConfig::new())
+                .unwrap();
+        let files = multipart.save_to_dir(&dir, &limits).await.unwrap();
+
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].name.as_deref(), Some("photo"));
+        assert_eq!(files[0].file_name.as_deref(), Some("beach.png"));
+        assert_eq!(files[0].content_type.as_deref(), Some("image/png"));
+        assert_eq!(files[0].size, 8);
+        assert_eq!(std::fs::read(&files[0].path).unwrap(), b"PNG_DATA");
+        assert_eq!(files[1].file_name.as_deref(), Some("notes.txt"));
+        assert_eq!(files[1].size, 14);
+        assert_eq!(std::fs::read(&files[1].path).unwrap(), b"sunny all week");
+        assert!(files.iter().all(|file| file.path.starts_with(&dir)));
+        assert_eq!(dir_entries(&dir), 2);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_multipart_save_to_dir_cleans_up_on_limit() {
+        let boundary = "----boundary";
+        let body = create_multipart_body(
+            boundary,
+            &[
+                ("small", "text/plain", Some("a.txt"), b"fits"),
+                ("large", "text/plain", Some("b.txt"), b"too large to fit"),
+            ],
+        );
+        let headers = multipart_headers(boundary);
+        let dir = upload_dir("cleanup");
+
+        // The second file exceeds the per-file cap
+        let limits = UploadLimits::new().max_file_size(10);
+        let mut multipart =
+            Multipart::from_request_default(&headers, Bytes::from(body.clone())).unwrap();
+        let err = multipart.save_to_dir(&dir, &limits).await.unwrap_err();
+        assert_eq!(err.status_code(), http::StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(err.field(), Some("large"));
+        assert_eq!(dir_entries(&dir), 0);
+
+        // Together the files exceed the total cap
+        let limits = UploadLimits::new().max_total_size(15);
+        let mut multipart =
+            Multipart::from_request_default(&headers, Bytes::from(body.clone())).unwrap();
+        let err = multipart.save_to_dir(&dir, &limits).await.unwrap_err();
+        assert_eq!(err.status_code(), http::StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(dir_entries(&dir), 0);
+
+        // There are more files than allowed
+        let limits = UploadLimits::new().max_files(1);
+        let mut multipart = Multipart::from_request_default(&headers, Bytes::from(body)).unwrap();
+        let err = multipart.save_to_dir(&dir, &limits).await.unwrap_err();
+        assert_eq!(err.status_code(), http::StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(dir_entries(&dir), 0);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
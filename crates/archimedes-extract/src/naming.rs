@@ -0,0 +1,166 @@
+//! Name matching between contract parameters and handler fields.
+//!
+//! The contract is the source of truth for parameter names, so a route
+//! declared as `/users/{userId}` produces a path parameter named `userId`.
+//! Rust handler structs conventionally use `snake_case` fields. The
+//! extractors bridge the two: a parameter binds to the field with the same
+//! name if there is one, and otherwise to the field whose name differs
+//! only by case or naming style (see [`canonical_name`]). An explicit
+//! `#[serde(rename = "userId")]` always wins, since it produces an exact
+//! match.
+//!
+//! [`struct_fields`] reads the field names a type deserializes from, which
+//! lets the server warn at startup about parameters that only bind through
+//! this tolerance.
+
+use std::cell::Cell;
+
+use serde::de::{self, DeserializeOwned, Visitor};
+
+pub use archimedes_router::canonical_name;
+
+/// Returns the field names `T` deserializes from.
+///
+/// Names reflect `#[serde(rename)]` and `#[serde(rename_all)]`. Types that
+/// are not plain structs, such as maps, primitives or structs with
+/// `#[serde(flatten)]` fields, report no fields.
+///
+/// # Example
+///
+/// ```rust
+/// use archimedes_extract::naming::struct_fields;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct UserPath {
+///     user_id: u64,
+///     #[serde(rename = "postId")]
+///     post: u64,
+/// }
+///
+/// assert_eq!(struct_fields::<UserPath>(), &["user_id", "postId"]);
+/// assert!(struct_fields::<u64>().is_empty());
+/// ```
+#[must_use]
+pub fn struct_fields<T: DeserializeOwned>() -> &'static [&'static str] {
+    let fields = Cell::new(&[][..]);
+    // The probe always fails once it has seen the field list
+    let _ = T::deserialize(FieldProbe { fields: &fields });
+    fields.get()
+}
+
+/// Returns the field a parameter binds to.
+///
+/// An exact match wins; otherwise the first field with the same canonical
+/// name. Returns `name` unchanged if no field matches.
+#[must_use]
+pub fn match_field<'a>(fields: &[&'static str], name: &'a str) -> &'a str {
+    if fields.iter().any(|field| *field == name) {
+        return name;
+    }
+    let canonical = canonical_name(name);
+    fields
+        .iter()
+        .find(|field| canonical_name(field) == canonical)
+        .map_or(name, |field| *field)
+}
+
+/// Returns parameters that only bind to a field by tolerance.
+///
+/// Each pair is a parameter name and the field it binds to, where the two
+/// differ by case or naming style. Parameters with an exact field match,
+/// or with no match at all, are not reported.
+///
+/// # Example
+///
+/// ```rust
+/// use archimedes_extract::naming::style_mismatches;
+///
+/// let mismatches = style_mismatches(["userId", "postId", "slug"], &["user_id", "postId"]);
+/// assert_eq!(mismatches, vec![("userId".to_string(), "user_id")]);
+/// ```
+#[must_use]
+pub fn style_mismatches<'a>(
+    params: impl IntoIterator<Item = &'a str>,
+    fields: &[&'static str],
+) -> Vec<(String, &'static str)> {
+    params
+        .into_iter()
+        .filter(|param| !fields.iter().any(|field| field == param))
+        .filter_map(|param| {
+            let canonical = canonical_name(param);
+            fields
+                .iter()
+                .find(|field| canonical_name(field) == canonical)
+                .map(|field| (param.to_string(), *field))
+        })
+        .collect()
+}
+
+/// Deserializer that records the field list of a struct and then fails.
+struct FieldProbe<'a> {
+    fields: &'a Cell<&'static [&'static str]>,
+}
+
+impl<'de> de::Deserializer<'de> for FieldProbe<'_> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("not a struct"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.fields.set(fields);
+        Err(de::Error::custom("field probe"))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::collections::HashMap;
+
+    #[derive(Deserialize)]
+    #[allow(dead_code)]
+    struct Plain {
+        user_id: u64,
+        post_id: u64,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    #[allow(dead_code)]
+    struct Camel {
+        user_id: u64,
+    }
+
+    #[test]
+    fn test_struct_fields() {
+        assert_eq!(struct_fields::<Plain>(), &["user_id", "post_id"]);
+        assert_eq!(struct_fields::<Camel>(), &["userId"]);
+        assert!(struct_fields::<HashMap<String, String>>().is_empty());
+        assert!(struct_fields::<String>().is_empty());
+    }
+
+    #[test]
+    fn test_match_field() {
+        let fields = &["user_id", "postId"];
+        assert_eq!(match_field(fields, "userId"), "user_id");
+        assert_eq!(match_field(fields, "USER_ID"), "user_id");
+        assert_eq!(match_field(fields, "postId"), "postId");
+        assert_eq!(match_field(fields, "post_id"), "postId");
+        assert_eq!(match_field(fields, "slug"), "slug");
+    }
+}
@@ -2,7 +2,10 @@
 //!
 //! The [`Path`] extractor deserializes URL path parameters into a typed struct.
 
+use crate::error::from_urlencoded;
+use crate::naming::{match_field, struct_fields};
 use crate::{ExtractionContext, ExtractionError, ExtractionSource, FromRequest};
+use archimedes_router::Params;
 use serde::de::DeserializeOwned;
 use std::ops::Deref;
 
@@ -12,6 +15,11 @@ use std::ops::Deref;
 /// implement [`serde::Deserialize`]. Path parameters are extracted from
 /// segments like `/users/{user_id}/posts/{post_id}`.
 ///
+/// Parameter names come from the contract. A parameter binds to the field
+/// of the same name, or failing that to the field whose name differs only
+/// by case or naming style, so `{userId}` fills a `user_id` field. Use
+/// `#[serde(rename = "...")]` to bind a field explicitly.
+///
 /// # Example
 ///
 /// ```rust
@@ -130,26 +138,91 @@ impl<T: DeserializeOwned> FromRequest for Path<T> {
 
         // Convert path params to a URL-encoded query string format
         // This allows serde_urlencoded to handle type coercion (string -> int, etc.)
+        // Keys are matched to field names first, so contract casing doesn't matter
+        let fields = struct_fields::<T>();
         let query_string: String = ctx
             .path_params()
             .iter()
-            .map(|(k, v)| format!("{}={}", k, v))
+            .map(|(k, v)| format!("{}={}", match_field(fields, k), v))
             .collect::<Vec<_>>()
             .join("&");
 
         // Deserialize using serde_urlencoded which handles string-to-type conversion
-        let value: T = serde_urlencoded::from_str(&query_string).map_err(|e| {
-            ExtractionError::deserialization_failed(ExtractionSource::Path, e.to_string())
-        })?;
+        let value: T = from_urlencoded(ExtractionSource::Path, &query_string)?;
 
         Ok(Path(value))
     }
 }
 
+/// Raw path parameter access.
+///
+/// Use this when you need the matched parameters as they are, without
+/// deserialization, for example in generic handlers that do not know the
+/// route. Names are as declared in the route pattern. Unlike [`Path`], it
+/// also succeeds for routes without parameters.
+///
+/// # Example
+///
+/// ```rust
+/// use archimedes_extract::{RawPath, FromRequest, ExtractionContext};
+/// use archimedes_router::Params;
+/// use http::{Method, Uri, HeaderMap};
+/// use bytes::Bytes;
+///
+/// let mut params = Params::new();
+/// params.push("user_id", "42");
+/// params.push("post_id", "abc");
+///
+/// let ctx = ExtractionContext::new(
+///     Method::GET,
+///     Uri::from_static("/users/42/posts/abc"),
+///     HeaderMap::new(),
+///     Bytes::new(),
+///     params,
+/// );
+///
+/// let RawPath(params) = RawPath::from_request(&ctx).unwrap();
+/// assert_eq!(params.get("post_id"), Some("abc"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawPath(pub Params);
+
+impl RawPath {
+    /// Parses a single parameter, like [`path_param`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the parameter is missing or cannot be parsed.
+    pub fn param<T: std::str::FromStr>(&self, name: &str) -> Result<T, ExtractionError> {
+        parse_param(&self.0, name)
+    }
+
+    /// Consumes the `RawPath` and returns the parameters.
+    #[must_use]
+    pub fn into_inner(self) -> Params {
+        self.0
+    }
+}
+
+impl Deref for RawPath {
+    type Target = Params;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl FromRequest for RawPath {
+    fn from_request(ctx: &ExtractionContext) -> Result<Self, ExtractionError> {
+        Ok(RawPath(ctx.path_params().clone()))
+    }
+}
+
 /// Extract a single path parameter by name.
 ///
 /// This is a convenience function for extracting a single parameter
-/// without needing to define a struct.
+/// without needing to define a struct. The name is matched exactly first,
+/// then ignoring case and naming style.
 ///
 /// # Example
 ///
@@ -181,9 +254,12 @@ pub fn path_param<T: std::str::FromStr>(
     ctx: &ExtractionContext,
     name: &str,
 ) -> Result<T, ExtractionError> {
-    let value = ctx
-        .path_params()
-        .get(name)
+    parse_param(ctx.path_params(), name)
+}
+
+fn parse_param<T: std::str::FromStr>(params: &Params, name: &str) -> Result<T, ExtractionError> {
+    let value = params
+        .find(name)
         .ok_or_else(|| ExtractionError::missing(ExtractionSource::Path, name))?;
 
     value.parse().map_err(|_| {
@@ -198,7 +274,6 @@ pub fn path_param<T: std::str::FromStr>(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use archimedes_router::Params;
     use bytes::Bytes;
     use http::{HeaderMap, Method, Uri};
     use serde::Deserialize;
@@ -221,6 +296,13 @@ mod tests {
         version: Option<String>,
     }
 
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct RenamedPath {
+        #[serde(rename = "userId")]
+        id: u64,
+        user_id: Option<u64>,
+    }
+
     fn make_ctx(params: Params) -> ExtractionContext {
         ExtractionContext::new(
             Method::GET,
@@ -255,6 +337,32 @@ mod tests {
         assert_eq!(path.post_id, "abc-123");
     }
 
+    #[test]
+    fn test_path_param_names_from_contract() {
+        // Contract declares /users/{userId}/posts/{PostID}
+        let mut params = Params::new();
+        params.push("userId", "42");
+        params.push("PostID", "abc");
+
+        let ctx = make_ctx(params);
+        let Path(path) = Path::<PostPath>::from_request(&ctx).unwrap();
+
+        assert_eq!(path.user_id, 42);
+        assert_eq!(path.post_id, "abc");
+    }
+
+    #[test]
+    fn test_path_param_serde_rename_wins() {
+        let mut params = Params::new();
+        params.push("userId", "42");
+
+        let ctx = make_ctx(params);
+        let Path(path) = Path::<RenamedPath>::from_request(&ctx).unwrap();
+
+        assert_eq!(path.id, 42);
+        assert_eq!(path.user_id, None);
+    }
+
     #[test]
     fn test_optional_path_param() {
         let mut params = Params::new();
@@ -300,6 +408,7 @@ mod tests {
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert_eq!(err.source(), ExtractionSource::Path);
+        assert_eq!(err.field(), Some("user_id"));
     }
 
     #[test]
@@ -339,6 +448,9 @@ mod tests {
 
         let name: String = path_param(&ctx, "name").unwrap();
         assert_eq!(name, "test");
+
+        let id: u64 = path_param(&ctx, "ID").unwrap();
+        assert_eq!(id, 42);
     }
 
     #[test]
@@ -360,4 +472,57 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_raw_path_multiple_params() {
+        let mut params = Params::new();
+        params.push("userId", "42");
+        params.push("post_id", "abc-123");
+
+        let ctx = make_ctx(params.clone());
+        let RawPath(raw) = RawPath::from_request(&ctx).unwrap();
+
+        assert_eq!(raw, params);
+        let pairs: Vec<_> = raw.iter().collect();
+        assert_eq!(pairs, vec![("userId", "42"), ("post_id", "abc-123")]);
+    }
+
+    #[test]
+    fn test_raw_path_alongside_path() {
+        let mut params = Params::new();
+        params.push("user_id", "42");
+        params.push("post_id", "abc");
+
+        let ctx = make_ctx(params);
+        let Path(path) = Path::<PostPath>::from_request(&ctx).unwrap();
+        let raw = RawPath::from_request(&ctx).unwrap();
+
+        assert_eq!(path.user_id, 42);
+        assert_eq!(raw.get("user_id"), Some("42"));
+        assert_eq!(raw.param::<u64>("user_id").unwrap(), path.user_id);
+        assert_eq!(raw.param::<String>("post_id").unwrap(), path.post_id);
+    }
+
+    #[test]
+    fn test_raw_path_single_param() {
+        let mut params = Params::new();
+        params.push("id", "7");
+
+        let ctx = make_ctx(params);
+        let raw = RawPath::from_request(&ctx).unwrap();
+
+        assert_eq!(raw.param::<u64>("id").unwrap(), 7);
+        assert_eq!(path_param::<u64>(&ctx, "id").unwrap(), 7);
+        let err = raw.param::<u64>("slug").unwrap_err();
+        assert_eq!(err.field(), Some("slug"));
+    }
+
+    #[test]
+    fn test_raw_path_without_params() {
+        let ctx = make_ctx(Params::new());
+        let raw = RawPath::from_request(&ctx).unwrap();
+
+        assert!(raw.is_empty());
+        assert!(Path::<UserPath>::from_request(&ctx).is_err());
+    }
 }
@@ -0,0 +1,352 @@
+//! Conditional requests for optimistic concurrency.
+//!
+//! [`Precondition`] reads the `If-Match`, `If-None-Match` and
+//! `If-Unmodified-Since` headers of a request. Once the handler has loaded
+//! the current resource, [`Precondition::check`] compares them with its
+//! entity tag and modification time, in the order RFC 9110 (section
+//! 13.2.2) prescribes:
+//!
+//! 1. `If-Match` must match the current entity tag (strong comparison).
+//! 2. Without `If-Match`, the resource must not have been modified after
+//!    `If-Unmodified-Since`.
+//! 3. `If-None-Match` must not match the current entity tag (weak
+//!    comparison).
+//!
+//! A failed precondition is answered with `412 Precondition Failed`,
+//! except for a matching `If-None-Match` on `GET` or `HEAD`, which is
+//! answered with `304 Not Modified`. Operations that must not overwrite a
+//! resource blindly call [`Precondition::require`] first, answering
+//! requests without `If-Match` or `If-Unmodified-Since` with
+//! `428 Precondition Required`.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use archimedes_extract::{EntityTag, Precondition};
+//!
+//! async fn update_user(precondition: Precondition, update: Json<UserUpdate>) -> Result<Json<User>, ErrorResponse> {
+//!     precondition.require()?;
+//!     let user = users.get(id)?;
+//!     precondition.check(Some(&EntityTag::strong(user.version.to_string())), Some(user.updated_at))?;
+//!     Ok(Json(users.update(id, update)?))
+//! }
+//! ```
+
+use std::time::SystemTime;
+
+use http::{header, HeaderValue, Method, StatusCode};
+
+use crate::header::{EntityTag, ExtractTypedHeader, IfMatch, IfNoneMatch, TypedHeader};
+use crate::response::ErrorResponse;
+use crate::{ExtractionContext, ExtractionError, ExtractionSource, FromRequest};
+
+/// Result of evaluating the preconditions of a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreconditionOutcome {
+    /// Every precondition holds (or none was given): perform the request.
+    Proceed,
+    /// A precondition failed: answer `412 Precondition Failed`.
+    Failed,
+    /// `If-None-Match` matched on `GET` or `HEAD`: answer
+    /// `304 Not Modified`.
+    NotModified,
+}
+
+/// Extractor for the conditional headers of a request.
+///
+/// Extraction fails with `400 Bad Request` when a conditional header is
+/// malformed; absent headers impose no precondition. See the
+/// [module documentation](self).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Precondition {
+    method: Method,
+    if_match: Option<IfMatch>,
+    if_none_match: Option<IfNoneMatch>,
+    if_unmodified_since: Option<SystemTime>,
+}
+
+impl Precondition {
+    /// Returns the `If-Match` header, if present.
+    #[must_use]
+    pub fn if_match(&self) -> Option<&IfMatch> {
+        self.if_match.as_ref()
+    }
+
+    /// Returns the `If-None-Match` header, if present.
+    #[must_use]
+    pub fn if_none_match(&self) -> Option<&IfNoneMatch> {
+        self.if_none_match.as_ref()
+    }
+
+    /// Returns the `If-Unmodified-Since` date, if present.
+    #[must_use]
+    pub fn if_unmodified_since(&self) -> Option<SystemTime> {
+        self.if_unmodified_since
+    }
+
+    /// Returns `true` if the request carries `If-Match` or
+    /// `If-Unmodified-Since`, i.e. it states which version it modifies.
+    #[must_use]
+    pub fn is_conditional(&self) -> bool {
+        self.if_match.is_some() || self.if_unmodified_since.is_some()
+    }
+
+    /// Evaluates the preconditions against the current resource.
+    ///
+    /// `current` is the current entity tag and `last_modified` the current
+    /// modification time; pass `None` for a resource that does not exist
+    /// or does not track it. `If-Unmodified-Since` is ignored when the
+    /// modification time is unknown.
+    #[must_use]
+    pub fn evaluate(
+        &self,
+        current: Option<&EntityTag>,
+        last_modified: Option<SystemTime>,
+    ) -> PreconditionOutcome {
+        if let Some(if_match) = &self.if_match {
+            if !if_match.matches(current) {
+                return PreconditionOutcome::Failed;
+            }
+        } else if let (Some(since), Some(modified)) = (self.if_unmodified_since, last_modified) {
+            // HTTP dates have second precision
+            if truncate_to_seconds(modified) > since {
+                return PreconditionOutcome::Failed;
+            }
+        }
+
+        if let Some(if_none_match) = &self.if_none_match {
+            if if_none_match.matches(current) {
+                return if self.method == Method::GET || self.method == Method::HEAD {
+                    PreconditionOutcome::NotModified
+                } else {
+                    PreconditionOutcome::Failed
+                };
+            }
+        }
+
+        PreconditionOutcome::Proceed
+    }
+
+    /// Checks the preconditions against the current resource, for
+    /// short-circuiting a handler with `?`.
+    ///
+    /// See [`evaluate`](Self::evaluate).
+    ///
+    /// # Errors
+    ///
+    /// Returns `412 Precondition Failed` when a precondition fails, or
+    /// `304 Not Modified` with the current `ETag` when `If-None-Match`
+    /// matched on `GET` or `HEAD`.
+    pub fn check(
+        &self,
+        current: Option<&EntityTag>,
+        last_modified: Option<SystemTime>,
+    ) -> Result<(), ErrorResponse> {
+        match self.evaluate(current, last_modified) {
+            PreconditionOutcome::Proceed => Ok(()),
+            PreconditionOutcome::Failed => Err(ErrorResponse::precondition_failed(
+                "The resource does not match the request's preconditions",
+            )),
+            PreconditionOutcome::NotModified => {
+                let response = ErrorResponse::new(
+                    StatusCode::NOT_MODIFIED,
+                    "NOT_MODIFIED",
+                    "The resource has not been modified",
+                );
+                let etag = current.and_then(|tag| HeaderValue::try_from(tag.to_string()).ok());
+                Err(match etag {
+                    Some(etag) => response.append_header(header::ETAG, etag),
+                    None => response,
+                })
+            }
+        }
+    }
+
+    /// Requires the request to state which version it modifies.
+    ///
+    /// # Errors
+    ///
+    /// Returns `428 Precondition Required` when the request carries neither
+    /// `If-Match` nor `If-Unmodified-Since`.
+    pub fn require(&self) -> Result<(), ErrorResponse> {
+        if self.is_conditional() {
+            Ok(())
+        } else {
+            Err(ErrorResponse::precondition_required(
+                "This operation requires an If-Match or If-Unmodified-Since header",
+            ))
+        }
+    }
+}
+
+impl FromRequest for Precondition {
+    fn from_request(ctx: &ExtractionContext) -> Result<Self, ExtractionError> {
+        let if_unmodified_since = ctx
+            .header(header::IF_UNMODIFIED_SINCE.as_str())
+            .map(|value| {
+                httpdate::parse_http_date(value).map_err(|e| {
+                    ExtractionError::invalid_type(
+                        ExtractionSource::Header,
+                        header::IF_UNMODIFIED_SINCE.as_str(),
+                        e.to_string(),
+                    )
+                })
+            })
+            .transpose()?;
+
+        Ok(Self {
+            method: ctx.method().clone(),
+            if_match: optional_header(ctx)?,
+            if_none_match: optional_header(ctx)?,
+            if_unmodified_since,
+        })
+    }
+}
+
+/// Extracts a typed header that may be absent.
+fn optional_header<T: TypedHeader>(ctx: &ExtractionContext) -> Result<Option<T>, ExtractionError> {
+    if ctx.headers().contains_key(T::NAME) {
+        ExtractTypedHeader::<T>::from_request(ctx).map(|header| Some(header.into_inner()))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Drops the sub-second part of a time, for comparison with HTTP dates.
+fn truncate_to_seconds(time: SystemTime) -> SystemTime {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(time, |since_epoch| {
+            SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(since_epoch.as_secs())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::ExtractionContextBuilder;
+    use std::time::Duration;
+
+    fn precondition(method: Method, headers: &[(&str, &str)]) -> Precondition {
+        let mut builder = ExtractionContextBuilder::new().method(method);
+        for (name, value) in headers {
+            builder = builder.header(name, value);
+        }
+        Precondition::from_request(&builder.build()).unwrap()
+    }
+
+    #[test]
+    fn test_stale_if_match_fails() {
+        let precondition = precondition(Method::PUT, &[("if-match", "\"v1\"")]);
+
+        let err = precondition
+            .check(Some(&EntityTag::strong("v2")), None)
+            .unwrap_err();
+        assert_eq!(err.status(), StatusCode::PRECONDITION_FAILED);
+        // A resource that no longer exists fails too
+        assert_eq!(
+            precondition.evaluate(None, None),
+            PreconditionOutcome::Failed
+        );
+    }
+
+    #[test]
+    fn test_matching_if_match_proceeds() {
+        let precondition = precondition(Method::PUT, &[("if-match", "\"v1\", \"v2\"")]);
+
+        assert!(precondition.require().is_ok());
+        assert!(precondition
+            .check(Some(&EntityTag::strong("v2")), None)
+            .is_ok());
+        // If-Match compares strongly
+        assert!(precondition
+            .check(Some(&EntityTag::weak("v2")), None)
+            .is_err());
+    }
+
+    #[test]
+    fn test_missing_precondition_is_required() {
+        let precondition = precondition(Method::DELETE, &[]);
+
+        let err = precondition.require().unwrap_err();
+        assert_eq!(err.status(), StatusCode::PRECONDITION_REQUIRED);
+        // Without preconditions every request proceeds
+        assert!(precondition
+            .check(Some(&EntityTag::strong("v1")), None)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_if_unmodified_since() {
+        let since = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let date = httpdate::fmt_http_date(since);
+        let precondition = precondition(Method::PUT, &[("if-unmodified-since", &date)]);
+        assert!(precondition.require().is_ok());
+
+        let unchanged = since + Duration::from_millis(500);
+        assert_eq!(
+            precondition.evaluate(None, Some(unchanged)),
+            PreconditionOutcome::Proceed
+        );
+        let modified = since + Duration::from_secs(1);
+        assert_eq!(
+            precondition.evaluate(None, Some(modified)),
+            PreconditionOutcome::Failed
+        );
+        // Unknown modification times ignore the header
+        assert_eq!(
+            precondition.evaluate(None, None),
+            PreconditionOutcome::Proceed
+        );
+    }
+
+    #[test]
+    fn test_if_match_takes_precedence_over_if_unmodified_since() {
+        let date = httpdate::fmt_http_date(SystemTime::UNIX_EPOCH);
+        let precondition = precondition(
+            Method::PUT,
+            &[("if-match", "*"), ("if-unmodified-since", &date)],
+        );
+
+        assert_eq!(
+            precondition.evaluate(Some(&EntityTag::strong("v1")), Some(SystemTime::now())),
+            PreconditionOutcome::Proceed
+        );
+    }
+
+    #[test]
+    fn test_if_none_match() {
+        let current = EntityTag::strong("v1");
+
+        let get = precondition(Method::GET, &[("if-none-match", "W/\"v1\"")]);
+        let err = get.check(Some(&current), None).unwrap_err();
+        assert_eq!(err.status(), StatusCode::NOT_MODIFIED);
+        let response = err.into_response();
+        assert_eq!(response.headers()[header::ETAG], "\"v1\"");
+        assert!(response.body().is_empty());
+
+        // Creating a resource that must not exist yet
+        let put = precondition(Method::PUT, &[("if-none-match", "*")]);
+        assert_eq!(
+            put.evaluate(Some(&current), None),
+            PreconditionOutcome::Failed
+        );
+        assert_eq!(put.evaluate(None, None), PreconditionOutcome::Proceed);
+    }
+
+    #[test]
+    fn test_malformed_headers_are_rejected() {
+        for (name, value) in [
+            ("if-match", "v1"),
+            ("if-none-match", "\"unterminated"),
+            ("if-unmodified-since", "yesterday"),
+        ] {
+            let ctx = ExtractionContextBuilder::new()
+                .method(Method::PUT)
+                .header(name, value)
+                .build();
+            let err = Precondition::from_request(&ctx).unwrap_err();
+            assert_eq!(err.status_code(), StatusCode::BAD_REQUEST, "{name}");
+            assert_eq!(err.field(), Some(name));
+        }
+    }
+}
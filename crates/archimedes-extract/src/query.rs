@@ -2,6 +2,8 @@
 //!
 //! The [`Query`] extractor deserializes URL query parameters into a typed struct.
 
+use crate::error::from_urlencoded;
+use crate::naming::{match_field, struct_fields};
 use crate::{ExtractionContext, ExtractionError, ExtractionSource, FromRequest};
 use serde::de::DeserializeOwned;
 use std::ops::Deref;
@@ -12,6 +14,9 @@ use std::ops::Deref;
 /// implement [`serde::Deserialize`]. Query parameters are extracted from
 /// the URL after the `?` character.
 ///
+/// As with [`Path`](crate::Path), a parameter whose name differs from a
+/// field only by case or naming style binds to that field.
+///
 /// # Example
 ///
 /// ```rust
@@ -64,6 +69,14 @@ use std::ops::Deref;
 ///
 /// fn default_limit() -> u32 { 20 }
 /// ```
+///
+/// # Type Coercion
+///
+/// Values arrive as strings and are converted to the field's declared type,
+/// following the same rules as the Sentinel's coercion mode: `"30"` for an
+/// integer, `"3.5"` for a float and `"true"`/`"false"` for a bool. Lossy
+/// conversions such as `"3.7"` into an integer fail, and `String` fields
+/// keep the raw value.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Query<T>(pub T);
 
@@ -86,15 +99,35 @@ impl<T> Deref for Query<T> {
 impl<T: DeserializeOwned> FromRequest for Query<T> {
     fn from_request(ctx: &ExtractionContext) -> Result<Self, ExtractionError> {
         let query_string = ctx.query_string().unwrap_or("");
+        let renamed = rename_to_fields(query_string, struct_fields::<T>());
 
-        let value: T = serde_urlencoded::from_str(query_string).map_err(|e| {
-            ExtractionError::deserialization_failed(ExtractionSource::Query, e.to_string())
-        })?;
+        let value: T = from_urlencoded(
+            ExtractionSource::Query,
+            renamed.as_deref().unwrap_or(query_string),
+        )?;
 
         Ok(Query(value))
     }
 }
 
+/// Rewrites query keys to the field names they bind to.
+///
+/// Returns `None` if every key already matches, which is the common case.
+fn rename_to_fields(query_string: &str, fields: &[&'static str]) -> Option<String> {
+    if fields.is_empty() {
+        return None;
+    }
+    let pairs: Vec<(String, String)> = serde_urlencoded::from_str(query_string).ok()?;
+    if pairs.iter().all(|(k, _)| match_field(fields, k) == k) {
+        return None;
+    }
+    let renamed: Vec<(&str, &str)> = pairs
+        .iter()
+        .map(|(k, v)| (match_field(fields, k), v.as_str()))
+        .collect();
+    serde_urlencoded::to_string(renamed).ok()
+}
+
 /// Raw query string access.
 ///
 /// Use this when you need access to the raw query string without deserialization.
@@ -155,6 +188,12 @@ mod tests {
         ids: Vec<u64>,
     }
 
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct PagedParams {
+        page_size: Option<u32>,
+        offset: Option<u32>,
+    }
+
     #[derive(Debug, Deserialize, PartialEq)]
     struct DefaultParams {
         #[serde(default = "default_page")]
@@ -219,6 +258,15 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_params_named_in_camel_case() {
+        let ctx = make_ctx("/users?pageSize=10&Offset=5");
+        let Query(params) = Query::<PagedParams>::from_request(&ctx).unwrap();
+
+        assert_eq!(params.page_size, Some(10));
+        assert_eq!(params.offset, Some(5));
+    }
+
     #[test]
     fn test_array_params() {
         // Note: serde_urlencoded doesn't support repeated keys for arrays.
@@ -301,6 +349,38 @@ mod tests {
         assert_eq!(query, None);
     }
 
+    #[test]
+    fn test_query_coercion() {
+        #[derive(Debug, Deserialize)]
+        struct Typed {
+            age: i64,
+            ratio: f64,
+            active: bool,
+            zip: String,
+        }
+
+        let ctx = make_ctx("/users?age=30&ratio=3.5&active=false&zip=02139");
+        let Query(typed) = Query::<Typed>::from_request(&ctx).unwrap();
+        assert_eq!(typed.age, 30);
+        assert!((typed.ratio - 3.5).abs() < f64::EPSILON);
+        assert!(!typed.active);
+        assert_eq!(typed.zip, "02139");
+
+        for uri in [
+            "/users?age=3.7&ratio=1&active=true&zip=1",
+            "/users?age=1&ratio=1&active=yes&zip=1",
+        ] {
+            let ctx = ExtractionContext::new(
+                Method::GET,
+                uri.parse().unwrap(),
+                HeaderMap::new(),
+                Bytes::new(),
+                Params::new(),
+            );
+            assert!(Query::<Typed>::from_request(&ctx).is_err(), "{uri}");
+        }
+    }
+
     #[test]
     fn test_invalid_type_in_query() {
         let ctx = make_ctx("/users?limit=not-a-number");
@@ -309,5 +389,7 @@ mod tests {
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert_eq!(err.source(), ExtractionSource::Query);
+        assert_eq!(err.field(), Some("limit"));
+        assert_eq!(err.issues()[0].location(), "query.limit");
     }
 }
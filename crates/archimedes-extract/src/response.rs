@@ -13,6 +13,7 @@
 //! | [`FileResponse`] | Auto-detected | File download response |
 //! | [`Redirect`] | N/A | HTTP redirect (301, 302, etc.) |
 //! | [`NoContent`] | N/A | 204 No Content |
+//! | [`NdJson`] | `application/x-ndjson` | Streamed newline-delimited JSON |
 //!
 //! # Example
 //!
@@ -35,10 +36,282 @@
 //! // Redirect
 //! let redirect = Redirect::to("/dashboard");
 //! ```
+//!
+//! # JSON Configuration
+//!
+//! [`JsonResponse`] serializes with [`JsonConfig::global`], which can enable
+//! pretty-printing, `null` skipping and `camelCase` keys for the whole
+//! service. A single response can opt into pretty output with
+//! [`JsonResponse::pretty`] or use its own [`JsonConfig`].
+//!
+//! # Streaming JSON Lines
+//!
+//! [`NdJson`] streams a list endpoint as newline-delimited JSON instead of
+//! buffering one large array. Each item is serialized and sent as its own
+//! body frame as soon as the source stream yields it.
+//!
+//! # Caching
+//!
+//! An operation whose contract declares `x-cache-policy` gets the policy's
+//! `Cache-Control` on its successful responses automatically. A handler can
+//! set a [`CachePolicy`] itself instead, which takes precedence:
+//!
+//! ```rust
+//! use archimedes_extract::response::{CachePolicy, JsonResponse};
+//!
+//! let mut response = JsonResponse::new(vec![1, 2, 3]).into_response();
+//! CachePolicy::private().max_age(60).apply(response.headers_mut());
+//! assert_eq!(response.headers()["cache-control"], "private, max-age=60");
+//! ```
+//!
+//! # Cookies and Repeated Headers
+//!
+//! Builders take extra headers with `append_header` and cookies with
+//! `with_cookie`. Both append rather than replace, so a response can set
+//! several cookies, each on its own `Set-Cookie` line, and values of a
+//! repeated header are sent in the order they were added:
+//!
+//! ```rust
+//! use archimedes_extract::response::JsonResponse;
+//! use archimedes_extract::SetCookie;
+//!
+//! let response = JsonResponse::new(vec![1, 2, 3])
+//!     .with_cookie(&SetCookie::new("session", "abc123").http_only(true))
+//!     .with_cookie(&SetCookie::new("theme", "dark"))
+//!     .into_response();
+//!
+//! let cookies: Vec<_> = response.headers().get_all("set-cookie").iter().collect();
+//! assert_eq!(cookies, ["session=abc123; HttpOnly", "theme=dark"]);
+//! ```
 
+use std::convert::Infallible;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::{Arc, OnceLock, PoisonError, RwLock};
+use std::task::{ready, Context, Poll};
+
+use archimedes_core::{IntoResponse, RequestContext, StreamOutcome, StreamStatus, UrlForError};
 use bytes::Bytes;
-use http::{header, Response, StatusCode};
+use futures_core::Stream;
+use http::header::{self, IntoHeaderName};
+use http::{HeaderMap, HeaderValue, Response, StatusCode};
+use http_body::{Body, Frame};
 use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::cookie::SetCookie;
+
+pub use archimedes_core::{CachePolicy, CacheScope};
+
+/// Custom serializer used by [`JsonConfig::serializer`].
+///
+/// Receives the response data as a [`Value`], after null-skipping and key
+/// rewriting have been applied.
+pub type JsonSerializer = Arc<dyn Fn(&Value) -> Result<Vec<u8>, serde_json::Error> + Send + Sync>;
+
+/// Global JSON configuration, see [`JsonConfig::set_global`].
+static GLOBAL_JSON_CONFIG: OnceLock<RwLock<JsonConfig>> = OnceLock::new();
+
+/// Serialization options for [`JsonResponse`].
+///
+/// Responses use the global configuration unless they are given one with
+/// [`JsonResponse::with_config`].
+///
+/// # Example
+///
+/// ```rust
+/// use archimedes_extract::response::{JsonConfig, JsonResponse};
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct User {
+///     user_id: u64,
+///     nickname: Option<String>,
+/// }
+///
+/// let config = JsonConfig::new().camel_case(true).skip_nulls(true);
+/// let response = JsonResponse::new(User { user_id: 1, nickname: None })
+///     .with_config(config)
+///     .into_response();
+///
+/// assert_eq!(response.body().as_ref(), br#"{"userId":1}"#);
+/// ```
+#[derive(Clone, Default)]
+pub struct JsonConfig {
+    pretty: bool,
+    skip_nulls: bool,
+    camel_case: bool,
+    serializer: Option<JsonSerializer>,
+}
+
+impl JsonConfig {
+    /// Creates a configuration with `serde_json` defaults.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pretty-prints responses with two-space indentation.
+    #[must_use]
+    pub fn pretty(mut self, pretty: bool) -> Self {
+        self.pretty = pretty;
+        self
+    }
+
+    /// Omits object fields whose value is `null`.
+    #[must_use]
+    pub fn skip_nulls(mut self, skip_nulls: bool) -> Self {
+        self.skip_nulls = skip_nulls;
+        self
+    }
+
+    /// Rewrites `snake_case` object keys to `camelCase`.
+    #[must_use]
+    pub fn camel_case(mut self, camel_case: bool) -> Self {
+        self.camel_case = camel_case;
+        self
+    }
+
+    /// Replaces the final serialization step.
+    ///
+    /// The serializer takes precedence over [`pretty`](Self::pretty).
+    #[must_use]
+    pub fn serializer<F>(mut self, serializer: F) -> Self
+    where
+        F: Fn(&Value) -> Result<Vec<u8>, serde_json::Error> + Send + Sync + 'static,
+    {
+        self.serializer = Some(Arc::new(serializer));
+        self
+    }
+
+    /// Returns `true` if responses are pretty-printed.
+    #[must_use]
+    pub fn is_pretty(&self) -> bool {
+        self.pretty
+    }
+
+    /// Returns `true` if `null` fields are omitted.
+    #[must_use]
+    pub fn skips_nulls(&self) -> bool {
+        self.skip_nulls
+    }
+
+    /// Returns `true` if keys are rewritten to `camelCase`.
+    #[must_use]
+    pub fn is_camel_case(&self) -> bool {
+        self.camel_case
+    }
+
+    /// Returns the global configuration.
+    #[must_use]
+    pub fn global() -> Self {
+        global_json_config()
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Sets the global configuration used by every [`JsonResponse`]
+    /// without an explicit configuration.
+    pub fn set_global(config: Self) {
+        *global_json_config()
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = config;
+    }
+
+    /// Serializes `data` with this configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `data` cannot be represented as JSON.
+    pub fn to_vec<T: Serialize>(&self, data: &T) -> Result<Vec<u8>, serde_json::Error> {
+        if !self.skip_nulls && !self.camel_case && self.serializer.is_none() {
+            return if self.pretty {
+                serde_json::to_vec_pretty(data)
+            } else {
+                serde_json::to_vec(data)
+            };
+        }
+
+        let mut value = serde_json::to_value(data)?;
+        self.rewrite(&mut value);
+
+        match &self.serializer {
+            Some(serializer) => serializer(&value),
+            None if self.pretty => serde_json::to_vec_pretty(&value),
+            None => serde_json::to_vec(&value),
+        }
+    }
+
+    /// Applies null-skipping and key rewriting to a value, recursively.
+    fn rewrite(&self, value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                let fields = std::mem::take(map);
+                *map = fields
+                    .into_iter()
+                    .filter(|(_, v)| !(self.skip_nulls && v.is_null()))
+                    .map(|(k, mut v)| {
+                        self.rewrite(&mut v);
+                        let key = if self.camel_case {
+                            to_camel_case(&k)
+                        } else {
+                            k
+                        };
+                        (key, v)
+                    })
+                    .collect::<Map<_, _>>();
+            }
+            Value::Array(items) => items.iter_mut().for_each(|item| self.rewrite(item)),
+            _ => {}
+        }
+    }
+}
+
+impl fmt::Debug for JsonConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JsonConfig")
+            .field("pretty", &self.pretty)
+            .field("skip_nulls", &self.skip_nulls)
+            .field("camel_case", &self.camel_case)
+            .field("serializer", &self.serializer.as_ref().map(|_| "<custom>"))
+            .finish()
+    }
+}
+
+fn global_json_config() -> &'static RwLock<JsonConfig> {
+    GLOBAL_JSON_CONFIG.get_or_init(|| RwLock::new(JsonConfig::default()))
+}
+
+/// Converts a `snake_case` key to `camelCase`, keeping leading underscores.
+fn to_camel_case(key: &str) -> String {
+    let mut out = String::with_capacity(key.len());
+    let mut upper = false;
+    for c in key.chars() {
+        if c == '_' && !out.trim_start_matches('_').is_empty() {
+            upper = true;
+        } else if upper {
+            out.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Appends `headers` after those the builder set itself, keeping every
+/// value of a repeated name in order.
+fn append_headers<B>(response: &mut Response<B>, headers: &HeaderMap) {
+    for (name, value) in headers {
+        response.headers_mut().append(name, value.clone());
+    }
+}
+
+/// Returns the `Set-Cookie` value for `cookie`.
+fn cookie_header(cookie: &SetCookie) -> HeaderValue {
+    HeaderValue::try_from(cookie.to_header_value()).expect("invalid Set-Cookie header value")
+}
 
 /// JSON response builder.
 ///
@@ -68,6 +341,9 @@ use serde::Serialize;
 pub struct JsonResponse<T> {
     data: T,
     status: StatusCode,
+    config: Option<JsonConfig>,
+    pretty: bool,
+    headers: HeaderMap,
 }
 
 impl<T: Serialize> JsonResponse<T> {
@@ -77,6 +353,9 @@ impl<T: Serialize> JsonResponse<T> {
         Self {
             data,
             status: StatusCode::OK,
+            config: None,
+            pretty: false,
+            headers: HeaderMap::new(),
         }
     }
 
@@ -86,6 +365,9 @@ impl<T: Serialize> JsonResponse<T> {
         Self {
             data,
             status: StatusCode::CREATED,
+            config: None,
+            pretty: false,
+            headers: HeaderMap::new(),
         }
     }
 
@@ -96,6 +378,37 @@ impl<T: Serialize> JsonResponse<T> {
         self
     }
 
+    /// Pretty-prints this response regardless of the configuration.
+    #[must_use]
+    pub fn pretty(mut self) -> Self {
+        self.pretty = true;
+        self
+    }
+
+    /// Uses `config` instead of the global [`JsonConfig`].
+    #[must_use]
+    pub fn with_config(mut self, config: JsonConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Appends a header, keeping any value already set under the same name.
+    #[must_use]
+    pub fn append_header(mut self, name: impl IntoHeaderName, value: HeaderValue) -> Self {
+        self.headers.append(name, value);
+        self
+    }
+
+    /// Adds a cookie, sent on its own `Set-Cookie` line.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cookie contains characters not allowed in a header.
+    #[must_use]
+    pub fn with_cookie(self, cookie: &SetCookie) -> Self {
+        self.append_header(header::SET_COOKIE, cookie_header(cookie))
+    }
+
     /// Returns the status code.
     #[must_use]
     pub fn status(&self) -> StatusCode {
@@ -115,13 +428,29 @@ impl<T: Serialize> JsonResponse<T> {
     /// Panics if JSON serialization fails.
     #[must_use]
     pub fn into_response(self) -> Response<Bytes> {
-        let body = serde_json::to_vec(&self.data).expect("JSON serialization failed");
+        let config = self.config.unwrap_or_else(JsonConfig::global);
+        let config = if self.pretty {
+            config.pretty(true)
+        } else {
+            config
+        };
+        let body = config
+            .to_vec(&self.data)
+            .expect("JSON serialization failed");
 
-        Response::builder()
+        let mut response = Response::builder()
             .status(self.status)
             .header(header::CONTENT_TYPE, "application/json")
             .body(Bytes::from(body))
-            .expect("Failed to build response")
+            .expect("Failed to build response");
+        append_headers(&mut response, &self.headers);
+        response
+    }
+}
+
+impl<T: Serialize> IntoResponse for JsonResponse<T> {
+    fn into_response(self) -> Response<Bytes> {
+        Self::into_response(self)
     }
 }
 
@@ -141,6 +470,7 @@ impl<T: Serialize> JsonResponse<T> {
 pub struct HtmlResponse {
     body: String,
     status: StatusCode,
+    headers: HeaderMap,
 }
 
 impl HtmlResponse {
@@ -150,6 +480,7 @@ impl HtmlResponse {
         Self {
             body: body.into(),
             status: StatusCode::OK,
+            headers: HeaderMap::new(),
         }
     }
 
@@ -160,6 +491,23 @@ impl HtmlResponse {
         self
     }
 
+    /// Appends a header, keeping any value already set under the same name.
+    #[must_use]
+    pub fn append_header(mut self, name: impl IntoHeaderName, value: HeaderValue) -> Self {
+        self.headers.append(name, value);
+        self
+    }
+
+    /// Adds a cookie, sent on its own `Set-Cookie` line.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cookie contains characters not allowed in a header.
+    #[must_use]
+    pub fn with_cookie(self, cookie: &SetCookie) -> Self {
+        self.append_header(header::SET_COOKIE, cookie_header(cookie))
+    }
+
     /// Returns the status code.
     #[must_use]
     pub fn status(&self) -> StatusCode {
@@ -175,11 +523,19 @@ impl HtmlResponse {
     /// Builds the HTTP response.
     #[must_use]
     pub fn into_response(self) -> Response<Bytes> {
-        Response::builder()
+        let mut response = Response::builder()
             .status(self.status)
             .header(header::CONTENT_TYPE, "text/html; charset=utf-8")
             .body(Bytes::from(self.body))
-            .expect("Failed to build response")
+            .expect("Failed to build response");
+        append_headers(&mut response, &self.headers);
+        response
+    }
+}
+
+impl IntoResponse for HtmlResponse {
+    fn into_response(self) -> Response<Bytes> {
+        Self::into_response(self)
     }
 }
 
@@ -199,6 +555,7 @@ impl HtmlResponse {
 pub struct TextResponse {
     body: String,
     status: StatusCode,
+    headers: HeaderMap,
 }
 
 impl TextResponse {
@@ -208,6 +565,7 @@ impl TextResponse {
         Self {
             body: body.into(),
             status: StatusCode::OK,
+            headers: HeaderMap::new(),
         }
     }
 
@@ -218,6 +576,23 @@ impl TextResponse {
         self
     }
 
+    /// Appends a header, keeping any value already set under the same name.
+    #[must_use]
+    pub fn append_header(mut self, name: impl IntoHeaderName, value: HeaderValue) -> Self {
+        self.headers.append(name, value);
+        self
+    }
+
+    /// Adds a cookie, sent on its own `Set-Cookie` line.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cookie contains characters not allowed in a header.
+    #[must_use]
+    pub fn with_cookie(self, cookie: &SetCookie) -> Self {
+        self.append_header(header::SET_COOKIE, cookie_header(cookie))
+    }
+
     /// Returns the status code.
     #[must_use]
     pub fn status(&self) -> StatusCode {
@@ -233,11 +608,19 @@ impl TextResponse {
     /// Builds the HTTP response.
     #[must_use]
     pub fn into_response(self) -> Response<Bytes> {
-        Response::builder()
+        let mut response = Response::builder()
             .status(self.status)
             .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
             .body(Bytes::from(self.body))
-            .expect("Failed to build response")
+            .expect("Failed to build response");
+        append_headers(&mut response, &self.headers);
+        response
+    }
+}
+
+impl IntoResponse for TextResponse {
+    fn into_response(self) -> Response<Bytes> {
+        Self::into_response(self)
     }
 }
 
@@ -263,6 +646,7 @@ impl TextResponse {
 pub struct Redirect {
     location: String,
     status: StatusCode,
+    headers: HeaderMap,
 }
 
 impl Redirect {
@@ -272,6 +656,7 @@ impl Redirect {
         Self {
             location: location.into(),
             status: StatusCode::FOUND,
+            headers: HeaderMap::new(),
         }
     }
 
@@ -281,6 +666,7 @@ impl Redirect {
         Self {
             location: location.into(),
             status: StatusCode::MOVED_PERMANENTLY,
+            headers: HeaderMap::new(),
         }
     }
 
@@ -291,6 +677,7 @@ impl Redirect {
         Self {
             location: location.into(),
             status: StatusCode::SEE_OTHER,
+            headers: HeaderMap::new(),
         }
     }
 
@@ -301,6 +688,7 @@ impl Redirect {
         Self {
             location: location.into(),
             status: StatusCode::TEMPORARY_REDIRECT,
+            headers: HeaderMap::new(),
         }
     }
 
@@ -311,9 +699,56 @@ impl Redirect {
         Self {
             location: location.into(),
             status: StatusCode::PERMANENT_REDIRECT,
+            headers: HeaderMap::new(),
         }
     }
 
+    /// Creates a temporary redirect (302 Found) to another operation.
+    ///
+    /// The location is built with [`RequestContext::url_for`], so it
+    /// follows the routes and base path the server was configured with.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UrlForError`] if the URL cannot be generated.
+    pub fn to_operation(
+        ctx: &RequestContext,
+        operation_id: &str,
+        params: &[(&str, &str)],
+    ) -> Result<Self, UrlForError> {
+        ctx.url_for(operation_id, params).map(Self::to)
+    }
+
+    /// Creates a See Other redirect (303) to another operation.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UrlForError`] if the URL cannot be generated.
+    pub fn see_other_operation(
+        ctx: &RequestContext,
+        operation_id: &str,
+        params: &[(&str, &str)],
+    ) -> Result<Self, UrlForError> {
+        ctx.url_for(operation_id, params).map(Self::see_other)
+    }
+
+    /// Appends a header, keeping any value already set under the same name.
+    #[must_use]
+    pub fn append_header(mut self, name: impl IntoHeaderName, value: HeaderValue) -> Self {
+        self.headers.append(name, value);
+        self
+    }
+
+    /// Adds a cookie, sent on its own `Set-Cookie` line.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cookie contains characters not allowed in a header.
+    #[must_use]
+    pub fn with_cookie(self, cookie: &SetCookie) -> Self {
+        self.append_header(header::SET_COOKIE, cookie_header(cookie))
+    }
+
     /// Returns the status code.
     #[must_use]
     pub fn status(&self) -> StatusCode {
@@ -329,11 +764,19 @@ impl Redirect {
     /// Builds the HTTP response.
     #[must_use]
     pub fn into_response(self) -> Response<Bytes> {
-        Response::builder()
+        let mut response = Response::builder()
             .status(self.status)
             .header(header::LOCATION, self.location)
             .body(Bytes::new())
-            .expect("Failed to build response")
+            .expect("Failed to build response");
+        append_headers(&mut response, &self.headers);
+        response
+    }
+}
+
+impl IntoResponse for Redirect {
+    fn into_response(self) -> Response<Bytes> {
+        Self::into_response(self)
     }
 }
 
@@ -377,6 +820,12 @@ impl NoContent {
     }
 }
 
+impl IntoResponse for NoContent {
+    fn into_response(self) -> Response<Bytes> {
+        Self::into_response(self)
+    }
+}
+
 /// Error response builder.
 ///
 /// Creates standardized error responses matching the Themis error envelope format.
@@ -399,6 +848,7 @@ pub struct ErrorResponse {
     code: String,
     message: String,
     request_id: Option<String>,
+    headers: HeaderMap,
 }
 
 impl ErrorResponse {
@@ -410,6 +860,7 @@ impl ErrorResponse {
             code: code.into(),
             message: message.into(),
             request_id: None,
+            headers: HeaderMap::new(),
         }
     }
 
@@ -437,6 +888,18 @@ impl ErrorResponse {
         Self::new(StatusCode::NOT_FOUND, "NOT_FOUND", message)
     }
 
+    /// Creates a 412 Precondition Failed error.
+    #[must_use]
+    pub fn precondition_failed(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::PRECONDITION_FAILED, "PRECONDITION_FAILED", message)
+    }
+
+    /// Creates a 428 Precondition Required error.
+    #[must_use]
+    pub fn precondition_required(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::PRECONDITION_REQUIRED, "PRECONDITION_REQUIRED", message)
+    }
+
     /// Creates a 500 Internal Server Error.
     #[must_use]
     pub fn internal_error(message: impl Into<String>) -> Self {
@@ -450,6 +913,23 @@ impl ErrorResponse {
         self
     }
 
+    /// Appends a header, keeping any value already set under the same name.
+    #[must_use]
+    pub fn append_header(mut self, name: impl IntoHeaderName, value: HeaderValue) -> Self {
+        self.headers.append(name, value);
+        self
+    }
+
+    /// Adds a cookie, sent on its own `Set-Cookie` line.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cookie contains characters not allowed in a header.
+    #[must_use]
+    pub fn with_cookie(self, cookie: &SetCookie) -> Self {
+        self.append_header(header::SET_COOKIE, cookie_header(cookie))
+    }
+
     /// Returns the status code.
     #[must_use]
     pub fn status(&self) -> StatusCode {
@@ -457,6 +937,8 @@ impl ErrorResponse {
     }
 
     /// Builds the HTTP response with JSON error envelope.
+    ///
+    /// A `304 Not Modified` carries no body, so it gets no envelope.
     #[must_use]
     pub fn into_response(self) -> Response<Bytes> {
         #[derive(serde::Serialize)]
@@ -467,6 +949,13 @@ impl ErrorResponse {
             request_id: Option<String>,
         }
 
+        if self.status == StatusCode::NOT_MODIFIED {
+            let mut response = Response::new(Bytes::new());
+            *response.status_mut() = self.status;
+            append_headers(&mut response, &self.headers);
+            return response;
+        }
+
         let envelope = ErrorEnvelope {
             code: self.code,
             message: self.message,
@@ -475,11 +964,19 @@ impl ErrorResponse {
 
         let body = serde_json::to_vec(&envelope).expect("JSON serialization failed");
 
-        Response::builder()
+        let mut response = Response::builder()
             .status(self.status)
             .header(header::CONTENT_TYPE, "application/json")
             .body(Bytes::from(body))
-            .expect("Failed to build response")
+            .expect("Failed to build response");
+        append_headers(&mut response, &self.headers);
+        response
+    }
+}
+
+impl IntoResponse for ErrorResponse {
+    fn into_response(self) -> Response<Bytes> {
+        Self::into_response(self)
     }
 }
 
@@ -514,6 +1011,7 @@ pub struct FileResponse {
     content_type: Option<String>,
     disposition: ContentDisposition,
     status: StatusCode,
+    headers: HeaderMap,
 }
 
 /// Content-Disposition type for file responses.
@@ -536,6 +1034,7 @@ impl FileResponse {
             content_type: None,
             disposition: ContentDisposition::default(),
             status: StatusCode::OK,
+            headers: HeaderMap::new(),
         }
     }
 
@@ -583,6 +1082,23 @@ impl FileResponse {
         self
     }
 
+    /// Appends a header, keeping any value already set under the same name.
+    #[must_use]
+    pub fn append_header(mut self, name: impl IntoHeaderName, value: HeaderValue) -> Self {
+        self.headers.append(name, value);
+        self
+    }
+
+    /// Adds a cookie, sent on its own `Set-Cookie` line.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cookie contains characters not allowed in a header.
+    #[must_use]
+    pub fn with_cookie(self, cookie: &SetCookie) -> Self {
+        self.append_header(header::SET_COOKIE, cookie_header(cookie))
+    }
+
     /// Returns the status code.
     #[must_use]
     pub fn status(&self) -> StatusCode {
@@ -743,13 +1259,229 @@ impl FileResponse {
         let content_disposition = self.build_content_disposition();
         let content_length = self.data.len();
 
-        Response::builder()
+        let mut response = Response::builder()
             .status(self.status)
             .header(header::CONTENT_TYPE, content_type)
             .header(header::CONTENT_DISPOSITION, content_disposition)
             .header(header::CONTENT_LENGTH, content_length)
             .body(Bytes::from(self.data))
-            .expect("Failed to build response")
+            .expect("Failed to build response");
+        append_headers(&mut response, &self.headers);
+        response
+    }
+}
+
+impl IntoResponse for FileResponse {
+    fn into_response(self) -> Response<Bytes> {
+        Self::into_response(self)
+    }
+}
+
+/// Formats the final line written when an [`NdJson`] stream fails.
+pub type NdJsonErrorLine = Arc<dyn Fn(&str) -> Value + Send + Sync>;
+
+/// Newline-delimited JSON streaming response.
+///
+/// Wraps a stream of `Result<T, E>` and writes each item serialized on its
+/// own line, with `Content-Type: application/x-ndjson`. Items are sent as
+/// separate body frames, so the client receives them as they are produced.
+///
+/// When the stream yields an error (or an item fails to serialize), the
+/// body ends with a final error line, by default
+/// `{"error":{"message":"..."}}`. Use [`error_line`](Self::error_line) to
+/// change its shape or [`without_error_line`](Self::without_error_line) to
+/// end the body without one. The response carries a [`StreamOutcome`]
+/// extension recording whether the stream completed.
+///
+/// # Example
+///
+/// ```rust
+/// use archimedes_extract::response::NdJson;
+/// use futures_util::stream;
+/// use serde_json::json;
+///
+/// let rows = stream::iter((0..3).map(|id| Ok::<_, std::io::Error>(json!({ "id": id }))));
+/// let response = NdJson::new(rows).into_response();
+///
+/// assert_eq!(response.headers()["content-type"], "application/x-ndjson");
+/// ```
+pub struct NdJson<S> {
+    stream: Pin<Box<S>>,
+    status: StatusCode,
+    config: JsonConfig,
+    error_line: Option<NdJsonErrorLine>,
+    outcome: StreamOutcome,
+    done: bool,
+    headers: HeaderMap,
+}
+
+impl<S> NdJson<S> {
+    /// Creates an NDJSON response with status 200 OK.
+    ///
+    /// Items are serialized with [`JsonConfig::global`].
+    #[must_use]
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream: Box::pin(stream),
+            status: StatusCode::OK,
+            config: JsonConfig::global().pretty(false),
+            error_line: Some(Arc::new(
+                |message: &str| serde_json::json!({ "error": { "message": message } }),
+            )),
+            outcome: StreamOutcome::new(),
+            done: false,
+            headers: HeaderMap::new(),
+        }
+    }
+
+    /// Sets a custom status code.
+    #[must_use]
+    pub fn with_status(mut self, status: StatusCode) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Serializes items with `config` instead of the global [`JsonConfig`].
+    ///
+    /// Pretty-printing is always disabled, since each item must fit on a
+    /// single line.
+    #[must_use]
+    pub fn with_config(mut self, config: JsonConfig) -> Self {
+        self.config = config.pretty(false);
+        self
+    }
+
+    /// Sets the formatter for the error line written when the stream fails.
+    ///
+    /// The formatter receives the error message.
+    #[must_use]
+    pub fn error_line<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&str) -> Value + Send + Sync + 'static,
+    {
+        self.error_line = Some(Arc::new(f));
+        self
+    }
+
+    /// Ends the body without an error line when the stream fails.
+    #[must_use]
+    pub fn without_error_line(mut self) -> Self {
+        self.error_line = None;
+        self
+    }
+
+    /// Appends a header, keeping any value already set under the same name.
+    #[must_use]
+    pub fn append_header(mut self, name: impl IntoHeaderName, value: HeaderValue) -> Self {
+        self.headers.append(name, value);
+        self
+    }
+
+    /// Adds a cookie, sent on its own `Set-Cookie` line.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cookie contains characters not allowed in a header.
+    #[must_use]
+    pub fn with_cookie(self, cookie: &SetCookie) -> Self {
+        self.append_header(header::SET_COOKIE, cookie_header(cookie))
+    }
+
+    /// Returns the status code.
+    #[must_use]
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Returns the outcome handle resolved when the stream ends.
+    #[must_use]
+    pub fn outcome(&self) -> &StreamOutcome {
+        &self.outcome
+    }
+
+    /// Builds the HTTP response, using `self` as the streaming body.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the response cannot be built.
+    #[must_use]
+    pub fn into_response(mut self) -> Response<Self> {
+        let outcome = self.outcome.clone();
+        let headers = std::mem::take(&mut self.headers);
+
+        let mut response = Response::builder()
+            .status(self.status)
+            .header(header::CONTENT_TYPE, "application/x-ndjson")
+            .body(self)
+            .expect("Failed to build response");
+        append_headers(&mut response, &headers);
+        response.extensions_mut().insert(outcome);
+        response
+    }
+
+    /// Ends the stream with an error, returning the error line if enabled.
+    fn fail(&mut self, message: &str) -> Option<Bytes> {
+        self.done = true;
+        self.outcome.set(StreamStatus::error(message));
+
+        let format = self.error_line.as_ref()?;
+        let mut line = serde_json::to_vec(&format(message)).ok()?;
+        line.push(b'\n');
+        Some(Bytes::from(line))
+    }
+}
+
+impl<S> fmt::Debug for NdJson<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NdJson")
+            .field("status", &self.status)
+            .field("config", &self.config)
+            .field("error_line", &self.error_line.is_some())
+            .field("outcome", &self.outcome)
+            .field("done", &self.done)
+            .field("headers", &self.headers)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, T, E> Body for NdJson<S>
+where
+    S: Stream<Item = Result<T, E>>,
+    T: Serialize,
+    E: fmt::Display,
+{
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+
+        let line = match ready!(self.stream.as_mut().poll_next(cx)) {
+            Some(Ok(item)) => match self.config.to_vec(&item) {
+                Ok(mut line) => {
+                    line.push(b'\n');
+                    Some(Bytes::from(line))
+                }
+                Err(e) => self.fail(&format!("failed to serialize item: {e}")),
+            },
+            Some(Err(e)) => self.fail(&e.to_string()),
+            None => {
+                self.done = true;
+                self.outcome.set(StreamStatus::Ok);
+                None
+            }
+        };
+
+        Poll::Ready(line.map(|line| Ok(Frame::data(line))))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.done
     }
 }
 
@@ -782,6 +1514,93 @@ mod tests {
         );
     }
 
+    #[derive(Serialize)]
+    struct Profile {
+        user_id: u64,
+        display_name: Option<String>,
+        home_address: Address,
+    }
+
+    #[derive(Serialize)]
+    struct Address {
+        street_line: Option<String>,
+        postal_code: String,
+    }
+
+    fn profile() -> Profile {
+        Profile {
+            user_id: 7,
+            display_name: None,
+            home_address: Address {
+                street_line: None,
+                postal_code: "12345".to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_json_response_pretty() {
+        let data = TestData {
+            id: 1,
+            name: "Test".to_string(),
+        };
+
+        let response = JsonResponse::new(data).pretty().into_response();
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/json"
+        );
+        assert_eq!(
+            response.body().as_ref(),
+            b"{\n  \"id\": 1,\n  \"name\": \"Test\"\n}"
+        );
+    }
+
+    #[test]
+    fn test_json_config_camel_case() {
+        let config = JsonConfig::new().camel_case(true);
+        let body = config.to_vec(&profile()).unwrap();
+        let value: Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(value["userId"], 7);
+        assert!(value["displayName"].is_null());
+        assert_eq!(value["homeAddress"]["postalCode"], "12345");
+        assert!(value.get("user_id").is_none());
+    }
+
+    #[test]
+    fn test_json_config_skip_nulls() {
+        let config = JsonConfig::new().skip_nulls(true);
+        let response = JsonResponse::new(profile())
+            .with_config(config)
+            .into_response();
+
+        assert_eq!(
+            response.body().as_ref(),
+            br#"{"home_address":{"postal_code":"12345"},"user_id":7}"#
+        );
+    }
+
+    #[test]
+    fn test_json_config_custom_serializer() {
+        let config = JsonConfig::new()
+            .camel_case(true)
+            .serializer(|value| Ok(format!("custom:{}", value["userId"]).into_bytes()));
+        let response = JsonResponse::new(profile())
+            .with_config(config)
+            .into_response();
+
+        assert_eq!(response.body().as_ref(), b"custom:7");
+    }
+
+    #[test]
+    fn test_to_camel_case() {
+        assert_eq!(to_camel_case("user_id"), "userId");
+        assert_eq!(to_camel_case("already"), "already");
+        assert_eq!(to_camel_case("_private_field"), "_privateField");
+        assert_eq!(to_camel_case("a__b"), "aB");
+    }
+
     #[test]
     fn test_json_response_created() {
         let data = TestData {
@@ -836,6 +1655,33 @@ mod tests {
         assert_eq!(redirect.location(), "/dashboard");
     }
 
+    #[test]
+    fn test_response_types_into_response() {
+        fn convert(value: impl IntoResponse) -> Response<Bytes> {
+            value.into_response()
+        }
+
+        let response = convert(JsonResponse::created(serde_json::json!({ "id": 1 })));
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+        assert_eq!(response.body().as_ref(), br#"{"id":1}"#);
+
+        let response = convert(NoContent);
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert!(response.headers().get(header::CONTENT_TYPE).is_none());
+        assert!(response.body().is_empty());
+
+        let response = convert(Redirect::see_other("/orders/7"));
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        assert_eq!(response.headers()[header::LOCATION], "/orders/7");
+
+        let response = convert(Err::<NoContent, _>(
+            archimedes_core::ThemisError::not_found("no order"),
+        ));
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+    }
+
     #[test]
     fn test_redirect_permanent() {
         let redirect = Redirect::permanent("/new-url");
@@ -848,6 +1694,29 @@ mod tests {
         assert_eq!(redirect.status(), StatusCode::SEE_OTHER);
     }
 
+    #[test]
+    fn test_redirect_to_operation() {
+        use archimedes_core::UrlGenerator;
+        use archimedes_router::{MethodRouter, Router};
+
+        let mut router = Router::new();
+        router.insert("/orders/{orderId}", MethodRouter::new().get("getOrder"));
+        let ctx = RequestContext::new().with_url_generator(UrlGenerator::new(router));
+
+        let redirect =
+            Redirect::see_other_operation(&ctx, "getOrder", &[("orderId", "7")]).unwrap();
+        assert_eq!(redirect.status(), StatusCode::SEE_OTHER);
+        assert_eq!(redirect.location(), "/orders/7");
+
+        let redirect = Redirect::to_operation(&ctx, "getOrder", &[("orderId", "7")]).unwrap();
+        assert_eq!(redirect.status(), StatusCode::FOUND);
+
+        assert!(matches!(
+            Redirect::to_operation(&ctx, "getOrder", &[]),
+            Err(UrlForError::MissingParam { .. })
+        ));
+    }
+
     #[test]
     fn test_redirect_response() {
         let redirect = Redirect::to("/target");
@@ -857,6 +1726,67 @@ mod tests {
         assert_eq!(response.headers().get(header::LOCATION).unwrap(), "/target");
     }
 
+    #[test]
+    fn test_two_cookies_emit_two_set_cookie_lines() {
+        let response = Redirect::see_other("/dashboard")
+            .with_cookie(&SetCookie::new("session", "abc123").path("/"))
+            .with_cookie(&SetCookie::new("theme", "dark"))
+            .into_response();
+
+        let cookies: Vec<_> = response
+            .headers()
+            .get_all(header::SET_COOKIE)
+            .iter()
+            .collect();
+        assert_eq!(cookies, ["session=abc123; Path=/", "theme=dark"]);
+    }
+
+    #[test]
+    fn test_append_header_keeps_existing_values() {
+        let response = TextResponse::new("hello")
+            .append_header(header::VARY, HeaderValue::from_static("Accept"))
+            .append_header(header::VARY, HeaderValue::from_static("Accept-Encoding"))
+            .append_header(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("text/x-custom"),
+            )
+            .into_response();
+
+        let vary: Vec<_> = response.headers().get_all(header::VARY).iter().collect();
+        assert_eq!(vary, ["Accept", "Accept-Encoding"]);
+
+        // The builder's own Content-Type comes first and is not replaced
+        let content_types: Vec<_> = response
+            .headers()
+            .get_all(header::CONTENT_TYPE)
+            .iter()
+            .collect();
+        assert_eq!(
+            content_types,
+            ["text/plain; charset=utf-8", "text/x-custom"]
+        );
+    }
+
+    #[test]
+    fn test_ndjson_appends_headers() {
+        let rows = futures_util::stream::iter(Vec::<Result<Value, Infallible>>::new());
+        let response = NdJson::new(rows)
+            .with_cookie(&SetCookie::new("a", "1"))
+            .with_cookie(&SetCookie::new("b", "2"))
+            .into_response();
+
+        let cookies: Vec<_> = response
+            .headers()
+            .get_all(header::SET_COOKIE)
+            .iter()
+            .collect();
+        assert_eq!(cookies, ["a=1", "b=2"]);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "application/x-ndjson"
+        );
+    }
+
     #[test]
     fn test_no_content() {
         let response = NoContent::new();
@@ -1098,4 +2028,112 @@ mod tests {
         let disposition = ContentDisposition::default();
         assert_eq!(disposition, ContentDisposition::Attachment);
     }
+
+    async fn collect_lines<S, T, E>(mut body: NdJson<S>) -> (Vec<Bytes>, String)
+    where
+        S: Stream<Item = Result<T, E>>,
+        T: Serialize,
+        E: fmt::Display,
+    {
+        use http_body_util::BodyExt;
+
+        let mut frames = Vec::new();
+        while let Some(frame) = body.frame().await {
+            match frame {
+                Ok(frame) => frames.push(frame.into_data().unwrap()),
+                Err(never) => match never {},
+            }
+        }
+        let text = frames
+            .iter()
+            .map(|f| std::str::from_utf8(f).unwrap())
+            .collect();
+        (frames, text)
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_streams_each_item_as_a_line() {
+        let items = futures_util::stream::iter((0..1000).map(|id| {
+            Ok::<_, Infallible>(TestData {
+                id,
+                name: format!("item-{id}"),
+            })
+        }));
+        let response = NdJson::new(items).into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/x-ndjson"
+        );
+        let outcome = response
+            .extensions()
+            .get::<StreamOutcome>()
+            .unwrap()
+            .clone();
+
+        let (frames, text) = collect_lines(response.into_body()).await;
+
+        // One frame per item, so lines are flushed as they are produced
+        assert_eq!(frames.len(), 1000);
+        assert!(text.ends_with('\n'));
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 1000);
+        for (id, line) in lines.iter().enumerate() {
+            let parsed: TestData = serde_json::from_str(line).unwrap();
+            assert_eq!(parsed.id, id as u64);
+        }
+        assert_eq!(outcome.get(), Some(&StreamStatus::Ok));
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_error_ends_with_error_line() {
+        let items = futures_util::stream::iter(vec![
+            Ok(serde_json::json!({ "id": 1 })),
+            Err("cursor expired"),
+            Ok(serde_json::json!({ "id": 2 })),
+        ]);
+        let body = NdJson::new(items);
+        let outcome = body.outcome().clone();
+
+        let (_, text) = collect_lines(body).await;
+        let lines: Vec<Value> = text
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                serde_json::json!({ "id": 1 }),
+                serde_json::json!({ "error": { "message": "cursor expired" } }),
+            ]
+        );
+        assert!(outcome.is_error());
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_error_line_is_configurable() {
+        let failing = || futures_util::stream::iter(vec![Ok(serde_json::json!(1)), Err("boom")]);
+
+        let body = NdJson::new(failing())
+            .error_line(|message| serde_json::json!({ "type": "error", "detail": message }));
+        let (_, text) = collect_lines(body).await;
+        assert_eq!(text, "1\n{\"detail\":\"boom\",\"type\":\"error\"}\n");
+
+        let body = NdJson::new(failing()).without_error_line();
+        let (_, text) = collect_lines(body).await;
+        assert_eq!(text, "1\n");
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_uses_config_without_pretty() {
+        let items = futures_util::stream::iter(vec![Ok::<_, Infallible>(
+            serde_json::json!({ "user_id": 1 }),
+        )]);
+        let response = NdJson::new(items)
+            .with_config(JsonConfig::new().pretty(true).camel_case(true))
+            .into_response();
+
+        let (_, text) = collect_lines(response.into_body()).await;
+        assert_eq!(text, "{\"userId\":1}\n");
+    }
 }
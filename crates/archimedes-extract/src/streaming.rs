@@ -0,0 +1,482 @@
+//! Streaming responses with trailers.
+//!
+//! A streaming response sends its status line before the body is produced,
+//! so a producer that fails halfway through would otherwise leave the
+//! client with a truncated body and a `200`. [`StreamingResponse`]
+//! declares its trailers up front in the `Trailer` header and finishes the
+//! body with a trailer block carrying the final status:
+//!
+//! - `x-stream-status: ok` or `x-stream-status: error`
+//! - `x-error-message: <message>` when the stream failed
+//! - any custom trailers declared with [`StreamingResponse::trailer`]
+//!
+//! HTTP/2 always delivers trailers. HTTP/1.1 delivers them on chunked
+//! responses when the client sent `TE: trailers`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use archimedes_extract::streaming::StreamingResponse;
+//! use http::header::HeaderName;
+//! use http::HeaderValue;
+//!
+//! # async fn export() {
+//! let (writer, response) = StreamingResponse::new()
+//!     .content_type("application/x-ndjson")
+//!     .trailer(HeaderName::from_static("x-row-count"))
+//!     .channel(16);
+//!
+//! tokio::spawn(async move {
+//!     for row in 0..3 {
+//!         if writer.send(format!("{{\"row\":{row}}}\n")).await.is_err() {
+//!             return; // client went away
+//!         }
+//!     }
+//!     writer
+//!         .set_trailer(HeaderName::from_static("x-row-count"), HeaderValue::from_static("3"))
+//!         .unwrap();
+//!     writer.finish();
+//! });
+//!
+//! assert_eq!(
+//!     response.headers()["trailer"],
+//!     "x-stream-status, x-error-message, x-row-count"
+//! );
+//! # }
+//! ```
+
+use std::convert::Infallible;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{ready, Context, Poll};
+
+use archimedes_core::{StreamOutcome, StreamStatus, ERROR_MESSAGE_TRAILER, STREAM_STATUS_TRAILER};
+use bytes::Bytes;
+use http::header::{self, HeaderName};
+use http::{HeaderMap, HeaderValue, Response, StatusCode};
+use http_body::{Body, Frame, SizeHint};
+use tokio::sync::mpsc;
+
+/// Message recorded when a [`StreamWriter`] is dropped without finishing.
+const ABORTED_MESSAGE: &str = "stream ended before the producer finished";
+
+/// Builder for a streaming response with trailers.
+///
+/// Call [`channel`](Self::channel) to obtain the [`StreamWriter`] used by
+/// the producing task and the response to return from the handler. The
+/// response carries a [`StreamOutcome`] extension so middleware can observe
+/// how the stream ended.
+#[derive(Debug)]
+pub struct StreamingResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    trailers: Vec<HeaderName>,
+}
+
+impl StreamingResponse {
+    /// Creates a builder for a `200 OK` streaming response.
+    ///
+    /// The `x-stream-status` and `x-error-message` trailers are always
+    /// declared.
+    #[must_use]
+    pub fn new() -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/octet-stream"),
+        );
+
+        Self {
+            status: StatusCode::OK,
+            headers,
+            trailers: vec![
+                HeaderName::from_static(STREAM_STATUS_TRAILER),
+                HeaderName::from_static(ERROR_MESSAGE_TRAILER),
+            ],
+        }
+    }
+
+    /// Sets the response status code.
+    #[must_use]
+    pub fn status(mut self, status: StatusCode) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Sets the `Content-Type` header.
+    ///
+    /// Invalid header values are ignored.
+    #[must_use]
+    pub fn content_type(mut self, content_type: &str) -> Self {
+        if let Ok(value) = HeaderValue::from_str(content_type) {
+            self.headers.insert(header::CONTENT_TYPE, value);
+        }
+        self
+    }
+
+    /// Adds a response header.
+    #[must_use]
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.append(name, value);
+        self
+    }
+
+    /// Declares a custom trailer the producer may set.
+    #[must_use]
+    pub fn trailer(mut self, name: HeaderName) -> Self {
+        if !self.trailers.contains(&name) {
+            self.trailers.push(name);
+        }
+        self
+    }
+
+    /// Returns the declared trailer names.
+    #[must_use]
+    pub fn trailers(&self) -> &[HeaderName] {
+        &self.trailers
+    }
+
+    /// Builds the response and the writer that produces its body.
+    ///
+    /// `buffer` is the number of chunks queued before [`StreamWriter::send`]
+    /// waits for the client to catch up.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buffer` is zero.
+    #[must_use]
+    pub fn channel(self, buffer: usize) -> (StreamWriter, Response<StreamingBody>) {
+        let (tx, rx) = mpsc::channel(buffer);
+        let shared = Arc::new(Shared {
+            declared: self.trailers,
+            values: Mutex::new(HeaderMap::new()),
+            outcome: StreamOutcome::new(),
+        });
+
+        let declared = shared
+            .declared
+            .iter()
+            .map(HeaderName::as_str)
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let mut response = Response::new(StreamingBody {
+            rx,
+            shared: Arc::clone(&shared),
+            finished: false,
+        });
+        *response.status_mut() = self.status;
+        *response.headers_mut() = self.headers;
+        if let Ok(value) = HeaderValue::from_str(&declared) {
+            response.headers_mut().insert(header::TRAILER, value);
+        }
+        response.extensions_mut().insert(shared.outcome.clone());
+
+        let writer = StreamWriter {
+            tx,
+            shared,
+            finished: false,
+        };
+
+        (writer, response)
+    }
+}
+
+impl Default for StreamingResponse {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// State shared between a [`StreamWriter`] and its [`StreamingBody`].
+#[derive(Debug)]
+struct Shared {
+    declared: Vec<HeaderName>,
+    values: Mutex<HeaderMap>,
+    outcome: StreamOutcome,
+}
+
+impl Shared {
+    /// Builds the trailer block sent after the last chunk.
+    fn trailers(&self) -> HeaderMap {
+        let mut trailers = self
+            .values
+            .lock()
+            .map(|values| values.clone())
+            .unwrap_or_default();
+
+        let status = self
+            .outcome
+            .get()
+            .cloned()
+            .unwrap_or_else(|| StreamStatus::error(ABORTED_MESSAGE));
+        trailers.insert(
+            HeaderName::from_static(STREAM_STATUS_TRAILER),
+            HeaderValue::from_static(status.as_str()),
+        );
+        if let Some(value) = status
+            .message()
+            .and_then(|message| HeaderValue::from_str(message).ok())
+        {
+            trailers.insert(HeaderName::from_static(ERROR_MESSAGE_TRAILER), value);
+        }
+
+        trailers
+    }
+}
+
+/// Error returned by [`StreamWriter`] operations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamError {
+    /// The client disconnected and the body was dropped.
+    Closed,
+    /// The trailer was not declared with [`StreamingResponse::trailer`].
+    UndeclaredTrailer(HeaderName),
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Closed => write!(f, "stream receiver dropped"),
+            Self::UndeclaredTrailer(name) => write!(f, "trailer '{}' was not declared", name),
+        }
+    }
+}
+
+impl std::error::Error for StreamError {}
+
+/// Producing half of a [`StreamingResponse`].
+///
+/// Finish the stream with [`finish`](Self::finish) or
+/// [`fail`](Self::fail). Dropping the writer without either marks the
+/// stream as failed.
+#[derive(Debug)]
+pub struct StreamWriter {
+    tx: mpsc::Sender<Bytes>,
+    shared: Arc<Shared>,
+    finished: bool,
+}
+
+impl StreamWriter {
+    /// Sends a body chunk, waiting if the buffer is full.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StreamError::Closed`] if the client disconnected.
+    pub async fn send(&self, chunk: impl Into<Bytes>) -> Result<(), StreamError> {
+        self.tx
+            .send(chunk.into())
+            .await
+            .map_err(|_| StreamError::Closed)
+    }
+
+    /// Sets the value of a declared trailer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StreamError::UndeclaredTrailer`] if `name` was not declared
+    /// on the builder. Undeclared trailers would be dropped by HTTP/1.1.
+    pub fn set_trailer(&self, name: HeaderName, value: HeaderValue) -> Result<(), StreamError> {
+        if !self.shared.declared.contains(&name) {
+            return Err(StreamError::UndeclaredTrailer(name));
+        }
+        if let Ok(mut values) = self.shared.values.lock() {
+            values.insert(name, value);
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the client disconnected.
+    #[must_use]
+    pub fn is_closed(&self) -> bool {
+        self.tx.is_closed()
+    }
+
+    /// Returns the outcome handle shared with the response.
+    #[must_use]
+    pub fn outcome(&self) -> &StreamOutcome {
+        &self.shared.outcome
+    }
+
+    /// Ends the stream successfully.
+    pub fn finish(mut self) {
+        self.finished = true;
+        self.shared.outcome.set(StreamStatus::Ok);
+    }
+
+    /// Ends the stream with an error reported in the trailers.
+    pub fn fail(mut self, message: impl Into<String>) {
+        self.finished = true;
+        self.shared.outcome.set(StreamStatus::error(message));
+    }
+}
+
+impl Drop for StreamWriter {
+    fn drop(&mut self) {
+        // Runs before `tx` is dropped, so the body sees the outcome once the
+        // channel closes
+        if !self.finished {
+            self.shared
+                .outcome
+                .set(StreamStatus::error(ABORTED_MESSAGE));
+        }
+    }
+}
+
+/// Response body of a [`StreamingResponse`].
+///
+/// Yields the chunks sent by the [`StreamWriter`], then a trailers frame.
+#[derive(Debug)]
+pub struct StreamingBody {
+    rx: mpsc::Receiver<Bytes>,
+    shared: Arc<Shared>,
+    finished: bool,
+}
+
+impl StreamingBody {
+    /// Returns the outcome handle shared with the writer.
+    #[must_use]
+    pub fn outcome(&self) -> &StreamOutcome {
+        &self.shared.outcome
+    }
+}
+
+impl Body for StreamingBody {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        if self.finished {
+            return Poll::Ready(None);
+        }
+
+        match ready!(self.rx.poll_recv(cx)) {
+            Some(chunk) => Poll::Ready(Some(Ok(Frame::data(chunk)))),
+            None => {
+                self.finished = true;
+                Poll::Ready(Some(Ok(Frame::trailers(self.shared.trailers()))))
+            }
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.finished
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_body_util::BodyExt;
+
+    async fn collect(body: StreamingBody) -> (Bytes, HeaderMap) {
+        let collected = body.collect().await.unwrap();
+        let trailers = collected.trailers().cloned().unwrap_or_default();
+        (collected.to_bytes(), trailers)
+    }
+
+    #[test]
+    fn test_declares_trailers() {
+        let (_writer, response) = StreamingResponse::new()
+            .status(StatusCode::ACCEPTED)
+            .content_type("text/csv")
+            .trailer(HeaderName::from_static("x-row-count"))
+            .trailer(HeaderName::from_static("x-row-count"))
+            .channel(4);
+
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "text/csv");
+        assert_eq!(
+            response.headers()[header::TRAILER],
+            "x-stream-status, x-error-message, x-row-count"
+        );
+        assert!(response.extensions().get::<StreamOutcome>().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_finish_emits_ok_trailers() {
+        let (writer, response) = StreamingResponse::new()
+            .trailer(HeaderName::from_static("x-row-count"))
+            .channel(4);
+
+        tokio::spawn(async move {
+            writer.send("a").await.unwrap();
+            writer.send("b").await.unwrap();
+            writer
+                .set_trailer(
+                    HeaderName::from_static("x-row-count"),
+                    HeaderValue::from_static("2"),
+                )
+                .unwrap();
+            writer.finish();
+        });
+
+        let outcome = response
+            .extensions()
+            .get::<StreamOutcome>()
+            .cloned()
+            .unwrap();
+        let (body, trailers) = collect(response.into_body()).await;
+
+        assert_eq!(body, "ab");
+        assert_eq!(trailers[STREAM_STATUS_TRAILER], "ok");
+        assert_eq!(trailers["x-row-count"], "2");
+        assert!(trailers.get(ERROR_MESSAGE_TRAILER).is_none());
+        assert!(!outcome.is_error());
+    }
+
+    #[tokio::test]
+    async fn test_mid_stream_failure_emits_error_trailers() {
+        let (writer, response) = StreamingResponse::new().channel(4);
+
+        tokio::spawn(async move {
+            writer.send("partial").await.unwrap();
+            writer.fail("database connection lost");
+        });
+
+        let outcome = response
+            .extensions()
+            .get::<StreamOutcome>()
+            .cloned()
+            .unwrap();
+        let (body, trailers) = collect(response.into_body()).await;
+
+        assert_eq!(body, "partial");
+        assert_eq!(trailers[STREAM_STATUS_TRAILER], "error");
+        assert_eq!(trailers[ERROR_MESSAGE_TRAILER], "database connection lost");
+        assert!(outcome.is_error());
+    }
+
+    #[tokio::test]
+    async fn test_dropped_writer_counts_as_failure() {
+        let (writer, response) = StreamingResponse::new().channel(4);
+        drop(writer);
+
+        let (_, trailers) = collect(response.into_body()).await;
+        assert_eq!(trailers[STREAM_STATUS_TRAILER], "error");
+        assert_eq!(trailers[ERROR_MESSAGE_TRAILER], ABORTED_MESSAGE);
+    }
+
+    #[test]
+    fn test_rejects_undeclared_trailer() {
+        let (writer, _response) = StreamingResponse::new().channel(1);
+        let err = writer
+            .set_trailer(
+                HeaderName::from_static("x-other"),
+                HeaderValue::from_static("1"),
+            )
+            .unwrap_err();
+        assert_eq!(
+            err,
+            StreamError::UndeclaredTrailer(HeaderName::from_static("x-other"))
+        );
+    }
+}
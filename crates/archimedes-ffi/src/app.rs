@@ -6,7 +6,9 @@
 use crate::config::{ArchimedesConfig, InternalConfig};
 use crate::error::FfiError;
 use crate::handler::HandlerRegistry;
+use crate::router::{ArchimedesRouter, RouteTable, RouterState};
 use crate::types::{ArchimedesError, ArchimedesHandlerFn};
+use archimedes_middleware::Pipeline;
 use std::ffi::{c_char, CStr, CString};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
@@ -25,6 +27,10 @@ pub(crate) struct AppState {
     pub config: InternalConfig,
     /// Handler registry
     pub handlers: Arc<HandlerRegistry>,
+    /// Routes merged from routers
+    pub routes: RouteTable,
+    /// Middleware pipeline requests are dispatched through
+    pub pipeline: Pipeline,
     /// Running flag
     pub running: Arc<AtomicBool>,
     /// Contract JSON (stored for lifetime)
@@ -35,9 +41,12 @@ pub(crate) struct AppState {
 impl AppState {
     /// Create a new application state
     pub fn new(config: InternalConfig) -> Self {
+        let pipeline = crate::dispatch::build_pipeline(&config);
         Self {
             config,
             handlers: Arc::new(HandlerRegistry::new()),
+            routes: RouteTable::default(),
+            pipeline,
             running: Arc::new(AtomicBool::new(false)),
             contract_json: None,
         }
@@ -145,6 +154,41 @@ pub unsafe extern "C" fn archimedes_register_handler(
     }
 }
 
+/// Merge a router's routes into the application
+///
+/// Routes registered with `archimedes_router_register_ex` become routable,
+/// including those of nested routers. Handlers are still registered per
+/// operation with `archimedes_register_handler`.
+///
+/// # Safety
+///
+/// - `app` must be a valid application pointer
+/// - `router` must be a valid router pointer; it remains owned by the caller
+///
+/// Returns 0 on success, or an error code on failure.
+#[no_mangle]
+pub unsafe extern "C" fn archimedes_merge(
+    app: *mut ArchimedesApp,
+    router: *const ArchimedesRouter,
+) -> ArchimedesError {
+    if app.is_null() {
+        crate::set_last_error(FfiError::NullPointer("app"));
+        return ArchimedesError::NullPointer;
+    }
+    if router.is_null() {
+        crate::set_last_error(FfiError::NullPointer("router"));
+        return ArchimedesError::NullPointer;
+    }
+
+    let state = &mut *(app as *mut AppState);
+    let router = &*(router as *const RouterState);
+
+    let table = router.route_table();
+    tracing::debug!(routes = table.len(), "Merged router");
+    state.routes.merge(table);
+    ArchimedesError::Ok
+}
+
 /// Load a contract from JSON
 ///
 /// # Safety
@@ -0,0 +1,329 @@
+//! Request dispatch
+//!
+//! Runs requests for routes registered with `archimedes_router_register_ex`
+//! through the middleware pipeline and into the registered FFI handler.
+
+use crate::app::AppState;
+use crate::config::InternalConfig;
+use crate::handler::{invoke_handler, RegisteredHandler};
+use crate::request::RequestContextBuilder;
+use crate::response::{extract_headers, maybe_free_response_body, response_to_bytes};
+use crate::router::{route_options, MatchedRoute};
+use archimedes_core::CallerIdentity;
+use archimedes_middleware::stages::{PolicyDecision, PolicyEvaluator, RequestBody};
+use archimedes_middleware::{
+    AuthorizationMiddleware, CallerScopes, IdentityMiddleware, MiddlewareContext, Pipeline,
+    Request, RequestIdMiddleware, Response, ResponseExt, ResponseValidationMiddleware,
+    ValidationMiddleware,
+};
+use bytes::Bytes;
+use http::StatusCode;
+use http_body_util::{BodyExt, Full};
+
+/// Policy used until a policy bundle is evaluated: any authenticated
+/// caller is allowed, anonymous callers are denied.
+#[derive(Debug)]
+struct RequireAuthenticated;
+
+impl PolicyEvaluator for RequireAuthenticated {
+    fn evaluate(&self, identity: &CallerIdentity, _operation_id: &str) -> PolicyDecision {
+        if matches!(identity, CallerIdentity::Anonymous) {
+            PolicyDecision::Deny {
+                reason: "Authentication required".to_string(),
+            }
+        } else {
+            PolicyDecision::Allow
+        }
+    }
+}
+
+/// Build the middleware pipeline for an application
+pub(crate) fn build_pipeline(config: &InternalConfig) -> Pipeline {
+    let authorization = if config.enable_authorization {
+        AuthorizationMiddleware::custom(RequireAuthenticated)
+    } else {
+        AuthorizationMiddleware::allow_all()
+    };
+
+    Pipeline::builder()
+        .add_pre_handler_stage(RequestIdMiddleware::new())
+        .add_pre_handler_stage(IdentityMiddleware::new())
+        .add_pre_handler_stage(authorization)
+        .add_pre_handler_stage(ValidationMiddleware::allow_all())
+        .add_post_handler_stage(
+            ResponseValidationMiddleware::allow_all().enforce(config.enable_response_validation),
+        )
+        .build()
+}
+
+/// Dispatch a request to the handler of the matching route
+pub(crate) async fn dispatch(state: &AppState, request: Request) -> Response {
+    let Some(route) = state.routes.resolve(request.method(), request.uri().path()) else {
+        return Response::json_error(
+            StatusCode::NOT_FOUND,
+            "ROUTE_NOT_FOUND",
+            "No route matches the request",
+        );
+    };
+
+    let Some(handler) = state.handlers.get(&route.operation_id) else {
+        return Response::json_error(
+            StatusCode::NOT_IMPLEMENTED,
+            "HANDLER_NOT_FOUND",
+            &format!(
+                "No handler registered for operation '{}'",
+                route.operation_id
+            ),
+        );
+    };
+
+    // Buffer the body so validation and the handler can both read it
+    let (parts, body) = request.into_parts();
+    let body = body
+        .collect()
+        .await
+        .map(|b| b.to_bytes())
+        .unwrap_or_default();
+    let mut request = Request::from_parts(parts, Full::new(body.clone()));
+    request.extensions_mut().insert(RequestBody(body.to_vec()));
+
+    let mut ctx = MiddlewareContext::new();
+    ctx.set_operation_id(route.operation_id.clone());
+    ctx.set_extension(route_options(route.flags));
+
+    state
+        .pipeline
+        .process(ctx, request, move |ctx, request| {
+            let response = call_handler(&handler, &route, ctx, &request);
+            Box::pin(async move { response })
+        })
+        .await
+}
+
+/// Invoke the FFI handler with a context built from the pipeline state
+fn call_handler(
+    handler: &RegisteredHandler,
+    route: &MatchedRoute,
+    ctx: &MiddlewareContext,
+    request: &Request,
+) -> Response {
+    let body = request
+        .extensions()
+        .get::<RequestBody>()
+        .map_or(&[][..], |b| b.0.as_slice());
+    let headers: Vec<(String, String)> = request
+        .headers()
+        .iter()
+        .filter_map(|(name, value)| {
+            Some((name.as_str().to_string(), value.to_str().ok()?.to_string()))
+        })
+        .collect();
+    let scopes = ctx
+        .get_extension::<CallerScopes>()
+        .map(|s| s.as_slice().to_vec())
+        .unwrap_or_default();
+
+    let mut builder = RequestContextBuilder::new(
+        &ctx.request_id().to_string(),
+        &route.operation_id,
+        request.method().as_str(),
+        request.uri().path(),
+    )
+    .with_trace(
+        ctx.trace_id().unwrap_or_default(),
+        ctx.span_id().unwrap_or_default(),
+    )
+    .with_query(request.uri().query().unwrap_or_default())
+    .with_caller(ctx.identity(), &scopes)
+    .with_path_params(&route.params)
+    .with_headers(&headers);
+    let ffi_ctx = builder.build();
+
+    let data = invoke_handler(handler, &ffi_ctx, body);
+    let (status, body, content_type) = response_to_bytes(&data);
+    let headers = extract_headers(&data);
+    // SAFETY: the body was copied above and is not read again
+    unsafe { maybe_free_response_body(&data) };
+
+    let mut response = http::Response::builder()
+        .status(status)
+        .header(http::header::CONTENT_TYPE, content_type);
+    for (name, value) in headers {
+        response = response.header(name, value);
+    }
+    response
+        .body(Full::new(Bytes::from(body)))
+        .unwrap_or_else(|e| {
+            Response::json_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "INVALID_RESPONSE",
+                &format!("Handler returned an invalid response: {e}"),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{
+        archimedes_free, archimedes_merge, archimedes_new, archimedes_register_handler,
+    };
+    use crate::config::ArchimedesConfig;
+    use crate::request::archimedes_ctx_path_param;
+    use crate::router::{
+        archimedes_router_free, archimedes_router_new, archimedes_router_prefix,
+        archimedes_router_register_ex, ARCHIMEDES_ROUTE_NO_AUTH,
+    };
+    use crate::test_client::{
+        archimedes_test_client_for_app, archimedes_test_client_free, archimedes_test_client_get,
+        archimedes_test_client_with_bearer_token, archimedes_test_response_free,
+        archimedes_test_response_status_code, archimedes_test_response_text,
+    };
+    use crate::types::{ArchimedesError, ArchimedesRequestContext, ArchimedesResponseData};
+    use std::ffi::{c_void, CStr, CString};
+    use std::ptr;
+
+    /// Echoes the `userId` path parameter as the response body
+    extern "C" fn echo_user_id(
+        ctx: *const ArchimedesRequestContext,
+        _body: *const u8,
+        _body_len: usize,
+        _user_data: *mut c_void,
+    ) -> ArchimedesResponseData {
+        let name = CString::new("userId").unwrap();
+        let value = unsafe { archimedes_ctx_path_param(ctx, name.as_ptr()) };
+        let len = if value.is_null() {
+            0
+        } else {
+            unsafe { CStr::from_ptr(value).to_bytes().len() }
+        };
+        // The body borrows the context, which outlives the handler call
+        ArchimedesResponseData {
+            status_code: 200,
+            body: value,
+            body_len: len,
+            content_type: b"text/plain\0".as_ptr().cast(),
+            ..Default::default()
+        }
+    }
+
+    /// App with authorization enabled and two routes under `/users`:
+    /// `getAvatar` (NO_AUTH) and `getUser` (default flags).
+    unsafe fn app_with_routes() -> *mut crate::app::ArchimedesApp {
+        let contract = CString::new("contract.json").unwrap();
+        let policy = CString::new("policy.bundle").unwrap();
+        let config = ArchimedesConfig {
+            contract_path: contract.as_ptr(),
+            policy_bundle_path: policy.as_ptr(),
+            ..Default::default()
+        };
+        let app = archimedes_new(&config);
+        assert!(!app.is_null());
+
+        let router = archimedes_router_new();
+        let prefix = CString::new("/users").unwrap();
+        let get = CString::new("GET").unwrap();
+        let avatar_path = CString::new("/{userId}/avatar").unwrap();
+        let user_path = CString::new("/{userId}").unwrap();
+        let get_avatar = CString::new("getAvatar").unwrap();
+        let get_user = CString::new("getUser").unwrap();
+
+        archimedes_router_prefix(router, prefix.as_ptr());
+        assert_eq!(
+            archimedes_router_register_ex(
+                router,
+                get.as_ptr(),
+                avatar_path.as_ptr(),
+                get_avatar.as_ptr(),
+                ARCHIMEDES_ROUTE_NO_AUTH,
+            ),
+            0
+        );
+        assert_eq!(
+            archimedes_router_register_ex(
+                router,
+                get.as_ptr(),
+                user_path.as_ptr(),
+                get_user.as_ptr(),
+                0,
+            ),
+            0
+        );
+        assert_eq!(archimedes_merge(app, router), ArchimedesError::Ok);
+        archimedes_router_free(router);
+
+        for op in [&get_avatar, &get_user] {
+            let result =
+                archimedes_register_handler(app, op.as_ptr(), echo_user_id, ptr::null_mut());
+            assert_eq!(result, ArchimedesError::Ok);
+        }
+        app
+    }
+
+    unsafe fn get(
+        client: *mut crate::test_client::ArchimedesTestClient,
+        path: &str,
+    ) -> (u16, String) {
+        let path = CString::new(path).unwrap();
+        let response = archimedes_test_client_get(client, path.as_ptr());
+        assert!(!response.is_null());
+        let status = archimedes_test_response_status_code(response);
+        let text = archimedes_test_response_text(response);
+        let body = CStr::from_ptr(text).to_str().unwrap().to_string();
+        drop(CString::from_raw(text));
+        archimedes_test_response_free(response);
+        (status, body)
+    }
+
+    #[test]
+    fn test_no_auth_route_allows_anonymous_request() {
+        unsafe {
+            let app = app_with_routes();
+            let client = archimedes_test_client_for_app(app);
+
+            let (status, body) = get(client, "/users/42/avatar");
+            assert_eq!(status, 200);
+            assert_eq!(body, "42");
+
+            archimedes_test_client_free(client);
+            archimedes_free(app);
+        }
+    }
+
+    #[test]
+    fn test_route_without_flag_requires_auth() {
+        unsafe {
+            let app = app_with_routes();
+
+            let anonymous = archimedes_test_client_for_app(app);
+            let (status, body) = get(anonymous, "/users/42");
+            assert_eq!(status, 403);
+            assert!(body.contains("AUTHORIZATION_DENIED"));
+            archimedes_test_client_free(anonymous);
+
+            let authenticated = archimedes_test_client_for_app(app);
+            let token = CString::new("test-token").unwrap();
+            archimedes_test_client_with_bearer_token(authenticated, token.as_ptr());
+            let (status, body) = get(authenticated, "/users/42");
+            assert_eq!(status, 200);
+            assert_eq!(body, "42");
+            archimedes_test_client_free(authenticated);
+
+            archimedes_free(app);
+        }
+    }
+
+    #[test]
+    fn test_unmatched_route_is_not_found() {
+        unsafe {
+            let app = app_with_routes();
+            let client = archimedes_test_client_for_app(app);
+
+            let (status, _) = get(client, "/orders/1");
+            assert_eq!(status, 404);
+
+            archimedes_test_client_free(client);
+            archimedes_free(app);
+        }
+    }
+}
@@ -204,6 +204,9 @@ mod tests {
             path_params_count: 0,
             path_param_names: std::ptr::null(),
             path_param_values: std::ptr::null(),
+            query_params_count: 0,
+            query_param_names: std::ptr::null(),
+            query_param_values: std::ptr::null(),
             headers_count: 0,
             header_names: std::ptr::null(),
             header_values: std::ptr::null(),
@@ -68,6 +68,7 @@
 
 mod app;
 mod config;
+mod dispatch;
 mod error;
 mod extractors;
 mod handler;
@@ -82,8 +83,9 @@ mod types;
 
 // Public re-exports for FFI consumers
 pub use app::{
-    archimedes_free, archimedes_is_running, archimedes_load_contract, archimedes_new,
-    archimedes_register_handler, archimedes_run, archimedes_stop, archimedes_version,
+    archimedes_free, archimedes_is_running, archimedes_load_contract, archimedes_merge,
+    archimedes_new, archimedes_register_handler, archimedes_run, archimedes_stop,
+    archimedes_version,
 };
 pub use config::ArchimedesConfig;
 pub use error::FfiError;
@@ -127,8 +129,9 @@ pub use router::{
     archimedes_router_count, archimedes_router_free, archimedes_router_get_prefix,
     archimedes_router_merge, archimedes_router_nest, archimedes_router_nested_count,
     archimedes_router_new, archimedes_router_operation_count, archimedes_router_prefix,
-    archimedes_router_register, archimedes_router_tag, archimedes_router_tag_count,
-    ArchimedesRouter,
+    archimedes_router_register, archimedes_router_register_ex, archimedes_router_tag,
+    archimedes_router_tag_count, ArchimedesRouter, ARCHIMEDES_ROUTE_NO_AUTH,
+    ARCHIMEDES_ROUTE_SKIP_REQUEST_VALIDATION, ARCHIMEDES_ROUTE_SKIP_RESPONSE_VALIDATION,
 };
 pub use extractors::{
     archimedes_cookies_free, archimedes_cookies_get, archimedes_cookies_parse,
@@ -143,18 +146,22 @@ pub use extractors::{
     ArchimedesForm, ArchimedesMultipart, ArchimedesMultipartField, ArchimedesSameSite,
     ArchimedesSetCookie,
 };
+pub use request::{
+    archimedes_ctx_path_param, archimedes_ctx_path_param_count, archimedes_ctx_query_param,
+    archimedes_ctx_query_param_count,
+};
 pub use test_client::{
-    archimedes_string_free, archimedes_test_client_delete, archimedes_test_client_free,
-    archimedes_test_client_get, archimedes_test_client_new, archimedes_test_client_patch,
-    archimedes_test_client_post, archimedes_test_client_put, archimedes_test_client_request,
-    archimedes_test_client_with_bearer_token, archimedes_test_client_with_header,
-    archimedes_test_response_assert_body_contains, archimedes_test_response_assert_header,
-    archimedes_test_response_assert_status, archimedes_test_response_assert_success,
-    archimedes_test_response_body, archimedes_test_response_free,
-    archimedes_test_response_get_header, archimedes_test_response_is_client_error,
-    archimedes_test_response_is_server_error, archimedes_test_response_is_success,
-    archimedes_test_response_status_code, archimedes_test_response_text, ArchimedesTestClient,
-    ArchimedesTestResponse,
+    archimedes_string_free, archimedes_test_client_delete, archimedes_test_client_for_app,
+    archimedes_test_client_free, archimedes_test_client_get, archimedes_test_client_new,
+    archimedes_test_client_patch, archimedes_test_client_post, archimedes_test_client_put,
+    archimedes_test_client_request, archimedes_test_client_with_bearer_token,
+    archimedes_test_client_with_header, archimedes_test_response_assert_body_contains,
+    archimedes_test_response_assert_header, archimedes_test_response_assert_status,
+    archimedes_test_response_assert_success, archimedes_test_response_body,
+    archimedes_test_response_free, archimedes_test_response_get_header,
+    archimedes_test_response_is_client_error, archimedes_test_response_is_server_error,
+    archimedes_test_response_is_success, archimedes_test_response_status_code,
+    archimedes_test_response_text, ArchimedesTestClient, ArchimedesTestResponse,
 };
 pub use types::{
     ArchimedesAsyncCallback, ArchimedesError, ArchimedesHandlerFn, ArchimedesRequestContext,
@@ -1,9 +1,11 @@
 //! Request context building
 //!
-//! Converts internal Archimedes request types to FFI-safe structs.
+//! Converts internal Archimedes request types to FFI-safe structs, and
+//! provides accessors for reading the context from native handlers.
 
 use crate::types::ArchimedesRequestContext;
-use std::ffi::CString;
+use archimedes_core::CallerIdentity;
+use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
 
 /// Builder for FFI request context
@@ -27,6 +29,12 @@ pub(crate) struct RequestContextBuilder {
     path_param_name_ptrs: Vec<*const c_char>,
     path_param_value_ptrs: Vec<*const c_char>,
 
+    // Query parameters
+    query_param_names: Vec<CString>,
+    query_param_values: Vec<CString>,
+    query_param_name_ptrs: Vec<*const c_char>,
+    query_param_value_ptrs: Vec<*const c_char>,
+
     // Headers
     header_names: Vec<CString>,
     header_values: Vec<CString>,
@@ -50,6 +58,10 @@ impl RequestContextBuilder {
             path_param_values: Vec::new(),
             path_param_name_ptrs: Vec::new(),
             path_param_value_ptrs: Vec::new(),
+            query_param_names: Vec::new(),
+            query_param_values: Vec::new(),
+            query_param_name_ptrs: Vec::new(),
+            query_param_value_ptrs: Vec::new(),
             header_names: Vec::new(),
             header_values: Vec::new(),
             header_name_ptrs: Vec::new(),
@@ -65,8 +77,20 @@ impl RequestContextBuilder {
     }
 
     /// Set query string
+    ///
+    /// The query is also decoded into name/value pairs. A query that is not
+    /// valid `application/x-www-form-urlencoded` yields no parameters.
     pub fn with_query(mut self, query: &str) -> Self {
         self.query = CString::new(query).unwrap_or_default();
+        let params: Vec<(String, String)> = serde_urlencoded::from_str(query).unwrap_or_default();
+        self.query_param_names = params
+            .iter()
+            .map(|(k, _)| CString::new(k.as_str()).unwrap_or_default())
+            .collect();
+        self.query_param_values = params
+            .iter()
+            .map(|(_, v)| CString::new(v.as_str()).unwrap_or_default())
+            .collect();
         self
     }
 
@@ -76,6 +100,18 @@ impl RequestContextBuilder {
         self
     }
 
+    /// Set caller identity along with the scopes granted to it
+    ///
+    /// The scopes are added to the identity JSON under a `scopes` key so
+    /// native handlers can make scope-based decisions.
+    pub fn with_caller(self, identity: &CallerIdentity, scopes: &[String]) -> Self {
+        let mut json = serde_json::to_value(identity).unwrap_or(serde_json::Value::Null);
+        if let Some(obj) = json.as_object_mut() {
+            obj.insert("scopes".to_string(), serde_json::json!(scopes));
+        }
+        self.with_caller_identity(&json.to_string())
+    }
+
     /// Add path parameters
     pub fn with_path_params(mut self, params: &[(String, String)]) -> Self {
         self.path_param_names = params
@@ -111,6 +147,10 @@ impl RequestContextBuilder {
         self.path_param_name_ptrs = self.path_param_names.iter().map(|s| s.as_ptr()).collect();
         self.path_param_value_ptrs = self.path_param_values.iter().map(|s| s.as_ptr()).collect();
 
+        // Build pointer arrays for query params
+        self.query_param_name_ptrs = self.query_param_names.iter().map(|s| s.as_ptr()).collect();
+        self.query_param_value_ptrs = self.query_param_values.iter().map(|s| s.as_ptr()).collect();
+
         // Build pointer arrays for headers
         self.header_name_ptrs = self.header_names.iter().map(|s| s.as_ptr()).collect();
         self.header_value_ptrs = self.header_values.iter().map(|s| s.as_ptr()).collect();
@@ -135,6 +175,17 @@ impl RequestContextBuilder {
             } else {
                 self.path_param_value_ptrs.as_ptr()
             },
+            query_params_count: self.query_param_names.len(),
+            query_param_names: if self.query_param_name_ptrs.is_empty() {
+                std::ptr::null()
+            } else {
+                self.query_param_name_ptrs.as_ptr()
+            },
+            query_param_values: if self.query_param_value_ptrs.is_empty() {
+                std::ptr::null()
+            } else {
+                self.query_param_value_ptrs.as_ptr()
+            },
             headers_count: self.header_names.len(),
             header_names: if self.header_name_ptrs.is_empty() {
                 std::ptr::null()
@@ -150,10 +201,114 @@ impl RequestContextBuilder {
     }
 }
 
+/// Look up a value by name in a pair of parallel C string arrays
+unsafe fn lookup(
+    count: usize,
+    names: *const *const c_char,
+    values: *const *const c_char,
+    name: *const c_char,
+) -> *const c_char {
+    if count == 0 || names.is_null() || values.is_null() || name.is_null() {
+        return std::ptr::null();
+    }
+
+    let wanted = CStr::from_ptr(name);
+    let names = std::slice::from_raw_parts(names, count);
+    let values = std::slice::from_raw_parts(values, count);
+    names
+        .iter()
+        .zip(values)
+        .find(|(&n, _)| !n.is_null() && CStr::from_ptr(n) == wanted)
+        .map_or(std::ptr::null(), |(_, &v)| v)
+}
+
+/// Get a path parameter of the matched route by name
+///
+/// # Safety
+///
+/// - `ctx` must be the context passed to the current handler call
+/// - `name` must be a valid null-terminated string
+///
+/// Returns the parameter value, or NULL if the route has no such parameter.
+/// The string is owned by the context and valid for the handler call only.
+#[no_mangle]
+pub unsafe extern "C" fn archimedes_ctx_path_param(
+    ctx: *const ArchimedesRequestContext,
+    name: *const c_char,
+) -> *const c_char {
+    if ctx.is_null() {
+        return std::ptr::null();
+    }
+    let ctx = &*ctx;
+    lookup(
+        ctx.path_params_count,
+        ctx.path_param_names,
+        ctx.path_param_values,
+        name,
+    )
+}
+
+/// Get a decoded query parameter by name
+///
+/// If the parameter is repeated, the first value is returned.
+///
+/// # Safety
+///
+/// - `ctx` must be the context passed to the current handler call
+/// - `name` must be a valid null-terminated string
+///
+/// Returns the parameter value, or NULL if the query has no such parameter.
+/// The string is owned by the context and valid for the handler call only.
+#[no_mangle]
+pub unsafe extern "C" fn archimedes_ctx_query_param(
+    ctx: *const ArchimedesRequestContext,
+    name: *const c_char,
+) -> *const c_char {
+    if ctx.is_null() {
+        return std::ptr::null();
+    }
+    let ctx = &*ctx;
+    lookup(
+        ctx.query_params_count,
+        ctx.query_param_names,
+        ctx.query_param_values,
+        name,
+    )
+}
+
+/// Get the number of path parameters of the matched route
+///
+/// # Safety
+///
+/// - `ctx` must be the context passed to the current handler call
+#[no_mangle]
+pub unsafe extern "C" fn archimedes_ctx_path_param_count(
+    ctx: *const ArchimedesRequestContext,
+) -> usize {
+    if ctx.is_null() {
+        return 0;
+    }
+    (*ctx).path_params_count
+}
+
+/// Get the number of decoded query parameters
+///
+/// # Safety
+///
+/// - `ctx` must be the context passed to the current handler call
+#[no_mangle]
+pub unsafe extern "C" fn archimedes_ctx_query_param_count(
+    ctx: *const ArchimedesRequestContext,
+) -> usize {
+    if ctx.is_null() {
+        return 0;
+    }
+    (*ctx).query_params_count
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::ffi::CStr;
 
     #[test]
     fn test_builder_basic() {
@@ -183,6 +338,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_builder_with_caller_scopes() {
+        let identity = CallerIdentity::user("alice", "alice@example.com");
+        let scopes = vec!["users:read".to_string(), "users:write".to_string()];
+        let mut builder =
+            RequestContextBuilder::new("req-1", "op", "GET", "/").with_caller(&identity, &scopes);
+        let ctx = builder.build();
+
+        let json = unsafe { CStr::from_ptr(ctx.caller_identity_json).to_str().unwrap() };
+        let value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            value["scopes"],
+            serde_json::json!(["users:read", "users:write"])
+        );
+    }
+
     #[test]
     fn test_builder_with_path_params() {
         let params = vec![
@@ -235,4 +406,44 @@ mod tests {
         assert_eq!(ctx.headers_count, 0);
         assert!(ctx.header_names.is_null());
     }
+
+    #[test]
+    fn test_builder_with_query_params() {
+        let mut builder =
+            RequestContextBuilder::new("req-1", "op", "GET", "/").with_query("q=a%20b&page=2&q=c");
+        let ctx = builder.build();
+
+        let q = CString::new("q").unwrap();
+        let page = CString::new("page").unwrap();
+        let missing = CString::new("missing").unwrap();
+
+        assert_eq!(ctx.query_params_count, 3);
+        unsafe {
+            assert_eq!(archimedes_ctx_query_param_count(&ctx), 3);
+            let value = archimedes_ctx_query_param(&ctx, q.as_ptr());
+            assert_eq!(CStr::from_ptr(value).to_str().unwrap(), "a b");
+            let value = archimedes_ctx_query_param(&ctx, page.as_ptr());
+            assert_eq!(CStr::from_ptr(value).to_str().unwrap(), "2");
+            assert!(archimedes_ctx_query_param(&ctx, missing.as_ptr()).is_null());
+        }
+    }
+
+    #[test]
+    fn test_ctx_path_param_accessor() {
+        let params = vec![("userId".to_string(), "123".to_string())];
+        let mut builder =
+            RequestContextBuilder::new("req-1", "op", "GET", "/").with_path_params(&params);
+        let ctx = builder.build();
+
+        let user_id = CString::new("userId").unwrap();
+        let post_id = CString::new("postId").unwrap();
+
+        unsafe {
+            assert_eq!(archimedes_ctx_path_param_count(&ctx), 1);
+            let value = archimedes_ctx_path_param(&ctx, user_id.as_ptr());
+            assert_eq!(CStr::from_ptr(value).to_str().unwrap(), "123");
+            assert!(archimedes_ctx_path_param(&ctx, post_id.as_ptr()).is_null());
+            assert!(archimedes_ctx_path_param(std::ptr::null(), user_id.as_ptr()).is_null());
+        }
+    }
 }
@@ -16,15 +16,39 @@
 //! // Register a handler on the router
 //! archimedes_router_register(users_router, "listUsers", list_users_handler, NULL);
 //!
+//! // Register a routed operation that anonymous callers may reach
+//! archimedes_router_register_ex(users_router, "GET", "/{userId}/avatar",
+//!                               "getAvatar", ARCHIMEDES_ROUTE_NO_AUTH);
+//!
 //! // Merge router into main app
 //! archimedes_merge(app, users_router);
 //! archimedes_router_free(users_router);
 //! ```
-
+//!
+//! Routes registered with a method and path are matched by the same radix
+//! router the native server uses; the matched path and query parameters are
+//! available to handlers through `archimedes_ctx_path_param` and
+//! `archimedes_ctx_query_param`.
+
+use archimedes_middleware::RouteOptions;
+use http::Method;
+use std::collections::HashMap;
 use std::ffi::{c_char, CStr, CString};
 use std::ptr;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
+/// Route flag: the route is reachable without authentication
+pub const ARCHIMEDES_ROUTE_NO_AUTH: u32 = 1;
+/// Route flag: skip request validation for the route
+pub const ARCHIMEDES_ROUTE_SKIP_REQUEST_VALIDATION: u32 = 1 << 1;
+/// Route flag: skip response validation for the route
+pub const ARCHIMEDES_ROUTE_SKIP_RESPONSE_VALIDATION: u32 = 1 << 2;
+
+/// All flags understood by `archimedes_router_register_ex`
+const KNOWN_ROUTE_FLAGS: u32 = ARCHIMEDES_ROUTE_NO_AUTH
+    | ARCHIMEDES_ROUTE_SKIP_REQUEST_VALIDATION
+    | ARCHIMEDES_ROUTE_SKIP_RESPONSE_VALIDATION;
+
 /// Opaque router handle for FFI
 #[repr(C)]
 pub struct ArchimedesRouter {
@@ -50,6 +74,75 @@ pub(crate) struct RouteEntry {
     pub operation_id: String,
     /// User-provided data pointer
     pub user_data: *mut std::ffi::c_void,
+    /// HTTP method and path pattern, for routes registered with
+    /// `archimedes_router_register_ex`
+    pub route: Option<(Method, String)>,
+    /// `ARCHIMEDES_ROUTE_*` flags
+    pub flags: u32,
+}
+
+/// Pipeline stage overrides for a set of route flags
+pub(crate) const fn route_options(flags: u32) -> RouteOptions {
+    RouteOptions {
+        no_auth: flags & ARCHIMEDES_ROUTE_NO_AUTH != 0,
+        skip_request_validation: flags & ARCHIMEDES_ROUTE_SKIP_REQUEST_VALIDATION != 0,
+        skip_response_validation: flags & ARCHIMEDES_ROUTE_SKIP_RESPONSE_VALIDATION != 0,
+        skip_rate_limit: false,
+        internal: false,
+        retain_raw_body: false,
+    }
+}
+
+/// Routes compiled from one or more routers
+///
+/// Matching is delegated to `archimedes_router::Router`, the radix router
+/// used by the native server.
+#[derive(Default)]
+pub(crate) struct RouteTable {
+    router: archimedes_router::Router,
+    flags: HashMap<String, u32>,
+}
+
+/// A route matched for an incoming request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct MatchedRoute {
+    /// Operation ID of the matched route
+    pub operation_id: String,
+    /// Path parameters in pattern order
+    pub params: Vec<(String, String)>,
+    /// `ARCHIMEDES_ROUTE_*` flags of the matched route
+    pub flags: u32,
+}
+
+impl RouteTable {
+    /// Add all routes of another table
+    pub fn merge(&mut self, other: Self) {
+        self.router.merge(other.router);
+        self.flags.extend(other.flags);
+    }
+
+    /// Match a request method and path
+    pub fn resolve(&self, method: &Method, path: &str) -> Option<MatchedRoute> {
+        let matched = self.router.match_route(method, path)?;
+        Some(MatchedRoute {
+            operation_id: matched.operation_id.to_string(),
+            params: matched
+                .params
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            flags: self
+                .flags
+                .get(matched.operation_id)
+                .copied()
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Number of routes in the table
+    pub fn len(&self) -> usize {
+        self.router.len()
+    }
 }
 
 impl RouterState {
@@ -61,6 +154,37 @@ impl RouterState {
             nested: Vec::new(),
         }
     }
+
+    /// Compile the routes of this router and its nested routers
+    ///
+    /// Operations registered without a method and path are resolved from
+    /// the contract and are not part of the table.
+    pub(crate) fn route_table(&self) -> RouteTable {
+        let mut table = RouteTable::default();
+        let mut router = archimedes_router::Router::new();
+        if let Some(prefix) = &self.prefix {
+            router = router.prefix(prefix.as_str());
+        }
+
+        for op in &self.operations {
+            if let Some((method, path)) = &op.route {
+                router.route(method, path, op.operation_id.as_str());
+                table.flags.insert(op.operation_id.clone(), op.flags);
+            }
+        }
+
+        for child in &self.nested {
+            let child_table = child.route_table();
+            match &self.prefix {
+                Some(prefix) => router.nest(prefix, child_table.router),
+                None => router.merge(child_table.router),
+            }
+            table.flags.extend(child_table.flags);
+        }
+
+        table.router = router;
+        table
+    }
 }
 
 /// Create a new router
@@ -199,6 +323,70 @@ pub unsafe extern "C" fn archimedes_router_register(
     state.operations.push(RouteEntry {
         operation_id: op_id,
         user_data,
+        route: None,
+        flags: 0,
+    });
+    0
+}
+
+/// Register an operation with an explicit route and flags
+///
+/// `flags` is a bitwise OR of `ARCHIMEDES_ROUTE_NO_AUTH`,
+/// `ARCHIMEDES_ROUTE_SKIP_REQUEST_VALIDATION` and
+/// `ARCHIMEDES_ROUTE_SKIP_RESPONSE_VALIDATION`, or 0. Flags are enforced by
+/// the authorization and validation stages for requests matching the route.
+///
+/// `path` is relative to the router prefix and may contain `{name}`
+/// parameters and a trailing `*name` wildcard.
+///
+/// # Safety
+///
+/// - `router` must be a valid router pointer
+/// - `method`, `path` and `operation_id` must be valid null-terminated UTF-8 strings
+///
+/// Returns 0 on success, 1 on error.
+#[no_mangle]
+pub unsafe extern "C" fn archimedes_router_register_ex(
+    router: *mut ArchimedesRouter,
+    method: *const c_char,
+    path: *const c_char,
+    operation_id: *const c_char,
+    flags: u32,
+) -> i32 {
+    if router.is_null() {
+        crate::set_last_error("router pointer is null");
+        return 1;
+    }
+    let (Some(method), Some(path), Some(op_id)) = (
+        crate::c_str_to_str(method),
+        crate::c_str_to_str(path),
+        crate::c_str_to_str(operation_id),
+    ) else {
+        crate::set_last_error("method, path and operation_id must be valid UTF-8 strings");
+        return 1;
+    };
+
+    let method = match Method::from_bytes(method.to_ascii_uppercase().as_bytes()) {
+        Ok(m) => m,
+        Err(e) => {
+            crate::set_last_error(format!("Invalid HTTP method '{}': {}", method, e));
+            return 1;
+        }
+    };
+    if flags & !KNOWN_ROUTE_FLAGS != 0 {
+        crate::set_last_error(format!(
+            "Unknown route flags: {:#x}",
+            flags & !KNOWN_ROUTE_FLAGS
+        ));
+        return 1;
+    }
+
+    let state = &mut *(router as *mut RouterState);
+    state.operations.push(RouteEntry {
+        operation_id: op_id.to_string(),
+        user_data: ptr::null_mut(),
+        route: Some((method, normalize_path(path))),
+        flags,
     });
     0
 }
@@ -482,6 +670,101 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_router_register_ex() {
+        unsafe {
+            let router = archimedes_router_new();
+            let method = CString::new("get").unwrap();
+            let path = CString::new("/users/{userId}").unwrap();
+            let op_id = CString::new("getUser").unwrap();
+
+            let result = archimedes_router_register_ex(
+                router,
+                method.as_ptr(),
+                path.as_ptr(),
+                op_id.as_ptr(),
+                ARCHIMEDES_ROUTE_NO_AUTH | ARCHIMEDES_ROUTE_SKIP_RESPONSE_VALIDATION,
+            );
+            assert_eq!(result, 0);
+            assert_eq!(archimedes_router_operation_count(router), 1);
+
+            // Unknown flag bits are rejected
+            let result = archimedes_router_register_ex(
+                router,
+                method.as_ptr(),
+                path.as_ptr(),
+                op_id.as_ptr(),
+                1 << 8,
+            );
+            assert_eq!(result, 1);
+
+            let state = &*(router as *const RouterState);
+            let matched = state
+                .route_table()
+                .resolve(&Method::GET, "/users/42")
+                .unwrap();
+            assert_eq!(matched.operation_id, "getUser");
+            assert_eq!(
+                matched.params,
+                vec![("userId".to_string(), "42".to_string())]
+            );
+            let options = route_options(matched.flags);
+            assert!(options.no_auth);
+            assert!(!options.skip_request_validation);
+            assert!(options.skip_response_validation);
+
+            archimedes_router_free(router);
+        }
+    }
+
+    #[test]
+    fn test_route_table_applies_prefixes() {
+        unsafe {
+            let parent = archimedes_router_new();
+            let child = archimedes_router_new();
+            let api = CString::new("/api").unwrap();
+            let users = CString::new("/users").unwrap();
+            let method = CString::new("GET").unwrap();
+            let root = CString::new("/").unwrap();
+            let by_id = CString::new("/{id}").unwrap();
+            let health = CString::new("health").unwrap();
+            let get_user = CString::new("getUser").unwrap();
+
+            archimedes_router_prefix(parent, api.as_ptr());
+            archimedes_router_prefix(child, users.as_ptr());
+            archimedes_router_register_ex(
+                parent,
+                method.as_ptr(),
+                root.as_ptr(),
+                health.as_ptr(),
+                ARCHIMEDES_ROUTE_NO_AUTH,
+            );
+            archimedes_router_register_ex(
+                child,
+                method.as_ptr(),
+                by_id.as_ptr(),
+                get_user.as_ptr(),
+                0,
+            );
+            // Operations without a route are resolved from the contract
+            archimedes_router_register(child, health.as_ptr(), ptr::null_mut());
+            archimedes_router_nest(parent, child);
+
+            let table = (*(parent as *const RouterState)).route_table();
+            assert_eq!(
+                table.resolve(&Method::GET, "/api").unwrap().operation_id,
+                "health"
+            );
+            let matched = table.resolve(&Method::GET, "/api/users/7").unwrap();
+            assert_eq!(matched.operation_id, "getUser");
+            assert_eq!(matched.flags, 0);
+            assert!(table.resolve(&Method::POST, "/api/users/7").is_none());
+            assert!(table.resolve(&Method::GET, "/users/7").is_none());
+
+            archimedes_router_free(parent);
+        }
+    }
+
     #[test]
     fn test_null_safety() {
         unsafe {
@@ -495,6 +778,16 @@ mod tests {
                 archimedes_router_register(ptr::null_mut(), ptr::null(), ptr::null_mut()),
                 1
             );
+            assert_eq!(
+                archimedes_router_register_ex(
+                    ptr::null_mut(),
+                    ptr::null(),
+                    ptr::null(),
+                    ptr::null(),
+                    0
+                ),
+                1
+            );
             assert_eq!(archimedes_router_nest(ptr::null_mut(), ptr::null_mut()), 1);
             assert_eq!(archimedes_router_merge(ptr::null_mut(), ptr::null()), 1);
 
@@ -3,6 +3,8 @@
 //! This module provides C ABI functions for testing Archimedes applications
 //! without starting a real HTTP server.
 
+use crate::app::{AppState, ArchimedesApp};
+use http_body_util::{BodyExt, Full};
 use std::collections::HashMap;
 use std::ffi::{c_char, CStr, CString};
 use std::ptr;
@@ -12,6 +14,8 @@ use std::ptr;
 pub struct ArchimedesTestClient {
     default_headers: HashMap<String, String>,
     base_url: String,
+    /// Application requests are dispatched to, if any
+    app: *const AppState,
 }
 
 /// Opaque test response handle.
@@ -47,10 +51,32 @@ pub unsafe extern "C" fn archimedes_test_client_new(
     let client = Box::new(ArchimedesTestClient {
         default_headers: HashMap::new(),
         base_url,
+        app: ptr::null(),
     });
     Box::into_raw(client)
 }
 
+/// Creates a test client that dispatches requests to an application.
+///
+/// Requests run through the application's routes, middleware pipeline and
+/// registered handlers without starting a server.
+///
+/// # Safety
+/// - `app` must be a valid application pointer that outlives the client.
+/// - Caller must free the returned handle with `archimedes_test_client_free`.
+#[no_mangle]
+pub unsafe extern "C" fn archimedes_test_client_for_app(
+    app: *const ArchimedesApp,
+) -> *mut ArchimedesTestClient {
+    if app.is_null() {
+        return ptr::null_mut();
+    }
+
+    let client = archimedes_test_client_new(ptr::null());
+    (*client).app = app.cast::<AppState>();
+    client
+}
+
 /// Frees a test client.
 ///
 /// # Safety
@@ -245,7 +271,7 @@ pub unsafe extern "C" fn archimedes_test_client_request(
     }
 
     let client = &*client;
-    let _method = match CStr::from_ptr(method).to_str() {
+    let method = match CStr::from_ptr(method).to_str() {
         Ok(s) => s.to_string(),
         Err(_) => return ptr::null_mut(),
     };
@@ -255,7 +281,7 @@ pub unsafe extern "C" fn archimedes_test_client_request(
     };
 
     // Build full URL
-    let _url = if path.starts_with("http://") || path.starts_with("https://") {
+    let url = if path.starts_with("http://") || path.starts_with("https://") {
         path
     } else {
         format!("{}{}", client.base_url, path)
@@ -268,6 +294,16 @@ pub unsafe extern "C" fn archimedes_test_client_request(
         Some(std::slice::from_raw_parts(body, body_len).to_vec())
     };
 
+    if !client.app.is_null() {
+        return dispatch_to_app(
+            &*client.app,
+            client,
+            &method,
+            &url,
+            body_bytes.unwrap_or_default(),
+        );
+    }
+
     // For now, create a mock response
     let response = Box::new(ArchimedesTestResponse {
         status_code: 200,
@@ -277,6 +313,47 @@ pub unsafe extern "C" fn archimedes_test_client_request(
     Box::into_raw(response)
 }
 
+/// Runs a request through an application and captures the response.
+fn dispatch_to_app(
+    state: &AppState,
+    client: &ArchimedesTestClient,
+    method: &str,
+    url: &str,
+    body: Vec<u8>,
+) -> *mut ArchimedesTestResponse {
+    let mut request = http::Request::builder().method(method).uri(url);
+    for (name, value) in &client.default_headers {
+        request = request.header(name, value);
+    }
+    let Ok(request) = request.body(Full::new(body.into())) else {
+        return ptr::null_mut();
+    };
+
+    let (status_code, headers, body) = crate::runtime::block_on(async {
+        let response = crate::dispatch::dispatch(state, request).await;
+        let (parts, body) = response.into_parts();
+        let body = body
+            .collect()
+            .await
+            .map(|b| b.to_bytes())
+            .unwrap_or_default();
+        (parts.status.as_u16(), parts.headers, body)
+    });
+
+    let headers = headers
+        .iter()
+        .filter_map(|(name, value)| {
+            Some((name.as_str().to_string(), value.to_str().ok()?.to_string()))
+        })
+        .collect();
+
+    Box::into_raw(Box::new(ArchimedesTestResponse {
+        status_code,
+        headers,
+        body: body.to_vec(),
+    }))
+}
+
 // ============================================================================
 // TestResponse Functions
 // ============================================================================
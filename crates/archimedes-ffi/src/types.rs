@@ -93,7 +93,7 @@ pub struct ArchimedesRequestContext {
     pub path: *const c_char,
     /// Query string (without leading ?)
     pub query: *const c_char,
-    /// JSON-encoded caller identity
+    /// JSON-encoded caller identity (granted scopes under `scopes`)
     pub caller_identity_json: *const c_char,
     /// Number of path parameters
     pub path_params_count: usize,
@@ -101,6 +101,12 @@ pub struct ArchimedesRequestContext {
     pub path_param_names: *const *const c_char,
     /// Path parameter values (array of C strings)
     pub path_param_values: *const *const c_char,
+    /// Number of decoded query parameters
+    pub query_params_count: usize,
+    /// Query parameter names (array of C strings)
+    pub query_param_names: *const *const c_char,
+    /// Query parameter values (array of C strings)
+    pub query_param_values: *const *const c_char,
     /// Number of headers
     pub headers_count: usize,
     /// Header names (array of C strings)
@@ -116,9 +116,14 @@ fn generate_handler_code(attrs: &HandlerAttrs, handler: &HandlerFn) -> syn::Resu
                     // Call the handler
                     let result = #fn_name(#call_args).await;
 
-                    // Convert result to response
-                    archimedes_core::handler::into_response(result)
-                }) as std::pin::Pin<Box<dyn std::future::Future<Output = Result<bytes::Bytes, archimedes_core::ThemisError>> + Send>>
+                    // Convert result to response: serializable results stay
+                    // JSON, anything else goes through `IntoResponse`
+                    #[allow(unused_imports)]
+                    use archimedes_core::response::__private::{
+                        HandlerReturn, ViaIntoResponse, ViaResult, ViaSerialize,
+                    };
+                    (&&&HandlerReturn::new(result)).into_handler_response()
+                }) as archimedes_core::handler::BoxedFuture<archimedes_core::handler::HandlerOutput>
             };
 
             register(
@@ -133,14 +138,18 @@ fn generate_handler_code(attrs: &HandlerAttrs, handler: &HandlerFn) -> syn::Resu
 
 /// Generates extraction code for handler parameters.
 ///
+/// Every extractor runs even after one fails, so a request with several
+/// problems gets a single error listing all of them.
+///
 /// Returns a tuple of:
 /// - Token stream for extraction bindings (let statements)
 /// - Token stream for call arguments
 fn generate_extractions(params: &[HandlerParam]) -> (TokenStream, TokenStream) {
+    let mut extractions = Vec::new();
     let mut bindings = Vec::new();
     let mut call_args = Vec::new();
 
-    for param in params {
+    for (index, param) in params.iter().enumerate() {
         let name = &param.name;
         let ty = &param.ty;
         let pattern = &param.pattern;
@@ -152,10 +161,18 @@ fn generate_extractions(params: &[HandlerParam]) -> (TokenStream, TokenStream) {
                     .map_err(|e| archimedes_core::ThemisError::validation(e.to_string()))?;
             });
         } else {
-            // For regular extractors, use FromRequest
+            // For regular extractors, use FromRequest and collect failures
+            let extracted = format_ident!("__archimedes_extracted_{}", index);
+            extractions.push(quote! {
+                let #extracted = <#ty as archimedes_extract::FromRequest>::from_request(&extraction_ctx)
+                    .map_err(|e| extraction_errors.push(e))
+                    .ok();
+            });
             bindings.push(quote! {
-                let #pattern: #ty = <#ty as archimedes_extract::FromRequest>::from_request(&extraction_ctx)
-                    .map_err(|e| archimedes_core::ThemisError::validation(e.to_string()))?;
+                let #pattern: #ty = match #extracted {
+                    Some(value) => value,
+                    None => unreachable!("extraction failures are returned above"),
+                };
             });
         }
 
@@ -163,7 +180,18 @@ fn generate_extractions(params: &[HandlerParam]) -> (TokenStream, TokenStream) {
         call_args.push(quote! { #name });
     }
 
-    let bindings_stream = quote! { #(#bindings)* };
+    let bindings_stream = if extractions.is_empty() {
+        quote! { #(#bindings)* }
+    } else {
+        quote! {
+            let mut extraction_errors: Vec<archimedes_extract::ExtractionError> = Vec::new();
+            #(#extractions)*
+            if let Some(error) = archimedes_extract::ExtractionError::aggregate(extraction_errors) {
+                return Err(archimedes_core::ThemisError::from(error));
+            }
+            #(#bindings)*
+        }
+    };
     let call_args_stream = if call_args.is_empty() {
         quote! {}
     } else {
@@ -203,6 +231,35 @@ mod tests {
         assert!(result.is_ok(), "expansion failed: {:?}", result.err());
     }
 
+    #[test]
+    fn test_expand_handler_returning_into_response() {
+        let attr: TokenStream = quote! { operation = "createUser" };
+        let item: TokenStream = quote! {
+            async fn create_user(body: Json<CreateUserRequest>) -> Result<(StatusCode, Json<User>), Error> {
+                Ok((StatusCode::CREATED, Json(User::default())))
+            }
+        };
+
+        let expanded = expand_handler(attr, item).unwrap().to_string();
+        assert!(expanded.contains("HandlerReturn :: new (result)"));
+        assert!(expanded.contains("into_handler_response ()"));
+        assert!(!expanded.contains("handler :: into_response"));
+    }
+
+    #[test]
+    fn test_expand_handler_with_const_operation() {
+        let attr: TokenStream = quote! { operation = ops::GET_USER };
+        let item: TokenStream = quote! {
+            async fn get_user() -> Result<(), Error> {
+                Ok(())
+            }
+        };
+
+        let expanded = expand_handler(attr, item).unwrap().to_string();
+        assert!(expanded.contains("{ ops :: GET_USER }"));
+        assert!(expanded.contains("register (ops :: GET_USER ,"));
+    }
+
     #[test]
     fn test_expand_handler_missing_operation() {
         let attr: TokenStream = quote! {};
@@ -56,7 +56,9 @@ use proc_macro::TokenStream;
 ///
 /// # Attributes
 ///
-/// - `operation`: The operation ID from the contract (required)
+/// - `operation`: The operation ID from the contract (required). Either a
+///   string literal or a `&'static str` constant, such as one generated by
+///   `archimedes_sentinel::codegen`, so a typo fails the build
 /// - `method`: HTTP method override (optional, defaults to contract)
 /// - `path`: Path override (optional, defaults to contract)
 ///
@@ -16,7 +16,10 @@ use syn::{
 #[derive(Debug)]
 pub struct HandlerAttrs {
     /// The operation ID from the contract.
-    pub operation: String,
+    ///
+    /// Either a string literal or a constant expression such as a generated
+    /// `ops::GET_USER`, so a misspelled constant fails to compile.
+    pub operation: Expr,
     /// Optional HTTP method override.
     pub method: Option<String>,
     /// Optional path override.
@@ -40,6 +43,11 @@ impl Parse for HandlerAttrs {
                         .ok_or_else(|| syn::Error::new(nv.path.span(), "expected identifier"))?
                         .to_string();
 
+                    if ident == "operation" {
+                        operation = Some(nv.value);
+                        continue;
+                    }
+
                     let value = match &nv.value {
                         Expr::Lit(ExprLit {
                             lit: Lit::Str(s), ..
@@ -50,7 +58,6 @@ impl Parse for HandlerAttrs {
                     };
 
                     match ident.as_str() {
-                        "operation" => operation = Some(value),
                         "method" => method = Some(value),
                         "path" => path = Some(value),
                         _ => {
@@ -236,12 +243,17 @@ impl HandlerFn {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use quote::ToTokens;
     use syn::parse_quote;
 
+    fn operation(attrs: &HandlerAttrs) -> String {
+        attrs.operation.to_token_stream().to_string()
+    }
+
     #[test]
     fn test_parse_handler_attrs() {
         let attrs: HandlerAttrs = syn::parse_quote!(operation = "getUser");
-        assert_eq!(attrs.operation, "getUser");
+        assert_eq!(operation(&attrs), "\"getUser\"");
         assert!(attrs.method.is_none());
         assert!(attrs.path.is_none());
     }
@@ -250,11 +262,21 @@ mod tests {
     fn test_parse_handler_attrs_with_all() {
         let attrs: HandlerAttrs =
             syn::parse_quote!(operation = "createUser", method = "POST", path = "/users");
-        assert_eq!(attrs.operation, "createUser");
+        assert_eq!(operation(&attrs), "\"createUser\"");
         assert_eq!(attrs.method, Some("POST".to_string()));
         assert_eq!(attrs.path, Some("/users".to_string()));
     }
 
+    #[test]
+    fn test_parse_handler_attrs_with_const_operation() {
+        let attrs: HandlerAttrs = syn::parse_quote!(operation = ops::GET_USER, method = "GET");
+        assert_eq!(operation(&attrs), "ops :: GET_USER");
+        assert_eq!(attrs.method, Some("GET".to_string()));
+
+        let err = syn::parse_str::<HandlerAttrs>("operation = ops::GET_USER, method = GET");
+        assert!(err.is_err());
+    }
+
     #[test]
     fn test_parse_handler_fn() {
         let item: ItemFn = parse_quote! {
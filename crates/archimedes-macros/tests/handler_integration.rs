@@ -304,3 +304,71 @@ async fn test_full_handler_workflow() {
     assert_eq!(user.name, "Charlie");
     assert_eq!(user.email, "charlie@example.com");
 }
+
+/// Test that failures from every extractor are reported together.
+#[tokio::test]
+async fn test_handler_reports_all_extraction_failures() {
+    use archimedes_extract::{ExtractionError, Path, Query};
+
+    #[derive(Debug, Deserialize)]
+    struct UserPath {
+        user_id: u64,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct ListParams {
+        limit: Option<u32>,
+    }
+
+    let mut params = Params::new();
+    params.push("user_id", "abc");
+
+    let ctx = InvocationContext::new(
+        Method::PUT,
+        Uri::from_static("/users/abc?limit=lots"),
+        HeaderMap::new(),
+        Bytes::from(r#"{"name":"Dana"}"#),
+        params,
+    );
+
+    // Simulate the extraction the macro generates
+    let handler = |ctx: InvocationContext| {
+        Box::pin(async move {
+            let extraction_ctx = ExtractionContext::from_invocation(&ctx);
+
+            let mut extraction_errors: Vec<ExtractionError> = Vec::new();
+            let path = Path::<UserPath>::from_request(&extraction_ctx)
+                .map_err(|e| extraction_errors.push(e))
+                .ok();
+            let query = Query::<ListParams>::from_request(&extraction_ctx)
+                .map_err(|e| extraction_errors.push(e))
+                .ok();
+            let body = Json::<CreateUserRequest>::from_request(&extraction_ctx)
+                .map_err(|e| extraction_errors.push(e))
+                .ok();
+            if let Some(error) = ExtractionError::aggregate(extraction_errors) {
+                return Err(ThemisError::from(error));
+            }
+
+            let (Some(path), Some(_query), Some(body)) = (path, query, body) else {
+                unreachable!("extraction failures are returned above");
+            };
+            Ok(User {
+                id: path.0.user_id,
+                name: body.0.name,
+                email: body.0.email,
+            })
+        })
+    };
+
+    let error = handler(ctx).await.unwrap_err();
+    assert_eq!(error.status_code(), http::StatusCode::BAD_REQUEST);
+
+    let envelope = error.to_envelope(None);
+    let details = envelope.error.details.unwrap();
+    let fields = details["fields"].as_object().unwrap();
+    let mut names: Vec<_> = fields.keys().map(String::as_str).collect();
+    names.sort_unstable();
+    assert_eq!(names, ["body.email", "path.user_id", "query.limit"]);
+    assert_eq!(fields["body.email"][0], "missing required value");
+}
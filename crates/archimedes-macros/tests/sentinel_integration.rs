@@ -12,7 +12,7 @@ use archimedes_core::di::Container;
 use archimedes_core::{InvocationContext, ThemisError};
 use archimedes_extract::{ExtractionContext, FromRequest, Inject, Json, Path, Query};
 use archimedes_router::Params;
-use archimedes_sentinel::{LoadedArtifact, LoadedOperation, Sentinel};
+use archimedes_sentinel::{LoadedArtifact, LoadedOperation, Sentinel, JSON_MEDIA_TYPE};
 use bytes::Bytes;
 use http::{HeaderMap, Method, Uri};
 use indexmap::IndexMap;
@@ -44,16 +44,26 @@ fn create_user_service_artifact() -> LoadedArtifact {
                 response_schemas: {
                     let mut m = HashMap::new();
                     m.insert(
-                        "200".to_string(),
+                        ("200".to_string(), JSON_MEDIA_TYPE.to_string()),
                         SchemaRef {
                             reference: "#/components/schemas/UserList".to_string(),
                             schema_type: "array".to_string(),
                             required: vec![],
+                            properties: HashMap::new(),
+                            property_schemas: HashMap::new(),
+                            required_scopes: HashMap::new(),
+                            items: None,
+                            additional_properties: None,
                         },
                     );
                     m
                 },
                 tags: vec!["users".to_string()],
+                header_params: Vec::new(),
+                event_schemas: HashMap::new(),
+                timeout: None,
+                cache_policy: None,
+                allow_reserved: Vec::new(),
             },
             LoadedOperation {
                 id: "getUser".to_string(),
@@ -66,16 +76,26 @@ fn create_user_service_artifact() -> LoadedArtifact {
                 response_schemas: {
                     let mut m = HashMap::new();
                     m.insert(
-                        "200".to_string(),
+                        ("200".to_string(), JSON_MEDIA_TYPE.to_string()),
                         SchemaRef {
                             reference: "#/components/schemas/User".to_string(),
                             schema_type: "object".to_string(),
                             required: vec!["id".to_string(), "email".to_string()],
+                            properties: HashMap::new(),
+                            property_schemas: HashMap::new(),
+                            required_scopes: HashMap::new(),
+                            items: None,
+                            additional_properties: None,
                         },
                     );
                     m
                 },
                 tags: vec!["users".to_string()],
+                header_params: Vec::new(),
+                event_schemas: HashMap::new(),
+                timeout: None,
+                cache_policy: None,
+                allow_reserved: Vec::new(),
             },
             LoadedOperation {
                 id: "createUser".to_string(),
@@ -88,20 +108,35 @@ fn create_user_service_artifact() -> LoadedArtifact {
                     reference: "#/components/schemas/CreateUserRequest".to_string(),
                     schema_type: "object".to_string(),
                     required: vec!["name".to_string(), "email".to_string()],
+                    properties: HashMap::new(),
+                    property_schemas: HashMap::new(),
+                    required_scopes: HashMap::new(),
+                    items: None,
+                    additional_properties: None,
                 }),
                 response_schemas: {
                     let mut m = HashMap::new();
                     m.insert(
-                        "201".to_string(),
+                        ("201".to_string(), JSON_MEDIA_TYPE.to_string()),
                         SchemaRef {
                             reference: "#/components/schemas/User".to_string(),
                             schema_type: "object".to_string(),
                             required: vec!["id".to_string(), "email".to_string()],
+                            properties: HashMap::new(),
+                            property_schemas: HashMap::new(),
+                            required_scopes: HashMap::new(),
+                            items: None,
+                            additional_properties: None,
                         },
                     );
                     m
                 },
                 tags: vec!["users".to_string()],
+                header_params: Vec::new(),
+                event_schemas: HashMap::new(),
+                timeout: None,
+                cache_policy: None,
+                allow_reserved: Vec::new(),
             },
             LoadedOperation {
                 id: "updateUser".to_string(),
@@ -114,20 +149,35 @@ fn create_user_service_artifact() -> LoadedArtifact {
                     reference: "#/components/schemas/UpdateUserRequest".to_string(),
                     schema_type: "object".to_string(),
                     required: vec![],
+                    properties: HashMap::new(),
+                    property_schemas: HashMap::new(),
+                    required_scopes: HashMap::new(),
+                    items: None,
+                    additional_properties: None,
                 }),
                 response_schemas: {
                     let mut m = HashMap::new();
                     m.insert(
-                        "200".to_string(),
+                        ("200".to_string(), JSON_MEDIA_TYPE.to_string()),
                         SchemaRef {
                             reference: "#/components/schemas/User".to_string(),
                             schema_type: "object".to_string(),
                             required: vec!["id".to_string(), "email".to_string()],
+                            properties: HashMap::new(),
+                            property_schemas: HashMap::new(),
+                            required_scopes: HashMap::new(),
+                            items: None,
+                            additional_properties: None,
                         },
                     );
                     m
                 },
                 tags: vec!["users".to_string()],
+                header_params: Vec::new(),
+                event_schemas: HashMap::new(),
+                timeout: None,
+                cache_policy: None,
+                allow_reserved: Vec::new(),
             },
             LoadedOperation {
                 id: "deleteUser".to_string(),
@@ -140,19 +190,30 @@ fn create_user_service_artifact() -> LoadedArtifact {
                 response_schemas: {
                     let mut m = HashMap::new();
                     m.insert(
-                        "204".to_string(),
+                        ("204".to_string(), JSON_MEDIA_TYPE.to_string()),
                         SchemaRef {
                             reference: "".to_string(),
                             schema_type: "null".to_string(),
                             required: vec![],
+                            properties: HashMap::new(),
+                            property_schemas: HashMap::new(),
+                            required_scopes: HashMap::new(),
+                            items: None,
+                            additional_properties: None,
                         },
                     );
                     m
                 },
                 tags: vec!["users".to_string()],
+                header_params: Vec::new(),
+                event_schemas: HashMap::new(),
+                timeout: None,
+                cache_policy: None,
+                allow_reserved: Vec::new(),
             },
         ],
         schemas: IndexMap::new(),
+        stats: Default::default(),
     }
 }
 
@@ -470,9 +531,8 @@ async fn test_list_users_handler_with_query_params() {
 #[tokio::test]
 async fn test_handler_binder_with_sentinel() {
     use archimedes_core::binder::HandlerBinder;
-    use archimedes_core::handler::BoxedHandler;
-    use std::future::Future;
-    use std::pin::Pin;
+    use archimedes_core::handler::{BoxedFuture, BoxedHandler, HandlerOutput};
+    use archimedes_core::IntoResponse;
 
     let artifact = create_user_service_artifact();
 
@@ -488,8 +548,8 @@ async fn test_handler_binder_with_sentinel() {
 
     // Helper to create a mock BoxedHandler
     fn mock_handler() -> BoxedHandler {
-        Box::new(|_ctx: InvocationContext| -> Pin<Box<dyn Future<Output = Result<Bytes, ThemisError>> + Send>> {
-            Box::pin(async move { Ok(Bytes::from("ok")) })
+        Box::new(|_ctx: InvocationContext| -> BoxedFuture<HandlerOutput> {
+            Box::pin(async move { Ok("ok".into_response()) })
         })
     }
 
@@ -619,3 +679,86 @@ async fn test_deprecated_operation() {
         .unwrap();
     assert!(operation.deprecated);
 }
+
+/// Test that Sentinel and the router report identical path parameter keys
+/// for mixed-case contract parameters, and both bind to snake_case fields.
+#[tokio::test]
+async fn test_mixed_case_params_agree_between_sentinel_and_router() {
+    use archimedes_router::{MethodRouter, Router};
+
+    let operation = |id: &str, path: &str| LoadedOperation {
+        id: id.to_string(),
+        method: "GET".to_string(),
+        path: path.to_string(),
+        summary: None,
+        deprecated: false,
+        security: vec![],
+        request_schema: None,
+        response_schemas: HashMap::new(),
+        tags: vec![],
+        header_params: Vec::new(),
+        event_schemas: HashMap::new(),
+        timeout: None,
+        cache_policy: None,
+        allow_reserved: Vec::new(),
+    };
+    let artifact = LoadedArtifact {
+        service: "org-service".to_string(),
+        version: "1.0.0".to_string(),
+        format: "openapi".to_string(),
+        operations: vec![
+            operation("getOrg", "/orgs/{orgId}"),
+            operation("getMember", "/orgs/{OrgID}/members/{member_id}"),
+        ],
+        schemas: IndexMap::new(),
+        stats: Default::default(),
+    };
+
+    let mut router = Router::new();
+    for op in &artifact.operations {
+        router.insert(&op.path, MethodRouter::new().get(&op.id));
+    }
+    let sentinel = Sentinel::with_defaults(artifact);
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct MemberPath {
+        org_id: String,
+        member_id: u64,
+    }
+
+    for (path, operation_id) in [
+        ("/orgs/acme", "getOrg"),
+        ("/orgs/acme/members/7", "getMember"),
+    ] {
+        let resolution = sentinel.resolve("GET", path).unwrap();
+        let route_match = router.match_route(&Method::GET, path).unwrap();
+        assert_eq!(resolution.operation_id, operation_id);
+        assert_eq!(route_match.operation_id, operation_id);
+
+        let router_params: HashMap<String, String> = route_match
+            .params
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        assert_eq!(router_params, resolution.path_params, "{path}");
+    }
+
+    let route_match = router
+        .match_route(&Method::GET, "/orgs/acme/members/7")
+        .unwrap();
+    let ctx = ExtractionContext::new(
+        Method::GET,
+        Uri::from_static("/orgs/acme/members/7"),
+        HeaderMap::new(),
+        Bytes::new(),
+        route_match.params,
+    );
+    let Path(member) = Path::<MemberPath>::from_request(&ctx).unwrap();
+    assert_eq!(
+        member,
+        MemberPath {
+            org_id: "acme".to_string(),
+            member_id: 7,
+        }
+    );
+}
@@ -4,7 +4,7 @@
 //! It is separate from [`RequestContext`] to allow middleware to modify
 //! context before the final context is passed to handlers.
 
-use archimedes_core::{CallerIdentity, RequestId};
+use archimedes_core::{CallerIdentity, RequestId, UrlForError, UrlGenerator};
 use http::{HeaderMap, Method};
 use std::any::{Any, TypeId};
 use std::collections::HashMap;
@@ -45,6 +45,12 @@ pub struct MiddlewareContext {
     /// The resolved operation ID from the contract.
     operation_id: Option<String>,
 
+    /// The tenant the request was made on behalf of.
+    tenant_id: Option<String>,
+
+    /// The locale negotiated from `Accept-Language`.
+    locale: Option<String>,
+
     /// The HTTP method of the request.
     method: Method,
 
@@ -57,6 +63,9 @@ pub struct MiddlewareContext {
     /// The service name (for policy evaluation).
     service_name: Option<String>,
 
+    /// Generator for URLs of other operations.
+    url_generator: Option<UrlGenerator>,
+
     /// When the request started processing.
     started_at: Instant,
 
@@ -76,10 +85,13 @@ impl MiddlewareContext {
             trace_id: None,
             span_id: None,
             operation_id: None,
+            tenant_id: None,
+            locale: None,
             method: Method::GET,
             path: String::new(),
             headers: None,
             service_name: None,
+            url_generator: None,
             started_at: Instant::now(),
             extensions: HashMap::new(),
         }
@@ -96,10 +108,13 @@ impl MiddlewareContext {
             trace_id: None,
             span_id: None,
             operation_id: None,
+            tenant_id: None,
+            locale: None,
             method: Method::GET,
             path: String::new(),
             headers: None,
             service_name: None,
+            url_generator: None,
             started_at: Instant::now(),
             extensions: HashMap::new(),
         }
@@ -116,10 +131,13 @@ impl MiddlewareContext {
             trace_id: None,
             span_id: None,
             operation_id: None,
+            tenant_id: None,
+            locale: None,
             method,
             path,
             headers: Some(headers),
             service_name: None,
+            url_generator: None,
             started_at: Instant::now(),
             extensions: HashMap::new(),
         }
@@ -234,6 +252,69 @@ impl MiddlewareContext {
         self.operation_id = Some(operation_id);
     }
 
+    /// Returns the tenant ID, if resolved.
+    #[must_use]
+    pub fn tenant_id(&self) -> Option<&str> {
+        self.tenant_id.as_deref()
+    }
+
+    /// Sets the tenant ID.
+    ///
+    /// This is set by the tenant policy stage after identity extraction.
+    pub fn set_tenant_id(&mut self, tenant_id: String) {
+        self.tenant_id = Some(tenant_id);
+    }
+
+    /// Returns the locale negotiated for the request, if any.
+    #[must_use]
+    pub fn locale(&self) -> Option<&str> {
+        self.locale.as_deref()
+    }
+
+    /// Sets the locale negotiated for the request.
+    ///
+    /// This is set by the error normalization stage when it has a message
+    /// catalog.
+    pub fn set_locale(&mut self, locale: String) {
+        self.locale = Some(locale);
+    }
+
+    /// Sets the generator used by [`url_for`](Self::url_for).
+    pub fn set_url_generator(&mut self, url_generator: UrlGenerator) {
+        self.url_generator = Some(url_generator);
+    }
+
+    /// Builds the URL of another operation, including the server base path.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UrlForError::Unavailable`] if no URL generator was set, or
+    /// the router's error if the URL cannot be built.
+    pub fn url_for(
+        &self,
+        operation_id: &str,
+        params: &[(&str, &str)],
+    ) -> Result<String, UrlForError> {
+        self.url_for_with_query(operation_id, params, &[])
+    }
+
+    /// Builds the URL of another operation with query parameters appended.
+    ///
+    /// # Errors
+    ///
+    /// See [`url_for`](Self::url_for).
+    pub fn url_for_with_query(
+        &self,
+        operation_id: &str,
+        params: &[(&str, &str)],
+        query: &[(&str, &str)],
+    ) -> Result<String, UrlForError> {
+        self.url_generator
+            .as_ref()
+            .ok_or(UrlForError::Unavailable)?
+            .url_for_with_query(operation_id, params, query)
+    }
+
     /// Returns when the request started processing.
     #[must_use]
     pub fn started_at(&self) -> Instant {
@@ -281,6 +362,15 @@ impl MiddlewareContext {
             .and_then(|v| v.downcast_ref())
     }
 
+    /// Retrieves a mutable reference to a typed extension value.
+    ///
+    /// Returns `None` if no extension of the given type was stored.
+    pub fn get_extension_mut<T: Send + Sync + 'static>(&mut self) -> Option<&mut T> {
+        self.extensions
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|v| v.downcast_mut())
+    }
+
     /// Removes and returns a typed extension value.
     pub fn remove_extension<T: Send + Sync + 'static>(&mut self) -> Option<T> {
         self.extensions
@@ -295,6 +385,53 @@ impl MiddlewareContext {
         self.extensions.contains_key(&TypeId::of::<T>())
     }
 
+    /// Returns `true` if the request targets an internal endpoint.
+    ///
+    /// Internal endpoints are marked with [`RouteOptions::internal`] when
+    /// the route is resolved.
+    #[must_use]
+    pub fn is_internal(&self) -> bool {
+        self.get_extension::<RouteOptions>()
+            .is_some_and(|options| options.internal)
+    }
+
+    /// Returns the raw request body, if it was retained.
+    ///
+    /// The body is retained by [`RawBodyMiddleware`] for operations that
+    /// opt in; it is the request as received, before validation or
+    /// coercion rewrote it.
+    ///
+    /// [`RawBodyMiddleware`]: crate::stages::signing::RawBodyMiddleware
+    #[must_use]
+    pub fn raw_body(&self) -> Option<&bytes::Bytes> {
+        self.get_extension::<crate::stages::signing::RawBody>()
+            .map(|raw| &raw.0)
+    }
+
+    /// Returns the contract resolution of the request, if Sentinel
+    /// resolved it.
+    ///
+    /// The request is resolved once, by request validation or by the
+    /// caller before the pipeline runs; later stages read it here instead
+    /// of resolving again.
+    #[cfg(feature = "sentinel")]
+    #[must_use]
+    pub fn operation_resolution(&self) -> Option<&archimedes_sentinel::OperationResolution> {
+        self.get_extension::<archimedes_sentinel::OperationResolution>()
+    }
+
+    /// Records the contract resolution of the request, along with its
+    /// operation ID and [`RoutePattern`].
+    #[cfg(feature = "sentinel")]
+    pub fn set_operation_resolution(
+        &mut self,
+        resolution: archimedes_sentinel::OperationResolution,
+    ) {
+        self.set_operation_id(resolution.operation_id.clone());
+        self.set_extension(RoutePattern(resolution.route_pattern.clone()));
+        self.set_extension(resolution);
+    }
+
     /// Converts this middleware context to a [`RequestContext`].
     ///
     /// This is called after all pre-handler middleware has run, before
@@ -316,6 +453,18 @@ impl MiddlewareContext {
             ctx = ctx.with_operation_id(op_id.clone());
         }
 
+        if let Some(tenant_id) = &self.tenant_id {
+            ctx = ctx.with_tenant_id(tenant_id.clone());
+        }
+
+        if let Some(locale) = &self.locale {
+            ctx = ctx.with_locale(locale.clone());
+        }
+
+        if let Some(url_generator) = &self.url_generator {
+            ctx = ctx.with_url_generator(url_generator.clone());
+        }
+
         ctx
     }
 }
@@ -335,16 +484,92 @@ impl Clone for MiddlewareContext {
             trace_id: self.trace_id.clone(),
             span_id: self.span_id.clone(),
             operation_id: self.operation_id.clone(),
+            tenant_id: self.tenant_id.clone(),
+            locale: self.locale.clone(),
             method: self.method.clone(),
             path: self.path.clone(),
             headers: self.headers.clone(),
             service_name: self.service_name.clone(),
+            url_generator: self.url_generator.clone(),
             started_at: self.started_at,
             extensions: HashMap::new(),
         }
     }
 }
 
+/// Marker extension for sub-requests dispatched from a batch request.
+///
+/// Set by the server before a batched sub-request enters the pipeline so
+/// telemetry can attribute it to its own operation with `batched = true`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BatchedRequest;
+
+/// The app a request was dispatched to, when one server hosts several.
+///
+/// Set by the server before the request enters the app's pipeline so
+/// telemetry can label requests by app.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppName(pub String);
+
+/// The contract version a request was resolved against.
+///
+/// Set by request validation when the contract serves several versions, so
+/// response validation uses the matching schemas and telemetry can label
+/// the request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractVersion(pub String);
+
+/// The route pattern a request matched, such as `/users/{userId}`.
+///
+/// Set when the operation is resolved so telemetry can label requests by
+/// route without the cardinality of raw paths.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoutePattern(pub String);
+
+/// Per-route overrides for the built-in pipeline stages.
+///
+/// Set before the request enters the pipeline by routers that let callers
+/// opt individual routes out of stages, such as the FFI router. When the
+/// extension is absent every stage runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RouteOptions {
+    /// Skip authorization; the route is reachable by anonymous callers.
+    pub no_auth: bool,
+    /// Skip request validation.
+    pub skip_request_validation: bool,
+    /// Skip response validation.
+    pub skip_response_validation: bool,
+    /// Skip rate limiting.
+    pub skip_rate_limit: bool,
+    /// The route is an internal endpoint such as `/health` or `/metrics`
+    /// rather than a contract operation.
+    pub internal: bool,
+    /// Keep the raw request body on the context as [`RawBody`], for
+    /// signature verification.
+    ///
+    /// [`RawBody`]: crate::stages::signing::RawBody
+    pub retain_raw_body: bool,
+}
+
+impl RouteOptions {
+    /// Options for internal endpoints.
+    ///
+    /// Skips authorization, request and response validation and rate
+    /// limiting. Request ID, tracing, telemetry and error normalization
+    /// still run.
+    #[must_use]
+    pub const fn internal() -> Self {
+        Self {
+            no_auth: true,
+            skip_request_validation: true,
+            skip_response_validation: true,
+            skip_rate_limit: true,
+            internal: true,
+            retain_raw_body: false,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -440,11 +665,81 @@ mod tests {
         ctx.set_trace_id("trace-123".to_string());
         ctx.set_span_id("span-456".to_string());
         ctx.set_operation_id("createUser".to_string());
+        ctx.set_tenant_id("acme".to_string());
+        ctx.set_locale("de".to_string());
 
         let req_ctx = ctx.to_request_context();
         assert_eq!(req_ctx.request_id(), *ctx.request_id());
         assert_eq!(req_ctx.trace_id(), Some("trace-123"));
         assert_eq!(req_ctx.span_id(), Some("span-456"));
         assert_eq!(req_ctx.operation_id(), Some("createUser"));
+        assert_eq!(req_ctx.tenant_id(), Some("acme"));
+        assert_eq!(req_ctx.locale(), Some("de"));
+    }
+
+    #[test]
+    fn test_url_for_passes_to_request_context() {
+        use archimedes_router::{MethodRouter, Router};
+
+        let mut ctx = MiddlewareContext::new();
+        assert_eq!(ctx.url_for("getUser", &[]), Err(UrlForError::Unavailable));
+
+        let mut router = Router::new();
+        router.insert("/users/{id}", MethodRouter::new().get("getUser"));
+        ctx.set_url_generator(UrlGenerator::new(router).base_path("/api"));
+
+        assert_eq!(
+            ctx.url_for_with_query("getUser", &[("id", "1")], &[("tab", "posts")])
+                .unwrap(),
+            "/api/users/1?tab=posts"
+        );
+        let req_ctx = ctx.to_request_context();
+        assert_eq!(
+            req_ctx.url_for("getUser", &[("id", "1")]).unwrap(),
+            "/api/users/1"
+        );
+    }
+
+    #[test]
+    fn test_batched_request_marker() {
+        let mut ctx = MiddlewareContext::new();
+        assert!(!ctx.has_extension::<BatchedRequest>());
+
+        ctx.set_extension(BatchedRequest);
+        assert!(ctx.has_extension::<BatchedRequest>());
+    }
+
+    #[test]
+    fn test_contract_version_extension() {
+        let mut ctx = MiddlewareContext::new();
+        ctx.set_extension(ContractVersion("v2".to_string()));
+        assert_eq!(
+            ctx.get_extension::<ContractVersion>(),
+            Some(&ContractVersion("v2".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_route_options_default_runs_all_stages() {
+        let options = RouteOptions::default();
+        assert!(!options.no_auth);
+        assert!(!options.skip_request_validation);
+        assert!(!options.skip_response_validation);
+        assert!(!options.skip_rate_limit);
+        assert!(!options.internal);
+    }
+
+    #[test]
+    fn test_route_options_internal_skips_policy_stages() {
+        let mut ctx = MiddlewareContext::new();
+        assert!(!ctx.is_internal());
+
+        ctx.set_extension(RouteOptions::internal());
+        let options = ctx.get_extension::<RouteOptions>().unwrap();
+        assert!(options.no_auth);
+        assert!(options.skip_request_validation);
+        assert!(options.skip_response_validation);
+        assert!(options.skip_rate_limit);
+        assert!(ctx.is_internal());
     }
 }
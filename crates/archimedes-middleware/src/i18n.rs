@@ -0,0 +1,552 @@
+//! Localized error messages.
+//!
+//! A [`MessageCatalog`] holds message templates keyed by locale and error
+//! code. Given one, the error normalization stage negotiates a locale from
+//! the request's `Accept-Language` header and renders error messages in it,
+//! for the envelope itself and for each issue in its `details`.
+//!
+//! # Catalog Files
+//!
+//! A catalog is loaded from a directory with one file per locale, named
+//! after it: `de.toml`, `pt-BR.json`. Each file maps error codes to
+//! templates:
+//!
+//! ```toml
+//! FIELD_REQUIRED = "Das Feld {field} ist erforderlich"
+//! NOT_FOUND = "Nicht gefunden"
+//! RATE_LIMITED = "Zu viele Anfragen, bitte in {retry_after_seconds} s erneut versuchen"
+//! ```
+//!
+//! Placeholders are filled from the object the code belongs to and from
+//! the envelope's `details`: an issue's `{field}`, `{expected}` or any
+//! other string, number or boolean it carries. A template with a
+//! placeholder that has no value is not used.
+//!
+//! # Locale Negotiation
+//!
+//! Language ranges are tried in order of their `q` value, then of their
+//! position in the header; ranges with `q=0` are skipped. Each range falls
+//! back to its prefixes, so `de-CH` matches a `de` catalog. When no range
+//! matches, the catalog's default locale is used.
+//!
+//! # Rendering
+//!
+//! A localized object keeps its machine-readable `code` unchanged and its
+//! original message as `developer_message`:
+//!
+//! ```json
+//! {
+//!   "error": {
+//!     "code": "NOT_FOUND",
+//!     "message": "Nicht gefunden",
+//!     "developer_message": "Not Found"
+//!   }
+//! }
+//! ```
+//!
+//! Codes without a template in the negotiated locale keep their message.
+//!
+//! # Hot Reload
+//!
+//! [`MessageCatalog`] is a shared handle: clones see the same messages, and
+//! [`MessageCatalog::reload_from_dir`] swaps in a new set for all of them,
+//! keeping the current messages if a file is invalid. Call it from a config
+//! file watcher.
+//!
+//! # Example
+//!
+//! ```rust
+//! use archimedes_middleware::i18n::MessageCatalog;
+//! use serde_json::json;
+//!
+//! let catalog = MessageCatalog::new("en")
+//!     .with_message("de", "INVALID_REQUEST", "Ungültige Anfrage")
+//!     .with_message("de", "FIELD_REQUIRED", "Das Feld {field} ist erforderlich");
+//!
+//! let locale = catalog.negotiate(Some("de-CH, en;q=0.5"));
+//! assert_eq!(locale, "de");
+//!
+//! let mut error = json!({
+//!     "code": "INVALID_REQUEST",
+//!     "message": "Invalid request",
+//!     "details": {
+//!         "issues": [
+//!             { "field": "email", "code": "FIELD_REQUIRED", "message": "Missing required field: email" }
+//!         ]
+//!     }
+//! });
+//! assert!(catalog.localize_error(&locale, &mut error));
+//! assert_eq!(error["code"], "INVALID_REQUEST");
+//! assert_eq!(error["message"], "Ungültige Anfrage");
+//! assert_eq!(error["developer_message"], "Invalid request");
+//!
+//! let issue = &error["details"]["issues"][0];
+//! assert_eq!(issue["message"], "Das Feld email ist erforderlich");
+//! ```
+
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, PoisonError, RwLock};
+
+/// Errors loading a message catalog.
+#[derive(Debug)]
+pub enum MessageCatalogError {
+    /// A file or directory could not be read.
+    Io(std::io::Error),
+    /// A file is not a valid catalog.
+    Parse(String),
+}
+
+impl std::fmt::Display for MessageCatalogError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "failed to read message catalog: {e}"),
+            Self::Parse(msg) => write!(f, "invalid message catalog: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for MessageCatalogError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Parse(_) => None,
+        }
+    }
+}
+
+/// Templates of one locale.
+#[derive(Debug, Clone, Default)]
+struct LocaleMessages {
+    /// The locale as named by the catalog.
+    name: String,
+    /// Templates by error code.
+    templates: HashMap<String, String>,
+}
+
+/// Shared, reloadable message templates by locale and error code.
+///
+/// Clones share the same messages; [`reload_from_dir`](Self::reload_from_dir)
+/// applies to all of them.
+#[derive(Debug, Clone)]
+pub struct MessageCatalog {
+    /// Messages by lowercased locale.
+    locales: Arc<RwLock<Arc<HashMap<String, LocaleMessages>>>>,
+    /// Locale used when no language range matches.
+    default_locale: String,
+}
+
+impl MessageCatalog {
+    /// Creates an empty catalog negotiating `default_locale` when nothing
+    /// else matches.
+    #[must_use]
+    pub fn new(default_locale: impl Into<String>) -> Self {
+        Self {
+            locales: Arc::default(),
+            default_locale: default_locale.into(),
+        }
+    }
+
+    /// Loads a catalog from the `.toml` and `.json` files in `dir`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory or a file cannot be read or
+    /// parsed.
+    pub fn from_dir(
+        dir: impl AsRef<Path>,
+        default_locale: impl Into<String>,
+    ) -> Result<Self, MessageCatalogError> {
+        let catalog = Self::new(default_locale);
+        catalog.replace(load_dir(dir.as_ref())?);
+        Ok(catalog)
+    }
+
+    /// Adds a template for `code` in `locale`.
+    #[must_use]
+    pub fn with_message(
+        self,
+        locale: impl Into<String>,
+        code: impl Into<String>,
+        template: impl Into<String>,
+    ) -> Self {
+        let mut locales = (*self.snapshot()).clone();
+        let locale = locale.into();
+        locales
+            .entry(locale.to_lowercase())
+            .or_insert_with(|| LocaleMessages {
+                name: locale,
+                templates: HashMap::new(),
+            })
+            .templates
+            .insert(code.into(), template.into());
+        *self.write() = Arc::new(locales);
+        self
+    }
+
+    /// Reloads the catalog from the files in `dir`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory or a file cannot be read or
+    /// parsed; the current messages are kept.
+    pub fn reload_from_dir(&self, dir: impl AsRef<Path>) -> Result<(), MessageCatalogError> {
+        let locales = load_dir(dir.as_ref())?;
+        tracing::info!(locales = locales.len(), "message catalog reloaded");
+        self.replace(locales);
+        Ok(())
+    }
+
+    /// Returns the locale used when no language range matches.
+    #[must_use]
+    pub fn default_locale(&self) -> &str {
+        &self.default_locale
+    }
+
+    /// Returns the locales the catalog has messages for, sorted.
+    #[must_use]
+    pub fn locales(&self) -> Vec<String> {
+        let mut locales: Vec<String> = self
+            .snapshot()
+            .values()
+            .map(|messages| messages.name.clone())
+            .collect();
+        locales.sort();
+        locales
+    }
+
+    /// Picks the locale for an `Accept-Language` header value.
+    ///
+    /// See the [module documentation](self#locale-negotiation).
+    #[must_use]
+    pub fn negotiate(&self, accept_language: Option<&str>) -> String {
+        let locales = self.snapshot();
+        language_ranges(accept_language.unwrap_or_default())
+            .into_iter()
+            .flat_map(fallback_chain)
+            .find_map(|tag| locales.get(&tag.to_lowercase()))
+            .map_or_else(
+                || self.default_locale.clone(),
+                |messages| messages.name.clone(),
+            )
+    }
+
+    /// Renders the template for `code` in `locale`, or one of its
+    /// prefixes, filling placeholders from `params`.
+    ///
+    /// Returns `None` if there is no template, or one of its placeholders
+    /// has no value.
+    #[must_use]
+    pub fn message(&self, locale: &str, code: &str, params: &Map<String, Value>) -> Option<String> {
+        let locales = self.snapshot();
+        let template = fallback_chain(locale)
+            .into_iter()
+            .filter_map(|tag| locales.get(&tag.to_lowercase()))
+            .find_map(|messages| messages.templates.get(code))?;
+        interpolate(template, params)
+    }
+
+    /// Localizes the `error` object of an error envelope in place, along
+    /// with each issue in its `details`.
+    ///
+    /// Returns `true` if the envelope's own message was localized.
+    pub fn localize_error(&self, locale: &str, error: &mut Value) -> bool {
+        let details = error
+            .get("details")
+            .and_then(Value::as_object)
+            .map(scalars)
+            .unwrap_or_default();
+
+        if let Some(issues) = error
+            .pointer_mut("/details/issues")
+            .and_then(Value::as_array_mut)
+        {
+            for issue in issues {
+                self.localize_object(locale, issue, &details);
+            }
+        }
+        self.localize_object(locale, error, &details)
+    }
+
+    /// Replaces `message` of an object carrying a `code`, keeping the
+    /// original as `developer_message`.
+    fn localize_object(&self, locale: &str, value: &mut Value, extra: &Map<String, Value>) -> bool {
+        let Some(object) = value.as_object_mut() else {
+            return false;
+        };
+        let Some(code) = object.get("code").and_then(Value::as_str) else {
+            return false;
+        };
+
+        let mut params = extra.clone();
+        params.extend(scalars(object));
+        let Some(message) = self.message(locale, code, &params) else {
+            return false;
+        };
+
+        if let Some(developer_message) = object.insert("message".to_string(), message.into()) {
+            object.insert("developer_message".to_string(), developer_message);
+        }
+        true
+    }
+
+    /// Returns the current messages.
+    fn snapshot(&self) -> Arc<HashMap<String, LocaleMessages>> {
+        Arc::clone(&self.locales.read().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Replaces the messages.
+    fn replace(&self, locales: HashMap<String, LocaleMessages>) {
+        *self.write() = Arc::new(locales);
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, Arc<HashMap<String, LocaleMessages>>> {
+        self.locales.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Loads one locale per `.toml` or `.json` file in `dir`.
+fn load_dir(dir: &Path) -> Result<HashMap<String, LocaleMessages>, MessageCatalogError> {
+    let mut locales = HashMap::new();
+    for entry in std::fs::read_dir(dir).map_err(MessageCatalogError::Io)? {
+        let path = entry.map_err(MessageCatalogError::Io)?.path();
+        let Some(locale) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        let extension = path.extension().and_then(|ext| ext.to_str());
+        if !matches!(extension, Some("toml" | "json")) {
+            continue;
+        }
+
+        let content = std::fs::read_to_string(&path).map_err(MessageCatalogError::Io)?;
+        let templates: HashMap<String, String> = if extension == Some("toml") {
+            toml::from_str(&content).map_err(|e| e.to_string())
+        } else {
+            serde_json::from_str(&content).map_err(|e| e.to_string())
+        }
+        .map_err(|e| MessageCatalogError::Parse(format!("{}: {e}", path.display())))?;
+
+        locales.insert(
+            locale.to_lowercase(),
+            LocaleMessages {
+                name: locale.to_string(),
+                templates,
+            },
+        );
+    }
+    Ok(locales)
+}
+
+/// Returns the language ranges of an `Accept-Language` value, most
+/// preferred first. Wildcards and ranges with `q=0` are left out.
+fn language_ranges(header: &str) -> Vec<&str> {
+    let mut ranges: Vec<(&str, f32)> = header
+        .split(',')
+        .filter_map(|range| {
+            let mut parts = range.split(';').map(str::trim);
+            let tag = parts.next().filter(|tag| !tag.is_empty() && *tag != "*")?;
+            let q = parts
+                .find_map(|param| param.strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.parse::<f32>().ok())?;
+            (q > 0.0).then_some((tag, q))
+        })
+        .collect();
+    // Stable, so equally preferred ranges keep their order
+    ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranges.into_iter().map(|(tag, _)| tag).collect()
+}
+
+/// Returns `tag` followed by its prefixes: `zh-Hant-TW`, `zh-Hant`, `zh`.
+fn fallback_chain(tag: &str) -> Vec<&str> {
+    let mut chain = vec![tag];
+    let mut rest = tag;
+    while let Some((prefix, _)) = rest.rsplit_once('-') {
+        chain.push(prefix);
+        rest = prefix;
+    }
+    chain
+}
+
+/// Returns the string, number and boolean members of an object.
+fn scalars(object: &Map<String, Value>) -> Map<String, Value> {
+    object
+        .iter()
+        .filter(|(_, value)| matches!(value, Value::String(_) | Value::Number(_) | Value::Bool(_)))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
+
+/// Fills the `{name}` placeholders of `template`.
+fn interpolate(template: &str, params: &Map<String, Value>) -> Option<String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let name = &rest[start + 1..start + len];
+        match params.get(name)? {
+            Value::String(value) => out.push_str(value),
+            value => out.push_str(&value.to_string()),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(rest);
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn catalog() -> MessageCatalog {
+        MessageCatalog::new("en")
+            .with_message("en", "NOT_FOUND", "Not found")
+            .with_message("de", "NOT_FOUND", "Nicht gefunden")
+            .with_message("de", "FIELD_REQUIRED", "Das Feld {field} ist erforderlich")
+            .with_message("fr", "NOT_FOUND", "Introuvable")
+            .with_message("pt-BR", "NOT_FOUND", "Não encontrado")
+    }
+
+    #[test]
+    fn test_negotiation_follows_q_values() {
+        let catalog = catalog();
+        let cases = [
+            (None, "en"),
+            (Some(""), "en"),
+            (Some("fr"), "fr"),
+            (Some("de, fr"), "de"),
+            (Some("de;q=0.5, fr"), "fr"),
+            (Some("fr;q=0.8, de;q=0.8"), "fr"),
+            (Some("de;q=0, fr;q=0.1"), "fr"),
+            (Some("de-CH, fr;q=0.9"), "de"),
+            (Some("pt-br"), "pt-BR"),
+            (Some("ja, *;q=0.5"), "en"),
+            (Some("fr;q=bad, de"), "de"),
+        ];
+        for (header, expected) in cases {
+            assert_eq!(catalog.negotiate(header), expected, "{header:?}");
+        }
+    }
+
+    #[test]
+    fn test_default_locale_used_without_match() {
+        let catalog = MessageCatalog::new("es");
+        assert_eq!(catalog.negotiate(Some("de")), "es");
+        assert_eq!(catalog.default_locale(), "es");
+    }
+
+    #[test]
+    fn test_interpolation() {
+        let params = json!({ "field": "email", "min": 3, "strict": true });
+        let params = params.as_object().unwrap();
+
+        assert_eq!(
+            interpolate("{field} needs {min} chars ({strict})", params).as_deref(),
+            Some("email needs 3 chars (true)")
+        );
+        assert_eq!(
+            interpolate("no params", params).as_deref(),
+            Some("no params")
+        );
+        assert_eq!(
+            interpolate("unclosed {field", params).as_deref(),
+            Some("unclosed {field")
+        );
+        assert_eq!(interpolate("{missing}", params), None);
+    }
+
+    #[test]
+    fn test_missing_translation_keeps_developer_message() {
+        let catalog = catalog();
+
+        let mut error = json!({ "code": "CONFLICT", "message": "Already exists" });
+        assert!(!catalog.localize_error("de", &mut error));
+        assert_eq!(
+            error,
+            json!({ "code": "CONFLICT", "message": "Already exists" })
+        );
+
+        // A template whose placeholders cannot be filled is not used either
+        let mut error = json!({ "code": "FIELD_REQUIRED", "message": "Missing field" });
+        assert!(!catalog.localize_error("de", &mut error));
+        assert_eq!(error["message"], "Missing field");
+    }
+
+    #[test]
+    fn test_regional_locale_falls_back_to_language() {
+        let catalog = catalog();
+        let params = Map::new();
+        assert_eq!(
+            catalog.message("de-AT", "NOT_FOUND", &params).as_deref(),
+            Some("Nicht gefunden")
+        );
+        assert_eq!(catalog.message("it", "NOT_FOUND", &params), None);
+    }
+
+    #[test]
+    fn test_issues_localized_and_code_kept() {
+        let catalog = catalog();
+        let mut error = json!({
+            "code": "NOT_FOUND",
+            "message": "Not Found",
+            "details": {
+                "issues": [
+                    { "field": "email", "code": "FIELD_REQUIRED", "message": "Missing required field: email" },
+                    { "field": "name", "code": "UNKNOWN", "message": "Odd name" }
+                ]
+            }
+        });
+
+        assert!(catalog.localize_error("de", &mut error));
+        assert_eq!(error["code"], "NOT_FOUND");
+        assert_eq!(error["message"], "Nicht gefunden");
+        assert_eq!(error["developer_message"], "Not Found");
+
+        let issues = &error["details"]["issues"];
+        assert_eq!(issues[0]["code"], "FIELD_REQUIRED");
+        assert_eq!(issues[0]["message"], "Das Feld email ist erforderlich");
+        assert_eq!(
+            issues[0]["developer_message"],
+            "Missing required field: email"
+        );
+        assert_eq!(issues[1]["message"], "Odd name");
+        assert!(issues[1].get("developer_message").is_none());
+    }
+
+    #[test]
+    fn test_load_and_reload_from_dir() {
+        let dir = std::env::temp_dir().join(format!("archimedes-i18n-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("de.toml"), "NOT_FOUND = \"Nicht gefunden\"\n").unwrap();
+        std::fs::write(dir.join("fr.json"), r#"{"NOT_FOUND": "Introuvable"}"#).unwrap();
+        std::fs::write(dir.join("README.md"), "ignored").unwrap();
+
+        let catalog = MessageCatalog::from_dir(&dir, "en").unwrap();
+        let shared = catalog.clone();
+        assert_eq!(catalog.locales(), ["de", "fr"]);
+
+        std::fs::write(dir.join("de.toml"), "NOT_FOUND = \"Nicht da\"\n").unwrap();
+        catalog.reload_from_dir(&dir).unwrap();
+        assert_eq!(
+            shared.message("de", "NOT_FOUND", &Map::new()).as_deref(),
+            Some("Nicht da")
+        );
+
+        // An invalid file keeps the current messages
+        std::fs::write(dir.join("de.toml"), "NOT_FOUND = ").unwrap();
+        assert!(matches!(
+            catalog.reload_from_dir(&dir),
+            Err(MessageCatalogError::Parse(_))
+        ));
+        assert_eq!(
+            shared.message("de", "NOT_FOUND", &Map::new()).as_deref(),
+            Some("Nicht da")
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -61,24 +61,36 @@
 #![forbid(unsafe_code)]
 
 pub mod context;
+pub mod i18n;
 pub mod middleware;
 pub mod pipeline;
 pub mod stages;
 pub mod types;
 
 // Re-export main types at crate root
-pub use context::MiddlewareContext;
+pub use context::{
+    AppName, BatchedRequest, ContractVersion, MiddlewareContext, RouteOptions, RoutePattern,
+};
+pub use i18n::{MessageCatalog, MessageCatalogError};
 pub use middleware::{BoxFuture, FnMiddleware, Middleware, Next};
 pub use pipeline::{HookError, Pipeline, PipelineBuilder, Stage};
-pub use types::{Request, Response, ResponseExt};
+pub use types::{Request, ResetConnection, Response, ResponseExt};
 
 // Re-export stage middleware
 pub use stages::{
-    AllowedOrigins, AuthorizationMiddleware, CorsBuilder, CorsConfig, CorsMiddleware,
-    ErrorNormalizationMiddleware, IdentityMiddleware, RequestIdMiddleware,
-    ResponseValidationMiddleware, TelemetryMiddleware, TracingMiddleware, ValidationMiddleware,
+    AllowedOrigins, AuthorizationMiddleware, BodyLimitMiddleware, CallerScopes, CaptureConfig,
+    CaptureMiddleware, CaptureSink, CorsBuilder, CorsConfig, CorsMiddleware, ErrorClassification,
+    ErrorNormalizationMiddleware, HmacSigner, IdentityMiddleware, OperationGateMiddleware,
+    OperationGates, RawBody, RawBodyMiddleware, RequestIdMiddleware, RequestSanitizationMiddleware,
+    ResponseBodyHookMiddleware, ResponseValidationMiddleware, ScopeEnforcement, ScopeRequirements,
+    StaticTenantPolicyStore, StatusMap, TelemetryMiddleware, TenantPolicyMiddleware,
+    TracingMiddleware, ValidationMiddleware,
 };
 
+// Fault injection middleware (requires `chaos` feature)
+#[cfg(feature = "chaos")]
+pub use stages::{FaultInjectionMiddleware, Faults};
+
 // Compression middleware (requires `compression` feature)
 #[cfg(feature = "compression")]
 pub use stages::{
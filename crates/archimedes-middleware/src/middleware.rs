@@ -40,9 +40,11 @@
 //! ```
 
 use crate::context::MiddlewareContext;
+use crate::stages::tracing::StageTimings;
 use crate::types::{Request, Response};
 use std::future::Future;
 use std::pin::Pin;
+use std::time::Instant;
 
 /// A boxed future that returns a response.
 pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
@@ -152,11 +154,28 @@ impl<'a> Next<'a> {
     /// Invokes the next middleware or handler in the chain.
     ///
     /// This consumes `self` to ensure it can only be called once.
+    ///
+    /// When the context carries a [`StageTimings`] collector (see
+    /// [`TracingMiddleware::record_stage_events`](crate::stages::TracingMiddleware::record_stage_events)),
+    /// the duration of the invoked stage is recorded.
     pub async fn run(self, ctx: &mut MiddlewareContext, request: Request) -> Response {
-        match self.inner {
-            NextInner::Chain { middleware, next } => middleware.process(ctx, request, *next).await,
-            NextInner::Handler(handler) => handler(ctx, request).await,
+        let start = ctx.has_extension::<StageTimings>().then(Instant::now);
+
+        let (stage, response) = match self.inner {
+            NextInner::Chain { middleware, next } => (
+                middleware.name(),
+                middleware.process(ctx, request, *next).await,
+            ),
+            NextInner::Handler(handler) => ("handler", handler(ctx, request).await),
+        };
+
+        if let Some(start) = start {
+            if let Some(timings) = ctx.get_extension_mut::<StageTimings>() {
+                timings.record(stage, start.elapsed());
+            }
         }
+
+        response
     }
 }
 
@@ -38,14 +38,43 @@
 //!     .allow_role("user", vec!["getUser", "listUsers"])
 //!     .build();
 //! ```
+//!
+//! # Scope Enforcement
+//!
+//! Contract security scopes can be checked alongside any mode with
+//! [`AuthorizationMiddleware::with_scope_enforcement`]. In
+//! [`ScopeEnforcementMode::Enforce`] callers lacking the required scopes are
+//! rejected with 403 before the policy runs; in
+//! [`ScopeEnforcementMode::Advisory`] the [`ScopeCheck`] is only recorded and
+//! forwarded to OPA so Rego can make the final decision.
+//!
+//! # Tenant Restrictions
+//!
+//! Operations listed in a tenant's `denied_operations` (see
+//! [`TenantPolicy`]) are rejected with 403 `TENANT_OPERATION_DENIED` before
+//! scopes or the policy are evaluated.
+//!
+//! # Obligations
+//!
+//! An allow decision can carry [`Obligations`], such as fields to mask,
+//! that the handler is expected to honor. OPA policies set them at the
+//! evaluator's obligations query and custom evaluators return them from
+//! [`PolicyEvaluator::obligations`]. They are stored in the context as an
+//! extension, recorded on the [`AuthorizationResult`], and handed to the
+//! handler on its `RequestContext`. Keys are passed through as the policy
+//! set them.
 
 use crate::{
-    context::MiddlewareContext,
+    context::{MiddlewareContext, RouteOptions},
     middleware::{BoxFuture, Middleware, Next},
+    stages::scopes::{CallerScopes, ScopeCheck, ScopeEnforcement, ScopeEnforcementMode},
+    stages::tenant::TenantPolicy,
     types::{Request, Response, ResponseExt},
 };
-use archimedes_core::CallerIdentity;
+use archimedes_core::{CallerIdentity, Obligations};
+use bytes::Bytes;
 use http::StatusCode;
+use http_body_util::Full;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
@@ -58,6 +87,18 @@ use themis_platform_types::PolicyInput;
 #[cfg(feature = "opa")]
 use std::collections::HashMap as StdHashMap;
 
+/// Policy input header key carrying whether the scope check passed.
+///
+/// Scope check results are forwarded to OPA as synthetic entries in
+/// `input.headers`, alongside the request headers.
+pub const SCOPES_SATISFIED_INPUT_KEY: &str = "x-archimedes-scopes-satisfied";
+
+/// Policy input header key carrying the caller's space-separated scopes.
+pub const GRANTED_SCOPES_INPUT_KEY: &str = "x-archimedes-granted-scopes";
+
+/// Policy input header key carrying the space-separated missing scopes.
+pub const MISSING_SCOPES_INPUT_KEY: &str = "x-archimedes-missing-scopes";
+
 /// Authorization middleware that enforces access control policies.
 ///
 /// This middleware supports multiple authorization modes:
@@ -71,12 +112,15 @@ use std::collections::HashMap as StdHashMap;
 pub struct AuthorizationMiddleware {
     /// The authorization mode.
     mode: AuthorizationMode,
+    /// Contract scope requirements checked before or alongside the policy.
+    scopes: Option<Arc<ScopeEnforcement>>,
 }
 
 impl std::fmt::Debug for AuthorizationMiddleware {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("AuthorizationMiddleware")
             .field("mode", &self.mode.name())
+            .field("scopes", &self.scopes.is_some())
             .finish()
     }
 }
@@ -126,6 +170,14 @@ struct RbacConfig {
 pub trait PolicyEvaluator: Send + Sync + std::fmt::Debug {
     /// Evaluates whether the request should be allowed.
     fn evaluate(&self, identity: &CallerIdentity, operation_id: &str) -> PolicyDecision;
+
+    /// Returns the obligations attached to an allowed request.
+    ///
+    /// Only called after [`PolicyEvaluator::evaluate`] allowed the request.
+    /// The default attaches none.
+    fn obligations(&self, _identity: &CallerIdentity, _operation_id: &str) -> Obligations {
+        Obligations::default()
+    }
 }
 
 /// Policy evaluation result.
@@ -148,6 +200,7 @@ impl AuthorizationMiddleware {
     pub fn allow_all() -> Self {
         Self {
             mode: AuthorizationMode::AllowAll,
+            scopes: None,
         }
     }
 
@@ -158,6 +211,7 @@ impl AuthorizationMiddleware {
     pub fn deny_all() -> Self {
         Self {
             mode: AuthorizationMode::DenyAll,
+            scopes: None,
         }
     }
 
@@ -172,6 +226,7 @@ impl AuthorizationMiddleware {
     pub fn custom<P: PolicyEvaluator + 'static>(evaluator: P) -> Self {
         Self {
             mode: AuthorizationMode::Custom(Arc::new(evaluator)),
+            scopes: None,
         }
     }
 
@@ -197,6 +252,7 @@ impl AuthorizationMiddleware {
     pub fn opa(authorizer: Authorizer) -> Self {
         Self {
             mode: AuthorizationMode::Opa(Arc::new(authorizer)),
+            scopes: None,
         }
     }
 
@@ -213,6 +269,52 @@ impl AuthorizationMiddleware {
         Ok(Self::opa(authorizer))
     }
 
+    /// Enables contract scope enforcement for this middleware.
+    ///
+    /// Operations without declared requirements are not affected.
+    #[must_use]
+    pub fn with_scope_enforcement(mut self, scopes: ScopeEnforcement) -> Self {
+        self.scopes = Some(Arc::new(scopes));
+        self
+    }
+
+    /// Checks the caller's scopes against the operation's requirements.
+    ///
+    /// Returns `None` when scope enforcement is disabled or the operation
+    /// declares no requirements.
+    fn check_scopes(&self, ctx: &MiddlewareContext, operation_id: &str) -> Option<ScopeCheck> {
+        let requirements = self.scopes.as_ref()?.requirements(operation_id)?;
+        let caller = ctx
+            .get_extension::<CallerScopes>()
+            .cloned()
+            .unwrap_or_default();
+        Some(requirements.check(&caller))
+    }
+
+    /// Builds a 403 response listing the scopes the caller is missing.
+    fn insufficient_scope_response(check: &ScopeCheck) -> Response {
+        let missing = check.missing.join(" ");
+        let body = serde_json::json!({
+            "error": {
+                "code": "INSUFFICIENT_SCOPE",
+                "message": format!("Missing required scopes: {missing}"),
+                "details": {
+                    "missing_scopes": check.missing,
+                }
+            }
+        });
+
+        http::Response::builder()
+            .status(StatusCode::FORBIDDEN)
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .header(
+                http::header::WWW_AUTHENTICATE,
+                format!("Bearer error=\"insufficient_scope\", scope=\"{missing}\""),
+            )
+            .body(Full::new(Bytes::from(body.to_string())))
+            .expect("failed to build insufficient scope response")
+    }
+
     /// Evaluates authorization for the given identity and operation (sync mock modes).
     fn evaluate(&self, identity: &CallerIdentity, operation_id: &str) -> PolicyDecision {
         match &self.mode {
@@ -230,6 +332,14 @@ impl AuthorizationMiddleware {
         }
     }
 
+    /// Returns the obligations for a request the sync modes allowed.
+    fn obligations(&self, identity: &CallerIdentity, operation_id: &str) -> Obligations {
+        match &self.mode {
+            AuthorizationMode::Custom(evaluator) => evaluator.obligations(identity, operation_id),
+            _ => Obligations::default(),
+        }
+    }
+
     /// Evaluates OPA authorization asynchronously.
     #[cfg(feature = "opa")]
     async fn evaluate_opa_async(
@@ -237,7 +347,7 @@ impl AuthorizationMiddleware {
         identity: &CallerIdentity,
         operation_id: &str,
         ctx: &MiddlewareContext,
-    ) -> Result<themis_platform_types::PolicyDecision, archimedes_authz::AuthzError> {
+    ) -> Result<archimedes_authz::Decision, archimedes_authz::AuthzError> {
         // Build PolicyInput from context
         let request_id = ctx.request_id().clone();
 
@@ -250,11 +360,31 @@ impl AuthorizationMiddleware {
             .request_id(request_id);
 
         // Add headers as context if available
-        if let Some(headers) = ctx.headers() {
-            let headers_map: StdHashMap<String, String> = headers
+        let mut headers_map: Option<StdHashMap<String, String>> = ctx.headers().map(|headers| {
+            headers
                 .iter()
                 .filter_map(|(k, v)| v.to_str().ok().map(|v| (k.to_string(), v.to_string())))
-                .collect();
+                .collect()
+        });
+
+        // Forward the scope check so Rego policies can layer on top of it
+        if let Some(check) = ctx.get_extension::<ScopeCheck>() {
+            let map = headers_map.get_or_insert_with(StdHashMap::new);
+            map.insert(
+                SCOPES_SATISFIED_INPUT_KEY.to_string(),
+                check.satisfied.to_string(),
+            );
+            map.insert(
+                GRANTED_SCOPES_INPUT_KEY.to_string(),
+                check.granted.join(" "),
+            );
+            map.insert(
+                MISSING_SCOPES_INPUT_KEY.to_string(),
+                check.missing.join(" "),
+            );
+        }
+
+        if let Some(headers_map) = headers_map {
             input_builder = input_builder.headers(headers_map);
         }
 
@@ -262,7 +392,7 @@ impl AuthorizationMiddleware {
             archimedes_authz::AuthzError::Evaluation(format!("Failed to build policy input: {}", e))
         })?;
 
-        authorizer.authorize(&input).await
+        authorizer.authorize_with_obligations(&input).await
     }
 
     /// Evaluates RBAC policy.
@@ -347,18 +477,83 @@ impl Middleware for AuthorizationMiddleware {
     ) -> BoxFuture<'a, Response> {
         Box::pin(async move {
             let operation_id = ctx.operation_id().unwrap_or("unknown").to_string();
+
+            // Routes marked no-auth bypass scope and policy checks entirely
+            if ctx
+                .get_extension::<RouteOptions>()
+                .is_some_and(|o| o.no_auth)
+            {
+                ctx.set_extension(AuthorizationResult {
+                    allowed: true,
+                    operation_id,
+                    reason: Some("route does not require authorization".to_string()),
+                    obligations: Obligations::default(),
+                });
+                return next.run(ctx, request).await;
+            }
+
+            // Tenant restrictions apply regardless of the caller's permissions
+            if let Some(tenant) = ctx
+                .get_extension::<TenantPolicy>()
+                .filter(|t| t.denies(&operation_id))
+            {
+                let reason = format!(
+                    "Operation {operation_id} is not permitted for tenant {}",
+                    tenant.tenant_id
+                );
+                ctx.set_extension(AuthorizationResult {
+                    allowed: false,
+                    operation_id,
+                    reason: Some(reason.clone()),
+                    obligations: Obligations::default(),
+                });
+                return Response::json_error(
+                    StatusCode::FORBIDDEN,
+                    "TENANT_OPERATION_DENIED",
+                    &reason,
+                );
+            }
+
             let identity = ctx.identity().clone();
 
+            // Check contract scopes before the policy runs
+            if let Some(check) = self.check_scopes(ctx, &operation_id) {
+                let enforce = self
+                    .scopes
+                    .as_ref()
+                    .is_some_and(|s| s.enforcement_mode() == ScopeEnforcementMode::Enforce);
+                if enforce && !check.satisfied {
+                    ctx.set_extension(AuthorizationResult {
+                        allowed: false,
+                        operation_id,
+                        reason: Some(format!(
+                            "Missing required scopes: {}",
+                            check.missing.join(" ")
+                        )),
+                        obligations: Obligations::default(),
+                    });
+                    let response = Self::insufficient_scope_response(&check);
+                    ctx.set_extension(check);
+                    return response;
+                }
+                ctx.set_extension(check);
+            }
+
             // Handle OPA mode with async evaluation
             #[cfg(feature = "opa")]
             if let AuthorizationMode::Opa(authorizer) = &self.mode {
                 match Self::evaluate_opa_async(authorizer, &identity, &operation_id, ctx).await {
-                    Ok(decision) => {
+                    Ok(archimedes_authz::Decision {
+                        decision,
+                        obligations,
+                    }) => {
                         if decision.allowed {
+                            ctx.set_extension(obligations.clone());
                             ctx.set_extension(AuthorizationResult {
                                 allowed: true,
                                 operation_id,
                                 reason: None,
+                                obligations,
                             });
                             return next.run(ctx, request).await;
                         } else {
@@ -369,6 +564,7 @@ impl Middleware for AuthorizationMiddleware {
                                 allowed: false,
                                 operation_id,
                                 reason: Some(reason.clone()),
+                                obligations: Obligations::default(),
                             });
                             return Response::json_error(
                                 StatusCode::FORBIDDEN,
@@ -383,6 +579,7 @@ impl Middleware for AuthorizationMiddleware {
                             allowed: false,
                             operation_id,
                             reason: Some(format!("Authorization error: {e}")),
+                            obligations: Obligations::default(),
                         });
                         return Response::json_error(
                             StatusCode::INTERNAL_SERVER_ERROR,
@@ -398,11 +595,16 @@ impl Middleware for AuthorizationMiddleware {
 
             match decision {
                 PolicyDecision::Allow => {
+                    // Hand obligations on to the handler
+                    let obligations = self.obligations(&identity, &operation_id);
+                    ctx.set_extension(obligations.clone());
+
                     // Store decision in context for auditing
                     ctx.set_extension(AuthorizationResult {
                         allowed: true,
                         operation_id,
                         reason: None,
+                        obligations,
                     });
 
                     // Continue to next middleware
@@ -414,6 +616,7 @@ impl Middleware for AuthorizationMiddleware {
                         allowed: false,
                         operation_id,
                         reason: Some(reason.clone()),
+                        obligations: Obligations::default(),
                     });
 
                     // Return 403 Forbidden response
@@ -433,6 +636,8 @@ pub struct AuthorizationResult {
     pub operation_id: String,
     /// Denial reason if not allowed.
     pub reason: Option<String>,
+    /// Obligations attached to the decision; empty unless allowed.
+    pub obligations: Obligations,
 }
 
 /// Builder for RBAC authorization middleware.
@@ -482,6 +687,7 @@ impl RbacBuilder {
     pub fn build(self) -> AuthorizationMiddleware {
         AuthorizationMiddleware {
             mode: AuthorizationMode::Rbac(Arc::new(self.config)),
+            scopes: None,
         }
     }
 }
@@ -490,9 +696,8 @@ impl RbacBuilder {
 mod tests {
     use super::*;
     use crate::context::MiddlewareContext;
-    use bytes::Bytes;
+    use crate::stages::scopes::ScopeRequirements;
     use http::{Request as HttpRequest, Response as HttpResponse, StatusCode};
-    use http_body_util::Full;
     use themis_platform_types::identity::UserIdentity;
 
     fn make_test_request() -> Request {
@@ -520,6 +725,52 @@ mod tests {
         assert_eq!(middleware.name(), "authorization");
     }
 
+    #[tokio::test]
+    async fn test_tenant_denied_operation_returns_403() {
+        use crate::stages::tenant::TenantOverrides;
+
+        let middleware = AuthorizationMiddleware::allow_all();
+        let mut ctx = MiddlewareContext::new();
+        ctx.set_operation_id("exportAll".to_string());
+        ctx.set_extension(TenantPolicy {
+            tenant_id: "acme".to_string(),
+            overrides: Some(TenantOverrides::new().deny_operation("exportAll")),
+        });
+        let next = Next::handler(create_handler());
+
+        let response = middleware
+            .process(&mut ctx, make_test_request(), next)
+            .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let body = http_body_util::BodyExt::collect(response.into_body())
+            .await
+            .unwrap()
+            .to_bytes();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["error"]["code"], "TENANT_OPERATION_DENIED");
+        assert_eq!(
+            json["error"]["message"],
+            "Operation exportAll is not permitted for tenant acme"
+        );
+
+        let result = ctx.get_extension::<AuthorizationResult>().unwrap();
+        assert!(!result.allowed);
+
+        // Other operations remain available to the tenant
+        let mut ctx = MiddlewareContext::new();
+        ctx.set_operation_id("getUser".to_string());
+        ctx.set_extension(TenantPolicy {
+            tenant_id: "acme".to_string(),
+            overrides: Some(TenantOverrides::new().deny_operation("exportAll")),
+        });
+        let next = Next::handler(create_handler());
+        let response = middleware
+            .process(&mut ctx, make_test_request(), next)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
     #[tokio::test]
     async fn test_allow_all_permits_any_request() {
         let middleware = AuthorizationMiddleware::allow_all();
@@ -536,6 +787,146 @@ mod tests {
         assert!(auth_result.allowed);
     }
 
+    #[tokio::test]
+    async fn test_no_auth_route_skips_policy() {
+        let middleware = AuthorizationMiddleware::deny_all();
+        let mut ctx = MiddlewareContext::new();
+        ctx.set_operation_id("health".to_string());
+        ctx.set_extension(RouteOptions {
+            no_auth: true,
+            ..RouteOptions::default()
+        });
+
+        let response = middleware
+            .process(
+                &mut ctx,
+                make_test_request(),
+                Next::handler(create_handler()),
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(ctx.get_extension::<AuthorizationResult>().unwrap().allowed);
+
+        // Other options leave authorization in place
+        let mut ctx = MiddlewareContext::new();
+        ctx.set_operation_id("health".to_string());
+        ctx.set_extension(RouteOptions {
+            skip_request_validation: true,
+            ..RouteOptions::default()
+        });
+        let response = middleware
+            .process(
+                &mut ctx,
+                make_test_request(),
+                Next::handler(create_handler()),
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    fn scope_middleware(mode: ScopeEnforcementMode) -> AuthorizationMiddleware {
+        let scopes = ScopeEnforcement::new()
+            .mode(mode)
+            .require("getUser", ScopeRequirements::all(["users:read"]))
+            .require(
+                "updateUser",
+                ScopeRequirements::all(["users:read", "users:write"]).or(["admin"]),
+            );
+        AuthorizationMiddleware::allow_all().with_scope_enforcement(scopes)
+    }
+
+    fn scoped_context(operation_id: &str, scopes: &[&str]) -> MiddlewareContext {
+        let mut ctx = MiddlewareContext::new();
+        ctx.set_operation_id(operation_id.to_string());
+        ctx.set_extension(CallerScopes::new(scopes.iter().copied()));
+        ctx
+    }
+
+    #[tokio::test]
+    async fn test_scope_enforcement_rejects_missing_scope() {
+        let middleware = scope_middleware(ScopeEnforcementMode::Enforce);
+        let mut ctx = scoped_context("updateUser", &["users:read"]);
+        let next = Next::handler(create_handler());
+
+        let response = middleware
+            .process(&mut ctx, make_test_request(), next)
+            .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert!(response
+            .headers()
+            .get(http::header::WWW_AUTHENTICATE)
+            .is_some());
+
+        let body = http_body_util::BodyExt::collect(response.into_body())
+            .await
+            .unwrap()
+            .to_bytes();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["error"]["code"], "INSUFFICIENT_SCOPE");
+        assert_eq!(
+            json["error"]["details"]["missing_scopes"],
+            serde_json::json!(["users:write"])
+        );
+
+        let check = ctx.get_extension::<ScopeCheck>().unwrap();
+        assert!(!check.satisfied);
+        assert!(!ctx.get_extension::<AuthorizationResult>().unwrap().allowed);
+    }
+
+    #[tokio::test]
+    async fn test_scope_enforcement_allows_sufficient_scopes() {
+        let middleware = scope_middleware(ScopeEnforcementMode::Enforce);
+        let mut ctx = scoped_context("getUser", &["users:read", "users:write"]);
+        let next = Next::handler(create_handler());
+
+        let response = middleware
+            .process(&mut ctx, make_test_request(), next)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(ctx.get_extension::<ScopeCheck>().unwrap().satisfied);
+    }
+
+    #[tokio::test]
+    async fn test_scope_enforcement_alternative_requirement() {
+        let middleware = scope_middleware(ScopeEnforcementMode::Enforce);
+        let mut ctx = scoped_context("updateUser", &["admin"]);
+        let next = Next::handler(create_handler());
+
+        let response = middleware
+            .process(&mut ctx, make_test_request(), next)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_scope_enforcement_ignores_undeclared_operation() {
+        let middleware = scope_middleware(ScopeEnforcementMode::Enforce);
+        let mut ctx = scoped_context("listUsers", &[]);
+        let next = Next::handler(create_handler());
+
+        let response = middleware
+            .process(&mut ctx, make_test_request(), next)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(ctx.get_extension::<ScopeCheck>().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_scope_advisory_mode_defers_to_policy() {
+        let middleware = scope_middleware(ScopeEnforcementMode::Advisory);
+        let mut ctx = scoped_context("getUser", &[]);
+        let next = Next::handler(create_handler());
+
+        let response = middleware
+            .process(&mut ctx, make_test_request(), next)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let check = ctx.get_extension::<ScopeCheck>().unwrap();
+        assert!(!check.satisfied);
+        assert_eq!(check.missing, vec!["users:read".to_string()]);
+    }
+
     #[tokio::test]
     async fn test_deny_all_rejects_any_request() {
         let middleware = AuthorizationMiddleware::deny_all();
@@ -746,4 +1137,54 @@ mod tests {
         let response = middleware.process(&mut ctx, request, next).await;
         assert_eq!(response.status(), StatusCode::FORBIDDEN);
     }
+
+    #[derive(Debug)]
+    struct MaskingEvaluator;
+
+    impl PolicyEvaluator for MaskingEvaluator {
+        fn evaluate(&self, _identity: &CallerIdentity, _operation_id: &str) -> PolicyDecision {
+            PolicyDecision::Allow
+        }
+
+        fn obligations(&self, _identity: &CallerIdentity, _operation_id: &str) -> Obligations {
+            Obligations::new()
+                .with("mask_fields", serde_json::json!(["ssn"]))
+                .with("x-vendor-hint", "keep")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_custom_evaluator_obligations_reach_handler() {
+        let middleware = AuthorizationMiddleware::custom(MaskingEvaluator);
+        let mut ctx = MiddlewareContext::new();
+        ctx.set_operation_id("getUser".to_string());
+        let next = Next::handler(|ctx: &mut MiddlewareContext, _req| {
+            let masked: Vec<String> = ctx
+                .get_extension::<Obligations>()
+                .and_then(|o| o.get_as("mask_fields"))
+                .unwrap_or_default();
+            Box::pin(async move {
+                HttpResponse::builder()
+                    .status(StatusCode::OK)
+                    .body(Full::new(Bytes::from(masked.join(","))))
+                    .unwrap()
+            }) as BoxFuture<'static, Response>
+        });
+
+        let response = middleware
+            .process(&mut ctx, make_test_request(), next)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = http_body_util::BodyExt::collect(response.into_body())
+            .await
+            .unwrap()
+            .to_bytes();
+        assert_eq!(&body[..], b"ssn");
+
+        let result = ctx.get_extension::<AuthorizationResult>().unwrap();
+        assert_eq!(
+            result.obligations.get("x-vendor-hint"),
+            Some(&serde_json::json!("keep"))
+        );
+    }
 }
@@ -0,0 +1,165 @@
+//! Request body size limit middleware.
+//!
+//! Rejects requests whose body exceeds a configured size with
+//! `413 Payload Too Large`. A tenant's `max_body_bytes` override (see
+//! [`TenantPolicy`]) replaces the global limit for that tenant's requests.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use archimedes_middleware::stages::BodyLimitMiddleware;
+//!
+//! // 1 MiB for every tenant without an override
+//! let body_limit = BodyLimitMiddleware::new(1024 * 1024);
+//! ```
+
+use crate::context::MiddlewareContext;
+use crate::middleware::{BoxFuture, Middleware, Next};
+use crate::stages::tenant::TenantPolicy;
+use crate::types::{Request, Response, ResponseExt};
+use http::{header, StatusCode};
+use http_body::Body;
+
+/// Default body size limit (1 MiB).
+pub const DEFAULT_MAX_BODY_BYTES: u64 = 1024 * 1024;
+
+/// Middleware enforcing a maximum request body size.
+#[derive(Debug, Clone, Copy)]
+pub struct BodyLimitMiddleware {
+    max_bytes: u64,
+}
+
+impl BodyLimitMiddleware {
+    /// Creates a body limit middleware with the given global limit.
+    #[must_use]
+    pub fn new(max_bytes: u64) -> Self {
+        Self { max_bytes }
+    }
+
+    /// Returns the global limit.
+    #[must_use]
+    pub fn max_bytes(&self) -> u64 {
+        self.max_bytes
+    }
+
+    /// Returns the limit for a request, preferring the tenant's override.
+    pub(crate) fn limit_for(&self, ctx: &MiddlewareContext) -> u64 {
+        ctx.get_extension::<TenantPolicy>()
+            .and_then(TenantPolicy::max_body_bytes)
+            .unwrap_or(self.max_bytes)
+    }
+
+    /// Returns the declared or buffered size of the request body.
+    fn body_size(request: &Request) -> u64 {
+        let declared = request
+            .headers()
+            .get(header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(0);
+        declared.max(request.body().size_hint().lower())
+    }
+}
+
+impl Default for BodyLimitMiddleware {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_BODY_BYTES)
+    }
+}
+
+impl Middleware for BodyLimitMiddleware {
+    fn name(&self) -> &'static str {
+        "body_limit"
+    }
+
+    fn process<'a>(
+        &'a self,
+        ctx: &'a mut MiddlewareContext,
+        request: Request,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Response> {
+        Box::pin(async move {
+            let limit = self.limit_for(ctx);
+            let size = Self::body_size(&request);
+
+            if size > limit {
+                return Response::json_error(
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    "PAYLOAD_TOO_LARGE",
+                    &format!("Request body of {size} bytes exceeds the limit of {limit} bytes"),
+                );
+            }
+
+            next.run(ctx, request).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stages::tenant::TenantOverrides;
+    use bytes::Bytes;
+    use http::{Request as HttpRequest, Response as HttpResponse};
+    use http_body_util::Full;
+
+    fn request_with_body(len: usize) -> Request {
+        HttpRequest::builder()
+            .method("POST")
+            .uri("/upload")
+            .body(Full::new(Bytes::from(vec![b'x'; len])))
+            .unwrap()
+    }
+
+    fn ok_handler() -> impl FnOnce(&mut MiddlewareContext, Request) -> BoxFuture<'static, Response>
+    {
+        |_ctx, _req| {
+            Box::pin(async {
+                HttpResponse::builder()
+                    .status(StatusCode::OK)
+                    .body(Full::new(Bytes::new()))
+                    .unwrap()
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rejects_oversized_body() {
+        let middleware = BodyLimitMiddleware::new(16);
+        let mut ctx = MiddlewareContext::new();
+
+        let next = Next::handler(ok_handler());
+        let response = middleware
+            .process(&mut ctx, request_with_body(17), next)
+            .await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let next = Next::handler(ok_handler());
+        let response = middleware
+            .process(&mut ctx, request_with_body(16), next)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_tenant_override_replaces_global_limit() {
+        let middleware = BodyLimitMiddleware::new(16);
+        let mut ctx = MiddlewareContext::new();
+        ctx.set_extension(TenantPolicy {
+            tenant_id: "acme".to_string(),
+            overrides: Some(TenantOverrides::new().max_body_bytes(64)),
+        });
+
+        let next = Next::handler(ok_handler());
+        let response = middleware
+            .process(&mut ctx, request_with_body(32), next)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let next = Next::handler(ok_handler());
+        let response = middleware
+            .process(&mut ctx, request_with_body(65), next)
+            .await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
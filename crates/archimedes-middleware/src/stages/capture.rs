@@ -0,0 +1,984 @@
+//! Request and response capture for debugging.
+//!
+//! Reproducing a production bug usually starts with the exact request that
+//! triggered it. The capture stage records chosen exchanges as JSON
+//! records, in the spirit of a HAR file, and hands them to a
+//! [`CaptureSink`]: the request and response with their headers and
+//! bodies, the timing, the resolved operation and the caller's subject.
+//! `archimedes-test` replays a capture against a local server and reports
+//! where the response differs from the recording.
+//!
+//! # Pipeline Position
+//!
+//! Capture runs after resolution and identity, so records can name the
+//! operation and caller, and wraps the handler:
+//!
+//! ```text
+//! Identity → Authorization → Validation → [Capture] → Handler
+//! ```
+//!
+//! # Choosing Requests
+//!
+//! A request is captured if either:
+//!
+//! - capture is `enabled`, its operation is one of `operations` (or the
+//!   list is empty), and it falls in the sampled `percentage`; sampling
+//!   hashes the request ID, so a retried request with the same ID gets the
+//!   same decision
+//! - it carries a valid [`CAPTURE_HEADER`] signed with the `debug_secret`
+//!   (see [`debug_token`]), whatever the rest of the config says
+//!
+//! Internal endpoints are never captured.
+//!
+//! ```toml
+//! enabled = true
+//! percentage = 0.5
+//! operations = ["createOrder", "getOrder"]
+//! redact_headers = ["x-api-key"]
+//! redact_fields = ["password", "cardNumber"]
+//! max_body_bytes = 65536
+//! debug_secret = "change-me"
+//! ```
+//!
+//! # Redaction
+//!
+//! Records never hold the values of the `Authorization`,
+//! `Proxy-Authorization`, `Cookie`, `Set-Cookie` and capture headers, nor
+//! of the headers in `redact_headers`; they are replaced with
+//! [`REDACTED`]. Fields named in `redact_fields` are redacted at any depth
+//! of JSON bodies, and as parameters of the query string and of form
+//! bodies. A JSON body that does not parse while fields are to be redacted
+//! is left out, as is any body over `max_body_bytes`; the record keeps its
+//! size. Of the caller identity only the subject is recorded.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use archimedes_middleware::stages::capture::{CaptureConfig, CaptureMiddleware, RotatingFileSink};
+//!
+//! let sink = RotatingFileSink::new("/var/log/orders/captures.jsonl").max_files(3);
+//! let pipeline = Pipeline::builder()
+//!     .add_pre_handler_stage(validation)
+//!     .add_pre_handler_stage(CaptureMiddleware::new(config.capture.clone(), sink))
+//!     .build();
+//! ```
+
+use crate::context::MiddlewareContext;
+use crate::middleware::{BoxFuture, Middleware, Next};
+use crate::stages::signing::decode_hex;
+use crate::stages::validation::RequestBody;
+use crate::types::{Request, Response};
+use archimedes_core::secret::REDACTED;
+use archimedes_core::{CallerIdentity, Secret};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use http::header::CONTENT_TYPE;
+use http::{HeaderMap, Method};
+use http_body_util::{BodyExt, Full};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt::Write as _;
+use std::fs::{File, OpenOptions};
+use std::hash::{Hash, Hasher};
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Request header asking for a request to be captured.
+///
+/// Its value is a token from [`debug_token`].
+pub const CAPTURE_HEADER: &str = "x-archimedes-capture";
+
+/// Headers whose values are never captured.
+const SENSITIVE_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+    CAPTURE_HEADER,
+];
+
+/// Default largest body captured, in bytes.
+const DEFAULT_MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// Default percentage of eligible requests captured.
+const DEFAULT_PERCENTAGE: f64 = 1.0;
+
+/// Which requests are captured, and what is left out of their records.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CaptureConfig {
+    /// Whether sampled requests are captured. Requests with a valid
+    /// capture header are captured either way.
+    pub enabled: bool,
+    /// Percentage of eligible requests, from 0 to 100, captured.
+    pub percentage: f64,
+    /// Operations whose requests are eligible; all when empty.
+    pub operations: Vec<String>,
+    /// Headers whose values are redacted, besides the sensitive ones.
+    pub redact_headers: Vec<String>,
+    /// Body fields and query parameters whose values are redacted.
+    pub redact_fields: Vec<String>,
+    /// Largest body captured, in bytes.
+    pub max_body_bytes: usize,
+    /// Secret the capture header is signed with; the header is ignored
+    /// without one.
+    pub debug_secret: Option<Secret>,
+}
+
+impl Default for CaptureConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            percentage: DEFAULT_PERCENTAGE,
+            operations: Vec::new(),
+            redact_headers: Vec::new(),
+            redact_fields: Vec::new(),
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            debug_secret: None,
+        }
+    }
+}
+
+impl CaptureConfig {
+    /// Creates a config that only captures requests with a capture
+    /// header, once a debug secret is set.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Turns sampled capture on.
+    #[must_use]
+    pub fn enabled(mut self) -> Self {
+        self.enabled = true;
+        self
+    }
+
+    /// Sets the percentage of eligible requests captured.
+    #[must_use]
+    pub fn percentage(mut self, percentage: f64) -> Self {
+        self.percentage = percentage;
+        self
+    }
+
+    /// Makes an operation eligible for sampled capture.
+    #[must_use]
+    pub fn operation(mut self, operation_id: impl Into<String>) -> Self {
+        self.operations.push(operation_id.into());
+        self
+    }
+
+    /// Redacts the value of a header.
+    #[must_use]
+    pub fn redact_header(mut self, name: impl Into<String>) -> Self {
+        self.redact_headers.push(name.into());
+        self
+    }
+
+    /// Redacts a body field or query parameter.
+    #[must_use]
+    pub fn redact_field(mut self, name: impl Into<String>) -> Self {
+        self.redact_fields.push(name.into());
+        self
+    }
+
+    /// Sets the largest body captured.
+    #[must_use]
+    pub fn max_body_bytes(mut self, max_body_bytes: usize) -> Self {
+        self.max_body_bytes = max_body_bytes;
+        self
+    }
+
+    /// Sets the secret the capture header is signed with.
+    #[must_use]
+    pub fn debug_secret(mut self, secret: impl Into<Secret>) -> Self {
+        self.debug_secret = Some(secret.into());
+        self
+    }
+
+    fn redacts_header(&self, name: &str) -> bool {
+        SENSITIVE_HEADERS
+            .iter()
+            .copied()
+            .chain(self.redact_headers.iter().map(String::as_str))
+            .any(|redacted| redacted.eq_ignore_ascii_case(name))
+    }
+
+    fn redacts_field(&self, name: &str) -> bool {
+        self.redact_fields
+            .iter()
+            .any(|redacted| redacted.eq_ignore_ascii_case(name))
+    }
+}
+
+/// Creates a capture header value for a request, valid until
+/// `expires_at`.
+///
+/// The token is `<expiry>.<signature>`: the expiry in Unix seconds and the
+/// lowercase hex HMAC-SHA256 of `<expiry>:<METHOD>:<path>` under `secret`.
+#[must_use]
+pub fn debug_token(secret: &Secret, method: &Method, path: &str, expires_at: SystemTime) -> String {
+    let expires = expires_at
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |expires| expires.as_secs());
+    let tag = token_mac(secret, expires, method, path)
+        .finalize()
+        .into_bytes();
+    let mut token = format!("{expires}.");
+    for byte in tag {
+        let _ = write!(token, "{byte:02x}");
+    }
+    token
+}
+
+/// Returns `true` if a capture token is unexpired and signed for the
+/// request. The signature comparison is constant-time.
+fn verify_token(secret: &Secret, token: &str, method: &Method, path: &str) -> bool {
+    let Some((expires, signature)) = token.split_once('.') else {
+        return false;
+    };
+    let (Ok(expires), Some(signature)) = (expires.parse::<u64>(), decode_hex(signature)) else {
+        return false;
+    };
+    let unexpired = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .is_ok_and(|now| now.as_secs() <= expires);
+    unexpired
+        && token_mac(secret, expires, method, path)
+            .verify_slice(&signature)
+            .is_ok()
+}
+
+fn token_mac(secret: &Secret, expires: u64, method: &Method, path: &str) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.expose().as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(format!("{expires}:{method}:{path}").as_bytes());
+    mac
+}
+
+/// Returns `true` for the sampled percentage of request IDs.
+#[allow(clippy::cast_precision_loss)]
+fn sampled(request_id: &str, percentage: f64) -> bool {
+    let mut hasher = DefaultHasher::new();
+    request_id.hash(&mut hasher);
+    let roll = (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64;
+    roll * 100.0 < percentage
+}
+
+/// Why a request was captured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureTrigger {
+    /// The request was sampled.
+    Sampled,
+    /// The request carried a valid capture header.
+    Debug,
+}
+
+/// A captured header.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapturedHeader {
+    /// Header name.
+    pub name: String,
+    /// Header value, or [`REDACTED`].
+    pub value: String,
+}
+
+/// A captured request or response body.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CapturedBody {
+    /// Size of the body as sent, in bytes.
+    pub size: usize,
+    /// Media type of the body.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    /// The body, with redactions applied; `None` if the body is empty or
+    /// was left out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// `base64` if `text` holds a binary body, base64-encoded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+}
+
+impl CapturedBody {
+    /// Returns the body bytes, or `None` if the body was left out.
+    #[must_use]
+    pub fn bytes(&self) -> Option<Vec<u8>> {
+        match (&self.text, self.encoding.as_deref()) {
+            (None, _) => (self.size == 0).then(Vec::new),
+            (Some(text), Some("base64")) => STANDARD.decode(text).ok(),
+            (Some(text), _) => Some(text.clone().into_bytes()),
+        }
+    }
+
+    /// Returns `true` for a JSON media type.
+    #[must_use]
+    pub fn is_json(&self) -> bool {
+        self.mime_type
+            .as_deref()
+            .is_some_and(|mime| mime == "application/json" || mime.ends_with("+json"))
+    }
+}
+
+/// A captured request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapturedRequest {
+    /// HTTP method.
+    pub method: String,
+    /// Path and query, with redacted query parameters.
+    pub url: String,
+    /// Headers, in the order received.
+    pub headers: Vec<CapturedHeader>,
+    /// Body.
+    pub body: CapturedBody,
+}
+
+/// A captured response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapturedResponse {
+    /// Status code.
+    pub status: u16,
+    /// Headers.
+    pub headers: Vec<CapturedHeader>,
+    /// Body.
+    pub body: CapturedBody,
+}
+
+/// A captured exchange.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureRecord {
+    /// Request ID.
+    pub request_id: String,
+    /// When the request was captured.
+    pub started_at: DateTime<Utc>,
+    /// Time from receiving the request to its response, in milliseconds.
+    pub time_ms: f64,
+    /// Why the request was captured.
+    pub trigger: CaptureTrigger,
+    /// Resolved operation, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operation_id: Option<String>,
+    /// Subject of the caller identity, if not anonymous.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    /// The request.
+    pub request: CapturedRequest,
+    /// The response.
+    pub response: CapturedResponse,
+}
+
+/// Destination of capture records.
+///
+/// Sinks are called from a blocking thread, one record at a time per
+/// request.
+pub trait CaptureSink: Send + Sync + 'static {
+    /// Writes a record.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the record cannot be written; the request is
+    /// unaffected.
+    fn write(&self, record: &CaptureRecord) -> std::io::Result<()>;
+}
+
+/// Sink appending records as JSON lines to a file, rotating it by size.
+///
+/// When a record would take the file past `max_bytes`, the file is renamed
+/// to `<path>.1`, earlier rotations move up one number, and the oldest
+/// beyond `max_files` is removed.
+#[derive(Debug)]
+pub struct RotatingFileSink {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    file: Mutex<Option<(File, u64)>>,
+}
+
+impl RotatingFileSink {
+    /// Creates a sink writing to `path`, rotating at 10 MiB and keeping 5
+    /// rotated files.
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            max_bytes: 10 * 1024 * 1024,
+            max_files: 5,
+            file: Mutex::new(None),
+        }
+    }
+
+    /// Sets the size at which the file is rotated.
+    #[must_use]
+    pub fn max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Sets the number of rotated files kept.
+    #[must_use]
+    pub fn max_files(mut self, max_files: usize) -> Self {
+        self.max_files = max_files;
+        self
+    }
+
+    /// Returns the path records are written to.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the path of a rotated file.
+    #[must_use]
+    pub fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{index}"));
+        path.into()
+    }
+
+    fn rotate(&self) -> std::io::Result<()> {
+        if self.max_files == 0 {
+            return std::fs::remove_file(&self.path);
+        }
+        let _ = std::fs::remove_file(self.rotated_path(self.max_files));
+        for index in (1..self.max_files).rev() {
+            let from = self.rotated_path(index);
+            if from.exists() {
+                std::fs::rename(from, self.rotated_path(index + 1))?;
+            }
+        }
+        std::fs::rename(&self.path, self.rotated_path(1))
+    }
+}
+
+impl CaptureSink for RotatingFileSink {
+    fn write(&self, record: &CaptureRecord) -> std::io::Result<()> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+
+        let mut file = self
+            .file
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some((_, size)) = file.as_ref() {
+            if *size > 0 && size + line.len() as u64 > self.max_bytes {
+                *file = None;
+                self.rotate()?;
+            }
+        }
+        if file.is_none() {
+            let opened = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?;
+            let size = opened.metadata()?.len();
+            *file = Some((opened, size));
+        }
+
+        let (writer, size) = file.as_mut().expect("file was opened");
+        writer.write_all(&line)?;
+        *size += line.len() as u64;
+        Ok(())
+    }
+}
+
+/// Middleware capturing chosen requests and their responses.
+#[derive(Clone)]
+pub struct CaptureMiddleware {
+    config: Arc<CaptureConfig>,
+    sink: Arc<dyn CaptureSink>,
+}
+
+impl std::fmt::Debug for CaptureMiddleware {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CaptureMiddleware")
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CaptureMiddleware {
+    /// Creates a capture stage writing to `sink`.
+    #[must_use]
+    pub fn new(config: CaptureConfig, sink: impl CaptureSink) -> Self {
+        Self {
+            config: Arc::new(config),
+            sink: Arc::new(sink),
+        }
+    }
+
+    /// Returns the config.
+    #[must_use]
+    pub fn config(&self) -> &CaptureConfig {
+        &self.config
+    }
+
+    /// Returns why a request is captured, if it is.
+    fn trigger(&self, ctx: &MiddlewareContext, request: &Request) -> Option<CaptureTrigger> {
+        let config = &self.config;
+        if let (Some(secret), Some(token)) =
+            (&config.debug_secret, request.headers().get(CAPTURE_HEADER))
+        {
+            let path = request.uri().path();
+            if token
+                .to_str()
+                .is_ok_and(|token| verify_token(secret, token, request.method(), path))
+            {
+                return Some(CaptureTrigger::Debug);
+            }
+            tracing::debug!(path, "ignoring invalid capture header");
+        }
+
+        let eligible = config.operations.is_empty()
+            || ctx
+                .operation_id()
+                .is_some_and(|id| config.operations.iter().any(|operation| operation == id));
+        (config.enabled && eligible && sampled(&ctx.request_id().to_string(), config.percentage))
+            .then_some(CaptureTrigger::Sampled)
+    }
+
+    fn headers(&self, headers: &HeaderMap) -> Vec<CapturedHeader> {
+        headers
+            .iter()
+            .map(|(name, value)| CapturedHeader {
+                name: name.as_str().to_string(),
+                value: if self.config.redacts_header(name.as_str()) {
+                    REDACTED.to_string()
+                } else {
+                    String::from_utf8_lossy(value.as_bytes()).into_owned()
+                },
+            })
+            .collect()
+    }
+
+    fn url(&self, uri: &http::Uri) -> String {
+        match uri.query() {
+            Some(query) => format!("{}?{}", uri.path(), self.redact_pairs(query)),
+            None => uri.path().to_string(),
+        }
+    }
+
+    /// Redacts the values of listed fields in `a=1&b=2` pairs.
+    fn redact_pairs(&self, pairs: &str) -> String {
+        pairs
+            .split('&')
+            .map(|pair| match pair.split_once('=') {
+                Some((name, _)) if self.config.redacts_field(name) => format!("{name}={REDACTED}"),
+                _ => pair.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+
+    fn redact_json(&self, value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map.iter_mut() {
+                    if self.config.redacts_field(key) {
+                        *value = Value::String(REDACTED.to_string());
+                    } else {
+                        self.redact_json(value);
+                    }
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|item| self.redact_json(item)),
+            _ => {}
+        }
+    }
+
+    fn body(&self, headers: &HeaderMap, body: &[u8]) -> CapturedBody {
+        let mut captured = CapturedBody {
+            size: body.len(),
+            mime_type: headers
+                .get(CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.split(';').next())
+                .map(|mime| mime.trim().to_ascii_lowercase()),
+            text: None,
+            encoding: None,
+        };
+        if body.is_empty() || body.len() > self.config.max_body_bytes {
+            return captured;
+        }
+
+        let redacting = !self.config.redact_fields.is_empty();
+        if captured.is_json() && redacting {
+            captured.text = serde_json::from_slice::<Value>(body).ok().map(|mut value| {
+                self.redact_json(&mut value);
+                value.to_string()
+            });
+            return captured;
+        }
+        match std::str::from_utf8(body) {
+            Ok(text)
+                if redacting
+                    && captured.mime_type.as_deref()
+                        == Some("application/x-www-form-urlencoded") =>
+            {
+                captured.text = Some(self.redact_pairs(text));
+            }
+            Ok(text) => captured.text = Some(text.to_string()),
+            Err(_) => {
+                captured.text = Some(STANDARD.encode(body));
+                captured.encoding = Some("base64".to_string());
+            }
+        }
+        captured
+    }
+
+    async fn write(&self, record: CaptureRecord) {
+        let sink = Arc::clone(&self.sink);
+        let request_id = record.request_id.clone();
+        match tokio::task::spawn_blocking(move || sink.write(&record)).await {
+            Ok(Ok(())) => tracing::debug!(request_id, "request captured"),
+            Ok(Err(error)) => tracing::warn!(request_id, %error, "failed to write capture"),
+            Err(error) => tracing::warn!(request_id, %error, "capture sink panicked"),
+        }
+    }
+}
+
+impl Middleware for CaptureMiddleware {
+    fn name(&self) -> &'static str {
+        "capture"
+    }
+
+    fn process<'a>(
+        &'a self,
+        ctx: &'a mut MiddlewareContext,
+        request: Request,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Response> {
+        Box::pin(async move {
+            if ctx.is_internal() {
+                return next.run(ctx, request).await;
+            }
+            let Some(trigger) = self.trigger(ctx, &request) else {
+                return next.run(ctx, request).await;
+            };
+            let started_at = Utc::now();
+
+            let (parts, body) = request.into_parts();
+            let body = match parts.extensions.get::<RequestBody>() {
+                Some(RequestBody(buffered)) => Bytes::from(buffered.clone()),
+                None => body
+                    .collect()
+                    .await
+                    .map_or_else(|never| match never {}, |collected| collected.to_bytes()),
+            };
+            let captured_request = CapturedRequest {
+                method: parts.method.to_string(),
+                url: self.url(&parts.uri),
+                headers: self.headers(&parts.headers),
+                body: self.body(&parts.headers, &body),
+            };
+            let request = Request::from_parts(parts, Full::new(body));
+
+            let response = next.run(ctx, request).await;
+            let (parts, body) = response.into_parts();
+            let body = body
+                .collect()
+                .await
+                .map_or_else(|never| match never {}, |collected| collected.to_bytes());
+
+            let record = CaptureRecord {
+                request_id: ctx.request_id().to_string(),
+                started_at,
+                time_ms: ctx.elapsed().as_secs_f64() * 1000.0,
+                trigger,
+                operation_id: ctx.operation_id().map(str::to_string),
+                subject: match ctx.identity() {
+                    CallerIdentity::Anonymous => None,
+                    identity => Some(identity.log_id()),
+                },
+                request: captured_request,
+                response: CapturedResponse {
+                    status: parts.status.as_u16(),
+                    headers: self.headers(&parts.headers),
+                    body: self.body(&parts.headers, &body),
+                },
+            };
+            self.write(record).await;
+
+            Response::from_parts(parts, Full::new(body))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::{Request as HttpRequest, Response as HttpResponse, StatusCode};
+    use std::time::Duration;
+
+    /// Sink keeping records in memory.
+    #[derive(Clone, Default)]
+    struct MemorySink(Arc<Mutex<Vec<CaptureRecord>>>);
+
+    impl MemorySink {
+        fn records(&self) -> Vec<CaptureRecord> {
+            self.0.lock().unwrap().clone()
+        }
+    }
+
+    impl CaptureSink for MemorySink {
+        fn write(&self, record: &CaptureRecord) -> std::io::Result<()> {
+            self.0.lock().unwrap().push(record.clone());
+            Ok(())
+        }
+    }
+
+    fn json_handler() -> impl FnOnce(&mut MiddlewareContext, Request) -> BoxFuture<'static, Response>
+    {
+        |_ctx, _req| {
+            Box::pin(async {
+                HttpResponse::builder()
+                    .status(StatusCode::CREATED)
+                    .header(CONTENT_TYPE, "application/json")
+                    .header("set-cookie", "session=abc")
+                    .body(Full::new(Bytes::from(
+                        r#"{"id":"o-1","card":{"cardNumber":"4111"}}"#,
+                    )))
+                    .unwrap()
+            })
+        }
+    }
+
+    async fn run(stage: &CaptureMiddleware, request: Request) -> Response {
+        let mut ctx = MiddlewareContext::new();
+        ctx.set_operation_id("createOrder".to_string());
+        ctx.set_identity(CallerIdentity::user("user-123", "alice@example.com"));
+        stage
+            .process(&mut ctx, request, Next::handler(json_handler()))
+            .await
+    }
+
+    fn order_request() -> HttpRequest<Full<Bytes>> {
+        HttpRequest::builder()
+            .method(Method::POST)
+            .uri("/orders?token=abc&page=2")
+            .header(CONTENT_TYPE, "application/json")
+            .header("authorization", "Bearer secret-token")
+            .header("x-api-key", "key-1")
+            .header("x-trace", "t-1")
+            .body(Full::new(Bytes::from(
+                r#"{"item":"book","payment":{"cardNumber":"4111","cvv":"123"}}"#,
+            )))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_capture_applies_redactions() {
+        let sink = MemorySink::default();
+        let config = CaptureConfig::new()
+            .enabled()
+            .percentage(100.0)
+            .redact_header("x-api-key")
+            .redact_field("cardNumber")
+            .redact_field("cvv")
+            .redact_field("token");
+        let stage = CaptureMiddleware::new(config, sink.clone());
+
+        let response = run(&stage, order_request()).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(
+            body,
+            Bytes::from(r#"{"id":"o-1","card":{"cardNumber":"4111"}}"#)
+        );
+
+        let records = sink.records();
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(record.trigger, CaptureTrigger::Sampled);
+        assert_eq!(record.operation_id.as_deref(), Some("createOrder"));
+        assert_eq!(record.subject.as_deref(), Some("user:user-123"));
+        assert_eq!(record.request.url, "/orders?token=[REDACTED]&page=2");
+
+        let header = |headers: &[CapturedHeader], name: &str| {
+            headers
+                .iter()
+                .find(|header| header.name == name)
+                .map(|header| header.value.clone())
+        };
+        assert_eq!(
+            header(&record.request.headers, "authorization").as_deref(),
+            Some(REDACTED)
+        );
+        assert_eq!(
+            header(&record.request.headers, "x-api-key").as_deref(),
+            Some(REDACTED)
+        );
+        assert_eq!(
+            header(&record.request.headers, "x-trace").as_deref(),
+            Some("t-1")
+        );
+        assert_eq!(
+            header(&record.response.headers, "set-cookie").as_deref(),
+            Some(REDACTED)
+        );
+
+        let request_body: Value =
+            serde_json::from_str(record.request.body.text.as_deref().unwrap()).unwrap();
+        assert_eq!(
+            request_body,
+            serde_json::json!({
+                "item": "book",
+                "payment": {"cardNumber": REDACTED, "cvv": REDACTED}
+            })
+        );
+        assert_eq!(record.response.status, 201);
+        assert!(!record
+            .response
+            .body
+            .text
+            .as_deref()
+            .unwrap()
+            .contains("4111"));
+
+        let serialized = serde_json::to_string(record).unwrap();
+        assert!(!serialized.contains("secret-token"));
+        assert!(!serialized.contains("4111"));
+    }
+
+    #[tokio::test]
+    async fn test_sampling_and_operations() {
+        let sink = MemorySink::default();
+        let stage = CaptureMiddleware::new(
+            CaptureConfig::new()
+                .enabled()
+                .percentage(100.0)
+                .operation("getOrder"),
+            sink.clone(),
+        );
+        run(&stage, order_request()).await;
+        assert!(sink.records().is_empty());
+
+        let stage = CaptureMiddleware::new(CaptureConfig::new().percentage(100.0), sink.clone());
+        run(&stage, order_request()).await;
+        assert!(sink.records().is_empty());
+
+        let stage =
+            CaptureMiddleware::new(CaptureConfig::new().enabled().percentage(0.0), sink.clone());
+        run(&stage, order_request()).await;
+        assert!(sink.records().is_empty());
+
+        assert!(sampled("request-1", 100.0));
+        assert!(!sampled("request-1", 0.0));
+        assert_eq!(sampled("request-1", 50.0), sampled("request-1", 50.0));
+    }
+
+    #[tokio::test]
+    async fn test_signed_header_forces_capture() {
+        let sink = MemorySink::default();
+        let secret = Secret::new("admin-secret");
+        let stage = CaptureMiddleware::new(
+            CaptureConfig::new().debug_secret(secret.clone()),
+            sink.clone(),
+        );
+        let expires = SystemTime::now() + Duration::from_secs(60);
+
+        let mut request = order_request();
+        let token = debug_token(&secret, &Method::POST, "/orders", expires);
+        request
+            .headers_mut()
+            .insert(CAPTURE_HEADER, token.parse().unwrap());
+        run(&stage, request).await;
+        let records = sink.records();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].trigger, CaptureTrigger::Debug);
+
+        // Signed for another path, with another secret, or expired
+        let tokens = [
+            debug_token(&secret, &Method::POST, "/users", expires),
+            debug_token(&Secret::new("guess"), &Method::POST, "/orders", expires),
+            debug_token(
+                &secret,
+                &Method::POST,
+                "/orders",
+                SystemTime::now() - Duration::from_secs(60),
+            ),
+        ];
+        for token in tokens {
+            let mut request = order_request();
+            request
+                .headers_mut()
+                .insert(CAPTURE_HEADER, token.parse().unwrap());
+            run(&stage, request).await;
+        }
+        assert_eq!(sink.records().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_large_and_binary_bodies() {
+        let sink = MemorySink::default();
+        let stage = CaptureMiddleware::new(
+            CaptureConfig::new()
+                .enabled()
+                .percentage(100.0)
+                .max_body_bytes(16),
+            sink.clone(),
+        );
+        run(&stage, order_request()).await;
+        let records = sink.records();
+        let record = &records[0];
+        assert_eq!(record.request.body.text, None);
+        assert_eq!(record.request.body.bytes(), None);
+        assert!(record.request.body.size > 16);
+
+        let body = CaptureMiddleware::new(CaptureConfig::new(), sink)
+            .body(&HeaderMap::new(), &[0xff, 0x00, 0x10]);
+        assert_eq!(body.encoding.as_deref(), Some("base64"));
+        assert_eq!(body.bytes(), Some(vec![0xff, 0x00, 0x10]));
+    }
+
+    #[test]
+    fn test_rotating_file_sink() {
+        let dir = std::env::temp_dir().join(format!("archimedes-capture-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let sink = RotatingFileSink::new(dir.join("captures.jsonl"))
+            .max_bytes(1)
+            .max_files(2);
+        let record = CaptureRecord {
+            request_id: "r-1".to_string(),
+            started_at: Utc::now(),
+            time_ms: 1.5,
+            trigger: CaptureTrigger::Sampled,
+            operation_id: None,
+            subject: None,
+            request: CapturedRequest {
+                method: "GET".to_string(),
+                url: "/".to_string(),
+                headers: Vec::new(),
+                body: CapturedBody::default(),
+            },
+            response: CapturedResponse {
+                status: 200,
+                headers: Vec::new(),
+                body: CapturedBody::default(),
+            },
+        };
+
+        for _ in 0..4 {
+            sink.write(&record).unwrap();
+        }
+        assert!(sink.path().exists());
+        assert!(sink.rotated_path(1).exists());
+        assert!(sink.rotated_path(2).exists());
+        assert!(!sink.rotated_path(3).exists());
+
+        let line = std::fs::read_to_string(sink.path()).unwrap();
+        let read: CaptureRecord = serde_json::from_str(line.trim()).unwrap();
+        assert_eq!(read, record);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -21,11 +21,18 @@
 //!   "error": {
 //!     "code": "ERROR_CODE",
 //!     "message": "Human-readable error message",
-//!     "request_id": "uuid-v7-request-id"
+//!     "request_id": "uuid-v7-request-id",
+//!     "details": { "issues": [] }
 //!   }
 //! }
 //! ```
 //!
+//! When the handler's response body is itself an error envelope, its
+//! `message` and `details` are carried over. This keeps per-field issues,
+//! such as every failing extractor of a request, in the normalized
+//! response. Server errors keep neither unless internal errors are
+//! exposed.
+//!
 //! # Example
 //!
 //! ```rust,ignore
@@ -37,16 +44,47 @@
 //! // With verbose internal errors (development only)
 //! let error_norm = ErrorNormalizationMiddleware::new()
 //!     .expose_internal_errors(true);
+//!
+//! // Remap error categories/codes to custom statuses
+//! let error_norm = ErrorNormalizationMiddleware::new().status_map(
+//!     StatusMap::new()
+//!         .map_code("DUPLICATE_EMAIL", StatusCode::CONFLICT)
+//!         .map_category(ErrorCategory::External, StatusCode::SERVICE_UNAVAILABLE),
+//! );
 //! ```
+//!
+//! # Status Mapping
+//!
+//! Handlers (or earlier stages) can attach an [`ErrorClassification`] to the
+//! response extensions to describe the error. When one is present, the
+//! configured [`StatusMap`] decides the final HTTP status. Code-level
+//! overrides win over category-level overrides; unmapped errors keep the
+//! status produced by the handler. Because this stage runs closest to the
+//! handler, response validation and telemetry observe the remapped status.
+//!
+//! # Localized Messages
+//!
+//! With a [`MessageCatalog`], the stage negotiates a locale from the
+//! request's `Accept-Language` header before the handler runs and records
+//! it on the context, where handlers find it as `RequestContext::locale`.
+//! Error messages, and the messages of issues in `details`, are rendered
+//! from the catalog's templates for their codes; the codes themselves are
+//! never localized and the original messages are kept as
+//! `developer_message`. Localized responses carry `Content-Language`. See
+//! [`i18n`](crate::i18n) for the catalog format.
 
 use crate::{
     context::MiddlewareContext,
+    i18n::MessageCatalog,
     middleware::{BoxFuture, Middleware, Next},
     types::{Request, Response},
 };
+use archimedes_core::{ErrorCategory, ThemisError};
 use bytes::Bytes;
-use http::StatusCode;
-use http_body_util::Full;
+use http::header::{ACCEPT_LANGUAGE, CONTENT_LANGUAGE};
+use http::{HeaderValue, StatusCode};
+use http_body_util::{BodyExt, Full};
+use std::collections::HashMap;
 
 /// Error normalization middleware that ensures consistent error responses.
 #[derive(Debug, Clone)]
@@ -55,6 +93,124 @@ pub struct ErrorNormalizationMiddleware {
     expose_internal_errors: bool,
     /// Default error message for internal errors.
     internal_error_message: String,
+    /// Overrides for the category/code → status mapping.
+    status_map: StatusMap,
+    /// Templates for localized error messages.
+    messages: Option<MessageCatalog>,
+}
+
+/// Classification of an error response.
+///
+/// Attach this to the response extensions so the error normalization stage
+/// can apply [`StatusMap`] overrides.
+///
+/// # Example
+///
+/// ```
+/// use archimedes_core::ErrorCategory;
+/// use archimedes_middleware::stages::error_normalization::ErrorClassification;
+///
+/// let class = ErrorClassification::new(ErrorCategory::Validation).with_code("DUPLICATE_EMAIL");
+/// assert_eq!(class.code.as_deref(), Some("DUPLICATE_EMAIL"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorClassification {
+    /// The error category.
+    pub category: ErrorCategory,
+    /// Optional machine-readable error code (a subtype of the category).
+    pub code: Option<String>,
+}
+
+impl ErrorClassification {
+    /// Creates a classification for the given category.
+    #[must_use]
+    pub fn new(category: ErrorCategory) -> Self {
+        Self {
+            category,
+            code: None,
+        }
+    }
+
+    /// Sets the error code.
+    #[must_use]
+    pub fn with_code(mut self, code: impl Into<String>) -> Self {
+        self.code = Some(code.into());
+        self
+    }
+}
+
+impl From<&ThemisError> for ErrorClassification {
+    fn from(error: &ThemisError) -> Self {
+        Self::new(error.category()).with_code(error.to_envelope(None).error.code)
+    }
+}
+
+/// Overrides for the default error category → HTTP status mapping.
+///
+/// Lookups check code-level overrides first, then category-level overrides.
+/// When neither matches, [`StatusMap::resolve`] returns `None` and the
+/// default status is retained.
+///
+/// # Example
+///
+/// ```
+/// use archimedes_core::ErrorCategory;
+/// use archimedes_middleware::stages::error_normalization::{ErrorClassification, StatusMap};
+/// use http::StatusCode;
+///
+/// let map = StatusMap::new().map_category(ErrorCategory::Validation, StatusCode::UNPROCESSABLE_ENTITY);
+///
+/// let class = ErrorClassification::new(ErrorCategory::Validation);
+/// assert_eq!(map.resolve(&class), Some(StatusCode::UNPROCESSABLE_ENTITY));
+///
+/// let class = ErrorClassification::new(ErrorCategory::NotFound);
+/// assert_eq!(map.resolve(&class), None);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StatusMap {
+    /// Category-level overrides.
+    categories: HashMap<ErrorCategory, StatusCode>,
+    /// Code-level overrides (take precedence over categories).
+    codes: HashMap<String, StatusCode>,
+}
+
+impl StatusMap {
+    /// Creates an empty status map (all defaults retained).
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Overrides the status for an error category.
+    #[must_use]
+    pub fn map_category(mut self, category: ErrorCategory, status: StatusCode) -> Self {
+        self.categories.insert(category, status);
+        self
+    }
+
+    /// Overrides the status for a specific error code.
+    #[must_use]
+    pub fn map_code(mut self, code: impl Into<String>, status: StatusCode) -> Self {
+        self.codes.insert(code.into(), status);
+        self
+    }
+
+    /// Returns `true` if no overrides are configured.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.categories.is_empty() && self.codes.is_empty()
+    }
+
+    /// Resolves the overridden status for a classification, if any.
+    #[must_use]
+    pub fn resolve(&self, classification: &ErrorClassification) -> Option<StatusCode> {
+        classification
+            .code
+            .as_ref()
+            .and_then(|code| self.codes.get(code))
+            .or_else(|| self.categories.get(&classification.category))
+            .copied()
+    }
 }
 
 /// Normalized error data stored in context.
@@ -83,6 +239,8 @@ impl ErrorNormalizationMiddleware {
         Self {
             expose_internal_errors: false,
             internal_error_message: "An internal error occurred".to_string(),
+            status_map: StatusMap::new(),
+            messages: None,
         }
     }
 
@@ -102,45 +260,89 @@ impl ErrorNormalizationMiddleware {
         self
     }
 
-    /// Normalizes an error response.
-    fn normalize_error_response(&self, ctx: &MiddlewareContext, response: Response) -> Response {
-        let status = response.status();
+    /// Sets the category/code → status overrides.
+    #[must_use]
+    pub fn status_map(mut self, status_map: StatusMap) -> Self {
+        self.status_map = status_map;
+        self
+    }
 
+    /// Localizes error messages with `catalog`, in the locale negotiated
+    /// from each request's `Accept-Language` header.
+    #[must_use]
+    pub fn messages(mut self, catalog: MessageCatalog) -> Self {
+        self.messages = Some(catalog);
+        self
+    }
+
+    /// Determines the final status and error code for an error response.
+    fn resolve_status(&self, response: &Response) -> (StatusCode, String) {
+        let classification = response.extensions().get::<ErrorClassification>();
+        let status = classification
+            .and_then(|class| self.status_map.resolve(class))
+            .unwrap_or_else(|| response.status());
+        let code = classification
+            .and_then(|class| class.code.clone())
+            .unwrap_or_else(|| self.status_to_code(status));
+        (status, code)
+    }
+
+    /// Normalizes an error response.
+    fn normalize_error_response(
+        &self,
+        ctx: &MiddlewareContext,
+        response: Response,
+        body: &[u8],
+        status: StatusCode,
+        code: &str,
+    ) -> Response {
         // Only normalize error responses (4xx and 5xx)
         if status.is_success() || status.is_informational() || status.is_redirection() {
             return response;
         }
 
-        // Get error code from status
-        let code = self.status_to_code(status);
-
-        // Get message - either from body or default
-        let message = if status.is_server_error() && !self.expose_internal_errors {
-            self.internal_error_message.clone()
+        // Get message and details - either from body or default
+        let (message, details) = if status.is_server_error() && !self.expose_internal_errors {
+            (self.internal_error_message.clone(), None)
         } else {
-            self.extract_message_from_response(&response)
-                .unwrap_or_else(|| {
-                    status
-                        .canonical_reason()
-                        .unwrap_or("Unknown error")
-                        .to_string()
-                })
+            let (message, details) = Self::parse_error_body(body);
+            let message = message.unwrap_or_else(|| {
+                status
+                    .canonical_reason()
+                    .unwrap_or("Unknown error")
+                    .to_string()
+            });
+            (message, details)
         };
 
         // Create normalized error response
-        let error_body = serde_json::json!({
+        let mut error_body = serde_json::json!({
             "error": {
                 "code": code,
                 "message": message,
                 "request_id": ctx.request_id().to_string()
             }
         });
+        if let Some(details) = details {
+            error_body["error"]["details"] = details;
+        }
 
-        http::Response::builder()
+        let mut content_language = None;
+        if let (Some(catalog), Some(locale)) = (&self.messages, ctx.locale()) {
+            if catalog.localize_error(locale, &mut error_body["error"]) {
+                content_language = HeaderValue::from_str(locale).ok();
+            }
+        }
+
+        let mut response = http::Response::builder()
             .status(status)
             .header(http::header::CONTENT_TYPE, "application/json")
             .body(Full::new(Bytes::from(error_body.to_string())))
-            .expect("failed to build error response")
+            .expect("failed to build error response");
+        if let Some(locale) = content_language {
+            response.headers_mut().insert(CONTENT_LANGUAGE, locale);
+        }
+        response
     }
 
     /// Converts HTTP status to error code.
@@ -163,11 +365,22 @@ impl ErrorNormalizationMiddleware {
         }
     }
 
-    /// Extracts error message from response body if possible.
-    fn extract_message_from_response(&self, _response: &Response) -> Option<String> {
-        // In a real implementation, we'd try to parse the body
-        // For now, return None to use default message
-        None
+    /// Extracts the message and details from an error envelope body.
+    ///
+    /// Bodies that are not `{"error": {...}}` objects yield neither.
+    fn parse_error_body(body: &[u8]) -> (Option<String>, Option<serde_json::Value>) {
+        let Ok(serde_json::Value::Object(mut envelope)) = serde_json::from_slice(body) else {
+            return (None, None);
+        };
+        let Some(serde_json::Value::Object(mut error)) = envelope.remove("error") else {
+            return (None, None);
+        };
+        let message = match error.remove("message") {
+            Some(serde_json::Value::String(message)) => Some(message),
+            _ => None,
+        };
+        let details = error.remove("details").filter(|details| !details.is_null());
+        (message, details)
     }
 }
 
@@ -183,13 +396,20 @@ impl Middleware for ErrorNormalizationMiddleware {
         next: Next<'a>,
     ) -> BoxFuture<'a, Response> {
         Box::pin(async move {
+            if let Some(catalog) = &self.messages {
+                let accept_language = request
+                    .headers()
+                    .get(ACCEPT_LANGUAGE)
+                    .and_then(|value| value.to_str().ok());
+                ctx.set_locale(catalog.negotiate(accept_language));
+            }
+
             // Process the request
             let response = next.run(ctx, request).await;
 
             // Check if it's an error response
             if response.status().is_client_error() || response.status().is_server_error() {
-                let status = response.status();
-                let code = self.status_to_code(status);
+                let (status, code) = self.resolve_status(&response);
 
                 // Store normalized error info in context
                 ctx.set_extension(NormalizedError {
@@ -202,8 +422,16 @@ impl Middleware for ErrorNormalizationMiddleware {
                     was_internal: status.is_server_error(),
                 });
 
+                // Buffer the body to carry over its message and details
+                let (parts, body) = response.into_parts();
+                let body = match body.collect().await {
+                    Ok(collected) => collected.to_bytes(),
+                    Err(never) => match never {},
+                };
+                let response = Response::from_parts(parts, Full::new(body.clone()));
+
                 // Normalize the error response
-                self.normalize_error_response(ctx, response)
+                self.normalize_error_response(ctx, response, &body, status, &code)
             } else {
                 response
             }
@@ -375,6 +603,253 @@ mod tests {
         assert_eq!(middleware.internal_error_message, "Custom internal error");
     }
 
+    fn classified_error_handler(
+        status: StatusCode,
+        classification: ErrorClassification,
+    ) -> impl FnOnce(&mut MiddlewareContext, Request) -> BoxFuture<'static, Response> {
+        move |_ctx, _req| {
+            Box::pin(async move {
+                let mut response = error_response(status);
+                response.extensions_mut().insert(classification);
+                response
+            })
+        }
+    }
+
+    async fn body_json(response: Response) -> serde_json::Value {
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[test]
+    fn test_status_map_resolution() {
+        let map = StatusMap::new()
+            .map_category(ErrorCategory::Validation, StatusCode::UNPROCESSABLE_ENTITY)
+            .map_code("DUPLICATE_EMAIL", StatusCode::CONFLICT);
+
+        let plain = ErrorClassification::new(ErrorCategory::Validation);
+        let subtype = plain.clone().with_code("DUPLICATE_EMAIL");
+        let other_code = plain.clone().with_code("BAD_FORMAT");
+        let unmapped = ErrorClassification::new(ErrorCategory::NotFound);
+
+        assert_eq!(map.resolve(&plain), Some(StatusCode::UNPROCESSABLE_ENTITY));
+        assert_eq!(map.resolve(&subtype), Some(StatusCode::CONFLICT));
+        assert_eq!(
+            map.resolve(&other_code),
+            Some(StatusCode::UNPROCESSABLE_ENTITY)
+        );
+        assert_eq!(map.resolve(&unmapped), None);
+        assert!(StatusMap::new().is_empty());
+    }
+
+    #[test]
+    fn test_classification_from_themis_error() {
+        let error = ThemisError::conflict("already exists");
+        let class = ErrorClassification::from(&error);
+        assert_eq!(class.category, ErrorCategory::Conflict);
+        assert_eq!(class.code.as_deref(), Some("CONFLICT"));
+    }
+
+    #[tokio::test]
+    async fn test_category_override_changes_status_and_envelope() {
+        let middleware = ErrorNormalizationMiddleware::new()
+            .status_map(StatusMap::new().map_code("DUPLICATE_EMAIL", StatusCode::CONFLICT));
+        let mut ctx = MiddlewareContext::new();
+
+        let class =
+            ErrorClassification::new(ErrorCategory::Validation).with_code("DUPLICATE_EMAIL");
+        let next = Next::handler(classified_error_handler(StatusCode::BAD_REQUEST, class));
+
+        let response = middleware
+            .process(&mut ctx, make_test_request(), next)
+            .await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let error = ctx.get_extension::<NormalizedError>().unwrap();
+        assert_eq!(error.status_code, 409);
+        assert_eq!(error.code, "DUPLICATE_EMAIL");
+
+        let body = body_json(response).await;
+        assert_eq!(body["error"]["code"], "DUPLICATE_EMAIL");
+        assert_eq!(body["error"]["request_id"], ctx.request_id().to_string());
+    }
+
+    #[tokio::test]
+    async fn test_unmapped_category_keeps_default_status() {
+        let middleware = ErrorNormalizationMiddleware::new().status_map(
+            StatusMap::new().map_category(ErrorCategory::Validation, StatusCode::CONFLICT),
+        );
+        let mut ctx = MiddlewareContext::new();
+
+        let class = ErrorClassification::new(ErrorCategory::NotFound);
+        let next = Next::handler(classified_error_handler(StatusCode::NOT_FOUND, class));
+
+        let response = middleware
+            .process(&mut ctx, make_test_request(), next)
+            .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            ctx.get_extension::<NormalizedError>().unwrap().status_code,
+            404
+        );
+    }
+
+    fn envelope_handler(
+        status: StatusCode,
+        body: serde_json::Value,
+    ) -> impl FnOnce(&mut MiddlewareContext, Request) -> BoxFuture<'static, Response> {
+        move |_ctx, _req| {
+            Box::pin(async move {
+                HttpResponse::builder()
+                    .status(status)
+                    .body(Full::new(Bytes::from(body.to_string())))
+                    .unwrap()
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_client_error_keeps_message_and_issues() {
+        let middleware = ErrorNormalizationMiddleware::new();
+        let mut ctx = MiddlewareContext::new();
+
+        let issues = serde_json::json!({
+            "issues": [
+                {"source": "path", "field": "user_id", "message": "invalid digit"},
+                {"source": "body", "field": "email", "message": "missing required value"}
+            ]
+        });
+        let next = Next::handler(envelope_handler(
+            StatusCode::BAD_REQUEST,
+            serde_json::json!({
+                "error": {
+                    "code": "INVALID_REQUEST",
+                    "message": "2 request issues",
+                    "details": issues
+                }
+            }),
+        ));
+
+        let response = middleware
+            .process(&mut ctx, make_test_request(), next)
+            .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = body_json(response).await;
+        assert_eq!(body["error"]["message"], "2 request issues");
+        assert_eq!(body["error"]["details"], issues);
+        assert_eq!(body["error"]["request_id"], ctx.request_id().to_string());
+    }
+
+    #[tokio::test]
+    async fn test_server_error_hides_details() {
+        let middleware = ErrorNormalizationMiddleware::new();
+        let mut ctx = MiddlewareContext::new();
+
+        let next = Next::handler(envelope_handler(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            serde_json::json!({
+                "error": {"message": "db password rejected", "details": {"host": "db-1"}}
+            }),
+        ));
+
+        let response = middleware
+            .process(&mut ctx, make_test_request(), next)
+            .await;
+        let body = body_json(response).await;
+        assert_eq!(body["error"]["message"], "An internal error occurred");
+        assert!(body["error"].get("details").is_none());
+    }
+
+    fn localized_request(accept_language: &str) -> Request {
+        HttpRequest::builder()
+            .uri("/test")
+            .header(ACCEPT_LANGUAGE, accept_language)
+            .body(Full::new(Bytes::new()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_error_message_localized() {
+        let middleware = ErrorNormalizationMiddleware::new().messages(
+            MessageCatalog::new("en")
+                .with_message("de", "BAD_REQUEST", "Ungültige Anfrage")
+                .with_message("de", "FIELD_REQUIRED", "Das Feld {field} fehlt"),
+        );
+        let mut ctx = MiddlewareContext::new();
+
+        let next = Next::handler(envelope_handler(
+            StatusCode::BAD_REQUEST,
+            serde_json::json!({
+                "error": {
+                    "code": "INVALID_REQUEST",
+                    "message": "1 request issue",
+                    "details": {
+                        "issues": [
+                            {"field": "email", "code": "FIELD_REQUIRED", "message": "Missing required field: email"}
+                        ]
+                    }
+                }
+            }),
+        ));
+
+        let response = middleware
+            .process(&mut ctx, localized_request("fr;q=0.9, de-DE"), next)
+            .await;
+        assert_eq!(ctx.locale(), Some("de"));
+        assert_eq!(response.headers()[CONTENT_LANGUAGE], "de");
+
+        let body = body_json(response).await;
+        assert_eq!(body["error"]["code"], "BAD_REQUEST");
+        assert_eq!(body["error"]["message"], "Ungültige Anfrage");
+        assert_eq!(body["error"]["developer_message"], "1 request issue");
+
+        let issue = &body["error"]["details"]["issues"][0];
+        assert_eq!(issue["code"], "FIELD_REQUIRED");
+        assert_eq!(issue["message"], "Das Feld email fehlt");
+        assert_eq!(issue["developer_message"], "Missing required field: email");
+    }
+
+    #[tokio::test]
+    async fn test_handler_sees_negotiated_locale() {
+        let middleware = ErrorNormalizationMiddleware::new()
+            .messages(MessageCatalog::new("en").with_message("fr", "NOT_FOUND", "Introuvable"));
+        let mut ctx = MiddlewareContext::new();
+
+        let next = Next::handler(|ctx: &mut MiddlewareContext, _req| {
+            let locale = ctx.to_request_context().locale().map(str::to_string);
+            Box::pin(async move {
+                HttpResponse::builder()
+                    .status(StatusCode::OK)
+                    .body(Full::new(Bytes::from(locale.unwrap_or_default())))
+                    .unwrap()
+            })
+        });
+        let response = middleware
+            .process(&mut ctx, localized_request("it, fr-CA;q=0.8"), next)
+            .await;
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body.as_ref(), b"fr");
+    }
+
+    #[tokio::test]
+    async fn test_unknown_code_keeps_developer_message() {
+        let middleware = ErrorNormalizationMiddleware::new()
+            .messages(MessageCatalog::new("en").with_message("de", "NOT_FOUND", "Nicht gefunden"));
+        let mut ctx = MiddlewareContext::new();
+
+        let next = Next::handler(create_error_handler(StatusCode::CONFLICT));
+        let response = middleware
+            .process(&mut ctx, localized_request("de"), next)
+            .await;
+        assert!(response.headers().get(CONTENT_LANGUAGE).is_none());
+
+        let body = body_json(response).await;
+        assert_eq!(body["error"]["code"], "CONFLICT");
+        assert_eq!(body["error"]["message"], "Conflict");
+        assert!(body["error"].get("developer_message").is_none());
+    }
+
     #[test]
     fn test_normalized_error_structure() {
         let error = NormalizedError {
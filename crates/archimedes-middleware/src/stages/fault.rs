@@ -0,0 +1,925 @@
+//! Fault injection for resilience testing.
+//!
+//! The fault stage makes chosen requests fail on purpose, so a service and
+//! its clients can be tested against slow responses, errors, broken bodies
+//! and dropped connections. It is off twice over: the stage exists only
+//! with the `chaos` feature, and its config injects nothing until
+//! `enabled = true`. It also refuses to run in the `production` profile.
+//!
+//! # Pipeline Position
+//!
+//! Faults run after resolution, so rules can name operations, and wrap the
+//! handler:
+//!
+//! ```text
+//! Identity → Authorization → Validation → [FaultInjection] → Handler
+//! ```
+//!
+//! # Faults
+//!
+//! A request takes the faults of the first rule matching its operation ID
+//! or path. `*` in a path glob matches one segment and `**` any number of
+//! them. Each fault is rolled for separately:
+//!
+//! - `latency` delays the request by `ms`, or a random time between `ms`
+//!   and `max_ms`
+//! - `abort` answers `status` instead of calling the handler
+//! - `truncate` cuts `bytes` off the end of the response body
+//! - `reset` drops the connection instead of answering
+//!
+//! ```toml
+//! enabled = true
+//!
+//! [[rules]]
+//! operation = "getCart"
+//! latency = { ms = 100, max_ms = 500 }
+//! abort = { percentage = 5, status = 503 }
+//!
+//! [[rules]]
+//! path = "/files/**"
+//! truncate = { percentage = 10, bytes = 64 }
+//! reset = { percentage = 1 }
+//! ```
+//!
+//! # Hot Reload
+//!
+//! [`Faults`] is a shared handle: clones see the same config, and
+//! [`Faults::reload_from_file`] swaps in a new fault file for all of them,
+//! keeping the current config if the file is invalid.
+//!
+//! # Observability
+//!
+//! Every injected fault is logged at `WARN` under the
+//! [`FAULT_LOG_TARGET`] target with a `CHAOS` marker, counted in
+//! `archimedes_faults_injected_total`, labelled by `type` and `operation`,
+//! and named in the `X-Archimedes-Fault` response header, so test
+//! harnesses can tell injected failures from real ones.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use archimedes_middleware::stages::{FaultInjectionMiddleware, Faults};
+//!
+//! let faults = Faults::from_toml_file("faults.toml")?;
+//! let stage = FaultInjectionMiddleware::new(faults.clone(), &config.telemetry.environment)?;
+//!
+//! // Later, when faults.toml changes
+//! faults.reload_from_file("faults.toml")?;
+//! ```
+
+use crate::context::MiddlewareContext;
+use crate::middleware::{BoxFuture, Middleware, Next};
+use crate::types::{Request, ResetConnection, Response, ResponseExt};
+use bytes::Bytes;
+use http::header::CONTENT_LENGTH;
+use http::{HeaderValue, StatusCode};
+use http_body_util::{BodyExt, Full};
+use metrics::counter;
+use serde::Deserialize;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// Injected faults, by type and operation.
+pub const FAULTS_INJECTED: &str = "archimedes_faults_injected_total";
+
+/// Response header naming the faults injected into a response.
+pub const FAULT_HEADER: &str = "x-archimedes-fault";
+
+/// Log target of injected fault events.
+pub const FAULT_LOG_TARGET: &str = "archimedes::chaos";
+
+/// Environment the fault stage refuses to run in.
+pub const PRODUCTION_ENVIRONMENT: &str = "production";
+
+/// Error code of aborted requests.
+const FAULT_CODE: &str = "FAULT_INJECTED";
+
+/// Operation label for requests without an operation.
+const UNKNOWN_OPERATION: &str = "unknown";
+
+/// Added latency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LatencyFault {
+    /// Delay in milliseconds, or the shortest delay when `max_ms` is set.
+    pub ms: u64,
+    /// Longest delay in milliseconds, for a random delay.
+    pub max_ms: Option<u64>,
+}
+
+impl LatencyFault {
+    /// Creates a fixed delay.
+    #[must_use]
+    pub fn fixed(delay: Duration) -> Self {
+        Self {
+            ms: duration_ms(delay),
+            max_ms: None,
+        }
+    }
+
+    /// Creates a random delay between `min` and `max`.
+    #[must_use]
+    pub fn random(min: Duration, max: Duration) -> Self {
+        Self {
+            ms: duration_ms(min),
+            max_ms: Some(duration_ms(max)),
+        }
+    }
+
+    /// Picks the delay for a request.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    fn delay(&self) -> Duration {
+        let ms = match self.max_ms {
+            Some(max_ms) if max_ms > self.ms => {
+                self.ms + (roll() * (max_ms - self.ms) as f64).round() as u64
+            }
+            _ => self.ms,
+        };
+        Duration::from_millis(ms)
+    }
+}
+
+fn duration_ms(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+/// Requests answered with an error status instead of being handled.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AbortFault {
+    /// Percentage of requests, from 0 to 100, aborted.
+    pub percentage: f64,
+    /// Status aborted requests are answered with.
+    #[serde(default = "default_abort_status")]
+    pub status: u16,
+}
+
+fn default_abort_status() -> u16 {
+    StatusCode::SERVICE_UNAVAILABLE.as_u16()
+}
+
+/// Responses whose body is cut short.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TruncateFault {
+    /// Percentage of responses, from 0 to 100, truncated.
+    pub percentage: f64,
+    /// Number of bytes cut off the end of the body.
+    pub bytes: usize,
+}
+
+/// Requests whose connection is dropped instead of answered.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ResetFault {
+    /// Percentage of requests, from 0 to 100, reset.
+    pub percentage: f64,
+}
+
+/// Faults injected into the requests a rule matches.
+///
+/// A rule matches by operation ID or by path glob; one of them is required.
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FaultRule {
+    /// Operation ID the rule applies to.
+    pub operation: Option<String>,
+    /// Path glob the rule applies to.
+    pub path: Option<String>,
+    /// Added latency.
+    pub latency: Option<LatencyFault>,
+    /// Aborted requests.
+    pub abort: Option<AbortFault>,
+    /// Truncated response bodies.
+    pub truncate: Option<TruncateFault>,
+    /// Dropped connections.
+    pub reset: Option<ResetFault>,
+}
+
+impl FaultRule {
+    /// Creates a rule for an operation.
+    #[must_use]
+    pub fn operation(operation_id: impl Into<String>) -> Self {
+        Self {
+            operation: Some(operation_id.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Creates a rule for the paths matching a glob.
+    #[must_use]
+    pub fn path(glob: impl Into<String>) -> Self {
+        Self {
+            path: Some(glob.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Adds latency to every matching request.
+    #[must_use]
+    pub fn latency(mut self, latency: LatencyFault) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    /// Aborts a percentage of matching requests with a status.
+    #[must_use]
+    pub fn abort(mut self, percentage: f64, status: StatusCode) -> Self {
+        self.abort = Some(AbortFault {
+            percentage,
+            status: status.as_u16(),
+        });
+        self
+    }
+
+    /// Cuts `bytes` off a percentage of matching responses.
+    #[must_use]
+    pub fn truncate(mut self, percentage: f64, bytes: usize) -> Self {
+        self.truncate = Some(TruncateFault { percentage, bytes });
+        self
+    }
+
+    /// Drops the connection of a percentage of matching requests.
+    #[must_use]
+    pub fn reset(mut self, percentage: f64) -> Self {
+        self.reset = Some(ResetFault { percentage });
+        self
+    }
+
+    /// Returns `true` if the rule applies to a request.
+    #[must_use]
+    pub fn matches(&self, operation_id: Option<&str>, path: &str) -> bool {
+        self.operation
+            .as_deref()
+            .is_some_and(|operation| Some(operation) == operation_id)
+            || self
+                .path
+                .as_deref()
+                .is_some_and(|glob| glob_matches(glob, path))
+    }
+
+    /// Rolls for each of the rule's faults.
+    #[must_use]
+    pub fn plan(&self) -> FaultPlan {
+        FaultPlan {
+            latency: self.latency.as_ref().map(LatencyFault::delay),
+            abort: self
+                .abort
+                .filter(|abort| chance(abort.percentage))
+                .map(|abort| {
+                    StatusCode::from_u16(abort.status).unwrap_or(StatusCode::SERVICE_UNAVAILABLE)
+                }),
+            truncate: self
+                .truncate
+                .filter(|truncate| chance(truncate.percentage))
+                .map(|truncate| truncate.bytes),
+            reset: self.reset.is_some_and(|reset| chance(reset.percentage)),
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.operation.is_none() && self.path.is_none() {
+            return Err("rule needs an operation or a path".to_string());
+        }
+        let percentages = [
+            self.abort.map(|f| f.percentage),
+            self.truncate.map(|f| f.percentage),
+            self.reset.map(|f| f.percentage),
+        ];
+        if let Some(p) = percentages
+            .into_iter()
+            .flatten()
+            .find(|p| !(0.0..=100.0).contains(p))
+        {
+            return Err(format!("percentage {p} is not between 0 and 100"));
+        }
+        if let Some(abort) = self.abort {
+            if !StatusCode::from_u16(abort.status)
+                .is_ok_and(|s| s.is_client_error() || s.is_server_error())
+            {
+                return Err(format!("abort status {} is not an error", abort.status));
+            }
+        }
+        if let Some(LatencyFault {
+            ms,
+            max_ms: Some(max_ms),
+        }) = self.latency
+        {
+            if max_ms < ms {
+                return Err(format!("latency max_ms {max_ms} is below ms {ms}"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns `true` if a path matches a glob, where `*` matches one segment
+/// and `**` any number of them.
+fn glob_matches(glob: &str, path: &str) -> bool {
+    fn matches(glob: &[&str], path: &[&str]) -> bool {
+        match glob.split_first() {
+            None => path.is_empty(),
+            Some((&"**", rest)) => (0..=path.len()).any(|skip| matches(rest, &path[skip..])),
+            Some((segment, rest)) => path.split_first().is_some_and(|(first, path_rest)| {
+                (*segment == "*" || segment == first) && matches(rest, path_rest)
+            }),
+        }
+    }
+
+    let glob: Vec<&str> = glob.trim_matches('/').split('/').collect();
+    let path: Vec<&str> = path.trim_matches('/').split('/').collect();
+    matches(&glob, &path)
+}
+
+/// The kinds of fault.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultKind {
+    /// Added latency.
+    Latency,
+    /// Aborted request.
+    Abort,
+    /// Truncated response body.
+    Truncate,
+    /// Dropped connection.
+    Reset,
+}
+
+impl FaultKind {
+    /// Returns the kind as used in logs, metric labels and the
+    /// `X-Archimedes-Fault` header.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Latency => "latency",
+            Self::Abort => "abort",
+            Self::Truncate => "truncate",
+            Self::Reset => "reset",
+        }
+    }
+
+    /// Logs and counts an injected fault.
+    pub fn record(self, operation: &str, request_id: &str) {
+        tracing::warn!(
+            target: FAULT_LOG_TARGET,
+            fault = self.as_str(),
+            operation,
+            request_id,
+            "CHAOS: injected {} fault",
+            self.as_str()
+        );
+        counter!(
+            FAULTS_INJECTED,
+            "type" => self.as_str(),
+            "operation" => operation.to_string()
+        )
+        .increment(1);
+    }
+}
+
+impl std::fmt::Display for FaultKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The faults rolled for a request.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FaultPlan {
+    /// Delay before the request proceeds.
+    pub latency: Option<Duration>,
+    /// Status the request is aborted with.
+    pub abort: Option<StatusCode>,
+    /// Bytes cut off the end of the response body.
+    pub truncate: Option<usize>,
+    /// Whether the connection is dropped.
+    pub reset: bool,
+}
+
+impl FaultPlan {
+    /// Returns `true` if no fault is injected.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.latency.is_none() && self.abort.is_none() && self.truncate.is_none() && !self.reset
+    }
+}
+
+/// Returns the `X-Archimedes-Fault` header value naming injected faults.
+#[must_use]
+pub fn fault_header_value(faults: &[FaultKind]) -> HeaderValue {
+    let names: Vec<&str> = faults.iter().map(|fault| fault.as_str()).collect();
+    HeaderValue::try_from(names.join(", ")).unwrap_or_else(|_| HeaderValue::from_static(""))
+}
+
+/// Error loading a fault file or enabling fault injection.
+#[derive(Debug)]
+pub enum FaultConfigError {
+    /// The file could not be read.
+    Io(std::io::Error),
+    /// The file is not a valid fault document.
+    Parse(String),
+    /// Fault injection was requested in the production profile.
+    Production,
+}
+
+impl std::fmt::Display for FaultConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "failed to read fault file: {e}"),
+            Self::Parse(msg) => write!(f, "invalid fault file: {msg}"),
+            Self::Production => f.write_str("fault injection is refused in production"),
+        }
+    }
+}
+
+impl std::error::Error for FaultConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Parse(_) | Self::Production => None,
+        }
+    }
+}
+
+/// Refuses fault injection in the production profile.
+///
+/// # Errors
+///
+/// Returns [`FaultConfigError::Production`] if `environment` is
+/// `production`.
+pub fn refuse_production(environment: &str) -> Result<(), FaultConfigError> {
+    if environment.eq_ignore_ascii_case(PRODUCTION_ENVIRONMENT) {
+        return Err(FaultConfigError::Production);
+    }
+    Ok(())
+}
+
+/// Fault rules, and whether they are injected at all.
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FaultConfig {
+    /// Whether faults are injected.
+    pub enabled: bool,
+    /// Rules, the first matching a request applying.
+    pub rules: Vec<FaultRule>,
+}
+
+impl FaultConfig {
+    /// Creates a disabled config without rules.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Turns fault injection on.
+    #[must_use]
+    pub fn enabled(mut self) -> Self {
+        self.enabled = true;
+        self
+    }
+
+    /// Adds a rule after the existing ones.
+    #[must_use]
+    pub fn rule(mut self, rule: FaultRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Parses a config from a TOML document.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FaultConfigError::Parse`] if the document is malformed,
+    /// contains unknown keys, or has a rule without an operation or path, a
+    /// percentage outside 0 to 100, or an abort status that is not an
+    /// error.
+    pub fn from_toml_str(toml: &str) -> Result<Self, FaultConfigError> {
+        let config: Self =
+            toml::from_str(toml).map_err(|e| FaultConfigError::Parse(e.to_string()))?;
+        for rule in &config.rules {
+            rule.validate().map_err(FaultConfigError::Parse)?;
+        }
+        Ok(config)
+    }
+
+    /// Loads a config from a TOML file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed.
+    pub fn from_toml_file(path: impl AsRef<Path>) -> Result<Self, FaultConfigError> {
+        let toml = std::fs::read_to_string(path).map_err(FaultConfigError::Io)?;
+        Self::from_toml_str(&toml)
+    }
+
+    /// Returns the first rule matching a request.
+    #[must_use]
+    pub fn rule_for(&self, operation_id: Option<&str>, path: &str) -> Option<&FaultRule> {
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(operation_id, path))
+    }
+}
+
+/// Shared, reloadable fault config.
+///
+/// Clones share the same config; [`replace`](Self::replace) and
+/// [`reload_from_file`](Self::reload_from_file) apply to all of them.
+#[derive(Debug, Clone, Default)]
+pub struct Faults {
+    config: Arc<RwLock<Arc<FaultConfig>>>,
+}
+
+impl Faults {
+    /// Creates faults from a config.
+    #[must_use]
+    pub fn new(config: FaultConfig) -> Self {
+        Self {
+            config: Arc::new(RwLock::new(Arc::new(config))),
+        }
+    }
+
+    /// Loads faults from a TOML file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed.
+    pub fn from_toml_file(path: impl AsRef<Path>) -> Result<Self, FaultConfigError> {
+        FaultConfig::from_toml_file(path).map(Self::new)
+    }
+
+    /// Returns the current config.
+    #[must_use]
+    pub fn config(&self) -> Arc<FaultConfig> {
+        Arc::clone(
+            &self
+                .config
+                .read()
+                .unwrap_or_else(std::sync::PoisonError::into_inner),
+        )
+    }
+
+    /// Replaces the config.
+    pub fn replace(&self, config: FaultConfig) {
+        *self
+            .config
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = Arc::new(config);
+    }
+
+    /// Reloads the config from a TOML file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed; the current
+    /// config is kept.
+    pub fn reload_from_file(&self, path: impl AsRef<Path>) -> Result<(), FaultConfigError> {
+        let config = FaultConfig::from_toml_file(path)?;
+        tracing::info!(
+            enabled = config.enabled,
+            rules = config.rules.len(),
+            "fault config reloaded"
+        );
+        self.replace(config);
+        Ok(())
+    }
+
+    /// Rolls the faults for a request; empty while injection is disabled.
+    #[must_use]
+    pub fn plan(&self, operation_id: Option<&str>, path: &str) -> FaultPlan {
+        let config = self.config();
+        if !config.enabled {
+            return FaultPlan::default();
+        }
+        config
+            .rule_for(operation_id, path)
+            .map(FaultRule::plan)
+            .unwrap_or_default()
+    }
+}
+
+/// Returns a uniformly distributed number in `[0, 1)`.
+///
+/// Each `RandomState` is keyed differently, so hashing a counter with a
+/// fresh one gives independent rolls without a random number crate.
+#[allow(clippy::cast_precision_loss)]
+fn roll() -> f64 {
+    static ROLLS: AtomicU64 = AtomicU64::new(0);
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(ROLLS.fetch_add(1, Ordering::Relaxed));
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Returns `true` with the given percentage chance.
+fn chance(percentage: f64) -> bool {
+    roll() * 100.0 < percentage
+}
+
+/// Middleware that injects faults into matching requests.
+///
+/// Internal endpoints are never faulted.
+#[derive(Debug, Clone)]
+pub struct FaultInjectionMiddleware {
+    faults: Faults,
+}
+
+impl FaultInjectionMiddleware {
+    /// Creates a fault stage for the service's environment.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FaultConfigError::Production`] if `environment` is
+    /// `production`, whether or not the faults are enabled.
+    pub fn new(faults: Faults, environment: &str) -> Result<Self, FaultConfigError> {
+        refuse_production(environment)?;
+        Ok(Self { faults })
+    }
+
+    /// Returns the faults, for reloading.
+    #[must_use]
+    pub fn faults(&self) -> &Faults {
+        &self.faults
+    }
+}
+
+impl Middleware for FaultInjectionMiddleware {
+    fn name(&self) -> &'static str {
+        "fault_injection"
+    }
+
+    fn process<'a>(
+        &'a self,
+        ctx: &'a mut MiddlewareContext,
+        request: Request,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Response> {
+        Box::pin(async move {
+            if ctx.is_internal() {
+                return next.run(ctx, request).await;
+            }
+            let plan = self.faults.plan(ctx.operation_id(), request.uri().path());
+            if plan.is_empty() {
+                return next.run(ctx, request).await;
+            }
+
+            let operation = ctx.operation_id().unwrap_or(UNKNOWN_OPERATION).to_string();
+            let request_id = ctx.request_id().to_string();
+            let mut injected = Vec::new();
+
+            if let Some(delay) = plan.latency {
+                FaultKind::Latency.record(&operation, &request_id);
+                injected.push(FaultKind::Latency);
+                tokio::time::sleep(delay).await;
+            }
+
+            let mut response = if plan.reset {
+                FaultKind::Reset.record(&operation, &request_id);
+                injected.push(FaultKind::Reset);
+                let mut response = Response::json_error(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    FAULT_CODE,
+                    "Connection reset by fault injection",
+                );
+                response.extensions_mut().insert(ResetConnection);
+                response
+            } else if let Some(status) = plan.abort {
+                FaultKind::Abort.record(&operation, &request_id);
+                injected.push(FaultKind::Abort);
+                Response::json_error(status, FAULT_CODE, "Request aborted by fault injection")
+            } else {
+                let response = next.run(ctx, request).await;
+                match plan.truncate {
+                    Some(bytes) => {
+                        FaultKind::Truncate.record(&operation, &request_id);
+                        injected.push(FaultKind::Truncate);
+                        truncate(response, bytes).await
+                    }
+                    None => response,
+                }
+            };
+
+            response
+                .headers_mut()
+                .insert(FAULT_HEADER, fault_header_value(&injected));
+            response
+        })
+    }
+}
+
+/// Cuts `bytes` off the end of a response body.
+async fn truncate(response: Response, bytes: usize) -> Response {
+    let (mut parts, body) = response.into_parts();
+    let body = body
+        .collect()
+        .await
+        .map(http_body_util::Collected::to_bytes)
+        .unwrap_or_default();
+    let body: Bytes = body.slice(..body.len().saturating_sub(bytes));
+    // The announced length would no longer match
+    parts.headers.remove(CONTENT_LENGTH);
+    Response::from_parts(parts, Full::new(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::{Request as HttpRequest, Response as HttpResponse};
+    use metrics_exporter_prometheus::PrometheusBuilder;
+
+    fn ok_handler() -> impl FnOnce(&mut MiddlewareContext, Request) -> BoxFuture<'static, Response>
+    {
+        |_ctx, _req| {
+            Box::pin(async {
+                HttpResponse::builder()
+                    .status(StatusCode::OK)
+                    .header(CONTENT_LENGTH, "11")
+                    .body(Full::new(Bytes::from(r#"{"ok":true}"#)))
+                    .unwrap()
+            })
+        }
+    }
+
+    async fn run(stage: &FaultInjectionMiddleware, operation_id: &str, path: &str) -> Response {
+        let mut ctx = MiddlewareContext::new();
+        ctx.set_operation_id(operation_id.to_string());
+        let request = HttpRequest::builder()
+            .uri(path)
+            .body(Full::new(Bytes::new()))
+            .unwrap();
+        let next = Next::handler(ok_handler());
+        stage.process(&mut ctx, request, next).await
+    }
+
+    fn stage(config: FaultConfig) -> FaultInjectionMiddleware {
+        FaultInjectionMiddleware::new(Faults::new(config), "test").unwrap()
+    }
+
+    #[test]
+    fn test_glob_matching() {
+        assert!(glob_matches("/files/*", "/files/a"));
+        assert!(!glob_matches("/files/*", "/files/a/b"));
+        assert!(!glob_matches("/files/*", "/files"));
+        assert!(glob_matches("/files/**", "/files"));
+        assert!(glob_matches("/files/**", "/files/a/b"));
+        assert!(glob_matches("/**/raw", "/files/a/raw"));
+        assert!(glob_matches("/users/*/orders", "/users/42/orders"));
+        assert!(!glob_matches("/users/*/orders", "/users/42/carts"));
+    }
+
+    #[test]
+    fn test_production_refused() {
+        let faults = Faults::new(FaultConfig::new().enabled());
+        assert!(matches!(
+            FaultInjectionMiddleware::new(faults.clone(), "production"),
+            Err(FaultConfigError::Production)
+        ));
+        assert!(matches!(
+            FaultInjectionMiddleware::new(faults.clone(), "Production"),
+            Err(FaultConfigError::Production)
+        ));
+        assert!(FaultInjectionMiddleware::new(faults, "staging").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_disabled_config_injects_nothing() {
+        let config = FaultConfig::new()
+            .rule(FaultRule::operation("getCart").abort(100.0, StatusCode::INTERNAL_SERVER_ERROR));
+        let response = run(&stage(config), "getCart", "/cart").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!response.headers().contains_key(FAULT_HEADER));
+    }
+
+    #[tokio::test]
+    async fn test_abort_and_reset_skip_handler() {
+        let config = FaultConfig::new()
+            .enabled()
+            .rule(FaultRule::operation("getCart").abort(100.0, StatusCode::BAD_GATEWAY))
+            .rule(FaultRule::path("/files/**").reset(100.0));
+        let stage = stage(config);
+
+        let response = run(&stage, "getCart", "/cart").await;
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+        assert_eq!(response.headers()[FAULT_HEADER], "abort");
+        assert!(response.extensions().get::<ResetConnection>().is_none());
+
+        let response = run(&stage, "getFile", "/files/a/b").await;
+        assert_eq!(response.headers()[FAULT_HEADER], "reset");
+        assert!(response.extensions().get::<ResetConnection>().is_some());
+
+        // Unmatched requests pass through
+        let response = run(&stage, "getUser", "/users/1").await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_truncate_cuts_body() {
+        let config = FaultConfig::new()
+            .enabled()
+            .rule(FaultRule::operation("getCart").truncate(100.0, 4));
+        let response = run(&stage(config), "getCart", "/cart").await;
+
+        assert_eq!(response.headers()[FAULT_HEADER], "truncate");
+        assert!(!response.headers().contains_key(CONTENT_LENGTH));
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body, Bytes::from(r#"{"ok":t"#));
+    }
+
+    #[test]
+    fn test_random_latency_within_bounds() {
+        let latency = LatencyFault::random(Duration::from_millis(10), Duration::from_millis(20));
+        for _ in 0..100 {
+            let delay = latency.delay();
+            assert!((Duration::from_millis(10)..=Duration::from_millis(20)).contains(&delay));
+        }
+        assert_eq!(
+            LatencyFault::fixed(Duration::from_millis(5)).delay(),
+            Duration::from_millis(5)
+        );
+    }
+
+    #[test]
+    fn test_injected_faults_are_counted() {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+        let config = FaultConfig::new().enabled().rule(
+            FaultRule::operation("getCart")
+                .latency(LatencyFault::fixed(Duration::ZERO))
+                .abort(100.0, StatusCode::SERVICE_UNAVAILABLE),
+        );
+        let stage = stage(config);
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        metrics::with_local_recorder(&recorder, || {
+            runtime.block_on(async {
+                let response = run(&stage, "getCart", "/cart").await;
+                assert_eq!(response.headers()[FAULT_HEADER], "latency, abort");
+            });
+        });
+
+        let rendered = handle.render();
+        assert!(rendered
+            .contains(r#"archimedes_faults_injected_total{type="latency",operation="getCart"} 1"#));
+        assert!(rendered
+            .contains(r#"archimedes_faults_injected_total{type="abort",operation="getCart"} 1"#));
+    }
+
+    #[test]
+    fn test_hot_reload() {
+        let path =
+            std::env::temp_dir().join(format!("archimedes-faults-{}.toml", std::process::id()));
+        std::fs::write(
+            &path,
+            "enabled = true\n\n[[rules]]\noperation = \"getCart\"\nabort = { percentage = 100 }\n",
+        )
+        .unwrap();
+
+        let faults = Faults::from_toml_file(&path).unwrap();
+        assert_eq!(
+            faults.plan(Some("getCart"), "/cart").abort,
+            Some(StatusCode::SERVICE_UNAVAILABLE)
+        );
+
+        std::fs::write(&path, "enabled = false\n").unwrap();
+        faults.reload_from_file(&path).unwrap();
+        assert!(faults.plan(Some("getCart"), "/cart").is_empty());
+
+        // An invalid file keeps the current config
+        std::fs::write(
+            &path,
+            "enabled = true\n\n[[rules]]\nabort = { percentage = 100 }\n",
+        )
+        .unwrap();
+        assert!(faults.reload_from_file(&path).is_err());
+        assert!(!faults.config().enabled);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(FaultConfig::from_toml_str("enabled = true").is_ok());
+        assert!(FaultConfig::from_toml_str(
+            "[[rules]]\noperation = \"a\"\nabort = { percentage = 150 }"
+        )
+        .is_err());
+        assert!(FaultConfig::from_toml_str(
+            "[[rules]]\noperation = \"a\"\nabort = { percentage = 5, status = 200 }"
+        )
+        .is_err());
+        assert!(FaultConfig::from_toml_str(
+            "[[rules]]\npath = \"/a\"\nlatency = { ms = 50, max_ms = 10 }"
+        )
+        .is_err());
+        assert!(FaultConfig::from_toml_str("[[rules]]\npath = \"/a\"\ndelay = 5").is_err());
+    }
+}
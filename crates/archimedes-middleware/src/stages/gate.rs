@@ -0,0 +1,874 @@
+//! Operation gates for dark launches and gradual rollouts.
+//!
+//! A gate keeps an operation that exists in the contract, and has a
+//! handler, unavailable to most callers. Gated-off requests get a
+//! configurable status and error code, `404 NOT_FOUND` by default, so the
+//! operation looks like it does not exist yet.
+//!
+//! # Pipeline Position
+//!
+//! Gates run after resolution and identity, before authorization:
+//!
+//! ```text
+//! Identity → [OperationGate] → Authorization → Validation → Handler
+//! ```
+//!
+//! # Gate Evaluation
+//!
+//! A request to a gated operation is let through when, in order:
+//!
+//! 1. the caller is allowlisted, by identity or by a JWT claim; allowlisted
+//!    callers bypass every other check
+//! 2. the gate is `enabled`
+//! 3. the current time is within `enable_after` (inclusive) and
+//!    `disable_after` (exclusive)
+//! 4. the caller falls within the `percentage` ramp, if there is one
+//!
+//! The ramp hashes the operation ID with the caller's identity (its
+//! [`log_id`](archimedes_core::CallerIdentityExt::log_id)), so a given
+//! caller consistently gets the same answer while the percentage is
+//! unchanged, and raising it only adds callers. Anonymous requests are
+//! hashed by request ID.
+//!
+//! # Gate File
+//!
+//! Timestamps are RFC 3339 strings. Identities are written as in logs:
+//! `user:<id>`, `apikey:<id>` or a SPIFFE ID.
+//!
+//! ```toml
+//! status = 404
+//! code = "NOT_FOUND"
+//!
+//! [gates.newCheckout]
+//! percentage = 10
+//! allow_identities = ["user:alice"]
+//! allow_claims = [{ claim = "groups", value = "beta-testers" }]
+//! enable_after = "2026-11-01T09:00:00Z"
+//! ```
+//!
+//! # Hot Reload
+//!
+//! [`OperationGates`] is a shared handle: clones see the same gates, and
+//! [`OperationGates::reload_from_file`] swaps in a new gate file for all
+//! of them, keeping the current gates if the file is invalid. Call it from
+//! a config file watcher.
+//!
+//! # Observability
+//!
+//! Each decision on a gated operation is stored as a [`GateDecision`]
+//! extension, which the telemetry stage reports as `gate`, and counted in
+//! `archimedes_operation_gate_decisions_total`, labelled by `operation` and
+//! `outcome`.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use archimedes_middleware::stages::{OperationGateMiddleware, OperationGates};
+//!
+//! let gates = OperationGates::from_toml_file("gates.toml")?;
+//! let gate = OperationGateMiddleware::new(gates.clone());
+//!
+//! // Later, when gates.toml changes
+//! gates.reload_from_file("gates.toml")?;
+//! ```
+
+use crate::context::MiddlewareContext;
+use crate::middleware::{BoxFuture, Middleware, Next};
+use crate::stages::identity::IdentityMiddleware;
+use crate::types::{Request, Response, ResponseExt};
+use archimedes_core::{CallerIdentity, CallerIdentityExt};
+use chrono::{DateTime, Utc};
+use http::StatusCode;
+use metrics::counter;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+/// Gate decisions, by operation and outcome.
+pub const GATE_DECISIONS: &str = "archimedes_operation_gate_decisions_total";
+
+/// Status returned for gated-off requests by default.
+pub const DEFAULT_GATED_STATUS: u16 = 404;
+
+/// Error code returned for gated-off requests by default.
+pub const DEFAULT_GATED_CODE: &str = "NOT_FOUND";
+
+/// Number of buckets callers are hashed into for percentage ramps.
+const RAMP_BUCKETS: u64 = 10_000;
+
+/// Matches a JWT claim against a value.
+///
+/// String and numeric claims match when equal to the value; array claims
+/// match when they contain it.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClaimMatcher {
+    /// The claim name.
+    pub claim: String,
+    /// The value to match.
+    pub value: String,
+}
+
+impl ClaimMatcher {
+    /// Creates a matcher for a claim value.
+    #[must_use]
+    pub fn new(claim: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            claim: claim.into(),
+            value: value.into(),
+        }
+    }
+
+    /// Returns `true` if the claims match.
+    #[must_use]
+    pub fn matches(&self, claims: &serde_json::Value) -> bool {
+        let matches_value = |v: &serde_json::Value| match v {
+            serde_json::Value::String(s) => *s == self.value,
+            serde_json::Value::Number(n) => n.to_string() == self.value,
+            _ => false,
+        };
+        match claims.get(&self.claim) {
+            Some(serde_json::Value::Array(values)) => values.iter().any(matches_value),
+            Some(value) => matches_value(value),
+            None => false,
+        }
+    }
+}
+
+/// How an operation is gated.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GateSpec {
+    /// Whether the operation is open at all to callers that are not
+    /// allowlisted.
+    pub enabled: bool,
+    /// Percentage of callers, from 0 to 100, the operation is open to.
+    pub percentage: Option<f64>,
+    /// Identities the operation is always open to.
+    pub allow_identities: Vec<String>,
+    /// Claims that open the operation to a caller.
+    pub allow_claims: Vec<ClaimMatcher>,
+    /// When the operation opens.
+    pub enable_after: Option<DateTime<Utc>>,
+    /// When the operation closes again.
+    pub disable_after: Option<DateTime<Utc>>,
+}
+
+impl Default for GateSpec {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            percentage: None,
+            allow_identities: Vec::new(),
+            allow_claims: Vec::new(),
+            enable_after: None,
+            disable_after: None,
+        }
+    }
+}
+
+impl GateSpec {
+    /// Creates a gate that is open to everyone.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a gate that is closed to everyone but allowlisted callers.
+    #[must_use]
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            ..Self::default()
+        }
+    }
+
+    /// Opens the operation to a percentage of callers.
+    #[must_use]
+    pub fn percentage(mut self, percentage: f64) -> Self {
+        self.percentage = Some(percentage);
+        self
+    }
+
+    /// Always opens the operation to an identity, such as `user:alice`.
+    #[must_use]
+    pub fn allow_identity(mut self, identity: impl Into<String>) -> Self {
+        self.allow_identities.push(identity.into());
+        self
+    }
+
+    /// Opens the operation to callers whose JWT has a claim value.
+    #[must_use]
+    pub fn allow_claim(mut self, claim: impl Into<String>, value: impl Into<String>) -> Self {
+        self.allow_claims.push(ClaimMatcher::new(claim, value));
+        self
+    }
+
+    /// Opens the operation from a point in time.
+    #[must_use]
+    pub fn enable_after(mut self, at: DateTime<Utc>) -> Self {
+        self.enable_after = Some(at);
+        self
+    }
+
+    /// Closes the operation from a point in time.
+    #[must_use]
+    pub fn disable_after(mut self, at: DateTime<Utc>) -> Self {
+        self.disable_after = Some(at);
+        self
+    }
+
+    /// Decides whether the operation is open to a caller at `now`.
+    #[must_use]
+    pub fn evaluate(
+        &self,
+        operation_id: &str,
+        caller: &GateCaller,
+        now: DateTime<Utc>,
+    ) -> GateOutcome {
+        let allowlisted = caller
+            .identity
+            .as_ref()
+            .is_some_and(|id| self.allow_identities.contains(id))
+            || caller
+                .claims
+                .as_ref()
+                .is_some_and(|claims| self.allow_claims.iter().any(|m| m.matches(claims)));
+        if allowlisted {
+            return GateOutcome::Allowlisted;
+        }
+
+        if !self.enabled {
+            return GateOutcome::Disabled;
+        }
+        if self.enable_after.is_some_and(|at| now < at) {
+            return GateOutcome::NotYetEnabled;
+        }
+        if self.disable_after.is_some_and(|at| now >= at) {
+            return GateOutcome::Expired;
+        }
+
+        match self.percentage {
+            None => GateOutcome::Open,
+            Some(percentage) => {
+                if ramp_bucket(operation_id, &caller.ramp_key) < ramp_threshold(percentage) {
+                    GateOutcome::RampedIn
+                } else {
+                    GateOutcome::RampedOut
+                }
+            }
+        }
+    }
+
+    /// Returns `true` if the operation is closed to every caller that is
+    /// not allowlisted at `now`.
+    #[must_use]
+    pub fn is_closed(&self, now: DateTime<Utc>) -> bool {
+        !self.enabled
+            || self.enable_after.is_some_and(|at| now < at)
+            || self.disable_after.is_some_and(|at| now >= at)
+            || self.percentage.is_some_and(|p| ramp_threshold(p) == 0)
+    }
+}
+
+/// Returns the ramp bucket of a caller for an operation.
+fn ramp_bucket(operation_id: &str, ramp_key: &str) -> u64 {
+    let digest = Sha256::new()
+        .chain_update(operation_id.as_bytes())
+        .chain_update(b":")
+        .chain_update(ramp_key.as_bytes())
+        .finalize();
+    let mut prefix = [0u8; 8];
+    prefix.copy_from_slice(&digest[..8]);
+    u64::from_be_bytes(prefix) % RAMP_BUCKETS
+}
+
+/// Returns the number of ramp buckets a percentage opens.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn ramp_threshold(percentage: f64) -> u64 {
+    (percentage.clamp(0.0, 100.0) * (RAMP_BUCKETS as f64 / 100.0)).round() as u64
+}
+
+/// The caller a gate is evaluated for.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct GateCaller {
+    /// The caller's identity, as in logs; `None` for anonymous callers.
+    pub identity: Option<String>,
+    /// The caller's JWT claims, if any.
+    pub claims: Option<serde_json::Value>,
+    /// The key the caller is hashed by for percentage ramps.
+    pub ramp_key: String,
+}
+
+impl GateCaller {
+    /// Creates a caller from its identity, hashed by `fallback_key` when
+    /// anonymous.
+    #[must_use]
+    pub fn new(identity: &CallerIdentity, fallback_key: impl Into<String>) -> Self {
+        let identity = match identity {
+            CallerIdentity::Anonymous => None,
+            identity => Some(identity.log_id()),
+        };
+        Self {
+            ramp_key: identity.clone().unwrap_or_else(|| fallback_key.into()),
+            identity,
+            claims: None,
+        }
+    }
+
+    /// Sets the caller's JWT claims.
+    #[must_use]
+    pub fn with_claims(mut self, claims: serde_json::Value) -> Self {
+        self.claims = Some(claims);
+        self
+    }
+}
+
+/// The outcome of evaluating a gate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GateOutcome {
+    /// The gate is open to everyone.
+    Open,
+    /// The caller is allowlisted.
+    Allowlisted,
+    /// The caller is within the percentage ramp.
+    RampedIn,
+    /// The caller is outside the percentage ramp.
+    RampedOut,
+    /// The gate is disabled.
+    Disabled,
+    /// The gate's time window has not started.
+    NotYetEnabled,
+    /// The gate's time window has ended.
+    Expired,
+}
+
+impl GateOutcome {
+    /// Returns `true` if the request may proceed.
+    #[must_use]
+    pub fn is_allowed(self) -> bool {
+        matches!(self, Self::Open | Self::Allowlisted | Self::RampedIn)
+    }
+
+    /// Returns the outcome as used in logs and metric labels.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Allowlisted => "allowlisted",
+            Self::RampedIn => "ramped_in",
+            Self::RampedOut => "ramped_out",
+            Self::Disabled => "disabled",
+            Self::NotYetEnabled => "not_yet_enabled",
+            Self::Expired => "expired",
+        }
+    }
+}
+
+impl std::fmt::Display for GateOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Gate decision for a request, stored as a context extension.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GateDecision {
+    /// The gated operation.
+    pub operation_id: String,
+    /// The outcome.
+    pub outcome: GateOutcome,
+}
+
+/// Error loading a gate file.
+#[derive(Debug)]
+pub enum GateConfigError {
+    /// The file could not be read.
+    Io(std::io::Error),
+    /// The file is not a valid gate document.
+    Parse(String),
+}
+
+impl std::fmt::Display for GateConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "failed to read gate file: {e}"),
+            Self::Parse(msg) => write!(f, "invalid gate file: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for GateConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Parse(_) => None,
+        }
+    }
+}
+
+/// Gates by operation ID, with the response for gated-off requests.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GateConfig {
+    /// Status returned for gated-off requests.
+    pub status: u16,
+    /// Error code returned for gated-off requests.
+    pub code: String,
+    /// Gates by operation ID; operations without one are not gated.
+    pub gates: HashMap<String, GateSpec>,
+}
+
+impl Default for GateConfig {
+    fn default() -> Self {
+        Self {
+            status: DEFAULT_GATED_STATUS,
+            code: DEFAULT_GATED_CODE.to_string(),
+            gates: HashMap::new(),
+        }
+    }
+}
+
+impl GateConfig {
+    /// Creates a config without gates.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gates an operation.
+    #[must_use]
+    pub fn gate(mut self, operation_id: impl Into<String>, spec: GateSpec) -> Self {
+        self.gates.insert(operation_id.into(), spec);
+        self
+    }
+
+    /// Sets the status and error code returned for gated-off requests.
+    #[must_use]
+    pub fn respond_with(mut self, status: StatusCode, code: impl Into<String>) -> Self {
+        self.status = status.as_u16();
+        self.code = code.into();
+        self
+    }
+
+    /// Parses a config from a TOML document.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GateConfigError::Parse`] if the document is malformed,
+    /// contains unknown keys, or sets a status that is not a client error.
+    pub fn from_toml_str(toml: &str) -> Result<Self, GateConfigError> {
+        let config: Self =
+            toml::from_str(toml).map_err(|e| GateConfigError::Parse(e.to_string()))?;
+        if !StatusCode::from_u16(config.status).is_ok_and(|s| s.is_client_error()) {
+            return Err(GateConfigError::Parse(format!(
+                "status {} is not a client error",
+                config.status
+            )));
+        }
+        Ok(config)
+    }
+
+    /// Loads a config from a TOML file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed.
+    pub fn from_toml_file(path: impl AsRef<Path>) -> Result<Self, GateConfigError> {
+        let toml = std::fs::read_to_string(path).map_err(GateConfigError::Io)?;
+        Self::from_toml_str(&toml)
+    }
+
+    fn status_code(&self) -> StatusCode {
+        StatusCode::from_u16(self.status).unwrap_or(StatusCode::NOT_FOUND)
+    }
+}
+
+/// Shared, reloadable operation gates.
+///
+/// Clones share the same gates; [`replace`](Self::replace) and
+/// [`reload_from_file`](Self::reload_from_file) apply to all of them.
+#[derive(Debug, Clone, Default)]
+pub struct OperationGates {
+    config: Arc<RwLock<Arc<GateConfig>>>,
+}
+
+impl OperationGates {
+    /// Creates gates from a config.
+    #[must_use]
+    pub fn new(config: GateConfig) -> Self {
+        Self {
+            config: Arc::new(RwLock::new(Arc::new(config))),
+        }
+    }
+
+    /// Loads gates from a TOML file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed.
+    pub fn from_toml_file(path: impl AsRef<Path>) -> Result<Self, GateConfigError> {
+        GateConfig::from_toml_file(path).map(Self::new)
+    }
+
+    /// Returns the current config.
+    #[must_use]
+    pub fn config(&self) -> Arc<GateConfig> {
+        Arc::clone(
+            &self
+                .config
+                .read()
+                .unwrap_or_else(std::sync::PoisonError::into_inner),
+        )
+    }
+
+    /// Replaces the config.
+    pub fn replace(&self, config: GateConfig) {
+        *self
+            .config
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = Arc::new(config);
+    }
+
+    /// Reloads the config from a TOML file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed; the current
+    /// config is kept.
+    pub fn reload_from_file(&self, path: impl AsRef<Path>) -> Result<(), GateConfigError> {
+        let config = GateConfig::from_toml_file(path)?;
+        tracing::info!(gates = config.gates.len(), "operation gates reloaded");
+        self.replace(config);
+        Ok(())
+    }
+
+    /// Returns the operations closed to every caller that is not
+    /// allowlisted at `now`, sorted.
+    ///
+    /// Pass these to the docs generator's `hide_operations` to leave them
+    /// out of public docs.
+    #[must_use]
+    pub fn closed_operations(&self, now: DateTime<Utc>) -> Vec<String> {
+        let mut closed: Vec<String> = self
+            .config()
+            .gates
+            .iter()
+            .filter(|(_, spec)| spec.is_closed(now))
+            .map(|(operation_id, _)| operation_id.clone())
+            .collect();
+        closed.sort();
+        closed
+    }
+}
+
+/// Middleware that enforces operation gates.
+///
+/// Internal endpoints and requests without an operation are not gated.
+#[derive(Debug, Clone)]
+pub struct OperationGateMiddleware {
+    gates: OperationGates,
+}
+
+impl OperationGateMiddleware {
+    /// Creates a gate middleware enforcing the given gates.
+    #[must_use]
+    pub fn new(gates: OperationGates) -> Self {
+        Self { gates }
+    }
+
+    /// Returns the gates, for reloading.
+    #[must_use]
+    pub fn gates(&self) -> &OperationGates {
+        &self.gates
+    }
+}
+
+impl Middleware for OperationGateMiddleware {
+    fn name(&self) -> &'static str {
+        "operation_gate"
+    }
+
+    fn process<'a>(
+        &'a self,
+        ctx: &'a mut MiddlewareContext,
+        request: Request,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Response> {
+        Box::pin(async move {
+            let config = self.gates.config();
+            let gate = ctx
+                .operation_id()
+                .filter(|_| !ctx.is_internal())
+                .and_then(|operation_id| {
+                    Some((operation_id.to_string(), config.gates.get(operation_id)?))
+                });
+            let Some((operation_id, spec)) = gate else {
+                return next.run(ctx, request).await;
+            };
+
+            let mut caller = GateCaller::new(ctx.identity(), ctx.request_id().to_string());
+            if !spec.allow_claims.is_empty() {
+                if let Some(claims) = IdentityMiddleware::decode_jwt_claims(&request) {
+                    caller = caller.with_claims(claims);
+                }
+            }
+            let outcome = spec.evaluate(&operation_id, &caller, Utc::now());
+
+            counter!(
+                GATE_DECISIONS,
+                "operation" => operation_id.clone(),
+                "outcome" => outcome.as_str()
+            )
+            .increment(1);
+            ctx.set_extension(GateDecision {
+                operation_id: operation_id.clone(),
+                outcome,
+            });
+
+            if !outcome.is_allowed() {
+                tracing::debug!(
+                    request_id = %ctx.request_id(),
+                    operation_id = %operation_id,
+                    outcome = %outcome,
+                    "operation gated off"
+                );
+                return Response::json_error(
+                    config.status_code(),
+                    &config.code,
+                    "The requested operation is not available",
+                );
+            }
+
+            next.run(ctx, request).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stages::telemetry::{TelemetryData, TelemetryMiddleware};
+    use bytes::Bytes;
+    use chrono::TimeZone;
+    use http::{Request as HttpRequest, Response as HttpResponse};
+    use http_body_util::Full;
+    use metrics_exporter_prometheus::PrometheusBuilder;
+
+    fn ok_handler() -> impl FnOnce(&mut MiddlewareContext, Request) -> BoxFuture<'static, Response>
+    {
+        |_ctx, _req| {
+            Box::pin(async {
+                HttpResponse::builder()
+                    .status(StatusCode::OK)
+                    .body(Full::new(Bytes::new()))
+                    .unwrap()
+            })
+        }
+    }
+
+    fn context(identity: CallerIdentity) -> MiddlewareContext {
+        let mut ctx = MiddlewareContext::new();
+        ctx.set_operation_id("newCheckout".to_string());
+        ctx.set_identity(identity);
+        ctx
+    }
+
+    async fn run(gate: &OperationGateMiddleware, ctx: &mut MiddlewareContext) -> Response {
+        let telemetry = TelemetryMiddleware::new("test-service");
+        let request = HttpRequest::builder()
+            .uri("/checkout")
+            .body(Full::new(Bytes::new()))
+            .unwrap();
+        let next = Next::new(gate, Next::handler(ok_handler()));
+        telemetry.process(ctx, request, next).await
+    }
+
+    fn caller(user: &str) -> GateCaller {
+        GateCaller::new(&CallerIdentity::user(user, "user@example.com"), "req")
+    }
+
+    #[test]
+    fn test_percentage_is_deterministic() {
+        let now = Utc::now();
+        let spec = GateSpec::new().percentage(30.0);
+
+        let outcomes: Vec<GateOutcome> = (0..1000)
+            .map(|i| spec.evaluate("newCheckout", &caller(&format!("u{i}")), now))
+            .collect();
+        let again: Vec<GateOutcome> = (0..1000)
+            .map(|i| spec.evaluate("newCheckout", &caller(&format!("u{i}")), now))
+            .collect();
+        assert_eq!(outcomes, again);
+
+        let ramped_in = outcomes.iter().filter(|o| o.is_allowed()).count();
+        assert!((250..350).contains(&ramped_in), "ramped in {ramped_in}");
+
+        // Raising the percentage only adds callers
+        let wider = GateSpec::new().percentage(60.0);
+        for (i, outcome) in outcomes.iter().enumerate() {
+            if outcome.is_allowed() {
+                assert!(wider
+                    .evaluate("newCheckout", &caller(&format!("u{i}")), now)
+                    .is_allowed());
+            }
+        }
+
+        assert!(!GateSpec::new()
+            .percentage(0.0)
+            .evaluate("newCheckout", &caller("u1"), now)
+            .is_allowed());
+        assert!(GateSpec::new()
+            .percentage(100.0)
+            .evaluate("newCheckout", &caller("u1"), now)
+            .is_allowed());
+    }
+
+    #[test]
+    fn test_allowlist_bypasses_gate() {
+        let now = Utc::now();
+        let spec = GateSpec::disabled()
+            .percentage(0.0)
+            .allow_identity("user:alice")
+            .allow_claim("groups", "beta")
+            .enable_after(now + chrono::Duration::days(1));
+
+        assert_eq!(
+            spec.evaluate("newCheckout", &caller("alice"), now),
+            GateOutcome::Allowlisted
+        );
+        assert_eq!(
+            spec.evaluate("newCheckout", &caller("bob"), now),
+            GateOutcome::Disabled
+        );
+
+        let beta = caller("carol").with_claims(serde_json::json!({ "groups": ["staff", "beta"] }));
+        assert_eq!(
+            spec.evaluate("newCheckout", &beta, now),
+            GateOutcome::Allowlisted
+        );
+    }
+
+    #[test]
+    fn test_time_window_edges() {
+        let start = Utc.with_ymd_and_hms(2026, 11, 1, 9, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2026, 11, 8, 9, 0, 0).unwrap();
+        let spec = GateSpec::new().enable_after(start).disable_after(end);
+        let second = chrono::Duration::seconds(1);
+        let evaluate = |now| spec.evaluate("newCheckout", &caller("bob"), now);
+
+        assert_eq!(evaluate(start - second), GateOutcome::NotYetEnabled);
+        assert_eq!(evaluate(start), GateOutcome::Open);
+        assert_eq!(evaluate(end - second), GateOutcome::Open);
+        assert_eq!(evaluate(end), GateOutcome::Expired);
+
+        assert!(spec.is_closed(start - second));
+        assert!(!spec.is_closed(start));
+        assert!(spec.is_closed(end));
+    }
+
+    #[tokio::test]
+    async fn test_hot_reload_flips_gate() {
+        let path =
+            std::env::temp_dir().join(format!("archimedes-gates-{}.toml", std::process::id()));
+        std::fs::write(
+            &path,
+            "status = 403\ncode = \"OPERATION_GATED\"\n\n[gates.newCheckout]\nenabled = false\n",
+        )
+        .unwrap();
+
+        let gates = OperationGates::from_toml_file(&path).unwrap();
+        let gate = OperationGateMiddleware::new(gates.clone());
+        assert_eq!(
+            gates.closed_operations(Utc::now()),
+            vec!["newCheckout".to_string()]
+        );
+
+        let mut ctx = context(CallerIdentity::user("bob", "bob@example.com"));
+        let response = run(&gate, &mut ctx).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            ctx.get_extension::<TelemetryData>()
+                .unwrap()
+                .gate
+                .as_deref(),
+            Some("disabled")
+        );
+
+        std::fs::write(&path, "[gates.newCheckout]\nenabled = true\n").unwrap();
+        gates.reload_from_file(&path).unwrap();
+
+        let mut ctx = context(CallerIdentity::user("bob", "bob@example.com"));
+        let response = run(&gate, &mut ctx).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            ctx.get_extension::<TelemetryData>()
+                .unwrap()
+                .gate
+                .as_deref(),
+            Some("open")
+        );
+
+        // An invalid file keeps the current gates
+        std::fs::write(&path, "[gates.newCheckout]\nenabled = \"sometimes\"\n").unwrap();
+        assert!(gates.reload_from_file(&path).is_err());
+        assert!(gates.closed_operations(Utc::now()).is_empty());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_gated_off_request_is_counted() {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+        let gate = OperationGateMiddleware::new(OperationGates::new(
+            GateConfig::new().gate("newCheckout", GateSpec::disabled()),
+        ));
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        metrics::with_local_recorder(&recorder, || {
+            runtime.block_on(async {
+                let mut ctx = context(CallerIdentity::Anonymous);
+                let response = run(&gate, &mut ctx).await;
+                assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+                // Operations without a gate are not counted
+                let mut ctx = context(CallerIdentity::Anonymous);
+                ctx.set_operation_id("getCart".to_string());
+                let response = run(&gate, &mut ctx).await;
+                assert_eq!(response.status(), StatusCode::OK);
+                assert!(ctx.get_extension::<GateDecision>().is_none());
+            });
+        });
+
+        let rendered = handle.render();
+        assert!(rendered.contains(
+            r#"archimedes_operation_gate_decisions_total{operation="newCheckout",outcome="disabled"} 1"#
+        ));
+        assert!(!rendered.contains("getCart"));
+    }
+
+    #[test]
+    fn test_config_rejects_non_client_error_status() {
+        assert!(GateConfig::from_toml_str("status = 200").is_err());
+        assert!(GateConfig::from_toml_str("[gates.x]\npercent = 5").is_err());
+
+        let config = GateConfig::from_toml_str(
+            "[gates.newCheckout]\npercentage = 10\nenable_after = \"2026-11-01T09:00:00Z\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.status, 404);
+        let spec = &config.gates["newCheckout"];
+        assert_eq!(spec.percentage, Some(10.0));
+        assert!(spec.enabled);
+        assert_eq!(
+            spec.enable_after,
+            Some(Utc.with_ymd_and_hms(2026, 11, 1, 9, 0, 0).unwrap())
+        );
+    }
+}
@@ -20,11 +20,21 @@
 //! For internal service-to-service communication, identity is extracted
 //! from the client's mTLS certificate SPIFFE ID (typically via a header
 //! set by the ingress/sidecar proxy).
+//!
+//! ## Scopes
+//!
+//! Scopes granted to the caller are stored as a [`CallerScopes`] context
+//! extension. For JWTs they are read from the `scope` claim (configurable
+//! via [`IdentityMiddleware::scope_claim`]) or the `scp` claim; for API keys
+//! they come from the key's scopes.
 
 use crate::context::MiddlewareContext;
 use crate::middleware::{BoxFuture, Middleware, Next};
+use crate::stages::scopes::{CallerScopes, DEFAULT_SCOPE_CLAIM};
 use crate::types::{Request, Response};
 use archimedes_core::CallerIdentity;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
 use themis_platform_types::identity::{ApiKeyIdentity, UserIdentity};
 
 /// Header for SPIFFE ID (set by ingress/sidecar).
@@ -60,6 +70,8 @@ pub const AUTHORIZATION_HEADER: &str = "authorization";
 pub struct IdentityMiddleware {
     /// Trusted SPIFFE trust domain for validation.
     trusted_trust_domain: Option<String>,
+    /// JWT claim holding scopes (defaults to `scope`).
+    scope_claim: Option<String>,
 }
 
 impl IdentityMiddleware {
@@ -76,9 +88,19 @@ impl IdentityMiddleware {
     pub fn with_trust_domain(trust_domain: impl Into<String>) -> Self {
         Self {
             trusted_trust_domain: Some(trust_domain.into()),
+            scope_claim: None,
         }
     }
 
+    /// Sets the JWT claim that scopes are read from.
+    ///
+    /// Defaults to `scope`; the `scp` claim is always used as a fallback.
+    #[must_use]
+    pub fn scope_claim(mut self, claim: impl Into<String>) -> Self {
+        self.scope_claim = Some(claim.into());
+        self
+    }
+
     /// Extracts SPIFFE identity from headers.
     fn extract_spiffe_identity(&self, request: &Request) -> Option<CallerIdentity> {
         let spiffe_id = request.headers().get(SPIFFE_ID_HEADER)?.to_str().ok()?;
@@ -138,6 +160,32 @@ impl IdentityMiddleware {
         })
     }
 
+    /// Decodes the claims of a bearer JWT without verifying the signature.
+    ///
+    /// Returns `None` when the token is not a well-formed JWT.
+    pub(crate) fn decode_jwt_claims(request: &Request) -> Option<serde_json::Value> {
+        let auth_header = request.headers().get(AUTHORIZATION_HEADER)?.to_str().ok()?;
+        let token = auth_header.strip_prefix("Bearer ")?;
+        let payload = token.split('.').nth(1)?;
+        let bytes = URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+        let claims: serde_json::Value = serde_json::from_slice(&bytes).ok()?;
+        claims.is_object().then_some(claims)
+    }
+
+    /// Extracts the scopes granted to the resolved identity.
+    fn extract_scopes(&self, identity: &CallerIdentity, request: &Request) -> CallerScopes {
+        match identity {
+            CallerIdentity::User(_) => Self::decode_jwt_claims(request)
+                .map(|claims| {
+                    let claim = self.scope_claim.as_deref().unwrap_or(DEFAULT_SCOPE_CLAIM);
+                    CallerScopes::from_claims(&claims, claim)
+                })
+                .unwrap_or_default(),
+            CallerIdentity::ApiKey(k) => CallerScopes::new(k.scopes.iter().cloned()),
+            _ => CallerScopes::default(),
+        }
+    }
+
     /// Extracts API key identity from headers.
     fn extract_api_key_identity(&self, request: &Request) -> Option<CallerIdentity> {
         let api_key = request.headers().get(API_KEY_HEADER)?.to_str().ok()?;
@@ -176,6 +224,7 @@ impl Middleware for IdentityMiddleware {
                 .unwrap_or(CallerIdentity::Anonymous);
 
             // Store in context
+            ctx.set_extension(self.extract_scopes(&identity, &request));
             ctx.set_identity(identity);
 
             // Process request through remaining middleware
@@ -310,6 +359,46 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_extracts_jwt_scopes() {
+        let middleware = IdentityMiddleware::new();
+        let mut ctx = MiddlewareContext::new();
+        let payload = URL_SAFE_NO_PAD.encode(r#"{"sub":"alice","scope":"users:read users:write"}"#);
+        let request = create_request_with_jwt(&format!("eyJhbGciOiJIUzI1NiJ9.{payload}.sig"));
+
+        let next = Next::handler(create_handler());
+        let _response = middleware.process(&mut ctx, request, next).await;
+
+        let scopes = ctx.get_extension::<CallerScopes>().unwrap();
+        assert_eq!(scopes.as_slice(), &["users:read", "users:write"]);
+    }
+
+    #[tokio::test]
+    async fn test_extracts_jwt_scopes_from_custom_claim() {
+        let middleware = IdentityMiddleware::new().scope_claim("permissions");
+        let mut ctx = MiddlewareContext::new();
+        let payload = URL_SAFE_NO_PAD.encode(r#"{"sub":"alice","permissions":["orders:create"]}"#);
+        let request = create_request_with_jwt(&format!("eyJhbGciOiJIUzI1NiJ9.{payload}.sig"));
+
+        let next = Next::handler(create_handler());
+        let _response = middleware.process(&mut ctx, request, next).await;
+
+        let scopes = ctx.get_extension::<CallerScopes>().unwrap();
+        assert!(scopes.contains("orders:create"));
+    }
+
+    #[tokio::test]
+    async fn test_opaque_token_has_no_scopes() {
+        let middleware = IdentityMiddleware::new();
+        let mut ctx = MiddlewareContext::new();
+        let request = create_request_with_jwt("some-opaque-token");
+
+        let next = Next::handler(create_handler());
+        let _response = middleware.process(&mut ctx, request, next).await;
+
+        assert!(ctx.get_extension::<CallerScopes>().unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn test_extracts_api_key_identity() {
         let middleware = IdentityMiddleware::new();
@@ -17,6 +17,32 @@
 //! 5. [`validation`] - Request validation
 //! 6. [`rate_limit`] - Rate limiting (optional)
 //!
+//! The optional [`tenant`] stage runs after identity to resolve per-tenant
+//! overrides consulted by [`rate_limit`], [`body_limit`] and
+//! [`authorization`].
+//!
+//! The optional [`gate`] stage runs after identity and before
+//! authorization to keep dark-launched operations from most callers.
+//!
+//! The optional [`sanitize`] stage rejects requests with ambiguous framing
+//! headers; it belongs first, ahead of [`body_limit`].
+//!
+//! The optional `fault` stage (with the `chaos` feature) injects latency,
+//! errors, truncated bodies and connection resets for resilience testing;
+//! it runs just before the handler and refuses the production profile.
+//!
+//! The optional [`capture`] stage records chosen requests and their
+//! responses for debugging; it runs after validation, just before the
+//! handler.
+//!
+//! The optional [`redaction`] stage masks or removes response fields the
+//! caller lacks the contract's `x-required-scope` for; its position
+//! relative to response validation is described in its module.
+//!
+//! The optional [`signing`] stages give hooks the exact bytes of requests
+//! and responses: `RawBodyMiddleware` runs before validation and
+//! `ResponseBodyHookMiddleware` runs before compression.
+//!
 //! ## Post-Handler Stages (7-10)
 //!
 //! 7. [`compression`] - Response compression (optional, gzip/brotli)
@@ -25,20 +51,37 @@
 //! 10. [`error_normalization`] - Error envelope conversion
 
 pub mod authorization;
+pub mod body_limit;
+pub mod capture;
 #[cfg(feature = "compression")]
 pub mod compression;
 pub mod cors;
 pub mod error_normalization;
+#[cfg(feature = "chaos")]
+pub mod fault;
+pub mod gate;
 pub mod identity;
 pub mod rate_limit;
+pub mod redaction;
 pub mod request_id;
+pub mod sanitize;
+pub mod scopes;
+pub mod signing;
 pub mod telemetry;
+pub mod tenant;
 pub mod tracing;
 pub mod validation;
 
 // Re-export main types
 pub use authorization::{
     AuthorizationMiddleware, AuthorizationResult, PolicyDecision, PolicyEvaluator, RbacBuilder,
+    GRANTED_SCOPES_INPUT_KEY, MISSING_SCOPES_INPUT_KEY, SCOPES_SATISFIED_INPUT_KEY,
+};
+pub use body_limit::BodyLimitMiddleware;
+pub use capture::{
+    debug_token, CaptureConfig, CaptureMiddleware, CaptureRecord, CaptureSink, CaptureTrigger,
+    CapturedBody, CapturedHeader, CapturedRequest, CapturedResponse, RotatingFileSink,
+    CAPTURE_HEADER,
 };
 #[cfg(feature = "compression")]
 pub use compression::{
@@ -46,14 +89,43 @@ pub use compression::{
     CompressionMiddleware,
 };
 pub use cors::{AllowedOrigins, CorsBuilder, CorsConfig, CorsMiddleware};
-pub use error_normalization::{ErrorNormalizationMiddleware, NormalizedError};
+pub use error_normalization::{
+    ErrorClassification, ErrorNormalizationMiddleware, NormalizedError, StatusMap,
+};
+#[cfg(feature = "chaos")]
+pub use fault::{
+    FaultConfig, FaultConfigError, FaultInjectionMiddleware, FaultKind, FaultPlan, FaultRule,
+    Faults, LatencyFault,
+};
+pub use gate::{
+    ClaimMatcher, GateCaller, GateConfig, GateConfigError, GateDecision, GateOutcome, GateSpec,
+    OperationGateMiddleware, OperationGates,
+};
 pub use identity::IdentityMiddleware;
 pub use rate_limit::{KeyExtractor, RateLimitBuilder, RateLimitConfig, RateLimitMiddleware};
+pub use redaction::{
+    RedactionAction, ResponseRedactionMiddleware, DEFAULT_MASK, RESPONSE_REDACTIONS,
+};
 pub use request_id::RequestIdMiddleware;
-pub use telemetry::{TelemetryBuilder, TelemetryData, TelemetryMiddleware};
-pub use tracing::{SpanInfo, TraceContext, TracingMiddleware};
+pub use sanitize::{FramingViolation, RequestSanitizationMiddleware};
+pub use scopes::{
+    CallerScopes, ScopeCheck, ScopeEnforcement, ScopeEnforcementMode, ScopeRequirements,
+    SecurityRequirement,
+};
+pub use signing::{
+    HmacSigner, RawBody, RawBodyMiddleware, ResponseBodyHook, ResponseBodyHookMiddleware,
+};
+pub use telemetry::{TelemetryBuilder, TelemetryData, TelemetryMiddleware, ACCESS_LOG_TARGET};
+pub use tenant::{
+    ClaimTenantResolver, StaticTenantPolicyStore, TenantOverrides, TenantPolicy, TenantPolicyError,
+    TenantPolicyMiddleware, TenantPolicyStore, TenantRateLimit, TenantResolver,
+};
+pub use tracing::{
+    FinishedSpan, InMemorySpanExporter, SpanExporter, SpanInfo, StageEvent, StageTimings,
+    TraceContext, TracingMiddleware,
+};
 pub use validation::{
-    FieldType, MockSchema, MockSchemaBuilder, RequestBody, ResponseValidationMiddleware,
-    ResponseValidationResult, ValidationBuilder, ValidationError, ValidationMiddleware,
-    ValidationResult,
+    FieldType, MockSchema, MockSchemaBuilder, RequestBody, RequestValidator,
+    ResponseValidationMiddleware, ResponseValidationResult, ResponseValidator, ValidationBuilder,
+    ValidationError, ValidationMiddleware, ValidationResult,
 };
@@ -13,6 +13,10 @@
 //! - **Per-API-Key**: Limit requests by API key
 //! - **Global**: Limit total requests across all clients
 //!
+//! When the [`tenant`](super::tenant) stage has attached a tenant with a
+//! `rate_limit` override, that tenant's requests share a bucket keyed by the
+//! tenant id and are limited by the override instead of the global settings.
+//!
 //! ## Algorithm
 //!
 //! Uses a sliding window algorithm for accurate rate limiting:
@@ -34,8 +38,9 @@
 //!     .build();
 //! ```
 
-use crate::context::MiddlewareContext;
+use crate::context::{MiddlewareContext, RouteOptions};
 use crate::middleware::{BoxFuture, Middleware, Next};
+use crate::stages::tenant::TenantPolicy;
 use crate::types::{Request, Response};
 use archimedes_core::CallerIdentity;
 use bytes::Bytes;
@@ -366,13 +371,34 @@ impl RateLimitMiddleware {
         }
     }
 
+    /// Resolves the bucket key, limit and window for a request.
+    ///
+    /// A tenant rate limit override takes precedence over the configured
+    /// key extractor and limits.
+    fn resolve_bucket(
+        &self,
+        request: &Request,
+        ctx: &MiddlewareContext,
+    ) -> Option<(String, u64, Duration)> {
+        if let Some(policy) = ctx.get_extension::<TenantPolicy>() {
+            if let Some(rate_limit) = policy.rate_limit() {
+                return Some((
+                    format!("tenant:{}", policy.tenant_id),
+                    rate_limit.limit(),
+                    rate_limit.window(),
+                ));
+            }
+        }
+
+        let key = self.extract_key(request, ctx)?;
+        Some((key, self.config.limit, self.config.window))
+    }
+
     /// Checks and updates the rate limit for a key.
     #[allow(clippy::significant_drop_tightening)]
-    async fn check_rate_limit(&self, key: &str) -> RateLimitResult {
+    async fn check_rate_limit(&self, key: &str, limit: u64, window: Duration) -> RateLimitResult {
         let mut store = self.store.lock().await;
         let now = Instant::now();
-        let window = self.config.window;
-        let limit = self.config.limit;
 
         let window_data = store.windows.entry(key.to_string()).or_insert_with(|| {
             WindowData {
@@ -517,6 +543,14 @@ impl Middleware for RateLimitMiddleware {
         next: Next<'a>,
     ) -> BoxFuture<'a, Response> {
         Box::pin(async move {
+            // Internal endpoints are never rate limited
+            if ctx
+                .get_extension::<RouteOptions>()
+                .is_some_and(|options| options.skip_rate_limit)
+            {
+                return next.run(ctx, request).await;
+            }
+
             // Check if we should skip rate limiting
             if let Some(ref predicate) = self.config.skip_predicate {
                 if predicate(&request) {
@@ -525,16 +559,13 @@ impl Middleware for RateLimitMiddleware {
             }
 
             // Extract the rate limit key
-            let key = match self.extract_key(&request, ctx) {
-                Some(k) => k,
-                None => {
-                    // If we can't extract a key, skip rate limiting
-                    return next.run(ctx, request).await;
-                }
+            let Some((key, limit, window)) = self.resolve_bucket(&request, ctx) else {
+                // If we can't extract a key, skip rate limiting
+                return next.run(ctx, request).await;
             };
 
             // Check rate limit
-            match self.check_rate_limit(&key).await {
+            match self.check_rate_limit(&key, limit, window).await {
                 RateLimitResult::Allowed {
                     limit,
                     remaining,
@@ -585,6 +616,12 @@ mod tests {
             .unwrap()
     }
 
+    async fn check(middleware: &RateLimitMiddleware, key: &str) -> RateLimitResult {
+        middleware
+            .check_rate_limit(key, middleware.config.limit, middleware.config.window)
+            .await
+    }
+
     #[test]
     fn test_builder_default() {
         let middleware = RateLimitMiddleware::builder().build();
@@ -740,7 +777,7 @@ mod tests {
             .global()
             .build();
 
-        let result = middleware.check_rate_limit("test-key").await;
+        let result = check(&middleware, "test-key").await;
         assert!(matches!(result, RateLimitResult::Allowed { .. }));
     }
 
@@ -754,12 +791,12 @@ mod tests {
 
         // Make 3 requests (should be allowed)
         for _ in 0..3 {
-            let result = middleware.check_rate_limit("test-key").await;
+            let result = check(&middleware, "test-key").await;
             assert!(matches!(result, RateLimitResult::Allowed { .. }));
         }
 
         // 4th request should be limited
-        let result = middleware.check_rate_limit("test-key").await;
+        let result = check(&middleware, "test-key").await;
         assert!(matches!(result, RateLimitResult::Limited { .. }));
     }
 
@@ -771,14 +808,14 @@ mod tests {
             .global()
             .build();
 
-        let result = middleware.check_rate_limit("test-key").await;
+        let result = check(&middleware, "test-key").await;
         if let RateLimitResult::Allowed { remaining, .. } = result {
             assert_eq!(remaining, 4);
         } else {
             panic!("Expected Allowed");
         }
 
-        let result = middleware.check_rate_limit("test-key").await;
+        let result = check(&middleware, "test-key").await;
         if let RateLimitResult::Allowed { remaining, .. } = result {
             assert_eq!(remaining, 3);
         } else {
@@ -794,16 +831,102 @@ mod tests {
             .build();
 
         // Use up key1's limit
-        middleware.check_rate_limit("key1").await;
-        middleware.check_rate_limit("key1").await;
-        let result = middleware.check_rate_limit("key1").await;
+        check(&middleware, "key1").await;
+        check(&middleware, "key1").await;
+        let result = check(&middleware, "key1").await;
         assert!(matches!(result, RateLimitResult::Limited { .. }));
 
         // key2 should still have capacity
-        let result = middleware.check_rate_limit("key2").await;
+        let result = check(&middleware, "key2").await;
         assert!(matches!(result, RateLimitResult::Allowed { .. }));
     }
 
+    #[tokio::test]
+    async fn test_internal_routes_skip_rate_limit() {
+        let middleware = RateLimitMiddleware::builder()
+            .limit(1)
+            .window_secs(60)
+            .global()
+            .build();
+
+        let mut statuses = Vec::new();
+        for _ in 0..3 {
+            let mut ctx = MiddlewareContext::new();
+            ctx.set_extension(RouteOptions::internal());
+            let next = Next::handler(|_ctx, _req| {
+                Box::pin(async {
+                    http::Response::builder()
+                        .status(StatusCode::OK)
+                        .body(Full::new(Bytes::new()))
+                        .unwrap()
+                })
+            });
+            let response = middleware
+                .process(&mut ctx, create_test_request(), next)
+                .await;
+            assert!(!response.headers().contains_key("x-ratelimit-limit"));
+            statuses.push(response.status());
+        }
+
+        assert_eq!(statuses, vec![StatusCode::OK; 3]);
+    }
+
+    #[tokio::test]
+    async fn test_tenant_rate_limit_overrides_global() {
+        use crate::stages::tenant::{TenantOverrides, TenantRateLimit};
+
+        let middleware = RateLimitMiddleware::builder()
+            .limit(100)
+            .window_secs(60)
+            .build();
+        let tenant = TenantPolicy {
+            tenant_id: "acme".to_string(),
+            overrides: Some(TenantOverrides::new().rate_limit(TenantRateLimit::new(2))),
+        };
+
+        let mut statuses = Vec::new();
+        for _ in 0..3 {
+            let mut ctx = MiddlewareContext::new();
+            ctx.set_extension(tenant.clone());
+            let next = Next::handler(|_ctx, _req| {
+                Box::pin(async {
+                    http::Response::builder()
+                        .status(StatusCode::OK)
+                        .body(Full::new(Bytes::new()))
+                        .unwrap()
+                })
+            });
+            let response = middleware
+                .process(&mut ctx, create_test_request_with_ip("10.0.0.1"), next)
+                .await;
+            statuses.push(response.status());
+        }
+
+        assert_eq!(
+            statuses,
+            vec![
+                StatusCode::OK,
+                StatusCode::OK,
+                StatusCode::TOO_MANY_REQUESTS
+            ]
+        );
+
+        // Requests without a tenant still use the global limit
+        let mut ctx = MiddlewareContext::new();
+        let next = Next::handler(|_ctx, _req| {
+            Box::pin(async {
+                http::Response::builder()
+                    .status(StatusCode::OK)
+                    .body(Full::new(Bytes::new()))
+                    .unwrap()
+            })
+        });
+        let response = middleware
+            .process(&mut ctx, create_test_request_with_ip("10.0.0.1"), next)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
     #[test]
     fn test_rate_limit_response() {
         let middleware = RateLimitMiddleware::builder()
@@ -0,0 +1,580 @@
+//! Declarative response field redaction.
+//!
+//! The contract reserves response fields for callers holding a scope with
+//! the `x-required-scope` extension. This stage removes or masks those
+//! fields when the caller's [`CallerScopes`] do not include the scope, so
+//! handlers return the full representation and never filter it by hand.
+//!
+//! # Field Paths
+//!
+//! Paths join property names with `.` and mark array elements with `[]`,
+//! as listed by `archimedes_sentinel::LoadedOperation::redactions`:
+//!
+//! - `email` - a top-level field
+//! - `billing.iban` - a field of a nested object
+//! - `contacts[].phone` - a field of each element of an array
+//! - `[].email` - a field of each element of a top-level array
+//!
+//! Fields that are absent or `null` carry nothing to hide and are left
+//! alone. Only successful JSON responses are redacted.
+//!
+//! # Pipeline Position
+//!
+//! Redaction is a post-handler stage, and its position relative to
+//! response validation decides which body is validated. Post-handler
+//! stages added later run closer to the handler, so they see the response
+//! first:
+//!
+//! - With unenforced response validation (`LogOnly`), add this stage
+//!   *after* `ResponseValidationMiddleware`. The body is redacted first,
+//!   so the validation results and logs describe what the caller received,
+//!   and never carry the hidden values.
+//! - With enforced response validation (`Reject`), add this stage *before*
+//!   `ResponseValidationMiddleware`. The handler's full response is
+//!   validated first, so a handler bug is caught whoever the caller is,
+//!   and a removed required field cannot fail validation.
+//!
+//! ```text
+//! LogOnly: Handler → [Redaction] → ResponseValidation → Telemetry
+//! Reject:  Handler → ResponseValidation → [Redaction] → Telemetry
+//! ```
+//!
+//! Masked fields become strings: mask only string fields, or use
+//! [`RedactionAction::Remove`], when responses are validated after
+//! redaction.
+//!
+//! # Opting Out
+//!
+//! A handler whose response must reach the caller unredacted calls
+//! `RequestContext::skip_redaction` (see [`archimedes_core::redaction`]).
+//!
+//! # Observability
+//!
+//! Each redacted field is counted in [`RESPONSE_REDACTIONS`], labelled by
+//! `operation` and `field` path.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use archimedes_middleware::stages::{RedactionAction, ResponseRedactionMiddleware};
+//! use archimedes_sentinel::ArtifactLoader;
+//!
+//! let artifact = ArtifactLoader::from_file("openapi.yaml").await?;
+//! let redaction = ResponseRedactionMiddleware::from_artifact(&artifact)
+//!     .action(RedactionAction::Mask("[redacted]".to_string()));
+//!
+//! // Or declare the fields by hand
+//! let redaction = ResponseRedactionMiddleware::new()
+//!     .redact("getCustomer", "contacts[].phone", "pii:read");
+//! ```
+
+use crate::context::MiddlewareContext;
+use crate::middleware::{BoxFuture, Middleware, Next};
+use crate::stages::scopes::CallerScopes;
+use crate::types::{Request, Response};
+use archimedes_core::redaction::{self, RedactionOptOut};
+use bytes::Bytes;
+use http::header;
+use http_body_util::{BodyExt, Full};
+use metrics::counter;
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[cfg(feature = "sentinel")]
+use archimedes_sentinel::LoadedArtifact;
+
+/// Redacted response fields, by operation and field.
+pub const RESPONSE_REDACTIONS: &str = "archimedes_response_redactions_total";
+
+/// Default value masked fields are replaced with.
+pub const DEFAULT_MASK: &str = "***";
+
+/// What happens to a field the caller may not receive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RedactionAction {
+    /// Replace the field's value with the given string.
+    Mask(String),
+    /// Remove the field from its object.
+    Remove,
+}
+
+impl Default for RedactionAction {
+    fn default() -> Self {
+        Self::Mask(DEFAULT_MASK.to_string())
+    }
+}
+
+/// One step of a field path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PathStep {
+    /// A property of an object.
+    Field(String),
+    /// Each element of an array.
+    Each,
+}
+
+/// A field returned only to callers holding a scope.
+#[derive(Debug, Clone)]
+struct ScopedField {
+    path: String,
+    steps: Vec<PathStep>,
+    scope: String,
+}
+
+impl ScopedField {
+    fn new(path: &str, scope: &str) -> Self {
+        let mut steps = Vec::new();
+        for segment in path.split('.') {
+            let mut name = segment;
+            let mut arrays = 0;
+            while let Some(inner) = name.strip_suffix("[]") {
+                name = inner;
+                arrays += 1;
+            }
+            if !name.is_empty() {
+                steps.push(PathStep::Field(name.to_string()));
+            }
+            for _ in 0..arrays {
+                steps.push(PathStep::Each);
+            }
+        }
+        Self {
+            path: path.to_string(),
+            steps,
+            scope: scope.to_string(),
+        }
+    }
+}
+
+/// Middleware removing or masking response fields the caller lacks the
+/// scope for.
+#[derive(Debug, Clone, Default)]
+pub struct ResponseRedactionMiddleware {
+    fields: HashMap<String, Vec<ScopedField>>,
+    action: RedactionAction,
+}
+
+impl ResponseRedactionMiddleware {
+    /// Creates a stage redacting no fields.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a stage redacting the fields the contract reserves for
+    /// callers holding a scope.
+    #[cfg(feature = "sentinel")]
+    #[must_use]
+    pub fn from_artifact(artifact: &LoadedArtifact) -> Self {
+        artifact
+            .redactions()
+            .flat_map(|(operation_id, fields)| {
+                fields.into_iter().map(move |field| (operation_id, field))
+            })
+            .fold(Self::new(), |stage, (operation_id, field)| {
+                stage.redact(operation_id, &field.path, &field.scope)
+            })
+    }
+
+    /// Returns the field at `path` in `operation_id`'s responses only to
+    /// callers holding `scope`.
+    #[must_use]
+    pub fn redact(mut self, operation_id: impl Into<String>, path: &str, scope: &str) -> Self {
+        self.fields
+            .entry(operation_id.into())
+            .or_default()
+            .push(ScopedField::new(path, scope));
+        self
+    }
+
+    /// Sets what happens to redacted fields, masking them with
+    /// [`DEFAULT_MASK`] by default.
+    #[must_use]
+    pub fn action(mut self, action: RedactionAction) -> Self {
+        self.action = action;
+        self
+    }
+
+    /// Redacts the fields of `body` the caller lacks the scope for,
+    /// returning the number of fields redacted per path.
+    fn redact_body(&self, fields: &[&ScopedField], body: &mut Value) -> Vec<(String, usize)> {
+        fields
+            .iter()
+            .map(|field| (field.path.clone(), apply(body, &field.steps, &self.action)))
+            .filter(|(_, count)| *count > 0)
+            .collect()
+    }
+}
+
+/// Applies `action` to the field at `steps` under `value`, returning the
+/// number of fields redacted.
+fn apply(value: &mut Value, steps: &[PathStep], action: &RedactionAction) -> usize {
+    match steps {
+        [] => 0,
+        [PathStep::Field(name)] => {
+            let Some(object) = value.as_object_mut() else {
+                return 0;
+            };
+            match object.get_mut(name) {
+                None | Some(Value::Null) => 0,
+                Some(field) => {
+                    match action {
+                        RedactionAction::Mask(mask) => *field = Value::String(mask.clone()),
+                        RedactionAction::Remove => {
+                            object.remove(name);
+                        }
+                    }
+                    1
+                }
+            }
+        }
+        [PathStep::Field(name), rest @ ..] => value
+            .get_mut(name.as_str())
+            .map_or(0, |field| apply(field, rest, action)),
+        [PathStep::Each, rest @ ..] => value.as_array_mut().map_or(0, |items| {
+            items.iter_mut().map(|item| apply(item, rest, action)).sum()
+        }),
+    }
+}
+
+impl Middleware for ResponseRedactionMiddleware {
+    fn name(&self) -> &'static str {
+        "response_redaction"
+    }
+
+    fn process<'a>(
+        &'a self,
+        ctx: &'a mut MiddlewareContext,
+        request: Request,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Response> {
+        Box::pin(async move {
+            let operation_id = ctx.operation_id().unwrap_or("unknown").to_string();
+            let Some(fields) = self.fields.get(&operation_id) else {
+                return next.run(ctx, request).await;
+            };
+
+            let opt_out = RedactionOptOut::new();
+            let response = redaction::scope(opt_out.clone(), next.run(ctx, request)).await;
+            if opt_out.is_skipped() || !response.status().is_success() {
+                return response;
+            }
+
+            let fields: Vec<&ScopedField> = {
+                let scopes = ctx.get_extension::<CallerScopes>();
+                fields
+                    .iter()
+                    .filter(|field| !scopes.is_some_and(|s| s.contains(&field.scope)))
+                    .collect()
+            };
+            if fields.is_empty() {
+                return response;
+            }
+
+            // Only JSON bodies are redacted
+            let is_json = response
+                .headers()
+                .get(header::CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .map_or(true, |content_type| content_type.contains("json"));
+            if !is_json {
+                return response;
+            }
+
+            let (mut parts, body) = response.into_parts();
+            let body = body
+                .collect()
+                .await
+                .map_or_else(|never| match never {}, |collected| collected.to_bytes());
+            let Ok(mut value) = serde_json::from_slice::<Value>(&body) else {
+                return Response::from_parts(parts, Full::new(body));
+            };
+
+            let redacted = self.redact_body(&fields, &mut value);
+            if redacted.is_empty() {
+                return Response::from_parts(parts, Full::new(body));
+            }
+            for (path, count) in redacted {
+                counter!(
+                    RESPONSE_REDACTIONS,
+                    "operation" => operation_id.clone(),
+                    "field" => path
+                )
+                .increment(count as u64);
+            }
+
+            parts.headers.remove(header::CONTENT_LENGTH);
+            let body = serde_json::to_vec(&value).map_or(body, Bytes::from);
+            Response::from_parts(parts, Full::new(body))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::{Request as HttpRequest, Response as HttpResponse, StatusCode};
+    use metrics_exporter_prometheus::PrometheusBuilder;
+    use serde_json::json;
+
+    fn customer() -> Value {
+        json!({
+            "name": "Ann",
+            "email": "ann@example.com",
+            "billing": { "iban": "DE89370400440532013000", "currency": "EUR" },
+            "contacts": [
+                { "label": "home", "phone": "555-0100" },
+                { "label": "work", "phone": "555-0199" },
+                { "label": "fax" }
+            ]
+        })
+    }
+
+    fn stage() -> ResponseRedactionMiddleware {
+        ResponseRedactionMiddleware::new()
+            .redact("getCustomer", "email", "pii:read")
+            .redact("getCustomer", "billing.iban", "billing:read")
+            .redact("getCustomer", "contacts[].phone", "pii:read")
+    }
+
+    fn context(scopes: &[&str]) -> MiddlewareContext {
+        let mut ctx = MiddlewareContext::new();
+        ctx.set_operation_id("getCustomer".to_string());
+        ctx.set_extension(CallerScopes::new(scopes.iter().copied()));
+        ctx
+    }
+
+    fn request() -> Request {
+        HttpRequest::builder()
+            .uri("/customers/1")
+            .body(Full::new(Bytes::new()))
+            .unwrap()
+    }
+
+    fn customer_handler(
+        skip: bool,
+    ) -> impl FnOnce(&mut MiddlewareContext, Request) -> BoxFuture<'static, Response> {
+        move |_ctx, _req| {
+            Box::pin(async move {
+                if skip {
+                    redaction::skip();
+                }
+                HttpResponse::builder()
+                    .status(StatusCode::OK)
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Full::new(Bytes::from(customer().to_string())))
+                    .unwrap()
+            })
+        }
+    }
+
+    async fn run(stage: &ResponseRedactionMiddleware, ctx: &mut MiddlewareContext) -> Value {
+        let response = stage
+            .process(ctx, request(), Next::handler(customer_handler(false)))
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[test]
+    fn test_path_steps() {
+        let steps = |path: &str| ScopedField::new(path, "s").steps;
+        let field = |name: &str| PathStep::Field(name.to_string());
+        assert_eq!(steps("email"), vec![field("email")]);
+        assert_eq!(
+            steps("contacts[].phone"),
+            vec![field("contacts"), PathStep::Each, field("phone")]
+        );
+        assert_eq!(steps("[].email"), vec![PathStep::Each, field("email")]);
+        assert_eq!(
+            steps("grid[][].cell"),
+            vec![field("grid"), PathStep::Each, PathStep::Each, field("cell")]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scoped_caller_sees_fields() {
+        let mut ctx = context(&["pii:read", "billing:read"]);
+        assert_eq!(run(&stage(), &mut ctx).await, customer());
+    }
+
+    #[tokio::test]
+    async fn test_unscoped_caller_gets_fields_masked() {
+        let mut ctx = context(&["billing:read"]);
+        let body = run(&stage(), &mut ctx).await;
+        assert_eq!(body["email"], "***");
+        assert_eq!(body["billing"]["iban"], "DE89370400440532013000");
+        assert_eq!(
+            body["contacts"][0],
+            json!({ "label": "home", "phone": "***" })
+        );
+        assert_eq!(body["contacts"][1]["phone"], "***");
+        assert!(body["contacts"][2].get("phone").is_none());
+
+        // Callers without any scopes get every field redacted
+        let mut ctx = MiddlewareContext::new();
+        ctx.set_operation_id("getCustomer".to_string());
+        let body = run(&stage(), &mut ctx).await;
+        assert_eq!(body["billing"]["iban"], "***");
+    }
+
+    #[tokio::test]
+    async fn test_remove_action_and_top_level_arrays() {
+        let stage =
+            stage()
+                .action(RedactionAction::Remove)
+                .redact("listCustomers", "[].email", "pii:read");
+        let body = run(&stage, &mut context(&[])).await;
+        assert!(body.get("email").is_none());
+        assert_eq!(body["billing"], json!({ "currency": "EUR" }));
+        assert_eq!(body["contacts"][0], json!({ "label": "home" }));
+
+        let mut list = json!([customer(), customer()]);
+        let fields = stage.fields["listCustomers"].iter().collect::<Vec<_>>();
+        assert_eq!(
+            stage.redact_body(&fields, &mut list),
+            vec![("[].email".to_string(), 2)]
+        );
+        assert!(list[1].get("email").is_none());
+
+        // Null fields carry nothing to hide
+        let mut null = json!({ "email": null });
+        let fields = stage.fields["getCustomer"].iter().collect::<Vec<_>>();
+        assert!(stage.redact_body(&fields, &mut null).is_empty());
+        assert_eq!(null, json!({ "email": null }));
+    }
+
+    #[tokio::test]
+    async fn test_handler_can_opt_out() {
+        let mut ctx = context(&[]);
+        let response = stage()
+            .process(&mut ctx, request(), Next::handler(customer_handler(true)))
+            .await;
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(serde_json::from_slice::<Value>(&body).unwrap(), customer());
+    }
+
+    #[test]
+    fn test_redactions_are_counted_per_field() {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        metrics::with_local_recorder(&recorder, || {
+            runtime.block_on(async {
+                run(&stage(), &mut context(&["billing:read"])).await;
+            });
+        });
+
+        let rendered = handle.render();
+        assert!(rendered.contains(
+            r#"archimedes_response_redactions_total{operation="getCustomer",field="contacts[].phone"} 2"#
+        ));
+        assert!(rendered.contains(
+            r#"archimedes_response_redactions_total{operation="getCustomer",field="email"} 1"#
+        ));
+        assert!(!rendered.contains("billing.iban"));
+    }
+
+    #[cfg(feature = "sentinel")]
+    #[tokio::test]
+    async fn test_response_validation_passes_for_both_variants() {
+        use crate::stages::validation::{ResponseValidationMiddleware, ResponseValidationResult};
+        use archimedes_sentinel::{ArtifactLoader, Sentinel, SentinelConfig, ValidationConfig};
+
+        let artifact = ArtifactLoader::from_value(json!({
+            "openapi": "3.1.0",
+            "info": { "title": "customers", "version": "1.0.0" },
+            "paths": {
+                "/customers/{id}": {
+                    "get": {
+                        "operationId": "getCustomer",
+                        "responses": {
+                            "200": {
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/Customer" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Customer": {
+                        "type": "object",
+                        "required": ["name", "email"],
+                        "properties": {
+                            "name": { "type": "string" },
+                            "email": { "type": "string", "x-required-scope": "pii:read" },
+                            "billing": {
+                                "type": "object",
+                                "properties": {
+                                    "iban": { "type": "string", "x-required-scope": "billing:read" },
+                                    "currency": { "type": "string" }
+                                }
+                            },
+                            "contacts": {
+                                "type": "array",
+                                "items": {
+                                    "type": "object",
+                                    "properties": {
+                                        "label": { "type": "string" },
+                                        "phone": { "type": "string", "x-required-scope": "pii:read" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let redaction = ResponseRedactionMiddleware::from_artifact(&artifact);
+        let config = SentinelConfig {
+            validation: ValidationConfig {
+                validate_responses: true,
+                ..ValidationConfig::default()
+            },
+            ..SentinelConfig::default()
+        };
+
+        for enforce in [false, true] {
+            let validation = ResponseValidationMiddleware::sentinel(
+                Sentinel::new(artifact.clone(), config.clone()),
+                enforce,
+            );
+            for scopes in [&["pii:read", "billing:read"][..], &[][..]] {
+                let mut ctx = context(scopes);
+                let handler = Next::handler(customer_handler(false));
+                // LogOnly validates the redacted body, Reject the full one
+                let response = if enforce {
+                    redaction
+                        .process(&mut ctx, request(), Next::new(&validation, handler))
+                        .await
+                } else {
+                    validation
+                        .process(&mut ctx, request(), Next::new(&redaction, handler))
+                        .await
+                };
+
+                assert_eq!(response.status(), StatusCode::OK);
+                let result = ctx.get_extension::<ResponseValidationResult>().unwrap();
+                assert!(result.0.valid, "{:?}", result.0.errors);
+                let body = response.into_body().collect().await.unwrap().to_bytes();
+                let body: Value = serde_json::from_slice(&body).unwrap();
+                let expected = if scopes.is_empty() {
+                    "***"
+                } else {
+                    "ann@example.com"
+                };
+                assert_eq!(body["email"], expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,250 @@
+//! Request framing sanitization.
+//!
+//! Rejects requests whose message framing is ambiguous, which is how
+//! request smuggling works: a proxy in front of the server and the server
+//! itself disagree on where the body ends. Rejected with
+//! `400 Bad Request` are requests with:
+//!
+//! - both `Content-Length` and `Transfer-Encoding`
+//! - more than one `Content-Length`, whether repeated or comma-separated,
+//!   even if the values agree
+//! - a `Content-Length` that is not a plain decimal number
+//! - a `Transfer-Encoding` whose final coding is not `chunked`
+//!
+//! Add this stage before [`BodyLimitMiddleware`], which trusts the
+//! declared `Content-Length`, so that a smaller duplicate length can never
+//! slip a body past the limit.
+//!
+//! [`BodyLimitMiddleware`]: crate::stages::BodyLimitMiddleware
+
+use crate::context::MiddlewareContext;
+use crate::middleware::{BoxFuture, Middleware, Next};
+use crate::types::{Request, Response, ResponseExt};
+use http::{header, HeaderMap, StatusCode};
+use std::fmt;
+
+/// Why a request's framing was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FramingViolation {
+    /// Both `Content-Length` and `Transfer-Encoding` are present.
+    ContentLengthWithTransferEncoding,
+    /// `Content-Length` is given more than once.
+    DuplicateContentLength,
+    /// `Content-Length` is not a decimal number.
+    InvalidContentLength(String),
+    /// The final transfer coding is not `chunked`.
+    UnsupportedTransferEncoding(String),
+}
+
+impl fmt::Display for FramingViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ContentLengthWithTransferEncoding => {
+                write!(f, "both Content-Length and Transfer-Encoding are present")
+            }
+            Self::DuplicateContentLength => write!(f, "Content-Length is given more than once"),
+            Self::InvalidContentLength(value) => write!(f, "invalid Content-Length '{value}'"),
+            Self::UnsupportedTransferEncoding(value) => {
+                write!(f, "unsupported Transfer-Encoding '{value}'")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FramingViolation {}
+
+/// Middleware rejecting requests with ambiguous framing headers.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RequestSanitizationMiddleware;
+
+impl RequestSanitizationMiddleware {
+    /// Creates the middleware.
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Checks the framing headers of a request.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first violation found.
+    pub fn check(headers: &HeaderMap) -> Result<(), FramingViolation> {
+        let lengths: Vec<&str> = headers
+            .get_all(header::CONTENT_LENGTH)
+            .iter()
+            .map(|value| value.to_str().unwrap_or("\u{fffd}"))
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .collect();
+        let has_transfer_encoding = headers.contains_key(header::TRANSFER_ENCODING);
+
+        if !lengths.is_empty() && has_transfer_encoding {
+            return Err(FramingViolation::ContentLengthWithTransferEncoding);
+        }
+        if lengths.len() > 1 {
+            return Err(FramingViolation::DuplicateContentLength);
+        }
+        if let Some(length) = lengths.first() {
+            let valid = !length.is_empty()
+                && length.bytes().all(|b| b.is_ascii_digit())
+                && length.parse::<u64>().is_ok();
+            if !valid {
+                return Err(FramingViolation::InvalidContentLength(
+                    (*length).to_string(),
+                ));
+            }
+        }
+
+        if has_transfer_encoding {
+            let codings: Vec<String> = headers
+                .get_all(header::TRANSFER_ENCODING)
+                .iter()
+                .map(|value| value.to_str().unwrap_or("\u{fffd}"))
+                .flat_map(|value| value.split(','))
+                .map(|coding| coding.trim().to_ascii_lowercase())
+                .collect();
+            if codings.last().map(String::as_str) != Some("chunked") {
+                return Err(FramingViolation::UnsupportedTransferEncoding(
+                    codings.join(", "),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Middleware for RequestSanitizationMiddleware {
+    fn name(&self) -> &'static str {
+        "request_sanitization"
+    }
+
+    fn process<'a>(
+        &'a self,
+        ctx: &'a mut MiddlewareContext,
+        request: Request,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Response> {
+        Box::pin(async move {
+            if let Err(violation) = Self::check(request.headers()) {
+                tracing::warn!(
+                    request_id = %ctx.request_id(),
+                    method = %request.method(),
+                    path = %request.uri().path(),
+                    reason = %violation,
+                    "rejecting request with ambiguous framing"
+                );
+                return Response::json_error(
+                    StatusCode::BAD_REQUEST,
+                    "INVALID_REQUEST_FRAMING",
+                    &format!("Invalid request framing: {violation}"),
+                );
+            }
+
+            next.run(ctx, request).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stages::BodyLimitMiddleware;
+    use bytes::Bytes;
+    use http::{HeaderValue, Request as HttpRequest, Response as HttpResponse};
+    use http_body_util::Full;
+
+    fn request(headers: &[(&str, &str)], body: &'static [u8]) -> Request {
+        let mut builder = HttpRequest::builder().method("POST").uri("/upload");
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        builder.body(Full::new(Bytes::from_static(body))).unwrap()
+    }
+
+    fn ok_handler() -> impl FnOnce(&mut MiddlewareContext, Request) -> BoxFuture<'static, Response>
+    {
+        |_ctx, _req| {
+            Box::pin(async {
+                HttpResponse::builder()
+                    .status(StatusCode::OK)
+                    .body(Full::new(Bytes::new()))
+                    .unwrap()
+            })
+        }
+    }
+
+    /// Runs a request through sanitization followed by a body limit.
+    async fn run(request: Request) -> Response {
+        let sanitize = RequestSanitizationMiddleware::new();
+        let body_limit = BodyLimitMiddleware::new(16);
+        let mut ctx = MiddlewareContext::new();
+        let next = Next::new(&body_limit, Next::handler(ok_handler()));
+        sanitize.process(&mut ctx, request, next).await
+    }
+
+    #[tokio::test]
+    async fn test_rejects_content_length_with_transfer_encoding() {
+        let response = run(request(
+            &[("content-length", "4"), ("transfer-encoding", "chunked")],
+            b"data",
+        ))
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_conflicting_content_lengths() {
+        // The body limit alone would trust the first, small length
+        let response = run(request(
+            &[("content-length", "4"), ("content-length", "4096")],
+            b"data",
+        ))
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = run(request(&[("content-length", "4, 4")], b"data")).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_chunked_request_passes() {
+        let response = run(request(&[("transfer-encoding", "chunked")], b"data")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // The body limit still applies to chunked bodies
+        let response = run(request(
+            &[("transfer-encoding", "chunked")],
+            b"this body is over the limit",
+        ))
+        .await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[test]
+    fn test_check_values() {
+        let check = |pairs: &[(&'static str, &'static str)]| {
+            let mut headers = HeaderMap::new();
+            for (name, value) in pairs {
+                headers.append(*name, HeaderValue::from_static(value));
+            }
+            RequestSanitizationMiddleware::check(&headers)
+        };
+
+        assert_eq!(check(&[]), Ok(()));
+        assert_eq!(check(&[("content-length", "42")]), Ok(()));
+        assert_eq!(
+            check(&[("content-length", "+42")]),
+            Err(FramingViolation::InvalidContentLength("+42".to_string()))
+        );
+        assert!(check(&[("content-length", "99999999999999999999999")]).is_err());
+        assert_eq!(check(&[("transfer-encoding", "gzip, chunked")]), Ok(()));
+        assert_eq!(
+            check(&[("transfer-encoding", "chunked, gzip")]),
+            Err(FramingViolation::UnsupportedTransferEncoding(
+                "chunked, gzip".to_string()
+            ))
+        );
+    }
+}
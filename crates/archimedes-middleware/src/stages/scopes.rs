@@ -0,0 +1,411 @@
+//! Contract security scope enforcement.
+//!
+//! Operations in a contract can declare security requirements listing the
+//! OAuth2-style scopes a caller must hold. This module provides the types
+//! used by the [`AuthorizationMiddleware`](super::AuthorizationMiddleware)
+//! to compare those requirements against the scopes carried by the caller.
+//!
+//! # Requirement Semantics
+//!
+//! A [`ScopeRequirements`] value is a list of alternative
+//! [`SecurityRequirement`]s. The caller is authorized if **any** alternative
+//! is satisfied, and an alternative is satisfied when the caller holds
+//! **all** of its scopes:
+//!
+//! ```text
+//! [ {users:read, users:write}, {admin} ]
+//!   => (users:read AND users:write) OR admin
+//! ```
+//!
+//! # Caller Scopes
+//!
+//! The [`IdentityMiddleware`](super::IdentityMiddleware) stores a
+//! [`CallerScopes`] extension in the context. For JWT callers the scopes
+//! come from the `scope` claim (space-separated string) or the `scp` claim
+//! (array); for API keys they come from the key's configured scopes.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use archimedes_middleware::stages::{
+//!     AuthorizationMiddleware, ScopeEnforcement, ScopeRequirements,
+//! };
+//!
+//! let scopes = ScopeEnforcement::new()
+//!     .require("getUser", ScopeRequirements::all(["users:read"]))
+//!     .require(
+//!         "deleteUser",
+//!         ScopeRequirements::all(["users:write"]).or(["admin"]),
+//!     );
+//!
+//! let authz = AuthorizationMiddleware::allow_all().with_scope_enforcement(scopes);
+//! ```
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// Default JWT claim holding the caller's scopes.
+pub const DEFAULT_SCOPE_CLAIM: &str = "scope";
+
+/// Alternate JWT claim holding scopes as an array (used by Azure AD and others).
+pub const SCP_CLAIM: &str = "scp";
+
+/// Scopes granted to the caller, stored as a context extension.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CallerScopes(Vec<String>);
+
+impl CallerScopes {
+    /// Creates a scope set from the given scopes.
+    pub fn new<I>(scopes: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        Self(scopes.into_iter().map(Into::into).collect())
+    }
+
+    /// Parses scopes from decoded JWT claims.
+    ///
+    /// The configured `claim` is read first, falling back to `scp` when it
+    /// is absent. Either claim may be a space-separated string or an array
+    /// of strings.
+    #[must_use]
+    pub fn from_claims(claims: &serde_json::Value, claim: &str) -> Self {
+        let value = claims.get(claim).or_else(|| claims.get(SCP_CLAIM));
+        let scopes = match value {
+            Some(serde_json::Value::String(s)) => {
+                s.split_whitespace().map(str::to_string).collect()
+            }
+            Some(serde_json::Value::Array(items)) => items
+                .iter()
+                .filter_map(|v| v.as_str())
+                .map(str::to_string)
+                .collect(),
+            _ => Vec::new(),
+        };
+        Self(scopes)
+    }
+
+    /// Returns true if the caller holds the given scope.
+    #[must_use]
+    pub fn contains(&self, scope: &str) -> bool {
+        self.0.iter().any(|s| s == scope)
+    }
+
+    /// Returns the scopes as a slice.
+    #[must_use]
+    pub fn as_slice(&self) -> &[String] {
+        &self.0
+    }
+
+    /// Returns true if the caller holds no scopes.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// A single security requirement: every listed scope must be present.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SecurityRequirement {
+    scopes: Vec<String>,
+}
+
+impl SecurityRequirement {
+    /// Creates a requirement for all of the given scopes.
+    pub fn new<I>(scopes: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        Self {
+            scopes: scopes.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Returns the required scopes.
+    #[must_use]
+    pub fn scopes(&self) -> &[String] {
+        &self.scopes
+    }
+
+    /// Returns the scopes from this requirement the caller does not hold.
+    fn missing(&self, granted: &HashSet<&str>) -> Vec<String> {
+        self.scopes
+            .iter()
+            .filter(|s| !granted.contains(s.as_str()))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Alternative security requirements for an operation (OR of ANDs).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScopeRequirements {
+    alternatives: Vec<SecurityRequirement>,
+}
+
+impl ScopeRequirements {
+    /// Creates requirements with a single alternative needing all `scopes`.
+    pub fn all<I>(scopes: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        Self {
+            alternatives: vec![SecurityRequirement::new(scopes)],
+        }
+    }
+
+    /// Adds an alternative requirement needing all `scopes`.
+    #[must_use]
+    pub fn or<I>(mut self, scopes: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.alternatives.push(SecurityRequirement::new(scopes));
+        self
+    }
+
+    /// Parses contract security entries.
+    ///
+    /// Each entry is one alternative, written as `scheme[scope_a scope_b]`.
+    /// A bare `scheme` entry requires no scopes and is therefore satisfied
+    /// by any caller.
+    pub fn from_security<I>(entries: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let alternatives = entries
+            .into_iter()
+            .map(|entry| {
+                let entry = entry.as_ref();
+                let scopes = entry
+                    .split_once('[')
+                    .and_then(|(_, rest)| rest.strip_suffix(']'))
+                    .unwrap_or("");
+                SecurityRequirement::new(scopes.split_whitespace())
+            })
+            .collect();
+        Self { alternatives }
+    }
+
+    /// Returns the alternative requirements.
+    #[must_use]
+    pub fn alternatives(&self) -> &[SecurityRequirement] {
+        &self.alternatives
+    }
+
+    /// Returns true if no requirements are declared.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.alternatives.is_empty()
+    }
+
+    /// Checks the caller's scopes against these requirements.
+    ///
+    /// When no alternative matches, the reported missing scopes are those
+    /// of the alternative closest to being satisfied.
+    #[must_use]
+    pub fn check(&self, caller: &CallerScopes) -> ScopeCheck {
+        if self.alternatives.is_empty() {
+            return ScopeCheck::satisfied(caller);
+        }
+
+        let granted: HashSet<&str> = caller.0.iter().map(String::as_str).collect();
+        let mut closest: Option<Vec<String>> = None;
+
+        for alternative in &self.alternatives {
+            let missing = alternative.missing(&granted);
+            if missing.is_empty() {
+                return ScopeCheck::satisfied(caller);
+            }
+            if closest.as_ref().map_or(true, |c| missing.len() < c.len()) {
+                closest = Some(missing);
+            }
+        }
+
+        ScopeCheck {
+            satisfied: false,
+            granted: caller.0.clone(),
+            missing: closest.unwrap_or_default(),
+        }
+    }
+}
+
+/// Outcome of a scope check, stored in the context for auditing and
+/// forwarded to the policy engine.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ScopeCheck {
+    /// Whether any requirement alternative was satisfied.
+    pub satisfied: bool,
+    /// Scopes held by the caller.
+    pub granted: Vec<String>,
+    /// Scopes missing from the closest alternative (empty when satisfied).
+    pub missing: Vec<String>,
+}
+
+impl ScopeCheck {
+    fn satisfied(caller: &CallerScopes) -> Self {
+        Self {
+            satisfied: true,
+            granted: caller.0.clone(),
+            missing: Vec::new(),
+        }
+    }
+}
+
+/// When scope checks are applied relative to policy evaluation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScopeEnforcementMode {
+    /// Reject with 403 before the policy is evaluated.
+    #[default]
+    Enforce,
+    /// Record the result and pass it to the policy without rejecting.
+    ///
+    /// Use this when a Rego policy makes the final decision.
+    Advisory,
+}
+
+/// Per-operation scope requirements used by the authorization stage.
+#[derive(Debug, Clone, Default)]
+pub struct ScopeEnforcement {
+    mode: ScopeEnforcementMode,
+    operations: HashMap<String, ScopeRequirements>,
+}
+
+impl ScopeEnforcement {
+    /// Creates an empty scope enforcement configuration.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds scope requirements from a loaded contract artifact.
+    ///
+    /// Operations without security entries are left unrestricted.
+    #[cfg(feature = "sentinel")]
+    #[must_use]
+    pub fn from_artifact(artifact: &archimedes_sentinel::LoadedArtifact) -> Self {
+        let operations = artifact
+            .operations
+            .iter()
+            .filter(|op| !op.security.is_empty())
+            .map(|op| {
+                (
+                    op.id.clone(),
+                    ScopeRequirements::from_security(&op.security),
+                )
+            })
+            .collect();
+        Self {
+            mode: ScopeEnforcementMode::default(),
+            operations,
+        }
+    }
+
+    /// Sets the scope requirements for an operation.
+    #[must_use]
+    pub fn require(
+        mut self,
+        operation_id: impl Into<String>,
+        requirements: ScopeRequirements,
+    ) -> Self {
+        self.operations.insert(operation_id.into(), requirements);
+        self
+    }
+
+    /// Sets when scope checks are applied.
+    #[must_use]
+    pub fn mode(mut self, mode: ScopeEnforcementMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Returns the configured enforcement mode.
+    #[must_use]
+    pub fn enforcement_mode(&self) -> ScopeEnforcementMode {
+        self.mode
+    }
+
+    /// Returns the requirements for an operation, if any are declared.
+    #[must_use]
+    pub fn requirements(&self, operation_id: &str) -> Option<&ScopeRequirements> {
+        self.operations.get(operation_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_caller_scopes_from_scope_claim() {
+        let claims = json!({ "sub": "alice", "scope": "users:read  users:write" });
+        let scopes = CallerScopes::from_claims(&claims, DEFAULT_SCOPE_CLAIM);
+        assert_eq!(scopes.as_slice(), &["users:read", "users:write"]);
+    }
+
+    #[test]
+    fn test_caller_scopes_from_scp_array() {
+        let claims = json!({ "scp": ["users:read", "admin"] });
+        let scopes = CallerScopes::from_claims(&claims, DEFAULT_SCOPE_CLAIM);
+        assert!(scopes.contains("admin"));
+        assert!(!scopes.contains("users:write"));
+    }
+
+    #[test]
+    fn test_caller_scopes_custom_claim() {
+        let claims = json!({ "permissions": "orders:create" });
+        let scopes = CallerScopes::from_claims(&claims, "permissions");
+        assert_eq!(scopes.as_slice(), &["orders:create"]);
+    }
+
+    #[test]
+    fn test_check_missing_scope() {
+        let requirements = ScopeRequirements::all(["users:read", "users:write"]);
+        let check = requirements.check(&CallerScopes::new(["users:read"]));
+        assert!(!check.satisfied);
+        assert_eq!(check.missing, vec!["users:write".to_string()]);
+    }
+
+    #[test]
+    fn test_check_sufficient_scopes() {
+        let requirements = ScopeRequirements::all(["users:read"]);
+        let check = requirements.check(&CallerScopes::new(["users:read", "users:write"]));
+        assert!(check.satisfied);
+        assert!(check.missing.is_empty());
+    }
+
+    #[test]
+    fn test_check_alternative_requirement_matches() {
+        let requirements = ScopeRequirements::all(["users:read", "users:write"]).or(["admin"]);
+        let check = requirements.check(&CallerScopes::new(["admin"]));
+        assert!(check.satisfied);
+    }
+
+    #[test]
+    fn test_check_reports_closest_alternative() {
+        let requirements = ScopeRequirements::all(["a", "b", "c"]).or(["a", "d"]);
+        let check = requirements.check(&CallerScopes::new(["a"]));
+        assert!(!check.satisfied);
+        assert_eq!(check.missing, vec!["d".to_string()]);
+    }
+
+    #[test]
+    fn test_from_security_entries() {
+        let requirements =
+            ScopeRequirements::from_security(["oauth2[users:read users:write]", "apiKey"]);
+        assert_eq!(requirements.alternatives().len(), 2);
+        assert_eq!(
+            requirements.alternatives()[0].scopes(),
+            &["users:read", "users:write"]
+        );
+        assert!(requirements.alternatives()[1].scopes().is_empty());
+        // The scope-less alternative is satisfied by anyone
+        assert!(requirements.check(&CallerScopes::default()).satisfied);
+    }
+}
@@ -0,0 +1,529 @@
+//! Byte-level body access for request and response signing.
+//!
+//! Signatures are computed over exact bytes, which the rest of the pipeline
+//! does not preserve: request validation may rewrite the body, and
+//! compression changes the response. This module provides two stages:
+//!
+//! - [`RawBodyMiddleware`] keeps the raw request body of opted-in
+//!   operations on the context as [`RawBody`] (see
+//!   [`MiddlewareContext::raw_body`]). Add it as a pre-handler stage ahead
+//!   of validation. Operations opt in through the middleware or through
+//!   [`RouteOptions::retain_raw_body`]. The retained body shares the
+//!   buffer of the request, and bodies over the body-size limit are never
+//!   retained.
+//! - [`ResponseBodyHookMiddleware`] runs hooks over the final response
+//!   body bytes with mutable access to the response headers. Add it as a
+//!   post-handler stage after compression, so it runs before compression
+//!   on the way out.
+//!
+//! [`HmacSigner`] computes HMAC-SHA256 signatures for both directions.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use archimedes_middleware::stages::signing::{
+//!     HmacSigner, RawBodyMiddleware, ResponseBodyHookMiddleware,
+//! };
+//!
+//! let partner = HmacSigner::new(partner_key).signed_header(http::HeaderName::from_static("x-timestamp"));
+//! let ours = HmacSigner::new(signing_key);
+//!
+//! let pipeline = Pipeline::builder()
+//!     .add_pre_handler_stage(RawBodyMiddleware::new().operation("receiveWebhook"))
+//!     .add_pre_handler_stage(validation)
+//!     .add_post_handler_stage(compression)
+//!     .add_post_handler_stage(ResponseBodyHookMiddleware::new().hook(ours.response_hook()))
+//!     .build();
+//!
+//! // In the handler of `receiveWebhook`:
+//! partner.verify_request(&ctx)?;
+//! ```
+
+use crate::context::{MiddlewareContext, RouteOptions};
+use crate::middleware::{BoxFuture, Middleware, Next};
+use crate::pipeline::HookError;
+use crate::stages::body_limit::BodyLimitMiddleware;
+use crate::types::{Request, Response, ResponseExt};
+use bytes::Bytes;
+use hmac::{Hmac, Mac};
+use http::{HeaderMap, HeaderName, HeaderValue, StatusCode};
+use http_body_util::{BodyExt, Full};
+use sha2::Sha256;
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::sync::Arc;
+
+/// Default header carrying signatures.
+pub const SIGNATURE_HEADER: &str = "x-signature";
+
+/// The raw body of a request, as received.
+///
+/// Stored on the [`MiddlewareContext`] by [`RawBodyMiddleware`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawBody(pub Bytes);
+
+/// Middleware keeping the raw request body of opted-in operations.
+#[derive(Debug, Clone, Default)]
+pub struct RawBodyMiddleware {
+    /// Operations whose bodies are retained, besides routes that opt in
+    /// through [`RouteOptions`].
+    operations: HashSet<String>,
+    /// Size limit of retained bodies.
+    limit: BodyLimitMiddleware,
+}
+
+impl RawBodyMiddleware {
+    /// Creates a middleware that retains the bodies of routes opting in
+    /// through [`RouteOptions::retain_raw_body`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Retains the raw body of an operation.
+    #[must_use]
+    pub fn operation(mut self, operation_id: impl Into<String>) -> Self {
+        self.operations.insert(operation_id.into());
+        self
+    }
+
+    /// Sets the largest body retained.
+    ///
+    /// Defaults to the default body-size limit; set it to the limit of
+    /// the [`BodyLimitMiddleware`] in use. Tenant overrides of the limit
+    /// apply here too.
+    #[must_use]
+    pub fn max_bytes(mut self, max_bytes: u64) -> Self {
+        self.limit = BodyLimitMiddleware::new(max_bytes);
+        self
+    }
+
+    /// Returns whether the body of the request must be retained.
+    fn retains(&self, ctx: &MiddlewareContext) -> bool {
+        ctx.get_extension::<RouteOptions>()
+            .is_some_and(|options| options.retain_raw_body)
+            || ctx
+                .operation_id()
+                .is_some_and(|id| self.operations.contains(id))
+    }
+}
+
+impl Middleware for RawBodyMiddleware {
+    fn name(&self) -> &'static str {
+        "raw_body"
+    }
+
+    fn process<'a>(
+        &'a self,
+        ctx: &'a mut MiddlewareContext,
+        request: Request,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Response> {
+        Box::pin(async move {
+            if !self.retains(ctx) {
+                return next.run(ctx, request).await;
+            }
+
+            let (parts, body) = request.into_parts();
+            let bytes = body
+                .collect()
+                .await
+                .map(http_body_util::Collected::to_bytes)
+                .unwrap_or_default();
+
+            let limit = self.limit.limit_for(ctx);
+            if bytes.len() as u64 <= limit {
+                ctx.set_extension(RawBody(bytes.clone()));
+            } else {
+                tracing::debug!(
+                    size = bytes.len(),
+                    limit,
+                    "request body over the size limit, not retained"
+                );
+            }
+
+            next.run(ctx, Request::from_parts(parts, Full::new(bytes)))
+                .await
+        })
+    }
+}
+
+/// A hook over the final response body.
+///
+/// Receives the body bytes and may add or change response headers. An
+/// error replaces the response with `500 Internal Server Error`.
+pub type ResponseBodyHook = Arc<
+    dyn Fn(&MiddlewareContext, &[u8], &mut HeaderMap) -> Result<(), HookError>
+        + Send
+        + Sync
+        + 'static,
+>;
+
+/// Middleware running hooks over the final response body bytes.
+#[derive(Clone, Default)]
+pub struct ResponseBodyHookMiddleware {
+    hooks: Vec<ResponseBodyHook>,
+}
+
+impl std::fmt::Debug for ResponseBodyHookMiddleware {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResponseBodyHookMiddleware")
+            .field("hooks", &self.hooks.len())
+            .finish()
+    }
+}
+
+impl ResponseBodyHookMiddleware {
+    /// Creates a middleware without hooks.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a hook; hooks run in the order they are added.
+    #[must_use]
+    pub fn hook<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&MiddlewareContext, &[u8], &mut HeaderMap) -> Result<(), HookError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.hooks.push(Arc::new(hook));
+        self
+    }
+}
+
+impl Middleware for ResponseBodyHookMiddleware {
+    fn name(&self) -> &'static str {
+        "response_body_hook"
+    }
+
+    fn process<'a>(
+        &'a self,
+        ctx: &'a mut MiddlewareContext,
+        request: Request,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Response> {
+        Box::pin(async move {
+            let response = next.run(ctx, request).await;
+            if self.hooks.is_empty() {
+                return response;
+            }
+
+            let (mut parts, body) = response.into_parts();
+            let bytes = body
+                .collect()
+                .await
+                .map(http_body_util::Collected::to_bytes)
+                .unwrap_or_default();
+
+            for hook in &self.hooks {
+                if let Err(e) = hook(ctx, &bytes, &mut parts.headers) {
+                    tracing::error!(error = %e, "response body hook failed");
+                    return Response::json_error(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "RESPONSE_HOOK_FAILED",
+                        "Failed to finalize the response",
+                    );
+                }
+            }
+
+            Response::from_parts(parts, Full::new(bytes))
+        })
+    }
+}
+
+/// HMAC-SHA256 signatures over a body and selected headers.
+///
+/// The signed message is each signed header as `name:value\n`, in the
+/// order they were added, followed by the body. Signatures are lowercase
+/// hex.
+#[derive(Clone)]
+pub struct HmacSigner {
+    key: Vec<u8>,
+    header: HeaderName,
+    signed_headers: Vec<HeaderName>,
+}
+
+impl std::fmt::Debug for HmacSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HmacSigner")
+            .field("header", &self.header)
+            .field("signed_headers", &self.signed_headers)
+            .finish_non_exhaustive()
+    }
+}
+
+impl HmacSigner {
+    /// Creates a signer with a secret key, using the `X-Signature` header.
+    #[must_use]
+    pub fn new(key: impl Into<Vec<u8>>) -> Self {
+        Self {
+            key: key.into(),
+            header: HeaderName::from_static(SIGNATURE_HEADER),
+            signed_headers: Vec::new(),
+        }
+    }
+
+    /// Sets the header carrying the signature.
+    #[must_use]
+    pub fn header(mut self, header: HeaderName) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Includes a header in the signed message.
+    #[must_use]
+    pub fn signed_header(mut self, header: HeaderName) -> Self {
+        self.signed_headers.push(header);
+        self
+    }
+
+    /// Signs a body and the signed headers.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a signed header is missing.
+    pub fn sign(&self, headers: &HeaderMap, body: &[u8]) -> Result<String, HookError> {
+        let tag = self.mac(headers, body)?.finalize().into_bytes();
+        let mut signature = String::with_capacity(tag.len() * 2);
+        for byte in tag {
+            let _ = write!(signature, "{byte:02x}");
+        }
+        Ok(signature)
+    }
+
+    /// Verifies the signature of a request against its retained raw body.
+    ///
+    /// The comparison is constant-time.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the body was not retained, a signed header or
+    /// the signature is missing, or the signature does not match.
+    pub fn verify_request(&self, ctx: &MiddlewareContext) -> Result<(), HookError> {
+        let body = ctx
+            .raw_body()
+            .ok_or_else(|| hook_error("raw request body was not retained"))?;
+        let headers = ctx
+            .headers()
+            .ok_or_else(|| hook_error("request headers are not available"))?;
+        let signature = headers
+            .get(&self.header)
+            .and_then(|value| value.to_str().ok())
+            .and_then(decode_hex)
+            .ok_or_else(|| hook_error(format!("missing or malformed {} header", self.header)))?;
+
+        self.mac(headers, body)?
+            .verify_slice(&signature)
+            .map_err(|_| hook_error("signature mismatch"))
+    }
+
+    /// Returns a response body hook adding the signature of each response
+    /// to its headers.
+    #[must_use]
+    pub fn response_hook(
+        self,
+    ) -> impl Fn(&MiddlewareContext, &[u8], &mut HeaderMap) -> Result<(), HookError>
+           + Send
+           + Sync
+           + 'static {
+        move |_ctx, body, headers| {
+            let signature = self.sign(headers, body)?;
+            let value = HeaderValue::try_from(signature).map_err(|e| hook_error(e.to_string()))?;
+            headers.insert(self.header.clone(), value);
+            Ok(())
+        }
+    }
+
+    fn mac(&self, headers: &HeaderMap, body: &[u8]) -> Result<Hmac<Sha256>, HookError> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.key)
+            .map_err(|e| hook_error(format!("invalid HMAC key: {e}")))?;
+        for name in &self.signed_headers {
+            let value = headers
+                .get(name)
+                .ok_or_else(|| hook_error(format!("missing signed header {name}")))?;
+            mac.update(name.as_str().as_bytes());
+            mac.update(b":");
+            mac.update(value.as_bytes());
+            mac.update(b"\n");
+        }
+        mac.update(body);
+        Ok(mac)
+    }
+}
+
+fn hook_error(message: impl Into<String>) -> HookError {
+    HookError {
+        message: message.into(),
+    }
+}
+
+/// Decodes lowercase or uppercase hex.
+pub(crate) fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::{Request as HttpRequest, Response as HttpResponse};
+
+    /// RFC 4231, test case 2.
+    const FIXTURE_KEY: &[u8] = b"Jefe";
+    const FIXTURE_BODY: &[u8] = b"what do ya want for nothing?";
+    const FIXTURE_SIGNATURE: &str =
+        "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843";
+
+    fn request(body: &'static [u8]) -> Request {
+        HttpRequest::builder()
+            .method("POST")
+            .uri("/webhooks")
+            .body(Full::new(Bytes::from_static(body)))
+            .unwrap()
+    }
+
+    fn context(operation_id: &str, headers: HeaderMap) -> MiddlewareContext {
+        let mut ctx =
+            MiddlewareContext::from_request(http::Method::POST, "/webhooks".to_string(), headers);
+        ctx.set_operation_id(operation_id.to_string());
+        ctx
+    }
+
+    fn echo_handler() -> impl FnOnce(&mut MiddlewareContext, Request) -> BoxFuture<'static, Response>
+    {
+        |_ctx, _req| {
+            Box::pin(async {
+                HttpResponse::builder()
+                    .status(StatusCode::OK)
+                    .body(Full::new(Bytes::from_static(FIXTURE_BODY)))
+                    .unwrap()
+            })
+        }
+    }
+
+    #[test]
+    fn test_signature_matches_fixture() {
+        let signer = HmacSigner::new(FIXTURE_KEY);
+        assert_eq!(
+            signer.sign(&HeaderMap::new(), FIXTURE_BODY).unwrap(),
+            FIXTURE_SIGNATURE
+        );
+    }
+
+    #[tokio::test]
+    async fn test_response_hook_signs_final_body() {
+        let middleware =
+            ResponseBodyHookMiddleware::new().hook(HmacSigner::new(FIXTURE_KEY).response_hook());
+        let mut ctx = context("getReport", HeaderMap::new());
+
+        let response = middleware
+            .process(&mut ctx, request(b""), Next::handler(echo_handler()))
+            .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get("x-signature").unwrap(),
+            FIXTURE_SIGNATURE
+        );
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body, FIXTURE_BODY);
+    }
+
+    #[tokio::test]
+    async fn test_raw_body_retained_and_verified() {
+        let signer =
+            HmacSigner::new(FIXTURE_KEY).signed_header(HeaderName::from_static("x-timestamp"));
+        let mut headers = HeaderMap::new();
+        headers.insert("x-timestamp", HeaderValue::from_static("1700000000"));
+        let signature = signer.sign(&headers, FIXTURE_BODY).unwrap();
+        headers.insert("x-signature", HeaderValue::try_from(signature).unwrap());
+
+        let middleware = RawBodyMiddleware::new().operation("receiveWebhook");
+        let mut ctx = context("receiveWebhook", headers);
+        let verifier = signer.clone();
+        let next = Next::handler(move |ctx: &mut MiddlewareContext, _req| {
+            let verified = verifier.verify_request(ctx);
+            Box::pin(async move {
+                let status = if verified.is_ok() {
+                    StatusCode::OK
+                } else {
+                    StatusCode::UNAUTHORIZED
+                };
+                HttpResponse::builder()
+                    .status(status)
+                    .body(Full::new(Bytes::new()))
+                    .unwrap()
+            })
+        });
+
+        let response = middleware
+            .process(&mut ctx, request(FIXTURE_BODY), next)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(ctx.raw_body().unwrap().as_ref(), FIXTURE_BODY);
+
+        // A tampered signed header fails verification
+        let mut tampered = ctx.headers().unwrap().clone();
+        tampered.insert("x-timestamp", HeaderValue::from_static("1700000001"));
+        let mut tampered_ctx = context("receiveWebhook", tampered);
+        tampered_ctx.set_extension(RawBody(Bytes::from_static(FIXTURE_BODY)));
+        assert!(signer.verify_request(&tampered_ctx).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_raw_body_not_retained_without_opt_in() {
+        let middleware = RawBodyMiddleware::new().operation("receiveWebhook");
+
+        let mut ctx = context("listReports", HeaderMap::new());
+        middleware
+            .process(
+                &mut ctx,
+                request(FIXTURE_BODY),
+                Next::handler(echo_handler()),
+            )
+            .await;
+        assert!(ctx.raw_body().is_none());
+        assert!(HmacSigner::new(FIXTURE_KEY).verify_request(&ctx).is_err());
+
+        // Route options opt in too
+        let mut ctx = context("listReports", HeaderMap::new());
+        ctx.set_extension(RouteOptions {
+            retain_raw_body: true,
+            ..RouteOptions::default()
+        });
+        middleware
+            .process(
+                &mut ctx,
+                request(FIXTURE_BODY),
+                Next::handler(echo_handler()),
+            )
+            .await;
+        assert!(ctx.raw_body().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_raw_body_over_limit_not_retained() {
+        let middleware = RawBodyMiddleware::new()
+            .operation("receiveWebhook")
+            .max_bytes(8);
+        let mut ctx = context("receiveWebhook", HeaderMap::new());
+
+        let response = middleware
+            .process(
+                &mut ctx,
+                request(FIXTURE_BODY),
+                Next::handler(echo_handler()),
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(ctx.raw_body().is_none());
+    }
+}
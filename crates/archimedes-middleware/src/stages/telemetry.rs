@@ -24,8 +24,50 @@
 //! - `trace_id` - Distributed trace ID
 //! - `span_id` - Current span ID
 //! - `operation_id` - Contract operation being called
+//! - `route` - Route pattern matched (e.g. `/users/{userId}`), never the raw path
 //! - `status_code` - HTTP response status
+//! - `stream_status` - For streaming responses, how the stream ended
 //! - `duration_ms` - Request duration in milliseconds
+//! - `batched` - Whether the request was dispatched from a batch request
+//! - `internal` - Whether the request targeted an internal endpoint such as
+//!   `/health`, rather than a contract operation
+//! - `contract_version` - Contract version the request was resolved against
+//! - `tenant` - Tenant the request belongs to (only when enabled, see below)
+//! - `gate` - Outcome of the operation's [`gate`](super::gate), if it is gated
+//! - `app` - App the request was dispatched to, when one server hosts several
+//! - `fields` - Business fields the handler recorded with
+//!   [`RequestContext::record`](archimedes_core::RequestContext::record),
+//!   when the [`tracing`](super::tracing) stage is running
+//!
+//! # Access Log
+//!
+//! Each completed request also emits one `INFO` event under the
+//! [`ACCESS_LOG_TARGET`] target, with the fields an access log needs:
+//! `method`, `path`, `protocol`, `status`, `bytes` (response body size),
+//! `request_bytes`, `duration_ms`, `caller` (the identity as in logs, absent
+//! for anonymous callers), `referer`, `user_agent` and `request_id`. The
+//! telemetry crate's access log layer renders these events as JSON or in
+//! Common or Combined Log Format.
+//!
+//! # Tenant Label
+//!
+//! [`TelemetryBuilder::tenant_label`] adds the tenant resolved by the
+//! [`tenant`](super::tenant) stage as a label. Tenant IDs are unbounded, so
+//! only the first [`max_tenant_labels`](TelemetryBuilder::max_tenant_labels)
+//! distinct tenants get their own label; later tenants are reported as
+//! [`OTHER_TENANT_LABEL`].
+//!
+//! # Streaming Responses
+//!
+//! A streaming response is sent with its status before the body is
+//! produced. When the response carries a [`StreamOutcome`] extension the
+//! collected [`TelemetryData`] keeps the handle, and
+//! [`TelemetryData::effective_status_code`] reports `500` once the stream
+//! has failed, so request metrics count the failure instead of the `200`.
+//!
+//! Buffered responses may carry [`Trailers`], such as `grpc-status`. They
+//! are final when the response leaves the handler, so the collected data
+//! keeps them and a failing trailer status also counts as `500`.
 //!
 //! # Example
 //!
@@ -43,12 +85,31 @@
 //! ```
 
 use crate::{
-    context::MiddlewareContext,
+    context::{AppName, BatchedRequest, ContractVersion, MiddlewareContext, RoutePattern},
     middleware::{BoxFuture, Middleware, Next},
+    stages::gate::GateDecision,
     types::{Request, Response},
 };
+use archimedes_core::span_fields::{FieldValue, SpanFields};
+use archimedes_core::{CallerIdentity, CallerIdentityExt, StreamOutcome, Trailers};
+use http::header::{REFERER, USER_AGENT};
+use http::HeaderMap;
+use http_body::Body;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
+/// Default number of distinct tenants given their own label.
+pub const DEFAULT_MAX_TENANT_LABELS: usize = 100;
+
+/// Label used for tenants beyond the distinct tenant limit.
+pub const OTHER_TENANT_LABEL: &str = "other";
+
+/// Target of the access log event emitted for each completed request.
+///
+/// Matches `archimedes_telemetry::access_log::ACCESS_LOG_TARGET`.
+pub const ACCESS_LOG_TARGET: &str = "archimedes::access";
+
 /// Telemetry middleware that emits metrics and logs for every request.
 #[derive(Debug, Clone)]
 pub struct TelemetryMiddleware {
@@ -60,6 +121,12 @@ pub struct TelemetryMiddleware {
     environment: String,
     /// Whether to emit detailed logs.
     verbose: bool,
+    /// Whether to label requests with their tenant.
+    tenant_label: bool,
+    /// Maximum number of distinct tenant labels.
+    max_tenant_labels: usize,
+    /// Tenants that have been given their own label.
+    seen_tenants: Arc<Mutex<HashSet<String>>>,
 }
 
 /// Telemetry data collected during request processing.
@@ -77,16 +144,69 @@ pub struct TelemetryData {
     pub method: String,
     /// The request path.
     pub path: String,
+    /// The HTTP version of the request, such as `HTTP/1.1`.
+    pub protocol: String,
+    /// The matched route pattern, used as the low-cardinality route label.
+    pub route: String,
     /// The HTTP status code.
     pub status_code: u16,
     /// Request duration in milliseconds.
     pub duration_ms: f64,
+    /// Size of the request body in bytes.
+    pub request_bytes: u64,
+    /// Size of the response body in bytes, if known before it is sent.
+    pub response_bytes: Option<u64>,
+    /// The caller's identity as in logs; `None` for anonymous callers.
+    pub caller: Option<String>,
+    /// The request's `Referer` header (if any).
+    pub referer: Option<String>,
+    /// The request's `User-Agent` header (if any).
+    pub user_agent: Option<String>,
     /// The request ID.
     pub request_id: String,
     /// The trace ID (if available).
     pub trace_id: Option<String>,
     /// The span ID (if available).
     pub span_id: Option<String>,
+    /// Whether the request was a sub-request of a batch.
+    pub batched: bool,
+    /// Whether the request targeted an internal endpoint.
+    pub internal: bool,
+    /// Contract version the request was resolved against (if versioned).
+    pub contract_version: Option<String>,
+    /// Outcome of a streaming response body (if streaming).
+    pub stream_outcome: Option<StreamOutcome>,
+    /// Trailers sent after the response body (if any).
+    pub trailers: Option<Trailers>,
+    /// Tenant label (if tenant labelling is enabled).
+    pub tenant: Option<String>,
+    /// Outcome of the operation's gate (if the operation is gated).
+    pub gate: Option<String>,
+    /// App the request was dispatched to (if the server hosts several).
+    pub app: Option<String>,
+    /// Fields recorded while handling the request, in recording order.
+    pub fields: Vec<(String, FieldValue)>,
+}
+
+impl TelemetryData {
+    /// Returns the status code to record for the request.
+    ///
+    /// This is `500` for a streaming response whose stream failed after the
+    /// status line was sent or whose trailers report a failure, and
+    /// [`status_code`](Self::status_code) otherwise.
+    #[must_use]
+    pub fn effective_status_code(&self) -> u16 {
+        let stream_failed = self
+            .stream_outcome
+            .as_ref()
+            .is_some_and(StreamOutcome::is_error);
+        let trailers_failed = self.trailers.as_ref().is_some_and(Trailers::is_failure);
+        if stream_failed || trailers_failed {
+            500
+        } else {
+            self.status_code
+        }
+    }
 }
 
 impl TelemetryMiddleware {
@@ -98,6 +218,9 @@ impl TelemetryMiddleware {
             version: "unknown".to_string(),
             environment: "unknown".to_string(),
             verbose: false,
+            tenant_label: false,
+            max_tenant_labels: DEFAULT_MAX_TENANT_LABELS,
+            seen_tenants: Arc::default(),
         }
     }
 
@@ -109,6 +232,31 @@ impl TelemetryMiddleware {
             version: "unknown".to_string(),
             environment: "unknown".to_string(),
             verbose: false,
+            tenant_label: false,
+            max_tenant_labels: DEFAULT_MAX_TENANT_LABELS,
+        }
+    }
+
+    /// Returns the tenant label for a request.
+    ///
+    /// Returns `None` when tenant labelling is disabled or the request has no
+    /// tenant, and [`OTHER_TENANT_LABEL`] once the distinct tenant limit has
+    /// been reached.
+    fn tenant_label(&self, ctx: &MiddlewareContext) -> Option<String> {
+        if !self.tenant_label {
+            return None;
+        }
+        let tenant = ctx.tenant_id()?;
+
+        let mut seen = self
+            .seen_tenants
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if seen.contains(tenant) || seen.len() < self.max_tenant_labels {
+            seen.insert(tenant.to_string());
+            Some(tenant.to_string())
+        } else {
+            Some(OTHER_TENANT_LABEL.to_string())
         }
     }
 
@@ -132,11 +280,27 @@ impl TelemetryMiddleware {
             operation_id: ctx.operation_id().unwrap_or("unknown").to_string(),
             method: request.method().to_string(),
             path: request.uri().path().to_string(),
+            protocol: format!("{:?}", request.version()),
+            route: route_label(ctx),
             status_code: response.status().as_u16(),
             duration_ms: duration.as_secs_f64() * 1000.0,
+            request_bytes: request.body().size_hint().exact().unwrap_or(0),
+            response_bytes: response.body().size_hint().exact(),
+            caller: caller_label(ctx.identity()),
+            referer: header_string(request.headers(), &REFERER),
+            user_agent: header_string(request.headers(), &USER_AGENT),
             request_id: ctx.request_id().to_string(),
             trace_id: ctx.trace_id().map(ToString::to_string),
             span_id: ctx.span_id().map(ToString::to_string),
+            batched: ctx.has_extension::<BatchedRequest>(),
+            internal: ctx.is_internal(),
+            contract_version: ctx.get_extension::<ContractVersion>().map(|v| v.0.clone()),
+            stream_outcome: response.extensions().get::<StreamOutcome>().cloned(),
+            trailers: response.extensions().get::<Trailers>().cloned(),
+            tenant: self.tenant_label(ctx),
+            gate: gate_label(ctx),
+            app: ctx.get_extension::<AppName>().map(|app| app.0.clone()),
+            fields: recorded_fields(ctx),
         }
     }
 
@@ -145,6 +309,8 @@ impl TelemetryMiddleware {
     /// In production, this would send to OpenTelemetry collectors.
     /// For now, this is a mock that stores data in context.
     fn emit_telemetry(&self, ctx: &mut MiddlewareContext, data: TelemetryData) {
+        emit_access_log(&data);
+
         // Store telemetry data in context for testing/inspection
         ctx.set_extension(data.clone());
 
@@ -178,6 +344,10 @@ impl Middleware for TelemetryMiddleware {
             // Clone request info before passing ownership
             let method = request.method().to_string();
             let path = request.uri().path().to_string();
+            let protocol = format!("{:?}", request.version());
+            let request_bytes = request.body().size_hint().exact().unwrap_or(0);
+            let referer = header_string(request.headers(), &REFERER);
+            let user_agent = header_string(request.headers(), &USER_AGENT);
 
             // Process the request
             let response = next.run(ctx, request).await;
@@ -193,11 +363,27 @@ impl Middleware for TelemetryMiddleware {
                 operation_id: ctx.operation_id().unwrap_or("unknown").to_string(),
                 method,
                 path,
+                protocol,
+                route: route_label(ctx),
                 status_code: response.status().as_u16(),
                 duration_ms: duration.as_secs_f64() * 1000.0,
+                request_bytes,
+                response_bytes: response.body().size_hint().exact(),
+                caller: caller_label(ctx.identity()),
+                referer,
+                user_agent,
                 request_id: ctx.request_id().to_string(),
                 trace_id: ctx.trace_id().map(ToString::to_string),
                 span_id: ctx.span_id().map(ToString::to_string),
+                batched: ctx.has_extension::<BatchedRequest>(),
+                internal: ctx.is_internal(),
+                contract_version: ctx.get_extension::<ContractVersion>().map(|v| v.0.clone()),
+                stream_outcome: response.extensions().get::<StreamOutcome>().cloned(),
+                trailers: response.extensions().get::<Trailers>().cloned(),
+                tenant: self.tenant_label(ctx),
+                gate: gate_label(ctx),
+                app: ctx.get_extension::<AppName>().map(|app| app.0.clone()),
+                fields: recorded_fields(ctx),
             };
 
             // Emit telemetry
@@ -208,6 +394,61 @@ impl Middleware for TelemetryMiddleware {
     }
 }
 
+/// Emits the access log event of a completed request.
+fn emit_access_log(data: &TelemetryData) {
+    tracing::info!(
+        target: ACCESS_LOG_TARGET,
+        method = %data.method,
+        path = %data.path,
+        protocol = %data.protocol,
+        status = data.effective_status_code(),
+        bytes = data.response_bytes,
+        request_bytes = data.request_bytes,
+        duration_ms = data.duration_ms,
+        caller = data.caller.as_deref(),
+        referer = data.referer.as_deref(),
+        user_agent = data.user_agent.as_deref(),
+        request_id = %data.request_id,
+        operation_id = %data.operation_id,
+        "request completed"
+    );
+}
+
+/// Returns the caller as in logs, or `None` for anonymous callers.
+fn caller_label(identity: &CallerIdentity) -> Option<String> {
+    match identity {
+        CallerIdentity::Anonymous => None,
+        identity => Some(identity.log_id()),
+    }
+}
+
+/// Returns a header value as a string, if present and printable.
+fn header_string(headers: &HeaderMap, name: &http::header::HeaderName) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(ToString::to_string)
+}
+
+/// Returns the route label for a request, falling back to `"unknown"`.
+fn route_label(ctx: &MiddlewareContext) -> String {
+    ctx.get_extension::<RoutePattern>()
+        .map_or_else(|| "unknown".to_string(), |r| r.0.clone())
+}
+
+/// Returns the fields recorded on the request span, if it is traced.
+fn recorded_fields(ctx: &MiddlewareContext) -> Vec<(String, FieldValue)> {
+    ctx.get_extension::<SpanFields>()
+        .map(SpanFields::snapshot)
+        .unwrap_or_default()
+}
+
+/// Returns the gate outcome of a request, if its operation is gated.
+fn gate_label(ctx: &MiddlewareContext) -> Option<String> {
+    ctx.get_extension::<GateDecision>()
+        .map(|decision| decision.outcome.as_str().to_string())
+}
+
 /// Builder for `TelemetryMiddleware`.
 #[derive(Debug)]
 pub struct TelemetryBuilder {
@@ -215,6 +456,8 @@ pub struct TelemetryBuilder {
     version: String,
     environment: String,
     verbose: bool,
+    tenant_label: bool,
+    max_tenant_labels: usize,
 }
 
 impl TelemetryBuilder {
@@ -239,6 +482,24 @@ impl TelemetryBuilder {
         self
     }
 
+    /// Labels requests with their tenant.
+    ///
+    /// Disabled by default to keep metric cardinality bounded.
+    #[must_use]
+    pub fn tenant_label(mut self, enabled: bool) -> Self {
+        self.tenant_label = enabled;
+        self
+    }
+
+    /// Sets the maximum number of distinct tenants given their own label.
+    ///
+    /// Defaults to [`DEFAULT_MAX_TENANT_LABELS`].
+    #[must_use]
+    pub fn max_tenant_labels(mut self, max: usize) -> Self {
+        self.max_tenant_labels = max;
+        self
+    }
+
     /// Builds the telemetry middleware.
     #[must_use]
     pub fn build(self) -> TelemetryMiddleware {
@@ -247,6 +508,9 @@ impl TelemetryBuilder {
             version: self.version,
             environment: self.environment,
             verbose: self.verbose,
+            tenant_label: self.tenant_label,
+            max_tenant_labels: self.max_tenant_labels,
+            seen_tenants: Arc::default(),
         }
     }
 }
@@ -258,6 +522,7 @@ impl TelemetryBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::context::RouteOptions;
     use crate::middleware::Next;
     use bytes::Bytes;
     use http::{Request as HttpRequest, Response as HttpResponse, StatusCode};
@@ -329,6 +594,160 @@ mod tests {
         assert_eq!(telemetry.path, "/users/123");
         assert_eq!(telemetry.status_code, 200);
         assert!(telemetry.duration_ms >= 0.0);
+        assert!(!telemetry.batched);
+        assert!(!telemetry.internal);
+    }
+
+    #[tokio::test]
+    async fn test_telemetry_captures_access_log_fields() {
+        let middleware = TelemetryMiddleware::new("test-service");
+
+        let mut ctx = MiddlewareContext::new();
+        ctx.set_identity(CallerIdentity::user("user-123", "alice@example.com"));
+
+        let request = HttpRequest::builder()
+            .method("POST")
+            .uri("/users")
+            .header(USER_AGENT, "curl/8.0")
+            .header(REFERER, "https://example.com/")
+            .body(Full::new(Bytes::from(r#"{"name":"alice"}"#)))
+            .unwrap();
+        let next = Next::handler(create_handler());
+        middleware.process(&mut ctx, request, next).await;
+
+        let telemetry = ctx.get_extension::<TelemetryData>().unwrap();
+        assert_eq!(telemetry.protocol, "HTTP/1.1");
+        assert_eq!(telemetry.request_bytes, 16);
+        assert_eq!(telemetry.response_bytes, Some(12));
+        assert_eq!(telemetry.caller.as_deref(), Some("user:user-123"));
+        assert_eq!(telemetry.referer.as_deref(), Some("https://example.com/"));
+        assert_eq!(telemetry.user_agent.as_deref(), Some("curl/8.0"));
+    }
+
+    #[tokio::test]
+    async fn test_anonymous_caller_not_captured() {
+        let middleware = TelemetryMiddleware::new("test-service");
+
+        let mut ctx = MiddlewareContext::new();
+        let next = Next::handler(create_handler());
+        middleware
+            .process(&mut ctx, make_test_request(), next)
+            .await;
+
+        let telemetry = ctx.get_extension::<TelemetryData>().unwrap();
+        assert_eq!(telemetry.caller, None);
+        assert_eq!(telemetry.request_bytes, 0);
+    }
+
+    #[tokio::test]
+    async fn test_telemetry_marks_internal_requests() {
+        let middleware = TelemetryMiddleware::new("test-service");
+
+        let mut ctx = MiddlewareContext::new();
+        ctx.set_extension(RouteOptions::internal());
+
+        let next = Next::handler(create_handler());
+        middleware
+            .process(&mut ctx, make_test_request(), next)
+            .await;
+
+        let telemetry = ctx.get_extension::<TelemetryData>().unwrap();
+        assert!(telemetry.internal);
+    }
+
+    #[tokio::test]
+    async fn test_telemetry_marks_batched_requests() {
+        let middleware = TelemetryMiddleware::new("test-service");
+
+        let mut ctx = MiddlewareContext::new();
+        ctx.set_operation_id("getUser".to_string());
+        ctx.set_extension(BatchedRequest);
+
+        let next = Next::handler(create_handler());
+        middleware
+            .process(&mut ctx, make_test_request(), next)
+            .await;
+
+        let telemetry = ctx.get_extension::<TelemetryData>().unwrap();
+        assert_eq!(telemetry.operation_id, "getUser");
+        assert!(telemetry.batched);
+        assert_eq!(telemetry.app, None);
+    }
+
+    #[tokio::test]
+    async fn test_telemetry_labels_app() {
+        let middleware = TelemetryMiddleware::new("test-service");
+
+        let mut ctx = MiddlewareContext::new();
+        ctx.set_extension(AppName("admin".to_string()));
+
+        let next = Next::handler(create_handler());
+        middleware
+            .process(&mut ctx, make_test_request(), next)
+            .await;
+
+        let telemetry = ctx.get_extension::<TelemetryData>().unwrap();
+        assert_eq!(telemetry.app.as_deref(), Some("admin"));
+    }
+
+    #[tokio::test]
+    async fn test_telemetry_includes_recorded_fields() {
+        let middleware = TelemetryMiddleware::new("test-service");
+
+        let mut ctx = MiddlewareContext::new();
+        let fields = SpanFields::new();
+        fields.record("tenant_id", "acme");
+        ctx.set_extension(fields);
+
+        let next = Next::handler(create_handler());
+        middleware
+            .process(&mut ctx, make_test_request(), next)
+            .await;
+
+        let telemetry = ctx.get_extension::<TelemetryData>().unwrap();
+        assert_eq!(
+            telemetry.fields,
+            vec![("tenant_id".to_string(), FieldValue::from("acme"))]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_telemetry_labels_contract_version() {
+        let middleware = TelemetryMiddleware::new("test-service");
+
+        let mut ctx = MiddlewareContext::new();
+        ctx.set_extension(ContractVersion("v2".to_string()));
+
+        let next = Next::handler(create_handler());
+        middleware
+            .process(&mut ctx, make_test_request(), next)
+            .await;
+
+        let telemetry = ctx.get_extension::<TelemetryData>().unwrap();
+        assert_eq!(telemetry.contract_version.as_deref(), Some("v2"));
+    }
+
+    #[tokio::test]
+    async fn test_telemetry_labels_route_pattern() {
+        let middleware = TelemetryMiddleware::new("test-service");
+
+        let mut ctx = MiddlewareContext::new();
+        let next = Next::handler(create_handler());
+        middleware
+            .process(&mut ctx, make_test_request(), next)
+            .await;
+        let telemetry = ctx.get_extension::<TelemetryData>().unwrap();
+        assert_eq!(telemetry.route, "unknown");
+
+        let mut ctx = MiddlewareContext::new();
+        ctx.set_extension(RoutePattern("/users/{userId}".to_string()));
+        let next = Next::handler(create_handler());
+        middleware
+            .process(&mut ctx, make_test_request(), next)
+            .await;
+        let telemetry = ctx.get_extension::<TelemetryData>().unwrap();
+        assert_eq!(telemetry.route, "/users/{userId}");
+        assert_eq!(telemetry.path, "/users/123");
     }
 
     #[tokio::test]
@@ -391,6 +810,122 @@ mod tests {
         assert_eq!(telemetry.status_code, 404);
     }
 
+    #[tokio::test]
+    async fn test_telemetry_reports_failed_stream() {
+        use archimedes_core::StreamStatus;
+
+        let middleware = TelemetryMiddleware::new("test-service");
+        let outcome = StreamOutcome::new();
+
+        let mut ctx = MiddlewareContext::new();
+        let handler_outcome = outcome.clone();
+        let next = Next::handler(move |_ctx, _req| {
+            Box::pin(async move {
+                let mut response = success_response();
+                response.extensions_mut().insert(handler_outcome);
+                response
+            })
+        });
+
+        let response = middleware
+            .process(&mut ctx, make_test_request(), next)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let telemetry = ctx.get_extension::<TelemetryData>().unwrap().clone();
+        assert_eq!(telemetry.effective_status_code(), 200);
+
+        // The stream fails after the status line has been sent
+        outcome.set(StreamStatus::error("upstream closed"));
+        assert_eq!(telemetry.status_code, 200);
+        assert_eq!(telemetry.effective_status_code(), 500);
+    }
+
+    #[tokio::test]
+    async fn test_telemetry_reports_failed_trailer_status() {
+        use archimedes_core::response::GRPC_STATUS_TRAILER;
+        use http::HeaderValue;
+
+        let middleware = TelemetryMiddleware::new("test-service");
+        let mut ctx = MiddlewareContext::new();
+        let next = Next::handler(|_ctx, _req| {
+            Box::pin(async {
+                let mut response = success_response();
+                response.extensions_mut().insert(
+                    Trailers::new().with(GRPC_STATUS_TRAILER, HeaderValue::from_static("14")),
+                );
+                response
+            })
+        });
+
+        middleware
+            .process(&mut ctx, make_test_request(), next)
+            .await;
+
+        let telemetry = ctx.get_extension::<TelemetryData>().unwrap();
+        assert_eq!(telemetry.status_code, 200);
+        assert_eq!(
+            telemetry
+                .trailers
+                .as_ref()
+                .unwrap()
+                .get(GRPC_STATUS_TRAILER)
+                .unwrap(),
+            "14"
+        );
+        assert_eq!(telemetry.effective_status_code(), 500);
+    }
+
+    #[tokio::test]
+    async fn test_tenant_label_toggled_by_config() {
+        let run = |middleware: TelemetryMiddleware, tenant: &'static str| async move {
+            let mut ctx = MiddlewareContext::new();
+            ctx.set_tenant_id(tenant.to_string());
+            let next = Next::handler(create_handler());
+            middleware
+                .process(&mut ctx, make_test_request(), next)
+                .await;
+            ctx.get_extension::<TelemetryData>().unwrap().tenant.clone()
+        };
+
+        let disabled = TelemetryMiddleware::new("test-service");
+        assert_eq!(run(disabled, "acme").await, None);
+
+        let enabled = TelemetryMiddleware::builder("test-service")
+            .tenant_label(true)
+            .build();
+        assert_eq!(run(enabled, "acme").await.as_deref(), Some("acme"));
+    }
+
+    #[tokio::test]
+    async fn test_tenant_label_caps_distinct_tenants() {
+        let middleware = TelemetryMiddleware::builder("test-service")
+            .tenant_label(true)
+            .max_tenant_labels(2)
+            .build();
+
+        let mut labels = Vec::new();
+        for tenant in ["acme", "globex", "initech", "acme"] {
+            let mut ctx = MiddlewareContext::new();
+            ctx.set_tenant_id(tenant.to_string());
+            let next = Next::handler(create_handler());
+            middleware
+                .process(&mut ctx, make_test_request(), next)
+                .await;
+            labels.push(ctx.get_extension::<TelemetryData>().unwrap().tenant.clone());
+        }
+
+        assert_eq!(
+            labels,
+            vec![
+                Some("acme".to_string()),
+                Some("globex".to_string()),
+                Some(OTHER_TENANT_LABEL.to_string()),
+                Some("acme".to_string()),
+            ]
+        );
+    }
+
     #[test]
     fn test_telemetry_data_structure() {
         let data = TelemetryData {
@@ -400,11 +935,27 @@ mod tests {
             operation_id: "getUser".to_string(),
             method: "GET".to_string(),
             path: "/users/123".to_string(),
+            protocol: "HTTP/1.1".to_string(),
+            route: "/users/{userId}".to_string(),
             status_code: 200,
             duration_ms: 45.5,
+            request_bytes: 0,
+            response_bytes: Some(13),
+            caller: Some("user:alice".to_string()),
+            referer: None,
+            user_agent: Some("curl/8.0".to_string()),
             request_id: "req-123".to_string(),
             trace_id: Some("trace-abc".to_string()),
             span_id: Some("span-xyz".to_string()),
+            batched: false,
+            internal: false,
+            contract_version: None,
+            stream_outcome: None,
+            trailers: None,
+            tenant: None,
+            gate: None,
+            app: None,
+            fields: Vec::new(),
         };
 
         assert_eq!(data.service_name, "test");
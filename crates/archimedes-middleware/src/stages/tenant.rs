@@ -0,0 +1,555 @@
+//! Per-tenant policy middleware.
+//!
+//! Multi-tenant services share one deployment across tenants that need
+//! different quotas and entitlements. This stage resolves the tenant of each
+//! request and stores its overrides in the context, where the rate-limit,
+//! body-limit and authorization stages consult them before their global
+//! defaults.
+//!
+//! # Pipeline Position
+//!
+//! Tenant resolution runs after Identity and before the stages it feeds:
+//!
+//! ```text
+//! Identity → [TenantPolicy] → RateLimit → BodyLimit → Authorization → Handler
+//! ```
+//!
+//! # Tenant Resolution
+//!
+//! By default the tenant ID is read from the `tenant_id` claim of the bearer
+//! JWT (configurable via [`TenantPolicyMiddleware::claim`]), falling back to
+//! the `tenant_id` of a user identity. Implement [`TenantResolver`] for other
+//! sources.
+//!
+//! # Policy File
+//!
+//! [`StaticTenantPolicyStore`] loads overrides from TOML. Tenants that are
+//! not listed use the global defaults of each stage.
+//!
+//! ```toml
+//! [tenants.acme]
+//! rate_limit = { rps = 5, burst = 10 }
+//! max_body_bytes = 1048576
+//! denied_operations = ["exportAll"]
+//! ```
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use archimedes_middleware::stages::{StaticTenantPolicyStore, TenantPolicyMiddleware};
+//!
+//! let store = StaticTenantPolicyStore::from_toml_file("tenants.toml")?;
+//! let tenants = TenantPolicyMiddleware::new(store).claim("org_id");
+//! ```
+
+use crate::context::MiddlewareContext;
+use crate::middleware::{BoxFuture, Middleware, Next};
+use crate::stages::identity::IdentityMiddleware;
+use crate::types::{Request, Response};
+use archimedes_core::CallerIdentity;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// JWT claim holding the tenant ID by default.
+pub const DEFAULT_TENANT_CLAIM: &str = "tenant_id";
+
+/// Resolves the tenant a request was made on behalf of.
+pub trait TenantResolver: Send + Sync {
+    /// Returns the tenant ID, or `None` for requests without a tenant.
+    fn resolve(&self, ctx: &MiddlewareContext, request: &Request) -> Option<String>;
+}
+
+/// Resolves the tenant from a JWT claim.
+///
+/// String and numeric claim values are accepted. When the token carries no
+/// such claim, the `tenant_id` of a user identity is used.
+#[derive(Debug, Clone)]
+pub struct ClaimTenantResolver {
+    claim: String,
+}
+
+impl ClaimTenantResolver {
+    /// Creates a resolver reading the given claim.
+    #[must_use]
+    pub fn new(claim: impl Into<String>) -> Self {
+        Self {
+            claim: claim.into(),
+        }
+    }
+
+    /// Returns the claim the tenant ID is read from.
+    #[must_use]
+    pub fn claim(&self) -> &str {
+        &self.claim
+    }
+}
+
+impl Default for ClaimTenantResolver {
+    fn default() -> Self {
+        Self::new(DEFAULT_TENANT_CLAIM)
+    }
+}
+
+impl TenantResolver for ClaimTenantResolver {
+    fn resolve(&self, ctx: &MiddlewareContext, request: &Request) -> Option<String> {
+        let from_claim = IdentityMiddleware::decode_jwt_claims(request).and_then(|claims| {
+            match claims.get(&self.claim)? {
+                serde_json::Value::String(s) if !s.is_empty() => Some(s.clone()),
+                serde_json::Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            }
+        });
+
+        from_claim.or_else(|| match ctx.identity() {
+            CallerIdentity::User(user) => user.tenant_id.clone(),
+            _ => None,
+        })
+    }
+}
+
+/// Rate limit override for a tenant.
+///
+/// Enforced as a one-second sliding window shared by all of the tenant's
+/// callers, admitting up to `burst` requests (at least `rps`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TenantRateLimit {
+    /// Sustained requests per second.
+    pub rps: u64,
+    /// Requests admitted in a single burst; defaults to `rps`.
+    #[serde(default)]
+    pub burst: Option<u64>,
+}
+
+impl TenantRateLimit {
+    /// Creates a rate limit of `rps` requests per second with no extra burst.
+    #[must_use]
+    pub fn new(rps: u64) -> Self {
+        Self { rps, burst: None }
+    }
+
+    /// Sets the burst size.
+    #[must_use]
+    pub fn with_burst(mut self, burst: u64) -> Self {
+        self.burst = Some(burst);
+        self
+    }
+
+    /// Returns the number of requests admitted per window.
+    #[must_use]
+    pub fn limit(&self) -> u64 {
+        self.burst.unwrap_or(self.rps).max(self.rps)
+    }
+
+    /// Returns the window the limit applies to.
+    #[must_use]
+    pub fn window(&self) -> Duration {
+        Duration::from_secs(1)
+    }
+}
+
+/// Overrides applied to one tenant's requests.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TenantOverrides {
+    /// Replaces the global rate limit.
+    pub rate_limit: Option<TenantRateLimit>,
+    /// Replaces the global request body size limit.
+    pub max_body_bytes: Option<u64>,
+    /// Operations the tenant may not call.
+    pub denied_operations: Vec<String>,
+}
+
+impl TenantOverrides {
+    /// Creates overrides that change nothing.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the rate limit override.
+    #[must_use]
+    pub fn rate_limit(mut self, rate_limit: TenantRateLimit) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
+    /// Sets the body size override.
+    #[must_use]
+    pub fn max_body_bytes(mut self, max: u64) -> Self {
+        self.max_body_bytes = Some(max);
+        self
+    }
+
+    /// Denies an operation to the tenant.
+    #[must_use]
+    pub fn deny_operation(mut self, operation_id: impl Into<String>) -> Self {
+        self.denied_operations.push(operation_id.into());
+        self
+    }
+
+    /// Returns `true` if the tenant may not call the operation.
+    #[must_use]
+    pub fn denies(&self, operation_id: &str) -> bool {
+        self.denied_operations.iter().any(|op| op == operation_id)
+    }
+}
+
+/// Source of per-tenant overrides.
+pub trait TenantPolicyStore: Send + Sync {
+    /// Returns the overrides for a tenant, or `None` if the tenant uses the
+    /// global defaults.
+    fn overrides(&self, tenant_id: &str) -> Option<TenantOverrides>;
+}
+
+/// Error loading a tenant policy file.
+#[derive(Debug)]
+pub enum TenantPolicyError {
+    /// The file could not be read.
+    Io(std::io::Error),
+    /// The file is not a valid tenant policy document.
+    Parse(String),
+}
+
+impl std::fmt::Display for TenantPolicyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "failed to read tenant policy file: {e}"),
+            Self::Parse(msg) => write!(f, "invalid tenant policy: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for TenantPolicyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Parse(_) => None,
+        }
+    }
+}
+
+/// Tenant policy document as written in TOML.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TenantPolicyFile {
+    #[serde(default)]
+    tenants: HashMap<String, TenantOverrides>,
+}
+
+/// In-memory tenant policy store, typically loaded from TOML.
+#[derive(Debug, Clone, Default)]
+pub struct StaticTenantPolicyStore {
+    tenants: HashMap<String, TenantOverrides>,
+}
+
+impl StaticTenantPolicyStore {
+    /// Creates an empty store.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds overrides for a tenant.
+    #[must_use]
+    pub fn with_tenant(mut self, tenant_id: impl Into<String>, overrides: TenantOverrides) -> Self {
+        self.tenants.insert(tenant_id.into(), overrides);
+        self
+    }
+
+    /// Parses a store from a TOML document.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TenantPolicyError::Parse`] if the document is malformed or
+    /// contains unknown keys.
+    pub fn from_toml_str(toml: &str) -> Result<Self, TenantPolicyError> {
+        let file: TenantPolicyFile =
+            toml::from_str(toml).map_err(|e| TenantPolicyError::Parse(e.to_string()))?;
+        Ok(Self {
+            tenants: file.tenants,
+        })
+    }
+
+    /// Loads a store from a TOML file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed.
+    pub fn from_toml_file(path: impl AsRef<Path>) -> Result<Self, TenantPolicyError> {
+        let toml = std::fs::read_to_string(path).map_err(TenantPolicyError::Io)?;
+        Self::from_toml_str(&toml)
+    }
+
+    /// Returns the number of tenants with overrides.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.tenants.len()
+    }
+
+    /// Returns `true` if no tenant has overrides.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.tenants.is_empty()
+    }
+}
+
+impl TenantPolicyStore for StaticTenantPolicyStore {
+    fn overrides(&self, tenant_id: &str) -> Option<TenantOverrides> {
+        self.tenants.get(tenant_id).cloned()
+    }
+}
+
+/// Tenant policy resolved for a request, stored as a context extension.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TenantPolicy {
+    /// The tenant ID.
+    pub tenant_id: String,
+    /// The tenant's overrides, or `None` if it uses the global defaults.
+    pub overrides: Option<TenantOverrides>,
+}
+
+impl TenantPolicy {
+    /// Returns the rate limit override, if any.
+    #[must_use]
+    pub fn rate_limit(&self) -> Option<TenantRateLimit> {
+        self.overrides.as_ref()?.rate_limit
+    }
+
+    /// Returns the body size override, if any.
+    #[must_use]
+    pub fn max_body_bytes(&self) -> Option<u64> {
+        self.overrides.as_ref()?.max_body_bytes
+    }
+
+    /// Returns `true` if the tenant may not call the operation.
+    #[must_use]
+    pub fn denies(&self, operation_id: &str) -> bool {
+        self.overrides
+            .as_ref()
+            .is_some_and(|o| o.denies(operation_id))
+    }
+}
+
+/// Middleware that resolves the tenant and its policy overrides.
+///
+/// Sets [`MiddlewareContext::tenant_id`] and a [`TenantPolicy`] extension.
+/// Requests without a tenant pass through unchanged.
+#[derive(Clone)]
+pub struct TenantPolicyMiddleware {
+    resolver: Arc<dyn TenantResolver>,
+    store: Arc<dyn TenantPolicyStore>,
+}
+
+impl TenantPolicyMiddleware {
+    /// Creates a tenant policy middleware backed by the given store.
+    ///
+    /// Tenants are resolved from the `tenant_id` JWT claim.
+    #[must_use]
+    pub fn new<S: TenantPolicyStore + 'static>(store: S) -> Self {
+        Self {
+            resolver: Arc::new(ClaimTenantResolver::default()),
+            store: Arc::new(store),
+        }
+    }
+
+    /// Reads the tenant ID from a different JWT claim.
+    #[must_use]
+    pub fn claim(mut self, claim: impl Into<String>) -> Self {
+        self.resolver = Arc::new(ClaimTenantResolver::new(claim));
+        self
+    }
+
+    /// Uses a custom tenant resolver.
+    #[must_use]
+    pub fn resolver<R: TenantResolver + 'static>(mut self, resolver: R) -> Self {
+        self.resolver = Arc::new(resolver);
+        self
+    }
+}
+
+impl std::fmt::Debug for TenantPolicyMiddleware {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TenantPolicyMiddleware")
+            .finish_non_exhaustive()
+    }
+}
+
+impl Middleware for TenantPolicyMiddleware {
+    fn name(&self) -> &'static str {
+        "tenant_policy"
+    }
+
+    fn process<'a>(
+        &'a self,
+        ctx: &'a mut MiddlewareContext,
+        request: Request,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Response> {
+        Box::pin(async move {
+            if let Some(tenant_id) = self.resolver.resolve(ctx, &request) {
+                let overrides = self.store.overrides(&tenant_id);
+                ctx.set_tenant_id(tenant_id.clone());
+                ctx.set_extension(TenantPolicy {
+                    tenant_id,
+                    overrides,
+                });
+            }
+
+            next.run(ctx, request).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stages::identity::AUTHORIZATION_HEADER;
+    use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+    use base64::Engine;
+    use bytes::Bytes;
+    use http::{Request as HttpRequest, Response as HttpResponse, StatusCode};
+    use http_body_util::Full;
+
+    /// Builds a request carrying a JWT with the given `tenant_id` claim.
+    fn tenant_request(tenant_id: &str) -> Request {
+        let payload =
+            URL_SAFE_NO_PAD.encode(format!(r#"{{"sub":"alice","tenant_id":"{tenant_id}"}}"#));
+        HttpRequest::builder()
+            .uri("/export")
+            .header(
+                AUTHORIZATION_HEADER,
+                format!("Bearer eyJhbGciOiJIUzI1NiJ9.{payload}.sig"),
+            )
+            .body(Full::new(Bytes::new()))
+            .unwrap()
+    }
+
+    fn ok_handler() -> impl FnOnce(&mut MiddlewareContext, Request) -> BoxFuture<'static, Response>
+    {
+        |_ctx, _req| {
+            Box::pin(async {
+                HttpResponse::builder()
+                    .status(StatusCode::OK)
+                    .body(Full::new(Bytes::new()))
+                    .unwrap()
+            })
+        }
+    }
+
+    const POLICY: &str = r#"
+        [tenants.acme]
+        rate_limit = { rps = 2, burst = 3 }
+        max_body_bytes = 1024
+        denied_operations = ["exportAll"]
+
+        [tenants.globex]
+        max_body_bytes = 2048
+    "#;
+
+    #[test]
+    fn test_store_from_toml() {
+        let store = StaticTenantPolicyStore::from_toml_str(POLICY).unwrap();
+        assert_eq!(store.len(), 2);
+
+        let acme = store.overrides("acme").unwrap();
+        assert_eq!(acme.rate_limit, Some(TenantRateLimit::new(2).with_burst(3)));
+        assert_eq!(acme.rate_limit.unwrap().limit(), 3);
+        assert_eq!(acme.max_body_bytes, Some(1024));
+        assert!(acme.denies("exportAll"));
+
+        let globex = store.overrides("globex").unwrap();
+        assert!(globex.rate_limit.is_none());
+        assert!(!globex.denies("exportAll"));
+
+        assert!(store.overrides("initech").is_none());
+    }
+
+    #[test]
+    fn test_store_rejects_unknown_keys() {
+        let err =
+            StaticTenantPolicyStore::from_toml_str("[tenants.acme]\nmax_rps = 5\n").unwrap_err();
+        assert!(matches!(err, TenantPolicyError::Parse(_)));
+    }
+
+    #[tokio::test]
+    async fn test_resolves_tenant_from_claim() {
+        let store = StaticTenantPolicyStore::from_toml_str(POLICY).unwrap();
+        let middleware = TenantPolicyMiddleware::new(store);
+        let mut ctx = MiddlewareContext::new();
+
+        let next = Next::handler(ok_handler());
+        middleware
+            .process(&mut ctx, tenant_request("acme"), next)
+            .await;
+
+        assert_eq!(ctx.tenant_id(), Some("acme"));
+        let policy = ctx.get_extension::<TenantPolicy>().unwrap();
+        assert_eq!(policy.max_body_bytes(), Some(1024));
+        assert!(policy.denies("exportAll"));
+        assert_eq!(ctx.to_request_context().tenant_id(), Some("acme"));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_tenant_uses_defaults() {
+        let store = StaticTenantPolicyStore::from_toml_str(POLICY).unwrap();
+        let middleware = TenantPolicyMiddleware::new(store);
+        let mut ctx = MiddlewareContext::new();
+
+        let next = Next::handler(ok_handler());
+        middleware
+            .process(&mut ctx, tenant_request("initech"), next)
+            .await;
+
+        let policy = ctx.get_extension::<TenantPolicy>().unwrap();
+        assert_eq!(policy.tenant_id, "initech");
+        assert!(policy.overrides.is_none());
+        assert!(policy.rate_limit().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_custom_resolver() {
+        struct HeaderResolver;
+
+        impl TenantResolver for HeaderResolver {
+            fn resolve(&self, _ctx: &MiddlewareContext, request: &Request) -> Option<String> {
+                request
+                    .headers()
+                    .get("x-tenant")
+                    .and_then(|v| v.to_str().ok())
+                    .map(String::from)
+            }
+        }
+
+        let middleware =
+            TenantPolicyMiddleware::new(StaticTenantPolicyStore::new()).resolver(HeaderResolver);
+        let mut ctx = MiddlewareContext::new();
+        let request = HttpRequest::builder()
+            .uri("/")
+            .header("x-tenant", "globex")
+            .body(Full::new(Bytes::new()))
+            .unwrap();
+
+        let next = Next::handler(ok_handler());
+        middleware.process(&mut ctx, request, next).await;
+        assert_eq!(ctx.tenant_id(), Some("globex"));
+    }
+
+    #[tokio::test]
+    async fn test_no_tenant_passes_through() {
+        let middleware = TenantPolicyMiddleware::new(StaticTenantPolicyStore::new()).claim("org");
+        let mut ctx = MiddlewareContext::new();
+
+        let next = Next::handler(ok_handler());
+        let response = middleware
+            .process(&mut ctx, tenant_request("acme"), next)
+            .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(ctx.tenant_id().is_none());
+        assert!(!ctx.has_extension::<TenantPolicy>());
+    }
+}
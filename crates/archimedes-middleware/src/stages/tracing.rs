@@ -16,10 +16,35 @@
 //! - `http.url` - Request URL
 //! - `http.target` - Request path
 //! - `http.status_code` - Response status (added on completion)
+//!
+//! ## Stage Events
+//!
+//! When [`TracingMiddleware::record_stage_events`] is enabled, every stage
+//! running inside the tracing stage (identity, authorization, validation,
+//! the handler and the post-handler stages) is timed. The timings are
+//! attached to the request span as [`StageEvent`]s so a trace shows where
+//! time went. This is disabled by default to limit overhead in production.
+//!
+//! ## Recorded Fields
+//!
+//! Downstream stages and the handler run inside a
+//! [`span_fields`](archimedes_core::span_fields) scope, so business
+//! context recorded with [`RequestContext::record`] ends up in
+//! [`FinishedSpan::fields`]. The [`SpanFields`] handle is also stored in
+//! the [`MiddlewareContext`] for the telemetry stage.
+//!
+//! Finished spans can be handed to a [`SpanExporter`]; the
+//! [`InMemorySpanExporter`] collects them for tests.
+//!
+//! [`RequestContext::record`]: archimedes_core::RequestContext::record
 
 use crate::context::MiddlewareContext;
 use crate::middleware::{BoxFuture, Middleware, Next};
 use crate::types::{Request, Response};
+use archimedes_core::span_fields::{self, FieldValue, SpanFields};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
 /// The W3C Trace Context header for trace propagation.
@@ -53,6 +78,10 @@ pub const TRACESTATE_HEADER: &str = "tracestate";
 pub struct TracingMiddleware {
     /// The service name for span attributes.
     service_name: String,
+    /// Whether to record per-stage timing events on the request span.
+    stage_events: bool,
+    /// Optional exporter for finished spans.
+    exporter: Option<Arc<dyn SpanExporter>>,
 }
 
 impl TracingMiddleware {
@@ -65,9 +94,25 @@ impl TracingMiddleware {
     pub fn new(service_name: impl Into<String>) -> Self {
         Self {
             service_name: service_name.into(),
+            stage_events: false,
+            exporter: None,
         }
     }
 
+    /// Enables or disables per-stage timing events on the request span.
+    #[must_use]
+    pub fn record_stage_events(mut self, enabled: bool) -> Self {
+        self.stage_events = enabled;
+        self
+    }
+
+    /// Sets the exporter that receives finished request spans.
+    #[must_use]
+    pub fn with_exporter(mut self, exporter: Arc<dyn SpanExporter>) -> Self {
+        self.exporter = Some(exporter);
+        self
+    }
+
     /// Extracts trace context from the `traceparent` header.
     ///
     /// Format: `{version}-{trace-id}-{parent-span-id}-{flags}`
@@ -111,6 +156,8 @@ impl Middleware for TracingMiddleware {
         next: Next<'a>,
     ) -> BoxFuture<'a, Response> {
         Box::pin(async move {
+            let started_at = Instant::now();
+
             // Extract or generate trace context
             let trace_context =
                 self.extract_trace_context(&request)
@@ -133,15 +180,53 @@ impl Middleware for TracingMiddleware {
                 method: request.method().to_string(),
                 path: request.uri().path().to_string(),
                 parent_span_id: trace_context.parent_span_id,
+                stage_events: Vec::new(),
             });
 
+            if self.stage_events {
+                ctx.set_extension(StageTimings::default());
+            }
+
+            let fields = SpanFields::new();
+            ctx.set_extension(fields.clone());
+
             // Process request through remaining middleware
-            let response = next.run(ctx, request).await;
+            let response = span_fields::scope(fields.clone(), next.run(ctx, request)).await;
+
+            let stage_events = ctx
+                .remove_extension::<StageTimings>()
+                .map(StageTimings::into_events)
+                .unwrap_or_default();
+            for event in &stage_events {
+                ::tracing::debug!(
+                    trace_id = %trace_context.trace_id,
+                    span_id = %span_id,
+                    stage = event.stage,
+                    duration_ms = event.duration.as_secs_f64() * 1000.0,
+                    "middleware stage completed"
+                );
+            }
+
+            if let Some(mut span_info) = ctx.remove_extension::<SpanInfo>() {
+                span_info.stage_events = stage_events;
+
+                if let Some(exporter) = &self.exporter {
+                    exporter.export(FinishedSpan {
+                        trace_id: trace_context.trace_id.clone(),
+                        span_id: span_id.clone(),
+                        parent_span_id: span_info.parent_span_id.clone(),
+                        service_name: span_info.service_name.clone(),
+                        method: span_info.method.clone(),
+                        path: span_info.path.clone(),
+                        status_code: response.status().as_u16(),
+                        duration: started_at.elapsed(),
+                        events: span_info.stage_events.clone(),
+                        fields: fields.snapshot(),
+                    });
+                }
 
-            // Note: In a real implementation, we would:
-            // 1. Complete the span with status code
-            // 2. Export the span to the tracing backend
-            // For now, we just return the response
+                ctx.set_extension(span_info);
+            }
 
             response
         })
@@ -230,6 +315,137 @@ pub struct SpanInfo {
     pub path: String,
     /// The parent span ID (if propagated).
     pub parent_span_id: Option<String>,
+    /// Per-stage timing events (empty unless stage events are enabled).
+    pub stage_events: Vec<StageEvent>,
+}
+
+/// Timing of a single pipeline stage, recorded as a span event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StageEvent {
+    /// The stage name (e.g. `identity`, `authorization`, `handler`).
+    pub stage: &'static str,
+    /// Time spent in the stage itself, excluding downstream stages.
+    pub duration: Duration,
+    /// Time spent in the stage including downstream stages.
+    pub total: Duration,
+}
+
+/// Collector for per-stage timings, stored as a context extension.
+///
+/// Its presence in the [`MiddlewareContext`] enables timing in
+/// [`Next::run`]. Stages finish innermost-first, so the most recently
+/// recorded entry is always the stage directly downstream of the one
+/// being recorded.
+#[derive(Debug, Clone, Default)]
+pub struct StageTimings {
+    events: Vec<StageEvent>,
+}
+
+impl StageTimings {
+    /// Records a stage that took `total` including downstream stages.
+    pub fn record(&mut self, stage: &'static str, total: Duration) {
+        let downstream = self.events.last().map_or(Duration::ZERO, |e| e.total);
+        self.events.push(StageEvent {
+            stage,
+            duration: total.saturating_sub(downstream),
+            total,
+        });
+    }
+
+    /// Returns the recorded events in pipeline order.
+    #[must_use]
+    pub fn into_events(mut self) -> Vec<StageEvent> {
+        self.events.reverse();
+        self.events
+    }
+}
+
+/// A completed request span.
+#[derive(Debug, Clone)]
+pub struct FinishedSpan {
+    /// The trace ID.
+    pub trace_id: String,
+    /// The span ID of the request span.
+    pub span_id: String,
+    /// The parent span ID (if propagated).
+    pub parent_span_id: Option<String>,
+    /// The service name.
+    pub service_name: String,
+    /// The HTTP method.
+    pub method: String,
+    /// The request path.
+    pub path: String,
+    /// The response status code.
+    pub status_code: u16,
+    /// Total span duration.
+    pub duration: Duration,
+    /// Per-stage timing events.
+    pub events: Vec<StageEvent>,
+    /// Fields recorded while handling the request, in recording order.
+    pub fields: Vec<(String, FieldValue)>,
+}
+
+impl FinishedSpan {
+    /// Returns the event for the given stage, if recorded.
+    #[must_use]
+    pub fn event(&self, stage: &str) -> Option<&StageEvent> {
+        self.events.iter().find(|e| e.stage == stage)
+    }
+
+    /// Returns the value of a recorded field.
+    #[must_use]
+    pub fn field(&self, key: &str) -> Option<&FieldValue> {
+        self.fields
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value)
+    }
+}
+
+/// Receives finished request spans.
+pub trait SpanExporter: fmt::Debug + Send + Sync + 'static {
+    /// Exports a finished span.
+    fn export(&self, span: FinishedSpan);
+}
+
+/// A [`SpanExporter`] that keeps spans in memory, for tests.
+#[derive(Debug, Clone, Default)]
+pub struct InMemorySpanExporter {
+    spans: Arc<Mutex<Vec<FinishedSpan>>>,
+}
+
+impl InMemorySpanExporter {
+    /// Creates an empty exporter.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns all exported spans.
+    #[must_use]
+    pub fn spans(&self) -> Vec<FinishedSpan> {
+        self.spans
+            .lock()
+            .expect("span exporter lock poisoned")
+            .clone()
+    }
+
+    /// Removes all exported spans.
+    pub fn clear(&self) {
+        self.spans
+            .lock()
+            .expect("span exporter lock poisoned")
+            .clear();
+    }
+}
+
+impl SpanExporter for InMemorySpanExporter {
+    fn export(&self, span: FinishedSpan) {
+        self.spans
+            .lock()
+            .expect("span exporter lock poisoned")
+            .push(span);
+    }
 }
 
 #[cfg(test)]
@@ -358,6 +574,89 @@ mod tests {
         assert!(TraceFlags(0x03).is_sampled());
     }
 
+    #[tokio::test]
+    async fn test_stage_events_disabled_by_default() {
+        let exporter = Arc::new(InMemorySpanExporter::new());
+        let middleware = TracingMiddleware::new("test-service").with_exporter(exporter.clone());
+        let mut ctx = MiddlewareContext::new();
+
+        let next = Next::handler(create_handler());
+        let _response = middleware
+            .process(&mut ctx, create_test_request(), next)
+            .await;
+
+        let spans = exporter.spans();
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].status_code, 200);
+        assert!(spans[0].events.is_empty());
+        assert!(!ctx.has_extension::<StageTimings>());
+    }
+
+    #[tokio::test]
+    async fn test_stage_events_record_handler() {
+        let middleware = TracingMiddleware::new("test-service").record_stage_events(true);
+        let mut ctx = MiddlewareContext::new();
+
+        let next = Next::handler(create_handler());
+        let _response = middleware
+            .process(&mut ctx, create_test_request(), next)
+            .await;
+
+        let span_info = ctx.get_extension::<SpanInfo>().unwrap();
+        assert_eq!(span_info.stage_events.len(), 1);
+        assert_eq!(span_info.stage_events[0].stage, "handler");
+    }
+
+    #[tokio::test]
+    async fn test_recorded_fields_exported_with_span() {
+        let exporter = Arc::new(InMemorySpanExporter::new());
+        let middleware = TracingMiddleware::new("test-service").with_exporter(exporter.clone());
+        let mut ctx = MiddlewareContext::new();
+
+        let next = Next::handler(|ctx, _req| {
+            let request_ctx = ctx.to_request_context();
+            request_ctx.record("tenant_id", "acme");
+            request_ctx.record("order_id", 42);
+            request_ctx.record("express", true);
+            Box::pin(async {
+                HttpResponse::builder()
+                    .status(StatusCode::OK)
+                    .body(Full::new(Bytes::new()))
+                    .unwrap()
+            }) as BoxFuture<'static, Response>
+        });
+        let _response = middleware
+            .process(&mut ctx, create_test_request(), next)
+            .await;
+
+        let spans = exporter.spans();
+        assert_eq!(
+            spans[0].field("tenant_id"),
+            Some(&FieldValue::Str("acme".to_string()))
+        );
+        assert_eq!(spans[0].field("order_id"), Some(&FieldValue::Int(42)));
+        assert_eq!(spans[0].field("express"), Some(&FieldValue::Bool(true)));
+        assert_eq!(
+            ctx.get_extension::<SpanFields>().unwrap().snapshot(),
+            spans[0].fields
+        );
+    }
+
+    #[test]
+    fn test_stage_timings_self_time() {
+        let mut timings = StageTimings::default();
+        // Innermost first: handler, then the stage wrapping it
+        timings.record("handler", Duration::from_millis(30));
+        timings.record("identity", Duration::from_millis(35));
+
+        let events = timings.into_events();
+        assert_eq!(events[0].stage, "identity");
+        assert_eq!(events[0].duration, Duration::from_millis(5));
+        assert_eq!(events[0].total, Duration::from_millis(35));
+        assert_eq!(events[1].stage, "handler");
+        assert_eq!(events[1].duration, Duration::from_millis(30));
+    }
+
     #[test]
     fn test_middleware_name() {
         let middleware = TracingMiddleware::new("test");
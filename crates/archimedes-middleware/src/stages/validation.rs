@@ -1,8 +1,9 @@
 //! Request and response validation middleware.
 //!
 //! This middleware validates incoming requests and outgoing responses against
-//! contract schemas. In the mock implementation, it uses simple schema definitions.
-//! In production, this will integrate with Themis contract artifacts.
+//! contract schemas. The schemas are checked by a [`RequestValidator`] and a
+//! [`ResponseValidator`]; with the `sentinel` feature, `Sentinel` implements
+//! both from a Themis contract artifact.
 //!
 //! # Pipeline Position
 //!
@@ -18,55 +19,269 @@
 //! Handler → [ResponseValidation] → Telemetry → ErrorNormalization → Response
 //! ```
 //!
-//! # Mock Implementation
+//! # Validators
 //!
-//! The mock validation middleware supports:
+//! - `sentinel`: contract validation via archimedes-sentinel (requires the
+//!   `sentinel` feature)
+//! - `new`: any [`RequestValidator`] or [`ResponseValidator`]
+//! - `allow_all` / `reject_all`: development and testing
 //!
-//! - Allow-all mode (for development/testing)
-//! - Reject-all mode (for testing validation errors)
-//! - Operation-based schemas with field validation
-//! - Required field checking
-//! - Type validation (string, integer, boolean, array, object)
+//! The [`MockSchema`] validators built with `with_schemas` are deprecated.
+//! They only check required fields and primitive types, so they pass
+//! requests the contract rejects. Tests that need a contract can describe
+//! it with `archimedes_core::contract::Contract` and convert it with
+//! `archimedes_sentinel::fixtures::from_contract`.
 //!
 //! # Example
 //!
 //! ```rust,ignore
-//! use archimedes_middleware::stages::ValidationMiddleware;
+//! use std::sync::Arc;
 //!
-//! // Allow all requests (development mode)
-//! let allow_all = ValidationMiddleware::allow_all();
+//! use archimedes_middleware::stages::{ResponseValidationMiddleware, ValidationMiddleware};
+//! use archimedes_sentinel::{ArtifactLoader, Sentinel};
 //!
-//! // Reject all requests (testing)
-//! let reject_all = ValidationMiddleware::reject_all();
+//! let artifact = ArtifactLoader::from_file("contract.artifact.json").await?;
+//! let sentinel = Arc::new(Sentinel::with_defaults(artifact));
 //!
-//! // Schema-based validation
-//! let schema_based = ValidationMiddleware::with_schemas()
-//!     .add_request_schema("createUser", user_schema())
-//!     .add_response_schema("createUser", user_response_schema())
-//!     .build();
+//! let requests = ValidationMiddleware::new(Arc::clone(&sentinel));
+//! let responses = ResponseValidationMiddleware::new(sentinel).enforce(true);
 //! ```
 //!
-//! # Production Integration
+//! # Content Types
 //!
-//! In production, this middleware will:
+//! Responses are validated against the schema their operation declares
+//! for the response's status and `Content-Type`, so an operation can
+//! answer `application/json` and `text/csv` with different schemas.
+//! Responses without a `Content-Type` are treated as JSON. A Sentinel
+//! validates bodies of other media types as a string; other validators
+//! only validate JSON bodies unless they implement
+//! [`ResponseValidator::validate_response_content`].
 //!
-//! 1. Load schemas from Themis contract artifacts
-//! 2. Validate request bodies against operation request schemas
-//! 3. Validate response bodies against operation response schemas
-//! 4. Return structured validation errors on failure
+//! # Warm-up
+//!
+//! [`RequestValidator::warm_up`] exercises the validation path of an
+//! operation before it serves traffic, so the first request does not pay
+//! for building its validation state. By default it validates the body
+//! returned by [`RequestValidator::example_body`], if any. A Sentinel
+//! builds the operation's validation state and validates an example
+//! generated from the request schema.
+//!
+//! # Operation Resolution
+//!
+//! With a Sentinel, the request is resolved against the contract once,
+//! before it is validated. The `OperationResolution` is kept on the context
+//! (see `MiddlewareContext::operation_resolution`) along with the operation
+//! ID and route pattern, so later stages read it instead of resolving again.
+//! When the contract serves several versions, the version is selected first;
+//! an unsupported version is answered with `406 Not Acceptable`.
+//!
+//! # Error Details
+//!
+//! A rejected request gets an error envelope whose code and message come
+//! from the first error, and whose `details` list every error:
+//!
+//! ```json
+//! {
+//!   "error": {
+//!     "code": "FIELD_REQUIRED",
+//!     "message": "Missing required field: email",
+//!     "details": {
+//!       "issues": [
+//!         { "field": "email", "message": "Missing required field: email", "code": "FIELD_REQUIRED" }
+//!       ]
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! # Unknown Fields
+//!
+//! With a Sentinel, fields a body carries but its schema does not declare
+//! are handled as the Sentinel's `ValidationConfig` says:
+//!
+//! - Requests: `Reject` answers `400` with code `UNKNOWN_FIELDS` listing the
+//!   field paths, `Strip` removes them from the body the handler sees.
+//! - Responses: `Reject` fails validation, `LogOnly` counts them in
+//!   [`RESPONSE_UNKNOWN_FIELDS`] by operation and field. Only the first
+//!   [`max_unknown_field_labels`](ResponseValidationMiddleware::max_unknown_field_labels)
+//!   distinct fields get their own label; later ones share
+//!   [`OTHER_FIELD_LABEL`].
 
 use crate::{
-    context::MiddlewareContext,
+    context::{ContractVersion, MiddlewareContext, RouteOptions},
     middleware::{BoxFuture, Middleware, Next},
     types::{Request, Response, ResponseExt},
 };
-use http::StatusCode;
+use archimedes_core::timing;
+use bytes::Bytes;
+use http::{HeaderMap, StatusCode};
+use http_body_util::{BodyExt, Full};
 use serde_json::Value;
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
 
 #[cfg(feature = "sentinel")]
-use archimedes_sentinel::Sentinel;
+use archimedes_sentinel::{RequestUnknownFields, ResponseUnknownFields, Sentinel, SentinelError};
+
+/// Unknown response fields, by operation and field.
+pub const RESPONSE_UNKNOWN_FIELDS: &str = "archimedes_response_unknown_fields_total";
+
+/// Default number of distinct unknown response fields given their own label.
+pub const DEFAULT_MAX_UNKNOWN_FIELD_LABELS: usize = 100;
+
+/// Label used for unknown fields beyond the distinct field limit.
+pub const OTHER_FIELD_LABEL: &str = "other";
+
+/// Validates requests against the schemas of their operation.
+pub trait RequestValidator: Send + Sync {
+    /// Resolves the request before it is validated, recording the operation
+    /// and contract version on the context.
+    ///
+    /// Returns the response to send instead when the request cannot be
+    /// served, such as one asking for an unsupported contract version. Does
+    /// nothing by default.
+    fn resolve(&self, ctx: &mut MiddlewareContext, request: &Request) -> Result<(), Response> {
+        let _ = (ctx, request);
+        Ok(())
+    }
+
+    /// Validates the headers and body of a request for an operation.
+    fn validate_request(
+        &self,
+        operation_id: &str,
+        version: Option<&str>,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> ValidationResult;
+
+    /// Rewrites a request that passed validation before the handler sees
+    /// it. Returns the request unchanged by default.
+    fn rewrite_request(
+        &self,
+        operation_id: &str,
+        version: Option<&str>,
+        request: Request,
+    ) -> Request {
+        let _ = (operation_id, version);
+        request
+    }
+
+    /// Returns an example request body for an operation, validated during
+    /// warm-up. Returns `None` by default.
+    fn example_body(&self, operation_id: &str, version: Option<&str>) -> Option<Vec<u8>> {
+        let _ = (operation_id, version);
+        None
+    }
+
+    /// Exercises the validation path of an operation before it serves
+    /// traffic.
+    ///
+    /// By default validates the operation's
+    /// [`example_body`](Self::example_body), if it has one; the result is
+    /// ignored.
+    fn warm_up(&self, operation_id: &str, version: Option<&str>) {
+        if let Some(body) = self.example_body(operation_id, version) {
+            let _ = self.validate_request(operation_id, version, &HeaderMap::new(), &body);
+        }
+    }
+}
+
+/// Validates handler responses against the schemas of their operation.
+pub trait ResponseValidator: Send + Sync {
+    /// Validates the body of a response for an operation and status code.
+    ///
+    /// Fields listed in the result's `unknown_fields` are counted in
+    /// [`RESPONSE_UNKNOWN_FIELDS`].
+    fn validate_response(
+        &self,
+        operation_id: &str,
+        version: Option<&str>,
+        status_code: u16,
+        body: &[u8],
+    ) -> ValidationResult;
+
+    /// Validates the body of a response for an operation and status code
+    /// against the schema for its `Content-Type`.
+    ///
+    /// By default JSON bodies are validated with
+    /// [`validate_response`](Self::validate_response) and others pass.
+    fn validate_response_content(
+        &self,
+        operation_id: &str,
+        version: Option<&str>,
+        status_code: u16,
+        content_type: &str,
+        body: &[u8],
+    ) -> ValidationResult {
+        if is_json(content_type) {
+            self.validate_response(operation_id, version, status_code, body)
+        } else {
+            ValidationResult::success()
+        }
+    }
+}
+
+/// Returns `true` for JSON media types, such as `application/json` and
+/// `application/problem+json`.
+fn is_json(content_type: &str) -> bool {
+    content_type.contains("json")
+}
+
+impl<V: RequestValidator + ?Sized> RequestValidator for Arc<V> {
+    fn resolve(&self, ctx: &mut MiddlewareContext, request: &Request) -> Result<(), Response> {
+        (**self).resolve(ctx, request)
+    }
+
+    fn validate_request(
+        &self,
+        operation_id: &str,
+        version: Option<&str>,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> ValidationResult {
+        (**self).validate_request(operation_id, version, headers, body)
+    }
+
+    fn rewrite_request(
+        &self,
+        operation_id: &str,
+        version: Option<&str>,
+        request: Request,
+    ) -> Request {
+        (**self).rewrite_request(operation_id, version, request)
+    }
+
+    fn example_body(&self, operation_id: &str, version: Option<&str>) -> Option<Vec<u8>> {
+        (**self).example_body(operation_id, version)
+    }
+
+    fn warm_up(&self, operation_id: &str, version: Option<&str>) {
+        (**self).warm_up(operation_id, version);
+    }
+}
+
+impl<V: ResponseValidator + ?Sized> ResponseValidator for Arc<V> {
+    fn validate_response(
+        &self,
+        operation_id: &str,
+        version: Option<&str>,
+        status_code: u16,
+        body: &[u8],
+    ) -> ValidationResult {
+        (**self).validate_response(operation_id, version, status_code, body)
+    }
+
+    fn validate_response_content(
+        &self,
+        operation_id: &str,
+        version: Option<&str>,
+        status_code: u16,
+        content_type: &str,
+        body: &[u8],
+    ) -> ValidationResult {
+        (**self).validate_response_content(operation_id, version, status_code, content_type, body)
+    }
+}
 
 /// Request validation middleware that validates against contract schemas.
 ///
@@ -74,12 +289,11 @@ use archimedes_sentinel::Sentinel;
 ///
 /// - **`AllowAll`**: Allow all requests (development mode)
 /// - **`RejectAll`**: Reject all requests (testing)
-/// - **Schema**: Mock schema-based validation
-/// - **Sentinel**: Real contract validation via archimedes-sentinel (requires `sentinel` feature)
+/// - **Validator**: A [`RequestValidator`], such as a Sentinel
 #[derive(Clone)]
 pub struct ValidationMiddleware {
     /// The validation mode.
-    mode: ValidationMode,
+    mode: ValidationMode<dyn RequestValidator>,
 }
 
 impl std::fmt::Debug for ValidationMiddleware {
@@ -94,9 +308,13 @@ impl std::fmt::Debug for ValidationMiddleware {
 #[derive(Clone)]
 pub struct ResponseValidationMiddleware {
     /// The validation mode.
-    mode: ValidationMode,
+    mode: ValidationMode<dyn ResponseValidator>,
     /// Whether to enforce validation or just log.
     enforce: bool,
+    /// Maximum number of distinct unknown field labels.
+    max_unknown_field_labels: usize,
+    /// Operation and field pairs given their own label so far.
+    seen_unknown_fields: Arc<Mutex<HashSet<(String, String)>>>,
 }
 
 impl std::fmt::Debug for ResponseValidationMiddleware {
@@ -104,37 +322,42 @@ impl std::fmt::Debug for ResponseValidationMiddleware {
         f.debug_struct("ResponseValidationMiddleware")
             .field("mode", &self.mode.name())
             .field("enforce", &self.enforce)
+            .field("max_unknown_field_labels", &self.max_unknown_field_labels)
             .finish()
     }
 }
 
 /// Validation mode configuration.
-#[derive(Clone)]
-enum ValidationMode {
+enum ValidationMode<V: ?Sized> {
     /// Allow all requests/responses (development mode).
     AllowAll,
     /// Reject all requests/responses (testing).
     RejectAll,
-    /// Schema-based validation.
-    Schema(Arc<SchemaConfig>),
-    /// Sentinel contract validation (requires `sentinel` feature).
-    #[cfg(feature = "sentinel")]
-    Sentinel(Arc<Sentinel>),
+    /// Validation by a request or response validator.
+    Validator(Arc<V>),
+}
+
+impl<V: ?Sized> Clone for ValidationMode<V> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::AllowAll => Self::AllowAll,
+            Self::RejectAll => Self::RejectAll,
+            Self::Validator(validator) => Self::Validator(Arc::clone(validator)),
+        }
+    }
 }
 
-impl ValidationMode {
+impl<V: ?Sized> ValidationMode<V> {
     fn name(&self) -> &'static str {
         match self {
             Self::AllowAll => "allow_all",
             Self::RejectAll => "reject_all",
-            Self::Schema(_) => "schema",
-            #[cfg(feature = "sentinel")]
-            Self::Sentinel(_) => "sentinel",
+            Self::Validator(_) => "validator",
         }
     }
 }
 
-/// Schema configuration for validation.
+/// Mock schemas by operation ID.
 #[derive(Debug, Default)]
 struct SchemaConfig {
     /// Request schemas by operation ID.
@@ -143,10 +366,45 @@ struct SchemaConfig {
     response_schemas: HashMap<String, MockSchema>,
 }
 
+impl RequestValidator for SchemaConfig {
+    fn validate_request(
+        &self,
+        operation_id: &str,
+        _version: Option<&str>,
+        _headers: &HeaderMap,
+        body: &[u8],
+    ) -> ValidationResult {
+        // Operations without a schema are allowed
+        self.request_schemas
+            .get(operation_id)
+            .map_or_else(ValidationResult::success, |schema| {
+                ValidationMiddleware::validate_body(body, schema)
+            })
+    }
+}
+
+impl ResponseValidator for SchemaConfig {
+    fn validate_response(
+        &self,
+        operation_id: &str,
+        _version: Option<&str>,
+        _status_code: u16,
+        body: &[u8],
+    ) -> ValidationResult {
+        // Operations without a schema are allowed
+        self.response_schemas
+            .get(operation_id)
+            .map_or_else(ValidationResult::success, |schema| {
+                ValidationMiddleware::validate_body(body, schema)
+            })
+    }
+}
+
 /// A mock schema for validation.
 ///
-/// This is a simplified schema that supports basic validation.
-/// Production will use JSON Schema from Themis contracts.
+/// This is a simplified schema that only checks required fields and
+/// primitive types. Use a Sentinel for contract validation; mock schemas
+/// remain for tests.
 #[derive(Debug, Clone)]
 pub struct MockSchema {
     /// Required fields for the schema.
@@ -183,10 +441,42 @@ pub struct ValidationResult {
     pub valid: bool,
     /// Validation errors if any.
     pub errors: Vec<ValidationError>,
+    /// Fields the body carries that its schema does not declare, reported
+    /// for counting rather than as errors.
+    pub unknown_fields: Vec<String>,
+}
+
+impl ValidationResult {
+    /// Returns a passing result.
+    #[must_use]
+    pub fn success() -> Self {
+        Self {
+            valid: true,
+            errors: Vec::new(),
+            unknown_fields: Vec::new(),
+        }
+    }
+
+    /// Returns a result that fails with `errors`.
+    #[must_use]
+    pub fn failure(errors: Vec<ValidationError>) -> Self {
+        Self {
+            valid: false,
+            errors,
+            unknown_fields: Vec::new(),
+        }
+    }
+
+    /// Returns the errors as error envelope details:
+    /// `{"issues": [{"field", "message", "code"}]}`.
+    #[must_use]
+    pub fn details(&self) -> Value {
+        serde_json::json!({ "issues": self.errors })
+    }
 }
 
 /// A single validation error.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct ValidationError {
     /// The field path that failed validation.
     pub field: String,
@@ -196,11 +486,30 @@ pub struct ValidationError {
     pub code: String,
 }
 
+impl ValidationError {
+    /// Creates an error that is not tied to a field.
+    fn body(message: String, code: &str) -> Self {
+        Self {
+            field: String::new(),
+            message,
+            code: code.to_string(),
+        }
+    }
+}
+
 // ============================================================================
 // ValidationMiddleware Implementation
 // ============================================================================
 
 impl ValidationMiddleware {
+    /// Creates a validation middleware that checks requests with `validator`.
+    #[must_use]
+    pub fn new<V: RequestValidator + 'static>(validator: V) -> Self {
+        Self {
+            mode: ValidationMode::Validator(Arc::new(validator)),
+        }
+    }
+
     /// Creates a new validation middleware that allows all requests.
     ///
     /// Use this for development or when validation is handled elsewhere.
@@ -222,6 +531,9 @@ impl ValidationMiddleware {
     }
 
     /// Creates a new schema-based validation middleware builder.
+    #[deprecated(
+        note = "mock schemas diverge from contract validation; use `ValidationMiddleware::sentinel`"
+    )]
     #[must_use]
     pub fn with_schemas() -> ValidationBuilder {
         ValidationBuilder::default()
@@ -229,7 +541,9 @@ impl ValidationMiddleware {
 
     /// Creates a new validation middleware using Themis contract artifacts.
     ///
-    /// This requires the `sentinel` feature to be enabled.
+    /// This requires the `sentinel` feature to be enabled. To share the
+    /// Sentinel with response validation, pass an `Arc<Sentinel>` to
+    /// [`ValidationMiddleware::new`] instead.
     ///
     /// # Arguments
     ///
@@ -248,103 +562,25 @@ impl ValidationMiddleware {
     #[cfg(feature = "sentinel")]
     #[must_use]
     pub fn sentinel(sentinel: Sentinel) -> Self {
-        Self {
-            mode: ValidationMode::Sentinel(Arc::new(sentinel)),
-        }
+        Self::new(sentinel)
     }
 
-    /// Validates the request body against the operation schema.
-    fn validate_request(&self, operation_id: &str, body: &[u8]) -> ValidationResult {
-        match &self.mode {
-            ValidationMode::AllowAll => ValidationResult {
-                valid: true,
-                errors: vec![],
-            },
-            ValidationMode::RejectAll => ValidationResult {
-                valid: false,
-                errors: vec![ValidationError {
-                    field: "".to_string(),
-                    message: "Validation rejected (reject-all mode)".to_string(),
-                    code: "VALIDATION_REJECTED".to_string(),
-                }],
-            },
-            ValidationMode::Schema(config) => {
-                if let Some(schema) = config.request_schemas.get(operation_id) {
-                    Self::validate_body(body, schema)
-                } else {
-                    // No schema defined, allow by default
-                    ValidationResult {
-                        valid: true,
-                        errors: vec![],
-                    }
-                }
-            }
-            #[cfg(feature = "sentinel")]
-            ValidationMode::Sentinel(sentinel) => {
-                Self::validate_with_sentinel(sentinel, operation_id, body)
-            }
-        }
-    }
-
-    /// Validates request body using Sentinel.
-    #[cfg(feature = "sentinel")]
-    fn validate_with_sentinel(
-        sentinel: &Sentinel,
+    /// Validates the request headers and body against the operation schema.
+    fn validate_request(
+        &self,
         operation_id: &str,
+        version: Option<&str>,
+        headers: &HeaderMap,
         body: &[u8],
     ) -> ValidationResult {
-        // Parse body as JSON
-        let json_body: serde_json::Value = if body.is_empty() {
-            serde_json::Value::Null
-        } else {
-            match serde_json::from_slice(body) {
-                Ok(v) => v,
-                Err(e) => {
-                    return ValidationResult {
-                        valid: false,
-                        errors: vec![ValidationError {
-                            field: "".to_string(),
-                            message: format!("Invalid JSON: {e}"),
-                            code: "INVALID_JSON".to_string(),
-                        }],
-                    };
-                }
-            }
-        };
-
-        // Validate using sentinel
-        match sentinel.validate_request(operation_id, &json_body) {
-            Ok(result) => {
-                if result.valid {
-                    ValidationResult {
-                        valid: true,
-                        errors: vec![],
-                    }
-                } else {
-                    ValidationResult {
-                        valid: false,
-                        errors: result
-                            .errors
-                            .into_iter()
-                            .map(|e| ValidationError {
-                                field: e.path,
-                                message: e.message,
-                                code: "SCHEMA_VALIDATION_ERROR".to_string(),
-                            })
-                            .collect(),
-                    }
-                }
-            }
-            Err(e) => {
-                tracing::error!(error = %e, "Sentinel validation error");
-                ValidationResult {
-                    valid: false,
-                    errors: vec![ValidationError {
-                        field: "".to_string(),
-                        message: format!("Validation error: {e}"),
-                        code: "VALIDATION_ERROR".to_string(),
-                    }],
-                }
+        match &self.mode {
+            ValidationMode::AllowAll => ValidationResult::success(),
+            ValidationMode::RejectAll => ValidationResult::failure(vec![ValidationError::body(
+                "Validation rejected (reject-all mode)".to_string(),
+                "VALIDATION_REJECTED",
+            )]),
+            ValidationMode::Validator(validator) => {
+                validator.validate_request(operation_id, version, headers, body)
             }
         }
     }
@@ -354,33 +590,22 @@ impl ValidationMiddleware {
         // Empty body handling
         if body.is_empty() {
             if schema.required_fields.is_empty() {
-                return ValidationResult {
-                    valid: true,
-                    errors: vec![],
-                };
+                return ValidationResult::success();
             }
-            return ValidationResult {
-                valid: false,
-                errors: vec![ValidationError {
-                    field: "".to_string(),
-                    message: "Request body is required".to_string(),
-                    code: "BODY_REQUIRED".to_string(),
-                }],
-            };
+            return ValidationResult::failure(vec![ValidationError::body(
+                "Request body is required".to_string(),
+                "BODY_REQUIRED",
+            )]);
         }
 
         // Parse JSON
         let value: Value = match serde_json::from_slice(body) {
             Ok(v) => v,
             Err(e) => {
-                return ValidationResult {
-                    valid: false,
-                    errors: vec![ValidationError {
-                        field: "".to_string(),
-                        message: format!("Invalid JSON: {e}"),
-                        code: "INVALID_JSON".to_string(),
-                    }],
-                };
+                return ValidationResult::failure(vec![ValidationError::body(
+                    format!("Invalid JSON: {e}"),
+                    "INVALID_JSON",
+                )]);
             }
         };
 
@@ -388,14 +613,10 @@ impl ValidationMiddleware {
         let obj = match value.as_object() {
             Some(o) => o,
             None => {
-                return ValidationResult {
-                    valid: false,
-                    errors: vec![ValidationError {
-                        field: "".to_string(),
-                        message: "Request body must be an object".to_string(),
-                        code: "BODY_NOT_OBJECT".to_string(),
-                    }],
-                };
+                return ValidationResult::failure(vec![ValidationError::body(
+                    "Request body must be an object".to_string(),
+                    "BODY_NOT_OBJECT",
+                )]);
             }
         };
 
@@ -433,9 +654,10 @@ impl ValidationMiddleware {
             }
         }
 
-        ValidationResult {
-            valid: errors.is_empty(),
-            errors,
+        if errors.is_empty() {
+            ValidationResult::success()
+        } else {
+            ValidationResult::failure(errors)
         }
     }
 
@@ -465,18 +687,33 @@ impl Middleware for ValidationMiddleware {
         next: Next<'a>,
     ) -> BoxFuture<'a, Response> {
         Box::pin(async move {
+            if let ValidationMode::Validator(validator) = &self.mode {
+                if let Err(response) = validator.resolve(ctx, &request) {
+                    return response;
+                }
+            }
+
+            if ctx
+                .get_extension::<RouteOptions>()
+                .is_some_and(|o| o.skip_request_validation)
+            {
+                return next.run(ctx, request).await;
+            }
+
             let operation_id = ctx.operation_id().unwrap_or("unknown").to_string();
+            let version = ctx.get_extension::<ContractVersion>().map(|v| v.0.clone());
 
-            // Get request body for validation
-            // In a real implementation, we'd read and buffer the body
-            // For mock, we'll use an empty body check or stored body
+            // The body is buffered, so validate the stored copy if an
+            // earlier stage kept one and otherwise read it directly
+            let request = buffer_body(request).await;
             let body = request
                 .extensions()
                 .get::<RequestBody>()
-                .map(|b| b.0.as_slice())
-                .unwrap_or(&[]);
+                .map_or(&[][..], |b| b.0.as_slice());
 
-            let result = self.validate_request(&operation_id, body);
+            let result = timing::time(timing::VALIDATION, || {
+                self.validate_request(&operation_id, version.as_deref(), request.headers(), body)
+            });
 
             // Store validation result in context
             ctx.set_extension(result.clone());
@@ -491,26 +728,394 @@ impl Middleware for ValidationMiddleware {
                     .map(|e| e.message.as_str())
                     .unwrap_or("Request validation failed");
 
-                return Response::json_error(StatusCode::BAD_REQUEST, code, message);
+                return Response::json_error_with_details(
+                    StatusCode::BAD_REQUEST,
+                    code,
+                    message,
+                    result.details(),
+                );
             }
 
+            let request = match &self.mode {
+                ValidationMode::Validator(validator) => {
+                    validator.rewrite_request(&operation_id, version.as_deref(), request)
+                }
+                _ => request,
+            };
+
             // Continue to next middleware/handler
             next.run(ctx, request).await
         })
     }
 }
 
+/// Stores the request body as a [`RequestBody`] extension, unless an
+/// earlier stage already did.
+async fn buffer_body(request: Request) -> Request {
+    if request.extensions().get::<RequestBody>().is_some() {
+        return request;
+    }
+    let (mut parts, body) = request.into_parts();
+    let body = body
+        .collect()
+        .await
+        .map_or_else(|never| match never {}, |collected| collected.to_bytes());
+    parts.extensions.insert(RequestBody(body.to_vec()));
+    Request::from_parts(parts, Full::new(body))
+}
+
+// ============================================================================
+// Sentinel Validation
+// ============================================================================
+
+#[cfg(feature = "sentinel")]
+impl RequestValidator for Sentinel {
+    /// Selects the contract version when the sentinel serves several, then
+    /// resolves the operation once and records the resolution on the
+    /// context.
+    ///
+    /// The operation is resolved against the selected version (the same
+    /// path may map to different operations across versions). Unsupported
+    /// versions yield a `406 Not Acceptable` response listing the supported
+    /// versions.
+    fn resolve(&self, ctx: &mut MiddlewareContext, request: &Request) -> Result<(), Response> {
+        let version = if self.versions().len() < 2 {
+            None
+        } else {
+            let selection = self
+                .select_version(request.headers(), request.uri().path())
+                .map_err(|e| match e {
+                    SentinelError::UnsupportedVersion {
+                        requested,
+                        supported,
+                    } => Response::json_error(
+                        StatusCode::NOT_ACCEPTABLE,
+                        "UNSUPPORTED_VERSION",
+                        &format!(
+                            "Contract version '{requested}' is not supported; supported versions: {}",
+                            supported.join(", ")
+                        ),
+                    ),
+                    other => Response::json_error(
+                        StatusCode::BAD_REQUEST,
+                        "VERSION_SELECTION_FAILED",
+                        &other.to_string(),
+                    ),
+                })?;
+            Some(selection.version)
+        };
+
+        let resolved = ctx.operation_resolution().is_some_and(|resolution| {
+            version
+                .as_ref()
+                .map_or(true, |version| &resolution.version == version)
+        });
+        if !resolved {
+            if let Ok(resolution) = self.resolve_with_headers(
+                request.method().as_str(),
+                request.uri().path(),
+                request.headers(),
+            ) {
+                ctx.set_operation_resolution(resolution);
+            }
+        }
+        if let Some(version) = version {
+            ctx.set_extension(ContractVersion(version));
+        }
+        Ok(())
+    }
+
+    /// Checks header requirements first, so a request missing a required
+    /// header is rejected before its body is parsed.
+    fn validate_request(
+        &self,
+        operation_id: &str,
+        version: Option<&str>,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> ValidationResult {
+        let result = match version {
+            Some(version) => {
+                self.validate_request_headers_for_version(version, operation_id, headers)
+            }
+            None => self.validate_request_headers(operation_id, headers),
+        };
+        let result = convert_sentinel_result(result, "HEADER_VALIDATION_ERROR");
+        if !result.valid {
+            return result;
+        }
+
+        let json_body = match parse_body(body) {
+            Ok(json_body) => json_body,
+            Err(e) => {
+                return ValidationResult::failure(vec![ValidationError::body(
+                    format!("Invalid JSON: {e}"),
+                    "INVALID_JSON",
+                )]);
+            }
+        };
+        let result = match version {
+            Some(version) => self.validate_request_for_version(version, operation_id, &json_body),
+            None => Sentinel::validate_request(self, operation_id, &json_body),
+        };
+        convert_sentinel_result(result, "SCHEMA_VALIDATION_ERROR")
+    }
+
+    /// Rewrites the request body with string values coerced to the types
+    /// declared by the operation schema and unknown fields stripped, so the
+    /// handler sees the same body that passed validation.
+    ///
+    /// Does nothing unless coercion or stripping is enabled in the Sentinel
+    /// config.
+    fn rewrite_request(
+        &self,
+        operation_id: &str,
+        version: Option<&str>,
+        mut request: Request,
+    ) -> Request {
+        let config = &self.config().validation;
+        if !config.coerce_primitives && config.request_unknown_fields != RequestUnknownFields::Strip
+        {
+            return request;
+        }
+        let Some(mut json_body) = request
+            .extensions()
+            .get::<RequestBody>()
+            .and_then(|body| serde_json::from_slice::<Value>(&body.0).ok())
+        else {
+            return request;
+        };
+
+        let coerced = match version {
+            Some(version) => self.coerce_request_for_version(version, operation_id, &mut json_body),
+            None => self.coerce_request(operation_id, &mut json_body),
+        };
+        let stripped = match version {
+            Some(version) => {
+                self.strip_unknown_fields_for_version(version, operation_id, &mut json_body)
+            }
+            None => self.strip_unknown_fields(operation_id, &mut json_body),
+        };
+        if let Some(stripped) = stripped.as_ref().ok().filter(|s| !s.is_empty()) {
+            tracing::debug!(operation_id, fields = ?stripped, "Stripped unknown request fields");
+        }
+        let changed = [coerced, stripped]
+            .into_iter()
+            .any(|fields| fields.is_ok_and(|fields| !fields.is_empty()));
+        if !changed {
+            return request;
+        }
+
+        let Ok(body) = serde_json::to_vec(&json_body) else {
+            return request;
+        };
+        request.headers_mut().insert(
+            http::header::CONTENT_LENGTH,
+            http::HeaderValue::from(body.len()),
+        );
+        *request.body_mut() = Full::new(Bytes::from(body.clone()));
+        request.extensions_mut().insert(RequestBody(body));
+        request
+    }
+
+    /// Generates an example from the operation's request schema; see
+    /// `archimedes_sentinel::ExampleGenerator`.
+    fn example_body(&self, operation_id: &str, version: Option<&str>) -> Option<Vec<u8>> {
+        let example = match version {
+            Some(version) => self.request_example_for_version(version, operation_id),
+            None => self.request_example(operation_id),
+        }?;
+        serde_json::to_vec(&example).ok()
+    }
+
+    /// Builds the operation's validation state, then validates its example
+    /// body.
+    fn warm_up(&self, operation_id: &str, version: Option<&str>) {
+        match version {
+            Some(version) => self.precompile_operation_for_version(version, operation_id),
+            None => self.precompile_operation(operation_id),
+        };
+        if let Some(body) = RequestValidator::example_body(self, operation_id, version) {
+            let _ = RequestValidator::validate_request(
+                self,
+                operation_id,
+                version,
+                &HeaderMap::new(),
+                &body,
+            );
+        }
+    }
+}
+
+#[cfg(feature = "sentinel")]
+impl ResponseValidator for Sentinel {
+    fn validate_response(
+        &self,
+        operation_id: &str,
+        version: Option<&str>,
+        status_code: u16,
+        body: &[u8],
+    ) -> ValidationResult {
+        ResponseValidator::validate_response_content(
+            self,
+            operation_id,
+            version,
+            status_code,
+            archimedes_sentinel::JSON_MEDIA_TYPE,
+            body,
+        )
+    }
+
+    /// Validates JSON bodies as parsed and other bodies as a string, and
+    /// reports unknown fields for counting when the Sentinel only logs
+    /// them.
+    fn validate_response_content(
+        &self,
+        operation_id: &str,
+        version: Option<&str>,
+        status_code: u16,
+        content_type: &str,
+        body: &[u8],
+    ) -> ValidationResult {
+        let parsed = if is_json(content_type) {
+            parse_body(body)
+        } else {
+            Ok(Value::String(String::from_utf8_lossy(body).into_owned()))
+        };
+        let body = match parsed {
+            Ok(body) => body,
+            Err(e) => {
+                return ValidationResult::failure(vec![ValidationError::body(
+                    format!("Invalid JSON response: {e}"),
+                    "INVALID_JSON",
+                )]);
+            }
+        };
+
+        let version = version.unwrap_or_else(|| self.default_version());
+        let result = self.validate_response_content_for_version(
+            version,
+            operation_id,
+            status_code,
+            content_type,
+            &body,
+        );
+        match result {
+            Ok(result) => {
+                let unknown_fields = if self.config().validation.response_unknown_fields
+                    == ResponseUnknownFields::LogOnly
+                {
+                    result.unknown_fields.clone()
+                } else {
+                    Vec::new()
+                };
+                let mut converted = if result.valid {
+                    ValidationResult::success()
+                } else {
+                    ValidationResult::failure(convert_sentinel_errors(
+                        result,
+                        "RESPONSE_SCHEMA_ERROR",
+                    ))
+                };
+                converted.unknown_fields = unknown_fields;
+                converted
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "Sentinel response validation error");
+                ValidationResult::failure(vec![ValidationError::body(
+                    format!("Response validation error: {e}"),
+                    "VALIDATION_ERROR",
+                )])
+            }
+        }
+    }
+}
+
+/// Parses a JSON body, treating an empty body as `null`.
+#[cfg(feature = "sentinel")]
+fn parse_body(body: &[u8]) -> serde_json::Result<Value> {
+    if body.is_empty() {
+        Ok(Value::Null)
+    } else {
+        serde_json::from_slice(body)
+    }
+}
+
+/// Converts a Sentinel validation result, tagging its errors with `code`.
+#[cfg(feature = "sentinel")]
+fn convert_sentinel_result(
+    result: Result<archimedes_sentinel::ValidationResult, SentinelError>,
+    code: &str,
+) -> ValidationResult {
+    match result {
+        Ok(result) if result.valid => ValidationResult::success(),
+        Ok(result) => ValidationResult::failure(convert_sentinel_errors(result, code)),
+        Err(e) => {
+            tracing::error!(error = %e, "Sentinel validation error");
+            ValidationResult::failure(vec![ValidationError::body(
+                format!("Validation error: {e}"),
+                "VALIDATION_ERROR",
+            )])
+        }
+    }
+}
+
+/// Converts Sentinel validation errors, tagging them with `code`.
+///
+/// Errors for unexpected fields are folded into one leading
+/// `UNKNOWN_FIELDS` error listing their paths.
+#[cfg(feature = "sentinel")]
+fn convert_sentinel_errors(
+    result: archimedes_sentinel::ValidationResult,
+    code: &str,
+) -> Vec<ValidationError> {
+    let (unknown, others): (Vec<_>, Vec<_>) = result
+        .errors
+        .into_iter()
+        .partition(|e| result.unknown_fields.contains(&e.path));
+
+    let mut errors = Vec::with_capacity(others.len() + 1);
+    if !unknown.is_empty() {
+        let paths: Vec<_> = unknown.iter().map(|e| e.path.as_str()).collect();
+        errors.push(ValidationError::body(
+            format!("Unexpected fields: {}", paths.join(", ")),
+            "UNKNOWN_FIELDS",
+        ));
+    }
+    errors.extend(others.into_iter().map(|e| ValidationError {
+        field: e.path,
+        message: e.message,
+        code: code.to_string(),
+    }));
+    errors
+}
+
 // ============================================================================
 // ResponseValidationMiddleware Implementation
 // ============================================================================
 
 impl ResponseValidationMiddleware {
+    /// Creates a response validation middleware that checks responses with
+    /// `validator`. Failures are logged but not enforced; see
+    /// [`enforce`](Self::enforce).
+    #[must_use]
+    pub fn new<V: ResponseValidator + 'static>(validator: V) -> Self {
+        Self {
+            mode: ValidationMode::Validator(Arc::new(validator)),
+            enforce: false,
+            max_unknown_field_labels: DEFAULT_MAX_UNKNOWN_FIELD_LABELS,
+            seen_unknown_fields: Arc::default(),
+        }
+    }
+
     /// Creates a new response validation middleware that allows all responses.
     #[must_use]
     pub fn allow_all() -> Self {
         Self {
             mode: ValidationMode::AllowAll,
             enforce: false,
+            max_unknown_field_labels: DEFAULT_MAX_UNKNOWN_FIELD_LABELS,
+            seen_unknown_fields: Arc::default(),
         }
     }
 
@@ -520,10 +1125,15 @@ impl ResponseValidationMiddleware {
         Self {
             mode: ValidationMode::RejectAll,
             enforce: true,
+            max_unknown_field_labels: DEFAULT_MAX_UNKNOWN_FIELD_LABELS,
+            seen_unknown_fields: Arc::default(),
         }
     }
 
     /// Creates a new schema-based response validation middleware builder.
+    #[deprecated(
+        note = "mock schemas diverge from contract validation; use `ResponseValidationMiddleware::sentinel`"
+    )]
     #[must_use]
     pub fn with_schemas() -> ResponseValidationBuilder {
         ResponseValidationBuilder::default()
@@ -535,10 +1145,7 @@ impl ResponseValidationMiddleware {
     #[cfg(feature = "sentinel")]
     #[must_use]
     pub fn sentinel(sentinel: Sentinel, enforce: bool) -> Self {
-        Self {
-            mode: ValidationMode::Sentinel(Arc::new(sentinel)),
-            enforce,
-        }
+        Self::new(sentinel).enforce(enforce)
     }
 
     /// Sets whether to enforce validation (return error) or just log.
@@ -548,106 +1155,66 @@ impl ResponseValidationMiddleware {
         self
     }
 
-    /// Validates the response body against the operation schema.
-    fn validate_response(
-        &self,
-        operation_id: &str,
-        _status_code: u16,
-        body: &[u8],
-    ) -> ValidationResult {
-        match &self.mode {
-            ValidationMode::AllowAll => ValidationResult {
-                valid: true,
-                errors: vec![],
-            },
-            ValidationMode::RejectAll => ValidationResult {
-                valid: false,
-                errors: vec![ValidationError {
-                    field: "".to_string(),
-                    message: "Response validation rejected (reject-all mode)".to_string(),
-                    code: "RESPONSE_VALIDATION_REJECTED".to_string(),
-                }],
-            },
-            ValidationMode::Schema(config) => {
-                if let Some(schema) = config.response_schemas.get(operation_id) {
-                    ValidationMiddleware::validate_body(body, schema)
-                } else {
-                    // No schema defined, allow by default
-                    ValidationResult {
-                        valid: true,
-                        errors: vec![],
-                    }
-                }
-            }
-            #[cfg(feature = "sentinel")]
-            ValidationMode::Sentinel(sentinel) => {
-                Self::validate_response_with_sentinel(sentinel, operation_id, _status_code, body)
-            }
-        }
+    /// Sets how many distinct unknown fields are counted under their own
+    /// label before the rest share [`OTHER_FIELD_LABEL`].
+    ///
+    /// Defaults to [`DEFAULT_MAX_UNKNOWN_FIELD_LABELS`].
+    #[must_use]
+    pub fn max_unknown_field_labels(mut self, max: usize) -> Self {
+        self.max_unknown_field_labels = max;
+        self
     }
 
-    /// Validates response body using Sentinel.
-    #[cfg(feature = "sentinel")]
-    fn validate_response_with_sentinel(
-        sentinel: &Sentinel,
-        operation_id: &str,
-        status_code: u16,
-        body: &[u8],
-    ) -> ValidationResult {
-        // Parse body as JSON
-        let json_body: serde_json::Value = if body.is_empty() {
-            serde_json::Value::Null
+    /// Returns the label for an unknown field of an operation: the field
+    /// itself, or [`OTHER_FIELD_LABEL`] once the distinct field limit has
+    /// been reached.
+    fn unknown_field_label(&self, operation_id: &str, field: &str) -> String {
+        let key = (operation_id.to_string(), field.to_string());
+        let mut seen = self
+            .seen_unknown_fields
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if seen.contains(&key) || seen.len() < self.max_unknown_field_labels {
+            seen.insert(key);
+            field.to_string()
         } else {
-            match serde_json::from_slice(body) {
-                Ok(v) => v,
-                Err(e) => {
-                    return ValidationResult {
-                        valid: false,
-                        errors: vec![ValidationError {
-                            field: "".to_string(),
-                            message: format!("Invalid JSON response: {e}"),
-                            code: "INVALID_JSON".to_string(),
-                        }],
-                    };
-                }
-            }
-        };
-
-        // Validate using sentinel
-        match sentinel.validate_response(operation_id, status_code, &json_body) {
-            Ok(result) => {
-                if result.valid {
-                    ValidationResult {
-                        valid: true,
-                        errors: vec![],
-                    }
-                } else {
-                    ValidationResult {
-                        valid: false,
-                        errors: result
-                            .errors
-                            .into_iter()
-                            .map(|e| ValidationError {
-                                field: e.path,
-                                message: e.message,
-                                code: "RESPONSE_SCHEMA_ERROR".to_string(),
-                            })
-                            .collect(),
-                    }
-                }
-            }
-            Err(e) => {
-                tracing::error!(error = %e, "Sentinel response validation error");
-                ValidationResult {
-                    valid: false,
-                    errors: vec![ValidationError {
-                        field: "".to_string(),
-                        message: format!("Response validation error: {e}"),
-                        code: "VALIDATION_ERROR".to_string(),
-                    }],
-                }
-            }
+            OTHER_FIELD_LABEL.to_string()
+        }
+    }
+
+    /// Validates the response body against the operation schema and counts
+    /// the unknown fields the validator reports.
+    fn validate_response(
+        &self,
+        operation_id: &str,
+        version: Option<&str>,
+        status_code: u16,
+        content_type: &str,
+        body: &[u8],
+    ) -> ValidationResult {
+        let result = match &self.mode {
+            ValidationMode::AllowAll => ValidationResult::success(),
+            ValidationMode::RejectAll => ValidationResult::failure(vec![ValidationError::body(
+                "Response validation rejected (reject-all mode)".to_string(),
+                "RESPONSE_VALIDATION_REJECTED",
+            )]),
+            ValidationMode::Validator(validator) => validator.validate_response_content(
+                operation_id,
+                version,
+                status_code,
+                content_type,
+                body,
+            ),
+        };
+        for field in &result.unknown_fields {
+            metrics::counter!(
+                RESPONSE_UNKNOWN_FIELDS,
+                "operation" => operation_id.to_string(),
+                "field" => self.unknown_field_label(operation_id, field)
+            )
+            .increment(1);
         }
+        result
     }
 }
 
@@ -664,24 +1231,48 @@ impl Middleware for ResponseValidationMiddleware {
     ) -> BoxFuture<'a, Response> {
         Box::pin(async move {
             let operation_id = ctx.operation_id().unwrap_or("unknown").to_string();
+            let version = ctx.get_extension::<ContractVersion>().map(|v| v.0.clone());
 
             // Run the handler/next middleware first
             let response = next.run(ctx, request).await;
 
+            let skip = ctx
+                .get_extension::<RouteOptions>()
+                .is_some_and(|o| o.skip_response_validation);
+
             // Only validate successful responses
-            if !response.status().is_success() {
+            if skip || !response.status().is_success() {
                 return response;
             }
 
+            // Bodies are validated against the schema for their content type
+            let content_type = response
+                .headers()
+                .get(http::header::CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or("application/json")
+                .to_string();
+
             // Get status code for sentinel validation
             let status_code = response.status().as_u16();
 
-            // For mock implementation, we'd need to extract response body
-            // In production, this would buffer and validate the response
-            // For now, we'll use a placeholder that assumes valid responses
-            let body: &[u8] = &[];
-
-            let result = self.validate_response(&operation_id, status_code, body);
+            // Buffer the body to validate it, then hand it on unchanged
+            let (parts, body) = response.into_parts();
+            let body = body
+                .collect()
+                .await
+                .map_or_else(|never| match never {}, |collected| collected.to_bytes());
+            let response = Response::from_parts(parts, Full::new(body.clone()));
+
+            let result = timing::time(timing::VALIDATION, || {
+                self.validate_response(
+                    &operation_id,
+                    version.as_deref(),
+                    status_code,
+                    &content_type,
+                    &body,
+                )
+            });
 
             // Store response validation result
             ctx.set_extension(ResponseValidationResult(result.clone()));
@@ -728,7 +1319,7 @@ impl ValidationBuilder {
     #[must_use]
     pub fn build(self) -> ValidationMiddleware {
         ValidationMiddleware {
-            mode: ValidationMode::Schema(Arc::new(self.config)),
+            mode: ValidationMode::Validator(Arc::new(self.config)),
         }
     }
 }
@@ -761,8 +1352,10 @@ impl ResponseValidationBuilder {
     #[must_use]
     pub fn build(self) -> ResponseValidationMiddleware {
         ResponseValidationMiddleware {
-            mode: ValidationMode::Schema(Arc::new(self.config)),
+            mode: ValidationMode::Validator(Arc::new(self.config)),
             enforce: self.enforce,
+            max_unknown_field_labels: DEFAULT_MAX_UNKNOWN_FIELD_LABELS,
+            seen_unknown_fields: Arc::default(),
         }
     }
 }
@@ -847,6 +1440,7 @@ pub struct ResponseValidationResult(pub ValidationResult);
 // ============================================================================
 
 #[cfg(test)]
+#[allow(deprecated)]
 mod tests {
     use super::*;
     use crate::middleware::Next;
@@ -854,6 +1448,9 @@ mod tests {
     use http::{Request as HttpRequest, Response as HttpResponse, StatusCode};
     use http_body_util::Full;
 
+    #[cfg(feature = "sentinel")]
+    use crate::context::RoutePattern;
+
     fn make_test_request() -> Request {
         HttpRequest::builder()
             .method("POST")
@@ -924,6 +1521,40 @@ mod tests {
         assert_eq!(response.status(), StatusCode::BAD_REQUEST);
     }
 
+    #[tokio::test]
+    async fn test_route_options_skip_validation() {
+        let options = RouteOptions {
+            skip_request_validation: true,
+            skip_response_validation: true,
+            ..RouteOptions::default()
+        };
+
+        let mut ctx = MiddlewareContext::new();
+        ctx.set_operation_id("testOp".to_string());
+        ctx.set_extension(options);
+        let response = ValidationMiddleware::reject_all()
+            .process(
+                &mut ctx,
+                make_test_request(),
+                Next::handler(create_handler()),
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(ctx.get_extension::<ValidationResult>().is_none());
+
+        let mut ctx = MiddlewareContext::new();
+        ctx.set_operation_id("testOp".to_string());
+        ctx.set_extension(options);
+        let response = ResponseValidationMiddleware::reject_all()
+            .process(
+                &mut ctx,
+                make_test_request(),
+                Next::handler(create_handler()),
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
     #[tokio::test]
     async fn test_schema_validates_required_fields() {
         let schema = MockSchema::builder()
@@ -949,6 +1580,39 @@ mod tests {
         assert_eq!(response.status(), StatusCode::BAD_REQUEST);
     }
 
+    #[tokio::test]
+    async fn test_rejection_details_list_every_error() {
+        let schema = MockSchema::builder()
+            .required("name")
+            .required("email")
+            .field("age", FieldType::Integer)
+            .allow_additional(true)
+            .build();
+
+        let middleware = ValidationMiddleware::with_schemas()
+            .add_request_schema("createUser", schema)
+            .build();
+
+        let mut ctx = MiddlewareContext::new();
+        ctx.set_operation_id("createUser".to_string());
+
+        let request = make_request_with_body(r#"{"name": "Alice", "age": "old"}"#);
+        let next = Next::handler(create_handler());
+
+        let response = middleware.process(&mut ctx, request, next).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"]["code"], "FIELD_REQUIRED");
+        let issues = body["error"]["details"]["issues"].as_array().unwrap();
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0]["field"], "email");
+        assert_eq!(issues[0]["code"], "FIELD_REQUIRED");
+        assert_eq!(issues[1]["field"], "age");
+        assert_eq!(issues[1]["code"], "INVALID_TYPE");
+    }
+
     #[tokio::test]
     async fn test_schema_validates_field_types() {
         let schema = MockSchema::builder()
@@ -1130,17 +1794,738 @@ mod tests {
 
     #[test]
     fn test_validation_result_structure() {
-        let result = ValidationResult {
-            valid: false,
-            errors: vec![ValidationError {
-                field: "email".to_string(),
-                message: "Invalid email format".to_string(),
-                code: "INVALID_FORMAT".to_string(),
-            }],
-        };
+        let result = ValidationResult::failure(vec![ValidationError {
+            field: "email".to_string(),
+            message: "Invalid email format".to_string(),
+            code: "INVALID_FORMAT".to_string(),
+        }]);
 
         assert!(!result.valid);
         assert_eq!(result.errors.len(), 1);
         assert_eq!(result.errors[0].field, "email");
+        assert!(result.unknown_fields.is_empty());
+        assert!(ValidationResult::success().valid);
+    }
+
+    /// Rejects bodies that are not the literal `"ok"`.
+    struct LiteralValidator;
+
+    impl RequestValidator for LiteralValidator {
+        fn validate_request(
+            &self,
+            operation_id: &str,
+            _version: Option<&str>,
+            _headers: &HeaderMap,
+            body: &[u8],
+        ) -> ValidationResult {
+            if body == b"ok" {
+                return ValidationResult::success();
+            }
+            ValidationResult::failure(vec![ValidationError {
+                field: String::new(),
+                message: format!("{operation_id} expects ok"),
+                code: "NOT_OK".to_string(),
+            }])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_custom_validator_reads_unbuffered_body() {
+        let middleware = ValidationMiddleware::new(LiteralValidator);
+
+        // No `RequestBody` extension, as on the server path
+        let request = |body: &'static str| {
+            HttpRequest::builder()
+                .method("POST")
+                .uri("/test")
+                .body(Full::new(Bytes::from(body)))
+                .unwrap()
+        };
+
+        let mut ctx = MiddlewareContext::new();
+        ctx.set_operation_id("testOp".to_string());
+        let response = middleware
+            .process(&mut ctx, request("ok"), Next::handler(create_handler()))
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let mut ctx = MiddlewareContext::new();
+        ctx.set_operation_id("testOp".to_string());
+        let response = middleware
+            .process(&mut ctx, request("nope"), Next::handler(create_handler()))
+            .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let result = ctx.get_extension::<ValidationResult>().unwrap();
+        assert_eq!(result.errors[0].message, "testOp expects ok");
+    }
+
+    #[cfg(feature = "sentinel")]
+    fn versioned_sentinel() -> Sentinel {
+        use archimedes_sentinel::{LoadedArtifact, LoadedOperation, VersionSelector};
+
+        let artifact = |version: &str, op: &str| LoadedArtifact {
+            service: "users".to_string(),
+            version: version.to_string(),
+            format: "openapi".to_string(),
+            operations: vec![LoadedOperation {
+                id: op.to_string(),
+                method: "POST".to_string(),
+                path: "/test".to_string(),
+                summary: None,
+                deprecated: false,
+                security: vec![],
+                request_schema: None,
+                response_schemas: HashMap::new(),
+                tags: vec![],
+                header_params: Vec::new(),
+                event_schemas: HashMap::new(),
+                timeout: None,
+                cache_policy: None,
+                allow_reserved: Vec::new(),
+            }],
+            schemas: Default::default(),
+            stats: Default::default(),
+        };
+
+        Sentinel::versioned(
+            [
+                ("v1", artifact("1.0.0", "createTestV1")),
+                ("v2", artifact("2.0.0", "createTestV2")),
+            ],
+            VersionSelector::new(),
+            archimedes_sentinel::SentinelConfig::default(),
+        )
+        .unwrap()
+    }
+
+    #[cfg(feature = "sentinel")]
+    #[tokio::test]
+    async fn test_sentinel_selects_contract_version() {
+        let middleware = ValidationMiddleware::sentinel(versioned_sentinel());
+        let mut ctx = MiddlewareContext::new();
+        ctx.set_operation_id("createTestV1".to_string());
+
+        let mut request = make_test_request();
+        request
+            .headers_mut()
+            .insert("accept-version", http::HeaderValue::from_static("v2"));
+
+        let next = Next::handler(create_handler());
+        let response = middleware.process(&mut ctx, request, next).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(ctx.operation_id(), Some("createTestV2"));
+        assert_eq!(
+            ctx.get_extension::<ContractVersion>(),
+            Some(&ContractVersion("v2".to_string()))
+        );
+        assert_eq!(
+            ctx.get_extension::<RoutePattern>(),
+            Some(&RoutePattern("/test".to_string()))
+        );
+        let resolution = ctx.operation_resolution().unwrap();
+        assert_eq!(resolution.operation_id, "createTestV2");
+        assert_eq!(resolution.version, "v2");
+    }
+
+    #[cfg(feature = "sentinel")]
+    #[tokio::test]
+    async fn test_sentinel_keeps_existing_resolution() {
+        let sentinel = versioned_sentinel();
+        let mut ctx = MiddlewareContext::new();
+
+        // Resolved before the pipeline, e.g. by the sidecar
+        let mut request = make_test_request();
+        request
+            .headers_mut()
+            .insert("accept-version", http::HeaderValue::from_static("v1"));
+        let mut resolution = sentinel
+            .resolve_with_headers("POST", "/test", request.headers())
+            .unwrap();
+        resolution.route_pattern = "/resolved".to_string();
+        ctx.set_operation_resolution(resolution);
+
+        let next = Next::handler(create_handler());
+        let response = ValidationMiddleware::sentinel(sentinel)
+            .process(&mut ctx, request, next)
+            .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(ctx.operation_id(), Some("createTestV1"));
+        assert_eq!(
+            ctx.get_extension::<RoutePattern>(),
+            Some(&RoutePattern("/resolved".to_string()))
+        );
+    }
+
+    #[cfg(feature = "sentinel")]
+    #[tokio::test]
+    async fn test_sentinel_rejects_unsupported_version() {
+        let middleware = ValidationMiddleware::sentinel(versioned_sentinel());
+        let mut ctx = MiddlewareContext::new();
+
+        let mut request = make_test_request();
+        request
+            .headers_mut()
+            .insert("accept-version", http::HeaderValue::from_static("v9"));
+
+        let next = Next::handler(create_handler());
+        let response = middleware.process(&mut ctx, request, next).await;
+
+        assert_eq!(response.status(), StatusCode::NOT_ACCEPTABLE);
+        assert!(!ctx.has_extension::<ContractVersion>());
+    }
+
+    #[cfg(feature = "sentinel")]
+    fn header_sentinel() -> Sentinel {
+        use archimedes_sentinel::{HeaderParam, LoadedArtifact, LoadedOperation};
+
+        let operation = |id: &str, header_params: Vec<HeaderParam>| LoadedOperation {
+            id: id.to_string(),
+            method: "POST".to_string(),
+            path: format!("/{id}"),
+            summary: None,
+            deprecated: false,
+            security: vec![],
+            request_schema: None,
+            response_schemas: HashMap::new(),
+            tags: vec![],
+            header_params,
+            event_schemas: HashMap::new(),
+            timeout: None,
+            cache_policy: None,
+            allow_reserved: Vec::new(),
+        };
+        let request_id = HeaderParam {
+            name: "X-Request-Id".to_string(),
+            required: true,
+            schema_type: "string".to_string(),
+            pattern: Some("^[0-9a-f]{8}$".to_string()),
+            allowed_values: vec![],
+        };
+
+        Sentinel::with_defaults(LoadedArtifact {
+            service: "users".to_string(),
+            version: "1.0.0".to_string(),
+            format: "openapi".to_string(),
+            operations: vec![
+                operation("createTest", vec![request_id]),
+                operation("listTests", vec![]),
+            ],
+            schemas: Default::default(),
+            stats: Default::default(),
+        })
+    }
+
+    #[cfg(feature = "sentinel")]
+    async fn run_header_validation(
+        operation_id: &str,
+        request_id: Option<&'static str>,
+    ) -> (Response, MiddlewareContext) {
+        let middleware = ValidationMiddleware::sentinel(header_sentinel());
+        let mut ctx = MiddlewareContext::new();
+        ctx.set_operation_id(operation_id.to_string());
+
+        let mut request = make_test_request();
+        if let Some(value) = request_id {
+            request
+                .headers_mut()
+                .insert("x-request-id", http::HeaderValue::from_static(value));
+        }
+
+        let next = Next::handler(create_handler());
+        let response = middleware.process(&mut ctx, request, next).await;
+        (response, ctx)
+    }
+
+    #[cfg(feature = "sentinel")]
+    #[tokio::test]
+    async fn test_sentinel_rejects_missing_required_header() {
+        let (response, ctx) = run_header_validation("createTest", None).await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let result = ctx.get_extension::<ValidationResult>().unwrap();
+        assert_eq!(result.errors[0].field, "header.X-Request-Id");
+        assert_eq!(result.errors[0].code, "HEADER_VALIDATION_ERROR");
+        assert!(result.errors[0].message.contains("X-Request-Id"));
+    }
+
+    #[cfg(feature = "sentinel")]
+    #[tokio::test]
+    async fn test_sentinel_rejects_header_pattern_mismatch() {
+        let (response, ctx) = run_header_validation("createTest", Some("not-hex!")).await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let result = ctx.get_extension::<ValidationResult>().unwrap();
+        assert!(result.errors[0].message.contains("pattern"));
+
+        let (response, _) = run_header_validation("createTest", Some("0badcafe")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[cfg(feature = "sentinel")]
+    #[tokio::test]
+    async fn test_sentinel_without_header_requirements() {
+        let (response, _) = run_header_validation("listTests", None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[cfg(feature = "sentinel")]
+    #[tokio::test]
+    async fn test_sentinel_warm_up_builds_validation_state() {
+        let sentinel = Arc::new(header_sentinel());
+        assert_eq!(sentinel.compiled_operations(), 0);
+
+        for operation_id in ["createTest", "listTests"] {
+            RequestValidator::warm_up(&sentinel, operation_id, None);
+        }
+        assert_eq!(sentinel.compiled_operations(), 2);
+
+        // The first request finds the state built
+        let middleware = ValidationMiddleware::new(Arc::clone(&sentinel));
+        let mut ctx = MiddlewareContext::new();
+        ctx.set_operation_id("createTest".to_string());
+        let next = Next::handler(create_handler());
+        let response = middleware
+            .process(&mut ctx, make_test_request(), next)
+            .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(sentinel.compiled_operations(), 2);
+    }
+
+    #[cfg(feature = "sentinel")]
+    #[test]
+    fn test_sentinel_example_body_passes_validation() {
+        let sentinel = coercing_sentinel();
+
+        let body = RequestValidator::example_body(&sentinel, "createTest", None).unwrap();
+        let example: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(example, serde_json::json!({ "age": 0, "active": false }));
+        let result = RequestValidator::validate_request(
+            &sentinel,
+            "createTest",
+            None,
+            &HeaderMap::new(),
+            &body,
+        );
+        assert!(result.valid);
+
+        assert!(RequestValidator::example_body(&sentinel, "unknownOperation", None).is_none());
+    }
+
+    #[cfg(feature = "sentinel")]
+    fn coercing_sentinel() -> Sentinel {
+        use archimedes_sentinel::{
+            LoadedArtifact, LoadedOperation, SchemaRef, SentinelConfig, ValidationConfig,
+        };
+
+        let artifact = LoadedArtifact {
+            service: "users".to_string(),
+            version: "1.0.0".to_string(),
+            format: "openapi".to_string(),
+            operations: vec![LoadedOperation {
+                id: "createTest".to_string(),
+                method: "POST".to_string(),
+                path: "/test".to_string(),
+                summary: None,
+                deprecated: false,
+                security: vec![],
+                request_schema: Some(SchemaRef {
+                    reference: "#/components/schemas/Test".to_string(),
+                    schema_type: "object".to_string(),
+                    required: vec!["age".to_string()],
+                    properties: HashMap::from([
+                        ("age".to_string(), "integer".to_string()),
+                        ("active".to_string(), "boolean".to_string()),
+                    ]),
+                    property_schemas: HashMap::new(),
+                    required_scopes: HashMap::new(),
+                    items: None,
+                    additional_properties: None,
+                }),
+                response_schemas: HashMap::new(),
+                tags: vec![],
+                header_params: Vec::new(),
+                event_schemas: HashMap::new(),
+                timeout: None,
+                cache_policy: None,
+                allow_reserved: Vec::new(),
+            }],
+            schemas: Default::default(),
+            stats: Default::default(),
+        };
+        let config = SentinelConfig {
+            validation: ValidationConfig::default().with_coercion(true),
+            ..SentinelConfig::default()
+        };
+        Sentinel::new(artifact, config)
+    }
+
+    #[cfg(feature = "sentinel")]
+    #[tokio::test]
+    async fn test_sentinel_coerced_body_reaches_handler() {
+        use http_body_util::BodyExt;
+
+        let middleware = ValidationMiddleware::sentinel(coercing_sentinel());
+        let mut ctx = MiddlewareContext::new();
+        ctx.set_operation_id("createTest".to_string());
+
+        let request = make_request_with_body(r#"{"age":"42","active":"true"}"#);
+        let next = Next::handler(|_ctx, req: Request| {
+            let body = req.extensions().get::<RequestBody>().unwrap().0.clone();
+            Box::pin(async move {
+                HttpResponse::builder()
+                    .status(StatusCode::OK)
+                    .body(Full::new(Bytes::from(body)))
+                    .unwrap()
+            }) as BoxFuture<'static, Response>
+        });
+        let response = middleware.process(&mut ctx, request, next).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body, serde_json::json!({ "age": 42, "active": true }));
+    }
+
+    #[cfg(feature = "sentinel")]
+    #[tokio::test]
+    async fn test_sentinel_rejects_uncoercible_string() {
+        let middleware = ValidationMiddleware::sentinel(coercing_sentinel());
+        let mut ctx = MiddlewareContext::new();
+        ctx.set_operation_id("createTest".to_string());
+
+        let request = make_request_with_body(r#"{"age":"abc"}"#);
+        let next = Next::handler(create_handler());
+        let response = middleware.process(&mut ctx, request, next).await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let result = ctx.get_extension::<ValidationResult>().unwrap();
+        assert_eq!(result.errors[0].field, "age");
+    }
+
+    #[cfg(feature = "sentinel")]
+    fn unknown_fields_sentinel(
+        validation: archimedes_sentinel::ValidationConfig,
+        customer_additional_properties: Option<bool>,
+    ) -> Sentinel {
+        use archimedes_sentinel::{LoadedArtifact, LoadedOperation, SchemaRef, SentinelConfig};
+
+        let object = |reference: &str, properties: &[&str]| SchemaRef {
+            reference: reference.to_string(),
+            schema_type: "object".to_string(),
+            required: vec![],
+            properties: properties
+                .iter()
+                .map(|name| ((*name).to_string(), "string".to_string()))
+                .collect(),
+            property_schemas: HashMap::new(),
+            required_scopes: HashMap::new(),
+            items: None,
+            additional_properties: None,
+        };
+        let mut customer = object("#/components/schemas/Customer", &["name"]);
+        customer.additional_properties = customer_additional_properties;
+        let mut order = object("#/components/schemas/Order", &["id", "customer"]);
+        order
+            .properties
+            .insert("customer".to_string(), "object".to_string());
+        order
+            .property_schemas
+            .insert("customer".to_string(), customer);
+
+        let artifact = LoadedArtifact {
+            service: "orders".to_string(),
+            version: "1.0.0".to_string(),
+            format: "openapi".to_string(),
+            operations: vec![LoadedOperation {
+                id: "createOrder".to_string(),
+                method: "POST".to_string(),
+                path: "/test".to_string(),
+                summary: None,
+                deprecated: false,
+                security: vec![],
+                request_schema: Some(order.clone()),
+                response_schemas: HashMap::from([(
+                    (
+                        "200".to_string(),
+                        archimedes_sentinel::JSON_MEDIA_TYPE.to_string(),
+                    ),
+                    order,
+                )]),
+                tags: vec![],
+                header_params: Vec::new(),
+                event_schemas: HashMap::new(),
+                timeout: None,
+                cache_policy: None,
+                allow_reserved: Vec::new(),
+            }],
+            schemas: Default::default(),
+            stats: Default::default(),
+        };
+        let config = SentinelConfig {
+            validation: archimedes_sentinel::ValidationConfig {
+                validate_responses: true,
+                ..validation
+            },
+            ..SentinelConfig::default()
+        };
+        Sentinel::new(artifact, config)
+    }
+
+    #[cfg(feature = "sentinel")]
+    const ORDER_WITH_UNKNOWN_FIELDS: &str =
+        r#"{"id":"o-1","coupon":"SPRING","customer":{"name":"Ann","vip":true}}"#;
+
+    /// Runs request validation and returns the response with its JSON body.
+    #[cfg(feature = "sentinel")]
+    async fn run_unknown_fields_request(middleware: &ValidationMiddleware) -> (StatusCode, Value) {
+        let mut ctx = MiddlewareContext::new();
+        ctx.set_operation_id("createOrder".to_string());
+        let request = make_request_with_body(ORDER_WITH_UNKNOWN_FIELDS);
+        let next = Next::handler(|_ctx, req: Request| {
+            let body = req.extensions().get::<RequestBody>().unwrap().0.clone();
+            Box::pin(async move {
+                HttpResponse::builder()
+                    .status(StatusCode::OK)
+                    .body(Full::new(Bytes::from(body)))
+                    .unwrap()
+            }) as BoxFuture<'static, Response>
+        });
+        let response = middleware.process(&mut ctx, request, next).await;
+
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[cfg(feature = "sentinel")]
+    #[tokio::test]
+    async fn test_sentinel_unknown_request_fields_allowed_by_default() {
+        let sentinel = unknown_fields_sentinel(Default::default(), None);
+        let middleware = ValidationMiddleware::sentinel(sentinel);
+
+        let (status, body) = run_unknown_fields_request(&middleware).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body,
+            serde_json::from_str::<Value>(ORDER_WITH_UNKNOWN_FIELDS).unwrap()
+        );
+    }
+
+    #[cfg(feature = "sentinel")]
+    #[tokio::test]
+    async fn test_sentinel_rejects_unknown_request_fields() {
+        let validation = archimedes_sentinel::ValidationConfig::default()
+            .with_request_unknown_fields(RequestUnknownFields::Reject);
+        let middleware = ValidationMiddleware::sentinel(unknown_fields_sentinel(validation, None));
+
+        let (status, body) = run_unknown_fields_request(&middleware).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"]["code"], "UNKNOWN_FIELDS");
+        let message = body["error"]["message"].as_str().unwrap();
+        assert!(message.starts_with("Unexpected fields: "));
+        assert!(message.contains("coupon"));
+        assert!(message.contains("customer.vip"));
+    }
+
+    #[cfg(feature = "sentinel")]
+    #[tokio::test]
+    async fn test_sentinel_strips_unknown_request_fields() {
+        let validation = archimedes_sentinel::ValidationConfig::default()
+            .with_request_unknown_fields(RequestUnknownFields::Strip);
+        let middleware = ValidationMiddleware::sentinel(unknown_fields_sentinel(validation, None));
+
+        let (status, body) = run_unknown_fields_request(&middleware).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body,
+            serde_json::json!({ "id": "o-1", "customer": { "name": "Ann" } })
+        );
+    }
+
+    #[cfg(feature = "sentinel")]
+    #[tokio::test]
+    async fn test_sentinel_schema_additional_properties_wins() {
+        // The schema rejects unknown customer fields whatever the policy
+        let sentinel = unknown_fields_sentinel(Default::default(), Some(false));
+        let middleware = ValidationMiddleware::sentinel(sentinel);
+        let (status, body) = run_unknown_fields_request(&middleware).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"]["message"], "Unexpected fields: customer.vip");
+
+        // ...and allows them when it says so
+        let validation = archimedes_sentinel::ValidationConfig::default()
+            .with_request_unknown_fields(RequestUnknownFields::Reject);
+        let middleware =
+            ValidationMiddleware::sentinel(unknown_fields_sentinel(validation, Some(true)));
+        let (status, body) = run_unknown_fields_request(&middleware).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"]["message"], "Unexpected fields: coupon");
+    }
+
+    /// Runs response validation over a handler returning an order with
+    /// unknown fields.
+    #[cfg(feature = "sentinel")]
+    async fn run_unknown_fields_response(middleware: &ResponseValidationMiddleware) -> Response {
+        let mut ctx = MiddlewareContext::new();
+        ctx.set_operation_id("createOrder".to_string());
+        let next = Next::handler(|_ctx, _req| {
+            Box::pin(async {
+                HttpResponse::builder()
+                    .status(StatusCode::OK)
+                    .header(http::header::CONTENT_TYPE, "application/json")
+                    .body(Full::new(Bytes::from(ORDER_WITH_UNKNOWN_FIELDS)))
+                    .unwrap()
+            }) as BoxFuture<'static, Response>
+        });
+        middleware
+            .process(&mut ctx, make_test_request(), next)
+            .await
+    }
+
+    #[cfg(feature = "sentinel")]
+    #[tokio::test]
+    async fn test_sentinel_rejects_unknown_response_fields() {
+        let sentinel = || {
+            let validation = archimedes_sentinel::ValidationConfig::default()
+                .with_response_unknown_fields(ResponseUnknownFields::Reject);
+            unknown_fields_sentinel(validation, None)
+        };
+
+        let middleware = ResponseValidationMiddleware::sentinel(sentinel(), true);
+        let response = run_unknown_fields_response(&middleware).await;
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"]["code"], "UNKNOWN_FIELDS");
+
+        // Unenforced validation passes the body through untouched
+        let middleware = ResponseValidationMiddleware::sentinel(sentinel(), false);
+        let response = run_unknown_fields_response(&middleware).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body, ORDER_WITH_UNKNOWN_FIELDS.as_bytes());
+    }
+
+    #[cfg(feature = "sentinel")]
+    #[test]
+    fn test_sentinel_counts_unknown_response_fields() {
+        use metrics_exporter_prometheus::PrometheusBuilder;
+
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+        let validation = archimedes_sentinel::ValidationConfig::default()
+            .with_response_unknown_fields(ResponseUnknownFields::LogOnly);
+        let middleware =
+            ResponseValidationMiddleware::sentinel(unknown_fields_sentinel(validation, None), true)
+                .max_unknown_field_labels(1);
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        metrics::with_local_recorder(&recorder, || {
+            runtime.block_on(async {
+                for _ in 0..2 {
+                    let response = run_unknown_fields_response(&middleware).await;
+                    assert_eq!(response.status(), StatusCode::OK);
+                }
+            });
+        });
+
+        // One field gets its own label, the other shares the overflow label
+        let rendered = handle.render();
+        let own = [
+            r#"archimedes_response_unknown_fields_total{operation="createOrder",field="coupon"} 2"#,
+            r#"archimedes_response_unknown_fields_total{operation="createOrder",field="customer.vip"} 2"#,
+        ];
+        assert_eq!(
+            own.iter().filter(|line| rendered.contains(*line)).count(),
+            1
+        );
+        assert!(rendered.contains(
+            r#"archimedes_response_unknown_fields_total{operation="createOrder",field="other"} 2"#
+        ));
+    }
+
+    #[cfg(feature = "sentinel")]
+    #[tokio::test]
+    async fn test_sentinel_validates_response_by_content_type() {
+        use archimedes_sentinel::{ArtifactLoader, SentinelConfig};
+
+        let artifact = ArtifactLoader::from_value(serde_json::json!({
+            "openapi": "3.1.0",
+            "info": { "title": "reports", "version": "1.0.0" },
+            "paths": {
+                "/reports/{id}": {
+                    "get": {
+                        "operationId": "getReport",
+                        "responses": {
+                            "200": {
+                                "content": {
+                                    "application/json": {
+                                        "schema": {
+                                            "type": "object",
+                                            "required": ["id", "rows"],
+                                            "properties": {
+                                                "id": { "type": "string" },
+                                                "rows": { "type": "array" }
+                                            }
+                                        }
+                                    },
+                                    "text/csv": { "schema": { "type": "string" } }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let config = SentinelConfig {
+            validation: archimedes_sentinel::ValidationConfig {
+                validate_responses: true,
+                ..Default::default()
+            },
+            ..SentinelConfig::default()
+        };
+        let middleware =
+            ResponseValidationMiddleware::sentinel(Sentinel::new(artifact, config), true);
+
+        let middleware = &middleware;
+        let respond = |content_type: &'static str, body: &'static str| async move {
+            let mut ctx = MiddlewareContext::new();
+            ctx.set_operation_id("getReport".to_string());
+            let next = Next::handler(move |_ctx, _req| {
+                Box::pin(async move {
+                    HttpResponse::builder()
+                        .status(StatusCode::OK)
+                        .header(http::header::CONTENT_TYPE, content_type)
+                        .body(Full::new(Bytes::from(body)))
+                        .unwrap()
+                }) as BoxFuture<'static, Response>
+            });
+            middleware
+                .process(&mut ctx, make_test_request(), next)
+                .await
+                .status()
+        };
+
+        assert_eq!(
+            respond("application/json", r#"{"id":"r-1","rows":[]}"#).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            respond("text/csv; charset=utf-8", "id,total\nr-1,10\n").await,
+            StatusCode::OK
+        );
+        // The JSON schema still applies to JSON responses
+        assert_eq!(
+            respond("application/json", r#"{"id":"r-1"}"#).await,
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+        // ...but not to CSV responses
+        assert_eq!(respond("text/csv", r#"{"id":"r-1"}"#).await, StatusCode::OK);
     }
 }
@@ -18,6 +18,13 @@ pub type Response = http::Response<Full<Bytes>>;
 /// A boxed HTTP body for streaming responses.
 pub type BoxBody = http_body_util::combinators::BoxBody<Bytes, std::convert::Infallible>;
 
+/// Response extension asking the server to drop the connection instead of
+/// sending the response.
+///
+/// Set by the fault injection stage to simulate connection resets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResetConnection;
+
 /// Extension trait for building error responses.
 pub trait ResponseExt {
     /// Creates an error response with the given status code and message.
@@ -25,6 +32,14 @@ pub trait ResponseExt {
 
     /// Creates a JSON error response.
     fn json_error(status: http::StatusCode, code: &str, message: &str) -> Response;
+
+    /// Creates a JSON error response carrying machine-readable `details`.
+    fn json_error_with_details(
+        status: http::StatusCode,
+        code: &str,
+        message: &str,
+        details: serde_json::Value,
+    ) -> Response;
 }
 
 impl ResponseExt for Response {
@@ -44,14 +59,35 @@ impl ResponseExt for Response {
             }
         });
 
-        http::Response::builder()
-            .status(status)
-            .header(http::header::CONTENT_TYPE, "application/json")
-            .body(Full::new(Bytes::from(body.to_string())))
-            .expect("failed to build JSON error response")
+        json_response(status, &body)
+    }
+
+    fn json_error_with_details(
+        status: http::StatusCode,
+        code: &str,
+        message: &str,
+        details: serde_json::Value,
+    ) -> Response {
+        let body = serde_json::json!({
+            "error": {
+                "code": code,
+                "message": message,
+                "details": details
+            }
+        });
+
+        json_response(status, &body)
     }
 }
 
+fn json_response(status: http::StatusCode, body: &serde_json::Value) -> Response {
+    http::Response::builder()
+        .status(status)
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .body(Full::new(Bytes::from(body.to_string())))
+        .expect("failed to build JSON error response")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -80,4 +116,23 @@ mod tests {
             "application/json"
         );
     }
+
+    #[tokio::test]
+    async fn test_json_error_with_details() {
+        use http_body_util::BodyExt;
+
+        let details = serde_json::json!({"issues": [{"field": "email"}]});
+        let response = Response::json_error_with_details(
+            StatusCode::BAD_REQUEST,
+            "FIELD_REQUIRED",
+            "Missing required field: email",
+            details.clone(),
+        );
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"]["code"], "FIELD_REQUIRED");
+        assert_eq!(body["error"]["details"], details);
+    }
 }
@@ -24,7 +24,7 @@ use archimedes_middleware::{
         identity::IdentityMiddleware,
         request_id::RequestIdMiddleware,
         telemetry::TelemetryMiddleware,
-        tracing::TracingMiddleware,
+        tracing::{InMemorySpanExporter, TracingMiddleware},
         validation::{MockSchema, RequestBody, ValidationMiddleware},
     },
     types::Request,
@@ -32,6 +32,8 @@ use archimedes_middleware::{
 use bytes::Bytes;
 use http::{Request as HttpRequest, Response as HttpResponse, StatusCode};
 use http_body_util::Full;
+use std::sync::Arc;
+use std::time::Duration;
 
 type Response = HttpResponse<Full<Bytes>>;
 
@@ -165,6 +167,7 @@ fn build_restricted_rbac_pipeline() -> Pipeline {
 }
 
 /// Builds a pipeline with schema validation.
+#[allow(deprecated)]
 fn build_validation_pipeline() -> Pipeline {
     let request_id = RequestIdMiddleware::new();
     let tracing = TracingMiddleware::new("validation-test-service");
@@ -565,6 +568,61 @@ async fn test_context_propagation_through_stages() {
     assert_eq!(response.status(), StatusCode::OK);
 }
 
+#[tokio::test]
+async fn test_stage_timings_recorded_on_request_span() {
+    let exporter = Arc::new(InMemorySpanExporter::new());
+    let tracing = TracingMiddleware::new("e2e-test-service")
+        .record_stage_events(true)
+        .with_exporter(exporter.clone());
+
+    let pipeline = Pipeline::builder()
+        .add_pre_handler_stage(RequestIdMiddleware::new())
+        .add_pre_handler_stage(tracing)
+        .add_pre_handler_stage(IdentityMiddleware::with_trust_domain("test.example.com"))
+        .add_pre_handler_stage(AuthorizationMiddleware::allow_all())
+        .add_pre_handler_stage(ValidationMiddleware::allow_all())
+        .add_post_handler_stage(TelemetryMiddleware::new("e2e-test-service"))
+        .add_post_handler_stage(ErrorNormalizationMiddleware::new())
+        .build();
+
+    let response = pipeline
+        .process(
+            MiddlewareContext::new(),
+            make_request("/users/123", "GET"),
+            |_ctx, _req| {
+                Box::pin(async {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    success_response()
+                })
+            },
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let spans = exporter.spans();
+    assert_eq!(spans.len(), 1, "one request span should be exported");
+    let span = &spans[0];
+    assert_eq!(span.status_code, 200);
+
+    let stages: Vec<&str> = span.events.iter().map(|e| e.stage).collect();
+    assert_eq!(
+        stages,
+        vec![
+            "identity",
+            "authorization",
+            "request_validation",
+            "telemetry",
+            "error_normalization",
+            "handler",
+        ]
+    );
+
+    let handler = span.event("handler").unwrap();
+    assert!(handler.duration >= Duration::from_millis(10));
+    assert!(span.event("identity").unwrap().total >= handler.total);
+    assert!(span.duration >= span.event("identity").unwrap().total);
+}
+
 // ============================================================================
 // Error Handling Tests
 // ============================================================================
@@ -624,6 +682,7 @@ async fn test_multiple_errors_normalized_consistently() {
 // ============================================================================
 
 /// Build a pipeline with enforced request validation.
+#[allow(deprecated)]
 fn build_enforce_validation_pipeline() -> Pipeline {
     let request_id = RequestIdMiddleware::new();
     let tracing = TracingMiddleware::new("enforce-test-service");
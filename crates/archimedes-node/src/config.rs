@@ -1,5 +1,6 @@
 //! Configuration types for Archimedes Node.js bindings.
 
+use archimedes_sentinel::{RequestUnknownFields, ResponseUnknownFields};
 use napi_derive::napi;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -36,6 +37,14 @@ pub struct Config {
     /// Enable request validation (default: true)
     pub enable_validation: Option<bool>,
 
+    /// What to do with request fields the schema does not declare:
+    /// "reject", "strip" or "allow" (default: "allow")
+    pub request_unknown_fields: Option<String>,
+
+    /// What to do with response fields the schema does not declare:
+    /// "reject", "log_only" or "allow" (default: "allow")
+    pub response_unknown_fields: Option<String>,
+
     /// Enable authorization (default: true)
     pub enable_authorization: Option<bool>,
 
@@ -54,6 +63,16 @@ pub struct Config {
     /// CORS allowed origins
     pub cors_origins: Option<Vec<String>>,
 
+    /// Base path prepended to URLs built with `urlFor`
+    pub base_path: Option<String>,
+
+    /// Shut down gracefully on SIGTERM/SIGINT (default: false)
+    pub handle_signals: Option<bool>,
+
+    /// Time in-flight requests get to finish during shutdown, in
+    /// milliseconds (default: 30000)
+    pub shutdown_grace_period_ms: Option<u32>,
+
     /// Additional custom configuration
     pub custom: Option<HashMap<String, String>>,
 }
@@ -67,12 +86,17 @@ impl Default for Config {
             opa_endpoint: None,
             opa_policy_path: Some("archimedes/allow".to_string()),
             enable_validation: Some(true),
+            request_unknown_fields: None,
+            response_unknown_fields: None,
             enable_authorization: Some(true),
             enable_telemetry: Some(true),
             request_timeout_ms: Some(30000),
             max_body_size: Some(10 * 1024 * 1024), // 10MB
             enable_cors: Some(false),
             cors_origins: None,
+            base_path: None,
+            handle_signals: Some(false),
+            shutdown_grace_period_ms: Some(30000),
             custom: None,
         }
     }
@@ -137,6 +161,30 @@ impl ConfigBuilder {
         self
     }
 
+    /// Set what to do with request fields the schema does not declare.
+    ///
+    /// An explicit `additionalProperties` in the schema always wins.
+    #[napi]
+    pub fn request_unknown_fields(&mut self, policy: String) -> napi::Result<&Self> {
+        policy
+            .parse::<RequestUnknownFields>()
+            .map_err(|e| napi::Error::new(napi::Status::InvalidArg, e))?;
+        self.config.request_unknown_fields = Some(policy);
+        Ok(self)
+    }
+
+    /// Set what to do with response fields the schema does not declare.
+    ///
+    /// An explicit `additionalProperties` in the schema always wins.
+    #[napi]
+    pub fn response_unknown_fields(&mut self, policy: String) -> napi::Result<&Self> {
+        policy
+            .parse::<ResponseUnknownFields>()
+            .map_err(|e| napi::Error::new(napi::Status::InvalidArg, e))?;
+        self.config.response_unknown_fields = Some(policy);
+        Ok(self)
+    }
+
     /// Enable or disable authorization.
     #[napi]
     pub fn enable_authorization(&mut self, enable: bool) -> &Self {
@@ -179,6 +227,27 @@ impl ConfigBuilder {
         self
     }
 
+    /// Set the base path for generated URLs.
+    #[napi]
+    pub fn base_path(&mut self, base_path: String) -> &Self {
+        self.config.base_path = Some(base_path);
+        self
+    }
+
+    /// Enable or disable graceful shutdown on SIGTERM/SIGINT.
+    #[napi]
+    pub fn handle_signals(&mut self, enable: bool) -> &Self {
+        self.config.handle_signals = Some(enable);
+        self
+    }
+
+    /// Set the shutdown grace period in milliseconds.
+    #[napi]
+    pub fn shutdown_grace_period_ms(&mut self, grace_period: u32) -> &Self {
+        self.config.shutdown_grace_period_ms = Some(grace_period);
+        self
+    }
+
     /// Add a custom configuration value.
     #[napi]
     pub fn custom(&mut self, key: String, value: String) -> &Self {
@@ -265,6 +334,40 @@ mod tests {
         assert_eq!(config.enable_cors, Some(false));
     }
 
+    #[test]
+    fn test_config_builder_shutdown() {
+        let mut builder = ConfigBuilder::new();
+        assert_eq!(builder.build().handle_signals, Some(false));
+
+        builder.handle_signals(true);
+        builder.shutdown_grace_period_ms(5000);
+        let config = builder.build();
+
+        assert_eq!(config.handle_signals, Some(true));
+        assert_eq!(config.shutdown_grace_period_ms, Some(5000));
+    }
+
+    #[test]
+    fn test_config_builder_unknown_fields() {
+        let mut builder = ConfigBuilder::new();
+        assert_eq!(builder.build().request_unknown_fields, None);
+
+        builder.request_unknown_fields("strip".to_string()).unwrap();
+        builder
+            .response_unknown_fields("log_only".to_string())
+            .unwrap();
+        assert!(builder
+            .request_unknown_fields("log_only".to_string())
+            .is_err());
+        assert!(builder
+            .response_unknown_fields("strip".to_string())
+            .is_err());
+        let config = builder.build();
+
+        assert_eq!(config.request_unknown_fields.as_deref(), Some("strip"));
+        assert_eq!(config.response_unknown_fields.as_deref(), Some("log_only"));
+    }
+
     #[test]
     fn test_config_builder_custom() {
         let mut builder = ConfigBuilder::new();
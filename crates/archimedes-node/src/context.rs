@@ -1,5 +1,6 @@
 //! Request context and identity types.
 
+use archimedes_core::UrlForError;
 use napi_derive::napi;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -191,6 +192,12 @@ pub struct RequestContext {
 
     /// Custom context data set by middleware
     pub custom: HashMap<String, String>,
+
+    /// Route path patterns by operation ID, used by `urlFor`
+    pub route_patterns: HashMap<String, String>,
+
+    /// Base path prepended to URLs built with `urlFor`
+    pub base_path: Option<String>,
 }
 
 /// Request context builder for programmatic construction.
@@ -290,6 +297,20 @@ impl RequestContextBuilder {
         self
     }
 
+    /// Register the route pattern of an operation for `urlFor`.
+    #[napi]
+    pub fn route(&mut self, operation_id: String, pattern: String) -> &Self {
+        self.ctx.route_patterns.insert(operation_id, pattern);
+        self
+    }
+
+    /// Set the base path for generated URLs.
+    #[napi]
+    pub fn base_path(&mut self, base_path: String) -> &Self {
+        self.ctx.base_path = Some(base_path);
+        self
+    }
+
     /// Build the request context.
     #[napi]
     pub fn build(&self) -> RequestContext {
@@ -315,7 +336,56 @@ pub fn mock_request_context() -> RequestContext {
         content_type: None,
         accept: None,
         custom: HashMap::new(),
+        route_patterns: HashMap::new(),
+        base_path: None,
+    }
+}
+
+/// Build the URL of another operation from a request context.
+///
+/// Path parameter values are percent-encoded and query parameters are
+/// appended in key order.
+///
+/// # Example (TypeScript)
+///
+/// ```typescript
+/// const location = urlFor(ctx, 'getUser', { userId: '42' });
+/// ```
+#[napi]
+pub fn url_for(
+    ctx: RequestContext,
+    operation_id: String,
+    params: Option<HashMap<String, String>>,
+    query: Option<HashMap<String, String>>,
+) -> napi::Result<String> {
+    let Some(pattern) = ctx.route_patterns.get(&operation_id) else {
+        return Err(napi::Error::from_reason(
+            UrlForError::UnknownOperation(operation_id).to_string(),
+        ));
+    };
+
+    let mut router = archimedes_server::Router::new();
+    router.add_route(http::Method::GET, pattern, operation_id.as_str());
+    let mut generator = router.url_generator();
+    if let Some(base_path) = ctx.base_path {
+        generator = generator.base_path(base_path);
     }
+
+    let params = params.unwrap_or_default();
+    let params: Vec<(&str, &str)> = params
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect();
+    let mut query: Vec<(String, String)> = query.unwrap_or_default().into_iter().collect();
+    query.sort();
+    let query: Vec<(&str, &str)> = query
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect();
+
+    generator
+        .url_for_with_query(&operation_id, &params, &query)
+        .map_err(|e| napi::Error::from_reason(e.to_string()))
 }
 
 #[cfg(test)]
@@ -408,4 +478,31 @@ mod tests {
 
         assert_eq!(identity.expires_at, Some(expires));
     }
+
+    #[test]
+    fn test_url_for() {
+        let mut builder = RequestContextBuilder::new();
+        builder.route("getUser".to_string(), "/users/{userId}".to_string());
+        builder.base_path("/api".to_string());
+        let ctx = builder.build();
+
+        let params = HashMap::from([("userId".to_string(), "a/b".to_string())]);
+        let query = HashMap::from([
+            ("tab".to_string(), "posts".to_string()),
+            ("page".to_string(), "2".to_string()),
+        ]);
+        assert_eq!(
+            url_for(
+                ctx.clone(),
+                "getUser".to_string(),
+                Some(params),
+                Some(query)
+            )
+            .unwrap(),
+            "/api/users/a%2Fb?page=2&tab=posts"
+        );
+
+        assert!(url_for(ctx.clone(), "getUser".to_string(), None, None).is_err());
+        assert!(url_for(ctx, "unknown".to_string(), None, None).is_err());
+    }
 }
@@ -94,7 +94,7 @@ pub use middleware_config::{
 };
 pub use response::Response;
 pub use router::{create_route_info, RouteInfo, Router};
-pub use server::Server;
+pub use server::{Server, ShutdownOptions, ShutdownReport};
 pub use telemetry::{Telemetry, TelemetryConfig};
 pub use test_client::{TestClient, TestResponse};
 pub use validation::{OperationResolution, Sentinel, ValidationError, ValidationResult};
@@ -29,11 +29,25 @@
 //!
 //! await app.listen(8080);
 //! ```
-
+//!
+//! ## Releasing hooks
+//!
+//! Shutdown hook callbacks are held as unreferenced threadsafe functions, so
+//! registering one does not keep the Node.js event loop alive. They are
+//! released once they have run, letting the process exit without
+//! `process.exit()`.
+
+use napi::bindgen_prelude::{Either, Promise};
+use napi::threadsafe_function::{ThreadSafeCallContext, ThreadsafeFunction};
+use napi::{Env, JsFunction, JsUnknown};
 use napi_derive::napi;
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::sync::RwLock;
 
+/// A JavaScript hook callback, callable from any thread.
+pub type HookCallback = ThreadsafeFunction<()>;
+
 /// A lifecycle hook entry with optional name.
 #[derive(Clone)]
 #[allow(dead_code)] // Fields used for future hook invocation
@@ -42,6 +56,8 @@ pub struct LifecycleHookEntry {
     pub name: Option<String>,
     /// The hook function placeholder (actual JS function stored separately)
     pub registered: bool,
+    /// The JavaScript callback, if one was registered
+    pub callback: Option<HookCallback>,
 }
 
 /// Manages application lifecycle hooks.
@@ -97,6 +113,7 @@ impl Lifecycle {
         hooks.push(LifecycleHookEntry {
             name: name.clone(),
             registered: true,
+            callback: None,
         });
 
         let display_name = name.unwrap_or_else(|| format!("startup_hook_{}", index));
@@ -121,17 +138,7 @@ impl Lifecycle {
     pub async fn add_shutdown(&self, name: Option<String>) -> u32 {
         let mut hooks = self.shutdown_hooks.write().await;
         let mut names = self.shutdown_names.write().await;
-
-        let index = hooks.len() as u32;
-        hooks.push(LifecycleHookEntry {
-            name: name.clone(),
-            registered: true,
-        });
-
-        let display_name = name.unwrap_or_else(|| format!("shutdown_hook_{}", index));
-        names.push(display_name);
-
-        index
+        Self::push_shutdown(&mut hooks, &mut names, name, None)
     }
 
     /// Get the number of registered startup hooks.
@@ -181,6 +188,96 @@ impl Lifecycle {
     }
 }
 
+impl Lifecycle {
+    /// Register a shutdown hook with a JavaScript callback.
+    ///
+    /// The callback is unreferenced so it does not keep the event loop
+    /// alive. Must be called from the JavaScript thread.
+    pub(crate) fn add_shutdown_callback(
+        &self,
+        env: &Env,
+        callback: &JsFunction,
+        name: Option<String>,
+    ) -> napi::Result<u32> {
+        let mut callback: HookCallback = callback
+            .create_threadsafe_function(0, |_ctx: ThreadSafeCallContext<()>| {
+                Ok(Vec::<JsUnknown>::new())
+            })?;
+        callback.unref(env)?;
+
+        // The JavaScript thread is never inside the Tokio runtime
+        let mut hooks = self.shutdown_hooks.blocking_write();
+        let mut names = self.shutdown_names.blocking_write();
+        Ok(Self::push_shutdown(
+            &mut hooks,
+            &mut names,
+            name,
+            Some(callback),
+        ))
+    }
+
+    fn push_shutdown(
+        hooks: &mut Vec<LifecycleHookEntry>,
+        names: &mut Vec<String>,
+        name: Option<String>,
+        callback: Option<HookCallback>,
+    ) -> u32 {
+        let index = hooks.len() as u32;
+        hooks.push(LifecycleHookEntry {
+            name: name.clone(),
+            registered: true,
+            callback,
+        });
+
+        let display_name = name.unwrap_or_else(|| format!("shutdown_hook_{}", index));
+        names.push(display_name);
+
+        index
+    }
+
+    /// Run the shutdown hooks in reverse registration order (LIFO).
+    ///
+    /// Each hook is awaited before the next one runs; a failing hook is
+    /// recorded and does not stop the others. Hooks run at most once: they
+    /// are removed and their callbacks released.
+    pub(crate) async fn run_shutdown_hooks(&self) -> LifecycleResult {
+        let start = Instant::now();
+        let hooks: Vec<_> = self.shutdown_hooks.write().await.drain(..).collect();
+        let names: Vec<_> = self.shutdown_names.write().await.drain(..).collect();
+
+        let mut executed = 0;
+        let mut failed = Vec::new();
+        for (hook, name) in hooks.into_iter().zip(names).rev() {
+            let Some(callback) = hook.callback else {
+                continue;
+            };
+            executed += 1;
+            if let Err(e) = call_hook(&callback).await {
+                tracing::warn!(hook = %name, error = %e, "Shutdown hook failed");
+                failed.push(name);
+            }
+        }
+
+        LifecycleResult {
+            success: failed.is_empty(),
+            executed,
+            failed,
+            duration_ms: start.elapsed().as_millis() as u32,
+        }
+    }
+}
+
+/// Calls a hook and waits for the promise it returns, if any.
+async fn call_hook(callback: &HookCallback) -> napi::Result<()> {
+    match callback
+        .call_async::<Either<Promise<()>, ()>>(Ok(()))
+        .await?
+    {
+        Either::A(promise) => promise.await,
+        Either::B(()) => Ok(()),
+    }
+}
+
 /// Configuration for a lifecycle hook.
 #[napi(object)]
 #[derive(Debug, Clone, Default)]
@@ -320,6 +417,20 @@ mod tests {
         assert_eq!(lifecycle.shutdown_count().await, 0);
     }
 
+    #[tokio::test]
+    async fn test_run_shutdown_hooks_consumes_hooks() {
+        let lifecycle = Lifecycle::new();
+        lifecycle.add_shutdown(Some("first".to_string())).await;
+        lifecycle.add_shutdown(Some("second".to_string())).await;
+
+        // Hooks without callbacks are skipped but still removed
+        let result = lifecycle.run_shutdown_hooks().await;
+        assert!(result.success);
+        assert_eq!(result.executed, 0);
+        assert!(!lifecycle.has_shutdown_hooks().await);
+        assert!(lifecycle.shutdown_names().await.is_empty());
+    }
+
     #[test]
     fn test_lifecycle_hook_options() {
         let opts = create_lifecycle_hook_options(Some("test".to_string()), Some(5000));
@@ -2,6 +2,9 @@
 
 use crate::context::RequestContext;
 use crate::response::Response;
+use archimedes_middleware::stages::scopes::{CallerScopes, DEFAULT_SCOPE_CLAIM};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
 use napi_derive::napi;
 use serde::{Deserialize, Serialize};
 
@@ -88,6 +91,17 @@ fn tracing_middleware_internal(mut ctx: RequestContext) -> MiddlewareResult {
     }
 }
 
+/// Parse granted scopes from the claims of a bearer JWT.
+///
+/// Returns `None` when the token is not a decodable JWT.
+fn jwt_scopes(token: &str) -> Option<Vec<String>> {
+    let payload = token.split('.').nth(1)?;
+    let bytes = URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+    let claims: serde_json::Value = serde_json::from_slice(&bytes).ok()?;
+    let scopes = CallerScopes::from_claims(&claims, DEFAULT_SCOPE_CLAIM);
+    Some(scopes.as_slice().to_vec())
+}
+
 /// Identity extraction middleware - extracts identity from headers.
 fn identity_middleware_internal(mut ctx: RequestContext) -> MiddlewareResult {
     use crate::context::Identity;
@@ -105,7 +119,7 @@ fn identity_middleware_internal(mut ctx: RequestContext) -> MiddlewareResult {
                     expires_at: None,
                     issued_at: None,
                     roles: None,
-                    scopes: None,
+                    scopes: jwt_scopes(token),
                     claims: None,
                 });
             }
@@ -279,6 +293,8 @@ mod tests {
             content_type: None,
             accept: None,
             custom: std::collections::HashMap::new(),
+            route_patterns: std::collections::HashMap::new(),
+            base_path: None,
         }
     }
 
@@ -340,6 +356,24 @@ mod tests {
         assert!(identity.subject.unwrap().starts_with("token:"));
     }
 
+    #[test]
+    fn test_identity_middleware_jwt_scopes() {
+        let mut ctx = test_context();
+        let payload = URL_SAFE_NO_PAD.encode(r#"{"sub":"alice","scope":"users:read admin"}"#);
+        ctx.headers.insert(
+            "authorization".to_string(),
+            format!("Bearer eyJhbGciOiJIUzI1NiJ9.{payload}.sig"),
+        );
+
+        let result = identity_middleware_internal(ctx);
+
+        let identity = result.context.identity.unwrap();
+        assert_eq!(
+            identity.scopes,
+            Some(vec!["users:read".to_string(), "admin".to_string()])
+        );
+    }
+
     #[test]
     fn test_identity_middleware_x_user_id() {
         let mut ctx = test_context();
@@ -2,15 +2,46 @@
 
 use crate::config::Config;
 use crate::handlers::HandlerRegistry;
-use crate::lifecycle::Lifecycle;
+use crate::lifecycle::{Lifecycle, LifecycleResult};
 use crate::response::Response;
 use crate::router::Router;
 use crate::telemetry::{Telemetry, TelemetryConfig};
 use crate::validation::Sentinel;
+use archimedes_server::shutdown::{wait_for_os_signal, ConnectionTracker};
+use archimedes_server::ShutdownSignal;
+use napi::{Env, JsFunction};
 use napi_derive::napi;
 use serde_json::json;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+
+/// Default time in-flight requests get to finish during shutdown.
+const DEFAULT_GRACE_PERIOD_MS: u32 = 30_000;
+
+/// Options for [`Server::shutdown`].
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct ShutdownOptions {
+    /// Time in-flight requests get to finish, in milliseconds. Defaults to
+    /// `Config.shutdownGracePeriodMs`.
+    pub grace_period_ms: Option<u32>,
+}
+
+/// Outcome of a graceful shutdown.
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct ShutdownReport {
+    /// Whether every in-flight request finished within the grace period
+    pub drained: bool,
+    /// Requests still in flight when the grace period ran out
+    pub aborted_requests: u32,
+    /// Result of running the shutdown hooks
+    pub hooks: LifecycleResult,
+    /// Total shutdown duration in milliseconds
+    pub duration_ms: u32,
+}
 
 /// Archimedes HTTP Server.
 ///
@@ -30,6 +61,9 @@ use tokio::sync::RwLock;
 /// server.onShutdown(() => console.log('Stopping...'));
 ///
 /// await server.listen(8080);
+///
+/// // Stop accepting requests and wait for in-flight ones to finish
+/// await server.shutdown({ gracePeriodMs: 10_000 });
 /// ```
 #[napi]
 #[derive(Clone)]
@@ -40,6 +74,10 @@ pub struct Server {
     sentinel: Arc<RwLock<Option<Sentinel>>>,
     telemetry: Arc<RwLock<Option<Telemetry>>>,
     running: Arc<RwLock<bool>>,
+    inflight: ConnectionTracker,
+    draining: ShutdownSignal,
+    abort: ShutdownSignal,
+    signal_task: Arc<Mutex<Option<JoinHandle<()>>>>,
 }
 
 #[napi]
@@ -54,6 +92,10 @@ impl Server {
             sentinel: Arc::new(RwLock::new(None)),
             telemetry: Arc::new(RwLock::new(None)),
             running: Arc::new(RwLock::new(false)),
+            inflight: ConnectionTracker::new(),
+            draining: ShutdownSignal::new(),
+            abort: ShutdownSignal::new(),
+            signal_task: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -142,7 +184,8 @@ impl Server {
     /// Register a shutdown hook.
     ///
     /// Shutdown hooks are executed in reverse registration order (LIFO)
-    /// when the server stops.
+    /// by [`shutdown`](Self::shutdown), after the listener has closed and
+    /// in-flight requests have drained. Async hooks are awaited in turn.
     ///
     /// ## Example
     ///
@@ -152,8 +195,13 @@ impl Server {
     /// });
     /// ```
     #[napi]
-    pub async fn on_shutdown(&self, name: Option<String>) -> u32 {
-        self.lifecycle.add_shutdown(name).await
+    pub fn on_shutdown(
+        &self,
+        env: Env,
+        hook: JsFunction,
+        name: Option<String>,
+    ) -> napi::Result<u32> {
+        self.lifecycle.add_shutdown_callback(&env, &hook, name)
     }
 
     /// Merge a router's handlers into this server.
@@ -224,13 +272,116 @@ impl Server {
         Ok(missing)
     }
 
+    /// Get the number of requests currently being processed.
+    #[napi]
+    pub fn inflight_count(&self) -> u32 {
+        self.inflight.active_connections() as u32
+    }
+
     /// Process a request (for testing or custom server implementations).
+    ///
+    /// Once [`shutdown`](Self::shutdown) has started, new requests are
+    /// refused with `503 Service Unavailable`.
     #[napi]
     pub async fn handle_request(
         &self,
         method: String,
         path: String,
         body: Option<String>,
+    ) -> napi::Result<Response> {
+        if self.draining.is_shutdown() {
+            return Ok(service_unavailable("Server is shutting down"));
+        }
+        let _inflight = self.inflight.acquire();
+
+        tokio::select! {
+            response = self.process(method, path, body) => response,
+            () = self.abort.recv() => {
+                Ok(service_unavailable("Request aborted by server shutdown"))
+            }
+        }
+    }
+
+    /// Start a graceful shutdown.
+    ///
+    /// Stops accepting requests, waits up to the grace period for in-flight
+    /// requests to finish, then runs the shutdown hooks in LIFO order. The
+    /// returned promise resolves once the hooks have completed. Requests
+    /// still running when the grace period ends are aborted with `503`.
+    ///
+    /// Calling `shutdown` again waits for in-flight requests again but does
+    /// not re-run hooks that have already run.
+    ///
+    /// ## Example
+    ///
+    /// ```typescript
+    /// process.on('SIGTERM', async () => {
+    ///   const report = await server.shutdown({ gracePeriodMs: 10_000 });
+    ///   if (!report.drained) {
+    ///     console.warn(`${report.abortedRequests} requests aborted`);
+    ///   }
+    /// });
+    /// ```
+    #[napi]
+    pub async fn shutdown(&self, options: Option<ShutdownOptions>) -> napi::Result<ShutdownReport> {
+        let start = Instant::now();
+        let grace_period_ms = options
+            .and_then(|o| o.grace_period_ms)
+            .or(self.config.shutdown_grace_period_ms)
+            .unwrap_or(DEFAULT_GRACE_PERIOD_MS);
+
+        // Close the listener
+        self.draining.trigger();
+        *self.running.write().await = false;
+        if let Some(task) = self.take_signal_task() {
+            task.abort();
+        }
+
+        // Drain in-flight requests, aborting any left at the deadline
+        let grace_period = Duration::from_millis(u64::from(grace_period_ms));
+        let drained = tokio::time::timeout(grace_period, self.inflight.wait_for_shutdown())
+            .await
+            .is_ok();
+        let aborted_requests = if drained {
+            0
+        } else {
+            let remaining = self.inflight_count();
+            tracing::warn!(
+                remaining,
+                grace_period_ms,
+                "Grace period elapsed, aborting in-flight requests"
+            );
+            self.abort.trigger();
+            remaining
+        };
+
+        let hooks = self.lifecycle.run_shutdown_hooks().await;
+
+        if let Some(telemetry) = self.telemetry.write().await.as_mut() {
+            telemetry.shutdown();
+        }
+
+        Ok(ShutdownReport {
+            drained,
+            aborted_requests,
+            hooks,
+            duration_ms: start.elapsed().as_millis() as u32,
+        })
+    }
+
+    fn take_signal_task(&self) -> Option<JoinHandle<()>> {
+        self.signal_task
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .take()
+    }
+
+    /// Run a request through middleware and its handler.
+    async fn process(
+        &self,
+        method: String,
+        path: String,
+        body: Option<String>,
     ) -> napi::Result<Response> {
         use crate::middleware::process_request;
 
@@ -250,6 +401,8 @@ impl Server {
             content_type: None,
             accept: None,
             custom: std::collections::HashMap::new(),
+            route_patterns: std::collections::HashMap::new(),
+            base_path: None,
         };
 
         // Process through middleware
@@ -260,12 +413,16 @@ impl Server {
             }));
         }
 
-        let ctx = middleware_result.context;
+        let mut ctx = middleware_result.context;
+        ctx.base_path.clone_from(&self.config.base_path);
 
         // Resolve operation from contract
+        let mut route = None;
         let operation_id = if let Some(sentinel) = self.sentinel.read().await.as_ref() {
+            ctx.route_patterns = sentinel.route_patterns();
             let resolution = sentinel.resolve_operation(method.clone(), path.clone())?;
             if resolution.found {
+                route = resolution.route_pattern;
                 Some(resolution.operation_id)
             } else {
                 None
@@ -276,16 +433,16 @@ impl Server {
 
         // Invoke handler
         if let Some(op_id) = operation_id {
-            let mut ctx = ctx;
             ctx.operation_id = Some(op_id.clone());
 
             match self.handlers.invoke(op_id.clone(), ctx).await {
                 Ok(response) => {
                     // Record telemetry
                     if let Some(telemetry) = self.telemetry.write().await.as_mut() {
+                        // Label by route pattern to keep path cardinality bounded
                         telemetry.record_request(
                             method,
-                            path,
+                            route.unwrap_or(path),
                             response.status_code(),
                             0.0, // Would measure actual duration
                         );
@@ -319,6 +476,22 @@ impl Server {
         // Mark as running
         *self.running.write().await = true;
 
+        if self.config.handle_signals.unwrap_or(false) {
+            let server = self.clone();
+            let task = tokio::spawn(async move {
+                wait_for_os_signal().await;
+                // Detach first so shutdown does not abort this task
+                drop(server.take_signal_task());
+                if let Err(e) = server.shutdown(None).await {
+                    tracing::error!(error = %e, "Graceful shutdown failed");
+                }
+            });
+            *self
+                .signal_task
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner) = Some(task);
+        }
+
         println!("Archimedes server listening on {}:{}", host, port);
 
         // In real implementation, would start hyper server
@@ -339,6 +512,63 @@ impl Server {
         Ok(())
     }
 
+    /// Get the startup diagnostics report.
+    ///
+    /// Uses the same stable field names as the Rust `Diagnostics` report:
+    /// service, version, config, contract, policy, middleware, listeners
+    /// and handler coverage. Secrets are redacted.
+    #[napi]
+    pub async fn diagnostics(&self) -> serde_json::Value {
+        use archimedes_server::{ContractInfo, Diagnostics, HandlerCoverage};
+
+        let config = &self.config;
+        let mut stages = vec!["request_id", "identity"];
+        if config.enable_authorization.unwrap_or(true) {
+            stages.push("authorization");
+        }
+        if config.enable_validation.unwrap_or(true) {
+            stages.push("request_validation");
+        }
+        if config.enable_telemetry.unwrap_or(true) {
+            stages.push("telemetry");
+        }
+        if config.enable_cors.unwrap_or(false) {
+            stages.push("cors");
+        }
+
+        let mut diagnostics = Diagnostics::new("archimedes-server", env!("CARGO_PKG_VERSION"))
+            .with_config(config)
+            .with_middleware_stages(stages)
+            .with_listener(
+                "http",
+                format!(
+                    "{}:{}",
+                    config.listen_host.as_deref().unwrap_or("0.0.0.0"),
+                    config.listen_port.unwrap_or(8080)
+                ),
+            );
+
+        if let Some(sentinel) = self.sentinel.read().await.as_ref() {
+            let (service, version) = sentinel.contract_info().unwrap_or_default();
+            diagnostics = diagnostics.with_contract(ContractInfo::new(
+                service,
+                version,
+                sentinel.operation_ids(),
+            ));
+        }
+
+        // Without a contract, the registered handlers are the only known operations
+        let handlers = self.registered_handlers().await;
+        let operations: Vec<&str> = match &diagnostics.contract {
+            Some(contract) => contract.operations.iter().map(String::as_str).collect(),
+            None => handlers.iter().map(String::as_str).collect(),
+        };
+        let coverage = HandlerCoverage::compute(operations, handlers.iter().map(String::as_str));
+
+        serde_json::to_value(diagnostics.with_handler_coverage(coverage))
+            .unwrap_or(serde_json::Value::Null)
+    }
+
     /// Get Prometheus metrics.
     #[napi]
     pub async fn metrics(&self) -> String {
@@ -350,6 +580,11 @@ impl Server {
     }
 }
 
+/// Builds a `503 Service Unavailable` response.
+fn service_unavailable(message: &str) -> Response {
+    Response::status(503).json(json!({ "error": message }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -416,6 +651,62 @@ mod tests {
         assert!(!server.is_running().await);
     }
 
+    #[tokio::test]
+    async fn test_shutdown_drains_inflight_requests() {
+        let server = Server::new(test_config());
+        server.listen(Some(9999)).await.unwrap();
+
+        // Simulate a slow request that is still running
+        let slow_request = server.inflight.acquire();
+        assert_eq!(server.inflight_count(), 1);
+
+        let shutdown = tokio::spawn({
+            let server = server.clone();
+            async move {
+                server
+                    .shutdown(Some(ShutdownOptions {
+                        grace_period_ms: Some(5_000),
+                    }))
+                    .await
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        // New requests are refused while draining
+        let response = server
+            .handle_request("GET".to_string(), "/users".to_string(), None)
+            .await
+            .unwrap();
+        assert_eq!(response.status_code(), 503);
+        assert!(!server.is_running().await);
+        assert!(!shutdown.is_finished());
+
+        // The slow request completes, letting shutdown finish
+        drop(slow_request);
+        let report = shutdown.await.unwrap().unwrap();
+        assert!(report.drained);
+        assert_eq!(report.aborted_requests, 0);
+        assert!(report.hooks.success);
+        assert_eq!(server.inflight_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_aborts_after_grace_period() {
+        let server = Server::new(test_config());
+        let _stuck_request = server.inflight.acquire();
+
+        let report = server
+            .shutdown(Some(ShutdownOptions {
+                grace_period_ms: Some(20),
+            }))
+            .await
+            .unwrap();
+
+        assert!(!report.drained);
+        assert_eq!(report.aborted_requests, 1);
+        assert!(server.abort.is_shutdown());
+    }
+
     #[tokio::test]
     async fn test_validate_handlers_empty() {
         let server = Server::new(test_config());
@@ -438,4 +729,17 @@ mod tests {
         let ops = server.available_operations().await;
         assert!(ops.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_server_diagnostics() {
+        let server = Server::new(test_config());
+        server.operation_ok("listUsers".to_string(), "{}".to_string());
+
+        let diagnostics = server.diagnostics().await;
+        assert_eq!(diagnostics["listeners"][0]["name"], "http");
+        assert_eq!(diagnostics["listeners"][0]["address"], "0.0.0.0:8080");
+        assert_eq!(diagnostics["handlers"]["handled"], 1);
+        assert!(diagnostics["contract"].is_null());
+        assert_eq!(diagnostics["config"]["listen_port"], 8080);
+    }
 }
@@ -1,6 +1,7 @@
 //! Contract validation via Sentinel.
 
 use crate::error::ArchimedesError;
+use archimedes_sentinel::{LoadedArtifact, LoadedOperation, OperationResolver};
 use napi_derive::napi;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -97,6 +98,9 @@ pub struct OperationResolution {
     /// Extracted path parameters
     pub path_params: HashMap<String, String>,
 
+    /// Route pattern that matched (e.g. `/users/{userId}`)
+    pub route_pattern: Option<String>,
+
     /// Whether the operation was found
     pub found: bool,
 }
@@ -120,7 +124,6 @@ pub struct Sentinel {
 /// Internal operation definition.
 #[derive(Debug, Clone)]
 struct Operation {
-    #[allow(dead_code)]
     operation_id: String,
     method: String,
     path_pattern: String,
@@ -219,6 +222,7 @@ impl Sentinel {
                     return Ok(OperationResolution {
                         operation_id: op.operation_id.clone(),
                         path_params: params,
+                        route_pattern: Some(op.path_pattern.clone()),
                         found: true,
                     });
                 }
@@ -228,10 +232,34 @@ impl Sentinel {
         Ok(OperationResolution {
             operation_id: String::new(),
             path_params: HashMap::new(),
+            route_pattern: None,
             found: false,
         })
     }
 
+    /// Explain how a request resolves, as JSON.
+    ///
+    /// Lists every candidate route with its per-segment results, whether
+    /// the path matched under another method, and suggestions for
+    /// near-miss paths. Useful for debugging a 404 or 405.
+    #[napi]
+    pub fn explain(&self, method: String, path: String) -> napi::Result<String> {
+        if !self.loaded {
+            return Err(napi::Error::new(
+                napi::Status::GenericFailure,
+                "Sentinel not initialized. Call init() first.",
+            ));
+        }
+
+        let resolver = OperationResolver::from_artifact(&self.artifact());
+        serde_json::to_string(&resolver.explain(&method, &path)).map_err(|e| {
+            napi::Error::new(
+                napi::Status::GenericFailure,
+                format!("Failed to serialize explanation: {}", e),
+            )
+        })
+    }
+
     /// Validate a request body against the operation schema.
     #[napi]
     pub fn validate_request(
@@ -339,6 +367,64 @@ impl Sentinel {
     }
 }
 
+impl Sentinel {
+    /// Contract title and version from the `info` object, if present.
+    pub(crate) fn contract_info(&self) -> Option<(String, String)> {
+        let contract: serde_json::Value = serde_json::from_str(&self.contract_json).ok()?;
+        let info = contract.get("info")?;
+        let title = info
+            .get("title")
+            .and_then(|t| t.as_str())
+            .unwrap_or_default();
+        let version = info
+            .get("version")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+        Some((title.to_string(), version.to_string()))
+    }
+
+    /// Artifact holding the routes of the loaded operations.
+    fn artifact(&self) -> LoadedArtifact {
+        let operations = self
+            .operations
+            .values()
+            .map(|op| LoadedOperation {
+                id: op.operation_id.clone(),
+                method: op.method.clone(),
+                path: op.path_pattern.clone(),
+                summary: None,
+                deprecated: false,
+                security: Vec::new(),
+                request_schema: None,
+                response_schemas: HashMap::new(),
+                tags: Vec::new(),
+                header_params: Vec::new(),
+                event_schemas: HashMap::new(),
+                timeout: None,
+                cache_policy: None,
+                allow_reserved: Vec::new(),
+            })
+            .collect();
+
+        LoadedArtifact {
+            service: String::new(),
+            version: String::new(),
+            format: "openapi".to_string(),
+            operations,
+            schemas: Default::default(),
+            stats: Default::default(),
+        }
+    }
+
+    /// Path pattern of every loaded operation, keyed by operation ID.
+    pub(crate) fn route_patterns(&self) -> HashMap<String, String> {
+        self.operations
+            .iter()
+            .map(|(id, op)| (id.clone(), op.path_pattern.clone()))
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -410,6 +496,28 @@ mod tests {
         assert!(!result.found);
     }
 
+    #[test]
+    fn test_explain_method_not_allowed() {
+        let mut sentinel = Sentinel::new(sample_contract());
+        sentinel.init().unwrap();
+
+        let json = sentinel
+            .explain("PATCH".to_string(), "/users/123".to_string())
+            .unwrap();
+        let explanation: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(explanation["decision"]["outcome"], "method_not_allowed");
+        assert_eq!(
+            explanation["decision"]["allowed"],
+            serde_json::json!(["DELETE", "GET", "PUT"])
+        );
+
+        let json = sentinel
+            .explain("GET".to_string(), "/user".to_string())
+            .unwrap();
+        let explanation: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(explanation["suggestions"][0]["operation_id"], "listUsers");
+    }
+
     #[test]
     fn test_validate_request_valid() {
         let mut sentinel = Sentinel::new(sample_contract());
@@ -23,6 +23,7 @@ from archimedes._archimedes import (
     Response,
     RequestContext,
     Identity,
+    TaskHandle,
     ArchimedesError,
 )
 
@@ -32,6 +33,7 @@ __all__ = [
     "Response",
     "RequestContext",
     "Identity",
+    "TaskHandle",
     "ArchimedesError",
 ]
 
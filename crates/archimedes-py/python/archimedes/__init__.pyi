@@ -1,6 +1,6 @@
 """Type stubs for Archimedes Python bindings."""
 
-from typing import Any, Awaitable, Callable, Optional
+from typing import Any, Awaitable, Callable, Coroutine, Generator, Literal, Optional, TypeVar
 
 class Identity:
     """Caller identity from authentication."""
@@ -91,6 +91,19 @@ class RequestContext:
         """OpenTelemetry span ID."""
         ...
 
+    def url_for(
+        self,
+        operation_id: str,
+        params: Optional[dict[str, Any]] = None,
+        query: Optional[dict[str, Any]] = None,
+    ) -> str:
+        """Build the URL of another operation.
+
+        Raises ValueError if the operation has no route or path parameters
+        are missing or unexpected.
+        """
+        ...
+
 
 class Response:
     """HTTP response returned from handlers."""
@@ -207,7 +220,31 @@ class Config:
         ...
 
 
+class TaskHandle:
+    """Handle to a background task started with ``App.spawn``."""
+    
+    name: str
+    id: str
+    
+    def cancel(self) -> None:
+        """Cancel the task."""
+        ...
+    
+    def done(self) -> bool:
+        """Check if the task has finished."""
+        ...
+    
+    def result(self) -> Any:
+        """Wait for the task and return its result (blocking)."""
+        ...
+    
+    def __await__(self) -> Generator[Any, None, Any]:
+        """Await the task's result."""
+        ...
+
+
 HandlerFunc = Callable[[RequestContext], Response | dict[str, Any] | Awaitable[Response | dict[str, Any]]]
+JobFunc = TypeVar("JobFunc", bound=Callable[[], Any])
 
 
 class App:
@@ -232,6 +269,32 @@ class App:
     async def run_async(self) -> None:
         """Run the application asynchronously."""
         ...
+    
+    def spawn(self, name: str, coroutine: Coroutine[Any, Any, Any]) -> TaskHandle:
+        """Spawn a coroutine as a background task."""
+        ...
+    
+    def scheduled(
+        self,
+        cron: str,
+        name: Optional[str] = None,
+        overlap: Literal["allow", "skip"] = "allow",
+    ) -> Callable[[JobFunc], JobFunc]:
+        """Decorator to register a cron-scheduled job."""
+        ...
+    
+    def interval(
+        self,
+        seconds: float,
+        name: Optional[str] = None,
+        overlap: Literal["allow", "skip"] = "allow",
+    ) -> Callable[[JobFunc], JobFunc]:
+        """Decorator to register a job running at a fixed interval."""
+        ...
+    
+    def task_stats(self) -> dict[str, Any]:
+        """Get background task and scheduled job statistics."""
+        ...
 
 
 class ArchimedesError(Exception):
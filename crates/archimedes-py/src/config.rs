@@ -253,6 +253,23 @@ impl PyConfig {
 }
 
 impl PyConfig {
+    /// Snapshot of the configuration as JSON, for diagnostics reports
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "contract_path": self.contract_path,
+            "listen_port": self.listen_port,
+            "listen_addr": self.listen_addr,
+            "enable_telemetry": self.enable_telemetry,
+            "log_level": self.log_level,
+            "service_name": self.service_name,
+            "opa_bundle_url": self.opa_bundle_url,
+            "enable_validation": self.enable_validation,
+            "enable_authorization": self.enable_authorization,
+            "max_body_size": self.max_body_size,
+            "request_timeout_secs": self.request_timeout_secs,
+        })
+    }
+
     /// Get listen address
     pub fn listen_addr(&self) -> &str {
         &self.listen_addr
@@ -625,4 +642,30 @@ mod tests {
             assert!(repr.contains("localhost"));
         });
     }
+
+    #[test]
+    fn test_config_to_json() {
+        pyo3::prepare_freethreaded_python();
+
+        Python::with_gil(|_py| {
+            let config = PyConfig::new(
+                "contract.json".to_string(),
+                8080,
+                "localhost".to_string(),
+                false,
+                "info".to_string(),
+                "test".to_string(),
+                None,
+                true,
+                true,
+                1_048_576,
+                30,
+            );
+
+            let json = config.to_json();
+            assert_eq!(json["listen_port"], 8080);
+            assert_eq!(json["service_name"], "test");
+            assert_eq!(json["enable_authorization"], true);
+        });
+    }
 }
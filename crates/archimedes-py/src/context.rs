@@ -1,5 +1,6 @@
 //! Python request context types for Archimedes
 
+use archimedes_core::{UrlForError, UrlGenerator};
 use pyo3::prelude::*;
 use pyo3::types::{PyDict, PyList};
 use std::collections::HashMap;
@@ -53,6 +54,9 @@ pub struct PyRequestContext {
 
     /// Identity information (if authenticated)
     identity: Option<PyIdentity>,
+
+    /// Generator for URLs of other operations
+    url_generator: Option<UrlGenerator>,
 }
 
 #[pymethods]
@@ -128,6 +132,28 @@ impl PyRequestContext {
         self.identity.as_ref().map(|i| i.subject.clone())
     }
 
+    /// Build the URL of another operation
+    ///
+    /// Path parameter values are percent-encoded. Raises `ValueError` if
+    /// the operation has no route or parameters are missing or unexpected.
+    ///
+    /// ```python,ignore
+    /// location = ctx.url_for("getUser", {"userId": "42"})
+    /// next_page = ctx.url_for("listUsers", query={"page": "2"})
+    /// ```
+    #[pyo3(signature = (operation_id, params=None, query=None))]
+    fn url_for(
+        &self,
+        operation_id: &str,
+        params: Option<&Bound<'_, PyDict>>,
+        query: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<String> {
+        let params = dict_to_pairs(params)?;
+        let query = dict_to_pairs(query)?;
+        self.url_for_rs(operation_id, &as_str_pairs(&params), &as_str_pairs(&query))
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
     /// String representation
     fn __repr__(&self) -> String {
         format!(
@@ -178,6 +204,7 @@ impl PyRequestContext {
             trace_id,
             span_id,
             identity,
+            url_generator: None,
         }
     }
 
@@ -193,9 +220,30 @@ impl PyRequestContext {
             trace_id: "test-trace-id".to_string(),
             span_id: "test-span-id".to_string(),
             identity: None,
+            url_generator: None,
         }
     }
 
+    /// Set the generator used by `url_for`
+    #[must_use]
+    pub fn with_url_generator(mut self, url_generator: UrlGenerator) -> Self {
+        self.url_generator = Some(url_generator);
+        self
+    }
+
+    /// Build the URL of another operation (Rust-native helper)
+    pub fn url_for_rs(
+        &self,
+        operation_id: &str,
+        params: &[(&str, &str)],
+        query: &[(&str, &str)],
+    ) -> Result<String, UrlForError> {
+        self.url_generator
+            .as_ref()
+            .ok_or(UrlForError::Unavailable)?
+            .url_for_with_query(operation_id, params, query)
+    }
+
     /// Check if the request is authenticated (Rust-native helper)
     pub fn is_authenticated_rs(&self) -> bool {
         self.identity.is_some()
@@ -245,6 +293,9 @@ pub struct PyIdentity {
 
     /// Permissions
     permissions: Vec<String>,
+
+    /// Scopes granted to the identity
+    scopes: Vec<String>,
 }
 
 #[pymethods]
@@ -289,6 +340,17 @@ impl PyIdentity {
         self.permissions.iter().any(|p| p == permission)
     }
 
+    /// Get granted scopes
+    #[getter]
+    fn scopes(&self) -> Vec<String> {
+        self.scopes.clone()
+    }
+
+    /// Check if identity has been granted a scope
+    fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == scope)
+    }
+
     /// Check if the token is expired
     fn is_expired(&self) -> bool {
         if let Some(exp) = self.expires_at {
@@ -317,12 +379,16 @@ impl PyIdentity {
         dict.set_item("claims", self.claims(py)?)?;
         dict.set_item("roles", &self.roles)?;
         dict.set_item("permissions", &self.permissions)?;
+        dict.set_item("scopes", &self.scopes)?;
         Ok(dict.into())
     }
 }
 
 impl PyIdentity {
     /// Create a new identity
+    ///
+    /// Scopes are parsed from the `scope` (space-separated) or `scp` (array)
+    /// claim when present.
     pub fn new(
         subject: String,
         issuer: Option<String>,
@@ -332,6 +398,7 @@ impl PyIdentity {
         roles: Vec<String>,
         permissions: Vec<String>,
     ) -> Self {
+        let scopes = scopes_from_claims(&claims);
         Self {
             subject,
             issuer,
@@ -340,9 +407,16 @@ impl PyIdentity {
             claims,
             roles,
             permissions,
+            scopes,
         }
     }
 
+    /// Set the granted scopes, replacing any parsed from claims
+    pub fn with_scopes(mut self, scopes: Vec<String>) -> Self {
+        self.scopes = scopes;
+        self
+    }
+
     /// Check if identity has a claim (Rust-native helper for testing)
     pub fn has_claim(&self, name: &str) -> bool {
         self.claims.contains_key(name)
@@ -390,6 +464,46 @@ impl PyIdentity {
     pub fn permissions_rs(&self) -> &[String] {
         &self.permissions
     }
+
+    /// Get scopes (Rust-native helper for testing)
+    pub fn scopes_rs(&self) -> &[String] {
+        &self.scopes
+    }
+}
+
+/// Collect a Python dict of strings into ordered pairs
+fn dict_to_pairs(dict: Option<&Bound<'_, PyDict>>) -> PyResult<Vec<(String, String)>> {
+    dict.map_or_else(
+        || Ok(Vec::new()),
+        |dict| {
+            dict.iter()
+                .map(|(k, v)| -> PyResult<(String, String)> {
+                    Ok((k.extract()?, v.str()?.to_string()))
+                })
+                .collect()
+        },
+    )
+}
+
+/// Borrow owned pairs as string slices
+fn as_str_pairs(pairs: &[(String, String)]) -> Vec<(&str, &str)> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect()
+}
+
+/// Parse granted scopes from the `scope` or `scp` claim
+fn scopes_from_claims(claims: &HashMap<String, serde_json::Value>) -> Vec<String> {
+    match claims.get("scope").or_else(|| claims.get("scp")) {
+        Some(serde_json::Value::String(s)) => s.split_whitespace().map(String::from).collect(),
+        Some(serde_json::Value::Array(items)) => items
+            .iter()
+            .filter_map(|v| v.as_str())
+            .map(String::from)
+            .collect(),
+        _ => Vec::new(),
+    }
 }
 
 /// Convert serde_json::Value to Python object
@@ -680,6 +794,29 @@ mod tests {
         assert!(!identity.has_role_rs("owner"));
     }
 
+    #[test]
+    fn test_identity_scopes_from_scope_claim() {
+        let mut claims = HashMap::new();
+        claims.insert(
+            "scope".to_string(),
+            serde_json::json!("users:read users:write"),
+        );
+        let identity =
+            PyIdentity::new("user".to_string(), None, None, None, claims, vec![], vec![]);
+
+        assert_eq!(identity.scopes_rs(), &["users:read", "users:write"]);
+    }
+
+    #[test]
+    fn test_identity_scopes_from_scp_claim() {
+        let mut claims = HashMap::new();
+        claims.insert("scp".to_string(), serde_json::json!(["admin"]));
+        let identity =
+            PyIdentity::new("user".to_string(), None, None, None, claims, vec![], vec![]);
+
+        assert_eq!(identity.scopes_rs(), &["admin"]);
+    }
+
     #[test]
     fn test_identity_multiple_permissions() {
         let identity = PyIdentity::new(
@@ -822,4 +959,22 @@ mod tests {
         assert!(repr.contains("POST"));
         assert!(repr.contains("/api/users"));
     }
+
+    #[test]
+    fn test_request_context_url_for() {
+        let ctx = PyRequestContext::test("listUsers");
+        assert_eq!(
+            ctx.url_for_rs("getUser", &[("userId", "1")], &[]),
+            Err(UrlForError::Unavailable)
+        );
+
+        let mut router = archimedes_server::Router::new();
+        router.add_route(http::Method::GET, "/users/{userId}", "getUser");
+        let ctx = ctx.with_url_generator(router.url_generator().base_path("/api"));
+        assert_eq!(
+            ctx.url_for_rs("getUser", &[("userId", "a b")], &[("tab", "posts")])
+                .unwrap(),
+            "/api/users/a%20b?tab=posts"
+        );
+    }
 }
@@ -153,7 +153,7 @@ impl Default for HandlerRegistry {
 }
 
 /// Convert serde_json::Value to Python object
-fn json_to_python(py: Python<'_>, value: &serde_json::Value) -> PyResult<PyObject> {
+pub(crate) fn json_to_python(py: Python<'_>, value: &serde_json::Value) -> PyResult<PyObject> {
     Ok(match value {
         serde_json::Value::Null => py.None(),
         serde_json::Value::Bool(b) => b.into_pyobject(py)?.to_owned().into_any().unbind(),
@@ -44,6 +44,7 @@ mod middleware_config;
 mod response;
 mod router;
 mod server;
+mod tasks;
 mod telemetry;
 mod test_client;
 mod validation;
@@ -67,6 +68,7 @@ pub use middleware_config::{
 pub use response::{PyFileResponse, PyResponse};
 pub use router::PyRouter;
 pub use server::{PyServer, ServerError};
+pub use tasks::{PyTaskHandle, ScheduleDecorator, TaskBridge};
 pub use telemetry::{py_record_request, py_render_metrics, PyTelemetry, PyTelemetryConfig};
 pub use test_client::{PyTestClient, PyTestResponse};
 pub use validation::{PyOperationResolution, PySentinel, PyValidationError, PyValidationResult};
@@ -80,6 +82,7 @@ pub struct PyApp {
     config: PyConfig,
     handlers: Arc<HandlerRegistry>,
     lifecycle: Arc<RwLock<PyLifecycle>>,
+    tasks: Arc<TaskBridge>,
     running: bool,
 }
 
@@ -105,6 +108,7 @@ impl PyApp {
             config,
             handlers: Arc::new(HandlerRegistry::new()),
             lifecycle: Arc::new(RwLock::new(PyLifecycle::new())),
+            tasks: Arc::new(TaskBridge::new()),
             running: false,
         }
     }
@@ -182,6 +186,70 @@ impl PyApp {
         ShutdownDecorator::new(name, Arc::clone(&self.lifecycle))
     }
 
+    /// Spawn a coroutine as a background task
+    ///
+    /// Returns a `TaskHandle` that can be awaited for the result or
+    /// cancelled. Tasks still running at shutdown are drained.
+    ///
+    /// # Example (Python)
+    ///
+    /// ```python,ignore
+    /// handle = app.spawn("send-welcome", send_welcome_email(user_id))
+    /// result = await handle
+    /// ```
+    fn spawn(&self, py: Python<'_>, name: String, coroutine: PyObject) -> PyResult<PyTaskHandle> {
+        self.tasks.spawn(py, name, coroutine)
+    }
+
+    /// Register a cron-scheduled job
+    ///
+    /// Uses the 6-field cron format with seconds. With `overlap="skip"`, a
+    /// run is skipped while the previous one is still in flight.
+    ///
+    /// # Example (Python)
+    ///
+    /// ```python,ignore
+    /// @app.scheduled("0 0 3 * * *", overlap="skip")
+    /// async def nightly_cleanup():
+    ///     await purge_expired_sessions()
+    /// ```
+    #[pyo3(signature = (cron, name=None, overlap="allow"))]
+    fn scheduled(
+        &self,
+        cron: String,
+        name: Option<String>,
+        overlap: &str,
+    ) -> PyResult<ScheduleDecorator> {
+        ScheduleDecorator::cron(Arc::clone(&self.tasks), cron, name, overlap)
+    }
+
+    /// Register a job running at a fixed interval
+    ///
+    /// # Example (Python)
+    ///
+    /// ```python,ignore
+    /// @app.interval(seconds=30)
+    /// async def refresh_cache():
+    ///     await cache.refresh()
+    /// ```
+    #[pyo3(signature = (seconds, name=None, overlap="allow"))]
+    fn interval(
+        &self,
+        seconds: f64,
+        name: Option<String>,
+        overlap: &str,
+    ) -> PyResult<ScheduleDecorator> {
+        ScheduleDecorator::interval(Arc::clone(&self.tasks), seconds, name, overlap)
+    }
+
+    /// Get background task and scheduled job statistics
+    ///
+    /// Returns a dict with `tasks` counters and a `jobs` list including
+    /// each job's run count, failure count and last error.
+    fn task_stats(&self, py: Python<'_>) -> PyResult<PyObject> {
+        handlers::json_to_python(py, &self.tasks.stats_json())
+    }
+
     /// Nest a router at a path prefix
     ///
     /// All routes from the nested router will be available under the given prefix.
@@ -257,6 +325,11 @@ impl PyApp {
             .parse()
             .map_err(|e| PyArchimedesError::new_err(format!("Invalid address: {e}")))?;
 
+        if let Err(e) = self.tasks.start() {
+            self.running = false;
+            return Err(e);
+        }
+
         // Release the GIL while running the server
         let result = py.allow_threads(|| {
             let rt = tokio::runtime::Runtime::new()
@@ -268,6 +341,10 @@ impl PyApp {
             })
         });
 
+        // Drain background tasks and scheduled jobs
+        let tasks = Arc::clone(&self.tasks);
+        py.allow_threads(move || tasks.shutdown());
+
         self.running = false;
 
         result.map_err(|e| PyArchimedesError::new_err(e))
@@ -348,6 +425,66 @@ impl PyApp {
     fn version() -> &'static str {
         env!("CARGO_PKG_VERSION")
     }
+
+    /// Get the startup diagnostics report
+    ///
+    /// Returns a dict with the same stable field names as the Rust
+    /// `Diagnostics` report: service, version, config, contract, policy,
+    /// middleware, listeners and handler coverage. Secrets are redacted.
+    fn diagnostics(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let report = serde_json::to_value(self.build_diagnostics())
+            .map_err(|e| PyArchimedesError::new_err(e.to_string()))?;
+        handlers::json_to_python(py, &report)
+    }
+}
+
+impl PyApp {
+    /// Builds the diagnostics report from the configuration, the contract
+    /// file and the registered handlers.
+    fn build_diagnostics(&self) -> archimedes_server::Diagnostics {
+        use archimedes_server::{ContractInfo, Diagnostics, HandlerCoverage};
+
+        let config = &self.config;
+        let mut stages = vec!["request_id", "identity"];
+        if config.enable_authorization {
+            stages.push("authorization");
+        }
+        if config.enable_validation {
+            stages.push("request_validation");
+        }
+        if config.enable_telemetry {
+            stages.push("telemetry");
+        }
+
+        let mut diagnostics = Diagnostics::new(&config.service_name, env!("CARGO_PKG_VERSION"))
+            .with_config(&config.to_json())
+            .with_middleware_stages(stages)
+            .with_listener(
+                "http",
+                format!("{}:{}", config.listen_addr, config.listen_port),
+            );
+
+        let artifact = config
+            .contract_path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| archimedes_sentinel::ArtifactLoader::from_json(&json).ok());
+        if let Some(artifact) = artifact {
+            diagnostics = diagnostics.with_contract(ContractInfo::new(
+                artifact.service,
+                artifact.version,
+                artifact.operations.iter().map(|op| op.id.as_str()),
+            ));
+        }
+
+        // Without a contract, the registered handlers are the only known operations
+        let handlers = self.handlers.operation_ids();
+        let operations: Vec<&str> = match &diagnostics.contract {
+            Some(contract) => contract.operations.iter().map(String::as_str).collect(),
+            None => handlers.iter().map(String::as_str).collect(),
+        };
+        let coverage = HandlerCoverage::compute(operations, handlers.iter().map(String::as_str));
+        diagnostics.with_handler_coverage(coverage)
+    }
 }
 
 /// Decorator helper for registering handlers
@@ -414,9 +551,10 @@ fn archimedes_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
 
     // Lifecycle decorators
     m.add_class::<StartupDecorator>()?;
+    m.add_class::<ScheduleDecorator>()?;
+    m.add_class::<PyTaskHandle>()?;
     m.add_class::<ShutdownDecorator>()?;
 
-
     // Telemetry functions
     m.add_function(wrap_pyfunction!(py_record_request, m)?)?;
     m.add_function(wrap_pyfunction!(py_render_metrics, m)?)?;
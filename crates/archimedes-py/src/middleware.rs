@@ -189,32 +189,43 @@ fn extract_identity(headers: &HeaderMap) -> Option<PyIdentity> {
                 })
                 .unwrap_or_default();
 
-            Some(PyIdentity::new(
-                subject,
-                None,
-                None,
-                None,
-                HashMap::new(),
-                roles,
-                Vec::new(),
-            ))
+            Some(
+                PyIdentity::new(subject, None, None, None, HashMap::new(), roles, Vec::new())
+                    .with_scopes(string_array(&json, "scopes")),
+            )
         }
         "api_key" => {
             let subject = json.get("key_id").and_then(|v| v.as_str())?.to_string();
-            Some(PyIdentity::new(
-                subject,
-                None,
-                None,
-                None,
-                HashMap::new(),
-                Vec::new(),
-                Vec::new(),
-            ))
+            Some(
+                PyIdentity::new(
+                    subject,
+                    None,
+                    None,
+                    None,
+                    HashMap::new(),
+                    Vec::new(),
+                    Vec::new(),
+                )
+                .with_scopes(string_array(&json, "scopes")),
+            )
         }
         _ => None,
     }
 }
 
+/// Read an array of strings from a JSON object field
+fn string_array(json: &serde_json::Value, field: &str) -> Vec<String> {
+    json.get(field)
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str())
+                .map(|s| s.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 /// Convert HeaderMap to HashMap for Python
 fn headers_to_map(headers: &HeaderMap) -> HashMap<String, String> {
     let mut map = HashMap::new();
@@ -389,6 +400,19 @@ mod tests {
         assert_eq!(identity.subject, "key-abc123");
     }
 
+    #[test]
+    fn test_extract_identity_scopes() {
+        let mut headers = HeaderMap::new();
+        let identity_json = r#"{"type":"api_key","key_id":"key-abc123","scopes":["orders:read"]}"#;
+        headers.insert(
+            headers::CALLER_IDENTITY,
+            HeaderValue::from_str(identity_json).unwrap(),
+        );
+
+        let identity = extract_identity(&headers).unwrap();
+        assert_eq!(identity.scopes_rs(), &["orders:read"]);
+    }
+
     #[test]
     fn test_no_identity_when_header_missing() {
         let headers = HeaderMap::new();
@@ -0,0 +1,690 @@
+//! Background tasks and scheduled jobs for Python applications
+//!
+//! Bridges `archimedes-tasks` to Python so services don't need a separate
+//! job runner for periodic work:
+//!
+//! ```python,ignore
+//! from archimedes import App
+//!
+//! app = App(config)
+//!
+//! @app.scheduled("0 0 3 * * *")
+//! async def nightly_cleanup():
+//!     await purge_expired_sessions()
+//!
+//! @app.interval(seconds=30, overlap="skip")
+//! async def refresh_cache():
+//!     await cache.refresh()
+//!
+//! async def send_report(user_id):
+//!     ...
+//!
+//! handle = app.spawn("send-report", send_report("u-1"))
+//! await handle          # result of the coroutine
+//! handle.cancel()       # or cancel it
+//!
+//! app.task_stats()      # {"tasks": {...}, "jobs": [...]}
+//! ```
+//!
+//! Every invocation runs as a fresh asyncio task on its own event loop in a
+//! worker thread, so jobs never block the server. Exceptions raised by a
+//! scheduled job are logged with the job name and recorded in the job's
+//! `fail_count` and `last_error`. The scheduler starts with the application
+//! and drains in-flight tasks and jobs on shutdown.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use archimedes_tasks::{
+    JobInfo, JobSpec, OverlapPolicy, Scheduler, SchedulerConfig, SharedSpawner, SpawnerConfig,
+    TaskError, TaskHandle, TaskStats,
+};
+use pyo3::exceptions::asyncio::CancelledError;
+use pyo3::exceptions::{PyTimeoutError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::IntoPyDict;
+use serde_json::json;
+
+use crate::error::PyArchimedesError;
+
+/// How long shutdown waits for in-flight tasks and jobs
+const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(30);
+
+/// Event loop and asyncio task of a running coroutine
+///
+/// Lets another thread cancel the coroutine on the loop it runs on.
+#[derive(Default)]
+struct RunningCoroutine {
+    /// `(event_loop, task)` while the coroutine runs
+    slot: Mutex<Option<(PyObject, PyObject)>>,
+    /// Set once cancellation was requested
+    cancelled: AtomicBool,
+}
+
+impl RunningCoroutine {
+    /// Request cancellation, delivering it to the event loop if running
+    fn cancel(&self, py: Python<'_>) -> PyResult<()> {
+        self.cancelled.store(true, Ordering::Release);
+        // Don't hold the lock while calling into Python, which may release the GIL
+        let slot = self
+            .slot
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|(event_loop, task)| (event_loop.clone_ref(py), task.clone_ref(py)));
+        if let Some((event_loop, task)) = slot {
+            let cancel = task.getattr(py, "cancel")?;
+            // Fails only if the loop closed in the meantime, i.e. the task finished
+            let _ = event_loop.call_method1(py, "call_soon_threadsafe", (cancel,));
+        }
+        Ok(())
+    }
+}
+
+/// Run an awaitable to completion as a fresh task on a new event loop
+///
+/// Plain values (from sync callables) are returned as-is.
+fn run_awaitable(
+    py: Python<'_>,
+    value: Bound<'_, PyAny>,
+    running: Option<&RunningCoroutine>,
+) -> PyResult<PyObject> {
+    let inspect = py.import("inspect")?;
+    if !inspect
+        .call_method1("isawaitable", (&value,))?
+        .extract::<bool>()?
+    {
+        return Ok(value.unbind());
+    }
+
+    let asyncio = py.import("asyncio")?;
+    let event_loop = asyncio.call_method0("new_event_loop")?;
+    let kwargs = [("loop", &event_loop)].into_py_dict(py)?;
+    let task = asyncio.call_method("ensure_future", (&value,), Some(&kwargs))?;
+
+    if let Some(running) = running {
+        *running.slot.lock().unwrap() = Some((event_loop.clone().unbind(), task.clone().unbind()));
+        if running.cancelled.load(Ordering::Acquire) {
+            task.call_method0("cancel")?;
+        }
+    }
+
+    let result = event_loop.call_method1("run_until_complete", (&task,));
+
+    if let Some(running) = running {
+        running.slot.lock().unwrap().take();
+    }
+    event_loop.call_method0("close")?;
+
+    result.map(Bound::unbind)
+}
+
+/// Call a job function and run its coroutine, describing any exception
+fn invoke_job(func: &PyObject) -> Result<(), String> {
+    Python::with_gil(|py| {
+        func.call0(py)
+            .and_then(|value| run_awaitable(py, value.into_bound(py), None))
+            .map(drop)
+            .map_err(|e| e.to_string())
+    })
+}
+
+/// Convert a task error to a Python exception
+fn task_error(e: TaskError) -> PyErr {
+    match e {
+        TaskError::Cancelled(reason) => CancelledError::new_err(reason),
+        TaskError::Timeout(_) => PyTimeoutError::new_err(e.to_string()),
+        other => PyArchimedesError::new_err(other.to_string()),
+    }
+}
+
+/// Parse an overlap policy name
+fn parse_overlap(overlap: &str) -> PyResult<OverlapPolicy> {
+    match overlap {
+        "allow" => Ok(OverlapPolicy::Allow),
+        "skip" => Ok(OverlapPolicy::Skip),
+        other => Err(PyValueError::new_err(format!(
+            "Invalid overlap policy '{other}', expected 'allow' or 'skip'"
+        ))),
+    }
+}
+
+/// Derive a job name from the decorated function
+fn callable_name(py: Python<'_>, func: &PyObject) -> String {
+    func.getattr(py, "__name__")
+        .and_then(|n| n.extract::<String>(py))
+        .unwrap_or_else(|_| "anonymous_job".to_string())
+}
+
+/// Background task spawner and job scheduler shared by an application
+pub struct TaskBridge {
+    /// Runtime executing tasks and the scheduler loop
+    runtime: OnceLock<tokio::runtime::Runtime>,
+    /// Scheduler running `@app.scheduled` and `@app.interval` jobs
+    scheduler: Scheduler,
+    /// Spawner running `app.spawn` tasks
+    spawner: SharedSpawner,
+}
+
+impl TaskBridge {
+    /// Create a bridge with the default scheduler configuration
+    pub fn new() -> Self {
+        Self::with_config(SchedulerConfig::default())
+    }
+
+    /// Create a bridge with a custom scheduler configuration
+    pub fn with_config(config: SchedulerConfig) -> Self {
+        Self {
+            runtime: OnceLock::new(),
+            scheduler: Scheduler::with_config(config),
+            spawner: SharedSpawner::with_config(SpawnerConfig::new().without_timeout()),
+        }
+    }
+
+    /// Get the runtime, creating it on first use
+    fn runtime(&self) -> PyResult<&tokio::runtime::Runtime> {
+        if let Some(runtime) = self.runtime.get() {
+            return Ok(runtime);
+        }
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .thread_name("archimedes-tasks")
+            .enable_all()
+            .build()
+            .map_err(|e| PyArchimedesError::new_err(format!("Failed to create runtime: {e}")))?;
+        Ok(self.runtime.get_or_init(|| runtime))
+    }
+
+    /// Spawn a coroutine as a background task
+    pub fn spawn(
+        &self,
+        py: Python<'_>,
+        name: String,
+        coroutine: PyObject,
+    ) -> PyResult<PyTaskHandle> {
+        let is_coroutine = py
+            .import("inspect")?
+            .call_method1("iscoroutine", (&coroutine,))?
+            .extract::<bool>()?;
+        if !is_coroutine {
+            return Err(PyTypeError::new_err(
+                "spawn() expects a coroutine object, e.g. app.spawn(\"name\", job())",
+            ));
+        }
+
+        let running = Arc::new(RunningCoroutine::default());
+        let task_running = Arc::clone(&running);
+        let watch = Arc::clone(&running);
+        let task_name = name.clone();
+        let runtime = self.runtime()?;
+        let _guard = runtime.enter();
+
+        let handle = self
+            .spawner
+            .spawn(name.clone(), async move {
+                let outcome = tokio::task::spawn_blocking(move || {
+                    Python::with_gil(|py| {
+                        run_awaitable(py, coroutine.into_bound(py), Some(&task_running))
+                    })
+                })
+                .await
+                .unwrap_or_else(|e| {
+                    Err(PyArchimedesError::new_err(format!(
+                        "Task '{task_name}' panicked: {e}"
+                    )))
+                });
+                if let Err(e) = &outcome {
+                    // Cancellation is requested by the caller, not a failure
+                    if !watch.cancelled.load(Ordering::Acquire) {
+                        tracing::error!(task_name = %task_name, error = %e, "Background task failed");
+                    }
+                }
+                outcome
+            })
+            .map_err(task_error)?;
+
+        Ok(PyTaskHandle {
+            name,
+            id: handle.id().to_string(),
+            state: Arc::new(tokio::sync::Mutex::new(JoinState {
+                handle: Some(handle),
+                outcome: None,
+            })),
+            running,
+            runtime: runtime.handle().clone(),
+        })
+    }
+
+    /// Register a Python function as a scheduled job
+    pub fn register_job(&self, spec: JobSpec, func: PyObject) -> PyResult<()> {
+        let func = Arc::new(func);
+        self.scheduler
+            .register_job(spec, move || {
+                let func = Arc::clone(&func);
+                async move {
+                    tokio::task::spawn_blocking(move || invoke_job(&func))
+                        .await
+                        .unwrap_or_else(|e| Err(format!("job panicked: {e}")))
+                }
+            })
+            .map_err(task_error)?;
+        Ok(())
+    }
+
+    /// Start the scheduler
+    ///
+    /// Starting an already running scheduler is a no-op.
+    pub fn start(&self) -> PyResult<()> {
+        if self.scheduler.is_running() {
+            return Ok(());
+        }
+        let _guard = self.runtime()?.enter();
+        self.scheduler.start().map_err(task_error)
+    }
+
+    /// Stop the scheduler and wait for in-flight tasks and jobs to finish
+    pub fn shutdown(&self) {
+        if let Some(runtime) = self.runtime.get() {
+            runtime.block_on(async {
+                self.scheduler.stop().await;
+                self.spawner.inner().shutdown(SHUTDOWN_GRACE_PERIOD).await;
+            });
+        }
+    }
+
+    /// Check if the scheduler is running
+    pub fn is_running(&self) -> bool {
+        self.scheduler.is_running()
+    }
+
+    /// Get the registered jobs
+    pub fn jobs(&self) -> Vec<JobInfo> {
+        self.scheduler.list_jobs()
+    }
+
+    /// Statistics for spawned tasks and scheduled jobs as JSON
+    pub fn stats_json(&self) -> serde_json::Value {
+        let jobs: Vec<serde_json::Value> = self.jobs().iter().map(job_json).collect();
+        json!({
+            "tasks": stats_json(self.spawner.inner().stats()),
+            "jobs": jobs,
+        })
+    }
+}
+
+impl Default for TaskBridge {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Convert task statistics to JSON
+fn stats_json(stats: &TaskStats) -> serde_json::Value {
+    json!({
+        "spawned": stats.total_spawned(),
+        "completed": stats.total_completed(),
+        "failed": stats.total_failed(),
+        "cancelled": stats.total_cancelled(),
+        "timed_out": stats.total_timed_out(),
+        "escalated": stats.total_escalated(),
+        "running": stats.currently_running(),
+    })
+}
+
+/// Convert job info to JSON
+fn job_json(job: &JobInfo) -> serde_json::Value {
+    json!({
+        "id": job.id.to_string(),
+        "name": job.name,
+        "schedule": job.cron,
+        "enabled": job.enabled,
+        "overlap": job.overlap.to_string(),
+        "last_run": job.last_run.map(|t| t.to_rfc3339()),
+        "next_run": job.next_run.map(|t| t.to_rfc3339()),
+        "run_count": job.run_count,
+        "fail_count": job.fail_count,
+        "last_error": job.last_error,
+    })
+}
+
+/// Join state of a spawned task
+struct JoinState {
+    /// Handle until the task is joined
+    handle: Option<TaskHandle<PyResult<PyObject>>>,
+    /// Outcome once joined
+    outcome: Option<PyResult<PyObject>>,
+}
+
+/// Handle to a task started with `app.spawn`
+///
+/// Awaiting the handle returns the coroutine's result or raises its
+/// exception; a cancelled task raises `asyncio.CancelledError` and one that
+/// exceeded its timeout raises `TimeoutError`.
+#[pyclass(name = "TaskHandle")]
+pub struct PyTaskHandle {
+    /// Task name
+    name: String,
+    /// Task ID
+    id: String,
+    /// Join state, locked while a caller waits for the result
+    state: Arc<tokio::sync::Mutex<JoinState>>,
+    /// The running coroutine, for cancellation
+    running: Arc<RunningCoroutine>,
+    /// Runtime the task runs on
+    runtime: tokio::runtime::Handle,
+}
+
+#[pymethods]
+impl PyTaskHandle {
+    /// Task name
+    #[getter]
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Task ID
+    #[getter]
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Cancel the task
+    fn cancel(&self, py: Python<'_>) -> PyResult<()> {
+        self.running.cancel(py)?;
+        if let Ok(mut state) = self.state.try_lock() {
+            if let Some(handle) = state.handle.as_mut() {
+                handle.cancel();
+            }
+        }
+        Ok(())
+    }
+
+    /// Check if the task has finished
+    fn done(&self) -> bool {
+        self.state.try_lock().is_ok_and(|state| {
+            state.outcome.is_some() || state.handle.as_ref().is_some_and(TaskHandle::is_finished)
+        })
+    }
+
+    /// Wait for the task and return its result (blocking)
+    fn result(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let state = Arc::clone(&self.state);
+        let runtime = self.runtime.clone();
+        let state = py.allow_threads(move || {
+            runtime.block_on(async move {
+                let mut state = state.lock_owned().await;
+                if let Some(handle) = state.handle.take() {
+                    state.outcome = Some(handle.join().await.map_err(task_error).and_then(|r| r));
+                }
+                state
+            })
+        });
+
+        match state.outcome.as_ref() {
+            Some(Ok(value)) => Ok(value.clone_ref(py)),
+            Some(Err(e)) => Err(e.clone_ref(py)),
+            None => Err(PyArchimedesError::new_err("Task result is unavailable")),
+        }
+    }
+
+    fn __await__<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyAny>> {
+        let py = slf.py();
+        let event_loop = py.import("asyncio")?.call_method0("get_running_loop")?;
+        let future =
+            event_loop.call_method1("run_in_executor", (py.None(), slf.getattr("result")?))?;
+        future.call_method0("__await__")
+    }
+
+    fn __repr__(&self) -> String {
+        format!("TaskHandle(name='{}', id='{}')", self.name, self.id)
+    }
+}
+
+/// Decorator registering a scheduled job
+#[pyclass(name = "ScheduleDecorator")]
+pub struct ScheduleDecorator {
+    /// Job name (defaults to the function name)
+    name: Option<String>,
+    /// Cron expression, if cron-scheduled
+    cron: Option<String>,
+    /// Interval, if interval-scheduled
+    every: Option<Duration>,
+    /// Overlap policy
+    overlap: OverlapPolicy,
+    /// Bridge to register with
+    bridge: Arc<TaskBridge>,
+}
+
+impl ScheduleDecorator {
+    /// Create a decorator for a cron-scheduled job
+    pub fn cron(
+        bridge: Arc<TaskBridge>,
+        cron: String,
+        name: Option<String>,
+        overlap: &str,
+    ) -> PyResult<Self> {
+        Ok(Self {
+            name,
+            cron: Some(cron),
+            every: None,
+            overlap: parse_overlap(overlap)?,
+            bridge,
+        })
+    }
+
+    /// Create a decorator for an interval-scheduled job
+    pub fn interval(
+        bridge: Arc<TaskBridge>,
+        seconds: f64,
+        name: Option<String>,
+        overlap: &str,
+    ) -> PyResult<Self> {
+        let every = Duration::try_from_secs_f64(seconds)
+            .ok()
+            .filter(|d| !d.is_zero())
+            .ok_or_else(|| PyValueError::new_err("Interval seconds must be positive"))?;
+        Ok(Self {
+            name,
+            cron: None,
+            every: Some(every),
+            overlap: parse_overlap(overlap)?,
+            bridge,
+        })
+    }
+}
+
+#[pymethods]
+impl ScheduleDecorator {
+    fn __call__(&self, py: Python<'_>, func: PyObject) -> PyResult<PyObject> {
+        let name = self
+            .name
+            .clone()
+            .unwrap_or_else(|| callable_name(py, &func));
+
+        let spec = match (&self.cron, self.every) {
+            (Some(cron), _) => JobSpec::cron(name, cron),
+            (None, Some(every)) => JobSpec::interval(name, every),
+            (None, None) => unreachable!("decorator has a cron expression or an interval"),
+        }
+        .map_err(|e| PyValueError::new_err(e.to_string()))?
+        .with_overlap(self.overlap);
+
+        self.bridge.register_job(spec, func.clone_ref(py))?;
+        Ok(func)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::ffi::c_str;
+    use pyo3::types::PyDict;
+
+    fn bridge() -> Arc<TaskBridge> {
+        Arc::new(TaskBridge::with_config(
+            SchedulerConfig::new().with_tick_interval(Duration::from_millis(10)),
+        ))
+    }
+
+    fn define<'py>(py: Python<'py>, code: &std::ffi::CStr) -> Bound<'py, PyDict> {
+        let globals = PyDict::new(py);
+        py.run(code, Some(&globals), None).unwrap();
+        globals
+    }
+
+    #[test]
+    fn test_interval_job_runs() {
+        pyo3::prepare_freethreaded_python();
+        let bridge = bridge();
+
+        Python::with_gil(|py| {
+            let globals = define(
+                py,
+                c_str!(
+                    "calls = []\n\
+                     async def tick():\n    calls.append(1)\n"
+                ),
+            );
+            let decorator =
+                ScheduleDecorator::interval(Arc::clone(&bridge), 0.05, None, "skip").unwrap();
+            decorator
+                .__call__(py, globals.get_item("tick").unwrap().unwrap().unbind())
+                .unwrap();
+
+            bridge.start().unwrap();
+            py.allow_threads(|| std::thread::sleep(Duration::from_millis(400)));
+            py.allow_threads(|| bridge.shutdown());
+
+            let calls = globals.get_item("calls").unwrap().unwrap();
+            assert!(calls.len().unwrap() >= 2);
+
+            let jobs = bridge.jobs();
+            assert_eq!(jobs[0].name, "tick");
+            assert_eq!(jobs[0].overlap, OverlapPolicy::Skip);
+            assert!(!bridge.is_running());
+        });
+    }
+
+    #[test]
+    fn test_job_exception_recorded() {
+        pyo3::prepare_freethreaded_python();
+        let bridge = bridge();
+
+        Python::with_gil(|py| {
+            let globals = define(
+                py,
+                c_str!("async def cleanup():\n    raise ValueError('disk full')\n"),
+            );
+            let decorator = ScheduleDecorator::interval(
+                Arc::clone(&bridge),
+                0.05,
+                Some("nightly-cleanup".to_string()),
+                "allow",
+            )
+            .unwrap();
+            decorator
+                .__call__(py, globals.get_item("cleanup").unwrap().unwrap().unbind())
+                .unwrap();
+
+            bridge.start().unwrap();
+            py.allow_threads(|| std::thread::sleep(Duration::from_millis(300)));
+            py.allow_threads(|| bridge.shutdown());
+
+            let job = &bridge.jobs()[0];
+            assert_eq!(job.name, "nightly-cleanup");
+            assert!(job.fail_count >= 1);
+            assert_eq!(job.fail_count, job.run_count);
+            assert!(job.last_error.as_deref().unwrap().contains("disk full"));
+
+            let stats = bridge.stats_json();
+            assert_eq!(stats["jobs"][0]["name"], "nightly-cleanup");
+            assert!(stats["jobs"][0]["last_error"]
+                .as_str()
+                .unwrap()
+                .contains("ValueError"));
+        });
+    }
+
+    #[test]
+    fn test_spawn_returns_result() {
+        pyo3::prepare_freethreaded_python();
+        let bridge = bridge();
+
+        Python::with_gil(|py| {
+            let globals = define(
+                py,
+                c_str!(
+                    "import asyncio\n\
+                     async def add(a, b):\n    await asyncio.sleep(0.01)\n    return a + b\n"
+                ),
+            );
+            let coroutine = globals
+                .get_item("add")
+                .unwrap()
+                .unwrap()
+                .call1((2, 3))
+                .unwrap()
+                .unbind();
+
+            let handle = bridge.spawn(py, "add".to_string(), coroutine).unwrap();
+            let result = handle.result(py).unwrap();
+            assert_eq!(result.extract::<i64>(py).unwrap(), 5);
+            assert!(handle.done());
+
+            let stats = bridge.stats_json();
+            assert_eq!(stats["tasks"]["spawned"], 1);
+            assert_eq!(stats["tasks"]["completed"], 1);
+        });
+    }
+
+    #[test]
+    fn test_spawn_cancel() {
+        pyo3::prepare_freethreaded_python();
+        let bridge = bridge();
+
+        Python::with_gil(|py| {
+            let globals = define(
+                py,
+                c_str!(
+                    "import asyncio\n\
+                     async def forever():\n    await asyncio.sleep(3600)\n"
+                ),
+            );
+            let coroutine = globals
+                .get_item("forever")
+                .unwrap()
+                .unwrap()
+                .call0()
+                .unwrap()
+                .unbind();
+
+            let handle = bridge.spawn(py, "forever".to_string(), coroutine).unwrap();
+            py.allow_threads(|| std::thread::sleep(Duration::from_millis(50)));
+            handle.cancel(py).unwrap();
+
+            let err = handle.result(py).unwrap_err();
+            assert!(err.is_instance_of::<CancelledError>(py));
+        });
+    }
+
+    #[test]
+    fn test_spawn_rejects_non_coroutine() {
+        pyo3::prepare_freethreaded_python();
+        let bridge = bridge();
+
+        Python::with_gil(|py| {
+            let err = bridge
+                .spawn(py, "bad".to_string(), py.None())
+                .err()
+                .unwrap();
+            assert!(err.is_instance_of::<PyTypeError>(py));
+        });
+    }
+
+    #[test]
+    fn test_invalid_overlap_policy() {
+        pyo3::prepare_freethreaded_python();
+        let result = ScheduleDecorator::cron(bridge(), "0 0 3 * * *".into(), None, "queue");
+        assert!(result.is_err());
+    }
+}
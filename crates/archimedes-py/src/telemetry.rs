@@ -231,6 +231,8 @@ impl PyTelemetryConfig {
                 .unwrap_or_else(|| "http://localhost:4317".to_string()),
             environment: self.environment.clone(),
             sample_ratio: self.sampling_ratio,
+            sampling: None,
+            sample_errors: false,
         }
     }
 
@@ -255,7 +257,6 @@ pub struct PyTelemetry {
     config: PyTelemetryConfig,
     /// Guard to keep telemetry providers alive.
     /// We use Option because TelemetryGuard is not Send/Sync for PyO3.
-    #[allow(dead_code)]
     guard: Option<TelemetryGuard>,
     /// Whether telemetry has been initialized.
     initialized: bool,
@@ -305,6 +306,26 @@ impl PyTelemetry {
         })
     }
 
+    /// Flush buffered traces, metrics and logs.
+    ///
+    /// Call this before `sys.exit()` / `os._exit()`, which skip the normal
+    /// shutdown path and would otherwise lose buffered spans.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout_seconds` - Maximum time to wait (default: wait until done)
+    #[pyo3(signature = (timeout_seconds=None))]
+    pub fn flush(&self, timeout_seconds: Option<f64>) -> PyResult<()> {
+        let Some(guard) = &self.guard else {
+            return Ok(());
+        };
+        match timeout_seconds {
+            Some(secs) => guard.flush_with_timeout(Duration::from_secs_f64(secs)),
+            None => guard.flush(),
+        }
+        .map_err(|e| ArchimedesError::new_err(e.to_string()))
+    }
+
     /// Record a completed request.
     ///
     /// Updates metrics:
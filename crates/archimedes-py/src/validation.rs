@@ -39,7 +39,9 @@
 
 use std::collections::HashMap;
 
-use archimedes_sentinel::{ArtifactLoader, Sentinel, SentinelConfig};
+use archimedes_sentinel::{
+    ArtifactLoader, RequestUnknownFields, ResponseUnknownFields, Sentinel, SentinelConfig,
+};
 use pyo3::prelude::*;
 use pyo3::types::PyDict;
 use serde_json::Value;
@@ -64,6 +66,10 @@ pub struct PyOperationResolution {
     #[pyo3(get)]
     pub path_template: String,
 
+    /// Route pattern that matched, for low-cardinality telemetry labels.
+    #[pyo3(get)]
+    pub route_pattern: String,
+
     /// Extracted path parameters.
     path_params: HashMap<String, String>,
 
@@ -286,15 +292,43 @@ impl PySentinel {
     /// * `validate_requests` - Whether to validate request bodies
     /// * `validate_responses` - Whether to validate response bodies
     /// * `strict_mode` - Whether to fail on unknown fields
+    /// * `request_unknown_fields` - What to do with request fields the schema
+    ///   does not declare: `"reject"`, `"strip"` or `"allow"` (default)
+    /// * `response_unknown_fields` - What to do with response fields the
+    ///   schema does not declare: `"reject"`, `"log_only"` or `"allow"`
+    ///   (default)
+    ///
+    /// An explicit `additionalProperties` in the schema always wins over the
+    /// unknown-field policies.
     #[staticmethod]
-    #[pyo3(signature = (path, validate_requests = true, validate_responses = true, strict_mode = false))]
+    #[pyo3(signature = (
+        path,
+        validate_requests = true,
+        validate_responses = true,
+        strict_mode = false,
+        request_unknown_fields = None,
+        response_unknown_fields = None,
+    ))]
     pub fn with_config(
         py: Python<'_>,
         path: String,
         validate_requests: bool,
         validate_responses: bool,
         strict_mode: bool,
+        request_unknown_fields: Option<&str>,
+        response_unknown_fields: Option<&str>,
     ) -> PyResult<Self> {
+        let request_unknown_fields = request_unknown_fields
+            .map(str::parse::<RequestUnknownFields>)
+            .transpose()
+            .map_err(pyo3::exceptions::PyValueError::new_err)?
+            .unwrap_or_default();
+        let response_unknown_fields = response_unknown_fields
+            .map(str::parse::<ResponseUnknownFields>)
+            .transpose()
+            .map_err(pyo3::exceptions::PyValueError::new_err)?
+            .unwrap_or_default();
+
         let sentinel = py.allow_threads(|| {
             let rt = tokio::runtime::Runtime::new().map_err(|e| {
                 ArchimedesError::new_err(format!("Failed to create runtime: {}", e))
@@ -309,6 +343,8 @@ impl PySentinel {
                 config.validation.validate_requests = validate_requests;
                 config.validation.validate_responses = validate_responses;
                 config.validation.strict_mode = strict_mode;
+                config.validation.request_unknown_fields = request_unknown_fields;
+                config.validation.response_unknown_fields = response_unknown_fields;
 
                 Ok::<Sentinel, PyErr>(Sentinel::new(artifact, config))
             })
@@ -358,6 +394,7 @@ impl PySentinel {
                 operation_id: resolution.operation_id,
                 method: resolution.method,
                 path_template: resolution.path_template,
+                route_pattern: resolution.route_pattern,
                 path_params: resolution.path_params,
                 deprecated: resolution.deprecated,
                 tags: resolution.tags,
@@ -366,6 +403,18 @@ impl PySentinel {
         }
     }
 
+    /// Explain how an HTTP request resolves, as a dict.
+    ///
+    /// Useful for debugging a 404 or 405: the dict has the `outcome`
+    /// (`matched`, `method_not_allowed`, `no_routes_for_method`,
+    /// `no_match`), every candidate route with its per-segment results,
+    /// and `suggestions` for near-miss paths.
+    pub fn explain(&self, py: Python<'_>, method: &str, path: &str) -> PyResult<PyObject> {
+        let explanation = serde_json::to_value(self.sentinel.explain(method, path))
+            .map_err(|e| ArchimedesError::new_err(e.to_string()))?;
+        crate::handlers::json_to_python(py, &explanation)
+    }
+
     /// Check if an operation exists for the given method and path.
     pub fn has_operation(&self, method: &str, path: &str) -> bool {
         self.sentinel.has_operation(method, path)
@@ -568,6 +617,7 @@ mod tests {
             operation_id: "getUser".to_string(),
             method: "GET".to_string(),
             path_template: "/users/{userId}".to_string(),
+            route_pattern: "/users/{userId}".to_string(),
             path_params: {
                 let mut map = HashMap::new();
                 map.insert("userId".to_string(), "123".to_string());
@@ -2,9 +2,15 @@
 //!
 //! Run with: `cargo bench -p archimedes-router`
 
+use std::alloc::System;
+
 use archimedes_router::{MethodRouter, Router};
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
 use http::Method;
+use stats_alloc::{Region, StatsAlloc, INSTRUMENTED_SYSTEM};
+
+#[global_allocator]
+static GLOBAL: &StatsAlloc<System> = &INSTRUMENTED_SYSTEM;
 
 fn build_router(num_routes: usize) -> Router {
     let mut router = Router::new();
@@ -36,6 +42,43 @@ fn build_router(num_routes: usize) -> Router {
     router
 }
 
+/// Builds a router of `num_routes` routes spread over five shapes: static,
+/// single parameter, nested parameters, a deep static path, and a path that
+/// is mostly parameters.
+fn build_scaling_router(num_routes: usize) -> Router {
+    let mut router = Router::new();
+
+    for i in 0..num_routes / 5 {
+        router.route(
+            &Method::GET,
+            &format!("/api/v1/resource{i}"),
+            format!("static{i}"),
+        );
+        router.route(
+            &Method::GET,
+            &format!("/api/v1/resource{i}/{{id}}"),
+            format!("param{i}"),
+        );
+        router.route(
+            &Method::GET,
+            &format!("/api/v1/org/{{orgId}}/resource{i}/{{id}}"),
+            format!("nested{i}"),
+        );
+        router.route(
+            &Method::GET,
+            &format!("/api/v1/a/b/c/d/e/f/g/h/resource{i}"),
+            format!("deep{i}"),
+        );
+        router.route(
+            &Method::GET,
+            &format!("/t/{{tenant}}/o/{{org}}/p/{{project}}/e/{{env}}/item{i}/{{id}}/{{version}}"),
+            format!("paramHeavy{i}"),
+        );
+    }
+
+    router
+}
+
 fn bench_static_match(c: &mut Criterion) {
     let router = build_router(100);
 
@@ -56,6 +99,27 @@ fn bench_param_match(c: &mut Criterion) {
     });
 }
 
+/// Matching a typical two-parameter route must not touch the heap.
+fn bench_nested_param_match_allocations(c: &mut Criterion) {
+    let router = build_router(100);
+    let path = "/api/v1/org/acme-corp/resource10/12345";
+
+    let region = Region::new(GLOBAL);
+    let route_match = router.match_route(&Method::GET, path);
+    let allocations = region.change().allocations;
+    assert_eq!(route_match.unwrap().params.len(), 2);
+    assert_eq!(allocations, 0, "matching {path} allocated");
+
+    // Criterion allocates between samples, so count around single matches
+    c.bench_function("nested_param_match_allocations", |b| {
+        b.iter(|| {
+            let region = Region::new(GLOBAL);
+            black_box(router.match_route(&Method::GET, path));
+            assert_eq!(region.change().allocations, 0);
+        });
+    });
+}
+
 fn bench_nested_param_match(c: &mut Criterion) {
     let router = build_router(100);
 
@@ -76,29 +140,44 @@ fn bench_miss(c: &mut Criterion) {
     });
 }
 
+/// Match latency as the table grows.
+///
+/// Matching walks one node per path segment, so each case should take about
+/// the same time at every size; a time that grows with the route count
+/// means a scan over routes has crept in.
 fn bench_scaling(c: &mut Criterion) {
     let mut group = c.benchmark_group("scaling");
 
-    for num_routes in [10, 50, 100, 500, 1000] {
-        let router = build_router(num_routes);
-
-        group.bench_with_input(
-            BenchmarkId::new("static_match", num_routes),
-            &num_routes,
-            |b, &n| {
-                let path = format!("/api/v1/resource{}", n / 6);
-                b.iter(|| black_box(router.match_route(&Method::GET, &path)));
-            },
-        );
-
-        group.bench_with_input(
-            BenchmarkId::new("param_match", num_routes),
-            &num_routes,
-            |b, &n| {
-                let path = format!("/api/v1/resource{}/12345", n / 6);
-                b.iter(|| black_box(router.match_route(&Method::GET, &path)));
-            },
-        );
+    for num_routes in [10, 1_000, 10_000] {
+        let router = build_scaling_router(num_routes);
+        let i = num_routes / 10;
+        let cases = [
+            ("static_match", format!("/api/v1/resource{i}")),
+            ("param_match", format!("/api/v1/resource{i}/12345")),
+            (
+                "nested_param_match",
+                format!("/api/v1/org/acme-corp/resource{i}/12345"),
+            ),
+            (
+                "deep_path_match",
+                format!("/api/v1/a/b/c/d/e/f/g/h/resource{i}"),
+            ),
+            (
+                "param_heavy_match",
+                format!("/t/acme/o/eng/p/atlas/e/prod/item{i}/12345/v2"),
+            ),
+            ("miss", "/api/v1/nonexistent/path".to_string()),
+        ];
+
+        for (name, path) in &cases {
+            assert!(
+                *name == "miss" || router.match_route(&Method::GET, path).is_some(),
+                "{path} should match"
+            );
+            group.bench_with_input(BenchmarkId::new(*name, num_routes), path, |b, path| {
+                b.iter(|| black_box(router.match_route(&Method::GET, path)));
+            });
+        }
     }
 
     group.finish();
@@ -109,6 +188,7 @@ criterion_group!(
     bench_static_match,
     bench_param_match,
     bench_nested_param_match,
+    bench_nested_param_match_allocations,
     bench_miss,
     bench_scaling
 );
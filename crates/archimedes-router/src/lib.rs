@@ -8,9 +8,12 @@
 //!
 //! - **Radix Tree Matching**: O(k) path lookup vs O(n) linear scan
 //! - **Path Parameters**: Extract named parameters from paths (`/users/{id}`)
-//! - **Wildcards**: Catch-all routes (`/files/*path`)
+//! - **Optional Parameters**: Trailing segments that may be omitted (`/posts/{id}/{slug?}`)
+//! - **Wildcards**: Catch-all routes (`/files/*path`), or zero-or-more with `*rest?`
+//! - **Percent-Decoding**: Segments are decoded before matching; `%2F` stays within one segment
 //! - **Method-Based Routing**: Different handlers per HTTP method
-//! - **Zero Allocations**: Path matching with minimal heap allocations
+//! - **Reverse Routing**: Build URLs from operation IDs (`Router::url_for`)
+//! - **Zero Allocations**: Matching a route with up to 4 short parameters does not allocate
 //!
 //! # Example
 //!
@@ -57,11 +60,13 @@ mod method_router;
 mod node;
 mod params;
 mod router;
+mod url;
 
 pub use method_router::MethodRouter;
 pub use node::Node;
-pub use params::Params;
+pub use params::{canonical_name, pattern_params, Params, ParamsIter};
 pub use router::Router;
+pub use url::{UrlForError, UrlGenerator};
 
 /// A matched route with its operation ID and extracted parameters.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -3,18 +3,31 @@
 //! This module provides the core radix tree (compressed trie) data structure
 //! used for efficient path matching.
 
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use smallvec::SmallVec;
+
 use crate::method_router::MethodRouter;
 use crate::params::Params;
+use crate::url::percent_decode;
 
 /// Type of path segment in the radix tree.
+///
+/// Parameter names are shared with the [`Params`] of every match, so
+/// capturing a parameter does not copy its name.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SegmentKind {
     /// Static path segment (e.g., "users", "api")
     Static,
     /// Named parameter (e.g., "{id}", "{userId}")
-    Param(String),
-    /// Catch-all wildcard (e.g., "*path")
-    Wildcard(String),
+    Param(Arc<str>),
+    /// Optional trailing parameter (e.g., "{slug?}")
+    OptionalParam(Arc<str>),
+    /// Catch-all wildcard matching one or more segments (e.g., "*path")
+    Wildcard(Arc<str>),
+    /// Catch-all wildcard matching zero or more segments (e.g., "*rest?")
+    OptionalWildcard(Arc<str>),
 }
 
 /// A node in the radix tree.
@@ -39,6 +52,9 @@ pub struct Node {
     /// Parameter child (at most one per node)
     pub param_child: Option<Box<Node>>,
 
+    /// Optional parameter child (at most one per node, must be leaf)
+    pub optional_child: Option<Box<Node>>,
+
     /// Wildcard child (at most one per node, must be leaf)
     pub wildcard_child: Option<Box<Node>>,
 }
@@ -53,6 +69,7 @@ impl Node {
             methods: None,
             static_children: Vec::new(),
             param_child: None,
+            optional_child: None,
             wildcard_child: None,
         }
     }
@@ -63,10 +80,26 @@ impl Node {
         let name = name.into();
         Self {
             segment: format!("{{{name}}}"),
-            kind: SegmentKind::Param(name),
+            kind: SegmentKind::Param(name.into()),
+            methods: None,
+            static_children: Vec::new(),
+            param_child: None,
+            optional_child: None,
+            wildcard_child: None,
+        }
+    }
+
+    /// Creates a new optional parameter node.
+    #[must_use]
+    pub fn new_optional_param(name: impl Into<String>) -> Self {
+        let name = name.into();
+        Self {
+            segment: format!("{{{name}?}}"),
+            kind: SegmentKind::OptionalParam(name.into()),
             methods: None,
             static_children: Vec::new(),
             param_child: None,
+            optional_child: None,
             wildcard_child: None,
         }
     }
@@ -77,10 +110,26 @@ impl Node {
         let name = name.into();
         Self {
             segment: format!("*{name}"),
-            kind: SegmentKind::Wildcard(name),
+            kind: SegmentKind::Wildcard(name.into()),
+            methods: None,
+            static_children: Vec::new(),
+            param_child: None,
+            optional_child: None,
+            wildcard_child: None,
+        }
+    }
+
+    /// Creates a new wildcard node that also matches zero segments.
+    #[must_use]
+    pub fn new_optional_wildcard(name: impl Into<String>) -> Self {
+        let name = name.into();
+        Self {
+            segment: format!("*{name}?"),
+            kind: SegmentKind::OptionalWildcard(name.into()),
             methods: None,
             static_children: Vec::new(),
             param_child: None,
+            optional_child: None,
             wildcard_child: None,
         }
     }
@@ -107,10 +156,14 @@ impl Node {
         path.split('/')
             .filter(|s| !s.is_empty())
             .map(|s| {
-                if let Some(name) = s.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
-                    (s.to_string(), SegmentKind::Param(name.to_string()))
+                if let Some(name) = s.strip_prefix('{').and_then(|s| s.strip_suffix("?}")) {
+                    (s.to_string(), SegmentKind::OptionalParam(name.into()))
+                } else if let Some(name) = s.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                    (s.to_string(), SegmentKind::Param(name.into()))
+                } else if let Some(name) = s.strip_prefix('*').and_then(|s| s.strip_suffix('?')) {
+                    (s.to_string(), SegmentKind::OptionalWildcard(name.into()))
                 } else if let Some(name) = s.strip_prefix('*') {
-                    (s.to_string(), SegmentKind::Wildcard(name.to_string()))
+                    (s.to_string(), SegmentKind::Wildcard(name.into()))
                 } else {
                     (s.to_string(), SegmentKind::Static)
                 }
@@ -135,32 +188,41 @@ impl Node {
 
         match kind {
             SegmentKind::Static => {
-                // Find or create static child
-                if let Some(child) = self
+                // Find or create static child, keeping children sorted for
+                // binary search
+                match self
                     .static_children
-                    .iter_mut()
-                    .find(|c| c.segment == *segment)
+                    .binary_search_by(|c| c.segment.as_str().cmp(segment))
                 {
-                    child.insert_segments(remaining, methods);
-                } else {
-                    let mut child = Node::new_static(segment);
-                    child.insert_segments(remaining, methods);
-                    self.static_children.push(child);
-                    // Keep sorted for binary search
-                    self.static_children
-                        .sort_by(|a, b| a.segment.cmp(&b.segment));
+                    Ok(i) => self.static_children[i].insert_segments(remaining, methods),
+                    Err(i) => {
+                        let mut child = Node::new_static(segment);
+                        child.insert_segments(remaining, methods);
+                        self.static_children.insert(i, child);
+                    }
                 }
             }
             SegmentKind::Param(name) => {
                 // Create or reuse param child
                 if self.param_child.is_none() {
-                    self.param_child = Some(Box::new(Node::new_param(name)));
+                    self.param_child = Some(Box::new(Node::new_param(&**name)));
                 }
                 if let Some(child) = &mut self.param_child {
                     child.insert_segments(remaining, methods);
                 }
             }
-            SegmentKind::Wildcard(name) => {
+            SegmentKind::OptionalParam(name) => {
+                // Create or reuse optional child (must be last segment)
+                assert!(
+                    remaining.is_empty(),
+                    "Optional parameter must be the last segment in path"
+                );
+                let child = self
+                    .optional_child
+                    .get_or_insert_with(|| Box::new(Node::new_optional_param(&**name)));
+                child.insert_segments(remaining, methods);
+            }
+            SegmentKind::Wildcard(name) | SegmentKind::OptionalWildcard(name) => {
                 // Create or reuse wildcard child (must be last segment)
                 assert!(
                     remaining.is_empty(),
@@ -174,7 +236,11 @@ impl Node {
                         child.methods = Some(methods);
                     }
                 } else {
-                    let mut child = Node::new_wildcard(name);
+                    let mut child = if matches!(kind, SegmentKind::OptionalWildcard(_)) {
+                        Node::new_optional_wildcard(&**name)
+                    } else {
+                        Node::new_wildcard(&**name)
+                    };
                     child.methods = Some(methods);
                     self.wildcard_child = Some(Box::new(child));
                 }
@@ -187,52 +253,117 @@ impl Node {
     /// Returns the method router and extracted parameters if found.
     #[must_use]
     pub fn match_path(&self, path: &str) -> Option<(&MethodRouter, Params)> {
-        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        self.match_path_with(path, false)
+    }
+
+    /// Matches a path against the tree, optionally ignoring ASCII case when
+    /// comparing static segments.
+    ///
+    /// Precedence at each level is static > parameter > optional parameter >
+    /// wildcard.
+    ///
+    /// The path is split on `/` before each segment is percent-decoded, so an
+    /// encoded slash (`%2F`) stays within its segment. Parameters receive the
+    /// decoded value.
+    #[must_use]
+    pub fn match_path_with(
+        &self,
+        path: &str,
+        case_insensitive: bool,
+    ) -> Option<(&MethodRouter, Params)> {
+        let segments: SmallVec<[Cow<'_, str>; 8]> = path
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .map(percent_decode)
+            .collect();
         let mut params = Params::new();
-        self.match_segments(&segments, &mut params)
+        self.match_segments(&segments, &mut params, case_insensitive)
     }
 
     /// Matches segments against the tree recursively.
     fn match_segments<'a>(
         &'a self,
-        segments: &[&str],
+        segments: &[Cow<'_, str>],
         params: &mut Params,
+        case_insensitive: bool,
     ) -> Option<(&'a MethodRouter, Params)> {
         if segments.is_empty() {
             // Check if this node has methods
-            return self.methods.as_ref().map(|m| (m, params.clone()));
+            if let Some(methods) = &self.methods {
+                return Some((methods, std::mem::take(params)));
+            }
+
+            // An omitted optional parameter
+            if let Some(methods) = self.optional_child.as_ref().and_then(|c| c.methods()) {
+                return Some((methods, std::mem::take(params)));
+            }
+
+            // A wildcard that may match zero segments
+            if let Some(child) = &self.wildcard_child {
+                if let SegmentKind::OptionalWildcard(name) = &child.kind {
+                    params.push_shared(name, "");
+                    return child.methods.as_ref().map(|m| (m, std::mem::take(params)));
+                }
+            }
+            return None;
         }
 
-        let segment = segments[0];
+        let segment = &*segments[0];
         let remaining = &segments[1..];
+        let captured = params.len();
 
         // Try static match first (highest priority)
         if let Some(child) = self.find_static_child(segment) {
-            if let Some(result) = child.match_segments(remaining, params) {
+            if let Some(result) = child.match_segments(remaining, params, case_insensitive) {
                 return Some(result);
             }
+            params.truncate(captured);
+        }
+
+        // Then static segments differing only in ASCII case
+        if case_insensitive {
+            for child in self
+                .static_children
+                .iter()
+                .filter(|c| c.segment != segment && c.segment.eq_ignore_ascii_case(segment))
+            {
+                if let Some(result) = child.match_segments(remaining, params, case_insensitive) {
+                    return Some(result);
+                }
+                params.truncate(captured);
+            }
         }
 
         // Try parameter match
         if let Some(child) = &self.param_child {
             if let SegmentKind::Param(name) = &child.kind {
-                params.push(name.clone(), segment.to_string());
-                if let Some(result) = child.match_segments(remaining, params) {
+                params.push_shared(name, segment);
+                if let Some(result) = child.match_segments(remaining, params, case_insensitive) {
                     return Some(result);
                 }
-                // Backtrack: remove the param we just added
-                // Note: This is a simplified backtracking; for complex cases,
-                // we'd need to clone params before trying each branch
+                // Backtrack: drop params captured along the failed branch
+                params.truncate(captured);
+            }
+        }
+
+        // Try optional parameter match (trailing segment only)
+        if let [_] = segments {
+            if let Some(child) = &self.optional_child {
+                if let (SegmentKind::OptionalParam(name), Some(methods)) =
+                    (&child.kind, &child.methods)
+                {
+                    params.push_shared(name, segment);
+                    return Some((methods, std::mem::take(params)));
+                }
             }
         }
 
         // Try wildcard match (lowest priority, catches all remaining)
         if let Some(child) = &self.wildcard_child {
-            if let SegmentKind::Wildcard(name) = &child.kind {
+            if let SegmentKind::Wildcard(name) | SegmentKind::OptionalWildcard(name) = &child.kind {
                 // Collect all remaining segments
-                let remaining_path = segments.join("/");
-                params.push(name.clone(), remaining_path);
-                return child.methods.as_ref().map(|m| (m, params.clone()));
+                params.push_parts(name, segments.iter().map(|s| &**s), "/");
+                return child.methods.as_ref().map(|m| (m, std::mem::take(params)));
             }
         }
 
@@ -259,11 +390,12 @@ impl Node {
         self.methods.as_ref()
     }
 
-    /// Returns an iterator over all children (static, param, wildcard).
+    /// Returns an iterator over all children (static, param, optional, wildcard).
     pub fn children(&self) -> impl Iterator<Item = &Node> {
         self.static_children
             .iter()
             .chain(self.param_child.as_ref().map(AsRef::as_ref))
+            .chain(self.optional_child.as_ref().map(AsRef::as_ref))
             .chain(self.wildcard_child.as_ref().map(AsRef::as_ref))
     }
 }
@@ -284,14 +416,14 @@ mod tests {
     fn test_node_new_param() {
         let node = Node::new_param("id");
         assert_eq!(node.segment, "{id}");
-        assert_eq!(node.kind, SegmentKind::Param("id".to_string()));
+        assert_eq!(node.kind, SegmentKind::Param("id".into()));
     }
 
     #[test]
     fn test_node_new_wildcard() {
         let node = Node::new_wildcard("path");
         assert_eq!(node.segment, "*path");
-        assert_eq!(node.kind, SegmentKind::Wildcard("path".to_string()));
+        assert_eq!(node.kind, SegmentKind::Wildcard("path".into()));
     }
 
     #[test]
@@ -309,7 +441,7 @@ mod tests {
         assert_eq!(segments[0], ("users".to_string(), SegmentKind::Static));
         assert_eq!(
             segments[1],
-            ("{id}".to_string(), SegmentKind::Param("id".to_string()))
+            ("{id}".to_string(), SegmentKind::Param("id".into()))
         );
     }
 
@@ -320,10 +452,7 @@ mod tests {
         assert_eq!(segments[0], ("files".to_string(), SegmentKind::Static));
         assert_eq!(
             segments[1],
-            (
-                "*path".to_string(),
-                SegmentKind::Wildcard("path".to_string())
-            )
+            ("*path".to_string(), SegmentKind::Wildcard("path".into()))
         );
     }
 
@@ -412,6 +541,85 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn test_parse_path_optional_segments() {
+        let segments = Node::parse_path("/posts/{id}/{slug?}");
+        assert_eq!(segments[2].1, SegmentKind::OptionalParam("slug".into()));
+
+        let segments = Node::parse_path("/files/*rest?");
+        assert_eq!(segments[1].1, SegmentKind::OptionalWildcard("rest".into()));
+    }
+
+    #[test]
+    fn test_optional_param_matches_with_and_without_segment() {
+        let mut root = Node::root();
+        root.insert("/posts/{id}/{slug?}", MethodRouter::new().get("getPost"));
+
+        let (methods, params) = root.match_path("/posts/123").unwrap();
+        assert_eq!(methods.get_operation(&Method::GET), Some("getPost"));
+        assert_eq!(params.get("id"), Some("123"));
+        assert_eq!(params.get("slug"), None);
+
+        let (methods, params) = root.match_path("/posts/123/my-title").unwrap();
+        assert_eq!(methods.get_operation(&Method::GET), Some("getPost"));
+        assert_eq!(params.get("id"), Some("123"));
+        assert_eq!(params.get("slug"), Some("my-title"));
+
+        assert!(root.match_path("/posts/123/my-title/extra").is_none());
+    }
+
+    #[test]
+    fn test_optional_wildcard_matches_zero_segments() {
+        let mut root = Node::root();
+        root.insert("/static/*rest?", MethodRouter::new().get("serveStatic"));
+
+        let (_, params) = root.match_path("/static").unwrap();
+        assert_eq!(params.get("rest"), Some(""));
+
+        let (_, params) = root.match_path("/static/css/site.css").unwrap();
+        assert_eq!(params.get("rest"), Some("css/site.css"));
+    }
+
+    #[test]
+    fn test_required_wildcard_needs_a_segment() {
+        let mut root = Node::root();
+        root.insert("/files/*path", MethodRouter::new().get("serveFile"));
+
+        assert!(root.match_path("/files").is_none());
+    }
+
+    #[test]
+    fn test_precedence_exact_param_optional_wildcard() {
+        let mut root = Node::root();
+        root.insert("/posts/{id}", MethodRouter::new().get("getPost"));
+        root.insert(
+            "/posts/{id}/{slug?}",
+            MethodRouter::new().get("getPostSlug"),
+        );
+        root.insert(
+            "/posts/{id}/comments",
+            MethodRouter::new().get("listComments"),
+        );
+        root.insert("/posts/{id}/{field}/raw", MethodRouter::new().get("getRaw"));
+        root.insert("/posts/*rest", MethodRouter::new().get("catchAll"));
+
+        let op = |path: &str| {
+            root.match_path(path)
+                .and_then(|(m, _)| m.get_operation(&Method::GET))
+        };
+        // An explicit route beats the omitted optional segment
+        assert_eq!(op("/posts/1"), Some("getPost"));
+        assert_eq!(op("/posts/1/comments"), Some("listComments"));
+        // A failed param branch falls back to the optional param
+        assert_eq!(op("/posts/1/hello"), Some("getPostSlug"));
+        assert_eq!(op("/posts/1/title/raw"), Some("getRaw"));
+        assert_eq!(op("/posts/1/a/b/c"), Some("catchAll"));
+
+        let (_, params) = root.match_path("/posts/1/hello").unwrap();
+        assert_eq!(params.len(), 2);
+        assert_eq!(params.get("field"), None);
+    }
+
     #[test]
     fn test_nested_routes() {
         let mut root = Node::root();
@@ -4,15 +4,24 @@
 //! using a small-vector optimization to avoid heap allocations for
 //! common cases (1-4 parameters).
 
+use std::fmt;
+use std::sync::Arc;
+
 use smallvec::SmallVec;
 
 /// Maximum number of parameters stored inline (stack allocated).
 const INLINE_PARAMS: usize = 4;
 
+/// Bytes of parameter values stored inline, enough for a few IDs or UUIDs.
+const INLINE_VALUE_BYTES: usize = 128;
+
 /// Extracted path parameters from a route match.
 ///
 /// Uses small-vector optimization to avoid heap allocation for common
-/// cases with few parameters. Parameters are stored as (name, value) pairs.
+/// cases with few parameters. Names are shared with the route tree, and
+/// values are stored back to back in one buffer, so matching a route with
+/// up to 4 parameters and 128 bytes of values does not allocate. Larger
+/// sets spill to the heap.
 ///
 /// # Example
 ///
@@ -27,10 +36,12 @@ const INLINE_PARAMS: usize = 4;
 /// assert_eq!(params.get("action"), Some("view"));
 /// assert_eq!(params.get("unknown"), None);
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Clone, Default)]
 pub struct Params {
-    /// Storage for parameter (name, value) pairs
-    inner: SmallVec<[(String, String); INLINE_PARAMS]>,
+    /// Parameter names with the byte range of their value in `values`
+    entries: SmallVec<[(Arc<str>, usize, usize); INLINE_PARAMS]>,
+    /// Parameter values, concatenated
+    values: SmallVec<[u8; INLINE_VALUE_BYTES]>,
 }
 
 impl Params {
@@ -44,64 +55,238 @@ impl Params {
     #[must_use]
     pub fn with_capacity(capacity: usize) -> Self {
         Self {
-            inner: SmallVec::with_capacity(capacity),
+            entries: SmallVec::with_capacity(capacity),
+            values: SmallVec::new(),
         }
     }
 
     /// Adds a parameter to the set.
     pub fn push(&mut self, name: impl Into<String>, value: impl Into<String>) {
-        self.inner.push((name.into(), value.into()));
+        self.push_shared(&Arc::from(name.into()), &value.into());
+    }
+
+    /// Adds a parameter whose name is shared with the route tree, without
+    /// allocating while the set fits inline.
+    pub(crate) fn push_shared(&mut self, name: &Arc<str>, value: &str) {
+        self.push_parts(name, [value], "");
+    }
+
+    /// Adds a parameter whose value is `parts` joined by `separator`.
+    pub(crate) fn push_parts<'a>(
+        &mut self,
+        name: &Arc<str>,
+        parts: impl IntoIterator<Item = &'a str>,
+        separator: &str,
+    ) {
+        let start = self.values.len();
+        for (i, part) in parts.into_iter().enumerate() {
+            if i > 0 {
+                self.values.extend_from_slice(separator.as_bytes());
+            }
+            self.values.extend_from_slice(part.as_bytes());
+        }
+        self.entries
+            .push((Arc::clone(name), start, self.values.len()));
+    }
+
+    /// Returns the value stored between `start` and `end`.
+    fn value(&self, start: usize, end: usize) -> &str {
+        // Values are only ever appended whole from `&str`s
+        std::str::from_utf8(&self.values[start..end]).expect("parameter values are valid UTF-8")
     }
 
     /// Returns the value for a parameter by name.
     #[must_use]
     pub fn get(&self, name: &str) -> Option<&str> {
-        self.inner
-            .iter()
-            .find(|(n, _)| n == name)
-            .map(|(_, v)| v.as_str())
+        self.iter().find(|(n, _)| *n == name).map(|(_, v)| v)
+    }
+
+    /// Returns the value for a parameter, tolerating differences in case
+    /// and naming style.
+    ///
+    /// An exact match wins. Otherwise the first parameter with the same
+    /// [`canonical_name`] is returned, so `user_id` finds a parameter
+    /// declared as `{userId}`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use archimedes_router::Params;
+    ///
+    /// let mut params = Params::new();
+    /// params.push("userId", "123");
+    ///
+    /// assert_eq!(params.find("userId"), Some("123"));
+    /// assert_eq!(params.find("user_id"), Some("123"));
+    /// assert_eq!(params.find("USERID"), Some("123"));
+    /// ```
+    #[must_use]
+    pub fn find(&self, name: &str) -> Option<&str> {
+        self.get(name).or_else(|| {
+            let canonical = canonical_name(name);
+            self.iter()
+                .find(|(n, _)| canonical_name(n) == canonical)
+                .map(|(_, v)| v)
+        })
     }
 
     /// Returns true if there are no parameters.
     #[must_use]
     pub fn is_empty(&self) -> bool {
-        self.inner.is_empty()
+        self.entries.is_empty()
     }
 
     /// Returns the number of parameters.
     #[must_use]
     pub fn len(&self) -> usize {
-        self.inner.len()
+        self.entries.len()
     }
 
     /// Returns an iterator over the parameters.
-    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
-        self.inner.iter().map(|(n, v)| (n.as_str(), v.as_str()))
+    pub fn iter(&self) -> ParamsIter<'_> {
+        ParamsIter {
+            params: self,
+            entries: self.entries.iter(),
+        }
     }
 
     /// Clears all parameters, retaining allocated capacity.
     pub fn clear(&mut self) {
-        self.inner.clear();
+        self.entries.clear();
+        self.values.clear();
+    }
+
+    /// Drops parameters pushed after the first `len`, for backtracking.
+    pub(crate) fn truncate(&mut self, len: usize) {
+        self.entries.truncate(len);
+        let end = self.entries.last().map_or(0, |&(_, _, end)| end);
+        self.values.truncate(end);
+    }
+
+    /// Renames parameters in order, leaving names that already match.
+    pub(crate) fn rename<'a>(&mut self, names: impl IntoIterator<Item = &'a str>) {
+        for ((current, _, _), name) in self.entries.iter_mut().zip(names) {
+            if &**current != name {
+                *current = Arc::from(name);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Params {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
     }
 }
 
+impl PartialEq for Params {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl Eq for Params {}
+
+/// Iterator over the `(name, value)` pairs of [`Params`], in match order.
+#[derive(Debug, Clone)]
+pub struct ParamsIter<'a> {
+    params: &'a Params,
+    entries: std::slice::Iter<'a, (Arc<str>, usize, usize)>,
+}
+
+impl<'a> Iterator for ParamsIter<'a> {
+    type Item = (&'a str, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (name, start, end) = self.entries.next()?;
+        Some((name, self.params.value(*start, *end)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl ExactSizeIterator for ParamsIter<'_> {}
+
+/// Reduces a parameter or field name to its canonical form.
+///
+/// ASCII letters are lowercased and `_` and `-` are dropped, so `userId`,
+/// `user_id`, `UserID` and `user-id` all become `userid`. Two names with
+/// the same canonical form differ only by case or naming style.
+///
+/// # Example
+///
+/// ```rust
+/// use archimedes_router::canonical_name;
+///
+/// assert_eq!(canonical_name("userId"), canonical_name("user_id"));
+/// assert_ne!(canonical_name("userId"), canonical_name("user"));
+/// ```
+#[must_use]
+pub fn canonical_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Returns the parameter names of a route pattern, in order.
+///
+/// The flag is `true` for parameters that may be omitted, which can only
+/// be the last one.
+///
+/// # Example
+///
+/// ```rust
+/// use archimedes_router::pattern_params;
+///
+/// assert_eq!(
+///     pattern_params("/users/{userId}/files/*path?"),
+///     vec![("userId", false), ("path", true)]
+/// );
+/// ```
+#[must_use]
+pub fn pattern_params(pattern: &str) -> Vec<(&str, bool)> {
+    pattern_param_iter(pattern).collect()
+}
+
+/// Iterates over the parameter names of a route pattern without
+/// allocating; see [`pattern_params`].
+pub(crate) fn pattern_param_iter(pattern: &str) -> impl Iterator<Item = (&str, bool)> + Clone {
+    pattern.split('/').filter_map(|segment| {
+        if let Some(name) = segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+            Some(match name.strip_suffix('?') {
+                Some(name) => (name, true),
+                None => (name, false),
+            })
+        } else if let Some(name) = segment.strip_prefix('*') {
+            Some(match name.strip_suffix('?') {
+                Some(name) => (name, true),
+                None => (name, false),
+            })
+        } else {
+            None
+        }
+    })
+}
+
 impl<'a> IntoIterator for &'a Params {
     type Item = (&'a str, &'a str);
-    type IntoIter = std::iter::Map<
-        std::slice::Iter<'a, (String, String)>,
-        fn(&'a (String, String)) -> (&'a str, &'a str),
-    >;
+    type IntoIter = ParamsIter<'a>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.inner.iter().map(|(n, v)| (n.as_str(), v.as_str()))
+        self.iter()
     }
 }
 
 impl FromIterator<(String, String)> for Params {
     fn from_iter<I: IntoIterator<Item = (String, String)>>(iter: I) -> Self {
-        Self {
-            inner: iter.into_iter().collect(),
+        let mut params = Self::new();
+        for (name, value) in iter {
+            params.push(name, value);
         }
+        params
     }
 }
 
@@ -172,6 +357,31 @@ mod tests {
         assert_eq!(params.get("b"), Some("2"));
     }
 
+    #[test]
+    fn test_params_find_tolerates_naming_style() {
+        let mut params = Params::new();
+        params.push("userId", "42");
+        params.push("post_id", "7");
+
+        assert_eq!(params.find("userId"), Some("42"));
+        assert_eq!(params.find("user_id"), Some("42"));
+        assert_eq!(params.find("UserID"), Some("42"));
+        assert_eq!(params.find("postId"), Some("7"));
+        assert_eq!(params.find("post"), None);
+        // Exact lookups stay exact
+        assert_eq!(params.get("user_id"), None);
+    }
+
+    #[test]
+    fn test_pattern_params() {
+        assert_eq!(
+            pattern_params("/users/{userId}/posts/{post_id}/{slug?}"),
+            vec![("userId", false), ("post_id", false), ("slug", true)]
+        );
+        assert_eq!(pattern_params("/files/*path"), vec![("path", false)]);
+        assert!(pattern_params("/health").is_empty());
+    }
+
     #[test]
     fn test_params_with_capacity() {
         let params = Params::with_capacity(10);
@@ -189,4 +399,52 @@ mod tests {
         assert_eq!(params.len(), 10);
         assert_eq!(params.get("key5"), Some("value5"));
     }
+
+    #[test]
+    fn test_params_spill_to_heap() {
+        let name: Arc<str> = Arc::from("long");
+        let long = "x".repeat(INLINE_VALUE_BYTES * 2);
+        let mut params = Params::new();
+        for i in 0..INLINE_PARAMS + 2 {
+            params.push(format!("p{i}"), format!("v{i}"));
+        }
+        params.push_shared(&name, &long);
+
+        assert!(params.entries.spilled());
+        assert!(params.values.spilled());
+        assert_eq!(params.len(), INLINE_PARAMS + 3);
+        assert_eq!(params.get("p0"), Some("v0"));
+        assert_eq!(params.get("p5"), Some("v5"));
+        assert_eq!(params.get("long"), Some(long.as_str()));
+    }
+
+    #[test]
+    fn test_params_truncate_drops_values() {
+        let mut params = Params::new();
+        params.push("a", "1");
+        params.push("b", "22");
+        params.truncate(1);
+        params.push("c", "333");
+
+        assert_eq!(
+            params.iter().collect::<Vec<_>>(),
+            [("a", "1"), ("c", "333")]
+        );
+        assert_eq!(params.values.len(), 4);
+    }
+
+    #[test]
+    fn test_params_multibyte_and_joined_values() {
+        let name: Arc<str> = Arc::from("path");
+        let mut params = Params::new();
+        params.push("city", "Zürich");
+        params.push_parts(&name, ["docs", "ünïcode", "a.md"], "/");
+
+        assert_eq!(params.get("city"), Some("Zürich"));
+        assert_eq!(params.get("path"), Some("docs/ünïcode/a.md"));
+        assert_eq!(
+            format!("{params:?}"),
+            r#"{"city": "Zürich", "path": "docs/ünïcode/a.md"}"#
+        );
+    }
 }
@@ -3,11 +3,14 @@
 //! This module provides the main [`Router`] struct which is the primary
 //! interface for building and matching routes.
 
+use std::collections::HashMap;
+
 use http::Method;
 
 use crate::method_router::MethodRouter;
 use crate::node::Node;
-use crate::params::Params;
+use crate::params::{pattern_param_iter, Params};
+use crate::url::{append_query, expand, UrlForError};
 use crate::RouteMatch;
 
 /// A high-performance radix tree router.
@@ -73,6 +76,10 @@ pub struct Router {
     prefix: Option<String>,
     /// Optional `OpenAPI` tags for all routes
     tags: Vec<String>,
+    /// Full path pattern registered for each operation, for reverse routing
+    operations: HashMap<String, String>,
+    /// Whether static segments match regardless of ASCII case
+    case_insensitive: bool,
 }
 
 impl Default for Router {
@@ -90,6 +97,8 @@ impl Router {
             route_count: 0,
             prefix: None,
             tags: Vec::new(),
+            operations: HashMap::new(),
+            case_insensitive: false,
         }
     }
 
@@ -116,6 +125,8 @@ impl Router {
             route_count: 0,
             prefix: Some(normalize_path(&prefix.into())),
             tags: Vec::new(),
+            operations: HashMap::new(),
+            case_insensitive: false,
         }
     }
 
@@ -141,6 +152,35 @@ impl Router {
         self
     }
 
+    /// Enables case-insensitive matching of static path segments.
+    ///
+    /// Only literal segments are affected; parameter values keep the case
+    /// they were sent with. An exact-case match still takes priority.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use archimedes_router::{Router, MethodRouter};
+    /// use http::Method;
+    ///
+    /// let mut router = Router::new().case_insensitive(true);
+    /// router.insert("/Users/{id}", MethodRouter::new().get("getUser"));
+    ///
+    /// let m = router.match_route(&Method::GET, "/users/AbC").unwrap();
+    /// assert_eq!(m.params.get("id"), Some("AbC"));
+    /// ```
+    #[must_use]
+    pub fn case_insensitive(mut self, enabled: bool) -> Self {
+        self.case_insensitive = enabled;
+        self
+    }
+
+    /// Returns true if static segments match regardless of case.
+    #[must_use]
+    pub fn is_case_insensitive(&self) -> bool {
+        self.case_insensitive
+    }
+
     /// Adds an `OpenAPI` tag to all routes in this router.
     ///
     /// Tags are used for grouping routes in `OpenAPI` documentation.
@@ -205,6 +245,10 @@ impl Router {
         // We need to traverse the tree and collect all paths
         self.merge_with_prefix(&other.root, &prefix, "");
         self.route_count += other.route_count;
+        for (operation_id, path) in other.operations {
+            self.operations
+                .insert(operation_id, join_paths(&prefix, &path));
+        }
     }
 
     /// Merges all routes from another router into this one.
@@ -231,6 +275,7 @@ impl Router {
     pub fn merge(&mut self, other: Router) {
         self.merge_with_prefix(&other.root, "", "");
         self.route_count += other.route_count;
+        self.operations.extend(other.operations);
     }
 
     /// Helper to recursively merge nodes with a prefix.
@@ -299,6 +344,12 @@ impl Router {
             }
             None => normalize_path(path),
         };
+        for method in methods.allowed_methods() {
+            if let Some(operation_id) = methods.get_operation(&method) {
+                self.operations
+                    .insert(operation_id.to_string(), full_path.clone());
+            }
+        }
         self.root.insert(&full_path, methods);
         self.route_count += 1;
     }
@@ -324,6 +375,15 @@ impl Router {
     ///
     /// Returns a [`RouteMatch`] if a matching route is found.
     ///
+    /// Path segments are percent-decoded before matching and parameters
+    /// receive the decoded value. The path is split on `/` first, so an
+    /// encoded slash (`%2F`) stays within one segment rather than
+    /// introducing a new one.
+    ///
+    /// Parameters are named exactly as in the matched operation's route
+    /// pattern, even when several routes share a parameter segment under
+    /// different names.
+    ///
     /// # Example
     ///
     /// ```rust
@@ -342,8 +402,22 @@ impl Router {
     /// ```
     #[must_use]
     pub fn match_route(&self, method: &Method, path: &str) -> Option<RouteMatch<'_>> {
-        let (methods, params) = self.root.match_path(path)?;
+        let (methods, mut params) = self.root.match_path_with(path, self.case_insensitive)?;
         let operation_id = methods.get_operation(method)?;
+
+        // Routes sharing a prefix share its parameter nodes, which carry the
+        // name of whichever route was registered first. Report the names
+        // the matched operation declared instead.
+        if let Some(pattern) = self.operations.get(operation_id) {
+            let names = pattern_param_iter(pattern);
+            let omitted = names.clone().count().checked_sub(params.len());
+            if omitted == Some(0)
+                || (omitted == Some(1) && names.clone().last().is_some_and(|n| n.1))
+            {
+                params.rename(names.map(|(name, _)| name));
+            }
+        }
+
         Some(RouteMatch::new(operation_id, params))
     }
 
@@ -353,7 +427,7 @@ impl Router {
     /// Useful for checking allowed methods or generating 405 responses.
     #[must_use]
     pub fn match_path(&self, path: &str) -> Option<(&MethodRouter, Params)> {
-        self.root.match_path(path)
+        self.root.match_path_with(path, self.case_insensitive)
     }
 
     /// Returns the number of routes registered.
@@ -373,10 +447,95 @@ impl Router {
     pub fn get_prefix(&self) -> Option<&str> {
         self.prefix.as_deref()
     }
+
+    /// Returns the full path pattern registered for an operation.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use archimedes_router::{Router, MethodRouter};
+    ///
+    /// let mut router = Router::new().prefix("/api");
+    /// router.insert("/users/{id}", MethodRouter::new().get("getUser"));
+    ///
+    /// assert_eq!(router.route_pattern("getUser"), Some("/api/users/{id}"));
+    /// ```
+    #[must_use]
+    pub fn route_pattern(&self, operation_id: &str) -> Option<&str> {
+        self.operations.get(operation_id).map(String::as_str)
+    }
+
+    /// Builds the URL for an operation by substituting path parameters.
+    ///
+    /// Parameter values are percent-encoded, so reserved characters such as
+    /// `/` or `?` cannot change the shape of the generated path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no route is registered for the operation, if a
+    /// path parameter is missing or not part of the route, or if the route
+    /// ends in a wildcard segment.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use archimedes_router::{Router, MethodRouter};
+    ///
+    /// let mut router = Router::new();
+    /// router.insert("/users/{id}", MethodRouter::new().get("getUser"));
+    ///
+    /// assert_eq!(router.url_for("getUser", &[("id", "123")]).unwrap(), "/users/123");
+    /// ```
+    pub fn url_for(
+        &self,
+        operation_id: &str,
+        params: &[(&str, &str)],
+    ) -> Result<String, UrlForError> {
+        self.path_for(operation_id, params)
+    }
+
+    /// Builds the URL for an operation with query parameters appended.
+    ///
+    /// # Errors
+    ///
+    /// See [`Router::url_for`].
+    pub fn url_for_with_query(
+        &self,
+        operation_id: &str,
+        params: &[(&str, &str)],
+        query: &[(&str, &str)],
+    ) -> Result<String, UrlForError> {
+        let mut url = self.path_for(operation_id, params)?;
+        append_query(&mut url, query);
+        Ok(url)
+    }
+
+    /// Expands the route pattern of an operation into a path.
+    pub(crate) fn path_for(
+        &self,
+        operation_id: &str,
+        params: &[(&str, &str)],
+    ) -> Result<String, UrlForError> {
+        let pattern = self
+            .route_pattern(operation_id)
+            .ok_or_else(|| UrlForError::UnknownOperation(operation_id.to_string()))?;
+        expand(operation_id, pattern, params)
+    }
+}
+
+/// Joins a normalized prefix and a normalized path.
+pub(crate) fn join_paths(prefix: &str, path: &str) -> String {
+    if path == "/" {
+        prefix.to_string()
+    } else if prefix == "/" || prefix.is_empty() {
+        path.to_string()
+    } else {
+        format!("{prefix}{path}")
+    }
 }
 
 /// Normalizes a path by ensuring it starts with `/` and doesn't end with `/`.
-fn normalize_path(path: &str) -> String {
+pub(crate) fn normalize_path(path: &str) -> String {
     let path = path.trim();
     if path.is_empty() || path == "/" {
         return "/".to_string();
@@ -399,6 +558,7 @@ fn normalize_path(path: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::time::{Duration, Instant};
 
     #[test]
     fn test_router_new() {
@@ -704,6 +864,73 @@ mod tests {
         assert_eq!(route_match.params.get("postId"), Some("456"));
     }
 
+    #[test]
+    fn test_router_params_named_by_matched_route() {
+        let mut router = Router::new();
+        router.insert("/users/{userId}", MethodRouter::new().get("getUser"));
+        router.insert(
+            "/users/{user_id}/posts/{postId}",
+            MethodRouter::new().get("getUserPost"),
+        );
+        router.insert("/users/{id}/avatar/{size?}", MethodRouter::new().get("getAvatar"));
+
+        let user = router.match_route(&Method::GET, "/users/42").unwrap();
+        assert_eq!(user.params.get("userId"), Some("42"));
+
+        let post = router.match_route(&Method::GET, "/users/42/posts/7").unwrap();
+        assert_eq!(post.params.get("user_id"), Some("42"));
+        assert_eq!(post.params.get("postId"), Some("7"));
+        assert_eq!(post.params.get("userId"), None);
+
+        let avatar = router.match_route(&Method::GET, "/users/42/avatar").unwrap();
+        assert_eq!(avatar.params.get("id"), Some("42"));
+        assert_eq!(avatar.params.len(), 1);
+    }
+
+    #[test]
+    fn test_router_more_params_than_inline() {
+        let mut router = Router::new();
+        router.insert(
+            "/t/{tenant}/o/{org}/p/{project}/e/{env}/i/{id}/v/{version}",
+            MethodRouter::new().get("getItem"),
+        );
+        router.insert(
+            "/t/{tenant}/o/{org}/p/{project}/e/{env}/files/*path",
+            MethodRouter::new().get("getFile"),
+        );
+
+        let item = router
+            .match_route(&Method::GET, "/t/acme/o/eng/p/atlas/e/prod/i/12345/v/2")
+            .unwrap();
+        assert_eq!(item.operation_id, "getItem");
+        assert_eq!(
+            item.params.iter().collect::<Vec<_>>(),
+            [
+                ("tenant", "acme"),
+                ("org", "eng"),
+                ("project", "atlas"),
+                ("env", "prod"),
+                ("id", "12345"),
+                ("version", "2"),
+            ]
+        );
+
+        let long = "x".repeat(200);
+        let file = router
+            .match_route(
+                &Method::GET,
+                &format!("/t/acme/o/eng/p/atlas/e/prod/files/docs/{long}.md"),
+            )
+            .unwrap();
+        assert_eq!(file.operation_id, "getFile");
+        assert_eq!(file.params.len(), 5);
+        assert_eq!(file.params.get("env"), Some("prod"));
+        assert_eq!(
+            file.params.get("path"),
+            Some(format!("docs/{long}.md").as_str())
+        );
+    }
+
     #[test]
     fn test_router_nest_deep() {
         let mut posts = Router::new();
@@ -740,6 +967,181 @@ mod tests {
 
     // ============== normalize_path Tests ==============
 
+    #[test]
+    fn test_router_optional_and_zero_length_catch_all() {
+        let mut blog = Router::new();
+        blog.insert("/posts/{id}/{slug?}", MethodRouter::new().get("getPost"));
+        blog.insert("/assets/*rest?", MethodRouter::new().get("serveAsset"));
+
+        let mut router = Router::new();
+        router.nest("/blog", blog);
+
+        let m = router.match_route(&Method::GET, "/blog/posts/123").unwrap();
+        assert_eq!(m.operation_id, "getPost");
+        assert_eq!(m.params.get("slug"), None);
+
+        let m = router
+            .match_route(&Method::GET, "/blog/posts/123/my-title")
+            .unwrap();
+        assert_eq!(m.operation_id, "getPost");
+        assert_eq!(m.params.get("slug"), Some("my-title"));
+
+        let m = router.match_route(&Method::GET, "/blog/assets").unwrap();
+        assert_eq!(m.operation_id, "serveAsset");
+        assert_eq!(m.params.get("rest"), Some(""));
+        assert_eq!(
+            router.route_pattern("getPost"),
+            Some("/blog/posts/{id}/{slug?}")
+        );
+    }
+
+    #[test]
+    fn test_router_decodes_percent_encoded_params() {
+        let mut router = Router::new();
+        router.insert("/users/{id}", MethodRouter::new().get("getUser"));
+
+        let m = router.match_route(&Method::GET, "/users/%7Bid%7D").unwrap();
+        assert_eq!(m.operation_id, "getUser");
+        assert_eq!(m.params.get("id"), Some("{id}"));
+
+        let m = router
+            .match_route(&Method::GET, "/users/j%C3%BCrgen%20k")
+            .unwrap();
+        assert_eq!(m.params.get("id"), Some("jürgen k"));
+    }
+
+    #[test]
+    fn test_router_encoded_slash_stays_in_segment() {
+        let mut router = Router::new();
+        router.insert("/files/{name}", MethodRouter::new().get("getFile"));
+        router.insert("/files/{dir}/{name}", MethodRouter::new().get("getNested"));
+
+        let m = router.match_route(&Method::GET, "/files/a%2Fb").unwrap();
+        assert_eq!(m.operation_id, "getFile");
+        assert_eq!(m.params.get("name"), Some("a/b"));
+
+        let m = router.match_route(&Method::GET, "/files/a/b").unwrap();
+        assert_eq!(m.operation_id, "getNested");
+    }
+
+    #[test]
+    fn test_router_decodes_static_segments() {
+        let mut router = Router::new();
+        router.insert("/caf\u{e9}/menu", MethodRouter::new().get("getMenu"));
+
+        let m = router.match_route(&Method::GET, "/caf%C3%A9/menu").unwrap();
+        assert_eq!(m.operation_id, "getMenu");
+    }
+
+    #[test]
+    fn test_router_case_insensitive() {
+        let mut strict = Router::new();
+        strict.insert("/Users/{id}", MethodRouter::new().get("getUser"));
+        assert!(!strict.is_case_insensitive());
+        assert!(strict.match_route(&Method::GET, "/users/1").is_none());
+
+        let mut router = Router::new().case_insensitive(true);
+        router.insert("/Users/{id}", MethodRouter::new().get("getUser"));
+        router.insert("/users/me", MethodRouter::new().get("getMe"));
+        assert!(router.is_case_insensitive());
+
+        let m = router.match_route(&Method::GET, "/USERS/AbC").unwrap();
+        assert_eq!(m.operation_id, "getUser");
+        assert_eq!(m.params.get("id"), Some("AbC"));
+
+        // Exact-case static segments still win
+        let m = router.match_route(&Method::GET, "/users/me").unwrap();
+        assert_eq!(m.operation_id, "getMe");
+    }
+
+    /// Builds `n` routes spread over five shapes, as the `scaling` benchmark
+    /// does.
+    fn scaling_router(n: usize) -> Router {
+        let mut router = Router::new();
+        for i in 0..n / 5 {
+            router.route(
+                &Method::GET,
+                &format!("/api/v1/resource{i}"),
+                format!("static{i}"),
+            );
+            router.route(
+                &Method::GET,
+                &format!("/api/v1/resource{i}/{{id}}"),
+                format!("param{i}"),
+            );
+            router.route(
+                &Method::GET,
+                &format!("/api/v1/org/{{orgId}}/resource{i}/{{id}}"),
+                format!("nested{i}"),
+            );
+            router.route(
+                &Method::GET,
+                &format!("/api/v1/a/b/c/d/e/f/g/h/resource{i}"),
+                format!("deep{i}"),
+            );
+            router.route(
+                &Method::GET,
+                &format!(
+                    "/t/{{tenant}}/o/{{org}}/p/{{project}}/e/{{env}}/item{i}/{{id}}/{{version}}"
+                ),
+                format!("paramHeavy{i}"),
+            );
+        }
+        router
+    }
+
+    fn scaling_paths(i: usize) -> Vec<String> {
+        vec![
+            format!("/api/v1/resource{i}"),
+            format!("/api/v1/resource{i}/12345"),
+            format!("/api/v1/org/acme-corp/resource{i}/12345"),
+            format!("/api/v1/a/b/c/d/e/f/g/h/resource{i}"),
+            format!("/t/acme/o/eng/p/atlas/e/prod/item{i}/12345/v2"),
+        ]
+    }
+
+    /// Best time per match over a few rounds, to ride out scheduler noise.
+    fn time_per_match(router: &Router, paths: &[String]) -> Duration {
+        const ROUNDS: usize = 200;
+        let matches = u32::try_from(ROUNDS * paths.len()).unwrap();
+        (0..5)
+            .map(|_| {
+                let start = Instant::now();
+                for _ in 0..ROUNDS {
+                    for path in paths {
+                        std::hint::black_box(router.match_route(&Method::GET, path));
+                    }
+                }
+                start.elapsed() / matches
+            })
+            .min()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_router_match_time_independent_of_table_size() {
+        let small = scaling_router(10);
+        let large = scaling_router(10_000);
+        assert_eq!(large.len(), 10_000);
+
+        for i in [0, 1_000, 1_999] {
+            for path in scaling_paths(i) {
+                assert!(large.match_route(&Method::GET, &path).is_some(), "{path}");
+            }
+        }
+
+        let small_time = time_per_match(&small, &scaling_paths(1));
+        let large_time = time_per_match(&large, &scaling_paths(1_000));
+
+        // Binary search over siblings adds a logarithmic factor; a scan over
+        // the table would be about a thousand times slower.
+        assert!(
+            large_time < small_time * 10 + Duration::from_micros(5),
+            "10k routes: {large_time:?} per match, 10 routes: {small_time:?}"
+        );
+        assert!(large_time < Duration::from_millis(1), "{large_time:?} per match");
+    }
+
     #[test]
     fn test_normalize_path_empty() {
         assert_eq!(normalize_path(""), "/");
@@ -0,0 +1,438 @@
+//! Reverse routing.
+//!
+//! This module builds URLs from operation IDs so handlers can link to other
+//! operations (`Location` headers, redirects, hypermedia links) without
+//! hard-coding paths that drift from the contract.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::router::{join_paths, normalize_path, Router};
+
+/// Error returned when a URL cannot be generated for an operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UrlForError {
+    /// No route is registered for the operation
+    UnknownOperation(String),
+    /// A path parameter required by the route was not supplied
+    MissingParam {
+        /// The operation the URL was requested for
+        operation_id: String,
+        /// The missing parameter name
+        param: String,
+    },
+    /// A supplied parameter does not appear in the route
+    ExtraParam {
+        /// The operation the URL was requested for
+        operation_id: String,
+        /// The unexpected parameter name
+        param: String,
+    },
+    /// The route ends in a wildcard segment, which cannot be reversed
+    WildcardRoute(String),
+    /// No router is available to generate URLs from
+    Unavailable,
+}
+
+impl fmt::Display for UrlForError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownOperation(op) => write!(f, "no route registered for operation '{op}'"),
+            Self::MissingParam {
+                operation_id,
+                param,
+            } => write!(
+                f,
+                "missing path parameter '{param}' for operation '{operation_id}'"
+            ),
+            Self::ExtraParam {
+                operation_id,
+                param,
+            } => write!(
+                f,
+                "unexpected path parameter '{param}' for operation '{operation_id}'"
+            ),
+            Self::WildcardRoute(op) => write!(
+                f,
+                "operation '{op}' uses a wildcard route and cannot be reversed"
+            ),
+            Self::Unavailable => write!(f, "no router available for URL generation"),
+        }
+    }
+}
+
+impl std::error::Error for UrlForError {}
+
+/// Generates URLs for operations, prefixed with an optional base path.
+///
+/// The generator shares the router it was built from, so it is cheap to
+/// clone into request contexts.
+///
+/// # Example
+///
+/// ```rust
+/// use archimedes_router::{MethodRouter, Router, UrlGenerator};
+///
+/// let mut router = Router::new();
+/// router.insert("/users/{id}", MethodRouter::new().get("getUser"));
+///
+/// let urls = UrlGenerator::new(router).base_path("/api");
+/// assert_eq!(urls.url_for("getUser", &[("id", "123")]).unwrap(), "/api/users/123");
+/// ```
+#[derive(Debug, Clone)]
+pub struct UrlGenerator {
+    router: Arc<Router>,
+    base_path: Option<String>,
+}
+
+impl UrlGenerator {
+    /// Creates a generator for the given router.
+    #[must_use]
+    pub fn new(router: impl Into<Arc<Router>>) -> Self {
+        Self {
+            router: router.into(),
+            base_path: None,
+        }
+    }
+
+    /// Sets the base path prepended to every generated URL.
+    #[must_use]
+    pub fn base_path(mut self, base_path: impl Into<String>) -> Self {
+        let base_path = normalize_path(&base_path.into());
+        self.base_path = (base_path != "/").then_some(base_path);
+        self
+    }
+
+    /// Returns the configured base path, if any.
+    #[must_use]
+    pub fn get_base_path(&self) -> Option<&str> {
+        self.base_path.as_deref()
+    }
+
+    /// Builds the URL for an operation.
+    ///
+    /// # Errors
+    ///
+    /// See [`Router::url_for`].
+    pub fn url_for(
+        &self,
+        operation_id: &str,
+        params: &[(&str, &str)],
+    ) -> Result<String, UrlForError> {
+        self.url_for_with_query(operation_id, params, &[])
+    }
+
+    /// Builds the URL for an operation with query parameters appended.
+    ///
+    /// # Errors
+    ///
+    /// See [`Router::url_for`].
+    pub fn url_for_with_query(
+        &self,
+        operation_id: &str,
+        params: &[(&str, &str)],
+        query: &[(&str, &str)],
+    ) -> Result<String, UrlForError> {
+        let path = self.router.path_for(operation_id, params)?;
+        let mut url = match &self.base_path {
+            Some(base) => join_paths(base, &path),
+            None => path,
+        };
+        append_query(&mut url, query);
+        Ok(url)
+    }
+}
+
+/// Substitutes `params` into a route `pattern`.
+pub(crate) fn expand(
+    operation_id: &str,
+    pattern: &str,
+    params: &[(&str, &str)],
+) -> Result<String, UrlForError> {
+    let mut used = vec![false; params.len()];
+    let mut url = String::with_capacity(pattern.len());
+
+    for segment in pattern.split('/').filter(|s| !s.is_empty()) {
+        if segment.starts_with('*') {
+            return Err(UrlForError::WildcardRoute(operation_id.to_string()));
+        }
+        let Some(name) = segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) else {
+            url.push('/');
+            url.push_str(segment);
+            continue;
+        };
+        let (name, optional) = match name.strip_suffix('?') {
+            Some(name) => (name, true),
+            None => (name, false),
+        };
+        let Some(index) = params.iter().position(|(n, _)| *n == name) else {
+            if optional {
+                continue;
+            }
+            return Err(UrlForError::MissingParam {
+                operation_id: operation_id.to_string(),
+                param: name.to_string(),
+            });
+        };
+        used[index] = true;
+        url.push('/');
+        percent_encode_into(&mut url, params[index].1);
+    }
+
+    if let Some(index) = used.iter().position(|used| !used) {
+        return Err(UrlForError::ExtraParam {
+            operation_id: operation_id.to_string(),
+            param: params[index].0.to_string(),
+        });
+    }
+
+    if url.is_empty() {
+        url.push('/');
+    }
+    Ok(url)
+}
+
+/// Appends percent-encoded query parameters to `url`.
+pub(crate) fn append_query(url: &mut String, query: &[(&str, &str)]) {
+    for (i, (name, value)) in query.iter().enumerate() {
+        url.push(if i == 0 { '?' } else { '&' });
+        percent_encode_into(url, name);
+        url.push('=');
+        percent_encode_into(url, value);
+    }
+}
+
+/// Percent-encodes everything except RFC 3986 unreserved characters.
+fn percent_encode_into(out: &mut String, value: &str) {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            out.push(char::from(byte));
+        } else {
+            out.push('%');
+            out.push(char::from(HEX[usize::from(byte >> 4)]));
+            out.push(char::from(HEX[usize::from(byte & 0x0F)]));
+        }
+    }
+}
+
+/// Decodes `%XX` escapes in a single path segment.
+///
+/// Malformed escapes are kept literally, and a segment that does not decode
+/// to valid UTF-8 is returned unchanged.
+pub(crate) fn percent_decode(segment: &str) -> Cow<'_, str> {
+    if !segment.contains('%') {
+        return Cow::Borrowed(segment);
+    }
+
+    let bytes = segment.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            if let Some(byte) = bytes.get(i + 1..i + 3).and_then(hex_pair) {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+
+    String::from_utf8(out).map_or(Cow::Borrowed(segment), Cow::Owned)
+}
+
+/// Parses two ASCII hex digits into a byte.
+fn hex_pair(pair: &[u8]) -> Option<u8> {
+    let hi = char::from(pair[0]).to_digit(16)?;
+    let lo = char::from(pair[1]).to_digit(16)?;
+    u8::try_from(hi * 16 + lo).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MethodRouter;
+
+    fn router() -> Router {
+        let mut router = Router::new();
+        router.insert("/", MethodRouter::new().get("index"));
+        router.insert(
+            "/users",
+            MethodRouter::new().get("listUsers").post("createUser"),
+        );
+        router.insert("/users/{id}", MethodRouter::new().get("getUser"));
+        router.insert(
+            "/orgs/{orgId}/users/{userId}",
+            MethodRouter::new().get("getOrgUser"),
+        );
+        router.insert("/files/*path", MethodRouter::new().get("serveFile"));
+        router.insert("/posts/{id}/{slug?}", MethodRouter::new().get("getPost"));
+        router
+    }
+
+    #[test]
+    fn test_url_for_static_route() {
+        let router = router();
+        assert_eq!(router.url_for("listUsers", &[]).unwrap(), "/users");
+        assert_eq!(router.url_for("createUser", &[]).unwrap(), "/users");
+        assert_eq!(router.url_for("index", &[]).unwrap(), "/");
+    }
+
+    #[test]
+    fn test_url_for_substitutes_params() {
+        let router = router();
+        assert_eq!(
+            router.url_for("getUser", &[("id", "123")]).unwrap(),
+            "/users/123"
+        );
+        assert_eq!(
+            router
+                .url_for("getOrgUser", &[("userId", "7"), ("orgId", "acme")])
+                .unwrap(),
+            "/orgs/acme/users/7"
+        );
+    }
+
+    #[test]
+    fn test_url_for_encodes_reserved_characters() {
+        let router = router();
+        assert_eq!(
+            router.url_for("getUser", &[("id", "a/b c?d#e%")]).unwrap(),
+            "/users/a%2Fb%20c%3Fd%23e%25"
+        );
+        assert_eq!(
+            router.url_for("getUser", &[("id", "ü")]).unwrap(),
+            "/users/%C3%BC"
+        );
+        assert_eq!(
+            router.url_for("getUser", &[("id", "a-b.c_d~e")]).unwrap(),
+            "/users/a-b.c_d~e"
+        );
+    }
+
+    #[test]
+    fn test_url_for_appends_query() {
+        let router = router();
+        assert_eq!(
+            router
+                .url_for_with_query("listUsers", &[], &[("page", "2"), ("q", "a&b")])
+                .unwrap(),
+            "/users?page=2&q=a%26b"
+        );
+    }
+
+    #[test]
+    fn test_url_for_optional_param() {
+        let router = router();
+        assert_eq!(
+            router.url_for("getPost", &[("id", "1")]).unwrap(),
+            "/posts/1"
+        );
+        assert_eq!(
+            router
+                .url_for("getPost", &[("id", "1"), ("slug", "hello")])
+                .unwrap(),
+            "/posts/1/hello"
+        );
+    }
+
+    #[test]
+    fn test_url_for_missing_param() {
+        let err = router().url_for("getUser", &[]).unwrap_err();
+        assert_eq!(
+            err,
+            UrlForError::MissingParam {
+                operation_id: "getUser".to_string(),
+                param: "id".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_url_for_extra_param() {
+        let err = router()
+            .url_for("getUser", &[("id", "1"), ("format", "json")])
+            .unwrap_err();
+        assert_eq!(
+            err,
+            UrlForError::ExtraParam {
+                operation_id: "getUser".to_string(),
+                param: "format".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_url_for_rejects_wildcard_route() {
+        let err = router()
+            .url_for("serveFile", &[("path", "a/b")])
+            .unwrap_err();
+        assert_eq!(err, UrlForError::WildcardRoute("serveFile".to_string()));
+    }
+
+    #[test]
+    fn test_url_for_unknown_operation() {
+        let err = router().url_for("nope", &[]).unwrap_err();
+        assert_eq!(err, UrlForError::UnknownOperation("nope".to_string()));
+        assert_eq!(err.to_string(), "no route registered for operation 'nope'");
+    }
+
+    #[test]
+    fn test_url_for_nested_and_prefixed_routes() {
+        let mut users = Router::new().prefix("/users");
+        users.insert("/{id}", MethodRouter::new().get("getUser"));
+
+        let mut api = Router::new();
+        api.nest("/api/v1", users);
+
+        assert_eq!(
+            api.url_for("getUser", &[("id", "1")]).unwrap(),
+            "/api/v1/users/1"
+        );
+    }
+
+    #[test]
+    fn test_generator_base_path() {
+        let urls = UrlGenerator::new(router()).base_path("/service/");
+        assert_eq!(urls.get_base_path(), Some("/service"));
+        assert_eq!(
+            urls.url_for("getUser", &[("id", "1")]).unwrap(),
+            "/service/users/1"
+        );
+        assert_eq!(urls.url_for("index", &[]).unwrap(), "/service");
+        assert_eq!(
+            urls.url_for_with_query("index", &[], &[("a", "b")])
+                .unwrap(),
+            "/service?a=b"
+        );
+        assert_eq!(
+            urls.url_for_with_query("listUsers", &[], &[("page", "1")])
+                .unwrap(),
+            "/service/users?page=1"
+        );
+    }
+
+    #[test]
+    fn test_generator_root_base_path_is_ignored() {
+        let urls = UrlGenerator::new(router()).base_path("/");
+        assert_eq!(urls.get_base_path(), None);
+        assert_eq!(urls.url_for("listUsers", &[]).unwrap(), "/users");
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("plain"), "plain");
+        assert_eq!(percent_decode("a%20b"), "a b");
+        assert_eq!(percent_decode("%7Bid%7d"), "{id}");
+        assert_eq!(percent_decode("a%2Fb"), "a/b");
+        assert_eq!(percent_decode("%C3%BC"), "ü");
+        // Malformed escapes and invalid UTF-8 are left as-is
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%zz%4"), "%zz%4");
+        assert_eq!(percent_decode("%FF"), "%FF");
+    }
+}
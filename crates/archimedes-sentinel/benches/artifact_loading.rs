@@ -0,0 +1,137 @@
+//! Artifact loading benchmarks.
+//!
+//! Run with: `cargo bench -p archimedes-sentinel`
+//!
+//! The fixture is a synthetic OpenAPI document shaped like a large
+//! production contract: 3,000 operations sharing 1,000 component schemas.
+
+use archimedes_sentinel::{ArtifactLoader, Sentinel};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use serde_json::{json, Map, Value};
+
+/// Build a synthetic OpenAPI document with `operations` operations, each
+/// with a request header, a request body and two responses referencing
+/// shared component schemas.
+fn synthetic_openapi(operations: usize) -> String {
+    let schema_count = (operations / 3).max(1);
+
+    let mut schemas = Map::new();
+    for i in 0..schema_count {
+        schemas.insert(
+            format!("Resource{i}"),
+            json!({
+                "type": "object",
+                "required": ["id", "name"],
+                "properties": {
+                    "id": { "type": "integer" },
+                    "name": { "type": "string" },
+                    "active": { "type": "boolean" },
+                    "score": { "type": "number" },
+                    "tags": { "type": "array", "items": { "type": "string" } }
+                }
+            }),
+        );
+    }
+
+    let mut paths = Map::new();
+    for i in 0..operations {
+        let schema =
+            json!({ "$ref": format!("#/components/schemas/Resource{}", i % schema_count) });
+        paths.insert(
+            format!("/api/v1/group{}/resource{i}/{{id}}", i % 50),
+            json!({
+                "post": {
+                    "operationId": format!("createResource{i}"),
+                    "tags": [format!("group{}", i % 50)],
+                    "parameters": [
+                        { "name": "id", "in": "path", "required": true, "schema": { "type": "integer" } },
+                        {
+                            "name": "X-Request-Id",
+                            "in": "header",
+                            "required": true,
+                            "schema": { "type": "string", "pattern": "^[0-9a-f-]{8,}$" }
+                        }
+                    ],
+                    "requestBody": {
+                        "content": { "application/json": { "schema": schema } }
+                    },
+                    "responses": {
+                        "201": {
+                            "description": "Created",
+                            "content": { "application/json": { "schema": schema } }
+                        },
+                        "400": { "description": "Bad request" }
+                    }
+                }
+            }),
+        );
+    }
+
+    let doc = json!({
+        "openapi": "3.1.0",
+        "info": { "title": "synthetic-service", "version": "1.0.0" },
+        "paths": Value::Object(paths),
+        "components": { "schemas": Value::Object(schemas) }
+    });
+    serde_json::to_string(&doc).expect("fixture serializes")
+}
+
+fn bench_load(c: &mut Criterion) {
+    let mut group = c.benchmark_group("load");
+    group.sample_size(10);
+
+    for operations in [300, 3_000] {
+        let doc = synthetic_openapi(operations);
+        group.bench_with_input(
+            BenchmarkId::new("from_document", operations),
+            &doc,
+            |b, doc| {
+                b.iter(|| black_box(ArtifactLoader::from_document(doc).unwrap()));
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn bench_startup(c: &mut Criterion) {
+    let artifact = ArtifactLoader::from_document(&synthetic_openapi(3_000)).unwrap();
+    let mut group = c.benchmark_group("startup");
+    group.sample_size(10);
+
+    group.bench_function("sentinel_lazy", |b| {
+        b.iter(|| black_box(Sentinel::with_defaults(artifact.clone())));
+    });
+    group.bench_function("sentinel_precompiled", |b| {
+        b.iter(|| {
+            let sentinel = Sentinel::with_defaults(artifact.clone());
+            sentinel.precompile_all();
+            black_box(sentinel)
+        });
+    });
+
+    group.finish();
+}
+
+fn bench_first_validation(c: &mut Criterion) {
+    let artifact = ArtifactLoader::from_document(&synthetic_openapi(3_000)).unwrap();
+    let mut headers = http::HeaderMap::new();
+    headers.insert("x-request-id", "0badcafe-1234".parse().unwrap());
+
+    c.bench_function("first_header_validation_lazy", |b| {
+        b.iter_batched(
+            || Sentinel::with_defaults(artifact.clone()),
+            |sentinel| {
+                black_box(
+                    sentinel
+                        .validate_request_headers("createResource2999", &headers)
+                        .unwrap(),
+                )
+            },
+            criterion::BatchSize::LargeInput,
+        );
+    });
+}
+
+criterion_group!(benches, bench_load, bench_startup, bench_first_validation);
+criterion_main!(benches);
@@ -2,12 +2,24 @@
 //!
 //! This module provides types for loading Themis artifacts and transforming
 //! them into a format suitable for runtime operation resolution.
-
+//!
+//! Loading is kept cheap so large contracts do not slow down cold starts:
+//! JSON Themis artifacts are deserialized directly instead of through an
+//! intermediate document tree, and per-operation validation state is only
+//! built when an operation is first validated (see
+//! [`SchemaValidator::precompile_all`](crate::SchemaValidator::precompile_all)).
+//! [`LoadedArtifact::load_stats`] reports what loading cost.
+
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::path::Path;
+use std::time::{Duration, Instant};
 
+use archimedes_core::CachePolicy;
 use indexmap::IndexMap;
-use serde::{Deserialize, Serialize};
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
 use themis_artifact::{Artifact, ArtifactOperation};
 use themis_core::Schema;
 use tokio::fs;
@@ -15,6 +27,10 @@ use tracing::{debug, info};
 
 use crate::error::{SentinelError, SentinelResult};
 
+/// Media type of JSON bodies, and of every response schema of a Themis
+/// artifact.
+pub const JSON_MEDIA_TYPE: &str = "application/json";
+
 /// A loaded artifact ready for runtime use.
 ///
 /// This is a processed form of a Themis artifact optimized for
@@ -31,6 +47,81 @@ pub struct LoadedArtifact {
     pub operations: Vec<LoadedOperation>,
     /// Named schemas for validation.
     pub schemas: IndexMap<String, Schema>,
+    /// What loading the artifact cost.
+    pub stats: LoadStats,
+}
+
+impl LoadedArtifact {
+    /// Get what loading the artifact cost, for startup diagnostics.
+    pub fn load_stats(&self) -> LoadStats {
+        self.stats
+    }
+
+    /// Get the timeouts declared by operations, by operation ID.
+    ///
+    /// Operations without a timeout of their own are skipped; pass the
+    /// result to the server's `operation_timeouts`.
+    pub fn operation_timeouts(&self) -> impl Iterator<Item = (&str, Duration)> {
+        self.operations
+            .iter()
+            .filter_map(|op| Some((op.id.as_str(), op.timeout?)))
+    }
+
+    /// Get the cache policies declared by operations, by operation ID.
+    ///
+    /// Operations without a cache policy are skipped; pass the result to the
+    /// server's `cache_policies`.
+    pub fn cache_policies(&self) -> impl Iterator<Item = (&str, CachePolicy)> {
+        self.operations
+            .iter()
+            .filter_map(|op| Some((op.id.as_str(), op.cache_policy?)))
+    }
+
+    /// Get the response fields each operation returns only to callers
+    /// holding a scope, by operation ID.
+    ///
+    /// Operations without such fields are skipped.
+    pub fn redactions(&self) -> impl Iterator<Item = (&str, Vec<FieldRedaction>)> {
+        self.operations.iter().filter_map(|op| {
+            let redactions = op.redactions();
+            (!redactions.is_empty()).then_some((op.id.as_str(), redactions))
+        })
+    }
+
+    /// Record load statistics for an artifact that took `started.elapsed()`
+    /// to load from a document of `bytes` bytes.
+    fn with_stats(mut self, started: Instant, bytes: usize) -> Self {
+        self.stats = LoadStats {
+            parse_time: started.elapsed(),
+            bytes,
+            operation_count: self.operations.len(),
+            schema_count: self.schemas.len(),
+        };
+        debug!(
+            service = self.service,
+            parse_ms = self.stats.parse_time.as_millis() as u64,
+            bytes,
+            operations = self.stats.operation_count,
+            schemas = self.stats.schema_count,
+            "artifact load stats"
+        );
+        self
+    }
+}
+
+/// Statistics about loading an artifact.
+///
+/// Artifacts built in code rather than loaded report zeros.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LoadStats {
+    /// Time spent parsing and converting the document.
+    pub parse_time: Duration,
+    /// Size of the source document in bytes (zero if it was already parsed).
+    pub bytes: usize,
+    /// Number of operations loaded.
+    pub operation_count: usize,
+    /// Number of named schemas loaded.
+    pub schema_count: usize,
 }
 
 /// A loaded operation ready for runtime use.
@@ -50,10 +141,118 @@ pub struct LoadedOperation {
     pub security: Vec<String>,
     /// Request schema reference.
     pub request_schema: Option<SchemaRef>,
-    /// Response schemas by status code.
-    pub response_schemas: HashMap<String, SchemaRef>,
+    /// Response schemas by status code and media type, e.g.
+    /// `("200", "text/csv")`.
+    pub response_schemas: HashMap<(String, String), SchemaRef>,
     /// Tags.
     pub tags: Vec<String>,
+    /// Declared request headers, including ones inherited from the path
+    /// or the contract.
+    pub header_params: Vec<HeaderParam>,
+    /// Schemas of the server-sent events the operation streams, by event
+    /// type.
+    pub event_schemas: HashMap<String, SchemaRef>,
+    /// Timeout the operation declares, overriding the server's request
+    /// timeout.
+    pub timeout: Option<Duration>,
+    /// Caching the operation declares for its successful responses.
+    pub cache_policy: Option<CachePolicy>,
+    /// Path parameters declared with `allowReserved`, whose values have
+    /// percent-encoded reserved characters such as `%2F` decoded.
+    pub allow_reserved: Vec<String>,
+}
+
+impl LoadedOperation {
+    /// Get the schema of a response with the given status code and
+    /// `Content-Type`.
+    ///
+    /// Parameters of the content type, such as `charset`, are ignored.
+    /// Falls back to `type/*` and `*/*` media ranges, then to the `default`
+    /// response.
+    pub fn response_schema(&self, status: &str, content_type: &str) -> Option<&SchemaRef> {
+        let media_type = media_type(content_type);
+        let range = media_type
+            .split_once('/')
+            .map(|(kind, _)| format!("{kind}/*"));
+        [status, "default"].into_iter().find_map(|status| {
+            [Some(media_type.as_str()), range.as_deref(), Some("*/*")]
+                .into_iter()
+                .flatten()
+                .find_map(|media| {
+                    self.response_schemas
+                        .get(&(status.to_string(), media.to_string()))
+                })
+        })
+    }
+
+    /// Get the schemas of the server-sent events the operation streams, by
+    /// `event:` type.
+    ///
+    /// Events sent without a type have the type `message`.
+    pub fn event_schemas(&self) -> &HashMap<String, SchemaRef> {
+        &self.event_schemas
+    }
+
+    /// Get the response fields returned only to callers holding a scope,
+    /// across the operation's response schemas.
+    ///
+    /// Sorted by path; a field marked in several responses is listed once
+    /// per distinct scope.
+    pub fn redactions(&self) -> Vec<FieldRedaction> {
+        let mut redactions: Vec<FieldRedaction> = self
+            .response_schemas
+            .values()
+            .flat_map(SchemaRef::redactions)
+            .collect();
+        redactions.sort_by(|a, b| a.path.cmp(&b.path).then_with(|| a.scope.cmp(&b.scope)));
+        redactions.dedup();
+        redactions
+    }
+}
+
+/// Returns the media type of a `Content-Type` value, lowercased and without
+/// parameters.
+pub(crate) fn media_type(content_type: &str) -> String {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+}
+
+/// Translates a contract path template to the resolver's syntax.
+///
+/// A greedy `{name+}` parameter in the last segment becomes a `*name`
+/// wildcard, matching the rest of the path.
+pub(crate) fn contract_path(path: &str) -> String {
+    let (prefix, last) = path.rsplit_once('/').unwrap_or(("", path));
+    match last
+        .strip_prefix('{')
+        .and_then(|name| name.strip_suffix("+}"))
+    {
+        Some(name) if !name.is_empty() && !name.contains(['{', '}']) => {
+            format!("{prefix}/*{name}")
+        }
+        _ => path.to_string(),
+    }
+}
+
+/// A request header declared by an operation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeaderParam {
+    /// Header name as declared in the contract.
+    pub name: String,
+    /// Whether the header must be present.
+    pub required: bool,
+    /// Declared schema type (e.g. "string", "integer").
+    pub schema_type: String,
+    /// Regular expression the value must match.
+    #[serde(default)]
+    pub pattern: Option<String>,
+    /// Allowed values, if the schema declares an enum.
+    #[serde(default)]
+    pub allowed_values: Vec<String>,
 }
 
 /// A reference to a schema for validation.
@@ -65,9 +264,119 @@ pub struct SchemaRef {
     pub schema_type: String,
     /// Required fields (for objects).
     pub required: Vec<String>,
+    /// Declared types of object properties, by property name.
+    #[serde(default)]
+    pub properties: HashMap<String, String>,
+    /// Schemas of object and array properties, by property name, for
+    /// checking nested fields.
+    #[serde(default)]
+    pub property_schemas: HashMap<String, SchemaRef>,
+    /// Scopes a caller must hold to receive a property, by property name,
+    /// from the property's `x-required-scope` extension.
+    #[serde(default)]
+    pub required_scopes: HashMap<String, String>,
+    /// Schema of the items, for arrays.
+    #[serde(default)]
+    pub items: Option<Box<SchemaRef>>,
+    /// The schema's explicit `additionalProperties` setting, if any; a
+    /// subschema counts as `true`.
+    #[serde(default)]
+    pub additional_properties: Option<bool>,
+}
+
+impl SchemaRef {
+    /// Get the fields that require a scope, at any depth, with their paths.
+    ///
+    /// Paths join property names with `.` and mark array elements with
+    /// `[]`, e.g. `contacts[].phone`. They are sorted.
+    pub fn redactions(&self) -> Vec<FieldRedaction> {
+        let mut redactions = Vec::new();
+        self.collect_redactions("", &mut redactions);
+        redactions.sort_by(|a, b| a.path.cmp(&b.path));
+        redactions
+    }
+
+    fn collect_redactions(&self, prefix: &str, redactions: &mut Vec<FieldRedaction>) {
+        let join = |name: &str| {
+            if prefix.is_empty() {
+                name.to_string()
+            } else {
+                format!("{prefix}.{name}")
+            }
+        };
+        for (name, scope) in &self.required_scopes {
+            redactions.push(FieldRedaction {
+                path: join(name),
+                scope: scope.clone(),
+            });
+        }
+        for (name, schema) in &self.property_schemas {
+            // A field requiring a scope is removed or masked as a whole
+            if !self.required_scopes.contains_key(name) {
+                schema.collect_redactions(&join(name), redactions);
+            }
+        }
+        if let Some(items) = &self.items {
+            items.collect_redactions(&format!("{prefix}[]"), redactions);
+        }
+    }
+}
+
+/// A response field returned only to callers holding a scope.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FieldRedaction {
+    /// Path of the field in the response body, e.g. `contacts[].phone`.
+    pub path: String,
+    /// Scope the caller must hold to receive the field.
+    pub scope: String,
+}
+
+/// Document formats recognized by [`ArtifactLoader`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentFormat {
+    /// A compiled Themis artifact (`*.artifact.json`).
+    Themis,
+    /// An OpenAPI 3.x specification.
+    OpenApi,
+}
+
+impl DocumentFormat {
+    /// Detects the format of a parsed document from its top-level keys.
+    ///
+    /// An `openapi` key marks an OpenAPI spec. A `themis` key, a Themis
+    /// `$schema` URL, or a `format` field next to `operations` marks a
+    /// Themis artifact.
+    pub fn detect(doc: &Value) -> Option<Self> {
+        let obj = doc.as_object()?;
+        if obj.contains_key("openapi") {
+            return Some(Self::OpenApi);
+        }
+
+        let themis_schema = obj
+            .get("$schema")
+            .and_then(Value::as_str)
+            .is_some_and(|s| s.contains("themis"));
+        let themis_shape = obj.contains_key("format") && obj.contains_key("operations");
+        if obj.contains_key("themis") || themis_schema || themis_shape {
+            return Some(Self::Themis);
+        }
+
+        None
+    }
+
+    /// Returns a human-readable name for error messages.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Themis => "Themis artifact",
+            Self::OpenApi => "OpenAPI",
+        }
+    }
 }
 
 /// Loads artifacts from various sources.
+///
+/// File and string inputs may be Themis artifacts or OpenAPI 3.x specs, in
+/// JSON or YAML; the format is detected from the document's content.
 pub struct ArtifactLoader;
 
 impl ArtifactLoader {
@@ -84,16 +393,107 @@ impl ArtifactLoader {
             ))
         })?;
 
-        Self::from_json(&content)
+        Self::from_document(&content)
+    }
+
+    /// Load an artifact from a JSON or YAML document, detecting its format.
+    pub fn from_document(content: &str) -> SentinelResult<LoadedArtifact> {
+        let started = Instant::now();
+        if let Some(result) = Self::themis_from_json(content) {
+            return result.map(|artifact| artifact.with_stats(started, content.len()));
+        }
+
+        let doc = match serde_json::from_str::<Value>(content) {
+            Ok(doc) => doc,
+            Err(json_err) => serde_yaml::from_str::<Value>(content).map_err(|yaml_err| {
+                let trimmed = content.trim_start();
+                let err = if trimmed.starts_with('{') || trimmed.starts_with('[') {
+                    format!("invalid JSON: {}", json_err)
+                } else {
+                    format!("invalid YAML: {}", yaml_err)
+                };
+                SentinelError::ArtifactParse(err)
+            })?,
+        };
+
+        Ok(Self::from_value(doc)?.with_stats(started, content.len()))
     }
 
     /// Load an artifact from JSON string.
+    ///
+    /// Accepts Themis artifacts and OpenAPI specs.
     pub fn from_json(json: &str) -> SentinelResult<LoadedArtifact> {
-        let artifact: Artifact = serde_json::from_str(json).map_err(|e| {
+        let started = Instant::now();
+        if let Some(result) = Self::themis_from_json(json) {
+            return result.map(|artifact| artifact.with_stats(started, json.len()));
+        }
+
+        let doc: Value = serde_json::from_str(json).map_err(|e| {
             SentinelError::ArtifactLoad(format!("failed to parse artifact JSON: {}", e))
         })?;
 
-        Self::from_artifact(artifact)
+        Ok(Self::from_value(doc)?.with_stats(started, json.len()))
+    }
+
+    /// Load an artifact from YAML string.
+    ///
+    /// Accepts Themis artifacts and OpenAPI specs.
+    pub fn from_yaml(yaml: &str) -> SentinelResult<LoadedArtifact> {
+        let started = Instant::now();
+        let doc: Value = serde_yaml::from_str(yaml).map_err(|e| {
+            SentinelError::ArtifactLoad(format!("failed to parse artifact YAML: {}", e))
+        })?;
+
+        Ok(Self::from_value(doc)?.with_stats(started, yaml.len()))
+    }
+
+    /// Load a JSON Themis artifact straight from its text.
+    ///
+    /// Deserializing the artifact directly skips building a [`Value`] tree
+    /// of the whole document first, which dominates the load time of large
+    /// artifacts. Returns `None` if the text is not a JSON Themis artifact.
+    fn themis_from_json(content: &str) -> Option<SentinelResult<LoadedArtifact>> {
+        if FormatProbe::detect(content)? != DocumentFormat::Themis {
+            return None;
+        }
+        let artifact = serde_json::from_str::<Artifact>(content).map_err(|e| {
+            SentinelError::ArtifactParse(format!(
+                "detected {} but failed to parse it: {}",
+                DocumentFormat::Themis.as_str(),
+                e
+            ))
+        });
+        Some(artifact.and_then(Self::from_artifact))
+    }
+
+    /// Load an artifact from a parsed document, detecting its format.
+    pub fn from_value(doc: Value) -> SentinelResult<LoadedArtifact> {
+        match DocumentFormat::detect(&doc) {
+            Some(DocumentFormat::OpenApi) => {
+                let version = doc
+                    .get("openapi")
+                    .and_then(crate::openapi::as_text)
+                    .unwrap_or_default();
+                if !version.starts_with('3') {
+                    return Err(SentinelError::ArtifactParse(format!(
+                        "detected OpenAPI {} document; only OpenAPI 3.x is supported",
+                        version
+                    )));
+                }
+                crate::openapi::to_loaded_artifact(&doc)
+            }
+            Some(DocumentFormat::Themis) => {
+                let artifact: Artifact = serde_json::from_value(doc).map_err(|e| {
+                    SentinelError::ArtifactParse(format!(
+                        "detected {} but failed to parse it: {}",
+                        DocumentFormat::Themis.as_str(),
+                        e
+                    ))
+                })?;
+                Self::from_artifact(artifact)
+            }
+            None => Err(SentinelError::ArtifactParse(describe_unrecognized(&doc))),
+        }
     }
 
     /// Load an artifact from a registry.
@@ -102,6 +502,16 @@ impl ArtifactLoader {
         service: &str,
         version: &str,
     ) -> SentinelResult<LoadedArtifact> {
+        let json = Self::fetch_registry_document(registry_url, service, version).await?;
+        Self::from_json(&json)
+    }
+
+    /// Fetch the text of an artifact from a registry without parsing it.
+    pub(crate) async fn fetch_registry_document(
+        registry_url: &str,
+        service: &str,
+        version: &str,
+    ) -> SentinelResult<String> {
         info!(
             registry = registry_url,
             service, version, "loading artifact from registry"
@@ -123,11 +533,9 @@ impl ArtifactLoader {
             )));
         }
 
-        let json = response.text().await.map_err(|e| {
+        response.text().await.map_err(|e| {
             SentinelError::ArtifactLoad(format!("failed to read registry response: {}", e))
-        })?;
-
-        Self::from_json(&json)
+        })
     }
 
     /// Convert a Themis Artifact to a LoadedArtifact.
@@ -157,6 +565,7 @@ impl ArtifactLoader {
             format: artifact.format,
             operations,
             schemas: artifact.schemas,
+            stats: LoadStats::default(),
         })
     }
 
@@ -164,7 +573,7 @@ impl ArtifactLoader {
         LoadedOperation {
             id: op.id.clone(),
             method: op.method.to_uppercase(),
-            path: op.path.clone(),
+            path: contract_path(&op.path),
             summary: op.summary.clone(),
             deprecated: op.deprecated,
             security: op.security.clone(),
@@ -172,9 +581,20 @@ impl ArtifactLoader {
             response_schemas: op
                 .response_schemas
                 .iter()
-                .map(|(k, v)| (k.clone(), Self::schema_to_ref(v)))
+                .map(|(k, v)| {
+                    (
+                        (k.clone(), JSON_MEDIA_TYPE.to_string()),
+                        Self::schema_to_ref(v),
+                    )
+                })
                 .collect(),
             tags: op.tags.clone(),
+            // Themis artifacts carry no header parameters yet
+            header_params: Vec::new(),
+            event_schemas: HashMap::new(),
+            timeout: None,
+            cache_policy: None,
+            allow_reserved: Vec::new(),
         }
     }
 
@@ -206,10 +626,81 @@ impl ArtifactLoader {
             reference,
             schema_type,
             required,
+            properties: HashMap::new(),
+            property_schemas: HashMap::new(),
+            required_scopes: HashMap::new(),
+            items: None,
+            additional_properties: None,
         }
     }
 }
 
+/// The top-level keys of a JSON document that decide its format.
+///
+/// Deserializing into this skips every value it does not borrow, so
+/// detecting the format of a large document allocates almost nothing.
+#[derive(Deserialize)]
+struct FormatProbe<'a> {
+    #[serde(default, deserialize_with = "present")]
+    openapi: bool,
+    #[serde(default, deserialize_with = "present")]
+    themis: bool,
+    #[serde(rename = "$schema", default, borrow)]
+    schema: Option<Cow<'a, str>>,
+    #[serde(default, deserialize_with = "present")]
+    format: bool,
+    #[serde(default, deserialize_with = "present")]
+    operations: bool,
+}
+
+impl FormatProbe<'_> {
+    /// Detects the format of a JSON document, applying the same rules as
+    /// [`DocumentFormat::detect`].
+    ///
+    /// Returns `None` if the text is not a JSON object or matches no format.
+    fn detect(content: &str) -> Option<DocumentFormat> {
+        let probe: FormatProbe<'_> = serde_json::from_str(content).ok()?;
+        if probe.openapi {
+            return Some(DocumentFormat::OpenApi);
+        }
+        let themis_schema = probe.schema.is_some_and(|s| s.contains("themis"));
+        if probe.themis || themis_schema || (probe.format && probe.operations) {
+            return Some(DocumentFormat::Themis);
+        }
+        None
+    }
+}
+
+/// Marks a key as present, whatever its value.
+fn present<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    IgnoredAny::deserialize(deserializer).map(|_| true)
+}
+
+/// Explains why a document was not recognized as a contract.
+fn describe_unrecognized(doc: &Value) -> String {
+    let found = match doc {
+        Value::Object(obj) if obj.contains_key("swagger") => {
+            "a Swagger 2.0 document (convert it to OpenAPI 3.x)".to_string()
+        }
+        Value::Object(obj) if obj.is_empty() => "an empty object".to_string(),
+        Value::Object(obj) => format!(
+            "top-level keys [{}]",
+            obj.keys()
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Value::Array(_) => "an array".to_string(),
+        Value::Null => "an empty document".to_string(),
+        _ => "a scalar value".to_string(),
+    };
+    format!(
+        "unrecognized artifact format: expected an OpenAPI spec (`openapi` key) or a Themis \
+         artifact (`themis`, `$schema` or `format` key), found {}",
+        found
+    )
+}
+
 impl From<Artifact> for LoadedArtifact {
     fn from(artifact: Artifact) -> Self {
         // Note: This doesn't verify checksum - use ArtifactLoader::from_artifact for that
@@ -225,6 +716,7 @@ impl From<Artifact> for LoadedArtifact {
             format: artifact.format,
             operations,
             schemas: artifact.schemas,
+            stats: LoadStats::default(),
         }
     }
 }
@@ -273,6 +765,11 @@ mod tests {
             reference: "#/components/schemas/User".to_string(),
             schema_type: "object".to_string(),
             required: vec!["id".to_string(), "name".to_string()],
+            properties: HashMap::new(),
+            property_schemas: HashMap::new(),
+            required_scopes: HashMap::new(),
+            items: None,
+            additional_properties: None,
         };
 
         assert_eq!(schema_ref.schema_type, "object");
@@ -281,4 +778,162 @@ mod tests {
 
     // Note: Full parsing tests would require proper checksum validation
     // which is complex to set up in unit tests
+
+    const OPENAPI_YAML: &str = r##"
+openapi: 3.1.0
+info:
+  title: users-service
+  version: 2.1.0
+security:
+  - bearerAuth: [users:read]
+paths:
+  /users:
+    get:
+      operationId: listUsers
+      tags: [users]
+      responses:
+        "200":
+          description: OK
+          content:
+            application/json:
+              schema:
+                type: array
+    post:
+      operationId: createUser
+      security:
+        - bearerAuth: [users:write]
+      requestBody:
+        content:
+          application/json:
+            schema:
+              $ref: "#/components/schemas/NewUser"
+      responses:
+        "201":
+          description: Created
+  /users/{userId}:
+    get:
+      deprecated: true
+      responses:
+        "200":
+          description: OK
+components:
+  schemas:
+    NewUser:
+      type: object
+      required: [name]
+      properties:
+        name:
+          type: string
+"##;
+
+    #[test]
+    fn test_probe_matches_detect() {
+        let docs = [
+            create_test_artifact_json(),
+            serde_json::to_string(&serde_yaml::from_str::<Value>(OPENAPI_YAML).unwrap()).unwrap(),
+            r#"{"themis": {}, "openapi": null}"#.to_string(),
+            r#"{"format": "openapi", "operations": []}"#.to_string(),
+            r#"{"$schema": 42}"#.to_string(),
+            r#"{"name": "not a contract"}"#.to_string(),
+            "[1, 2]".to_string(),
+        ];
+        for doc in docs {
+            let value: Value = serde_json::from_str(&doc).unwrap();
+            assert_eq!(
+                FormatProbe::detect(&doc),
+                DocumentFormat::detect(&value),
+                "{doc}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_load_stats() {
+        let artifact = ArtifactLoader::from_document(OPENAPI_YAML).unwrap();
+        let stats = artifact.load_stats();
+        assert_eq!(stats.bytes, OPENAPI_YAML.len());
+        assert_eq!(stats.operation_count, 3);
+        assert_eq!(stats.schema_count, 1);
+    }
+
+    #[test]
+    fn test_detect_formats() {
+        let openapi: Value = serde_yaml::from_str(OPENAPI_YAML).unwrap();
+        assert_eq!(
+            DocumentFormat::detect(&openapi),
+            Some(DocumentFormat::OpenApi)
+        );
+
+        let themis: Value = serde_json::from_str(&create_test_artifact_json()).unwrap();
+        assert_eq!(
+            DocumentFormat::detect(&themis),
+            Some(DocumentFormat::Themis)
+        );
+
+        let other = serde_json::json!({ "name": "not a contract" });
+        assert_eq!(DocumentFormat::detect(&other), None);
+    }
+
+    #[test]
+    fn test_load_openapi_yaml() {
+        let artifact = ArtifactLoader::from_document(OPENAPI_YAML).unwrap();
+
+        assert_eq!(artifact.service, "users-service");
+        assert_eq!(artifact.version, "2.1.0");
+        assert_eq!(artifact.format, "openapi");
+        assert_eq!(artifact.operations.len(), 3);
+
+        let list = &artifact.operations[0];
+        assert_eq!(list.id, "listUsers");
+        assert_eq!(list.method, "GET");
+        assert_eq!(list.tags, vec!["users".to_string()]);
+        assert_eq!(list.security, vec!["bearerAuth[users:read]".to_string()]);
+        assert_eq!(
+            list.response_schema("200", JSON_MEDIA_TYPE)
+                .unwrap()
+                .schema_type,
+            "array"
+        );
+
+        let create = &artifact.operations[1];
+        assert_eq!(create.id, "createUser");
+        assert_eq!(create.security, vec!["bearerAuth[users:write]".to_string()]);
+        let request = create.request_schema.as_ref().unwrap();
+        assert_eq!(request.reference, "#/components/schemas/NewUser");
+        assert_eq!(request.schema_type, "object");
+        assert_eq!(request.required, vec!["name".to_string()]);
+
+        let get = &artifact.operations[2];
+        assert_eq!(get.id, "get_users_userId");
+        assert!(get.deprecated);
+    }
+
+    #[test]
+    fn test_load_themis_artifact_json() {
+        // The fixture carries a placeholder checksum, so loading stops at
+        // checksum verification, after the format was detected and parsed.
+        let err = ArtifactLoader::from_document(&create_test_artifact_json()).unwrap_err();
+        assert!(err.to_string().contains("checksum"), "{err}");
+    }
+
+    #[test]
+    fn test_load_unrecognized_document() {
+        let err = ArtifactLoader::from_document("name: not a contract\nitems: []\n").unwrap_err();
+        let message = err.to_string();
+        assert!(matches!(err, SentinelError::ArtifactParse(_)));
+        assert!(
+            message.contains("unrecognized artifact format"),
+            "{message}"
+        );
+        assert!(message.contains("[name, items]"), "{message}");
+
+        let err = ArtifactLoader::from_json(r#"{"swagger": "2.0"}"#).unwrap_err();
+        assert!(err.to_string().contains("Swagger 2.0"));
+    }
+
+    #[test]
+    fn test_load_invalid_json() {
+        let err = ArtifactLoader::from_document("{ not json").unwrap_err();
+        assert!(err.to_string().contains("invalid JSON"), "{err}");
+    }
 }
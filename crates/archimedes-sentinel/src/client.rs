@@ -0,0 +1,731 @@
+//! Contract-aware client for calling other services.
+//!
+//! [`ContractClient`] calls the operations of another service through that
+//! service's contract rather than hand-written paths and request types:
+//!
+//! - URLs are built from the operation's path template, so a renamed path
+//!   does not drift from the caller.
+//! - Request bodies are validated against the operation's request schema
+//!   before they are sent, so a body that no longer matches the contract
+//!   fails at the caller rather than at the callee.
+//! - Response bodies are validated against the operation's response
+//!   schemas, logging or rejecting mismatches (see
+//!   [`ResponseValidationMode`]).
+//! - The request ID, trace context and deadline of the calling request are
+//!   propagated (see [`CallContext`]).
+//!
+//! Unknown operation IDs and missing or unknown path parameters are errors
+//! at call time.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use archimedes_sentinel::{ArtifactLoader, CallContext, ContractClient, PathParams, Query};
+//!
+//! let artifact = ArtifactLoader::from_file("users.artifact.json").await?;
+//! let users = ContractClient::new(artifact, "http://users.internal", reqwest::Client::new())?;
+//!
+//! let response = users
+//!     .call_in(
+//!         &CallContext::from(&ctx),
+//!         "getUser",
+//!         PathParams::new().with("userId", "42"),
+//!         Query::new(),
+//!         None,
+//!     )
+//!     .await?;
+//! let user: User = response.json()?;
+//! ```
+
+use std::time::Instant;
+
+use archimedes_core::RequestContext;
+use http::{HeaderMap, HeaderValue, Method, StatusCode};
+use reqwest::Url;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tracing::{debug, warn};
+
+use crate::artifact::{LoadedArtifact, LoadedOperation};
+use crate::config::ValidationConfig;
+use crate::error::{SentinelError, SentinelResult};
+use crate::validation::SchemaValidator;
+
+/// Header carrying the request ID.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Header carrying the W3C trace context.
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// Header carrying the time left until the caller's deadline, in
+/// milliseconds.
+pub const DEADLINE_HEADER: &str = "x-request-deadline-ms";
+
+/// What to do with a response that does not match the contract.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResponseValidationMode {
+    /// Log the mismatch and return the response.
+    #[default]
+    LogOnly,
+    /// Fail the call with [`SentinelError::ResponseValidation`].
+    Reject,
+}
+
+/// Values of an operation's path parameters, by name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathParams(Vec<(String, String)>);
+
+impl PathParams {
+    /// Create an empty set of path parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a path parameter.
+    pub fn with(mut self, name: impl Into<String>, value: impl ToString) -> Self {
+        self.0.push((name.into(), value.to_string()));
+        self
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(param, _)| param == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Query parameters of a call, in order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Query(Vec<(String, String)>);
+
+impl Query {
+    /// Create an empty query.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a query parameter; a name may be added more than once.
+    pub fn with(mut self, name: impl Into<String>, value: impl ToString) -> Self {
+        self.0.push((name.into(), value.to_string()));
+        self
+    }
+}
+
+/// Context of the request on whose behalf a call is made.
+///
+/// Propagated to the callee as the `x-request-id`, `traceparent` and
+/// `x-request-deadline-ms` headers. The deadline also bounds the call.
+#[derive(Debug, Clone, Default)]
+pub struct CallContext {
+    request_id: Option<String>,
+    traceparent: Option<String>,
+    deadline: Option<Instant>,
+}
+
+impl CallContext {
+    /// Create an empty context; nothing is propagated.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Propagate a request ID.
+    pub fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.request_id = Some(request_id.into());
+        self
+    }
+
+    /// Propagate a trace, as the parent of the call.
+    pub fn with_trace(mut self, trace_id: &str, span_id: &str) -> Self {
+        self.traceparent = Some(format!("00-{trace_id}-{span_id}-01"));
+        self
+    }
+
+    /// Bound the call by a deadline, and propagate it.
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+}
+
+impl From<&RequestContext> for CallContext {
+    fn from(ctx: &RequestContext) -> Self {
+        let context = Self::new().with_request_id(ctx.request_id().to_string());
+        match (ctx.trace_id(), ctx.span_id()) {
+            (Some(trace_id), Some(span_id)) => context.with_trace(trace_id, span_id),
+            _ => context,
+        }
+    }
+}
+
+/// The response to a contract call.
+#[derive(Debug, Clone)]
+pub struct TypedResponse {
+    /// Response status.
+    pub status: StatusCode,
+    /// Response headers.
+    pub headers: HeaderMap,
+    /// Response body, if it is JSON.
+    pub body: Option<Value>,
+}
+
+impl TypedResponse {
+    /// Check if the status is 2xx.
+    pub fn is_success(&self) -> bool {
+        self.status.is_success()
+    }
+
+    /// Deserialize the body.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the body is missing or does not deserialize
+    /// into `T`.
+    pub fn json<T: DeserializeOwned>(&self) -> SentinelResult<T> {
+        let body = self.body.clone().unwrap_or(Value::Null);
+        serde_json::from_value(body)
+            .map_err(|e| SentinelError::Client(format!("failed to decode response body: {}", e)))
+    }
+}
+
+/// A client calling another service's operations through its contract.
+#[derive(Debug)]
+pub struct ContractClient {
+    artifact: LoadedArtifact,
+    validator: SchemaValidator,
+    base_url: Url,
+    http: reqwest::Client,
+    response_validation: ResponseValidationMode,
+}
+
+impl ContractClient {
+    /// Create a client for the service described by `artifact`, reachable
+    /// at `base_url`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `base_url` is not a valid base URL.
+    pub fn new(
+        artifact: LoadedArtifact,
+        base_url: &str,
+        http: reqwest::Client,
+    ) -> SentinelResult<Self> {
+        let base_url = Url::parse(base_url)
+            .ok()
+            .filter(|url| !url.cannot_be_a_base())
+            .ok_or_else(|| SentinelError::Client(format!("invalid base URL '{}'", base_url)))?;
+        let validator = SchemaValidator::from_artifact(&artifact, ValidationConfig::default());
+        Ok(Self {
+            artifact,
+            validator,
+            base_url,
+            http,
+            response_validation: ResponseValidationMode::default(),
+        })
+    }
+
+    /// Set what to do with responses that do not match the contract.
+    pub fn with_response_validation(mut self, mode: ResponseValidationMode) -> Self {
+        self.response_validation = mode;
+        self
+    }
+
+    /// Get the contract of the called service.
+    pub fn artifact(&self) -> &LoadedArtifact {
+        &self.artifact
+    }
+
+    /// Build the URL of an operation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the operation is unknown, or a path parameter
+    /// is missing or not declared by the operation's path.
+    pub fn url_for(
+        &self,
+        operation_id: &str,
+        path: &PathParams,
+        query: &Query,
+    ) -> SentinelResult<Url> {
+        let operation = self.operation(operation_id)?;
+        self.build_url(operation, path, query)
+    }
+
+    /// Call an operation.
+    ///
+    /// # Errors
+    ///
+    /// See [`call_in`](Self::call_in).
+    pub async fn call(
+        &self,
+        operation_id: &str,
+        path: PathParams,
+        query: Query,
+        body: Option<Value>,
+    ) -> SentinelResult<TypedResponse> {
+        self.call_in(&CallContext::new(), operation_id, path, query, body)
+            .await
+    }
+
+    /// Call an operation on behalf of a request.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - the operation is unknown or the path parameters do not match its
+    ///   path
+    /// - the body does not match the operation's request schema; nothing
+    ///   is sent
+    /// - the deadline has passed or the call fails
+    /// - the response does not match the contract, in
+    ///   [`ResponseValidationMode::Reject`]
+    ///
+    /// Error statuses returned by the service are responses, not errors.
+    pub async fn call_in(
+        &self,
+        context: &CallContext,
+        operation_id: &str,
+        path: PathParams,
+        query: Query,
+        body: Option<Value>,
+    ) -> SentinelResult<TypedResponse> {
+        let operation = self.operation(operation_id)?;
+        let url = self.build_url(operation, &path, &query)?;
+
+        if let Some(body) = &body {
+            let result = self
+                .validator
+                .validate_request(operation_id, &self.artifact, body)?;
+            if !result.valid {
+                return Err(SentinelError::RequestValidation {
+                    operation_id: operation_id.to_string(),
+                    errors: result.errors,
+                });
+            }
+        }
+
+        let method = Method::from_bytes(operation.method.as_bytes()).map_err(|_| {
+            SentinelError::Client(format!(
+                "operation '{}' has invalid method '{}'",
+                operation_id, operation.method
+            ))
+        })?;
+        let mut request = self
+            .http
+            .request(method, url)
+            .headers(Self::propagated_headers(context));
+        if let Some(deadline) = context.deadline {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(SentinelError::Client(format!(
+                    "deadline passed before calling '{}'",
+                    operation_id
+                )));
+            }
+            request = request
+                .timeout(remaining)
+                .header(DEADLINE_HEADER, remaining.as_millis().to_string());
+        }
+        if let Some(body) = &body {
+            request = request.json(body);
+        }
+
+        debug!(operation_id, "calling operation");
+        let response = request
+            .send()
+            .await
+            .map_err(|e| SentinelError::Client(format!("'{}': {}", operation_id, e)))?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| SentinelError::Client(format!("'{}': {}", operation_id, e)))?;
+        let body = serde_json::from_slice::<Value>(&bytes).ok();
+
+        if let Some(body) = &body {
+            self.check_response(operation_id, status, body)?;
+        }
+
+        Ok(TypedResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+
+    fn operation(&self, operation_id: &str) -> SentinelResult<&LoadedOperation> {
+        self.artifact
+            .operations
+            .iter()
+            .find(|op| op.id == operation_id)
+            .ok_or_else(|| SentinelError::UnknownOperation {
+                operation_id: operation_id.to_string(),
+            })
+    }
+
+    /// Build the URL of an operation by filling in its path template.
+    fn build_url(
+        &self,
+        operation: &LoadedOperation,
+        path: &PathParams,
+        query: &Query,
+    ) -> SentinelResult<Url> {
+        let mut used = Vec::new();
+        let mut segments = Vec::new();
+        for segment in operation.path.split('/').filter(|s| !s.is_empty()) {
+            segments.push(fill_segment(segment, path, &mut used, &operation.path)?);
+        }
+        if let Some((name, _)) = path.0.iter().find(|(name, _)| !used.contains(name)) {
+            return Err(SentinelError::PathParameterError {
+                parameter: name.clone(),
+                message: format!("not a parameter of '{}'", operation.path),
+            });
+        }
+
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .map_err(|()| SentinelError::Client("base URL cannot have a path".to_string()))?
+            .pop_if_empty()
+            .extend(&segments);
+        if !query.0.is_empty() {
+            url.query_pairs_mut().extend_pairs(&query.0);
+        }
+        Ok(url)
+    }
+
+    fn propagated_headers(context: &CallContext) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        let values = [
+            (REQUEST_ID_HEADER, &context.request_id),
+            (TRACEPARENT_HEADER, &context.traceparent),
+        ];
+        for (name, value) in values {
+            if let Some(value) = value.as_deref().and_then(|v| HeaderValue::from_str(v).ok()) {
+                headers.insert(name, value);
+            }
+        }
+        headers
+    }
+
+    fn check_response(
+        &self,
+        operation_id: &str,
+        status: StatusCode,
+        body: &Value,
+    ) -> SentinelResult<()> {
+        let result = self.validator.validate_response(
+            operation_id,
+            &self.artifact,
+            status.as_u16(),
+            body,
+        )?;
+        if result.valid {
+            return Ok(());
+        }
+
+        warn!(
+            operation_id,
+            status = status.as_u16(),
+            errors = ?result.errors,
+            mode = ?self.response_validation,
+            "response does not match the contract"
+        );
+        match self.response_validation {
+            ResponseValidationMode::LogOnly => Ok(()),
+            ResponseValidationMode::Reject => Err(SentinelError::ResponseValidation {
+                operation_id: operation_id.to_string(),
+                status_code: status.as_u16(),
+                errors: result.errors,
+            }),
+        }
+    }
+}
+
+/// Fill the parameters of one path template segment, such as `{userId}`
+/// or `{name}.json`.
+fn fill_segment(
+    segment: &str,
+    path: &PathParams,
+    used: &mut Vec<String>,
+    template: &str,
+) -> SentinelResult<String> {
+    let mut filled = String::new();
+    let mut rest = segment;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+            break;
+        };
+        let name = &rest[start + 1..end];
+        let value = path
+            .get(name)
+            .ok_or_else(|| SentinelError::PathParameterError {
+                parameter: name.to_string(),
+                message: format!("missing value for '{}'", template),
+            })?;
+        filled.push_str(&rest[..start]);
+        filled.push_str(value);
+        used.push(name.to_string());
+        rest = &rest[end + 1..];
+    }
+    filled.push_str(rest);
+    Ok(filled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artifact::ArtifactLoader;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// The user-service contract used across the Sentinel tests.
+    fn user_service() -> LoadedArtifact {
+        let doc = serde_json::json!({
+            "openapi": "3.1.0",
+            "info": { "title": "user-service", "version": "1.0.0" },
+            "paths": {
+                "/users": {
+                    "post": {
+                        "operationId": "createUser",
+                        "requestBody": {
+                            "content": {
+                                "application/json": {
+                                    "schema": { "$ref": "#/components/schemas/CreateUser" }
+                                }
+                            }
+                        },
+                        "responses": {
+                            "201": {
+                                "description": "Created",
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/User" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+                "/users/{userId}": {
+                    "get": {
+                        "operationId": "getUser",
+                        "responses": {
+                            "200": {
+                                "description": "User",
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/User" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "CreateUser": {
+                        "type": "object",
+                        "required": ["name", "email"],
+                        "properties": {
+                            "name": { "type": "string" },
+                            "email": { "type": "string" }
+                        }
+                    },
+                    "User": {
+                        "type": "object",
+                        "required": ["id", "name", "email"],
+                        "properties": {
+                            "id": { "type": "string" },
+                            "name": { "type": "string" },
+                            "email": { "type": "string" }
+                        }
+                    }
+                }
+            }
+        });
+        ArtifactLoader::from_document(&doc.to_string()).unwrap()
+    }
+
+    /// Serve one request with `status` and `body`, returning the base URL
+    /// and the raw request received.
+    async fn mock_server(
+        status: u16,
+        body: &'static str,
+    ) -> (String, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            loop {
+                let n = stream.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some(head_end) = text.find("\r\n\r\n") {
+                    let length = text[..head_end]
+                        .lines()
+                        .find_map(|line| {
+                            let (name, value) = line.split_once(':')?;
+                            name.eq_ignore_ascii_case("content-length")
+                                .then(|| value.trim().parse::<usize>().ok())?
+                        })
+                        .unwrap_or(0);
+                    if request.len() >= head_end + 4 + length {
+                        break;
+                    }
+                }
+                if n == 0 {
+                    break;
+                }
+            }
+            let response = format!(
+                "HTTP/1.1 {status} OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&request).to_string()
+        });
+        (base_url, handle)
+    }
+
+    fn client(base_url: &str) -> ContractClient {
+        ContractClient::new(user_service(), base_url, reqwest::Client::new()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_call_builds_url_and_propagates_context() {
+        let (base_url, server) =
+            mock_server(200, r#"{"id":"42","name":"Ada","email":"ada@example.com"}"#).await;
+        let context = CallContext::new()
+            .with_request_id("req-1")
+            .with_trace("4bf92f3577b34da6a3ce929d0e0e4736", "00f067aa0ba902b7")
+            .with_deadline(Instant::now() + std::time::Duration::from_secs(5));
+
+        let response = client(&base_url)
+            .call_in(
+                &context,
+                "getUser",
+                PathParams::new().with("userId", "42"),
+                Query::new().with("expand", "groups"),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status, StatusCode::OK);
+        assert_eq!(response.json::<Value>().unwrap()["name"], "Ada");
+
+        let request = server.await.unwrap();
+        assert!(request.starts_with("GET /users/42?expand=groups HTTP/1.1"));
+        assert!(request.contains("x-request-id: req-1"));
+        assert!(request
+            .contains("traceparent: 00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"));
+        assert!(request.contains("x-request-deadline-ms: "));
+    }
+
+    #[tokio::test]
+    async fn test_drifted_request_body_caught_before_sending() {
+        let (base_url, server) = mock_server(201, "{}").await;
+
+        // The contract renamed `mail` to `email`
+        let body = serde_json::json!({ "name": "Ada", "mail": "ada@example.com" });
+        let err = client(&base_url)
+            .call("createUser", PathParams::new(), Query::new(), Some(body))
+            .await
+            .unwrap_err();
+
+        match &err {
+            SentinelError::RequestValidation { errors, .. } => {
+                assert!(errors.iter().any(|e| e.path == "email"));
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+        assert!(err.to_string().contains("missing required field 'email'"));
+        // Nothing reached the server
+        assert!(!server.is_finished());
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn test_response_validation_modes() {
+        let drifted = r#"{"id":"42","name":"Ada"}"#;
+
+        let (base_url, _server) = mock_server(200, drifted).await;
+        let response = client(&base_url)
+            .call(
+                "getUser",
+                PathParams::new().with("userId", "42"),
+                Query::new(),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status, StatusCode::OK);
+
+        let (base_url, _server) = mock_server(200, drifted).await;
+        let err = client(&base_url)
+            .with_response_validation(ResponseValidationMode::Reject)
+            .call(
+                "getUser",
+                PathParams::new().with("userId", "42"),
+                Query::new(),
+                None,
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            SentinelError::ResponseValidation {
+                status_code: 200,
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_operation_and_param_mismatches() {
+        let client = client("http://localhost:1");
+
+        let err = client
+            .call("deleteUser", PathParams::new(), Query::new(), None)
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "unknown operation 'deleteUser'");
+
+        let err = client
+            .url_for("getUser", &PathParams::new(), &Query::new())
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            SentinelError::PathParameterError { ref parameter, .. } if parameter == "userId"
+        ));
+
+        let err = client
+            .url_for(
+                "getUser",
+                &PathParams::new().with("userId", "42").with("groupId", "7"),
+                &Query::new(),
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            SentinelError::PathParameterError { ref parameter, .. } if parameter == "groupId"
+        ));
+    }
+
+    #[test]
+    fn test_url_for_encodes_values_under_base_path() {
+        let client = client("http://users.internal/api/");
+        let url = client
+            .url_for(
+                "getUser",
+                &PathParams::new().with("userId", "a/b c"),
+                &Query::new(),
+            )
+            .unwrap();
+        assert_eq!(url.as_str(), "http://users.internal/api/users/a%2Fb%20c");
+    }
+}
@@ -0,0 +1,427 @@
+//! Operation constants generated from a contract.
+//!
+//! Handlers are registered by operation ID, and a mistyped ID only fails
+//! when the operation is called. [`generate_operation_consts`] writes a Rust
+//! module, usually from a build script, with for every operation:
+//!
+//! - a constant holding its ID, such as `GET_USER` for `getUser`
+//! - `GET_USER_METHOD` and `GET_USER_PATH` with its method and path template
+//! - a variant of the `Operation` enum, which has `as_str`, `method`,
+//!   `path`, `FromStr` and `Display`, and implements
+//!   [`archimedes_core::ContractOperation`]
+//!
+//! A typo in a constant is then a compile error, and since `Operation` is
+//! exhaustive, a `match` over it stops compiling when the contract gains an
+//! operation. Pass it to `HandlerBinder::for_operations` or
+//! `HandlerCoverage::for_operations` to check that every operation has a
+//! handler.
+//!
+//! # Build script
+//!
+//! Add `archimedes-sentinel` to `[build-dependencies]` and generate the
+//! module in `build.rs`:
+//!
+//! ```rust,ignore
+//! use archimedes_sentinel::{codegen, ArtifactLoader};
+//!
+//! fn main() {
+//!     println!("cargo:rerun-if-changed=contract.yaml");
+//!     let contract = std::fs::read_to_string("contract.yaml").unwrap();
+//!     let artifact = ArtifactLoader::from_document(&contract).unwrap();
+//!     let out_dir = std::env::var("OUT_DIR").unwrap();
+//!     codegen::generate_operation_consts(&artifact, out_dir).unwrap();
+//! }
+//! ```
+//!
+//! Then include it as a module. The generated code refers to
+//! `archimedes_core`, which the crate must depend on:
+//!
+//! ```rust,ignore
+//! #[allow(dead_code)]
+//! mod ops {
+//!     include!(concat!(env!("OUT_DIR"), "/operations.rs"));
+//! }
+//!
+//! registry.register(ops::GET_USER, get_user);
+//! ```
+//!
+//! With the module in place, the constants resolve:
+//!
+//! ```rust
+//! # #[allow(dead_code)]
+//! mod ops {
+//!     include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/operations.rs"));
+//! }
+//!
+//! assert_eq!(ops::GET_USER, "getUser");
+//! assert_eq!(ops::Operation::GetUser.path(), "/users/{userId}");
+//! ```
+//!
+//! and a misspelled one does not compile:
+//!
+//! ```rust,compile_fail
+//! # #[allow(dead_code)]
+//! mod ops {
+//!     include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/operations.rs"));
+//! }
+//!
+//! let id = ops::GET_USR;
+//! ```
+
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use crate::artifact::{LoadedArtifact, LoadedOperation};
+use crate::error::{SentinelError, SentinelResult};
+
+/// Name of the file [`generate_operation_consts`] writes.
+pub const OPERATIONS_FILE: &str = "operations.rs";
+
+/// Writes the operations module for `artifact` to [`OPERATIONS_FILE`] in
+/// `out_dir` and returns its path.
+///
+/// The file is left untouched when its content would not change, so Cargo
+/// does not rebuild the crate needlessly.
+///
+/// # Errors
+///
+/// Returns an error if two operations map to the same Rust name, an
+/// operation ID has no letters or digits, or the file cannot be written.
+pub fn generate_operation_consts(
+    artifact: &LoadedArtifact,
+    out_dir: impl AsRef<Path>,
+) -> SentinelResult<PathBuf> {
+    let source = render_operation_consts(artifact)?;
+    let path = out_dir.as_ref().join(OPERATIONS_FILE);
+    if std::fs::read_to_string(&path).ok().as_deref() != Some(source.as_str()) {
+        std::fs::write(&path, source)?;
+    }
+    Ok(path)
+}
+
+/// Renders the operations module for `artifact`.
+///
+/// # Errors
+///
+/// Returns an error if two operations map to the same Rust name or an
+/// operation ID has no letters or digits.
+pub fn render_operation_consts(artifact: &LoadedArtifact) -> SentinelResult<String> {
+    let operations = artifact
+        .operations
+        .iter()
+        .map(Names::new)
+        .collect::<SentinelResult<Vec<_>>>()?;
+
+    let mut seen = HashSet::new();
+    for names in &operations {
+        let generated = [
+            names.constant.clone(),
+            format!("{}_METHOD", names.constant),
+            format!("{}_PATH", names.constant),
+            names.variant.clone(),
+        ];
+        for name in generated {
+            if !seen.insert(name.clone()) {
+                return Err(SentinelError::Codegen(format!(
+                    "operation '{}' maps to {}, which another operation already uses",
+                    names.operation.id, name
+                )));
+            }
+        }
+    }
+
+    let mut out = String::new();
+    write_module(&mut out, artifact, &operations).expect("writing to a String cannot fail");
+    Ok(out)
+}
+
+/// Rust names generated for one operation.
+struct Names<'a> {
+    operation: &'a LoadedOperation,
+    constant: String,
+    variant: String,
+}
+
+impl<'a> Names<'a> {
+    fn new(operation: &'a LoadedOperation) -> SentinelResult<Self> {
+        let words = words(&operation.id);
+        if words.is_empty() {
+            return Err(SentinelError::Codegen(format!(
+                "operation ID '{}' has no letters or digits to name it by",
+                operation.id
+            )));
+        }
+
+        let mut constant = words.join("_").to_ascii_uppercase();
+        let mut variant: String = words.iter().map(|word| capitalize(word)).collect();
+        if constant.starts_with(|c: char| c.is_ascii_digit()) {
+            constant.insert_str(0, "OP_");
+            variant.insert_str(0, "Op");
+        }
+        if variant == "Self" {
+            variant.push('_');
+        }
+
+        Ok(Self {
+            operation,
+            constant,
+            variant,
+        })
+    }
+}
+
+/// Splits an operation ID into words at case changes and at anything that
+/// is not an ASCII letter or digit, so `getHTTPStatus` becomes `get`,
+/// `HTTP`, `Status`.
+fn words(id: &str) -> Vec<String> {
+    let chars: Vec<char> = id.chars().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_ascii_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+
+        let prev = i.checked_sub(1).map(|j| chars[j]);
+        let next = chars.get(i + 1);
+        let boundary = c.is_ascii_uppercase()
+            && prev.is_some_and(|p| {
+                p.is_ascii_lowercase()
+                    || p.is_ascii_digit()
+                    || (p.is_ascii_uppercase() && next.is_some_and(char::is_ascii_lowercase))
+            });
+        if boundary && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let lower = word.to_ascii_lowercase();
+    let mut chars = lower.chars();
+    chars
+        .next()
+        .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+        .unwrap_or_default()
+}
+
+/// The first line of an operation's summary, for doc comments.
+fn summary(operation: &LoadedOperation) -> Option<&str> {
+    operation
+        .summary
+        .as_deref()
+        .and_then(|s| s.lines().next())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+fn write_module(
+    out: &mut String,
+    artifact: &LoadedArtifact,
+    operations: &[Names<'_>],
+) -> std::fmt::Result {
+    writeln!(
+        out,
+        "// @generated by archimedes-sentinel from the `{}` contract, version {}.",
+        artifact.service, artifact.version
+    )?;
+    writeln!(
+        out,
+        "// Do not edit; regenerate it from the contract instead."
+    )?;
+
+    for names in operations {
+        let op = names.operation;
+        let constant = &names.constant;
+        writeln!(out)?;
+        writeln!(out, "/// Operation ID of `{} {}`.", op.method, op.path)?;
+        if let Some(summary) = summary(op) {
+            writeln!(out, "///")?;
+            writeln!(out, "/// {summary}")?;
+        }
+        if op.deprecated {
+            writeln!(out, "///")?;
+            writeln!(out, "/// The operation is deprecated.")?;
+        }
+        writeln!(out, "pub const {constant}: &str = {:?};", op.id)?;
+        writeln!(out, "/// HTTP method of [`{constant}`].")?;
+        writeln!(out, "pub const {constant}_METHOD: &str = {:?};", op.method)?;
+        writeln!(out, "/// Path template of [`{constant}`].")?;
+        writeln!(out, "pub const {constant}_PATH: &str = {:?};", op.path)?;
+    }
+
+    writeln!(out)?;
+    writeln!(
+        out,
+        "/// The operations of the `{}` contract.",
+        artifact.service
+    )?;
+    writeln!(
+        out,
+        "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]"
+    )?;
+    writeln!(out, "pub enum Operation {{")?;
+    for names in operations {
+        writeln!(out, "    /// `{}`", names.operation.id)?;
+        writeln!(out, "    {},", names.variant)?;
+    }
+    writeln!(out, "}}")?;
+
+    writeln!(out)?;
+    writeln!(out, "impl Operation {{")?;
+    writeln!(out, "    /// Every operation, in contract order.")?;
+    writeln!(out, "    pub const ALL: &'static [Self] = &[")?;
+    for names in operations {
+        writeln!(out, "        Self::{},", names.variant)?;
+    }
+    writeln!(out, "    ];")?;
+    let accessors = [
+        ("as_str", "Returns the operation ID.", ""),
+        ("method", "Returns the HTTP method.", "_METHOD"),
+        ("path", "Returns the path template.", "_PATH"),
+    ];
+    for (name, doc, suffix) in accessors {
+        writeln!(out)?;
+        writeln!(out, "    /// {doc}")?;
+        writeln!(out, "    pub const fn {name}(self) -> &'static str {{")?;
+        writeln!(out, "        match self {{")?;
+        for names in operations {
+            writeln!(
+                out,
+                "            Self::{} => {}{suffix},",
+                names.variant, names.constant
+            )?;
+        }
+        writeln!(out, "        }}")?;
+        writeln!(out, "    }}")?;
+    }
+    writeln!(out, "}}")?;
+
+    writeln!(out)?;
+    writeln!(out, "impl ::core::str::FromStr for Operation {{")?;
+    writeln!(out, "    type Err = ::archimedes_core::UnknownOperation;")?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "    fn from_str(id: &str) -> ::core::result::Result<Self, Self::Err> {{"
+    )?;
+    writeln!(out, "        match id {{")?;
+    for names in operations {
+        writeln!(
+            out,
+            "            {} => Ok(Self::{}),",
+            names.constant, names.variant
+        )?;
+    }
+    writeln!(
+        out,
+        "            _ => Err(::archimedes_core::UnknownOperation(id.to_string())),"
+    )?;
+    writeln!(out, "        }}")?;
+    writeln!(out, "    }}")?;
+    writeln!(out, "}}")?;
+
+    writeln!(out)?;
+    writeln!(out, "impl ::core::fmt::Display for Operation {{")?;
+    writeln!(
+        out,
+        "    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {{"
+    )?;
+    writeln!(out, "        f.write_str(self.as_str())")?;
+    writeln!(out, "    }}")?;
+    writeln!(out, "}}")?;
+
+    writeln!(out)?;
+    writeln!(
+        out,
+        "impl ::archimedes_core::ContractOperation for Operation {{"
+    )?;
+    writeln!(out, "    const ALL: &'static [Self] = Operation::ALL;")?;
+    for (name, _, _) in accessors {
+        writeln!(out)?;
+        writeln!(out, "    fn {name}(self) -> &'static str {{")?;
+        writeln!(out, "        Operation::{name}(self)")?;
+        writeln!(out, "    }}")?;
+    }
+    writeln!(out, "}}")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use archimedes_core::contract::{Contract, Operation};
+    use http::Method;
+
+    fn artifact(ids: &[&str]) -> LoadedArtifact {
+        let contract = ids
+            .iter()
+            .fold(Contract::builder("names"), |builder, id| {
+                builder.operation(
+                    Operation::builder(*id)
+                        .method(Method::GET)
+                        .path(format!("/{id}"))
+                        .build(),
+                )
+            })
+            .build();
+        crate::fixtures::from_contract(&contract)
+    }
+
+    #[test]
+    fn test_names() {
+        let cases = [
+            ("getUser", "GET_USER", "GetUser"),
+            ("getHTTPStatus", "GET_HTTP_STATUS", "GetHttpStatus"),
+            ("get-health.v2", "GET_HEALTH_V2", "GetHealthV2"),
+            ("list_users", "LIST_USERS", "ListUsers"),
+            ("2fa", "OP_2FA", "Op2fa"),
+            ("self", "SELF", "Self_"),
+        ];
+        for (id, constant, variant) in cases {
+            let artifact = artifact(&[id]);
+            let names = Names::new(&artifact.operations[0]).unwrap();
+            assert_eq!(names.constant, constant, "{id}");
+            assert_eq!(names.variant, variant, "{id}");
+        }
+    }
+
+    #[test]
+    fn test_rejects_clashing_names() {
+        let err = render_operation_consts(&artifact(&["getUser", "get-user"])).unwrap_err();
+        assert!(err.to_string().contains("get-user"), "{err}");
+
+        let err = render_operation_consts(&artifact(&["getUser", "getUserPath"])).unwrap_err();
+        assert!(err.to_string().contains("GET_USER_PATH"), "{err}");
+
+        assert!(render_operation_consts(&artifact(&["--"])).is_err());
+    }
+
+    #[test]
+    fn test_generate_skips_unchanged_file() {
+        let dir = std::env::temp_dir().join(format!("sentinel-codegen-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let artifact = artifact(&["getUser"]);
+
+        let path = generate_operation_consts(&artifact, &dir).unwrap();
+        assert_eq!(path, dir.join(OPERATIONS_FILE));
+        let written = std::fs::metadata(&path).unwrap().modified().unwrap();
+
+        generate_operation_consts(&artifact, &dir).unwrap();
+        assert_eq!(
+            std::fs::metadata(&path).unwrap().modified().unwrap(),
+            written
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
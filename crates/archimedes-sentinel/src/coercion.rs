@@ -0,0 +1,200 @@
+//! String-to-primitive coercion for loosely typed inputs.
+//!
+//! Values from query strings and HTML forms always arrive as strings, even
+//! when the contract declares an integer, number or boolean. When coercion
+//! is enabled in [`ValidationConfig`](crate::ValidationConfig), such strings
+//! are converted to the declared type before validation, as long as the
+//! conversion is unambiguous and lossless:
+//!
+//! | Declared type | Accepted strings            | Result        |
+//! |---------------|-----------------------------|---------------|
+//! | `integer`     | `"30"`, `"-7"`              | `30`, `-7`    |
+//! | `number`      | `"3.5"`, `"30"`, `"1e3"`    | `3.5`, `30`, `1000.0` |
+//! | `boolean`     | `"true"`, `"false"`         | `true`, `false` |
+//!
+//! Everything else, such as `"3.7"` for an integer, `"yes"` for a boolean
+//! or any value in a string-typed field, is left untouched so validation
+//! reports the original type error.
+
+use serde_json::{Number, Value};
+
+use crate::artifact::SchemaRef;
+
+/// Coerce a string to a primitive schema type.
+///
+/// Returns `None` if the type is not a primitive or the conversion is
+/// ambiguous or lossy.
+pub fn coerce_str(value: &str, schema_type: &str) -> Option<Value> {
+    match schema_type {
+        "integer" => value
+            .parse::<i64>()
+            .map(Value::from)
+            .or_else(|_| value.parse::<u64>().map(Value::from))
+            .ok(),
+        "number" => {
+            if let Ok(int) = value.parse::<i64>() {
+                return Some(Value::from(int));
+            }
+            // Rejects "inf" and "NaN", which JSON cannot represent
+            value
+                .parse::<f64>()
+                .ok()
+                .and_then(Number::from_f64)
+                .map(Value::Number)
+        }
+        "boolean" => match value {
+            "true" => Some(Value::Bool(true)),
+            "false" => Some(Value::Bool(false)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Coerce string values in a body to the types declared by a schema.
+///
+/// Coerces the body itself when the schema is a primitive, or the declared
+/// properties when it is an object. Returns the paths of coerced fields;
+/// the body itself is reported as an empty path.
+pub fn coerce_value(value: &mut Value, schema_ref: &SchemaRef) -> Vec<String> {
+    let mut coerced = Vec::new();
+
+    match value {
+        Value::String(s) => {
+            if let Some(v) = coerce_str(s, &schema_ref.schema_type) {
+                *value = v;
+                coerced.push(String::new());
+            }
+        }
+        Value::Object(obj) => {
+            for (name, property_type) in &schema_ref.properties {
+                let Some(field) = obj.get_mut(name) else {
+                    continue;
+                };
+                let Some(v) = field.as_str().and_then(|s| coerce_str(s, property_type)) else {
+                    continue;
+                };
+                *field = v;
+                coerced.push(name.clone());
+            }
+            // Report fields in a stable order
+            coerced.sort();
+        }
+        _ => {}
+    }
+
+    coerced
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_coercion_matrix() {
+        let cases: &[(&str, &str, Option<Value>)] = &[
+            // integer
+            ("30", "integer", Some(json!(30))),
+            ("-7", "integer", Some(json!(-7))),
+            ("18446744073709551615", "integer", Some(json!(u64::MAX))),
+            ("3.7", "integer", None),
+            ("3.0", "integer", None),
+            ("1e3", "integer", None),
+            (" 30", "integer", None),
+            ("", "integer", None),
+            ("thirty", "integer", None),
+            // number
+            ("3.5", "number", Some(json!(3.5))),
+            ("30", "number", Some(json!(30))),
+            ("-0.25", "number", Some(json!(-0.25))),
+            ("1e3", "number", Some(json!(1000.0))),
+            ("inf", "number", None),
+            ("NaN", "number", None),
+            ("3,5", "number", None),
+            // boolean
+            ("true", "boolean", Some(json!(true))),
+            ("false", "boolean", Some(json!(false))),
+            ("True", "boolean", None),
+            ("1", "boolean", None),
+            ("yes", "boolean", None),
+            // never coerced
+            ("30", "string", None),
+            ("true", "string", None),
+            ("30", "object", None),
+            ("30", "array", None),
+            ("null", "null", None),
+        ];
+
+        for (input, schema_type, expected) in cases {
+            assert_eq!(
+                &coerce_str(input, schema_type),
+                expected,
+                "coercing {input:?} to {schema_type}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_coerce_object_properties() {
+        let schema_ref = SchemaRef {
+            reference: "#/components/schemas/Person".to_string(),
+            schema_type: "object".to_string(),
+            required: vec![],
+            properties: HashMap::from([
+                ("age".to_string(), "integer".to_string()),
+                ("active".to_string(), "boolean".to_string()),
+                ("score".to_string(), "number".to_string()),
+                ("zip".to_string(), "string".to_string()),
+            ]),
+            property_schemas: HashMap::new(),
+            required_scopes: HashMap::new(),
+            items: None,
+            additional_properties: None,
+        };
+
+        let mut body = json!({
+            "age": "30",
+            "active": "true",
+            "score": 4.5,
+            "zip": "02139",
+            "extra": "42"
+        });
+        let coerced = coerce_value(&mut body, &schema_ref);
+
+        assert_eq!(coerced, vec!["active".to_string(), "age".to_string()]);
+        assert_eq!(
+            body,
+            json!({
+                "age": 30,
+                "active": true,
+                "score": 4.5,
+                "zip": "02139",
+                "extra": "42"
+            })
+        );
+    }
+
+    #[test]
+    fn test_coerce_primitive_body() {
+        let schema_ref = SchemaRef {
+            reference: "#/inline/integer".to_string(),
+            schema_type: "integer".to_string(),
+            required: vec![],
+            properties: HashMap::new(),
+            property_schemas: HashMap::new(),
+            required_scopes: HashMap::new(),
+            items: None,
+            additional_properties: None,
+        };
+
+        let mut body = json!("12");
+        assert_eq!(coerce_value(&mut body, &schema_ref), vec![String::new()]);
+        assert_eq!(body, json!(12));
+
+        let mut body = json!("12.5");
+        assert!(coerce_value(&mut body, &schema_ref).is_empty());
+        assert_eq!(body, json!("12.5"));
+    }
+}
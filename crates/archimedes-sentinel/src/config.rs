@@ -2,9 +2,113 @@
 //!
 //! This module provides configuration types for validation behavior
 //! and Sentinel operation.
+//!
+//! # Unknown Fields
+//!
+//! Fields a body carries but its schema does not declare are handled by
+//! [`ValidationConfig::request_unknown_fields`] and
+//! [`ValidationConfig::response_unknown_fields`]. The policies only apply
+//! to object schemas that declare their properties and do not set
+//! `additionalProperties`; a schema setting `additionalProperties: false`
+//! always rejects unknown fields, and one setting it to `true` (or to a
+//! schema) always allows them. Nested objects and array items are checked
+//! too, with unknown fields reported by path (e.g. `items[0].note`).
+
+use std::fmt;
+use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
 
+/// What to do with unknown fields in request bodies.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestUnknownFields {
+    /// Reject the request with a 400 listing the unknown fields.
+    Reject,
+    /// Remove unknown fields from the body before it reaches the handler.
+    Strip,
+    /// Pass unknown fields through to the handler.
+    #[default]
+    Allow,
+}
+
+/// What to do with unknown fields in response bodies.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseUnknownFields {
+    /// Fail response validation.
+    Reject,
+    /// Let the response through, but log and count each unknown field.
+    LogOnly,
+    /// Let the response through.
+    #[default]
+    Allow,
+}
+
+impl RequestUnknownFields {
+    /// Get the policy name as used in configuration.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Reject => "reject",
+            Self::Strip => "strip",
+            Self::Allow => "allow",
+        }
+    }
+}
+
+impl ResponseUnknownFields {
+    /// Get the policy name as used in configuration.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Reject => "reject",
+            Self::LogOnly => "log_only",
+            Self::Allow => "allow",
+        }
+    }
+}
+
+impl fmt::Display for RequestUnknownFields {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Display for ResponseUnknownFields {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RequestUnknownFields {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "reject" => Ok(Self::Reject),
+            "strip" => Ok(Self::Strip),
+            "allow" => Ok(Self::Allow),
+            _ => Err(format!(
+                "unknown request field policy '{s}'; expected reject, strip or allow"
+            )),
+        }
+    }
+}
+
+impl FromStr for ResponseUnknownFields {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().replace('-', "_").as_str() {
+            "reject" => Ok(Self::Reject),
+            "log_only" => Ok(Self::LogOnly),
+            "allow" => Ok(Self::Allow),
+            _ => Err(format!(
+                "unknown response field policy '{s}'; expected reject, log_only or allow"
+            )),
+        }
+    }
+}
+
 /// Configuration for validation behavior.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidationConfig {
@@ -15,9 +119,24 @@ pub struct ValidationConfig {
     /// Enable strict mode (fail on any validation warning).
     pub strict_mode: bool,
     /// Allow properties not defined in schema.
+    ///
+    /// Superseded by [`request_unknown_fields`](Self::request_unknown_fields)
+    /// and [`response_unknown_fields`](Self::response_unknown_fields).
     pub allow_additional_properties: bool,
+    /// What to do with unknown fields in request bodies.
+    #[serde(default)]
+    pub request_unknown_fields: RequestUnknownFields,
+    /// What to do with unknown fields in response bodies.
+    #[serde(default)]
+    pub response_unknown_fields: ResponseUnknownFields,
     /// Allow missing path parameters (useful for optional params).
     pub allow_missing_path_params: bool,
+    /// Coerce string values to the declared primitive type before
+    /// validating (e.g. `"30"` for an integer field).
+    ///
+    /// See the [`coercion`](crate::coercion) module for the rules.
+    #[serde(default, alias = "coerce_scalars")]
+    pub coerce_primitives: bool,
 }
 
 impl Default for ValidationConfig {
@@ -27,7 +146,10 @@ impl Default for ValidationConfig {
             validate_responses: false,
             strict_mode: false,
             allow_additional_properties: true,
+            request_unknown_fields: RequestUnknownFields::Allow,
+            response_unknown_fields: ResponseUnknownFields::Allow,
             allow_missing_path_params: false,
+            coerce_primitives: false,
         }
     }
 }
@@ -40,7 +162,10 @@ impl ValidationConfig {
             validate_responses: true,
             strict_mode: true,
             allow_additional_properties: false,
+            request_unknown_fields: RequestUnknownFields::Reject,
+            response_unknown_fields: ResponseUnknownFields::Reject,
             allow_missing_path_params: false,
+            coerce_primitives: false,
         }
     }
 
@@ -51,7 +176,10 @@ impl ValidationConfig {
             validate_responses: false,
             strict_mode: false,
             allow_additional_properties: true,
+            request_unknown_fields: RequestUnknownFields::Allow,
+            response_unknown_fields: ResponseUnknownFields::Allow,
             allow_missing_path_params: true,
+            coerce_primitives: false,
         }
     }
 
@@ -62,9 +190,30 @@ impl ValidationConfig {
             validate_responses: false,
             strict_mode: false,
             allow_additional_properties: true,
+            request_unknown_fields: RequestUnknownFields::Allow,
+            response_unknown_fields: ResponseUnknownFields::Allow,
             allow_missing_path_params: false,
+            coerce_primitives: false,
         }
     }
+
+    /// Enable or disable coercion of strings to primitive types.
+    pub fn with_coercion(mut self, enabled: bool) -> Self {
+        self.coerce_primitives = enabled;
+        self
+    }
+
+    /// Set what to do with unknown fields in request bodies.
+    pub fn with_request_unknown_fields(mut self, policy: RequestUnknownFields) -> Self {
+        self.request_unknown_fields = policy;
+        self
+    }
+
+    /// Set what to do with unknown fields in response bodies.
+    pub fn with_response_unknown_fields(mut self, policy: ResponseUnknownFields) -> Self {
+        self.response_unknown_fields = policy;
+        self
+    }
 }
 
 /// Configuration for the Sentinel.
@@ -130,6 +279,56 @@ mod tests {
         assert!(!config.validate_responses);
         assert!(!config.strict_mode);
         assert!(config.allow_additional_properties);
+        assert!(!config.coerce_primitives);
+        assert_eq!(config.request_unknown_fields, RequestUnknownFields::Allow);
+        assert_eq!(config.response_unknown_fields, ResponseUnknownFields::Allow);
+    }
+
+    #[test]
+    fn test_unknown_field_policies() {
+        let strict = ValidationConfig::strict();
+        assert_eq!(strict.request_unknown_fields, RequestUnknownFields::Reject);
+        assert_eq!(
+            strict.response_unknown_fields,
+            ResponseUnknownFields::Reject
+        );
+
+        let config: ValidationConfig = serde_json::from_str(
+            r#"{"validate_requests":true,"validate_responses":true,"strict_mode":false,
+                "allow_additional_properties":true,"allow_missing_path_params":false,
+                "request_unknown_fields":"strip","response_unknown_fields":"log_only"}"#,
+        )
+        .unwrap();
+        assert_eq!(config.request_unknown_fields, RequestUnknownFields::Strip);
+        assert_eq!(
+            config.response_unknown_fields,
+            ResponseUnknownFields::LogOnly
+        );
+
+        assert_eq!("Reject".parse(), Ok(RequestUnknownFields::Reject));
+        assert_eq!("log-only".parse(), Ok(ResponseUnknownFields::LogOnly));
+        assert!("log_only".parse::<RequestUnknownFields>().is_err());
+        assert_eq!(ResponseUnknownFields::LogOnly.to_string(), "log_only");
+    }
+
+    #[test]
+    fn test_coercion_off_unless_enabled() {
+        assert!(!ValidationConfig::strict().coerce_primitives);
+        assert!(!ValidationConfig::permissive().coerce_primitives);
+        assert!(!ValidationConfig::request_only().coerce_primitives);
+
+        let config: ValidationConfig = serde_json::from_str(
+            r#"{"validate_requests":true,"validate_responses":false,"strict_mode":false,
+                "allow_additional_properties":true,"allow_missing_path_params":false}"#,
+        )
+        .unwrap();
+        assert!(!config.coerce_primitives);
+
+        assert!(
+            ValidationConfig::default()
+                .with_coercion(true)
+                .coerce_primitives
+        );
     }
 
     #[test]
@@ -62,6 +62,26 @@ pub enum SentinelError {
         reference: String,
     },
 
+    /// The requested contract version is not served.
+    UnsupportedVersion {
+        /// The requested version.
+        requested: String,
+        /// Versions clients may request.
+        supported: Vec<String>,
+    },
+
+    /// The contract declares no operation with this ID.
+    UnknownOperation {
+        /// The operation ID.
+        operation_id: String,
+    },
+
+    /// An outbound call to another service failed.
+    Client(String),
+
+    /// Generating code from a contract failed.
+    Codegen(String),
+
     /// IO error.
     Io(std::io::Error),
 }
@@ -92,9 +112,10 @@ impl fmt::Display for SentinelError {
             } => {
                 write!(
                     f,
-                    "request validation failed for '{}': {} error(s)",
+                    "request validation failed for '{}': {} error(s): {}",
                     operation_id,
-                    errors.len()
+                    errors.len(),
+                    join_errors(errors)
                 )
             }
             Self::ResponseValidation {
@@ -104,20 +125,46 @@ impl fmt::Display for SentinelError {
             } => {
                 write!(
                     f,
-                    "response validation failed for '{}' (status {}): {} error(s)",
+                    "response validation failed for '{}' (status {}): {} error(s): {}",
                     operation_id,
                     status_code,
-                    errors.len()
+                    errors.len(),
+                    join_errors(errors)
                 )
             }
             Self::SchemaNotFound { reference } => {
                 write!(f, "schema not found: {}", reference)
             }
+            Self::UnsupportedVersion {
+                requested,
+                supported,
+            } => {
+                write!(
+                    f,
+                    "unsupported contract version '{}' (supported: {})",
+                    requested,
+                    supported.join(", ")
+                )
+            }
+            Self::UnknownOperation { operation_id } => {
+                write!(f, "unknown operation '{}'", operation_id)
+            }
+            Self::Client(msg) => write!(f, "outbound call failed: {}", msg),
+            Self::Codegen(msg) => write!(f, "code generation failed: {}", msg),
             Self::Io(e) => write!(f, "io error: {}", e),
         }
     }
 }
 
+/// Lists validation errors on one line.
+fn join_errors(errors: &[ValidationError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
 impl From<std::io::Error> for SentinelError {
     fn from(e: std::io::Error) -> Self {
         Self::Io(e)
@@ -153,6 +200,18 @@ impl std::error::Error for ValidationError {}
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_unsupported_version_display() {
+        let err = SentinelError::UnsupportedVersion {
+            requested: "v3".to_string(),
+            supported: vec!["v1".to_string(), "v2".to_string()],
+        };
+        assert_eq!(
+            err.to_string(),
+            "unsupported contract version 'v3' (supported: v1, v2)"
+        );
+    }
+
     #[test]
     fn test_artifact_load_error_display() {
         let err = SentinelError::ArtifactLoad("file not found".to_string());
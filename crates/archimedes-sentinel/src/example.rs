@@ -0,0 +1,185 @@
+//! Example values generated from operation schemas.
+//!
+//! [`ExampleGenerator`] builds a JSON value with the shape a schema
+//! declares: every declared property of an object, one item of an array
+//! and a placeholder for each primitive type:
+//!
+//! | Declared type | Example    |
+//! |---------------|------------|
+//! | `string`      | `"string"` |
+//! | `integer`     | `0`        |
+//! | `number`      | `0.0`      |
+//! | `boolean`     | `false`    |
+//! | `array`       | `[item]`   |
+//! | `object`      | `{...}`    |
+//!
+//! Examples exercise the validation path of an operation, e.g. to warm it
+//! up before the first request; they are not guaranteed to pass
+//! validation, since patterns, formats and bounds are not considered.
+
+use serde_json::{Map, Value};
+
+use crate::artifact::{LoadedOperation, SchemaRef};
+
+/// Default depth below which nested objects and arrays are left empty.
+pub const DEFAULT_MAX_DEPTH: usize = 8;
+
+/// Generates example values from schemas.
+///
+/// # Example
+///
+/// ```ignore
+/// use archimedes_sentinel::ExampleGenerator;
+///
+/// let operation = sentinel.artifact().operations.first().unwrap();
+/// let body = ExampleGenerator::new().request_example(operation);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ExampleGenerator {
+    max_depth: usize,
+}
+
+impl Default for ExampleGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ExampleGenerator {
+    /// Create a generator with the default depth limit.
+    pub const fn new() -> Self {
+        Self {
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+
+    /// Set the depth below which nested objects and arrays are left empty,
+    /// bounding recursive schemas.
+    #[must_use]
+    pub const fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Generate an example of a schema.
+    pub fn example(&self, schema: &SchemaRef) -> Value {
+        self.example_at(&schema.schema_type, Some(schema), 0)
+    }
+
+    /// Generate an example request body of an operation, if it declares a
+    /// request schema.
+    pub fn request_example(&self, operation: &LoadedOperation) -> Option<Value> {
+        operation
+            .request_schema
+            .as_ref()
+            .map(|schema| self.example(schema))
+    }
+
+    fn example_at(&self, schema_type: &str, schema: Option<&SchemaRef>, depth: usize) -> Value {
+        match schema_type {
+            "string" => Value::from("string"),
+            "integer" => Value::from(0),
+            "number" => Value::from(0.0),
+            "boolean" => Value::Bool(false),
+            "array" => {
+                let item = schema
+                    .and_then(|schema| schema.items.as_deref())
+                    .filter(|_| depth < self.max_depth);
+                item.map_or_else(
+                    || Value::Array(vec![]),
+                    |item| {
+                        Value::Array(vec![self.example_at(
+                            &item.schema_type,
+                            Some(item),
+                            depth + 1,
+                        )])
+                    },
+                )
+            }
+            "object" => {
+                let mut object = Map::new();
+                if let Some(schema) = schema.filter(|_| depth < self.max_depth) {
+                    for (name, property_type) in &schema.properties {
+                        let nested = schema.property_schemas.get(name);
+                        object.insert(
+                            name.clone(),
+                            self.example_at(property_type, nested, depth + 1),
+                        );
+                    }
+                }
+                Value::Object(object)
+            }
+            _ => Value::Null,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn schema(schema_type: &str) -> SchemaRef {
+        SchemaRef {
+            reference: String::new(),
+            schema_type: schema_type.to_string(),
+            required: vec![],
+            properties: HashMap::new(),
+            property_schemas: HashMap::new(),
+            required_scopes: HashMap::new(),
+            items: None,
+            additional_properties: None,
+        }
+    }
+
+    fn user() -> SchemaRef {
+        let mut tag = schema("array");
+        tag.items = Some(Box::new(schema("string")));
+        let mut user = schema("object");
+        user.properties = HashMap::from([
+            ("name".to_string(), "string".to_string()),
+            ("age".to_string(), "integer".to_string()),
+            ("score".to_string(), "number".to_string()),
+            ("active".to_string(), "boolean".to_string()),
+            ("tags".to_string(), "array".to_string()),
+        ]);
+        user.property_schemas.insert("tags".to_string(), tag);
+        user
+    }
+
+    #[test]
+    fn test_example_of_object() {
+        let example = ExampleGenerator::new().example(&user());
+
+        assert_eq!(
+            example,
+            json!({
+                "name": "string",
+                "age": 0,
+                "score": 0.0,
+                "active": false,
+                "tags": ["string"],
+            })
+        );
+    }
+
+    #[test]
+    fn test_example_depth_limit() {
+        let mut outer = schema("object");
+        outer
+            .properties
+            .insert("user".to_string(), "object".to_string());
+        outer.property_schemas.insert("user".to_string(), user());
+
+        let example = ExampleGenerator::new().max_depth(1).example(&outer);
+
+        assert_eq!(example, json!({ "user": {} }));
+    }
+
+    #[test]
+    fn test_example_of_untyped_array_is_empty() {
+        assert_eq!(ExampleGenerator::new().example(&schema("array")), json!([]));
+        assert_eq!(ExampleGenerator::new().example(&schema("")), Value::Null);
+    }
+}
@@ -0,0 +1,290 @@
+//! Artifacts built from core test fixtures.
+//!
+//! [`artifact`] turns an [`archimedes_core::fixtures::FixtureBuilder`] into
+//! a [`LoadedArtifact`] describing the same operations, so Sentinel tests
+//! can share parameterized contracts with the rest of the test suites
+//! instead of spelling out every [`LoadedOperation`] by hand.
+//! [`from_contract`] does the same for a mock [`Contract`], so tests
+//! written against mock contracts can validate through a Sentinel.
+//!
+//! # Example
+//!
+//! ```
+//! use archimedes_core::fixtures::FixtureBuilder;
+//! use archimedes_sentinel::fixtures;
+//! use archimedes_sentinel::OperationResolver;
+//!
+//! let fixture = FixtureBuilder::new("widget-service")
+//!     .resource("widget")
+//!     .deprecate("deleteWidget");
+//! let artifact = fixtures::artifact(&fixture);
+//!
+//! let resolver = OperationResolver::from_artifact(&artifact);
+//! let resolution = resolver.resolve("DELETE", "/widgets/w-1").unwrap();
+//! assert!(resolution.deprecated);
+//! ```
+
+use std::collections::HashMap;
+
+use archimedes_core::contract::{Contract, MockSchema, Operation};
+use archimedes_core::fixtures::{error_schema, FixtureBuilder};
+use indexmap::IndexMap;
+
+use crate::artifact::{LoadStats, LoadedArtifact, LoadedOperation, SchemaRef, JSON_MEDIA_TYPE};
+
+/// Security scheme listed for operations that require authentication.
+pub const SECURITY_SCHEME: &str = "bearerAuth";
+
+/// Build a [`LoadedArtifact`] matching the contract of `fixture`.
+///
+/// Operations requiring authentication list [`SECURITY_SCHEME`]. Response
+/// schemas are declared for status `200`, and with error schemas enabled
+/// the error envelope is declared as the `default` response.
+pub fn artifact(fixture: &FixtureBuilder) -> LoadedArtifact {
+    loaded_artifact(&fixture.build(), fixture.has_error_schemas())
+}
+
+/// Build a [`LoadedArtifact`] matching a mock `contract`.
+///
+/// Operations are converted as by [`artifact`], without error schemas.
+pub fn from_contract(contract: &Contract) -> LoadedArtifact {
+    loaded_artifact(contract, false)
+}
+
+fn loaded_artifact(contract: &Contract, error_schemas: bool) -> LoadedArtifact {
+    let operations = contract
+        .operations()
+        .iter()
+        .map(|op| loaded_operation(op, error_schemas))
+        .collect();
+
+    LoadedArtifact {
+        service: contract.name().to_string(),
+        version: contract.version().to_string(),
+        format: "themis".to_string(),
+        operations,
+        schemas: IndexMap::new(),
+        stats: LoadStats::default(),
+    }
+}
+
+fn loaded_operation(op: &Operation, error_schemas: bool) -> LoadedOperation {
+    let id = op.operation_id();
+    let mut response_schemas = HashMap::new();
+    if let Some(schema) = op.response_schema() {
+        let reference = format!("#/operations/{id}/responses/200");
+        response_schemas.insert(
+            ("200".to_string(), JSON_MEDIA_TYPE.to_string()),
+            schema_ref(&reference, schema),
+        );
+    }
+    if error_schemas {
+        response_schemas.insert(
+            ("default".to_string(), JSON_MEDIA_TYPE.to_string()),
+            schema_ref("#/components/schemas/Error", &error_schema()),
+        );
+    }
+
+    LoadedOperation {
+        id: id.to_string(),
+        method: op.method().as_str().to_string(),
+        path: op.path().to_string(),
+        summary: op.description().map(ToString::to_string),
+        deprecated: op.is_deprecated(),
+        security: if op.requires_auth() {
+            vec![SECURITY_SCHEME.to_string()]
+        } else {
+            Vec::new()
+        },
+        request_schema: op
+            .request_schema()
+            .map(|schema| schema_ref(&format!("#/operations/{id}/request"), schema)),
+        response_schemas,
+        tags: op.tags().to_vec(),
+        header_params: Vec::new(),
+        event_schemas: HashMap::new(),
+        timeout: None,
+        cache_policy: None,
+        allow_reserved: Vec::new(),
+    }
+}
+
+/// Convert a mock schema to the schema reference Sentinel validates with.
+///
+/// Nested object and array schemas get references below `reference`.
+pub fn schema_ref(reference: &str, schema: &MockSchema) -> SchemaRef {
+    let mut converted = SchemaRef {
+        reference: reference.to_string(),
+        schema_type: schema_type(schema).to_string(),
+        required: Vec::new(),
+        properties: HashMap::new(),
+        property_schemas: HashMap::new(),
+        required_scopes: HashMap::new(),
+        items: None,
+        additional_properties: None,
+    };
+
+    match schema {
+        MockSchema::Object {
+            properties,
+            required_properties,
+            ..
+        } => {
+            converted.required = required_properties.clone();
+            for (name, property) in properties {
+                converted
+                    .properties
+                    .insert(name.clone(), schema_type(property).to_string());
+                if matches!(
+                    property,
+                    MockSchema::Object { .. } | MockSchema::Array { .. }
+                ) {
+                    let nested = format!("{reference}/properties/{name}");
+                    converted
+                        .property_schemas
+                        .insert(name.clone(), schema_ref(&nested, property));
+                }
+            }
+        }
+        MockSchema::Array { items, .. } => {
+            let nested = format!("{reference}/items");
+            converted.items = Some(Box::new(schema_ref(&nested, items)));
+        }
+        _ => {}
+    }
+
+    converted
+}
+
+fn schema_type(schema: &MockSchema) -> &'static str {
+    match schema {
+        MockSchema::String { .. } => "string",
+        MockSchema::Integer { .. } => "integer",
+        MockSchema::Number { .. } => "number",
+        MockSchema::Boolean { .. } => "boolean",
+        MockSchema::Array { .. } => "array",
+        MockSchema::Object { .. } => "object",
+        MockSchema::Any { .. } => "any",
+        MockSchema::Null => "null",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ValidationConfig;
+    use crate::resolver::OperationResolver;
+    use crate::validation::SchemaValidator;
+    use http::Method;
+    use serde_json::json;
+
+    fn ledger() -> FixtureBuilder {
+        FixtureBuilder::new("ledger")
+            .operation(
+                Operation::builder("postEntry")
+                    .method(Method::POST)
+                    .path("/entries")
+                    .request_schema(
+                        MockSchema::object(vec![
+                            ("amount", MockSchema::integer().required()),
+                            ("memo", MockSchema::string()),
+                        ])
+                        .required(),
+                    )
+                    .build(),
+            )
+            .resource("account")
+            .deprecate("getAccount")
+    }
+
+    #[test]
+    fn test_artifact_resolves_deprecated_operation() {
+        let artifact = artifact(&ledger());
+        assert_eq!(artifact.service, "ledger");
+        assert_eq!(artifact.operations.len(), 6);
+
+        let resolver = OperationResolver::from_artifact(&artifact);
+        let resolution = resolver.resolve("GET", "/accounts/acc-1").unwrap();
+        assert_eq!(resolution.operation_id, "getAccount");
+        assert!(resolution.deprecated);
+        assert_eq!(
+            resolution.path_params.get("accountId"),
+            Some(&"acc-1".to_string())
+        );
+
+        let resolution = resolver.resolve("POST", "/entries").unwrap();
+        assert!(!resolution.deprecated);
+    }
+
+    #[test]
+    fn test_artifact_validates_required_body() {
+        let artifact = artifact(&ledger());
+        let validator = SchemaValidator::from_artifact(&artifact, ValidationConfig::default());
+
+        let valid = validator
+            .validate_request("postEntry", &artifact, &json!({"amount": 10}))
+            .unwrap();
+        assert!(valid.valid);
+
+        let missing = validator
+            .validate_request("postEntry", &artifact, &json!({"memo": "rent"}))
+            .unwrap();
+        assert!(!missing.valid);
+        assert_eq!(missing.errors[0].path, "amount");
+
+        let wrong_type = validator
+            .validate_request("postEntry", &artifact, &json!({"amount": "ten"}))
+            .unwrap();
+        assert!(!wrong_type.valid);
+    }
+
+    #[test]
+    fn test_from_contract() {
+        let contract = Contract::builder("ledger")
+            .version("2.1.0")
+            .operation(
+                Operation::builder("getEntry")
+                    .method(Method::GET)
+                    .path("/entries/{entryId}")
+                    .build(),
+            )
+            .build();
+
+        let artifact = from_contract(&contract);
+        assert_eq!(artifact.service, "ledger");
+        assert_eq!(artifact.version, "2.1.0");
+        assert_eq!(artifact.operations[0].id, "getEntry");
+        assert!(artifact.operations[0].response_schemas.is_empty());
+
+        let resolver = OperationResolver::from_artifact(&artifact);
+        let resolution = resolver.resolve("GET", "/entries/e-1").unwrap();
+        assert_eq!(resolution.operation_id, "getEntry");
+    }
+
+    #[test]
+    fn test_artifact_toggles() {
+        let open = artifact(&ledger().auth(false).error_schemas(true));
+        let op = open
+            .operations
+            .iter()
+            .find(|op| op.id == "listAccounts")
+            .unwrap();
+        assert!(op.security.is_empty());
+        assert_eq!(
+            op.response_schema("200", JSON_MEDIA_TYPE)
+                .unwrap()
+                .schema_type,
+            "array"
+        );
+        assert_eq!(
+            op.response_schema("500", JSON_MEDIA_TYPE).unwrap().required,
+            vec!["error"]
+        );
+
+        let secured = artifact(&ledger());
+        assert_eq!(secured.operations[0].security, vec![SECURITY_SCHEME]);
+        assert!(secured.operations[0]
+            .response_schema("500", JSON_MEDIA_TYPE)
+            .is_none());
+    }
+}
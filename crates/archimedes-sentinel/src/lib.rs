@@ -7,10 +7,18 @@
 //! # Overview
 //!
 //! Sentinel acts as the bridge between Archimedes and Themis by:
-//! - Loading contract artifacts from the registry or local files
-//! - Resolving incoming requests to specific operation IDs
-//! - Validating request bodies against operation schemas
+//! - Loading contract artifacts from the registry or local files (Themis
+//!   artifacts or OpenAPI 3.x specs, in JSON or YAML)
+//! - Resolving incoming requests to specific operation IDs, and explaining
+//!   how a request resolved when it did not reach the expected operation
+//! - Validating request headers and bodies against operation schemas
 //! - Validating response bodies against operation schemas
+//! - Serving multiple contract versions side by side
+//! - Caching registry artifacts and refreshing them in the background
+//! - Calling other services through their contracts with
+//!   [`ContractClient`]
+//! - Generating typed operation constants from a contract at build time
+//!   (see [`codegen`])
 //!
 //! # Architecture
 //!
@@ -56,73 +64,304 @@
 #![warn(missing_docs)]
 
 pub mod artifact;
+pub mod client;
+pub mod codegen;
+pub mod coercion;
 pub mod config;
 pub mod error;
+pub mod example;
+pub mod fixtures;
+mod openapi;
 pub mod resolver;
+pub mod source;
 pub mod validation;
+pub mod version;
 
 // Re-exports for convenience
-pub use artifact::{ArtifactLoader, LoadedArtifact, LoadedOperation, SchemaRef};
-pub use config::{SentinelConfig, ValidationConfig};
+pub use artifact::{
+    ArtifactLoader, DocumentFormat, FieldRedaction, HeaderParam, LoadStats, LoadedArtifact,
+    LoadedOperation, SchemaRef, JSON_MEDIA_TYPE,
+};
+pub use client::{
+    CallContext, ContractClient, PathParams, Query, ResponseValidationMode, TypedResponse,
+};
+pub use config::{RequestUnknownFields, ResponseUnknownFields, SentinelConfig, ValidationConfig};
 pub use error::{SentinelError, SentinelResult, ValidationError};
-pub use resolver::{OperationResolution, OperationResolver};
+pub use example::ExampleGenerator;
+pub use resolver::{
+    CandidateRoute, OperationResolution, OperationResolver, ResolutionDecision,
+    ResolutionExplanation, RouteSuggestion, SegmentKind, SegmentMatch, MAX_SUGGESTIONS,
+};
+pub use source::CachingArtifactSource;
 pub use validation::{ParamType, SchemaValidator, ValidationResult};
+pub use version::{VersionSelection, VersionSelector, DEFAULT_VERSION_HEADER};
+
+use indexmap::IndexMap;
 
 /// The main Sentinel service for contract-aware request handling.
 ///
 /// Sentinel coordinates artifact loading, operation resolution, and validation.
+/// It can serve several contract versions side by side; see the
+/// [`version`] module.
 #[derive(Debug)]
 pub struct Sentinel {
     config: SentinelConfig,
+    contracts: IndexMap<String, Contract>,
+    selector: VersionSelector,
+}
+
+/// A loaded artifact with its resolver and validator.
+#[derive(Debug)]
+struct Contract {
     artifact: LoadedArtifact,
     resolver: OperationResolver,
     validator: SchemaValidator,
 }
 
-impl Sentinel {
-    /// Create a new Sentinel with the given artifact and configuration.
-    pub fn new(artifact: LoadedArtifact, config: SentinelConfig) -> Self {
+impl Contract {
+    fn new(artifact: LoadedArtifact, config: &SentinelConfig) -> Self {
         let resolver = OperationResolver::from_artifact(&artifact);
         let validator = SchemaValidator::from_artifact(&artifact, config.validation.clone());
-
         Self {
-            config,
             artifact,
             resolver,
             validator,
         }
     }
+}
+
+impl Sentinel {
+    /// Create a new Sentinel with the given artifact and configuration.
+    ///
+    /// The artifact is served as its own version (`artifact.version`).
+    pub fn new(artifact: LoadedArtifact, config: SentinelConfig) -> Self {
+        let version = artifact.version.clone();
+        let mut contracts = IndexMap::new();
+        contracts.insert(version, Contract::new(artifact, &config));
+
+        Self {
+            config,
+            contracts,
+            selector: VersionSelector::default(),
+        }
+    }
 
     /// Create a new Sentinel with default configuration.
     pub fn with_defaults(artifact: LoadedArtifact) -> Self {
         Self::new(artifact, SentinelConfig::default())
     }
 
-    /// Get the service name from the loaded artifact.
+    /// Create a Sentinel serving several contract versions side by side.
+    ///
+    /// Each artifact is keyed by the given version label (e.g. `"v1"`),
+    /// which is what clients request and what resolutions report.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no artifacts are given, or if the selector's
+    /// default or supported versions name a version that is not loaded.
+    pub fn versioned<I, V>(
+        artifacts: I,
+        selector: VersionSelector,
+        config: SentinelConfig,
+    ) -> SentinelResult<Self>
+    where
+        I: IntoIterator<Item = (V, LoadedArtifact)>,
+        V: Into<String>,
+    {
+        let contracts: IndexMap<String, Contract> = artifacts
+            .into_iter()
+            .map(|(version, artifact)| (version.into(), Contract::new(artifact, &config)))
+            .collect();
+
+        if contracts.is_empty() {
+            return Err(SentinelError::ArtifactLoad(
+                "no contract versions provided".to_string(),
+            ));
+        }
+
+        let unknown = selector
+            .default_version_name()
+            .into_iter()
+            .chain(selector.supported_versions().iter().map(String::as_str))
+            .find(|v| !contracts.contains_key(*v));
+        if let Some(requested) = unknown {
+            return Err(SentinelError::UnsupportedVersion {
+                requested: requested.to_string(),
+                supported: contracts.keys().cloned().collect(),
+            });
+        }
+
+        Ok(Self {
+            config,
+            contracts,
+            selector,
+        })
+    }
+
+    /// Get the service name from the default artifact.
     pub fn service_name(&self) -> &str {
-        &self.artifact.service
+        &self.default_contract().artifact.service
     }
 
-    /// Get the artifact version.
+    /// Get the default artifact's version.
     pub fn version(&self) -> &str {
-        &self.artifact.version
+        &self.default_contract().artifact.version
     }
 
-    /// Get the artifact format.
+    /// Get the default artifact's format.
     pub fn format(&self) -> &str {
-        &self.artifact.format
+        &self.default_contract().artifact.format
+    }
+
+    /// Get the version label used when a request names none.
+    ///
+    /// This is the selector's default, else the first supported version,
+    /// else the first loaded version.
+    pub fn default_version(&self) -> &str {
+        self.selector
+            .default_version_name()
+            .into_iter()
+            .chain(
+                self.selector
+                    .supported_versions()
+                    .iter()
+                    .map(String::as_str),
+            )
+            .chain(self.contracts.keys().map(String::as_str))
+            .find(|v| self.contracts.contains_key(*v))
+            .expect("sentinel holds at least one contract")
+    }
+
+    /// Get all loaded version labels, in load order.
+    pub fn versions(&self) -> Vec<&str> {
+        self.contracts.keys().map(String::as_str).collect()
+    }
+
+    /// Get the version labels clients may request.
+    pub fn supported_versions(&self) -> Vec<&str> {
+        if self.selector.supported_versions().is_empty() {
+            self.versions()
+        } else {
+            self.selector
+                .supported_versions()
+                .iter()
+                .map(String::as_str)
+                .collect()
+        }
+    }
+
+    /// Get the version selection policy.
+    pub fn version_selector(&self) -> &VersionSelector {
+        &self.selector
+    }
+
+    /// Select the contract version for a request.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SentinelError::UnsupportedVersion` if the request names a
+    /// version that is not supported.
+    pub fn select_version(
+        &self,
+        headers: &http::HeaderMap,
+        path: &str,
+    ) -> SentinelResult<VersionSelection> {
+        let supported = self.supported_versions();
+        let (requested, path) = self.selector.requested(headers, path, &supported);
+        let version = match requested {
+            Some(v) => {
+                self.supported_contract(&v)?;
+                v
+            }
+            None => self.default_version().to_string(),
+        };
+
+        Ok(VersionSelection {
+            version,
+            path: path.to_string(),
+        })
     }
 
     /// Resolve an HTTP request to an operation.
     ///
-    /// Returns the operation ID and extracted path parameters.
+    /// Resolves against the default version and returns the operation ID and
+    /// extracted path parameters.
     pub fn resolve(&self, method: &str, path: &str) -> SentinelResult<OperationResolution> {
-        self.resolver.resolve(method, path)
+        self.resolve_version(method, path, None)
+    }
+
+    /// Resolve an HTTP request to an operation, selecting the contract
+    /// version from the request headers (or path prefix).
+    ///
+    /// # Errors
+    ///
+    /// Returns `SentinelError::UnsupportedVersion` for an unsupported
+    /// version, or `SentinelError::OperationNotFound` if nothing matches.
+    pub fn resolve_with_headers(
+        &self,
+        method: &str,
+        path: &str,
+        headers: &http::HeaderMap,
+    ) -> SentinelResult<OperationResolution> {
+        let selection = self.select_version(headers, path)?;
+        let mut resolution =
+            self.resolve_version(method, &selection.path, Some(&selection.version))?;
+
+        // Report the pattern as the client sent it, version prefix included
+        if selection.path != path {
+            resolution.route_pattern = if resolution.route_pattern == "/" {
+                format!("/{}", selection.version)
+            } else {
+                format!("/{}{}", selection.version, resolution.route_pattern)
+            };
+        }
+        Ok(resolution)
+    }
+
+    /// Resolve an HTTP request against a specific contract version.
+    ///
+    /// `None` resolves against the default version.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SentinelError::UnsupportedVersion` for an unsupported
+    /// version, or `SentinelError::OperationNotFound` if nothing matches.
+    pub fn resolve_version(
+        &self,
+        method: &str,
+        path: &str,
+        version: Option<&str>,
+    ) -> SentinelResult<OperationResolution> {
+        let (label, contract) = match version {
+            Some(v) => (v, self.supported_contract(v)?),
+            None => (self.default_version(), self.default_contract()),
+        };
+        let mut resolution = contract.resolver.resolve(method, path)?;
+        resolution.version = label.to_string();
+        Ok(resolution)
+    }
+
+    /// Explain how a request resolves against the default version, without
+    /// resolving it.
+    ///
+    /// Lists the routes considered for the method and how each compared
+    /// with the path, segment by segment, along with the final decision:
+    /// a match, a method mismatch with the allowed methods, or no match
+    /// with the nearest routes as suggestions. Useful for debug endpoints
+    /// and tooling when a request does not reach the expected operation.
+    pub fn explain(&self, method: &str, path: &str) -> ResolutionExplanation {
+        let mut explanation = self.default_contract().resolver.explain(method, path);
+        explanation.version = self.default_version().to_string();
+        if let ResolutionDecision::Matched(resolution) = &mut explanation.decision {
+            resolution.version.clone_from(&explanation.version);
+        }
+        explanation
     }
 
     /// Check if an operation exists for the given method and path.
     pub fn has_operation(&self, method: &str, path: &str) -> bool {
-        self.resolver.has_route(method, path)
+        self.default_contract().resolver.has_route(method, path)
     }
 
     /// Validate a request body against the operation schema.
@@ -130,12 +369,109 @@ impl Sentinel {
         &self,
         operation_id: &str,
         body: &serde_json::Value,
+    ) -> SentinelResult<ValidationResult> {
+        self.validate_request_for_version(self.default_version(), operation_id, body)
+    }
+
+    /// Validate a request body against the operation schema of a specific
+    /// contract version.
+    pub fn validate_request_for_version(
+        &self,
+        version: &str,
+        operation_id: &str,
+        body: &serde_json::Value,
+    ) -> SentinelResult<ValidationResult> {
+        if !self.config.validation.validate_requests {
+            return Ok(ValidationResult::success(None));
+        }
+        let contract = self.contract(version)?;
+        contract
+            .validator
+            .validate_request(operation_id, &contract.artifact, body)
+    }
+
+    /// Validate request headers against the operation's header parameters.
+    ///
+    /// Checks that required headers are present and that values match the
+    /// declared type, enum and pattern. Header names are case-insensitive.
+    pub fn validate_request_headers(
+        &self,
+        operation_id: &str,
+        headers: &http::HeaderMap,
+    ) -> SentinelResult<ValidationResult> {
+        self.validate_request_headers_for_version(self.default_version(), operation_id, headers)
+    }
+
+    /// Validate request headers against the operation's header parameters
+    /// in a specific contract version.
+    pub fn validate_request_headers_for_version(
+        &self,
+        version: &str,
+        operation_id: &str,
+        headers: &http::HeaderMap,
     ) -> SentinelResult<ValidationResult> {
         if !self.config.validation.validate_requests {
             return Ok(ValidationResult::success(None));
         }
-        self.validator
-            .validate_request(operation_id, &self.artifact, body)
+        let contract = self.contract(version)?;
+        Ok(contract
+            .validator
+            .validate_request_headers(operation_id, &contract.artifact, headers))
+    }
+
+    /// Coerce string values in a request body to the types declared by the
+    /// operation's request schema.
+    ///
+    /// Does nothing unless [`ValidationConfig::coerce_primitives`] is set.
+    /// Returns the paths of the coerced fields.
+    pub fn coerce_request(
+        &self,
+        operation_id: &str,
+        body: &mut serde_json::Value,
+    ) -> SentinelResult<Vec<String>> {
+        self.coerce_request_for_version(self.default_version(), operation_id, body)
+    }
+
+    /// Coerce string values in a request body to the types declared by the
+    /// operation's request schema in a specific contract version.
+    pub fn coerce_request_for_version(
+        &self,
+        version: &str,
+        operation_id: &str,
+        body: &mut serde_json::Value,
+    ) -> SentinelResult<Vec<String>> {
+        let contract = self.contract(version)?;
+        Ok(contract
+            .validator
+            .coerce_request(operation_id, &contract.artifact, body))
+    }
+
+    /// Remove fields a request body carries but its schema does not
+    /// declare.
+    ///
+    /// Does nothing unless [`ValidationConfig::request_unknown_fields`] is
+    /// [`RequestUnknownFields::Strip`]. Returns the paths of the removed
+    /// fields.
+    pub fn strip_unknown_fields(
+        &self,
+        operation_id: &str,
+        body: &mut serde_json::Value,
+    ) -> SentinelResult<Vec<String>> {
+        self.strip_unknown_fields_for_version(self.default_version(), operation_id, body)
+    }
+
+    /// Remove fields a request body carries but its schema does not
+    /// declare in a specific contract version.
+    pub fn strip_unknown_fields_for_version(
+        &self,
+        version: &str,
+        operation_id: &str,
+        body: &mut serde_json::Value,
+    ) -> SentinelResult<Vec<String>> {
+        let contract = self.contract(version)?;
+        Ok(contract
+            .validator
+            .strip_unknown_fields(operation_id, &contract.artifact, body))
     }
 
     /// Validate a response body against the operation schema.
@@ -144,38 +480,232 @@ impl Sentinel {
         operation_id: &str,
         status_code: u16,
         body: &serde_json::Value,
+    ) -> SentinelResult<ValidationResult> {
+        self.validate_response_for_version(self.default_version(), operation_id, status_code, body)
+    }
+
+    /// Validate a response body against the operation schema of a specific
+    /// contract version.
+    pub fn validate_response_for_version(
+        &self,
+        version: &str,
+        operation_id: &str,
+        status_code: u16,
+        body: &serde_json::Value,
+    ) -> SentinelResult<ValidationResult> {
+        self.validate_response_content_for_version(
+            version,
+            operation_id,
+            status_code,
+            JSON_MEDIA_TYPE,
+            body,
+        )
+    }
+
+    /// Validate a response body against the operation schema for its
+    /// `Content-Type`, so an operation can answer JSON and, say, CSV with
+    /// different schemas.
+    ///
+    /// Bodies of media types other than JSON are given as a JSON string.
+    pub fn validate_response_content(
+        &self,
+        operation_id: &str,
+        status_code: u16,
+        content_type: &str,
+        body: &serde_json::Value,
+    ) -> SentinelResult<ValidationResult> {
+        self.validate_response_content_for_version(
+            self.default_version(),
+            operation_id,
+            status_code,
+            content_type,
+            body,
+        )
+    }
+
+    /// Validate a response body against the operation schema for its
+    /// `Content-Type` in a specific contract version.
+    pub fn validate_response_content_for_version(
+        &self,
+        version: &str,
+        operation_id: &str,
+        status_code: u16,
+        content_type: &str,
+        body: &serde_json::Value,
     ) -> SentinelResult<ValidationResult> {
         if !self.config.validation.validate_responses {
             return Ok(ValidationResult::success(None));
         }
-        self.validator
-            .validate_response(operation_id, &self.artifact, status_code, body)
+        let contract = self.contract(version)?;
+        contract.validator.validate_response_content(
+            operation_id,
+            &contract.artifact,
+            status_code,
+            content_type,
+            body,
+        )
+    }
+
+    /// Validate the data of a server-sent event against the operation's
+    /// schema for its `event:` type.
+    ///
+    /// Unlike [`validate_response`](Self::validate_response), this is not
+    /// gated by `validate_responses`: streams opt in by validating their
+    /// events.
+    pub fn validate_event(
+        &self,
+        operation_id: &str,
+        event_type: &str,
+        data: &serde_json::Value,
+    ) -> SentinelResult<ValidationResult> {
+        self.validate_event_for_version(self.default_version(), operation_id, event_type, data)
+    }
+
+    /// Validate the data of a server-sent event against the operation schema
+    /// of a specific contract version.
+    pub fn validate_event_for_version(
+        &self,
+        version: &str,
+        operation_id: &str,
+        event_type: &str,
+        data: &serde_json::Value,
+    ) -> SentinelResult<ValidationResult> {
+        let contract = self.contract(version)?;
+        contract
+            .validator
+            .validate_event(operation_id, &contract.artifact, event_type, data)
+    }
+
+    /// Build the validation state of every operation in every contract
+    /// version now, rather than when each operation is first validated.
+    ///
+    /// For latency-sensitive deployments that prefer to pay the cost at
+    /// startup; see [`SchemaValidator::precompile_all`].
+    pub fn precompile_all(&self) {
+        for contract in self.contracts.values() {
+            contract.validator.precompile_all(&contract.artifact);
+        }
+    }
+
+    /// Build the validation state of one operation of the default contract
+    /// version now.
+    ///
+    /// Returns `false` if the operation is unknown.
+    pub fn precompile_operation(&self, operation_id: &str) -> bool {
+        self.precompile_operation_for_version(self.default_version(), operation_id)
+    }
+
+    /// Build the validation state of one operation of a specific contract
+    /// version now.
+    ///
+    /// Returns `false` if the version or operation is unknown.
+    pub fn precompile_operation_for_version(&self, version: &str, operation_id: &str) -> bool {
+        self.contracts.get(version).is_some_and(|contract| {
+            contract
+                .validator
+                .precompile(operation_id, &contract.artifact)
+        })
+    }
+
+    /// Get the number of operations, across contract versions, whose
+    /// validation state has been built.
+    pub fn compiled_operations(&self) -> usize {
+        self.contracts
+            .values()
+            .map(|contract| contract.validator.compiled_operations())
+            .sum()
+    }
+
+    /// Generate an example request body of an operation of the default
+    /// contract version; see [`ExampleGenerator`].
+    ///
+    /// Returns `None` if the operation is unknown or declares no request
+    /// schema.
+    pub fn request_example(&self, operation_id: &str) -> Option<serde_json::Value> {
+        self.request_example_for_version(self.default_version(), operation_id)
+    }
+
+    /// Generate an example request body of an operation of a specific
+    /// contract version.
+    pub fn request_example_for_version(
+        &self,
+        version: &str,
+        operation_id: &str,
+    ) -> Option<serde_json::Value> {
+        let artifact = &self.contracts.get(version)?.artifact;
+        let operation = artifact
+            .operations
+            .iter()
+            .find(|op| op.id == operation_id)?;
+        ExampleGenerator::new().request_example(operation)
     }
 
-    /// Get the underlying artifact.
+    /// Get the default artifact.
     pub fn artifact(&self) -> &LoadedArtifact {
-        &self.artifact
+        &self.default_contract().artifact
     }
 
-    /// Get the operation count.
+    /// Get the artifact loaded for a version label.
+    pub fn artifact_for_version(&self, version: &str) -> Option<&LoadedArtifact> {
+        self.contracts.get(version).map(|c| &c.artifact)
+    }
+
+    /// Iterate over all loaded artifacts with their version labels.
+    pub fn artifacts(&self) -> impl Iterator<Item = (&str, &LoadedArtifact)> {
+        self.contracts
+            .iter()
+            .map(|(version, contract)| (version.as_str(), &contract.artifact))
+    }
+
+    /// Get the default artifact's operation count.
     pub fn operation_count(&self) -> usize {
-        self.artifact.operations.len()
+        self.default_contract().artifact.operations.len()
     }
 
-    /// Get all registered HTTP methods.
+    /// Get all registered HTTP methods of the default artifact.
     pub fn methods(&self) -> Vec<&str> {
-        self.resolver.methods()
+        self.default_contract().resolver.methods()
     }
 
-    /// Get all routes for a specific method.
+    /// Get all routes of the default artifact for a specific method.
     pub fn routes_for_method(&self, method: &str) -> Vec<&str> {
-        self.resolver.routes_for_method(method)
+        self.default_contract().resolver.routes_for_method(method)
     }
 
     /// Get the configuration.
     pub fn config(&self) -> &SentinelConfig {
         &self.config
     }
+
+    fn default_contract(&self) -> &Contract {
+        &self.contracts[self.default_version()]
+    }
+
+    /// Looks up any loaded version (retired versions still validate).
+    fn contract(&self, version: &str) -> SentinelResult<&Contract> {
+        self.contracts
+            .get(version)
+            .ok_or_else(|| self.unsupported(version))
+    }
+
+    /// Looks up a version clients may request.
+    fn supported_contract(&self, version: &str) -> SentinelResult<&Contract> {
+        if !self.supported_versions().contains(&version) {
+            return Err(self.unsupported(version));
+        }
+        self.contract(version)
+    }
+
+    fn unsupported(&self, version: &str) -> SentinelError {
+        SentinelError::UnsupportedVersion {
+            requested: version.to_string(),
+            supported: self
+                .supported_versions()
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -200,6 +730,11 @@ mod tests {
                     request_schema: None,
                     response_schemas: HashMap::new(),
                     tags: vec!["users".to_string()],
+                    header_params: Vec::new(),
+                    event_schemas: HashMap::new(),
+                    timeout: None,
+                    cache_policy: None,
+                    allow_reserved: Vec::new(),
                 },
                 LoadedOperation {
                     id: "getUser".to_string(),
@@ -211,9 +746,15 @@ mod tests {
                     request_schema: None,
                     response_schemas: HashMap::new(),
                     tags: vec!["users".to_string()],
+                    header_params: Vec::new(),
+                    event_schemas: HashMap::new(),
+                    timeout: None,
+                    cache_policy: None,
+                    allow_reserved: Vec::new(),
                 },
             ],
             schemas: IndexMap::new(),
+            stats: Default::default(),
         }
     }
 
@@ -243,6 +784,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_sentinel_explain() {
+        let sentinel = Sentinel::with_defaults(create_test_artifact());
+
+        let explanation = sentinel.explain("GET", "/users/123");
+        assert_eq!(explanation.operation_id(), Some("getUser"));
+        assert_eq!(explanation.version, "1.0.0");
+        let ResolutionDecision::Matched(resolution) = &explanation.decision else {
+            panic!("expected a match: {explanation}");
+        };
+        assert_eq!(
+            resolution.path_params.get("userId"),
+            Some(&"123".to_string())
+        );
+        assert_eq!(explanation.candidates.len(), 2);
+
+        // Explaining is a dry run: resolution is unaffected
+        let explanation = sentinel.explain("GET", "/users/123/avatar");
+        assert_eq!(explanation.operation_id(), None);
+        assert_eq!(
+            sentinel.resolve("GET", "/users/123").unwrap().operation_id,
+            "getUser"
+        );
+    }
+
     #[test]
     fn test_sentinel_has_operation() {
         let artifact = create_test_artifact();
@@ -281,4 +847,121 @@ mod tests {
 
         assert!(sentinel.config().validation.strict_mode);
     }
+
+    fn versioned_sentinel(selector: VersionSelector) -> Sentinel {
+        let v1 = create_test_artifact();
+        let mut v2 = create_test_artifact();
+        v2.version = "2.0.0".to_string();
+        v2.operations[1].id = "getUserV2".to_string();
+
+        Sentinel::versioned(
+            [("v1", v1), ("v2", v2)],
+            selector,
+            SentinelConfig::default(),
+        )
+        .unwrap()
+    }
+
+    fn version_header(version: &'static str) -> http::HeaderMap {
+        let mut headers = http::HeaderMap::new();
+        headers.insert(
+            DEFAULT_VERSION_HEADER,
+            http::HeaderValue::from_static(version),
+        );
+        headers
+    }
+
+    #[test]
+    fn test_versioned_same_path_resolves_per_version() {
+        let sentinel = versioned_sentinel(VersionSelector::new());
+
+        let v1 = sentinel
+            .resolve_with_headers("GET", "/users/1", &version_header("v1"))
+            .unwrap();
+        assert_eq!(v1.operation_id, "getUser");
+        assert_eq!(v1.version, "v1");
+
+        let v2 = sentinel
+            .resolve_with_headers("GET", "/users/1", &version_header("v2"))
+            .unwrap();
+        assert_eq!(v2.operation_id, "getUserV2");
+        assert_eq!(v2.version, "v2");
+
+        let pre_extracted = sentinel
+            .resolve_version("GET", "/users/1", Some("v2"))
+            .unwrap();
+        assert_eq!(pre_extracted.operation_id, "getUserV2");
+    }
+
+    #[test]
+    fn test_versioned_path_prefix() {
+        let sentinel = versioned_sentinel(VersionSelector::new().path_prefix(true));
+
+        let resolution = sentinel
+            .resolve_with_headers("GET", "/v2/users/1", &http::HeaderMap::new())
+            .unwrap();
+        assert_eq!(resolution.operation_id, "getUserV2");
+        assert_eq!(resolution.path_params.get("userId"), Some(&"1".to_string()));
+        assert_eq!(resolution.route_pattern, "/v2/users/{userId}");
+        assert_eq!(resolution.path_template, "/users/{userId}");
+    }
+
+    #[test]
+    fn test_versioned_default_selection() {
+        let sentinel = versioned_sentinel(VersionSelector::new());
+        assert_eq!(sentinel.default_version(), "v1");
+        let resolution = sentinel
+            .resolve_with_headers("GET", "/users/1", &http::HeaderMap::new())
+            .unwrap();
+        assert_eq!(resolution.operation_id, "getUser");
+
+        let sentinel = versioned_sentinel(VersionSelector::new().default_version("v2"));
+        assert_eq!(sentinel.default_version(), "v2");
+        assert_eq!(sentinel.version(), "2.0.0");
+        let resolution = sentinel.resolve("GET", "/users/1").unwrap();
+        assert_eq!(resolution.operation_id, "getUserV2");
+        assert_eq!(resolution.version, "v2");
+    }
+
+    #[test]
+    fn test_versioned_unsupported_version_rejected() {
+        let sentinel = versioned_sentinel(VersionSelector::new().supported(["v2"]));
+
+        let err = sentinel
+            .resolve_with_headers("GET", "/users/1", &version_header("v1"))
+            .unwrap_err();
+        match err {
+            SentinelError::UnsupportedVersion {
+                requested,
+                supported,
+            } => {
+                assert_eq!(requested, "v1");
+                assert_eq!(supported, vec!["v2".to_string()]);
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+
+        assert!(matches!(
+            sentinel.resolve_version("GET", "/users", Some("v9")),
+            Err(SentinelError::UnsupportedVersion { .. })
+        ));
+    }
+
+    #[test]
+    fn test_versioned_rejects_unknown_default() {
+        let result = Sentinel::versioned(
+            [("v1", create_test_artifact())],
+            VersionSelector::new().default_version("v3"),
+            SentinelConfig::default(),
+        );
+        assert!(matches!(
+            result,
+            Err(SentinelError::UnsupportedVersion { .. })
+        ));
+
+        let empty: Vec<(String, LoadedArtifact)> = Vec::new();
+        assert!(
+            Sentinel::versioned(empty, VersionSelector::new(), SentinelConfig::default()).is_err()
+        );
+    }
 }
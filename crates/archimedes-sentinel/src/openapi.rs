@@ -0,0 +1,1029 @@
+//! OpenAPI document normalization.
+//!
+//! Converts an OpenAPI 3.x document (already parsed from JSON or YAML) into
+//! a [`LoadedArtifact`], so plain OpenAPI specs can be loaded alongside
+//! Themis artifacts.
+//!
+//! Header parameters declared under the document's `x-common-parameters`
+//! extension apply to every operation, in addition to those declared on
+//! the path item and the operation itself.
+//!
+//! Schemas of server-sent events are declared per `event:` type under the
+//! `x-event-schemas` extension of a response's `text/event-stream` media
+//! type. Events sent without a type are looked up as `message`.
+//!
+//! Response schemas are kept per status code and media type, so an
+//! operation can answer `application/json` and `text/csv` with different
+//! schemas.
+//!
+//! An operation's `x-timeout-ms` extension sets its timeout, overriding the
+//! server's request timeout.
+//!
+//! An operation's `x-cache-policy` extension sets the caching of its
+//! successful responses:
+//!
+//! ```yaml
+//! x-cache-policy:
+//!   scope: public          # or private
+//!   maxAge: 300
+//!   sMaxAge: 600
+//!   staleWhileRevalidate: 60
+//!   staleIfError: 86400
+//!   immutable: false
+//!   mustRevalidate: false
+//!   noCache: false         # noStore: true forbids caching altogether
+//! ```
+//!
+//! A schema property's `x-required-scope` extension names the scope a
+//! caller must hold to receive the property in a response; see
+//! [`LoadedOperation::redactions`]:
+//!
+//! ```yaml
+//! email:
+//!   type: string
+//!   x-required-scope: pii:read
+//! ```
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use archimedes_core::CachePolicy;
+use indexmap::IndexMap;
+use serde_json::Value;
+use tracing::debug;
+
+use crate::artifact::{
+    contract_path, media_type, HeaderParam, LoadedArtifact, LoadedOperation, SchemaRef,
+    JSON_MEDIA_TYPE,
+};
+use crate::error::{SentinelError, SentinelResult};
+
+/// HTTP methods that may appear as keys of an OpenAPI path item.
+const METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Media type of server-sent event streams.
+const EVENT_STREAM_MEDIA_TYPE: &str = "text/event-stream";
+
+/// Media type extension holding the schema of each event type.
+const EVENT_SCHEMAS: &str = "x-event-schemas";
+
+/// Operation extension holding the operation's timeout in milliseconds.
+const TIMEOUT_MS: &str = "x-timeout-ms";
+
+/// Operation extension holding the caching of the operation's responses.
+const CACHE_POLICY: &str = "x-cache-policy";
+
+/// Property extension holding the scope needed to receive the property.
+const REQUIRED_SCOPE: &str = "x-required-scope";
+
+/// Document-level extension holding parameters shared by all operations.
+const COMMON_PARAMETERS: &str = "x-common-parameters";
+
+/// Header parameters that OpenAPI says to ignore, as they are described
+/// elsewhere in the document.
+const IGNORED_HEADERS: [&str; 3] = ["accept", "content-type", "authorization"];
+
+/// Converts an OpenAPI document to a loaded artifact.
+pub(crate) fn to_loaded_artifact(doc: &Value) -> SentinelResult<LoadedArtifact> {
+    let info = doc
+        .get("info")
+        .ok_or_else(|| SentinelError::ArtifactParse("OpenAPI document has no `info`".into()))?;
+    let service = info
+        .get("title")
+        .and_then(as_text)
+        .unwrap_or_else(|| "unknown".to_string());
+    let version = info
+        .get("version")
+        .and_then(as_text)
+        .unwrap_or_else(|| "0.0.0".to_string());
+
+    let components = doc
+        .pointer("/components/schemas")
+        .and_then(Value::as_object);
+    let global_security = doc.get("security");
+
+    let mut refs = SchemaRefs::new(doc);
+    let mut operations = Vec::new();
+    if let Some(paths) = doc.get("paths").and_then(Value::as_object) {
+        for (path, item) in paths {
+            for method in METHODS {
+                if let Some(op) = item.get(method) {
+                    operations.push(convert_operation(
+                        &mut refs,
+                        path,
+                        method,
+                        item,
+                        op,
+                        global_security,
+                    ));
+                }
+            }
+        }
+    }
+
+    let mut schemas = IndexMap::new();
+    for (name, schema) in components.into_iter().flatten() {
+        match serde_json::from_value(schema.clone()) {
+            Ok(schema) => {
+                schemas.insert(name.clone(), schema);
+            }
+            Err(e) => debug!(schema = %name, error = %e, "skipping unsupported OpenAPI schema"),
+        }
+    }
+
+    debug!(
+        service,
+        version,
+        operations = operations.len(),
+        schemas = schemas.len(),
+        "OpenAPI document loaded"
+    );
+
+    Ok(LoadedArtifact {
+        service,
+        version,
+        format: "openapi".to_string(),
+        operations,
+        schemas,
+        stats: Default::default(),
+    })
+}
+
+fn convert_operation<'a>(
+    refs: &mut SchemaRefs<'a>,
+    path: &str,
+    method: &str,
+    item: &'a Value,
+    op: &'a Value,
+    global_security: Option<&Value>,
+) -> LoadedOperation {
+    let id = op
+        .get("operationId")
+        .and_then(Value::as_str)
+        .map_or_else(|| fallback_operation_id(method, path), str::to_string);
+
+    let request_schema = op
+        .pointer("/requestBody/content")
+        .and_then(|content| content.get(JSON_MEDIA_TYPE))
+        .and_then(|media| media.get("schema"))
+        .map(|schema| refs.get(schema));
+
+    let mut response_schemas = HashMap::new();
+    let responses = op.get("responses").and_then(Value::as_object);
+    for (status, response) in responses.into_iter().flatten() {
+        let content = response.get("content").and_then(Value::as_object);
+        for (media, body) in content.into_iter().flatten() {
+            let media = media_type(media);
+            // Event streams are validated per event, see `event_schemas`
+            if media == EVENT_STREAM_MEDIA_TYPE {
+                continue;
+            }
+            if let Some(schema) = body.get("schema") {
+                response_schemas.insert((status.clone(), media), refs.get(schema));
+            }
+        }
+    }
+
+    let event_schemas = event_schemas(refs, op);
+
+    let security = op
+        .get("security")
+        .or(global_security)
+        .map(convert_security)
+        .unwrap_or_default();
+
+    LoadedOperation {
+        id,
+        method: method.to_uppercase(),
+        path: contract_path(path),
+        summary: op
+            .get("summary")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        deprecated: op
+            .get("deprecated")
+            .and_then(Value::as_bool)
+            .unwrap_or(false),
+        security,
+        request_schema,
+        response_schemas,
+        tags: op
+            .get("tags")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        header_params: header_params(refs.doc, item, op),
+        event_schemas,
+        timeout: op
+            .get(TIMEOUT_MS)
+            .and_then(Value::as_u64)
+            .map(Duration::from_millis),
+        cache_policy: op.get(CACHE_POLICY).and_then(cache_policy),
+        allow_reserved: allow_reserved(refs.doc, item, op),
+    }
+}
+
+/// Converts an `x-cache-policy` extension to a cache policy.
+///
+/// Returns `None` if the extension is not an object.
+fn cache_policy(ext: &Value) -> Option<CachePolicy> {
+    let ext = ext.as_object()?;
+    let flag = |name: &str| ext.get(name).and_then(Value::as_bool).unwrap_or(false);
+    let seconds = |name: &str| ext.get(name).and_then(Value::as_u64);
+
+    let mut policy = if flag("noStore") {
+        CachePolicy::no_store()
+    } else if flag("noCache") {
+        CachePolicy::no_cache()
+    } else if ext.get("scope").and_then(Value::as_str) == Some("private") {
+        CachePolicy::private()
+    } else {
+        CachePolicy::public()
+    };
+    if let Some(max_age) = seconds("maxAge") {
+        policy = policy.max_age(max_age);
+    }
+    if let Some(s_maxage) = seconds("sMaxAge") {
+        policy = policy.s_maxage(s_maxage);
+    }
+    if let Some(window) = seconds("staleWhileRevalidate") {
+        policy = policy.stale_while_revalidate(window);
+    }
+    if let Some(window) = seconds("staleIfError") {
+        policy = policy.stale_if_error(window);
+    }
+    if flag("immutable") {
+        policy = policy.immutable();
+    }
+    if flag("mustRevalidate") {
+        policy = policy.must_revalidate();
+    }
+    Some(policy)
+}
+
+/// Collects the event schemas declared by the event stream responses of an
+/// operation, by event type.
+fn event_schemas<'a>(refs: &mut SchemaRefs<'a>, op: &'a Value) -> HashMap<String, SchemaRef> {
+    op.get("responses")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .filter_map(|(_, response)| {
+            response
+                .pointer("/content")
+                .and_then(|content| content.get(EVENT_STREAM_MEDIA_TYPE))
+                .and_then(|media| media.get(EVENT_SCHEMAS))
+                .and_then(Value::as_object)
+        })
+        .flatten()
+        .map(|(event_type, schema)| (event_type.clone(), refs.get(schema)))
+        .collect()
+}
+
+/// Collects the header parameters of an operation.
+///
+/// Parameters from the document's common parameters, the path item and
+/// the operation are merged in that order, a later parameter replacing an
+/// earlier one with the same (case-insensitive) name.
+fn header_params(doc: &Value, item: &Value, op: &Value) -> Vec<HeaderParam> {
+    let sources = [
+        doc.get(COMMON_PARAMETERS),
+        item.get("parameters"),
+        op.get("parameters"),
+    ];
+
+    let mut params: Vec<HeaderParam> = Vec::new();
+    for param in sources
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_array)
+        .flatten()
+    {
+        let param = resolve_local_ref(doc, param);
+        if param.get("in").and_then(Value::as_str) != Some("header") {
+            continue;
+        }
+        let Some(name) = param.get("name").and_then(Value::as_str) else {
+            continue;
+        };
+        if IGNORED_HEADERS
+            .iter()
+            .any(|ignored| ignored.eq_ignore_ascii_case(name))
+        {
+            continue;
+        }
+
+        let schema = param
+            .get("schema")
+            .map_or(&Value::Null, |schema| resolve_local_ref(doc, schema));
+        let header = HeaderParam {
+            name: name.to_string(),
+            required: param
+                .get("required")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+            schema_type: schema_type(schema),
+            pattern: schema
+                .get("pattern")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            allowed_values: schema
+                .get("enum")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(as_text)
+                .collect(),
+        };
+
+        match params
+            .iter_mut()
+            .find(|existing| existing.name.eq_ignore_ascii_case(name))
+        {
+            Some(existing) => *existing = header,
+            None => params.push(header),
+        }
+    }
+    params
+}
+
+/// Collects the path parameters declared with `allowReserved: true`.
+///
+/// Greedy parameters may be named with their `+` suffix.
+fn allow_reserved(doc: &Value, item: &Value, op: &Value) -> Vec<String> {
+    let mut names: Vec<String> = [item.get("parameters"), op.get("parameters")]
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_array)
+        .flatten()
+        .map(|param| resolve_local_ref(doc, param))
+        .filter(|param| param.get("in").and_then(Value::as_str) == Some("path"))
+        .filter(|param| {
+            param
+                .get("allowReserved")
+                .and_then(Value::as_bool)
+                .unwrap_or(false)
+        })
+        .filter_map(|param| param.get("name").and_then(Value::as_str))
+        .map(|name| name.trim_end_matches('+').to_string())
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+    names
+}
+
+/// Reads a string field that YAML may have parsed as a number
+/// (e.g. `version: 1.0`).
+pub(crate) fn as_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Builds an operation ID for operations without `operationId`,
+/// e.g. `get_users_userId` for `GET /users/{userId}`.
+fn fallback_operation_id(method: &str, path: &str) -> String {
+    let segments = path
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(|s| s.trim_start_matches('{').trim_end_matches('}'));
+    std::iter::once(method)
+        .chain(segments)
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Converts OpenAPI security requirements to contract security entries.
+///
+/// Each requirement object is one alternative, written as
+/// `scheme[scope_a scope_b]`. Requirements naming several schemes join the
+/// names with `+` and merge their scopes.
+fn convert_security(security: &Value) -> Vec<String> {
+    security
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_object)
+        .filter(|requirement| !requirement.is_empty())
+        .map(|requirement| {
+            let schemes: Vec<&str> = requirement.keys().map(String::as_str).collect();
+            let scopes: Vec<&str> = requirement
+                .values()
+                .filter_map(Value::as_array)
+                .flatten()
+                .filter_map(Value::as_str)
+                .collect();
+            if scopes.is_empty() {
+                schemes.join("+")
+            } else {
+                format!("{}[{}]", schemes.join("+"), scopes.join(" "))
+            }
+        })
+        .collect()
+}
+
+/// Converts schemas to schema references, converting the target of each
+/// `$ref` once however many operations use it.
+struct SchemaRefs<'a> {
+    doc: &'a Value,
+    by_ref: HashMap<&'a str, SchemaRef>,
+}
+
+impl<'a> SchemaRefs<'a> {
+    fn new(doc: &'a Value) -> Self {
+        Self {
+            doc,
+            by_ref: HashMap::new(),
+        }
+    }
+
+    fn get(&mut self, schema: &'a Value) -> SchemaRef {
+        let doc = self.doc;
+        match schema.get("$ref").and_then(Value::as_str) {
+            Some(reference) => self
+                .by_ref
+                .entry(reference)
+                .or_insert_with(|| schema_to_ref(doc, schema))
+                .clone(),
+            None => schema_to_ref(doc, schema),
+        }
+    }
+}
+
+/// Converts a JSON Schema to a schema reference, following one level of
+/// local `$ref` to pick up the target's type, required fields and property
+/// types.
+///
+/// Object and array properties are converted too, so nested fields can be
+/// checked.
+fn schema_to_ref(doc: &Value, schema: &Value) -> SchemaRef {
+    nested_schema_to_ref(doc, schema, &mut Vec::new())
+}
+
+/// Converts a schema with its nested schemas, stopping below a `$ref` that
+/// is already being converted so recursive schemas terminate.
+fn nested_schema_to_ref<'a>(
+    doc: &'a Value,
+    schema: &'a Value,
+    converting: &mut Vec<&'a str>,
+) -> SchemaRef {
+    let reference = schema.get("$ref").and_then(Value::as_str);
+    let target = resolve_local_ref(doc, schema);
+
+    let schema_type = schema_type(target);
+    let required = target
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .map(str::to_string)
+        .collect();
+    let declared = target
+        .get("properties")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten();
+    let properties = declared
+        .clone()
+        .map(|(name, property)| (name.clone(), schema_type(resolve_local_ref(doc, property))))
+        .collect();
+    let required_scopes = declared
+        .clone()
+        .filter_map(|(name, property)| {
+            let scope = property
+                .get(REQUIRED_SCOPE)
+                .or_else(|| resolve_local_ref(doc, property).get(REQUIRED_SCOPE))
+                .and_then(Value::as_str)?;
+            Some((name.clone(), scope.to_string()))
+        })
+        .collect();
+    let additional_properties = match target.get("additionalProperties") {
+        Some(Value::Bool(allowed)) => Some(*allowed),
+        Some(Value::Object(_)) => Some(true),
+        _ => None,
+    };
+
+    let mut property_schemas = HashMap::new();
+    let mut items = None;
+    let recursive = reference.is_some_and(|r| converting.contains(&r));
+    if !recursive {
+        converting.extend(reference);
+        property_schemas = declared
+            .filter(|(_, property)| {
+                matches!(
+                    schema_type(resolve_local_ref(doc, property)).as_str(),
+                    "object" | "array"
+                )
+            })
+            .map(|(name, property)| {
+                (
+                    name.clone(),
+                    nested_schema_to_ref(doc, property, converting),
+                )
+            })
+            .collect();
+        items = target
+            .get("items")
+            .filter(|_| schema_type == "array")
+            .map(|item| Box::new(nested_schema_to_ref(doc, item, converting)));
+        if reference.is_some() {
+            converting.pop();
+        }
+    }
+
+    SchemaRef {
+        reference: reference.map_or_else(|| format!("#/inline/{schema_type}"), str::to_string),
+        schema_type,
+        required,
+        properties,
+        property_schemas,
+        required_scopes,
+        items,
+        additional_properties,
+    }
+}
+
+/// Follows a local `$ref`, returning the schema itself when it has none.
+fn resolve_local_ref<'a>(doc: &'a Value, schema: &'a Value) -> &'a Value {
+    schema
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|r| r.strip_prefix('#'))
+        .and_then(|pointer| doc.pointer(pointer))
+        .unwrap_or(schema)
+}
+
+fn schema_type(schema: &Value) -> String {
+    match schema.get("type") {
+        Some(Value::String(t)) => return t.clone(),
+        // OpenAPI 3.1 allows `type: [string, "null"]`
+        Some(Value::Array(types)) => {
+            if let Some(t) = types
+                .iter()
+                .filter_map(Value::as_str)
+                .find(|t| *t != "null")
+            {
+                return t.to_string();
+            }
+        }
+        _ => {}
+    }
+
+    ["oneOf", "allOf", "anyOf", "enum"]
+        .into_iter()
+        .find(|key| schema.get(key).is_some())
+        .map_or_else(
+            || {
+                if schema.get("properties").is_some() {
+                    "object".to_string()
+                } else {
+                    "any".to_string()
+                }
+            },
+            str::to_string,
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resolver::OperationResolver;
+    use serde_json::json;
+
+    #[test]
+    fn test_fallback_operation_id() {
+        assert_eq!(
+            fallback_operation_id("get", "/users/{userId}"),
+            "get_users_userId"
+        );
+        assert_eq!(fallback_operation_id("post", "/"), "post");
+    }
+
+    #[test]
+    fn test_convert_security() {
+        let security = json!([{ "oauth": ["read", "write"] }, { "apiKey": [] }]);
+        assert_eq!(
+            convert_security(&security),
+            vec!["oauth[read write]".to_string(), "apiKey".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_schema_ref_follows_local_ref() {
+        let doc = json!({
+            "components": {
+                "schemas": {
+                    "User": { "type": "object", "required": ["id"] }
+                }
+            }
+        });
+        let schema_ref = schema_to_ref(&doc, &json!({ "$ref": "#/components/schemas/User" }));
+        assert_eq!(schema_ref.reference, "#/components/schemas/User");
+        assert_eq!(schema_ref.schema_type, "object");
+        assert_eq!(schema_ref.required, vec!["id".to_string()]);
+    }
+
+    #[test]
+    fn test_schema_ref_collects_property_types() {
+        let doc = json!({
+            "components": {
+                "schemas": {
+                    "Age": { "type": "integer" },
+                    "Person": {
+                        "type": "object",
+                        "properties": {
+                            "name": { "type": "string" },
+                            "age": { "$ref": "#/components/schemas/Age" },
+                            "active": { "type": ["boolean", "null"] }
+                        }
+                    }
+                }
+            }
+        });
+        let schema_ref = schema_to_ref(&doc, &json!({ "$ref": "#/components/schemas/Person" }));
+        assert_eq!(schema_ref.properties["name"], "string");
+        assert_eq!(schema_ref.properties["age"], "integer");
+        assert_eq!(schema_ref.properties["active"], "boolean");
+    }
+
+    #[test]
+    fn test_schema_ref_nested_schemas() {
+        let doc = json!({
+            "components": {
+                "schemas": {
+                    "Node": {
+                        "type": "object",
+                        "additionalProperties": false,
+                        "properties": {
+                            "name": { "type": "string" },
+                            "meta": {
+                                "type": "object",
+                                "additionalProperties": { "type": "string" },
+                                "properties": { "owner": { "type": "string" } }
+                            },
+                            "children": {
+                                "type": "array",
+                                "items": { "$ref": "#/components/schemas/Node" }
+                            }
+                        }
+                    }
+                }
+            }
+        });
+        let node = schema_to_ref(&doc, &json!({ "$ref": "#/components/schemas/Node" }));
+        assert_eq!(node.additional_properties, Some(false));
+        assert!(!node.property_schemas.contains_key("name"));
+        assert_eq!(
+            node.property_schemas["meta"].additional_properties,
+            Some(true)
+        );
+        assert_eq!(node.property_schemas["meta"].properties["owner"], "string");
+
+        // The recursive reference is kept shallow
+        let child = node.property_schemas["children"].items.as_deref().unwrap();
+        assert_eq!(child.reference, "#/components/schemas/Node");
+        assert_eq!(child.properties.len(), 3);
+        assert!(child.property_schemas.is_empty());
+    }
+
+    #[test]
+    fn test_schema_type_nullable_array() {
+        assert_eq!(
+            schema_type(&json!({ "type": ["null", "string"] })),
+            "string"
+        );
+        assert_eq!(schema_type(&json!({ "oneOf": [] })), "oneOf");
+        assert_eq!(schema_type(&json!({})), "any");
+    }
+
+    #[test]
+    fn test_header_params_merge_levels() {
+        let doc = json!({
+            "x-common-parameters": [
+                { "$ref": "#/components/parameters/RequestId" },
+                { "name": "X-Tenant", "in": "header", "schema": { "type": "string" } }
+            ],
+            "components": {
+                "parameters": {
+                    "RequestId": {
+                        "name": "X-Request-Id",
+                        "in": "header",
+                        "required": true,
+                        "schema": { "type": "string", "pattern": "^[0-9a-f-]+$" }
+                    }
+                }
+            }
+        });
+        let item = json!({
+            "parameters": [
+                { "name": "x-tenant", "in": "header", "required": true }
+            ]
+        });
+        let op = json!({
+            "parameters": [
+                { "name": "limit", "in": "query" },
+                { "name": "Accept", "in": "header", "required": true },
+                {
+                    "name": "X-Mode",
+                    "in": "header",
+                    "schema": { "type": "string", "enum": ["fast", "safe"] }
+                }
+            ]
+        });
+
+        let params = header_params(&doc, &item, &op);
+        let names: Vec<&str> = params.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["X-Request-Id", "x-tenant", "X-Mode"]);
+        assert_eq!(params[0].pattern.as_deref(), Some("^[0-9a-f-]+$"));
+        assert!(params[1].required);
+        assert_eq!(params[2].allowed_values, vec!["fast", "safe"]);
+    }
+
+    #[test]
+    fn test_event_schemas_by_event_type() {
+        let doc = json!({
+            "openapi": "3.1.0",
+            "info": { "title": "feed", "version": "1.0.0" },
+            "paths": {
+                "/feed": {
+                    "get": {
+                        "operationId": "streamFeed",
+                        "responses": {
+                            "200": {
+                                "description": "Feed",
+                                "content": {
+                                    "text/event-stream": {
+                                        "x-event-schemas": {
+                                            "post": { "$ref": "#/components/schemas/Post" },
+                                            "heartbeat": { "type": "object" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Post": { "type": "object", "required": ["id"] }
+                }
+            }
+        });
+
+        let artifact = to_loaded_artifact(&doc).unwrap();
+        let events = artifact.operations[0].event_schemas();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events["post"].reference, "#/components/schemas/Post");
+        assert_eq!(events["post"].required, vec!["id"]);
+        assert!(artifact.operations[0].response_schemas.is_empty());
+    }
+
+    #[test]
+    fn test_response_schemas_by_media_type() {
+        let doc = json!({
+            "openapi": "3.1.0",
+            "info": { "title": "reports", "version": "1.0.0" },
+            "paths": {
+                "/reports/{id}": {
+                    "get": {
+                        "operationId": "getReport",
+                        "responses": {
+                            "200": {
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/Report" }
+                                    },
+                                    "Text/CSV": { "schema": { "type": "string" } },
+                                    "application/pdf": {}
+                                }
+                            },
+                            "default": {
+                                "content": {
+                                    "*/*": { "schema": { "type": "object" } }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Report": { "type": "object", "required": ["id"] }
+                }
+            }
+        });
+
+        let artifact = to_loaded_artifact(&doc).unwrap();
+        let op = &artifact.operations[0];
+        assert_eq!(op.response_schemas.len(), 3);
+        let schema = |status: &str, content_type: &str| {
+            op.response_schema(status, content_type)
+                .map(|schema| schema.reference.as_str())
+        };
+        assert_eq!(
+            schema("200", "application/json"),
+            Some("#/components/schemas/Report")
+        );
+        assert_eq!(
+            schema("200", "text/csv; charset=utf-8"),
+            Some("#/inline/string")
+        );
+        assert_eq!(schema("200", "application/pdf"), Some("#/inline/object"));
+        assert_eq!(schema("404", "text/csv"), Some("#/inline/object"));
+    }
+
+    #[test]
+    fn test_operation_timeouts() {
+        let doc = json!({
+            "openapi": "3.1.0",
+            "info": { "title": "reports", "version": "1.0.0" },
+            "paths": {
+                "/reports": {
+                    "post": { "operationId": "generateReport", "x-timeout-ms": 120000 },
+                    "get": { "operationId": "listReports" }
+                }
+            }
+        });
+
+        let artifact = to_loaded_artifact(&doc).unwrap();
+        let timeouts: Vec<_> = artifact.operation_timeouts().collect();
+        assert_eq!(timeouts, vec![("generateReport", Duration::from_secs(120))]);
+    }
+
+    #[test]
+    fn test_cache_policies() {
+        let doc = json!({
+            "openapi": "3.1.0",
+            "info": { "title": "catalog", "version": "1.0.0" },
+            "paths": {
+                "/products": {
+                    "get": {
+                        "operationId": "listProducts",
+                        "x-cache-policy": { "maxAge": 300, "staleWhileRevalidate": 60 }
+                    },
+                    "post": { "operationId": "createProduct" }
+                },
+                "/assets/{id}": {
+                    "get": {
+                        "operationId": "getAsset",
+                        "x-cache-policy": { "maxAge": 31536000, "immutable": true }
+                    }
+                },
+                "/me": {
+                    "get": {
+                        "operationId": "getProfile",
+                        "x-cache-policy": { "scope": "private", "maxAge": 60, "mustRevalidate": true }
+                    }
+                },
+                "/cart": {
+                    "get": { "operationId": "getCart", "x-cache-policy": { "noStore": true } }
+                }
+            }
+        });
+
+        let artifact = to_loaded_artifact(&doc).unwrap();
+        let policies: HashMap<_, _> = artifact
+            .cache_policies()
+            .map(|(id, policy)| (id, policy.to_string()))
+            .collect();
+        assert_eq!(
+            policies,
+            HashMap::from([
+                (
+                    "listProducts",
+                    "public, max-age=300, stale-while-revalidate=60".to_string()
+                ),
+                (
+                    "getAsset",
+                    "public, max-age=31536000, immutable".to_string()
+                ),
+                (
+                    "getProfile",
+                    "private, max-age=60, must-revalidate".to_string()
+                ),
+                ("getCart", "no-store".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_required_scope_redactions() {
+        let doc = json!({
+            "openapi": "3.1.0",
+            "info": { "title": "customers", "version": "1.0.0" },
+            "paths": {
+                "/customers/{id}": {
+                    "get": {
+                        "operationId": "getCustomer",
+                        "responses": {
+                            "200": {
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/Customer" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+                "/health": { "get": { "operationId": "health" } }
+            },
+            "components": {
+                "schemas": {
+                    "Customer": {
+                        "type": "object",
+                        "properties": {
+                            "name": { "type": "string" },
+                            "email": { "type": "string", "x-required-scope": "pii:read" },
+                            "billing": { "$ref": "#/components/schemas/Billing" },
+                            "contacts": {
+                                "type": "array",
+                                "items": {
+                                    "type": "object",
+                                    "properties": {
+                                        "label": { "type": "string" },
+                                        "phone": { "type": "string", "x-required-scope": "pii:read" }
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    "Billing": {
+                        "type": "object",
+                        "properties": {
+                            "iban": { "type": "string", "x-required-scope": "billing:read" }
+                        }
+                    }
+                }
+            }
+        });
+
+        let artifact = to_loaded_artifact(&doc).unwrap();
+        let redactions: Vec<_> = artifact.redactions().collect();
+        assert_eq!(redactions.len(), 1);
+        let (operation_id, fields) = &redactions[0];
+        assert_eq!(*operation_id, "getCustomer");
+        let fields: Vec<_> = fields
+            .iter()
+            .map(|f| (f.path.as_str(), f.scope.as_str()))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("billing.iban", "billing:read"),
+                ("contacts[].phone", "pii:read"),
+                ("email", "pii:read"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_greedy_path_params_honor_allow_reserved() {
+        let doc = json!({
+            "openapi": "3.1.0",
+            "info": { "title": "storage", "version": "1.0.0" },
+            "paths": {
+                "/files/{path+}": {
+                    "get": {
+                        "operationId": "getFile",
+                        "parameters": [
+                            { "name": "path", "in": "path", "required": true, "allowReserved": true }
+                        ]
+                    }
+                },
+                "/blobs/{key+}": {
+                    "get": {
+                        "operationId": "getBlob",
+                        "parameters": [{ "name": "key", "in": "path", "required": true }]
+                    }
+                }
+            }
+        });
+
+        let artifact = to_loaded_artifact(&doc).unwrap();
+        let file = artifact
+            .operations
+            .iter()
+            .find(|op| op.id == "getFile")
+            .unwrap();
+        assert_eq!(file.path, "/files/*path");
+        assert_eq!(file.allow_reserved, vec!["path"]);
+
+        let resolver = OperationResolver::from_artifact(&artifact);
+        let resolution = resolver.resolve("GET", "/files/docs/a%2Fb.txt").unwrap();
+        assert_eq!(resolution.route_pattern, "/files/*path");
+        assert_eq!(resolution.path_params["path"], "docs/a/b.txt");
+
+        let resolution = resolver.resolve("GET", "/blobs/docs/a%2Fb.txt").unwrap();
+        assert_eq!(resolution.path_params["key"], "docs/a%2Fb.txt");
+    }
+}
@@ -2,17 +2,55 @@
 //!
 //! This module provides the `OperationResolver` which maps incoming HTTP
 //! requests (method + path) to Themis operation IDs.
+//!
+//! [`OperationResolver::explain`] performs the same resolution as a dry run
+//! and reports every route it considered, segment by segment, to help find
+//! out why a request did not reach the expected operation. When nothing
+//! matches it also tells a wrong method apart from a wrong path, and
+//! suggests the routes closest to the request path. Explanations serialize
+//! deterministically, so they can be served from a debug endpoint or
+//! snapshotted in tests.
+//!
+//! # Route precedence
+//!
+//! Routes are matched segment by segment. Where several routes match a
+//! path, the one whose leftmost differing segment is most specific wins:
+//!
+//! 1. Literal segments, e.g. `/files/readme`
+//! 2. Segments with several parameters between literal separators, e.g.
+//!    `/reports/{year}.{month}`
+//! 3. Single parameters, e.g. `/files/{id}`
+//! 4. Wildcards, e.g. `/files/*path`, which contracts write as a greedy
+//!    `/files/{path+}` parameter
+//!
+//! This is the order the Archimedes router uses for its own routes. Among
+//! equally specific routes the longer template is tried first.
+//!
+//! A parameter within a multi-parameter segment ends at the first
+//! occurrence of the literal that follows it, except that a parameter
+//! before a closing literal runs up to that suffix. `{year}.{month}`
+//! matches `2024.03`, and `{name}.json` matches `v1.2.json`. Matching
+//! never backtracks.
+//!
+//! Parameter values are not percent-decoded, so `%2F` in a wildcard stays
+//! encoded, unless the contract declares the parameter with
+//! `allowReserved`; then encoded reserved characters are decoded.
 
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 
-use regex::Regex;
+use serde::{Serialize, Serializer};
 use tracing::debug;
 
 use crate::artifact::{LoadedArtifact, LoadedOperation};
 use crate::error::{SentinelError, SentinelResult};
 
+/// Most route suggestions an explanation includes.
+pub const MAX_SUGGESTIONS: usize = 3;
+
 /// Result of resolving an HTTP request to an operation.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct OperationResolution {
     /// The Themis operation ID.
     pub operation_id: String,
@@ -20,12 +58,220 @@ pub struct OperationResolution {
     pub method: String,
     /// Path template that was matched.
     pub path_template: String,
+    /// Route pattern that matched the request path, e.g. `/users/{userId}`.
+    ///
+    /// Unlike `path_template` this includes any version path prefix that
+    /// was stripped before matching. Wildcard routes report the template,
+    /// never the expanded path, so the pattern is safe to use as a
+    /// low-cardinality telemetry label.
+    pub route_pattern: String,
     /// Extracted path parameters.
+    #[serde(serialize_with = "serialize_sorted")]
     pub path_params: HashMap<String, String>,
     /// Whether the operation is deprecated.
     pub deprecated: bool,
     /// Tags from the operation.
     pub tags: Vec<String>,
+    /// Contract version the request was resolved against.
+    pub version: String,
+}
+
+/// Serializes a map with its keys in order.
+fn serialize_sorted<S: Serializer>(
+    map: &HashMap<String, String>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    map.iter().collect::<BTreeMap<_, _>>().serialize(serializer)
+}
+
+/// The kind of a route template segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SegmentKind {
+    /// A literal segment, matched exactly.
+    Literal,
+    /// A `{param}` segment, matching any non-empty segment.
+    Parameter,
+    /// A segment with several parameters between literal separators, e.g.
+    /// `{year}.{month}`.
+    Pattern,
+    /// A `*rest` segment, matching the non-empty rest of the path.
+    Wildcard,
+    /// A request segment past the end of the template.
+    Extra,
+}
+
+impl SegmentKind {
+    fn of(template: &str) -> Self {
+        CompiledSegment::parse(template).kind()
+    }
+}
+
+/// How a request path segment compared with a route template segment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SegmentMatch {
+    /// Template segment, e.g. `users`, `{userId}` or `*path`; `None` for a
+    /// request segment past the end of the template.
+    pub template: Option<String>,
+    /// Kind of the template segment.
+    pub kind: SegmentKind,
+    /// Request path segment; `None` if the path ended before the template.
+    /// A wildcard segment holds the whole remaining path.
+    pub actual: Option<String>,
+    /// Whether the segments matched.
+    pub matched: bool,
+}
+
+impl fmt::Display for SegmentMatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.template, &self.actual) {
+            (Some(template), Some(actual)) if self.matched => {
+                write!(f, "'{}' matched '{}'", actual, template)
+            }
+            (Some(template), Some(actual)) => {
+                write!(f, "expected '{}', got '{}'", template, actual)
+            }
+            (Some(template), None) => write!(f, "expected '{}', path ended", template),
+            (None, Some(actual)) => write!(f, "unexpected extra segment '{}'", actual),
+            (None, None) => write!(f, "empty segment"),
+        }
+    }
+}
+
+/// A route considered while explaining a resolution.
+#[derive(Debug, Clone, Serialize)]
+pub struct CandidateRoute {
+    /// Operation ID of the route.
+    pub operation_id: String,
+    /// Path template of the route.
+    pub path_template: String,
+    /// Segment comparisons, up to and including the first mismatch.
+    pub segments: Vec<SegmentMatch>,
+    /// Whether the route matched the request path.
+    pub matched: bool,
+}
+
+impl CandidateRoute {
+    /// Get the first segment that failed to match, with its index in the
+    /// request path.
+    pub fn failing_segment(&self) -> Option<(usize, &SegmentMatch)> {
+        self.segments
+            .iter()
+            .enumerate()
+            .find(|(_, segment)| !segment.matched)
+    }
+}
+
+/// The outcome of an explained resolution.
+///
+/// Serializes with an `outcome` tag, e.g.
+/// `{"outcome": "method_not_allowed", "allowed": ["GET"]}`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum ResolutionDecision {
+    /// The request resolved to an operation.
+    Matched(OperationResolution),
+    /// The path matches routes for other methods only.
+    MethodNotAllowed {
+        /// Methods whose routes match the path, sorted.
+        allowed: Vec<String>,
+    },
+    /// No operation uses the request method.
+    NoRoutesForMethod,
+    /// Operations use the method, but none matched the path.
+    NoMatch,
+}
+
+/// A route close to a request path that did not resolve.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RouteSuggestion {
+    /// HTTP method of the route.
+    pub method: String,
+    /// Operation ID of the route.
+    pub operation_id: String,
+    /// Path template of the route.
+    pub path_template: String,
+    /// Character edits between the request path and the template, with
+    /// parameters filled in from the request path.
+    pub distance: usize,
+}
+
+/// A dry-run explanation of how a request resolves.
+///
+/// Produced by [`OperationResolver::explain`] and
+/// [`Sentinel::explain`](crate::Sentinel::explain).
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolutionExplanation {
+    /// Request method (uppercase).
+    pub method: String,
+    /// Request path.
+    pub path: String,
+    /// Contract version the request was resolved against.
+    pub version: String,
+    /// Routes considered, in the order they are tried.
+    pub candidates: Vec<CandidateRoute>,
+    /// The final decision.
+    pub decision: ResolutionDecision,
+    /// Routes closest to the request path, nearest first, when the request
+    /// did not resolve. At most [`MAX_SUGGESTIONS`].
+    pub suggestions: Vec<RouteSuggestion>,
+}
+
+impl ResolutionExplanation {
+    /// Get the ID of the matched operation, if any.
+    pub fn operation_id(&self) -> Option<&str> {
+        match &self.decision {
+            ResolutionDecision::Matched(resolution) => Some(&resolution.operation_id),
+            _ => None,
+        }
+    }
+
+    /// Get the considered route with the given operation ID.
+    pub fn candidate(&self, operation_id: &str) -> Option<&CandidateRoute> {
+        self.candidates
+            .iter()
+            .find(|candidate| candidate.operation_id == operation_id)
+    }
+}
+
+impl fmt::Display for ResolutionExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} (version {}): ",
+            self.method, self.path, self.version
+        )?;
+        match &self.decision {
+            ResolutionDecision::Matched(resolution) => {
+                write!(f, "matched {}", resolution.operation_id)?;
+            }
+            ResolutionDecision::MethodNotAllowed { allowed } => {
+                write!(f, "method not allowed (allowed: {})", allowed.join(", "))?;
+            }
+            ResolutionDecision::NoRoutesForMethod => write!(f, "no routes for method")?,
+            ResolutionDecision::NoMatch => write!(f, "no match")?,
+        }
+        for candidate in &self.candidates {
+            write!(
+                f,
+                "\n  {} {}: ",
+                candidate.operation_id, candidate.path_template
+            )?;
+            match candidate.failing_segment() {
+                _ if candidate.matched => write!(f, "matched")?,
+                Some((index, segment)) => write!(f, "segment {}: {}", index, segment)?,
+                None => write!(f, "no match")?,
+            }
+        }
+        for suggestion in &self.suggestions {
+            write!(
+                f,
+                "\n  did you mean {} {} ({})?",
+                suggestion.method, suggestion.path_template, suggestion.operation_id
+            )?;
+        }
+        Ok(())
+    }
 }
 
 /// Resolves HTTP requests to Themis operations.
@@ -36,6 +282,8 @@ pub struct OperationResolution {
 pub struct OperationResolver {
     /// Routes indexed by HTTP method.
     routes: HashMap<String, Vec<CompiledRoute>>,
+    /// Version of the artifact the routes came from.
+    version: String,
 }
 
 /// A compiled route for efficient matching.
@@ -43,10 +291,10 @@ pub struct OperationResolver {
 struct CompiledRoute {
     /// Original path template.
     template: String,
-    /// Regex for matching paths.
-    pattern: Regex,
-    /// Parameter names in order.
-    param_names: Vec<String>,
+    /// Compiled template segments.
+    segments: Vec<CompiledSegment>,
+    /// Parameters whose encoded reserved characters are decoded.
+    allow_reserved: Vec<String>,
     /// Operation ID.
     operation_id: String,
     /// Whether deprecated.
@@ -74,7 +322,7 @@ impl OperationResolver {
 
         // Sort routes by specificity (more specific paths first)
         for method_routes in routes.values_mut() {
-            method_routes.sort_by(|a, b| Self::route_specificity(&b.template, &a.template));
+            method_routes.sort_by(Self::route_specificity);
         }
 
         debug!(
@@ -83,7 +331,10 @@ impl OperationResolver {
             "operation resolver initialized"
         );
 
-        Self { routes }
+        Self {
+            routes,
+            version: artifact.version.clone(),
+        }
     }
 
     /// Resolve an HTTP request to an operation.
@@ -99,21 +350,16 @@ impl OperationResolver {
 
         // Try each route in order (already sorted by specificity)
         for route in routes {
-            if let Some(captures) = route.pattern.captures(path) {
-                let mut path_params = HashMap::new();
-                for (i, name) in route.param_names.iter().enumerate() {
-                    if let Some(value) = captures.get(i + 1) {
-                        path_params.insert(name.clone(), value.as_str().to_string());
-                    }
-                }
-
+            if let Some(path_params) = Self::match_route(route, path) {
                 return Ok(OperationResolution {
                     operation_id: route.operation_id.clone(),
                     method: method_upper,
                     path_template: route.template.clone(),
+                    route_pattern: route.template.clone(),
                     path_params,
                     deprecated: route.deprecated,
                     tags: route.tags.clone(),
+                    version: self.version.clone(),
                 });
             }
         }
@@ -124,6 +370,240 @@ impl OperationResolver {
         })
     }
 
+    /// Explain how a request resolves, without resolving it.
+    ///
+    /// Reports every route registered for the method, in the order they
+    /// are tried, with how each compared with the request path segment by
+    /// segment. The decision is the one [`resolve`](Self::resolve) makes,
+    /// except that a path served only under other methods is reported as
+    /// [`MethodNotAllowed`](ResolutionDecision::MethodNotAllowed). Requests
+    /// that do not resolve also get the nearest routes as suggestions.
+    pub fn explain(&self, method: &str, path: &str) -> ResolutionExplanation {
+        let method_upper = method.to_uppercase();
+        let routes = self
+            .routes
+            .get(&method_upper)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        let candidates = routes
+            .iter()
+            .map(|route| Self::explain_route(route, path))
+            .collect();
+        let decision = match self.resolve(method, path) {
+            Ok(resolution) => ResolutionDecision::Matched(resolution),
+            Err(_) => {
+                let mut allowed: Vec<String> = self
+                    .routes
+                    .iter()
+                    .filter(|(other, routes)| {
+                        **other != method_upper
+                            && routes
+                                .iter()
+                                .any(|route| Self::match_route(route, path).is_some())
+                    })
+                    .map(|(other, _)| other.clone())
+                    .collect();
+                allowed.sort_unstable();
+                if allowed.is_empty() && routes.is_empty() {
+                    ResolutionDecision::NoRoutesForMethod
+                } else if allowed.is_empty() {
+                    ResolutionDecision::NoMatch
+                } else {
+                    ResolutionDecision::MethodNotAllowed { allowed }
+                }
+            }
+        };
+        let suggestions = match decision {
+            ResolutionDecision::Matched(_) => Vec::new(),
+            _ => self.suggest(path),
+        };
+
+        ResolutionExplanation {
+            method: method_upper,
+            path: path.to_string(),
+            version: self.version.clone(),
+            candidates,
+            decision,
+            suggestions,
+        }
+    }
+
+    /// Find the routes nearest to a path that did not resolve.
+    ///
+    /// Routes the path already matches are left out, as are routes more
+    /// than a quarter of the path length away.
+    fn suggest(&self, path: &str) -> Vec<RouteSuggestion> {
+        let path = match path.strip_suffix('/') {
+            Some(trimmed) if !trimmed.is_empty() => trimmed,
+            _ => path,
+        };
+        let limit = (path.chars().count() / 4).max(1);
+
+        let mut suggestions: Vec<RouteSuggestion> = self
+            .routes
+            .iter()
+            .flat_map(|(method, routes)| routes.iter().map(move |route| (method, route)))
+            .filter_map(|(method, route)| {
+                let distance = edit_distance(path, &Self::fill_template(&route.template, path));
+                (distance > 0 && distance <= limit).then(|| RouteSuggestion {
+                    method: method.clone(),
+                    operation_id: route.operation_id.clone(),
+                    path_template: route.template.clone(),
+                    distance,
+                })
+            })
+            .collect();
+        suggestions.sort_by(|a, b| {
+            (a.distance, &a.path_template, &a.method).cmp(&(
+                b.distance,
+                &b.path_template,
+                &b.method,
+            ))
+        });
+        suggestions.truncate(MAX_SUGGESTIONS);
+        suggestions
+    }
+
+    /// Fill the parameters of a template with the segments of `path` at the
+    /// same positions, so only the literal segments count as edits.
+    fn fill_template(template: &str, path: &str) -> String {
+        let actual: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        let mut filled = String::new();
+        for (index, segment) in template.split('/').filter(|s| !s.is_empty()).enumerate() {
+            filled.push('/');
+            match SegmentKind::of(segment) {
+                SegmentKind::Wildcard => {
+                    filled.push_str(&actual.get(index..).unwrap_or_default().join("/"));
+                    break;
+                }
+                SegmentKind::Parameter | SegmentKind::Pattern => {
+                    filled.push_str(actual.get(index).copied().unwrap_or(segment));
+                }
+                SegmentKind::Literal | SegmentKind::Extra => filled.push_str(segment),
+            }
+        }
+        if filled.is_empty() {
+            filled.push('/');
+        }
+        filled
+    }
+
+    /// Match a request path against a route, returning its parameters.
+    ///
+    /// One trailing slash is ignored.
+    fn match_route(route: &CompiledRoute, path: &str) -> Option<HashMap<String, String>> {
+        if route.segments.is_empty() {
+            return (path == "/").then(HashMap::new);
+        }
+
+        let actual = path_segments(path)?;
+        let mut params = HashMap::new();
+        for (index, segment) in route.segments.iter().enumerate() {
+            if let SegmentMatcher::Wildcard(name) = &segment.matcher {
+                let remaining = actual.get(index..).unwrap_or_default().join("/");
+                if remaining.is_empty() {
+                    return None;
+                }
+                if !name.is_empty() {
+                    params.insert(name.clone(), remaining);
+                }
+                return Some(Self::decode_params(route, params));
+            }
+            if !segment.capture(actual.get(index)?, &mut params) {
+                return None;
+            }
+        }
+
+        (actual.len() == route.segments.len()).then(|| Self::decode_params(route, params))
+    }
+
+    /// Decode the reserved characters of the parameters declared with
+    /// `allowReserved`.
+    fn decode_params(
+        route: &CompiledRoute,
+        mut params: HashMap<String, String>,
+    ) -> HashMap<String, String> {
+        for name in &route.allow_reserved {
+            if let Some(value) = params.get_mut(name) {
+                *value = decode_reserved(value);
+            }
+        }
+        params
+    }
+
+    /// Compare a request path with a route, segment by segment.
+    ///
+    /// Follows the rules of [`match_route`](Self::match_route): literals
+    /// match exactly, parameters match any non-empty segment, patterns split
+    /// a segment on their literal separators, a wildcard matches the
+    /// non-empty rest of the path, and one trailing slash is ignored.
+    fn explain_route(route: &CompiledRoute, path: &str) -> CandidateRoute {
+        let mut candidate = CandidateRoute {
+            operation_id: route.operation_id.clone(),
+            path_template: route.template.clone(),
+            segments: Vec::new(),
+            matched: false,
+        };
+
+        if route.segments.is_empty() {
+            let matched = path == "/";
+            candidate.segments.push(SegmentMatch {
+                template: Some("/".to_string()),
+                kind: SegmentKind::Literal,
+                actual: Some(path.to_string()),
+                matched,
+            });
+            candidate.matched = matched;
+            return candidate;
+        }
+
+        let Some(actual) = path_segments(path) else {
+            return candidate;
+        };
+        let mut params = HashMap::new();
+
+        for (index, segment) in route.segments.iter().enumerate() {
+            let kind = segment.kind();
+            if kind == SegmentKind::Wildcard {
+                let remaining = actual.get(index..).unwrap_or_default().join("/");
+                let matched = !remaining.is_empty();
+                candidate.segments.push(SegmentMatch {
+                    template: Some(segment.template.clone()),
+                    kind,
+                    actual: matched.then_some(remaining),
+                    matched,
+                });
+                candidate.matched = matched;
+                return candidate;
+            }
+
+            let actual_segment = actual.get(index).copied();
+            let matched = actual_segment.is_some_and(|actual| segment.capture(actual, &mut params));
+            candidate.segments.push(SegmentMatch {
+                template: Some(segment.template.clone()),
+                kind,
+                actual: actual_segment.map(str::to_string),
+                matched,
+            });
+            if !matched {
+                return candidate;
+            }
+        }
+
+        if let Some(extra) = actual.get(route.segments.len()) {
+            candidate.segments.push(SegmentMatch {
+                template: None,
+                kind: SegmentKind::Extra,
+                actual: Some((*extra).to_string()),
+                matched: false,
+            });
+            return candidate;
+        }
+        candidate.matched = true;
+        candidate
+    }
+
     /// Check if a route exists for the given method and path.
     pub fn has_route(&self, method: &str, path: &str) -> bool {
         self.resolve(method, path).is_ok()
@@ -143,74 +623,247 @@ impl OperationResolver {
     }
 
     fn compile_route(op: &LoadedOperation) -> CompiledRoute {
-        let (pattern, param_names) = Self::compile_path(&op.path);
-
         CompiledRoute {
             template: op.path.clone(),
-            pattern,
-            param_names,
+            segments: op
+                .path
+                .split('/')
+                .filter(|segment| !segment.is_empty())
+                .map(CompiledSegment::parse)
+                .collect(),
+            allow_reserved: op.allow_reserved.clone(),
             operation_id: op.id.clone(),
             deprecated: op.deprecated,
             tags: op.tags.clone(),
         }
     }
 
-    fn compile_path(template: &str) -> (Regex, Vec<String>) {
-        let mut pattern = String::from("^");
-        let mut param_names = Vec::new();
+    /// Compare routes for sorting, most specific first.
+    ///
+    /// Segments are compared left to right, literals before patterns before
+    /// parameters before wildcards; equally specific routes are ordered
+    /// longer template first.
+    fn route_specificity(a: &CompiledRoute, b: &CompiledRoute) -> Ordering {
+        let ranks = |route: &CompiledRoute| {
+            route
+                .segments
+                .iter()
+                .map(CompiledSegment::rank)
+                .collect::<Vec<_>>()
+        };
+        ranks(a)
+            .cmp(&ranks(b))
+            .then_with(|| b.template.len().cmp(&a.template.len()))
+    }
+}
 
-        for segment in template.split('/') {
-            if segment.is_empty() {
-                continue;
+/// A compiled route template segment.
+#[derive(Debug)]
+struct CompiledSegment {
+    /// The template segment, e.g. `{year}.{month}`.
+    template: String,
+    /// How the segment matches request segments.
+    matcher: SegmentMatcher,
+}
+
+/// How a template segment matches request segments.
+#[derive(Debug)]
+enum SegmentMatcher {
+    /// Matches the template exactly.
+    Literal,
+    /// Matches any non-empty segment.
+    Parameter(String),
+    /// Splits the segment on the literal separators between parameters.
+    Pattern(Vec<PatternPart>),
+    /// Matches the non-empty rest of the path.
+    Wildcard(String),
+}
+
+/// A part of a multi-parameter segment.
+#[derive(Debug)]
+enum PatternPart {
+    Literal(String),
+    Parameter(String),
+}
+
+impl CompiledSegment {
+    fn parse(template: &str) -> Self {
+        let matcher = if let Some(name) = template.strip_prefix('*') {
+            SegmentMatcher::Wildcard(name.to_string())
+        } else {
+            match parse_pattern(template) {
+                Some(parts) => match parts.as_slice() {
+                    [PatternPart::Parameter(name)] => SegmentMatcher::Parameter(name.clone()),
+                    _ => SegmentMatcher::Pattern(parts),
+                },
+                None => SegmentMatcher::Literal,
             }
+        };
+        Self {
+            template: template.to_string(),
+            matcher,
+        }
+    }
 
-            pattern.push('/');
-
-            if segment.starts_with('{') && segment.ends_with('}') {
-                // Path parameter
-                let name = &segment[1..segment.len() - 1];
-                param_names.push(name.to_string());
-                // Match any non-slash characters
-                pattern.push_str("([^/]+)");
-            } else if segment.starts_with('*') {
-                // Wildcard (catch-all)
-                let name = &segment[1..];
-                if !name.is_empty() {
-                    param_names.push(name.to_string());
+    fn kind(&self) -> SegmentKind {
+        match self.matcher {
+            SegmentMatcher::Literal => SegmentKind::Literal,
+            SegmentMatcher::Parameter(_) => SegmentKind::Parameter,
+            SegmentMatcher::Pattern(_) => SegmentKind::Pattern,
+            SegmentMatcher::Wildcard(_) => SegmentKind::Wildcard,
+        }
+    }
+
+    /// Precedence of the segment; lower ranks are tried first.
+    fn rank(&self) -> u8 {
+        match self.matcher {
+            SegmentMatcher::Literal => 0,
+            SegmentMatcher::Pattern(_) => 1,
+            SegmentMatcher::Parameter(_) => 2,
+            SegmentMatcher::Wildcard(_) => 3,
+        }
+    }
+
+    /// Match a single request segment, adding its parameters to `params`.
+    ///
+    /// Parameters are only added when the whole segment matches.
+    fn capture(&self, segment: &str, params: &mut HashMap<String, String>) -> bool {
+        match &self.matcher {
+            SegmentMatcher::Literal => segment == self.template,
+            SegmentMatcher::Parameter(name) => {
+                if segment.is_empty() {
+                    return false;
                 }
-                // Match remaining path
-                pattern.push_str("(.+)");
-            } else {
-                // Literal segment - escape regex metacharacters
-                pattern.push_str(&regex::escape(segment));
+                params.insert(name.clone(), segment.to_string());
+                true
             }
+            SegmentMatcher::Pattern(parts) => {
+                let Some(captured) = match_pattern(parts, segment) else {
+                    return false;
+                };
+                for (name, value) in captured {
+                    params.insert(name.to_string(), value.to_string());
+                }
+                true
+            }
+            SegmentMatcher::Wildcard(_) => false,
         }
+    }
+}
 
-        // Handle root path
-        if template == "/" {
-            pattern = String::from("^/$");
+/// Split a template segment into literals and `{param}`s.
+///
+/// Returns `None` for a segment without parameters, or one that cannot be
+/// matched without backtracking: adjacent parameters, or an unclosed or
+/// empty `{}`.
+fn parse_pattern(template: &str) -> Option<Vec<PatternPart>> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('{') {
+            let end = after.find('}')?;
+            let name = &after[..end];
+            if name.is_empty() || matches!(parts.last(), Some(PatternPart::Parameter(_))) {
+                return None;
+            }
+            parts.push(PatternPart::Parameter(name.to_string()));
+            rest = &after[end + 1..];
         } else {
-            pattern.push_str("/?$");
+            let end = rest.find('{').unwrap_or(rest.len());
+            parts.push(PatternPart::Literal(rest[..end].to_string()));
+            rest = &rest[end..];
         }
+    }
+    parts
+        .iter()
+        .any(|part| matches!(part, PatternPart::Parameter(_)))
+        .then_some(parts)
+}
 
-        let regex = Regex::new(&pattern).expect("valid regex");
-        (regex, param_names)
+/// Match a request segment against the parts of a multi-parameter segment.
+///
+/// A parameter ends at the first occurrence of the literal after it, or at
+/// the closing literal if that comes next, and must not be empty.
+fn match_pattern<'a>(
+    parts: &'a [PatternPart],
+    segment: &'a str,
+) -> Option<Vec<(&'a str, &'a str)>> {
+    let mut captured = Vec::new();
+    let mut rest = segment;
+    for (index, part) in parts.iter().enumerate() {
+        match part {
+            PatternPart::Literal(literal) => rest = rest.strip_prefix(literal.as_str())?,
+            PatternPart::Parameter(name) => {
+                let end = match parts.get(index + 1) {
+                    Some(PatternPart::Literal(suffix)) if index + 2 == parts.len() => {
+                        rest.strip_suffix(suffix.as_str())?.len()
+                    }
+                    Some(PatternPart::Literal(separator)) => rest.find(separator.as_str())?,
+                    _ => rest.len(),
+                };
+                if end == 0 {
+                    return None;
+                }
+                captured.push((name.as_str(), &rest[..end]));
+                rest = &rest[end..];
+            }
+        }
     }
+    rest.is_empty().then_some(captured)
+}
+
+/// Split a request path into its segments, ignoring one trailing slash.
+fn path_segments(path: &str) -> Option<Vec<&str>> {
+    let rest = path.strip_prefix('/')?;
+    let rest = rest.strip_suffix('/').unwrap_or(rest);
+    Some(if rest.is_empty() {
+        Vec::new()
+    } else {
+        rest.split('/').collect()
+    })
+}
 
-    /// Compare route specificity for sorting.
-    /// More specific routes (fewer parameters, longer literals) come first.
-    fn route_specificity(a: &str, b: &str) -> std::cmp::Ordering {
-        let a_params = a.matches('{').count();
-        let b_params = b.matches('{').count();
+/// Decode percent-encoded reserved characters (RFC 3986), such as `%2F`,
+/// leaving other escapes as they are.
+fn decode_reserved(value: &str) -> String {
+    const RESERVED: &[u8] = b":/?#[]@!$&'()*+,;=";
 
-        // Fewer parameters = more specific
-        if a_params != b_params {
-            return a_params.cmp(&b_params);
+    let mut decoded = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(percent) = rest.find('%') {
+        decoded.push_str(&rest[..percent]);
+        let escaped = rest
+            .get(percent + 1..percent + 3)
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            .filter(|byte| RESERVED.contains(byte));
+        if let Some(byte) = escaped {
+            decoded.push(char::from(byte));
+            rest = &rest[percent + 3..];
+        } else {
+            decoded.push('%');
+            rest = &rest[percent + 1..];
         }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Count the single-character insertions, deletions and substitutions
+/// that turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
 
-        // Longer path = more specific (among same param count)
-        b.len().cmp(&a.len())
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
     }
+    previous[b.len()]
 }
 
 impl From<&LoadedArtifact> for OperationResolver {
@@ -241,6 +894,11 @@ mod tests {
                     request_schema: None,
                     response_schemas: HashMap::new(),
                     tags: vec!["users".to_string()],
+                    header_params: Vec::new(),
+                    event_schemas: HashMap::new(),
+                    timeout: None,
+                    cache_policy: None,
+                    allow_reserved: Vec::new(),
                 },
                 LoadedOperation {
                     id: "getUser".to_string(),
@@ -252,6 +910,11 @@ mod tests {
                     request_schema: None,
                     response_schemas: HashMap::new(),
                     tags: vec!["users".to_string()],
+                    header_params: Vec::new(),
+                    event_schemas: HashMap::new(),
+                    timeout: None,
+                    cache_policy: None,
+                    allow_reserved: Vec::new(),
                 },
                 LoadedOperation {
                     id: "createUser".to_string(),
@@ -263,6 +926,11 @@ mod tests {
                     request_schema: None,
                     response_schemas: HashMap::new(),
                     tags: vec!["users".to_string()],
+                    header_params: Vec::new(),
+                    event_schemas: HashMap::new(),
+                    timeout: None,
+                    cache_policy: None,
+                    allow_reserved: Vec::new(),
                 },
                 LoadedOperation {
                     id: "getUserOrders".to_string(),
@@ -274,6 +942,11 @@ mod tests {
                     request_schema: None,
                     response_schemas: HashMap::new(),
                     tags: vec!["users".to_string(), "orders".to_string()],
+                    header_params: Vec::new(),
+                    event_schemas: HashMap::new(),
+                    timeout: None,
+                    cache_policy: None,
+                    allow_reserved: Vec::new(),
                 },
                 LoadedOperation {
                     id: "getOrder".to_string(),
@@ -285,9 +958,15 @@ mod tests {
                     request_schema: None,
                     response_schemas: HashMap::new(),
                     tags: vec!["orders".to_string()],
+                    header_params: Vec::new(),
+                    event_schemas: HashMap::new(),
+                    timeout: None,
+                    cache_policy: None,
+                    allow_reserved: Vec::new(),
                 },
             ],
             schemas: IndexMap::new(),
+            stats: Default::default(),
         }
     }
 
@@ -299,6 +978,7 @@ mod tests {
         let resolution = resolver.resolve("GET", "/users").unwrap();
         assert_eq!(resolution.operation_id, "listUsers");
         assert!(resolution.path_params.is_empty());
+        assert_eq!(resolution.version, "1.0.0");
     }
 
     #[test]
@@ -393,6 +1073,45 @@ mod tests {
         assert!(resolver.resolve("GET", "/users").is_ok());
     }
 
+    #[test]
+    fn test_route_pattern_for_param_route() {
+        let artifact = create_test_artifact();
+        let resolver = OperationResolver::from_artifact(&artifact);
+
+        let resolution = resolver.resolve("GET", "/users/123").unwrap();
+        assert_eq!(resolution.route_pattern, "/users/{userId}");
+    }
+
+    #[test]
+    fn test_route_pattern_for_wildcard_route() {
+        let mut artifact = create_test_artifact();
+        artifact.operations.push(LoadedOperation {
+            id: "getFile".to_string(),
+            method: "GET".to_string(),
+            path: "/files/*path".to_string(),
+            summary: None,
+            deprecated: false,
+            security: vec![],
+            request_schema: None,
+            response_schemas: HashMap::new(),
+            tags: vec![],
+            header_params: Vec::new(),
+            event_schemas: HashMap::new(),
+            timeout: None,
+            cache_policy: None,
+            allow_reserved: Vec::new(),
+        });
+        let resolver = OperationResolver::from_artifact(&artifact);
+
+        let resolution = resolver.resolve("GET", "/files/images/logo.png").unwrap();
+        assert_eq!(resolution.operation_id, "getFile");
+        assert_eq!(resolution.route_pattern, "/files/*path");
+        assert_eq!(
+            resolution.path_params.get("path"),
+            Some(&"images/logo.png".to_string())
+        );
+    }
+
     #[test]
     fn test_trailing_slash() {
         let artifact = create_test_artifact();
@@ -402,4 +1121,299 @@ mod tests {
         assert!(resolver.resolve("GET", "/users").is_ok());
         assert!(resolver.resolve("GET", "/users/").is_ok());
     }
+
+    #[test]
+    fn test_explain_match() {
+        let artifact = create_test_artifact();
+        let resolver = OperationResolver::from_artifact(&artifact);
+
+        let explanation = resolver.explain("get", "/users/42/orders");
+        assert_eq!(explanation.method, "GET");
+        assert_eq!(explanation.operation_id(), Some("getUserOrders"));
+        let ResolutionDecision::Matched(resolution) = &explanation.decision else {
+            panic!("expected a match: {explanation}");
+        };
+        assert_eq!(
+            resolution.path_params.get("userId"),
+            Some(&"42".to_string())
+        );
+
+        let candidate = explanation.candidate("getUserOrders").unwrap();
+        assert!(candidate.matched);
+        assert_eq!(candidate.segments.len(), 3);
+        assert!(explanation
+            .candidate("getUser")
+            .unwrap()
+            .failing_segment()
+            .is_some_and(|(index, segment)| index == 2 && segment.template.is_none()));
+    }
+
+    #[test]
+    fn test_explain_no_match_names_failing_segment() {
+        let artifact = create_test_artifact();
+        let resolver = OperationResolver::from_artifact(&artifact);
+
+        let explanation = resolver.explain("GET", "/users/42/orderz");
+        assert!(matches!(explanation.decision, ResolutionDecision::NoMatch));
+        let (index, segment) = explanation
+            .candidate("getUserOrders")
+            .unwrap()
+            .failing_segment()
+            .unwrap();
+        assert_eq!(index, 2);
+        assert_eq!(segment.template.as_deref(), Some("orders"));
+        assert_eq!(segment.actual.as_deref(), Some("orderz"));
+        assert!(explanation.to_string().contains(
+            "getUserOrders /users/{userId}/orders: segment 2: expected 'orders', got 'orderz'"
+        ));
+
+        let explanation = resolver.explain("DELETE", "/accounts/42");
+        assert!(matches!(
+            explanation.decision,
+            ResolutionDecision::NoRoutesForMethod
+        ));
+        assert!(explanation.candidates.is_empty());
+    }
+
+    #[test]
+    fn test_explain_method_mismatch() {
+        let artifact = create_test_artifact();
+        let resolver = OperationResolver::from_artifact(&artifact);
+
+        let explanation = resolver.explain("DELETE", "/users/42");
+        let ResolutionDecision::MethodNotAllowed { allowed } = &explanation.decision else {
+            panic!("expected a method mismatch: {explanation}");
+        };
+        assert_eq!(allowed, &["GET"]);
+        assert!(explanation.candidates.is_empty());
+        // The matching route is not repeated as a suggestion
+        assert!(explanation.suggestions.is_empty());
+
+        let explanation = resolver.explain("POST", "/users/42");
+        assert!(matches!(
+            &explanation.decision,
+            ResolutionDecision::MethodNotAllowed { allowed } if allowed == &["GET"]
+        ));
+        assert_eq!(explanation.candidates.len(), 1);
+        assert!(explanation
+            .to_string()
+            .contains("method not allowed (allowed: GET)"));
+    }
+
+    #[test]
+    fn test_explain_suggests_near_misses() {
+        let artifact = create_test_artifact();
+        let resolver = OperationResolver::from_artifact(&artifact);
+
+        let explanation = resolver.explain("GET", "/users/42/orderz");
+        assert_eq!(
+            explanation.suggestions,
+            vec![RouteSuggestion {
+                method: "GET".to_string(),
+                operation_id: "getUserOrders".to_string(),
+                path_template: "/users/{userId}/orders".to_string(),
+                distance: 1,
+            }]
+        );
+        assert!(explanation
+            .to_string()
+            .contains("did you mean GET /users/{userId}/orders (getUserOrders)?"));
+
+        // Nearest first, across methods
+        let explanation = resolver.explain("GET", "/user");
+        let suggested: Vec<_> = explanation
+            .suggestions
+            .iter()
+            .map(|s| (s.method.as_str(), s.operation_id.as_str(), s.distance))
+            .collect();
+        assert_eq!(
+            suggested,
+            vec![("GET", "listUsers", 1), ("POST", "createUser", 1)]
+        );
+
+        // Nothing close enough
+        let explanation = resolver.explain("GET", "/nonexistent");
+        assert!(explanation.suggestions.is_empty());
+
+        // Resolved requests need no suggestions
+        assert!(resolver.explain("GET", "/users").suggestions.is_empty());
+    }
+
+    #[test]
+    fn test_explanation_serializes_stably() {
+        let artifact = create_test_artifact();
+        let resolver = OperationResolver::from_artifact(&artifact);
+
+        let explanation = resolver.explain("PUT", "/orders/o-1");
+        assert_eq!(
+            serde_json::to_value(&explanation).unwrap(),
+            serde_json::json!({
+                "method": "PUT",
+                "path": "/orders/o-1",
+                "version": "1.0.0",
+                "candidates": [],
+                "decision": {"outcome": "method_not_allowed", "allowed": ["GET"]},
+                "suggestions": []
+            })
+        );
+
+        let explanation = resolver.explain("GET", "/users/42/orderz");
+        let value = serde_json::to_value(&explanation).unwrap();
+        assert_eq!(
+            value["decision"],
+            serde_json::json!({"outcome": "no_match"})
+        );
+        let candidate =
+            serde_json::to_value(explanation.candidate("getUserOrders").unwrap()).unwrap();
+        assert_eq!(candidate["path_template"], "/users/{userId}/orders");
+        assert_eq!(
+            candidate["segments"][2],
+            serde_json::json!({
+                "template": "orders",
+                "kind": "literal",
+                "actual": "orderz",
+                "matched": false
+            })
+        );
+
+        let explanation = resolver.explain("GET", "/users/42/orders");
+        let value = serde_json::to_value(&explanation).unwrap();
+        assert_eq!(value["decision"]["outcome"], "matched");
+        assert_eq!(value["decision"]["operation_id"], "getUserOrders");
+        assert_eq!(value["decision"]["path_params"]["userId"], "42");
+        let candidate =
+            serde_json::to_value(explanation.candidate("getUserOrders").unwrap()).unwrap();
+        assert_eq!(candidate["segments"][1]["kind"], "parameter");
+    }
+
+    #[test]
+    fn test_explain_wildcard_route() {
+        let mut artifact = create_test_artifact();
+        artifact.operations.push(LoadedOperation {
+            id: "getFile".to_string(),
+            method: "GET".to_string(),
+            path: "/files/*path".to_string(),
+            summary: None,
+            deprecated: false,
+            security: vec![],
+            request_schema: None,
+            response_schemas: HashMap::new(),
+            tags: vec![],
+            header_params: Vec::new(),
+            event_schemas: HashMap::new(),
+            timeout: None,
+            cache_policy: None,
+            allow_reserved: Vec::new(),
+        });
+        let resolver = OperationResolver::from_artifact(&artifact);
+
+        let explanation = resolver.explain("GET", "/files/images/logo.png");
+        assert_eq!(explanation.operation_id(), Some("getFile"));
+        let candidate = explanation.candidate("getFile").unwrap();
+        assert_eq!(
+            candidate.segments[1].actual.as_deref(),
+            Some("images/logo.png")
+        );
+
+        let explanation = resolver.explain("GET", "/files");
+        assert!(matches!(explanation.decision, ResolutionDecision::NoMatch));
+        let (index, segment) = explanation
+            .candidate("getFile")
+            .unwrap()
+            .failing_segment()
+            .unwrap();
+        assert_eq!(index, 1);
+        assert_eq!(segment.actual, None);
+    }
+
+    fn operation(id: &str, path: &str) -> LoadedOperation {
+        LoadedOperation {
+            id: id.to_string(),
+            method: "GET".to_string(),
+            path: path.to_string(),
+            summary: None,
+            deprecated: false,
+            security: vec![],
+            request_schema: None,
+            response_schemas: HashMap::new(),
+            tags: vec![],
+            header_params: Vec::new(),
+            event_schemas: HashMap::new(),
+            timeout: None,
+            cache_policy: None,
+            allow_reserved: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_multiple_params_in_one_segment() {
+        let mut artifact = create_test_artifact();
+        artifact
+            .operations
+            .push(operation("getMonthlyReport", "/reports/{year}.{month}"));
+        artifact
+            .operations
+            .push(operation("getDocument", "/documents/{name}.json"));
+        let resolver = OperationResolver::from_artifact(&artifact);
+
+        let resolution = resolver.resolve("GET", "/reports/2024.03").unwrap();
+        assert_eq!(resolution.operation_id, "getMonthlyReport");
+        assert_eq!(resolution.path_params["year"], "2024");
+        assert_eq!(resolution.path_params["month"], "03");
+
+        let resolution = resolver.resolve("GET", "/documents/v1.2.json").unwrap();
+        assert_eq!(resolution.path_params["name"], "v1.2");
+
+        assert!(resolver.resolve("GET", "/reports/2024").is_err());
+        assert!(resolver.resolve("GET", "/reports/.03").is_err());
+        assert!(resolver.resolve("GET", "/documents/v1.yaml").is_err());
+
+        let explanation = resolver.explain("GET", "/reports/2024.03");
+        let segment = &explanation.candidate("getMonthlyReport").unwrap().segments[1];
+        assert_eq!(segment.kind, SegmentKind::Pattern);
+        assert_eq!(segment.to_string(), "'2024.03' matched '{year}.{month}'");
+
+        let explanation = resolver.explain("GET", "/reports/2024");
+        let (index, segment) = explanation
+            .candidate("getMonthlyReport")
+            .unwrap()
+            .failing_segment()
+            .unwrap();
+        assert_eq!(index, 1);
+        assert_eq!(segment.kind, SegmentKind::Pattern);
+    }
+
+    #[test]
+    fn test_route_precedence() {
+        let mut artifact = create_test_artifact();
+        artifact
+            .operations
+            .push(operation("getFile", "/files/*path"));
+        artifact
+            .operations
+            .push(operation("getFileById", "/files/{id}"));
+        artifact
+            .operations
+            .push(operation("getReadme", "/files/readme"));
+        artifact
+            .operations
+            .push(operation("getArchive", "/files/{name}.zip"));
+        let resolver = OperationResolver::from_artifact(&artifact);
+
+        let resolve = |path| resolver.resolve("GET", path).unwrap().operation_id;
+        assert_eq!(resolve("/files/readme"), "getReadme");
+        assert_eq!(resolve("/files/backup.zip"), "getArchive");
+        assert_eq!(resolve("/files/42"), "getFileById");
+        assert_eq!(resolve("/files/readme/v2"), "getFile");
+        assert_eq!(resolve("/files/a/b.zip"), "getFile");
+
+        let explanation = resolver.explain("GET", "/files/readme");
+        let order: Vec<&str> = explanation
+            .candidates
+            .iter()
+            .map(|candidate| candidate.operation_id.as_str())
+            .filter(|id| id.starts_with("getFile") || *id == "getReadme" || *id == "getArchive")
+            .collect();
+        assert_eq!(order, ["getReadme", "getArchive", "getFileById", "getFile"]);
+    }
 }
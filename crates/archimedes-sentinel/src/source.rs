@@ -0,0 +1,377 @@
+//! Cached artifact sources with background refresh.
+//!
+//! Fetching the contract from the registry on every startup makes startup
+//! slow and dependent on the registry being up. [`CachingArtifactSource`]
+//! keeps the last good artifact, optionally persisted to a cache file, and
+//! refreshes it from the registry in the background:
+//!
+//! - [`load`](CachingArtifactSource::load) serves the cache file if there
+//!   is one, and only waits for the registry when there is not.
+//! - [`refresh`](CachingArtifactSource::refresh) fetches the artifact and
+//!   swaps it in atomically. A failed refresh is logged and the previous
+//!   artifact is kept.
+//! - [`spawn_refresh`](CachingArtifactSource::spawn_refresh) refreshes on
+//!   an interval.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use std::time::Duration;
+//! use archimedes_sentinel::{CachingArtifactSource, Sentinel};
+//!
+//! let source = CachingArtifactSource::registry("https://registry.example.com", "users", "1.2.0")
+//!     .with_cache_file("/var/cache/archimedes/users.artifact.json");
+//! let artifact = source.load().await?;
+//! let _refresh = source.spawn_refresh(Duration::from_secs(300));
+//!
+//! let sentinel = Sentinel::with_defaults((*artifact).clone());
+//! ```
+
+use std::fmt;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+
+use tokio::fs;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::artifact::{ArtifactLoader, LoadedArtifact};
+use crate::error::{SentinelError, SentinelResult};
+
+/// A pending fetch of an artifact document.
+type FetchFuture = Pin<Box<dyn Future<Output = SentinelResult<String>> + Send>>;
+
+/// Fetches the text of an artifact document.
+type Fetch = dyn Fn() -> FetchFuture + Send + Sync;
+
+/// The artifact currently served, with when it was fetched.
+#[derive(Debug, Clone)]
+struct Snapshot {
+    artifact: Arc<LoadedArtifact>,
+    fetched_at: SystemTime,
+}
+
+/// An artifact source that serves the last good artifact while refreshing
+/// it in the background.
+///
+/// Cloning is cheap; clones share the cached artifact.
+#[derive(Clone)]
+pub struct CachingArtifactSource {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    fetch: Box<Fetch>,
+    cache_file: Option<PathBuf>,
+    current: RwLock<Option<Snapshot>>,
+}
+
+impl fmt::Debug for CachingArtifactSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CachingArtifactSource")
+            .field("cache_file", &self.inner.cache_file)
+            .field("version", &self.version())
+            .field("age", &self.age())
+            .finish_non_exhaustive()
+    }
+}
+
+impl CachingArtifactSource {
+    /// Create a source that fetches artifact documents with `fetch`.
+    ///
+    /// The documents may be Themis artifacts or OpenAPI specs, in JSON or
+    /// YAML.
+    pub fn new<F, Fut>(fetch: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = SentinelResult<String>> + Send + 'static,
+    {
+        Self {
+            inner: Arc::new(Inner {
+                fetch: Box::new(move || -> FetchFuture { Box::pin(fetch()) }),
+                cache_file: None,
+                current: RwLock::new(None),
+            }),
+        }
+    }
+
+    /// Create a source that fetches an artifact from a registry.
+    pub fn registry(
+        registry_url: impl Into<String>,
+        service: impl Into<String>,
+        version: impl Into<String>,
+    ) -> Self {
+        let registry_url = Arc::new(registry_url.into());
+        let service = Arc::new(service.into());
+        let version = Arc::new(version.into());
+        Self::new(move || {
+            let registry_url = Arc::clone(&registry_url);
+            let service = Arc::clone(&service);
+            let version = Arc::clone(&version);
+            async move {
+                ArtifactLoader::fetch_registry_document(&registry_url, &service, &version).await
+            }
+        })
+    }
+
+    /// Persist the last good artifact to `path`, and serve it from there on
+    /// the next [`load`](Self::load).
+    ///
+    /// Must be called before the source is cloned or loaded.
+    pub fn with_cache_file(mut self, path: impl Into<PathBuf>) -> Self {
+        match Arc::get_mut(&mut self.inner) {
+            Some(inner) => inner.cache_file = Some(path.into()),
+            None => warn!("cache file ignored: artifact source is already shared"),
+        }
+        self
+    }
+
+    /// Get the artifact, loading it if none has been loaded yet.
+    ///
+    /// Serves the cache file when there is one, without waiting for the
+    /// registry; otherwise fetches the artifact.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no usable cache file and the fetch
+    /// fails.
+    pub async fn load(&self) -> SentinelResult<Arc<LoadedArtifact>> {
+        if let Some(artifact) = self.current() {
+            return Ok(artifact);
+        }
+
+        if let Some(path) = &self.inner.cache_file {
+            match Self::read_cache_file(path).await {
+                Ok(snapshot) => {
+                    info!(
+                        path = %path.display(),
+                        version = %snapshot.artifact.version,
+                        "serving cached artifact"
+                    );
+                    let artifact = Arc::clone(&snapshot.artifact);
+                    self.swap(snapshot);
+                    return Ok(artifact);
+                }
+                Err(e) => debug!(path = %path.display(), error = %e, "no usable cached artifact"),
+            }
+        }
+
+        self.refresh().await?;
+        self.current()
+            .ok_or_else(|| SentinelError::ArtifactLoad("artifact was not loaded".to_string()))
+    }
+
+    /// Fetch the artifact and swap it in.
+    ///
+    /// On failure the error is logged and the previous artifact, if any,
+    /// keeps being served.
+    ///
+    /// # Errors
+    ///
+    /// Returns the fetch or parse error.
+    pub async fn refresh(&self) -> SentinelResult<()> {
+        let result = self.fetch().await;
+        match result {
+            Ok((document, artifact)) => {
+                info!(
+                    service = %artifact.service,
+                    version = %artifact.version,
+                    "artifact refreshed"
+                );
+                self.swap(Snapshot {
+                    artifact: Arc::new(artifact),
+                    fetched_at: SystemTime::now(),
+                });
+                if let Some(path) = &self.inner.cache_file {
+                    if let Err(e) = Self::write_cache_file(path, &document).await {
+                        warn!(path = %path.display(), error = %e, "failed to write artifact cache");
+                    }
+                }
+                Ok(())
+            }
+            Err(e) => {
+                warn!(
+                    error = %e,
+                    version = self.version().as_deref().unwrap_or("none"),
+                    "artifact refresh failed, keeping the current artifact"
+                );
+                Err(e)
+            }
+        }
+    }
+
+    /// Refresh the artifact every `interval` in a background task.
+    ///
+    /// The first refresh happens after one interval, so a freshly loaded
+    /// artifact is not fetched twice. The task stops when every clone of
+    /// the source has been dropped.
+    pub fn spawn_refresh(&self, interval: Duration) -> JoinHandle<()> {
+        let weak = Arc::downgrade(&self.inner);
+        tokio::spawn(async move {
+            let mut ticker =
+                tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+            loop {
+                ticker.tick().await;
+                let Some(inner) = weak.upgrade() else {
+                    break;
+                };
+                // Failures are logged by refresh
+                let _ = Self { inner }.refresh().await;
+            }
+        })
+    }
+
+    /// Get the artifact currently served, if one has been loaded.
+    pub fn current(&self) -> Option<Arc<LoadedArtifact>> {
+        self.snapshot().map(|snapshot| snapshot.artifact)
+    }
+
+    /// Get the version of the artifact currently served.
+    pub fn version(&self) -> Option<String> {
+        self.snapshot()
+            .map(|snapshot| snapshot.artifact.version.clone())
+    }
+
+    /// Get how long ago the artifact currently served was fetched.
+    ///
+    /// For an artifact served from the cache file, this is the age of the
+    /// file.
+    pub fn age(&self) -> Option<Duration> {
+        self.snapshot().map(|snapshot| {
+            SystemTime::now()
+                .duration_since(snapshot.fetched_at)
+                .unwrap_or_default()
+        })
+    }
+
+    async fn fetch(&self) -> SentinelResult<(String, LoadedArtifact)> {
+        let document = (self.inner.fetch)().await?;
+        let artifact = ArtifactLoader::from_document(&document)?;
+        Ok((document, artifact))
+    }
+
+    fn snapshot(&self) -> Option<Snapshot> {
+        self.inner
+            .current
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+
+    fn swap(&self, snapshot: Snapshot) {
+        *self
+            .inner
+            .current
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = Some(snapshot);
+    }
+
+    async fn read_cache_file(path: &Path) -> SentinelResult<Snapshot> {
+        let document = fs::read_to_string(path).await.map_err(|e| {
+            SentinelError::ArtifactLoad(format!(
+                "failed to read artifact cache {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        let fetched_at = fs::metadata(path)
+            .await
+            .and_then(|metadata| metadata.modified())
+            .unwrap_or_else(|_| SystemTime::now());
+        Ok(Snapshot {
+            artifact: Arc::new(ArtifactLoader::from_document(&document)?),
+            fetched_at,
+        })
+    }
+
+    /// Write the cache file through a temporary file, so a crash never
+    /// leaves a truncated cache behind.
+    async fn write_cache_file(path: &Path, document: &str) -> std::io::Result<()> {
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        fs::write(&tmp, document).await?;
+        fs::rename(&tmp, path).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+
+    fn document(version: &str) -> String {
+        format!(
+            r#"{{"openapi": "3.1.0", "info": {{"title": "users", "version": "{version}"}}, "paths": {{}}}}"#
+        )
+    }
+
+    /// A source whose fetches return `responses` in order.
+    fn scripted_source(responses: Vec<SentinelResult<String>>) -> CachingArtifactSource {
+        let responses = Arc::new(Mutex::new(VecDeque::from(responses)));
+        CachingArtifactSource::new(move || {
+            let next = responses.lock().unwrap().pop_front();
+            async move {
+                next.unwrap_or_else(|| Err(SentinelError::ArtifactLoad("no response".to_string())))
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_failed_refresh_keeps_artifact() {
+        let source = scripted_source(vec![
+            Ok(document("1.0.0")),
+            Err(SentinelError::ArtifactLoad("registry down".to_string())),
+            Ok("{ not json".to_string()),
+        ]);
+        assert_eq!(source.version(), None);
+
+        source.load().await.unwrap();
+        assert_eq!(source.version().as_deref(), Some("1.0.0"));
+
+        assert!(source.refresh().await.is_err());
+        assert!(source.refresh().await.is_err());
+        assert_eq!(source.version().as_deref(), Some("1.0.0"));
+        assert!(source.age().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_successful_refresh_swaps_artifact() {
+        let source = scripted_source(vec![Ok(document("1.0.0")), Ok(document("1.1.0"))]);
+
+        let first = source.load().await.unwrap();
+        source.refresh().await.unwrap();
+
+        assert_eq!(source.version().as_deref(), Some("1.1.0"));
+        // Artifacts already handed out are unaffected by the swap
+        assert_eq!(first.version, "1.0.0");
+    }
+
+    #[tokio::test]
+    async fn test_load_serves_cache_file() {
+        let path = std::env::temp_dir().join(format!(
+            "archimedes-artifact-cache-{}.json",
+            std::process::id()
+        ));
+
+        let source = scripted_source(vec![Ok(document("2.0.0"))]).with_cache_file(&path);
+        source.load().await.unwrap();
+
+        // With the registry unreachable, the cached artifact is served
+        let offline = scripted_source(vec![]).with_cache_file(&path);
+        let artifact = offline.load().await.unwrap();
+        assert_eq!(artifact.version, "2.0.0");
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_load_fails_without_cache_or_registry() {
+        let source = scripted_source(vec![]);
+        assert!(source.load().await.is_err());
+        assert!(source.current().is_none());
+    }
+}
@@ -2,16 +2,28 @@
 //!
 //! This module provides validators that check HTTP requests and responses
 //! against the JSON schemas defined in Themis contracts.
+//!
+//! Per-operation state that is costly to build, such as compiled header
+//! patterns, is built the first time an operation is validated. Call
+//! [`SchemaValidator::precompile_all`] to pay that cost at startup instead.
+//!
+//! Fields a body carries but its schema does not declare are reported in
+//! [`ValidationResult::unknown_fields`] and handled as the
+//! [`config`](crate::config#unknown-fields) says.
 
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::Instant;
 
-use indexmap::IndexMap;
+use http::HeaderMap;
+use regex::Regex;
 use serde_json::Value;
-use themis_core::Schema;
 use tracing::{debug, warn};
 
-use crate::artifact::{LoadedArtifact, SchemaRef};
-use crate::config::ValidationConfig;
+use crate::artifact::{HeaderParam, LoadedArtifact, LoadedOperation, SchemaRef, JSON_MEDIA_TYPE};
+use crate::coercion::{coerce_str, coerce_value};
+use crate::config::{RequestUnknownFields, ResponseUnknownFields, ValidationConfig};
 use crate::error::{SentinelResult, ValidationError};
 
 /// Result of a validation operation.
@@ -23,6 +35,12 @@ pub struct ValidationResult {
     pub errors: Vec<ValidationError>,
     /// Schema that was validated against.
     pub schema_ref: Option<SchemaRef>,
+    /// Paths of fields coerced from strings before validation.
+    pub coerced: Vec<String>,
+    /// Paths of fields the schema does not declare, e.g. `items[0].note`.
+    ///
+    /// Unknown fields that are rejected are reported in `errors` too.
+    pub unknown_fields: Vec<String>,
 }
 
 impl ValidationResult {
@@ -32,6 +50,8 @@ impl ValidationResult {
             valid: true,
             errors: vec![],
             schema_ref,
+            coerced: vec![],
+            unknown_fields: vec![],
         }
     }
 
@@ -41,13 +61,150 @@ impl ValidationResult {
             valid: false,
             errors,
             schema_ref,
+            coerced: vec![],
+            unknown_fields: vec![],
         }
     }
 
+    /// Record the fields that were coerced before validation.
+    pub fn with_coerced(mut self, coerced: Vec<String>) -> Self {
+        self.coerced = coerced;
+        self
+    }
+
     /// Check if any errors exist.
     pub fn has_errors(&self) -> bool {
         !self.errors.is_empty()
     }
+
+    /// Check if any fields were coerced from strings.
+    pub fn was_coerced(&self) -> bool {
+        !self.coerced.is_empty()
+    }
+
+    /// Check if the body carried fields its schema does not declare.
+    pub fn has_unknown_fields(&self) -> bool {
+        !self.unknown_fields.is_empty()
+    }
+}
+
+/// A field a body carries but its schema does not declare.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct UnknownField {
+    /// Path of the field, e.g. `items[0].note`.
+    path: String,
+    /// Reference of the schema of the object holding the field.
+    schema: String,
+    /// Whether the schema rejects it with `additionalProperties: false`,
+    /// whatever the configured policy.
+    rejected_by_schema: bool,
+}
+
+/// How an object schema treats fields it does not declare.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Undeclared {
+    /// The schema allows them, explicitly or by declaring no properties.
+    Allowed,
+    /// The schema sets `additionalProperties: false`.
+    Rejected,
+    /// The configured policy decides.
+    ByPolicy,
+}
+
+impl Undeclared {
+    fn of(schema_ref: &SchemaRef) -> Self {
+        match schema_ref.additional_properties {
+            Some(true) => Self::Allowed,
+            Some(false) => Self::Rejected,
+            None if schema_ref.properties.is_empty() => Self::Allowed,
+            None => Self::ByPolicy,
+        }
+    }
+}
+
+/// Join a field name to the path of the object holding it.
+fn field_path(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
+
+/// Collect the fields of a value that its schema, or the schemas of its
+/// nested objects and array items, do not declare.
+fn collect_unknown_fields(
+    value: &Value,
+    schema_ref: &SchemaRef,
+    path: &str,
+    unknown: &mut Vec<UnknownField>,
+) {
+    match value {
+        Value::Object(obj) if schema_ref.schema_type == "object" => {
+            let undeclared = Undeclared::of(schema_ref);
+            for (name, field) in obj {
+                if let Some(nested) = schema_ref.property_schemas.get(name) {
+                    collect_unknown_fields(field, nested, &field_path(path, name), unknown);
+                } else if undeclared != Undeclared::Allowed
+                    && !schema_ref.properties.contains_key(name)
+                {
+                    unknown.push(UnknownField {
+                        path: field_path(path, name),
+                        schema: schema_ref.reference.clone(),
+                        rejected_by_schema: undeclared == Undeclared::Rejected,
+                    });
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_schema) = &schema_ref.items {
+                for (index, item) in items.iter().enumerate() {
+                    let item_path = format!("{}[{}]", path, index);
+                    collect_unknown_fields(item, item_schema, &item_path, unknown);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Remove the fields of a value that its schemas do not declare and that
+/// the configured policy decides on, returning their paths.
+///
+/// Fields the schema rejects itself are left for validation to report.
+fn strip_unknown_fields(
+    value: &mut Value,
+    schema_ref: &SchemaRef,
+    path: &str,
+    stripped: &mut Vec<String>,
+) {
+    match value {
+        Value::Object(obj) if schema_ref.schema_type == "object" => {
+            if Undeclared::of(schema_ref) == Undeclared::ByPolicy {
+                obj.retain(|name, _| {
+                    let declared = schema_ref.properties.contains_key(name);
+                    if !declared {
+                        stripped.push(field_path(path, name));
+                    }
+                    declared
+                });
+            }
+            for (name, nested) in &schema_ref.property_schemas {
+                if let Some(field) = obj.get_mut(name) {
+                    strip_unknown_fields(field, nested, &field_path(path, name), stripped);
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_schema) = &schema_ref.items {
+                for (index, item) in items.iter_mut().enumerate() {
+                    let item_path = format!("{}[{}]", path, index);
+                    strip_unknown_fields(item, item_schema, &item_path, stripped);
+                }
+            }
+        }
+        _ => {}
+    }
 }
 
 /// Validates requests and responses against Themis schemas.
@@ -55,21 +212,143 @@ impl ValidationResult {
 pub struct SchemaValidator {
     /// Validation configuration.
     config: ValidationConfig,
-    /// Named schemas from the artifact.
-    _schemas: IndexMap<String, Schema>,
+    /// Position of each operation in the artifact, by operation ID.
+    operation_index: HashMap<String, usize>,
+    /// Per-operation validation state, built on first use.
+    compiled: Vec<OnceLock<CompiledOperation>>,
+}
+
+/// Validation state of an operation that is costly to build.
+#[derive(Debug, Clone)]
+struct CompiledOperation {
+    /// Compiled pattern of each header parameter, in declaration order.
+    header_patterns: Vec<Option<Regex>>,
+}
+
+impl CompiledOperation {
+    fn new(operation: &LoadedOperation) -> Self {
+        let header_patterns = operation
+            .header_params
+            .iter()
+            .map(|param| {
+                let pattern = param.pattern.as_deref()?;
+                Regex::new(pattern)
+                    .map_err(|e| {
+                        warn!(
+                            operation_id = %operation.id,
+                            header = %param.name,
+                            pattern,
+                            error = %e,
+                            "ignoring invalid header pattern"
+                        );
+                    })
+                    .ok()
+            })
+            .collect();
+        Self { header_patterns }
+    }
 }
 
 impl SchemaValidator {
     /// Create a validator from a loaded artifact.
+    ///
+    /// Only an index of the operations is built here; the rest of each
+    /// operation's validation state is built on first use.
     pub fn from_artifact(artifact: &LoadedArtifact, config: ValidationConfig) -> Self {
         debug!(
+            operation_count = artifact.operations.len(),
             schema_count = artifact.schemas.len(),
             "schema validator initialized"
         );
 
+        let operation_index = artifact
+            .operations
+            .iter()
+            .enumerate()
+            .map(|(index, op)| (op.id.clone(), index))
+            .collect();
+
         Self {
             config,
-            _schemas: artifact.schemas.clone(),
+            operation_index,
+            compiled: artifact
+                .operations
+                .iter()
+                .map(|_| OnceLock::new())
+                .collect(),
+        }
+    }
+
+    /// Build the validation state of every operation now rather than on
+    /// first use.
+    ///
+    /// For latency-sensitive deployments that prefer to pay the cost at
+    /// startup. `artifact` must be the one the validator was created from.
+    pub fn precompile_all(&self, artifact: &LoadedArtifact) {
+        let started = Instant::now();
+        for (slot, operation) in self.compiled.iter().zip(&artifact.operations) {
+            slot.get_or_init(|| CompiledOperation::new(operation));
+        }
+        debug!(
+            operations = self.compiled.len(),
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "validator precompiled"
+        );
+    }
+
+    /// Build the validation state of one operation now.
+    ///
+    /// Returns `false` if the artifact has no such operation.
+    pub fn precompile(&self, operation_id: &str, artifact: &LoadedArtifact) -> bool {
+        let Some((index, operation)) = self.find_operation(operation_id, artifact) else {
+            return false;
+        };
+        self.compiled(index, operation);
+        true
+    }
+
+    /// Get the number of operations whose validation state has been built.
+    pub fn compiled_operations(&self) -> usize {
+        self.compiled
+            .iter()
+            .filter(|slot| slot.get().is_some())
+            .count()
+    }
+
+    /// Find an operation of the artifact by ID, with its position.
+    fn find_operation<'a>(
+        &self,
+        operation_id: &str,
+        artifact: &'a LoadedArtifact,
+    ) -> Option<(usize, &'a LoadedOperation)> {
+        let indexed = self
+            .operation_index
+            .get(operation_id)
+            .and_then(|&index| Some((index, artifact.operations.get(index)?)))
+            .filter(|(_, op)| op.id == operation_id);
+        // Artifacts other than the one the validator was built from are
+        // searched instead
+        indexed.or_else(|| {
+            artifact
+                .operations
+                .iter()
+                .enumerate()
+                .find(|(_, op)| op.id == operation_id)
+        })
+    }
+
+    /// Get the validation state of the operation at `index`, building it if
+    /// this is its first use.
+    fn compiled<'a>(
+        &'a self,
+        index: usize,
+        operation: &LoadedOperation,
+    ) -> Cow<'a, CompiledOperation> {
+        match self.compiled.get(index) {
+            Some(slot) if self.operation_index.get(&operation.id) == Some(&index) => {
+                Cow::Borrowed(slot.get_or_init(|| CompiledOperation::new(operation)))
+            }
+            _ => Cow::Owned(CompiledOperation::new(operation)),
         }
     }
 
@@ -81,10 +360,10 @@ impl SchemaValidator {
         body: &Value,
     ) -> SentinelResult<ValidationResult> {
         // Find the operation
-        let operation = artifact.operations.iter().find(|op| op.id == operation_id);
+        let operation = self.find_operation(operation_id, artifact);
 
         let operation = match operation {
-            Some(op) => op,
+            Some((_, op)) => op,
             None => {
                 warn!(operation_id, "operation not found for validation");
                 return Ok(ValidationResult::success(None));
@@ -103,49 +382,275 @@ impl SchemaValidator {
             }
         };
 
-        // Validate against the schema
-        self.validate_against_schema_ref(schema_ref, body)
+        let reject_unknown = self.config.request_unknown_fields == RequestUnknownFields::Reject;
+        if !self.config.coerce_primitives {
+            let result = self.validate_against_schema_ref(schema_ref, body)?;
+            return Ok(Self::check_unknown_fields(
+                result,
+                schema_ref,
+                body,
+                reject_unknown,
+            ));
+        }
+
+        // Validate the coerced body, leaving the caller's body untouched
+        let mut coerced_body = body.clone();
+        let coerced = coerce_value(&mut coerced_body, schema_ref);
+        let result = self
+            .validate_against_schema_ref(schema_ref, &coerced_body)?
+            .with_coerced(coerced);
+        Ok(Self::check_unknown_fields(
+            result,
+            schema_ref,
+            &coerced_body,
+            reject_unknown,
+        ))
     }
 
-    /// Validate a response body against an operation's response schema.
+    /// Remove unknown fields from a request body, so the handler never
+    /// sees them.
+    ///
+    /// Does nothing unless the request policy is
+    /// [`Strip`](RequestUnknownFields::Strip). Fields a schema rejects with
+    /// `additionalProperties: false` are kept, for validation to reject.
+    /// Returns the paths of the removed fields.
+    pub fn strip_unknown_fields(
+        &self,
+        operation_id: &str,
+        artifact: &LoadedArtifact,
+        body: &mut Value,
+    ) -> Vec<String> {
+        if self.config.request_unknown_fields != RequestUnknownFields::Strip {
+            return vec![];
+        }
+        let mut stripped = Vec::new();
+        if let Some(schema_ref) = self
+            .find_operation(operation_id, artifact)
+            .and_then(|(_, op)| op.request_schema.as_ref())
+        {
+            strip_unknown_fields(body, schema_ref, "", &mut stripped);
+        }
+        stripped
+    }
+
+    /// Record the unknown fields of a validated body, failing the result
+    /// for those the schema rejects, or all of them if `reject` is set.
+    fn check_unknown_fields(
+        mut result: ValidationResult,
+        schema_ref: &SchemaRef,
+        body: &Value,
+        reject: bool,
+    ) -> ValidationResult {
+        let mut unknown = Vec::new();
+        collect_unknown_fields(body, schema_ref, "", &mut unknown);
+
+        for field in &unknown {
+            if reject || field.rejected_by_schema {
+                result.valid = false;
+                result.errors.push(ValidationError {
+                    path: field.path.clone(),
+                    message: format!("unexpected field '{}'", field.path),
+                    schema_path: Some(field.schema.clone()),
+                    value: None,
+                });
+            }
+        }
+        result.unknown_fields = unknown.into_iter().map(|field| field.path).collect();
+        result
+    }
+
+    /// Coerce string values in a request body to the types declared by the
+    /// operation's request schema.
+    ///
+    /// Does nothing unless coercion is enabled. Returns the paths of the
+    /// coerced fields.
+    pub fn coerce_request(
+        &self,
+        operation_id: &str,
+        artifact: &LoadedArtifact,
+        body: &mut Value,
+    ) -> Vec<String> {
+        if !self.config.coerce_primitives {
+            return vec![];
+        }
+        self.find_operation(operation_id, artifact)
+            .and_then(|(_, op)| op.request_schema.as_ref())
+            .map(|schema_ref| coerce_value(body, schema_ref))
+            .unwrap_or_default()
+    }
+
+    /// Validate a JSON response body against an operation's response schema.
     pub fn validate_response(
         &self,
         operation_id: &str,
         artifact: &LoadedArtifact,
         status_code: u16,
         body: &Value,
+    ) -> SentinelResult<ValidationResult> {
+        self.validate_response_content(operation_id, artifact, status_code, JSON_MEDIA_TYPE, body)
+    }
+
+    /// Validate a response body against the operation's schema for its
+    /// status code and `Content-Type`.
+    ///
+    /// Bodies of media types other than JSON are given as a JSON string.
+    pub fn validate_response_content(
+        &self,
+        operation_id: &str,
+        artifact: &LoadedArtifact,
+        status_code: u16,
+        content_type: &str,
+        body: &Value,
     ) -> SentinelResult<ValidationResult> {
         // Find the operation
-        let operation = artifact.operations.iter().find(|op| op.id == operation_id);
+        let operation = self.find_operation(operation_id, artifact);
 
         let operation = match operation {
-            Some(op) => op,
+            Some((_, op)) => op,
             None => {
                 warn!(operation_id, "operation not found for validation");
                 return Ok(ValidationResult::success(None));
             }
         };
 
-        // Find schema for this status code
-        let status_key = status_code.to_string();
-        let schema_ref = operation
-            .response_schemas
-            .get(&status_key)
-            .or_else(|| operation.response_schemas.get("default"));
-
-        let schema_ref = match schema_ref {
+        let schema_ref = match operation.response_schema(&status_code.to_string(), content_type) {
             Some(sr) => sr,
             None => {
                 debug!(
                     operation_id,
-                    status_code, "no response schema for status code"
+                    status_code, content_type, "no response schema for status code"
                 );
                 return Ok(ValidationResult::success(None));
             }
         };
 
         // Validate against the schema
-        self.validate_against_schema_ref(schema_ref, body)
+        let result = self.validate_against_schema_ref(schema_ref, body)?;
+        let reject_unknown = self.config.response_unknown_fields == ResponseUnknownFields::Reject;
+        let result = Self::check_unknown_fields(result, schema_ref, body, reject_unknown);
+        if self.config.response_unknown_fields == ResponseUnknownFields::LogOnly
+            && result.has_unknown_fields()
+        {
+            warn!(
+                operation_id,
+                status_code,
+                fields = ?result.unknown_fields,
+                "response carries fields its schema does not declare"
+            );
+        }
+        Ok(result)
+    }
+
+    /// Validate the data of a server-sent event against the operation's
+    /// schema for its event type.
+    ///
+    /// Events of a type the operation declares no schema for pass.
+    pub fn validate_event(
+        &self,
+        operation_id: &str,
+        artifact: &LoadedArtifact,
+        event_type: &str,
+        data: &Value,
+    ) -> SentinelResult<ValidationResult> {
+        let Some((_, operation)) = self.find_operation(operation_id, artifact) else {
+            warn!(operation_id, "operation not found for validation");
+            return Ok(ValidationResult::success(None));
+        };
+
+        match operation.event_schemas.get(event_type) {
+            Some(schema_ref) => self.validate_against_schema_ref(schema_ref, data),
+            None => {
+                debug!(operation_id, event_type, "no schema for event type");
+                Ok(ValidationResult::success(None))
+            }
+        }
+    }
+
+    /// Validate request headers against an operation's header parameters.
+    ///
+    /// Header names are matched case-insensitively. Errors are reported at
+    /// `header.<name>`, using the name declared by the contract.
+    pub fn validate_request_headers(
+        &self,
+        operation_id: &str,
+        artifact: &LoadedArtifact,
+        headers: &HeaderMap,
+    ) -> ValidationResult {
+        let Some((index, operation)) = self.find_operation(operation_id, artifact) else {
+            warn!(operation_id, "operation not found for validation");
+            return ValidationResult::success(None);
+        };
+        if operation.header_params.is_empty() {
+            return ValidationResult::success(None);
+        }
+
+        let compiled = self.compiled(index, operation);
+        let errors: Vec<ValidationError> = operation
+            .header_params
+            .iter()
+            .zip(&compiled.header_patterns)
+            .filter_map(|(param, pattern)| Self::validate_header(param, pattern.as_ref(), headers))
+            .collect();
+
+        if errors.is_empty() {
+            ValidationResult::success(None)
+        } else {
+            ValidationResult::failure(errors, None)
+        }
+    }
+
+    fn validate_header(
+        param: &HeaderParam,
+        pattern: Option<&Regex>,
+        headers: &HeaderMap,
+    ) -> Option<ValidationError> {
+        let path = format!("header.{}", param.name);
+        let Some(value) = headers.get(param.name.to_ascii_lowercase().as_str()) else {
+            return param.required.then(|| ValidationError {
+                path,
+                message: format!("missing required header '{}'", param.name),
+                schema_path: None,
+                value: None,
+            });
+        };
+
+        let Ok(value) = value.to_str() else {
+            return Some(ValidationError {
+                path,
+                message: format!("header '{}' is not valid text", param.name),
+                schema_path: None,
+                value: None,
+            });
+        };
+
+        let message = if matches!(param.schema_type.as_str(), "integer" | "number" | "boolean")
+            && coerce_str(value, &param.schema_type).is_none()
+        {
+            format!("expected {}, got '{}'", param.schema_type, value)
+        } else if !param.allowed_values.is_empty()
+            && !param.allowed_values.iter().any(|allowed| allowed == value)
+        {
+            format!(
+                "expected one of [{}], got '{}'",
+                param.allowed_values.join(", "),
+                value
+            )
+        } else if pattern.is_some_and(|regex| !regex.is_match(value)) {
+            format!(
+                "value '{}' does not match pattern '{}'",
+                value,
+                param.pattern.as_deref().unwrap_or_default()
+            )
+        } else {
+            return None;
+        };
+
+        Some(ValidationError {
+            path,
+            message,
+            schema_path: None,
+            value: Some(value.to_string()),
+        })
     }
 
     /// Validate path parameters against expected types.
@@ -184,12 +689,18 @@ impl SchemaValidator {
     }
 
     /// Validate query parameters against expected schema.
+    ///
+    /// Query values are always strings, so integer, number and boolean
+    /// parameters only pass when coercion is enabled and the value converts
+    /// unambiguously. Coerced parameters are recorded in the result.
     pub fn validate_query_params(
         &self,
         params: &HashMap<String, String>,
         required: &[String],
+        expected: &HashMap<String, ParamType>,
     ) -> ValidationResult {
         let mut errors = Vec::new();
+        let mut coerced = Vec::new();
 
         for name in required {
             if !params.contains_key(name) {
@@ -202,8 +713,34 @@ impl SchemaValidator {
             }
         }
 
+        for (name, param_type) in expected {
+            let Some(value) = params.get(name) else {
+                continue;
+            };
+            let valid = match param_type {
+                ParamType::String | ParamType::Uuid => self.is_valid_param_type(value, param_type),
+                ParamType::Integer | ParamType::Number | ParamType::Boolean => {
+                    let ok = self.config.coerce_primitives
+                        && coerce_str(value, param_type.as_str()).is_some();
+                    if ok {
+                        coerced.push(format!("query.{}", name));
+                    }
+                    ok
+                }
+            };
+            if !valid {
+                errors.push(ValidationError {
+                    path: format!("query.{}", name),
+                    message: format!("expected {}, got '{}'", param_type.as_str(), value),
+                    schema_path: None,
+                    value: Some(value.clone()),
+                });
+            }
+        }
+        coerced.sort();
+
         if errors.is_empty() {
-            ValidationResult::success(None)
+            ValidationResult::success(None).with_coerced(coerced)
         } else {
             ValidationResult::failure(errors, None)
         }
@@ -307,12 +844,43 @@ impl SchemaValidator {
                         });
                     }
                 }
+
+                // Check declared primitive property types
+                for (name, property_type) in &schema_ref.properties {
+                    let Some(field) = obj.get(name) else {
+                        continue;
+                    };
+                    if !Self::is_primitive_match(field, property_type) {
+                        errors.push(ValidationError {
+                            path: if path.is_empty() {
+                                name.clone()
+                            } else {
+                                format!("{}.{}", path, name)
+                            },
+                            message: format!("expected {}", property_type),
+                            schema_path: Some(schema_ref.reference.clone()),
+                            value: Some(field.to_string()),
+                        });
+                    }
+                }
             }
         }
 
         errors
     }
 
+    /// Check a value against a primitive type; non-primitive types match.
+    fn is_primitive_match(value: &Value, schema_type: &str) -> bool {
+        match schema_type {
+            _ if value.is_null() => true,
+            "string" => value.is_string(),
+            "integer" => value.is_i64() || value.is_u64(),
+            "number" => value.is_number(),
+            "boolean" => value.is_boolean(),
+            _ => true,
+        }
+    }
+
     fn is_valid_param_type(&self, value: &str, param_type: &ParamType) -> bool {
         match param_type {
             ParamType::String => true,
@@ -354,7 +922,7 @@ impl ParamType {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::artifact::LoadedOperation;
+    use indexmap::IndexMap;
 
     fn create_test_config() -> ValidationConfig {
         ValidationConfig {
@@ -362,18 +930,26 @@ mod tests {
             validate_responses: true,
             strict_mode: false,
             allow_additional_properties: true,
+            request_unknown_fields: RequestUnknownFields::Allow,
+            response_unknown_fields: ResponseUnknownFields::Allow,
             allow_missing_path_params: false,
+            coerce_primitives: false,
         }
     }
 
     fn create_test_artifact() -> LoadedArtifact {
         let mut response_schemas = HashMap::new();
         response_schemas.insert(
-            "200".to_string(),
+            ("200".to_string(), JSON_MEDIA_TYPE.to_string()),
             SchemaRef {
                 reference: "#/components/schemas/User".to_string(),
                 schema_type: "object".to_string(),
                 required: vec!["id".to_string(), "name".to_string()],
+                properties: HashMap::new(),
+                property_schemas: HashMap::new(),
+                required_scopes: HashMap::new(),
+                items: None,
+                additional_properties: None,
             },
         );
 
@@ -392,11 +968,22 @@ mod tests {
                     reference: "#/components/schemas/CreateUser".to_string(),
                     schema_type: "object".to_string(),
                     required: vec!["name".to_string(), "email".to_string()],
+                    properties: HashMap::new(),
+                    property_schemas: HashMap::new(),
+                    required_scopes: HashMap::new(),
+                    items: None,
+                    additional_properties: None,
                 }),
                 response_schemas,
                 tags: vec![],
+                header_params: Vec::new(),
+                event_schemas: HashMap::new(),
+                timeout: None,
+                cache_policy: None,
+                allow_reserved: Vec::new(),
             }],
             schemas: IndexMap::new(),
+            stats: Default::default(),
         }
     }
 
@@ -470,6 +1057,51 @@ mod tests {
         assert!(result.valid);
     }
 
+    #[test]
+    fn test_validate_response_by_content_type() {
+        let mut artifact = create_test_artifact();
+        artifact.operations[0].response_schemas.insert(
+            ("200".to_string(), "text/csv".to_string()),
+            SchemaRef {
+                reference: "#/inline/string".to_string(),
+                schema_type: "string".to_string(),
+                required: vec![],
+                properties: HashMap::new(),
+                property_schemas: HashMap::new(),
+                required_scopes: HashMap::new(),
+                items: None,
+                additional_properties: None,
+            },
+        );
+        let validator = SchemaValidator::from_artifact(&artifact, create_test_config());
+        let validate = |content_type: &str, body: &Value| {
+            validator
+                .validate_response_content("createUser", &artifact, 200, content_type, body)
+                .unwrap()
+        };
+        let json = serde_json::json!({ "id": "123", "name": "John Doe" });
+        let csv = Value::String("id,name\n123,John Doe\n".to_string());
+
+        // Each body is checked against the schema of its content type
+        let result = validate("application/json; charset=utf-8", &json);
+        assert!(result.valid);
+        assert_eq!(
+            result.schema_ref.unwrap().reference,
+            "#/components/schemas/User"
+        );
+        let result = validate("text/csv", &csv);
+        assert!(result.valid);
+        assert_eq!(result.schema_ref.unwrap().reference, "#/inline/string");
+
+        assert!(!validate("text/csv", &json).valid);
+        assert!(!validate("application/json", &csv).valid);
+
+        // Undeclared content types are not validated
+        let result = validate("text/xml", &csv);
+        assert!(result.valid);
+        assert!(result.schema_ref.is_none());
+    }
+
     #[test]
     fn test_validate_path_params_valid() {
         let config = create_test_config();
@@ -506,11 +1138,128 @@ mod tests {
         let params = HashMap::new();
         let required = vec!["page".to_string(), "limit".to_string()];
 
-        let result = validator.validate_query_params(&params, &required);
+        let result = validator.validate_query_params(&params, &required, &HashMap::new());
         assert!(!result.valid);
         assert_eq!(result.errors.len(), 2);
     }
 
+    fn create_coercion_artifact() -> LoadedArtifact {
+        let mut artifact = create_test_artifact();
+        let schema = artifact.operations[0].request_schema.as_mut().unwrap();
+        schema.properties = HashMap::from([
+            ("name".to_string(), "string".to_string()),
+            ("email".to_string(), "string".to_string()),
+            ("age".to_string(), "integer".to_string()),
+            ("subscribed".to_string(), "boolean".to_string()),
+        ]);
+        artifact
+    }
+
+    #[test]
+    fn test_coercion_off_by_default() {
+        let artifact = create_coercion_artifact();
+        let validator = SchemaValidator::from_artifact(&artifact, ValidationConfig::default());
+
+        let body = serde_json::json!({ "name": "Ann", "email": "a@b.c", "age": "30" });
+        let result = validator
+            .validate_request("createUser", &artifact, &body)
+            .unwrap();
+        assert!(!result.valid);
+        assert_eq!(result.errors[0].path, "age");
+        assert!(!result.was_coerced());
+
+        let mut body = body;
+        assert!(validator
+            .coerce_request("createUser", &artifact, &mut body)
+            .is_empty());
+        assert_eq!(body["age"], "30");
+    }
+
+    #[test]
+    fn test_coercion_records_coerced_fields() {
+        let artifact = create_coercion_artifact();
+        let config = create_test_config().with_coercion(true);
+        let validator = SchemaValidator::from_artifact(&artifact, config);
+
+        let body = serde_json::json!({
+            "name": "1234",
+            "email": "a@b.c",
+            "age": "30",
+            "subscribed": "false"
+        });
+        let result = validator
+            .validate_request("createUser", &artifact, &body)
+            .unwrap();
+        assert!(result.valid);
+        assert_eq!(
+            result.coerced,
+            vec!["age".to_string(), "subscribed".to_string()]
+        );
+
+        let mut body = body;
+        validator.coerce_request("createUser", &artifact, &mut body);
+        assert_eq!(body["age"], 30);
+        assert_eq!(body["subscribed"], false);
+        // String-typed fields are never coerced
+        assert_eq!(body["name"], "1234");
+    }
+
+    #[test]
+    fn test_coercion_keeps_lossy_type_error() {
+        let artifact = create_coercion_artifact();
+        let config = create_test_config().with_coercion(true);
+        let validator = SchemaValidator::from_artifact(&artifact, config);
+
+        let body = serde_json::json!({ "name": "Ann", "email": "a@b.c", "age": "3.7" });
+        let result = validator
+            .validate_request("createUser", &artifact, &body)
+            .unwrap();
+        assert!(!result.valid);
+        assert_eq!(result.errors[0].path, "age");
+        assert_eq!(result.errors[0].message, "expected integer");
+        assert!(result.coerced.is_empty());
+    }
+
+    #[test]
+    fn test_query_params_coercion() {
+        let artifact = create_test_artifact();
+        let expected = HashMap::from([
+            ("limit".to_string(), ParamType::Integer),
+            ("ratio".to_string(), ParamType::Number),
+            ("active".to_string(), ParamType::Boolean),
+            ("q".to_string(), ParamType::String),
+        ]);
+        let params = HashMap::from([
+            ("limit".to_string(), "30".to_string()),
+            ("ratio".to_string(), "0.5".to_string()),
+            ("active".to_string(), "true".to_string()),
+            ("q".to_string(), "42".to_string()),
+        ]);
+
+        let strict = SchemaValidator::from_artifact(&artifact, create_test_config());
+        let result = strict.validate_query_params(&params, &[], &expected);
+        assert!(!result.valid);
+        assert_eq!(result.errors.len(), 3);
+
+        let config = create_test_config().with_coercion(true);
+        let lenient = SchemaValidator::from_artifact(&artifact, config);
+        let result = lenient.validate_query_params(&params, &[], &expected);
+        assert!(result.valid);
+        assert_eq!(
+            result.coerced,
+            vec![
+                "query.active".to_string(),
+                "query.limit".to_string(),
+                "query.ratio".to_string()
+            ]
+        );
+
+        let lossy = HashMap::from([("limit".to_string(), "3.7".to_string())]);
+        let result = lenient.validate_query_params(&lossy, &[], &expected);
+        assert!(!result.valid);
+        assert_eq!(result.errors[0].message, "expected integer, got '3.7'");
+    }
+
     #[test]
     fn test_validate_uuid_param() {
         let config = create_test_config();
@@ -530,6 +1279,393 @@ mod tests {
         assert!(!result.valid);
     }
 
+    fn artifact_with_headers() -> LoadedArtifact {
+        let mut artifact = create_test_artifact();
+        artifact.operations[0].header_params = vec![
+            HeaderParam {
+                name: "Idempotency-Key".to_string(),
+                required: true,
+                schema_type: "string".to_string(),
+                pattern: Some("^[A-Za-z0-9-]{8,}$".to_string()),
+                allowed_values: vec![],
+            },
+            HeaderParam {
+                name: "X-Priority".to_string(),
+                required: false,
+                schema_type: "string".to_string(),
+                pattern: None,
+                allowed_values: vec!["low".to_string(), "high".to_string()],
+            },
+        ];
+        artifact
+    }
+
+    #[test]
+    fn test_validate_headers_missing_required() {
+        let artifact = artifact_with_headers();
+        let validator = SchemaValidator::from_artifact(&artifact, create_test_config());
+
+        let result = validator.validate_request_headers("createUser", &artifact, &HeaderMap::new());
+        assert!(!result.valid);
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].path, "header.Idempotency-Key");
+        assert!(result.errors[0].message.contains("Idempotency-Key"));
+    }
+
+    #[test]
+    fn test_validate_headers_pattern_and_enum() {
+        let artifact = artifact_with_headers();
+        let validator = SchemaValidator::from_artifact(&artifact, create_test_config());
+
+        let mut headers = HeaderMap::new();
+        headers.insert("idempotency-key", "abc".parse().unwrap());
+        headers.insert("x-priority", "urgent".parse().unwrap());
+        let result = validator.validate_request_headers("createUser", &artifact, &headers);
+        let paths: Vec<&str> = result.errors.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, vec!["header.Idempotency-Key", "header.X-Priority"]);
+        assert!(result.errors[0].message.contains("pattern"));
+
+        headers.insert("idempotency-key", "a1b2c3d4-e5".parse().unwrap());
+        headers.insert("x-priority", "high".parse().unwrap());
+        let result = validator.validate_request_headers("createUser", &artifact, &headers);
+        assert!(result.valid);
+    }
+
+    #[test]
+    fn test_validate_headers_without_requirements() {
+        let artifact = create_test_artifact();
+        let validator = SchemaValidator::from_artifact(&artifact, create_test_config());
+
+        let result = validator.validate_request_headers("createUser", &artifact, &HeaderMap::new());
+        assert!(result.valid);
+    }
+
+    #[test]
+    fn test_validate_event_by_type() {
+        let mut artifact = create_test_artifact();
+        artifact.operations[0].event_schemas.insert(
+            "created".to_string(),
+            SchemaRef {
+                reference: "#/components/schemas/User".to_string(),
+                schema_type: "object".to_string(),
+                required: vec!["id".to_string()],
+                properties: HashMap::from([("id".to_string(), "integer".to_string())]),
+                property_schemas: HashMap::new(),
+                required_scopes: HashMap::new(),
+                items: None,
+                additional_properties: None,
+            },
+        );
+        let validator = SchemaValidator::from_artifact(&artifact, create_test_config());
+
+        let valid = serde_json::json!({ "id": 1 });
+        let invalid = serde_json::json!({ "id": "one" });
+        let check = |event_type, data| {
+            validator
+                .validate_event("createUser", &artifact, event_type, data)
+                .unwrap()
+                .valid
+        };
+
+        assert!(check("created", &valid));
+        assert!(!check("created", &invalid));
+        // Event types without a schema are not validated
+        assert!(check("deleted", &invalid));
+    }
+
+    #[test]
+    fn test_operation_state_built_on_first_use() {
+        let artifact = artifact_with_headers();
+        let validator = SchemaValidator::from_artifact(&artifact, create_test_config());
+        assert_eq!(validator.compiled_operations(), 0);
+
+        validator.validate_request_headers("createUser", &artifact, &HeaderMap::new());
+        assert_eq!(validator.compiled_operations(), 1);
+
+        let eager = SchemaValidator::from_artifact(&artifact, create_test_config());
+        eager.precompile_all(&artifact);
+        assert_eq!(eager.compiled_operations(), artifact.operations.len());
+
+        let single = SchemaValidator::from_artifact(&artifact, create_test_config());
+        assert!(single.precompile("createUser", &artifact));
+        assert!(!single.precompile("unknownOperation", &artifact));
+        assert_eq!(single.compiled_operations(), 1);
+    }
+
+    #[test]
+    fn test_lazy_and_eager_validate_identically() {
+        let artifact = artifact_with_headers();
+        let lazy = SchemaValidator::from_artifact(&artifact, create_test_config());
+        let eager = SchemaValidator::from_artifact(&artifact, create_test_config());
+        eager.precompile_all(&artifact);
+
+        let describe = |result: ValidationResult| {
+            result
+                .errors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        };
+        let header_cases = [
+            vec![],
+            vec![("idempotency-key", "abc")],
+            vec![("idempotency-key", "a1b2c3d4-e5"), ("x-priority", "urgent")],
+            vec![("idempotency-key", "a1b2c3d4-e5"), ("x-priority", "low")],
+        ];
+        for case in header_cases {
+            let mut headers = HeaderMap::new();
+            for (name, value) in case {
+                headers.insert(name, value.parse().unwrap());
+            }
+            assert_eq!(
+                describe(lazy.validate_request_headers("createUser", &artifact, &headers)),
+                describe(eager.validate_request_headers("createUser", &artifact, &headers)),
+            );
+        }
+
+        let bodies = [
+            serde_json::json!({ "name": "Ann", "email": "a@b.c" }),
+            serde_json::json!({ "name": "Ann" }),
+            serde_json::json!([1, 2, 3]),
+        ];
+        for body in bodies {
+            assert_eq!(
+                describe(
+                    lazy.validate_request("createUser", &artifact, &body)
+                        .unwrap()
+                ),
+                describe(
+                    eager
+                        .validate_request("createUser", &artifact, &body)
+                        .unwrap()
+                ),
+            );
+        }
+    }
+
+    fn object_schema(
+        reference: &str,
+        properties: &[(&str, &str)],
+        additional_properties: Option<bool>,
+    ) -> SchemaRef {
+        SchemaRef {
+            reference: reference.to_string(),
+            schema_type: "object".to_string(),
+            required: vec![],
+            properties: properties
+                .iter()
+                .map(|(name, ty)| ((*name).to_string(), (*ty).to_string()))
+                .collect(),
+            property_schemas: HashMap::new(),
+            required_scopes: HashMap::new(),
+            items: None,
+            additional_properties,
+        }
+    }
+
+    /// An order with a nested customer and an array of line items; the
+    /// customer schema sets `additionalProperties` as given.
+    fn order_artifact(customer_additional_properties: Option<bool>) -> LoadedArtifact {
+        let customer = object_schema(
+            "#/components/schemas/Customer",
+            &[("name", "string")],
+            customer_additional_properties,
+        );
+        let line = object_schema(
+            "#/components/schemas/Line",
+            &[("sku", "string"), ("qty", "integer")],
+            None,
+        );
+        let lines = SchemaRef {
+            reference: "#/components/schemas/Order/properties/lines".to_string(),
+            schema_type: "array".to_string(),
+            required: vec![],
+            properties: HashMap::new(),
+            property_schemas: HashMap::new(),
+            required_scopes: HashMap::new(),
+            items: Some(Box::new(line)),
+            additional_properties: None,
+        };
+        let mut order = object_schema(
+            "#/components/schemas/Order",
+            &[("id", "string"), ("customer", "object"), ("lines", "array")],
+            None,
+        );
+        order
+            .property_schemas
+            .insert("customer".to_string(), customer);
+        order.property_schemas.insert("lines".to_string(), lines);
+
+        let mut artifact = create_test_artifact();
+        let operation = &mut artifact.operations[0];
+        operation.request_schema = Some(order.clone());
+        operation
+            .response_schemas
+            .insert(("200".to_string(), JSON_MEDIA_TYPE.to_string()), order);
+        artifact
+    }
+
+    fn order_with_unknown_fields() -> Value {
+        serde_json::json!({
+            "id": "o-1",
+            "coupon": "SPRING",
+            "customer": { "name": "Ann", "vip": true },
+            "lines": [
+                { "sku": "a", "qty": 1 },
+                { "sku": "b", "qty": 2, "note": "gift" }
+            ]
+        })
+    }
+
+    const ORDER_UNKNOWN_FIELDS: [&str; 3] = ["coupon", "customer.vip", "lines[1].note"];
+
+    fn sorted(mut fields: Vec<String>) -> Vec<String> {
+        fields.sort();
+        fields
+    }
+
+    #[test]
+    fn test_request_unknown_fields_allow() {
+        let artifact = order_artifact(None);
+        let validator = SchemaValidator::from_artifact(&artifact, create_test_config());
+        let body = order_with_unknown_fields();
+
+        let result = validator
+            .validate_request("createUser", &artifact, &body)
+            .unwrap();
+        assert!(result.valid);
+        assert_eq!(sorted(result.unknown_fields), ORDER_UNKNOWN_FIELDS);
+
+        let mut stripped = body.clone();
+        assert!(validator
+            .strip_unknown_fields("createUser", &artifact, &mut stripped)
+            .is_empty());
+        assert_eq!(stripped, body);
+    }
+
+    #[test]
+    fn test_request_unknown_fields_reject() {
+        let artifact = order_artifact(None);
+        let config = create_test_config().with_request_unknown_fields(RequestUnknownFields::Reject);
+        let validator = SchemaValidator::from_artifact(&artifact, config);
+
+        let result = validator
+            .validate_request("createUser", &artifact, &order_with_unknown_fields())
+            .unwrap();
+        assert!(!result.valid);
+        let paths = result.errors.iter().map(|e| e.path.clone()).collect();
+        assert_eq!(sorted(paths), ORDER_UNKNOWN_FIELDS);
+        let vip = result
+            .errors
+            .iter()
+            .find(|e| e.path == "customer.vip")
+            .unwrap();
+        assert_eq!(vip.message, "unexpected field 'customer.vip'");
+        assert_eq!(
+            vip.schema_path.as_deref(),
+            Some("#/components/schemas/Customer")
+        );
+
+        let known = serde_json::json!({
+            "id": "o-1",
+            "customer": { "name": "Ann" },
+            "lines": [{ "sku": "a", "qty": 1 }]
+        });
+        let result = validator
+            .validate_request("createUser", &artifact, &known)
+            .unwrap();
+        assert!(result.valid);
+        assert!(!result.has_unknown_fields());
+    }
+
+    #[test]
+    fn test_request_unknown_fields_strip() {
+        let artifact = order_artifact(None);
+        let config = create_test_config().with_request_unknown_fields(RequestUnknownFields::Strip);
+        let validator = SchemaValidator::from_artifact(&artifact, config);
+
+        let mut body = order_with_unknown_fields();
+        let stripped = validator.strip_unknown_fields("createUser", &artifact, &mut body);
+        assert_eq!(sorted(stripped), ORDER_UNKNOWN_FIELDS);
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "id": "o-1",
+                "customer": { "name": "Ann" },
+                "lines": [
+                    { "sku": "a", "qty": 1 },
+                    { "sku": "b", "qty": 2 }
+                ]
+            })
+        );
+
+        let result = validator
+            .validate_request("createUser", &artifact, &body)
+            .unwrap();
+        assert!(result.valid);
+        assert!(!result.has_unknown_fields());
+    }
+
+    #[test]
+    fn test_unknown_fields_schema_setting_wins() {
+        let body = order_with_unknown_fields();
+
+        // `additionalProperties: false` rejects even when the policy allows
+        let artifact = order_artifact(Some(false));
+        let validator = SchemaValidator::from_artifact(&artifact, create_test_config());
+        let result = validator
+            .validate_request("createUser", &artifact, &body)
+            .unwrap();
+        assert!(!result.valid);
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].path, "customer.vip");
+
+        // ...and is left for validation to reject rather than stripped
+        let config = create_test_config().with_request_unknown_fields(RequestUnknownFields::Strip);
+        let validator = SchemaValidator::from_artifact(&artifact, config);
+        let mut stripped = body.clone();
+        validator.strip_unknown_fields("createUser", &artifact, &mut stripped);
+        assert_eq!(stripped["customer"]["vip"], true);
+        assert!(stripped.get("coupon").is_none());
+
+        // `additionalProperties: true` allows even when the policy rejects
+        let artifact = order_artifact(Some(true));
+        let config = create_test_config()
+            .with_request_unknown_fields(RequestUnknownFields::Reject)
+            .with_response_unknown_fields(ResponseUnknownFields::Reject);
+        let validator = SchemaValidator::from_artifact(&artifact, config);
+        let result = validator
+            .validate_request("createUser", &artifact, &body)
+            .unwrap();
+        let paths = result.errors.iter().map(|e| e.path.clone()).collect();
+        assert_eq!(sorted(paths), ["coupon", "lines[1].note"]);
+        assert!(!result.unknown_fields.contains(&"customer.vip".to_string()));
+        let result = validator
+            .validate_response("createUser", &artifact, 200, &body)
+            .unwrap();
+        assert_eq!(result.errors.len(), 2);
+    }
+
+    #[test]
+    fn test_response_unknown_fields_policies() {
+        let artifact = order_artifact(None);
+        let body = order_with_unknown_fields();
+
+        for (policy, valid) in [
+            (ResponseUnknownFields::Allow, true),
+            (ResponseUnknownFields::LogOnly, true),
+            (ResponseUnknownFields::Reject, false),
+        ] {
+            let config = create_test_config().with_response_unknown_fields(policy);
+            let validator = SchemaValidator::from_artifact(&artifact, config);
+            let result = validator
+                .validate_response("createUser", &artifact, 200, &body)
+                .unwrap();
+            assert_eq!(result.valid, valid, "{policy}");
+            assert_eq!(sorted(result.unknown_fields), ORDER_UNKNOWN_FIELDS);
+        }
+    }
+
     #[test]
     fn test_validation_result_has_errors() {
         let result = ValidationResult::success(None);
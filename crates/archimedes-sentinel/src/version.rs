@@ -0,0 +1,212 @@
+//! Contract version negotiation.
+//!
+//! During a migration a service may serve several versions of its contract
+//! side by side. The [`VersionSelector`] decides which loaded artifact a
+//! request is resolved against, using a request header (default
+//! `Accept-Version`) or, optionally, a leading path segment such as
+//! `/v2/users`.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use archimedes_sentinel::{Sentinel, SentinelConfig, VersionSelector};
+//!
+//! let sentinel = Sentinel::versioned(
+//!     [("v1", v1_artifact), ("v2", v2_artifact)],
+//!     VersionSelector::new().default_version("v1").path_prefix(true),
+//!     SentinelConfig::default(),
+//! )?;
+//!
+//! let resolution = sentinel.resolve_with_headers("GET", "/users/1", &headers)?;
+//! println!("resolved against contract {}", resolution.version);
+//! ```
+
+use http::HeaderMap;
+
+/// Default header used to request a contract version.
+pub const DEFAULT_VERSION_HEADER: &str = "accept-version";
+
+/// Policy for selecting a contract version per request.
+///
+/// Selection order:
+///
+/// 1. A leading path segment naming a supported version, when path-prefix
+///    selection is enabled (the segment is stripped before resolution)
+/// 2. The version header
+/// 3. The default version
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionSelector {
+    header_name: String,
+    path_prefix: bool,
+    default_version: Option<String>,
+    supported: Vec<String>,
+}
+
+impl Default for VersionSelector {
+    fn default() -> Self {
+        Self {
+            header_name: DEFAULT_VERSION_HEADER.to_string(),
+            path_prefix: false,
+            default_version: None,
+            supported: Vec::new(),
+        }
+    }
+}
+
+impl VersionSelector {
+    /// Creates a selector reading the `Accept-Version` header.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the header carrying the requested version.
+    #[must_use]
+    pub fn header_name(mut self, name: impl Into<String>) -> Self {
+        self.header_name = name.into().to_ascii_lowercase();
+        self
+    }
+
+    /// Enables or disables selection by leading path segment.
+    #[must_use]
+    pub fn path_prefix(mut self, enabled: bool) -> Self {
+        self.path_prefix = enabled;
+        self
+    }
+
+    /// Sets the version used when the request does not name one.
+    ///
+    /// Defaults to the first loaded artifact.
+    #[must_use]
+    pub fn default_version(mut self, version: impl Into<String>) -> Self {
+        self.default_version = Some(version.into());
+        self
+    }
+
+    /// Restricts the versions clients may request.
+    ///
+    /// Defaults to every loaded artifact. Listing fewer versions retires a
+    /// version for clients while keeping its artifact loaded.
+    #[must_use]
+    pub fn supported<I>(mut self, versions: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.supported = versions.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Returns the version header name (lowercase).
+    #[must_use]
+    pub fn header(&self) -> &str {
+        &self.header_name
+    }
+
+    /// Returns whether path-prefix selection is enabled.
+    #[must_use]
+    pub fn uses_path_prefix(&self) -> bool {
+        self.path_prefix
+    }
+
+    /// Returns the configured default version, if any.
+    #[must_use]
+    pub fn default_version_name(&self) -> Option<&str> {
+        self.default_version.as_deref()
+    }
+
+    /// Returns the configured supported list (empty means all loaded).
+    #[must_use]
+    pub fn supported_versions(&self) -> &[String] {
+        &self.supported
+    }
+
+    /// Extracts the requested version from the request, if any.
+    ///
+    /// `known` lists the versions a path prefix may name; a leading segment
+    /// that is not a known version is treated as part of the path. Returns
+    /// the requested version and the path with any version prefix removed.
+    pub(crate) fn requested<'p>(
+        &self,
+        headers: &HeaderMap,
+        path: &'p str,
+        known: &[&str],
+    ) -> (Option<String>, &'p str) {
+        if self.path_prefix {
+            let trimmed = path.trim_start_matches('/');
+            let (segment, rest) = trimmed
+                .find('/')
+                .map_or((trimmed, ""), |i| (&trimmed[..i], &trimmed[i..]));
+            if !segment.is_empty() && known.contains(&segment) {
+                let rest = if rest.is_empty() { "/" } else { rest };
+                return (Some(segment.to_string()), rest);
+            }
+        }
+
+        let header = headers
+            .get(self.header_name.as_str())
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string);
+
+        (header, path)
+    }
+}
+
+/// The outcome of version selection for a request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionSelection {
+    /// The selected contract version.
+    pub version: String,
+    /// The request path with any version prefix removed.
+    pub path: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::HeaderValue;
+
+    #[test]
+    fn test_selector_defaults() {
+        let selector = VersionSelector::new();
+        assert_eq!(selector.header(), "accept-version");
+        assert!(!selector.uses_path_prefix());
+        assert!(selector.default_version_name().is_none());
+        assert!(selector.supported_versions().is_empty());
+    }
+
+    #[test]
+    fn test_requested_from_header() {
+        let selector = VersionSelector::new().header_name("X-API-Version");
+        let mut headers = HeaderMap::new();
+        headers.insert("x-api-version", HeaderValue::from_static(" v2 "));
+
+        let (version, path) = selector.requested(&headers, "/users", &["v1", "v2"]);
+        assert_eq!(version.as_deref(), Some("v2"));
+        assert_eq!(path, "/users");
+    }
+
+    #[test]
+    fn test_requested_from_path_prefix() {
+        let selector = VersionSelector::new().path_prefix(true);
+        let headers = HeaderMap::new();
+
+        let (version, path) = selector.requested(&headers, "/v2/users/1", &["v1", "v2"]);
+        assert_eq!(version.as_deref(), Some("v2"));
+        assert_eq!(path, "/users/1");
+
+        let (version, path) = selector.requested(&headers, "/v1", &["v1", "v2"]);
+        assert_eq!(version.as_deref(), Some("v1"));
+        assert_eq!(path, "/");
+    }
+
+    #[test]
+    fn test_unknown_prefix_is_part_of_path() {
+        let selector = VersionSelector::new().path_prefix(true);
+        let (version, path) = selector.requested(&HeaderMap::new(), "/users/1", &["v1"]);
+        assert!(version.is_none());
+        assert_eq!(path, "/users/1");
+    }
+}
@@ -0,0 +1,128 @@
+//! Golden-file tests for the operations module generated from a contract.
+//!
+//! The golden file is also compiled in here, so the generated code is
+//! checked to build and behave, not just to be stable. After an intended
+//! change to the output, regenerate it with
+//! `UPDATE_GOLDEN=1 cargo test -p archimedes-sentinel --test codegen`.
+
+use archimedes_core::contract::{Contract, Operation as ContractOp};
+use archimedes_core::ContractOperation;
+use archimedes_sentinel::{codegen, fixtures, LoadedArtifact};
+use http::Method;
+
+#[allow(dead_code)]
+mod ops {
+    include!("golden/operations.rs");
+}
+
+use ops::Operation;
+
+const GOLDEN: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/operations.rs");
+
+fn artifact() -> LoadedArtifact {
+    let contract = Contract::builder("users")
+        .version("1.0.0")
+        .operation(
+            ContractOp::builder("getUser")
+                .method(Method::GET)
+                .path("/users/{userId}")
+                .description("Get a user by ID")
+                .build(),
+        )
+        .operation(
+            ContractOp::builder("listUsers")
+                .method(Method::GET)
+                .path("/users")
+                .build(),
+        )
+        .operation(
+            ContractOp::builder("createUser")
+                .method(Method::POST)
+                .path("/users")
+                .description("Create a user")
+                .build(),
+        )
+        .operation(
+            ContractOp::builder("get-health.v2")
+                .method(Method::GET)
+                .path("/health")
+                .build(),
+        )
+        .operation(
+            ContractOp::builder("exportUsersCSV")
+                .method(Method::GET)
+                .path("/users/export")
+                .description("Export every user as CSV")
+                .deprecated()
+                .build(),
+        )
+        .build();
+    fixtures::from_contract(&contract)
+}
+
+#[test]
+fn test_generated_module_matches_golden() {
+    let generated = codegen::render_operation_consts(&artifact()).unwrap();
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(GOLDEN, &generated).unwrap();
+        return;
+    }
+
+    let golden = std::fs::read_to_string(GOLDEN).unwrap();
+    assert!(
+        generated == golden,
+        "generated module differs from {GOLDEN}; rerun with UPDATE_GOLDEN=1 if the change is intended:\n{generated}"
+    );
+}
+
+#[test]
+fn test_generated_constants() {
+    assert_eq!(ops::GET_USER, "getUser");
+    assert_eq!(ops::GET_USER_METHOD, "GET");
+    assert_eq!(ops::GET_USER_PATH, "/users/{userId}");
+    assert_eq!(ops::GET_HEALTH_V2, "get-health.v2");
+    assert_eq!(ops::EXPORT_USERS_CSV_PATH, "/users/export");
+}
+
+#[test]
+fn test_generated_enum() {
+    assert_eq!(
+        Operation::ids(),
+        [
+            "getUser",
+            "listUsers",
+            "createUser",
+            "get-health.v2",
+            "exportUsersCSV"
+        ]
+    );
+    for op in Operation::ALL {
+        assert_eq!(op.as_str().parse::<Operation>(), Ok(*op));
+    }
+    assert_eq!(Operation::CreateUser.method(), "POST");
+    assert_eq!(Operation::GetUser.path(), "/users/{userId}");
+    assert_eq!(Operation::GetHealthV2.to_string(), "get-health.v2");
+
+    let err = "getUsr".parse::<Operation>().unwrap_err();
+    assert_eq!(err.to_string(), "unknown operation: getUsr");
+}
+
+#[test]
+fn test_generated_enum_matches_exhaustively() {
+    // Adding an operation to the contract makes this match stop compiling.
+    const fn is_read(op: Operation) -> bool {
+        match op {
+            Operation::GetUser
+            | Operation::ListUsers
+            | Operation::GetHealthV2
+            | Operation::ExportUsersCsv => true,
+            Operation::CreateUser => false,
+        }
+    }
+
+    let reads = Operation::ALL.iter().filter(|op| is_read(**op)).count();
+    assert_eq!(reads, 4);
+    assert!(Operation::ALL
+        .iter()
+        .all(|op| is_read(*op) == (op.method() == "GET")));
+}
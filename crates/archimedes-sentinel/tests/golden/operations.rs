@@ -0,0 +1,141 @@
+// @generated by archimedes-sentinel from the `users` contract, version 1.0.0.
+// Do not edit; regenerate it from the contract instead.
+
+/// Operation ID of `GET /users/{userId}`.
+///
+/// Get a user by ID
+pub const GET_USER: &str = "getUser";
+/// HTTP method of [`GET_USER`].
+pub const GET_USER_METHOD: &str = "GET";
+/// Path template of [`GET_USER`].
+pub const GET_USER_PATH: &str = "/users/{userId}";
+
+/// Operation ID of `GET /users`.
+pub const LIST_USERS: &str = "listUsers";
+/// HTTP method of [`LIST_USERS`].
+pub const LIST_USERS_METHOD: &str = "GET";
+/// Path template of [`LIST_USERS`].
+pub const LIST_USERS_PATH: &str = "/users";
+
+/// Operation ID of `POST /users`.
+///
+/// Create a user
+pub const CREATE_USER: &str = "createUser";
+/// HTTP method of [`CREATE_USER`].
+pub const CREATE_USER_METHOD: &str = "POST";
+/// Path template of [`CREATE_USER`].
+pub const CREATE_USER_PATH: &str = "/users";
+
+/// Operation ID of `GET /health`.
+pub const GET_HEALTH_V2: &str = "get-health.v2";
+/// HTTP method of [`GET_HEALTH_V2`].
+pub const GET_HEALTH_V2_METHOD: &str = "GET";
+/// Path template of [`GET_HEALTH_V2`].
+pub const GET_HEALTH_V2_PATH: &str = "/health";
+
+/// Operation ID of `GET /users/export`.
+///
+/// Export every user as CSV
+///
+/// The operation is deprecated.
+pub const EXPORT_USERS_CSV: &str = "exportUsersCSV";
+/// HTTP method of [`EXPORT_USERS_CSV`].
+pub const EXPORT_USERS_CSV_METHOD: &str = "GET";
+/// Path template of [`EXPORT_USERS_CSV`].
+pub const EXPORT_USERS_CSV_PATH: &str = "/users/export";
+
+/// The operations of the `users` contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Operation {
+    /// `getUser`
+    GetUser,
+    /// `listUsers`
+    ListUsers,
+    /// `createUser`
+    CreateUser,
+    /// `get-health.v2`
+    GetHealthV2,
+    /// `exportUsersCSV`
+    ExportUsersCsv,
+}
+
+impl Operation {
+    /// Every operation, in contract order.
+    pub const ALL: &'static [Self] = &[
+        Self::GetUser,
+        Self::ListUsers,
+        Self::CreateUser,
+        Self::GetHealthV2,
+        Self::ExportUsersCsv,
+    ];
+
+    /// Returns the operation ID.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::GetUser => GET_USER,
+            Self::ListUsers => LIST_USERS,
+            Self::CreateUser => CREATE_USER,
+            Self::GetHealthV2 => GET_HEALTH_V2,
+            Self::ExportUsersCsv => EXPORT_USERS_CSV,
+        }
+    }
+
+    /// Returns the HTTP method.
+    pub const fn method(self) -> &'static str {
+        match self {
+            Self::GetUser => GET_USER_METHOD,
+            Self::ListUsers => LIST_USERS_METHOD,
+            Self::CreateUser => CREATE_USER_METHOD,
+            Self::GetHealthV2 => GET_HEALTH_V2_METHOD,
+            Self::ExportUsersCsv => EXPORT_USERS_CSV_METHOD,
+        }
+    }
+
+    /// Returns the path template.
+    pub const fn path(self) -> &'static str {
+        match self {
+            Self::GetUser => GET_USER_PATH,
+            Self::ListUsers => LIST_USERS_PATH,
+            Self::CreateUser => CREATE_USER_PATH,
+            Self::GetHealthV2 => GET_HEALTH_V2_PATH,
+            Self::ExportUsersCsv => EXPORT_USERS_CSV_PATH,
+        }
+    }
+}
+
+impl ::core::str::FromStr for Operation {
+    type Err = ::archimedes_core::UnknownOperation;
+
+    fn from_str(id: &str) -> ::core::result::Result<Self, Self::Err> {
+        match id {
+            GET_USER => Ok(Self::GetUser),
+            LIST_USERS => Ok(Self::ListUsers),
+            CREATE_USER => Ok(Self::CreateUser),
+            GET_HEALTH_V2 => Ok(Self::GetHealthV2),
+            EXPORT_USERS_CSV => Ok(Self::ExportUsersCsv),
+            _ => Err(::archimedes_core::UnknownOperation(id.to_string())),
+        }
+    }
+}
+
+impl ::core::fmt::Display for Operation {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl ::archimedes_core::ContractOperation for Operation {
+    const ALL: &'static [Self] = Operation::ALL;
+
+    fn as_str(self) -> &'static str {
+        Operation::as_str(self)
+    }
+
+    fn method(self) -> &'static str {
+        Operation::method(self)
+    }
+
+    fn path(self) -> &'static str {
+        Operation::path(self)
+    }
+}
@@ -0,0 +1,114 @@
+//! Overhead of detailed per-request timing.
+//!
+//! Compares handler invocation with detailed timing off (no timing scope)
+//! and on. With the flag off the instrumentation only checks a task local,
+//! so `invoke/off` should match `baseline` within noise.
+//!
+//! Run with: `cargo bench -p archimedes-server --bench detailed_timing`
+
+use archimedes_core::timing::{self, RequestTiming};
+use archimedes_core::RequestContext;
+use archimedes_server::{HandlerError, HandlerRegistry};
+use bytes::Bytes;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use serde::{Deserialize, Serialize};
+use tokio::runtime::Runtime;
+
+#[derive(Deserialize)]
+struct GetUserRequest {
+    user_id: String,
+}
+
+#[derive(Serialize)]
+struct User {
+    id: String,
+    name: String,
+}
+
+async fn get_user(_ctx: RequestContext, req: GetUserRequest) -> Result<User, HandlerError> {
+    Ok(User {
+        id: req.user_id,
+        name: "Ada".to_string(),
+    })
+}
+
+fn runtime() -> Runtime {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .expect("runtime")
+}
+
+fn registry() -> HandlerRegistry {
+    let mut registry = HandlerRegistry::new();
+    registry.register("getUser", get_user);
+    registry
+}
+
+fn bench_invoke(c: &mut Criterion) {
+    let runtime = runtime();
+    let registry = registry();
+    let body = Bytes::from_static(br#"{"user_id":"42"}"#);
+
+    let mut group = c.benchmark_group("invoke");
+
+    // The same work as the registered handler, without any instrumentation
+    group.bench_function("baseline", |b| {
+        b.iter(|| {
+            runtime.block_on(async {
+                let request: GetUserRequest = serde_json::from_slice(black_box(&body)).unwrap();
+                let user = get_user(RequestContext::new(), request).await.unwrap();
+                black_box(serde_json::to_vec(&user).unwrap())
+            })
+        });
+    });
+
+    group.bench_function("off", |b| {
+        b.iter(|| {
+            runtime.block_on(async {
+                black_box(
+                    registry
+                        .invoke("getUser", RequestContext::new(), black_box(body.clone()))
+                        .await
+                        .unwrap(),
+                )
+            })
+        });
+    });
+
+    group.bench_function("on", |b| {
+        b.iter(|| {
+            runtime.block_on(timing::scope(RequestTiming::new(), async {
+                black_box(
+                    registry
+                        .invoke("getUser", RequestContext::new(), black_box(body.clone()))
+                        .await
+                        .unwrap(),
+                )
+            }))
+        });
+    });
+
+    group.finish();
+}
+
+fn bench_record(c: &mut Criterion) {
+    let mut group = c.benchmark_group("record");
+
+    group.bench_function("off", |b| {
+        b.iter(|| timing::time(timing::VALIDATION, || black_box(1) + 1));
+    });
+
+    let runtime = runtime();
+    group.bench_function("on", |b| {
+        b.iter(|| {
+            runtime.block_on(timing::scope(RequestTiming::new(), async {
+                timing::time(timing::VALIDATION, || black_box(1) + 1)
+            }))
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_invoke, bench_record);
+criterion_main!(benches);
@@ -0,0 +1,626 @@
+//! Several apps served on one listener.
+//!
+//! A server can host several apps, each with its own routes, handlers,
+//! middleware pipeline and DI container, so small services can share one
+//! process and port. Every request is dispatched to exactly one app before
+//! any middleware runs:
+//!
+//! 1. An app whose [`host`](AppSpec::host) matches the request host (the
+//!    `Host` header, or the URI authority for HTTP/2), longest path prefix
+//!    first
+//! 2. An app without a host, longest path prefix first
+//! 3. The [default app](crate::ServerBuilder::default_app), if configured
+//!
+//! A request that selects no app is rejected with `421 Misdirected
+//! Request`. The app's [path prefix](AppSpec::path_prefix) is stripped
+//! before it routes the request, so its routes are those of its contract.
+//! Internal endpoints such as `/health` belong to the server and are served
+//! ahead of app selection.
+//!
+//! Each app runs its own pipeline, so apps can authorize against different
+//! policy bundles or validate with different strictness. The pipelines
+//! share the metrics recorder, and telemetry labels each request with the
+//! app that served it (see [`AppName`]).
+//!
+//! Overlapping apps are a configuration error, reported by
+//! [`ServerBuilder::try_build`](crate::ServerBuilder::try_build) and again
+//! when the server starts:
+//!
+//! - two apps with the same name
+//! - two apps selected by the same host and path prefix
+//! - an app route that another app's path prefix would capture
+//! - an app route shadowed by an internal endpoint
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use archimedes_server::{AppSpec, Server};
+//!
+//! let server = Server::builder()
+//!     .app(
+//!         AppSpec::new("api")
+//!             .host("api.example.com")
+//!             .router(api_router)
+//!             .handlers(api_handlers)
+//!             .pipeline(api_pipeline),
+//!     )
+//!     .app(
+//!         AppSpec::new("admin")
+//!             .host("admin.example.com")
+//!             .router(admin_router)
+//!             .handlers(admin_handlers)
+//!             .pipeline(admin_pipeline)
+//!             .container(Arc::new(admin_services)),
+//!     )
+//!     .default_app("api")
+//!     .try_build()?;
+//! ```
+//!
+//! [`AppName`]: archimedes_middleware::AppName
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use http::{header, HeaderMap, Uri};
+
+use archimedes_core::di::Container;
+use archimedes_middleware::Pipeline;
+
+use crate::diagnostics::ContractInfo;
+use crate::handler::HandlerRegistry;
+use crate::internal::InternalRoutes;
+use crate::router::Router;
+use crate::server::{Server, ServerError};
+
+/// An app served on a shared listener.
+///
+/// An app must be selected by a host, a path prefix or both, unless it is
+/// the [default app](crate::ServerBuilder::default_app). Request
+/// validation and authorization are stages of its
+/// [pipeline](Self::pipeline).
+///
+/// # Example
+///
+/// ```rust
+/// use archimedes_server::{AppSpec, Router};
+/// use http::Method;
+///
+/// let mut router = Router::new();
+/// router.add_route(Method::GET, "/users/{id}", "getUser");
+///
+/// let app = AppSpec::new("admin")
+///     .host("Admin.Example.com")
+///     .path_prefix("/internal/")
+///     .router(router);
+///
+/// assert_eq!(app.name(), "admin");
+/// assert_eq!(app.host_match(), Some("admin.example.com"));
+/// assert_eq!(app.prefix(), Some("/internal"));
+/// ```
+pub struct AppSpec {
+    pub(crate) name: String,
+    pub(crate) host: Option<String>,
+    pub(crate) path_prefix: Option<String>,
+    pub(crate) router: Router,
+    pub(crate) handlers: HandlerRegistry,
+    pub(crate) pipeline: Option<Pipeline>,
+    pub(crate) container: Option<Arc<Container>>,
+    pub(crate) contract: Option<ContractInfo>,
+}
+
+impl AppSpec {
+    /// Creates an app with no routes.
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            host: None,
+            path_prefix: None,
+            router: Router::new(),
+            handlers: HandlerRegistry::new(),
+            pipeline: None,
+            container: None,
+            contract: None,
+        }
+    }
+
+    /// Selects the app for requests to `host`.
+    ///
+    /// Hosts compare case-insensitively and without the port.
+    #[must_use]
+    pub fn host(mut self, host: impl Into<String>) -> Self {
+        self.host = Some(normalize_host(&host.into()));
+        self
+    }
+
+    /// Mounts the app under a path prefix, such as `/admin`.
+    ///
+    /// The prefix is stripped before the app routes a request.
+    #[must_use]
+    pub fn path_prefix(mut self, prefix: impl AsRef<str>) -> Self {
+        self.path_prefix = normalize_prefix(prefix.as_ref());
+        self
+    }
+
+    /// Sets the app's routes.
+    #[must_use]
+    pub fn router(mut self, router: Router) -> Self {
+        self.router = router;
+        self
+    }
+
+    /// Sets the app's handlers.
+    #[must_use]
+    pub fn handlers(mut self, handlers: HandlerRegistry) -> Self {
+        self.handlers = handlers;
+        self
+    }
+
+    /// Sets the middleware pipeline run for the app's requests.
+    #[must_use]
+    pub fn pipeline(mut self, pipeline: Pipeline) -> Self {
+        self.pipeline = Some(pipeline);
+        self
+    }
+
+    /// Sets the DI container handed to the app's handlers through
+    /// [`RequestContext::container`](archimedes_core::RequestContext::container).
+    #[must_use]
+    pub fn container(mut self, container: Arc<Container>) -> Self {
+        self.container = Some(container);
+        self
+    }
+
+    /// Reports the app's contract in the diagnostics report.
+    ///
+    /// Handler coverage of the app is then computed against the contract
+    /// operations instead of the routed ones.
+    #[must_use]
+    pub fn contract(mut self, contract: ContractInfo) -> Self {
+        self.contract = Some(contract);
+        self
+    }
+
+    /// Returns the app name.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the host the app is selected by, if any.
+    #[must_use]
+    pub fn host_match(&self) -> Option<&str> {
+        self.host.as_deref()
+    }
+
+    /// Returns the path prefix the app is mounted under, if any.
+    #[must_use]
+    pub fn prefix(&self) -> Option<&str> {
+        self.path_prefix.as_deref()
+    }
+}
+
+impl std::fmt::Debug for AppSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AppSpec")
+            .field("name", &self.name)
+            .field("host", &self.host)
+            .field("path_prefix", &self.path_prefix)
+            .field("routes", &self.router.route_count())
+            .field("handlers", &self.handlers.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// An app mounted on a server.
+pub(crate) struct MountedApp {
+    /// App name.
+    pub(crate) name: String,
+    /// Host the app is selected by.
+    pub(crate) host: Option<String>,
+    /// Path prefix the app is mounted under.
+    pub(crate) path_prefix: Option<String>,
+    /// Server serving the app's routes.
+    pub(crate) server: Arc<Server>,
+}
+
+impl MountedApp {
+    /// Returns the path and query of a request as the app routes it, or
+    /// `None` if the path is outside the app's prefix.
+    pub(crate) fn strip_prefix(&self, path_and_query: &str) -> Option<String> {
+        let Some(prefix) = &self.path_prefix else {
+            return Some(path_and_query.to_string());
+        };
+        let rest = path_and_query.strip_prefix(prefix.as_str())?;
+        if rest.is_empty() || rest.starts_with('?') {
+            Some(format!("/{rest}"))
+        } else if rest.starts_with('/') {
+            Some(rest.to_string())
+        } else {
+            None
+        }
+    }
+
+    /// Length of the path prefix, used to prefer the most specific app.
+    fn prefix_len(&self) -> usize {
+        self.path_prefix.as_ref().map_or(0, String::len)
+    }
+}
+
+/// Returns the host a request was sent to, lowercased and without port.
+pub(crate) fn request_host(headers: &HeaderMap, uri: &Uri) -> Option<String> {
+    headers
+        .get(header::HOST)
+        .and_then(|value| value.to_str().ok())
+        .or_else(|| uri.host())
+        .map(normalize_host)
+        .filter(|host| !host.is_empty())
+}
+
+/// Selects the app serving a request.
+///
+/// Returns the app and the path and query it routes, or `None` if no app
+/// is selected and there is no default app.
+pub(crate) fn select<'a>(
+    apps: &'a [MountedApp],
+    default_app: Option<&str>,
+    host: Option<&str>,
+    path_and_query: &str,
+) -> Option<(&'a MountedApp, String)> {
+    let best = |host_match: Option<&str>| {
+        apps.iter()
+            .filter(|app| app.host.as_deref() == host_match)
+            .filter_map(|app| Some((app, app.strip_prefix(path_and_query)?)))
+            .max_by_key(|(app, _)| app.prefix_len())
+    };
+
+    host.and_then(|host| best(Some(host)))
+        .or_else(|| best(None))
+        .or_else(|| {
+            let app = apps
+                .iter()
+                .find(|app| Some(app.name.as_str()) == default_app)?;
+            let path = app
+                .strip_prefix(path_and_query)
+                .unwrap_or_else(|| path_and_query.to_string());
+            Some((app, path))
+        })
+}
+
+/// Checks the mounted apps for names, hosts and routes that overlap.
+///
+/// # Errors
+///
+/// Returns [`ServerError::InvalidConfig`] describing the first conflict.
+pub(crate) fn check_conflicts(
+    apps: &[MountedApp],
+    default_app: Option<&str>,
+    internal: &InternalRoutes,
+) -> Result<(), ServerError> {
+    let invalid = |msg: String| Err(ServerError::InvalidConfig(msg));
+
+    let mut names = HashSet::new();
+    for app in apps {
+        if !names.insert(app.name.as_str()) {
+            return invalid(format!("app '{}' is registered twice", app.name));
+        }
+        if app.host.is_none() && app.path_prefix.is_none() && default_app != Some(app.name.as_str())
+        {
+            return invalid(format!(
+                "app '{}' has neither a host nor a path prefix and is not the default app",
+                app.name
+            ));
+        }
+    }
+    if let Some(default_app) = default_app {
+        if !names.contains(default_app) {
+            return invalid(format!("default app '{}' is not registered", default_app));
+        }
+    }
+
+    for (i, outer) in apps.iter().enumerate() {
+        for inner in &apps[i + 1..] {
+            if outer.host != inner.host {
+                continue;
+            }
+            if outer.path_prefix == inner.path_prefix {
+                return invalid(format!(
+                    "apps '{}' and '{}' are both served at {}",
+                    outer.name,
+                    inner.name,
+                    describe_mount(outer)
+                ));
+            }
+            check_shadowed(outer, inner)?;
+            check_shadowed(inner, outer)?;
+        }
+    }
+
+    for app in apps {
+        for (method, path) in internal.routes() {
+            let Some(path) = app.strip_prefix(path) else {
+                continue;
+            };
+            if let Some(route_match) = app.server.router().match_route(method, &path) {
+                return invalid(format!(
+                    "internal route {} {} shadows operation '{}' of app '{}'",
+                    method,
+                    path,
+                    route_match.operation_id(),
+                    app.name
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Fails if `inner`'s prefix would capture requests for `app`'s routes.
+fn check_shadowed(app: &MountedApp, inner: &MountedApp) -> Result<(), ServerError> {
+    let Some(inner_prefix) = &inner.path_prefix else {
+        return Ok(());
+    };
+    // Requests under the inner prefix, as the app would route them
+    let Some(captured) = app.strip_prefix(inner_prefix) else {
+        return Ok(());
+    };
+
+    let router = app.server.router();
+    let mut operations: Vec<&str> = router.operation_ids().collect();
+    operations.sort_unstable();
+    for operation_id in operations {
+        let Some(pattern) = router.route_pattern(operation_id) else {
+            continue;
+        };
+        if pattern_reaches(pattern, &captured) {
+            return Err(ServerError::InvalidConfig(format!(
+                "route {} of app '{}' (operation '{}') is captured by app '{}' at {}",
+                pattern,
+                app.name,
+                operation_id,
+                inner.name,
+                describe_mount(inner)
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Returns true if some path matching `pattern` lies under `prefix`.
+fn pattern_reaches(pattern: &str, prefix: &str) -> bool {
+    let mut segments = pattern.split('/').filter(|s| !s.is_empty());
+    for expected in prefix.split('/').filter(|s| !s.is_empty()) {
+        match segments.next() {
+            Some(segment) if segment.starts_with('*') => return true,
+            Some(segment) if segment.starts_with('{') || segment == expected => {}
+            _ => return false,
+        }
+    }
+    true
+}
+
+/// Describes where an app is mounted, for error messages.
+fn describe_mount(app: &MountedApp) -> String {
+    format!(
+        "host '{}' prefix '{}'",
+        app.host.as_deref().unwrap_or("*"),
+        app.path_prefix.as_deref().unwrap_or("/")
+    )
+}
+
+/// Lowercases a host and removes its port.
+fn normalize_host(host: &str) -> String {
+    let host = host.trim();
+    let host = if host.starts_with('[') {
+        // IPv6 literal, e.g. [::1]:8080
+        host.find(']').map_or(host, |end| &host[..=end])
+    } else {
+        host.rsplit_once(':').map_or(host, |(name, _)| name)
+    };
+    host.trim_end_matches('.').to_ascii_lowercase()
+}
+
+/// Normalizes a path prefix to `/segment[/segment...]`, or `None` for the
+/// root.
+fn normalize_prefix(prefix: &str) -> Option<String> {
+    let trimmed = prefix.trim().trim_matches('/');
+    (!trimmed.is_empty()).then(|| format!("/{trimmed}"))
+}
+
+/// Joins the server base path and an app prefix into the app's base path.
+pub(crate) fn join_base_path(base_path: Option<&str>, prefix: Option<&str>) -> Option<String> {
+    match (base_path, prefix) {
+        (Some(base), Some(prefix)) => Some(format!("{}{}", base.trim_end_matches('/'), prefix)),
+        (Some(base), None) => Some(base.to_string()),
+        (None, prefix) => prefix.map(ToString::to_string),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::{HeaderValue, Method};
+
+    fn mounted(
+        name: &str,
+        host: Option<&str>,
+        prefix: Option<&str>,
+        routes: &[&str],
+    ) -> MountedApp {
+        let mut spec = AppSpec::new(name);
+        if let Some(host) = host {
+            spec = spec.host(host);
+        }
+        if let Some(prefix) = prefix {
+            spec = spec.path_prefix(prefix);
+        }
+        for (i, pattern) in routes.iter().enumerate() {
+            spec.router
+                .add_route(Method::GET, pattern, format!("{name}Op{i}"));
+        }
+        Server::builder().build().mount(spec)
+    }
+
+    #[test]
+    fn test_request_host() {
+        let mut headers = HeaderMap::new();
+        let uri = Uri::from_static("https://API.example.com:8443/users");
+        assert_eq!(
+            request_host(&headers, &uri).as_deref(),
+            Some("api.example.com")
+        );
+
+        headers.insert(
+            header::HOST,
+            HeaderValue::from_static("Admin.Example.com:80"),
+        );
+        assert_eq!(
+            request_host(&headers, &uri).as_deref(),
+            Some("admin.example.com")
+        );
+
+        headers.insert(header::HOST, HeaderValue::from_static("[::1]:8080"));
+        assert_eq!(request_host(&headers, &uri).as_deref(), Some("[::1]"));
+
+        assert_eq!(
+            request_host(&HeaderMap::new(), &Uri::from_static("/users")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_select_prefers_host_then_longest_prefix() {
+        let apps = vec![
+            mounted("api", Some("api.example.com"), None, &["/users"]),
+            mounted("admin", Some("admin.example.com"), None, &["/users"]),
+            mounted("docs", None, Some("/docs"), &["/{page}"]),
+            mounted(
+                "reports",
+                Some("api.example.com"),
+                Some("/reports"),
+                &["/daily"],
+            ),
+        ];
+
+        let pick = |host: Option<&str>, path: &str| {
+            select(&apps, None, host, path).map(|(app, path)| (app.name.clone(), path))
+        };
+
+        assert_eq!(
+            pick(Some("admin.example.com"), "/users?limit=5"),
+            Some(("admin".to_string(), "/users?limit=5".to_string()))
+        );
+        assert_eq!(
+            pick(Some("api.example.com"), "/reports/daily"),
+            Some(("reports".to_string(), "/daily".to_string()))
+        );
+        assert_eq!(
+            pick(Some("api.example.com"), "/reportsx"),
+            Some(("api".to_string(), "/reportsx".to_string()))
+        );
+        assert_eq!(
+            pick(Some("other.example.com"), "/docs"),
+            Some(("docs".to_string(), "/".to_string()))
+        );
+        assert_eq!(pick(Some("other.example.com"), "/users"), None);
+        assert_eq!(pick(None, "/users"), None);
+    }
+
+    #[test]
+    fn test_select_falls_back_to_default_app() {
+        let apps = vec![
+            mounted("api", Some("api.example.com"), None, &["/users"]),
+            mounted("admin", Some("admin.example.com"), None, &["/users"]),
+        ];
+
+        let (app, path) = select(&apps, Some("api"), None, "/users").unwrap();
+        assert_eq!(app.name, "api");
+        assert_eq!(path, "/users");
+    }
+
+    #[test]
+    fn test_conflicting_mounts() {
+        let internal = InternalRoutes::new();
+        let apps = vec![
+            mounted("api", Some("api.example.com"), None, &["/users"]),
+            mounted("admin", Some("admin.example.com"), None, &["/users"]),
+        ];
+        assert!(check_conflicts(&apps, None, &internal).is_ok());
+
+        let apps = vec![
+            mounted("api", Some("api.example.com"), None, &["/users"]),
+            mounted("api2", Some("API.example.com"), None, &["/orders"]),
+        ];
+        match check_conflicts(&apps, None, &internal) {
+            Err(ServerError::InvalidConfig(msg)) => {
+                assert!(msg.contains("'api' and 'api2'"), "{msg}");
+            }
+            other => panic!("Expected InvalidConfig, got {other:?}"),
+        }
+
+        let apps = vec![
+            mounted("api", Some("api.example.com"), None, &["/users"]),
+            mounted("api", Some("admin.example.com"), None, &["/users"]),
+        ];
+        assert!(check_conflicts(&apps, None, &internal).is_err());
+
+        let apps = vec![mounted("api", None, None, &["/users"])];
+        assert!(check_conflicts(&apps, None, &internal).is_err());
+        assert!(check_conflicts(&apps, Some("api"), &internal).is_ok());
+        assert!(check_conflicts(&apps, Some("missing"), &internal).is_err());
+    }
+
+    #[test]
+    fn test_prefix_capturing_routes_conflicts() {
+        let internal = InternalRoutes::new();
+        let apps = vec![
+            mounted("api", None, Some("/api"), &["/users/{id}", "/admin-tools"]),
+            mounted("admin", None, Some("/api/users/admin"), &["/audit"]),
+        ];
+        match check_conflicts(&apps, None, &internal) {
+            Err(ServerError::InvalidConfig(msg)) => {
+                assert!(msg.contains("/users/{id}"), "{msg}");
+                assert!(msg.contains("'admin'"), "{msg}");
+            }
+            other => panic!("Expected InvalidConfig, got {other:?}"),
+        }
+
+        let apps = vec![
+            mounted("api", None, Some("/api"), &["/users/{id}"]),
+            mounted("admin", None, Some("/api/admin"), &["/audit"]),
+        ];
+        assert!(check_conflicts(&apps, None, &internal).is_ok());
+    }
+
+    #[test]
+    fn test_internal_route_shadowing_app_route() {
+        let internal = InternalRoutes::with_builtins(false, false);
+        let apps = vec![mounted("api", Some("api.example.com"), None, &["/health"])];
+        assert!(check_conflicts(&apps, None, &internal).is_err());
+    }
+
+    #[test]
+    fn test_pattern_reaches() {
+        assert!(pattern_reaches("/admin/users", "/admin"));
+        assert!(pattern_reaches("/{section}/users", "/admin"));
+        assert!(pattern_reaches("/files/*path", "/files/a/b"));
+        assert!(pattern_reaches("/admin", "/admin"));
+        assert!(!pattern_reaches("/users", "/admin"));
+        assert!(!pattern_reaches("/admin", "/admin/users"));
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize_prefix("admin/"), Some("/admin".to_string()));
+        assert_eq!(normalize_prefix("/"), None);
+        assert_eq!(
+            join_base_path(Some("/v1/"), Some("/admin")),
+            Some("/v1/admin".to_string())
+        );
+        assert_eq!(
+            join_base_path(None, Some("/admin")),
+            Some("/admin".to_string())
+        );
+    }
+}
@@ -0,0 +1,441 @@
+//! Batch request endpoint.
+//!
+//! Clients can bundle several operations into one HTTP call by posting a
+//! JSON array of sub-requests to [`BATCH_PATH`]:
+//!
+//! ```json
+//! [
+//!   { "id": "a", "method": "GET", "path": "/users/1" },
+//!   { "id": "b", "method": "GET", "path": "/users/2", "headers": { "authorization": "Bearer ..." } }
+//! ]
+//! ```
+//!
+//! Each sub-request is dispatched through the same path as a regular
+//! request, including the middleware pipeline, so authorization and
+//! validation apply per sub-request. A failing sub-request only fails its
+//! own entry. The response is a JSON array pairing each `id` with the
+//! sub-request's status, a subset of its headers and its body.
+//!
+//! Sub-requests inherit the headers of the batch request, except
+//! `content-length` and `content-type`; headers given on the sub-request
+//! take precedence. Sub-requests may not target the batch endpoint itself.
+//!
+//! The endpoint is disabled by default.
+//!
+//! # Example
+//!
+//! ```rust
+//! use archimedes_server::batch::BatchConfig;
+//!
+//! let config = BatchConfig::new()
+//!     .enabled(true)
+//!     .max_requests(10)
+//!     .concurrency(4);
+//!
+//! assert!(config.is_enabled());
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+
+use http::{HeaderMap, StatusCode};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Path of the batch endpoint.
+pub const BATCH_PATH: &str = "/-/batch";
+
+/// Default maximum number of sub-requests per batch.
+pub const DEFAULT_MAX_REQUESTS: usize = 20;
+
+/// Default maximum batch body size in bytes (1 MiB).
+pub const DEFAULT_MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// Default number of sub-requests executed concurrently.
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+/// Response headers copied into each sub-response.
+pub const FORWARDED_RESPONSE_HEADERS: &[&str] = &[
+    "content-type",
+    "cache-control",
+    "etag",
+    "last-modified",
+    "location",
+    "retry-after",
+    "www-authenticate",
+];
+
+/// Batch request headers not inherited by sub-requests.
+const NON_INHERITED_HEADERS: &[&str] = &["content-length", "content-type"];
+
+/// Configuration for the batch endpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchConfig {
+    enabled: bool,
+    max_requests: usize,
+    max_body_bytes: usize,
+    concurrency: usize,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_requests: DEFAULT_MAX_REQUESTS,
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            concurrency: DEFAULT_CONCURRENCY,
+        }
+    }
+}
+
+impl BatchConfig {
+    /// Creates a batch configuration with default limits (disabled).
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables or disables the batch endpoint.
+    #[must_use]
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Sets the maximum number of sub-requests per batch.
+    #[must_use]
+    pub fn max_requests(mut self, max: usize) -> Self {
+        self.max_requests = max;
+        self
+    }
+
+    /// Sets the maximum batch body size in bytes.
+    #[must_use]
+    pub fn max_body_bytes(mut self, max: usize) -> Self {
+        self.max_body_bytes = max;
+        self
+    }
+
+    /// Sets how many sub-requests run concurrently (at least 1).
+    #[must_use]
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Returns whether the batch endpoint is enabled.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns the maximum number of sub-requests per batch.
+    #[must_use]
+    pub fn max_request_count(&self) -> usize {
+        self.max_requests
+    }
+
+    /// Returns the maximum batch body size in bytes.
+    #[must_use]
+    pub fn max_body_size(&self) -> usize {
+        self.max_body_bytes
+    }
+
+    /// Returns how many sub-requests run concurrently.
+    #[must_use]
+    pub fn concurrency_limit(&self) -> usize {
+        self.concurrency
+    }
+
+    /// Parses and checks a batch body against the configured limits.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the body exceeds the size limit, is not a JSON
+    /// array of sub-requests, or holds more sub-requests than allowed.
+    pub fn parse(&self, body: &[u8]) -> Result<Vec<BatchSubRequest>, BatchError> {
+        if body.len() > self.max_body_bytes {
+            return Err(BatchError::TooLarge {
+                size: body.len(),
+                max: self.max_body_bytes,
+            });
+        }
+
+        let requests: Vec<BatchSubRequest> =
+            serde_json::from_slice(body).map_err(|e| BatchError::InvalidBody(e.to_string()))?;
+
+        if requests.len() > self.max_requests {
+            return Err(BatchError::TooManyRequests {
+                count: requests.len(),
+                max: self.max_requests,
+            });
+        }
+
+        Ok(requests)
+    }
+}
+
+/// A single sub-request within a batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchSubRequest {
+    /// Client-chosen identifier echoed in the matching sub-response.
+    pub id: String,
+    /// HTTP method.
+    pub method: String,
+    /// Request path, optionally with a query string.
+    pub path: String,
+    /// Request headers.
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// JSON request body.
+    #[serde(default)]
+    pub body: Option<serde_json::Value>,
+}
+
+impl BatchSubRequest {
+    /// Returns true if the sub-request targets the batch endpoint.
+    #[must_use]
+    pub fn is_recursive(&self) -> bool {
+        is_batch_path(&self.path)
+    }
+
+    /// Builds the sub-request headers from the batch request headers.
+    pub(crate) fn merged_headers(&self, inherited: &HeaderMap) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in inherited {
+            if !NON_INHERITED_HEADERS.contains(&name.as_str()) {
+                headers.append(name.clone(), value.clone());
+            }
+        }
+        for (name, value) in &self.headers {
+            if let (Ok(name), Ok(value)) = (
+                http::header::HeaderName::from_bytes(name.as_bytes()),
+                http::header::HeaderValue::from_str(value),
+            ) {
+                headers.insert(name, value);
+            }
+        }
+        if self.body.is_some() {
+            headers.insert(
+                http::header::CONTENT_TYPE,
+                http::header::HeaderValue::from_static("application/json"),
+            );
+        }
+        headers
+    }
+}
+
+/// The outcome of a single sub-request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchSubResponse {
+    /// Identifier of the matching sub-request.
+    pub id: String,
+    /// HTTP status code.
+    pub status: u16,
+    /// Subset of the response headers (see [`FORWARDED_RESPONSE_HEADERS`]).
+    pub headers: BTreeMap<String, String>,
+    /// Response body; JSON when the handler returned JSON, otherwise a string.
+    pub body: serde_json::Value,
+}
+
+impl BatchSubResponse {
+    /// Builds a sub-response from a dispatched response's parts.
+    pub(crate) fn new(id: String, status: StatusCode, headers: &HeaderMap, body: &[u8]) -> Self {
+        let headers = FORWARDED_RESPONSE_HEADERS
+            .iter()
+            .filter_map(|name| {
+                headers
+                    .get(*name)
+                    .and_then(|v| v.to_str().ok())
+                    .map(|v| ((*name).to_string(), v.to_string()))
+            })
+            .collect();
+
+        let body = if body.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_slice(body).unwrap_or_else(|_| {
+                serde_json::Value::String(String::from_utf8_lossy(body).into_owned())
+            })
+        };
+
+        Self {
+            id,
+            status: status.as_u16(),
+            headers,
+            body,
+        }
+    }
+
+    /// Builds an error sub-response using the standard error envelope.
+    pub(crate) fn error(id: String, status: StatusCode, code: &str, message: &str) -> Self {
+        let mut headers = BTreeMap::new();
+        headers.insert("content-type".to_string(), "application/json".to_string());
+        Self {
+            id,
+            status: status.as_u16(),
+            headers,
+            body: serde_json::json!({
+                "error": {
+                    "code": code,
+                    "message": message
+                }
+            }),
+        }
+    }
+}
+
+/// Errors that reject a whole batch.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum BatchError {
+    /// The batch body exceeds the size limit.
+    #[error("Batch body of {size} bytes exceeds the limit of {max} bytes")]
+    TooLarge {
+        /// Body size in bytes.
+        size: usize,
+        /// Configured limit in bytes.
+        max: usize,
+    },
+
+    /// The batch holds more sub-requests than allowed.
+    #[error("Batch of {count} requests exceeds the limit of {max}")]
+    TooManyRequests {
+        /// Number of sub-requests.
+        count: usize,
+        /// Configured limit.
+        max: usize,
+    },
+
+    /// The batch body is not a JSON array of sub-requests.
+    #[error("Invalid batch body: {0}")]
+    InvalidBody(String),
+}
+
+impl BatchError {
+    /// Returns the HTTP status code for this error.
+    #[must_use]
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            Self::TooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::TooManyRequests { .. } | Self::InvalidBody(_) => StatusCode::BAD_REQUEST,
+        }
+    }
+
+    /// Returns the error code used in the error envelope.
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::TooLarge { .. } => "BATCH_TOO_LARGE",
+            Self::TooManyRequests { .. } => "BATCH_TOO_MANY_REQUESTS",
+            Self::InvalidBody(_) => "INVALID_BATCH",
+        }
+    }
+}
+
+/// Returns true if a path (with optional query string) is the batch endpoint.
+pub(crate) fn is_batch_path(path: &str) -> bool {
+    let path = path.split('?').next().unwrap_or_default();
+    let path = path.trim_end_matches('/');
+    path.eq_ignore_ascii_case(BATCH_PATH)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_defaults() {
+        let config = BatchConfig::default();
+        assert!(!config.is_enabled());
+        assert_eq!(config.max_request_count(), DEFAULT_MAX_REQUESTS);
+        assert_eq!(config.max_body_size(), DEFAULT_MAX_BODY_BYTES);
+        assert_eq!(config.concurrency_limit(), DEFAULT_CONCURRENCY);
+        assert_eq!(BatchConfig::new().concurrency(0).concurrency_limit(), 1);
+    }
+
+    #[test]
+    fn test_parse_batch() {
+        let config = BatchConfig::new().enabled(true);
+        let body = br#"[
+            {"id": "a", "method": "GET", "path": "/users/1"},
+            {"id": "b", "method": "POST", "path": "/users", "headers": {"x-tenant": "t1"}, "body": {"name": "Bob"}}
+        ]"#;
+
+        let requests = config.parse(body).unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].id, "a");
+        assert!(requests[0].headers.is_empty());
+        assert_eq!(requests[1].headers["x-tenant"], "t1");
+        assert_eq!(requests[1].body, Some(serde_json::json!({"name": "Bob"})));
+    }
+
+    #[test]
+    fn test_parse_limits() {
+        let config = BatchConfig::new().max_requests(1).max_body_bytes(200);
+        let body =
+            br#"[{"id":"a","method":"GET","path":"/a"},{"id":"b","method":"GET","path":"/b"}]"#;
+        let err = config.parse(body).unwrap_err();
+        assert_eq!(err.code(), "BATCH_TOO_MANY_REQUESTS");
+
+        let err = config.parse(&[b' '; 201]).unwrap_err();
+        assert_eq!(err.status_code(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let err = config.parse(br#"{"id":"a"}"#).unwrap_err();
+        assert_eq!(err.code(), "INVALID_BATCH");
+    }
+
+    #[test]
+    fn test_recursion_detection() {
+        let sub = |path: &str| BatchSubRequest {
+            id: "a".to_string(),
+            method: "POST".to_string(),
+            path: path.to_string(),
+            headers: HashMap::new(),
+            body: None,
+        };
+
+        assert!(sub("/-/batch").is_recursive());
+        assert!(sub("/-/batch/").is_recursive());
+        assert!(sub("/-/BATCH?x=1").is_recursive());
+        assert!(!sub("/users").is_recursive());
+    }
+
+    #[test]
+    fn test_merged_headers() {
+        let mut inherited = HeaderMap::new();
+        inherited.insert("authorization", "Bearer outer".parse().unwrap());
+        inherited.insert("content-length", "512".parse().unwrap());
+
+        let mut sub = BatchSubRequest {
+            id: "a".to_string(),
+            method: "GET".to_string(),
+            path: "/users".to_string(),
+            headers: HashMap::new(),
+            body: None,
+        };
+        let headers = sub.merged_headers(&inherited);
+        assert_eq!(headers["authorization"], "Bearer outer");
+        assert!(headers.get("content-length").is_none());
+
+        sub.headers
+            .insert("Authorization".to_string(), "Bearer inner".to_string());
+        let headers = sub.merged_headers(&inherited);
+        assert_eq!(headers["authorization"], "Bearer inner");
+    }
+
+    #[test]
+    fn test_sub_response_body_and_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", "application/json".parse().unwrap());
+        headers.insert("x-internal", "hidden".parse().unwrap());
+
+        let response =
+            BatchSubResponse::new("a".to_string(), StatusCode::OK, &headers, br#"{"ok":true}"#);
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, serde_json::json!({"ok": true}));
+        assert_eq!(response.headers.len(), 1);
+
+        let response =
+            BatchSubResponse::new("b".to_string(), StatusCode::OK, &HeaderMap::new(), b"plain");
+        assert_eq!(response.body, serde_json::json!("plain"));
+    }
+}
@@ -20,6 +20,8 @@
 use std::net::SocketAddr;
 use std::time::Duration;
 
+use crate::server::ServerError;
+
 /// Default HTTP bind address.
 pub const DEFAULT_HTTP_ADDR: &str = "0.0.0.0:8080";
 
@@ -29,6 +31,9 @@ pub const DEFAULT_SHUTDOWN_TIMEOUT_SECS: u64 = 30;
 /// Default keep-alive timeout in seconds.
 pub const DEFAULT_KEEP_ALIVE_SECS: u64 = 75;
 
+/// Largest HTTP/2 flow control window allowed by RFC 9113 (2^31 - 1).
+pub const MAX_HTTP2_WINDOW_SIZE: u32 = (1 << 31) - 1;
+
 /// Server configuration.
 ///
 /// Contains all settings needed to configure the HTTP server.
@@ -49,6 +54,25 @@ pub struct ServerConfig {
 
     /// Whether to enable HTTP/2 (default: true)
     http2_enabled: bool,
+
+    /// Requests served per HTTP/1 connection before `Connection: close`
+    max_requests_per_connection: Option<u64>,
+
+    /// HTTP/2 protocol settings
+    http2: Http2Settings,
+}
+
+/// HTTP/2 connection settings.
+///
+/// Unset values fall back to hyper's defaults.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Http2Settings {
+    max_concurrent_streams: Option<u32>,
+    initial_stream_window_size: Option<u32>,
+    initial_connection_window_size: Option<u32>,
+    keep_alive_interval: Option<Duration>,
+    keep_alive_timeout: Option<Duration>,
+    max_header_list_size: Option<u32>,
 }
 
 impl ServerConfig {
@@ -106,6 +130,115 @@ impl ServerConfig {
     pub fn http2_enabled(&self) -> bool {
         self.http2_enabled
     }
+
+    /// Returns the number of requests served on an HTTP/1 connection before
+    /// it is closed, if configured.
+    #[must_use]
+    pub fn max_requests_per_connection(&self) -> Option<u64> {
+        self.max_requests_per_connection
+    }
+
+    /// Returns the maximum number of concurrent HTTP/2 streams, if configured.
+    #[must_use]
+    pub fn http2_max_concurrent_streams(&self) -> Option<u32> {
+        self.http2.max_concurrent_streams
+    }
+
+    /// Returns the initial HTTP/2 stream window size, if configured.
+    #[must_use]
+    pub fn http2_initial_stream_window_size(&self) -> Option<u32> {
+        self.http2.initial_stream_window_size
+    }
+
+    /// Returns the initial HTTP/2 connection window size, if configured.
+    #[must_use]
+    pub fn http2_initial_connection_window_size(&self) -> Option<u32> {
+        self.http2.initial_connection_window_size
+    }
+
+    /// Returns the HTTP/2 keep-alive ping interval, if configured.
+    #[must_use]
+    pub fn http2_keep_alive_interval(&self) -> Option<Duration> {
+        self.http2.keep_alive_interval
+    }
+
+    /// Returns the HTTP/2 keep-alive ping timeout, if configured.
+    #[must_use]
+    pub fn http2_keep_alive_timeout(&self) -> Option<Duration> {
+        self.http2.keep_alive_timeout
+    }
+
+    /// Returns the maximum HTTP/2 header list size, if configured.
+    #[must_use]
+    pub fn http2_max_header_list_size(&self) -> Option<u32> {
+        self.http2.max_header_list_size
+    }
+
+    /// Validates connection settings against protocol bounds.
+    ///
+    /// Called before the server binds, so misconfiguration fails fast.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServerError::InvalidConfig`] if a limit or timeout is zero,
+    /// or an HTTP/2 window size exceeds [`MAX_HTTP2_WINDOW_SIZE`].
+    pub fn validate(&self) -> Result<(), ServerError> {
+        let positive = [
+            (
+                "keep_alive_timeout",
+                self.keep_alive_timeout.map(|d| d.as_nanos()),
+            ),
+            (
+                "max_requests_per_connection",
+                self.max_requests_per_connection.map(u128::from),
+            ),
+            (
+                "http2_max_concurrent_streams",
+                self.http2.max_concurrent_streams.map(u128::from),
+            ),
+            (
+                "http2_keep_alive_interval",
+                self.http2.keep_alive_interval.map(|d| d.as_nanos()),
+            ),
+            (
+                "http2_keep_alive_timeout",
+                self.http2.keep_alive_timeout.map(|d| d.as_nanos()),
+            ),
+            (
+                "http2_max_header_list_size",
+                self.http2.max_header_list_size.map(u128::from),
+            ),
+        ];
+        for (field, value) in positive {
+            if value == Some(0) {
+                return Err(ServerError::InvalidConfig(format!(
+                    "{field} must be greater than zero"
+                )));
+            }
+        }
+
+        let windows = [
+            (
+                "http2_initial_stream_window_size",
+                self.http2.initial_stream_window_size,
+            ),
+            (
+                "http2_initial_connection_window_size",
+                self.http2.initial_connection_window_size,
+            ),
+        ];
+        for (field, value) in windows {
+            if let Some(size) = value {
+                if size == 0 || size > MAX_HTTP2_WINDOW_SIZE {
+                    return Err(ServerError::InvalidConfig(format!(
+                        "{field} must be between 1 and {MAX_HTTP2_WINDOW_SIZE}, got {size}"
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl Default for ServerConfig {
@@ -124,6 +257,8 @@ pub struct ServerConfigBuilder {
     keep_alive_timeout: Option<Duration>,
     max_connections: Option<usize>,
     http2_enabled: bool,
+    max_requests_per_connection: Option<u64>,
+    http2: Http2Settings,
 }
 
 impl ServerConfigBuilder {
@@ -136,6 +271,8 @@ impl ServerConfigBuilder {
             keep_alive_timeout: Some(Duration::from_secs(DEFAULT_KEEP_ALIVE_SECS)),
             max_connections: None,
             http2_enabled: true,
+            max_requests_per_connection: None,
+            http2: Http2Settings::default(),
         }
     }
 
@@ -222,6 +359,78 @@ impl ServerConfigBuilder {
         self
     }
 
+    /// Sets how many requests an HTTP/1 connection serves before closing.
+    ///
+    /// The response to the last request carries `Connection: close`, so
+    /// clients reconnect and load balancers can rebalance long-lived
+    /// connections. HTTP/2 connections are not affected.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use archimedes_server::ServerConfig;
+    ///
+    /// let config = ServerConfig::builder()
+    ///     .max_requests_per_connection(Some(1000))
+    ///     .build();
+    ///
+    /// assert_eq!(config.max_requests_per_connection(), Some(1000));
+    /// ```
+    #[must_use]
+    pub fn max_requests_per_connection(mut self, max: Option<u64>) -> Self {
+        self.max_requests_per_connection = max;
+        self
+    }
+
+    /// Sets the maximum number of concurrent HTTP/2 streams per connection.
+    #[must_use]
+    pub fn http2_max_concurrent_streams(mut self, max: Option<u32>) -> Self {
+        self.http2.max_concurrent_streams = max;
+        self
+    }
+
+    /// Sets the initial HTTP/2 stream-level flow control window, in bytes.
+    ///
+    /// Must not exceed [`MAX_HTTP2_WINDOW_SIZE`].
+    #[must_use]
+    pub fn http2_initial_stream_window_size(mut self, size: Option<u32>) -> Self {
+        self.http2.initial_stream_window_size = size;
+        self
+    }
+
+    /// Sets the initial HTTP/2 connection-level flow control window, in bytes.
+    ///
+    /// Must not exceed [`MAX_HTTP2_WINDOW_SIZE`].
+    #[must_use]
+    pub fn http2_initial_connection_window_size(mut self, size: Option<u32>) -> Self {
+        self.http2.initial_connection_window_size = size;
+        self
+    }
+
+    /// Sets the interval between HTTP/2 keep-alive pings.
+    ///
+    /// Set to `None` to disable pings (default).
+    #[must_use]
+    pub fn http2_keep_alive_interval(mut self, interval: Option<Duration>) -> Self {
+        self.http2.keep_alive_interval = interval;
+        self
+    }
+
+    /// Sets how long to wait for an HTTP/2 keep-alive ping acknowledgement
+    /// before closing the connection.
+    #[must_use]
+    pub fn http2_keep_alive_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.http2.keep_alive_timeout = timeout;
+        self
+    }
+
+    /// Sets the maximum HTTP/2 header list size, in bytes.
+    #[must_use]
+    pub fn http2_max_header_list_size(mut self, size: Option<u32>) -> Self {
+        self.http2.max_header_list_size = size;
+        self
+    }
+
     /// Builds the [`ServerConfig`] with the configured values.
     ///
     /// # Example
@@ -241,6 +450,8 @@ impl ServerConfigBuilder {
             keep_alive_timeout: self.keep_alive_timeout,
             max_connections: self.max_connections,
             http2_enabled: self.http2_enabled,
+            max_requests_per_connection: self.max_requests_per_connection,
+            http2: self.http2,
         }
     }
 }
@@ -344,6 +555,88 @@ mod tests {
         assert!(config.http2_enabled());
     }
 
+    #[test]
+    fn test_builder_connection_settings() {
+        let config = ServerConfig::builder()
+            .max_requests_per_connection(Some(100))
+            .http2_max_concurrent_streams(Some(256))
+            .http2_initial_stream_window_size(Some(1 << 20))
+            .http2_initial_connection_window_size(Some(MAX_HTTP2_WINDOW_SIZE))
+            .http2_keep_alive_interval(Some(Duration::from_secs(20)))
+            .http2_keep_alive_timeout(Some(Duration::from_secs(5)))
+            .http2_max_header_list_size(Some(16 * 1024))
+            .build();
+
+        assert_eq!(config.max_requests_per_connection(), Some(100));
+        assert_eq!(config.http2_max_concurrent_streams(), Some(256));
+        assert_eq!(config.http2_initial_stream_window_size(), Some(1 << 20));
+        assert_eq!(
+            config.http2_initial_connection_window_size(),
+            Some(MAX_HTTP2_WINDOW_SIZE)
+        );
+        assert_eq!(
+            config.http2_keep_alive_interval(),
+            Some(Duration::from_secs(20))
+        );
+        assert_eq!(
+            config.http2_keep_alive_timeout(),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(config.http2_max_header_list_size(), Some(16 * 1024));
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_default_connection_settings_are_unset() {
+        let config = ServerConfig::default();
+
+        assert!(config.max_requests_per_connection().is_none());
+        assert!(config.http2_max_concurrent_streams().is_none());
+        assert!(config.http2_initial_stream_window_size().is_none());
+        assert!(config.http2_keep_alive_interval().is_none());
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_window_size_out_of_bounds() {
+        let config = ServerConfig::builder()
+            .http2_initial_stream_window_size(Some(MAX_HTTP2_WINDOW_SIZE + 1))
+            .build();
+
+        let err = config.validate().unwrap_err();
+        assert!(matches!(err, ServerError::InvalidConfig(_)));
+        assert!(err
+            .to_string()
+            .contains("http2_initial_stream_window_size must be between 1 and 2147483647"));
+
+        let config = ServerConfig::builder()
+            .http2_initial_connection_window_size(Some(0))
+            .build();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_rejects_zero_limits() {
+        let config = ServerConfig::builder()
+            .max_requests_per_connection(Some(0))
+            .build();
+        assert!(config
+            .validate()
+            .unwrap_err()
+            .to_string()
+            .contains("max_requests_per_connection must be greater than zero"));
+
+        let config = ServerConfig::builder()
+            .http2_keep_alive_interval(Some(Duration::ZERO))
+            .build();
+        assert!(config.validate().is_err());
+
+        let config = ServerConfig::builder()
+            .http2_max_concurrent_streams(Some(0))
+            .build();
+        assert!(config.validate().is_err());
+    }
+
     #[test]
     fn test_config_clone() {
         let config1 = ServerConfig::builder()
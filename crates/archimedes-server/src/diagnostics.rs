@@ -0,0 +1,541 @@
+//! Startup diagnostics report.
+//!
+//! A [`Diagnostics`] snapshot describes how a service was assembled: the
+//! resolved configuration profile, the loaded contract, the policy bundle
+//! revision, the middleware stages, the listener addresses, how well the
+//! registered handlers cover the contract operations and, once it finished,
+//! how long each [warm-up](crate::warmup) step took.
+//!
+//! The server logs the snapshot once at startup and returns it from
+//! [`Server::diagnostics`](crate::Server::diagnostics). When enabled with
+//! [`ServerBuilder::diagnostics_endpoint`](crate::ServerBuilder::diagnostics_endpoint)
+//! it is also served as JSON at [`DIAGNOSTICS_PATH`]. The Prometheus exporter
+//! owns the metrics listener, so the endpoint is served by the main listener
+//! next to `/health` and `/ready`.
+//!
+//! Field names are part of the public contract: dashboards and alerts are
+//! built on them, so renaming a field is a breaking change.
+//!
+//! # Example
+//!
+//! ```rust
+//! use archimedes_server::diagnostics::{ContractInfo, Diagnostics};
+//!
+//! let diagnostics = Diagnostics::new("users-service", "1.2.0")
+//!     .with_config_profile("production")
+//!     .with_contract(ContractInfo::new("users", "2.0.0", ["getUser", "listUsers"]))
+//!     .with_policy_revision("bundle-42")
+//!     .with_middleware_stages(["request_id", "tracing", "identity", "cors"]);
+//!
+//! assert_eq!(diagnostics.middleware.optional_stages, vec!["cors"]);
+//! ```
+
+use archimedes_core::ContractOperation;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use crate::warmup::WarmupReport;
+
+/// Path of the diagnostics admin endpoint.
+pub const DIAGNOSTICS_PATH: &str = "/-/diagnostics";
+
+/// Replacement value for redacted configuration fields.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Core middleware stages, in pipeline order.
+///
+/// Any other stage name reported to [`Diagnostics::with_middleware_stages`]
+/// is listed as optional.
+pub const CORE_STAGES: &[&str] = &[
+    "request_id",
+    "tracing",
+    "identity",
+    "authorization",
+    "request_validation",
+    "response_validation",
+    "telemetry",
+    "error_normalization",
+];
+
+/// Key fragments that mark a configuration field as secret-bearing.
+const SECRET_KEY_FRAGMENTS: &[&str] = &[
+    "secret",
+    "password",
+    "passwd",
+    "token",
+    "api_key",
+    "apikey",
+    "private_key",
+    "credential",
+    "authorization",
+];
+
+/// Structured diagnostics snapshot of a running service.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Diagnostics {
+    /// Service name.
+    pub service: String,
+    /// Service version.
+    pub version: String,
+    /// Resolved configuration profile (e.g., "production").
+    pub config_profile: Option<String>,
+    /// Configuration snapshot with secret-bearing fields redacted.
+    pub config: serde_json::Value,
+    /// Loaded contract, if any (the default version when several are served).
+    pub contract: Option<ContractInfo>,
+    /// All contract versions served side by side, when more than one.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contract_versions: Vec<ContractInfo>,
+    /// Loaded policy bundle, if any.
+    pub policy: Option<PolicyInfo>,
+    /// Enabled middleware stages.
+    pub middleware: MiddlewareInfo,
+    /// Listener addresses.
+    pub listeners: Vec<ListenerInfo>,
+    /// Handler coverage of the contract operations.
+    pub handlers: HandlerCoverage,
+    /// Apps served on the listener, when the server hosts several.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub apps: Vec<AppInfo>,
+    /// Warm-up step timings, once warm-up finished.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warmup: Option<WarmupReport>,
+}
+
+impl Diagnostics {
+    /// Creates an empty diagnostics snapshot for a service.
+    #[must_use]
+    pub fn new(service: impl Into<String>, version: impl Into<String>) -> Self {
+        Self {
+            service: service.into(),
+            version: version.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the resolved configuration profile.
+    #[must_use]
+    pub fn with_config_profile(mut self, profile: impl Into<String>) -> Self {
+        self.config_profile = Some(profile.into());
+        self
+    }
+
+    /// Sets the configuration snapshot, redacting secret-bearing fields.
+    ///
+    /// Values that fail to serialize are recorded as `null`.
+    #[must_use]
+    pub fn with_config<C: Serialize>(mut self, config: &C) -> Self {
+        let mut value = serde_json::to_value(config).unwrap_or(serde_json::Value::Null);
+        redact_secrets(&mut value);
+        self.config = value;
+        self
+    }
+
+    /// Sets the loaded contract.
+    #[must_use]
+    pub fn with_contract(mut self, contract: ContractInfo) -> Self {
+        self.contract = Some(contract);
+        self
+    }
+
+    /// Adds a contract version served side by side with others.
+    ///
+    /// The first version added also becomes [`contract`](Self::contract)
+    /// unless one is already set.
+    #[must_use]
+    pub fn with_contract_version(mut self, contract: ContractInfo) -> Self {
+        if self.contract.is_none() {
+            self.contract = Some(contract.clone());
+        }
+        self.contract_versions.push(contract);
+        self
+    }
+
+    /// Returns the operation IDs of every loaded contract version.
+    pub fn contract_operations(&self) -> impl Iterator<Item = &str> {
+        self.contract
+            .iter()
+            .chain(&self.contract_versions)
+            .flat_map(|c| c.operations.iter().map(String::as_str))
+    }
+
+    /// Sets the loaded policy bundle revision.
+    #[must_use]
+    pub fn with_policy_revision(mut self, revision: impl Into<String>) -> Self {
+        self.policy = Some(PolicyInfo {
+            bundle_revision: Some(revision.into()),
+        });
+        self
+    }
+
+    /// Sets the enabled middleware stages, in pipeline order.
+    #[must_use]
+    pub fn with_middleware_stages<I>(mut self, stages: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.middleware = MiddlewareInfo::from_stages(stages);
+        self
+    }
+
+    /// Adds a listener address.
+    #[must_use]
+    pub fn with_listener(mut self, name: impl Into<String>, address: impl Into<String>) -> Self {
+        self.listeners.push(ListenerInfo {
+            name: name.into(),
+            address: address.into(),
+        });
+        self
+    }
+
+    /// Sets the handler coverage report.
+    #[must_use]
+    pub fn with_handler_coverage(mut self, coverage: HandlerCoverage) -> Self {
+        self.handlers = coverage;
+        self
+    }
+
+    /// Sets the warm-up step timings.
+    #[must_use]
+    pub fn with_warmup(mut self, report: WarmupReport) -> Self {
+        self.warmup = Some(report);
+        self
+    }
+
+    /// Logs the snapshot as a single structured event.
+    pub fn log(&self) {
+        let report = serde_json::to_string(self).unwrap_or_default();
+        tracing::info!(
+            service = %self.service,
+            version = %self.version,
+            operations = self.handlers.operations,
+            missing_handlers = self.handlers.missing.len(),
+            diagnostics = %report,
+            "startup diagnostics"
+        );
+    }
+}
+
+/// Contract information.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractInfo {
+    /// Contract service name.
+    pub service: String,
+    /// Contract version.
+    pub version: String,
+    /// Number of operations in the contract.
+    pub operation_count: usize,
+    /// Operation IDs in the contract.
+    pub operations: Vec<String>,
+}
+
+impl ContractInfo {
+    /// Creates contract information from the contract's operation IDs.
+    pub fn new<I>(service: impl Into<String>, version: impl Into<String>, operations: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let operations: Vec<String> = operations.into_iter().map(Into::into).collect();
+        Self {
+            service: service.into(),
+            version: version.into(),
+            operation_count: operations.len(),
+            operations,
+        }
+    }
+}
+
+/// An app served on a shared listener.
+///
+/// See [`AppSpec`](crate::apps::AppSpec).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppInfo {
+    /// App name.
+    pub name: String,
+    /// Host the app is selected by, if any.
+    pub host: Option<String>,
+    /// Path prefix the app is mounted under, if any.
+    pub path_prefix: Option<String>,
+    /// Whether the app serves requests that match no other app.
+    pub default: bool,
+    /// The app's contract, if reported.
+    pub contract: Option<ContractInfo>,
+    /// The app's middleware stages.
+    pub middleware: MiddlewareInfo,
+    /// Handler coverage of the app's operations.
+    pub handlers: HandlerCoverage,
+}
+
+/// Policy bundle information.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyInfo {
+    /// Revision of the loaded policy bundle.
+    pub bundle_revision: Option<String>,
+}
+
+/// Enabled middleware stages.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MiddlewareInfo {
+    /// All enabled stages, in pipeline order.
+    pub stages: Vec<String>,
+    /// Enabled stages that are not part of the core pipeline.
+    pub optional_stages: Vec<String>,
+}
+
+impl MiddlewareInfo {
+    /// Builds the stage report from stage names in pipeline order.
+    pub fn from_stages<I>(stages: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let stages: Vec<String> = stages.into_iter().map(Into::into).collect();
+        let optional_stages = stages
+            .iter()
+            .filter(|s| !CORE_STAGES.contains(&s.as_str()))
+            .cloned()
+            .collect();
+        Self {
+            stages,
+            optional_stages,
+        }
+    }
+}
+
+/// A listener the service accepts connections on.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListenerInfo {
+    /// Listener name (e.g., "http", "metrics").
+    pub name: String,
+    /// Bind address.
+    pub address: String,
+}
+
+/// Coverage of contract operations by registered handlers.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HandlerCoverage {
+    /// Number of operations expected to have a handler.
+    pub operations: usize,
+    /// Number of those operations with a registered handler.
+    pub handled: usize,
+    /// Operations without a registered handler (sorted).
+    pub missing: Vec<String>,
+    /// Registered handlers that match no operation (sorted).
+    pub unknown: Vec<String>,
+    /// Operations whose handlers consume the request body as a stream, so
+    /// their request bodies are not validated (sorted).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unvalidated_bodies: Vec<String>,
+}
+
+impl HandlerCoverage {
+    /// Compares expected operations against registered handlers.
+    pub fn compute<'a, O, H>(operations: O, handlers: H) -> Self
+    where
+        O: IntoIterator<Item = &'a str>,
+        H: IntoIterator<Item = &'a str>,
+    {
+        let operations: BTreeSet<&str> = operations.into_iter().collect();
+        let handlers: BTreeSet<&str> = handlers.into_iter().collect();
+
+        Self {
+            operations: operations.len(),
+            handled: operations.intersection(&handlers).count(),
+            missing: operations
+                .difference(&handlers)
+                .map(ToString::to_string)
+                .collect(),
+            unknown: handlers
+                .difference(&operations)
+                .map(ToString::to_string)
+                .collect(),
+            unvalidated_bodies: Vec::new(),
+        }
+    }
+
+    /// Records the operations whose request bodies are not validated.
+    #[must_use]
+    pub fn with_unvalidated_bodies<'a>(
+        mut self,
+        operations: impl IntoIterator<Item = &'a str>,
+    ) -> Self {
+        let operations: BTreeSet<&str> = operations.into_iter().collect();
+        self.unvalidated_bodies = operations.into_iter().map(ToString::to_string).collect();
+        self
+    }
+
+    /// Compares the operations of a generated operation enum (see
+    /// [`ContractOperation`]) against registered handlers.
+    pub fn for_operations<'a, O, H>(handlers: H) -> Self
+    where
+        O: ContractOperation,
+        H: IntoIterator<Item = &'a str>,
+    {
+        Self::compute(O::ids(), handlers)
+    }
+}
+
+/// Replaces the values of secret-bearing fields with [`REDACTED`].
+///
+/// A field is secret-bearing when its key contains one of a fixed set of
+/// fragments such as `secret`, `password`, `token` or `api_key`, compared
+/// case-insensitively. Only string values are replaced, so flags such as
+/// `enable_authorization` are kept. Nested objects and arrays are redacted
+/// recursively.
+pub fn redact_secrets(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                if field.is_string() && is_secret_key(key) {
+                    *field = serde_json::Value::String(REDACTED.to_string());
+                } else {
+                    redact_secrets(field);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact_secrets),
+        _ => {}
+    }
+}
+
+/// Returns true if a configuration key names a secret.
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase().replace('-', "_");
+    SECRET_KEY_FRAGMENTS.iter().any(|f| key.contains(f))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_middleware_optional_stages() {
+        let info = MiddlewareInfo::from_stages(["cors", "request_id", "rate-limit", "telemetry"]);
+        assert_eq!(info.stages.len(), 4);
+        assert_eq!(info.optional_stages, vec!["cors", "rate-limit"]);
+    }
+
+    #[test]
+    fn test_handler_coverage() {
+        let coverage = HandlerCoverage::compute(
+            ["getUser", "listUsers", "deleteUser"],
+            ["getUser", "listUsers", "legacyOp"],
+        );
+        assert_eq!(coverage.operations, 3);
+        assert_eq!(coverage.handled, 2);
+        assert_eq!(coverage.missing, vec!["deleteUser"]);
+        assert_eq!(coverage.unknown, vec!["legacyOp"]);
+        assert!(coverage.unvalidated_bodies.is_empty());
+    }
+
+    #[test]
+    fn test_handler_coverage_unvalidated_bodies() {
+        let coverage = HandlerCoverage::compute(["ingestEvents", "getUser"], ["ingestEvents"])
+            .with_unvalidated_bodies(["ingestEvents"]);
+        assert_eq!(coverage.unvalidated_bodies, vec!["ingestEvents"]);
+
+        let json = serde_json::to_value(&coverage).unwrap();
+        assert_eq!(json["unvalidated_bodies"][0], "ingestEvents");
+        let json = serde_json::to_value(HandlerCoverage::default()).unwrap();
+        assert!(json.get("unvalidated_bodies").is_none());
+    }
+
+    #[test]
+    fn test_handler_coverage_for_operations() {
+        #[derive(Debug, Clone, Copy)]
+        enum Operation {
+            GetUser,
+            ListUsers,
+        }
+
+        impl ContractOperation for Operation {
+            const ALL: &'static [Self] = &[Self::GetUser, Self::ListUsers];
+
+            fn as_str(self) -> &'static str {
+                match self {
+                    Self::GetUser => "getUser",
+                    Self::ListUsers => "listUsers",
+                }
+            }
+
+            fn method(self) -> &'static str {
+                "GET"
+            }
+
+            fn path(self) -> &'static str {
+                match self {
+                    Self::GetUser => "/users/{userId}",
+                    Self::ListUsers => "/users",
+                }
+            }
+        }
+
+        let coverage = HandlerCoverage::for_operations::<Operation, _>(["getUser"]);
+        assert_eq!(coverage.operations, 2);
+        assert_eq!(coverage.missing, vec!["listUsers"]);
+    }
+
+    #[test]
+    fn test_redacts_secret_fields() {
+        let config = json!({
+            "server": { "http_addr": "0.0.0.0:8080" },
+            "authz": { "bundle_url": "https://opa", "api-key": "k-123" },
+            "database": { "password": "hunter2", "pool": 5 },
+            "oauth": [{ "client_secret": "s3cr3t", "client_id": "web" }],
+            "session_token": null,
+            "enable_authorization": true,
+        });
+
+        let diagnostics = Diagnostics::new("svc", "1.0.0").with_config(&config);
+        let redacted = &diagnostics.config;
+
+        assert_eq!(redacted["server"]["http_addr"], "0.0.0.0:8080");
+        assert_eq!(redacted["authz"]["bundle_url"], "https://opa");
+        assert_eq!(redacted["authz"]["api-key"], REDACTED);
+        assert_eq!(redacted["database"]["password"], REDACTED);
+        assert_eq!(redacted["database"]["pool"], 5);
+        assert_eq!(redacted["oauth"][0]["client_secret"], REDACTED);
+        assert_eq!(redacted["oauth"][0]["client_id"], "web");
+        assert!(redacted["session_token"].is_null());
+        assert_eq!(redacted["enable_authorization"], true);
+    }
+
+    #[test]
+    fn test_stable_field_names() {
+        let diagnostics = Diagnostics::new("svc", "1.0.0")
+            .with_config_profile("production")
+            .with_contract(ContractInfo::new("users", "2.0.0", ["getUser"]))
+            .with_policy_revision("rev-7")
+            .with_middleware_stages(["request_id"])
+            .with_listener("http", "0.0.0.0:8080");
+
+        let json = serde_json::to_value(&diagnostics).unwrap();
+        assert_eq!(json["service"], "svc");
+        assert_eq!(json["config_profile"], "production");
+        assert_eq!(json["contract"]["service"], "users");
+        assert_eq!(json["contract"]["operation_count"], 1);
+        assert_eq!(json["policy"]["bundle_revision"], "rev-7");
+        assert_eq!(json["middleware"]["stages"], json!(["request_id"]));
+        assert_eq!(json["middleware"]["optional_stages"], json!([]));
+        assert_eq!(json["listeners"][0]["name"], "http");
+        assert_eq!(json["listeners"][0]["address"], "0.0.0.0:8080");
+        assert_eq!(json["handlers"]["missing"], json!([]));
+        assert!(json.get("contract_versions").is_none());
+        assert!(json.get("apps").is_none());
+    }
+
+    #[test]
+    fn test_contract_versions() {
+        let diagnostics = Diagnostics::new("svc", "1.0.0")
+            .with_contract_version(ContractInfo::new("users", "v1", ["getUser"]))
+            .with_contract_version(ContractInfo::new("users", "v2", ["getUserV2"]));
+
+        assert_eq!(diagnostics.contract.as_ref().unwrap().version, "v1");
+        assert_eq!(diagnostics.contract_versions.len(), 2);
+        let operations: BTreeSet<&str> = diagnostics.contract_operations().collect();
+        assert_eq!(operations, BTreeSet::from(["getUser", "getUserV2"]));
+    }
+}
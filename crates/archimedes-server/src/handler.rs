@@ -39,6 +39,90 @@
 //! let mut registry = HandlerRegistry::new();
 //! registry.register("getUser", get_user);
 //! ```
+//!
+//! # Typed Errors
+//!
+//! Handlers may return their own error type instead of [`HandlerError`],
+//! as long as it implements [`IntoErrorResponse`]. Each variant then maps
+//! straight to a status code and error envelope:
+//!
+//! ```rust,ignore
+//! use archimedes_server::{ErrorResponse, IntoErrorResponse};
+//! use http::StatusCode;
+//!
+//! enum OrderError {
+//!     NotFound(String),
+//!     AlreadyShipped(String),
+//! }
+//!
+//! impl IntoErrorResponse for OrderError {
+//!     fn into_error_response(self) -> ErrorResponse {
+//!         match self {
+//!             Self::NotFound(id) => ErrorResponse::new(
+//!                 StatusCode::NOT_FOUND,
+//!                 "ORDER_NOT_FOUND",
+//!                 format!("Order {} not found", id),
+//!             ),
+//!             Self::AlreadyShipped(id) => ErrorResponse::new(
+//!                 StatusCode::CONFLICT,
+//!                 "ORDER_SHIPPED",
+//!                 format!("Order {} has already shipped", id),
+//!             ),
+//!         }
+//!     }
+//! }
+//!
+//! async fn cancel_order(ctx: RequestContext, req: CancelRequest) -> Result<Order, OrderError> {
+//!     // ...
+//! }
+//! ```
+//!
+//! # Status Codes and Headers
+//!
+//! [`HandlerRegistry::register`] sends the handler's value as a `200` JSON
+//! response. Handlers that choose their own status, headers or content type
+//! are registered with [`HandlerRegistry::register_response`] and return any
+//! [`IntoResponse`]:
+//!
+//! ```rust,ignore
+//! use archimedes_extract::Json;
+//! use http::StatusCode;
+//!
+//! registry.register_response("createOrder", |_ctx, req: CreateOrder| async move {
+//!     let order = orders.create(req).await?;
+//!     Ok::<_, HandlerError>((StatusCode::CREATED, Json(order)))
+//! });
+//! ```
+//!
+//! # Streaming Request Bodies
+//!
+//! Handlers registered with [`HandlerRegistry::register_body_stream`]
+//! consume the request body themselves as a [`BodyStream`], for bodies too
+//! large to buffer such as NDJSON ingests. The server does not buffer the
+//! body or validate it against the request schema; with an item validator
+//! set (see [`HandlerRegistry::set_item_validator`]) each NDJSON line is
+//! validated against the operation's request schema instead. Such
+//! operations are listed as `unvalidated_bodies` in the diagnostics report.
+//!
+//! ```rust,ignore
+//! use archimedes_extract::BodyStream;
+//!
+//! registry.register_body_stream("ingestEvents", |_ctx, body: BodyStream| async move {
+//!     let mut events = body.ndjson::<Event>();
+//!     let (mut accepted, mut rejected) = (0, 0);
+//!     while let Some(event) = events.next_item().await {
+//!         match event {
+//!             Ok(event) => {
+//!                 store.append(event).await?;
+//!                 accepted += 1;
+//!             }
+//!             Err(e) if e.is_item() => rejected += 1,
+//!             Err(e) => return Err(HandlerError::from(e)),
+//!         }
+//!     }
+//!     Ok::<_, HandlerError>(Json(IngestSummary { accepted, rejected }))
+//! });
+//! ```
 
 use std::collections::HashMap;
 use std::future::Future;
@@ -46,16 +130,38 @@ use std::pin::Pin;
 use std::sync::Arc;
 
 use bytes::Bytes;
+use http::{header, HeaderValue, Response, StatusCode};
 use serde::{de::DeserializeOwned, Serialize};
 
-use archimedes_core::{RequestContext, ThemisError};
+use archimedes_core::response::APPLICATION_JSON;
+use archimedes_core::{timing, IntoResponse, RequestContext, ThemisError};
+use archimedes_extract::body_stream::ItemValidator;
+use archimedes_extract::naming::struct_fields;
+use archimedes_extract::{
+    BodyStream, ExtractionError, ExtractionSource, NdjsonError, StreamingBody,
+};
+use archimedes_middleware::stages::RequestValidator;
 
 /// Type alias for boxed handler result.
-pub type BoxedHandlerResult = Pin<Box<dyn Future<Output = Result<Bytes, HandlerError>> + Send>>;
+pub type BoxedHandlerResult =
+    Pin<Box<dyn Future<Output = Result<Response<Bytes>, HandlerError>> + Send>>;
 
 /// A type-erased handler function.
 pub type ErasedHandler = Arc<dyn Fn(RequestContext, Bytes) -> BoxedHandlerResult + Send + Sync>;
 
+/// Type alias for boxed streaming handler result.
+pub type BoxedStreamingResult =
+    Pin<Box<dyn Future<Output = Result<Response<StreamingBody>, HandlerError>> + Send>>;
+
+/// A type-erased streaming handler function.
+pub type ErasedStreamingHandler =
+    Arc<dyn Fn(RequestContext, Bytes) -> BoxedStreamingResult + Send + Sync>;
+
+/// A type-erased handler function that consumes the request body as a
+/// stream.
+pub type ErasedBodyStreamHandler =
+    Arc<dyn Fn(RequestContext, BodyStream) -> BoxedHandlerResult + Send + Sync>;
+
 /// Handler error type.
 ///
 /// Wraps errors that can occur during handler execution.
@@ -72,6 +178,9 @@ pub enum HandlerError {
 
     /// Handler returned a custom error.
     Custom(Box<dyn std::error::Error + Send + Sync>),
+
+    /// Handler returned a typed error already mapped to a response.
+    Response(ErrorResponse),
 }
 
 impl std::fmt::Display for HandlerError {
@@ -81,6 +190,7 @@ impl std::fmt::Display for HandlerError {
             Self::SerializationError(msg) => write!(f, "Serialization error: {}", msg),
             Self::ThemisError(e) => write!(f, "Themis error: {}", e),
             Self::Custom(e) => write!(f, "Handler error: {}", e),
+            Self::Response(e) => write!(f, "Handler error: {}", e),
         }
     }
 }
@@ -107,6 +217,179 @@ impl From<serde_json::Error> for HandlerError {
     }
 }
 
+impl From<ErrorResponse> for HandlerError {
+    fn from(err: ErrorResponse) -> Self {
+        Self::Response(err)
+    }
+}
+
+impl From<NdjsonError> for HandlerError {
+    fn from(err: NdjsonError) -> Self {
+        Self::Response(err.into_error_response())
+    }
+}
+
+/// An error response: the status code and the contents of the error
+/// envelope sent to the client.
+///
+/// The server renders it as
+/// `{"error": {"code", "message", "operation_id", "details"}}`, with
+/// `details` omitted when empty.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorResponse {
+    /// HTTP status code.
+    pub status: StatusCode,
+    /// Machine-readable error code.
+    pub code: String,
+    /// Human-readable error message.
+    pub message: String,
+    /// Additional error details.
+    pub details: Option<serde_json::Value>,
+}
+
+impl ErrorResponse {
+    /// Creates an error response.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use archimedes_server::ErrorResponse;
+    /// use http::StatusCode;
+    ///
+    /// let error = ErrorResponse::new(StatusCode::CONFLICT, "ORDER_SHIPPED", "Order has shipped");
+    /// assert_eq!(error.status, StatusCode::CONFLICT);
+    /// ```
+    #[must_use]
+    pub fn new(status: StatusCode, code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            code: code.into(),
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    /// Attaches additional details to the envelope.
+    #[must_use]
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+}
+
+impl std::fmt::Display for ErrorResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} ({}): {}",
+            self.code,
+            self.status.as_u16(),
+            self.message
+        )
+    }
+}
+
+impl std::error::Error for ErrorResponse {}
+
+/// Conversion of a handler's error type into an [`ErrorResponse`].
+///
+/// Implement this for an application error enum to let handlers return
+/// `Result<T, MyError>` directly. It is implemented for [`ThemisError`],
+/// [`ExtractionError`], [`NdjsonError`], [`HandlerError`], and
+/// `Box<dyn Error + Send + Sync>`, the last of which any `std::error::Error` converts into with `?` and which is reported as
+/// a `500 Internal Server Error`. A blanket implementation over every
+/// `std::error::Error` would keep application errors, which usually
+/// implement `Error` themselves, from choosing their own status codes.
+pub trait IntoErrorResponse {
+    /// Converts the error into a response.
+    fn into_error_response(self) -> ErrorResponse;
+
+    /// Converts the error into a [`HandlerError`] for dispatch.
+    ///
+    /// The default wraps [`into_error_response`](Self::into_error_response).
+    fn into_handler_error(self) -> HandlerError
+    where
+        Self: Sized,
+    {
+        HandlerError::Response(self.into_error_response())
+    }
+}
+
+impl IntoErrorResponse for ErrorResponse {
+    fn into_error_response(self) -> ErrorResponse {
+        self
+    }
+}
+
+impl IntoErrorResponse for ThemisError {
+    fn into_error_response(self) -> ErrorResponse {
+        let envelope = self.to_envelope(None);
+        ErrorResponse {
+            status: self.status_code(),
+            code: envelope.error.code,
+            message: envelope.error.message,
+            details: envelope.error.details,
+        }
+    }
+
+    fn into_handler_error(self) -> HandlerError {
+        HandlerError::ThemisError(self)
+    }
+}
+
+impl IntoErrorResponse for ExtractionError {
+    fn into_error_response(self) -> ErrorResponse {
+        ErrorResponse::new(self.status_code(), self.error_code(), self.to_string())
+            .with_details(self.details())
+    }
+}
+
+impl IntoErrorResponse for NdjsonError {
+    fn into_error_response(self) -> ErrorResponse {
+        let error = self.extraction_error();
+        ErrorResponse::new(error.status_code(), error.error_code(), self.to_string())
+            .with_details(error.details())
+    }
+}
+
+impl IntoErrorResponse for Box<dyn std::error::Error + Send + Sync> {
+    fn into_error_response(self) -> ErrorResponse {
+        ErrorResponse::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "INTERNAL_ERROR",
+            format!("Internal error: {}", self),
+        )
+    }
+
+    fn into_handler_error(self) -> HandlerError {
+        HandlerError::Custom(self)
+    }
+}
+
+impl IntoErrorResponse for HandlerError {
+    fn into_error_response(self) -> ErrorResponse {
+        match self {
+            Self::DeserializationError(msg) => ErrorResponse::new(
+                StatusCode::BAD_REQUEST,
+                "VALIDATION_ERROR",
+                format!("Invalid request body: {}", msg),
+            ),
+            Self::SerializationError(msg) => ErrorResponse::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "SERIALIZATION_ERROR",
+                format!("Failed to serialize response: {}", msg),
+            ),
+            Self::ThemisError(e) => e.into_error_response(),
+            Self::Custom(e) => e.into_error_response(),
+            Self::Response(response) => response,
+        }
+    }
+
+    fn into_handler_error(self) -> HandlerError {
+        self
+    }
+}
+
 /// Registry for operation handlers.
 ///
 /// Maps operation IDs to their handler functions, handling type
@@ -123,6 +406,12 @@ impl From<serde_json::Error> for HandlerError {
 #[derive(Default)]
 pub struct HandlerRegistry {
     handlers: HashMap<String, ErasedHandler>,
+    streaming: HashMap<String, ErasedStreamingHandler>,
+    body_streams: HashMap<String, ErasedBodyStreamHandler>,
+    /// Field names of each handler's request type
+    request_fields: HashMap<String, &'static [&'static str]>,
+    /// Validates the items of streamed request bodies
+    item_validator: Option<Arc<dyn RequestValidator>>,
 }
 
 impl HandlerRegistry {
@@ -140,6 +429,10 @@ impl HandlerRegistry {
     pub fn new() -> Self {
         Self {
             handlers: HashMap::new(),
+            streaming: HashMap::new(),
+            body_streams: HashMap::new(),
+            request_fields: HashMap::new(),
+            item_validator: None,
         }
     }
 
@@ -147,8 +440,10 @@ impl HandlerRegistry {
     ///
     /// The handler function must:
     /// - Accept a `&RequestContext` and a request type `Req`
-    /// - Return a `Future` resolving to `Result<Res, HandlerError>`
-    /// - Have `Req: DeserializeOwned` and `Res: Serialize`
+    /// - Return a `Future` resolving to `Result<Res, E>`
+    /// - Have `Req: DeserializeOwned`, `Res: Serialize` and
+    ///   `E: IntoErrorResponse`, such as [`HandlerError`] or an
+    ///   application error enum
     ///
     /// # Arguments
     ///
@@ -175,12 +470,13 @@ impl HandlerRegistry {
     /// let mut registry = HandlerRegistry::new();
     /// registry.register("greet", greet);
     /// ```
-    pub fn register<Req, Res, F, Fut>(&mut self, operation_id: impl Into<String>, handler: F)
+    pub fn register<Req, Res, E, F, Fut>(&mut self, operation_id: impl Into<String>, handler: F)
     where
         Req: DeserializeOwned + Send + 'static,
         Res: Serialize + Send + 'static,
+        E: IntoErrorResponse + 'static,
         F: Fn(RequestContext, Req) -> Fut + Send + Sync + 'static,
-        Fut: Future<Output = Result<Res, HandlerError>> + Send + 'static,
+        Fut: Future<Output = Result<Res, E>> + Send + 'static,
     {
         let handler = Arc::new(handler);
         let erased: ErasedHandler = Arc::new(move |ctx: RequestContext, body: Bytes| {
@@ -189,21 +485,23 @@ impl HandlerRegistry {
                 // Deserialize request - treat empty body as empty JSON object
                 // This allows GET requests with empty bodies to work with Default types
                 let body_slice = if body.is_empty() { b"{}" as &[u8] } else { &body };
-                let request: Req = serde_json::from_slice(body_slice)
-                    .map_err(|e| HandlerError::DeserializationError(e.to_string()))?;
+                let request: Req = timing::time(timing::DESERIALIZATION, || {
+                    serde_json::from_slice(body_slice)
+                })
+                .map_err(|e| HandlerError::DeserializationError(e.to_string()))?;
 
                 // Invoke handler
-                let response = handler(ctx, request).await?;
-
-                // Serialize response
-                let bytes = serde_json::to_vec(&response)
-                    .map_err(|e| HandlerError::SerializationError(e.to_string()))?;
+                let response = timing::time_handler(handler(ctx, request))
+                    .await
+                    .map_err(IntoErrorResponse::into_handler_error)?;
 
-                Ok(Bytes::from(bytes))
+                json_response(&response)
             })
         });
 
-        self.handlers.insert(operation_id.into(), erased);
+        let operation_id = operation_id.into();
+        self.request_fields.insert(operation_id.clone(), struct_fields::<Req>());
+        self.handlers.insert(operation_id, erased);
     }
 
     /// Registers a handler that takes no request body.
@@ -232,28 +530,288 @@ impl HandlerRegistry {
     /// let mut registry = HandlerRegistry::new();
     /// registry.register_no_body("health", health);
     /// ```
-    pub fn register_no_body<Res, F, Fut>(&mut self, operation_id: impl Into<String>, handler: F)
+    pub fn register_no_body<Res, E, F, Fut>(&mut self, operation_id: impl Into<String>, handler: F)
     where
         Res: Serialize + Send + 'static,
+        E: IntoErrorResponse + 'static,
         F: Fn(RequestContext) -> Fut + Send + Sync + 'static,
-        Fut: Future<Output = Result<Res, HandlerError>> + Send + 'static,
+        Fut: Future<Output = Result<Res, E>> + Send + 'static,
     {
         let handler = Arc::new(handler);
         let erased: ErasedHandler = Arc::new(move |ctx: RequestContext, _body: Bytes| {
             let handler = Arc::clone(&handler);
             Box::pin(async move {
                 // Invoke handler (no request body)
-                let response = handler(ctx).await?;
+                let response = timing::time_handler(handler(ctx))
+                    .await
+                    .map_err(IntoErrorResponse::into_handler_error)?;
+
+                json_response(&response)
+            })
+        });
+
+        let operation_id = operation_id.into();
+        self.request_fields.remove(&operation_id);
+        self.handlers.insert(operation_id, erased);
+    }
+
+    /// Registers a handler that builds its own response.
+    ///
+    /// Like [`register`](Self::register), but the handler returns any
+    /// [`IntoResponse`], such as `Json<T>`, `(StatusCode, Json<T>)`,
+    /// `(StatusCode, HeaderMap, T)`, `NoContent` or `Redirect`. The status,
+    /// headers and body it sets are sent unchanged. Responses with a JSON
+    /// content type still go through response validation.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use archimedes_extract::Json;
+    /// use archimedes_server::handler::{HandlerRegistry, HandlerError};
+    /// use archimedes_core::RequestContext;
+    /// use http::StatusCode;
+    ///
+    /// async fn create_user(_ctx: RequestContext, req: NewUser) -> Result<(StatusCode, Json<User>), HandlerError> {
+    ///     Ok((StatusCode::CREATED, Json(User::from(req))))
+    /// }
+    ///
+    /// let mut registry = HandlerRegistry::new();
+    /// registry.register_response("createUser", create_user);
+    /// ```
+    pub fn register_response<Req, R, E, F, Fut>(
+        &mut self,
+        operation_id: impl Into<String>,
+        handler: F,
+    ) where
+        Req: DeserializeOwned + Send + 'static,
+        R: IntoResponse + Send + 'static,
+        E: IntoErrorResponse + 'static,
+        F: Fn(RequestContext, Req) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<R, E>> + Send + 'static,
+    {
+        let handler = Arc::new(handler);
+        let erased: ErasedHandler = Arc::new(move |ctx: RequestContext, body: Bytes| {
+            let handler = Arc::clone(&handler);
+            Box::pin(async move {
+                let body_slice = if body.is_empty() { b"{}" as &[u8] } else { &body };
+                let request: Req = timing::time(timing::DESERIALIZATION, || {
+                    serde_json::from_slice(body_slice)
+                })
+                .map_err(|e| HandlerError::DeserializationError(e.to_string()))?;
+
+                let response = timing::time_handler(handler(ctx, request))
+                    .await
+                    .map_err(IntoErrorResponse::into_handler_error)?;
+
+                Ok(timing::time(timing::SERIALIZATION, || {
+                    response.into_response()
+                }))
+            })
+        });
+
+        let operation_id = operation_id.into();
+        self.request_fields.insert(operation_id.clone(), struct_fields::<Req>());
+        self.handlers.insert(operation_id, erased);
+    }
 
-                // Serialize response
-                let bytes = serde_json::to_vec(&response)
-                    .map_err(|e| HandlerError::SerializationError(e.to_string()))?;
+    /// Registers a handler that takes no request body and builds its own
+    /// response.
+    ///
+    /// See [`register_response`](Self::register_response).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use archimedes_extract::response::NoContent;
+    ///
+    /// registry.register_response_no_body("purgeCache", |_ctx| async move {
+    ///     cache.clear();
+    ///     Ok::<_, HandlerError>(NoContent)
+    /// });
+    /// ```
+    pub fn register_response_no_body<R, E, F, Fut>(
+        &mut self,
+        operation_id: impl Into<String>,
+        handler: F,
+    ) where
+        R: IntoResponse + Send + 'static,
+        E: IntoErrorResponse + 'static,
+        F: Fn(RequestContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<R, E>> + Send + 'static,
+    {
+        let handler = Arc::new(handler);
+        let erased: ErasedHandler = Arc::new(move |ctx: RequestContext, _body: Bytes| {
+            let handler = Arc::clone(&handler);
+            Box::pin(async move {
+                let response = timing::time_handler(handler(ctx))
+                    .await
+                    .map_err(IntoErrorResponse::into_handler_error)?;
 
-                Ok(Bytes::from(bytes))
+                Ok(timing::time(timing::SERIALIZATION, || {
+                    response.into_response()
+                }))
             })
         });
 
-        self.handlers.insert(operation_id.into(), erased);
+        let operation_id = operation_id.into();
+        self.request_fields.remove(&operation_id);
+        self.handlers.insert(operation_id, erased);
+    }
+
+    /// Registers a handler whose response body is streamed.
+    ///
+    /// The handler returns the response built by
+    /// [`StreamingResponse::channel`](archimedes_extract::StreamingResponse::channel)
+    /// and hands the writer to a task that produces the body. The server
+    /// sends the body as it is written and finishes it with trailers
+    /// reporting whether the stream completed.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use archimedes_extract::StreamingResponse;
+    /// use archimedes_server::handler::{HandlerRegistry, HandlerError};
+    /// use archimedes_core::RequestContext;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct ExportRequest { table: String }
+    ///
+    /// let mut registry = HandlerRegistry::new();
+    /// registry.register_streaming("exportRows", |_ctx: RequestContext, req: ExportRequest| async move {
+    ///     let (writer, response) = StreamingResponse::new()
+    ///         .content_type("application/x-ndjson")
+    ///         .channel(16);
+    ///     tokio::spawn(async move {
+    ///         match export_rows(&req.table, &writer).await {
+    ///             Ok(()) => writer.finish(),
+    ///             Err(e) => writer.fail(e.to_string()),
+    ///         }
+    ///     });
+    ///     Ok::<_, HandlerError>(response)
+    /// });
+    /// ```
+    pub fn register_streaming<Req, F, Fut>(&mut self, operation_id: impl Into<String>, handler: F)
+    where
+        Req: DeserializeOwned + Send + 'static,
+        F: Fn(RequestContext, Req) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Response<StreamingBody>, HandlerError>> + Send + 'static,
+    {
+        let handler = Arc::new(handler);
+        let erased: ErasedStreamingHandler = Arc::new(move |ctx: RequestContext, body: Bytes| {
+            let handler = Arc::clone(&handler);
+            Box::pin(async move {
+                let body_slice = if body.is_empty() { b"{}" as &[u8] } else { &body };
+                let request: Req = serde_json::from_slice(body_slice)
+                    .map_err(|e| HandlerError::DeserializationError(e.to_string()))?;
+
+                handler(ctx, request).await
+            })
+        });
+
+        let operation_id = operation_id.into();
+        self.request_fields.insert(operation_id.clone(), struct_fields::<Req>());
+        self.streaming.insert(operation_id, erased);
+    }
+
+    /// Registers a handler that consumes the request body as a stream.
+    ///
+    /// The server hands the handler the body unbuffered, as it arrives, so
+    /// the handler can process bodies of any size in bounded memory; reading
+    /// slowly holds back the client. Request body validation is skipped for
+    /// the operation. Use [`BodyStream::ndjson`] to read NDJSON items, each
+    /// validated by the [item validator](Self::set_item_validator) if one is
+    /// set. Path parameters are not passed to the handler.
+    ///
+    /// The request timeout covers the whole body, so long ingests need an
+    /// operation timeout to match. If the client disconnects, reading the
+    /// body fails and the handler should stop.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use archimedes_extract::BodyStream;
+    ///
+    /// registry.register_body_stream("ingestEvents", |_ctx, body: BodyStream| async move {
+    ///     let mut events = body.ndjson::<Event>();
+    ///     let mut accepted = 0;
+    ///     while let Some(event) = events.next_item().await {
+    ///         store.append(event?).await?;
+    ///         accepted += 1;
+    ///     }
+    ///     Ok::<_, HandlerError>(Json(IngestSummary { accepted }))
+    /// });
+    /// ```
+    pub fn register_body_stream<R, E, F, Fut>(
+        &mut self,
+        operation_id: impl Into<String>,
+        handler: F,
+    ) where
+        R: IntoResponse + Send + 'static,
+        E: IntoErrorResponse + 'static,
+        F: Fn(RequestContext, BodyStream) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<R, E>> + Send + 'static,
+    {
+        let handler = Arc::new(handler);
+        let erased: ErasedBodyStreamHandler =
+            Arc::new(move |ctx: RequestContext, body: BodyStream| {
+                let handler = Arc::clone(&handler);
+                Box::pin(async move {
+                    let response = timing::time_handler(handler(ctx, body))
+                        .await
+                        .map_err(IntoErrorResponse::into_handler_error)?;
+
+                    Ok(timing::time(timing::SERIALIZATION, || {
+                        response.into_response()
+                    }))
+                })
+            });
+
+        let operation_id = operation_id.into();
+        self.request_fields.remove(&operation_id);
+        self.body_streams.insert(operation_id, erased);
+    }
+
+    /// Sets the validator for items of streamed request bodies.
+    ///
+    /// Each NDJSON line read from a [`BodyStream`] is validated as if it
+    /// were the request body of the operation, so the contract declares the
+    /// item schema as the operation's request schema. Pass the validator
+    /// used by the request validation stage, such as the Sentinel.
+    pub fn set_item_validator(&mut self, validator: Arc<dyn RequestValidator>) {
+        self.item_validator = Some(validator);
+    }
+
+    /// Returns the validator for the items of a streamed request body of
+    /// an operation, if an item validator is set.
+    #[must_use]
+    pub fn item_validator(
+        &self,
+        operation_id: &str,
+        version: Option<&str>,
+        headers: &http::HeaderMap,
+    ) -> Option<ItemValidator> {
+        let validator = Arc::clone(self.item_validator.as_ref()?);
+        let operation_id = operation_id.to_string();
+        let version = version.map(str::to_string);
+        let headers = headers.clone();
+        Some(Arc::new(move |item: &[u8]| {
+            let result =
+                validator.validate_request(&operation_id, version.as_deref(), &headers, item);
+            if result.valid {
+                return Ok(());
+            }
+            let errors = result.errors.into_iter().map(|e| {
+                ExtractionError::validation_failed(ExtractionSource::Body, e.field, e.message)
+            });
+            Err(ExtractionError::aggregate(errors).unwrap_or_else(|| {
+                ExtractionError::validation_failed(
+                    ExtractionSource::Body,
+                    "item",
+                    "does not match the request schema",
+                )
+            }))
+        }))
     }
 
     /// Looks up a handler by operation ID.
@@ -294,6 +852,64 @@ impl HandlerRegistry {
     #[must_use]
     pub fn contains(&self, operation_id: &str) -> bool {
         self.handlers.contains_key(operation_id)
+            || self.streaming.contains_key(operation_id)
+            || self.body_streams.contains_key(operation_id)
+    }
+
+    /// Returns the field names of the request type an operation's handler
+    /// deserializes.
+    ///
+    /// Returns `None` if no handler with a request type is registered, and
+    /// an empty slice if the request type is not a plain struct.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use archimedes_server::handler::HandlerRegistry;
+    ///
+    /// let registry = HandlerRegistry::new();
+    /// assert!(registry.request_fields("getUser").is_none());
+    /// ```
+    #[must_use]
+    pub fn request_fields(&self, operation_id: &str) -> Option<&'static [&'static str]> {
+        self.request_fields.get(operation_id).copied()
+    }
+
+    /// Checks if the handler for an operation streams its response.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use archimedes_server::handler::HandlerRegistry;
+    ///
+    /// let registry = HandlerRegistry::new();
+    /// assert!(!registry.is_streaming("exportRows"));
+    /// ```
+    #[must_use]
+    pub fn is_streaming(&self, operation_id: &str) -> bool {
+        self.streaming.contains_key(operation_id)
+    }
+
+    /// Checks if the handler for an operation consumes the request body as
+    /// a stream.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use archimedes_server::handler::HandlerRegistry;
+    ///
+    /// let registry = HandlerRegistry::new();
+    /// assert!(!registry.is_body_stream("ingestEvents"));
+    /// ```
+    #[must_use]
+    pub fn is_body_stream(&self, operation_id: &str) -> bool {
+        self.body_streams.contains_key(operation_id)
+    }
+
+    /// Returns the operations whose handlers consume the request body as a
+    /// stream, skipping request body validation.
+    pub fn body_stream_operation_ids(&self) -> impl Iterator<Item = &str> {
+        self.body_streams.keys().map(String::as_str)
     }
 
     /// Returns the number of registered handlers.
@@ -308,7 +924,7 @@ impl HandlerRegistry {
     /// ```
     #[must_use]
     pub fn len(&self) -> usize {
-        self.handlers.len()
+        self.handlers.len() + self.streaming.len() + self.body_streams.len()
     }
 
     /// Returns `true` if no handlers are registered.
@@ -323,7 +939,7 @@ impl HandlerRegistry {
     /// ```
     #[must_use]
     pub fn is_empty(&self) -> bool {
-        self.handlers.is_empty()
+        self.handlers.is_empty() && self.streaming.is_empty() && self.body_streams.is_empty()
     }
 
     /// Returns an iterator over registered operation IDs.
@@ -337,10 +953,18 @@ impl HandlerRegistry {
     /// assert_eq!(registry.operation_ids().count(), 0);
     /// ```
     pub fn operation_ids(&self) -> impl Iterator<Item = &str> {
-        self.handlers.keys().map(String::as_str)
+        self.handlers
+            .keys()
+            .chain(self.streaming.keys())
+            .chain(self.body_streams.keys())
+            .map(String::as_str)
     }
 
-    /// Invokes a handler for the given operation.
+    /// Invokes a handler for the given operation and returns the response
+    /// body.
+    ///
+    /// Use [`invoke_response`](Self::invoke_response) to also get the status
+    /// and headers the handler set.
     ///
     /// # Arguments
     ///
@@ -371,6 +995,23 @@ impl HandlerRegistry {
         ctx: RequestContext,
         body: Bytes,
     ) -> Result<Bytes, InvokeError> {
+        self.invoke_response(operation_id, ctx, body)
+            .await
+            .map(Response::into_body)
+    }
+
+    /// Invokes a handler for the given operation and returns its full
+    /// response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the handler is not found or execution fails.
+    pub async fn invoke_response(
+        &self,
+        operation_id: &str,
+        ctx: RequestContext,
+        body: Bytes,
+    ) -> Result<Response<Bytes>, InvokeError> {
         let handler = self
             .handlers
             .get(operation_id)
@@ -378,16 +1019,101 @@ impl HandlerRegistry {
 
         handler(ctx, body).await.map_err(InvokeError::HandlerError)
     }
+
+    /// Invokes a streaming handler for the given operation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no streaming handler is registered for the
+    /// operation or the handler fails before the response is built.
+    pub async fn invoke_streaming(
+        &self,
+        operation_id: &str,
+        ctx: RequestContext,
+        body: Bytes,
+    ) -> Result<Response<StreamingBody>, InvokeError> {
+        let handler = self
+            .streaming
+            .get(operation_id)
+            .ok_or_else(|| InvokeError::HandlerNotFound(operation_id.to_string()))?;
+
+        handler(ctx, body).await.map_err(InvokeError::HandlerError)
+    }
+
+    /// Invokes the handler of an operation that consumes the request body
+    /// as a stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no body stream handler is registered for the
+    /// operation or the handler fails.
+    pub async fn invoke_body_stream(
+        &self,
+        operation_id: &str,
+        ctx: RequestContext,
+        body: BodyStream,
+    ) -> Result<Response<Bytes>, InvokeError> {
+        let handler = self
+            .body_streams
+            .get(operation_id)
+            .ok_or_else(|| InvokeError::HandlerNotFound(operation_id.to_string()))?;
+
+        handler(ctx, body).await.map_err(InvokeError::HandlerError)
+    }
+}
+
+/// Serializes a handler's value as a `200` JSON response.
+fn json_response<T: Serialize>(value: &T) -> Result<Response<Bytes>, HandlerError> {
+    let bytes = timing::time(timing::SERIALIZATION, || serde_json::to_vec(value))
+        .map_err(|e| HandlerError::SerializationError(e.to_string()))?;
+
+    let mut response = Response::new(Bytes::from(bytes));
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(APPLICATION_JSON),
+    );
+    Ok(response)
 }
 
 impl std::fmt::Debug for HandlerRegistry {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("HandlerRegistry")
             .field("handlers", &self.handlers.keys().collect::<Vec<_>>())
+            .field("streaming", &self.streaming.keys().collect::<Vec<_>>())
+            .field("body_streams", &self.body_streams.keys().collect::<Vec<_>>())
             .finish()
     }
 }
 
+/// A path parameter that binds to a handler field only by naming style.
+///
+/// The contract declares the parameter name; the field is the one in the
+/// handler's request type that receives it. The two differ only by case or
+/// style, such as `userId` and `user_id`. Binding still works, but an
+/// explicit `#[serde(rename)]` makes the mapping visible in the code.
+///
+/// Reported by [`Server::param_name_mismatches`](crate::Server::param_name_mismatches).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamNameMismatch {
+    /// Operation the parameter belongs to.
+    pub operation_id: String,
+    /// Parameter name as declared in the contract.
+    pub param: String,
+    /// Request type field the parameter binds to.
+    pub field: &'static str,
+}
+
+impl std::fmt::Display for ParamNameMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "path parameter '{}' of operation '{}' binds to field '{}' by naming style; \
+             add #[serde(rename = \"{}\")] to make it explicit",
+            self.param, self.operation_id, self.field, self.param
+        )
+    }
+}
+
 /// Error returned when invoking a handler fails.
 #[derive(Debug)]
 pub enum InvokeError {
@@ -434,6 +1160,7 @@ impl<T: Serialize + Send + 'static> HandlerResponse for T {}
 mod tests {
     use super::*;
     use archimedes_core::RequestContext;
+    use archimedes_extract::ExtractionSource;
     use serde::{Deserialize, Serialize};
 
     #[derive(Deserialize)]
@@ -477,6 +1204,7 @@ mod tests {
         assert_eq!(registry.len(), 1);
         assert!(registry.contains("test"));
         assert!(!registry.contains("other"));
+        assert_eq!(registry.request_fields("test"), Some(&["name"][..]));
     }
 
     #[test]
@@ -485,6 +1213,118 @@ mod tests {
         registry.register_no_body("health", test_no_body_handler);
 
         assert!(registry.contains("health"));
+        assert_eq!(registry.request_fields("health"), None);
+    }
+
+    #[tokio::test]
+    async fn test_registry_register_streaming() {
+        use archimedes_extract::StreamingResponse;
+
+        let mut registry = HandlerRegistry::new();
+        registry.register_streaming("export", |_ctx, req: TestRequest| async move {
+            let (writer, response) = StreamingResponse::new().channel(1);
+            writer.fail(format!("no rows for {}", req.name));
+            Ok(response)
+        });
+
+        assert!(registry.contains("export"));
+        assert!(registry.is_streaming("export"));
+        assert!(registry.get("export").is_none());
+        assert_eq!(registry.len(), 1);
+
+        let response = registry
+            .invoke_streaming(
+                "export",
+                RequestContext::new(),
+                Bytes::from(r#"{"name":"a"}"#),
+            )
+            .await
+            .unwrap();
+        assert!(response.headers().contains_key(http::header::TRAILER));
+        assert!(registry
+            .invoke_streaming("missing", RequestContext::new(), Bytes::new())
+            .await
+            .is_err());
+    }
+
+    /// Rejects items without a `name` field.
+    struct NameRequired;
+
+    impl RequestValidator for NameRequired {
+        fn validate_request(
+            &self,
+            _operation_id: &str,
+            _version: Option<&str>,
+            _headers: &http::HeaderMap,
+            body: &[u8],
+        ) -> archimedes_middleware::stages::ValidationResult {
+            use archimedes_middleware::stages::{ValidationError, ValidationResult};
+
+            let item: serde_json::Value = serde_json::from_slice(body).unwrap_or_default();
+            if item.get("name").is_some() {
+                ValidationResult::success()
+            } else {
+                ValidationResult::failure(vec![ValidationError {
+                    field: "name".to_string(),
+                    message: "required".to_string(),
+                    code: "REQUIRED".to_string(),
+                }])
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_registry_register_body_stream() {
+        let mut registry = HandlerRegistry::new();
+        registry.register_body_stream("ingest", |_ctx, body: BodyStream| async move {
+            let mut items = body.ndjson::<TestRequest>();
+            let (mut accepted, mut rejected) = (Vec::new(), Vec::new());
+            while let Some(item) = items.next_item().await {
+                match item {
+                    Ok(item) => accepted.push(item.name),
+                    Err(e) if e.is_item() => rejected.push(e.line().unwrap_or_default()),
+                    Err(e) => return Err(HandlerError::from(e)),
+                }
+            }
+            Ok(archimedes_extract::Json(
+                serde_json::json!({ "accepted": accepted, "rejected": rejected }),
+            ))
+        });
+        registry.set_item_validator(Arc::new(NameRequired));
+
+        assert!(registry.contains("ingest"));
+        assert!(registry.is_body_stream("ingest"));
+        assert!(!registry.is_streaming("ingest"));
+        assert!(registry.get("ingest").is_none());
+        assert_eq!(registry.len(), 1);
+        assert_eq!(
+            registry.body_stream_operation_ids().collect::<Vec<_>>(),
+            ["ingest"]
+        );
+
+        let validator = registry
+            .item_validator("ingest", None, &http::HeaderMap::new())
+            .unwrap();
+        let body = BodyStream::from_bytes(Bytes::from_static(
+            b"{\"name\":\"a\"}\n{\"id\":1}\n{\"name\":\"b\"}\n",
+        ))
+        .with_item_validator(validator);
+        let response = registry
+            .invoke_body_stream("ingest", RequestContext::new(), body)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(json["accepted"], serde_json::json!(["a", "b"]));
+        assert_eq!(json["rejected"], serde_json::json!([2]));
+
+        assert!(registry
+            .invoke_body_stream(
+                "missing",
+                RequestContext::new(),
+                BodyStream::from_bytes(Bytes::new())
+            )
+            .await
+            .is_err());
     }
 
     #[test]
@@ -540,6 +1380,52 @@ mod tests {
         assert_eq!(response.greeting, "Hello, World!");
     }
 
+    #[tokio::test]
+    async fn test_registry_invoke_response_keeps_json() {
+        let mut registry = HandlerRegistry::new();
+        registry.register("test", test_handler);
+
+        let body = Bytes::from(r#"{"name":"Alice"}"#);
+        let response = registry
+            .invoke_response("test", RequestContext::new(), body)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], APPLICATION_JSON);
+    }
+
+    #[tokio::test]
+    async fn test_registry_register_response() {
+        use archimedes_extract::response::NoContent;
+        use archimedes_extract::Json;
+
+        let mut registry = HandlerRegistry::new();
+        registry.register_response("create", |_ctx, req: TestRequest| async move {
+            let greeting = format!("Hello, {}!", req.name);
+            Ok::<_, HandlerError>((StatusCode::CREATED, Json(TestResponse { greeting })))
+        });
+        registry
+            .register_response_no_body("purge", |_ctx| async { Ok::<_, HandlerError>(NoContent) });
+        assert_eq!(registry.request_fields("create"), Some(&["name"][..]));
+
+        let body = Bytes::from(r#"{"name":"Alice"}"#);
+        let response = registry
+            .invoke_response("create", RequestContext::new(), body)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], APPLICATION_JSON);
+        let created: TestResponse = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(created.greeting, "Hello, Alice!");
+
+        let response = registry
+            .invoke_response("purge", RequestContext::new(), Bytes::new())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert!(response.body().is_empty());
+    }
+
     #[tokio::test]
     async fn test_registry_invoke_not_found() {
         let registry = HandlerRegistry::new();
@@ -633,4 +1519,100 @@ mod tests {
             _ => panic!("Expected Custom error"),
         }
     }
+
+    #[derive(Debug)]
+    enum OrderError {
+        NotFound(String),
+        AlreadyShipped(String),
+    }
+
+    impl IntoErrorResponse for OrderError {
+        fn into_error_response(self) -> ErrorResponse {
+            match self {
+                Self::NotFound(id) => ErrorResponse::new(
+                    StatusCode::NOT_FOUND,
+                    "ORDER_NOT_FOUND",
+                    format!("Order {} not found", id),
+                ),
+                Self::AlreadyShipped(id) => ErrorResponse::new(
+                    StatusCode::CONFLICT,
+                    "ORDER_SHIPPED",
+                    format!("Order {} has already shipped", id),
+                )
+                .with_details(serde_json::json!({ "order_id": id })),
+            }
+        }
+    }
+
+    async fn cancel_order(
+        _ctx: RequestContext,
+        req: TestRequest,
+    ) -> Result<TestResponse, OrderError> {
+        match req.name.as_str() {
+            "missing" => Err(OrderError::NotFound(req.name)),
+            "shipped" => Err(OrderError::AlreadyShipped(req.name)),
+            _ => Ok(TestResponse {
+                greeting: format!("Cancelled {}", req.name),
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_registry_invoke_typed_error() {
+        let mut registry = HandlerRegistry::new();
+        registry.register("cancelOrder", cancel_order);
+
+        let invoke = |name: &str| {
+            let body = Bytes::from(format!(r#"{{"name":"{}"}}"#, name));
+            registry.invoke("cancelOrder", RequestContext::new(), body)
+        };
+
+        let Err(InvokeError::HandlerError(error)) = invoke("missing").await else {
+            panic!("Expected handler error");
+        };
+        let response = error.into_error_response();
+        assert_eq!(response.status, StatusCode::NOT_FOUND);
+        assert_eq!(response.code, "ORDER_NOT_FOUND");
+        assert_eq!(response.message, "Order missing not found");
+
+        let Err(InvokeError::HandlerError(error)) = invoke("shipped").await else {
+            panic!("Expected handler error");
+        };
+        let response = error.into_error_response();
+        assert_eq!(response.status, StatusCode::CONFLICT);
+        assert_eq!(response.details.unwrap()["order_id"], "shipped");
+
+        assert!(invoke("o-1").await.is_ok());
+    }
+
+    #[test]
+    fn test_error_response_conversions() {
+        let response = ThemisError::not_found("no such user").into_error_response();
+        assert_eq!(response.status, StatusCode::NOT_FOUND);
+        assert_eq!(response.code, "NOT_FOUND");
+
+        let boxed: Box<dyn std::error::Error + Send + Sync> = "disk full".into();
+        let response = boxed.into_error_response();
+        assert_eq!(response.status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(response.code, "INTERNAL_ERROR");
+
+        // Existing error kinds keep their variant through dispatch
+        let error = ThemisError::conflict("taken").into_handler_error();
+        assert!(matches!(error, HandlerError::ThemisError(_)));
+        let response = HandlerError::DeserializationError("bad".to_string()).into_error_response();
+        assert_eq!(response.status, StatusCode::BAD_REQUEST);
+
+        // Extraction errors list every failing field
+        let error = ExtractionError::aggregate([
+            ExtractionError::missing(ExtractionSource::Body, "email"),
+            ExtractionError::invalid_type(ExtractionSource::Query, "limit", "invalid digit"),
+        ])
+        .unwrap();
+        let response = error.into_error_response();
+        assert_eq!(response.status, StatusCode::BAD_REQUEST);
+        assert_eq!(response.code, "INVALID_REQUEST");
+        let issues = &response.details.unwrap()["issues"];
+        assert_eq!(issues[0]["field"], "email");
+        assert_eq!(issues[1]["source"], "query");
+    }
 }
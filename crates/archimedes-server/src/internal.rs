@@ -0,0 +1,292 @@
+//! Internal endpoints served outside the contract.
+//!
+//! Health, readiness, diagnostics and the batch endpoint are not contract
+//! operations: they have no schemas to validate against and no policy to
+//! authorize, and probes must not be rate limited. [`InternalRoutes`] is
+//! the registry of such endpoints. Requests to them still pass through the
+//! middleware pipeline, so they get a request ID, tracing, telemetry and
+//! normalized errors, but they carry [`RouteOptions::internal`], which
+//! makes authorization, request and response validation and rate limiting
+//! step aside. The decision is made once, when the route is resolved, and
+//! is visible to every stage through [`MiddlewareContext::is_internal`].
+//!
+//! Internal routes are keyed by method and path, never by operation ID, so
+//! a contract operation cannot be marked internal. A route that collides
+//! with a contract operation is rejected when the server starts.
+//!
+//! Internal handlers see the full request target, query string included.
+//!
+//! # Resolution debugging
+//!
+//! [`ServerBuilder::resolve_endpoint`](crate::ServerBuilder::resolve_endpoint)
+//! serves a dry-run resolution at [`RESOLVE_PATH`]:
+//! `GET /-/resolve?method=POST&path=/users/123` returns the explanation
+//! produced by the given [`ResolveExplainer`], typically
+//! `archimedes_sentinel::Sentinel::explain`. It is never registered by
+//! default and should stay off in production.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use archimedes_server::Server;
+//! use http::Method;
+//!
+//! let server = Server::builder()
+//!     .internal_route(Method::GET, "/metrics", |_request| async {
+//!         http::Response::new(http_body_util::Full::new(render_metrics()))
+//!     })
+//!     .build();
+//! ```
+//!
+//! [`RouteOptions::internal`]: archimedes_middleware::RouteOptions::internal
+//! [`MiddlewareContext::is_internal`]: archimedes_middleware::MiddlewareContext::is_internal
+
+use std::future::Future;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use futures_util::future::BoxFuture;
+use http::{Method, Request, Response};
+use http_body_util::Full;
+
+use crate::batch::{is_batch_path, BATCH_PATH};
+use crate::diagnostics::DIAGNOSTICS_PATH;
+use crate::observability::MetricsSource;
+use crate::router::Router;
+use crate::server::ServerError;
+
+/// Path of the resolution debugging endpoint.
+pub const RESOLVE_PATH: &str = "/-/resolve";
+
+/// Handler for a custom internal endpoint.
+pub type InternalHandler =
+    Arc<dyn Fn(Request<Bytes>) -> BoxFuture<'static, Response<Full<Bytes>>> + Send + Sync>;
+
+/// Explains how a request method and path resolve, as JSON.
+pub type ResolveExplainer = Arc<dyn Fn(&str, &str) -> serde_json::Value + Send + Sync>;
+
+/// What serves an internal route.
+#[derive(Clone)]
+pub(crate) enum InternalEndpoint {
+    /// The `/health` liveness endpoint.
+    Health,
+    /// The `/ready` readiness endpoint.
+    Ready,
+    /// The `/-/diagnostics` report.
+    Diagnostics,
+    /// The `/-/batch` endpoint.
+    Batch,
+    /// The `/-/resolve` endpoint.
+    Resolve(ResolveExplainer),
+    /// The metrics endpoint.
+    Metrics(MetricsSource),
+    /// A handler registered by the application.
+    Custom(InternalHandler),
+}
+
+/// A registered internal route.
+#[derive(Clone)]
+pub(crate) struct InternalRoute {
+    /// HTTP method.
+    pub(crate) method: Method,
+    /// Path, also used as the route label.
+    pub(crate) path: String,
+    /// What serves the route.
+    pub(crate) endpoint: InternalEndpoint,
+}
+
+impl InternalRoute {
+    /// Returns true if the route serves `method` and `path`.
+    fn matches(&self, method: &Method, path: &str) -> bool {
+        if self.method != method {
+            return false;
+        }
+        match self.endpoint {
+            InternalEndpoint::Batch => is_batch_path(path),
+            _ => self.path == path,
+        }
+    }
+}
+
+/// Registry of endpoints served outside the contract.
+///
+/// The server registers its built-in endpoints here; applications add
+/// their own, such as `/metrics` or API docs, with
+/// [`ServerBuilder::internal_route`](crate::ServerBuilder::internal_route).
+#[derive(Clone, Default)]
+pub struct InternalRoutes {
+    routes: Vec<InternalRoute>,
+}
+
+impl InternalRoutes {
+    /// Creates an empty registry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a custom internal endpoint.
+    ///
+    /// Replaces any route already registered for the same method and path,
+    /// including a built-in one.
+    pub fn register<F, Fut>(&mut self, method: Method, path: impl Into<String>, handler: F)
+    where
+        F: Fn(Request<Bytes>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Response<Full<Bytes>>> + Send + 'static,
+    {
+        let handler: InternalHandler = Arc::new(move |request| Box::pin(handler(request)));
+        self.insert(method, path, InternalEndpoint::Custom(handler));
+    }
+
+    /// Registers the server's built-in endpoints.
+    pub(crate) fn with_builtins(diagnostics: bool, batch: bool) -> Self {
+        let mut routes = Self::new();
+        routes.insert(Method::GET, "/health", InternalEndpoint::Health);
+        routes.insert(Method::GET, "/ready", InternalEndpoint::Ready);
+        if diagnostics {
+            routes.insert(Method::GET, DIAGNOSTICS_PATH, InternalEndpoint::Diagnostics);
+        }
+        if batch {
+            routes.insert(Method::POST, BATCH_PATH, InternalEndpoint::Batch);
+        }
+        routes
+    }
+
+    /// Adds a route, replacing one with the same method and path.
+    pub(crate) fn insert(
+        &mut self,
+        method: Method,
+        path: impl Into<String>,
+        endpoint: InternalEndpoint,
+    ) {
+        let path = path.into();
+        self.routes
+            .retain(|route| route.method != method || route.path != path);
+        self.routes.push(InternalRoute {
+            method,
+            path,
+            endpoint,
+        });
+    }
+
+    /// Removes the route for a method and path, if any.
+    pub(crate) fn remove(&mut self, method: &Method, path: &str) {
+        self.routes
+            .retain(|route| route.method != method || route.path != path);
+    }
+
+    /// Finds the route serving `method` and `path`.
+    pub(crate) fn find(&self, method: &Method, path: &str) -> Option<&InternalRoute> {
+        self.routes.iter().find(|route| route.matches(method, path))
+    }
+
+    /// Returns true if `method` and `path` are served as an internal route.
+    #[must_use]
+    pub fn contains(&self, method: &Method, path: &str) -> bool {
+        self.find(method, path).is_some()
+    }
+
+    /// Returns the method and path of each registered route.
+    pub fn routes(&self) -> impl Iterator<Item = (&Method, &str)> {
+        self.routes
+            .iter()
+            .map(|route| (&route.method, route.path.as_str()))
+    }
+
+    /// Returns the number of registered routes.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.routes.len()
+    }
+
+    /// Returns true if no routes are registered.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    /// Checks that no internal route shadows a contract operation.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServerError::InvalidConfig`] naming the first internal
+    /// route that the router resolves to a contract operation.
+    pub fn check_conflicts(&self, router: &Router) -> Result<(), ServerError> {
+        for route in &self.routes {
+            if let Some(route_match) = router.match_route(&route.method, &route.path) {
+                return Err(ServerError::InvalidConfig(format!(
+                    "internal route {} {} conflicts with contract operation '{}'",
+                    route.method,
+                    route.path,
+                    route_match.operation_id()
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for InternalRoutes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(
+                self.routes
+                    .iter()
+                    .map(|route| format!("{} {}", route.method, route.path)),
+            )
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtins() {
+        let routes = InternalRoutes::with_builtins(false, false);
+        assert_eq!(routes.len(), 2);
+        assert!(routes.contains(&Method::GET, "/health"));
+        assert!(routes.contains(&Method::GET, "/ready"));
+        assert!(!routes.contains(&Method::POST, "/health"));
+        assert!(!routes.contains(&Method::GET, DIAGNOSTICS_PATH));
+        assert!(!routes.contains(&Method::POST, BATCH_PATH));
+
+        let routes = InternalRoutes::with_builtins(true, true);
+        assert_eq!(routes.len(), 4);
+        assert!(routes.contains(&Method::GET, DIAGNOSTICS_PATH));
+        assert!(routes.contains(&Method::POST, "/-/Batch/"));
+    }
+
+    #[test]
+    fn test_register_replaces_existing_route() {
+        let mut routes = InternalRoutes::with_builtins(false, false);
+        routes.register(Method::GET, "/health", |_request| async {
+            Response::new(Full::new(Bytes::from_static(b"custom")))
+        });
+
+        assert_eq!(routes.len(), 2);
+        let route = routes.find(&Method::GET, "/health").unwrap();
+        assert!(matches!(route.endpoint, InternalEndpoint::Custom(_)));
+    }
+
+    #[test]
+    fn test_check_conflicts() {
+        let mut router = Router::new();
+        router.add_route(Method::GET, "/users/{id}", "getUser");
+
+        let mut routes = InternalRoutes::with_builtins(true, true);
+        assert!(routes.check_conflicts(&router).is_ok());
+
+        routes.register(Method::GET, "/users/metrics", |_request| async {
+            Response::new(Full::new(Bytes::new()))
+        });
+        match routes.check_conflicts(&router) {
+            Err(ServerError::InvalidConfig(msg)) => {
+                assert!(msg.contains("/users/metrics"));
+                assert!(msg.contains("getUser"));
+            }
+            other => panic!("Expected InvalidConfig, got {other:?}"),
+        }
+    }
+}
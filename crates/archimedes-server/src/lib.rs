@@ -7,7 +7,18 @@
 //! - HTTP/1.1 and HTTP/2 support via Hyper
 //! - Request routing with contract-based path resolution
 //! - Graceful shutdown with configurable timeout
+//! - Connection tuning (keep-alive, HTTP/2 settings, max requests per connection)
 //! - Health check endpoints (`/health`, `/ready`)
+//! - Prometheus metrics endpoint, optionally on its own listener (see
+//!   [`observability`])
+//! - Opt-in batch endpoint (`/-/batch`)
+//! - Startup diagnostics report (optionally served at `/-/diagnostics`)
+//! - Internal endpoints that bypass authorization, validation and rate
+//!   limiting (see [`internal`])
+//! - Several apps on one listener, selected by host or path prefix (see
+//!   [`apps`])
+//! - Warm-up of validation, authorization and connections before the
+//!   server reports ready (see [`warmup`])
 //!
 //! ## Example
 //!
@@ -47,20 +58,35 @@
 #![warn(missing_docs)]
 #![forbid(unsafe_code)]
 
+pub mod apps;
+pub mod batch;
 mod config;
+pub mod diagnostics;
 pub mod handler;
 mod health;
+pub mod internal;
 mod lifecycle;
+pub mod observability;
 mod router;
 mod server;
 pub mod shutdown;
 pub mod static_files;
+mod trailers;
+pub mod warmup;
 
-pub use config::{ServerConfig, ServerConfigBuilder};
-pub use handler::{HandlerError, HandlerRegistry, InvokeError};
+pub use apps::AppSpec;
+pub use batch::{BatchConfig, BatchError, BatchSubRequest, BatchSubResponse};
+pub use config::{ServerConfig, ServerConfigBuilder, MAX_HTTP2_WINDOW_SIZE};
+pub use diagnostics::{ContractInfo, Diagnostics, HandlerCoverage};
+pub use handler::{
+    ErrorResponse, HandlerError, HandlerRegistry, IntoErrorResponse, InvokeError, ParamNameMismatch,
+};
 pub use health::{HealthCheck, HealthStatus, ReadinessCheck, ReadinessStatus};
-pub use lifecycle::{Lifecycle, LifecycleError, LifecycleHook, LifecycleResult};
+pub use internal::{InternalHandler, InternalRoutes, ResolveExplainer, RESOLVE_PATH};
+pub use lifecycle::{Lifecycle, LifecycleError, LifecycleHook, LifecycleResult, WarmupHook};
+pub use observability::ObservabilityEndpoints;
 pub use router::{RouteMatch, Router};
-pub use server::{Server, ServerBuilder, ServerError};
+pub use server::{Server, ServerBuilder, ServerError, DEFAULT_DRAIN_TIMEOUT};
 pub use shutdown::ShutdownSignal;
 pub use static_files::{StaticFileError, StaticFiles, StaticFilesBuilder};
+pub use warmup::{Warmup, WarmupReport, WarmupStep, DEFAULT_WARMUP_BUDGET};
@@ -30,6 +30,8 @@
 //!
 //! - **Startup hooks**: Run in registration order before server starts accepting connections
 //! - **Shutdown hooks**: Run in reverse registration order after server stops accepting connections
+//! - **Warm-up hooks**: Run in registration order during the server's
+//!   [warm-up](crate::warmup), before it reports ready
 
 use std::fmt;
 use std::future::Future;
@@ -95,6 +97,13 @@ pub type LifecycleHook = Arc<
         + Sync,
 >;
 
+/// A warm-up callback, e.g. priming a cache or a connection pool.
+///
+/// Runs during the server's [warm-up](crate::warmup), bounded by its
+/// budget.
+pub type WarmupHook =
+    Arc<dyn Fn() -> Pin<Box<dyn Future<Output = LifecycleResult> + Send>> + Send + Sync>;
+
 /// Lifecycle manager for server startup and shutdown hooks.
 ///
 /// Provides a fluent API for registering callbacks that run during
@@ -115,6 +124,8 @@ pub struct Lifecycle {
     startup_hooks: Vec<(String, LifecycleHook)>,
     /// Shutdown hooks (run in reverse order)
     shutdown_hooks: Vec<(String, LifecycleHook)>,
+    /// Warm-up hooks (run in order)
+    warmup_hooks: Vec<(String, WarmupHook)>,
 }
 
 impl Default for Lifecycle {
@@ -128,6 +139,7 @@ impl fmt::Debug for Lifecycle {
         f.debug_struct("Lifecycle")
             .field("startup_hooks", &self.startup_hooks.len())
             .field("shutdown_hooks", &self.shutdown_hooks.len())
+            .field("warmup_hooks", &self.warmup_hooks.len())
             .finish()
     }
 }
@@ -138,6 +150,7 @@ impl Lifecycle {
         Self {
             startup_hooks: Vec::new(),
             shutdown_hooks: Vec::new(),
+            warmup_hooks: Vec::new(),
         }
     }
 
@@ -225,6 +238,48 @@ impl Lifecycle {
         self
     }
 
+    /// Registers a warm-up hook.
+    ///
+    /// Warm-up hooks run after the server starts accepting connections and
+    /// before it reports ready, when the lifecycle is passed to
+    /// [`Warmup::lifecycle`](crate::Warmup::lifecycle). They run in
+    /// registration order, bounded by the warm-up budget; a failing hook is
+    /// logged and does not keep the server from becoming ready.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use archimedes_server::Lifecycle;
+    ///
+    /// let lifecycle = Lifecycle::new()
+    ///     .on_warmup(|| async move {
+    ///         println!("Priming caches...");
+    ///         Ok(())
+    ///     });
+    /// ```
+    pub fn on_warmup<F, Fut>(self, hook: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = LifecycleResult> + Send + 'static,
+    {
+        let name = format!("warmup_{}", self.warmup_hooks.len());
+        self.on_warmup_named(name, hook)
+    }
+
+    /// Registers a named warm-up hook.
+    ///
+    /// Like `on_warmup` but with a custom name, reported in the warm-up
+    /// timings.
+    pub fn on_warmup_named<F, Fut>(mut self, name: impl Into<String>, hook: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = LifecycleResult> + Send + 'static,
+    {
+        let hook: WarmupHook = Arc::new(move || Box::pin(hook()));
+        self.warmup_hooks.push((name.into(), hook));
+        self
+    }
+
     /// Returns the number of startup hooks.
     pub fn startup_hook_count(&self) -> usize {
         self.startup_hooks.len()
@@ -235,6 +290,16 @@ impl Lifecycle {
         self.shutdown_hooks.len()
     }
 
+    /// Returns the number of warm-up hooks.
+    pub fn warmup_hook_count(&self) -> usize {
+        self.warmup_hooks.len()
+    }
+
+    /// Returns the warm-up hooks with their names, in registration order.
+    pub(crate) fn warmup_hooks(&self) -> &[(String, WarmupHook)] {
+        &self.warmup_hooks
+    }
+
     /// Runs all startup hooks in registration order.
     ///
     /// If any hook fails, execution stops and the error is returned.
@@ -308,6 +373,7 @@ impl Lifecycle {
     pub fn merge(mut self, other: Lifecycle) -> Self {
         self.startup_hooks.extend(other.startup_hooks);
         self.shutdown_hooks.extend(other.shutdown_hooks);
+        self.warmup_hooks.extend(other.warmup_hooks);
         self
     }
 }
@@ -521,12 +587,15 @@ mod tests {
 
         let lifecycle2 = Lifecycle::new()
             .on_startup(|_| async { Ok(()) })
-            .on_shutdown(|_| async { Ok(()) });
+            .on_shutdown(|_| async { Ok(()) })
+            .on_warmup(|| async { Ok(()) });
 
         let merged = lifecycle1.merge(lifecycle2);
 
         assert_eq!(merged.startup_hook_count(), 2);
         assert_eq!(merged.shutdown_hook_count(), 2);
+        assert_eq!(merged.warmup_hook_count(), 1);
+        assert_eq!(merged.warmup_hooks()[0].0, "warmup_0");
     }
 
     #[tokio::test]
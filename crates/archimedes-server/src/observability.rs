@@ -0,0 +1,300 @@
+//! Metrics, health and readiness endpoints.
+//!
+//! [`ServerBuilder::with_observability_endpoints`] serves the Prometheus
+//! exposition of the telemetry metrics and the server's health and
+//! readiness checks at the given paths, so services stop wiring them by
+//! hand:
+//!
+//! ```rust,ignore
+//! use archimedes_server::Server;
+//!
+//! let server = Server::builder()
+//!     .with_observability_endpoints("/metrics", "/health", "/ready")
+//!     .build();
+//! ```
+//!
+//! The endpoints are [internal routes](crate::internal): they skip
+//! authorization, validation and rate limiting, so probes and scrapers
+//! need no credentials.
+//!
+//! Metrics are rendered from the recorder installed by
+//! `archimedes_telemetry::init_metrics` unless a
+//! [`MetricsRegistry`] is given. To keep metrics off the public listener,
+//! serve them on their own address with
+//! [`ObservabilityEndpoints::metrics_addr`]; the health and readiness
+//! endpoints stay on the main listener, where the orchestrator probes them.
+//!
+//! [`ServerBuilder::with_observability_endpoints`]: crate::ServerBuilder::with_observability_endpoints
+
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use archimedes_telemetry::metrics::render_metrics;
+use archimedes_telemetry::{ExpositionFormat, MetricsRegistry};
+use bytes::Bytes;
+use http::{header, HeaderMap, HeaderValue, Method, Request, Response, StatusCode};
+use http_body_util::Full;
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper_util::rt::TokioIo;
+use tokio::net::TcpListener;
+
+use crate::shutdown::ShutdownSignal;
+
+/// Default path of the metrics endpoint.
+pub const DEFAULT_METRICS_PATH: &str = "/metrics";
+
+/// Paths and metrics source of the observability endpoints.
+#[derive(Debug, Clone)]
+pub struct ObservabilityEndpoints {
+    metrics_path: String,
+    health_path: String,
+    ready_path: String,
+    metrics_addr: Option<String>,
+    registry: Option<Arc<MetricsRegistry>>,
+}
+
+impl Default for ObservabilityEndpoints {
+    fn default() -> Self {
+        Self::new(DEFAULT_METRICS_PATH, "/health", "/ready")
+    }
+}
+
+impl ObservabilityEndpoints {
+    /// Serves metrics, health and readiness at the given paths.
+    #[must_use]
+    pub fn new(
+        metrics_path: impl Into<String>,
+        health_path: impl Into<String>,
+        ready_path: impl Into<String>,
+    ) -> Self {
+        Self {
+            metrics_path: metrics_path.into(),
+            health_path: health_path.into(),
+            ready_path: ready_path.into(),
+            metrics_addr: None,
+            registry: None,
+        }
+    }
+
+    /// Serves metrics on their own listener at `addr` instead of the main
+    /// listener.
+    #[must_use]
+    pub fn metrics_addr(mut self, addr: impl Into<String>) -> Self {
+        self.metrics_addr = Some(addr.into());
+        self
+    }
+
+    /// Renders metrics from `registry` instead of the global recorder.
+    #[must_use]
+    pub fn metrics_registry(mut self, registry: Arc<MetricsRegistry>) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
+    /// Returns the path of the metrics endpoint.
+    #[must_use]
+    pub fn metrics_path(&self) -> &str {
+        &self.metrics_path
+    }
+
+    /// Returns the path of the health endpoint.
+    #[must_use]
+    pub fn health_path(&self) -> &str {
+        &self.health_path
+    }
+
+    /// Returns the path of the readiness endpoint.
+    #[must_use]
+    pub fn ready_path(&self) -> &str {
+        &self.ready_path
+    }
+
+    /// Returns the address of the dedicated metrics listener, if any.
+    #[must_use]
+    pub fn metrics_listener_addr(&self) -> Option<&str> {
+        self.metrics_addr.as_deref()
+    }
+
+    /// Returns the source metrics are rendered from.
+    pub(crate) fn metrics_source(&self) -> MetricsSource {
+        MetricsSource(self.registry.clone())
+    }
+}
+
+/// Where metrics are rendered from: a registry, or the global recorder.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MetricsSource(Option<Arc<MetricsRegistry>>);
+
+impl MetricsSource {
+    /// Renders the metrics in Prometheus text format, or `None` when no
+    /// recorder is installed.
+    fn render(&self) -> Option<String> {
+        match &self.0 {
+            Some(registry) => Some(registry.render()),
+            None => render_metrics(),
+        }
+    }
+
+    /// Answers a scrape in the format negotiated from its `Accept` header.
+    ///
+    /// Answers `503 Service Unavailable` while no recorder is installed.
+    pub(crate) fn respond(&self, headers: &HeaderMap) -> Response<Full<Bytes>> {
+        let Some(text) = self.render() else {
+            return text_response(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "text/plain; charset=utf-8",
+                "metrics are not initialized".to_string(),
+            );
+        };
+        let format = ExpositionFormat::negotiate(
+            headers
+                .get(header::ACCEPT)
+                .and_then(|value| value.to_str().ok()),
+        );
+        text_response(StatusCode::OK, format.content_type(), format.encode(&text))
+    }
+}
+
+/// Serves metrics at `path` on `listener` until shutdown.
+///
+/// Any other request is answered with `404 Not Found`.
+pub(crate) async fn serve_metrics(
+    listener: TcpListener,
+    path: String,
+    source: MetricsSource,
+    shutdown: ShutdownSignal,
+) {
+    let path: Arc<str> = path.into();
+    loop {
+        let stream = tokio::select! {
+            result = listener.accept() => match result {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    tracing::warn!("Failed to accept metrics connection: {}", e);
+                    continue;
+                }
+            },
+            _ = shutdown.recv() => break,
+        };
+
+        let path = Arc::clone(&path);
+        let source = source.clone();
+        tokio::spawn(async move {
+            let service = service_fn(move |request: Request<hyper::body::Incoming>| {
+                let response = if request.uri().path() != &*path {
+                    text_response(
+                        StatusCode::NOT_FOUND,
+                        "text/plain; charset=utf-8",
+                        "not found".to_string(),
+                    )
+                } else if request.method() != Method::GET {
+                    let mut response = text_response(
+                        StatusCode::METHOD_NOT_ALLOWED,
+                        "text/plain; charset=utf-8",
+                        "method not allowed".to_string(),
+                    );
+                    response
+                        .headers_mut()
+                        .insert(header::ALLOW, HeaderValue::from_static("GET"));
+                    response
+                } else {
+                    source.respond(request.headers())
+                };
+                async move { Ok::<_, Infallible>(response) }
+            });
+            if let Err(e) = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await
+            {
+                tracing::debug!("Metrics connection error: {}", e);
+            }
+        });
+    }
+}
+
+fn text_response(
+    status: StatusCode,
+    content_type: &'static str,
+    body: String,
+) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(Bytes::from(body)));
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_body_util::BodyExt;
+    use metrics_exporter_prometheus::PrometheusBuilder;
+
+    fn registry_with_counter() -> Arc<MetricsRegistry> {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        metrics::with_local_recorder(&recorder, || {
+            metrics::counter!("orders_created_total").increment(3);
+        });
+        Arc::new(MetricsRegistry::new(recorder.handle()))
+    }
+
+    #[test]
+    fn test_default_paths() {
+        let endpoints = ObservabilityEndpoints::default();
+        assert_eq!(endpoints.metrics_path(), "/metrics");
+        assert_eq!(endpoints.health_path(), "/health");
+        assert_eq!(endpoints.ready_path(), "/ready");
+        assert_eq!(endpoints.metrics_listener_addr(), None);
+    }
+
+    #[tokio::test]
+    async fn test_respond_renders_registry() {
+        let source = ObservabilityEndpoints::default()
+            .metrics_registry(registry_with_counter())
+            .metrics_source();
+
+        let response = source.respond(&HeaderMap::new());
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            ExpositionFormat::Prometheus.content_type()
+        );
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("orders_created_total 3"), "{body}");
+    }
+
+    #[tokio::test]
+    async fn test_serve_metrics_on_own_listener() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let shutdown = ShutdownSignal::new();
+        let source = MetricsSource(Some(registry_with_counter()));
+        tokio::spawn(serve_metrics(
+            listener,
+            "/internal/metrics".to_string(),
+            source,
+            shutdown.clone(),
+        ));
+
+        for (path, expected) in [("/internal/metrics", "200"), ("/health", "404")] {
+            let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+            stream
+                .write_all(
+                    format!("GET {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+                        .as_bytes(),
+                )
+                .await
+                .unwrap();
+            let mut raw = String::new();
+            stream.read_to_string(&mut raw).await.unwrap();
+            assert!(raw.starts_with(&format!("HTTP/1.1 {expected}")), "{raw}");
+        }
+
+        shutdown.trigger();
+    }
+}
@@ -45,7 +45,7 @@
 
 use std::collections::HashMap;
 
-use archimedes_router::MethodRouter;
+use archimedes_router::{MethodRouter, UrlForError, UrlGenerator};
 use http::Method;
 
 /// A matched route with extracted path parameters.
@@ -128,6 +128,9 @@ pub struct Router {
     /// Track operation IDs for `has_operation` queries
     /// Maps `operation_id` -> route count (for tracking)
     operation_ids: HashMap<String, usize>,
+
+    /// Registered routes in insertion order, for listing
+    routes: Vec<(Method, String, String)>,
 }
 
 impl Router {
@@ -146,6 +149,7 @@ impl Router {
         Self {
             inner: archimedes_router::Router::new(),
             operation_ids: HashMap::new(),
+            routes: Vec::new(),
         }
     }
 
@@ -178,7 +182,9 @@ impl Router {
         self.inner.insert(pattern.as_ref(), method_router);
 
         // Track the operation ID
-        *self.operation_ids.entry(operation_id).or_insert(0) += 1;
+        *self.operation_ids.entry(operation_id.clone()).or_insert(0) += 1;
+        self.routes
+            .push((method, pattern.as_ref().to_string(), operation_id));
     }
 
     /// Returns the number of registered routes.
@@ -281,6 +287,66 @@ impl Router {
     pub fn operation_ids(&self) -> impl Iterator<Item = &str> {
         self.operation_ids.keys().map(String::as_str)
     }
+
+    /// Returns the registered routes as `(method, pattern, operation_id)`,
+    /// in the order they were added.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use archimedes_server::Router;
+    /// use http::Method;
+    ///
+    /// let mut router = Router::new();
+    /// router.add_route(Method::GET, "/users/{userId}", "getUser");
+    ///
+    /// let routes: Vec<_> = router.routes().collect();
+    /// assert_eq!(routes, [(&Method::GET, "/users/{userId}", "getUser")]);
+    /// ```
+    pub fn routes(&self) -> impl Iterator<Item = (&Method, &str, &str)> {
+        self.routes.iter().map(|(method, pattern, operation_id)| {
+            (method, pattern.as_str(), operation_id.as_str())
+        })
+    }
+
+    /// Builds the URL for an operation by substituting path parameters.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UrlForError`] if the operation has no route, a parameter is
+    /// missing or unexpected, or the route ends in a wildcard.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use archimedes_server::Router;
+    /// use http::Method;
+    ///
+    /// let mut router = Router::new();
+    /// router.add_route(Method::GET, "/users/{userId}", "getUser");
+    ///
+    /// let url = router.url_for("getUser", &[("userId", "42")]).unwrap();
+    /// assert_eq!(url, "/users/42");
+    /// ```
+    pub fn url_for(
+        &self,
+        operation_id: &str,
+        params: &[(&str, &str)],
+    ) -> Result<String, UrlForError> {
+        self.inner.url_for(operation_id, params)
+    }
+
+    /// Returns the route pattern registered for an operation.
+    #[must_use]
+    pub fn route_pattern(&self, operation_id: &str) -> Option<&str> {
+        self.inner.route_pattern(operation_id)
+    }
+
+    /// Returns a URL generator over a snapshot of the current routes.
+    #[must_use]
+    pub fn url_generator(&self) -> UrlGenerator {
+        UrlGenerator::new(self.inner.clone())
+    }
 }
 
 /// Normalizes a path for routing.
@@ -523,4 +589,39 @@ mod tests {
         assert_eq!(router1.route_count(), router2.route_count());
         assert!(router2.match_route(&Method::GET, "/health").is_some());
     }
+
+    #[test]
+    fn test_router_url_for() {
+        let mut router = Router::new();
+        router.add_route(Method::GET, "/users/{userId}", "getUser");
+        router.add_route(Method::GET, "/files/*path", "serveFile");
+
+        assert_eq!(
+            router.url_for("getUser", &[("userId", "a/b")]).unwrap(),
+            "/users/a%2Fb"
+        );
+        assert_eq!(
+            router.url_for("serveFile", &[("path", "x")]),
+            Err(UrlForError::WildcardRoute("serveFile".to_string()))
+        );
+        assert_eq!(
+            router
+                .url_generator()
+                .base_path("/api")
+                .url_for("getUser", &[("userId", "1")])
+                .unwrap(),
+            "/api/users/1"
+        );
+    }
+
+    #[test]
+    fn test_router_route_pattern() {
+        let mut router = Router::new();
+        router.add_route(Method::GET, "/users/{userId}", "getUser");
+        router.add_route(Method::GET, "/files/*path", "serveFile");
+
+        assert_eq!(router.route_pattern("getUser"), Some("/users/{userId}"));
+        assert_eq!(router.route_pattern("serveFile"), Some("/files/*path"));
+        assert_eq!(router.route_pattern("unknown"), None);
+    }
 }
@@ -29,27 +29,53 @@
 //! }
 //! ```
 
+use std::collections::HashMap;
 use std::convert::Infallible;
+use std::future::Future;
+use std::io;
 use std::net::SocketAddr;
-use std::sync::Arc;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
 use std::time::Duration;
 
 use bytes::Bytes;
-use http::{Method, Request, Response, StatusCode};
-use http_body_util::{BodyExt, Full};
-use hyper::body::Incoming;
-use hyper::server::conn::http1;
+use futures_util::StreamExt;
+use http::header::{CACHE_CONTROL, CONNECTION};
+use http::{HeaderMap, HeaderValue, Method, Request, Response, StatusCode, Uri, Version};
+use http_body_util::{BodyExt, Either, Full};
+use hyper::body::{Body, Incoming};
 use hyper::service::service_fn;
-use hyper_util::rt::TokioIo;
+use hyper_util::rt::{TokioExecutor, TokioIo, TokioTimer};
+use hyper_util::server::conn::auto;
 use tokio::net::TcpListener;
 
-use archimedes_core::RequestContext;
-
+use archimedes_core::di::Container;
+use archimedes_core::startup::StartupError;
+use archimedes_core::timing::{self, PhaseTimings, RequestTiming};
+use archimedes_core::{CachePolicy, Obligations, RequestContext, StreamOutcome, UrlGenerator};
+use archimedes_extract::naming::{match_field, style_mismatches};
+use archimedes_extract::{BodyStream, StreamingBody};
+use archimedes_middleware::{
+    AppName, BatchedRequest, ContractVersion, MiddlewareContext, Pipeline, ResetConnection,
+    RouteOptions, RoutePattern,
+};
+use archimedes_router::pattern_params;
+
+use crate::apps::{self, AppSpec, MountedApp};
+use crate::batch::{BatchConfig, BatchSubRequest, BatchSubResponse};
 use crate::config::ServerConfig;
-use crate::handler::{HandlerRegistry, InvokeError};
+use crate::diagnostics::{
+    AppInfo, ContractInfo, Diagnostics, HandlerCoverage, ListenerInfo, MiddlewareInfo,
+};
+use crate::handler::{HandlerRegistry, InvokeError, ParamNameMismatch};
 use crate::health::{HealthCheck, ReadinessCheck};
+use crate::internal::{InternalEndpoint, InternalRoutes, ResolveExplainer, RESOLVE_PATH};
+use crate::observability::{self, ObservabilityEndpoints};
 use crate::router::{RouteMatch, Router};
 use crate::shutdown::{ConnectionTracker, ShutdownSignal};
+use crate::trailers::{self, TrailersBody};
+use crate::warmup::{Warmup, WarmupReport};
 
 /// Type alias for HTTP response body.
 pub type ResponseBody = Full<Bytes>;
@@ -57,6 +83,13 @@ pub type ResponseBody = Full<Bytes>;
 /// Type alias for the HTTP response.
 pub type HttpResponse = Response<ResponseBody>;
 
+/// Body sent on the connection: buffered, streamed with trailers, or
+/// buffered with trailers.
+type ConnectionBody = Either<ResponseBody, Either<StreamingBody, TrailersBody>>;
+
+/// Histogram of per-request phase durations, recorded with detailed timing.
+const REQUEST_PHASE_DURATION: &str = "archimedes_request_phase_duration_seconds";
+
 /// The Archimedes HTTP server.
 ///
 /// Handles incoming HTTP requests and routes them to handlers.
@@ -119,8 +152,83 @@ pub struct Server {
 
     /// Request timeout
     request_timeout: Duration,
+
+    /// Timeouts of operations that override the request timeout
+    operation_timeouts: HashMap<String, Duration>,
+
+    /// Upper bound for operation timeouts
+    max_request_timeout: Option<Duration>,
+
+    /// Cache policies of operations, applied to their successful responses
+    cache_policies: HashMap<String, CachePolicy>,
+
+    /// Diagnostics seed (contract, policy, middleware, profile)
+    diagnostics: Diagnostics,
+
+    /// Whether to serve the diagnostics report at `/-/diagnostics`
+    diagnostics_endpoint: bool,
+
+    /// Whether to record per-request phase timings
+    detailed_timing: bool,
+
+    /// Middleware pipeline applied to routed requests
+    pipeline: Option<Arc<Pipeline>>,
+
+    /// Batch endpoint configuration
+    batch: BatchConfig,
+
+    /// Endpoints served outside the contract
+    internal: InternalRoutes,
+
+    /// Hooks run after connections drain, before `run` returns
+    exit_hooks: Vec<ExitHook>,
+
+    /// Hooks run when shutdown begins, closing SSE and WebSocket streams
+    drain_hooks: Vec<DrainHook>,
+
+    /// Upper bound for the drain hooks
+    drain_timeout: Duration,
+
+    /// Base path prepended to URLs generated with `url_for`
+    base_path: Option<String>,
+
+    /// URL generator, built from the router on first use
+    url_generator: OnceLock<UrlGenerator>,
+
+    /// Tracks open connections
+    connections: ConnectionTracker,
+
+    /// Apps served on this listener, selected by host or path prefix
+    apps: Vec<MountedApp>,
+
+    /// App serving requests that select no other app
+    default_app: Option<String>,
+
+    /// Name of the app this server serves, when mounted on another server
+    app_name: Option<String>,
+
+    /// DI container handed to handlers
+    container: Option<Arc<Container>>,
+
+    /// Observability endpoints, for serving metrics on their own listener
+    observability: Option<ObservabilityEndpoints>,
+
+    /// Warm-up run before the server reports ready
+    warmup: Option<Warmup>,
+
+    /// Report of the finished warm-up; the server is not ready without it
+    warmup_report: Arc<OnceLock<WarmupReport>>,
 }
 
+/// A hook run during shutdown, after in-flight connections have drained.
+type ExitHook = Arc<dyn Fn() + Send + Sync>;
+
+/// A hook run when shutdown begins, closing long-lived streams.
+type DrainHook = Arc<dyn Fn() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
+/// How long drain hooks may run before shutdown proceeds without them.
+pub const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(10);
+
 impl Server {
     /// Creates a new server with the given configuration.
     ///
@@ -148,6 +256,28 @@ impl Server {
             health: HealthCheck::new("archimedes", env!("CARGO_PKG_VERSION")),
             readiness: ReadinessCheck::new(),
             request_timeout: Duration::from_secs(30),
+            operation_timeouts: HashMap::new(),
+            max_request_timeout: None,
+            cache_policies: HashMap::new(),
+            diagnostics: Diagnostics::default(),
+            diagnostics_endpoint: false,
+            detailed_timing: false,
+            pipeline: None,
+            batch: BatchConfig::default(),
+            internal: InternalRoutes::with_builtins(false, false),
+            exit_hooks: Vec::new(),
+            drain_hooks: Vec::new(),
+            drain_timeout: DEFAULT_DRAIN_TIMEOUT,
+            base_path: None,
+            url_generator: OnceLock::new(),
+            connections: ConnectionTracker::new(),
+            apps: Vec::new(),
+            default_app: None,
+            app_name: None,
+            container: None,
+            observability: None,
+            warmup: None,
+            warmup_report: Arc::new(OnceLock::new()),
         }
     }
 
@@ -175,9 +305,26 @@ impl Server {
 
     /// Returns a mutable reference to the router.
     pub fn router_mut(&mut self) -> &mut Router {
+        // Routes may change, so the URL generator is rebuilt on next use
+        self.url_generator = OnceLock::new();
         &mut self.router
     }
 
+    /// Returns the URL generator handed to request contexts.
+    ///
+    /// It covers the routes registered when it is first used and prefixes
+    /// URLs with the configured [`base_path`](ServerBuilder::base_path).
+    #[must_use]
+    pub fn url_generator(&self) -> &UrlGenerator {
+        self.url_generator.get_or_init(|| {
+            let generator = self.router.url_generator();
+            match &self.base_path {
+                Some(base_path) => generator.base_path(base_path.clone()),
+                None => generator,
+            }
+        })
+    }
+
     /// Returns a reference to the health check handler.
     #[must_use]
     pub fn health(&self) -> &HealthCheck {
@@ -207,12 +354,272 @@ impl Server {
         &mut self.handlers
     }
 
+    /// Returns path parameters that bind to handler fields only by
+    /// naming style.
+    ///
+    /// Compares the parameter names of each routed operation's pattern
+    /// with the fields of its handler's request type. Logged as warnings
+    /// when the server starts.
+    #[must_use]
+    pub fn param_name_mismatches(&self) -> Vec<ParamNameMismatch> {
+        let mut mismatches = Vec::new();
+        for operation_id in self.router.operation_ids() {
+            let (Some(fields), Some(pattern)) = (
+                self.handlers.request_fields(operation_id),
+                self.router.route_pattern(operation_id),
+            ) else {
+                continue;
+            };
+            let params = pattern_params(pattern).into_iter().map(|(name, _)| name);
+            for (param, field) in style_mismatches(params, fields) {
+                mismatches.push(ParamNameMismatch {
+                    operation_id: operation_id.to_string(),
+                    param,
+                    field,
+                });
+            }
+        }
+        mismatches.sort_by(|a, b| (&a.operation_id, &a.param).cmp(&(&b.operation_id, &b.param)));
+        mismatches
+    }
+
     /// Returns the request timeout.
     #[must_use]
     pub fn request_timeout(&self) -> Duration {
         self.request_timeout
     }
 
+    /// Returns the timeout applied to an operation.
+    ///
+    /// This is the operation's own timeout, clamped to the maximum request
+    /// timeout, or the request timeout if the operation declares none.
+    #[must_use]
+    pub fn operation_timeout(&self, operation_id: &str) -> Duration {
+        self.operation_timeouts
+            .get(operation_id)
+            .map_or(self.request_timeout, |&timeout| {
+                self.max_request_timeout
+                    .map_or(timeout, |max| timeout.min(max))
+            })
+    }
+
+    /// Returns the timeout for the operation a request routes to.
+    fn timeout_for(&self, method: &Method, path: &str) -> Duration {
+        let route_path = path.split('?').next().unwrap_or_default();
+        self.router
+            .match_route(method, route_path)
+            .map_or(self.request_timeout, |route_match| {
+                self.operation_timeout(route_match.operation_id())
+            })
+    }
+
+    /// Returns the cache policy of an operation, if it declares one.
+    #[must_use]
+    pub fn cache_policy(&self, operation_id: &str) -> Option<CachePolicy> {
+        self.cache_policies.get(operation_id).copied()
+    }
+
+    /// Sets the caching headers of a response.
+    ///
+    /// Error responses must never be reused, so they always get `no-store`.
+    /// Successful responses get the operation's policy, unless the handler
+    /// set `Cache-Control` itself.
+    fn apply_cache_policy<B>(policy: Option<CachePolicy>, response: &mut Response<B>) {
+        let status = response.status();
+        if status.is_client_error() || status.is_server_error() {
+            CachePolicy::no_store().apply(response.headers_mut());
+        } else if status.is_success() && !response.headers().contains_key(CACHE_CONTROL) {
+            if let Some(policy) = policy {
+                policy.apply(response.headers_mut());
+            }
+        }
+    }
+
+    /// Returns the batch endpoint configuration.
+    #[must_use]
+    pub fn batch_config(&self) -> &BatchConfig {
+        &self.batch
+    }
+
+    /// Returns the startup diagnostics report.
+    ///
+    /// Starts from the seed passed to [`ServerBuilder::diagnostics`] and
+    /// fills in what the server knows itself: service name and version,
+    /// the HTTP listener, a configuration snapshot (unless the seed carries
+    /// one), handler coverage and, once it finished, the warm-up timings.
+    /// Coverage is computed against the contract operations when a contract
+    /// is reported, otherwise against the routed operations.
+    #[must_use]
+    pub fn diagnostics(&self) -> Diagnostics {
+        let mut diagnostics = self.diagnostics.clone();
+        diagnostics.service = self.health.service().to_string();
+        diagnostics.version = self.health.version().to_string();
+
+        if diagnostics.config.is_null() {
+            diagnostics.config = serde_json::json!({
+                "http_addr": self.config.http_addr(),
+                "shutdown_timeout_secs": self.config.shutdown_timeout().as_secs(),
+                "keep_alive_timeout_secs": self.config.keep_alive_timeout().map(|t| t.as_secs()),
+                "max_connections": self.config.max_connections(),
+                "http2_enabled": self.config.http2_enabled(),
+                "max_requests_per_connection": self.config.max_requests_per_connection(),
+                "request_timeout_secs": self.request_timeout.as_secs(),
+            });
+        }
+
+        if !diagnostics.listeners.iter().any(|l| l.name == "http") {
+            diagnostics.listeners.insert(
+                0,
+                ListenerInfo {
+                    name: "http".to_string(),
+                    address: self.config.http_addr().to_string(),
+                },
+            );
+        }
+
+        let coverage = if diagnostics.contract.is_some() {
+            HandlerCoverage::compute(
+                diagnostics.contract_operations(),
+                self.handlers.operation_ids(),
+            )
+        } else {
+            HandlerCoverage::compute(self.router.operation_ids(), self.handlers.operation_ids())
+        };
+        diagnostics.handlers =
+            coverage.with_unvalidated_bodies(self.handlers.body_stream_operation_ids());
+        diagnostics.apps = self.apps.iter().map(|app| self.app_info(app)).collect();
+        diagnostics.warmup = self.warmup_report.get().cloned();
+        diagnostics
+    }
+
+    /// Describes a mounted app for the diagnostics report.
+    fn app_info(&self, app: &MountedApp) -> AppInfo {
+        let report = app.server.diagnostics();
+        AppInfo {
+            name: app.name.clone(),
+            host: app.host.clone(),
+            path_prefix: app.path_prefix.clone(),
+            default: self.default_app.as_deref() == Some(app.name.as_str()),
+            contract: report.contract,
+            middleware: app
+                .server
+                .pipeline
+                .as_ref()
+                .map(|pipeline| MiddlewareInfo::from_stages(pipeline.stage_names()))
+                .unwrap_or_default(),
+            handlers: report.handlers,
+        }
+    }
+
+    /// Builds the server for an app mounted on this server.
+    ///
+    /// The app shares the connection settings, request timeout and health
+    /// of this server; its URLs are generated below its path prefix.
+    pub(crate) fn mount(&self, spec: AppSpec) -> MountedApp {
+        let server = Self {
+            config: self.config.clone(),
+            router: spec.router,
+            handlers: spec.handlers,
+            health: self.health.clone(),
+            readiness: ReadinessCheck::new(),
+            request_timeout: self.request_timeout,
+            operation_timeouts: self.operation_timeouts.clone(),
+            max_request_timeout: self.max_request_timeout,
+            cache_policies: self.cache_policies.clone(),
+            diagnostics: spec
+                .contract
+                .map(|contract| Diagnostics::default().with_contract(contract))
+                .unwrap_or_default(),
+            diagnostics_endpoint: false,
+            detailed_timing: self.detailed_timing,
+            pipeline: spec.pipeline.map(Arc::new),
+            batch: BatchConfig::default(),
+            internal: InternalRoutes::new(),
+            exit_hooks: Vec::new(),
+            drain_hooks: Vec::new(),
+            drain_timeout: self.drain_timeout,
+            base_path: apps::join_base_path(self.base_path.as_deref(), spec.path_prefix.as_deref()),
+            url_generator: OnceLock::new(),
+            connections: ConnectionTracker::new(),
+            apps: Vec::new(),
+            default_app: None,
+            app_name: Some(spec.name.clone()),
+            container: spec.container,
+            observability: None,
+            warmup: None,
+            warmup_report: Arc::new(OnceLock::new()),
+        };
+        MountedApp {
+            name: spec.name,
+            host: spec.host,
+            path_prefix: spec.path_prefix,
+            server: Arc::new(server),
+        }
+    }
+
+    /// Checks the mounted apps for overlapping hosts, prefixes and routes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServerError::InvalidConfig`] describing the first conflict;
+    /// see [`apps`](crate::apps).
+    pub fn check_apps(&self) -> Result<(), ServerError> {
+        apps::check_conflicts(&self.apps, self.default_app.as_deref(), &self.internal)
+    }
+
+    /// Returns the names of the apps served on this listener.
+    pub fn app_names(&self) -> impl Iterator<Item = &str> {
+        self.apps.iter().map(|app| app.name.as_str())
+    }
+
+    /// Returns a handle to the server's open-connection tracker.
+    ///
+    /// The handle stays valid after the server is moved into
+    /// [`run`](Self::run), so it can back an open-connections gauge.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use archimedes_server::Server;
+    ///
+    /// let server = Server::builder().build();
+    /// let connections = server.connections();
+    /// assert_eq!(connections.active_connections(), 0);
+    /// ```
+    #[must_use]
+    pub fn connections(&self) -> ConnectionTracker {
+        self.connections.clone()
+    }
+
+    /// Returns the number of currently open connections.
+    #[must_use]
+    pub fn open_connections(&self) -> usize {
+        self.connections.active_connections()
+    }
+
+    /// Returns whether the diagnostics endpoint is served.
+    #[must_use]
+    pub fn diagnostics_endpoint_enabled(&self) -> bool {
+        self.diagnostics_endpoint
+    }
+
+    /// Returns whether detailed per-request timing is enabled.
+    #[must_use]
+    pub fn detailed_timing_enabled(&self) -> bool {
+        self.detailed_timing
+    }
+
+    /// Returns the endpoints served outside the contract.
+    #[must_use]
+    pub fn internal_routes(&self) -> &InternalRoutes {
+        &self.internal
+    }
+
+    /// Returns a mutable reference to the internal endpoints.
+    pub fn internal_routes_mut(&mut self) -> &mut InternalRoutes {
+        &mut self.internal
+    }
+
     /// Runs the server until a shutdown signal is received.
     ///
     /// This method binds to the configured address and begins
@@ -221,9 +628,16 @@ impl Server {
     ///
     /// # Errors
     ///
-    /// Returns an error if:
+    /// Returns a [`StartupError`] if:
+    /// - The configuration is invalid ([`StartupCategory::ConfigInvalid`])
     /// - The server cannot bind to the configured address
-    /// - An I/O error occurs
+    ///   ([`StartupCategory::Bind`])
+    ///
+    /// A binary can end with [`StartupError::exit`] to log the failure and
+    /// exit with the category's documented exit code.
+    ///
+    /// [`StartupCategory::ConfigInvalid`]: archimedes_core::StartupCategory::ConfigInvalid
+    /// [`StartupCategory::Bind`]: archimedes_core::StartupCategory::Bind
     ///
     /// # Example
     ///
@@ -231,18 +645,19 @@ impl Server {
     /// use archimedes_server::{Server, ServerConfig};
     ///
     /// #[tokio::main]
-    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// async fn main() {
     ///     let server = Server::builder()
     ///         .http_addr("0.0.0.0:8080")
     ///         .build();
     ///
-    ///     server.run().await?;
-    ///     Ok(())
+    ///     if let Err(err) = server.run().await {
+    ///         err.exit();
+    ///     }
     /// }
     /// ```
-    pub async fn run(self) -> Result<(), ServerError> {
+    pub async fn run(self) -> Result<(), StartupError> {
         let shutdown = ShutdownSignal::with_os_signals();
-        self.run_with_shutdown(shutdown).await
+        self.run_with_shutdown(shutdown).await.map_err(Into::into)
     }
 
     /// Runs the server with a custom shutdown signal.
@@ -258,6 +673,10 @@ impl Server {
     ///
     /// Returns an error if the server cannot bind or an I/O error occurs.
     pub async fn run_with_shutdown(self, shutdown: ShutdownSignal) -> Result<(), ServerError> {
+        self.config.validate()?;
+        self.internal.check_conflicts(&self.router)?;
+        self.check_apps()?;
+
         let addr = self.config.socket_addr().map_err(|e| {
             ServerError::BindError(format!(
                 "Invalid address '{}': {}",
@@ -270,10 +689,54 @@ impl Server {
             .await
             .map_err(|e| ServerError::BindError(format!("Failed to bind to {}: {}", addr, e)))?;
 
+        self.serve(listener, shutdown).await
+    }
+
+    /// Runs the server on an already-bound listener.
+    ///
+    /// Useful when the caller needs the bound address before the server
+    /// starts, e.g. when binding to port 0 in tests.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection settings are invalid or an
+    /// internal route conflicts with a contract operation.
+    pub async fn run_with_listener(
+        self,
+        listener: TcpListener,
+        shutdown: ShutdownSignal,
+    ) -> Result<(), ServerError> {
+        self.config.validate()?;
+        self.internal.check_conflicts(&self.router)?;
+        self.check_apps()?;
+        self.serve(listener, shutdown).await
+    }
+
+    /// Accepts connections on `listener` until shutdown.
+    async fn serve(
+        self,
+        listener: TcpListener,
+        shutdown: ShutdownSignal,
+    ) -> Result<(), ServerError> {
+        let addr = listener
+            .local_addr()
+            .map_err(|e| ServerError::IoError(e.to_string()))?;
         tracing::info!("Server listening on {}", addr);
+        self.spawn_metrics_listener(&shutdown).await?;
+        self.diagnostics().log();
+        for mismatch in self.param_name_mismatches() {
+            tracing::warn!("{}", mismatch);
+        }
 
         let server = Arc::new(self);
-        let tracker = ConnectionTracker::new();
+        let tracker = server.connections.clone();
+        if let Some(warmup) = server.warmup.clone() {
+            let server = Arc::clone(&server);
+            tokio::spawn(async move {
+                let report = warmup.run(server.router.operation_ids()).await;
+                let _ = server.warmup_report.set(report);
+            });
+        }
 
         // Accept connections until shutdown
         loop {
@@ -308,6 +771,8 @@ impl Server {
         // Mark as not ready during shutdown
         server.readiness.set_ready(false);
 
+        server.drain().await;
+
         // Wait for in-flight connections with timeout
         let shutdown_timeout = server.config.shutdown_timeout();
         tracing::info!(
@@ -328,26 +793,112 @@ impl Server {
             }
         }
 
+        // Run exit hooks (e.g. telemetry flush) before returning to the caller
+        for hook in &server.exit_hooks {
+            let hook = Arc::clone(hook);
+            if let Err(e) = tokio::task::spawn_blocking(move || hook()).await {
+                tracing::error!("Exit hook failed: {}", e);
+            }
+        }
+
         tracing::info!("Server stopped");
         Ok(())
     }
 
+    /// Binds the dedicated metrics listener, if one is configured, and
+    /// serves it until shutdown.
+    async fn spawn_metrics_listener(&self, shutdown: &ShutdownSignal) -> Result<(), ServerError> {
+        let Some(endpoints) = &self.observability else {
+            return Ok(());
+        };
+        let Some(addr) = endpoints.metrics_listener_addr() else {
+            return Ok(());
+        };
+
+        let listener = TcpListener::bind(addr).await.map_err(|e| {
+            ServerError::BindError(format!(
+                "Failed to bind metrics listener to {}: {}",
+                addr, e
+            ))
+        })?;
+        tracing::info!(
+            "Metrics listening on {} at {}",
+            listener
+                .local_addr()
+                .map_err(|e| ServerError::IoError(e.to_string()))?,
+            endpoints.metrics_path()
+        );
+        tokio::spawn(observability::serve_metrics(
+            listener,
+            endpoints.metrics_path().to_string(),
+            endpoints.metrics_source(),
+            shutdown.clone(),
+        ));
+        Ok(())
+    }
+
+    /// Runs the drain hooks, closing long-lived streams within the drain
+    /// timeout.
+    async fn drain(&self) {
+        if self.drain_hooks.is_empty() {
+            return;
+        }
+
+        tracing::info!(
+            "Draining streams for up to {:?} ({} hooks)",
+            self.drain_timeout,
+            self.drain_hooks.len()
+        );
+        let hooks = futures_util::future::join_all(self.drain_hooks.iter().map(|hook| hook()));
+        if tokio::time::timeout(self.drain_timeout, hooks)
+            .await
+            .is_err()
+        {
+            tracing::warn!("Drain timeout reached, closing remaining streams");
+        }
+    }
+
     /// Handles a single connection.
     async fn handle_connection(
         self: &Arc<Self>,
         stream: tokio::net::TcpStream,
         remote_addr: SocketAddr,
         shutdown: ShutdownSignal,
-    ) -> Result<(), hyper::Error> {
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let io = TokioIo::new(stream);
         let server = Arc::clone(self);
+        let max_requests = self.config.max_requests_per_connection();
+        let served = Arc::new(AtomicU64::new(0));
 
         let service = service_fn(move |req: Request<Incoming>| {
             let server = Arc::clone(&server);
-            async move { server.handle_request(req).await }
+            let served = Arc::clone(&served);
+            async move {
+                // Ask HTTP/1 clients to reconnect once the request budget is
+                // spent, so load balancers can rebalance the connection
+                let close = req.version() < Version::HTTP_2
+                    && max_requests
+                        .is_some_and(|max| served.fetch_add(1, Ordering::Relaxed) + 1 >= max);
+
+                let mut response = server.serve_request(req).await;
+                // A stage asked for the connection to be dropped unanswered
+                if response.extensions().get::<ResetConnection>().is_some() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::ConnectionReset,
+                        "connection reset",
+                    ));
+                }
+                if close {
+                    response
+                        .headers_mut()
+                        .insert(CONNECTION, HeaderValue::from_static("close"));
+                }
+                Ok::<_, io::Error>(response)
+            }
         });
 
-        let conn = http1::Builder::new().serve_connection(io, service);
+        let builder = self.connection_builder();
+        let conn = builder.serve_connection(io, service);
 
         tokio::select! {
             result = conn => {
@@ -360,6 +911,45 @@ impl Server {
         }
     }
 
+    /// Builds the HTTP/1 and HTTP/2 connection builder from the config.
+    fn connection_builder(&self) -> auto::Builder<TokioExecutor> {
+        let config = &self.config;
+        let mut builder = auto::Builder::new(TokioExecutor::new());
+
+        {
+            let mut http1 = builder.http1();
+            http1
+                .timer(TokioTimer::new())
+                .keep_alive(config.keep_alive_timeout().is_some());
+            if let Some(timeout) = config.keep_alive_timeout() {
+                // Bounds how long an idle connection waits for the next request
+                http1.header_read_timeout(timeout);
+            }
+        }
+
+        if !config.http2_enabled() {
+            return builder.http1_only();
+        }
+
+        {
+            let mut http2 = builder.http2();
+            http2
+                .timer(TokioTimer::new())
+                .max_concurrent_streams(config.http2_max_concurrent_streams())
+                .initial_stream_window_size(config.http2_initial_stream_window_size())
+                .initial_connection_window_size(config.http2_initial_connection_window_size())
+                .keep_alive_interval(config.http2_keep_alive_interval());
+            if let Some(timeout) = config.http2_keep_alive_timeout() {
+                http2.keep_alive_timeout(timeout);
+            }
+            if let Some(size) = config.http2_max_header_list_size() {
+                http2.max_header_list_size(size);
+            }
+        }
+
+        builder
+    }
+
     /// Handles a single HTTP request.
     async fn handle_request(
         self: &Arc<Self>,
@@ -367,687 +957,3893 @@ impl Server {
     ) -> Result<HttpResponse, Infallible> {
         let method = req.method().clone();
         let path = req.uri().path().to_string();
+        let target = req
+            .uri()
+            .path_and_query()
+            .map_or_else(|| path.clone(), ToString::to_string);
+        let headers = req.headers().clone();
 
         tracing::debug!("{} {}", method, path);
 
-        // Handle built-in health endpoints first (no body needed)
-        match (method.as_ref(), path.as_str()) {
-            ("GET", "/health") => return Ok(self.handle_health()),
-            ("GET", "/ready") => return Ok(self.handle_ready()),
-            _ => {}
-        }
+        let body = match self.read_body(req).await {
+            Ok(body) => body,
+            Err(response) => return Ok(response),
+        };
 
-        // Collect request body with timeout
-        let body_result = tokio::time::timeout(self.request_timeout, Self::collect_body(req)).await;
+        if let Some(route) = self.internal.find(&method, &path) {
+            let (endpoint, route) = (route.endpoint.clone(), route.path.clone());
+            return Ok(self
+                .dispatch_internal(endpoint, route, method, &target, headers, body)
+                .await);
+        }
 
-        let body = match body_result {
-            Ok(Ok(body)) => body,
-            Ok(Err(e)) => {
-                tracing::error!("Failed to collect request body: {}", e);
-                return Ok(self.handle_error(
-                    StatusCode::BAD_REQUEST,
-                    "BODY_READ_ERROR",
-                    &format!("Failed to read request body: {}", e),
-                ));
-            }
-            Err(_) => {
-                tracing::warn!("Request body collection timed out");
-                return Ok(self.handle_error(
-                    StatusCode::REQUEST_TIMEOUT,
-                    "REQUEST_TIMEOUT",
-                    "Request body collection timed out",
-                ));
-            }
-        };
+        if self.detailed_timing {
+            return Ok(self.dispatch_timed(&method, &path, headers, body).await);
+        }
+        Ok(self
+            .dispatch_with_timeout(&method, &path, headers, body)
+            .await)
+    }
 
-        // Route and invoke handler with timeout
+    /// Routes and invokes the handler of a request within its timeout.
+    async fn dispatch_with_timeout(
+        self: &Arc<Self>,
+        method: &Method,
+        path: &str,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> HttpResponse {
         let response = tokio::time::timeout(
-            self.request_timeout,
-            self.route_request(&method, &path, body),
+            self.timeout_for(method, path),
+            self.dispatch(method.clone(), path, headers, body, false),
         )
         .await;
 
-        match response {
-            Ok(resp) => Ok(resp),
-            Err(_) => {
-                tracing::warn!("Handler execution timed out for {} {}", method, path);
-                Ok(self.handle_error(
-                    StatusCode::GATEWAY_TIMEOUT,
-                    "HANDLER_TIMEOUT",
-                    "Handler execution timed out",
-                ))
-            }
-        }
+        response.unwrap_or_else(|_| {
+            tracing::warn!("Handler execution timed out for {} {}", method, path);
+            self.handle_error(
+                StatusCode::GATEWAY_TIMEOUT,
+                "HANDLER_TIMEOUT",
+                "Handler execution timed out",
+            )
+        })
     }
 
-    /// Collects the request body into bytes.
-    async fn collect_body(req: Request<Incoming>) -> Result<Bytes, hyper::Error> {
-        let body = req.into_body();
-        let collected = body.collect().await?;
-        Ok(collected.to_bytes())
+    /// Dispatches a request with detailed timing, then reports the timings.
+    async fn dispatch_timed(
+        self: &Arc<Self>,
+        method: &Method,
+        path: &str,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> HttpResponse {
+        let timing = RequestTiming::new();
+        timing.set_request_bytes(body.len() as u64);
+        let mut response = timing::scope(
+            timing.clone(),
+            self.dispatch_with_timeout(method, path, headers, body),
+        )
+        .await;
+        self.report_timing(method, path, &timing, &mut response);
+        response
     }
 
-    /// Handles the /health endpoint.
-    fn handle_health(&self) -> HttpResponse {
-        let status = self.health.status();
-        let body = serde_json::to_string(&status)
-            .unwrap_or_else(|_| r#"{"status":"healthy"}"#.to_string());
+    /// Reports the timings of a request: records the phase histogram, logs
+    /// them, and attaches them to the response as a [`PhaseTimings`]
+    /// extension.
+    fn report_timing(
+        &self,
+        method: &Method,
+        path: &str,
+        timing: &RequestTiming,
+        response: &mut HttpResponse,
+    ) {
+        let operation = self
+            .router
+            .match_route(method, path)
+            .map_or_else(|| "unknown".to_string(), |m| m.operation_id().to_string());
+        timing.set_response_bytes(response.body().size_hint().exact().unwrap_or_default());
+        let timings = timing.snapshot();
+
+        for (phase, duration) in timings.phases() {
+            metrics::histogram!(
+                REQUEST_PHASE_DURATION,
+                "operation" => operation.clone(),
+                "phase" => phase
+            )
+            .record(duration.as_secs_f64());
+        }
 
-        Response::builder()
-            .status(StatusCode::OK)
-            .header("Content-Type", "application/json")
-            .body(Full::new(Bytes::from(body)))
-            .unwrap_or_else(|_| Response::new(Full::new(Bytes::from(r#"{"status":"healthy"}"#))))
+        let millis = |phase| timings.phase(phase).map(|d| d.as_secs_f64() * 1000.0);
+        tracing::info!(
+            operation = %operation,
+            status = response.status().as_u16(),
+            request_bytes = timings.request_bytes(),
+            response_bytes = timings.response_bytes(),
+            validation_ms = millis(timing::VALIDATION),
+            deserialization_ms = millis(timing::DESERIALIZATION),
+            handler_ms = millis(timing::HANDLER),
+            handler_busy_ms = timings.handler_busy().as_secs_f64() * 1000.0,
+            serialization_ms = millis(timing::SERIALIZATION),
+            "request timing"
+        );
+
+        response.extensions_mut().insert(timings);
     }
 
-    /// Handles the /ready endpoint.
-    fn handle_ready(&self) -> HttpResponse {
-        let status = self.readiness.status();
-        let status_code = if status.is_ready() {
-            StatusCode::OK
-        } else {
-            StatusCode::SERVICE_UNAVAILABLE
+    /// Handles a request for a streaming operation.
+    ///
+    /// The request timeout covers the handler building the response; the
+    /// body is then streamed for as long as the producer keeps writing.
+    async fn handle_streaming_request(
+        self: &Arc<Self>,
+        req: Request<Incoming>,
+    ) -> Response<ConnectionBody> {
+        let method = req.method().clone();
+        let path = req.uri().path().to_string();
+        let headers = req.headers().clone();
+
+        tracing::debug!("{} {} (streaming)", method, path);
+
+        let body = match self.read_body(req).await {
+            Ok(body) => body,
+            Err(response) => return response.map(Either::Left),
         };
 
-        let body = serde_json::to_string(&status)
-            .unwrap_or_else(|_| format!(r#"{{"ready":{}}}"#, status.is_ready()));
+        let response = tokio::time::timeout(
+            self.timeout_for(&method, &path),
+            self.dispatch_streaming(method.clone(), &path, headers, body),
+        )
+        .await;
 
-        Response::builder()
-            .status(status_code)
-            .header("Content-Type", "application/json")
-            .body(Full::new(Bytes::from(body)))
-            .unwrap_or_else(|_| Response::new(Full::new(Bytes::from(r#"{"ready":false}"#))))
+        response.unwrap_or_else(|_| {
+            tracing::warn!("Handler execution timed out for {} {}", method, path);
+            self.handle_error(
+                StatusCode::GATEWAY_TIMEOUT,
+                "HANDLER_TIMEOUT",
+                "Handler execution timed out",
+            )
+            .map(Either::Left)
+        })
     }
 
-    /// Routes a request to the appropriate handler.
-    async fn route_request(&self, method: &Method, path: &str, body: Bytes) -> HttpResponse {
-        match self.router.match_route(method, path) {
-            Some(route_match) => self.handle_matched_route(route_match, body).await,
-            None => self.handle_not_found(path),
-        }
-    }
+    /// Handles a request for an operation that consumes the request body as
+    /// a stream.
+    ///
+    /// The body is handed to the handler unbuffered, so it is read only as
+    /// fast as the handler consumes it. The request timeout covers the
+    /// whole handler, including reading the body.
+    async fn handle_body_stream_request(self: &Arc<Self>, req: Request<Incoming>) -> HttpResponse {
+        let (parts, body) = req.into_parts();
+        let path = parts.uri.path().to_string();
 
-    /// Handles a matched route by invoking the registered handler.
-    async fn handle_matched_route(&self, route_match: RouteMatch, body: Bytes) -> HttpResponse {
-        let operation_id = route_match.operation_id();
+        tracing::debug!("{} {} (body stream)", parts.method, path);
 
-        // Check if handler is registered
-        if !self.handlers.contains(operation_id) {
-            tracing::warn!("No handler registered for operation: {}", operation_id);
-            return self.handle_error(
-                StatusCode::NOT_IMPLEMENTED,
-                "HANDLER_NOT_IMPLEMENTED",
-                &format!("No handler registered for operation: {}", operation_id),
-            );
+        let response = tokio::time::timeout(
+            self.timeout_for(&parts.method, &path),
+            self.dispatch_body_stream(
+                parts.method.clone(),
+                &path,
+                parts.headers,
+                BodyStream::from_body(body),
+            ),
+        )
+        .await;
+
+        response.unwrap_or_else(|_| {
+            tracing::warn!("Handler execution timed out for {} {}", parts.method, path);
+            self.handle_error(
+                StatusCode::GATEWAY_TIMEOUT,
+                "HANDLER_TIMEOUT",
+                "Handler execution timed out",
+            )
+        })
+    }
+
+    /// Serves a request, first selecting the app that serves it when the
+    /// server hosts several.
+    async fn serve_request(
+        self: &Arc<Self>,
+        mut req: Request<Incoming>,
+    ) -> Response<ConnectionBody> {
+        if self.apps.is_empty() || self.internal.contains(req.method(), req.uri().path()) {
+            return self.serve_routed(req).await;
         }
 
-        // Create request context with operation ID
-        let ctx = RequestContext::new().with_operation_id(operation_id);
+        let path_and_query = req
+            .uri()
+            .path_and_query()
+            .map_or("/", http::uri::PathAndQuery::as_str)
+            .to_string();
+        match self.select_app(req.headers(), req.uri(), &path_and_query) {
+            Ok((server, path)) => {
+                *req.uri_mut() = path.parse().unwrap_or_default();
+                server.serve_routed(req).await
+            }
+            Err(response) => response.map(Either::Left),
+        }
+    }
 
-        // Merge path parameters into the request body
-        // This allows handlers to receive path params (e.g., userId) as part of their request type
-        let merged_body = self.merge_path_params_into_body(route_match.params(), body);
+    /// Selects the server for a request: the server itself when it hosts
+    /// no apps, otherwise the selected app and the path it routes.
+    ///
+    /// Fails with `421 Misdirected Request` when no app is selected.
+    fn select_app(
+        self: &Arc<Self>,
+        headers: &HeaderMap,
+        uri: &Uri,
+        path_and_query: &str,
+    ) -> Result<(Arc<Self>, String), HttpResponse> {
+        if self.apps.is_empty() {
+            return Ok((Arc::clone(self), path_and_query.to_string()));
+        }
 
-        // Invoke the handler
-        match self.handlers.invoke(operation_id, ctx, merged_body).await {
-            Ok(response_body) => Response::builder()
-                .status(StatusCode::OK)
-                .header("Content-Type", "application/json")
-                .body(Full::new(response_body))
-                .unwrap_or_else(|_| Response::new(Full::new(Bytes::new()))),
-            Err(InvokeError::HandlerNotFound(id)) => {
-                tracing::error!("Handler not found during invocation: {}", id);
-                self.handle_error(
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    "HANDLER_NOT_FOUND",
-                    &format!("Handler not found: {}", id),
+        let host = apps::request_host(headers, uri);
+        match apps::select(
+            &self.apps,
+            self.default_app.as_deref(),
+            host.as_deref(),
+            path_and_query,
+        ) {
+            Some((app, path)) => Ok((Arc::clone(&app.server), path)),
+            None => {
+                tracing::debug!(host = ?host, path = %path_and_query, "no app selected");
+                Err(self.handle_error(
+                    StatusCode::MISDIRECTED_REQUEST,
+                    "MISDIRECTED_REQUEST",
+                    &format!(
+                        "No app is served for host '{}'",
+                        host.as_deref().unwrap_or_default()
+                    ),
+                ))
+            }
+        }
+    }
+
+    /// Serves a request, streaming the body of streaming operations.
+    async fn serve_routed(self: &Arc<Self>, req: Request<Incoming>) -> Response<ConnectionBody> {
+        let route_match = self.router.match_route(req.method(), req.uri().path());
+        let streaming = route_match
+            .as_ref()
+            .is_some_and(|route_match| self.handlers.is_streaming(route_match.operation_id()));
+        let body_stream = route_match
+            .as_ref()
+            .is_some_and(|route_match| self.handlers.is_body_stream(route_match.operation_id()));
+        let cache_policy =
+            route_match.and_then(|route_match| self.cache_policy(route_match.operation_id()));
+
+        if streaming {
+            let mut response = self.handle_streaming_request(req).await;
+            Self::apply_cache_policy(cache_policy, &mut response);
+            return response;
+        }
+
+        if body_stream {
+            let mut response = self.handle_body_stream_request(req).await;
+            Self::apply_cache_policy(cache_policy, &mut response);
+            trailers::fold_into_headers(&mut response);
+            return response.map(Either::Left);
+        }
+
+        let carries_trailers = trailers::can_carry(req.version(), req.method(), req.headers());
+        let mut response = match self.handle_request(req).await {
+            Ok(response) => response,
+            Err(never) => match never {},
+        };
+        Self::apply_cache_policy(cache_policy, &mut response);
+
+        // Trailers follow the body where the connection allows, and are
+        // sent as headers otherwise
+        let trailers = if carries_trailers {
+            trailers::take(&mut response)
+        } else {
+            None
+        };
+        match trailers {
+            Some(trailers) => {
+                let (parts, body) = response.into_parts();
+                let body = body
+                    .collect()
+                    .await
+                    .map(http_body_util::Collected::to_bytes)
+                    .unwrap_or_default();
+                Response::from_parts(
+                    parts,
+                    Either::Right(Either::Right(TrailersBody::new(body, trailers))),
                 )
             }
-            Err(InvokeError::HandlerError(e)) => {
-                tracing::error!("Handler error for {}: {}", operation_id, e);
-                self.handle_handler_error(operation_id, e)
+            None => {
+                trailers::fold_into_headers(&mut response);
+                response.map(Either::Left)
             }
         }
     }
 
-    /// Handles handler errors and converts them to HTTP responses.
-    fn handle_handler_error(
-        &self,
-        operation_id: &str,
-        error: crate::handler::HandlerError,
-    ) -> HttpResponse {
-        use crate::handler::HandlerError;
+    /// Collects the request body, mapping failures to error responses.
+    async fn read_body(&self, req: Request<Incoming>) -> Result<Bytes, HttpResponse> {
+        let body_result = tokio::time::timeout(self.request_timeout, Self::collect_body(req)).await;
 
-        let (status, code, message) = match &error {
-            HandlerError::DeserializationError(msg) => (
-                StatusCode::BAD_REQUEST,
-                "VALIDATION_ERROR".to_string(),
-                format!("Invalid request body: {}", msg),
-            ),
-            HandlerError::SerializationError(msg) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "SERIALIZATION_ERROR".to_string(),
-                format!("Failed to serialize response: {}", msg),
-            ),
-            HandlerError::ThemisError(e) => {
-                // Use to_envelope to get proper error structure
-                let envelope = e.to_envelope(None);
-                (e.status_code(), envelope.error.code, envelope.error.message)
+        match body_result {
+            Ok(Ok(body)) => Ok(body),
+            Ok(Err(e)) => {
+                tracing::error!("Failed to collect request body: {}", e);
+                Err(self.handle_error(
+                    StatusCode::BAD_REQUEST,
+                    "BODY_READ_ERROR",
+                    &format!("Failed to read request body: {}", e),
+                ))
             }
-            HandlerError::Custom(e) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "INTERNAL_ERROR".to_string(),
-                format!("Internal error: {}", e),
-            ),
-        };
-
-        let body = serde_json::json!({
-            "error": {
-                "code": code,
-                "message": message,
-                "operation_id": operation_id
+            Err(_) => {
+                tracing::warn!("Request body collection timed out");
+                Err(self.handle_error(
+                    StatusCode::REQUEST_TIMEOUT,
+                    "REQUEST_TIMEOUT",
+                    "Request body collection timed out",
+                ))
             }
-        });
+        }
+    }
+
+    /// Collects the request body into bytes.
+    async fn collect_body(req: Request<Incoming>) -> Result<Bytes, hyper::Error> {
+        let body = req.into_body();
+        let collected = body.collect().await?;
+        Ok(collected.to_bytes())
+    }
+
+    /// Handles the /health endpoint.
+    fn handle_health(&self) -> HttpResponse {
+        let status = self.health.status();
+        let body = serde_json::to_string(&status)
+            .unwrap_or_else(|_| r#"{"status":"healthy"}"#.to_string());
 
         Response::builder()
-            .status(status)
+            .status(StatusCode::OK)
             .header("Content-Type", "application/json")
-            .body(Full::new(Bytes::from(body.to_string())))
-            .unwrap_or_else(|_| Response::new(Full::new(Bytes::new())))
+            .body(Full::new(Bytes::from(body)))
+            .unwrap_or_else(|_| Response::new(Full::new(Bytes::from(r#"{"status":"healthy"}"#))))
     }
 
-    /// Creates a standard error response.
-    fn handle_error(&self, status: StatusCode, code: &str, message: &str) -> HttpResponse {
-        let body = serde_json::json!({
-            "error": {
-                "code": code,
-                "message": message
-            }
-        });
+    /// Handles the /ready endpoint.
+    fn handle_ready(&self) -> HttpResponse {
+        let status = self.readiness.status();
+        let status_code = if status.is_ready() {
+            StatusCode::OK
+        } else {
+            StatusCode::SERVICE_UNAVAILABLE
+        };
+
+        let body = serde_json::to_string(&status)
+            .unwrap_or_else(|_| format!(r#"{{"ready":{}}}"#, status.is_ready()));
 
         Response::builder()
-            .status(status)
+            .status(status_code)
             .header("Content-Type", "application/json")
-            .body(Full::new(Bytes::from(body.to_string())))
-            .unwrap_or_else(|_| Response::new(Full::new(Bytes::new())))
+            .body(Full::new(Bytes::from(body)))
+            .unwrap_or_else(|_| Response::new(Full::new(Bytes::from(r#"{"ready":false}"#))))
     }
 
-    /// Handles a not found response.
-    fn handle_not_found(&self, path: &str) -> HttpResponse {
-        let body = serde_json::json!({
-            "error": "Not Found",
-            "path": path
-        });
+    /// Handles the /-/diagnostics endpoint.
+    fn handle_diagnostics(&self) -> HttpResponse {
+        let body = serde_json::to_string(&self.diagnostics()).unwrap_or_else(|_| "{}".to_string());
 
         Response::builder()
-            .status(StatusCode::NOT_FOUND)
+            .status(StatusCode::OK)
             .header("Content-Type", "application/json")
-            .body(Full::new(Bytes::from(body.to_string())))
-            .unwrap_or_else(|_| Response::new(Full::new(Bytes::new())))
+            .body(Full::new(Bytes::from(body)))
+            .unwrap_or_else(|_| Response::new(Full::new(Bytes::from("{}"))))
     }
 
-    /// Merges path parameters into the request body.
+    /// Dispatches a request through the middleware pipeline (if configured)
+    /// to its handler.
     ///
-    /// This allows handlers to receive path parameters (e.g., `userId` from `/users/{userId}`)
-    /// as part of their typed request struct. Path params are converted from camelCase to snake_case
-    /// to match Rust naming conventions (e.g., `userId` -> `user_id`).
-    fn merge_path_params_into_body(
-        &self,
-        params: &std::collections::HashMap<String, String>,
+    /// `batched` marks sub-requests of a batch so telemetry attributes them
+    /// to their own operation with `batched = true`.
+    async fn dispatch(
+        self: &Arc<Self>,
+        method: Method,
+        path: &str,
+        headers: HeaderMap,
         body: Bytes,
-    ) -> Bytes {
-        if params.is_empty() {
-            return body;
-        }
-
-        tracing::debug!("Merging path params into body: {:?}", params);
+        batched: bool,
+    ) -> HttpResponse {
+        let route_path = path.split('?').next().unwrap_or_default().to_string();
 
-        // Parse existing body as JSON, or create empty object
-        let mut json: serde_json::Value = if body.is_empty() {
-            serde_json::json!({})
-        } else {
-            match serde_json::from_slice(&body) {
-                Ok(v) => v,
-                Err(_) => return body, // Can't merge into non-JSON body
-            }
+        let Some(pipeline) = &self.pipeline else {
+            return self.route_request(&method, &route_path, body).await;
         };
 
-        // Merge path params into the JSON object
-        if let serde_json::Value::Object(ref mut map) = json {
-            for (key, value) in params {
-                // Convert camelCase to snake_case for Rust compatibility
-                let snake_key = camel_to_snake(key);
-                tracing::debug!("  {} -> {} = {}", key, snake_key, value);
-                map.insert(snake_key, serde_json::Value::String(value.clone()));
-            }
+        let mut ctx = self.middleware_context(&method, &route_path, &headers);
+        if batched {
+            ctx.set_extension(BatchedRequest);
         }
+        let request = Self::pipeline_request(method, path, headers, body);
 
-        let result = Bytes::from(serde_json::to_vec(&json).unwrap_or_default());
-        tracing::debug!("Merged body: {:?}", String::from_utf8_lossy(&result));
-        result
+        let server = Arc::clone(self);
+        pipeline
+            .process(ctx, request, move |ctx, request| {
+                let obligations = ctx
+                    .get_extension::<Obligations>()
+                    .cloned()
+                    .unwrap_or_default();
+                let locale = ctx.locale().map(str::to_string);
+                Box::pin(async move {
+                    let (parts, body) = request.into_parts();
+                    let body = body
+                        .collect()
+                        .await
+                        .map(http_body_util::Collected::to_bytes)
+                        .unwrap_or_default();
+                    server
+                        .route_request_with_obligations(
+                            &parts.method,
+                            parts.uri.path(),
+                            body,
+                            obligations,
+                            locale,
+                        )
+                        .await
+                })
+            })
+            .await
     }
-}
 
-/// Converts camelCase to snake_case.
-fn camel_to_snake(s: &str) -> String {
-    let mut result = String::with_capacity(s.len() + 4);
-    for (i, c) in s.chars().enumerate() {
-        if c.is_uppercase() {
-            if i > 0 {
-                result.push('_');
-            }
-            result.push(c.to_ascii_lowercase());
-        } else {
-            result.push(c);
-        }
-    }
-    result
-}
+    /// Dispatches a request to an internal endpoint.
+    ///
+    /// The request passes through the pipeline (if configured) marked with
+    /// [`RouteOptions::internal`], so policy stages step aside while
+    /// request ID, tracing, telemetry and error normalization still run.
+    /// `target` is the request path, with its query string if any.
+    async fn dispatch_internal(
+        self: &Arc<Self>,
+        endpoint: InternalEndpoint,
+        route: String,
+        method: Method,
+        target: &str,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> HttpResponse {
+        let Some(pipeline) = &self.pipeline else {
+            let request = Self::internal_request(method, target, headers, body);
+            return self.serve_internal(endpoint, request).await;
+        };
 
-/// Builder for configuring and creating a [`Server`].
-///
-/// # Example
-///
-/// ```rust
-/// use archimedes_server::{Server, ServerBuilder, HandlerRegistry};
-/// use std::time::Duration;
-///
-/// let server = ServerBuilder::new()
-///     .http_addr("0.0.0.0:9090")
-///     .shutdown_timeout(Duration::from_secs(60))
-///     .request_timeout(Duration::from_secs(30))
-///     .build();
-/// ```
-#[derive(Default)]
-pub struct ServerBuilder {
-    config_builder: crate::config::ServerConfigBuilder,
-    handlers: Option<HandlerRegistry>,
-    health_service: Option<String>,
-    health_version: Option<String>,
-    request_timeout: Option<Duration>,
-}
+        let path = target.split('?').next().unwrap_or_default().to_string();
+        let mut ctx = MiddlewareContext::from_request(method.clone(), path, headers.clone());
+        ctx.set_extension(RouteOptions::internal());
+        ctx.set_extension(RoutePattern(route));
+        ctx.set_url_generator(self.url_generator().clone());
+        let request = Self::pipeline_request(method, target, headers, body);
 
-impl ServerBuilder {
-    /// Creates a new server builder with default settings.
-    #[must_use]
-    pub fn new() -> Self {
-        Self::default()
+        let server = Arc::clone(self);
+        pipeline
+            .process(ctx, request, move |_ctx, request| {
+                Box::pin(async move {
+                    let (parts, body) = request.into_parts();
+                    let body = body
+                        .collect()
+                        .await
+                        .map(http_body_util::Collected::to_bytes)
+                        .unwrap_or_default();
+                    let request = Request::from_parts(parts, body);
+                    server.serve_internal(endpoint, request).await
+                })
+            })
+            .await
     }
 
-    /// Sets the handler registry.
-    ///
-    /// # Arguments
-    ///
-    /// * `handlers` - The handler registry with registered operation handlers
-    #[must_use]
-    pub fn handlers(mut self, handlers: HandlerRegistry) -> Self {
-        self.handlers = Some(handlers);
-        self
+    /// Serves an internal endpoint.
+    async fn serve_internal(
+        self: &Arc<Self>,
+        endpoint: InternalEndpoint,
+        request: Request<Bytes>,
+    ) -> HttpResponse {
+        match endpoint {
+            InternalEndpoint::Health => self.handle_health(),
+            InternalEndpoint::Ready => self.handle_ready(),
+            InternalEndpoint::Diagnostics => self.handle_diagnostics(),
+            InternalEndpoint::Batch => self.handle_batch(request.headers(), request.body()).await,
+            InternalEndpoint::Resolve(explain) => self.handle_resolve(&explain, request.uri()),
+            InternalEndpoint::Metrics(source) => source.respond(request.headers()),
+            InternalEndpoint::Custom(handler) => handler(request).await,
+        }
     }
 
-    /// Sets the HTTP bind address.
-    ///
-    /// # Arguments
+    /// Handles the /-/resolve endpoint.
     ///
-    /// * `addr` - Address to bind to (e.g., "0.0.0.0:8080")
-    #[must_use]
-    pub fn http_addr(mut self, addr: impl Into<String>) -> Self {
-        self.config_builder = self.config_builder.http_addr(addr);
-        self
-    }
+    /// Expects `method` and `path` query parameters.
+    fn handle_resolve(&self, explain: &ResolveExplainer, uri: &Uri) -> HttpResponse {
+        let mut method = None;
+        let mut path = None;
+        for (key, value) in form_urlencoded::parse(uri.query().unwrap_or_default().as_bytes()) {
+            match key.as_ref() {
+                "method" => method = Some(value.into_owned()),
+                "path" => path = Some(value.into_owned()),
+                _ => {}
+            }
+        }
+        let (Some(method), Some(path)) = (method, path) else {
+            return self.handle_error(
+                StatusCode::BAD_REQUEST,
+                "BAD_REQUEST",
+                "Both the 'method' and 'path' query parameters are required",
+            );
+        };
 
-    /// Sets the graceful shutdown timeout.
-    #[must_use]
-    pub fn shutdown_timeout(mut self, timeout: Duration) -> Self {
-        self.config_builder = self.config_builder.shutdown_timeout(timeout);
-        self
+        let body = explain(&method.to_ascii_uppercase(), &path).to_string();
+        Response::builder()
+            .status(StatusCode::OK)
+            .header("Content-Type", "application/json")
+            .body(Full::new(Bytes::from(body)))
+            .unwrap_or_else(|_| Response::new(Full::new(Bytes::from("{}"))))
     }
 
-    /// Sets the TCP keep-alive timeout.
-    #[must_use]
-    pub fn keep_alive_timeout(mut self, timeout: Option<Duration>) -> Self {
-        self.config_builder = self.config_builder.keep_alive_timeout(timeout);
-        self
+    /// Builds the request passed to an internal endpoint.
+    fn internal_request(
+        method: Method,
+        path: &str,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> Request<Bytes> {
+        let mut request = Request::new(body);
+        *request.method_mut() = method;
+        *request.uri_mut() = path.parse().unwrap_or_default();
+        *request.headers_mut() = headers;
+        request
     }
 
-    /// Sets the maximum concurrent connections.
-    #[must_use]
+    /// Dispatches a request for a streaming operation.
+    ///
+    /// Middleware sees the response head with an empty body; the stream is
+    /// reattached afterwards. If a middleware replaces the response the
+    /// stream is dropped, which fails it.
+    async fn dispatch_streaming(
+        self: &Arc<Self>,
+        method: Method,
+        path: &str,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> Response<ConnectionBody> {
+        let Some(pipeline) = &self.pipeline else {
+            return match self
+                .route_streaming_request(&method, path, body, Obligations::default(), None)
+                .await
+            {
+                Ok(response) => response.map(|stream| Either::Right(Either::Left(stream))),
+                Err(response) => response.map(Either::Left),
+            };
+        };
+
+        let ctx = self.middleware_context(&method, path, &headers);
+        let request = Self::pipeline_request(method, path, headers, body);
+
+        let slot = Arc::new(Mutex::new(None));
+        let handler_slot = Arc::clone(&slot);
+        let server = Arc::clone(self);
+        let response = pipeline
+            .process(ctx, request, move |ctx, request| {
+                let obligations = ctx
+                    .get_extension::<Obligations>()
+                    .cloned()
+                    .unwrap_or_default();
+                let locale = ctx.locale().map(str::to_string);
+                Box::pin(async move {
+                    let (parts, body) = request.into_parts();
+                    let body = body
+                        .collect()
+                        .await
+                        .map(http_body_util::Collected::to_bytes)
+                        .unwrap_or_default();
+                    match server
+                        .route_streaming_request(
+                            &parts.method,
+                            parts.uri.path(),
+                            body,
+                            obligations,
+                            locale,
+                        )
+                        .await
+                    {
+                        Ok(response) => {
+                            let (parts, stream) = response.into_parts();
+                            *handler_slot.lock().unwrap_or_else(PoisonError::into_inner) =
+                                Some(stream);
+                            Response::from_parts(parts, Full::new(Bytes::new()))
+                        }
+                        Err(response) => response,
+                    }
+                })
+            })
+            .await;
+
+        let (parts, body) = response.into_parts();
+        let stream = slot.lock().unwrap_or_else(PoisonError::into_inner).take();
+        match stream {
+            Some(stream) if parts.extensions.get::<StreamOutcome>().is_some() => {
+                Response::from_parts(parts, Either::Right(Either::Left(stream)))
+            }
+            _ => Response::from_parts(parts, Either::Left(body)),
+        }
+    }
+
+    /// Dispatches a request for an operation that consumes the request body
+    /// as a stream.
+    ///
+    /// Middleware sees the request with an empty body and skips request
+    /// validation; the body stream is handed to the handler directly.
+    async fn dispatch_body_stream(
+        self: &Arc<Self>,
+        method: Method,
+        path: &str,
+        headers: HeaderMap,
+        body: BodyStream,
+    ) -> HttpResponse {
+        let Some(pipeline) = &self.pipeline else {
+            return self
+                .route_body_stream_request(
+                    &method,
+                    path,
+                    &headers,
+                    body,
+                    Obligations::default(),
+                    None,
+                    None,
+                )
+                .await;
+        };
+
+        let mut ctx = self.middleware_context(&method, path, &headers);
+        ctx.set_extension(RouteOptions {
+            skip_request_validation: true,
+            ..RouteOptions::default()
+        });
+        let request = Self::pipeline_request(method, path, headers, Bytes::new());
+
+        let server = Arc::clone(self);
+        pipeline
+            .process(ctx, request, move |ctx, request| {
+                let obligations = ctx
+                    .get_extension::<Obligations>()
+                    .cloned()
+                    .unwrap_or_default();
+                let locale = ctx.locale().map(str::to_string);
+                let version = ctx.get_extension::<ContractVersion>().map(|v| v.0.clone());
+                Box::pin(async move {
+                    let (parts, _) = request.into_parts();
+                    server
+                        .route_body_stream_request(
+                            &parts.method,
+                            parts.uri.path(),
+                            &parts.headers,
+                            body,
+                            obligations,
+                            locale,
+                            version,
+                        )
+                        .await
+                })
+            })
+            .await
+    }
+
+    /// Builds the middleware context for a request.
+    fn middleware_context(
+        &self,
+        method: &Method,
+        route_path: &str,
+        headers: &HeaderMap,
+    ) -> MiddlewareContext {
+        let mut ctx = MiddlewareContext::from_request(
+            method.clone(),
+            route_path.to_string(),
+            headers.clone(),
+        );
+        if let Some(route_match) = self.router.match_route(method, route_path) {
+            if let Some(pattern) = self.router.route_pattern(route_match.operation_id()) {
+                ctx.set_extension(RoutePattern(pattern.to_string()));
+            }
+            ctx.set_operation_id(route_match.operation_id().to_string());
+        }
+        if let Some(app) = &self.app_name {
+            ctx.set_extension(AppName(app.clone()));
+        }
+        ctx.set_url_generator(self.url_generator().clone());
+        ctx
+    }
+
+    /// Builds the request passed through the middleware pipeline.
+    fn pipeline_request(
+        method: Method,
+        path: &str,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> archimedes_middleware::Request {
+        let mut request = Request::new(Full::new(body));
+        *request.method_mut() = method;
+        *request.uri_mut() = path.parse().unwrap_or_default();
+        *request.headers_mut() = headers;
+        request
+    }
+
+    /// Handles the /-/batch endpoint.
+    async fn handle_batch(self: &Arc<Self>, headers: &HeaderMap, body: &Bytes) -> HttpResponse {
+        let requests = match self.batch.parse(body) {
+            Ok(requests) => requests,
+            Err(e) => return self.handle_error(e.status_code(), e.code(), &e.to_string()),
+        };
+
+        let responses: Vec<BatchSubResponse> = futures_util::stream::iter(requests)
+            .map(|sub| self.dispatch_sub_request(headers, sub))
+            .buffered(self.batch.concurrency_limit())
+            .collect()
+            .await;
+
+        let body = serde_json::to_string(&responses).unwrap_or_else(|_| "[]".to_string());
+
+        Response::builder()
+            .status(StatusCode::OK)
+            .header("Content-Type", "application/json")
+            .body(Full::new(Bytes::from(body)))
+            .unwrap_or_else(|_| Response::new(Full::new(Bytes::from("[]"))))
+    }
+
+    /// Dispatches one sub-request of a batch.
+    async fn dispatch_sub_request(
+        self: &Arc<Self>,
+        headers: &HeaderMap,
+        sub: BatchSubRequest,
+    ) -> BatchSubResponse {
+        if sub.is_recursive() {
+            return BatchSubResponse::error(
+                sub.id,
+                StatusCode::BAD_REQUEST,
+                "BATCH_RECURSION",
+                "Batch sub-requests cannot target the batch endpoint",
+            );
+        }
+
+        let Ok(method) = Method::from_bytes(sub.method.to_ascii_uppercase().as_bytes()) else {
+            let message = format!("Invalid method: {}", sub.method);
+            return BatchSubResponse::error(
+                sub.id,
+                StatusCode::BAD_REQUEST,
+                "INVALID_METHOD",
+                &message,
+            );
+        };
+
+        let sub_headers = sub.merged_headers(headers);
+        let body = sub
+            .body
+            .as_ref()
+            .map(|b| Bytes::from(serde_json::to_vec(b).unwrap_or_default()))
+            .unwrap_or_default();
+
+        // Sub-requests go to the app their host and path select
+        let uri: Uri = sub.path.parse().unwrap_or_default();
+        let Ok((server, path)) = self.select_app(&sub_headers, &uri, &sub.path) else {
+            return BatchSubResponse::error(
+                sub.id,
+                StatusCode::MISDIRECTED_REQUEST,
+                "MISDIRECTED_REQUEST",
+                "No app is served for the sub-request",
+            );
+        };
+
+        let response = tokio::time::timeout(
+            server.timeout_for(&method, &path),
+            server.dispatch(method, &path, sub_headers, body, true),
+        )
+        .await;
+
+        match response {
+            Ok(mut response) => {
+                trailers::fold_into_headers(&mut response);
+                let (parts, body) = response.into_parts();
+                let body = body
+                    .collect()
+                    .await
+                    .map(http_body_util::Collected::to_bytes)
+                    .unwrap_or_default();
+                BatchSubResponse::new(sub.id, parts.status, &parts.headers, &body)
+            }
+            Err(_) => BatchSubResponse::error(
+                sub.id,
+                StatusCode::GATEWAY_TIMEOUT,
+                "HANDLER_TIMEOUT",
+                "Handler execution timed out",
+            ),
+        }
+    }
+
+    /// Routes a request to the appropriate handler.
+    async fn route_request(&self, method: &Method, path: &str, body: Bytes) -> HttpResponse {
+        self.route_request_with_obligations(method, path, body, Obligations::default(), None)
+            .await
+    }
+
+    /// Routes a request to the appropriate handler, handing it the
+    /// obligations the authorization stage attached to the request and the
+    /// locale negotiated for it.
+    async fn route_request_with_obligations(
+        &self,
+        method: &Method,
+        path: &str,
+        body: Bytes,
+        obligations: Obligations,
+        locale: Option<String>,
+    ) -> HttpResponse {
+        match self.router.match_route(method, path) {
+            Some(route_match) => {
+                self.handle_matched_route(route_match, body, obligations, locale)
+                    .await
+            }
+            None => self.handle_not_found(path),
+        }
+    }
+
+    /// Routes a request to a streaming handler.
+    ///
+    /// Returns the buffered error response if the handler fails before
+    /// building the stream.
+    async fn route_streaming_request(
+        &self,
+        method: &Method,
+        path: &str,
+        body: Bytes,
+        obligations: Obligations,
+        locale: Option<String>,
+    ) -> Result<Response<StreamingBody>, HttpResponse> {
+        let Some(route_match) = self.router.match_route(method, path) else {
+            return Err(self.handle_not_found(path));
+        };
+        let operation_id = route_match.operation_id();
+
+        let ctx = self.request_context(operation_id, obligations, locale);
+        let merged_body =
+            self.merge_path_params_into_body(operation_id, route_match.params(), body);
+
+        match self
+            .handlers
+            .invoke_streaming(operation_id, ctx, merged_body)
+            .await
+        {
+            Ok(response) => Ok(response),
+            Err(InvokeError::HandlerNotFound(id)) => Err(self.handle_error(
+                StatusCode::NOT_IMPLEMENTED,
+                "HANDLER_NOT_IMPLEMENTED",
+                &format!("No handler registered for operation: {}", id),
+            )),
+            Err(InvokeError::HandlerError(e)) => {
+                tracing::error!("Handler error for {}: {}", operation_id, e);
+                Err(self.handle_handler_error(operation_id, e))
+            }
+        }
+    }
+
+    /// Routes a request to a handler that consumes the request body as a
+    /// stream, validating its items against the contract version the
+    /// request resolved to.
+    #[allow(clippy::too_many_arguments)]
+    async fn route_body_stream_request(
+        &self,
+        method: &Method,
+        path: &str,
+        headers: &HeaderMap,
+        body: BodyStream,
+        obligations: Obligations,
+        locale: Option<String>,
+        version: Option<String>,
+    ) -> HttpResponse {
+        let Some(route_match) = self.router.match_route(method, path) else {
+            return self.handle_not_found(path);
+        };
+        let operation_id = route_match.operation_id();
+
+        let ctx = self.request_context(operation_id, obligations, locale);
+        let body = match self
+            .handlers
+            .item_validator(operation_id, version.as_deref(), headers)
+        {
+            Some(validator) => body.with_item_validator(validator),
+            None => body,
+        };
+
+        match self
+            .handlers
+            .invoke_body_stream(operation_id, ctx, body)
+            .await
+        {
+            Ok(response) => response.map(Full::new),
+            Err(InvokeError::HandlerNotFound(id)) => self.handle_error(
+                StatusCode::NOT_IMPLEMENTED,
+                "HANDLER_NOT_IMPLEMENTED",
+                &format!("No handler registered for operation: {}", id),
+            ),
+            Err(InvokeError::HandlerError(e)) => {
+                tracing::error!("Handler error for {}: {}", operation_id, e);
+                self.handle_handler_error(operation_id, e)
+            }
+        }
+    }
+
+    /// Handles a matched route by invoking the registered handler.
+    async fn handle_matched_route(
+        &self,
+        route_match: RouteMatch,
+        body: Bytes,
+        obligations: Obligations,
+        locale: Option<String>,
+    ) -> HttpResponse {
+        let operation_id = route_match.operation_id();
+
+        // Check if handler is registered
+        if !self.handlers.contains(operation_id) {
+            tracing::warn!("No handler registered for operation: {}", operation_id);
+            return self.handle_error(
+                StatusCode::NOT_IMPLEMENTED,
+                "HANDLER_NOT_IMPLEMENTED",
+                &format!("No handler registered for operation: {}", operation_id),
+            );
+        }
+
+        // Streaming handlers only run on the connection path, never in a batch
+        if self.handlers.is_streaming(operation_id) {
+            return self.handle_error(
+                StatusCode::BAD_REQUEST,
+                "STREAMING_NOT_SUPPORTED",
+                &format!("Operation {} streams its response", operation_id),
+            );
+        }
+
+        // Create request context with operation ID
+        let ctx = self.request_context(operation_id, obligations, locale);
+
+        // Body stream handlers read an already buffered body, as in a batch
+        if self.handlers.is_body_stream(operation_id) {
+            return match self
+                .handlers
+                .invoke_body_stream(operation_id, ctx, BodyStream::from_bytes(body))
+                .await
+            {
+                Ok(response) => response.map(Full::new),
+                Err(InvokeError::HandlerNotFound(id)) => self.handle_error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "HANDLER_NOT_FOUND",
+                    &format!("Handler not found: {}", id),
+                ),
+                Err(InvokeError::HandlerError(e)) => self.handle_handler_error(operation_id, e),
+            };
+        }
+
+        // Merge path parameters into the request body
+        // This allows handlers to receive path params (e.g., userId) as part of their request type
+        let merged_body =
+            self.merge_path_params_into_body(operation_id, route_match.params(), body);
+
+        // Invoke the handler
+        match self
+            .handlers
+            .invoke_response(operation_id, ctx, merged_body)
+            .await
+        {
+            Ok(response) => response.map(Full::new),
+            Err(InvokeError::HandlerNotFound(id)) => {
+                tracing::error!("Handler not found during invocation: {}", id);
+                self.handle_error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "HANDLER_NOT_FOUND",
+                    &format!("Handler not found: {}", id),
+                )
+            }
+            Err(InvokeError::HandlerError(e)) => {
+                tracing::error!("Handler error for {}: {}", operation_id, e);
+                self.handle_handler_error(operation_id, e)
+            }
+        }
+    }
+
+    /// Builds the context handed to the handler of an operation.
+    fn request_context(
+        &self,
+        operation_id: &str,
+        obligations: Obligations,
+        locale: Option<String>,
+    ) -> RequestContext {
+        let mut ctx = RequestContext::new()
+            .with_operation_id(operation_id)
+            .with_obligations(obligations)
+            .with_url_generator(self.url_generator().clone());
+        if let Some(locale) = locale {
+            ctx.set_locale(locale);
+        }
+        match &self.container {
+            Some(container) => ctx.with_container(Arc::clone(container)),
+            None => ctx,
+        }
+    }
+
+    /// Handles handler errors and converts them to HTTP responses.
+    fn handle_handler_error(
+        &self,
+        operation_id: &str,
+        error: crate::handler::HandlerError,
+    ) -> HttpResponse {
+        use crate::handler::IntoErrorResponse;
+
+        let response = error.into_error_response();
+        let mut body = serde_json::json!({
+            "error": {
+                "code": response.code,
+                "message": response.message,
+                "operation_id": operation_id
+            }
+        });
+        if let Some(details) = response.details {
+            body["error"]["details"] = details;
+        }
+
+        Response::builder()
+            .status(response.status)
+            .header("Content-Type", "application/json")
+            .body(Full::new(Bytes::from(body.to_string())))
+            .unwrap_or_else(|_| Response::new(Full::new(Bytes::new())))
+    }
+
+    /// Creates a standard error response.
+    fn handle_error(&self, status: StatusCode, code: &str, message: &str) -> HttpResponse {
+        let body = serde_json::json!({
+            "error": {
+                "code": code,
+                "message": message
+            }
+        });
+
+        Response::builder()
+            .status(status)
+            .header("Content-Type", "application/json")
+            .body(Full::new(Bytes::from(body.to_string())))
+            .unwrap_or_else(|_| Response::new(Full::new(Bytes::new())))
+    }
+
+    /// Handles a not found response.
+    fn handle_not_found(&self, path: &str) -> HttpResponse {
+        let body = serde_json::json!({
+            "error": "Not Found",
+            "path": path
+        });
+
+        Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .header("Content-Type", "application/json")
+            .body(Full::new(Bytes::from(body.to_string())))
+            .unwrap_or_else(|_| Response::new(Full::new(Bytes::new())))
+    }
+
+    /// Merges path parameters into the request body.
+    ///
+    /// This allows handlers to receive path parameters (e.g., `userId` from `/users/{userId}`)
+    /// as part of their typed request struct. Each param is stored under the request field it
+    /// matches exactly or by naming style, so `#[serde(rename = "userId")]` is honored. Params
+    /// with no matching field are converted from camelCase to snake_case (`userId` -> `user_id`).
+    fn merge_path_params_into_body(
+        &self,
+        operation_id: &str,
+        params: &std::collections::HashMap<String, String>,
+        body: Bytes,
+    ) -> Bytes {
+        if params.is_empty() {
+            return body;
+        }
+
+        tracing::debug!("Merging path params into body: {:?}", params);
+
+        // Parse existing body as JSON, or create empty object
+        let mut json: serde_json::Value = if body.is_empty() {
+            serde_json::json!({})
+        } else {
+            match serde_json::from_slice(&body) {
+                Ok(v) => v,
+                Err(_) => return body, // Can't merge into non-JSON body
+            }
+        };
+
+        // Merge path params into the JSON object
+        let fields = self
+            .handlers
+            .request_fields(operation_id)
+            .unwrap_or_default();
+        if let serde_json::Value::Object(ref mut map) = json {
+            for (key, value) in params {
+                let field = match_field(fields, key);
+                let field_key = if fields.iter().any(|f| *f == field) {
+                    field.to_string()
+                } else {
+                    // Convert camelCase to snake_case for Rust compatibility
+                    camel_to_snake(key)
+                };
+                tracing::debug!("  {} -> {} = {}", key, field_key, value);
+                map.insert(field_key, serde_json::Value::String(value.clone()));
+            }
+        }
+
+        let result = Bytes::from(serde_json::to_vec(&json).unwrap_or_default());
+        tracing::debug!("Merged body: {:?}", String::from_utf8_lossy(&result));
+        result
+    }
+}
+
+/// Converts camelCase to snake_case.
+fn camel_to_snake(s: &str) -> String {
+    let mut result = String::with_capacity(s.len() + 4);
+    for (i, c) in s.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                result.push('_');
+            }
+            result.push(c.to_ascii_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Builder for configuring and creating a [`Server`].
+///
+/// # Example
+///
+/// ```rust
+/// use archimedes_server::{Server, ServerBuilder, HandlerRegistry};
+/// use std::time::Duration;
+///
+/// let server = ServerBuilder::new()
+///     .http_addr("0.0.0.0:9090")
+///     .shutdown_timeout(Duration::from_secs(60))
+///     .request_timeout(Duration::from_secs(30))
+///     .build();
+/// ```
+#[derive(Default)]
+pub struct ServerBuilder {
+    config_builder: crate::config::ServerConfigBuilder,
+    handlers: Option<HandlerRegistry>,
+    health_service: Option<String>,
+    health_version: Option<String>,
+    request_timeout: Option<Duration>,
+    operation_timeouts: HashMap<String, Duration>,
+    max_request_timeout: Option<Duration>,
+    cache_policies: HashMap<String, CachePolicy>,
+    diagnostics: Option<Diagnostics>,
+    diagnostics_endpoint: bool,
+    detailed_timing: bool,
+    pipeline: Option<Pipeline>,
+    batch: Option<BatchConfig>,
+    internal_routes: Vec<(Method, String, InternalEndpoint)>,
+    exit_hooks: Vec<ExitHook>,
+    drain_hooks: Vec<DrainHook>,
+    drain_timeout: Option<Duration>,
+    contracts: Vec<ContractInfo>,
+    base_path: Option<String>,
+    apps: Vec<AppSpec>,
+    default_app: Option<String>,
+    observability: Option<ObservabilityEndpoints>,
+    warmup: Option<Warmup>,
+}
+
+impl ServerBuilder {
+    /// Creates a new server builder with default settings.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the handler registry.
+    ///
+    /// # Arguments
+    ///
+    /// * `handlers` - The handler registry with registered operation handlers
+    #[must_use]
+    pub fn handlers(mut self, handlers: HandlerRegistry) -> Self {
+        self.handlers = Some(handlers);
+        self
+    }
+
+    /// Sets the HTTP bind address.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - Address to bind to (e.g., "0.0.0.0:8080")
+    #[must_use]
+    pub fn http_addr(mut self, addr: impl Into<String>) -> Self {
+        self.config_builder = self.config_builder.http_addr(addr);
+        self
+    }
+
+    /// Sets the graceful shutdown timeout.
+    #[must_use]
+    pub fn shutdown_timeout(mut self, timeout: Duration) -> Self {
+        self.config_builder = self.config_builder.shutdown_timeout(timeout);
+        self
+    }
+
+    /// Sets the TCP keep-alive timeout.
+    #[must_use]
+    pub fn keep_alive_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.config_builder = self.config_builder.keep_alive_timeout(timeout);
+        self
+    }
+
+    /// Sets the maximum concurrent connections.
+    #[must_use]
     pub fn max_connections(mut self, max: Option<usize>) -> Self {
         self.config_builder = self.config_builder.max_connections(max);
         self
     }
 
-    /// Enables or disables HTTP/2.
-    #[must_use]
-    pub fn http2_enabled(mut self, enabled: bool) -> Self {
-        self.config_builder = self.config_builder.http2_enabled(enabled);
-        self
+    /// Enables or disables HTTP/2.
+    #[must_use]
+    pub fn http2_enabled(mut self, enabled: bool) -> Self {
+        self.config_builder = self.config_builder.http2_enabled(enabled);
+        self
+    }
+
+    /// Sets how many requests an HTTP/1 connection serves before closing.
+    ///
+    /// See [`ServerConfigBuilder::max_requests_per_connection`](crate::ServerConfigBuilder::max_requests_per_connection).
+    #[must_use]
+    pub fn max_requests_per_connection(mut self, max: Option<u64>) -> Self {
+        self.config_builder = self.config_builder.max_requests_per_connection(max);
+        self
+    }
+
+    /// Sets the maximum number of concurrent HTTP/2 streams per connection.
+    #[must_use]
+    pub fn http2_max_concurrent_streams(mut self, max: Option<u32>) -> Self {
+        self.config_builder = self.config_builder.http2_max_concurrent_streams(max);
+        self
+    }
+
+    /// Sets the initial HTTP/2 stream-level flow control window, in bytes.
+    #[must_use]
+    pub fn http2_initial_stream_window_size(mut self, size: Option<u32>) -> Self {
+        self.config_builder = self.config_builder.http2_initial_stream_window_size(size);
+        self
+    }
+
+    /// Sets the initial HTTP/2 connection-level flow control window, in bytes.
+    #[must_use]
+    pub fn http2_initial_connection_window_size(mut self, size: Option<u32>) -> Self {
+        self.config_builder = self
+            .config_builder
+            .http2_initial_connection_window_size(size);
+        self
+    }
+
+    /// Sets the interval between HTTP/2 keep-alive pings.
+    #[must_use]
+    pub fn http2_keep_alive_interval(mut self, interval: Option<Duration>) -> Self {
+        self.config_builder = self.config_builder.http2_keep_alive_interval(interval);
+        self
+    }
+
+    /// Sets how long to wait for an HTTP/2 keep-alive ping acknowledgement.
+    #[must_use]
+    pub fn http2_keep_alive_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.config_builder = self.config_builder.http2_keep_alive_timeout(timeout);
+        self
+    }
+
+    /// Sets the maximum HTTP/2 header list size, in bytes.
+    #[must_use]
+    pub fn http2_max_header_list_size(mut self, size: Option<u32>) -> Self {
+        self.config_builder = self.config_builder.http2_max_header_list_size(size);
+        self
+    }
+
+    /// Sets the service name for health checks.
+    #[must_use]
+    pub fn service_name(mut self, name: impl Into<String>) -> Self {
+        self.health_service = Some(name.into());
+        self
+    }
+
+    /// Sets the service version for health checks.
+    #[must_use]
+    pub fn service_version(mut self, version: impl Into<String>) -> Self {
+        self.health_version = Some(version.into());
+        self
+    }
+
+    /// Sets the request timeout.
+    ///
+    /// This timeout applies to both body collection and handler execution.
+    /// Default is 30 seconds.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - The request timeout duration
+    #[must_use]
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the timeout of a single operation.
+    ///
+    /// The operation's handler runs under this timeout instead of the
+    /// request timeout, clamped to the
+    /// [maximum request timeout](Self::max_request_timeout). Body
+    /// collection still uses the request timeout.
+    ///
+    /// # Arguments
+    ///
+    /// * `operation_id` - The operation the timeout applies to
+    /// * `timeout` - The operation's timeout
+    #[must_use]
+    pub fn operation_timeout(mut self, operation_id: impl Into<String>, timeout: Duration) -> Self {
+        self.operation_timeouts.insert(operation_id.into(), timeout);
+        self
+    }
+
+    /// Sets the timeouts of several operations, e.g. the ones a contract
+    /// declares with `x-timeout-ms`.
+    ///
+    /// See [`operation_timeout`](Self::operation_timeout).
+    #[must_use]
+    pub fn operation_timeouts<I, S>(mut self, timeouts: I) -> Self
+    where
+        I: IntoIterator<Item = (S, Duration)>,
+        S: Into<String>,
+    {
+        self.operation_timeouts.extend(
+            timeouts
+                .into_iter()
+                .map(|(id, timeout)| (id.into(), timeout)),
+        );
+        self
+    }
+
+    /// Sets the cache policy of a single operation.
+    ///
+    /// The policy's `Cache-Control` (and `Expires`) headers are set on the
+    /// operation's successful responses whose handler did not set
+    /// `Cache-Control` itself. Error responses always get `no-store`.
+    ///
+    /// # Arguments
+    ///
+    /// * `operation_id` - The operation the policy applies to
+    /// * `policy` - The operation's cache policy
+    #[must_use]
+    pub fn cache_policy(mut self, operation_id: impl Into<String>, policy: CachePolicy) -> Self {
+        self.cache_policies.insert(operation_id.into(), policy);
+        self
+    }
+
+    /// Sets the cache policies of several operations, e.g. the ones a
+    /// contract declares with `x-cache-policy`.
+    ///
+    /// See [`cache_policy`](Self::cache_policy).
+    #[must_use]
+    pub fn cache_policies<I, S>(mut self, policies: I) -> Self
+    where
+        I: IntoIterator<Item = (S, CachePolicy)>,
+        S: Into<String>,
+    {
+        self.cache_policies
+            .extend(policies.into_iter().map(|(id, policy)| (id.into(), policy)));
+        self
+    }
+
+    /// Sets the upper bound for operation timeouts.
+    ///
+    /// Operation timeouts above it are clamped to it. The request timeout
+    /// itself is not clamped. No bound by default.
+    #[must_use]
+    pub fn max_request_timeout(mut self, max: Duration) -> Self {
+        self.max_request_timeout = Some(max);
+        self
+    }
+
+    /// Sets the diagnostics seed.
+    ///
+    /// Use this to report what the server cannot see itself: the loaded
+    /// contract, policy bundle revision, middleware stages and config
+    /// profile. See [`Server::diagnostics`].
+    #[must_use]
+    pub fn diagnostics(mut self, diagnostics: Diagnostics) -> Self {
+        self.diagnostics = Some(diagnostics);
+        self
+    }
+
+    /// Adds a contract version served by this server.
+    ///
+    /// Call once per artifact when serving several contract versions side
+    /// by side; the first becomes the default. Handler coverage in
+    /// [`Server::diagnostics`] is computed against the operations of every
+    /// version.
+    #[must_use]
+    pub fn contract(mut self, contract: ContractInfo) -> Self {
+        self.contracts.push(contract);
+        self
+    }
+
+    /// Enables or disables the `/-/diagnostics` endpoint.
+    ///
+    /// Disabled by default.
+    #[must_use]
+    pub fn diagnostics_endpoint(mut self, enabled: bool) -> Self {
+        self.diagnostics_endpoint = enabled;
+        self
+    }
+
+    /// Serves a dry-run resolution at `GET /-/resolve?method=..&path=..`.
+    ///
+    /// `explain` receives the method and path and returns the explanation
+    /// as JSON, typically from `archimedes_sentinel::Sentinel::explain`.
+    /// The endpoint is not registered unless this is called, and it reveals
+    /// the shape of the whole contract, so keep it out of production.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let sentinel = Arc::new(sentinel);
+    /// let server = Server::builder()
+    ///     .resolve_endpoint(move |method, path| {
+    ///         serde_json::to_value(sentinel.explain(method, path)).unwrap_or_default()
+    ///     })
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn resolve_endpoint<F>(mut self, explain: F) -> Self
+    where
+        F: Fn(&str, &str) -> serde_json::Value + Send + Sync + 'static,
+    {
+        self.internal_routes.push((
+            Method::GET,
+            RESOLVE_PATH.to_string(),
+            InternalEndpoint::Resolve(Arc::new(explain)),
+        ));
+        self
+    }
+
+    /// Enables or disables detailed per-request timing.
+    ///
+    /// When enabled, each request records the time spent in validation,
+    /// deserialization, the handler and serialization, along with the
+    /// request and response body sizes. The timings are logged, recorded
+    /// in the `archimedes_request_phase_duration_seconds` histogram and
+    /// attached to the response as a [`PhaseTimings`] extension.
+    ///
+    /// Disabled by default because of the per-request overhead and the
+    /// histogram's cardinality.
+    #[must_use]
+    pub fn detailed_timing(mut self, enabled: bool) -> Self {
+        self.detailed_timing = enabled;
+        self
+    }
+
+    /// Sets the middleware pipeline applied to routed requests.
+    ///
+    /// The router resolves the operation ID before the pipeline runs, so
+    /// stages such as authorization see it on the context.
+    #[must_use]
+    pub fn pipeline(mut self, pipeline: Pipeline) -> Self {
+        self.pipeline = Some(pipeline);
+        self
+    }
+
+    /// Sets the base path prepended to URLs built with `url_for`.
+    ///
+    /// Use this when the server is mounted below a path prefix by a proxy
+    /// or gateway, so generated links resolve from the client's side.
+    #[must_use]
+    pub fn base_path(mut self, base_path: impl Into<String>) -> Self {
+        self.base_path = Some(base_path.into());
+        self
+    }
+
+    /// Sets the batch endpoint configuration.
+    ///
+    /// The endpoint is served at [`BATCH_PATH`](crate::batch::BATCH_PATH)
+    /// when the configuration is enabled.
+    #[must_use]
+    pub fn batch(mut self, config: BatchConfig) -> Self {
+        self.batch = Some(config);
+        self
+    }
+
+    /// Registers an endpoint served outside the contract, such as
+    /// `/metrics` or API docs.
+    ///
+    /// Requests to it skip authorization, request and response validation
+    /// and rate limiting, but still pass through request ID, tracing,
+    /// telemetry and error normalization. Registering a path that a
+    /// contract operation also serves makes the server fail to start; see
+    /// [`InternalRoutes`].
+    #[must_use]
+    pub fn internal_route<F, Fut>(
+        mut self,
+        method: Method,
+        path: impl Into<String>,
+        handler: F,
+    ) -> Self
+    where
+        F: Fn(Request<Bytes>) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = HttpResponse> + Send + 'static,
+    {
+        let handler: crate::internal::InternalHandler =
+            Arc::new(move |request| Box::pin(handler(request)));
+        self.internal_routes
+            .push((method, path.into(), InternalEndpoint::Custom(handler)));
+        self
+    }
+
+    /// Serves Prometheus metrics, health and readiness at the given paths.
+    ///
+    /// The health and readiness endpoints replace the built-in `/health`
+    /// and `/ready`. Like every internal route they skip authorization,
+    /// validation and rate limiting. Metrics are rendered from the recorder
+    /// installed by `archimedes_telemetry::init_metrics`; see
+    /// [`observability`](Self::observability) to render them from a
+    /// registry or serve them on their own listener.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use archimedes_server::Server;
+    ///
+    /// let server = Server::builder()
+    ///     .with_observability_endpoints("/metrics", "/healthz", "/readyz")
+    ///     .build();
+    /// assert!(server.internal_routes().contains(&http::Method::GET, "/healthz"));
+    /// ```
+    #[must_use]
+    pub fn with_observability_endpoints(
+        self,
+        metrics_path: impl Into<String>,
+        health_path: impl Into<String>,
+        ready_path: impl Into<String>,
+    ) -> Self {
+        self.observability(ObservabilityEndpoints::new(
+            metrics_path,
+            health_path,
+            ready_path,
+        ))
+    }
+
+    /// Serves the given observability endpoints.
+    ///
+    /// See [`with_observability_endpoints`](Self::with_observability_endpoints).
+    #[must_use]
+    pub fn observability(mut self, endpoints: ObservabilityEndpoints) -> Self {
+        self.observability = Some(endpoints);
+        self
+    }
+
+    /// Adds an app served on this listener.
+    ///
+    /// Call once per app. Requests are dispatched to the app selected by
+    /// their host and path before any middleware runs, and each app runs
+    /// its own pipeline; see [`apps`](crate::apps). Once apps are added,
+    /// the routes, handlers and pipeline set on the builder itself only
+    /// serve internal endpoints.
+    #[must_use]
+    pub fn app(mut self, app: AppSpec) -> Self {
+        self.apps.push(app);
+        self
+    }
+
+    /// Names the app serving requests that select no other app, such as
+    /// requests without a `Host` header.
+    ///
+    /// Without a default app such requests are rejected with
+    /// `421 Misdirected Request`.
+    #[must_use]
+    pub fn default_app(mut self, name: impl Into<String>) -> Self {
+        self.default_app = Some(name.into());
+        self
+    }
+
+    /// Registers a hook to run on shutdown, before the server exits.
+    ///
+    /// Hooks run in registration order once in-flight connections have
+    /// drained (or the shutdown timeout has elapsed). They run on a blocking
+    /// thread, so they may block, e.g. to flush telemetry:
+    ///
+    /// ```rust,ignore
+    /// let flusher = telemetry_guard.flusher();
+    /// let server = Server::builder()
+    ///     .before_exit(move || {
+    ///         let _ = flusher.flush_with_timeout(Duration::from_secs(5));
+    ///     })
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn before_exit<F>(mut self, hook: F) -> Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.exit_hooks.push(Arc::new(hook));
+        self
+    }
+
+    /// Adds a hook run when shutdown begins, to close long-lived streams.
+    ///
+    /// SSE responses and WebSocket connections outlive the requests that
+    /// opened them, so graceful shutdown cannot wait for them to finish on
+    /// their own. Once the server stops accepting connections it runs every
+    /// drain hook concurrently, bounded by the
+    /// [drain timeout](Self::drain_timeout), then waits for connections as
+    /// usual. A hook notifies its streams and resolves once they closed:
+    ///
+    /// ```rust,ignore
+    /// let sse = SseRegistry::new();
+    /// let ws = ConnectionManager::default_manager();
+    /// let server = Server::builder()
+    ///     .on_drain({
+    ///         let sse = sse.clone();
+    ///         move || {
+    ///             let sse = sse.clone();
+    ///             async move { sse.drain(SseEvent::shutdown("server shutting down")).await }
+    ///         }
+    ///     })
+    ///     .on_drain(move || {
+    ///         let ws = Arc::clone(&ws);
+    ///         async move { ws.drain().await }
+    ///     })
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn on_drain<F, Fut>(mut self, hook: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.drain_hooks.push(Arc::new(move || Box::pin(hook())));
+        self
+    }
+
+    /// Sets how long drain hooks may run before shutdown proceeds.
+    ///
+    /// Streams still open when it elapses are closed with their
+    /// connections. Defaults to [`DEFAULT_DRAIN_TIMEOUT`].
+    #[must_use]
+    pub fn drain_timeout(mut self, timeout: Duration) -> Self {
+        self.drain_timeout = Some(timeout);
+        self
+    }
+
+    /// Warms the server up before it reports ready.
+    ///
+    /// Once the server accepts connections it runs the warm-up for the
+    /// routed operations; `/ready` answers `503` until it finished or its
+    /// budget ran out. A [disabled](Warmup::enabled) warm-up is ignored.
+    /// See [`warmup`](crate::warmup).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    ///
+    /// use archimedes_server::{Lifecycle, Server, Warmup};
+    ///
+    /// let lifecycle = Lifecycle::new().on_warmup(|| async { Ok(()) });
+    /// let server = Server::builder()
+    ///     .warmup(
+    ///         Warmup::new()
+    ///             .lifecycle(&lifecycle)
+    ///             .budget(Duration::from_secs(5)),
+    ///     )
+    ///     .build();
+    /// assert!(!server.readiness().is_ready());
+    /// ```
+    #[must_use]
+    pub fn warmup(mut self, warmup: Warmup) -> Self {
+        self.warmup = Some(warmup);
+        self
+    }
+
+    /// Builds the server, checking the apps for conflicts.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServerError::InvalidConfig`] if two apps overlap; see
+    /// [`apps`](crate::apps).
+    pub fn try_build(self) -> Result<Server, ServerError> {
+        let server = self.build();
+        server.check_apps()?;
+        Ok(server)
+    }
+
+    /// Builds the server with the configured settings.
+    ///
+    /// App conflicts are reported when the server starts; use
+    /// [`try_build`](Self::try_build) to check them here.
+    #[must_use]
+    pub fn build(self) -> Server {
+        let config = self.config_builder.build();
+        let service = self
+            .health_service
+            .unwrap_or_else(|| "archimedes".to_string());
+        let version = self
+            .health_version
+            .unwrap_or_else(|| env!("CARGO_PKG_VERSION").to_string());
+        let batch = self.batch.unwrap_or_default();
+        let mut internal =
+            InternalRoutes::with_builtins(self.diagnostics_endpoint, batch.is_enabled());
+        if let Some(endpoints) = &self.observability {
+            internal.remove(&Method::GET, "/health");
+            internal.remove(&Method::GET, "/ready");
+            internal.insert(
+                Method::GET,
+                endpoints.health_path(),
+                InternalEndpoint::Health,
+            );
+            internal.insert(Method::GET, endpoints.ready_path(), InternalEndpoint::Ready);
+            if endpoints.metrics_listener_addr().is_none() {
+                internal.insert(
+                    Method::GET,
+                    endpoints.metrics_path(),
+                    InternalEndpoint::Metrics(endpoints.metrics_source()),
+                );
+            }
+        }
+        for (method, path, endpoint) in self.internal_routes {
+            internal.insert(method, path, endpoint);
+        }
+        let warmup = self.warmup.filter(Warmup::is_enabled);
+        let warmup_report = Arc::new(OnceLock::new());
+        let readiness = if warmup.is_some() {
+            let report = Arc::clone(&warmup_report);
+            ReadinessCheck::new().add_check("warmup", move || report.get().is_some())
+        } else {
+            ReadinessCheck::new()
+        };
+
+        let mut server = Server {
+            config,
+            router: Router::new(),
+            handlers: self.handlers.unwrap_or_default(),
+            health: HealthCheck::new(service, version),
+            readiness,
+            request_timeout: self.request_timeout.unwrap_or(Duration::from_secs(30)),
+            operation_timeouts: self.operation_timeouts,
+            max_request_timeout: self.max_request_timeout,
+            cache_policies: self.cache_policies,
+            diagnostics: self
+                .contracts
+                .into_iter()
+                .fold(self.diagnostics.unwrap_or_default(), |d, contract| {
+                    d.with_contract_version(contract)
+                }),
+            diagnostics_endpoint: self.diagnostics_endpoint,
+            detailed_timing: self.detailed_timing,
+            pipeline: self.pipeline.map(Arc::new),
+            batch,
+            internal,
+            exit_hooks: self.exit_hooks,
+            drain_hooks: self.drain_hooks,
+            drain_timeout: self.drain_timeout.unwrap_or(DEFAULT_DRAIN_TIMEOUT),
+            base_path: self.base_path,
+            url_generator: OnceLock::new(),
+            connections: ConnectionTracker::new(),
+            apps: Vec::new(),
+            default_app: self.default_app,
+            app_name: None,
+            container: None,
+            observability: self.observability,
+            warmup,
+            warmup_report,
+        };
+        server.apps = self
+            .apps
+            .into_iter()
+            .map(|spec| server.mount(spec))
+            .collect();
+        server
+    }
+}
+
+/// Server error types.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServerError {
+    /// Failed to bind to the configured address.
+    BindError(String),
+
+    /// I/O error during server operation.
+    IoError(String),
+
+    /// The server configuration is invalid.
+    InvalidConfig(String),
+}
+
+impl std::fmt::Display for ServerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BindError(msg) => write!(f, "Bind error: {}", msg),
+            Self::IoError(msg) => write!(f, "I/O error: {}", msg),
+            Self::InvalidConfig(msg) => write!(f, "Invalid configuration: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ServerError {}
+
+impl From<ServerError> for StartupError {
+    fn from(err: ServerError) -> Self {
+        match err {
+            ServerError::BindError(msg) | ServerError::IoError(msg) => Self::bind(msg),
+            ServerError::InvalidConfig(msg) => Self::config_invalid(msg),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_server_new() {
+        let config = ServerConfig::builder().http_addr("127.0.0.1:8080").build();
+
+        let server = Server::new(config);
+        assert_eq!(server.config().http_addr(), "127.0.0.1:8080");
+    }
+
+    #[test]
+    fn test_server_builder() {
+        let server = Server::builder()
+            .http_addr("0.0.0.0:9090")
+            .shutdown_timeout(Duration::from_secs(60))
+            .build();
+
+        assert_eq!(server.config().http_addr(), "0.0.0.0:9090");
+        assert_eq!(server.config().shutdown_timeout(), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_server_builder_service_name() {
+        let server = Server::builder()
+            .service_name("my-service")
+            .service_version("2.0.0")
+            .build();
+
+        assert_eq!(server.health().service(), "my-service");
+        assert_eq!(server.health().version(), "2.0.0");
+    }
+
+    #[test]
+    fn test_server_router_access() {
+        let mut server = Server::builder().build();
+
+        server
+            .router_mut()
+            .add_route(Method::GET, "/test", "testOp");
+        assert!(server.router().has_operation("testOp"));
+    }
+
+    #[test]
+    fn test_server_health_endpoint() {
+        let server = Arc::new(Server::builder().build());
+        let response = server.handle_health();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn test_server_ready_endpoint() {
+        let server = Arc::new(Server::builder().build());
+        let response = server.handle_ready();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn test_server_ready_not_ready() {
+        let server = Arc::new(Server::builder().build());
+        server.readiness().set_ready(false);
+
+        let response = server.handle_ready();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[test]
+    fn test_server_route_not_found() {
+        let server = Arc::new(Server::builder().build());
+        let response = server.handle_not_found("/nonexistent");
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_server_route_matched_no_handler() {
+        let mut server = Server::builder().build();
+        server
+            .router_mut()
+            .add_route(Method::GET, "/users/{id}", "getUser");
+
+        let server = Arc::new(server);
+        let response = server
+            .route_request(&Method::GET, "/users/123", Bytes::new())
+            .await;
+
+        // Without a handler registered, should return NOT_IMPLEMENTED
+        assert_eq!(response.status(), StatusCode::NOT_IMPLEMENTED);
+    }
+
+    #[test]
+    fn test_server_diagnostics_defaults() {
+        let mut registry = HandlerRegistry::new();
+        registry.register_no_body("healthCheck", health_handler);
+
+        let mut server = Server::builder()
+            .http_addr("127.0.0.1:8080")
+            .service_name("diag-service")
+            .service_version("1.0.0")
+            .handlers(registry)
+            .build();
+        server
+            .router_mut()
+            .add_route(Method::GET, "/status", "healthCheck");
+        server
+            .router_mut()
+            .add_route(Method::GET, "/users", "listUsers");
+
+        let diagnostics = server.diagnostics();
+        assert_eq!(diagnostics.service, "diag-service");
+        assert_eq!(diagnostics.version, "1.0.0");
+        assert_eq!(diagnostics.listeners[0].name, "http");
+        assert_eq!(diagnostics.listeners[0].address, "127.0.0.1:8080");
+        assert_eq!(diagnostics.config["http_addr"], "127.0.0.1:8080");
+        assert_eq!(diagnostics.handlers.handled, 1);
+        assert_eq!(diagnostics.handlers.missing, vec!["listUsers"]);
+    }
+
+    #[test]
+    fn test_server_diagnostics_uses_contract_operations() {
+        let mut registry = HandlerRegistry::new();
+        registry.register_no_body("healthCheck", health_handler);
+
+        let server = Server::builder()
+            .handlers(registry)
+            .diagnostics(Diagnostics::default().with_contract(ContractInfo::new(
+                "svc",
+                "1.0.0",
+                ["getUser"],
+            )))
+            .build();
+
+        let diagnostics = server.diagnostics();
+        assert_eq!(diagnostics.handlers.missing, vec!["getUser"]);
+        assert_eq!(diagnostics.handlers.unknown, vec!["healthCheck"]);
+    }
+
+    #[test]
+    fn test_server_diagnostics_multiple_contract_versions() {
+        let mut registry = HandlerRegistry::new();
+        registry.register_no_body("getUser", health_handler);
+
+        let server = Server::builder()
+            .handlers(registry)
+            .contract(ContractInfo::new("svc", "v1", ["getUser"]))
+            .contract(ContractInfo::new("svc", "v2", ["getUser", "getUserV2"]))
+            .build();
+
+        let diagnostics = server.diagnostics();
+        assert_eq!(diagnostics.contract.unwrap().version, "v1");
+        assert_eq!(diagnostics.contract_versions.len(), 2);
+        assert_eq!(diagnostics.handlers.operations, 2);
+        assert_eq!(diagnostics.handlers.missing, vec!["getUserV2"]);
+    }
+
+    #[test]
+    fn test_server_diagnostics_endpoint() {
+        let server = Server::builder().diagnostics_endpoint(true).build();
+        assert!(server.diagnostics_endpoint_enabled());
+
+        let response = server.handle_diagnostics();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!Server::builder().build().diagnostics_endpoint_enabled());
+    }
+
+    #[test]
+    fn test_server_error_display() {
+        let bind_err = ServerError::BindError("Address in use".to_string());
+        assert!(bind_err.to_string().contains("Bind error"));
+
+        let io_err = ServerError::IoError("Connection reset".to_string());
+        assert!(io_err.to_string().contains("I/O error"));
+
+        let config_err = ServerError::InvalidConfig("bad window".to_string());
+        assert!(config_err.to_string().contains("Invalid configuration"));
+    }
+
+    #[tokio::test]
+    async fn test_server_run_rejects_invalid_connection_settings() {
+        let server = Server::builder()
+            .http_addr("127.0.0.1:0")
+            .http2_initial_stream_window_size(Some(u32::MAX))
+            .build();
+
+        let result = server.run_with_shutdown(ShutdownSignal::new()).await;
+        match result {
+            Err(ServerError::InvalidConfig(msg)) => {
+                assert!(msg.contains("http2_initial_stream_window_size"));
+            }
+            other => panic!("Expected InvalidConfig, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_max_requests_per_connection_closes_connection() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let server = Server::builder()
+            .max_requests_per_connection(Some(2))
+            .shutdown_timeout(Duration::from_millis(100))
+            .build();
+        let connections = server.connections();
+        assert_eq!(server.open_connections(), 0);
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let shutdown = ShutdownSignal::new();
+        let running = tokio::spawn(server.run_with_listener(listener, shutdown.clone()));
+
+        // Pipeline two requests; the server must answer both and then close
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let request = "GET /health HTTP/1.1\r\nHost: localhost\r\n\r\n";
+        stream
+            .write_all(format!("{request}{request}").as_bytes())
+            .await
+            .unwrap();
+
+        let mut raw = Vec::new();
+        tokio::time::timeout(Duration::from_secs(5), stream.read_to_end(&mut raw))
+            .await
+            .expect("server should close the connection")
+            .unwrap();
+        let raw = String::from_utf8(raw).unwrap().to_ascii_lowercase();
+
+        let responses: Vec<&str> = raw.split("http/1.1 ").filter(|r| !r.is_empty()).collect();
+        assert_eq!(responses.len(), 2);
+        assert!(responses[0].starts_with("200"));
+        assert!(!responses[0].contains("connection: close"));
+        assert!(responses[1].starts_with("200"));
+        assert!(responses[1].contains("connection: close"));
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while connections.active_connections() > 0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("closed connection should no longer be counted");
+
+        shutdown.trigger();
+        running.await.unwrap().unwrap();
+    }
+
+    /// Records the telemetry collected for each request.
+    struct StreamTelemetryRecorder(
+        Arc<std::sync::Mutex<Vec<archimedes_middleware::stages::TelemetryData>>>,
+    );
+
+    impl archimedes_middleware::Middleware for StreamTelemetryRecorder {
+        fn name(&self) -> &'static str {
+            "stream_telemetry_recorder"
+        }
+
+        fn process<'a>(
+            &'a self,
+            ctx: &'a mut MiddlewareContext,
+            request: archimedes_middleware::Request,
+            next: archimedes_middleware::Next<'a>,
+        ) -> archimedes_middleware::BoxFuture<'a, archimedes_middleware::Response> {
+            Box::pin(async move {
+                let response = next.run(ctx, request).await;
+                if let Some(data) =
+                    ctx.get_extension::<archimedes_middleware::stages::TelemetryData>()
+                {
+                    self.0.lock().unwrap().push(data.clone());
+                }
+                response
+            })
+        }
+    }
+
+    /// Starts a server whose `/export` stream fails after the first row.
+    async fn failing_export_server() -> (
+        SocketAddr,
+        ShutdownSignal,
+        Arc<std::sync::Mutex<Vec<archimedes_middleware::stages::TelemetryData>>>,
+    ) {
+        use archimedes_extract::StreamingResponse;
+
+        let mut registry = HandlerRegistry::new();
+        registry.register_streaming("exportRows", |_ctx, _req: serde_json::Value| async move {
+            let (writer, response) = StreamingResponse::new()
+                .content_type("application/x-ndjson")
+                .channel(4);
+            tokio::spawn(async move {
+                if writer.send("{\"row\":1}\n").await.is_ok() {
+                    writer.fail("database connection lost");
+                }
+            });
+            Ok(response)
+        });
+
+        let recorded = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let pipeline = Pipeline::builder()
+            .add_pre_handler_stage(StreamTelemetryRecorder(Arc::clone(&recorded)))
+            .add_post_handler_stage(archimedes_middleware::TelemetryMiddleware::new("test"))
+            .build();
+
+        let mut server = Server::builder()
+            .handlers(registry)
+            .pipeline(pipeline)
+            .shutdown_timeout(Duration::from_millis(100))
+            .build();
+        server
+            .router_mut()
+            .add_route(Method::GET, "/export", "exportRows");
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let shutdown = ShutdownSignal::new();
+        tokio::spawn(server.run_with_listener(listener, shutdown.clone()));
+
+        (addr, shutdown, recorded)
+    }
+
+    #[tokio::test]
+    async fn test_streaming_failure_sends_http1_trailers() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let (addr, shutdown, recorded) = failing_export_server().await;
+
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(
+                b"GET /export HTTP/1.1\r\nHost: localhost\r\nTE: trailers\r\nConnection: close\r\n\r\n",
+            )
+            .await
+            .unwrap();
+
+        let mut raw = Vec::new();
+        tokio::time::timeout(Duration::from_secs(5), stream.read_to_end(&mut raw))
+            .await
+            .expect("server should finish the stream")
+            .unwrap();
+        let raw = String::from_utf8(raw).unwrap().to_ascii_lowercase();
+        let (head, body) = raw.split_once("\r\n\r\n").unwrap();
+
+        assert!(head.starts_with("http/1.1 200"));
+        assert!(head.contains("transfer-encoding: chunked"));
+        assert!(head.contains("trailer: x-stream-status, x-error-message"));
+        assert!(body.contains("{\"row\":1}"));
+
+        // Trailers follow the last (zero-length) chunk
+        let (_, trailers) = body.split_once("\r\n0\r\n").unwrap();
+        assert!(trailers.contains("x-stream-status: error"));
+        assert!(trailers.contains("x-error-message: database connection lost"));
+
+        let recorded = recorded.lock().unwrap();
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(recorded[0].status_code, 200);
+        assert_eq!(recorded[0].effective_status_code(), 500);
+
+        shutdown.trigger();
+    }
+
+    #[tokio::test]
+    async fn test_streaming_failure_sends_http2_trailers() {
+        let (addr, shutdown, recorded) = failing_export_server().await;
+
+        let stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let (mut sender, conn) =
+            hyper::client::conn::http2::handshake(TokioExecutor::new(), TokioIo::new(stream))
+                .await
+                .unwrap();
+        tokio::spawn(conn);
+
+        let request = Request::get(format!("http://{addr}/export"))
+            .body(Full::new(Bytes::new()))
+            .unwrap();
+        let response = sender.send_request(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let collected =
+            tokio::time::timeout(Duration::from_secs(5), response.into_body().collect())
+                .await
+                .expect("server should finish the stream")
+                .unwrap();
+        let trailers = collected.trailers().cloned().unwrap();
+        assert_eq!(collected.to_bytes(), "{\"row\":1}\n");
+        assert_eq!(trailers["x-stream-status"], "error");
+        assert_eq!(trailers["x-error-message"], "database connection lost");
+
+        let recorded = recorded.lock().unwrap();
+        assert_eq!(recorded[0].effective_status_code(), 500);
+
+        shutdown.trigger();
+    }
+
+    /// Reports how many lines an ingest handler read when it stops.
+    struct ReportLinesRead {
+        lines: usize,
+        tx: tokio::sync::mpsc::UnboundedSender<usize>,
+    }
+
+    impl Drop for ReportLinesRead {
+        fn drop(&mut self) {
+            let _ = self.tx.send(self.lines);
+        }
+    }
+
+    /// Starts a server whose `/events` handler ingests an NDJSON body,
+    /// behind a pipeline that rejects every request body it validates.
+    async fn ingest_server() -> (
+        SocketAddr,
+        ShutdownSignal,
+        tokio::sync::mpsc::UnboundedReceiver<usize>,
+    ) {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut registry = HandlerRegistry::new();
+        registry.register_body_stream("ingestEvents", move |_ctx, body: BodyStream| {
+            let mut report = ReportLinesRead {
+                lines: 0,
+                tx: tx.clone(),
+            };
+            async move {
+                let mut events = body.ndjson::<serde_json::Value>();
+                let (mut accepted, mut rejected) = (0, Vec::new());
+                while let Some(event) = events.next_item().await {
+                    report.lines = events.lines_read();
+                    match event {
+                        Ok(_) => accepted += 1,
+                        Err(e) if e.is_item() => rejected.push(e.line()),
+                        Err(e) => return Err(crate::handler::HandlerError::from(e)),
+                    }
+                }
+                Ok(archimedes_extract::Json(serde_json::json!({
+                    "accepted": accepted,
+                    "rejected": rejected,
+                })))
+            }
+        });
+
+        let pipeline = Pipeline::builder()
+            .add_pre_handler_stage(
+                archimedes_middleware::stages::ValidationMiddleware::reject_all(),
+            )
+            .build();
+        let mut server = Server::builder()
+            .handlers(registry)
+            .pipeline(pipeline)
+            .shutdown_timeout(Duration::from_millis(100))
+            .build();
+        server
+            .router_mut()
+            .add_route(Method::POST, "/events", "ingestEvents");
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let shutdown = ShutdownSignal::new();
+        tokio::spawn(server.run_with_listener(listener, shutdown.clone()));
+
+        (addr, shutdown, rx)
+    }
+
+    /// Writes the head of a chunked `POST /events` request.
+    async fn start_ingest(addr: SocketAddr) -> tokio::net::TcpStream {
+        use tokio::io::AsyncWriteExt;
+
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(
+                b"POST /events HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/x-ndjson\r\n\
+                  Transfer-Encoding: chunked\r\nConnection: close\r\n\r\n",
+            )
+            .await
+            .unwrap();
+        stream
+    }
+
+    /// Writes one chunk of a chunked request body.
+    async fn write_chunk(stream: &mut tokio::net::TcpStream, data: &str) {
+        use tokio::io::AsyncWriteExt;
+
+        stream
+            .write_all(format!("{:x}\r\n{data}\r\n", data.len()).as_bytes())
+            .await
+            .unwrap();
+    }
+
+    /// Finishes a chunked request and reads the response body.
+    async fn finish_ingest(mut stream: tokio::net::TcpStream) -> (String, serde_json::Value) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        stream.write_all(b"0\r\n\r\n").await.unwrap();
+        let mut raw = Vec::new();
+        tokio::time::timeout(Duration::from_secs(5), stream.read_to_end(&mut raw))
+            .await
+            .expect("server should answer")
+            .unwrap();
+        let raw = String::from_utf8(raw).unwrap();
+        let (head, body) = raw.split_once("\r\n\r\n").unwrap();
+        (head.to_string(), serde_json::from_str(body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_body_stream_ingests_chunked_ndjson() {
+        let (addr, shutdown, mut lines_read) = ingest_server().await;
+
+        let mut stream = start_ingest(addr).await;
+        for chunk in 0..20 {
+            let lines: String = (0..500)
+                .map(|i| format!("{{\"seq\":{}}}\n", chunk * 500 + i))
+                .collect();
+            write_chunk(&mut stream, &lines).await;
+        }
+        let (head, body) = finish_ingest(stream).await;
+
+        // The rejecting validation stage is skipped for the operation
+        assert!(head.starts_with("HTTP/1.1 200"), "{head}");
+        assert_eq!(body["accepted"], 10_000);
+        assert_eq!(body["rejected"], serde_json::json!([]));
+        assert_eq!(lines_read.recv().await, Some(10_000));
+
+        shutdown.trigger();
+    }
+
+    #[tokio::test]
+    async fn test_body_stream_reports_malformed_lines() {
+        let (addr, shutdown, _lines_read) = ingest_server().await;
+
+        let mut stream = start_ingest(addr).await;
+        write_chunk(&mut stream, "{\"seq\":1}\n{\"seq\":").await;
+        write_chunk(&mut stream, "2}\nnot json\n{\"seq\":3}").await;
+        let (head, body) = finish_ingest(stream).await;
+
+        assert!(head.starts_with("HTTP/1.1 200"), "{head}");
+        assert_eq!(body["accepted"], 3);
+        assert_eq!(body["rejected"], serde_json::json!([3]));
+
+        shutdown.trigger();
+    }
+
+    #[tokio::test]
+    async fn test_body_stream_stops_when_client_disconnects() {
+        let (addr, shutdown, mut lines_read) = ingest_server().await;
+
+        let mut stream = start_ingest(addr).await;
+        write_chunk(&mut stream, "{\"seq\":1}\n{\"seq\":2}\n{\"seq\":3}\n").await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        drop(stream);
+
+        let lines = tokio::time::timeout(Duration::from_secs(5), lines_read.recv())
+            .await
+            .expect("handler should stop when the client disconnects");
+        assert_eq!(lines, Some(3));
+
+        shutdown.trigger();
+    }
+
+    #[test]
+    fn test_diagnostics_flags_unvalidated_bodies() {
+        let mut registry = HandlerRegistry::new();
+        registry.register_body_stream("ingestEvents", |_ctx, _body: BodyStream| async {
+            Ok::<_, crate::handler::HandlerError>(StatusCode::ACCEPTED)
+        });
+        let mut server = Server::builder().handlers(registry).build();
+        server
+            .router_mut()
+            .add_route(Method::POST, "/events", "ingestEvents");
+
+        let coverage = server.diagnostics().handlers;
+        assert_eq!(coverage.handled, 1);
+        assert_eq!(coverage.unvalidated_bodies, vec!["ingestEvents"]);
+    }
+
+    /// Starts a server whose `/check` handler reports a gRPC status in a
+    /// trailer.
+    async fn trailer_status_server() -> (
+        SocketAddr,
+        ShutdownSignal,
+        Arc<std::sync::Mutex<Vec<archimedes_middleware::stages::TelemetryData>>>,
+    ) {
+        use archimedes_core::response::GRPC_STATUS_TRAILER;
+        use archimedes_core::Trailers;
+
+        let mut registry = HandlerRegistry::new();
+        registry.register_response_no_body("check", |_ctx| async {
+            let trailers =
+                Trailers::new().with(GRPC_STATUS_TRAILER, HeaderValue::from_static("14"));
+            Ok::<_, crate::handler::HandlerError>(("checked", trailers))
+        });
+
+        let recorded = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let pipeline = Pipeline::builder()
+            .add_pre_handler_stage(StreamTelemetryRecorder(Arc::clone(&recorded)))
+            .add_post_handler_stage(archimedes_middleware::TelemetryMiddleware::new("test"))
+            .build();
+
+        let mut server = Server::builder()
+            .handlers(registry)
+            .pipeline(pipeline)
+            .shutdown_timeout(Duration::from_millis(100))
+            .build();
+        server
+            .router_mut()
+            .add_route(Method::GET, "/check", "check");
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let shutdown = ShutdownSignal::new();
+        tokio::spawn(server.run_with_listener(listener, shutdown.clone()));
+
+        (addr, shutdown, recorded)
+    }
+
+    #[tokio::test]
+    async fn test_handler_trailers_sent_over_http2() {
+        let (addr, shutdown, recorded) = trailer_status_server().await;
+
+        let stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let (mut sender, conn) =
+            hyper::client::conn::http2::handshake(TokioExecutor::new(), TokioIo::new(stream))
+                .await
+                .unwrap();
+        tokio::spawn(conn);
+
+        let request = Request::get(format!("http://{addr}/check"))
+            .body(Full::new(Bytes::new()))
+            .unwrap();
+        let response = sender.send_request(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get("grpc-status").is_none());
+
+        let collected = response.into_body().collect().await.unwrap();
+        let trailers = collected.trailers().cloned().unwrap();
+        assert_eq!(collected.to_bytes(), "checked");
+        assert_eq!(trailers["grpc-status"], "14");
+
+        let recorded = recorded.lock().unwrap();
+        assert_eq!(recorded[0].status_code, 200);
+        assert_eq!(recorded[0].effective_status_code(), 500);
+
+        shutdown.trigger();
+    }
+
+    #[tokio::test]
+    async fn test_handler_trailers_become_headers_without_te() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let (addr, shutdown, _) = trailer_status_server().await;
+
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"GET /check HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+
+        let mut raw = Vec::new();
+        stream.read_to_end(&mut raw).await.unwrap();
+        let raw = String::from_utf8(raw).unwrap().to_ascii_lowercase();
+        let (head, body) = raw.split_once("\r\n\r\n").unwrap();
+
+        assert!(head.starts_with("http/1.1 200"));
+        assert!(head.contains("grpc-status: 14"));
+        assert!(!head.contains("trailer:"));
+        assert_eq!(body, "checked");
+
+        shutdown.trigger();
+    }
+
+    #[tokio::test]
+    async fn test_server_run_invalid_address() {
+        let server = Server::builder().http_addr("not-a-valid-address").build();
+
+        let result = server.run_with_shutdown(ShutdownSignal::new()).await;
+        assert!(result.is_err());
+
+        if let Err(ServerError::BindError(msg)) = result {
+            assert!(msg.contains("Invalid address"));
+        } else {
+            panic!("Expected BindError");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_server_run_returns_startup_error() {
+        use archimedes_core::StartupCategory;
+
+        let err = Server::builder()
+            .http_addr("not-a-valid-address")
+            .build()
+            .run()
+            .await
+            .unwrap_err();
+        assert_eq!(err.category(), StartupCategory::Bind);
+        assert_eq!(err.exit_code(), 13);
+        assert!(!err.is_transient());
+
+        let err = StartupError::from(ServerError::InvalidConfig("bad window".to_string()));
+        assert_eq!(err.category(), StartupCategory::ConfigInvalid);
+        assert_eq!(err.detail(), "bad window");
+    }
+
+    #[tokio::test]
+    async fn test_server_run_and_shutdown() {
+        let server = Server::builder()
+            .http_addr("127.0.0.1:0") // Use port 0 for random available port
+            .shutdown_timeout(Duration::from_millis(100))
+            .build();
+
+        let shutdown = ShutdownSignal::new();
+        let shutdown_trigger = shutdown.clone();
+
+        // Trigger shutdown immediately
+        shutdown_trigger.trigger();
+
+        // Server should exit quickly
+        let result =
+            tokio::time::timeout(Duration::from_secs(5), server.run_with_shutdown(shutdown)).await;
+
+        assert!(result.is_ok());
+        assert!(result.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_server_runs_exit_hooks_on_shutdown() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let first = Arc::clone(&calls);
+        let second = Arc::clone(&calls);
+
+        let server = Server::builder()
+            .http_addr("127.0.0.1:0")
+            .shutdown_timeout(Duration::from_millis(100))
+            .before_exit(move || {
+                first.fetch_add(1, Ordering::SeqCst);
+            })
+            .before_exit(move || {
+                second.fetch_add(1, Ordering::SeqCst);
+            })
+            .build();
+
+        let shutdown = ShutdownSignal::new();
+        shutdown.trigger();
+
+        let result =
+            tokio::time::timeout(Duration::from_secs(5), server.run_with_shutdown(shutdown)).await;
+
+        assert!(result.unwrap().is_ok());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_server_runs_drain_hooks_on_shutdown() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let drained = Arc::new(AtomicUsize::new(0));
+        let hook_drained = Arc::clone(&drained);
+        let (closed_tx, closed_rx) = tokio::sync::watch::channel(false);
+
+        let server = Server::builder()
+            .http_addr("127.0.0.1:0")
+            .shutdown_timeout(Duration::from_millis(100))
+            .on_drain(move || {
+                let drained = Arc::clone(&hook_drained);
+                let mut closed = closed_rx.clone();
+                async move {
+                    // Resolves once the streams report closed
+                    let _ = closed.wait_for(|closed| *closed).await;
+                    drained.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+            // Never resolves; the drain timeout bounds it
+            .on_drain(std::future::pending::<()>)
+            .drain_timeout(Duration::from_millis(200))
+            .build();
+
+        let shutdown = ShutdownSignal::new();
+        shutdown.trigger();
+        let running = tokio::spawn(server.run_with_shutdown(shutdown));
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(drained.load(Ordering::SeqCst), 0);
+        closed_tx.send(true).unwrap();
+
+        let result = tokio::time::timeout(Duration::from_secs(5), running).await;
+        assert!(result.unwrap().unwrap().is_ok());
+        assert_eq!(drained.load(Ordering::SeqCst), 1);
+    }
+
+    // Integration tests for handler invocation
+
+    #[derive(serde::Deserialize)]
+    struct EchoRequest {
+        message: String,
+    }
+
+    #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+    struct EchoResponse {
+        echo: String,
+    }
+
+    async fn echo_handler(
+        _ctx: archimedes_core::RequestContext,
+        req: EchoRequest,
+    ) -> Result<EchoResponse, crate::handler::HandlerError> {
+        Ok(EchoResponse {
+            echo: format!("Echo: {}", req.message),
+        })
+    }
+
+    #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+    struct HealthResponse {
+        status: String,
+    }
+
+    async fn health_handler(
+        _ctx: archimedes_core::RequestContext,
+    ) -> Result<HealthResponse, crate::handler::HandlerError> {
+        Ok(HealthResponse {
+            status: "ok".to_string(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_handler_invocation() {
+        use crate::handler::HandlerRegistry;
+
+        let mut registry = HandlerRegistry::new();
+        registry.register("echo", echo_handler);
+
+        let mut server = Server::builder().handlers(registry).build();
+        server.router_mut().add_route(Method::POST, "/echo", "echo");
+
+        let server = Arc::new(server);
+        let body = Bytes::from(r#"{"message":"Hello"}"#);
+        let response = server.route_request(&Method::POST, "/echo", body).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Extract body and verify
+        let body_bytes = response.into_body();
+        let collected = http_body_util::BodyExt::collect(body_bytes).await.unwrap();
+        let resp: EchoResponse = serde_json::from_slice(&collected.to_bytes()).unwrap();
+        assert_eq!(resp.echo, "Echo: Hello");
+    }
+
+    #[tokio::test]
+    async fn test_handler_no_body_invocation() {
+        use crate::handler::HandlerRegistry;
+
+        let mut registry = HandlerRegistry::new();
+        registry.register_no_body("healthCheck", health_handler);
+
+        let mut server = Server::builder().handlers(registry).build();
+        server
+            .router_mut()
+            .add_route(Method::GET, "/status", "healthCheck");
+
+        let server = Arc::new(server);
+        let response = server
+            .route_request(&Method::GET, "/status", Bytes::new())
+            .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body_bytes = response.into_body();
+        let collected = http_body_util::BodyExt::collect(body_bytes).await.unwrap();
+        let resp: HealthResponse = serde_json::from_slice(&collected.to_bytes()).unwrap();
+        assert_eq!(resp.status, "ok");
+    }
+
+    #[tokio::test]
+    async fn test_handler_response_status_and_headers() {
+        use crate::handler::{HandlerError, HandlerRegistry};
+        use http::{header, HeaderMap, HeaderValue};
+
+        let mut registry = HandlerRegistry::new();
+        registry.register_response("echo", |_ctx, req: EchoRequest| async move {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::LOCATION, HeaderValue::from_static("/echo/1"));
+            let echo = archimedes_extract::Json(EchoResponse {
+                echo: format!("Echo: {}", req.message),
+            });
+            Ok::<_, HandlerError>((StatusCode::CREATED, headers, echo))
+        });
+
+        let mut server = Server::builder().handlers(registry).build();
+        server.router_mut().add_route(Method::POST, "/echo", "echo");
+
+        let body = Bytes::from(r#"{"message":"Hello"}"#);
+        let response = server.route_request(&Method::POST, "/echo", body).await;
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(response.headers()[header::LOCATION], "/echo/1");
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+        let collected = http_body_util::BodyExt::collect(response.into_body())
+            .await
+            .unwrap();
+        let resp: EchoResponse = serde_json::from_slice(&collected.to_bytes()).unwrap();
+        assert_eq!(resp.echo, "Echo: Hello");
+    }
+
+    fn timed_echo_server(detailed_timing: bool) -> Arc<Server> {
+        use crate::handler::HandlerRegistry;
+
+        let mut registry = HandlerRegistry::new();
+        registry.register("echo", echo_handler);
+
+        let mut server = Server::builder()
+            .handlers(registry)
+            .detailed_timing(detailed_timing)
+            .build();
+        server.router_mut().add_route(Method::POST, "/echo", "echo");
+        Arc::new(server)
     }
 
-    /// Sets the service name for health checks.
-    #[must_use]
-    pub fn service_name(mut self, name: impl Into<String>) -> Self {
-        self.health_service = Some(name.into());
-        self
+    #[test]
+    fn test_detailed_timing_records_phases() {
+        use metrics_exporter_prometheus::PrometheusBuilder;
+
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+        let server = timed_echo_server(true);
+        assert!(server.detailed_timing_enabled());
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let response = metrics::with_local_recorder(&recorder, || {
+            runtime.block_on(server.dispatch_timed(
+                &Method::POST,
+                "/echo",
+                HeaderMap::new(),
+                Bytes::from(r#"{"message":"Hello"}"#),
+            ))
+        });
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let timings = response.extensions().get::<PhaseTimings>().unwrap();
+        assert_eq!(
+            timings.phases().map(|(phase, _)| phase).collect::<Vec<_>>(),
+            vec![
+                timing::DESERIALIZATION,
+                timing::HANDLER,
+                timing::SERIALIZATION
+            ]
+        );
+        assert_eq!(timings.request_bytes(), 19);
+        assert_eq!(
+            timings.response_bytes(),
+            r#"{"echo":"Echo: Hello"}"#.len() as u64
+        );
+        assert!(timings.handler_busy() <= timings.phase(timing::HANDLER).unwrap());
+
+        let rendered = handle.render();
+        assert!(rendered.contains(
+            r#"archimedes_request_phase_duration_seconds_count{operation="echo",phase="handler"} 1"#
+        ));
+        assert!(rendered.contains(r#"phase="serialization""#));
     }
 
-    /// Sets the service version for health checks.
-    #[must_use]
-    pub fn service_version(mut self, version: impl Into<String>) -> Self {
-        self.health_version = Some(version.into());
-        self
+    #[tokio::test]
+    async fn test_detailed_timing_off_by_default() {
+        let server = timed_echo_server(false);
+        assert!(!Server::builder().build().detailed_timing_enabled());
+
+        let response = server
+            .dispatch_with_timeout(
+                &Method::POST,
+                "/echo",
+                HeaderMap::new(),
+                Bytes::from(r#"{"message":"Hello"}"#),
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.extensions().get::<PhaseTimings>().is_none());
     }
 
-    /// Sets the request timeout.
-    ///
-    /// This timeout applies to both body collection and handler execution.
-    /// Default is 30 seconds.
-    ///
-    /// # Arguments
-    ///
-    /// * `timeout` - The request timeout duration
-    #[must_use]
-    pub fn request_timeout(mut self, timeout: Duration) -> Self {
-        self.request_timeout = Some(timeout);
-        self
+    #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+    struct LinkResponse {
+        next: String,
     }
 
-    /// Builds the server with the configured settings.
-    #[must_use]
-    pub fn build(self) -> Server {
-        let config = self.config_builder.build();
-        let service = self
-            .health_service
-            .unwrap_or_else(|| "archimedes".to_string());
-        let version = self
-            .health_version
-            .unwrap_or_else(|| env!("CARGO_PKG_VERSION").to_string());
+    async fn link_handler(
+        ctx: archimedes_core::RequestContext,
+    ) -> Result<LinkResponse, crate::handler::HandlerError> {
+        let next = ctx
+            .url_for("getUser", &[("userId", "42")])
+            .map_err(|e| crate::handler::HandlerError::Custom(Box::new(e)))?;
+        Ok(LinkResponse { next })
+    }
 
-        Server {
-            config,
-            router: Router::new(),
-            handlers: self.handlers.unwrap_or_default(),
-            health: HealthCheck::new(service, version),
-            readiness: ReadinessCheck::new(),
-            request_timeout: self.request_timeout.unwrap_or(Duration::from_secs(30)),
+    #[tokio::test]
+    async fn test_handler_url_for_uses_base_path() {
+        use crate::handler::HandlerRegistry;
+
+        let mut registry = HandlerRegistry::new();
+        registry.register_no_body("listUsers", link_handler);
+
+        let mut server = Server::builder()
+            .handlers(registry)
+            .base_path("/service")
+            .build();
+        server
+            .router_mut()
+            .add_route(Method::GET, "/users", "listUsers");
+        server
+            .router_mut()
+            .add_route(Method::GET, "/users/{userId}", "getUser");
+
+        let server = Arc::new(server);
+        let response = server
+            .route_request(&Method::GET, "/users", Bytes::new())
+            .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body_bytes = response.into_body();
+        let collected = http_body_util::BodyExt::collect(body_bytes).await.unwrap();
+        let resp: LinkResponse = serde_json::from_slice(&collected.to_bytes()).unwrap();
+        assert_eq!(resp.next, "/service/users/42");
+    }
+
+    #[derive(serde::Deserialize)]
+    struct UserPathRequest {
+        user_id: String,
+    }
+
+    #[derive(serde::Deserialize)]
+    struct RenamedPostRequest {
+        #[serde(rename = "userId")]
+        owner: String,
+        #[serde(rename = "PostID")]
+        post: String,
+    }
+
+    #[tokio::test]
+    async fn test_mixed_case_path_params_bind_to_handler_fields() {
+        use crate::handler::HandlerRegistry;
+
+        let mut registry = HandlerRegistry::new();
+        registry.register("getUser", |_ctx, req: UserPathRequest| async move {
+            Ok::<_, crate::handler::HandlerError>(EchoResponse { echo: req.user_id })
+        });
+        registry.register("getPost", |_ctx, req: RenamedPostRequest| async move {
+            Ok::<_, crate::handler::HandlerError>(EchoResponse {
+                echo: format!("{}/{}", req.owner, req.post),
+            })
+        });
+
+        let mut server = Server::builder().handlers(registry).build();
+        // Both routes share the parameter node under /users
+        server
+            .router_mut()
+            .add_route(Method::GET, "/users/{userId}", "getUser");
+        server
+            .router_mut()
+            .add_route(Method::GET, "/users/{UserId}/posts/{PostID}", "getPost");
+
+        let server = Arc::new(server);
+        for (path, expected) in [("/users/42", "42"), ("/users/42/posts/7", "42/7")] {
+            let response = server.route_request(&Method::GET, path, Bytes::new()).await;
+            assert_eq!(response.status(), StatusCode::OK, "{path}");
+
+            let collected = http_body_util::BodyExt::collect(response.into_body())
+                .await
+                .unwrap();
+            let resp: EchoResponse = serde_json::from_slice(&collected.to_bytes()).unwrap();
+            assert_eq!(resp.echo, expected);
         }
+
+        // getUser binds userId to user_id by style; getPost binds UserId to the
+        // field renamed "userId" by style too, while PostID matches exactly
+        let mismatches = server.param_name_mismatches();
+        assert_eq!(
+            mismatches,
+            vec![
+                crate::handler::ParamNameMismatch {
+                    operation_id: "getPost".to_string(),
+                    param: "UserId".to_string(),
+                    field: "userId",
+                },
+                crate::handler::ParamNameMismatch {
+                    operation_id: "getUser".to_string(),
+                    param: "userId".to_string(),
+                    field: "user_id",
+                },
+            ]
+        );
+        assert!(mismatches[1]
+            .to_string()
+            .contains("#[serde(rename = \"userId\")]"));
     }
-}
 
-/// Server error types.
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum ServerError {
-    /// Failed to bind to the configured address.
-    BindError(String),
+    #[tokio::test]
+    async fn test_handler_deserialization_error() {
+        use crate::handler::HandlerRegistry;
 
-    /// I/O error during server operation.
-    IoError(String),
-}
+        let mut registry = HandlerRegistry::new();
+        registry.register("echo", echo_handler);
 
-impl std::fmt::Display for ServerError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::BindError(msg) => write!(f, "Bind error: {}", msg),
-            Self::IoError(msg) => write!(f, "I/O error: {}", msg),
+        let mut server = Server::builder().handlers(registry).build();
+        server.router_mut().add_route(Method::POST, "/echo", "echo");
+
+        let server = Arc::new(server);
+        // Invalid JSON
+        let body = Bytes::from(r#"not valid json"#);
+        let response = server.route_request(&Method::POST, "/echo", body).await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_handler_typed_error_response() {
+        use crate::handler::{ErrorResponse, HandlerRegistry};
+
+        let mut registry = HandlerRegistry::new();
+        registry.register("echo", |_ctx, req: EchoRequest| async move {
+            if req.message == "taken" {
+                return Err(ErrorResponse::new(
+                    StatusCode::CONFLICT,
+                    "NAME_TAKEN",
+                    "Name is already taken",
+                ));
+            }
+            Ok(EchoResponse { echo: req.message })
+        });
+
+        let mut server = Server::builder().handlers(registry).build();
+        server.router_mut().add_route(Method::POST, "/echo", "echo");
+
+        let server = Arc::new(server);
+        let body = Bytes::from(r#"{"message":"taken"}"#);
+        let response = server.route_request(&Method::POST, "/echo", body).await;
+
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let collected = http_body_util::BodyExt::collect(response.into_body())
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&collected.to_bytes()).unwrap();
+        assert_eq!(body["error"]["code"], "NAME_TAKEN");
+        assert_eq!(body["error"]["message"], "Name is already taken");
+        assert_eq!(body["error"]["operation_id"], "echo");
+    }
+
+    #[tokio::test]
+    async fn test_handler_not_registered() {
+        use crate::handler::HandlerRegistry;
+
+        let registry = HandlerRegistry::new();
+
+        let mut server = Server::builder().handlers(registry).build();
+        server
+            .router_mut()
+            .add_route(Method::GET, "/missing", "missingOp");
+
+        let server = Arc::new(server);
+        let response = server
+            .route_request(&Method::GET, "/missing", Bytes::new())
+            .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_IMPLEMENTED);
+    }
+
+    // Batch endpoint tests
+
+    struct RequireAuthorization;
+
+    impl archimedes_middleware::Middleware for RequireAuthorization {
+        fn name(&self) -> &'static str {
+            "require_authorization"
+        }
+
+        fn process<'a>(
+            &'a self,
+            ctx: &'a mut MiddlewareContext,
+            request: archimedes_middleware::Request,
+            next: archimedes_middleware::Next<'a>,
+        ) -> archimedes_middleware::BoxFuture<'a, archimedes_middleware::Response> {
+            Box::pin(async move {
+                if request.headers().get("authorization").map(|v| v.as_bytes())
+                    == Some(b"Bearer denied".as_slice())
+                {
+                    use archimedes_middleware::ResponseExt;
+                    return archimedes_middleware::Response::json_error(
+                        StatusCode::FORBIDDEN,
+                        "FORBIDDEN",
+                        "Access denied",
+                    );
+                }
+                next.run(ctx, request).await
+            })
         }
     }
-}
 
-impl std::error::Error for ServerError {}
+    /// Records the telemetry of each request that reached the handler.
+    struct TelemetryRecorder(Arc<std::sync::Mutex<Vec<(String, bool)>>>);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    impl archimedes_middleware::Middleware for TelemetryRecorder {
+        fn name(&self) -> &'static str {
+            "telemetry_recorder"
+        }
 
-    #[test]
-    fn test_server_new() {
-        let config = ServerConfig::builder().http_addr("127.0.0.1:8080").build();
+        fn process<'a>(
+            &'a self,
+            ctx: &'a mut MiddlewareContext,
+            request: archimedes_middleware::Request,
+            next: archimedes_middleware::Next<'a>,
+        ) -> archimedes_middleware::BoxFuture<'a, archimedes_middleware::Response> {
+            Box::pin(async move {
+                let response = next.run(ctx, request).await;
+                if let Some(data) =
+                    ctx.get_extension::<archimedes_middleware::stages::TelemetryData>()
+                {
+                    self.0
+                        .lock()
+                        .unwrap()
+                        .push((data.operation_id.clone(), data.batched));
+                }
+                response
+            })
+        }
+    }
 
-        let server = Server::new(config);
-        assert_eq!(server.config().http_addr(), "127.0.0.1:8080");
+    fn batch_server(pipeline: Option<Pipeline>) -> Arc<Server> {
+        let mut registry = HandlerRegistry::new();
+        registry.register("echo", echo_handler);
+        registry.register_no_body("healthCheck", health_handler);
+
+        let mut builder = Server::builder().handlers(registry).batch(
+            BatchConfig::new()
+                .enabled(true)
+                .max_requests(5)
+                .concurrency(2),
+        );
+        if let Some(pipeline) = pipeline {
+            builder = builder.pipeline(pipeline);
+        }
+
+        let mut server = builder.build();
+        server.router_mut().add_route(Method::POST, "/echo", "echo");
+        server
+            .router_mut()
+            .add_route(Method::GET, "/status", "healthCheck");
+        Arc::new(server)
     }
 
-    #[test]
-    fn test_server_builder() {
-        let server = Server::builder()
-            .http_addr("0.0.0.0:9090")
-            .shutdown_timeout(Duration::from_secs(60))
-            .build();
+    async fn run_batch(server: &Arc<Server>, body: &str) -> (StatusCode, serde_json::Value) {
+        let response = server
+            .handle_batch(&HeaderMap::new(), &Bytes::from(body.to_string()))
+            .await;
+        let status = response.status();
+        let collected = http_body_util::BodyExt::collect(response.into_body())
+            .await
+            .unwrap();
+        (
+            status,
+            serde_json::from_slice(&collected.to_bytes()).unwrap(),
+        )
+    }
 
-        assert_eq!(server.config().http_addr(), "0.0.0.0:9090");
-        assert_eq!(server.config().shutdown_timeout(), Duration::from_secs(60));
+    #[tokio::test]
+    async fn test_batch_mixed_success_and_failure() {
+        let server = batch_server(None);
+        let (status, responses) = run_batch(
+            &server,
+            r#"[
+                {"id": "ok", "method": "POST", "path": "/echo", "body": {"message": "Hi"}},
+                {"id": "missing", "method": "GET", "path": "/nowhere"},
+                {"id": "invalid", "method": "POST", "path": "/echo", "body": {"wrong": 1}},
+                {"id": "status", "method": "get", "path": "/status"}
+            ]"#,
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        let responses = responses.as_array().unwrap();
+        assert_eq!(responses.len(), 4);
+        assert_eq!(responses[0]["id"], "ok");
+        assert_eq!(responses[0]["status"], 200);
+        assert_eq!(responses[0]["body"]["echo"], "Echo: Hi");
+        assert_eq!(responses[0]["headers"]["content-type"], "application/json");
+        assert_eq!(responses[1]["status"], 404);
+        assert_eq!(responses[2]["status"], 400);
+        assert_eq!(responses[3]["status"], 200);
+        assert_eq!(responses[3]["body"]["status"], "ok");
     }
 
-    #[test]
-    fn test_server_builder_service_name() {
-        let server = Server::builder()
-            .service_name("my-service")
-            .service_version("2.0.0")
+    #[tokio::test]
+    async fn test_batch_auth_denial_is_per_sub_request() {
+        let recorded = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let pipeline = Pipeline::builder()
+            .add_pre_handler_stage(TelemetryRecorder(Arc::clone(&recorded)))
+            .add_pre_handler_stage(RequireAuthorization)
+            .add_post_handler_stage(archimedes_middleware::TelemetryMiddleware::new("test"))
             .build();
+        let server = batch_server(Some(pipeline));
+
+        let (status, responses) = run_batch(
+            &server,
+            r#"[
+                {"id": "allowed", "method": "GET", "path": "/status"},
+                {"id": "denied", "method": "GET", "path": "/status", "headers": {"authorization": "Bearer denied"}}
+            ]"#,
+        )
+        .await;
 
-        assert_eq!(server.health().service(), "my-service");
-        assert_eq!(server.health().version(), "2.0.0");
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(responses[0]["status"], 200);
+        assert_eq!(responses[1]["status"], 403);
+        assert_eq!(responses[1]["body"]["error"]["code"], "FORBIDDEN");
+
+        let recorded = recorded.lock().unwrap();
+        assert_eq!(recorded.as_slice(), &[("healthCheck".to_string(), true)]);
     }
 
-    #[test]
-    fn test_server_router_access() {
-        let mut server = Server::builder().build();
+    #[tokio::test]
+    async fn test_batch_recursion_guard() {
+        let server = batch_server(None);
+        let (_, responses) = run_batch(
+            &server,
+            r#"[
+                {"id": "nested", "method": "POST", "path": "/-/batch", "body": []},
+                {"id": "status", "method": "GET", "path": "/status"}
+            ]"#,
+        )
+        .await;
+
+        assert_eq!(responses[0]["status"], 400);
+        assert_eq!(responses[0]["body"]["error"]["code"], "BATCH_RECURSION");
+        assert_eq!(responses[1]["status"], 200);
+    }
 
+    #[tokio::test]
+    async fn test_batch_rejects_oversized_batch() {
+        let server = batch_server(None);
+        let sub = r#"{"id": "s", "method": "GET", "path": "/status"}"#;
+        let body = format!("[{}]", vec![sub; 6].join(","));
+        let (status, response) = run_batch(&server, &body).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(response["error"]["code"], "BATCH_TOO_MANY_REQUESTS");
+        assert!(!Server::builder().build().batch_config().is_enabled());
+    }
+
+    // Internal route tests
+
+    fn internal_server() -> Arc<Server> {
+        let mut registry = HandlerRegistry::new();
+        registry.register_no_body("healthCheck", health_handler);
+
+        let pipeline = Pipeline::builder()
+            .add_pre_handler_stage(archimedes_middleware::AuthorizationMiddleware::deny_all())
+            .build();
+        let mut server = Server::builder()
+            .handlers(registry)
+            .pipeline(pipeline)
+            .internal_route(Method::GET, "/metrics", |_request| async {
+                Response::new(Full::new(Bytes::from_static(b"requests_total 1")))
+            })
+            .build();
         server
             .router_mut()
-            .add_route(Method::GET, "/test", "testOp");
-        assert!(server.router().has_operation("testOp"));
+            .add_route(Method::GET, "/status", "healthCheck");
+        Arc::new(server)
+    }
+
+    async fn call_internal(server: &Arc<Server>, method: Method, path: &str) -> HttpResponse {
+        let route_path = path.split('?').next().unwrap_or_default();
+        let route = server.internal.find(&method, route_path).unwrap().clone();
+        server
+            .dispatch_internal(
+                route.endpoint,
+                route.path,
+                method,
+                path,
+                HeaderMap::new(),
+                Bytes::new(),
+            )
+            .await
     }
 
-    #[test]
-    fn test_server_health_endpoint() {
-        let server = Arc::new(Server::builder().build());
-        let response = server.handle_health();
+    #[tokio::test]
+    async fn test_internal_route_bypasses_authorization() {
+        let server = internal_server();
+
+        let response = call_internal(&server, Method::GET, "/metrics").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body, Bytes::from_static(b"requests_total 1"));
 
+        let response = call_internal(&server, Method::GET, "/health").await;
         assert_eq!(response.status(), StatusCode::OK);
+
+        // Contract operations still go through authorization
+        let response = server
+            .dispatch(
+                Method::GET,
+                "/status",
+                HeaderMap::new(),
+                Bytes::new(),
+                false,
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
     }
 
-    #[test]
-    fn test_server_ready_endpoint() {
-        let server = Arc::new(Server::builder().build());
-        let response = server.handle_ready();
+    fn metrics_registry() -> Arc<archimedes_telemetry::MetricsRegistry> {
+        let recorder = metrics_exporter_prometheus::PrometheusBuilder::new().build_recorder();
+        metrics::with_local_recorder(&recorder, || {
+            metrics::counter!("orders_created_total").increment(2);
+        });
+        Arc::new(archimedes_telemetry::MetricsRegistry::new(
+            recorder.handle(),
+        ))
+    }
 
+    #[tokio::test]
+    async fn test_observability_endpoints_bypass_authorization() {
+        let pipeline = Pipeline::builder()
+            .add_pre_handler_stage(archimedes_middleware::AuthorizationMiddleware::deny_all())
+            .build();
+        let server = Arc::new(
+            Server::builder()
+                .pipeline(pipeline)
+                .observability(
+                    ObservabilityEndpoints::new("/metrics", "/healthz", "/readyz")
+                        .metrics_registry(metrics_registry()),
+                )
+                .build(),
+        );
+
+        let response = call_internal(&server, Method::GET, "/metrics").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/plain; version=0.0.4"));
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("orders_created_total 2"), "{body}");
+
+        let response = call_internal(&server, Method::GET, "/healthz").await;
         assert_eq!(response.status(), StatusCode::OK);
+        let response = call_internal(&server, Method::GET, "/readyz").await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // The built-in paths are replaced
+        assert!(!server.internal_routes().contains(&Method::GET, "/health"));
+        assert!(!server.internal_routes().contains(&Method::GET, "/ready"));
     }
 
-    #[test]
-    fn test_server_ready_not_ready() {
-        let server = Arc::new(Server::builder().build());
-        server.readiness().set_ready(false);
+    #[tokio::test]
+    async fn test_metrics_served_on_own_listener() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Reserve a free port for the metrics listener
+        let metrics_addr = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let server = Server::builder()
+            .shutdown_timeout(Duration::from_millis(100))
+            .observability(
+                ObservabilityEndpoints::default()
+                    .metrics_addr(metrics_addr.to_string())
+                    .metrics_registry(metrics_registry()),
+            )
+            .build();
+        assert!(!server.internal_routes().contains(&Method::GET, "/metrics"));
+        assert!(server.internal_routes().contains(&Method::GET, "/health"));
 
-        let response = server.handle_ready();
-        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let shutdown = ShutdownSignal::new();
+        let running = tokio::spawn(server.run_with_listener(listener, shutdown.clone()));
+
+        let mut raw = String::new();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                if let Ok(mut stream) = tokio::net::TcpStream::connect(metrics_addr).await {
+                    stream
+                        .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+                        .await
+                        .unwrap();
+                    stream.read_to_string(&mut raw).await.unwrap();
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("metrics listener should accept connections");
+        assert!(raw.starts_with("HTTP/1.1 200"), "{raw}");
+        assert!(raw.contains("orders_created_total 2"), "{raw}");
+
+        shutdown.trigger();
+        running.await.unwrap().unwrap();
     }
 
-    #[test]
-    fn test_server_route_not_found() {
-        let server = Arc::new(Server::builder().build());
-        let response = server.handle_not_found("/nonexistent");
+    #[derive(Debug)]
+    struct MaskingPolicy;
 
-        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    impl archimedes_middleware::stages::PolicyEvaluator for MaskingPolicy {
+        fn evaluate(
+            &self,
+            _identity: &archimedes_core::CallerIdentity,
+            _operation_id: &str,
+        ) -> archimedes_middleware::stages::PolicyDecision {
+            archimedes_middleware::stages::PolicyDecision::Allow
+        }
+
+        fn obligations(
+            &self,
+            _identity: &archimedes_core::CallerIdentity,
+            _operation_id: &str,
+        ) -> Obligations {
+            Obligations::new().with("mask_fields", serde_json::json!(["ssn"]))
+        }
     }
 
     #[tokio::test]
-    async fn test_server_route_matched_no_handler() {
-        let mut server = Server::builder().build();
+    async fn test_handler_reads_authorization_obligations() {
+        let mut registry = HandlerRegistry::new();
+        registry.register_no_body("getUser", |ctx: RequestContext| async move {
+            let masked: Vec<String> = ctx.obligations().get_as("mask_fields").unwrap_or_default();
+            Ok::<_, crate::handler::HandlerError>(serde_json::json!({ "masked": masked }))
+        });
+        let pipeline = Pipeline::builder()
+            .add_pre_handler_stage(archimedes_middleware::AuthorizationMiddleware::custom(
+                MaskingPolicy,
+            ))
+            .build();
+        let mut server = Server::builder()
+            .handlers(registry)
+            .pipeline(pipeline)
+            .build();
         server
             .router_mut()
             .add_route(Method::GET, "/users/{id}", "getUser");
-
         let server = Arc::new(server);
+
         let response = server
-            .route_request(&Method::GET, "/users/123", Bytes::new())
+            .dispatch(
+                Method::GET,
+                "/users/1",
+                HeaderMap::new(),
+                Bytes::new(),
+                false,
+            )
             .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["masked"], serde_json::json!(["ssn"]));
 
-        // Without a handler registered, should return NOT_IMPLEMENTED
-        assert_eq!(response.status(), StatusCode::NOT_IMPLEMENTED);
+        // Without a pipeline there is nothing to honor
+        let response = server
+            .route_request(&Method::GET, "/users/1", Bytes::new())
+            .await;
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["masked"], serde_json::json!([]));
     }
 
     #[test]
-    fn test_server_error_display() {
-        let bind_err = ServerError::BindError("Address in use".to_string());
-        assert!(bind_err.to_string().contains("Bind error"));
+    fn test_builtin_endpoints_are_internal() {
+        let server = Server::builder()
+            .diagnostics_endpoint(true)
+            .batch(BatchConfig::new().enabled(true))
+            .build();
+        let routes = server.internal_routes();
+        assert!(routes.contains(&Method::GET, "/health"));
+        assert!(routes.contains(&Method::GET, "/ready"));
+        assert!(routes.contains(&Method::GET, "/-/diagnostics"));
+        assert!(routes.contains(&Method::POST, "/-/batch"));
+
+        let server = Server::new(ServerConfig::default());
+        assert_eq!(server.internal_routes().len(), 2);
+    }
 
-        let io_err = ServerError::IoError("Connection reset".to_string());
-        assert!(io_err.to_string().contains("I/O error"));
+    #[tokio::test]
+    async fn test_resolve_endpoint_passes_query_to_explainer() {
+        let server = Arc::new(
+            Server::builder()
+                .resolve_endpoint(
+                    |method, path| serde_json::json!({ "method": method, "path": path }),
+                )
+                .build(),
+        );
+        assert!(server
+            .internal_routes()
+            .contains(&Method::GET, RESOLVE_PATH));
+
+        let response = call_internal(
+            &server,
+            Method::GET,
+            "/-/resolve?method=get&path=%2Fusers%2F42",
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "method": "GET", "path": "/users/42" })
+        );
+
+        let response = call_internal(&server, Method::GET, "/-/resolve?method=GET").await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
     }
 
     #[tokio::test]
-    async fn test_server_run_invalid_address() {
-        let server = Server::builder().http_addr("not-a-valid-address").build();
+    async fn test_server_run_rejects_internal_route_shadowing_operation() {
+        let mut server = Server::builder()
+            .http_addr("127.0.0.1:0")
+            .internal_route(Method::GET, "/status", |_request| async {
+                Response::new(Full::new(Bytes::new()))
+            })
+            .build();
+        server
+            .router_mut()
+            .add_route(Method::GET, "/status", "healthCheck");
 
         let result = server.run_with_shutdown(ShutdownSignal::new()).await;
-        assert!(result.is_err());
-
-        if let Err(ServerError::BindError(msg)) = result {
-            assert!(msg.contains("Invalid address"));
-        } else {
-            panic!("Expected BindError");
+        match result {
+            Err(ServerError::InvalidConfig(msg)) => {
+                assert!(msg.contains("GET /status"));
+                assert!(msg.contains("healthCheck"));
+            }
+            other => panic!("Expected InvalidConfig, got {other:?}"),
         }
     }
 
-    #[tokio::test]
-    async fn test_server_run_and_shutdown() {
-        let server = Server::builder()
-            .http_addr("127.0.0.1:0") // Use port 0 for random available port
-            .shutdown_timeout(Duration::from_millis(100))
-            .build();
+    /// Builds an app serving `GET /users` that reports which app answered.
+    fn users_app(name: &'static str, host: &str) -> AppSpec {
+        use crate::handler::HandlerRegistry;
 
-        let shutdown = ShutdownSignal::new();
-        let shutdown_trigger = shutdown.clone();
+        let mut router = Router::new();
+        router.add_route(Method::GET, "/users", "listUsers");
 
-        // Trigger shutdown immediately
-        shutdown_trigger.trigger();
+        let mut registry = HandlerRegistry::new();
+        registry.register_no_body("listUsers", move |ctx: RequestContext| async move {
+            let greeting = ctx
+                .container()
+                .and_then(Container::resolve::<String>)
+                .map(|greeting| greeting.as_str().to_string());
+            Ok::<_, crate::handler::HandlerError>(
+                serde_json::json!({ "app": name, "greeting": greeting }),
+            )
+        });
 
-        // Server should exit quickly
-        let result =
-            tokio::time::timeout(Duration::from_secs(5), server.run_with_shutdown(shutdown)).await;
+        let mut container = Container::new();
+        container.register(Arc::new(format!("hello from {name}")));
 
-        assert!(result.is_ok());
-        assert!(result.unwrap().is_ok());
+        AppSpec::new(name)
+            .host(host)
+            .router(router)
+            .handlers(registry)
+            .container(Arc::new(container))
     }
 
-    // Integration tests for handler invocation
-
-    #[derive(serde::Deserialize)]
-    struct EchoRequest {
-        message: String,
+    /// Sends one request on a fresh connection and returns status and body.
+    async fn raw_request(addr: SocketAddr, request: &str) -> (u16, String) {
+        let raw = raw_response(addr, request).await;
+        let status = raw.split(' ').nth(1).unwrap().parse().unwrap();
+        let body = raw.split_once("\r\n\r\n").unwrap().1.to_string();
+        (status, body)
     }
 
-    #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
-    struct EchoResponse {
-        echo: String,
+    async fn raw_response(addr: SocketAddr, request: &str) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut raw = Vec::new();
+        tokio::time::timeout(Duration::from_secs(5), stream.read_to_end(&mut raw))
+            .await
+            .expect("server should close the connection")
+            .unwrap();
+        String::from_utf8(raw).unwrap()
     }
 
-    async fn echo_handler(
-        _ctx: archimedes_core::RequestContext,
-        req: EchoRequest,
-    ) -> Result<EchoResponse, crate::handler::HandlerError> {
-        Ok(EchoResponse {
-            echo: format!("Echo: {}", req.message),
+    /// Returns the value of a header in a raw HTTP/1.1 response.
+    fn raw_header<'a>(raw: &'a str, name: &str) -> Option<&'a str> {
+        let head = raw.split_once("\r\n\r\n").map_or(raw, |(head, _)| head);
+        head.lines().skip(1).find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.eq_ignore_ascii_case(name).then(|| value.trim())
         })
     }
 
-    #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
-    struct HealthResponse {
-        status: String,
+    fn get_with_host(path: &str, host: &str) -> String {
+        format!("GET {path} HTTP/1.1\r\nHost: {host}\r\nConnection: close\r\n\r\n")
     }
 
-    async fn health_handler(
-        _ctx: archimedes_core::RequestContext,
-    ) -> Result<HealthResponse, crate::handler::HandlerError> {
-        Ok(HealthResponse {
-            status: "ok".to_string(),
-        })
+    #[tokio::test]
+    async fn test_apps_with_overlapping_paths_resolve_by_host() {
+        let server = Server::builder()
+            .app(users_app("api", "api.example.com"))
+            .app(users_app("admin", "admin.example.com"))
+            .shutdown_timeout(Duration::from_millis(100))
+            .try_build()
+            .unwrap();
+        assert_eq!(server.app_names().collect::<Vec<_>>(), ["api", "admin"]);
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let shutdown = ShutdownSignal::new();
+        let running = tokio::spawn(server.run_with_listener(listener, shutdown.clone()));
+
+        let (status, body) = raw_request(addr, &get_with_host("/users", "api.example.com")).await;
+        assert_eq!(status, 200);
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["app"], "api");
+        assert_eq!(body["greeting"], "hello from api");
+
+        let request = get_with_host("/users", "Admin.Example.com:8080");
+        let (status, body) = raw_request(addr, &request).await;
+        assert_eq!(status, 200);
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["app"], "admin");
+        assert_eq!(body["greeting"], "hello from admin");
+
+        // No default app: unknown hosts are misdirected
+        let request = get_with_host("/users", "other.example.com");
+        let (status, body) = raw_request(addr, &request).await;
+        assert_eq!(status, 421);
+        assert!(body.contains("MISDIRECTED_REQUEST"));
+
+        // Internal endpoints are served regardless of host
+        let (status, _) = raw_request(addr, &get_with_host("/health", "other.example.com")).await;
+        assert_eq!(status, 200);
+
+        shutdown.trigger();
+        running.await.unwrap().unwrap();
     }
 
     #[tokio::test]
-    async fn test_handler_invocation() {
-        use crate::handler::HandlerRegistry;
+    async fn test_apps_missing_host_falls_back_to_default_app() {
+        let server = Server::builder()
+            .app(users_app("api", "api.example.com"))
+            .app(users_app("admin", "admin.example.com"))
+            .default_app("admin")
+            .shutdown_timeout(Duration::from_millis(100))
+            .try_build()
+            .unwrap();
 
-        let mut registry = HandlerRegistry::new();
-        registry.register("echo", echo_handler);
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let shutdown = ShutdownSignal::new();
+        let running = tokio::spawn(server.run_with_listener(listener, shutdown.clone()));
+
+        // HTTP/1.0 requests may omit the Host header entirely
+        let (status, body) = raw_request(addr, "GET /users HTTP/1.0\r\n\r\n").await;
+        assert_eq!(status, 200);
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["app"], "admin");
+
+        let request = get_with_host("/users", "other.example.com");
+        let (status, body) = raw_request(addr, &request).await;
+        assert_eq!(status, 200);
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["app"], "admin");
+
+        shutdown.trigger();
+        running.await.unwrap().unwrap();
+    }
 
-        let mut server = Server::builder().handlers(registry).build();
-        server.router_mut().add_route(Method::POST, "/echo", "echo");
+    #[test]
+    fn test_apps_conflicts_fail_build() {
+        let result = Server::builder()
+            .app(users_app("api", "api.example.com"))
+            .app(users_app("api-v2", "API.example.com"))
+            .try_build();
+        match result {
+            Err(ServerError::InvalidConfig(msg)) => {
+                assert!(msg.contains("api"));
+                assert!(msg.contains("api-v2"));
+            }
+            other => panic!("Expected InvalidConfig, got {:?}", other.err()),
+        }
 
-        let server = Arc::new(server);
-        let body = Bytes::from(r#"{"message":"Hello"}"#);
-        let response = server.route_request(&Method::POST, "/echo", body).await;
+        let result = Server::builder()
+            .app(users_app("api", "api.example.com"))
+            .default_app("missing")
+            .try_build();
+        assert!(matches!(result, Err(ServerError::InvalidConfig(_))));
+    }
 
-        assert_eq!(response.status(), StatusCode::OK);
+    #[test]
+    fn test_apps_reported_in_diagnostics_and_telemetry() {
+        let server = Server::builder()
+            .app(users_app("api", "api.example.com"))
+            .app(users_app("admin", "admin.example.com"))
+            .default_app("api")
+            .build();
 
-        // Extract body and verify
-        let body_bytes = response.into_body();
-        let collected = http_body_util::BodyExt::collect(body_bytes).await.unwrap();
-        let resp: EchoResponse = serde_json::from_slice(&collected.to_bytes()).unwrap();
-        assert_eq!(resp.echo, "Echo: Hello");
+        let diagnostics = server.diagnostics();
+        assert_eq!(diagnostics.apps.len(), 2);
+        assert_eq!(diagnostics.apps[0].name, "api");
+        assert_eq!(diagnostics.apps[0].host.as_deref(), Some("api.example.com"));
+        assert!(diagnostics.apps[0].default);
+        assert!(!diagnostics.apps[1].default);
+
+        let admin = &server.apps[1].server;
+        let ctx = admin.middleware_context(&Method::GET, "/users", &HeaderMap::new());
+        assert_eq!(
+            ctx.get_extension::<AppName>().map(|app| app.0.as_str()),
+            Some("admin")
+        );
     }
 
-    #[tokio::test]
-    async fn test_handler_no_body_invocation() {
-        use crate::handler::HandlerRegistry;
-
+    /// Starts a server with a `/report` operation that takes 200ms.
+    async fn slow_report_server(
+        builder: ServerBuilder,
+    ) -> (
+        SocketAddr,
+        ShutdownSignal,
+        tokio::task::JoinHandle<Result<(), ServerError>>,
+    ) {
         let mut registry = HandlerRegistry::new();
-        registry.register_no_body("healthCheck", health_handler);
-
-        let mut server = Server::builder().handlers(registry).build();
+        registry.register_no_body("generateReport", |_ctx: RequestContext| async {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            Ok::<_, crate::handler::HandlerError>(serde_json::json!({ "rows": 3 }))
+        });
+        let mut server = builder
+            .handlers(registry)
+            .request_timeout(Duration::from_millis(50))
+            .shutdown_timeout(Duration::from_millis(100))
+            .build();
         server
             .router_mut()
-            .add_route(Method::GET, "/status", "healthCheck");
+            .add_route(Method::GET, "/report", "generateReport");
 
-        let server = Arc::new(server);
-        let response = server
-            .route_request(&Method::GET, "/status", Bytes::new())
-            .await;
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let shutdown = ShutdownSignal::new();
+        let running = tokio::spawn(server.run_with_listener(listener, shutdown.clone()));
+        (addr, shutdown, running)
+    }
 
-        assert_eq!(response.status(), StatusCode::OK);
+    #[tokio::test]
+    async fn test_operation_timeout_overrides_request_timeout() {
+        let request = get_with_host("/report", "localhost");
+
+        // The default timeout is too short for the report
+        let (addr, shutdown, running) = slow_report_server(Server::builder()).await;
+        let (status, body) = raw_request(addr, &request).await;
+        assert_eq!(status, 504);
+        assert!(body.contains("HANDLER_TIMEOUT"));
+        shutdown.trigger();
+        running.await.unwrap().unwrap();
+
+        // A generous per-operation timeout lets it finish
+        let builder =
+            Server::builder().operation_timeouts([("generateReport", Duration::from_secs(5))]);
+        let (addr, shutdown, running) = slow_report_server(builder).await;
+        let (status, body) = raw_request(addr, &request).await;
+        assert_eq!(status, 200);
+        assert!(body.contains("\"rows\":3"));
+        shutdown.trigger();
+        running.await.unwrap().unwrap();
+
+        // Clamped to the maximum, it times out again
+        let builder = Server::builder()
+            .operation_timeout("generateReport", Duration::from_secs(5))
+            .max_request_timeout(Duration::from_millis(100));
+        let (addr, shutdown, running) = slow_report_server(builder).await;
+        let (status, _) = raw_request(addr, &request).await;
+        assert_eq!(status, 504);
+        shutdown.trigger();
+        running.await.unwrap().unwrap();
+    }
 
-        let body_bytes = response.into_body();
-        let collected = http_body_util::BodyExt::collect(body_bytes).await.unwrap();
-        let resp: HealthResponse = serde_json::from_slice(&collected.to_bytes()).unwrap();
-        assert_eq!(resp.status, "ok");
+    #[test]
+    fn test_operation_timeout_resolution() {
+        let server = Server::builder()
+            .request_timeout(Duration::from_secs(10))
+            .operation_timeout("generateReport", Duration::from_secs(120))
+            .operation_timeout("ping", Duration::from_millis(500))
+            .max_request_timeout(Duration::from_secs(60))
+            .build();
+
+        assert_eq!(
+            server.operation_timeout("generateReport"),
+            Duration::from_secs(60)
+        );
+        assert_eq!(server.operation_timeout("ping"), Duration::from_millis(500));
+        assert_eq!(
+            server.operation_timeout("listUsers"),
+            Duration::from_secs(10)
+        );
     }
 
     #[tokio::test]
-    async fn test_handler_deserialization_error() {
-        use crate::handler::HandlerRegistry;
+    async fn test_cache_policy_headers() {
+        use crate::handler::HandlerError;
 
         let mut registry = HandlerRegistry::new();
-        registry.register("echo", echo_handler);
+        registry.register_no_body("listProducts", |_ctx: RequestContext| async {
+            Ok::<_, HandlerError>(serde_json::json!([]))
+        });
+        registry.register_response_no_body("getProduct", |_ctx: RequestContext| async {
+            let mut headers = HeaderMap::new();
+            headers.insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+            Ok::<_, HandlerError>((StatusCode::OK, headers, "product"))
+        });
+        registry.register_response_no_body("getStock", |_ctx: RequestContext| async {
+            let mut headers = HeaderMap::new();
+            headers.insert(CACHE_CONTROL, HeaderValue::from_static("max-age=60"));
+            Ok::<_, HandlerError>((StatusCode::SERVICE_UNAVAILABLE, headers, "try later"))
+        });
+        registry.register_no_body("getPrice", |_ctx: RequestContext| async {
+            Err::<String, _>(HandlerError::Custom("pricing is down".into()))
+        });
+        let policy = CachePolicy::public()
+            .max_age(300)
+            .stale_while_revalidate(60);
+        let mut server = Server::builder()
+            .handlers(registry)
+            .cache_policies(
+                ["listProducts", "getProduct", "getStock", "getPrice"].map(|id| (id, policy)),
+            )
+            .shutdown_timeout(Duration::from_millis(100))
+            .build();
+        let router = server.router_mut();
+        router.add_route(Method::GET, "/products", "listProducts");
+        router.add_route(Method::GET, "/products/{id}", "getProduct");
+        router.add_route(Method::GET, "/products/{id}/stock", "getStock");
+        router.add_route(Method::GET, "/products/{id}/price", "getPrice");
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let shutdown = ShutdownSignal::new();
+        let running = tokio::spawn(server.run_with_listener(listener, shutdown.clone()));
+        let get = |path: &str| get_with_host(path, "localhost");
+
+        // The contract's policy applies to successful responses
+        let raw = raw_response(addr, &get("/products")).await;
+        assert!(raw.starts_with("HTTP/1.1 200"));
+        assert_eq!(
+            raw_header(&raw, "cache-control"),
+            Some("public, max-age=300, stale-while-revalidate=60")
+        );
+        assert!(raw_header(&raw, "expires").is_some_and(|expires| expires.ends_with("GMT")));
+
+        // A handler's own Cache-Control wins
+        let raw = raw_response(addr, &get("/products/1")).await;
+        assert_eq!(raw_header(&raw, "cache-control"), Some("no-cache"));
+        assert_eq!(raw_header(&raw, "expires"), None);
+
+        // Error responses are never cached, whoever produced them
+        for path in ["/products/1/stock", "/products/1/price", "/missing"] {
+            let raw = raw_response(addr, &get(path)).await;
+            assert!(!raw.starts_with("HTTP/1.1 2"), "{path}: {raw}");
+            assert_eq!(
+                raw_header(&raw, "cache-control"),
+                Some("no-store"),
+                "{path}"
+            );
+            assert_eq!(raw_header(&raw, "expires"), Some("0"), "{path}");
+        }
 
-        let mut server = Server::builder().handlers(registry).build();
-        server.router_mut().add_route(Method::POST, "/echo", "echo");
+        shutdown.trigger();
+        running.await.unwrap().unwrap();
+    }
 
-        let server = Arc::new(server);
-        // Invalid JSON
-        let body = Bytes::from(r#"not valid json"#);
-        let response = server.route_request(&Method::POST, "/echo", body).await;
+    /// Polls `/ready` until it answers `200`.
+    async fn wait_until_ready(addr: SocketAddr) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while raw_request(addr, &get_with_host("/ready", "localhost"))
+                .await
+                .0
+                != 200
+            {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("server should become ready");
+    }
 
-        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    #[tokio::test]
+    async fn test_readiness_waits_for_warmup() {
+        let gate = Arc::new(tokio::sync::Notify::new());
+        let lifecycle = crate::Lifecycle::new().on_warmup_named("gate", {
+            let gate = Arc::clone(&gate);
+            move || {
+                let gate = Arc::clone(&gate);
+                async move {
+                    gate.notified().await;
+                    Ok(())
+                }
+            }
+        });
+        let server = Server::builder()
+            .warmup(Warmup::new().lifecycle(&lifecycle))
+            .diagnostics_endpoint(true)
+            .shutdown_timeout(Duration::from_millis(100))
+            .build();
+        assert!(!server.readiness().is_ready());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let shutdown = ShutdownSignal::new();
+        let running = tokio::spawn(server.run_with_listener(listener, shutdown.clone()));
+
+        let (status, body) = raw_request(addr, &get_with_host("/ready", "localhost")).await;
+        assert_eq!(status, 503);
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["checks"]["warmup"], false);
+        let (_, body) = raw_request(addr, &get_with_host("/-/diagnostics", "localhost")).await;
+        let diagnostics: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert!(diagnostics.get("warmup").is_none());
+
+        gate.notify_one();
+        wait_until_ready(addr).await;
+
+        let (_, body) = raw_request(addr, &get_with_host("/-/diagnostics", "localhost")).await;
+        let diagnostics: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(diagnostics["warmup"]["steps"][0]["name"], "hook:gate");
+        assert_eq!(diagnostics["warmup"]["steps"][0]["completed"], 1);
+        assert_eq!(diagnostics["warmup"]["budget_exceeded"], false);
+
+        shutdown.trigger();
+        running.await.unwrap().unwrap();
     }
 
     #[tokio::test]
-    async fn test_handler_not_registered() {
-        use crate::handler::HandlerRegistry;
+    async fn test_readiness_after_warmup_budget_expires() {
+        let lifecycle = crate::Lifecycle::new().on_warmup_named("stuck", || async {
+            std::future::pending::<()>().await;
+            Ok(())
+        });
+        let server = Server::builder()
+            .warmup(
+                Warmup::new()
+                    .lifecycle(&lifecycle)
+                    .budget(Duration::from_millis(50)),
+            )
+            .diagnostics_endpoint(true)
+            .shutdown_timeout(Duration::from_millis(100))
+            .build();
 
-        let registry = HandlerRegistry::new();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let shutdown = ShutdownSignal::new();
+        let running = tokio::spawn(server.run_with_listener(listener, shutdown.clone()));
 
-        let mut server = Server::builder().handlers(registry).build();
-        server
-            .router_mut()
-            .add_route(Method::GET, "/missing", "missingOp");
+        wait_until_ready(addr).await;
 
-        let server = Arc::new(server);
-        let response = server
-            .route_request(&Method::GET, "/missing", Bytes::new())
-            .await;
+        let (_, body) = raw_request(addr, &get_with_host("/-/diagnostics", "localhost")).await;
+        let diagnostics: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(diagnostics["warmup"]["budget_exceeded"], true);
+        assert_eq!(
+            diagnostics["warmup"]["steps"][0]["not_warmed"],
+            serde_json::json!(["stuck"])
+        );
 
-        assert_eq!(response.status(), StatusCode::NOT_IMPLEMENTED);
+        shutdown.trigger();
+        running.await.unwrap().unwrap();
+    }
+
+    #[test]
+    fn test_disabled_warmup_is_ready_immediately() {
+        let server = Server::builder()
+            .warmup(Warmup::new().enabled(false))
+            .build();
+
+        assert!(server.readiness().is_ready());
+        assert!(server.diagnostics().warmup.is_none());
     }
 }
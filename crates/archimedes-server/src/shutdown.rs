@@ -221,7 +221,11 @@ impl Future for ShutdownReceiver {
 ///
 /// On Unix systems, this waits for SIGTERM or SIGINT.
 /// On other systems, this only waits for SIGINT (Ctrl+C).
-async fn wait_for_os_signal() {
+///
+/// # Panics
+///
+/// Panics if signal handlers cannot be registered.
+pub async fn wait_for_os_signal() {
     #[cfg(unix)]
     {
         use tokio::signal::unix::{signal, SignalKind};
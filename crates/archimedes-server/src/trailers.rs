@@ -0,0 +1,163 @@
+//! Delivery of trailers set on buffered responses.
+//!
+//! A handler attaches [`Trailers`] to its response, and middleware sees
+//! them as a response extension. When the response is written, the server
+//! sends them after the body if the connection can carry trailers: always
+//! on HTTP/2, and on HTTP/1.1 when the client sent `TE: trailers`, in which
+//! case the body is chunked. Otherwise the body is already buffered, so the
+//! fields are sent as headers instead of being dropped.
+
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use http::header::{TE, TRAILER};
+use http::{HeaderMap, Method, Response, StatusCode, Version};
+use hyper::body::{Body, Frame};
+
+use archimedes_core::Trailers;
+
+/// A buffered body followed by a trailers frame.
+///
+/// The size is deliberately not reported, so HTTP/1.1 responses are
+/// chunked and can end with trailers.
+#[derive(Debug)]
+pub struct TrailersBody {
+    data: Option<Bytes>,
+    trailers: Option<HeaderMap>,
+}
+
+impl TrailersBody {
+    pub fn new(data: Bytes, trailers: HeaderMap) -> Self {
+        Self {
+            data: Some(data).filter(|data| !data.is_empty()),
+            trailers: Some(trailers),
+        }
+    }
+}
+
+impl Body for TrailersBody {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        if let Some(data) = self.data.take() {
+            return Poll::Ready(Some(Ok(Frame::data(data))));
+        }
+        Poll::Ready(
+            self.trailers
+                .take()
+                .map(|trailers| Ok(Frame::trailers(trailers))),
+        )
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.data.is_none() && self.trailers.is_none()
+    }
+}
+
+/// Whether a response to this request can end with trailers.
+pub fn can_carry(version: Version, method: &Method, headers: &HeaderMap) -> bool {
+    if method == Method::HEAD {
+        return false;
+    }
+    match version {
+        Version::HTTP_2 | Version::HTTP_3 => true,
+        Version::HTTP_11 => headers
+            .get_all(TE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .any(|coding| coding.trim().eq_ignore_ascii_case("trailers")),
+        _ => false,
+    }
+}
+
+/// Takes the trailers off a response so they can be sent after the body.
+///
+/// Declares them in the `Trailer` header and returns them, or returns
+/// `None` if the response has none or its status has no body.
+pub fn take<B>(response: &mut Response<B>) -> Option<HeaderMap> {
+    if matches!(
+        response.status(),
+        StatusCode::NO_CONTENT | StatusCode::NOT_MODIFIED
+    ) {
+        return None;
+    }
+    let trailers = response.extensions().get::<Trailers>()?;
+    let declaration = trailers.declaration()?;
+    let trailers = trailers.headers().clone();
+    response.headers_mut().insert(TRAILER, declaration);
+    Some(trailers)
+}
+
+/// Sends the trailers of a response as headers instead.
+pub fn fold_into_headers<B>(response: &mut Response<B>) {
+    let Some(trailers) = response.extensions().get::<Trailers>() else {
+        return;
+    };
+    let trailers = trailers.headers().clone();
+    let headers = response.headers_mut();
+    headers.remove(TRAILER);
+    for (name, value) in &trailers {
+        headers.insert(name, value.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use archimedes_core::response::GRPC_STATUS_TRAILER;
+    use http::HeaderValue;
+    use http_body_util::BodyExt;
+
+    fn response_with_trailers() -> Response<Bytes> {
+        let mut response = Response::new(Bytes::from_static(b"payload"));
+        response
+            .extensions_mut()
+            .insert(Trailers::new().with(GRPC_STATUS_TRAILER, HeaderValue::from_static("0")));
+        response
+    }
+
+    #[test]
+    fn test_can_carry() {
+        let none = HeaderMap::new();
+        let mut te = HeaderMap::new();
+        te.insert(TE, HeaderValue::from_static("gzip, trailers"));
+
+        assert!(can_carry(Version::HTTP_2, &Method::GET, &none));
+        assert!(can_carry(Version::HTTP_11, &Method::GET, &te));
+        assert!(!can_carry(Version::HTTP_11, &Method::GET, &none));
+        assert!(!can_carry(Version::HTTP_10, &Method::GET, &te));
+        assert!(!can_carry(Version::HTTP_2, &Method::HEAD, &none));
+    }
+
+    #[tokio::test]
+    async fn test_body_ends_with_trailers() {
+        let mut response = response_with_trailers();
+        let trailers = take(&mut response).unwrap();
+        assert_eq!(response.headers()[TRAILER], GRPC_STATUS_TRAILER);
+
+        let body = TrailersBody::new(response.into_body(), trailers);
+        assert!(body.size_hint().exact().is_none());
+        let collected = body.collect().await.unwrap();
+        assert_eq!(collected.trailers().unwrap()[GRPC_STATUS_TRAILER], "0");
+        assert_eq!(collected.to_bytes(), "payload");
+    }
+
+    #[test]
+    fn test_fold_into_headers() {
+        let mut response = response_with_trailers();
+        fold_into_headers(&mut response);
+        assert_eq!(response.headers()[GRPC_STATUS_TRAILER], "0");
+        assert!(response.headers().get(TRAILER).is_none());
+
+        let mut no_content = response_with_trailers();
+        *no_content.status_mut() = StatusCode::NO_CONTENT;
+        assert!(take(&mut no_content).is_none());
+    }
+}
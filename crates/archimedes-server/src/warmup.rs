@@ -0,0 +1,510 @@
+//! Warm-up run before the server reports ready.
+//!
+//! The first request to an operation pays for work done lazily: building
+//! its validation state, evaluating its authorization policy, opening
+//! upstream connections. A [`Warmup`] does that work once the server
+//! accepts connections and before `/ready` reports ready, in these steps:
+//!
+//! 1. `validation`: each routed operation's validation path is exercised
+//!    with an example body (see [`RequestValidator::warm_up`])
+//! 2. `authorization`: the policy is evaluated for each routed operation
+//!    with a representative caller
+//! 3. `connections`: a configured number of upstream or sidecar
+//!    connections are opened
+//! 4. `hook:<name>`: the warm-up hooks registered on a [`Lifecycle`] run
+//!
+//! The whole warm-up is bounded by a time [budget](Warmup::budget). Once it
+//! runs out the remaining work is skipped and logged, and the server
+//! reports ready anyway: a cold operation is slower, not broken.
+//!
+//! The timings of each step are reported in the
+//! [diagnostics](crate::Diagnostics) as [`WarmupReport`]. Warm-up can be
+//! [disabled](Warmup::enabled) for fast local development.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use archimedes_server::{Lifecycle, Server, Warmup};
+//!
+//! let lifecycle = Lifecycle::new().on_warmup_named("cache", || async { prime_cache().await });
+//!
+//! let server = Server::builder()
+//!     .warmup(
+//!         Warmup::new()
+//!             .validator(Arc::clone(&sentinel))
+//!             .policy(policy, CallerIdentity::Anonymous)
+//!             .connections(4, move || pool.open())
+//!             .lifecycle(&lifecycle)
+//!             .enabled(!config.local_dev),
+//!     )
+//!     .build();
+//! ```
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use archimedes_core::CallerIdentity;
+use archimedes_middleware::stages::{PolicyEvaluator, RequestValidator};
+use serde::{Deserialize, Serialize};
+
+use crate::lifecycle::{Lifecycle, LifecycleResult, WarmupHook};
+
+/// How long warm-up may run before the server reports ready anyway.
+pub const DEFAULT_WARMUP_BUDGET: Duration = Duration::from_secs(10);
+
+/// Opens one upstream connection, e.g. into a pool.
+type ConnectionOpener =
+    Arc<dyn Fn() -> Pin<Box<dyn Future<Output = LifecycleResult> + Send>> + Send + Sync>;
+
+/// Warm-up configuration.
+///
+/// See the [module documentation](self).
+#[derive(Clone)]
+#[must_use]
+pub struct Warmup {
+    enabled: bool,
+    budget: Duration,
+    validator: Option<Arc<dyn RequestValidator>>,
+    policy: Option<(Arc<dyn PolicyEvaluator>, CallerIdentity)>,
+    connections: Option<(usize, ConnectionOpener)>,
+    hooks: Vec<(String, WarmupHook)>,
+}
+
+impl Default for Warmup {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Warmup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Warmup")
+            .field("enabled", &self.enabled)
+            .field("budget", &self.budget)
+            .field("validator", &self.validator.is_some())
+            .field("policy", &self.policy.as_ref().map(|(policy, _)| policy))
+            .field("connections", &self.connections.as_ref().map(|(n, _)| n))
+            .field(
+                "hooks",
+                &self.hooks.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl Warmup {
+    /// Creates an enabled warm-up with nothing to warm and the
+    /// [default budget](DEFAULT_WARMUP_BUDGET).
+    pub fn new() -> Self {
+        Self {
+            enabled: true,
+            budget: DEFAULT_WARMUP_BUDGET,
+            validator: None,
+            policy: None,
+            connections: None,
+            hooks: Vec::new(),
+        }
+    }
+
+    /// Enables or disables warm-up. A disabled warm-up is skipped and the
+    /// server is ready as soon as it accepts connections.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Sets how long warm-up may run before the server reports ready
+    /// anyway.
+    pub fn budget(mut self, budget: Duration) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Warms the validation path of each operation with this validator,
+    /// usually the one the validation middleware uses.
+    pub fn validator(mut self, validator: Arc<dyn RequestValidator>) -> Self {
+        self.validator = Some(validator);
+        self
+    }
+
+    /// Evaluates the policy for each operation with a representative
+    /// caller.
+    pub fn policy(mut self, policy: Arc<dyn PolicyEvaluator>, identity: CallerIdentity) -> Self {
+        self.policy = Some((policy, identity));
+        self
+    }
+
+    /// Opens `count` upstream connections, one after the other, with
+    /// `open`.
+    ///
+    /// The opener keeps the connection, e.g. in its pool; warm-up only
+    /// records whether it succeeded.
+    pub fn connections<F, Fut>(mut self, count: usize, open: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = LifecycleResult> + Send + 'static,
+    {
+        let open: ConnectionOpener = Arc::new(move || Box::pin(open()));
+        self.connections = Some((count, open));
+        self
+    }
+
+    /// Runs the warm-up hooks registered on a lifecycle, after the other
+    /// steps.
+    pub fn lifecycle(mut self, lifecycle: &Lifecycle) -> Self {
+        self.hooks.extend(lifecycle.warmup_hooks().iter().cloned());
+        self
+    }
+
+    /// Returns whether warm-up runs.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns the warm-up budget.
+    #[must_use]
+    pub fn budget_duration(&self) -> Duration {
+        self.budget
+    }
+
+    /// Runs every step for the given operations within the budget.
+    ///
+    /// Work not done when the budget runs out is listed in the report and
+    /// logged.
+    pub async fn run<'a, I>(&self, operation_ids: I) -> WarmupReport
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let started = Instant::now();
+        let deadline = started + self.budget;
+        let operation_ids: Vec<&str> = operation_ids.into_iter().collect();
+        let mut steps = Vec::new();
+
+        if let Some(validator) = &self.validator {
+            steps.push(Self::each_operation(
+                "validation",
+                &operation_ids,
+                deadline,
+                |operation_id| validator.warm_up(operation_id, None),
+            ));
+        }
+        if let Some((policy, identity)) = &self.policy {
+            steps.push(Self::each_operation(
+                "authorization",
+                &operation_ids,
+                deadline,
+                |operation_id| {
+                    policy.evaluate(identity, operation_id);
+                },
+            ));
+        }
+        if let Some((count, open)) = &self.connections {
+            let step_started = Instant::now();
+            let mut step = WarmupStep::new("connections", *count);
+            for index in 0..*count {
+                match within(deadline, open()).await {
+                    Some(Ok(())) => step.completed += 1,
+                    Some(Err(e)) => {
+                        tracing::warn!(connection = index, error = %e, "Warm-up connection failed");
+                    }
+                    None => {
+                        step.not_warmed
+                            .extend((index..*count).map(|i| format!("connection_{i}")));
+                        break;
+                    }
+                }
+            }
+            steps.push(step.finish(step_started));
+        }
+        for (name, hook) in &self.hooks {
+            let step_started = Instant::now();
+            let mut step = WarmupStep::new(format!("hook:{name}"), 1);
+            match within(deadline, hook()).await {
+                Some(Ok(())) => step.completed = 1,
+                Some(Err(e)) => {
+                    tracing::warn!(hook = %name, error = %e, "Warm-up hook failed");
+                }
+                None => step.not_warmed.push(name.clone()),
+            }
+            steps.push(step.finish(step_started));
+        }
+
+        let report = WarmupReport {
+            budget_exceeded: steps.iter().any(|step| !step.not_warmed.is_empty()),
+            duration_ms: millis(started.elapsed()),
+            steps,
+        };
+        report.log();
+        report
+    }
+
+    /// Runs a synchronous step for each operation until the deadline.
+    fn each_operation(
+        name: &str,
+        operation_ids: &[&str],
+        deadline: Instant,
+        mut warm: impl FnMut(&str),
+    ) -> WarmupStep {
+        let started = Instant::now();
+        let mut step = WarmupStep::new(name, operation_ids.len());
+        for (index, operation_id) in operation_ids.iter().enumerate() {
+            if Instant::now() >= deadline {
+                step.not_warmed
+                    .extend(operation_ids[index..].iter().map(ToString::to_string));
+                break;
+            }
+            warm(operation_id);
+            step.completed += 1;
+        }
+        step.finish(started)
+    }
+}
+
+/// Timings of a warm-up, reported in the diagnostics.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WarmupReport {
+    /// Steps in the order they ran.
+    pub steps: Vec<WarmupStep>,
+    /// Total duration, in milliseconds.
+    pub duration_ms: u64,
+    /// Whether the budget ran out before every step completed.
+    pub budget_exceeded: bool,
+}
+
+impl WarmupReport {
+    /// Returns a step by name.
+    #[must_use]
+    pub fn step(&self, name: &str) -> Option<&WarmupStep> {
+        self.steps.iter().find(|step| step.name == name)
+    }
+
+    /// Logs the report, with a warning listing the work the budget cut
+    /// short.
+    pub fn log(&self) {
+        for step in &self.steps {
+            tracing::debug!(
+                step = %step.name,
+                duration_ms = step.duration_ms,
+                completed = step.completed,
+                total = step.total,
+                "Warm-up step finished"
+            );
+            if !step.not_warmed.is_empty() {
+                tracing::warn!(
+                    step = %step.name,
+                    not_warmed = ?step.not_warmed,
+                    "Warm-up budget exceeded before these were warmed"
+                );
+            }
+        }
+        tracing::info!(
+            duration_ms = self.duration_ms,
+            budget_exceeded = self.budget_exceeded,
+            "Warm-up finished"
+        );
+    }
+}
+
+/// Timings of one warm-up step.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WarmupStep {
+    /// Step name, e.g. `validation` or `hook:cache`.
+    pub name: String,
+    /// Duration, in milliseconds.
+    pub duration_ms: u64,
+    /// Number of items (operations, connections, hooks) warmed.
+    pub completed: usize,
+    /// Number of items to warm.
+    pub total: usize,
+    /// Items not warmed before the budget ran out.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub not_warmed: Vec<String>,
+}
+
+impl WarmupStep {
+    fn new(name: impl Into<String>, total: usize) -> Self {
+        Self {
+            name: name.into(),
+            total,
+            ..Self::default()
+        }
+    }
+
+    fn finish(mut self, started: Instant) -> Self {
+        self.duration_ms = millis(started.elapsed());
+        self
+    }
+}
+
+/// Runs a warm-up future until the deadline.
+///
+/// Returns `None` if the deadline passed before it started or finished.
+async fn within<F>(deadline: Instant, future: F) -> Option<LifecycleResult>
+where
+    F: Future<Output = LifecycleResult>,
+{
+    if Instant::now() >= deadline {
+        return None;
+    }
+    tokio::time::timeout_at(deadline.into(), future).await.ok()
+}
+
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use archimedes_middleware::stages::{PolicyDecision, ValidationResult};
+    use http::HeaderMap;
+    use std::sync::Mutex;
+
+    /// Builds an operation's validation state on first use.
+    #[derive(Default)]
+    struct LazyValidator {
+        compiled: Mutex<Vec<String>>,
+        compilations: Mutex<usize>,
+    }
+
+    impl LazyValidator {
+        fn compile(&self, operation_id: &str) {
+            let mut compiled = self.compiled.lock().unwrap();
+            if !compiled.iter().any(|id| id == operation_id) {
+                compiled.push(operation_id.to_string());
+                *self.compilations.lock().unwrap() += 1;
+            }
+        }
+    }
+
+    impl RequestValidator for LazyValidator {
+        fn validate_request(
+            &self,
+            operation_id: &str,
+            _version: Option<&str>,
+            _headers: &HeaderMap,
+            _body: &[u8],
+        ) -> ValidationResult {
+            self.compile(operation_id);
+            ValidationResult::success()
+        }
+
+        fn example_body(&self, _operation_id: &str, _version: Option<&str>) -> Option<Vec<u8>> {
+            Some(b"{}".to_vec())
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct RecordingPolicy(Mutex<Vec<String>>);
+
+    impl PolicyEvaluator for RecordingPolicy {
+        fn evaluate(&self, _identity: &CallerIdentity, operation_id: &str) -> PolicyDecision {
+            self.0.lock().unwrap().push(operation_id.to_string());
+            PolicyDecision::Allow
+        }
+    }
+
+    #[tokio::test]
+    async fn test_warmup_populates_validation_state() {
+        let validator = Arc::new(LazyValidator::default());
+        let warmup = Warmup::new().validator(Arc::clone(&validator) as _);
+
+        let report = warmup.run(["getUser", "listUsers"]).await;
+
+        let step = report.step("validation").unwrap();
+        assert_eq!((step.completed, step.total), (2, 2));
+        assert!(!report.budget_exceeded);
+        assert_eq!(*validator.compilations.lock().unwrap(), 2);
+
+        // The first real request finds the state built
+        validator.validate_request("getUser", None, &HeaderMap::new(), b"{}");
+        assert_eq!(*validator.compilations.lock().unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_warmup_evaluates_policy_per_operation() {
+        let policy = Arc::new(RecordingPolicy::default());
+        let warmup = Warmup::new().policy(Arc::clone(&policy) as _, CallerIdentity::Anonymous);
+
+        let report = warmup.run(["getUser", "listUsers"]).await;
+
+        assert_eq!(report.step("authorization").unwrap().completed, 2);
+        assert_eq!(*policy.0.lock().unwrap(), ["getUser", "listUsers"]);
+    }
+
+    #[tokio::test]
+    async fn test_warmup_opens_connections_and_runs_hooks() {
+        let opened = Arc::new(Mutex::new(0));
+        let lifecycle = Lifecycle::new()
+            .on_warmup_named("cache", || async { Ok(()) })
+            .on_warmup_named("broken", || async {
+                Err(crate::LifecycleError::new("unavailable"))
+            });
+        let warmup = Warmup::new()
+            .connections(3, {
+                let opened = Arc::clone(&opened);
+                move || {
+                    let opened = Arc::clone(&opened);
+                    async move {
+                        *opened.lock().unwrap() += 1;
+                        Ok(())
+                    }
+                }
+            })
+            .lifecycle(&lifecycle);
+
+        let report = warmup.run([]).await;
+
+        assert_eq!(*opened.lock().unwrap(), 3);
+        let names: Vec<&str> = report.steps.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["connections", "hook:cache", "hook:broken"]);
+        assert_eq!(report.step("connections").unwrap().completed, 3);
+        assert_eq!(report.step("hook:cache").unwrap().completed, 1);
+        // A failing hook is reported but does not exceed the budget
+        assert_eq!(report.step("hook:broken").unwrap().completed, 0);
+        assert!(!report.budget_exceeded);
+    }
+
+    #[tokio::test]
+    async fn test_warmup_budget_skips_remaining_work() {
+        let lifecycle = Lifecycle::new()
+            .on_warmup_named("slow", || async {
+                std::future::pending::<()>().await;
+                Ok(())
+            })
+            .on_warmup_named("after", || async { Ok(()) });
+        let validator = Arc::new(LazyValidator::default());
+        let warmup = Warmup::new()
+            .validator(Arc::clone(&validator) as _)
+            .lifecycle(&lifecycle)
+            .budget(Duration::from_millis(50));
+
+        let report = tokio::time::timeout(Duration::from_secs(5), warmup.run(["getUser"]))
+            .await
+            .expect("warm-up should stop at its budget");
+
+        assert!(report.budget_exceeded);
+        assert_eq!(report.step("validation").unwrap().completed, 1);
+        assert_eq!(report.step("hook:slow").unwrap().not_warmed, ["slow"]);
+        assert_eq!(report.step("hook:after").unwrap().not_warmed, ["after"]);
+    }
+
+    #[tokio::test]
+    async fn test_warmup_budget_exhausted_before_operations() {
+        let validator = Arc::new(LazyValidator::default());
+        let warmup = Warmup::new()
+            .validator(Arc::clone(&validator) as _)
+            .budget(Duration::ZERO);
+
+        let report = warmup.run(["getUser", "listUsers"]).await;
+
+        let step = report.step("validation").unwrap();
+        assert_eq!(step.completed, 0);
+        assert_eq!(step.not_warmed, ["getUser", "listUsers"]);
+        assert_eq!(*validator.compilations.lock().unwrap(), 0);
+    }
+}
@@ -21,6 +21,10 @@ pub struct SidecarConfig {
     pub telemetry: TelemetrySettings,
     /// Identity settings.
     pub identity: IdentitySettings,
+    /// Request and response transformation rules.
+    pub transforms: TransformSettings,
+    /// Fault injection into upstream calls.
+    pub faults: FaultSettings,
 }
 
 impl SidecarConfig {
@@ -104,6 +108,33 @@ impl SidecarConfig {
             ));
         }
 
+        crate::transform::Transformer::new(&self.transforms)?;
+
+        if self.faults.path.is_some() {
+            if !cfg!(feature = "chaos") {
+                return Err(SidecarError::config(
+                    "faults.path requires the `chaos` feature",
+                ));
+            }
+            if self
+                .telemetry
+                .environment
+                .eq_ignore_ascii_case("production")
+            {
+                return Err(SidecarError::config(
+                    "fault injection is refused in production",
+                ));
+            }
+        }
+
+        if let Some(quantile) = self.sidecar.hedging.quantile {
+            if quantile.is_nan() || quantile <= 0.0 || quantile >= 1.0 {
+                return Err(SidecarError::config(
+                    "hedging.quantile must be between 0 and 1",
+                ));
+            }
+        }
+
         Ok(())
     }
 }
@@ -131,6 +162,10 @@ pub struct SidecarSettings {
     pub buffer_response_body: bool,
     /// Maximum response body size in bytes.
     pub max_response_body_size: usize,
+    /// Hedged request settings.
+    pub hedging: HedgeSettings,
+    /// Multipart request settings.
+    pub multipart: MultipartSettings,
 }
 
 impl Default for SidecarSettings {
@@ -145,6 +180,82 @@ impl Default for SidecarSettings {
             max_request_body_size: 10 * 1024 * 1024, // 10MB
             buffer_response_body: false,
             max_response_body_size: 50 * 1024 * 1024, // 50MB
+            hedging: HedgeSettings::default(),
+            multipart: MultipartSettings::default(),
+        }
+    }
+}
+
+/// Multipart request settings.
+///
+/// When streaming, `multipart/form-data` bodies are forwarded as they
+/// arrive instead of being buffered first, so file uploads larger than
+/// `max_request_body_size` can pass through; see [`crate::multipart`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MultipartSettings {
+    /// Stream multipart bodies to the upstream.
+    pub streaming: bool,
+    /// Maximum size of a single part in bytes.
+    pub max_part_size: u64,
+    /// Maximum size of the whole body in bytes.
+    pub max_total_size: u64,
+    /// Maximum size of a text field buffered for validation, in bytes.
+    pub max_field_size: usize,
+    /// Maximum number of parts.
+    pub max_parts: usize,
+}
+
+impl Default for MultipartSettings {
+    fn default() -> Self {
+        Self {
+            streaming: true,
+            max_part_size: 1024 * 1024 * 1024,      // 1GB
+            max_total_size: 4 * 1024 * 1024 * 1024, // 4GB
+            max_field_size: 64 * 1024,              // 64KB
+            max_parts: 100,
+        }
+    }
+}
+
+/// Hedged request settings.
+///
+/// A hedged request sends a second attempt to the upstream when the first
+/// has not answered within the hedge delay, and uses whichever answers
+/// first. Only idempotent methods are ever hedged.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HedgeSettings {
+    /// Enable request hedging.
+    pub enabled: bool,
+    /// Methods to hedge. Non-idempotent methods are ignored.
+    pub methods: Vec<String>,
+    /// Operation IDs to hedge in addition to `methods`.
+    pub operations: Vec<String>,
+    /// Hedge delay, or the fallback delay while `quantile` lacks samples.
+    #[serde(with = "humantime_serde")]
+    pub delay: Duration,
+    /// Latency quantile to use as the hedge delay, e.g. `0.95`.
+    pub quantile: Option<f64>,
+    /// Number of recent latencies the quantile is computed over.
+    pub window_size: usize,
+    /// Samples required before the quantile replaces `delay`.
+    pub min_samples: usize,
+    /// Maximum hedges in flight across all requests.
+    pub max_concurrent_hedges: usize,
+}
+
+impl Default for HedgeSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            methods: vec!["GET".to_string()],
+            operations: Vec::new(),
+            delay: Duration::from_millis(100),
+            quantile: None,
+            window_size: 1000,
+            min_samples: 100,
+            max_concurrent_hedges: 10,
         }
     }
 }
@@ -227,6 +338,8 @@ pub struct TelemetrySettings {
     pub access_log: bool,
     /// Log level.
     pub log_level: String,
+    /// Deployment environment, such as `staging` or `production`.
+    pub environment: String,
 }
 
 impl Default for TelemetrySettings {
@@ -237,10 +350,23 @@ impl Default for TelemetrySettings {
             service_name: "archimedes-sidecar".to_string(),
             access_log: true,
             log_level: "info".to_string(),
+            environment: "development".to_string(),
         }
     }
 }
 
+/// Fault injection settings, for resilience testing.
+///
+/// The fault file has the format of the Archimedes fault stage; see
+/// `fault` module. Requires the `chaos` feature, and is refused when
+/// `telemetry.environment` is `production`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FaultSettings {
+    /// Path to the fault file.
+    pub path: Option<PathBuf>,
+}
+
 /// Identity extraction settings.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -281,6 +407,105 @@ impl Default for IdentitySettings {
     }
 }
 
+/// Request and response transformation settings.
+///
+/// Rules run in order. See [`crate::transform`] for how they are applied.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TransformSettings {
+    /// Add an `x-archimedes-transformed` response header listing the rules
+    /// that matched.
+    pub debug_header: bool,
+    /// Ordered transformation rules.
+    pub rules: Vec<TransformRule>,
+}
+
+/// A transformation rule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransformRule {
+    /// Rule name, reported in the debug header.
+    pub name: String,
+    /// Which requests the rule applies to.
+    #[serde(rename = "match", default)]
+    pub matches: TransformMatch,
+    /// Actions applied, in order, to matching requests.
+    pub actions: Vec<TransformAction>,
+}
+
+/// Match clause of a transformation rule. Empty clauses match every request.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TransformMatch {
+    /// Methods to match. Empty matches any method.
+    pub methods: Vec<String>,
+    /// Path glob. `*` matches one segment, `**` the rest of the path and
+    /// `{name}` captures a segment as a named parameter.
+    pub path: Option<String>,
+    /// Path regex, an alternative to `path`.
+    pub path_regex: Option<String>,
+    /// Headers that must be present.
+    pub headers: Vec<String>,
+}
+
+/// A transformation action.
+///
+/// Header values and paths are templates: `{name}` and `{1}` insert the
+/// named or numbered capture of the rule's path pattern.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum TransformAction {
+    /// Set a request header, replacing any existing value.
+    AddRequestHeader {
+        /// Header name.
+        name: String,
+        /// Header value template.
+        value: String,
+    },
+    /// Remove a request header.
+    RemoveRequestHeader {
+        /// Header name.
+        name: String,
+    },
+    /// Rename a request header, keeping its values.
+    RenameRequestHeader {
+        /// Current header name.
+        from: String,
+        /// New header name.
+        to: String,
+    },
+    /// Set a response header, replacing any existing value.
+    AddResponseHeader {
+        /// Header name.
+        name: String,
+        /// Header value template.
+        value: String,
+    },
+    /// Remove a response header.
+    RemoveResponseHeader {
+        /// Header name.
+        name: String,
+    },
+    /// Rename a response header, keeping its values.
+    RenameResponseHeader {
+        /// Current header name.
+        from: String,
+        /// New header name.
+        to: String,
+    },
+    /// Rewrite the request path, keeping the query string.
+    RewritePath {
+        /// Path template.
+        to: String,
+    },
+    /// Add a query parameter unless the request already has it.
+    DefaultQueryParam {
+        /// Parameter name.
+        name: String,
+        /// Parameter value template.
+        value: String,
+    },
+}
+
 /// Builder for `SidecarConfig`.
 #[derive(Debug, Default)]
 pub struct SidecarConfigBuilder {
@@ -316,6 +541,27 @@ impl SidecarConfigBuilder {
         self
     }
 
+    /// Set the hedged request settings.
+    #[must_use]
+    pub fn hedging(mut self, hedging: HedgeSettings) -> Self {
+        self.config.sidecar.hedging = hedging;
+        self
+    }
+
+    /// Set the multipart request settings.
+    #[must_use]
+    pub fn multipart(mut self, multipart: MultipartSettings) -> Self {
+        self.config.sidecar.multipart = multipart;
+        self
+    }
+
+    /// Add a transformation rule after the existing ones.
+    #[must_use]
+    pub fn transform_rule(mut self, rule: TransformRule) -> Self {
+        self.config.transforms.rules.push(rule);
+        self
+    }
+
     /// Set the contract path.
     #[must_use]
     pub fn contract_path(mut self, path: impl Into<PathBuf>) -> Self {
@@ -383,7 +629,11 @@ mod humantime_serde {
     where
         S: Serializer,
     {
-        let s = format!("{}s", duration.as_secs());
+        let s = if duration.subsec_nanos() == 0 {
+            format!("{}s", duration.as_secs())
+        } else {
+            format!("{}ms", duration.as_millis())
+        };
         serializer.serialize_str(&s)
     }
 
@@ -487,5 +737,75 @@ service_name = "test-service"
         assert_eq!(config.sidecar.listen_port, 8080);
         assert!(config.contract.validate_requests);
         assert_eq!(config.telemetry.service_name, "test-service");
+        assert!(!config.sidecar.hedging.enabled);
+        assert!(config.sidecar.multipart.streaming);
+    }
+
+    #[test]
+    fn test_toml_multipart() {
+        let toml = r#"
+[sidecar.multipart]
+max_part_size = 1048576
+max_parts = 8
+"#;
+        let config: SidecarConfig = toml::from_str(toml).unwrap();
+        let multipart = &config.sidecar.multipart;
+        assert!(multipart.streaming);
+        assert_eq!(multipart.max_part_size, 1_048_576);
+        assert_eq!(multipart.max_parts, 8);
+        assert_eq!(multipart.max_field_size, 64 * 1024);
+    }
+
+    #[test]
+    fn test_toml_hedging() {
+        let toml = r#"
+[sidecar.hedging]
+enabled = true
+methods = ["GET", "HEAD"]
+operations = ["getUser"]
+delay = "50ms"
+quantile = 0.95
+"#;
+        let config: SidecarConfig = toml::from_str(toml).unwrap();
+        let hedging = &config.sidecar.hedging;
+        assert!(hedging.enabled);
+        assert_eq!(hedging.methods, vec!["GET", "HEAD"]);
+        assert_eq!(hedging.operations, vec!["getUser"]);
+        assert_eq!(hedging.delay, Duration::from_millis(50));
+        assert_eq!(hedging.quantile, Some(0.95));
+        assert_eq!(hedging.max_concurrent_hedges, 10);
+        assert!(config.validate().is_ok());
+
+        let serialized = toml::to_string(&config).unwrap();
+        assert!(serialized.contains(r#"delay = "50ms""#));
+    }
+
+    #[test]
+    fn test_hedging_quantile_validation() {
+        let config = SidecarConfig::builder()
+            .hedging(HedgeSettings {
+                quantile: Some(1.5),
+                ..HedgeSettings::default()
+            })
+            .build();
+        assert!(config.is_err());
+    }
+
+    #[test]
+    fn test_faults_refused_in_production() {
+        let mut config: SidecarConfig = toml::from_str(
+            r#"
+[telemetry]
+environment = "production"
+
+[faults]
+path = "/etc/archimedes/faults.toml"
+"#,
+        )
+        .unwrap();
+        assert!(config.validate().is_err());
+
+        config.telemetry.environment = "staging".to_string();
+        assert_eq!(config.validate().is_ok(), cfg!(feature = "chaos"));
     }
 }
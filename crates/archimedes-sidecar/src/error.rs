@@ -2,6 +2,7 @@
 
 use std::fmt;
 
+use archimedes_core::StartupError;
 use thiserror::Error;
 
 /// Sidecar-specific errors.
@@ -39,6 +40,13 @@ pub enum SidecarError {
         field: Option<String>,
     },
 
+    /// Request body or one of its parts exceeds a size limit.
+    #[error("Payload too large: {message}")]
+    PayloadTooLarge {
+        /// Error message.
+        message: String,
+    },
+
     /// Authorization denied.
     #[error("Authorization denied: {reason}")]
     AuthorizationDenied {
@@ -82,6 +90,10 @@ pub enum SidecarError {
         /// Error message.
         message: String,
     },
+
+    /// Startup failure with a known category.
+    #[error("Startup error: {0}")]
+    Startup(#[from] StartupError),
 }
 
 impl SidecarError {
@@ -131,6 +143,13 @@ impl SidecarError {
         }
     }
 
+    /// Create a payload too large error.
+    pub fn payload_too_large(message: impl Into<String>) -> Self {
+        Self::PayloadTooLarge {
+            message: message.into(),
+        }
+    }
+
     /// Create an authorization denied error.
     pub fn authorization_denied(reason: impl Into<String>) -> Self {
         Self::AuthorizationDenied {
@@ -167,6 +186,7 @@ impl SidecarError {
             Self::Upstream { status, .. } => status.unwrap_or(502),
             Self::Proxy { .. } => 502,
             Self::Validation { .. } => 400,
+            Self::PayloadTooLarge { .. } => 413,
             Self::AuthorizationDenied { .. } => 403,
             Self::HealthCheck { .. } => 503,
             Self::Server { .. } => 500,
@@ -175,6 +195,7 @@ impl SidecarError {
             Self::Json(_) => 400,
             Self::Request(_) => 502,
             Self::Internal { .. } => 500,
+            Self::Startup(_) => 500,
         }
     }
 
@@ -196,6 +217,7 @@ impl SidecarError {
             Self::Upstream { .. } => "upstream",
             Self::Proxy { .. } => "proxy",
             Self::Validation { .. } => "validation",
+            Self::PayloadTooLarge { .. } => "payload_too_large",
             Self::AuthorizationDenied { .. } => "authorization",
             Self::HealthCheck { .. } => "health",
             Self::Server { .. } => "server",
@@ -204,6 +226,27 @@ impl SidecarError {
             Self::Json(_) => "json",
             Self::Request(_) => "request",
             Self::Internal { .. } => "internal",
+            Self::Startup(_) => "startup",
+        }
+    }
+}
+
+impl From<SidecarError> for StartupError {
+    /// Classifies an error raised while the sidecar was starting.
+    ///
+    /// Upstream failures are transient dependency probe errors, bind
+    /// failures keep their category, and anything else is reported as
+    /// invalid configuration.
+    fn from(err: SidecarError) -> Self {
+        match err {
+            SidecarError::Startup(err) => err,
+            SidecarError::Server { message } => Self::bind(message),
+            SidecarError::Upstream { .. }
+            | SidecarError::Request(_)
+            | SidecarError::HealthCheck { .. } => {
+                Self::dependency_probe(err.to_string()).transient()
+            }
+            err => Self::config_invalid(err.to_string()),
         }
     }
 }
@@ -283,6 +326,10 @@ mod tests {
         let err = SidecarError::validation("invalid JSON");
         assert_eq!(err.status_code(), 400);
 
+        let err = SidecarError::payload_too_large("part 'file' exceeds 1024 bytes");
+        assert_eq!(err.status_code(), 413);
+        assert_eq!(err.category(), "payload_too_large");
+
         let err = SidecarError::authorization_denied("insufficient permissions");
         assert_eq!(err.status_code(), 403);
     }
@@ -318,4 +365,26 @@ mod tests {
         let resp: ErrorResponse = err.into();
         assert_eq!(resp.error, "validation");
     }
+
+    #[test]
+    fn test_startup_error_mapping() {
+        use archimedes_core::StartupCategory;
+
+        let err = StartupError::from(SidecarError::config("upstream_url is required"));
+        assert_eq!(err.category(), StartupCategory::ConfigInvalid);
+        assert_eq!(err.exit_code(), 10);
+
+        let err = StartupError::from(SidecarError::server("failed to bind"));
+        assert_eq!(err.category(), StartupCategory::Bind);
+
+        let err = StartupError::from(SidecarError::upstream("connection refused"));
+        assert_eq!(err.category(), StartupCategory::DependencyProbe);
+        assert!(err.is_transient());
+
+        let contract = SidecarError::from(StartupError::contract_load("not found"));
+        assert_eq!(contract.category(), "startup");
+        let err = StartupError::from(contract);
+        assert_eq!(err.category(), StartupCategory::ContractLoad);
+        assert_eq!(err.detail(), "not found");
+    }
 }
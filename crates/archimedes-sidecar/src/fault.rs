@@ -0,0 +1,178 @@
+//! Fault injection into upstream calls.
+//!
+//! With the `chaos` feature, the sidecar reads the fault file of the
+//! Archimedes fault stage (see [`archimedes_middleware::stages::fault`])
+//! from `faults.path` and applies it to the calls it forwards, so services
+//! behind the sidecar can be tested against a slow or failing upstream:
+//!
+//! - `latency` delays the upstream call
+//! - `abort` answers with the configured status without calling upstream
+//! - `truncate` cuts bytes off the end of the upstream's response body
+//! - `reset` fails the call as if the upstream had reset the connection,
+//!   which the sidecar answers with `502 Bad Gateway`
+//!
+//! Rules match the operation ID of the request, or its path. Faults are
+//! logged, counted and named in the `X-Archimedes-Fault` header exactly as
+//! by the fault stage. The sidecar refuses to start with a fault file when
+//! `telemetry.environment` is `production`.
+//!
+//! ```toml
+//! [telemetry]
+//! environment = "staging"
+//!
+//! [faults]
+//! path = "/etc/archimedes/faults.toml"
+//! ```
+
+use archimedes_middleware::stages::fault::{
+    fault_header_value, refuse_production, FaultKind, Faults, FAULT_HEADER,
+};
+use bytes::Bytes;
+use http::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use http::{HeaderMap, HeaderValue};
+
+use crate::config::SidecarConfig;
+use crate::error::{SidecarError, SidecarResult};
+use crate::proxy::{ProxyClient, ProxyRequest, ProxyResponse};
+
+/// Loads the fault file the config names, if any.
+///
+/// Fails if the file cannot be loaded, or if the config sets one in the
+/// production environment.
+pub fn load(config: &SidecarConfig) -> SidecarResult<Option<Faults>> {
+    let Some(path) = &config.faults.path else {
+        return Ok(None);
+    };
+    refuse_production(&config.telemetry.environment)
+        .map_err(|e| SidecarError::config(e.to_string()))?;
+    let faults = Faults::from_toml_file(path).map_err(|e| SidecarError::config(e.to_string()))?;
+    tracing::warn!(path = %path.display(), "fault injection into upstream calls is enabled");
+    Ok(Some(faults))
+}
+
+/// Forwards a request, injecting the faults rolled for it.
+pub(crate) async fn forward(
+    client: &ProxyClient,
+    faults: &Faults,
+    request: ProxyRequest,
+) -> SidecarResult<ProxyResponse> {
+    let path = request.path.split('?').next().unwrap_or_default();
+    let plan = faults.plan(request.operation_id.as_deref(), path);
+    if plan.is_empty() {
+        return client.forward_upstream(request).await;
+    }
+
+    let operation = request
+        .operation_id
+        .clone()
+        .unwrap_or_else(|| "unknown".to_string());
+    let request_id = request.request_id().to_string();
+    let mut injected = Vec::new();
+
+    if let Some(delay) = plan.latency {
+        FaultKind::Latency.record(&operation, &request_id);
+        injected.push(FaultKind::Latency);
+        tokio::time::sleep(delay).await;
+    }
+
+    let mut result = if plan.reset {
+        FaultKind::Reset.record(&operation, &request_id);
+        Err(SidecarError::upstream(
+            "request failed: connection reset by fault injection",
+        ))
+    } else if let Some(status) = plan.abort {
+        FaultKind::Abort.record(&operation, &request_id);
+        injected.push(FaultKind::Abort);
+        let body = serde_json::json!({
+            "error": {
+                "code": "FAULT_INJECTED",
+                "message": "Upstream call aborted by fault injection",
+            }
+        });
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        Ok(ProxyResponse {
+            status,
+            headers,
+            body: Bytes::from(body.to_string()),
+        })
+    } else {
+        let mut result = client.forward_upstream(request).await;
+        if let (Some(bytes), Ok(response)) = (plan.truncate, &mut result) {
+            FaultKind::Truncate.record(&operation, &request_id);
+            injected.push(FaultKind::Truncate);
+            response.body = response
+                .body
+                .slice(..response.body.len().saturating_sub(bytes));
+            response.headers.remove(CONTENT_LENGTH);
+        }
+        result
+    };
+
+    if let Ok(response) = &mut result {
+        if !injected.is_empty() {
+            response
+                .headers
+                .insert(FAULT_HEADER, fault_header_value(&injected));
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use archimedes_middleware::stages::{FaultConfig, FaultRule};
+    use http::{Method, StatusCode};
+
+    fn config(environment: &str, path: &std::path::Path) -> SidecarConfig {
+        let mut config = SidecarConfig::default();
+        config.telemetry.environment = environment.to_string();
+        config.faults.path = Some(path.to_path_buf());
+        config
+    }
+
+    #[test]
+    fn test_load_refused_in_production() {
+        let path = std::env::temp_dir().join(format!(
+            "archimedes-sidecar-faults-{}.toml",
+            std::process::id()
+        ));
+        std::fs::write(&path, "enabled = true\n").unwrap();
+
+        assert!(load(&config("production", &path)).is_err());
+        assert!(load(&config("staging", &path)).unwrap().is_some());
+        assert!(load(&SidecarConfig::default()).unwrap().is_none());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_abort_skips_upstream() {
+        // Nothing listens on the upstream, so only an injected answer succeeds
+        let config = SidecarConfig::builder()
+            .upstream_url("http://127.0.0.1:1")
+            .build()
+            .unwrap();
+        let faults = Faults::new(
+            FaultConfig::new()
+                .enabled()
+                .rule(FaultRule::path("/items/*").abort(100.0, StatusCode::SERVICE_UNAVAILABLE))
+                .rule(FaultRule::operation("deleteItem").reset(100.0)),
+        );
+        let client = ProxyClient::new(&config).unwrap().with_faults(faults);
+
+        let response = client
+            .forward(ProxyRequest::new(Method::GET, "/items/1?full=true"))
+            .await
+            .unwrap();
+        assert_eq!(response.status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.header(FAULT_HEADER), Some("abort"));
+
+        let err = client
+            .forward(ProxyRequest::new(Method::DELETE, "/other").with_operation_id("deleteItem"))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status_code(), 502);
+    }
+}
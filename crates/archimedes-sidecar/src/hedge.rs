@@ -0,0 +1,294 @@
+//! Hedged requests for idempotent upstream calls.
+//!
+//! When a request to the upstream has not answered within the hedge delay,
+//! [`ProxyClient`](crate::ProxyClient) sends a second attempt and returns
+//! whichever answers first. The slower attempt is dropped, which cancels it.
+//!
+//! Hedging is bounded so it cannot double the upstream load during an
+//! incident:
+//!
+//! - Only idempotent methods are hedged, whatever the settings say.
+//! - A request is hedged at most once.
+//! - A global budget caps the hedges in flight across all requests.
+//! - Hedging is suspended while the upstream circuit is not closed.
+//!
+//! The hedge delay is either fixed or the configured quantile of recent
+//! upstream latencies.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, AtomicU8, AtomicUsize, Ordering};
+use std::time::Duration;
+
+use http::Method;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::config::HedgeSettings;
+
+/// State of the circuit breaker guarding the upstream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// Requests flow normally.
+    #[default]
+    Closed,
+    /// Requests are rejected.
+    Open,
+    /// Trial requests probe whether the upstream recovered.
+    HalfOpen,
+}
+
+impl CircuitState {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::Open,
+            2 => Self::HalfOpen,
+            _ => Self::Closed,
+        }
+    }
+
+    fn as_u8(self) -> u8 {
+        match self {
+            Self::Closed => 0,
+            Self::Open => 1,
+            Self::HalfOpen => 2,
+        }
+    }
+}
+
+/// Counters for hedged requests.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HedgeStats {
+    /// Hedge attempts sent.
+    pub hedges_fired: u64,
+    /// Requests answered by the hedge rather than the first attempt.
+    pub hedge_wins: u64,
+    /// Hedges not sent because the budget was exhausted.
+    pub hedges_over_budget: u64,
+}
+
+/// Decides when to hedge and keeps the latency window and counters.
+#[derive(Debug)]
+pub(crate) struct Hedger {
+    settings: HedgeSettings,
+    latencies: Mutex<VecDeque<Duration>>,
+    in_flight: AtomicUsize,
+    circuit: AtomicU8,
+    fired: AtomicU64,
+    wins: AtomicU64,
+    over_budget: AtomicU64,
+}
+
+impl Hedger {
+    pub(crate) fn new(settings: HedgeSettings) -> Self {
+        Self {
+            settings,
+            latencies: Mutex::new(VecDeque::new()),
+            in_flight: AtomicUsize::new(0),
+            circuit: AtomicU8::new(CircuitState::Closed.as_u8()),
+            fired: AtomicU64::new(0),
+            wins: AtomicU64::new(0),
+            over_budget: AtomicU64::new(0),
+        }
+    }
+
+    /// Whether a request with this method and operation may be hedged.
+    pub(crate) fn applies_to(&self, method: &Method, operation_id: Option<&str>) -> bool {
+        if !self.settings.enabled || !is_idempotent(method) {
+            return false;
+        }
+        if self.circuit_state() != CircuitState::Closed {
+            return false;
+        }
+        self.settings
+            .methods
+            .iter()
+            .any(|m| m.eq_ignore_ascii_case(method.as_str()))
+            || operation_id.is_some_and(|id| self.settings.operations.iter().any(|op| op == id))
+    }
+
+    /// Delay after which the hedge is sent.
+    pub(crate) fn delay(&self) -> Duration {
+        let Some(quantile) = self.settings.quantile else {
+            return self.settings.delay;
+        };
+        let latencies = self.latencies.lock();
+        if latencies.is_empty() || latencies.len() < self.settings.min_samples {
+            return self.settings.delay;
+        }
+        let mut sorted: Vec<Duration> = latencies.iter().copied().collect();
+        drop(latencies);
+        sorted.sort_unstable();
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let rank = (quantile * sorted.len() as f64).ceil() as usize;
+        sorted[rank.clamp(1, sorted.len()) - 1]
+    }
+
+    /// Add an upstream latency to the rolling window.
+    pub(crate) fn record_latency(&self, latency: Duration) {
+        if self.settings.quantile.is_none() {
+            return;
+        }
+        let mut latencies = self.latencies.lock();
+        latencies.push_back(latency);
+        while latencies.len() > self.settings.window_size.max(1) {
+            latencies.pop_front();
+        }
+    }
+
+    /// Take a slot from the hedge budget.
+    pub(crate) fn try_acquire(&self) -> Option<HedgePermit<'_>> {
+        let max = self.settings.max_concurrent_hedges;
+        let acquired = self
+            .in_flight
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |current| {
+                (current < max).then_some(current + 1)
+            })
+            .is_ok();
+        if acquired {
+            self.fired.fetch_add(1, Ordering::Relaxed);
+            metrics::counter!("archimedes_sidecar_hedges_fired_total").increment(1);
+            Some(HedgePermit { hedger: self })
+        } else {
+            self.over_budget.fetch_add(1, Ordering::Relaxed);
+            metrics::counter!("archimedes_sidecar_hedges_over_budget_total").increment(1);
+            None
+        }
+    }
+
+    /// Count a request answered by its hedge.
+    pub(crate) fn record_win(&self) {
+        self.wins.fetch_add(1, Ordering::Relaxed);
+        metrics::counter!("archimedes_sidecar_hedge_wins_total").increment(1);
+    }
+
+    pub(crate) fn circuit_state(&self) -> CircuitState {
+        CircuitState::from_u8(self.circuit.load(Ordering::Acquire))
+    }
+
+    pub(crate) fn set_circuit_state(&self, state: CircuitState) {
+        self.circuit.store(state.as_u8(), Ordering::Release);
+    }
+
+    pub(crate) fn stats(&self) -> HedgeStats {
+        HedgeStats {
+            hedges_fired: self.fired.load(Ordering::Relaxed),
+            hedge_wins: self.wins.load(Ordering::Relaxed),
+            hedges_over_budget: self.over_budget.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// A slot in the hedge budget, released on drop.
+pub(crate) struct HedgePermit<'a> {
+    hedger: &'a Hedger,
+}
+
+impl Drop for HedgePermit<'_> {
+    fn drop(&mut self) {
+        self.hedger.in_flight.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Whether repeating a request with this method is safe.
+pub fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::PUT | Method::DELETE
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> HedgeSettings {
+        HedgeSettings {
+            enabled: true,
+            ..HedgeSettings::default()
+        }
+    }
+
+    #[test]
+    fn test_non_idempotent_never_hedged() {
+        let hedger = Hedger::new(HedgeSettings {
+            methods: vec!["GET".to_string(), "POST".to_string(), "PATCH".to_string()],
+            operations: vec!["createUser".to_string()],
+            ..settings()
+        });
+
+        assert!(hedger.applies_to(&Method::GET, None));
+        assert!(!hedger.applies_to(&Method::POST, None));
+        assert!(!hedger.applies_to(&Method::POST, Some("createUser")));
+        assert!(!hedger.applies_to(&Method::PATCH, None));
+    }
+
+    #[test]
+    fn test_method_and_operation_enablement() {
+        let hedger = Hedger::new(HedgeSettings {
+            methods: Vec::new(),
+            operations: vec!["getUser".to_string()],
+            ..settings()
+        });
+
+        assert!(!hedger.applies_to(&Method::GET, None));
+        assert!(!hedger.applies_to(&Method::GET, Some("listUsers")));
+        assert!(hedger.applies_to(&Method::GET, Some("getUser")));
+
+        let disabled = Hedger::new(HedgeSettings::default());
+        assert!(!disabled.applies_to(&Method::GET, None));
+    }
+
+    #[test]
+    fn test_circuit_not_closed_disables_hedging() {
+        let hedger = Hedger::new(settings());
+        hedger.set_circuit_state(CircuitState::HalfOpen);
+        assert!(!hedger.applies_to(&Method::GET, None));
+
+        hedger.set_circuit_state(CircuitState::Open);
+        assert!(!hedger.applies_to(&Method::GET, None));
+
+        hedger.set_circuit_state(CircuitState::Closed);
+        assert!(hedger.applies_to(&Method::GET, None));
+    }
+
+    #[test]
+    fn test_quantile_delay() {
+        let hedger = Hedger::new(HedgeSettings {
+            delay: Duration::from_millis(500),
+            quantile: Some(0.9),
+            window_size: 10,
+            min_samples: 5,
+            ..settings()
+        });
+
+        for ms in 1..=4 {
+            hedger.record_latency(Duration::from_millis(ms));
+        }
+        assert_eq!(hedger.delay(), Duration::from_millis(500));
+
+        for ms in 5..=20 {
+            hedger.record_latency(Duration::from_millis(ms));
+        }
+        // Window holds 11..=20, so p90 is the 9th smallest
+        assert_eq!(hedger.delay(), Duration::from_millis(19));
+    }
+
+    #[test]
+    fn test_budget() {
+        let hedger = Hedger::new(HedgeSettings {
+            max_concurrent_hedges: 1,
+            ..settings()
+        });
+
+        let permit = hedger.try_acquire();
+        assert!(permit.is_some());
+        assert!(hedger.try_acquire().is_none());
+        drop(permit);
+        assert!(hedger.try_acquire().is_some());
+
+        let stats = hedger.stats();
+        assert_eq!(stats.hedges_fired, 2);
+        assert_eq!(stats.hedges_over_budget, 1);
+    }
+}
@@ -36,6 +36,10 @@
 //! - **Contract Validation**: Request/response validation against Themis contracts
 //! - **Policy Evaluation**: Authorization via embedded OPA with Eunomia policies
 //! - **Telemetry**: Automatic metrics, traces, and structured logging
+//! - **Request Transformation**: Declarative header, path and query rewrites for legacy clients
+//! - **Request Hedging**: Duplicate slow idempotent requests to cut the upstream latency tail
+//! - **Streaming Uploads**: Multipart bodies are validated and forwarded without buffering files
+//! - **Fault Injection**: Upstream latency, errors and resets for resilience testing (`chaos`)
 //! - **Hot Reload**: Configuration, contracts, and policies can be reloaded at runtime
 //!
 //! # Example Usage
@@ -56,18 +60,29 @@
 
 pub mod config;
 pub mod error;
+#[cfg(feature = "chaos")]
+pub mod fault;
 pub mod headers;
 pub mod health;
+pub mod hedge;
 pub mod middleware;
+pub mod multipart;
 pub mod proxy;
 pub mod server;
+pub mod transform;
 
-pub use config::{SidecarConfig, SidecarConfigBuilder};
+pub use config::{
+    FaultSettings, HedgeSettings, MultipartSettings, SidecarConfig, SidecarConfigBuilder,
+};
 pub use error::{SidecarError, SidecarResult};
 pub use health::{HealthChecker, HealthStatus, ReadinessStatus};
+pub use hedge::{CircuitState, HedgeStats};
 pub use middleware::{MiddlewarePipeline, MiddlewareResult};
-pub use proxy::{ProxyClient, ProxyRequest, ProxyResponse};
+pub use proxy::{BodyStream, ProxyClient, ProxyRequest, ProxyResponse};
 pub use server::SidecarServer;
+pub use transform::Transformer;
+
+pub use archimedes_core::{StartupCategory, StartupError};
 
 /// Sidecar version
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -3,11 +3,12 @@
 //! This is the main binary for the Archimedes sidecar proxy.
 
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use tracing::{error, info};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-use archimedes_sidecar::{SidecarConfig, SidecarServer};
+use archimedes_sidecar::{SidecarConfig, SidecarServer, StartupError, Transformer};
 
 /// Command-line arguments.
 struct Args {
@@ -66,6 +67,15 @@ ENVIRONMENT VARIABLES:
     ARCHIMEDES_SIDECAR_OTLP_ENDPOINT      OpenTelemetry collector endpoint
     ARCHIMEDES_SIDECAR_METRICS_PORT       Prometheus metrics port (default: 9090)
 
+EXIT CODES:
+    10    Invalid configuration
+    11    Contract could not be loaded
+    12    Policy bundle could not be loaded
+    13    Listen address could not be bound
+    14    TLS setup failed
+    15    Dependency probe failed
+    75    Transient failure, safe to retry
+
 EXAMPLES:
     # Run with configuration file
     archimedes-sidecar --config /etc/archimedes/sidecar.toml
@@ -93,15 +103,12 @@ async fn main() {
     let args = Args::parse();
 
     // Load configuration
-    let config = match args.config {
+    let config = match &args.config {
         Some(path) => {
             info!("Loading configuration from {:?}", path);
-            match SidecarConfig::from_file(&path) {
+            match SidecarConfig::from_file(path) {
                 Ok(config) => config.with_env_overrides(),
-                Err(e) => {
-                    error!("Failed to load configuration: {}", e);
-                    std::process::exit(1);
-                }
+                Err(e) => StartupError::from(e).exit(),
             }
         }
         None => {
@@ -112,8 +119,7 @@ async fn main() {
 
     // Validate configuration
     if let Err(e) = config.validate() {
-        error!("Invalid configuration: {}", e);
-        std::process::exit(1);
+        StartupError::from(e).exit();
     }
 
     info!(
@@ -129,14 +135,42 @@ async fn main() {
     // Create and run server
     let server = match SidecarServer::new(config) {
         Ok(server) => server,
-        Err(e) => {
-            error!("Failed to create server: {}", e);
-            std::process::exit(1);
-        }
+        Err(e) => e.exit(),
     };
 
+    if let Some(path) = args.config {
+        reload_transforms_on_hangup(path, server.transformer());
+    }
+
     if let Err(e) = server.run().await {
-        error!("Server error: {}", e);
-        std::process::exit(1);
+        e.exit();
     }
 }
+
+/// Reload the transformation rules from the configuration file on SIGHUP.
+#[cfg(unix)]
+fn reload_transforms_on_hangup(path: PathBuf, transformer: Arc<Transformer>) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    tokio::spawn(async move {
+        let mut hangup = match signal(SignalKind::hangup()) {
+            Ok(hangup) => hangup,
+            Err(e) => {
+                error!("Failed to listen for SIGHUP: {}", e);
+                return;
+            }
+        };
+
+        while hangup.recv().await.is_some() {
+            let reloaded = SidecarConfig::from_file(&path)
+                .and_then(|config| transformer.reload(&config.transforms));
+            match reloaded {
+                Ok(()) => info!("Reloaded {} transformation rules", transformer.rule_count()),
+                Err(e) => error!("Failed to reload transformation rules: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(not(unix))]
+fn reload_transforms_on_hangup(_path: PathBuf, _transformer: Arc<Transformer>) {}
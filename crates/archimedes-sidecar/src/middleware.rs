@@ -6,17 +6,28 @@
 use std::sync::Arc;
 
 use bytes::Bytes;
-use http::{Method, StatusCode};
-use serde_json::Value;
+use futures_util::Stream;
+use http::StatusCode;
+use serde_json::{Map, Value};
 use tracing::{debug, warn};
 
+#[cfg(any(feature = "sentinel", feature = "authz"))]
+use archimedes_core::StartupError;
+
 use crate::config::{SidecarConfig, ValidationMode};
 use crate::error::{SidecarError, SidecarResult};
 use crate::headers::PropagatedHeaders;
-use crate::proxy::ProxyRequest;
+use crate::multipart::{self, FormValidator};
+use crate::proxy::{BodyStream, ProxyRequest};
 
 #[cfg(feature = "sentinel")]
-use archimedes_sentinel::{ArtifactLoader, Sentinel, SentinelConfig};
+use archimedes_middleware::stages::RequestValidator;
+#[cfg(feature = "sentinel")]
+use archimedes_sentinel::coercion::coerce_value;
+#[cfg(feature = "sentinel")]
+use archimedes_sentinel::{
+    ArtifactLoader, OperationResolution, SchemaRef, Sentinel, SentinelConfig,
+};
 
 #[cfg(feature = "authz")]
 use archimedes_authz::{EvaluatorConfig, PolicyEvaluator};
@@ -47,9 +58,9 @@ impl MiddlewarePipeline {
         #[cfg(feature = "sentinel")]
         let sentinel = if let Some(ref path) = config.contract.path {
             debug!("Loading contract from {:?}", path);
-            let artifact = ArtifactLoader::from_file(path)
-                .await
-                .map_err(|e| SidecarError::config(format!("failed to load contract: {e}")))?;
+            let artifact = ArtifactLoader::from_file(path).await.map_err(|e| {
+                StartupError::contract_load(format!("failed to load contract: {e}"))
+            })?;
             Some(Arc::new(Sentinel::new(artifact, SentinelConfig::default())))
         } else {
             None
@@ -58,12 +69,13 @@ impl MiddlewarePipeline {
         #[cfg(feature = "authz")]
         let evaluator = if let Some(ref path) = config.policy.bundle_path {
             debug!("Loading policy bundle from {:?}", path);
-            let mut evaluator = PolicyEvaluator::new(EvaluatorConfig::default())
-                .map_err(|e| SidecarError::config(format!("failed to create evaluator: {e}")))?;
+            let mut evaluator = PolicyEvaluator::new(EvaluatorConfig::default()).map_err(|e| {
+                StartupError::policy_load(format!("failed to create evaluator: {e}"))
+            })?;
             evaluator
                 .load_bundle_from_file(path)
                 .await
-                .map_err(|e| SidecarError::config(format!("failed to load policy: {e}")))?;
+                .map_err(|e| StartupError::policy_load(format!("failed to load policy: {e}")))?;
             Some(Arc::new(parking_lot::RwLock::new(evaluator)))
         } else {
             None
@@ -78,6 +90,15 @@ impl MiddlewarePipeline {
         })
     }
 
+    /// Validate requests against `sentinel` instead of the configured
+    /// contract.
+    #[cfg(feature = "sentinel")]
+    #[must_use]
+    pub fn with_sentinel(mut self, sentinel: Sentinel) -> Self {
+        self.sentinel = Some(Arc::new(sentinel));
+        self
+    }
+
     /// Process a request through the middleware pipeline.
     ///
     /// Returns the processed request with any modifications, or an error
@@ -93,22 +114,21 @@ impl MiddlewarePipeline {
         // Try to match operation from contract
         #[cfg(feature = "sentinel")]
         if let Some(ref sentinel) = self.sentinel {
-            result.operation_id = self.match_operation(sentinel, &request.method, &request.path);
-
-            if let Some(ref operation_id) = result.operation_id {
+            if let Some(resolution) = Self::match_operation(sentinel, request) {
                 // Validate request against contract
-                if let Err(e) = self.validate_request(sentinel, operation_id, body) {
+                if let Err(e) = Self::validate_request(sentinel, &resolution, request, body) {
                     match self.config.contract.mode {
                         ValidationMode::Enforce => return Err(e),
                         ValidationMode::Monitor => {
                             warn!(
-                                operation_id = %operation_id,
+                                operation_id = %resolution.operation_id,
                                 error = %e,
                                 "Request validation failed (monitor mode)"
                             );
                         }
                     }
                 }
+                result.operation_id = Some(resolution.operation_id);
             }
         }
 
@@ -124,44 +144,147 @@ impl MiddlewarePipeline {
         Ok(result)
     }
 
+    /// Process a `multipart/form-data` request whose body is still
+    /// arriving.
+    ///
+    /// Resolves the operation and evaluates authorization like
+    /// [`process`](Self::process), and returns the body to forward: text
+    /// fields are validated against the operation's request schema as they
+    /// complete, while file parts stream through unbuffered. Without a
+    /// request schema to validate against, the body is forwarded untouched.
+    /// See [`crate::multipart`].
+    pub fn process_multipart<S, E>(
+        &self,
+        request: &ProxyRequest,
+        body: S,
+    ) -> SidecarResult<(MiddlewareResult, BodyStream)>
+    where
+        S: Stream<Item = Result<Bytes, E>> + Send + 'static,
+        E: Into<Box<dyn std::error::Error + Send + Sync>> + Send + 'static,
+    {
+        let mut result = MiddlewareResult::default();
+        #[allow(unused_mut)]
+        let mut validator: Option<Box<dyn FormValidator>> = None;
+
+        #[cfg(feature = "sentinel")]
+        if let Some(ref sentinel) = self.sentinel {
+            if let Some(resolution) = Self::match_operation(sentinel, request) {
+                if self.config.contract.validate_requests {
+                    validator = self.form_validator(sentinel, &resolution, request)?;
+                }
+                result.operation_id = Some(resolution.operation_id);
+            }
+        }
+
+        #[cfg(feature = "authz")]
+        if let Some(ref evaluator) = self.evaluator {
+            if self.config.policy.enabled {
+                let input = self.build_policy_input(request, result.operation_id.as_deref());
+                self.evaluate_policy(evaluator, &input)?;
+            }
+        }
+
+        let body = multipart::forward(
+            &request.headers,
+            body,
+            &self.config.sidecar.multipart,
+            validator,
+        )?;
+        Ok((result, body))
+    }
+
+    /// Validate the headers of a form request and build the validator for
+    /// its fields, if the operation has a request schema.
+    #[cfg(feature = "sentinel")]
+    fn form_validator(
+        &self,
+        sentinel: &Arc<Sentinel>,
+        resolution: &OperationResolution,
+        request: &ProxyRequest,
+    ) -> SidecarResult<Option<Box<dyn FormValidator>>> {
+        let headers = sentinel.validate_request_headers_for_version(
+            &resolution.version,
+            &resolution.operation_id,
+            &request.headers,
+        );
+        let error = match headers {
+            Ok(result) if !result.valid => result
+                .errors
+                .into_iter()
+                .next()
+                .map(|error| SidecarError::validation_with_field(error.message, error.path)),
+            Ok(_) => None,
+            Err(e) => Some(SidecarError::validation(e.to_string())),
+        };
+        if let Some(e) = error {
+            match self.config.contract.mode {
+                ValidationMode::Enforce => return Err(e),
+                ValidationMode::Monitor => {
+                    warn!(
+                        operation_id = %resolution.operation_id,
+                        error = %e,
+                        "Request validation failed (monitor mode)"
+                    );
+                }
+            }
+        }
+
+        let schema = sentinel
+            .artifact_for_version(&resolution.version)
+            .and_then(|artifact| {
+                artifact
+                    .operations
+                    .iter()
+                    .find(|op| op.id == resolution.operation_id)
+            })
+            .and_then(|op| op.request_schema.clone());
+        Ok(schema.map(|schema| {
+            Box::new(ContractFormValidator {
+                sentinel: sentinel.clone(),
+                version: resolution.version.clone(),
+                operation_id: resolution.operation_id.clone(),
+                schema,
+                mode: self.config.contract.mode,
+            }) as Box<dyn FormValidator>
+        }))
+    }
+
     /// Match the request to a contract operation.
     #[cfg(feature = "sentinel")]
-    fn match_operation(&self, sentinel: &Sentinel, method: &Method, path: &str) -> Option<String> {
+    fn match_operation(sentinel: &Sentinel, request: &ProxyRequest) -> Option<OperationResolution> {
+        let path = request.path.split('?').next().unwrap_or_default();
         sentinel
-            .resolve(method.as_str(), path)
+            .resolve_with_headers(request.method.as_str(), path, &request.headers)
             .ok()
-            .map(|r| r.operation_id)
     }
 
-    /// Validate request body against the contract schema.
+    /// Validate request headers and body against the contract schema.
+    ///
+    /// Uses the same validation as the server's `ValidationMiddleware`, so
+    /// a request is rejected with the same message either way.
     #[cfg(feature = "sentinel")]
     fn validate_request(
-        &self,
         sentinel: &Sentinel,
-        operation_id: &str,
+        resolution: &OperationResolution,
+        request: &ProxyRequest,
         body: &Bytes,
     ) -> SidecarResult<()> {
-        if body.is_empty() {
-            return Ok(());
-        }
-
-        let body_json: Value = serde_json::from_slice(body)
-            .map_err(|e| SidecarError::validation(format!("invalid JSON body: {e}")))?;
-
-        let result = sentinel
-            .validate_request(operation_id, &body_json)
-            .map_err(|e| SidecarError::validation(e.to_string()))?;
-
-        if !result.valid {
-            let errors: Vec<String> = result
-                .errors
-                .iter()
-                .map(|e| format!("{}: {}", e.path, e.message))
-                .collect();
-            return Err(SidecarError::validation(errors.join("; ")));
+        let result = RequestValidator::validate_request(
+            sentinel,
+            &resolution.operation_id,
+            Some(&resolution.version),
+            &request.headers,
+            body,
+        );
+
+        match result.errors.into_iter().next() {
+            Some(error) if !result.valid => Err(if error.field.is_empty() {
+                SidecarError::validation(error.message)
+            } else {
+                SidecarError::validation_with_field(error.message, error.field)
+            }),
+            _ => Ok(()),
         }
-
-        Ok(())
     }
 
     /// Build policy input for authorization evaluation.
@@ -258,6 +381,59 @@ impl MiddlewarePipeline {
     }
 }
 
+/// Validates form fields against an operation's request schema.
+#[cfg(feature = "sentinel")]
+struct ContractFormValidator {
+    sentinel: Arc<Sentinel>,
+    version: String,
+    operation_id: String,
+    schema: SchemaRef,
+    mode: ValidationMode,
+}
+
+#[cfg(feature = "sentinel")]
+impl FormValidator for ContractFormValidator {
+    fn validate(&mut self, fields: &Map<String, Value>, complete: bool) -> SidecarResult<()> {
+        // Monitor mode only reports, so reporting the whole form once is enough
+        if !complete && self.mode == ValidationMode::Monitor {
+            return Ok(());
+        }
+
+        // Form values arrive as strings, whatever type the schema declares
+        let mut body = Value::Object(fields.clone());
+        coerce_value(&mut body, &self.schema);
+        let result = self
+            .sentinel
+            .validate_request_for_version(&self.version, &self.operation_id, &body)
+            .map_err(|e| SidecarError::validation(e.to_string()))?;
+
+        // Until every part has arrived, only the fields received are judged
+        let Some(error) = result.errors.into_iter().find(|error| {
+            let field = error.path.split(['.', '[']).next().unwrap_or_default();
+            complete || fields.contains_key(field)
+        }) else {
+            return Ok(());
+        };
+        let e = if error.path.is_empty() {
+            SidecarError::validation(error.message)
+        } else {
+            SidecarError::validation_with_field(error.message, error.path)
+        };
+
+        match self.mode {
+            ValidationMode::Enforce => Err(e),
+            ValidationMode::Monitor => {
+                warn!(
+                    operation_id = %self.operation_id,
+                    error = %e,
+                    "Request validation failed (monitor mode)"
+                );
+                Ok(())
+            }
+        }
+    }
+}
+
 /// Result of middleware processing.
 #[derive(Debug, Default)]
 pub struct MiddlewareResult {
@@ -277,6 +453,7 @@ impl MiddlewareResult {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use http::Method;
 
     #[tokio::test]
     async fn test_middleware_pipeline_creation() {
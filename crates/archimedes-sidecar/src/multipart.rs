@@ -0,0 +1,576 @@
+//! Streaming `multipart/form-data` forwarding.
+//!
+//! File uploads can be far larger than the sidecar should hold in memory, so
+//! multipart bodies are parsed as they arrive, with the same incremental
+//! parser as the native [`Multipart`] extractor, and forwarded to the
+//! upstream while they are still being received:
+//!
+//! - With request validation on, text fields are buffered, up to
+//!   `max_field_size` each, and checked by a [`FormValidator`] as soon as
+//!   they are complete. File parts are never buffered: they are re-encoded
+//!   with the original boundary and their chunks streamed straight through.
+//!   Checks that need every field, such as required fields, run before the
+//!   closing boundary is forwarded.
+//! - With validation off, the body is forwarded untouched.
+//!
+//! Either way the [`MultipartSettings`] limits on part size, total size and
+//! part count are enforced while streaming. A body that breaks a limit or
+//! fails validation aborts the upstream request, so the upstream never sees
+//! it complete, and the client is answered with `413` or `400`.
+
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use archimedes_extract::{ExtractionError, Field, Multipart, MultipartConfig};
+use bytes::{BufMut, Bytes, BytesMut};
+use futures_util::{Stream, StreamExt};
+use http::header::{HeaderMap, CONTENT_TYPE};
+use parking_lot::Mutex;
+use serde_json::{Map, Value};
+use tokio::sync::mpsc;
+use tracing::debug;
+
+use crate::config::MultipartSettings;
+use crate::error::{SidecarError, SidecarResult};
+use crate::proxy::BodyStream;
+
+/// Chunks produced ahead of the upstream before parsing waits for it.
+const BODY_BUFFER: usize = 4;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Checks the text fields of a form as they arrive.
+pub trait FormValidator: Send {
+    /// Validate the fields received so far, keyed by field name.
+    ///
+    /// `complete` is `false` while parts are still arriving, when errors
+    /// about fields not received yet, such as a missing required field,
+    /// must be ignored, and `true` once every part has arrived.
+    fn validate(&mut self, fields: &Map<String, Value>, complete: bool) -> SidecarResult<()>;
+}
+
+/// Check if a request body is `multipart/form-data`.
+pub fn is_multipart(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(';').next())
+        .is_some_and(|essence| essence.trim().eq_ignore_ascii_case("multipart/form-data"))
+}
+
+/// Start forwarding a multipart body, returning the body to send upstream.
+///
+/// The body is parsed on a spawned task, which stops when the returned
+/// stream is dropped. Without a `validator` the body is forwarded
+/// untouched.
+///
+/// # Errors
+///
+/// Returns an error if the Content-Type header has no valid boundary.
+pub fn forward<S, E>(
+    headers: &HeaderMap,
+    body: S,
+    settings: &MultipartSettings,
+    validator: Option<Box<dyn FormValidator>>,
+) -> SidecarResult<BodyStream>
+where
+    S: Stream<Item = Result<Bytes, E>> + Send + 'static,
+    E: Into<Box<dyn std::error::Error + Send + Sync>> + Send + 'static,
+{
+    let boundary =
+        Multipart::boundary(headers).map_err(|e| SidecarError::validation(e.to_string()))?;
+
+    let source = Arc::new(Mutex::new(Source {
+        body: Box::pin(body.map(|chunk| chunk.map_err(Into::<BoxError>::into))),
+        received: 0,
+        pending: validator.is_none().then(Vec::new),
+    }));
+    let parsed = {
+        let source = source.clone();
+        futures_util::stream::poll_fn(move |cx| Source::poll_chunk(&source, cx))
+    };
+
+    // The part count is enforced here, so every part is read to the end
+    let config = MultipartConfig::new()
+        .max_body_size(usize::try_from(settings.max_total_size).unwrap_or(usize::MAX))
+        .max_field_size(settings.max_field_size)
+        .max_fields(usize::MAX);
+    let multipart = Multipart::from_stream(headers, parsed, config)
+        .map_err(|e| SidecarError::validation(e.to_string()))?;
+
+    let (sender, stream) = BodyStream::channel(BODY_BUFFER);
+    let forwarder = Forwarder {
+        multipart,
+        boundary,
+        settings: settings.clone(),
+        source,
+        validator,
+        fields: Map::new(),
+        sender,
+    };
+    tokio::spawn(forwarder.run());
+
+    Ok(stream)
+}
+
+/// The incoming body, read by the parser and, once it is done, by the
+/// forwarder.
+struct Source {
+    body: Pin<Box<dyn Stream<Item = Result<Bytes, BoxError>> + Send>>,
+    /// Bytes read so far.
+    received: u64,
+    /// Chunks read but not yet forwarded, when forwarding them untouched.
+    pending: Option<Vec<Bytes>>,
+}
+
+impl Source {
+    fn poll_chunk(
+        source: &Mutex<Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, BoxError>>> {
+        let mut source = source.lock();
+        let polled = source.body.as_mut().poll_next(cx);
+        if let Poll::Ready(Some(Ok(chunk))) = &polled {
+            source.received += chunk.len() as u64;
+            if let Some(pending) = &mut source.pending {
+                pending.push(chunk.clone());
+            }
+        }
+        polled
+    }
+}
+
+/// Parses a multipart body and produces the body forwarded upstream.
+struct Forwarder {
+    multipart: Multipart,
+    boundary: String,
+    settings: MultipartSettings,
+    source: Arc<Mutex<Source>>,
+    validator: Option<Box<dyn FormValidator>>,
+    /// Text field values and file names received so far.
+    fields: Map<String, Value>,
+    sender: mpsc::Sender<SidecarResult<Bytes>>,
+}
+
+impl Forwarder {
+    /// Forward every part, then end the body with an error if one failed.
+    async fn run(mut self) {
+        if let Err(e) = self.forward_parts().await {
+            debug!(error = %e, "multipart forwarding stopped");
+            let _ = self.sender.send(Err(e)).await;
+        }
+    }
+
+    async fn forward_parts(&mut self) -> SidecarResult<()> {
+        let mut parts = 0;
+        while let Some(field) = self.next_field().await? {
+            parts += 1;
+            if parts > self.settings.max_parts {
+                return Err(SidecarError::payload_too_large(format!(
+                    "too many parts (max {})",
+                    self.settings.max_parts
+                )));
+            }
+
+            let name = field.name().unwrap_or_default().to_string();
+            if self.validator.is_some() && field.file_name().is_none() {
+                self.forward_text_field(field, name).await?;
+            } else {
+                self.forward_part(field, &name).await?;
+            }
+        }
+
+        if let Some(validator) = &mut self.validator {
+            validator.validate(&self.fields, true)?;
+        }
+        if self.is_raw() {
+            // The parser stops at the closing boundary, before any epilogue
+            self.flush_raw().await?;
+            while let Some(chunk) =
+                std::future::poll_fn(|cx| Source::poll_chunk(&self.source, cx)).await
+            {
+                chunk.map_err(|e| {
+                    SidecarError::validation(format!("failed to read request body: {e}"))
+                })?;
+                self.check_total_size()?;
+                self.flush_raw().await?;
+            }
+            Ok(())
+        } else {
+            self.emit(Bytes::from(format!("--{}--\r\n", self.boundary)))
+                .await
+        }
+    }
+
+    /// Buffer a text field, validate it, then forward it re-encoded.
+    async fn forward_text_field(&mut self, mut field: Field, name: String) -> SidecarResult<()> {
+        let head = part_head(&self.boundary, field.headers());
+        let mut data = BytesMut::new();
+        while let Some(chunk) = self.chunk(&mut field).await? {
+            if data.len() + chunk.len() > self.settings.max_field_size {
+                return Err(SidecarError::payload_too_large(format!(
+                    "field '{name}' exceeds {} bytes",
+                    self.settings.max_field_size
+                )));
+            }
+            data.extend_from_slice(&chunk);
+        }
+        self.check_part_size(&name, data.len() as u64)?;
+
+        let data = data.freeze();
+        let text = std::str::from_utf8(&data).map_err(|_| {
+            SidecarError::validation_with_field(format!("field '{name}' is not valid UTF-8"), &name)
+        })?;
+        self.fields.insert(name, Value::String(text.to_string()));
+        if let Some(validator) = &mut self.validator {
+            validator.validate(&self.fields, false)?;
+        }
+
+        self.emit(head).await?;
+        self.emit(data).await?;
+        self.emit(Bytes::from_static(b"\r\n")).await
+    }
+
+    /// Forward a part chunk by chunk, without buffering it.
+    async fn forward_part(&mut self, mut field: Field, name: &str) -> SidecarResult<()> {
+        if let Some(validator) = &mut self.validator {
+            let file_name = field.file_name().unwrap_or_default().to_string();
+            self.fields
+                .insert(name.to_string(), Value::String(file_name));
+            validator.validate(&self.fields, false)?;
+        }
+        if !self.is_raw() {
+            self.emit(part_head(&self.boundary, field.headers()))
+                .await?;
+        }
+
+        let mut size = 0;
+        while let Some(chunk) = self.chunk(&mut field).await? {
+            size += chunk.len() as u64;
+            self.check_part_size(name, size)?;
+            if self.is_raw() {
+                self.flush_raw().await?;
+            } else {
+                self.emit(chunk).await?;
+            }
+        }
+
+        if !self.is_raw() {
+            self.emit(Bytes::from_static(b"\r\n")).await?;
+        }
+        Ok(())
+    }
+
+    /// Check if the body is forwarded untouched.
+    fn is_raw(&self) -> bool {
+        self.validator.is_none()
+    }
+
+    async fn next_field(&mut self) -> SidecarResult<Option<Field>> {
+        let field = self
+            .multipart
+            .next_field()
+            .await
+            .map_err(|e| self.parse_error(&e))?;
+        self.check_total_size()?;
+        Ok(field)
+    }
+
+    async fn chunk(&self, field: &mut Field) -> SidecarResult<Option<Bytes>> {
+        let chunk = field.chunk().await.map_err(|e| self.parse_error(&e))?;
+        self.check_total_size()?;
+        Ok(chunk)
+    }
+
+    /// Map a parse error, which the body size limit also surfaces as.
+    fn parse_error(&self, e: &ExtractionError) -> SidecarError {
+        self.check_total_size()
+            .err()
+            .unwrap_or_else(|| SidecarError::validation(e.to_string()))
+    }
+
+    fn check_total_size(&self) -> SidecarResult<()> {
+        if self.source.lock().received > self.settings.max_total_size {
+            return Err(SidecarError::payload_too_large(format!(
+                "multipart body exceeds {} bytes",
+                self.settings.max_total_size
+            )));
+        }
+        Ok(())
+    }
+
+    fn check_part_size(&self, name: &str, size: u64) -> SidecarResult<()> {
+        if size > self.settings.max_part_size {
+            return Err(SidecarError::payload_too_large(format!(
+                "part '{name}' exceeds {} bytes",
+                self.settings.max_part_size
+            )));
+        }
+        Ok(())
+    }
+
+    /// Forward the incoming chunks read so far, untouched.
+    async fn flush_raw(&self) -> SidecarResult<()> {
+        let chunks = self
+            .source
+            .lock()
+            .pending
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default();
+        for chunk in chunks {
+            self.emit(chunk).await?;
+        }
+        Ok(())
+    }
+
+    async fn emit(&self, chunk: Bytes) -> SidecarResult<()> {
+        self.sender
+            .send(Ok(chunk))
+            .await
+            .map_err(|_| SidecarError::proxy("upstream request closed"))
+    }
+}
+
+/// Encode the delimiter and headers that open a part.
+fn part_head(boundary: &str, headers: &HeaderMap) -> Bytes {
+    let mut head = BytesMut::new();
+    head.put_slice(b"--");
+    head.put_slice(boundary.as_bytes());
+    head.put_slice(b"\r\n");
+    for (name, value) in headers {
+        head.put_slice(name.as_str().as_bytes());
+        head.put_slice(b": ");
+        head.put_slice(value.as_bytes());
+        head.put_slice(b"\r\n");
+    }
+    head.put_slice(b"\r\n");
+    head.freeze()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    const BOUNDARY: &str = "----sidecar";
+
+    fn headers() -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            CONTENT_TYPE,
+            format!("multipart/form-data; boundary={BOUNDARY}")
+                .parse()
+                .unwrap(),
+        );
+        headers
+    }
+
+    /// Encode `parts` of (name, file name, data) as a form body.
+    fn form(parts: &[(&str, Option<&str>, &[u8])]) -> Vec<u8> {
+        let mut body = Vec::new();
+        for (name, file_name, data) in parts {
+            body.extend_from_slice(format!("--{BOUNDARY}\r\n").as_bytes());
+            match file_name {
+                Some(file_name) => body.extend_from_slice(
+                    format!(
+                        "content-disposition: form-data; name=\"{name}\"; filename=\"{file_name}\"\r\n\
+                         content-type: application/octet-stream\r\n\r\n"
+                    )
+                    .as_bytes(),
+                ),
+                None => body.extend_from_slice(
+                    format!("content-disposition: form-data; name=\"{name}\"\r\n\r\n").as_bytes(),
+                ),
+            }
+            body.extend_from_slice(data);
+            body.extend_from_slice(b"\r\n");
+        }
+        body.extend_from_slice(format!("--{BOUNDARY}--\r\n").as_bytes());
+        body
+    }
+
+    /// Deliver `body` in small chunks, splitting parts across them.
+    fn chunked(body: &[u8]) -> impl Stream<Item = Result<Bytes, io::Error>> + Send + 'static {
+        let chunks: Vec<_> = body
+            .chunks(7)
+            .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+            .collect();
+        futures_util::stream::iter(chunks)
+    }
+
+    /// Collect the forwarded body, or the error that ended it.
+    async fn collect(mut stream: BodyStream) -> SidecarResult<Vec<u8>> {
+        let mut body = Vec::new();
+        while let Some(chunk) = stream.next().await {
+            body.extend_from_slice(&chunk?);
+        }
+        Ok(body)
+    }
+
+    /// Rejects a `count` field that is not a number.
+    struct CountValidator;
+
+    impl FormValidator for CountValidator {
+        fn validate(&mut self, fields: &Map<String, Value>, complete: bool) -> SidecarResult<()> {
+            match fields.get("count").and_then(Value::as_str) {
+                Some(count) if count.parse::<u32>().is_err() => Err(
+                    SidecarError::validation_with_field("count must be a number", "count"),
+                ),
+                None if complete => Err(SidecarError::validation_with_field(
+                    "missing required field 'count'",
+                    "count",
+                )),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_is_multipart() {
+        assert!(is_multipart(&headers()));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            CONTENT_TYPE,
+            "Multipart/Form-Data; boundary=x".parse().unwrap(),
+        );
+        assert!(is_multipart(&headers));
+        headers.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+        assert!(!is_multipart(&headers));
+        assert!(!is_multipart(&HeaderMap::new()));
+    }
+
+    #[tokio::test]
+    async fn test_body_forwarded_untouched_without_validation() {
+        // A preamble is not part of any field, but is still forwarded
+        let mut body = b"preamble\r\n".to_vec();
+        body.extend(form(&[
+            ("count", None, b"three"),
+            ("photo", Some("beach.png"), b"PNG_DATA"),
+        ]));
+
+        let stream = forward(
+            &headers(),
+            chunked(&body),
+            &MultipartSettings::default(),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(collect(stream).await.unwrap(), body);
+    }
+
+    #[tokio::test]
+    async fn test_validated_form_reencoded() {
+        let body = form(&[
+            ("count", None, b"3"),
+            ("photo", Some("beach.png"), b"PNG_DATA"),
+        ]);
+
+        let stream = forward(
+            &headers(),
+            chunked(&body),
+            &MultipartSettings::default(),
+            Some(Box::new(CountValidator)),
+        )
+        .unwrap();
+        let forwarded = collect(stream).await.unwrap();
+
+        // The re-encoded body parses to the same fields
+        let mut multipart =
+            Multipart::from_stream(&headers(), chunked(&forwarded), MultipartConfig::default())
+                .unwrap();
+        let field = multipart.next_field().await.unwrap().unwrap();
+        assert_eq!(field.name(), Some("count"));
+        assert_eq!(field.text().await.unwrap(), "3");
+        let field = multipart.next_field().await.unwrap().unwrap();
+        assert_eq!(field.file_name(), Some("beach.png"));
+        assert_eq!(field.bytes().await.unwrap(), "PNG_DATA");
+        assert!(multipart.next_field().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_field_ends_body_with_error() {
+        let body = form(&[
+            ("count", None, b"three"),
+            ("photo", Some("beach.png"), b"PNG_DATA"),
+        ]);
+        let stream = forward(
+            &headers(),
+            chunked(&body),
+            &MultipartSettings::default(),
+            Some(Box::new(CountValidator)),
+        )
+        .unwrap();
+        let err = collect(stream).await.unwrap_err();
+        assert!(matches!(err, SidecarError::Validation { field: Some(ref f), .. } if f == "count"));
+
+        // Required fields are only checked once every part has arrived
+        let body = form(&[("photo", Some("beach.png"), b"PNG_DATA")]);
+        let stream = forward(
+            &headers(),
+            chunked(&body),
+            &MultipartSettings::default(),
+            Some(Box::new(CountValidator)),
+        )
+        .unwrap();
+        let err = collect(stream).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Validation error: missing required field 'count'"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_limits_enforced_in_both_modes() {
+        let body = form(&[
+            ("count", None, b"3"),
+            ("photo", Some("beach.png"), &[0; 64]),
+        ]);
+        let limits = [
+            MultipartSettings {
+                max_part_size: 32,
+                ..MultipartSettings::default()
+            },
+            MultipartSettings {
+                max_total_size: 100,
+                ..MultipartSettings::default()
+            },
+            MultipartSettings {
+                max_parts: 1,
+                ..MultipartSettings::default()
+            },
+        ];
+
+        for settings in &limits {
+            let unvalidated = forward(&headers(), chunked(&body), settings, None).unwrap();
+            let validated = forward(
+                &headers(),
+                chunked(&body),
+                settings,
+                Some(Box::new(CountValidator)),
+            )
+            .unwrap();
+            for stream in [unvalidated, validated] {
+                let err = collect(stream).await.unwrap_err();
+                assert_eq!(err.status_code(), 413, "{settings:?}: {err}");
+            }
+        }
+
+        let settings = MultipartSettings {
+            max_field_size: 2,
+            ..MultipartSettings::default()
+        };
+        let body = form(&[("count", None, b"300")]);
+        let stream = forward(
+            &headers(),
+            chunked(&body),
+            &settings,
+            Some(Box::new(CountValidator)),
+        )
+        .unwrap();
+        assert_eq!(collect(stream).await.unwrap_err().status_code(), 413);
+    }
+}
@@ -1,15 +1,25 @@
 //! HTTP proxy client for forwarding requests to upstream services.
 
-use std::time::Duration;
-
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "chaos")]
+use archimedes_middleware::stages::Faults;
 use bytes::Bytes;
-use http::{header::HeaderMap, Method, StatusCode};
-use reqwest::Client;
+use futures_util::{Stream, StreamExt};
+use http::header::{HeaderMap, CONTENT_LENGTH};
+use http::{Method, StatusCode};
+use reqwest::{Client, RequestBuilder};
 use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, oneshot};
 
 use crate::config::SidecarConfig;
 use crate::error::{SidecarError, SidecarResult};
 use crate::headers::{filter_headers_for_upstream, PropagatedHeaders};
+use crate::hedge::{CircuitState, HedgeStats, Hedger};
 
 /// HTTP proxy client for forwarding requests to upstream.
 #[derive(Debug, Clone)]
@@ -20,6 +30,19 @@ pub struct ProxyClient {
     upstream_url: String,
     /// Request timeout.
     timeout: Duration,
+    /// Hedging policy, latency window and counters.
+    hedger: Arc<Hedger>,
+    /// Faults injected into upstream calls.
+    #[cfg(feature = "chaos")]
+    faults: Option<Faults>,
+}
+
+/// A request ready to send, kept so it can be sent twice when hedged.
+struct Attempt {
+    method: Method,
+    url: String,
+    headers: HeaderMap,
+    body: Option<Bytes>,
 }
 
 impl ProxyClient {
@@ -35,28 +58,60 @@ impl ProxyClient {
             client,
             upstream_url: config.sidecar.upstream_url.clone(),
             timeout: config.sidecar.upstream_timeout,
+            hedger: Arc::new(Hedger::new(config.sidecar.hedging.clone())),
+            #[cfg(feature = "chaos")]
+            faults: None,
         })
     }
 
+    /// Inject faults into upstream calls; see [`crate::fault`].
+    #[cfg(feature = "chaos")]
+    #[must_use]
+    pub fn with_faults(mut self, faults: Faults) -> Self {
+        self.faults = Some(faults);
+        self
+    }
+
+    /// Get the faults injected into upstream calls, for reloading.
+    #[cfg(feature = "chaos")]
+    pub fn faults(&self) -> Option<&Faults> {
+        self.faults.as_ref()
+    }
+
     /// Forward a request to the upstream service.
+    ///
+    /// Requests eligible for hedging are sent a second time if the upstream
+    /// has not answered within the hedge delay; see [`crate::hedge`].
+    /// Requests with a [`BodyStream`] are sent with chunked transfer
+    /// encoding and never hedged, since their body cannot be replayed.
     pub async fn forward(&self, request: ProxyRequest) -> SidecarResult<ProxyResponse> {
-        let url = format!("{}{}", self.upstream_url, request.path);
-
-        let mut req_builder = match request.method {
-            Method::GET => self.client.get(&url),
-            Method::POST => self.client.post(&url),
-            Method::PUT => self.client.put(&url),
-            Method::DELETE => self.client.delete(&url),
-            Method::PATCH => self.client.patch(&url),
-            Method::HEAD => self.client.head(&url),
-            Method::OPTIONS => self.client.request(Method::OPTIONS, &url),
-            _ => {
-                return Err(SidecarError::proxy(format!(
-                    "unsupported method: {}",
-                    request.method
-                )));
-            }
-        };
+        #[cfg(feature = "chaos")]
+        if let Some(faults) = &self.faults {
+            return crate::fault::forward(self, faults, request).await;
+        }
+        self.forward_upstream(request).await
+    }
+
+    /// Forward a request to the upstream service, without injected faults.
+    pub(crate) async fn forward_upstream(
+        &self,
+        request: ProxyRequest,
+    ) -> SidecarResult<ProxyResponse> {
+        if !matches!(
+            request.method,
+            Method::GET
+                | Method::POST
+                | Method::PUT
+                | Method::DELETE
+                | Method::PATCH
+                | Method::HEAD
+                | Method::OPTIONS
+        ) {
+            return Err(SidecarError::proxy(format!(
+                "unsupported method: {}",
+                request.method
+            )));
+        }
 
         // Add filtered headers
         let mut headers = filter_headers_for_upstream(&request.headers);
@@ -64,18 +119,132 @@ impl ProxyClient {
         // Add propagated headers
         request.propagated.add_to_headers(&mut headers);
 
-        // Set headers on request
-        for (name, value) in headers {
-            if let Some(name) = name {
-                req_builder = req_builder.header(name, value);
+        // A streamed body may not keep the length the client announced
+        if request.body_stream.is_some() {
+            headers.remove(CONTENT_LENGTH);
+        }
+
+        let hedge = self
+            .hedger
+            .applies_to(&request.method, request.operation_id.as_deref());
+        let attempt = Attempt {
+            url: format!("{}{}", self.upstream_url, request.path),
+            method: request.method,
+            headers,
+            body: request.body,
+        };
+
+        if let Some(body) = request.body_stream {
+            self.send_streaming(&attempt, body).await
+        } else if hedge {
+            self.send_hedged(&attempt).await
+        } else {
+            self.send(&attempt).await
+        }
+    }
+
+    /// Send `attempt`, and a hedge if it is slower than the hedge delay.
+    async fn send_hedged(&self, attempt: &Attempt) -> SidecarResult<ProxyResponse> {
+        let start = Instant::now();
+        let first = self.send(attempt);
+        tokio::pin!(first);
+
+        tokio::select! {
+            result = &mut first => {
+                self.hedger.record_latency(start.elapsed());
+                return result;
             }
+            () = tokio::time::sleep(self.hedger.delay()) => {}
         }
 
+        // The circuit may have left the closed state while we waited
+        let permit = if self.hedger.circuit_state() == CircuitState::Closed {
+            self.hedger.try_acquire()
+        } else {
+            None
+        };
+        let Some(_permit) = permit else {
+            let result = first.await;
+            self.hedger.record_latency(start.elapsed());
+            return result;
+        };
+
+        let hedge = self.send(attempt);
+        tokio::pin!(hedge);
+
+        let (winner, hedge_first) = tokio::select! {
+            result = &mut first => (result, false),
+            result = &mut hedge => (result, true),
+        };
+        // A failed attempt does not win while the other may still succeed
+        let (result, hedge_won) = match winner {
+            Ok(response) => (Ok(response), hedge_first),
+            Err(_) if hedge_first => (first.await, false),
+            Err(_) => (hedge.await, true),
+        };
+        if hedge_won && result.is_ok() {
+            self.hedger.record_win();
+        }
+        self.hedger.record_latency(start.elapsed());
+
+        // Returning drops the losing attempt, which cancels it
+        result
+    }
+
+    /// Send a single attempt to the upstream.
+    async fn send(&self, attempt: &Attempt) -> SidecarResult<ProxyResponse> {
+        let mut req_builder = self.request(attempt);
+
         // Add body if present
-        if let Some(body) = request.body {
-            req_builder = req_builder.body(body);
+        if let Some(body) = &attempt.body {
+            req_builder = req_builder.body(body.clone());
         }
 
+        Self::execute(req_builder).await
+    }
+
+    /// Send an attempt with a streamed body.
+    ///
+    /// If the body fails, the upstream request is dropped, which aborts it,
+    /// and the body's error is returned.
+    async fn send_streaming(
+        &self,
+        attempt: &Attempt,
+        body: BodyStream,
+    ) -> SidecarResult<ProxyResponse> {
+        let (failed_tx, failed_rx) = oneshot::channel();
+        let mut failed_tx = Some(failed_tx);
+        let chunks = body.map(move |chunk| {
+            chunk.map_err(|e| {
+                let message = e.to_string();
+                if let Some(failed) = failed_tx.take() {
+                    let _ = failed.send(e);
+                }
+                io::Error::other(message)
+            })
+        });
+        let req_builder = self
+            .request(attempt)
+            .body(reqwest::Body::wrap_stream(chunks));
+
+        tokio::select! {
+            // Checked first, so the body's error wins over the upload error
+            // it causes
+            biased;
+            Ok(e) = failed_rx => Err(e),
+            result = Self::execute(req_builder) => result,
+        }
+    }
+
+    /// Build the upstream request for an attempt, without its body.
+    fn request(&self, attempt: &Attempt) -> RequestBuilder {
+        self.client
+            .request(attempt.method.clone(), &attempt.url)
+            .headers(attempt.headers.clone())
+    }
+
+    /// Send a request and read the upstream's response.
+    async fn execute(req_builder: RequestBuilder) -> SidecarResult<ProxyResponse> {
         // Send request
         let response = req_builder
             .send()
@@ -108,6 +277,24 @@ impl ProxyClient {
     pub fn timeout(&self) -> Duration {
         self.timeout
     }
+
+    /// Get the hedged request counters.
+    pub fn hedge_stats(&self) -> HedgeStats {
+        self.hedger.stats()
+    }
+
+    /// Get the upstream circuit state last reported to the client.
+    pub fn circuit_state(&self) -> CircuitState {
+        self.hedger.circuit_state()
+    }
+
+    /// Report the state of the circuit breaker guarding the upstream.
+    ///
+    /// Hedging is suspended unless the circuit is closed, so trial requests
+    /// of a half-open circuit are not doubled.
+    pub fn set_circuit_state(&self, state: CircuitState) {
+        self.hedger.set_circuit_state(state);
+    }
 }
 
 /// Request to be forwarded to upstream.
@@ -121,8 +308,12 @@ pub struct ProxyRequest {
     pub headers: HeaderMap,
     /// Request body.
     pub body: Option<Bytes>,
+    /// Request body streamed to the upstream, sent instead of `body`.
+    pub body_stream: Option<BodyStream>,
     /// Headers to propagate.
     pub propagated: PropagatedHeaders,
+    /// Matched contract operation ID, used to decide on hedging.
+    pub operation_id: Option<String>,
 }
 
 impl ProxyRequest {
@@ -133,7 +324,9 @@ impl ProxyRequest {
             path: path.into(),
             headers: HeaderMap::new(),
             body: None,
+            body_stream: None,
             propagated: PropagatedHeaders::new(),
+            operation_id: None,
         }
     }
 
@@ -151,6 +344,13 @@ impl ProxyRequest {
         self
     }
 
+    /// Stream the request body to the upstream while it is produced.
+    #[must_use]
+    pub fn with_body_stream(mut self, body: BodyStream) -> Self {
+        self.body_stream = Some(body);
+        self
+    }
+
     /// Set the propagated headers.
     #[must_use]
     pub fn with_propagated(mut self, propagated: PropagatedHeaders) -> Self {
@@ -158,12 +358,46 @@ impl ProxyRequest {
         self
     }
 
+    /// Set the matched contract operation ID.
+    #[must_use]
+    pub fn with_operation_id(mut self, operation_id: impl Into<String>) -> Self {
+        self.operation_id = Some(operation_id.into());
+        self
+    }
+
     /// Get the request ID.
     pub fn request_id(&self) -> &str {
         &self.propagated.request_id
     }
 }
 
+/// A request body forwarded to the upstream as its chunks are produced.
+///
+/// Chunks are sent through the channel [`BodyStream::channel`] returns.
+/// Sending an error aborts the upstream request, and forwarding fails with
+/// that error.
+#[derive(Debug)]
+pub struct BodyStream {
+    receiver: mpsc::Receiver<SidecarResult<Bytes>>,
+}
+
+impl BodyStream {
+    /// Create a body stream and the sender its chunks are produced into,
+    /// holding at most `buffer` chunks the upstream has not taken yet.
+    pub fn channel(buffer: usize) -> (mpsc::Sender<SidecarResult<Bytes>>, Self) {
+        let (sender, receiver) = mpsc::channel(buffer);
+        (sender, Self { receiver })
+    }
+}
+
+impl Stream for BodyStream {
+    type Item = SidecarResult<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
 /// Response from upstream.
 #[derive(Debug)]
 pub struct ProxyResponse {
@@ -272,6 +506,196 @@ impl ProxyMetrics {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::HedgeSettings;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Upstream that answers its first connection after a delay and the
+    /// rest at once.
+    struct MockUpstream {
+        url: String,
+        connections: Arc<AtomicUsize>,
+        cancelled: Arc<AtomicUsize>,
+    }
+
+    async fn mock_upstream(slow: Duration) -> MockUpstream {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let connections = Arc::new(AtomicUsize::new(0));
+        let cancelled = Arc::new(AtomicUsize::new(0));
+
+        let (accepted, dropped) = (connections.clone(), cancelled.clone());
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let first = accepted.fetch_add(1, Ordering::SeqCst) == 0;
+                let dropped = dropped.clone();
+                tokio::spawn(async move {
+                    let mut buf = vec![0; 4096];
+                    let mut len = 0;
+                    while !buf[..len].windows(4).any(|w| w == b"\r\n\r\n") {
+                        match socket.read(&mut buf[len..]).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => len += n,
+                        }
+                    }
+
+                    let body = if first {
+                        // The client closes the connection when it drops the attempt
+                        tokio::select! {
+                            () = tokio::time::sleep(slow) => "slow",
+                            _ = socket.read(&mut buf) => {
+                                dropped.fetch_add(1, Ordering::SeqCst);
+                                return;
+                            }
+                        }
+                    } else {
+                        "fast"
+                    };
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+
+        MockUpstream {
+            url,
+            connections,
+            cancelled,
+        }
+    }
+
+    fn hedging_client(url: &str, hedging: HedgeSettings) -> ProxyClient {
+        let config = SidecarConfig::builder()
+            .upstream_url(url)
+            .hedging(HedgeSettings {
+                enabled: true,
+                delay: Duration::from_millis(50),
+                ..hedging
+            })
+            .build()
+            .unwrap();
+        ProxyClient::new(&config).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_hedge_wins_and_slow_attempt_cancelled() {
+        let upstream = mock_upstream(Duration::from_secs(10)).await;
+        let client = hedging_client(&upstream.url, HedgeSettings::default());
+
+        let start = Instant::now();
+        let response = client
+            .forward(ProxyRequest::new(Method::GET, "/items"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.body_string().as_deref(), Some("fast"));
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(upstream.connections.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            client.hedge_stats(),
+            HedgeStats {
+                hedges_fired: 1,
+                hedge_wins: 1,
+                hedges_over_budget: 0,
+            }
+        );
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while upstream.cancelled.load(Ordering::SeqCst) == 0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("slow attempt should be cancelled");
+    }
+
+    #[tokio::test]
+    async fn test_non_idempotent_method_never_hedged() {
+        let upstream = mock_upstream(Duration::from_millis(200)).await;
+        let client = hedging_client(
+            &upstream.url,
+            HedgeSettings {
+                methods: vec!["GET".to_string(), "POST".to_string()],
+                ..HedgeSettings::default()
+            },
+        );
+
+        let response = client
+            .forward(ProxyRequest::new(Method::POST, "/items").with_body("{}"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.body_string().as_deref(), Some("slow"));
+        assert_eq!(upstream.connections.load(Ordering::SeqCst), 1);
+        assert_eq!(client.hedge_stats().hedges_fired, 0);
+    }
+
+    #[tokio::test]
+    async fn test_half_open_circuit_disables_hedging() {
+        let upstream = mock_upstream(Duration::from_millis(200)).await;
+        let client = hedging_client(&upstream.url, HedgeSettings::default());
+        client.set_circuit_state(CircuitState::HalfOpen);
+
+        let response = client
+            .forward(ProxyRequest::new(Method::GET, "/items"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.body_string().as_deref(), Some("slow"));
+        assert_eq!(upstream.connections.load(Ordering::SeqCst), 1);
+        assert_eq!(client.hedge_stats().hedges_fired, 0);
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_budget_skips_hedge() {
+        let upstream = mock_upstream(Duration::from_millis(200)).await;
+        let client = hedging_client(
+            &upstream.url,
+            HedgeSettings {
+                methods: Vec::new(),
+                operations: vec!["listItems".to_string()],
+                max_concurrent_hedges: 0,
+                ..HedgeSettings::default()
+            },
+        );
+
+        let response = client
+            .forward(ProxyRequest::new(Method::GET, "/items").with_operation_id("listItems"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.body_string().as_deref(), Some("slow"));
+        assert_eq!(upstream.connections.load(Ordering::SeqCst), 1);
+        assert_eq!(client.hedge_stats().hedges_over_budget, 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_body_stream_aborts_request() {
+        let upstream = mock_upstream(Duration::from_secs(10)).await;
+        let client = hedging_client(&upstream.url, HedgeSettings::default());
+
+        let (sender, body) = BodyStream::channel(1);
+        tokio::spawn(async move {
+            let _ = sender.send(Ok(Bytes::from("partial"))).await;
+            let _ = sender
+                .send(Err(SidecarError::validation("invalid field")))
+                .await;
+        });
+
+        let start = Instant::now();
+        let err = client
+            .forward(ProxyRequest::new(Method::POST, "/upload").with_body_stream(body))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, SidecarError::Validation { .. }));
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(client.hedge_stats().hedges_fired, 0);
+    }
 
     #[test]
     fn test_proxy_request() {
@@ -15,11 +15,16 @@ use hyper_util::rt::TokioIo;
 use tokio::net::TcpListener;
 use tracing::{debug, error, info, warn, Instrument};
 
+use archimedes_core::StartupError;
+
 use crate::config::SidecarConfig;
-use crate::error::{ErrorResponse, SidecarError, SidecarResult};
+use crate::error::ErrorResponse;
 use crate::headers::PropagatedHeaders;
 use crate::health::HealthChecker;
+use crate::middleware::MiddlewarePipeline;
+use crate::multipart;
 use crate::proxy::{ProxyClient, ProxyRequest};
+use crate::transform::Transformer;
 
 /// Sidecar server.
 pub struct SidecarServer {
@@ -29,36 +34,72 @@ pub struct SidecarServer {
     proxy: Arc<ProxyClient>,
     /// Health checker.
     health: Arc<HealthChecker>,
+    /// Request and response transformations.
+    transformer: Arc<Transformer>,
 }
 
 impl SidecarServer {
     /// Create a new sidecar server.
-    pub fn new(config: SidecarConfig) -> SidecarResult<Self> {
+    ///
+    /// Fails with [`StartupCategory::TlsSetup`] if the upstream client
+    /// cannot be built, or [`StartupCategory::ConfigInvalid`] if a
+    /// transformation rule is invalid.
+    ///
+    /// [`StartupCategory::TlsSetup`]: archimedes_core::StartupCategory::TlsSetup
+    /// [`StartupCategory::ConfigInvalid`]: archimedes_core::StartupCategory::ConfigInvalid
+    pub fn new(config: SidecarConfig) -> Result<Self, StartupError> {
         let config = Arc::new(config);
-        let proxy = Arc::new(ProxyClient::new(&config)?);
+        let proxy =
+            ProxyClient::new(&config).map_err(|e| StartupError::tls_setup(e.to_string()))?;
+        #[cfg(feature = "chaos")]
+        let proxy = match crate::fault::load(&config).map_err(StartupError::from)? {
+            Some(faults) => proxy.with_faults(faults),
+            None => proxy,
+        };
+        let proxy = Arc::new(proxy);
         let health = Arc::new(HealthChecker::new(config.clone()));
+        let transformer =
+            Arc::new(Transformer::new(&config.transforms).map_err(StartupError::from)?);
 
         Ok(Self {
             config,
             proxy,
             health,
+            transformer,
         })
     }
 
+    /// Get the transformer, for reloading its rules while the server runs.
+    pub fn transformer(&self) -> Arc<Transformer> {
+        self.transformer.clone()
+    }
+
+    /// Get the faults injected into upstream calls, for reloading their
+    /// file while the server runs.
+    #[cfg(feature = "chaos")]
+    pub fn faults(&self) -> Option<archimedes_middleware::stages::Faults> {
+        self.proxy.faults().cloned()
+    }
+
     /// Run the sidecar server.
-    pub async fn run(self) -> SidecarResult<()> {
+    ///
+    /// The contract and policy bundle are loaded before the listener is
+    /// bound, so a bad artifact fails startup rather than the first request.
+    /// The returned [`StartupError`] carries the exit code a binary should
+    /// use; see [`archimedes_core::startup`].
+    pub async fn run(self) -> Result<(), StartupError> {
         let addr = SocketAddr::new(
-            self.config
-                .sidecar
-                .listen_addr
-                .parse()
-                .map_err(|e| SidecarError::config(format!("invalid listen address: {e}")))?,
+            self.config.sidecar.listen_addr.parse().map_err(|e| {
+                StartupError::config_invalid(format!("invalid listen address: {e}"))
+            })?,
             self.config.sidecar.listen_port,
         );
 
+        let pipeline = Arc::new(MiddlewarePipeline::new(self.config.clone()).await?);
+
         let listener = TcpListener::bind(addr)
             .await
-            .map_err(|e| SidecarError::server(format!("failed to bind: {e}")))?;
+            .map_err(|e| StartupError::bind(format!("failed to bind {addr}: {e}")))?;
 
         info!("Archimedes sidecar listening on {}", addr);
         info!("Proxying to upstream: {}", self.config.sidecar.upstream_url);
@@ -79,6 +120,8 @@ impl SidecarServer {
             let config = self.config.clone();
             let proxy = self.proxy.clone();
             let health = self.health.clone();
+            let transformer = self.transformer.clone();
+            let pipeline = pipeline.clone();
 
             // Spawn handler for this connection
             tokio::spawn(async move {
@@ -88,8 +131,10 @@ impl SidecarServer {
                     let config = config.clone();
                     let proxy = proxy.clone();
                     let health = health.clone();
+                    let transformer = transformer.clone();
+                    let pipeline = pipeline.clone();
                     async move {
-                        handle_request(req, config, proxy, health, peer_addr)
+                        handle_request(req, config, proxy, health, transformer, pipeline, peer_addr)
                             .await
                             .map_err(|_| -> Infallible { unreachable!() })
                     }
@@ -106,9 +151,11 @@ impl SidecarServer {
 /// Handle an incoming request.
 async fn handle_request(
     req: Request<Incoming>,
-    _config: Arc<SidecarConfig>,
+    config: Arc<SidecarConfig>,
     proxy: Arc<ProxyClient>,
     health: Arc<HealthChecker>,
+    transformer: Arc<Transformer>,
+    pipeline: Arc<MiddlewarePipeline>,
     peer_addr: SocketAddr,
 ) -> Result<Response<Full<Bytes>>, Infallible> {
     let start = Instant::now();
@@ -137,29 +184,59 @@ async fn handle_request(
             return handle_internal_endpoint(&path, &health).await;
         }
 
-        // Extract request body
         let (parts, body) = req.into_parts();
-        let body_bytes = match body.collect().await {
-            Ok(collected) => collected.to_bytes(),
-            Err(e) => {
-                warn!("Failed to read request body: {}", e);
-                return Ok(error_response(
-                    StatusCode::BAD_REQUEST,
-                    "failed to read request body",
-                    &request_id,
-                ));
-            }
-        };
+
+        // Apply transformation rules
+        let mut path = path;
+        let mut headers = parts.headers;
+        let response_transforms = transformer.apply_request(&method, &mut path, &mut headers);
 
         // Create proxy request
         let proxy_req = ProxyRequest::new(method.clone(), &path)
-            .with_headers(parts.headers.clone())
-            .with_body(body_bytes.clone())
+            .with_headers(headers)
             .with_propagated(propagated);
 
+        // Stream multipart bodies, so large uploads are never buffered
+        let proxy_req = if config.sidecar.multipart.streaming
+            && multipart::is_multipart(&proxy_req.headers)
+        {
+            match pipeline.process_multipart(&proxy_req, body.into_data_stream()) {
+                Ok((result, body)) => {
+                    let proxy_req = proxy_req.with_body_stream(body);
+                    match result.operation_id {
+                        Some(operation_id) => proxy_req.with_operation_id(operation_id),
+                        None => proxy_req,
+                    }
+                }
+                Err(e) => {
+                    warn!(error = %e, "multipart request rejected");
+                    return Ok(error_response(
+                        StatusCode::from_u16(e.status_code()).unwrap_or(StatusCode::BAD_REQUEST),
+                        &e.to_string(),
+                        &request_id,
+                    ));
+                }
+            }
+        } else {
+            // Extract request body
+            match body.collect().await {
+                Ok(collected) => proxy_req.with_body(collected.to_bytes()),
+                Err(e) => {
+                    warn!("Failed to read request body: {}", e);
+                    return Ok(error_response(
+                        StatusCode::BAD_REQUEST,
+                        "failed to read request body",
+                        &request_id,
+                    ));
+                }
+            }
+        };
+
         // Forward to upstream
         match proxy.forward(proxy_req).await {
-            Ok(response) => {
+            Ok(mut response) => {
+                response_transforms.apply(&mut response.headers);
+
                 let duration = start.elapsed();
                 info!(
                     status = %response.status,
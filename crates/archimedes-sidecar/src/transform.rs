@@ -0,0 +1,785 @@
+//! Declarative request and response transformations.
+//!
+//! Transformation rules let the sidecar adapt legacy clients without
+//! changes to the application: renaming headers, rewriting paths, adding
+//! default query parameters and stripping response headers.
+//!
+//! Rules run in order, each seeing the request as the rules before it left
+//! it, before the request is resolved and forwarded. The response actions
+//! of the rules that matched run on the upstream response, in the same
+//! order.
+//!
+//! Rules are compiled when the configuration is validated, so a bad regex,
+//! header name or template fails the load. [`Transformer::reload`] swaps in
+//! new rules at runtime; invalid rules leave the current ones in place.
+//!
+//! # Example
+//!
+//! ```toml
+//! [transforms]
+//! debug_header = true
+//!
+//! [[transforms.rules]]
+//! name = "rename-widgets"
+//! match = { methods = ["GET"], path = "/v1/old-widgets/{id}" }
+//! actions = [
+//!     { action = "rewrite_path", to = "/v1/widgets/{id}" },
+//!     { action = "add_request_header", name = "x-widget-id", value = "{id}" },
+//!     { action = "default_query_param", name = "expand", value = "owner" },
+//!     { action = "remove_response_header", name = "x-legacy-debug" },
+//! ]
+//! ```
+
+use std::sync::Arc;
+
+use http::{HeaderMap, HeaderName, HeaderValue, Method};
+use parking_lot::RwLock;
+use regex::Regex;
+use tracing::warn;
+
+use crate::config::{TransformAction, TransformRule, TransformSettings};
+use crate::error::{SidecarError, SidecarResult};
+
+/// Response header listing the rules applied to a request, when enabled.
+pub const TRANSFORMED_HEADER: &str = "x-archimedes-transformed";
+
+/// Applies the configured transformation rules.
+#[derive(Debug)]
+pub struct Transformer {
+    rules: RwLock<Arc<CompiledRules>>,
+}
+
+impl Transformer {
+    /// Compile the transformation rules.
+    pub fn new(settings: &TransformSettings) -> SidecarResult<Self> {
+        Ok(Self {
+            rules: RwLock::new(Arc::new(CompiledRules::compile(settings)?)),
+        })
+    }
+
+    /// Replace the rules with newly configured ones.
+    ///
+    /// Requests already being transformed finish with the old rules. If the
+    /// new rules are invalid, the current rules stay in place.
+    pub fn reload(&self, settings: &TransformSettings) -> SidecarResult<()> {
+        let compiled = CompiledRules::compile(settings)?;
+        *self.rules.write() = Arc::new(compiled);
+        Ok(())
+    }
+
+    /// Number of configured rules.
+    pub fn rule_count(&self) -> usize {
+        self.rules.read().rules.len()
+    }
+
+    /// Apply the request actions of every matching rule.
+    ///
+    /// `path_and_query` and `headers` are updated in place. The returned
+    /// [`ResponseTransforms`] holds the response actions of the matching
+    /// rules.
+    pub fn apply_request(
+        &self,
+        method: &Method,
+        path_and_query: &mut String,
+        headers: &mut HeaderMap,
+    ) -> ResponseTransforms {
+        let rules = self.rules.read().clone();
+        let mut response = ResponseTransforms {
+            debug_header: rules.debug_header,
+            ..ResponseTransforms::default()
+        };
+
+        for rule in &rules.rules {
+            let Some(captures) = rule.matches(method, path_and_query, headers) else {
+                continue;
+            };
+            response.applied.push(rule.name.clone());
+
+            for action in &rule.actions {
+                match action {
+                    CompiledAction::Request(header_action) => {
+                        if let Some(header_action) = header_action.render(&rule.name, &captures) {
+                            header_action.apply(headers);
+                        }
+                    }
+                    CompiledAction::Response(header_action) => {
+                        if let Some(header_action) = header_action.render(&rule.name, &captures) {
+                            response.actions.push(header_action);
+                        }
+                    }
+                    CompiledAction::RewritePath(template) => {
+                        let query = path_and_query
+                            .find('?')
+                            .map(|index| path_and_query[index..].to_string())
+                            .unwrap_or_default();
+                        *path_and_query = format!("{}{query}", template.render(&captures));
+                    }
+                    CompiledAction::DefaultQueryParam(name, template) => {
+                        add_default_query_param(path_and_query, name, &template.render(&captures));
+                    }
+                }
+            }
+        }
+
+        response
+    }
+}
+
+/// Response actions of the rules that matched a request.
+#[derive(Debug, Default)]
+pub struct ResponseTransforms {
+    applied: Vec<String>,
+    actions: Vec<HeaderAction>,
+    debug_header: bool,
+}
+
+impl ResponseTransforms {
+    /// Names of the rules that matched, in order.
+    pub fn applied(&self) -> &[String] {
+        &self.applied
+    }
+
+    /// Apply the response actions to the upstream response headers.
+    pub fn apply(&self, headers: &mut HeaderMap) {
+        for action in &self.actions {
+            action.apply(headers);
+        }
+
+        if self.debug_header && !self.applied.is_empty() {
+            if let Ok(value) = HeaderValue::from_str(&self.applied.join(", ")) {
+                headers.insert(TRANSFORMED_HEADER, value);
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CompiledRules {
+    debug_header: bool,
+    rules: Vec<CompiledRule>,
+}
+
+impl CompiledRules {
+    fn compile(settings: &TransformSettings) -> SidecarResult<Self> {
+        let rules = settings
+            .rules
+            .iter()
+            .map(|rule| {
+                CompiledRule::compile(rule).map_err(|message| {
+                    SidecarError::config(format!("transform rule '{}': {message}", rule.name))
+                })
+            })
+            .collect::<SidecarResult<_>>()?;
+
+        Ok(Self {
+            debug_header: settings.debug_header,
+            rules,
+        })
+    }
+}
+
+#[derive(Debug)]
+struct CompiledRule {
+    name: String,
+    methods: Vec<Method>,
+    path: Option<Regex>,
+    headers: Vec<HeaderName>,
+    actions: Vec<CompiledAction>,
+}
+
+impl CompiledRule {
+    fn compile(rule: &TransformRule) -> Result<Self, String> {
+        if rule.name.is_empty() {
+            return Err("rule name is required".to_string());
+        }
+
+        let matches = &rule.matches;
+        let path = match (&matches.path, &matches.path_regex) {
+            (Some(_), Some(_)) => return Err("set either path or path_regex, not both".to_string()),
+            (Some(glob), None) => Some(glob_to_regex(glob)?),
+            (None, Some(pattern)) => {
+                Some(Regex::new(pattern).map_err(|e| format!("invalid path regex: {e}"))?)
+            }
+            (None, None) => None,
+        };
+
+        let methods = matches
+            .methods
+            .iter()
+            .map(|method| {
+                Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+                    .map_err(|_| format!("invalid method '{method}'"))
+            })
+            .collect::<Result<_, _>>()?;
+        let headers = matches
+            .headers
+            .iter()
+            .map(|name| header_name(name))
+            .collect::<Result<_, _>>()?;
+        let actions = rule
+            .actions
+            .iter()
+            .map(|action| CompiledAction::compile(action, path.as_ref()))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            name: rule.name.clone(),
+            methods,
+            path,
+            headers,
+            actions,
+        })
+    }
+
+    /// Returns the path captures if the request matches.
+    fn matches(
+        &self,
+        method: &Method,
+        path_and_query: &str,
+        headers: &HeaderMap,
+    ) -> Option<Vec<Option<String>>> {
+        if !self.methods.is_empty() && !self.methods.contains(method) {
+            return None;
+        }
+        if !self.headers.iter().all(|name| headers.contains_key(name)) {
+            return None;
+        }
+
+        let Some(path) = &self.path else {
+            return Some(Vec::new());
+        };
+        let request_path = path_and_query.split('?').next().unwrap_or_default();
+        let captures = path.captures(request_path)?;
+        Some(
+            captures
+                .iter()
+                .map(|capture| capture.map(|m| m.as_str().to_string()))
+                .collect(),
+        )
+    }
+}
+
+#[derive(Debug)]
+enum CompiledAction {
+    Request(HeaderActionTemplate),
+    Response(HeaderActionTemplate),
+    RewritePath(Template),
+    DefaultQueryParam(String, Template),
+}
+
+impl CompiledAction {
+    fn compile(action: &TransformAction, path: Option<&Regex>) -> Result<Self, String> {
+        Ok(match action {
+            TransformAction::AddRequestHeader { name, value } => Self::Request(
+                HeaderActionTemplate::Set(header_name(name)?, Template::parse(value, path)?),
+            ),
+            TransformAction::RemoveRequestHeader { name } => {
+                Self::Request(HeaderActionTemplate::Remove(header_name(name)?))
+            }
+            TransformAction::RenameRequestHeader { from, to } => Self::Request(
+                HeaderActionTemplate::Rename(header_name(from)?, header_name(to)?),
+            ),
+            TransformAction::AddResponseHeader { name, value } => Self::Response(
+                HeaderActionTemplate::Set(header_name(name)?, Template::parse(value, path)?),
+            ),
+            TransformAction::RemoveResponseHeader { name } => {
+                Self::Response(HeaderActionTemplate::Remove(header_name(name)?))
+            }
+            TransformAction::RenameResponseHeader { from, to } => Self::Response(
+                HeaderActionTemplate::Rename(header_name(from)?, header_name(to)?),
+            ),
+            TransformAction::RewritePath { to } => {
+                if !to.starts_with('/') {
+                    return Err(format!("rewritten path '{to}' must start with '/'"));
+                }
+                Self::RewritePath(Template::parse(to, path)?)
+            }
+            TransformAction::DefaultQueryParam { name, value } => {
+                if name.is_empty() {
+                    return Err("query parameter name is required".to_string());
+                }
+                Self::DefaultQueryParam(name.clone(), Template::parse(value, path)?)
+            }
+        })
+    }
+}
+
+/// A header action whose value is rendered per request.
+#[derive(Debug)]
+enum HeaderActionTemplate {
+    Set(HeaderName, Template),
+    Remove(HeaderName),
+    Rename(HeaderName, HeaderName),
+}
+
+impl HeaderActionTemplate {
+    fn render(&self, rule: &str, captures: &[Option<String>]) -> Option<HeaderAction> {
+        Some(match self {
+            Self::Set(name, template) => {
+                let value = template.render(captures);
+                let Ok(value) = HeaderValue::from_str(&value) else {
+                    warn!(rule, header = %name, "transform produced an invalid header value");
+                    return None;
+                };
+                HeaderAction::Set(name.clone(), value)
+            }
+            Self::Remove(name) => HeaderAction::Remove(name.clone()),
+            Self::Rename(from, to) => HeaderAction::Rename(from.clone(), to.clone()),
+        })
+    }
+}
+
+#[derive(Debug)]
+enum HeaderAction {
+    Set(HeaderName, HeaderValue),
+    Remove(HeaderName),
+    Rename(HeaderName, HeaderName),
+}
+
+impl HeaderAction {
+    fn apply(&self, headers: &mut HeaderMap) {
+        match self {
+            Self::Set(name, value) => {
+                headers.insert(name.clone(), value.clone());
+            }
+            Self::Remove(name) => {
+                headers.remove(name);
+            }
+            Self::Rename(from, to) => {
+                let values: Vec<HeaderValue> = headers.get_all(from).iter().cloned().collect();
+                headers.remove(from);
+                for value in values {
+                    headers.append(to.clone(), value);
+                }
+            }
+        }
+    }
+}
+
+/// A string with `{name}` or `{1}` placeholders for path captures.
+#[derive(Debug)]
+struct Template {
+    parts: Vec<TemplatePart>,
+}
+
+#[derive(Debug)]
+enum TemplatePart {
+    Literal(String),
+    Capture(usize),
+}
+
+impl Template {
+    fn parse(template: &str, path: Option<&Regex>) -> Result<Self, String> {
+        let mut parts = Vec::new();
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            if start > 0 {
+                parts.push(TemplatePart::Literal(rest[..start].to_string()));
+            }
+            let after = &rest[start + 1..];
+            let end = after
+                .find('}')
+                .ok_or_else(|| format!("unclosed '{{' in template '{template}'"))?;
+            let key = &after[..end];
+            let index = capture_index(key, path).ok_or_else(|| {
+                format!("template '{template}' references unknown capture '{key}'")
+            })?;
+            parts.push(TemplatePart::Capture(index));
+            rest = &after[end + 1..];
+        }
+        if !rest.is_empty() {
+            parts.push(TemplatePart::Literal(rest.to_string()));
+        }
+        Ok(Self { parts })
+    }
+
+    fn render(&self, captures: &[Option<String>]) -> String {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                TemplatePart::Literal(text) => text.as_str(),
+                TemplatePart::Capture(index) => captures
+                    .get(*index)
+                    .and_then(Option::as_deref)
+                    .unwrap_or_default(),
+            })
+            .collect()
+    }
+}
+
+/// Index of a named or numbered capture group of `path`.
+fn capture_index(key: &str, path: Option<&Regex>) -> Option<usize> {
+    let path = path?;
+    if let Ok(index) = key.parse::<usize>() {
+        return (index < path.captures_len()).then_some(index);
+    }
+    path.capture_names().position(|name| name == Some(key))
+}
+
+/// Translate a path glob into an anchored regex.
+fn glob_to_regex(glob: &str) -> Result<Regex, String> {
+    let mut pattern = String::from("^");
+    let mut rest = glob;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("**") {
+            pattern.push_str("(.*)");
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('*') {
+            pattern.push_str("([^/]+)");
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('{') {
+            let end = after
+                .find('}')
+                .ok_or_else(|| format!("unclosed '{{' in path '{glob}'"))?;
+            let name = &after[..end];
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return Err(format!("invalid parameter name '{name}' in path '{glob}'"));
+            }
+            pattern.push_str(&format!("(?P<{name}>[^/]+)"));
+            rest = &after[end + 1..];
+        } else {
+            let end = rest.find(|c| c == '*' || c == '{').unwrap_or(rest.len());
+            pattern.push_str(&regex::escape(&rest[..end]));
+            rest = &rest[end..];
+        }
+    }
+    pattern.push('$');
+    Regex::new(&pattern).map_err(|e| format!("invalid path '{glob}': {e}"))
+}
+
+fn header_name(name: &str) -> Result<HeaderName, String> {
+    HeaderName::from_bytes(name.as_bytes()).map_err(|_| format!("invalid header name '{name}'"))
+}
+
+/// Append `name=value` to the query unless `name` is already present.
+fn add_default_query_param(path_and_query: &mut String, name: &str, value: &str) {
+    let name = encode_query_component(name);
+    let present = path_and_query.split_once('?').is_some_and(|(_, query)| {
+        query
+            .split('&')
+            .any(|pair| pair.split('=').next() == Some(name.as_str()))
+    });
+    if present {
+        return;
+    }
+
+    let separator = if !path_and_query.contains('?') {
+        "?"
+    } else if path_and_query.ends_with('?') || path_and_query.ends_with('&') {
+        ""
+    } else {
+        "&"
+    };
+    path_and_query.push_str(&format!(
+        "{separator}{name}={}",
+        encode_query_component(value)
+    ));
+}
+
+/// Percent-encode everything but unreserved characters.
+fn encode_query_component(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(char::from(byte));
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{SidecarConfig, TransformMatch};
+
+    fn rule(name: &str, matches: TransformMatch, actions: Vec<TransformAction>) -> TransformRule {
+        TransformRule {
+            name: name.to_string(),
+            matches,
+            actions,
+        }
+    }
+
+    fn transformer(rules: Vec<TransformRule>) -> Transformer {
+        Transformer::new(&TransformSettings {
+            debug_header: true,
+            rules,
+        })
+        .unwrap()
+    }
+
+    fn apply(
+        transformer: &Transformer,
+        method: Method,
+        path: &str,
+        headers: &mut HeaderMap,
+    ) -> (String, ResponseTransforms) {
+        let mut path = path.to_string();
+        let response = transformer.apply_request(&method, &mut path, headers);
+        (path, response)
+    }
+
+    #[test]
+    fn test_request_header_actions() {
+        let transformer = transformer(vec![rule(
+            "headers",
+            TransformMatch::default(),
+            vec![
+                TransformAction::RenameRequestHeader {
+                    from: "x-old-token".to_string(),
+                    to: "x-token".to_string(),
+                },
+                TransformAction::RemoveRequestHeader {
+                    name: "x-legacy".to_string(),
+                },
+                TransformAction::AddRequestHeader {
+                    name: "x-client".to_string(),
+                    value: "legacy".to_string(),
+                },
+            ],
+        )]);
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-old-token", HeaderValue::from_static("abc"));
+        headers.insert("x-legacy", HeaderValue::from_static("1"));
+        apply(&transformer, Method::GET, "/items", &mut headers);
+
+        assert!(headers.get("x-old-token").is_none());
+        assert_eq!(headers["x-token"], "abc");
+        assert!(headers.get("x-legacy").is_none());
+        assert_eq!(headers["x-client"], "legacy");
+    }
+
+    #[test]
+    fn test_path_rewrite_with_captures() {
+        let transformer = transformer(vec![
+            rule(
+                "glob",
+                TransformMatch {
+                    path: Some("/v1/old-name/{id}/**".to_string()),
+                    ..TransformMatch::default()
+                },
+                vec![
+                    TransformAction::RewritePath {
+                        to: "/v1/new-name/{id}/{2}".to_string(),
+                    },
+                    TransformAction::AddRequestHeader {
+                        name: "x-item-id".to_string(),
+                        value: "item-{id}".to_string(),
+                    },
+                ],
+            ),
+            rule(
+                "regex",
+                TransformMatch {
+                    path_regex: Some(r"^/v0/(\w+)$".to_string()),
+                    ..TransformMatch::default()
+                },
+                vec![TransformAction::RewritePath {
+                    to: "/v1/{1}".to_string(),
+                }],
+            ),
+        ]);
+
+        let mut headers = HeaderMap::new();
+        let (path, _) = apply(
+            &transformer,
+            Method::GET,
+            "/v1/old-name/42/parts/7?verbose=true",
+            &mut headers,
+        );
+        assert_eq!(path, "/v1/new-name/42/parts/7?verbose=true");
+        assert_eq!(headers["x-item-id"], "item-42");
+
+        let (path, response) = apply(&transformer, Method::GET, "/v0/users", &mut headers);
+        assert_eq!(path, "/v1/users");
+        assert_eq!(response.applied(), ["regex"]);
+
+        let (path, response) = apply(&transformer, Method::GET, "/v2/users", &mut headers);
+        assert_eq!(path, "/v2/users");
+        assert!(response.applied().is_empty());
+    }
+
+    #[test]
+    fn test_default_query_param() {
+        let transformer = transformer(vec![rule(
+            "defaults",
+            TransformMatch::default(),
+            vec![TransformAction::DefaultQueryParam {
+                name: "page size".to_string(),
+                value: "20&up".to_string(),
+            }],
+        )]);
+
+        let mut headers = HeaderMap::new();
+        let (path, _) = apply(&transformer, Method::GET, "/items", &mut headers);
+        assert_eq!(path, "/items?page%20size=20%26up");
+
+        let (path, _) = apply(&transformer, Method::GET, "/items?a=1", &mut headers);
+        assert_eq!(path, "/items?a=1&page%20size=20%26up");
+
+        let (path, _) = apply(
+            &transformer,
+            Method::GET,
+            "/items?page%20size=5",
+            &mut headers,
+        );
+        assert_eq!(path, "/items?page%20size=5");
+    }
+
+    #[test]
+    fn test_response_header_actions_and_debug_header() {
+        let transformer = transformer(vec![rule(
+            "response",
+            TransformMatch {
+                methods: vec!["get".to_string()],
+                path: Some("/items/{id}".to_string()),
+                ..TransformMatch::default()
+            },
+            vec![
+                TransformAction::RemoveResponseHeader {
+                    name: "x-internal".to_string(),
+                },
+                TransformAction::RenameResponseHeader {
+                    from: "x-new-etag".to_string(),
+                    to: "x-etag".to_string(),
+                },
+                TransformAction::AddResponseHeader {
+                    name: "x-item".to_string(),
+                    value: "{id}".to_string(),
+                },
+            ],
+        )]);
+
+        let mut headers = HeaderMap::new();
+        let (_, post) = apply(&transformer, Method::POST, "/items/9", &mut headers);
+        assert!(post.applied().is_empty());
+
+        let (_, response) = apply(&transformer, Method::GET, "/items/9", &mut headers);
+        let mut upstream = HeaderMap::new();
+        upstream.insert("x-internal", HeaderValue::from_static("secret"));
+        upstream.insert("x-new-etag", HeaderValue::from_static("v1"));
+        response.apply(&mut upstream);
+
+        assert!(upstream.get("x-internal").is_none());
+        assert_eq!(upstream["x-etag"], "v1");
+        assert_eq!(upstream["x-item"], "9");
+        assert_eq!(upstream[TRANSFORMED_HEADER], "response");
+    }
+
+    #[test]
+    fn test_rules_apply_in_order() {
+        let transformer = transformer(vec![
+            rule(
+                "rename",
+                TransformMatch::default(),
+                vec![TransformAction::RenameRequestHeader {
+                    from: "x-api-token".to_string(),
+                    to: "authorization".to_string(),
+                }],
+            ),
+            rule(
+                "needs-auth",
+                TransformMatch {
+                    headers: vec!["authorization".to_string()],
+                    ..TransformMatch::default()
+                },
+                vec![TransformAction::AddRequestHeader {
+                    name: "x-auth-source".to_string(),
+                    value: "legacy".to_string(),
+                }],
+            ),
+            rule(
+                "override",
+                TransformMatch::default(),
+                vec![TransformAction::AddRequestHeader {
+                    name: "x-auth-source".to_string(),
+                    value: "override".to_string(),
+                }],
+            ),
+        ]);
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-api-token", HeaderValue::from_static("t"));
+        let (_, response) = apply(&transformer, Method::GET, "/", &mut headers);
+
+        assert_eq!(response.applied(), ["rename", "needs-auth", "override"]);
+        assert_eq!(headers["x-auth-source"], "override");
+
+        let mut upstream = HeaderMap::new();
+        response.apply(&mut upstream);
+        assert_eq!(upstream[TRANSFORMED_HEADER], "rename, needs-auth, override");
+    }
+
+    #[test]
+    fn test_invalid_rules_rejected() {
+        let bad_regex = rule(
+            "bad-regex",
+            TransformMatch {
+                path_regex: Some("/items/(".to_string()),
+                ..TransformMatch::default()
+            },
+            Vec::new(),
+        );
+        let error = SidecarConfig::builder()
+            .transform_rule(bad_regex)
+            .build()
+            .unwrap_err();
+        assert!(error.to_string().contains("bad-regex"));
+
+        let unknown_capture = rule(
+            "unknown-capture",
+            TransformMatch {
+                path: Some("/items/{id}".to_string()),
+                ..TransformMatch::default()
+            },
+            vec![TransformAction::RewritePath {
+                to: "/things/{item}".to_string(),
+            }],
+        );
+        assert!(Transformer::new(&TransformSettings {
+            debug_header: false,
+            rules: vec![unknown_capture],
+        })
+        .is_err());
+
+        let toml = r#"
+[[transforms.rules]]
+name = "unknown-action"
+actions = [{ action = "teleport_request" }]
+"#;
+        assert!(toml::from_str::<SidecarConfig>(toml).is_err());
+    }
+
+    #[test]
+    fn test_reload_swaps_rules_and_keeps_them_on_error() {
+        let transformer = transformer(Vec::new());
+        assert_eq!(transformer.rule_count(), 0);
+
+        let settings: SidecarConfig = toml::from_str(
+            r#"
+[[transforms.rules]]
+name = "strip"
+actions = [{ action = "remove_request_header", name = "x-legacy" }]
+"#,
+        )
+        .unwrap();
+        transformer.reload(&settings.transforms).unwrap();
+        assert_eq!(transformer.rule_count(), 1);
+
+        let invalid = TransformSettings {
+            debug_header: false,
+            rules: vec![rule(
+                "bad-header",
+                TransformMatch::default(),
+                vec![TransformAction::RemoveRequestHeader {
+                    name: "bad header".to_string(),
+                }],
+            )],
+        };
+        assert!(transformer.reload(&invalid).is_err());
+        assert_eq!(transformer.rule_count(), 1);
+    }
+}
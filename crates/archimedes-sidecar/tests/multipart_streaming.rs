@@ -0,0 +1,225 @@
+//! Streaming multipart uploads through the sidecar.
+//!
+//! Uploads are forwarded while they arrive, so a file far larger than the
+//! buffered request body limit passes through in bounded memory, and a form
+//! whose text fields fail validation is rejected before its file reaches
+//! the upstream.
+
+#![cfg(feature = "sentinel")]
+
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use archimedes_core::contract::{Contract, MockSchema, Operation};
+use archimedes_sentinel::{fixtures, Sentinel};
+use archimedes_sidecar::{
+    MiddlewarePipeline, ProxyClient, ProxyRequest, SidecarConfig, SidecarError,
+};
+use bytes::Bytes;
+use futures_util::Stream;
+use http::{HeaderMap, HeaderValue, Method, Request, Response};
+use http_body_util::{BodyExt, Full};
+use hyper::body::Incoming;
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper_util::rt::TokioIo;
+use tokio::net::TcpListener;
+
+const BOUNDARY: &str = "----upload";
+
+/// Size of the uploaded file, 200MB.
+const FILE_SIZE: u64 = 200 * 1024 * 1024;
+
+/// Size of each chunk the client sends.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+fn sentinel() -> Sentinel {
+    let contract = Contract::builder("files")
+        .operation(
+            Operation::builder("uploadFile")
+                .method(Method::POST)
+                .path("/files")
+                .request_schema(
+                    MockSchema::object(vec![
+                        ("title", MockSchema::string().required()),
+                        ("count", MockSchema::integer().required()),
+                        ("file", MockSchema::string()),
+                    ])
+                    .required(),
+                )
+                .build(),
+        )
+        .build();
+    Sentinel::with_defaults(fixtures::from_contract(&contract))
+}
+
+/// Starts an upstream that reads each request body to the end and answers
+/// with the number of bytes it received. `received` counts the bytes of
+/// every body, including aborted ones.
+async fn start_upstream() -> (String, Arc<AtomicU64>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let received = Arc::new(AtomicU64::new(0));
+
+    let counter = received.clone();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let counter = counter.clone();
+            tokio::spawn(async move {
+                let service = service_fn(move |req: Request<Incoming>| {
+                    let counter = counter.clone();
+                    async move {
+                        let mut body = req.into_body();
+                        let mut total = 0;
+                        while let Some(Ok(frame)) = body.frame().await {
+                            if let Some(data) = frame.data_ref() {
+                                total += data.len() as u64;
+                                counter.fetch_add(data.len() as u64, Ordering::SeqCst);
+                            }
+                        }
+                        Ok::<_, Infallible>(Response::new(Full::new(Bytes::from(
+                            total.to_string(),
+                        ))))
+                    }
+                });
+                let _ = http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await;
+            });
+        }
+    });
+
+    (url, received)
+}
+
+async fn sidecar(url: &str, validate: bool) -> (MiddlewarePipeline, ProxyClient) {
+    let mut config = SidecarConfig::builder()
+        .upstream_url(url)
+        .upstream_timeout(Duration::from_secs(300))
+        .build()
+        .unwrap();
+    config.contract.validate_requests = validate;
+
+    let client = ProxyClient::new(&config).unwrap();
+    let pipeline = MiddlewarePipeline::new(Arc::new(config))
+        .await
+        .unwrap()
+        .with_sentinel(sentinel());
+    (pipeline, client)
+}
+
+fn upload_request() -> ProxyRequest {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "content-type",
+        HeaderValue::from_str(&format!("multipart/form-data; boundary={BOUNDARY}")).unwrap(),
+    );
+    ProxyRequest::new(Method::POST, "/files").with_headers(headers)
+}
+
+/// A form with text fields `title` and `count` followed by a file of
+/// `file_size` bytes, produced lazily so the client never holds it either.
+fn upload(count: &str, file_size: u64) -> (u64, impl Stream<Item = Result<Bytes, Infallible>>) {
+    let head = Bytes::from(format!(
+        "--{BOUNDARY}\r\n\
+         content-disposition: form-data; name=\"title\"\r\n\r\n\
+         holiday\r\n\
+         --{BOUNDARY}\r\n\
+         content-disposition: form-data; name=\"count\"\r\n\r\n\
+         {count}\r\n\
+         --{BOUNDARY}\r\n\
+         content-disposition: form-data; name=\"file\"; filename=\"video.mp4\"\r\n\
+         content-type: video/mp4\r\n\r\n"
+    ));
+    let tail = Bytes::from(format!("\r\n--{BOUNDARY}--\r\n"));
+    let chunk = Bytes::from(vec![b'x'; CHUNK_SIZE]);
+
+    let chunks = file_size / CHUNK_SIZE as u64;
+    let size = head.len() as u64 + chunks * CHUNK_SIZE as u64 + tail.len() as u64;
+    let body = std::iter::once(head)
+        .chain(std::iter::repeat(chunk).take(usize::try_from(chunks).unwrap()))
+        .chain(std::iter::once(tail))
+        .map(Ok);
+    (size, futures_util::stream::iter(body))
+}
+
+/// Peak resident memory of this process in bytes, where the platform
+/// reports it.
+fn peak_memory() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+#[tokio::test]
+async fn test_large_file_streamed_in_bounded_memory() {
+    let (url, _) = start_upstream().await;
+    let (pipeline, client) = sidecar(&url, true).await;
+    let before = peak_memory();
+
+    let request = upload_request();
+    let (_, body) = upload("3", FILE_SIZE);
+    let (result, body) = pipeline.process_multipart(&request, body).unwrap();
+    assert_eq!(result.operation_id(), Some("uploadFile"));
+
+    let response = client
+        .forward(request.with_body_stream(body))
+        .await
+        .unwrap();
+    assert!(response.is_success());
+    let forwarded: u64 = response.body_string().unwrap().parse().unwrap();
+    assert!(forwarded > FILE_SIZE, "upstream received {forwarded} bytes");
+
+    if let (Some(before), Some(after)) = (before, peak_memory()) {
+        let growth = after.saturating_sub(before);
+        assert!(growth < FILE_SIZE / 4, "peak memory grew by {growth} bytes");
+    }
+}
+
+#[tokio::test]
+async fn test_body_forwarded_untouched_without_validation() {
+    let (url, _) = start_upstream().await;
+    let (pipeline, client) = sidecar(&url, false).await;
+
+    // Not a valid count, but nothing validates it
+    let request = upload_request();
+    let (size, body) = upload("three", 8 * 1024 * 1024);
+    let (_, body) = pipeline.process_multipart(&request, body).unwrap();
+
+    let response = client
+        .forward(request.with_body_stream(body))
+        .await
+        .unwrap();
+    assert_eq!(response.body_string(), Some(size.to_string()));
+}
+
+#[tokio::test]
+async fn test_invalid_field_rejected_before_file_forwarded() {
+    let (url, received) = start_upstream().await;
+    let (pipeline, client) = sidecar(&url, true).await;
+
+    let request = upload_request();
+    let (_, body) = upload("three", FILE_SIZE);
+    let (_, body) = pipeline.process_multipart(&request, body).unwrap();
+
+    let err = client
+        .forward(request.with_body_stream(body))
+        .await
+        .unwrap_err();
+    assert_eq!(err.status_code(), 400);
+    assert!(
+        matches!(err, SidecarError::Validation { field: Some(ref field), .. } if field == "count"),
+        "{err}"
+    );
+
+    // Give an aborted upload time to be counted
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let forwarded = received.load(Ordering::SeqCst);
+    assert!(
+        forwarded < FILE_SIZE / 100,
+        "upstream received {forwarded} bytes"
+    );
+}
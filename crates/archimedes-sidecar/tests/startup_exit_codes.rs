@@ -0,0 +1,73 @@
+//! Integration tests for the sidecar binary's startup exit codes.
+//!
+//! These tests run the real binary against a bad configuration and check
+//! the exit code an orchestrator would see.
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+use archimedes_core::StartupCategory;
+
+/// Writes `contents` to a config file unique to `name` and runs the sidecar.
+fn run_sidecar(name: &str, contents: &str) -> Output {
+    let path: PathBuf = std::env::temp_dir().join(format!(
+        "archimedes-sidecar-{}-{name}.toml",
+        std::process::id()
+    ));
+    std::fs::write(&path, contents).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_archimedes-sidecar"))
+        .arg("--config")
+        .arg(&path)
+        .env_remove("ARCHIMEDES_SIDECAR_CONTRACT_PATH")
+        .env_remove("ARCHIMEDES_SIDECAR_POLICY_BUNDLE_PATH")
+        .env_remove("ARCHIMEDES_SIDECAR_UPSTREAM_URL")
+        .env_remove("ARCHIMEDES_SIDECAR_LISTEN_PORT")
+        .output()
+        .unwrap();
+
+    std::fs::remove_file(&path).ok();
+    output
+}
+
+#[test]
+fn test_invalid_config_exit_code() {
+    let output = run_sidecar("invalid", "[sidecar]\nupstream_url = \"ftp://upstream\"\n");
+
+    assert_eq!(
+        output.status.code(),
+        Some(StartupCategory::ConfigInvalid.exit_code())
+    );
+}
+
+#[cfg(feature = "sentinel")]
+#[test]
+fn test_malformed_contract_path_exit_code() {
+    let output = run_sidecar(
+        "contract",
+        r#"
+[sidecar]
+listen_addr = "127.0.0.1"
+listen_port = 0
+upstream_url = "http://127.0.0.1:1"
+
+[contract]
+path = "/nonexistent/archimedes/contract.json"
+"#,
+    );
+
+    assert_eq!(
+        output.status.code(),
+        Some(StartupCategory::ContractLoad.exit_code())
+    );
+
+    let logs = String::from_utf8_lossy(&output.stdout);
+    let record = logs
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .find(|record| record["fields"]["message"] == "startup failed")
+        .expect("final startup failure record");
+    assert_eq!(record["fields"]["category"], "contract_load");
+    assert_eq!(record["fields"]["transient"], false);
+    assert!(record["fields"]["remediation"].is_string());
+}
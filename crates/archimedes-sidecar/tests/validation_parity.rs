@@ -0,0 +1,135 @@
+//! Request validation parity between the server and the sidecar.
+//!
+//! Both paths validate through the same Sentinel, so a request the contract
+//! rejects gets the same `400` whether it reaches a native handler or is
+//! proxied to an upstream service.
+
+#![cfg(feature = "sentinel")]
+
+use std::sync::Arc;
+
+use archimedes_core::contract::{Contract, MockSchema, Operation};
+use archimedes_middleware::stages::ValidationMiddleware;
+use archimedes_middleware::Pipeline;
+use archimedes_sentinel::{fixtures, Sentinel};
+use archimedes_server::{HandlerRegistry, Server, ShutdownSignal};
+use archimedes_sidecar::{MiddlewarePipeline, ProxyRequest, SidecarConfig, SidecarError};
+use bytes::Bytes;
+use http::{HeaderValue, Method};
+use serde_json::Value;
+use tokio::net::TcpListener;
+
+fn contract() -> Contract {
+    Contract::builder("users")
+        .operation(
+            Operation::builder("createUser")
+                .method(Method::POST)
+                .path("/users")
+                .request_schema(
+                    MockSchema::object(vec![
+                        ("name", MockSchema::string().required()),
+                        ("age", MockSchema::integer()),
+                    ])
+                    .required(),
+                )
+                .build(),
+        )
+        .build()
+}
+
+fn sentinel() -> Sentinel {
+    Sentinel::with_defaults(fixtures::from_contract(&contract()))
+}
+
+/// Starts a server whose pipeline validates against the contract.
+async fn start_server() -> (String, ShutdownSignal) {
+    let mut registry = HandlerRegistry::new();
+    registry.register("createUser", |_ctx, body: Value| async move {
+        Ok::<_, archimedes_server::HandlerError>(body)
+    });
+
+    let pipeline = Pipeline::builder()
+        .add_pre_handler_stage(ValidationMiddleware::sentinel(sentinel()))
+        .build();
+    let mut server = Server::builder()
+        .handlers(registry)
+        .pipeline(pipeline)
+        .build();
+    server
+        .router_mut()
+        .add_route(Method::POST, "/users", "createUser");
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let shutdown = ShutdownSignal::new();
+    tokio::spawn(server.run_with_listener(listener, shutdown.clone()));
+
+    (format!("http://{addr}/users"), shutdown)
+}
+
+/// Sends `body` to the server, returning the status and error message.
+async fn through_server(url: &str, body: &'static str) -> (u16, Option<String>) {
+    let response = reqwest::Client::new()
+        .post(url)
+        .header("content-type", "application/json")
+        .body(body)
+        .send()
+        .await
+        .unwrap();
+    let status = response.status().as_u16();
+    let body: Value = response.json().await.unwrap();
+    let message = body["error"]["message"].as_str().map(ToString::to_string);
+    (status, message)
+}
+
+/// Runs `body` through the sidecar pipeline, returning the status it would
+/// answer with and the error message.
+async fn through_sidecar(
+    pipeline: &MiddlewarePipeline,
+    body: &'static str,
+) -> (u16, Option<String>) {
+    let mut request = ProxyRequest::new(Method::POST, "/users");
+    request
+        .headers
+        .insert("content-type", HeaderValue::from_static("application/json"));
+
+    match pipeline.process(&request, &Bytes::from(body)).await {
+        Ok(_) => (200, None),
+        Err(e) => {
+            let message = match &e {
+                SidecarError::Validation { message, .. } => Some(message.clone()),
+                other => Some(other.to_string()),
+            };
+            (e.status_code(), message)
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_server_and_sidecar_reject_alike() {
+    let (url, shutdown) = start_server().await;
+    let sidecar = MiddlewarePipeline::new(Arc::new(SidecarConfig::default()))
+        .await
+        .unwrap()
+        .with_sentinel(sentinel());
+
+    let invalid = [
+        r#"{"age": 30}"#,
+        r#"{"name": "Ada", "age": "thirty"}"#,
+        r#"{"name": "#,
+    ];
+    for body in invalid {
+        let server = through_server(&url, body).await;
+        let sidecar = through_sidecar(&sidecar, body).await;
+
+        assert_eq!(server.0, 400, "server status for {body}");
+        assert!(server.1.is_some(), "server message for {body}");
+        assert_eq!(server, sidecar, "responses differ for {body}");
+    }
+
+    let valid = r#"{"name": "Ada", "age": 30}"#;
+    assert_eq!(through_server(&url, valid).await.0, 200);
+    assert_eq!(through_sidecar(&sidecar, valid).await, (200, None));
+
+    shutdown.trigger();
+}
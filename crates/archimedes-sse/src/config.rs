@@ -4,13 +4,24 @@
 
 use std::time::Duration;
 
+/// Default text of the keep-alive comment.
+pub const DEFAULT_KEEP_ALIVE_COMMENT: &str = "keepalive";
+
 /// Configuration for SSE streams.
 #[derive(Debug, Clone)]
 pub struct SseConfig {
     /// Buffer size for the event channel.
     pub buffer_size: usize,
     /// Keep-alive interval (sends comment to keep connection alive).
+    ///
+    /// A keep-alive is sent once the stream has been idle this long. `None`
+    /// or a zero interval disables keep-alive.
     pub keep_alive_interval: Option<Duration>,
+    /// Text of the keep-alive comment.
+    ///
+    /// Each line is sent as a `:`-prefixed comment line; an empty text sends
+    /// a bare `:` line.
+    pub keep_alive_comment: String,
     /// Default retry interval to suggest to clients.
     pub default_retry: Option<Duration>,
     /// Maximum number of queued events before backpressure.
@@ -22,6 +33,7 @@ impl Default for SseConfig {
         Self {
             buffer_size: 32,
             keep_alive_interval: Some(Duration::from_secs(15)),
+            keep_alive_comment: DEFAULT_KEEP_ALIVE_COMMENT.to_string(),
             default_retry: Some(Duration::from_secs(3)),
             max_queued_events: 256,
         }
@@ -57,6 +69,15 @@ impl SseConfig {
         self
     }
 
+    /// Set the text of the keep-alive comment.
+    ///
+    /// Some proxies only recognize a specific keep-alive line; pass an empty
+    /// text to send a zero-length comment.
+    pub fn keep_alive_comment(mut self, text: impl Into<String>) -> Self {
+        self.keep_alive_comment = text.into();
+        self
+    }
+
     /// Set the default retry interval.
     pub fn with_default_retry(mut self, retry: Duration) -> Self {
         self.default_retry = Some(retry);
@@ -75,6 +96,7 @@ impl SseConfig {
 pub struct SseConfigBuilder {
     buffer_size: Option<usize>,
     keep_alive_interval: Option<Option<Duration>>,
+    keep_alive_comment: Option<String>,
     default_retry: Option<Option<Duration>>,
     max_queued_events: Option<usize>,
 }
@@ -98,6 +120,12 @@ impl SseConfigBuilder {
         self
     }
 
+    /// Set the text of the keep-alive comment.
+    pub fn keep_alive_comment(mut self, text: impl Into<String>) -> Self {
+        self.keep_alive_comment = Some(text.into());
+        self
+    }
+
     /// Set the default retry interval.
     pub fn default_retry(mut self, retry: Duration) -> Self {
         self.default_retry = Some(Some(retry));
@@ -120,6 +148,9 @@ impl SseConfigBuilder {
         if let Some(interval) = self.keep_alive_interval {
             config.keep_alive_interval = interval;
         }
+        if let Some(text) = self.keep_alive_comment {
+            config.keep_alive_comment = text;
+        }
         if let Some(retry) = self.default_retry {
             config.default_retry = retry;
         }
@@ -140,6 +171,7 @@ mod tests {
         let config = SseConfig::default();
         assert_eq!(config.buffer_size, 32);
         assert!(config.keep_alive_interval.is_some());
+        assert_eq!(config.keep_alive_comment, "keepalive");
         assert!(config.default_retry.is_some());
     }
 
@@ -148,12 +180,14 @@ mod tests {
         let config = SseConfig::builder()
             .buffer_size(64)
             .keep_alive_interval(Duration::from_secs(30))
+            .keep_alive_comment("ping")
             .default_retry(Duration::from_secs(5))
             .max_queued_events(512)
             .build();
 
         assert_eq!(config.buffer_size, 64);
         assert_eq!(config.keep_alive_interval, Some(Duration::from_secs(30)));
+        assert_eq!(config.keep_alive_comment, "ping");
         assert_eq!(config.default_retry, Some(Duration::from_secs(5)));
         assert_eq!(config.max_queued_events, 512);
     }
@@ -51,6 +51,24 @@ impl SseEvent {
         }
     }
 
+    /// Create a structured `error` event.
+    ///
+    /// The data is a JSON object with a `message` field, so clients can
+    /// tell a stream that failed from one that ended cleanly.
+    pub fn error(message: impl Into<String>) -> Self {
+        let data = serde_json::json!({ "message": message.into() }).to_string();
+        Self::new(data).event("error")
+    }
+
+    /// Create a structured `shutdown` event.
+    ///
+    /// Sent as the last event of a stream when the server shuts down; the
+    /// data is a JSON object with a `message` field.
+    pub fn shutdown(message: impl Into<String>) -> Self {
+        let data = serde_json::json!({ "message": message.into() }).to_string();
+        Self::new(data).event("shutdown")
+    }
+
     /// Create an SSE event from a JSON-serializable value.
     pub fn json<T: Serialize>(value: &T) -> SseResult<Self> {
         let data = serde_json::to_string(value)
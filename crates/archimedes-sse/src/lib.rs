@@ -9,9 +9,15 @@
 //!
 //! - **Event Types**: Structured SSE events with ID, type, data, and retry fields
 //! - **Async Streaming**: Tokio-based async event streaming
-//! - **Keep-Alive**: Automatic keep-alive comments to maintain connections
+//! - **Keep-Alive**: Keep-alive comments on idle streams, with a configurable
+//!   interval and comment text
 //! - **Backpressure**: Channel-based flow control with configurable buffer sizes
 //! - **Multiple Senders**: Clone-able sender for multi-producer scenarios
+//! - **Stream Adapters**: Turn any `Stream` into an SSE body with [`SseStream::from_stream`]
+//! - **Graceful Shutdown**: Streams tracked by an [`SseRegistry`] end with a
+//!   terminal event when the server shuts down
+//! - **Contract Validation**: Validate event data against the schema of each
+//!   event type with `ValidatingSseSender` (requires the `sentinel` feature)
 //!
 //! ## Example
 //!
@@ -73,19 +79,28 @@
 mod config;
 mod error;
 mod event;
+mod registry;
 mod stream;
+#[cfg(feature = "sentinel")]
+mod validation;
 
-pub use config::{SseConfig, SseConfigBuilder};
+pub use config::{SseConfig, SseConfigBuilder, DEFAULT_KEEP_ALIVE_COMMENT};
 pub use error::{SseError, SseResult};
 pub use event::{SseComment, SseEvent, SseItem};
+pub use registry::SseRegistry;
 pub use stream::{sse_response, SseSender, SseStream};
+#[cfg(feature = "sentinel")]
+pub use validation::{SseValidationMode, ValidatingSseSender, VALIDATION_FAILURES};
 
 /// Prelude module for convenient imports.
 pub mod prelude {
     pub use crate::config::SseConfig;
     pub use crate::error::{SseError, SseResult};
     pub use crate::event::{SseComment, SseEvent, SseItem};
+    pub use crate::registry::SseRegistry;
     pub use crate::stream::{sse_response, SseSender, SseStream};
+    #[cfg(feature = "sentinel")]
+    pub use crate::validation::{SseValidationMode, ValidatingSseSender};
 }
 
 #[cfg(test)]
@@ -0,0 +1,246 @@
+//! Registry of active SSE streams for graceful shutdown.
+//!
+//! SSE responses stay open until the client goes away, so without help a
+//! shutting-down server waits out its whole shutdown timeout and then cuts
+//! them off. Streams tracked by an [`SseRegistry`] instead end with a
+//! terminal event when the registry shuts down, and
+//! [`drain`](SseRegistry::drain) waits for them to finish.
+//!
+//! # Example
+//!
+//! ```rust
+//! use archimedes_sse::{SseEvent, SseRegistry, SseStream};
+//!
+//! # tokio_test::block_on(async {
+//! let registry = SseRegistry::new();
+//!
+//! let (sender, stream) = SseStream::new();
+//! let stream = registry.track(stream);
+//! assert_eq!(registry.len(), 1);
+//!
+//! // On shutdown every tracked stream sends this event, then ends
+//! registry.shutdown(SseEvent::shutdown("server shutting down"));
+//! # drop((sender, stream));
+//! # });
+//! ```
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use tokio::sync::{mpsc, Notify};
+
+use crate::event::SseEvent;
+use crate::stream::SseStream;
+
+/// Tracks active SSE streams and ends them on shutdown.
+///
+/// Cloning the registry shares the tracked streams.
+#[derive(Debug, Clone, Default)]
+pub struct SseRegistry {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    state: Mutex<State>,
+    next_id: AtomicU64,
+    closed: Notify,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Terminal event senders of the tracked streams.
+    streams: HashMap<u64, mpsc::UnboundedSender<SseEvent>>,
+    /// The terminal event, once the registry has shut down.
+    terminal: Option<SseEvent>,
+}
+
+impl Inner {
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl SseRegistry {
+    /// Create an empty registry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Track a stream until it is dropped.
+    ///
+    /// Streams tracked after [`shutdown`](Self::shutdown) send the
+    /// terminal event straight away.
+    #[must_use]
+    pub fn track(&self, stream: SseStream) -> SseStream {
+        let id = self.inner.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        let mut state = self.inner.state();
+        if let Some(terminal) = &state.terminal {
+            let _ = tx.send(terminal.clone());
+        }
+        state.streams.insert(id, tx);
+        drop(state);
+
+        stream.with_terminal(
+            rx,
+            Registration {
+                inner: Arc::clone(&self.inner),
+                id,
+            },
+        )
+    }
+
+    /// Get the number of tracked streams.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.inner.state().streams.len()
+    }
+
+    /// Check whether no streams are tracked.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Check whether the registry has shut down.
+    #[must_use]
+    pub fn is_shutdown(&self) -> bool {
+        self.inner.state().terminal.is_some()
+    }
+
+    /// Send `event` as the last event of every tracked stream.
+    ///
+    /// Each stream yields the event ahead of anything still buffered and
+    /// then ends. Returns the number of streams notified; a second call
+    /// notifies none.
+    pub fn shutdown(&self, event: SseEvent) -> usize {
+        let mut state = self.inner.state();
+        if state.terminal.is_some() {
+            return 0;
+        }
+
+        for tx in state.streams.values() {
+            let _ = tx.send(event.clone());
+        }
+        state.terminal = Some(event);
+        let count = state.streams.len();
+        drop(state);
+
+        tracing::info!(streams = count, "Shutting down SSE streams");
+        count
+    }
+
+    /// Shut down with `event` and wait for every tracked stream to be
+    /// dropped.
+    ///
+    /// A stream is dropped once the server has written its terminal event
+    /// and finished the response. Bound this with a timeout to force
+    /// shutdown past slow clients.
+    pub async fn drain(&self, event: SseEvent) {
+        self.shutdown(event);
+
+        loop {
+            let closed = self.inner.closed.notified();
+            tokio::pin!(closed);
+            closed.as_mut().enable();
+            if self.is_empty() {
+                return;
+            }
+            closed.await;
+        }
+    }
+}
+
+/// Keeps a stream tracked by its registry until dropped.
+#[derive(Debug)]
+pub(crate) struct Registration {
+    inner: Arc<Inner>,
+    id: u64,
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        let mut state = self.inner.state();
+        state.streams.remove(&self.id);
+        if state.streams.is_empty() {
+            self.inner.closed.notify_waiters();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::SseSender;
+    use futures_util::StreamExt;
+    use std::time::Duration;
+
+    /// Creates a tracked stream past its initial retry hint.
+    async fn tracked(registry: &SseRegistry) -> (SseSender, SseStream) {
+        let (sender, stream) = SseStream::new();
+        let mut stream = registry.track(stream);
+        let retry = stream.next().await.unwrap().unwrap();
+        assert!(retry.starts_with(b"retry: "));
+        (sender, stream)
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_sends_terminal_event_and_ends_stream() {
+        let registry = SseRegistry::new();
+        let (sender, mut stream) = tracked(&registry).await;
+
+        sender.send_text("before").await.unwrap();
+        assert_eq!(registry.shutdown(SseEvent::shutdown("bye")), 1);
+        assert_eq!(registry.shutdown(SseEvent::shutdown("again")), 0);
+
+        let first = stream.next().await.unwrap().unwrap();
+        assert_eq!(
+            first,
+            "event: shutdown\ndata: {\"message\":\"bye\"}\n\n".as_bytes()
+        );
+        assert!(stream.next().await.is_none());
+        assert!(stream.is_closed());
+
+        // The producer sees the stream go away
+        drop(stream);
+        assert!(sender.send_text("after").await.is_err());
+        assert!(registry.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_drain_waits_for_streams() {
+        let registry = SseRegistry::new();
+        let (_sender, mut stream) = tracked(&registry).await;
+
+        let drain = tokio::spawn({
+            let registry = registry.clone();
+            async move { registry.drain(SseEvent::shutdown("bye")).await }
+        });
+
+        let terminal = stream.next().await.unwrap().unwrap();
+        assert!(terminal.starts_with(b"event: shutdown\n"));
+        assert!(!drain.is_finished());
+
+        drop(stream);
+        tokio::time::timeout(Duration::from_secs(1), drain)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_track_after_shutdown_ends_immediately() {
+        let registry = SseRegistry::new();
+        registry.shutdown(SseEvent::shutdown("bye"));
+        assert!(registry.is_shutdown());
+
+        let (_sender, mut stream) = tracked(&registry).await;
+        let terminal = stream.next().await.unwrap().unwrap();
+        assert!(terminal.starts_with(b"event: shutdown\n"));
+        assert!(stream.next().await.is_none());
+    }
+}
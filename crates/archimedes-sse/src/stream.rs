@@ -11,11 +11,12 @@ use std::time::Duration;
 use bytes::Bytes;
 use futures_util::Stream;
 use tokio::sync::mpsc;
-use tokio::time::{interval, Interval};
+use tokio::time::{interval_at, Instant, Interval, MissedTickBehavior};
 
 use crate::config::SseConfig;
 use crate::error::{SseError, SseResult};
 use crate::event::{SseComment, SseEvent, SseItem};
+use crate::registry::Registration;
 
 /// A sender for SSE events.
 ///
@@ -115,6 +116,25 @@ impl SseSender {
         }
     }
 
+    /// Send a final `error` event and close the stream.
+    ///
+    /// Use this instead of dropping the sender when the producer fails, so
+    /// the client sees an `event: error` rather than a silent end of stream.
+    pub async fn fail(self, message: impl Into<String>) -> SseResult<()> {
+        if self.closed.load(Ordering::Acquire) {
+            return Err(SseError::stream_closed("stream is closed"));
+        }
+
+        let result = self
+            .tx
+            .send(SseItem::Event(SseEvent::error(message)))
+            .await
+            .map_err(|_| SseError::send_failed("receiver dropped"));
+
+        self.close();
+        result
+    }
+
     /// Check if the stream is closed.
     pub fn is_closed(&self) -> bool {
         self.closed.load(Ordering::Acquire) || self.tx.is_closed()
@@ -131,15 +151,63 @@ impl SseSender {
     }
 }
 
+/// Keep-alive comments sent while a stream is idle.
+struct KeepAlive {
+    interval: Interval,
+    comment: Bytes,
+}
+
+impl KeepAlive {
+    fn new(config: &SseConfig) -> Option<Self> {
+        let period = config
+            .keep_alive_interval
+            .filter(|period| !period.is_zero())?;
+        let mut interval = interval_at(Instant::now() + period, period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        Some(Self {
+            interval,
+            comment: keep_alive_bytes(&config.keep_alive_comment),
+        })
+    }
+
+    /// Push the next keep-alive back a full interval.
+    fn reset(&mut self) {
+        self.interval.reset();
+    }
+}
+
+/// Format the keep-alive comment, prefixing every line with `:` so a
+/// multi-line text cannot be read as event fields.
+fn keep_alive_bytes(text: &str) -> Bytes {
+    let mut out = String::with_capacity(text.len() + 4);
+    for line in text.split(['\r', '\n']) {
+        out.push(':');
+        if !line.is_empty() {
+            out.push(' ');
+            out.push_str(line);
+        }
+        out.push('\n');
+    }
+    out.push('\n');
+    Bytes::from(out)
+}
+
 /// An SSE stream that can be used as an HTTP response body.
 ///
 /// This stream yields bytes that are properly formatted SSE messages.
 pub struct SseStream {
     rx: mpsc::Receiver<SseItem>,
-    keep_alive: Option<Interval>,
+    keep_alive: Option<KeepAlive>,
     closed: Arc<AtomicBool>,
     initial_retry: Option<Duration>,
     sent_initial: bool,
+    /// Terminal event sent by the registry tracking this stream.
+    terminal: Option<mpsc::UnboundedReceiver<SseEvent>>,
+    /// Whether the terminal event has been sent.
+    ending: bool,
+    /// Keeps this stream tracked by its registry until dropped.
+    _registration: Option<Registration>,
 }
 
 impl SseStream {
@@ -153,9 +221,7 @@ impl SseStream {
         let (tx, rx) = mpsc::channel(config.buffer_size);
         let closed = Arc::new(AtomicBool::new(false));
 
-        let keep_alive = config
-            .keep_alive_interval
-            .map(|duration| interval(duration));
+        let keep_alive = KeepAlive::new(&config);
 
         let sender = SseSender {
             tx,
@@ -169,23 +235,63 @@ impl SseStream {
             closed,
             initial_retry: config.default_retry,
             sent_initial: false,
+            terminal: None,
+            ending: false,
+            _registration: None,
         };
 
         (sender, stream)
     }
 
-    /// Create a stream from a futures Stream.
-    pub fn from_stream<S>(stream: S) -> Self
+    /// Create a stream from a futures Stream of SSE items.
+    pub fn from_items<S>(stream: S) -> Self
     where
         S: Stream<Item = SseItem> + Send + 'static,
     {
-        Self::from_stream_with_config(stream, SseConfig::default())
+        Self::from_items_with_config(stream, SseConfig::default())
     }
 
-    /// Create a stream from a futures Stream with configuration.
-    pub fn from_stream_with_config<S>(stream: S, config: SseConfig) -> Self
+    /// Create a stream from a futures Stream of SSE items with configuration.
+    pub fn from_items_with_config<S>(stream: S, config: SseConfig) -> Self
     where
         S: Stream<Item = SseItem> + Send + 'static,
+    {
+        Self::from_stream_with_config(stream, Ok, config)
+    }
+
+    /// Adapt an arbitrary Stream into an SSE stream.
+    ///
+    /// Each item is turned into an event by `map_fn`, typically by
+    /// serializing it with [`SseEvent::json`]. Items are pulled from the
+    /// source only as fast as the client consumes them, bounded by the
+    /// configured buffer size. When the client disconnects, the source
+    /// stream is dropped. If `map_fn` fails, an `error` event is sent and
+    /// the stream ends.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let stream = SseStream::from_stream(change_feed, |change| {
+    ///     SseEvent::json(&change).map(|event| event.event("change"))
+    /// });
+    /// ```
+    pub fn from_stream<S, F, E>(stream: S, map_fn: F) -> Self
+    where
+        S: Stream + Send + 'static,
+        F: FnMut(S::Item) -> SseResult<E> + Send + 'static,
+        E: Into<SseItem>,
+    {
+        Self::from_stream_with_config(stream, map_fn, SseConfig::default())
+    }
+
+    /// Adapt an arbitrary Stream into an SSE stream with configuration.
+    ///
+    /// See [`SseStream::from_stream`].
+    pub fn from_stream_with_config<S, F, E>(stream: S, mut map_fn: F, config: SseConfig) -> Self
+    where
+        S: Stream + Send + 'static,
+        F: FnMut(S::Item) -> SseResult<E> + Send + 'static,
+        E: Into<SseItem>,
     {
         let (tx, rx) = mpsc::channel(config.buffer_size);
         let closed = Arc::new(AtomicBool::new(false));
@@ -196,7 +302,25 @@ impl SseStream {
             use futures_util::StreamExt;
             tokio::pin!(stream);
 
-            while let Some(item) = stream.next().await {
+            loop {
+                // Stop as soon as the client goes away, even if the source is idle
+                let next = tokio::select! {
+                    biased;
+                    () = tx.closed() => break,
+                    next = stream.next() => next,
+                };
+                let Some(next) = next else { break };
+
+                let item = match map_fn(next) {
+                    Ok(item) => item.into(),
+                    Err(e) => {
+                        tracing::warn!(error = %e, "failed to convert stream item to SSE event");
+                        let _ = tx
+                            .send(SseItem::Event(SseEvent::error(e.to_string())))
+                            .await;
+                        break;
+                    }
+                };
                 if tx.send(item).await.is_err() {
                     break;
                 }
@@ -205,9 +329,7 @@ impl SseStream {
             closed_clone.store(true, Ordering::Release);
         });
 
-        let keep_alive = config
-            .keep_alive_interval
-            .map(|duration| interval(duration));
+        let keep_alive = KeepAlive::new(&config);
 
         Self {
             rx,
@@ -215,6 +337,9 @@ impl SseStream {
             closed,
             initial_retry: config.default_retry,
             sent_initial: false,
+            terminal: None,
+            ending: false,
+            _registration: None,
         }
     }
 
@@ -223,6 +348,17 @@ impl SseStream {
         self.closed.load(Ordering::Acquire)
     }
 
+    /// End the stream with the first event received on `terminal`.
+    pub(crate) fn with_terminal(
+        mut self,
+        terminal: mpsc::UnboundedReceiver<SseEvent>,
+        registration: Registration,
+    ) -> Self {
+        self.terminal = Some(terminal);
+        self._registration = Some(registration);
+        self
+    }
+
     /// Get the retry comment for initial connection.
     fn initial_retry_bytes(&self) -> Option<Bytes> {
         self.initial_retry
@@ -248,18 +384,42 @@ impl Stream for SseStream {
             }
         }
 
-        // Try to receive an item
+        if self.ending {
+            self.closed.store(true, Ordering::Release);
+            return Poll::Ready(None);
+        }
+
+        // A terminal event from the registry preempts buffered items
+        if let Some(terminal) = self.terminal.as_mut() {
+            match terminal.poll_recv(cx) {
+                Poll::Ready(Some(event)) => {
+                    self.ending = true;
+                    self.rx.close();
+                    return Poll::Ready(Some(Ok(event.to_bytes())));
+                }
+                Poll::Ready(None) => self.terminal = None,
+                Poll::Pending => {}
+            }
+        }
+
+        // Try to receive an item. Each item is written as one chunk, so a
+        // keep-alive can only fall between events, never inside one.
         match self.rx.poll_recv(cx) {
-            Poll::Ready(Some(item)) => Poll::Ready(Some(Ok(item.to_bytes()))),
+            Poll::Ready(Some(item)) => {
+                if let Some(keep_alive) = self.keep_alive.as_mut() {
+                    keep_alive.reset();
+                }
+                Poll::Ready(Some(Ok(item.to_bytes())))
+            }
             Poll::Ready(None) => {
                 self.closed.store(true, Ordering::Release);
                 Poll::Ready(None)
             }
             Poll::Pending => {
                 // Check keepalive timer
-                if let Some(ref mut keepalive) = self.keep_alive {
-                    if keepalive.poll_tick(cx).is_ready() {
-                        return Poll::Ready(Some(Ok(Bytes::from(": keepalive\n\n"))));
+                if let Some(keep_alive) = self.keep_alive.as_mut() {
+                    if keep_alive.interval.poll_tick(cx).is_ready() {
+                        return Poll::Ready(Some(Ok(keep_alive.comment.clone())));
                     }
                 }
                 Poll::Pending
@@ -388,6 +548,26 @@ mod tests {
         assert!(String::from_utf8_lossy(&item).contains("data: immediate"));
     }
 
+    #[tokio::test]
+    async fn test_sender_fail_sends_error_event() {
+        let (sender, mut stream) = SseStream::new();
+        let other = sender.clone();
+
+        sender.send_text("partial").await.unwrap();
+        sender.fail("upstream unavailable").await.unwrap();
+
+        // Skip initial retry
+        let _ = stream.next().await;
+
+        let _ = stream.next().await.unwrap().unwrap();
+        let item = stream.next().await.unwrap().unwrap();
+        let text = String::from_utf8_lossy(&item);
+        assert!(text.contains("event: error"));
+        assert!(text.contains(r#"data: {"message":"upstream unavailable"}"#));
+
+        assert!(other.send_text("late").await.is_err());
+    }
+
     #[tokio::test]
     async fn test_sender_closed() {
         let (sender, stream) = SseStream::new();
@@ -474,7 +654,7 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_from_stream() {
+    async fn test_from_items() {
         let items = vec![
             SseItem::event(SseEvent::new("one")),
             SseItem::event(SseEvent::new("two")),
@@ -486,7 +666,7 @@ mod tests {
             default_retry: None,
             ..config
         };
-        let mut stream = SseStream::from_stream_with_config(source, config);
+        let mut stream = SseStream::from_items_with_config(source, config);
 
         let item1 = stream.next().await.unwrap().unwrap();
         assert!(String::from_utf8_lossy(&item1).contains("data: one"));
@@ -494,4 +674,119 @@ mod tests {
         let item2 = stream.next().await.unwrap().unwrap();
         assert!(String::from_utf8_lossy(&item2).contains("data: two"));
     }
+
+    #[tokio::test]
+    async fn test_from_stream_serializes_items() {
+        #[derive(serde::Serialize)]
+        struct Change {
+            id: u32,
+        }
+
+        let source = futures_util::stream::iter((1..=3).map(|id| Change { id }));
+        let config = SseConfig {
+            default_retry: None,
+            keep_alive_interval: None,
+            ..SseConfig::default()
+        };
+        let stream = SseStream::from_stream_with_config(
+            source,
+            |change| SseEvent::json(&change).map(|event| event.event("change")),
+            config,
+        );
+
+        let items: Vec<_> = stream.map(Result::unwrap).collect().await;
+        assert_eq!(items.len(), 3);
+        for (id, item) in (1..=3).zip(&items) {
+            let text = String::from_utf8_lossy(item);
+            assert!(text.contains("event: change"));
+            assert!(text.contains(&format!(r#"data: {{"id":{id}}}"#)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_from_stream_disconnect_drops_source() {
+        struct DropFlag(Arc<AtomicBool>);
+
+        impl Drop for DropFlag {
+            fn drop(&mut self) {
+                self.0.store(true, Ordering::Release);
+            }
+        }
+
+        let dropped = Arc::new(AtomicBool::new(false));
+        let flag = DropFlag(dropped.clone());
+        // An idle source that would never yield another item
+        let source = futures_util::stream::iter(vec!["first"])
+            .chain(futures_util::stream::pending())
+            .map(move |item| {
+                let _ = &flag;
+                item
+            });
+
+        let mut stream = SseStream::from_stream(source, |item| Ok(SseEvent::new(item)));
+
+        // Skip initial retry
+        let _ = stream.next().await;
+        let item = stream.next().await.unwrap().unwrap();
+        assert!(String::from_utf8_lossy(&item).contains("data: first"));
+
+        drop(stream);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        assert!(dropped.load(Ordering::Acquire));
+    }
+
+    fn keep_alive_config(text: &str) -> SseConfig {
+        SseConfig {
+            default_retry: None,
+            ..SseConfig::default()
+        }
+        .with_keep_alive(Duration::from_secs(10))
+        .keep_alive_comment(text)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_custom_keep_alive_comment_at_interval() {
+        let (sender, mut stream) = SseStream::with_config(keep_alive_config("ping"));
+        let start = Instant::now();
+
+        let item = stream.next().await.unwrap().unwrap();
+        assert_eq!(item, ": ping\n\n");
+        assert_eq!(start.elapsed(), Duration::from_secs(10));
+
+        let item = stream.next().await.unwrap().unwrap();
+        assert_eq!(item, ": ping\n\n");
+        assert_eq!(start.elapsed(), Duration::from_secs(20));
+
+        // An event postpones the next keep-alive and arrives in one piece
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        sender.send_text("line1\nline2").await.unwrap();
+        let item = stream.next().await.unwrap().unwrap();
+        assert_eq!(item, "data: line1\ndata: line2\n\n");
+
+        let item = stream.next().await.unwrap().unwrap();
+        assert_eq!(item, ": ping\n\n");
+        assert_eq!(start.elapsed(), Duration::from_secs(35));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_keep_alive_comment_formatting() {
+        let (_sender, mut stream) = SseStream::with_config(keep_alive_config(""));
+        assert_eq!(stream.next().await.unwrap().unwrap(), ":\n\n");
+
+        let (_sender, mut stream) = SseStream::with_config(keep_alive_config("a\nb"));
+        assert_eq!(stream.next().await.unwrap().unwrap(), ": a\n: b\n\n");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_disabled_keep_alive_emits_nothing() {
+        for config in [
+            keep_alive_config("ping").without_keep_alive(),
+            keep_alive_config("ping").with_keep_alive(Duration::ZERO),
+        ] {
+            let (_sender, mut stream) = SseStream::with_config(config);
+            let next = tokio::time::timeout(Duration::from_secs(3600), stream.next()).await;
+            assert!(next.is_err());
+        }
+    }
 }
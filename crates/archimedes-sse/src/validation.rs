@@ -0,0 +1,361 @@
+//! Contract validation of streamed events.
+//!
+//! A [`ValidatingSseSender`] checks the JSON data of each event against the
+//! schema the operation's contract declares for the event's `event:` type
+//! (see `LoadedOperation::event_schemas`). Events sent without a type are
+//! checked against the `message` schema. Events whose data is not JSON,
+//! events of a type without a schema, and comments, including keep-alives,
+//! are sent unchecked.
+//!
+//! Failures are counted in `archimedes_sse_validation_failures_total`,
+//! labelled by `operation` and `event`. What happens to the event depends
+//! on the [`SseValidationMode`].
+
+use std::sync::Arc;
+
+use archimedes_sentinel::Sentinel;
+use metrics::counter;
+use tracing::warn;
+
+use crate::error::SseResult;
+use crate::event::SseEvent;
+use crate::stream::SseSender;
+
+/// Events that failed validation, by operation and event type.
+pub const VALIDATION_FAILURES: &str = "archimedes_sse_validation_failures_total";
+
+/// The `event:` type of events sent without one.
+const DEFAULT_EVENT_TYPE: &str = "message";
+
+/// What to do with an event that fails validation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SseValidationMode {
+    /// Log and count the failure, and send the event anyway.
+    #[default]
+    LogOnly,
+    /// Drop the event and send an error comment in its place.
+    Reject,
+}
+
+/// A sender that validates events against the operation's contract before
+/// sending them.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let (sender, stream) = SseStream::new();
+/// let sender = ValidatingSseSender::new(sender, sentinel, "streamFeed")
+///     .with_mode(SseValidationMode::Reject);
+///
+/// sender.send_json_event("post", &post).await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct ValidatingSseSender {
+    sender: SseSender,
+    sentinel: Arc<Sentinel>,
+    operation_id: String,
+    mode: SseValidationMode,
+}
+
+impl ValidatingSseSender {
+    /// Wrap a sender to validate events against the schemas of an
+    /// operation, logging failures.
+    pub fn new(
+        sender: SseSender,
+        sentinel: Arc<Sentinel>,
+        operation_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            sender,
+            sentinel,
+            operation_id: operation_id.into(),
+            mode: SseValidationMode::default(),
+        }
+    }
+
+    /// Set what to do with events that fail validation.
+    pub fn with_mode(mut self, mode: SseValidationMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Get the validation mode.
+    pub fn mode(&self) -> SseValidationMode {
+        self.mode
+    }
+
+    /// Get the ID of the operation whose schemas are used.
+    pub fn operation_id(&self) -> &str {
+        &self.operation_id
+    }
+
+    /// Get the wrapped sender, which sends without validation.
+    pub fn inner(&self) -> &SseSender {
+        &self.sender
+    }
+
+    /// Validate and send an event.
+    ///
+    /// In [`SseValidationMode::Reject`] an invalid event is replaced by an
+    /// error comment; this still returns `Ok`.
+    pub async fn send(&self, event: SseEvent) -> SseResult<()> {
+        match self.check(&event) {
+            Ok(()) => self.sender.send(event).await,
+            Err(message) => {
+                self.sender
+                    .send_comment(format!("validation error: {message}"))
+                    .await
+            }
+        }
+    }
+
+    /// Validate and send a JSON value as an event.
+    pub async fn send_json<T: serde::Serialize>(&self, value: &T) -> SseResult<()> {
+        self.send(SseEvent::json(value)?).await
+    }
+
+    /// Validate and send a JSON value as an event with a specific type.
+    pub async fn send_json_event<T: serde::Serialize>(
+        &self,
+        event_type: impl Into<String>,
+        value: &T,
+    ) -> SseResult<()> {
+        self.send(SseEvent::json(value)?.event(event_type)).await
+    }
+
+    /// Send a comment; comments are never validated.
+    pub async fn send_comment(&self, text: impl Into<String>) -> SseResult<()> {
+        self.sender.send_comment(text).await
+    }
+
+    /// Send a final `error` event and close the stream.
+    ///
+    /// See [`SseSender::fail`].
+    pub async fn fail(self, message: impl Into<String>) -> SseResult<()> {
+        self.sender.fail(message).await
+    }
+
+    /// Check if the stream is closed.
+    pub fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+
+    /// Get the number of events sent.
+    pub fn events_sent(&self) -> u64 {
+        self.sender.events_sent()
+    }
+
+    /// Close the sender.
+    pub fn close(&self) {
+        self.sender.close();
+    }
+
+    /// Validate an event, returning the failure message if it must not be
+    /// sent.
+    fn check(&self, event: &SseEvent) -> Result<(), String> {
+        let Ok(data) = serde_json::from_str::<serde_json::Value>(event.data()) else {
+            return Ok(());
+        };
+        let event_type = event.event_type().unwrap_or(DEFAULT_EVENT_TYPE);
+
+        let result = match self
+            .sentinel
+            .validate_event(&self.operation_id, event_type, &data)
+        {
+            Ok(result) => result,
+            Err(e) => {
+                warn!(
+                    operation_id = %self.operation_id,
+                    event_type,
+                    error = %e,
+                    "event validation error, sending event unchecked"
+                );
+                return Ok(());
+            }
+        };
+        if result.valid {
+            return Ok(());
+        }
+
+        let message = result
+            .errors
+            .iter()
+            .map(|error| {
+                if error.path.is_empty() {
+                    error.message.clone()
+                } else {
+                    format!("{}: {}", error.path, error.message)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        counter!(
+            VALIDATION_FAILURES,
+            "operation" => self.operation_id.clone(),
+            "event" => event_type.to_string()
+        )
+        .increment(1);
+        warn!(
+            operation_id = %self.operation_id,
+            event_type,
+            errors = %message,
+            mode = ?self.mode,
+            "event failed contract validation"
+        );
+
+        match self.mode {
+            SseValidationMode::LogOnly => Ok(()),
+            SseValidationMode::Reject => Err(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SseConfig;
+    use crate::stream::SseStream;
+    use archimedes_sentinel::ArtifactLoader;
+    use futures_util::StreamExt;
+    use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusRecorder};
+    use std::time::Duration;
+
+    fn sentinel() -> Arc<Sentinel> {
+        let doc = serde_json::json!({
+            "openapi": "3.1.0",
+            "info": { "title": "feed", "version": "1.0.0" },
+            "paths": {
+                "/feed": {
+                    "get": {
+                        "operationId": "streamFeed",
+                        "responses": {
+                            "200": {
+                                "description": "Feed",
+                                "content": {
+                                    "text/event-stream": {
+                                        "x-event-schemas": {
+                                            "post": {
+                                                "type": "object",
+                                                "required": ["id"],
+                                                "properties": { "id": { "type": "integer" } }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        });
+        let artifact = ArtifactLoader::from_document(&doc.to_string()).unwrap();
+        Arc::new(Sentinel::with_defaults(artifact))
+    }
+
+    /// Run `test` on a current-thread runtime with `recorder` installed.
+    fn with_recorder<F>(recorder: &PrometheusRecorder, test: F)
+    where
+        F: std::future::Future<Output = ()>,
+    {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        metrics::with_local_recorder(recorder, || runtime.block_on(test));
+    }
+
+    /// Send a mix of valid, invalid, untyped and non-JSON events, returning
+    /// the stream output.
+    async fn send_mixed(mode: SseValidationMode) -> Vec<String> {
+        let config = SseConfig {
+            default_retry: None,
+            keep_alive_interval: None,
+            ..SseConfig::default()
+        };
+        let (sender, stream) = SseStream::with_config(config);
+        let sender = ValidatingSseSender::new(sender, sentinel(), "streamFeed").with_mode(mode);
+
+        let valid = serde_json::json!({ "id": 1 });
+        let invalid = serde_json::json!({ "id": "one" });
+        sender.send_json_event("post", &valid).await.unwrap();
+        sender.send_json_event("post", &invalid).await.unwrap();
+        sender
+            .send(SseEvent::new("not json").event("post"))
+            .await
+            .unwrap();
+        sender.send_json(&invalid).await.unwrap();
+        drop(sender);
+
+        stream
+            .map(|item| String::from_utf8_lossy(&item.unwrap()).into_owned())
+            .collect()
+            .await
+    }
+
+    #[test]
+    fn test_log_only_sends_invalid_events() {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+
+        with_recorder(&recorder, async {
+            let output = send_mixed(SseValidationMode::LogOnly).await;
+            assert_eq!(output.len(), 4);
+            assert!(output[1].contains(r#"data: {"id":"one"}"#));
+            assert!(output.iter().all(|item| !item.starts_with(':')));
+        });
+
+        let rendered = handle.render();
+        assert!(rendered.contains(
+            r#"archimedes_sse_validation_failures_total{operation="streamFeed",event="post"} 1"#
+        ));
+    }
+
+    #[test]
+    fn test_reject_replaces_invalid_events_with_comment() {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+
+        with_recorder(&recorder, async {
+            let output = send_mixed(SseValidationMode::Reject).await;
+            assert_eq!(output.len(), 4);
+            assert!(output[0].contains(r#"data: {"id":1}"#));
+            assert!(output[1].starts_with(": validation error: id: expected integer"));
+            // Non-JSON data and event types without a schema are not validated
+            assert!(output[2].contains("data: not json"));
+            assert!(output[3].contains(r#"data: {"id":"one"}"#));
+        });
+
+        assert!(handle.render().contains(
+            r#"archimedes_sse_validation_failures_total{operation="streamFeed",event="post"} 1"#
+        ));
+    }
+
+    #[test]
+    fn test_keep_alive_comments_are_not_validated() {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+
+        with_recorder(&recorder, async {
+            let config = SseConfig {
+                default_retry: None,
+                keep_alive_interval: Some(Duration::from_secs(1)),
+                ..SseConfig::default()
+            };
+            let (sender, mut stream) = SseStream::with_config(config);
+            let sender = ValidatingSseSender::new(sender, sentinel(), "streamFeed")
+                .with_mode(SseValidationMode::Reject);
+
+            sender.send_comment(r#"{"id":"one"}"#).await.unwrap();
+            let comment = stream.next().await.unwrap().unwrap();
+            assert_eq!(comment, r#": {"id":"one"}"#.to_string() + "\n");
+
+            // The first keep-alive is due once the stream has been idle
+            // for the interval
+            let keep_alive = stream.next().await.unwrap().unwrap();
+            assert_eq!(keep_alive, ": keepalive\n\n");
+        });
+
+        assert!(!handle.render().contains(VALIDATION_FAILURES));
+    }
+}
@@ -17,6 +17,10 @@ pub enum TaskError {
     #[error("task timed out after {0:?}")]
     Timeout(std::time::Duration),
 
+    /// Task ran past its `kill_after` threshold and was cancelled.
+    #[error("task killed after running for {0:?}")]
+    TimeoutEscalation(std::time::Duration),
+
     /// Task panicked during execution.
     #[error("task panicked: {0}")]
     Panicked(String),
@@ -45,6 +49,10 @@ pub enum TaskError {
     #[error("scheduler not running")]
     SchedulerNotRunning,
 
+    /// The event bus has been shut down.
+    #[error("event bus is shut down")]
+    EventBusClosed,
+
     /// Internal error.
     #[error("internal error: {0}")]
     Internal(String),
@@ -61,6 +69,11 @@ impl TaskError {
         Self::Timeout(duration)
     }
 
+    /// Create a timeout escalation error.
+    pub fn timeout_escalation(duration: std::time::Duration) -> Self {
+        Self::TimeoutEscalation(duration)
+    }
+
     /// Create a panicked error.
     pub fn panicked(reason: impl Into<String>) -> Self {
         Self::Panicked(reason.into())
@@ -119,6 +132,7 @@ mod tests {
     fn test_error_constructors() {
         let _ = TaskError::cancelled("user requested");
         let _ = TaskError::timeout(Duration::from_secs(30));
+        let _ = TaskError::timeout_escalation(Duration::from_secs(3600));
         let _ = TaskError::panicked("assertion failed");
         let _ = TaskError::spawn_failed("no capacity");
         let _ = TaskError::not_found("task-123");
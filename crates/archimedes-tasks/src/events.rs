@@ -0,0 +1,997 @@
+//! In-process publish/subscribe of typed domain events.
+//!
+//! An [`EventBus`] carries events such as `UserCreated` from the handler
+//! that publishes them to other parts of the same process, for example a
+//! cache invalidator, the WebSocket broadcaster or a [`WebhookDispatcher`].
+//! Each event type is its own topic, named by [`Event::TOPIC`].
+//!
+//! Events are consumed in two ways:
+//!
+//! - [`EventBus::subscribe`] returns a [`Subscription`], a stream of
+//!   [`Envelope`]s for a consumer that runs its own loop.
+//! - [`EventBus::on`] registers an async handler that the bus runs as
+//!   spawner tasks. A handler sees the events of its topic one at a time,
+//!   in publish order, and a failed or panicking call is retried with the
+//!   bus's [`RetryPolicy`], so each event is handled at least once unless
+//!   it is dropped by the lag policy or every attempt fails.
+//!
+//! Every subscription and handler has its own bounded buffer. When a slow
+//! consumer's buffer is full, its [`LagPolicy`] decides which event it
+//! loses; other consumers of the topic are not affected. Published,
+//! delivered, dropped and failed events are counted per topic, see
+//! [`EventBus::stats`].
+//!
+//! Events published with [`EventBus::publish_in`] carry the ID of the
+//! request they were published from. Handlers run inside an `event` span
+//! with `topic` and `request_id` fields, so their logs correlate with the
+//! request.
+//!
+//! [`EventBus::shutdown`] stops accepting events, waits for the handlers
+//! to work through their buffers, then ends the subscriptions once they
+//! have read what is buffered. Handler calls are spawner tasks, so they
+//! are also bounded by the spawner's task timeout.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use archimedes_core::di::Container;
+//! use archimedes_tasks::{Envelope, Event, EventBus, SharedSpawner};
+//! use futures_util::StreamExt;
+//! use std::sync::Arc;
+//!
+//! #[derive(Debug, Clone)]
+//! struct UserCreated {
+//!     id: u64,
+//! }
+//!
+//! impl Event for UserCreated {
+//!     const TOPIC: &'static str = "user.created";
+//! }
+//!
+//! # async fn example() -> archimedes_tasks::TaskResult<()> {
+//! let bus = EventBus::new(SharedSpawner::new());
+//!
+//! bus.on(|envelope: Envelope<UserCreated>| async move {
+//!     println!("invalidating cache for user {}", envelope.event.id);
+//!     Ok::<_, std::convert::Infallible>(())
+//! });
+//!
+//! let mut created = bus.subscribe::<UserCreated>();
+//! tokio::spawn(async move {
+//!     while let Some(envelope) = created.next().await {
+//!         println!("broadcasting user {}", envelope.event.id);
+//!     }
+//! });
+//!
+//! // Handlers get the bus injected
+//! let mut container = Container::new();
+//! container.register(Arc::new(bus.clone()));
+//!
+//! bus.publish(UserCreated { id: 1 })?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`WebhookDispatcher`]: crate::WebhookDispatcher
+
+use std::any::{Any, TypeId};
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+use archimedes_core::{RequestContext, RequestId};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use futures_util::future::BoxFuture;
+use futures_util::{FutureExt, Stream};
+use parking_lot::{Mutex, RwLock};
+use tracing::{debug, warn, Instrument};
+
+use crate::error::{TaskError, TaskResult};
+use crate::spawner::SharedSpawner;
+use crate::webhook::RetryPolicy;
+
+/// Default number of events buffered per subscription or handler.
+pub const DEFAULT_BUFFER_SIZE: usize = 1024;
+
+/// A domain event that can be published on an [`EventBus`].
+pub trait Event: Clone + Send + Sync + 'static {
+    /// Topic name, such as `"user.created"`, used in logs, task names and
+    /// statistics.
+    const TOPIC: &'static str;
+}
+
+/// A published event with its metadata.
+#[derive(Debug, Clone)]
+pub struct Envelope<E> {
+    /// The event.
+    pub event: E,
+    /// ID of the request the event was published from, if any.
+    pub request_id: Option<RequestId>,
+    /// When the event was published.
+    pub published_at: DateTime<Utc>,
+    seq: u64,
+}
+
+/// What a full buffer does with one more event.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LagPolicy {
+    /// Discard the oldest buffered event to make room, so a consumer that
+    /// catches up sees the most recent events.
+    #[default]
+    DropOldest,
+    /// Discard the event being published, so a consumer that catches up
+    /// sees the events it fell behind on.
+    DropNewest,
+}
+
+/// Configuration for an [`EventBus`].
+#[derive(Debug, Clone)]
+pub struct EventBusConfig {
+    /// Number of events buffered per subscription or handler.
+    pub buffer_size: usize,
+    /// What a full buffer does with one more event.
+    pub lag_policy: LagPolicy,
+    /// When and how often failed handler calls are retried.
+    pub retry: RetryPolicy,
+}
+
+impl Default for EventBusConfig {
+    fn default() -> Self {
+        Self {
+            buffer_size: DEFAULT_BUFFER_SIZE,
+            lag_policy: LagPolicy::default(),
+            retry: RetryPolicy::default(),
+        }
+    }
+}
+
+impl EventBusConfig {
+    /// Create a configuration with defaults.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the number of events buffered per subscription or handler.
+    ///
+    /// Values below 1 are treated as 1.
+    #[must_use]
+    pub fn with_buffer_size(mut self, size: usize) -> Self {
+        self.buffer_size = size.max(1);
+        self
+    }
+
+    /// Set what a full buffer does with one more event.
+    #[must_use]
+    pub fn with_lag_policy(mut self, policy: LagPolicy) -> Self {
+        self.lag_policy = policy;
+        self
+    }
+
+    /// Set the retry policy of handler calls.
+    #[must_use]
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+}
+
+/// Event counters of a topic.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TopicStats {
+    /// Number of events published.
+    pub published: u64,
+    /// Number of events read by a subscription or handled by a handler,
+    /// counted once per consumer.
+    pub delivered: u64,
+    /// Number of events a consumer lost to its lag policy.
+    pub dropped: u64,
+    /// Number of events a handler failed on every attempt.
+    pub failed: u64,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    published: AtomicU64,
+    delivered: AtomicU64,
+    dropped: AtomicU64,
+    failed: AtomicU64,
+}
+
+impl Counters {
+    fn add(counter: &AtomicU64) {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> TopicStats {
+        TopicStats {
+            published: self.published.load(Ordering::Relaxed),
+            delivered: self.delivered.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+            failed: self.failed.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Result of buffering an event for one consumer.
+enum Pushed {
+    /// Buffered, possibly at the cost of the oldest buffered event.
+    Queued { evicted: bool },
+    /// Discarded by [`LagPolicy::DropNewest`].
+    Rejected,
+    /// The consumer is gone.
+    Closed,
+}
+
+struct QueueState<E> {
+    buf: VecDeque<Envelope<E>>,
+    waker: Option<Waker>,
+    closed: bool,
+    draining: bool,
+}
+
+/// Bounded buffer of one consumer.
+struct Queue<E> {
+    state: Mutex<QueueState<E>>,
+    capacity: usize,
+    lag_policy: LagPolicy,
+}
+
+impl<E: Clone> Queue<E> {
+    fn new(config: &EventBusConfig) -> Self {
+        Self {
+            state: Mutex::new(QueueState {
+                buf: VecDeque::new(),
+                waker: None,
+                closed: false,
+                draining: false,
+            }),
+            capacity: config.buffer_size.max(1),
+            lag_policy: config.lag_policy,
+        }
+    }
+
+    fn push(&self, envelope: Envelope<E>) -> Pushed {
+        let mut state = self.state.lock();
+        if state.closed {
+            return Pushed::Closed;
+        }
+
+        let mut evicted = false;
+        if state.buf.len() >= self.capacity {
+            match self.lag_policy {
+                LagPolicy::DropOldest => {
+                    state.buf.pop_front();
+                    evicted = true;
+                }
+                LagPolicy::DropNewest => return Pushed::Rejected,
+            }
+        }
+        state.buf.push_back(envelope);
+
+        let waker = state.waker.take();
+        drop(state);
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+        Pushed::Queued { evicted }
+    }
+
+    fn close(&self) {
+        let waker = {
+            let mut state = self.state.lock();
+            state.closed = true;
+            state.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.state.lock().closed
+    }
+
+    /// Mark the queue as being drained, unless it already is or is empty.
+    fn claim(&self) -> bool {
+        let mut state = self.state.lock();
+        if state.draining || state.buf.is_empty() {
+            return false;
+        }
+        state.draining = true;
+        true
+    }
+
+    fn release(&self) {
+        self.state.lock().draining = false;
+    }
+
+    /// The oldest buffered event, left in the buffer until it is handled;
+    /// releases the claim when there is none.
+    fn head_or_release(&self) -> Option<Envelope<E>> {
+        let mut state = self.state.lock();
+        let head = state.buf.front().cloned();
+        if head.is_none() {
+            state.draining = false;
+        }
+        head
+    }
+
+    /// Remove the event numbered `seq` if it is still the oldest, that is,
+    /// if the lag policy did not evict it while it was being handled.
+    fn pop_handled(&self, seq: u64) {
+        let mut state = self.state.lock();
+        if state.buf.front().is_some_and(|head| head.seq == seq) {
+            state.buf.pop_front();
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        let state = self.state.lock();
+        state.buf.is_empty() && !state.draining
+    }
+}
+
+type HandlerFn<E> =
+    Arc<dyn Fn(Envelope<E>) -> BoxFuture<'static, Result<(), String>> + Send + Sync>;
+
+struct Handler<E> {
+    queue: Queue<E>,
+    call: HandlerFn<E>,
+}
+
+struct Topic<E> {
+    subscribers: RwLock<Vec<Arc<Queue<E>>>>,
+    handlers: RwLock<Vec<Arc<Handler<E>>>>,
+    counters: Arc<Counters>,
+}
+
+/// Type-erased view of a [`Topic`], for statistics and shutdown.
+trait AnyTopic: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn counters(&self) -> &Counters;
+    fn handlers_idle(&self) -> bool;
+    fn close_subscribers(&self);
+}
+
+impl<E: Event> AnyTopic for Topic<E> {
+    fn name(&self) -> &'static str {
+        E::TOPIC
+    }
+
+    fn counters(&self) -> &Counters {
+        &self.counters
+    }
+
+    fn handlers_idle(&self) -> bool {
+        self.handlers
+            .read()
+            .iter()
+            .all(|handler| handler.queue.is_idle())
+    }
+
+    fn close_subscribers(&self) {
+        for queue in self.subscribers.read().iter() {
+            queue.close();
+        }
+    }
+}
+
+/// Releases a handler's claim if its drain task ends early, for example
+/// because the spawner timed it out, so the next publish starts a new one.
+struct DrainGuard<E: Clone> {
+    handler: Arc<Handler<E>>,
+    armed: bool,
+}
+
+impl<E: Clone> Drop for DrainGuard<E> {
+    fn drop(&mut self) {
+        if self.armed {
+            self.handler.queue.release();
+        }
+    }
+}
+
+/// A topic, both as its concrete type and type-erased.
+struct TopicEntry {
+    typed: Arc<dyn Any + Send + Sync>,
+    erased: Arc<dyn AnyTopic>,
+}
+
+struct Inner {
+    spawner: SharedSpawner,
+    config: EventBusConfig,
+    topics: DashMap<TypeId, TopicEntry>,
+    closed: AtomicBool,
+    seq: AtomicU64,
+}
+
+/// In-process publish/subscribe bus for typed events.
+///
+/// Cloning the bus shares its topics, consumers and statistics.
+#[derive(Clone)]
+pub struct EventBus {
+    inner: Arc<Inner>,
+}
+
+impl fmt::Debug for EventBus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventBus")
+            .field("topics", &self.inner.topics.len())
+            .field("config", &self.inner.config)
+            .field("closed", &self.inner.closed.load(Ordering::Relaxed))
+            .finish_non_exhaustive()
+    }
+}
+
+impl EventBus {
+    /// Create a bus running handlers on `spawner`.
+    #[must_use]
+    pub fn new(spawner: SharedSpawner) -> Self {
+        Self::with_config(spawner, EventBusConfig::default())
+    }
+
+    /// Create a bus with custom configuration.
+    #[must_use]
+    pub fn with_config(spawner: SharedSpawner, config: EventBusConfig) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                spawner,
+                config,
+                topics: DashMap::new(),
+                closed: AtomicBool::new(false),
+                seq: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Get the configuration.
+    #[must_use]
+    pub fn config(&self) -> &EventBusConfig {
+        &self.inner.config
+    }
+
+    /// Check if the bus has been shut down.
+    #[must_use]
+    pub fn is_closed(&self) -> bool {
+        self.inner.closed.load(Ordering::Acquire)
+    }
+
+    /// Subscribe to the events of type `E` published from now on.
+    ///
+    /// Dropping the subscription unsubscribes.
+    #[must_use]
+    pub fn subscribe<E: Event>(&self) -> Subscription<E> {
+        let topic = self.topic::<E>();
+        let queue = Arc::new(Queue::new(&self.inner.config));
+        if self.is_closed() {
+            queue.close();
+        }
+        topic.subscribers.write().push(Arc::clone(&queue));
+        Subscription {
+            queue,
+            counters: Arc::clone(&topic.counters),
+        }
+    }
+
+    /// Run `handler` for every event of type `E` published from now on.
+    ///
+    /// Calls run as spawner tasks, one event at a time per handler. A call
+    /// that fails or panics is retried according to the bus's
+    /// [`RetryPolicy`]; once every attempt has failed, the event is logged
+    /// and counted as failed.
+    pub fn on<E, F, Fut, Err>(&self, handler: F)
+    where
+        E: Event,
+        F: Fn(Envelope<E>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), Err>> + Send + 'static,
+        Err: fmt::Display + 'static,
+    {
+        let call: HandlerFn<E> = Arc::new(move |envelope| {
+            let call = handler(envelope);
+            Box::pin(async move { call.await.map_err(|e| e.to_string()) })
+        });
+        self.topic::<E>().handlers.write().push(Arc::new(Handler {
+            queue: Queue::new(&self.inner.config),
+            call,
+        }));
+    }
+
+    /// Publish `event` to the subscriptions and handlers of its topic.
+    ///
+    /// Returns the number of consumers it was buffered for.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bus has been shut down.
+    pub fn publish<E: Event>(&self, event: E) -> TaskResult<usize> {
+        self.publish_envelope(event, None)
+    }
+
+    /// Publish `event`, tagged with the ID of the request in `ctx` for log
+    /// correlation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bus has been shut down.
+    pub fn publish_in<E: Event>(&self, ctx: &RequestContext, event: E) -> TaskResult<usize> {
+        self.publish_envelope(event, Some(ctx.request_id()))
+    }
+
+    /// Get the counters of a topic, by [`Event::TOPIC`].
+    ///
+    /// Returns `None` until the topic is first published or subscribed to.
+    #[must_use]
+    pub fn stats(&self, topic: &str) -> Option<TopicStats> {
+        self.inner
+            .topics
+            .iter()
+            .find(|entry| entry.erased.name() == topic)
+            .map(|entry| entry.erased.counters().snapshot())
+    }
+
+    /// Stop accepting events and drain pending deliveries.
+    ///
+    /// Waits up to `timeout` for handlers to work through their buffers,
+    /// then ends every subscription once it has read the events it has
+    /// buffered.
+    pub async fn shutdown(&self, timeout: Duration) {
+        self.inner.closed.store(true, Ordering::Release);
+
+        let deadline = tokio::time::Instant::now() + timeout;
+        while !self.handlers_idle() {
+            if tokio::time::Instant::now() >= deadline {
+                warn!("event bus shutdown timeout reached, deliveries still pending");
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        for entry in self.inner.topics.iter() {
+            entry.erased.close_subscribers();
+        }
+        debug!("event bus shutdown complete");
+    }
+
+    fn handlers_idle(&self) -> bool {
+        self.inner
+            .topics
+            .iter()
+            .all(|entry| entry.erased.handlers_idle())
+    }
+
+    fn topic<E: Event>(&self) -> Arc<Topic<E>> {
+        let typed = Arc::clone(
+            &self
+                .inner
+                .topics
+                .entry(TypeId::of::<E>())
+                .or_insert_with(|| {
+                    let topic = Arc::new(Topic::<E> {
+                        subscribers: RwLock::new(Vec::new()),
+                        handlers: RwLock::new(Vec::new()),
+                        counters: Arc::new(Counters::default()),
+                    });
+                    TopicEntry {
+                        typed: Arc::clone(&topic) as Arc<dyn Any + Send + Sync>,
+                        erased: topic,
+                    }
+                })
+                .typed,
+        );
+        typed
+            .downcast::<Topic<E>>()
+            .unwrap_or_else(|_| unreachable!("topics are keyed by event type"))
+    }
+
+    fn publish_envelope<E: Event>(
+        &self,
+        event: E,
+        request_id: Option<RequestId>,
+    ) -> TaskResult<usize> {
+        if self.is_closed() {
+            return Err(TaskError::EventBusClosed);
+        }
+
+        let topic = self.topic::<E>();
+        let counters = &topic.counters;
+        Counters::add(&counters.published);
+        let envelope = Envelope {
+            event,
+            request_id,
+            published_at: Utc::now(),
+            seq: self.inner.seq.fetch_add(1, Ordering::Relaxed),
+        };
+
+        let mut buffered = 0;
+        let mut record = |pushed: Pushed| match pushed {
+            Pushed::Queued { evicted } => {
+                buffered += 1;
+                if evicted {
+                    Counters::add(&counters.dropped);
+                }
+                true
+            }
+            Pushed::Rejected => {
+                Counters::add(&counters.dropped);
+                true
+            }
+            Pushed::Closed => false,
+        };
+
+        let mut unsubscribed = false;
+        for queue in topic.subscribers.read().iter() {
+            unsubscribed |= !record(queue.push(envelope.clone()));
+        }
+        if unsubscribed {
+            topic.subscribers.write().retain(|queue| !queue.is_closed());
+        }
+
+        for handler in topic.handlers.read().iter() {
+            record(handler.queue.push(envelope.clone()));
+            self.drain(handler, &topic.counters);
+        }
+
+        Ok(buffered)
+    }
+
+    /// Start a task working through `handler`'s buffer, unless one is
+    /// already running.
+    fn drain<E: Event>(&self, handler: &Arc<Handler<E>>, counters: &Arc<Counters>) {
+        if !handler.queue.claim() {
+            return;
+        }
+
+        let task = drain_handler(
+            Arc::clone(handler),
+            Arc::clone(counters),
+            self.inner.config.retry,
+        );
+        let name = format!("event:{}", E::TOPIC);
+        if let Err(error) = self.inner.spawner.spawn_detached(name, task) {
+            // The events stay buffered for the next publish to retry
+            handler.queue.release();
+            warn!(topic = E::TOPIC, %error, "failed to spawn event handler");
+        }
+    }
+}
+
+async fn drain_handler<E: Event>(
+    handler: Arc<Handler<E>>,
+    counters: Arc<Counters>,
+    retry: RetryPolicy,
+) {
+    let mut guard = DrainGuard {
+        handler: Arc::clone(&handler),
+        armed: true,
+    };
+
+    while let Some(envelope) = handler.queue.head_or_release() {
+        let seq = envelope.seq;
+        let request_id = envelope.request_id.map(|id| id.to_string());
+        let span = tracing::info_span!(
+            "event",
+            topic = E::TOPIC,
+            request_id = request_id.as_deref()
+        );
+
+        if handle(&handler, envelope, retry).instrument(span).await {
+            Counters::add(&counters.delivered);
+        } else {
+            Counters::add(&counters.failed);
+        }
+        handler.queue.pop_handled(seq);
+    }
+    guard.armed = false;
+}
+
+/// Call `handler` until it succeeds or runs out of attempts.
+async fn handle<E: Event>(handler: &Handler<E>, envelope: Envelope<E>, retry: RetryPolicy) -> bool {
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let result = AssertUnwindSafe((handler.call)(envelope.clone()))
+            .catch_unwind()
+            .await;
+        let error = match result {
+            Ok(Ok(())) => return true,
+            Ok(Err(error)) => error,
+            Err(_) => "handler panicked".to_string(),
+        };
+
+        if attempt >= retry.max_attempts() {
+            warn!(topic = E::TOPIC, attempts = attempt, %error, "event handler failed");
+            return false;
+        }
+
+        let backoff = retry.backoff(attempt);
+        debug!(topic = E::TOPIC, attempt, %error, ?backoff, "event handler failed, retrying");
+        tokio::time::sleep(backoff).await;
+    }
+}
+
+/// A stream of the events of one topic.
+///
+/// Created by [`EventBus::subscribe`]. The stream ends once the bus is
+/// shut down and the buffered events have been read.
+pub struct Subscription<E> {
+    queue: Arc<Queue<E>>,
+    counters: Arc<Counters>,
+}
+
+impl<E> fmt::Debug for Subscription<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.queue.state.lock();
+        f.debug_struct("Subscription")
+            .field("buffered", &state.buf.len())
+            .field("closed", &state.closed)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<E: Event> Subscription<E> {
+    /// Receive the next event, or `None` once the stream has ended.
+    pub async fn recv(&mut self) -> Option<Envelope<E>> {
+        futures_util::StreamExt::next(self).await
+    }
+
+    /// Take the next event if one is buffered.
+    pub fn try_recv(&mut self) -> Option<Envelope<E>> {
+        let envelope = self.queue.state.lock().buf.pop_front();
+        if envelope.is_some() {
+            Counters::add(&self.counters.delivered);
+        }
+        envelope
+    }
+}
+
+impl<E: Event> Stream for Subscription<E> {
+    type Item = Envelope<E>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let mut state = this.queue.state.lock();
+        if let Some(envelope) = state.buf.pop_front() {
+            Counters::add(&this.counters.delivered);
+            return Poll::Ready(Some(envelope));
+        }
+        if state.closed {
+            return Poll::Ready(None);
+        }
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl<E> Drop for Subscription<E> {
+    fn drop(&mut self) {
+        self.queue.state.lock().closed = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+    use tokio::sync::Semaphore;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct UserCreated {
+        id: u64,
+    }
+
+    impl Event for UserCreated {
+        const TOPIC: &'static str = "user.created";
+    }
+
+    fn bus(config: EventBusConfig) -> EventBus {
+        EventBus::with_config(SharedSpawner::new(), config)
+    }
+
+    fn ids(subscription: &mut Subscription<UserCreated>) -> Vec<u64> {
+        std::iter::from_fn(|| subscription.try_recv())
+            .map(|envelope| envelope.event.id)
+            .collect()
+    }
+
+    async fn wait_until(condition: impl Fn() -> bool) {
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+        while !condition() {
+            assert!(tokio::time::Instant::now() < deadline, "timed out");
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_every_subscriber_receives_event() {
+        let bus = bus(EventBusConfig::new());
+        let mut first = bus.subscribe::<UserCreated>();
+        let mut second = bus.subscribe::<UserCreated>();
+        let handled = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&handled);
+        bus.on(move |envelope: Envelope<UserCreated>| {
+            let counter = Arc::clone(&counter);
+            async move {
+                assert_eq!(envelope.event.id, 7);
+                counter.fetch_add(1, Ordering::SeqCst);
+                Ok::<_, String>(())
+            }
+        });
+
+        assert_eq!(bus.publish(UserCreated { id: 7 }).unwrap(), 3);
+
+        assert_eq!(first.recv().await.unwrap().event, UserCreated { id: 7 });
+        assert_eq!(second.recv().await.unwrap().event, UserCreated { id: 7 });
+        wait_until(|| handled.load(Ordering::SeqCst) == 1).await;
+
+        let stats = bus.stats("user.created").unwrap();
+        assert_eq!(stats.published, 1);
+        assert_eq!(stats.delivered, 3);
+        assert_eq!(stats.dropped, 0);
+    }
+
+    #[tokio::test]
+    async fn test_slow_subscriber_hits_lag_policy_alone() {
+        let bus = bus(EventBusConfig::new().with_buffer_size(2));
+        let mut fast = bus.subscribe::<UserCreated>();
+        let mut slow = bus.subscribe::<UserCreated>();
+
+        // The handler is held up until every event is published
+        let gate = Arc::new(Semaphore::new(0));
+        let started = Arc::new(AtomicUsize::new(0));
+        let handled = Arc::new(Mutex::new(Vec::new()));
+        let (permits, calls, log) = (
+            Arc::clone(&gate),
+            Arc::clone(&started),
+            Arc::clone(&handled),
+        );
+        bus.on(move |envelope: Envelope<UserCreated>| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            let (permits, log) = (Arc::clone(&permits), Arc::clone(&log));
+            async move {
+                permits.acquire().await.unwrap().forget();
+                log.lock().push(envelope.event.id);
+                Ok::<_, String>(())
+            }
+        });
+
+        let mut fast_ids = Vec::new();
+        for id in 1..=5 {
+            bus.publish(UserCreated { id }).unwrap();
+            fast_ids.push(fast.recv().await.unwrap().event.id);
+            if id == 1 {
+                wait_until(|| started.load(Ordering::SeqCst) == 1).await;
+            }
+        }
+        gate.add_permits(5);
+
+        assert_eq!(fast_ids, [1, 2, 3, 4, 5]);
+        assert_eq!(ids(&mut slow), [4, 5]);
+        // The handler was holding event 1 when 3 pushed it out of the
+        // buffer; it finishes it and then takes the newest two
+        wait_until(|| handled.lock().len() == 3).await;
+        assert_eq!(*handled.lock(), [1, 4, 5]);
+
+        let stats = bus.stats("user.created").unwrap();
+        assert_eq!(stats.published, 5);
+        assert_eq!(stats.dropped, 6);
+    }
+
+    #[tokio::test]
+    async fn test_drop_newest_keeps_buffered_events() {
+        let bus = bus(EventBusConfig::new()
+            .with_buffer_size(2)
+            .with_lag_policy(LagPolicy::DropNewest));
+        let mut slow = bus.subscribe::<UserCreated>();
+
+        for id in 1..=4 {
+            bus.publish(UserCreated { id }).unwrap();
+        }
+
+        assert_eq!(ids(&mut slow), [1, 2]);
+        assert_eq!(bus.stats("user.created").unwrap().dropped, 2);
+    }
+
+    #[tokio::test]
+    async fn test_request_id_is_propagated() {
+        let bus = bus(EventBusConfig::new());
+        let mut subscription = bus.subscribe::<UserCreated>();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = Arc::clone(&seen);
+        bus.on(move |envelope: Envelope<UserCreated>| {
+            let log = Arc::clone(&log);
+            async move {
+                log.lock().push(envelope.request_id);
+                Ok::<_, String>(())
+            }
+        });
+
+        let ctx = RequestContext::new();
+        bus.publish_in(&ctx, UserCreated { id: 1 }).unwrap();
+        bus.publish(UserCreated { id: 2 }).unwrap();
+
+        let published_in = subscription.recv().await.unwrap();
+        assert_eq!(published_in.request_id, Some(ctx.request_id()));
+        assert_eq!(subscription.recv().await.unwrap().request_id, None);
+        wait_until(|| bus.stats("user.created").unwrap().delivered == 4).await;
+        assert_eq!(*seen.lock(), [Some(ctx.request_id()), None]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_handler_is_retried() {
+        let retry = RetryPolicy::new()
+            .with_max_attempts(3)
+            .with_initial_backoff(Duration::ZERO);
+        let bus = bus(EventBusConfig::new().with_retry_policy(retry));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        bus.on(move |envelope: Envelope<UserCreated>| {
+            let calls = counter.fetch_add(1, Ordering::SeqCst) + 1;
+            async move {
+                match envelope.event.id {
+                    1 if calls < 3 => Err("not yet"),
+                    2 => panic!("handler bug"),
+                    _ => Ok(()),
+                }
+            }
+        });
+
+        bus.publish(UserCreated { id: 1 }).unwrap();
+        bus.publish(UserCreated { id: 2 }).unwrap();
+        wait_until(|| calls.load(Ordering::SeqCst) == 6).await;
+        bus.shutdown(Duration::from_secs(1)).await;
+
+        let stats = bus.stats("user.created").unwrap();
+        assert_eq!(stats.delivered, 1);
+        assert_eq!(stats.failed, 1);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_drains_pending_deliveries() {
+        let bus = bus(EventBusConfig::new());
+        let mut subscription = bus.subscribe::<UserCreated>();
+        let handled = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&handled);
+        bus.on(move |_: Envelope<UserCreated>| {
+            let counter = Arc::clone(&counter);
+            async move {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                counter.fetch_add(1, Ordering::SeqCst);
+                Ok::<_, String>(())
+            }
+        });
+
+        for id in 1..=3 {
+            bus.publish(UserCreated { id }).unwrap();
+        }
+        bus.shutdown(Duration::from_secs(5)).await;
+
+        assert_eq!(handled.load(Ordering::SeqCst), 3);
+        assert!(matches!(
+            bus.publish(UserCreated { id: 4 }),
+            Err(TaskError::EventBusClosed)
+        ));
+
+        let mut received = Vec::new();
+        while let Some(envelope) = subscription.recv().await {
+            received.push(envelope.event.id);
+        }
+        assert_eq!(received, [1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_dropped_subscription_is_removed() {
+        let bus = bus(EventBusConfig::new());
+        drop(bus.subscribe::<UserCreated>());
+
+        assert_eq!(bus.publish(UserCreated { id: 1 }).unwrap(), 0);
+        assert!(bus.stats("user.updated").is_none());
+    }
+}
@@ -0,0 +1,795 @@
+//! Tracked asynchronous jobs (the "202 Accepted + poll" pattern).
+//!
+//! Long-running operations often respond with `202 Accepted` and a job ID,
+//! letting the client poll a status endpoint until the work finishes. The
+//! [`JobTracker`] wraps a [`SharedSpawner`] and records status, progress,
+//! errors and (size-capped) result payloads for each submitted job, retaining
+//! finished jobs for a configurable TTL.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use archimedes_tasks::{jobs_status_handler, JobTracker, JobTrackerConfig};
+//! use std::time::Duration;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let tracker =
+//!         JobTracker::new(JobTrackerConfig::new().with_retention(Duration::from_secs(600)));
+//!
+//!     // In a handler: kick off work and return 202 with `Location: /jobs/{id}`
+//!     let accepted = tracker
+//!         .submit("resize-image", |ctx| async move {
+//!             ctx.progress(0.4, "resizing");
+//!             Ok::<_, String>(serde_json::json!({ "width": 640 }))
+//!         })
+//!         .unwrap();
+//!     let response = accepted.into_response();
+//!     assert_eq!(response.status(), http::StatusCode::ACCEPTED);
+//!
+//!     // In the `/jobs/{id}` handler:
+//!     let status = jobs_status_handler(&tracker, "some-job-id");
+//! }
+//! ```
+//!
+//! # Polling Semantics
+//!
+//! | Job state | `jobs_status_handler` | `jobs_result_handler` |
+//! |-----------|----------------------|----------------------|
+//! | Pending / Running | 200 with status body | 202 with `Location` of status |
+//! | Completed | 303 to the result URL | 200 with result payload |
+//! | Failed / Cancelled / Timed out | 200 with status body | 409 with error envelope |
+//! | Expired (past retention) | 410 Gone | 410 Gone |
+//! | Unknown | 404 Not Found | 404 Not Found |
+
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use archimedes_core::{RequestContext, UrlForError};
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use http::{header, Response, StatusCode};
+use serde::Serialize;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::error::TaskResult;
+use crate::spawner::SharedSpawner;
+use crate::task::{TaskId, TaskStatus};
+
+/// Public identifier for a tracked job.
+///
+/// Unlike [`TaskId`], this identifier is meant to be handed out to clients
+/// in `Location` headers and status URLs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TrackedJobId(String);
+
+impl TrackedJobId {
+    /// Generate a new unique job ID.
+    pub fn new() -> Self {
+        Self(Uuid::now_v7().simple().to_string())
+    }
+
+    /// Get the ID as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for TrackedJobId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for TrackedJobId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<&str> for TrackedJobId {
+    fn from(id: &str) -> Self {
+        Self(id.to_string())
+    }
+}
+
+/// Configuration for the job tracker.
+#[derive(Debug, Clone)]
+pub struct JobTrackerConfig {
+    /// How long finished jobs are retained before they expire.
+    pub retention: Duration,
+    /// Maximum serialized size of a job result payload, in bytes.
+    pub max_result_bytes: usize,
+    /// Base path of the polling endpoints (e.g. `/jobs`).
+    pub base_path: String,
+}
+
+impl Default for JobTrackerConfig {
+    fn default() -> Self {
+        Self {
+            retention: Duration::from_secs(3600), // 1 hour
+            max_result_bytes: 1024 * 1024,        // 1 MiB
+            base_path: "/jobs".to_string(),
+        }
+    }
+}
+
+impl JobTrackerConfig {
+    /// Create a new configuration with defaults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set how long finished jobs are retained.
+    pub fn with_retention(mut self, retention: Duration) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// Set the maximum result payload size.
+    pub fn with_max_result_bytes(mut self, max: usize) -> Self {
+        self.max_result_bytes = max;
+        self
+    }
+
+    /// Set the base path of the polling endpoints.
+    pub fn with_base_path(mut self, base_path: impl Into<String>) -> Self {
+        self.base_path = base_path.into().trim_end_matches('/').to_string();
+        self
+    }
+}
+
+/// Snapshot of a tracked job.
+#[derive(Debug, Clone)]
+pub struct JobRecord {
+    /// Public job ID.
+    pub id: TrackedJobId,
+    /// Human-readable job name.
+    pub name: String,
+    /// Underlying spawner task ID (once spawned).
+    pub task_id: Option<TaskId>,
+    /// Current status.
+    pub status: TaskStatus,
+    /// Progress fraction (0.0 to 1.0).
+    pub progress: f32,
+    /// Latest progress message.
+    pub message: Option<String>,
+    /// Error message if the job failed.
+    pub error: Option<String>,
+    /// Result payload if the job completed.
+    pub result: Option<serde_json::Value>,
+    /// When the job was submitted.
+    pub created_at: DateTime<Utc>,
+    /// When the job record was last updated.
+    pub updated_at: DateTime<Utc>,
+    /// When the job finished.
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl JobRecord {
+    fn new(id: TrackedJobId, name: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id,
+            name,
+            task_id: None,
+            status: TaskStatus::Pending,
+            progress: 0.0,
+            message: None,
+            error: None,
+            result: None,
+            created_at: now,
+            updated_at: now,
+            completed_at: None,
+        }
+    }
+
+    fn finish(&mut self, status: TaskStatus) {
+        let now = Utc::now();
+        self.status = status;
+        self.updated_at = now;
+        self.completed_at = Some(now);
+        if status.is_success() {
+            self.progress = 1.0;
+        }
+    }
+
+    /// Render the status document returned by the polling endpoint.
+    pub fn status_json(&self, base_path: &str) -> serde_json::Value {
+        let mut body = serde_json::json!({
+            "job_id": self.id.as_str(),
+            "name": self.name,
+            "status": self.status.to_string(),
+            "progress": self.progress,
+            "created_at": self.created_at.to_rfc3339(),
+            "updated_at": self.updated_at.to_rfc3339(),
+        });
+        if let Some(message) = &self.message {
+            body["message"] = serde_json::json!(message);
+        }
+        if let Some(error) = &self.error {
+            body["error"] = serde_json::json!(error);
+        }
+        if let Some(completed_at) = self.completed_at {
+            body["completed_at"] = serde_json::json!(completed_at.to_rfc3339());
+        }
+        if self.status.is_success() {
+            body["result_url"] = serde_json::json!(result_url(base_path, &self.id));
+        }
+        body
+    }
+}
+
+/// Outcome of looking up a job.
+#[derive(Debug, Clone)]
+pub enum JobLookup {
+    /// The job is known.
+    Found(JobRecord),
+    /// The job existed but its retention period elapsed.
+    Expired,
+    /// The job is unknown.
+    NotFound,
+}
+
+/// Handle passed to a running job for reporting progress.
+#[derive(Debug, Clone)]
+pub struct JobContext {
+    id: TrackedJobId,
+    records: Arc<DashMap<TrackedJobId, JobRecord>>,
+}
+
+impl JobContext {
+    /// Get the public job ID.
+    pub fn id(&self) -> &TrackedJobId {
+        &self.id
+    }
+
+    /// Report progress (clamped to `0.0..=1.0`) with a short message.
+    pub fn progress(&self, fraction: f32, message: impl Into<String>) {
+        if let Some(mut record) = self.records.get_mut(&self.id) {
+            record.progress = fraction.clamp(0.0, 1.0);
+            record.message = Some(message.into());
+            record.updated_at = Utc::now();
+        }
+    }
+}
+
+/// Tracks asynchronous jobs submitted through a [`SharedSpawner`].
+///
+/// Register the tracker in the DI container (`container.register(Arc::new(tracker))`)
+/// so handlers can submit jobs and mount the polling endpoints.
+#[derive(Debug, Clone)]
+pub struct JobTracker {
+    /// Configuration.
+    config: JobTrackerConfig,
+    /// Underlying spawner.
+    spawner: SharedSpawner,
+    /// Live job records.
+    records: Arc<DashMap<TrackedJobId, JobRecord>>,
+    /// IDs of jobs whose retention elapsed, with the time they expired.
+    expired: Arc<DashMap<TrackedJobId, DateTime<Utc>>>,
+}
+
+impl JobTracker {
+    /// Create a job tracker with its own spawner.
+    pub fn new(config: JobTrackerConfig) -> Self {
+        Self::with_spawner(config, SharedSpawner::new())
+    }
+
+    /// Create a job tracker on top of an existing spawner.
+    pub fn with_spawner(config: JobTrackerConfig, spawner: SharedSpawner) -> Self {
+        Self {
+            config,
+            spawner,
+            records: Arc::new(DashMap::new()),
+            expired: Arc::new(DashMap::new()),
+        }
+    }
+
+    /// Get the tracker configuration.
+    pub fn config(&self) -> &JobTrackerConfig {
+        &self.config
+    }
+
+    /// Get the underlying spawner.
+    pub fn spawner(&self) -> &SharedSpawner {
+        &self.spawner
+    }
+
+    /// Submit a job.
+    ///
+    /// The job receives a [`JobContext`] for progress reporting. Its `Ok`
+    /// value is serialized as the result payload (subject to
+    /// [`JobTrackerConfig::max_result_bytes`]); its `Err` value is recorded
+    /// as the failure message.
+    pub fn submit<F, Fut, T, E>(&self, name: impl Into<String>, job: F) -> TaskResult<AcceptedJob>
+    where
+        F: FnOnce(JobContext) -> Fut,
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+        T: Serialize + Send + 'static,
+        E: fmt::Display + Send + 'static,
+    {
+        self.purge_expired();
+
+        let name = name.into();
+        let id = TrackedJobId::new();
+        self.records
+            .insert(id.clone(), JobRecord::new(id.clone(), name.clone()));
+
+        let ctx = JobContext {
+            id: id.clone(),
+            records: self.records.clone(),
+        };
+        let fut = job(ctx);
+        let records = self.records.clone();
+        let job_id = id.clone();
+        let max_result_bytes = self.config.max_result_bytes;
+
+        let spawned = self.spawner.spawn_detached(name, async move {
+            if let Some(mut record) = records.get_mut(&job_id) {
+                record.status = TaskStatus::Running;
+                record.updated_at = Utc::now();
+            }
+
+            let outcome = fut.await;
+
+            let Some(mut record) = records.get_mut(&job_id) else {
+                return;
+            };
+            match outcome {
+                Ok(value) => match serde_json::to_vec(&value) {
+                    Ok(bytes) if bytes.len() > max_result_bytes => {
+                        warn!(job_id = %job_id, size = bytes.len(), "job result exceeds size cap");
+                        record.error = Some(format!(
+                            "result payload of {} bytes exceeds limit of {max_result_bytes} bytes",
+                            bytes.len()
+                        ));
+                        record.finish(TaskStatus::Failed);
+                    }
+                    Ok(bytes) => {
+                        record.result = serde_json::from_slice(&bytes).ok();
+                        record.finish(TaskStatus::Completed);
+                    }
+                    Err(e) => {
+                        record.error = Some(format!("failed to serialize result: {e}"));
+                        record.finish(TaskStatus::Failed);
+                    }
+                },
+                Err(e) => {
+                    record.error = Some(e.to_string());
+                    record.finish(TaskStatus::Failed);
+                }
+            }
+            debug!(job_id = %job_id, status = %record.status, "tracked job finished");
+        });
+
+        match spawned {
+            Ok(task_id) => {
+                if let Some(mut record) = self.records.get_mut(&id) {
+                    record.task_id = Some(task_id);
+                }
+                Ok(AcceptedJob {
+                    location: status_url(&self.config.base_path, &id),
+                    id,
+                })
+            }
+            Err(e) => {
+                self.records.remove(&id);
+                Err(e)
+            }
+        }
+    }
+
+    /// Look up a job, applying retention and spawner-level terminal states.
+    pub fn lookup(&self, id: &str) -> JobLookup {
+        let id = TrackedJobId::from(id);
+
+        if self.expired.contains_key(&id) {
+            return JobLookup::Expired;
+        }
+
+        let Some(mut record) = self.records.get(&id).map(|r| r.clone()) else {
+            return JobLookup::NotFound;
+        };
+
+        // The spawner may have timed out or cancelled the job; reflect that.
+        if !record.status.is_terminal() {
+            if let Some(info) = record
+                .task_id
+                .and_then(|task_id| self.spawner.inner().get_task(task_id))
+            {
+                if matches!(info.status, TaskStatus::TimedOut | TaskStatus::Cancelled) {
+                    record.finish(info.status);
+                    record.error = Some(format!("job {}", info.status));
+                    self.records.insert(id.clone(), record.clone());
+                }
+            }
+        }
+
+        if self.is_past_retention(&record) {
+            self.records.remove(&id);
+            self.expired.insert(id, Utc::now());
+            return JobLookup::Expired;
+        }
+
+        JobLookup::Found(record)
+    }
+
+    /// Get a job record if it is known and not expired.
+    pub fn get(&self, id: &str) -> Option<JobRecord> {
+        match self.lookup(id) {
+            JobLookup::Found(record) => Some(record),
+            _ => None,
+        }
+    }
+
+    /// Number of live (non-expired) job records.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Check if no jobs are tracked.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Expire finished jobs past their retention period.
+    ///
+    /// Expired IDs are remembered (so polling returns 410) for one further
+    /// retention period, after which they are forgotten entirely.
+    pub fn purge_expired(&self) {
+        let now = Utc::now();
+        let retention = self.retention();
+
+        let expired: Vec<TrackedJobId> = self
+            .records
+            .iter()
+            .filter(|entry| self.is_past_retention(entry.value()))
+            .map(|entry| entry.key().clone())
+            .collect();
+        for id in expired {
+            self.records.remove(&id);
+            self.expired.insert(id, now);
+        }
+
+        self.expired
+            .retain(|_, expired_at| now - *expired_at < retention);
+    }
+
+    fn retention(&self) -> chrono::Duration {
+        chrono::Duration::from_std(self.config.retention).unwrap_or(chrono::Duration::MAX)
+    }
+
+    fn is_past_retention(&self, record: &JobRecord) -> bool {
+        record
+            .completed_at
+            .is_some_and(|completed_at| Utc::now() - completed_at >= self.retention())
+    }
+}
+
+impl Default for JobTracker {
+    fn default() -> Self {
+        Self::new(JobTrackerConfig::default())
+    }
+}
+
+/// A `202 Accepted` response for a newly submitted job.
+///
+/// The response carries `Location: {base_path}/{id}` and a JSON body with
+/// the job ID and status URL.
+#[derive(Debug, Clone)]
+pub struct AcceptedJob {
+    id: TrackedJobId,
+    location: String,
+}
+
+impl AcceptedJob {
+    /// Get the job ID.
+    pub fn id(&self) -> &TrackedJobId {
+        &self.id
+    }
+
+    /// Get the status URL (`Location` header value).
+    pub fn location(&self) -> &str {
+        &self.location
+    }
+
+    /// Points the status URL at a contract operation instead of
+    /// `{base_path}/{id}`.
+    ///
+    /// Use this when job status is served by an operation in the contract;
+    /// the URL is built with [`RequestContext::url_for`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UrlForError`] if the URL cannot be generated.
+    pub fn with_location_for(
+        mut self,
+        ctx: &RequestContext,
+        operation_id: &str,
+        params: &[(&str, &str)],
+    ) -> Result<Self, UrlForError> {
+        self.location = ctx.url_for(operation_id, params)?;
+        Ok(self)
+    }
+
+    /// Returns the status code (always 202).
+    pub fn status(&self) -> StatusCode {
+        StatusCode::ACCEPTED
+    }
+
+    /// Builds the HTTP response.
+    pub fn into_response(self) -> Response<Bytes> {
+        let body = serde_json::json!({
+            "job_id": self.id.as_str(),
+            "status": TaskStatus::Pending.to_string(),
+            "status_url": self.location,
+        });
+
+        Response::builder()
+            .status(StatusCode::ACCEPTED)
+            .header(header::LOCATION, &self.location)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Bytes::from(body.to_string()))
+            .expect("Failed to build response")
+    }
+}
+
+/// Handler for `GET {base_path}/{id}`: reports job status.
+///
+/// Returns 200 with the status document while the job is in flight or has
+/// failed, 303 to the result URL once it completed, 410 if it expired and
+/// 404 if it is unknown.
+pub fn jobs_status_handler(tracker: &JobTracker, id: &str) -> Response<Bytes> {
+    let base_path = &tracker.config.base_path;
+    match tracker.lookup(id) {
+        JobLookup::Found(record) if record.status.is_success() => Response::builder()
+            .status(StatusCode::SEE_OTHER)
+            .header(header::LOCATION, result_url(base_path, &record.id))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Bytes::from(record.status_json(base_path).to_string()))
+            .expect("Failed to build response"),
+        JobLookup::Found(record) => json_response(StatusCode::OK, &record.status_json(base_path)),
+        JobLookup::Expired => job_error(StatusCode::GONE, "JOB_EXPIRED", id),
+        JobLookup::NotFound => job_error(StatusCode::NOT_FOUND, "JOB_NOT_FOUND", id),
+    }
+}
+
+/// Handler for `GET {base_path}/{id}/result`: returns the job result.
+///
+/// Returns 200 with the result payload once completed, 202 (pointing back
+/// at the status URL) while in flight, 409 if the job did not succeed, 410
+/// if it expired and 404 if it is unknown.
+pub fn jobs_result_handler(tracker: &JobTracker, id: &str) -> Response<Bytes> {
+    let base_path = &tracker.config.base_path;
+    match tracker.lookup(id) {
+        JobLookup::Found(record) if record.status.is_success() => json_response(
+            StatusCode::OK,
+            record.result.as_ref().unwrap_or(&serde_json::Value::Null),
+        ),
+        JobLookup::Found(record) if !record.status.is_terminal() => Response::builder()
+            .status(StatusCode::ACCEPTED)
+            .header(header::LOCATION, status_url(base_path, &record.id))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Bytes::from(record.status_json(base_path).to_string()))
+            .expect("Failed to build response"),
+        JobLookup::Found(record) => {
+            let body = serde_json::json!({
+                "error": {
+                    "code": "JOB_NOT_COMPLETED",
+                    "message": record.error.as_deref().unwrap_or("job did not complete"),
+                    "status": record.status.to_string(),
+                }
+            });
+            json_response(StatusCode::CONFLICT, &body)
+        }
+        JobLookup::Expired => job_error(StatusCode::GONE, "JOB_EXPIRED", id),
+        JobLookup::NotFound => job_error(StatusCode::NOT_FOUND, "JOB_NOT_FOUND", id),
+    }
+}
+
+fn status_url(base_path: &str, id: &TrackedJobId) -> String {
+    format!("{base_path}/{id}")
+}
+
+fn result_url(base_path: &str, id: &TrackedJobId) -> String {
+    format!("{base_path}/{id}/result")
+}
+
+fn json_response(status: StatusCode, body: &serde_json::Value) -> Response<Bytes> {
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Bytes::from(body.to_string()))
+        .expect("Failed to build response")
+}
+
+fn job_error(status: StatusCode, code: &str, id: &str) -> Response<Bytes> {
+    let message = if status == StatusCode::GONE {
+        format!("job {id} has expired")
+    } else {
+        format!("job {id} not found")
+    };
+    json_response(
+        status,
+        &serde_json::json!({ "error": { "code": code, "message": message } }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use archimedes_core::di::Container;
+
+    fn body_json(response: &Response<Bytes>) -> serde_json::Value {
+        serde_json::from_slice(response.body()).unwrap()
+    }
+
+    /// A handler that resolves the tracker from DI and submits work.
+    fn submit_handler(container: &Container) -> Response<Bytes> {
+        let tracker = container.resolve::<JobTracker>().unwrap();
+        tracker
+            .submit("resize", |ctx| async move {
+                tokio::time::sleep(Duration::from_millis(30)).await;
+                ctx.progress(0.4, "resizing");
+                tokio::time::sleep(Duration::from_millis(30)).await;
+                Ok::<_, String>(serde_json::json!({ "width": 640 }))
+            })
+            .unwrap()
+            .into_response()
+    }
+
+    #[test]
+    fn test_config_builder() {
+        let config = JobTrackerConfig::new()
+            .with_retention(Duration::from_secs(60))
+            .with_max_result_bytes(512)
+            .with_base_path("/api/jobs/");
+
+        assert_eq!(config.retention, Duration::from_secs(60));
+        assert_eq!(config.max_result_bytes, 512);
+        assert_eq!(config.base_path, "/api/jobs");
+    }
+
+    #[tokio::test]
+    async fn test_submit_and_poll_to_completion() {
+        let mut container = Container::new();
+        container.register(Arc::new(JobTracker::default()));
+        let tracker = container.resolve::<JobTracker>().unwrap();
+
+        let response = submit_handler(&container);
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        let location = response.headers()[header::LOCATION].to_str().unwrap();
+        let id = location.strip_prefix("/jobs/").unwrap().to_string();
+        assert_eq!(body_json(&response)["job_id"], id.as_str());
+
+        // In flight
+        let status = jobs_status_handler(&tracker, &id);
+        assert_eq!(status.status(), StatusCode::OK);
+        let result = jobs_result_handler(&tracker, &id);
+        assert_eq!(result.status(), StatusCode::ACCEPTED);
+
+        // Progress reported from within the task
+        tokio::time::sleep(Duration::from_millis(45)).await;
+        let status = body_json(&jobs_status_handler(&tracker, &id));
+        assert_eq!(status["status"], "running");
+        assert_eq!(status["message"], "resizing");
+
+        // Completed: status redirects to the result
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        let status = jobs_status_handler(&tracker, &id);
+        assert_eq!(status.status(), StatusCode::SEE_OTHER);
+        assert_eq!(
+            status.headers()[header::LOCATION],
+            format!("/jobs/{id}/result").as_str()
+        );
+
+        let result = jobs_result_handler(&tracker, &id);
+        assert_eq!(result.status(), StatusCode::OK);
+        assert_eq!(body_json(&result)["width"], 640);
+    }
+
+    #[tokio::test]
+    async fn test_failed_job() {
+        let tracker = JobTracker::default();
+        let accepted = tracker
+            .submit("explode", |_ctx| async { Err::<(), _>("boom") })
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(30)).await;
+
+        let id = accepted.id().to_string();
+        let status = jobs_status_handler(&tracker, &id);
+        assert_eq!(status.status(), StatusCode::OK);
+        assert_eq!(body_json(&status)["status"], "failed");
+        assert_eq!(body_json(&status)["error"], "boom");
+
+        let result = jobs_result_handler(&tracker, &id);
+        assert_eq!(result.status(), StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn test_result_size_cap() {
+        let tracker = JobTracker::new(JobTrackerConfig::new().with_max_result_bytes(8));
+        let accepted = tracker
+            .submit("big", |_ctx| async { Ok::<_, String>("x".repeat(64)) })
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(30)).await;
+
+        let record = tracker.get(accepted.id().as_str()).unwrap();
+        assert_eq!(record.status, TaskStatus::Failed);
+        assert!(record.result.is_none());
+        assert!(record.error.unwrap().contains("exceeds limit"));
+    }
+
+    #[tokio::test]
+    async fn test_retention_expiry() {
+        let tracker =
+            JobTracker::new(JobTrackerConfig::new().with_retention(Duration::from_millis(50)));
+        let accepted = tracker
+            .submit("quick", |_ctx| async { Ok::<_, String>(1) })
+            .unwrap();
+        let id = accepted.id().to_string();
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(jobs_result_handler(&tracker, &id).status(), StatusCode::OK);
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert_eq!(
+            jobs_status_handler(&tracker, &id).status(),
+            StatusCode::GONE
+        );
+        assert_eq!(
+            jobs_result_handler(&tracker, &id).status(),
+            StatusCode::GONE
+        );
+        assert!(tracker.is_empty());
+    }
+
+    #[test]
+    fn test_unknown_job() {
+        let tracker = JobTracker::default();
+        assert_eq!(
+            jobs_status_handler(&tracker, "missing").status(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            jobs_result_handler(&tracker, "missing").status(),
+            StatusCode::NOT_FOUND
+        );
+    }
+
+    #[tokio::test]
+    async fn test_accepted_job_location_for_operation() {
+        use archimedes_core::UrlGenerator;
+        use archimedes_router::{MethodRouter, Router};
+
+        let mut router = Router::new();
+        router.insert("/exports/{exportId}", MethodRouter::new().get("getExport"));
+        let ctx =
+            RequestContext::new().with_url_generator(UrlGenerator::new(router).base_path("/api"));
+
+        let tracker = JobTracker::default();
+        let accepted = tracker
+            .submit("export", |_ctx| async { Ok::<_, String>(()) })
+            .unwrap();
+        let id = accepted.id().to_string();
+        let accepted = accepted
+            .with_location_for(&ctx, "getExport", &[("exportId", &id)])
+            .unwrap();
+        assert_eq!(accepted.location(), format!("/api/exports/{id}"));
+
+        let response = accepted.into_response();
+        assert_eq!(
+            response.headers()[header::LOCATION],
+            format!("/api/exports/{id}").as_str()
+        );
+        assert_eq!(
+            body_json(&response)["status_url"],
+            format!("/api/exports/{id}")
+        );
+    }
+}
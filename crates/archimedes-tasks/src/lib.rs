@@ -2,10 +2,14 @@
 //!
 //! Background task execution and scheduling for the Archimedes framework.
 //!
-//! This crate provides two main capabilities:
+//! This crate provides six main capabilities:
 //!
 //! 1. **Task Spawner**: Spawn background tasks with timeout, cancellation, and tracking
 //! 2. **Cron Scheduler**: Schedule recurring jobs using cron expressions
+//! 3. **Job Tracker**: Run long jobs behind a `202 Accepted` + status polling API
+//! 4. **Webhooks**: Deliver signed webhooks with retries and dead-lettering
+//! 5. **Event Bus**: Publish typed domain events to in-process subscribers
+//! 6. **Outbox**: Relay events committed with a write to the event bus and webhooks
 //!
 //! ## Task Spawner
 //!
@@ -40,6 +44,48 @@
 //! }
 //! ```
 //!
+//! By default a spawn fails once `max_concurrent` tasks are running. With
+//! [`SpawnerConfig::with_max_queued`], further tasks wait in a bounded queue
+//! instead and start in [`Priority`] order as running tasks finish:
+//!
+//! ```rust,no_run
+//! use archimedes_tasks::{Priority, Spawner, SpawnerConfig};
+//!
+//! # async fn example() {
+//! let spawner = Spawner::with_config(
+//!     SpawnerConfig::new().with_max_concurrent(10).with_max_queued(100),
+//! );
+//!
+//! spawner.spawn_detached_with_priority("rebuild-index", Priority::Low, async {
+//!     // Runs once nothing more urgent is waiting
+//! }).unwrap();
+//! # }
+//! ```
+//!
+//! Tasks that hang, for example on a dead connection, can be detected with
+//! a warn threshold and killed with a much longer kill threshold, set in
+//! the config or per task with [`TaskOptions`]. A task past its warn
+//! threshold is logged once and listed by [`Spawner::long_running_tasks`]
+//! but keeps running; a task past its kill threshold is cancelled and
+//! fails with [`TaskError::TimeoutEscalation`]:
+//!
+//! ```rust,no_run
+//! use archimedes_tasks::{SharedSpawner, SpawnerConfig};
+//! use std::time::Duration;
+//!
+//! # async fn example() {
+//! let spawner = SharedSpawner::with_config(
+//!     SpawnerConfig::new()
+//!         .without_timeout()
+//!         .with_warn_after(Duration::from_secs(300))
+//!         .with_kill_after(Duration::from_secs(24 * 3600)),
+//! );
+//!
+//! // Log tasks past their warn threshold every minute
+//! spawner.spawn_long_running_sweep(Duration::from_secs(60));
+//! # }
+//! ```
+//!
 //! ## Cron Scheduler
 //!
 //! Schedule recurring jobs using standard cron expressions:
@@ -72,6 +118,124 @@
 //! }
 //! ```
 //!
+//! Jobs needing a fixed interval, an overlap policy or failure tracking are
+//! registered from a [`JobSpec`]:
+//!
+//! ```rust,no_run
+//! use archimedes_tasks::{JobSpec, OverlapPolicy, Scheduler};
+//! use std::time::Duration;
+//!
+//! # fn example(scheduler: &Scheduler) -> archimedes_tasks::TaskResult<()> {
+//! let spec = JobSpec::interval("sync-inventory", Duration::from_secs(30))?
+//!     .with_overlap(OverlapPolicy::Skip);
+//!
+//! // Errors are recorded in the job's `fail_count` and `last_error`
+//! scheduler.register_job(spec, || async { Err::<(), _>("upstream unavailable") })?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ## Persistence
+//!
+//! Each job's last run, last success and failure streak can be persisted
+//! with a [`JobStore`], so a restarted process resumes schedules where they
+//! left off and, with [`SchedulerConfig::with_run_missed_on_startup`],
+//! catches up runs missed while it was down. Schedulers sharing a store
+//! take a lease on each job before running it, so replicas run each due
+//! job once between them:
+//!
+//! ```rust,no_run
+//! use archimedes_tasks::{FileStore, Scheduler, SchedulerConfig};
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! # fn example() -> archimedes_tasks::TaskResult<()> {
+//! let store = Arc::new(FileStore::open("/var/lib/myapp/scheduler.json")?);
+//! let scheduler = Scheduler::with_config(
+//!     SchedulerConfig::new()
+//!         .with_run_missed_on_startup()
+//!         .with_lease_ttl(Duration::from_secs(30))
+//!         .with_clock_skew_tolerance(Duration::from_secs(2)),
+//! )
+//! .with_store(store);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ## Webhooks
+//!
+//! A [`WebhookDispatcher`] delivers events to registered endpoints as
+//! spawner tasks, signing each request with HMAC-SHA256 and retrying 5xx
+//! responses and timeouts with exponential backoff. See [`webhook`] for the
+//! signature scheme.
+//!
+//! ```rust,no_run
+//! use archimedes_tasks::{SharedSpawner, WebhookDispatcher, WebhookEndpoint, WebhookEvent};
+//!
+//! # fn example() -> archimedes_tasks::TaskResult<()> {
+//! let dispatcher = WebhookDispatcher::new(SharedSpawner::new());
+//! dispatcher.register_endpoint("crm", WebhookEndpoint::new("https://crm.example.com/hooks", "secret"));
+//! dispatcher.dispatch(WebhookEvent::new("crm", "user.created", serde_json::json!({ "id": 1 })))?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ## Event Bus
+//!
+//! An [`EventBus`] delivers typed events published by handlers to
+//! subscriptions and to async handlers run on the spawner. Register it in
+//! the DI container so handlers can publish through `Inject<EventBus>`.
+//! See [`events`] for buffering, lag policies and delivery guarantees.
+//!
+//! ```rust,no_run
+//! use archimedes_tasks::{Envelope, Event, EventBus, SharedSpawner};
+//!
+//! #[derive(Debug, Clone)]
+//! struct UserCreated {
+//!     id: u64,
+//! }
+//!
+//! impl Event for UserCreated {
+//!     const TOPIC: &'static str = "user.created";
+//! }
+//!
+//! # fn example() -> archimedes_tasks::TaskResult<()> {
+//! let bus = EventBus::new(SharedSpawner::new());
+//! bus.on(|envelope: Envelope<UserCreated>| async move {
+//!     println!("user {} created", envelope.event.id);
+//!     Ok::<_, std::convert::Infallible>(())
+//! });
+//! bus.publish(UserCreated { id: 1 })?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ## Outbox
+//!
+//! Handlers append events to an [`Outbox`] in the transaction of their
+//! write, and an [`OutboxRelay`] scheduled on the [`Scheduler`] publishes
+//! the committed events to the event bus and webhook endpoints, in order
+//! per partition key. See [`outbox`] for delivery guarantees.
+//!
+//! ```rust,no_run
+//! use archimedes_tasks::{MemoryOutbox, Outbox, OutboxEvent, OutboxRelay, Scheduler};
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! # fn example(scheduler: &Scheduler) -> archimedes_tasks::TaskResult<()> {
+//! let outbox = MemoryOutbox::new();
+//! let mut tx = outbox.begin();
+//! outbox.append(
+//!     &mut tx,
+//!     OutboxEvent::new("user-1", "user.created", serde_json::json!({ "id": 1 })),
+//! )?;
+//! tx.commit();
+//!
+//! OutboxRelay::new(Arc::new(outbox)).schedule(scheduler, Duration::from_secs(1))?;
+//! # Ok(())
+//! # }
+//! ```
+//!
 //! ## Cron Expression Format
 //!
 //! The cron format follows standard 6-field syntax:
@@ -99,21 +263,47 @@
 #![allow(clippy::module_name_repetitions)]
 
 mod error;
+pub mod events;
+mod jobs;
+pub mod outbox;
 mod scheduler;
 mod spawner;
+pub mod store;
 mod task;
+pub mod webhook;
 
 pub use error::{TaskError, TaskResult};
-pub use scheduler::{JobFn, JobId, JobInfo, Scheduler, SchedulerConfig};
-pub use spawner::{SharedSpawner, Spawner, SpawnerConfig, TaskHandle};
-pub use task::{TaskId, TaskInfo, TaskStats, TaskStatus};
+pub use events::{Envelope, Event, EventBus, EventBusConfig, LagPolicy, Subscription, TopicStats};
+pub use jobs::{
+    jobs_result_handler, jobs_status_handler, AcceptedJob, JobContext, JobLookup, JobRecord,
+    JobTracker, JobTrackerConfig, TrackedJobId,
+};
+pub use outbox::{
+    MemoryOutbox, MemoryTransaction, Outbox, OutboxEvent, OutboxRelay, OutboxRelayConfig,
+    OutboxSource, OutboxStats,
+};
+pub use scheduler::{JobFn, JobId, JobInfo, JobSpec, OverlapPolicy, Scheduler, SchedulerConfig};
+pub use spawner::{SharedSpawner, Spawner, SpawnerConfig, TaskHandle, TaskOptions};
+pub use store::{FileStore, JobPersistentState, JobStore, MemoryStore};
+pub use task::{Priority, TaskId, TaskInfo, TaskStats, TaskStatus};
+pub use webhook::{
+    DeadLetter, DeliveryError, DeliveryFailure, EndpointStats, RetryPolicy, WebhookDispatcher,
+    WebhookEndpoint, WebhookEvent,
+};
 
 /// Prelude module for convenient imports.
 pub mod prelude {
     pub use crate::error::{TaskError, TaskResult};
-    pub use crate::scheduler::{JobId, JobInfo, Scheduler, SchedulerConfig};
-    pub use crate::spawner::{SharedSpawner, Spawner, SpawnerConfig, TaskHandle};
-    pub use crate::task::{TaskId, TaskInfo, TaskStats, TaskStatus};
+    pub use crate::events::{Envelope, Event, EventBus, EventBusConfig, LagPolicy};
+    pub use crate::jobs::{AcceptedJob, JobContext, JobTracker, JobTrackerConfig};
+    pub use crate::outbox::{Outbox, OutboxEvent, OutboxRelay, OutboxRelayConfig, OutboxSource};
+    pub use crate::scheduler::{
+        JobId, JobInfo, JobSpec, OverlapPolicy, Scheduler, SchedulerConfig,
+    };
+    pub use crate::spawner::{SharedSpawner, Spawner, SpawnerConfig, TaskHandle, TaskOptions};
+    pub use crate::store::{FileStore, JobPersistentState, JobStore, MemoryStore};
+    pub use crate::task::{Priority, TaskId, TaskInfo, TaskStats, TaskStatus};
+    pub use crate::webhook::{RetryPolicy, WebhookDispatcher, WebhookEndpoint, WebhookEvent};
 }
 
 #[cfg(test)]
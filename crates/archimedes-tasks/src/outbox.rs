@@ -0,0 +1,741 @@
+//! Transactional outbox for events emitted by write handlers.
+//!
+//! A handler that commits a write and then publishes an event can lose the
+//! event if the process dies in between, or publish an event for a write
+//! that was rolled back. With an outbox the handler instead appends the
+//! event through [`Outbox::append`] in the same database transaction as
+//! the write, and an [`OutboxRelay`] publishes committed events afterwards:
+//!
+//! - to an [`EventBus`], as [`OutboxEvent`]s on the `outbox` topic
+//! - to a [`WebhookDispatcher`], for events naming a webhook endpoint
+//!
+//! The relay reads the outbox through an [`OutboxSource`], which the
+//! application implements against its own tables; [`MemoryOutbox`] is an
+//! in-memory reference implementation. Events are relayed at least once:
+//! a relay that dies after publishing an event but before marking it
+//! published publishes it again after a restart.
+//!
+//! # Ordering and retries
+//!
+//! Events with the same [`partition_key`](OutboxEvent::partition_key),
+//! such as the ID of the aggregate they describe, are published in the
+//! order they were appended. When publishing an event fails, it is retried
+//! with the relay's [`RetryPolicy`] backoff and the later events of its
+//! partition wait for it; other partitions are not held up. Events are
+//! never given up on, since the outbox is their only copy.
+//!
+//! # Scheduling
+//!
+//! [`OutboxRelay::schedule`] polls the outbox as an interval job on a
+//! [`Scheduler`]. Replicas sharing the scheduler's [`JobStore`] take the
+//! job's lease before each run, so one replica relays at a time, and
+//! [`Scheduler::stop`] waits for a run in progress to finish.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use archimedes_tasks::{
+//!     EventBus, MemoryOutbox, Outbox, OutboxEvent, OutboxRelay, Scheduler, SharedSpawner,
+//! };
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! # fn example() -> archimedes_tasks::TaskResult<()> {
+//! let outbox = MemoryOutbox::new();
+//!
+//! // In the handler, within the transaction of the write
+//! let mut tx = outbox.begin();
+//! outbox.append(
+//!     &mut tx,
+//!     OutboxEvent::new("order-42", "order.paid", serde_json::json!({ "id": 42 }))
+//!         .with_webhook("billing"),
+//! )?;
+//! tx.commit();
+//!
+//! // At startup
+//! let bus = EventBus::new(SharedSpawner::new());
+//! let scheduler = Scheduler::new();
+//! OutboxRelay::new(Arc::new(outbox))
+//!     .with_event_bus(bus)
+//!     .schedule(&scheduler, Duration::from_secs(1))?;
+//! scheduler.start()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`JobStore`]: crate::JobStore
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::error::{TaskError, TaskResult};
+use crate::events::{Event, EventBus};
+use crate::scheduler::{JobId, JobSpec, OverlapPolicy, Scheduler};
+use crate::webhook::{RetryPolicy, WebhookDispatcher, WebhookEvent};
+
+/// Default number of events read from the outbox per relay run.
+pub const DEFAULT_BATCH_SIZE: usize = 100;
+
+/// Default name of the relay's scheduled job.
+pub const DEFAULT_JOB_NAME: &str = "outbox-relay";
+
+/// An event stored in the outbox until it is published.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutboxEvent {
+    /// Event ID, ordered by creation time.
+    pub id: Uuid,
+    /// Events with the same key are published in the order they were
+    /// appended.
+    pub partition_key: String,
+    /// Event type, such as `"order.paid"`.
+    pub event_type: String,
+    /// JSON payload.
+    pub payload: serde_json::Value,
+    /// Webhook endpoint to dispatch the event to, if any.
+    pub webhook: Option<String>,
+    /// When the event was created.
+    pub created_at: DateTime<Utc>,
+}
+
+impl OutboxEvent {
+    /// Create an event in the partition `partition_key`.
+    #[must_use]
+    pub fn new(
+        partition_key: impl Into<String>,
+        event_type: impl Into<String>,
+        payload: serde_json::Value,
+    ) -> Self {
+        Self {
+            id: Uuid::now_v7(),
+            partition_key: partition_key.into(),
+            event_type: event_type.into(),
+            payload,
+            webhook: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Also dispatch the event to the named webhook endpoint.
+    #[must_use]
+    pub fn with_webhook(mut self, endpoint: impl Into<String>) -> Self {
+        self.webhook = Some(endpoint.into());
+        self
+    }
+}
+
+impl Event for OutboxEvent {
+    const TOPIC: &'static str = "outbox";
+}
+
+/// Appends events to the outbox within an application transaction.
+///
+/// Implemented by the application, or a crate for its database, so the
+/// event is committed or rolled back together with the write it describes.
+pub trait Outbox: Send + Sync {
+    /// The application's transaction type.
+    type Transaction;
+
+    /// Append `event` within `tx`; it becomes visible to the relay once
+    /// `tx` commits.
+    fn append(&self, tx: &mut Self::Transaction, event: OutboxEvent) -> TaskResult<()>;
+}
+
+/// Reads committed events for an [`OutboxRelay`].
+///
+/// Methods are called from a blocking thread, so implementations may do
+/// blocking IO.
+pub trait OutboxSource: Send + Sync + 'static {
+    /// Get up to `limit` unpublished events, oldest first.
+    fn fetch_unpublished(&self, limit: usize) -> TaskResult<Vec<OutboxEvent>>;
+
+    /// Mark events as published, so they are not fetched again.
+    fn mark_published(&self, ids: &[Uuid]) -> TaskResult<()>;
+}
+
+#[derive(Debug)]
+struct StoredEvent {
+    event: OutboxEvent,
+    published: bool,
+}
+
+/// In-memory outbox; events do not survive the process.
+///
+/// Cloning the outbox shares its events, so a clone can stand in for the
+/// database of a restarted process in tests.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryOutbox {
+    events: Arc<Mutex<Vec<StoredEvent>>>,
+}
+
+/// Transaction of a [`MemoryOutbox`]; dropping it without committing
+/// discards its events.
+#[derive(Debug)]
+pub struct MemoryTransaction {
+    outbox: MemoryOutbox,
+    events: Vec<OutboxEvent>,
+}
+
+impl MemoryTransaction {
+    /// Commit the transaction, making its events visible to the relay.
+    pub fn commit(self) {
+        self.outbox
+            .events
+            .lock()
+            .extend(self.events.into_iter().map(|event| StoredEvent {
+                event,
+                published: false,
+            }));
+    }
+}
+
+impl MemoryOutbox {
+    /// Create an empty outbox.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a transaction.
+    #[must_use]
+    pub fn begin(&self) -> MemoryTransaction {
+        MemoryTransaction {
+            outbox: self.clone(),
+            events: Vec::new(),
+        }
+    }
+
+    /// Get the number of events not yet published.
+    #[must_use]
+    pub fn unpublished_count(&self) -> usize {
+        self.events
+            .lock()
+            .iter()
+            .filter(|stored| !stored.published)
+            .count()
+    }
+}
+
+impl Outbox for MemoryOutbox {
+    type Transaction = MemoryTransaction;
+
+    fn append(&self, tx: &mut MemoryTransaction, event: OutboxEvent) -> TaskResult<()> {
+        tx.events.push(event);
+        Ok(())
+    }
+}
+
+impl OutboxSource for MemoryOutbox {
+    fn fetch_unpublished(&self, limit: usize) -> TaskResult<Vec<OutboxEvent>> {
+        Ok(self
+            .events
+            .lock()
+            .iter()
+            .filter(|stored| !stored.published)
+            .take(limit)
+            .map(|stored| stored.event.clone())
+            .collect())
+    }
+
+    fn mark_published(&self, ids: &[Uuid]) -> TaskResult<()> {
+        for stored in self.events.lock().iter_mut() {
+            if ids.contains(&stored.event.id) {
+                stored.published = true;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Configuration for an [`OutboxRelay`].
+#[derive(Debug, Clone)]
+pub struct OutboxRelayConfig {
+    /// Number of events read from the outbox per run.
+    pub batch_size: usize,
+    /// Backoff between attempts to publish an event.
+    ///
+    /// The number of attempts is not limited.
+    pub retry: RetryPolicy,
+    /// Name of the scheduled job, which replicas lease by.
+    pub job_name: String,
+}
+
+impl Default for OutboxRelayConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: DEFAULT_BATCH_SIZE,
+            retry: RetryPolicy::default(),
+            job_name: DEFAULT_JOB_NAME.to_string(),
+        }
+    }
+}
+
+impl OutboxRelayConfig {
+    /// Create a configuration with defaults.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the number of events read from the outbox per run.
+    ///
+    /// Values below 1 are treated as 1.
+    #[must_use]
+    pub fn with_batch_size(mut self, size: usize) -> Self {
+        self.batch_size = size.max(1);
+        self
+    }
+
+    /// Set the backoff between attempts to publish an event.
+    #[must_use]
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Set the name of the scheduled job.
+    #[must_use]
+    pub fn with_job_name(mut self, name: impl Into<String>) -> Self {
+        self.job_name = name.into();
+        self
+    }
+}
+
+/// Relay counters and outbox lag.
+///
+/// The lag is measured at the end of each run among the events it read,
+/// so it counts at most [`batch_size`](OutboxRelayConfig::batch_size)
+/// events.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OutboxStats {
+    /// Number of events published.
+    pub relayed: u64,
+    /// Number of failed attempts to publish an event.
+    pub failed_attempts: u64,
+    /// Number of events left unpublished by the last run.
+    pub backlog: usize,
+    /// Age of the oldest event left unpublished by the last run.
+    pub oldest_unpublished_age: Option<Duration>,
+}
+
+/// Retry state of an event that failed to publish.
+#[derive(Debug, Clone, Copy)]
+struct Retry {
+    attempts: u32,
+    next_attempt: Instant,
+    /// Whether the event already reached the event bus, so a retry of its
+    /// webhook does not publish it there twice.
+    published_to_bus: bool,
+}
+
+#[derive(Debug, Default)]
+struct Lag {
+    backlog: usize,
+    oldest: Option<DateTime<Utc>>,
+}
+
+#[derive(Default)]
+struct RelayState {
+    retries: Mutex<HashMap<Uuid, Retry>>,
+    lag: Mutex<Lag>,
+    /// Held for the duration of a run, so runs never overlap.
+    run: tokio::sync::Mutex<()>,
+    stopped: AtomicBool,
+    relayed: AtomicU64,
+    failed_attempts: AtomicU64,
+}
+
+/// Publishes committed outbox events to the event bus and webhooks.
+///
+/// Cloning the relay shares its retry state and statistics.
+#[derive(Clone)]
+pub struct OutboxRelay {
+    source: Arc<dyn OutboxSource>,
+    config: OutboxRelayConfig,
+    bus: Option<EventBus>,
+    webhooks: Option<WebhookDispatcher>,
+    state: Arc<RelayState>,
+}
+
+impl fmt::Debug for OutboxRelay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OutboxRelay")
+            .field("config", &self.config)
+            .field("bus", &self.bus.is_some())
+            .field("webhooks", &self.webhooks.is_some())
+            .field("stopped", &self.state.stopped.load(Ordering::Relaxed))
+            .finish_non_exhaustive()
+    }
+}
+
+impl OutboxRelay {
+    /// Create a relay reading events from `source`.
+    #[must_use]
+    pub fn new(source: Arc<dyn OutboxSource>) -> Self {
+        Self::with_config(source, OutboxRelayConfig::default())
+    }
+
+    /// Create a relay with custom configuration.
+    #[must_use]
+    pub fn with_config(source: Arc<dyn OutboxSource>, config: OutboxRelayConfig) -> Self {
+        Self {
+            source,
+            config,
+            bus: None,
+            webhooks: None,
+            state: Arc::new(RelayState::default()),
+        }
+    }
+
+    /// Publish every event to `bus`.
+    #[must_use]
+    pub fn with_event_bus(mut self, bus: EventBus) -> Self {
+        self.bus = Some(bus);
+        self
+    }
+
+    /// Dispatch events naming a webhook endpoint through `dispatcher`.
+    ///
+    /// Without a dispatcher, the webhook of an event is ignored.
+    #[must_use]
+    pub fn with_webhooks(mut self, dispatcher: WebhookDispatcher) -> Self {
+        self.webhooks = Some(dispatcher);
+        self
+    }
+
+    /// Get the configuration.
+    #[must_use]
+    pub fn config(&self) -> &OutboxRelayConfig {
+        &self.config
+    }
+
+    /// Get the relay counters and the outbox lag.
+    #[must_use]
+    pub fn stats(&self) -> OutboxStats {
+        let lag = self.state.lag.lock();
+        OutboxStats {
+            relayed: self.state.relayed.load(Ordering::Relaxed),
+            failed_attempts: self.state.failed_attempts.load(Ordering::Relaxed),
+            backlog: lag.backlog,
+            oldest_unpublished_age: lag
+                .oldest
+                .map(|oldest| (Utc::now() - oldest).to_std().unwrap_or_default()),
+        }
+    }
+
+    /// Register a job on `scheduler` relaying events every `every`.
+    ///
+    /// Runs are skipped while the previous one is in flight.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `every` is zero.
+    pub fn schedule(&self, scheduler: &Scheduler, every: Duration) -> TaskResult<JobId> {
+        let spec = JobSpec::interval(self.config.job_name.clone(), every)?
+            .with_overlap(OverlapPolicy::Skip);
+        let relay = self.clone();
+        scheduler.register_job(spec, move || {
+            let relay = relay.clone();
+            async move { relay.relay_once().await.map(|_| ()) }
+        })
+    }
+
+    /// Publish a batch of committed events, returning how many were
+    /// published.
+    ///
+    /// Does nothing once the relay is stopped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the outbox cannot be read or updated. Events
+    /// published before a failed update are published again by the next
+    /// run.
+    pub async fn relay_once(&self) -> TaskResult<usize> {
+        let _run = self.state.run.lock().await;
+        if self.is_stopped() {
+            return Ok(0);
+        }
+
+        let limit = self.config.batch_size;
+        let events = self
+            .blocking(move |source| source.fetch_unpublished(limit))
+            .await?;
+
+        let mut blocked = HashSet::new();
+        let mut published = Vec::new();
+        for event in &events {
+            if self.is_stopped() {
+                break;
+            }
+            if blocked.contains(event.partition_key.as_str()) || !self.publish(event) {
+                // Later events of the partition wait for this one
+                blocked.insert(event.partition_key.as_str());
+                continue;
+            }
+            published.push(event.id);
+        }
+
+        self.state
+            .retries
+            .lock()
+            .retain(|id, _| events.iter().any(|event| event.id == *id) && !published.contains(id));
+        *self.state.lag.lock() = {
+            let remaining = events.iter().filter(|event| !published.contains(&event.id));
+            Lag {
+                backlog: remaining.clone().count(),
+                oldest: remaining.map(|event| event.created_at).min(),
+            }
+        };
+
+        if published.is_empty() {
+            return Ok(0);
+        }
+        let count = published.len();
+        self.blocking(move |source| source.mark_published(&published))
+            .await?;
+        self.state
+            .relayed
+            .fetch_add(count as u64, Ordering::Relaxed);
+        debug!(count, "relayed outbox events");
+        Ok(count)
+    }
+
+    /// Stop relaying and wait for a run in progress to finish.
+    ///
+    /// A run in progress stops before its next event and marks the events
+    /// it published.
+    pub async fn shutdown(&self) {
+        self.state.stopped.store(true, Ordering::Release);
+        drop(self.state.run.lock().await);
+        debug!("outbox relay stopped");
+    }
+
+    /// Check if the relay has been shut down.
+    #[must_use]
+    pub fn is_stopped(&self) -> bool {
+        self.state.stopped.load(Ordering::Acquire)
+    }
+
+    /// Publish `event` unless it is waiting out a retry backoff, returning
+    /// whether it was published.
+    fn publish(&self, event: &OutboxEvent) -> bool {
+        let now = Instant::now();
+        let mut retries = self.state.retries.lock();
+        let retry = retries.get(&event.id).copied();
+        if retry.is_some_and(|retry| retry.next_attempt > now) {
+            return false;
+        }
+
+        let mut published_to_bus = retry.is_some_and(|retry| retry.published_to_bus);
+        let result = self
+            .publish_to_bus(event, &mut published_to_bus)
+            .and_then(|()| self.dispatch(event));
+        let Err(error) = result else {
+            return true;
+        };
+
+        self.state.failed_attempts.fetch_add(1, Ordering::Relaxed);
+        let attempts = retry.map_or(0, |retry| retry.attempts) + 1;
+        let backoff = self.config.retry.backoff(attempts);
+        warn!(
+            event_id = %event.id,
+            partition_key = %event.partition_key,
+            event_type = %event.event_type,
+            attempts,
+            %error,
+            ?backoff,
+            "failed to relay outbox event, retrying"
+        );
+        retries.insert(
+            event.id,
+            Retry {
+                attempts,
+                next_attempt: now + backoff,
+                published_to_bus,
+            },
+        );
+        false
+    }
+
+    fn publish_to_bus(&self, event: &OutboxEvent, published: &mut bool) -> TaskResult<()> {
+        if let Some(bus) = self.bus.as_ref().filter(|_| !*published) {
+            bus.publish(event.clone())?;
+            *published = true;
+        }
+        Ok(())
+    }
+
+    fn dispatch(&self, event: &OutboxEvent) -> TaskResult<()> {
+        if let (Some(dispatcher), Some(endpoint)) = (&self.webhooks, &event.webhook) {
+            dispatcher.dispatch(WebhookEvent::new(
+                endpoint.clone(),
+                event.event_type.clone(),
+                event.payload.clone(),
+            ))?;
+        }
+        Ok(())
+    }
+
+    /// Call the source off the runtime threads.
+    async fn blocking<T, F>(&self, call: F) -> TaskResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&dyn OutboxSource) -> TaskResult<T> + Send + 'static,
+    {
+        let source = Arc::clone(&self.source);
+        tokio::task::spawn_blocking(move || call(source.as_ref()))
+            .await
+            .unwrap_or_else(|e| Err(TaskError::panicked(e.to_string())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::Subscription;
+    use crate::spawner::SharedSpawner;
+    use crate::webhook::WebhookEndpoint;
+
+    fn event(key: &str, n: u64) -> OutboxEvent {
+        OutboxEvent::new(key, "order.updated", serde_json::json!({ "n": n }))
+    }
+
+    fn commit(outbox: &MemoryOutbox, events: Vec<OutboxEvent>) {
+        let mut tx = outbox.begin();
+        for event in events {
+            outbox.append(&mut tx, event).unwrap();
+        }
+        tx.commit();
+    }
+
+    fn received(subscription: &mut Subscription<OutboxEvent>) -> Vec<(String, u64)> {
+        std::iter::from_fn(|| subscription.try_recv())
+            .map(|envelope| {
+                let event = envelope.event;
+                (event.partition_key, event.payload["n"].as_u64().unwrap())
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_relays_once_after_restart() {
+        let outbox = MemoryOutbox::new();
+        commit(&outbox, vec![event("a", 1), event("b", 1)]);
+        // A transaction still open when the process dies is rolled back
+        let mut tx = outbox.begin();
+        outbox.append(&mut tx, event("a", 2)).unwrap();
+        drop(tx);
+
+        // The restarted process relays from the surviving outbox
+        let bus = EventBus::new(SharedSpawner::new());
+        let mut subscription = bus.subscribe::<OutboxEvent>();
+        let relay = OutboxRelay::new(Arc::new(outbox.clone())).with_event_bus(bus);
+
+        assert_eq!(relay.relay_once().await.unwrap(), 2);
+        assert_eq!(relay.relay_once().await.unwrap(), 0);
+        assert_eq!(
+            received(&mut subscription),
+            vec![("a".to_string(), 1), ("b".to_string(), 1)]
+        );
+        assert_eq!(outbox.unpublished_count(), 0);
+        assert_eq!(relay.stats().relayed, 2);
+    }
+
+    #[tokio::test]
+    async fn test_failed_event_holds_up_its_partition_only() {
+        let outbox = MemoryOutbox::new();
+        commit(
+            &outbox,
+            vec![
+                event("a", 1).with_webhook("crm"),
+                event("b", 1),
+                event("a", 2),
+            ],
+        );
+
+        let bus = EventBus::new(SharedSpawner::new());
+        let mut subscription = bus.subscribe::<OutboxEvent>();
+        let dispatcher = WebhookDispatcher::new(SharedSpawner::new());
+        let config = OutboxRelayConfig::new()
+            .with_retry_policy(RetryPolicy::new().with_initial_backoff(Duration::ZERO));
+        let relay = OutboxRelay::with_config(Arc::new(outbox.clone()), config)
+            .with_event_bus(bus)
+            .with_webhooks(dispatcher.clone());
+
+        // The webhook endpoint is not registered yet
+        assert_eq!(relay.relay_once().await.unwrap(), 1);
+        assert_eq!(relay.stats().failed_attempts, 1);
+        assert_eq!(outbox.unpublished_count(), 2);
+
+        dispatcher.register_endpoint("crm", WebhookEndpoint::new("http://127.0.0.1:9", "secret"));
+        assert_eq!(relay.relay_once().await.unwrap(), 2);
+        assert_eq!(outbox.unpublished_count(), 0);
+
+        // The retry dispatched the webhook without publishing to the bus again
+        assert_eq!(
+            received(&mut subscription),
+            vec![
+                ("a".to_string(), 1),
+                ("b".to_string(), 1),
+                ("a".to_string(), 2),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lag_is_reported() {
+        let outbox = MemoryOutbox::new();
+        let mut stale = event("a", 1).with_webhook("crm");
+        stale.created_at = Utc::now() - chrono::Duration::seconds(60);
+        commit(&outbox, vec![stale, event("a", 2), event("b", 1)]);
+
+        let relay = OutboxRelay::new(Arc::new(outbox.clone()))
+            .with_webhooks(WebhookDispatcher::new(SharedSpawner::new()));
+        assert_eq!(relay.stats(), OutboxStats::default());
+
+        assert_eq!(relay.relay_once().await.unwrap(), 1);
+        let stats = relay.stats();
+        assert_eq!(stats.backlog, 2);
+        assert!(stats.oldest_unpublished_age.unwrap() >= Duration::from_secs(60));
+
+        // The failed event waits out its backoff
+        assert_eq!(relay.relay_once().await.unwrap(), 0);
+        assert_eq!(relay.stats().failed_attempts, 1);
+    }
+
+    #[tokio::test]
+    async fn test_scheduled_relay_stops_with_scheduler() {
+        let outbox = MemoryOutbox::new();
+        commit(&outbox, vec![event("a", 1)]);
+
+        let scheduler = Scheduler::with_config(
+            crate::SchedulerConfig::new().with_tick_interval(Duration::from_millis(10)),
+        );
+        let relay = OutboxRelay::new(Arc::new(outbox.clone()));
+        relay
+            .schedule(&scheduler, Duration::from_millis(10))
+            .unwrap();
+        scheduler.start().unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while outbox.unpublished_count() > 0 {
+            assert!(Instant::now() < deadline, "timed out");
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        scheduler.stop().await;
+        relay.shutdown().await;
+
+        commit(&outbox, vec![event("a", 2)]);
+        assert_eq!(relay.relay_once().await.unwrap(), 0);
+        assert_eq!(outbox.unpublished_count(), 1);
+    }
+}
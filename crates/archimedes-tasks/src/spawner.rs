@@ -1,26 +1,41 @@
 //! Task spawner for background execution.
 
+use std::cmp::Ordering as CmpOrdering;
+use std::collections::BinaryHeap;
 use std::future::Future;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Weak};
 use std::time::Duration;
 
 use dashmap::DashMap;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use tokio::sync::oneshot;
 use tokio::task::JoinHandle;
+use tokio::time::Instant;
 use tracing::{debug, info, warn};
 
 use crate::error::{TaskError, TaskResult};
-use crate::task::{TaskId, TaskInfo, TaskStats, TaskStatus};
+use crate::task::{Priority, TaskId, TaskInfo, TaskStats, TaskStatus};
 
 /// Configuration for the task spawner.
 #[derive(Debug, Clone)]
 pub struct SpawnerConfig {
     /// Maximum number of concurrent tasks.
     pub max_concurrent: usize,
+    /// Maximum number of tasks waiting for a slot while `max_concurrent`
+    /// tasks are running. Queued tasks start in [`Priority`] order. Zero
+    /// rejects spawns at the concurrency limit.
+    pub max_queued: usize,
     /// Default timeout for tasks.
     pub default_timeout: Option<Duration>,
+    /// Default time after which a still running task is reported as
+    /// possibly stuck. The task keeps running.
+    pub warn_after: Option<Duration>,
+    /// Default time after which a still running task is cancelled and
+    /// marked failed with [`TaskError::TimeoutEscalation`]. Unlike
+    /// `default_timeout`, this is meant as a last resort well beyond any
+    /// expected run time.
+    pub kill_after: Option<Duration>,
     /// Maximum task registry size.
     pub max_registry_size: usize,
     /// Whether to track task history.
@@ -33,7 +48,10 @@ impl Default for SpawnerConfig {
     fn default() -> Self {
         Self {
             max_concurrent: 1000,
+            max_queued: 0,
             default_timeout: Some(Duration::from_secs(300)), // 5 minutes
+            warn_after: None,
+            kill_after: None,
             max_registry_size: 10000,
             track_history: true,
             history_retention: Duration::from_secs(3600), // 1 hour
@@ -53,6 +71,12 @@ impl SpawnerConfig {
         self
     }
 
+    /// Set the maximum number of tasks queued at the concurrency limit.
+    pub fn with_max_queued(mut self, max: usize) -> Self {
+        self.max_queued = max;
+        self
+    }
+
     /// Set default timeout.
     pub fn with_default_timeout(mut self, timeout: Duration) -> Self {
         self.default_timeout = Some(timeout);
@@ -65,6 +89,18 @@ impl SpawnerConfig {
         self
     }
 
+    /// Set the default warn threshold for long running tasks.
+    pub fn with_warn_after(mut self, warn_after: Duration) -> Self {
+        self.warn_after = Some(warn_after);
+        self
+    }
+
+    /// Set the default kill threshold for stuck tasks.
+    pub fn with_kill_after(mut self, kill_after: Duration) -> Self {
+        self.kill_after = Some(kill_after);
+        self
+    }
+
     /// Set maximum registry size.
     pub fn with_max_registry_size(mut self, size: usize) -> Self {
         self.max_registry_size = size;
@@ -84,13 +120,136 @@ impl SpawnerConfig {
     }
 }
 
+/// Per-task overrides of the spawner configuration.
+///
+/// Settings left unset use the spawner's [`SpawnerConfig`].
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use archimedes_tasks::{Priority, Spawner, TaskOptions};
+/// use std::time::Duration;
+///
+/// # async fn example(spawner: &Spawner) {
+/// let options = TaskOptions::new()
+///     .with_priority(Priority::Low)
+///     .with_warn_after(Duration::from_secs(60))
+///     .with_kill_after(Duration::from_secs(6 * 3600));
+/// spawner.spawn_detached_with_options("reindex", options, async {
+///     // Long running work
+/// }).unwrap();
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TaskOptions {
+    priority: Priority,
+    timeout: Option<Duration>,
+    warn_after: Option<Duration>,
+    kill_after: Option<Duration>,
+}
+
+impl TaskOptions {
+    /// Create options that use the spawner's configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the scheduling priority.
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Set the timeout.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set the time after which the task is reported as possibly stuck.
+    pub fn with_warn_after(mut self, warn_after: Duration) -> Self {
+        self.warn_after = Some(warn_after);
+        self
+    }
+
+    /// Set the time after which the task is killed.
+    pub fn with_kill_after(mut self, kill_after: Duration) -> Self {
+        self.kill_after = Some(kill_after);
+        self
+    }
+}
+
+/// When a running task is warned about, timed out or killed.
+#[derive(Debug, Clone, Copy)]
+struct Deadlines {
+    timeout: Option<Duration>,
+    warn_after: Option<Duration>,
+    kill_after: Option<Duration>,
+}
+
+/// How a supervised task ended.
+enum Outcome<T> {
+    Completed(T),
+    TimedOut(Duration),
+    Escalated(Duration),
+    Cancelled,
+}
+
+/// Run a task until it completes, times out, is killed or is cancelled.
+///
+/// Once the task runs past its warn threshold, a warning is logged and
+/// the task is marked as warned, but it keeps running.
+async fn supervise<F, C>(
+    task: F,
+    mut cancel: C,
+    deadlines: Deadlines,
+    info: &RwLock<TaskInfo>,
+) -> Outcome<F::Output>
+where
+    F: Future,
+    C: Future + Unpin,
+{
+    tokio::pin!(task);
+    let start = Instant::now();
+    let timeout = deadlines.timeout.unwrap_or_default();
+    let warn_after = deadlines.warn_after.unwrap_or_default();
+    let kill_after = deadlines.kill_after.unwrap_or_default();
+    let mut warned = false;
+
+    loop {
+        let should_warn = deadlines.warn_after.is_some() && !warned;
+        tokio::select! {
+            result = &mut task => return Outcome::Completed(result),
+            () = tokio::time::sleep_until(start + timeout), if deadlines.timeout.is_some() => {
+                return Outcome::TimedOut(timeout);
+            }
+            () = tokio::time::sleep_until(start + kill_after), if deadlines.kill_after.is_some() => {
+                return Outcome::Escalated(kill_after);
+            }
+            () = tokio::time::sleep_until(start + warn_after), if should_warn => {
+                warned = true;
+                let elapsed = start.elapsed();
+                let mut info = info.write();
+                warn!(
+                    task_id = %info.id,
+                    task_name = %info.name,
+                    elapsed_ms = u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
+                    "task still running past its warn threshold"
+                );
+                info.mark_warned(elapsed);
+            }
+            _ = &mut cancel => return Outcome::Cancelled,
+        }
+    }
+}
+
 /// A handle to a spawned task.
 #[derive(Debug)]
 pub struct TaskHandle<T> {
     /// Task ID.
     id: TaskId,
     /// Join handle for the task.
-    handle: JoinHandle<Option<T>>,
+    handle: JoinHandle<TaskResult<T>>,
     /// Cancel sender.
     cancel_tx: Option<oneshot::Sender<()>>,
 }
@@ -121,11 +280,13 @@ impl<T> TaskHandle<T> {
     /// Wait for the task to complete.
     ///
     /// Returns the task result if successful, or an error if the task
-    /// was cancelled, timed out, or panicked.
+    /// was cancelled, timed out, or panicked. A timed out task returns
+    /// [`TaskError::Timeout`] with the timeout it exceeded, and a task
+    /// killed past its kill threshold returns
+    /// [`TaskError::TimeoutEscalation`].
     pub async fn join(self) -> TaskResult<T> {
         match self.handle.await {
-            Ok(Some(result)) => Ok(result),
-            Ok(None) => Err(TaskError::cancelled("task was cancelled or timed out")),
+            Ok(result) => result,
             Err(e) => {
                 if e.is_cancelled() {
                     Err(TaskError::cancelled("task was aborted"))
@@ -139,6 +300,187 @@ impl<T> TaskHandle<T> {
     }
 }
 
+/// A task waiting for a concurrency slot.
+#[derive(Debug)]
+struct Waiter {
+    priority: Priority,
+    /// Spawn order, to keep the queue FIFO within a priority.
+    seq: u64,
+    start: oneshot::Sender<()>,
+}
+
+impl PartialEq for Waiter {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == CmpOrdering::Equal
+    }
+}
+
+impl Eq for Waiter {}
+
+impl PartialOrd for Waiter {
+    fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Waiter {
+    fn cmp(&self, other: &Self) -> CmpOrdering {
+        // Max-heap: higher priority first, then earlier spawn
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+#[derive(Debug, Default)]
+struct SlotState {
+    running: usize,
+    queue: BinaryHeap<Waiter>,
+    next_seq: u64,
+}
+
+/// Concurrency slots shared by the spawner and its tasks.
+///
+/// A finishing task hands its slot straight to the highest priority
+/// waiter, so a slot is never up for grabs between a release and a queued
+/// task starting.
+#[derive(Debug)]
+struct Slots {
+    max_running: usize,
+    max_queued: usize,
+    state: Mutex<SlotState>,
+}
+
+impl Slots {
+    fn new(max_running: usize, max_queued: usize) -> Self {
+        Self {
+            max_running,
+            max_queued,
+            state: Mutex::new(SlotState::default()),
+        }
+    }
+
+    /// Take a slot, or queue for one. Returns `None` if the queue is full.
+    fn acquire(&self, priority: Priority) -> Option<Admission> {
+        let mut state = self.state.lock();
+        if state.running < self.max_running {
+            state.running += 1;
+            return Some(Admission::Started);
+        }
+        if state.queue.len() >= self.max_queued {
+            return None;
+        }
+
+        let (start, started) = oneshot::channel();
+        let seq = state.next_seq;
+        state.next_seq += 1;
+        state.queue.push(Waiter {
+            priority,
+            seq,
+            start,
+        });
+        Some(Admission::Queued { seq, started })
+    }
+
+    /// Give up a slot, handing it to the next waiter if there is one.
+    fn release(&self) {
+        let mut state = self.state.lock();
+        while let Some(waiter) = state.queue.pop() {
+            if waiter.start.send(()).is_ok() {
+                return;
+            }
+        }
+        state.running = state.running.saturating_sub(1);
+    }
+
+    /// Remove a waiter that gave up before it started.
+    fn dequeue(&self, seq: u64) {
+        self.state.lock().queue.retain(|waiter| waiter.seq != seq);
+    }
+
+    fn running(&self) -> usize {
+        self.state.lock().running
+    }
+
+    fn queued(&self) -> usize {
+        self.state.lock().queue.len()
+    }
+}
+
+/// Outcome of asking for a slot.
+#[derive(Debug)]
+enum Admission {
+    /// The task holds a slot.
+    Started,
+    /// The task is queued and is sent a slot when one frees up.
+    Queued {
+        seq: u64,
+        started: oneshot::Receiver<()>,
+    },
+}
+
+impl Admission {
+    /// Wait until the task holds a slot.
+    ///
+    /// Returns `false` if `cancel` completes first, in which case the task
+    /// holds no slot.
+    async fn wait<C>(self, slots: &Slots, cancel: C) -> bool
+    where
+        C: Future + Unpin,
+    {
+        let Self::Queued { seq, mut started } = self else {
+            return true;
+        };
+        tokio::select! {
+            result = &mut started => result.is_ok(),
+            _ = cancel => {
+                slots.dequeue(seq);
+                // A slot may have been handed over just before the dequeue
+                if started.try_recv().is_ok() {
+                    slots.release();
+                }
+                false
+            }
+        }
+    }
+}
+
+/// Record how a supervised task ended.
+fn finish<T>(
+    id: TaskId,
+    outcome: Outcome<T>,
+    info: &RwLock<TaskInfo>,
+    stats: &TaskStats,
+) -> TaskResult<T> {
+    match outcome {
+        Outcome::Completed(result) => {
+            info.write().mark_completed();
+            stats.record_completed();
+            debug!(task_id = %id, "task completed");
+            Ok(result)
+        }
+        Outcome::TimedOut(timeout) => {
+            warn!(task_id = %id, ?timeout, "task timed out");
+            info.write().mark_timed_out();
+            stats.record_timed_out();
+            Err(TaskError::timeout(timeout))
+        }
+        Outcome::Escalated(kill_after) => {
+            let error = TaskError::timeout_escalation(kill_after);
+            warn!(task_id = %id, ?kill_after, "task killed after exceeding its kill threshold");
+            info.write().mark_failed(error.to_string());
+            stats.record_escalated();
+            Err(error)
+        }
+        Outcome::Cancelled => {
+            info!(task_id = %id, "task cancelled");
+            info.write().mark_cancelled();
+            stats.record_cancelled();
+            Err(TaskError::cancelled("task was cancelled"))
+        }
+    }
+}
+
 /// Background task spawner with DI support.
 #[derive(Debug)]
 pub struct Spawner {
@@ -148,8 +490,8 @@ pub struct Spawner {
     registry: DashMap<TaskId, Arc<RwLock<TaskInfo>>>,
     /// Statistics.
     stats: Arc<TaskStats>,
-    /// Currently running count.
-    running: Arc<AtomicU64>,
+    /// Concurrency slots and the queue of tasks waiting for one.
+    slots: Arc<Slots>,
     /// Whether the spawner is shutdown.
     shutdown: AtomicBool,
 }
@@ -163,10 +505,10 @@ impl Spawner {
     /// Create a new spawner with custom configuration.
     pub fn with_config(config: SpawnerConfig) -> Self {
         Self {
+            slots: Arc::new(Slots::new(config.max_concurrent, config.max_queued)),
             config,
             registry: DashMap::new(),
             stats: Arc::new(TaskStats::new()),
-            running: Arc::new(AtomicU64::new(0)),
             shutdown: AtomicBool::new(false),
         }
     }
@@ -178,7 +520,12 @@ impl Spawner {
 
     /// Get the current number of running tasks.
     pub fn running_count(&self) -> u64 {
-        self.running.load(Ordering::Relaxed)
+        self.slots.running() as u64
+    }
+
+    /// Get the number of tasks queued at the concurrency limit.
+    pub fn queued_count(&self) -> usize {
+        self.slots.queued()
     }
 
     /// Get task statistics.
@@ -223,6 +570,38 @@ impl Spawner {
         self.spawn_with_timeout(name, task, self.config.default_timeout)
     }
 
+    /// Spawn a background task with a priority.
+    ///
+    /// The priority decides the order in which queued tasks start when the
+    /// spawner is at its concurrency limit.
+    pub fn spawn_with_priority<F, T>(
+        &self,
+        name: impl Into<String>,
+        priority: Priority,
+        task: F,
+    ) -> TaskResult<TaskHandle<T>>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        self.spawn_with_options(name, TaskOptions::new().with_priority(priority), task)
+    }
+
+    /// Spawn a background task with per-task overrides of the configuration.
+    pub fn spawn_with_options<F, T>(
+        &self,
+        name: impl Into<String>,
+        options: TaskOptions,
+        task: F,
+    ) -> TaskResult<TaskHandle<T>>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let deadlines = self.deadlines(&options);
+        self.spawn_task(name.into(), task, deadlines, options.priority)
+    }
+
     /// Spawn a task with a specific timeout.
     pub fn spawn_with_timeout<F, T>(
         &self,
@@ -234,93 +613,64 @@ impl Spawner {
         F: Future<Output = T> + Send + 'static,
         T: Send + 'static,
     {
-        if self.shutdown.load(Ordering::Acquire) {
-            return Err(TaskError::spawn_failed("spawner is shutdown"));
-        }
-
-        let current_running = self.running.load(Ordering::Relaxed);
-        if current_running >= self.config.max_concurrent as u64 {
-            return Err(TaskError::spawn_failed(format!(
-                "max concurrent tasks ({}) reached",
-                self.config.max_concurrent
-            )));
-        }
-
-        if self.registry.len() >= self.config.max_registry_size {
-            // Try to clean up old completed tasks
-            self.cleanup_completed_tasks();
+        let deadlines = Deadlines {
+            timeout,
+            ..self.deadlines(&TaskOptions::new())
+        };
+        self.spawn_task(name.into(), task, deadlines, Priority::Normal)
+    }
 
-            if self.registry.len() >= self.config.max_registry_size {
-                return Err(TaskError::registry_full(self.config.max_registry_size));
-            }
-        }
+    fn spawn_task<F, T>(
+        &self,
+        name: String,
+        task: F,
+        deadlines: Deadlines,
+        priority: Priority,
+    ) -> TaskResult<TaskHandle<T>>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        self.check_registry()?;
+        let admission = self.admit(priority)?;
 
-        let name = name.into();
         let id = TaskId::new();
-        let info = Arc::new(RwLock::new(TaskInfo::new(id, name.clone())));
+        let info = Arc::new(RwLock::new(
+            TaskInfo::new(id, name.clone()).with_priority(priority),
+        ));
 
         // Create cancellation channel
-        let (cancel_tx, cancel_rx) = oneshot::channel();
+        let (cancel_tx, mut cancel_rx) = oneshot::channel();
 
         // Clone for the task
         let info_clone = info.clone();
         let stats = self.stats.clone();
-        let running = self.running.clone();
+        let slots = self.slots.clone();
 
         // Register the task
         if self.config.track_history {
             self.registry.insert(id, info);
         }
 
-        self.running.fetch_add(1, Ordering::Relaxed);
         self.stats.record_spawn();
 
-        debug!(task_id = %id, task_name = %name, "spawning background task");
+        debug!(task_id = %id, task_name = %name, %priority, "spawning background task");
 
         // Spawn the task
         let handle = tokio::spawn(async move {
+            if !admission.wait(&slots, &mut cancel_rx).await {
+                info!(task_id = %id, "queued task cancelled");
+                info_clone.write().mark_cancelled();
+                stats.record_cancelled();
+                return Err(TaskError::cancelled("task was cancelled"));
+            }
+
             info_clone.write().mark_started();
 
-            let result = if let Some(timeout_duration) = timeout {
-                tokio::select! {
-                    result = task => Some(result),
-                    _ = tokio::time::sleep(timeout_duration) => {
-                        warn!(task_id = %id, "task timed out");
-                        info_clone.write().mark_timed_out();
-                        stats.record_timed_out();
-                        running.fetch_sub(1, Ordering::Relaxed);
-                        return None;
-                    }
-                    _ = cancel_rx => {
-                        info!(task_id = %id, "task cancelled");
-                        info_clone.write().mark_cancelled();
-                        stats.record_cancelled();
-                        running.fetch_sub(1, Ordering::Relaxed);
-                        return None;
-                    }
-                }
-            } else {
-                tokio::select! {
-                    result = task => Some(result),
-                    _ = cancel_rx => {
-                        info!(task_id = %id, "task cancelled");
-                        info_clone.write().mark_cancelled();
-                        stats.record_cancelled();
-                        running.fetch_sub(1, Ordering::Relaxed);
-                        return None;
-                    }
-                }
-            };
-
-            if let Some(result) = result {
-                info_clone.write().mark_completed();
-                stats.record_completed();
-                running.fetch_sub(1, Ordering::Relaxed);
-                debug!(task_id = %id, "task completed");
-                Some(result)
-            } else {
-                None
-            }
+            let outcome = supervise(task, &mut cancel_rx, deadlines, &info_clone).await;
+            let result = finish(id, outcome, &info_clone, &stats);
+            slots.release();
+            result
         });
 
         Ok(TaskHandle {
@@ -335,74 +685,153 @@ impl Spawner {
     where
         F: Future<Output = ()> + Send + 'static,
     {
-        if self.shutdown.load(Ordering::Acquire) {
-            return Err(TaskError::spawn_failed("spawner is shutdown"));
-        }
-
-        let current_running = self.running.load(Ordering::Relaxed);
-        if current_running >= self.config.max_concurrent as u64 {
-            return Err(TaskError::spawn_failed(format!(
-                "max concurrent tasks ({}) reached",
-                self.config.max_concurrent
-            )));
-        }
+        self.spawn_detached_with_priority(name, Priority::Normal, task)
+    }
 
-        if self.registry.len() >= self.config.max_registry_size {
-            self.cleanup_completed_tasks();
+    /// Spawn a fire-and-forget task with a priority.
+    pub fn spawn_detached_with_priority<F>(
+        &self,
+        name: impl Into<String>,
+        priority: Priority,
+        task: F,
+    ) -> TaskResult<TaskId>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.spawn_detached_with_options(name, TaskOptions::new().with_priority(priority), task)
+    }
 
-            if self.registry.len() >= self.config.max_registry_size {
-                return Err(TaskError::registry_full(self.config.max_registry_size));
-            }
-        }
+    /// Spawn a fire-and-forget task with per-task overrides of the
+    /// configuration.
+    pub fn spawn_detached_with_options<F>(
+        &self,
+        name: impl Into<String>,
+        options: TaskOptions,
+        task: F,
+    ) -> TaskResult<TaskId>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let priority = options.priority;
+        self.check_registry()?;
+        let admission = self.admit(priority)?;
 
         let name = name.into();
         let id = TaskId::new();
-        let info = Arc::new(RwLock::new(TaskInfo::new(id, name.clone())));
+        let info = Arc::new(RwLock::new(
+            TaskInfo::new(id, name.clone()).with_priority(priority),
+        ));
 
         let info_clone = info.clone();
         let stats = self.stats.clone();
-        let running = self.running.clone();
-        let timeout = self.config.default_timeout;
+        let slots = self.slots.clone();
+        let deadlines = self.deadlines(&options);
 
         if self.config.track_history {
             self.registry.insert(id, info);
         }
 
-        self.running.fetch_add(1, Ordering::Relaxed);
         self.stats.record_spawn();
 
-        debug!(task_id = %id, task_name = %name, "spawning detached background task");
+        debug!(task_id = %id, task_name = %name, %priority, "spawning detached background task");
 
         tokio::spawn(async move {
+            // Detached tasks cannot be cancelled
+            admission.wait(&slots, std::future::pending::<()>()).await;
+
             info_clone.write().mark_started();
 
-            let completed = if let Some(timeout_duration) = timeout {
-                tokio::select! {
-                    _ = task => true,
-                    _ = tokio::time::sleep(timeout_duration) => {
-                        warn!(task_id = %id, "detached task timed out");
-                        info_clone.write().mark_timed_out();
-                        stats.record_timed_out();
-                        running.fetch_sub(1, Ordering::Relaxed);
-                        false
-                    }
-                }
-            } else {
-                task.await;
-                true
-            };
-
-            if completed {
-                info_clone.write().mark_completed();
-                stats.record_completed();
-                running.fetch_sub(1, Ordering::Relaxed);
-                debug!(task_id = %id, "detached task completed");
-            }
+            let outcome =
+                supervise(task, std::future::pending::<()>(), deadlines, &info_clone).await;
+            let _ = finish(id, outcome, &info_clone, &stats);
+            slots.release();
         });
 
         Ok(id)
     }
 
+    /// List running tasks that have run past their warn threshold.
+    ///
+    /// Tasks are listed longest running first. Only tasks tracked in the
+    /// registry are listed, so this is empty with history tracking
+    /// disabled.
+    pub fn long_running_tasks(&self) -> Vec<TaskInfo> {
+        let mut tasks: Vec<TaskInfo> = self
+            .registry
+            .iter()
+            .map(|entry| entry.value().read().clone())
+            .filter(|info| info.status.is_running() && info.warned)
+            .collect();
+        tasks.sort_by_key(|info| info.started_at);
+        tasks
+    }
+
+    /// Render the spawner state served by the admin API's `/-/tasks`.
+    ///
+    /// Tasks are listed oldest first.
+    pub fn state_json(&self) -> serde_json::Value {
+        let mut tasks = self.list_tasks();
+        tasks.sort_by_key(|info| info.created_at);
+        let stats = &self.stats;
+        serde_json::json!({
+            "shutdown": self.is_shutdown(),
+            "running": self.running_count(),
+            "queued": self.queued_count(),
+            "stats": {
+                "spawned": stats.spawned.load(Ordering::Relaxed),
+                "completed": stats.completed.load(Ordering::Relaxed),
+                "failed": stats.failed.load(Ordering::Relaxed),
+                "cancelled": stats.cancelled.load(Ordering::Relaxed),
+                "timed_out": stats.timed_out.load(Ordering::Relaxed),
+                "escalated": stats.escalated.load(Ordering::Relaxed),
+            },
+            "tasks": tasks.iter().map(task_json).collect::<Vec<_>>(),
+        })
+    }
+
+    /// Resolve the deadlines of a task from its options and the config.
+    fn deadlines(&self, options: &TaskOptions) -> Deadlines {
+        Deadlines {
+            timeout: options.timeout.or(self.config.default_timeout),
+            warn_after: options.warn_after.or(self.config.warn_after),
+            kill_after: options.kill_after.or(self.config.kill_after),
+        }
+    }
+
+    /// Reject new tasks once shut down or when the registry is full.
+    fn check_registry(&self) -> TaskResult<()> {
+        if self.shutdown.load(Ordering::Acquire) {
+            return Err(TaskError::spawn_failed("spawner is shutdown"));
+        }
+
+        if self.registry.len() >= self.config.max_registry_size {
+            // Try to clean up old completed tasks
+            self.cleanup_completed_tasks();
+
+            if self.registry.len() >= self.config.max_registry_size {
+                return Err(TaskError::registry_full(self.config.max_registry_size));
+            }
+        }
+        Ok(())
+    }
+
+    /// Take a concurrency slot, or a place in the queue.
+    fn admit(&self, priority: Priority) -> TaskResult<Admission> {
+        self.slots.acquire(priority).ok_or_else(|| {
+            if self.config.max_queued == 0 {
+                TaskError::spawn_failed(format!(
+                    "max concurrent tasks ({}) reached",
+                    self.config.max_concurrent
+                ))
+            } else {
+                TaskError::spawn_failed(format!(
+                    "max concurrent tasks ({}) reached and {} tasks queued",
+                    self.config.max_concurrent, self.config.max_queued
+                ))
+            }
+        })
+    }
+
     /// Clean up completed tasks older than retention period.
     fn cleanup_completed_tasks(&self) {
         let retention = self.config.history_retention;
@@ -425,12 +854,14 @@ impl Spawner {
         info!("shutting down task spawner");
         self.shutdown.store(true, Ordering::Release);
 
-        // Wait for running tasks to complete
+        // Wait for running tasks to complete; queued tasks take over slots
+        // as they free up, so this waits for them too
         let deadline = tokio::time::Instant::now() + timeout;
-        while self.running.load(Ordering::Relaxed) > 0 {
+        while self.slots.running() > 0 {
             if tokio::time::Instant::now() >= deadline {
                 warn!(
-                    running = self.running.load(Ordering::Relaxed),
+                    running = self.slots.running(),
+                    queued = self.slots.queued(),
                     "shutdown timeout reached, tasks still running"
                 );
                 break;
@@ -448,6 +879,22 @@ impl Default for Spawner {
     }
 }
 
+fn task_json(info: &TaskInfo) -> serde_json::Value {
+    serde_json::json!({
+        "id": info.id.to_string(),
+        "name": info.name,
+        "status": info.status.to_string(),
+        "priority": info.priority.to_string(),
+        "created_at": info.created_at.to_rfc3339(),
+        "started_at": info.started_at.map(|at| at.to_rfc3339()),
+        "completed_at": info.completed_at.map(|at| at.to_rfc3339()),
+        "duration_ms": info.duration.map(|d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX)),
+        "retry_count": info.retry_count,
+        "error": info.error,
+        "warned": info.warned,
+    })
+}
+
 /// A shared spawner that can be cloned.
 #[derive(Debug, Clone)]
 pub struct SharedSpawner(Arc<Spawner>);
@@ -477,6 +924,20 @@ impl SharedSpawner {
         self.0.spawn(name, task)
     }
 
+    /// Spawn a background task with a priority.
+    pub fn spawn_with_priority<F, T>(
+        &self,
+        name: impl Into<String>,
+        priority: Priority,
+        task: F,
+    ) -> TaskResult<TaskHandle<T>>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        self.0.spawn_with_priority(name, priority, task)
+    }
+
     /// Spawn a fire-and-forget task.
     pub fn spawn_detached<F>(&self, name: impl Into<String>, task: F) -> TaskResult<TaskId>
     where
@@ -484,6 +945,81 @@ impl SharedSpawner {
     {
         self.0.spawn_detached(name, task)
     }
+
+    /// Spawn a fire-and-forget task with a priority.
+    pub fn spawn_detached_with_priority<F>(
+        &self,
+        name: impl Into<String>,
+        priority: Priority,
+        task: F,
+    ) -> TaskResult<TaskId>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.0.spawn_detached_with_priority(name, priority, task)
+    }
+
+    /// Spawn a background task with per-task overrides of the configuration.
+    pub fn spawn_with_options<F, T>(
+        &self,
+        name: impl Into<String>,
+        options: TaskOptions,
+        task: F,
+    ) -> TaskResult<TaskHandle<T>>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        self.0.spawn_with_options(name, options, task)
+    }
+
+    /// Spawn a fire-and-forget task with per-task overrides of the
+    /// configuration.
+    pub fn spawn_detached_with_options<F>(
+        &self,
+        name: impl Into<String>,
+        options: TaskOptions,
+        task: F,
+    ) -> TaskResult<TaskId>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.0.spawn_detached_with_options(name, options, task)
+    }
+
+    /// Periodically log the tasks listed by
+    /// [`Spawner::long_running_tasks`].
+    ///
+    /// Each sweep that finds such tasks logs one warning naming them. The
+    /// sweep stops once the spawner is shut down or dropped.
+    pub fn spawn_long_running_sweep(&self, interval: Duration) -> JoinHandle<()> {
+        let spawner: Weak<Spawner> = Arc::downgrade(&self.0);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                let Some(spawner) = spawner.upgrade() else {
+                    break;
+                };
+                if spawner.is_shutdown() {
+                    break;
+                }
+                let tasks = spawner.long_running_tasks();
+                if !tasks.is_empty() {
+                    let names: Vec<String> = tasks
+                        .iter()
+                        .map(|info| format!("{} ({})", info.name, info.id))
+                        .collect();
+                    warn!(
+                        count = tasks.len(),
+                        tasks = %names.join(", "),
+                        "tasks running past their warn threshold"
+                    );
+                }
+            }
+        })
+    }
 }
 
 impl Default for SharedSpawner {
@@ -577,6 +1113,130 @@ mod tests {
 
         assert!(handle.is_finished());
         assert_eq!(spawner.stats().total_timed_out(), 1);
+
+        let id = handle.id();
+        assert!(matches!(
+            handle.join().await,
+            Err(TaskError::Timeout(d)) if d == Duration::from_millis(50)
+        ));
+        let info = spawner.get_task(id).unwrap();
+        assert_eq!(info.status, TaskStatus::TimedOut);
+        assert_eq!(spawner.stats().total_cancelled(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_task_timeout_override() {
+        let spawner = Spawner::with_config(
+            SpawnerConfig::new().with_default_timeout(Duration::from_millis(20)),
+        );
+
+        let handle = spawner
+            .spawn_with_timeout(
+                "slow-task",
+                async {
+                    tokio::time::sleep(Duration::from_millis(60)).await;
+                    42
+                },
+                Some(Duration::from_secs(5)),
+            )
+            .unwrap();
+        let id = handle.id();
+
+        assert_eq!(handle.join().await.unwrap(), 42);
+        assert_eq!(spawner.get_task(id).unwrap().status, TaskStatus::Completed);
+        assert_eq!(spawner.stats().total_timed_out(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_task_is_not_timed_out() {
+        let spawner = Spawner::new();
+
+        let mut handle = spawner
+            .spawn("cancelled", async {
+                tokio::time::sleep(Duration::from_secs(10)).await;
+            })
+            .unwrap();
+        handle.cancel();
+
+        assert!(matches!(handle.join().await, Err(TaskError::Cancelled(_))));
+        assert_eq!(spawner.stats().total_timed_out(), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_warn_after_does_not_cancel() {
+        let spawner = Spawner::with_config(
+            SpawnerConfig::new()
+                .without_timeout()
+                .with_warn_after(Duration::from_secs(1)),
+        );
+
+        let handle = spawner
+            .spawn("slow", async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                7
+            })
+            .unwrap();
+        let id = handle.id();
+
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        let info = spawner.get_task(id).unwrap();
+        assert_eq!(info.status, TaskStatus::Running);
+        assert!(info.warned);
+        assert!(info.elapsed_at_warn.unwrap() >= Duration::from_secs(1));
+        let long_running = spawner.long_running_tasks();
+        assert_eq!(long_running.len(), 1);
+        assert_eq!(long_running[0].id, id);
+
+        assert_eq!(handle.join().await.unwrap(), 7);
+        assert_eq!(spawner.get_task(id).unwrap().status, TaskStatus::Completed);
+        assert!(spawner.long_running_tasks().is_empty());
+        assert_eq!(spawner.stats().total_escalated(), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_kill_after_escalates() {
+        let spawner = Spawner::with_config(
+            SpawnerConfig::new()
+                .without_timeout()
+                .with_warn_after(Duration::from_secs(1))
+                .with_kill_after(Duration::from_secs(3600)),
+        );
+
+        let handle = spawner
+            .spawn_with_options(
+                "stuck",
+                TaskOptions::new().with_kill_after(Duration::from_secs(10)),
+                std::future::pending::<()>(),
+            )
+            .unwrap();
+        let id = handle.id();
+
+        assert!(matches!(
+            handle.join().await,
+            Err(TaskError::TimeoutEscalation(d)) if d == Duration::from_secs(10)
+        ));
+        let info = spawner.get_task(id).unwrap();
+        assert_eq!(info.status, TaskStatus::Failed);
+        assert!(info.warned);
+        assert!(info.error.is_some());
+
+        let detached = spawner
+            .spawn_detached_with_options(
+                "stuck-detached",
+                TaskOptions::new().with_kill_after(Duration::from_secs(5)),
+                std::future::pending::<()>(),
+            )
+            .unwrap();
+        tokio::time::sleep(Duration::from_secs(6)).await;
+        assert_eq!(
+            spawner.get_task(detached).unwrap().status,
+            TaskStatus::Failed
+        );
+
+        assert_eq!(spawner.stats().total_escalated(), 2);
+        assert_eq!(spawner.stats().total_failed(), 2);
+        assert_eq!(spawner.stats().total_timed_out(), 0);
+        assert_eq!(spawner.running_count(), 0);
     }
 
     #[tokio::test]
@@ -600,6 +1260,67 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_queued_tasks_start_in_priority_order() {
+        let spawner = Spawner::with_config(
+            SpawnerConfig::new()
+                .with_max_concurrent(1)
+                .with_max_queued(3),
+        );
+        let started = Arc::new(Mutex::new(Vec::new()));
+
+        let (release_tx, release_rx) = oneshot::channel::<()>();
+        let blocker = spawner
+            .spawn("blocker", async move {
+                let _ = release_rx.await;
+            })
+            .unwrap();
+
+        let mut queued = Vec::new();
+        for (name, priority) in [
+            ("low-1", Priority::Low),
+            ("normal", Priority::Normal),
+            ("low-2", Priority::Low),
+        ] {
+            let started = started.clone();
+            let handle = spawner
+                .spawn_with_priority(name, priority, async move {
+                    started.lock().push(name);
+                })
+                .unwrap();
+            queued.push(handle);
+        }
+        // The queue holds three tasks, so a fourth is rejected
+        let rejected = spawner.spawn_detached_with_priority("high", Priority::High, async {});
+        assert!(rejected.is_err());
+        assert_eq!(spawner.queued_count(), 3);
+        let info = spawner.get_task(queued[1].id()).unwrap();
+        assert_eq!(info.status, TaskStatus::Pending);
+        assert_eq!(info.priority, Priority::Normal);
+
+        // Make room by cancelling a queued task, then queue a high one
+        queued.remove(2).cancel();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(spawner.queued_count(), 2);
+        let started_high = started.clone();
+        spawner
+            .spawn_detached_with_priority("high", Priority::High, async move {
+                started_high.lock().push("high");
+            })
+            .unwrap();
+
+        release_tx.send(()).unwrap();
+        blocker.join().await.unwrap();
+        for handle in queued {
+            handle.join().await.unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        assert_eq!(*started.lock(), vec!["high", "normal", "low-1"]);
+        assert_eq!(spawner.running_count(), 0);
+        assert_eq!(spawner.queued_count(), 0);
+    }
+
     #[tokio::test]
     async fn test_spawner_shutdown() {
         let spawner = Spawner::new();
@@ -635,6 +1356,11 @@ mod tests {
 
         let tasks = spawner.list_tasks();
         assert_eq!(tasks.len(), 2);
+
+        let state = spawner.state_json();
+        assert_eq!(state["stats"]["spawned"], 2);
+        assert_eq!(state["tasks"].as_array().unwrap().len(), 2);
+        assert_eq!(state["tasks"][0]["status"], "completed");
     }
 
     #[tokio::test]
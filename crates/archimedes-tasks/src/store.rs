@@ -0,0 +1,454 @@
+//! Persistence of scheduler state across restarts and replicas.
+//!
+//! A restarted process would otherwise lose each job's last run time: an
+//! interval job restarts its cadence from scratch and a missed cron run is
+//! never caught up. Replicas running the same jobs would also all fire
+//! them. A [`JobStore`] records a [`JobPersistentState`] per job, keyed by
+//! job name, and hands out leases so only one scheduler instance runs a
+//! job at a time.
+//!
+//! Two stores are provided:
+//!
+//! - [`MemoryStore`], the default, keeps state for the life of the process
+//!   only, so restarts behave as if nothing was persisted
+//! - [`FileStore`] keeps state in a JSON file, rewritten atomically on
+//!   every change, which processes on the same host can share
+//!
+//! Stores for shared databases such as Redis or Postgres implement
+//! [`JobStore`] in their own crates.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{TaskError, TaskResult};
+
+/// Persisted state of a scheduled job.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobPersistentState {
+    /// Fire time of the most recent run, whatever its outcome.
+    pub last_run: Option<DateTime<Utc>>,
+    /// Fire time of the most recent successful run.
+    pub last_success: Option<DateTime<Utc>>,
+    /// Number of failed runs since the last success.
+    pub consecutive_failures: u32,
+}
+
+impl JobPersistentState {
+    /// Record a run that fired at `fired_at`.
+    ///
+    /// Runs may finish out of order, so an earlier fire time never replaces
+    /// a later one.
+    pub fn record_run(&mut self, fired_at: DateTime<Utc>, succeeded: bool) {
+        self.last_run = self.last_run.max(Some(fired_at));
+        if succeeded {
+            self.last_success = self.last_success.max(Some(fired_at));
+            self.consecutive_failures = 0;
+        } else {
+            self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        }
+    }
+}
+
+/// Persists scheduled job state and arbitrates which scheduler instance
+/// runs a job.
+///
+/// Jobs are identified by name, since job IDs are regenerated on every
+/// start. Methods are called from a blocking thread, never from the
+/// scheduler tick, so implementations may do blocking IO.
+pub trait JobStore: Send + Sync + 'static {
+    /// Load the state of a job.
+    fn load(&self, job_name: &str) -> TaskResult<Option<JobPersistentState>>;
+
+    /// Replace the state of a job.
+    fn save(&self, job_name: &str, state: &JobPersistentState) -> TaskResult<()>;
+
+    /// Take or renew the lease on a job for `ttl`.
+    ///
+    /// Returns `false` if another holder has a lease that has not expired.
+    /// Calling again as the current holder extends the lease.
+    fn acquire_lease(&self, job_name: &str, holder: &str, ttl: Duration) -> TaskResult<bool>;
+
+    /// Give up a lease taken with [`acquire_lease`](Self::acquire_lease).
+    ///
+    /// Does nothing if `holder` does not hold the lease.
+    fn release_lease(&self, job_name: &str, holder: &str) -> TaskResult<()>;
+}
+
+/// Lease on a job, held by one scheduler instance until it expires.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Lease {
+    holder: String,
+    expires_at: DateTime<Utc>,
+}
+
+/// Everything stored for a job.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct JobRecord {
+    #[serde(flatten)]
+    state: JobPersistentState,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    lease: Option<Lease>,
+}
+
+impl JobRecord {
+    /// Take the lease unless another holder has it, returning whether it
+    /// was taken.
+    fn acquire(&mut self, holder: &str, ttl: Duration, now: DateTime<Utc>) -> bool {
+        if let Some(lease) = &self.lease {
+            if lease.holder != holder && lease.expires_at > now {
+                return false;
+            }
+        }
+        let expires_at = chrono::Duration::from_std(ttl)
+            .ok()
+            .and_then(|ttl| now.checked_add_signed(ttl))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC);
+        self.lease = Some(Lease {
+            holder: holder.to_string(),
+            expires_at,
+        });
+        true
+    }
+
+    /// Drop the lease if `holder` has it, returning whether it was dropped.
+    fn release(&mut self, holder: &str) -> bool {
+        if self
+            .lease
+            .as_ref()
+            .is_some_and(|lease| lease.holder == holder)
+        {
+            self.lease = None;
+            return true;
+        }
+        false
+    }
+}
+
+/// In-memory store; state does not survive a restart.
+///
+/// Leases only exclude schedulers sharing the same store instance.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    records: Mutex<HashMap<String, JobRecord>>,
+}
+
+impl MemoryStore {
+    /// Create an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl JobStore for MemoryStore {
+    fn load(&self, job_name: &str) -> TaskResult<Option<JobPersistentState>> {
+        Ok(self.records.lock().get(job_name).map(|record| record.state))
+    }
+
+    fn save(&self, job_name: &str, state: &JobPersistentState) -> TaskResult<()> {
+        self.records
+            .lock()
+            .entry(job_name.to_string())
+            .or_default()
+            .state = *state;
+        Ok(())
+    }
+
+    fn acquire_lease(&self, job_name: &str, holder: &str, ttl: Duration) -> TaskResult<bool> {
+        Ok(self
+            .records
+            .lock()
+            .entry(job_name.to_string())
+            .or_default()
+            .acquire(holder, ttl, Utc::now()))
+    }
+
+    fn release_lease(&self, job_name: &str, holder: &str) -> TaskResult<()> {
+        if let Some(record) = self.records.lock().get_mut(job_name) {
+            record.release(holder);
+        }
+        Ok(())
+    }
+}
+
+/// How long a writer waits for the lock file before giving up.
+const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Age after which a lock file is assumed to be left by a crashed writer.
+const STALE_LOCK_AGE: Duration = Duration::from_secs(30);
+
+/// Store backed by a JSON file.
+///
+/// The file maps job names to their state and lease, with times in
+/// RFC 3339. Every change writes the whole map to a temporary file next to
+/// it and renames it into place, so a crash mid-write leaves the previous
+/// state intact. Changes are serialized across processes with a `.lock`
+/// file beside the state file, so several processes on one host, each
+/// with its own `FileStore`, can share the same path.
+#[derive(Debug)]
+pub struct FileStore {
+    path: PathBuf,
+    /// Serializes changes within this process.
+    write: Mutex<()>,
+}
+
+impl FileStore {
+    /// Open a store, checking existing state at `path` if present.
+    pub fn open(path: impl Into<PathBuf>) -> TaskResult<Self> {
+        let store = Self {
+            path: path.into(),
+            write: Mutex::new(()),
+        };
+        store.read()?;
+        Ok(store)
+    }
+
+    /// Get the path of the state file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Read the state file, which is only ever replaced whole.
+    fn read(&self) -> TaskResult<HashMap<String, JobRecord>> {
+        match fs::read(&self.path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).map_err(|e| {
+                TaskError::invalid_config(format!(
+                    "invalid scheduler state in {}: {e}",
+                    self.path.display()
+                ))
+            }),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(HashMap::new()),
+            Err(e) => Err(io_error(&self.path, &e)),
+        }
+    }
+
+    /// Apply `change` to the current state under the lock, writing the
+    /// state back if `change` reports it changed anything.
+    fn update<T>(
+        &self,
+        change: impl FnOnce(&mut HashMap<String, JobRecord>) -> (T, bool),
+    ) -> TaskResult<T> {
+        let _local = self.write.lock();
+        let _lock = LockFile::acquire(self.sibling(".lock"))?;
+
+        let mut records = self.read()?;
+        let (result, changed) = change(&mut records);
+        if changed {
+            self.persist(&records)?;
+        }
+        Ok(result)
+    }
+
+    /// Write the state to a temporary file and rename it into place.
+    fn persist(&self, records: &HashMap<String, JobRecord>) -> TaskResult<()> {
+        let json =
+            serde_json::to_vec_pretty(records).map_err(|e| TaskError::internal(e.to_string()))?;
+
+        let tmp = self.sibling(".tmp");
+        let mut file = fs::File::create(&tmp).map_err(|e| io_error(&tmp, &e))?;
+        file.write_all(&json)
+            .and_then(|()| file.sync_all())
+            .map_err(|e| io_error(&tmp, &e))?;
+        fs::rename(&tmp, &self.path).map_err(|e| io_error(&self.path, &e))
+    }
+
+    /// Path of the state file with `suffix` appended.
+    fn sibling(&self, suffix: &str) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(suffix);
+        PathBuf::from(path)
+    }
+}
+
+impl JobStore for FileStore {
+    fn load(&self, job_name: &str) -> TaskResult<Option<JobPersistentState>> {
+        Ok(self.read()?.get(job_name).map(|record| record.state))
+    }
+
+    fn save(&self, job_name: &str, state: &JobPersistentState) -> TaskResult<()> {
+        self.update(|records| {
+            records.entry(job_name.to_string()).or_default().state = *state;
+            ((), true)
+        })
+    }
+
+    fn acquire_lease(&self, job_name: &str, holder: &str, ttl: Duration) -> TaskResult<bool> {
+        self.update(|records| {
+            let acquired =
+                records
+                    .entry(job_name.to_string())
+                    .or_default()
+                    .acquire(holder, ttl, Utc::now());
+            (acquired, acquired)
+        })
+    }
+
+    fn release_lease(&self, job_name: &str, holder: &str) -> TaskResult<()> {
+        self.update(|records| {
+            let released = records
+                .get_mut(job_name)
+                .is_some_and(|record| record.release(holder));
+            ((), released)
+        })
+    }
+}
+
+/// Exclusive lock held by creating a file, removed on drop.
+struct LockFile(PathBuf);
+
+impl LockFile {
+    /// Create the lock file, waiting while another writer holds it.
+    fn acquire(path: PathBuf) -> TaskResult<Self> {
+        let started = Instant::now();
+        loop {
+            match fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(_) => return Ok(Self(path)),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    if Self::is_stale(&path) {
+                        let _ = fs::remove_file(&path);
+                        continue;
+                    }
+                    if started.elapsed() > LOCK_TIMEOUT {
+                        return Err(TaskError::internal(format!(
+                            "timed out waiting for {}",
+                            path.display()
+                        )));
+                    }
+                    std::thread::sleep(Duration::from_millis(2));
+                }
+                Err(e) => return Err(io_error(&path, &e)),
+            }
+        }
+    }
+
+    /// Whether the lock file was left behind by a crashed writer.
+    fn is_stale(path: &Path) -> bool {
+        fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_some_and(|age| age > STALE_LOCK_AGE)
+    }
+}
+
+impl Drop for LockFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+fn io_error(path: &Path, error: &io::Error) -> TaskError {
+    TaskError::internal(format!("scheduler state {}: {error}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(secs, 0).unwrap()
+    }
+
+    #[test]
+    fn test_record_run_keeps_latest() {
+        let mut state = JobPersistentState::default();
+        state.record_run(at(200), true);
+        state.record_run(at(100), false);
+        state.record_run(at(300), false);
+
+        assert_eq!(state.last_run, Some(at(300)));
+        assert_eq!(state.last_success, Some(at(200)));
+        assert_eq!(state.consecutive_failures, 2);
+
+        state.record_run(at(400), true);
+        assert_eq!(state.consecutive_failures, 0);
+    }
+
+    #[test]
+    fn test_memory_store_leases() {
+        let store = MemoryStore::new();
+        assert_eq!(store.load("report").unwrap(), None);
+
+        let ttl = Duration::from_secs(60);
+        assert!(store.acquire_lease("report", "a", ttl).unwrap());
+        assert!(store.acquire_lease("report", "a", ttl).unwrap());
+        assert!(!store.acquire_lease("report", "b", ttl).unwrap());
+        assert!(store.acquire_lease("cleanup", "b", ttl).unwrap());
+
+        store.release_lease("report", "b").unwrap();
+        assert!(!store.acquire_lease("report", "b", ttl).unwrap());
+        store.release_lease("report", "a").unwrap();
+        assert!(store.acquire_lease("report", "b", ttl).unwrap());
+
+        // An expired lease can be taken over
+        assert!(store
+            .acquire_lease("expiring", "a", Duration::ZERO)
+            .unwrap());
+        assert!(store.acquire_lease("expiring", "b", ttl).unwrap());
+    }
+
+    #[test]
+    fn test_file_store_survives_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("scheduler.json");
+
+        let mut state = JobPersistentState::default();
+        state.record_run(at(100), false);
+
+        let store = FileStore::open(&path).unwrap();
+        store.save("report", &state).unwrap();
+        assert!(store
+            .acquire_lease("report", "a", Duration::from_secs(60))
+            .unwrap());
+        drop(store);
+
+        let reopened = FileStore::open(&path).unwrap();
+        assert_eq!(reopened.load("report").unwrap(), Some(state));
+        assert_eq!(reopened.load("cleanup").unwrap(), None);
+        assert!(!reopened
+            .acquire_lease("report", "b", Duration::from_secs(60))
+            .unwrap());
+        assert!(!dir.path().join("scheduler.json.tmp").exists());
+        assert!(!dir.path().join("scheduler.json.lock").exists());
+    }
+
+    #[test]
+    fn test_file_stores_share_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("scheduler.json");
+        let first = FileStore::open(&path).unwrap();
+        let second = FileStore::open(&path).unwrap();
+
+        let ttl = Duration::from_secs(60);
+        assert!(first.acquire_lease("report", "a", ttl).unwrap());
+        assert!(!second.acquire_lease("report", "b", ttl).unwrap());
+
+        let mut state = JobPersistentState::default();
+        state.record_run(at(100), true);
+        first.save("report", &state).unwrap();
+        first.release_lease("report", "a").unwrap();
+
+        assert_eq!(second.load("report").unwrap(), Some(state));
+        assert!(second.acquire_lease("report", "b", ttl).unwrap());
+    }
+
+    #[test]
+    fn test_file_store_rejects_corrupt_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("scheduler.json");
+        fs::write(&path, "not json").unwrap();
+
+        assert!(FileStore::open(&path).is_err());
+    }
+}
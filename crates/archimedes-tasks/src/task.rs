@@ -106,6 +106,32 @@ impl fmt::Display for TaskStatus {
     }
 }
 
+/// Scheduling priority of a task.
+///
+/// Priority only matters when the spawner is at its concurrency limit:
+/// queued tasks then start highest priority first, and in spawn order
+/// within a priority. Running tasks are never preempted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// Starts after all queued normal and high priority tasks.
+    Low,
+    /// The default priority.
+    #[default]
+    Normal,
+    /// Starts before all queued normal and low priority tasks.
+    High,
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Low => write!(f, "low"),
+            Self::Normal => write!(f, "normal"),
+            Self::High => write!(f, "high"),
+        }
+    }
+}
+
 /// Information about a task.
 #[derive(Debug, Clone)]
 pub struct TaskInfo {
@@ -115,6 +141,8 @@ pub struct TaskInfo {
     pub name: String,
     /// Current status.
     pub status: TaskStatus,
+    /// Scheduling priority.
+    pub priority: Priority,
     /// When the task was created.
     pub created_at: DateTime<Utc>,
     /// When the task started running.
@@ -127,6 +155,10 @@ pub struct TaskInfo {
     pub retry_count: u32,
     /// Error message if failed.
     pub error: Option<String>,
+    /// Whether the task ran past its warn threshold.
+    pub warned: bool,
+    /// How long the task had been running when the warning was emitted.
+    pub elapsed_at_warn: Option<Duration>,
 }
 
 impl TaskInfo {
@@ -136,21 +168,36 @@ impl TaskInfo {
             id,
             name: name.into(),
             status: TaskStatus::Pending,
+            priority: Priority::Normal,
             created_at: Utc::now(),
             started_at: None,
             completed_at: None,
             duration: None,
             retry_count: 0,
             error: None,
+            warned: false,
+            elapsed_at_warn: None,
         }
     }
 
+    /// Set the scheduling priority.
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
     /// Mark as started.
     pub fn mark_started(&mut self) {
         self.status = TaskStatus::Running;
         self.started_at = Some(Utc::now());
     }
 
+    /// Mark as having run past its warn threshold after `elapsed`.
+    pub fn mark_warned(&mut self, elapsed: Duration) {
+        self.warned = true;
+        self.elapsed_at_warn = Some(elapsed);
+    }
+
     /// Mark as completed.
     pub fn mark_completed(&mut self) {
         self.status = TaskStatus::Completed;
@@ -207,6 +254,8 @@ pub struct TaskStats {
     pub cancelled: AtomicU64,
     /// Tasks that timed out.
     pub timed_out: AtomicU64,
+    /// Tasks killed by timeout escalation, also counted as failed.
+    pub escalated: AtomicU64,
     /// Currently running tasks.
     pub running: AtomicU64,
 }
@@ -247,6 +296,12 @@ impl TaskStats {
         self.running.fetch_sub(1, Ordering::Relaxed);
     }
 
+    /// Record a task killed by timeout escalation.
+    pub fn record_escalated(&self) {
+        self.escalated.fetch_add(1, Ordering::Relaxed);
+        self.record_failed();
+    }
+
     /// Get total spawned count.
     pub fn total_spawned(&self) -> u64 {
         self.spawned.load(Ordering::Relaxed)
@@ -272,6 +327,11 @@ impl TaskStats {
         self.timed_out.load(Ordering::Relaxed)
     }
 
+    /// Get the count of tasks killed by timeout escalation.
+    pub fn total_escalated(&self) -> u64 {
+        self.escalated.load(Ordering::Relaxed)
+    }
+
     /// Get currently running count.
     pub fn currently_running(&self) -> u64 {
         self.running.load(Ordering::Relaxed)
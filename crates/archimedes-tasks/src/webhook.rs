@@ -0,0 +1,996 @@
+//! Signed webhook delivery with retries and dead-lettering.
+//!
+//! A [`WebhookDispatcher`] posts [`WebhookEvent`]s to registered endpoints
+//! as background tasks on a [`SharedSpawner`]. Each request is signed with
+//! the endpoint's secret, failed deliveries are retried with exponential
+//! backoff, and events that cannot be delivered are handed to a dead-letter
+//! callback instead of being lost.
+//!
+//! Deliveries are ordinary spawner tasks, so [`Spawner::shutdown`] waits
+//! for them, retries included, up to its timeout. A delivery is also
+//! bounded by the spawner's task timeout.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use archimedes_tasks::{SharedSpawner, WebhookDispatcher, WebhookEndpoint, WebhookEvent};
+//! use std::time::Duration;
+//!
+//! # fn example() -> archimedes_tasks::TaskResult<()> {
+//! let dispatcher = WebhookDispatcher::new(SharedSpawner::new()).on_dead_letter(|dead| {
+//!     eprintln!("giving up on {} after {} attempts: {}", dead.id, dead.attempts, dead.error);
+//! });
+//!
+//! dispatcher.register_endpoint(
+//!     "billing",
+//!     WebhookEndpoint::new("https://billing.example.com/hooks", "whsec_...")
+//!         .with_timeout(Duration::from_secs(5)),
+//! );
+//!
+//! dispatcher.dispatch(WebhookEvent::new(
+//!     "billing",
+//!     "invoice.paid",
+//!     serde_json::json!({ "invoice": "inv_123" }),
+//! ))?;
+//!
+//! // Pause deliveries while the receiver is down for maintenance
+//! dispatcher.set_enabled("billing", false)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Signatures
+//!
+//! Every request carries these headers:
+//!
+//! | Header | Value |
+//! |--------|-------|
+//! | `X-Webhook-Id` | Delivery ID, the same across retries |
+//! | `X-Webhook-Event` | The event type |
+//! | `X-Webhook-Timestamp` | Unix time of the attempt, in seconds |
+//! | `X-Webhook-Signature` | `t=<timestamp>,v1=<hex HMAC-SHA256>` |
+//!
+//! The HMAC covers `<timestamp>.<body>`, so a captured request cannot be
+//! replayed with a new timestamp. Receivers check it with
+//! [`verify_signature`].
+//!
+//! # Retries
+//!
+//! Only responses with a 5xx status and attempts that time out are retried;
+//! any other failure is dead-lettered straight away. Endpoint settings are
+//! read again before every attempt, so disabling an endpoint or rotating
+//! its secret takes effect for deliveries already in progress.
+//!
+//! [`Spawner::shutdown`]: crate::Spawner::shutdown
+
+use std::fmt::{self, Write};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use hmac::{Hmac, Mac};
+use http::{header, StatusCode};
+use serde::Serialize;
+use sha2::Sha256;
+use thiserror::Error;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::error::{TaskError, TaskResult};
+use crate::spawner::SharedSpawner;
+
+/// Header carrying the delivery ID.
+pub const ID_HEADER: &str = "x-webhook-id";
+
+/// Header carrying the event type.
+pub const EVENT_HEADER: &str = "x-webhook-event";
+
+/// Header carrying the Unix timestamp of the attempt.
+pub const TIMESTAMP_HEADER: &str = "x-webhook-timestamp";
+
+/// Header carrying the signature.
+pub const SIGNATURE_HEADER: &str = "x-webhook-signature";
+
+/// Default timeout of a single delivery attempt.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// An event to deliver to a registered endpoint.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WebhookEvent {
+    /// Name of the endpoint to deliver to.
+    pub endpoint: String,
+    /// Event type, sent in the `X-Webhook-Event` header.
+    pub event_type: String,
+    /// JSON payload, sent as the request body.
+    pub payload: serde_json::Value,
+}
+
+impl WebhookEvent {
+    /// Create an event for the named endpoint.
+    #[must_use]
+    pub fn new(
+        endpoint: impl Into<String>,
+        event_type: impl Into<String>,
+        payload: serde_json::Value,
+    ) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            event_type: event_type.into(),
+            payload,
+        }
+    }
+}
+
+/// Settings of a webhook endpoint.
+#[derive(Clone)]
+pub struct WebhookEndpoint {
+    url: String,
+    secret: String,
+    timeout: Duration,
+    enabled: bool,
+}
+
+impl fmt::Debug for WebhookEndpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebhookEndpoint")
+            .field("url", &self.url)
+            .field("secret", &"<redacted>")
+            .field("timeout", &self.timeout)
+            .field("enabled", &self.enabled)
+            .finish()
+    }
+}
+
+impl WebhookEndpoint {
+    /// Create an enabled endpoint posting to `url`, signed with `secret`.
+    #[must_use]
+    pub fn new(url: impl Into<String>, secret: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            secret: secret.into(),
+            timeout: DEFAULT_TIMEOUT,
+            enabled: true,
+        }
+    }
+
+    /// Set the timeout of a single delivery attempt.
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set the signing secret.
+    #[must_use]
+    pub fn with_secret(mut self, secret: impl Into<String>) -> Self {
+        self.secret = secret.into();
+        self
+    }
+
+    /// Set whether events are delivered to this endpoint.
+    #[must_use]
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Get the URL events are posted to.
+    #[must_use]
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Get the timeout of a single delivery attempt.
+    #[must_use]
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Check whether events are delivered to this endpoint.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+}
+
+/// When and how often failed deliveries are retried.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    multiplier: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            multiplier: 2.0,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Create the default policy: 5 attempts, backing off from 1 second,
+    /// doubling up to 60 seconds.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the total number of attempts, including the first.
+    ///
+    /// Values below 1 are treated as 1.
+    #[must_use]
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Set the delay before the first retry.
+    #[must_use]
+    pub fn with_initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    /// Set the longest delay between attempts.
+    #[must_use]
+    pub fn with_max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff = backoff;
+        self
+    }
+
+    /// Set the factor the delay grows by after each retry.
+    ///
+    /// Values below 1 are treated as 1.
+    #[must_use]
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier.max(1.0);
+        self
+    }
+
+    /// Get the total number of attempts, including the first.
+    #[must_use]
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Get the delay after failed attempt number `attempt`, counting from 1.
+    #[must_use]
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = i32::try_from(attempt.saturating_sub(1)).unwrap_or(i32::MAX);
+        let secs = self.initial_backoff.as_secs_f64() * self.multiplier.powi(exponent);
+        Duration::from_secs_f64(secs.min(self.max_backoff.as_secs_f64()))
+    }
+}
+
+/// Why a delivery attempt failed.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum DeliveryError {
+    /// The endpoint responded with a non-success status.
+    #[error("endpoint responded with {0}")]
+    Status(StatusCode),
+
+    /// The attempt exceeded the endpoint's timeout.
+    #[error("delivery timed out")]
+    Timeout,
+
+    /// The request could not be sent.
+    #[error("delivery failed: {0}")]
+    Transport(String),
+
+    /// The endpoint is disabled.
+    #[error("endpoint is disabled")]
+    Disabled,
+
+    /// The endpoint was removed while the event was pending.
+    #[error("endpoint is not registered")]
+    UnknownEndpoint,
+}
+
+impl DeliveryError {
+    /// Check if the failure is worth retrying.
+    ///
+    /// Only 5xx responses and timeouts are retried.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Status(status) => status.is_server_error(),
+            Self::Timeout => true,
+            Self::Transport(_) | Self::Disabled | Self::UnknownEndpoint => false,
+        }
+    }
+}
+
+/// A failed delivery attempt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeliveryFailure {
+    /// When the attempt failed.
+    pub at: DateTime<Utc>,
+    /// Attempt number, counting from 1.
+    pub attempt: u32,
+    /// Why it failed.
+    pub error: DeliveryError,
+}
+
+/// Delivery counters of an endpoint.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EndpointStats {
+    /// Number of events delivered.
+    pub delivered: u64,
+    /// Number of events dead-lettered.
+    pub dead_lettered: u64,
+    /// Number of requests sent, retries included.
+    pub attempts: u64,
+    /// Number of requests that failed.
+    pub failed_attempts: u64,
+    /// When an event was last delivered.
+    pub last_success: Option<DateTime<Utc>>,
+    /// The most recent failed attempt.
+    pub last_failure: Option<DeliveryFailure>,
+}
+
+/// An event that could not be delivered.
+#[derive(Debug, Clone)]
+pub struct DeadLetter {
+    /// Delivery ID, as sent in the `X-Webhook-Id` header.
+    pub id: Uuid,
+    /// The undelivered event.
+    pub event: WebhookEvent,
+    /// Number of requests sent.
+    pub attempts: u32,
+    /// Why the last attempt failed.
+    pub error: DeliveryError,
+}
+
+type DeadLetterFn = Arc<dyn Fn(DeadLetter) + Send + Sync>;
+
+struct EndpointEntry {
+    config: WebhookEndpoint,
+    stats: EndpointStats,
+}
+
+/// Delivers signed webhook events in the background.
+///
+/// Cloning the dispatcher shares its endpoints and statistics.
+#[derive(Clone)]
+pub struct WebhookDispatcher {
+    spawner: SharedSpawner,
+    client: reqwest::Client,
+    endpoints: Arc<DashMap<String, EndpointEntry>>,
+    retry: RetryPolicy,
+    dead_letter: Option<DeadLetterFn>,
+}
+
+impl fmt::Debug for WebhookDispatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebhookDispatcher")
+            .field("endpoints", &self.endpoints.len())
+            .field("retry", &self.retry)
+            .field("dead_letter", &self.dead_letter.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl WebhookDispatcher {
+    /// Create a dispatcher running deliveries on `spawner`.
+    #[must_use]
+    pub fn new(spawner: SharedSpawner) -> Self {
+        Self {
+            spawner,
+            client: reqwest::Client::new(),
+            endpoints: Arc::new(DashMap::new()),
+            retry: RetryPolicy::default(),
+            dead_letter: None,
+        }
+    }
+
+    /// Use `client` to send deliveries, for example to configure a proxy or
+    /// TLS roots.
+    ///
+    /// The endpoint timeout overrides any timeout set on the client.
+    #[must_use]
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Set the retry policy.
+    #[must_use]
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Call `f` with every event that could not be delivered.
+    #[must_use]
+    pub fn on_dead_letter<F>(mut self, f: F) -> Self
+    where
+        F: Fn(DeadLetter) + Send + Sync + 'static,
+    {
+        self.dead_letter = Some(Arc::new(f));
+        self
+    }
+
+    /// Register an endpoint, replacing the settings of one with the same
+    /// name.
+    ///
+    /// Statistics of a replaced endpoint are kept.
+    pub fn register_endpoint(&self, name: impl Into<String>, endpoint: WebhookEndpoint) {
+        self.endpoints
+            .entry(name.into())
+            .and_modify(|entry| entry.config = endpoint.clone())
+            .or_insert_with(|| EndpointEntry {
+                config: endpoint,
+                stats: EndpointStats::default(),
+            });
+    }
+
+    /// Change the settings of a registered endpoint.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no endpoint has this name.
+    pub fn update_endpoint(
+        &self,
+        name: &str,
+        f: impl FnOnce(WebhookEndpoint) -> WebhookEndpoint,
+    ) -> TaskResult<()> {
+        let mut entry = self
+            .endpoints
+            .get_mut(name)
+            .ok_or_else(|| unknown_endpoint(name))?;
+        entry.config = f(entry.config.clone());
+        Ok(())
+    }
+
+    /// Enable or disable a registered endpoint.
+    ///
+    /// Events for a disabled endpoint are dead-lettered without being sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no endpoint has this name.
+    pub fn set_enabled(&self, name: &str, enabled: bool) -> TaskResult<()> {
+        self.update_endpoint(name, |endpoint| endpoint.with_enabled(enabled))
+    }
+
+    /// Remove an endpoint, returning its settings.
+    pub fn remove_endpoint(&self, name: &str) -> Option<WebhookEndpoint> {
+        self.endpoints.remove(name).map(|(_, entry)| entry.config)
+    }
+
+    /// Get the settings of an endpoint.
+    #[must_use]
+    pub fn endpoint(&self, name: &str) -> Option<WebhookEndpoint> {
+        self.endpoints.get(name).map(|entry| entry.config.clone())
+    }
+
+    /// Get the delivery statistics of an endpoint.
+    #[must_use]
+    pub fn stats(&self, name: &str) -> Option<EndpointStats> {
+        self.endpoints.get(name).map(|entry| entry.stats.clone())
+    }
+
+    /// Deliver `event` in the background, returning its delivery ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the endpoint is not registered or the spawner
+    /// rejects the task, for example because it is shutting down.
+    pub fn dispatch(&self, event: WebhookEvent) -> TaskResult<Uuid> {
+        if !self.endpoints.contains_key(&event.endpoint) {
+            return Err(unknown_endpoint(&event.endpoint));
+        }
+
+        let id = Uuid::new_v4();
+        let name = format!("webhook:{}:{}", event.endpoint, event.event_type);
+        self.spawner
+            .spawn_detached(name, self.clone().deliver(id, event))?;
+        Ok(id)
+    }
+
+    async fn deliver(self, id: Uuid, event: WebhookEvent) {
+        let body = event.payload.to_string();
+        let mut attempt = 0;
+
+        loop {
+            let Some(endpoint) = self.endpoint(&event.endpoint) else {
+                self.dead_letter(id, event, attempt, DeliveryError::UnknownEndpoint);
+                return;
+            };
+            if !endpoint.enabled {
+                self.dead_letter(id, event, attempt, DeliveryError::Disabled);
+                return;
+            }
+
+            attempt += 1;
+            let result = self.send(&endpoint, id, &event.event_type, &body).await;
+            let now = Utc::now();
+
+            let Err(error) = result else {
+                debug!(delivery_id = %id, endpoint = %event.endpoint, attempt, "webhook delivered");
+                self.update_stats(&event.endpoint, |stats| {
+                    stats.attempts += 1;
+                    stats.delivered += 1;
+                    stats.last_success = Some(now);
+                });
+                return;
+            };
+
+            self.update_stats(&event.endpoint, |stats| {
+                stats.attempts += 1;
+                stats.failed_attempts += 1;
+                stats.last_failure = Some(DeliveryFailure {
+                    at: now,
+                    attempt,
+                    error: error.clone(),
+                });
+            });
+
+            if !error.is_retryable() || attempt >= self.retry.max_attempts {
+                self.dead_letter(id, event, attempt, error);
+                return;
+            }
+
+            let backoff = self.retry.backoff(attempt);
+            debug!(
+                delivery_id = %id,
+                endpoint = %event.endpoint,
+                attempt,
+                %error,
+                ?backoff,
+                "webhook delivery failed, retrying"
+            );
+            tokio::time::sleep(backoff).await;
+        }
+    }
+
+    async fn send(
+        &self,
+        endpoint: &WebhookEndpoint,
+        id: Uuid,
+        event_type: &str,
+        body: &str,
+    ) -> Result<(), DeliveryError> {
+        let timestamp = Utc::now().timestamp();
+        let response = self
+            .client
+            .post(&endpoint.url)
+            .timeout(endpoint.timeout)
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(ID_HEADER, id.to_string())
+            .header(EVENT_HEADER, event_type)
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(
+                SIGNATURE_HEADER,
+                sign(&endpoint.secret, timestamp, body.as_bytes()),
+            )
+            .body(body.to_owned())
+            .send()
+            .await;
+
+        match response {
+            Ok(response) if response.status().is_success() => Ok(()),
+            Ok(response) => Err(DeliveryError::Status(response.status())),
+            Err(e) if e.is_timeout() => Err(DeliveryError::Timeout),
+            Err(e) => Err(DeliveryError::Transport(e.to_string())),
+        }
+    }
+
+    fn update_stats(&self, name: &str, f: impl FnOnce(&mut EndpointStats)) {
+        if let Some(mut entry) = self.endpoints.get_mut(name) {
+            f(&mut entry.stats);
+        }
+    }
+
+    fn dead_letter(&self, id: Uuid, event: WebhookEvent, attempts: u32, error: DeliveryError) {
+        warn!(
+            delivery_id = %id,
+            endpoint = %event.endpoint,
+            event_type = %event.event_type,
+            attempts,
+            %error,
+            "webhook dead-lettered"
+        );
+        self.update_stats(&event.endpoint, |stats| stats.dead_lettered += 1);
+
+        if let Some(callback) = &self.dead_letter {
+            callback(DeadLetter {
+                id,
+                event,
+                attempts,
+                error,
+            });
+        }
+    }
+}
+
+fn unknown_endpoint(name: &str) -> TaskError {
+    TaskError::invalid_config(format!("unknown webhook endpoint `{name}`"))
+}
+
+fn mac(secret: &str, timestamp: i64, body: &[u8]) -> Hmac<Sha256> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac
+}
+
+/// Compute the `X-Webhook-Signature` value of `body` sent at `timestamp`.
+#[must_use]
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let digest = mac(secret, timestamp, body).finalize().into_bytes();
+    let mut signature = format!("t={timestamp},v1=");
+    for byte in digest {
+        let _ = write!(signature, "{byte:02x}");
+    }
+    signature
+}
+
+/// Check an `X-Webhook-Signature` value against a received body.
+///
+/// Fails if the signature does not match or its timestamp is more than
+/// `tolerance` away from now.
+#[must_use]
+pub fn verify_signature(secret: &str, signature: &str, body: &[u8], tolerance: Duration) -> bool {
+    let mut timestamp = None;
+    let mut expected = None;
+    for part in signature.split(',') {
+        match part.trim().split_once('=') {
+            Some(("t", value)) => timestamp = value.parse::<i64>().ok(),
+            Some(("v1", value)) => expected = decode_hex(value),
+            _ => {}
+        }
+    }
+    let (Some(timestamp), Some(expected)) = (timestamp, expected) else {
+        return false;
+    };
+
+    let age = Utc::now().timestamp().abs_diff(timestamp);
+    if age > tolerance.as_secs() {
+        return false;
+    }
+    mac(secret, timestamp, body).verify_slice(&expected).is_ok()
+}
+
+/// Decodes lowercase or uppercase hex.
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{HashMap, VecDeque};
+    use std::sync::Mutex;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio::sync::mpsc;
+
+    /// A request received by the mock receiver.
+    #[derive(Debug)]
+    struct Received {
+        headers: HashMap<String, String>,
+        body: Vec<u8>,
+    }
+
+    /// HTTP server answering with scripted `(status, delay)` responses,
+    /// then 200 once the script runs out.
+    struct MockReceiver {
+        url: String,
+        received: Arc<Mutex<Vec<Received>>>,
+    }
+
+    impl MockReceiver {
+        async fn start(script: Vec<(u16, Duration)>) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+            let received = Arc::new(Mutex::new(Vec::new()));
+            let script = Arc::new(Mutex::new(VecDeque::from(script)));
+
+            let log = Arc::clone(&received);
+            tokio::spawn(async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    let (status, delay) = script
+                        .lock()
+                        .unwrap()
+                        .pop_front()
+                        .unwrap_or((200, Duration::ZERO));
+                    tokio::spawn(respond(stream, Arc::clone(&log), status, delay));
+                }
+            });
+
+            Self { url, received }
+        }
+
+        fn received(&self) -> usize {
+            self.received.lock().unwrap().len()
+        }
+    }
+
+    async fn respond(
+        mut stream: TcpStream,
+        log: Arc<Mutex<Vec<Received>>>,
+        status: u16,
+        delay: Duration,
+    ) {
+        let mut buf = Vec::new();
+        let header_end = loop {
+            let mut chunk = [0; 1024];
+            let n = stream.read(&mut chunk).await.unwrap();
+            assert!(n > 0, "connection closed before headers");
+            buf.extend_from_slice(&chunk[..n]);
+            if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                break pos + 4;
+            }
+        };
+
+        let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+        let headers: HashMap<String, String> = head
+            .lines()
+            .skip(1)
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.to_lowercase(), value.trim().to_string()))
+            .collect();
+        let length: usize = headers["content-length"].parse().unwrap();
+        let mut body = buf.split_off(header_end);
+        while body.len() < length {
+            let mut chunk = [0; 1024];
+            let n = stream.read(&mut chunk).await.unwrap();
+            body.extend_from_slice(&chunk[..n]);
+        }
+        log.lock().unwrap().push(Received { headers, body });
+
+        tokio::time::sleep(delay).await;
+        let response =
+            format!("HTTP/1.1 {status} Mock\r\ncontent-length: 0\r\nconnection: close\r\n\r\n");
+        let _ = stream.write_all(response.as_bytes()).await;
+    }
+
+    fn fast_retries() -> RetryPolicy {
+        RetryPolicy::new()
+            .with_max_attempts(3)
+            .with_initial_backoff(Duration::from_millis(10))
+    }
+
+    fn dispatcher(
+        receiver: &MockReceiver,
+    ) -> (WebhookDispatcher, mpsc::UnboundedReceiver<DeadLetter>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let dispatcher = WebhookDispatcher::new(SharedSpawner::new())
+            .with_retry_policy(fast_retries())
+            .on_dead_letter(move |dead| {
+                let _ = tx.send(dead);
+            });
+        dispatcher.register_endpoint("orders", WebhookEndpoint::new(&receiver.url, "secret"));
+        (dispatcher, rx)
+    }
+
+    async fn drain(dispatcher: &WebhookDispatcher) {
+        dispatcher
+            .spawner
+            .inner()
+            .shutdown(Duration::from_secs(5))
+            .await;
+    }
+
+    fn event() -> WebhookEvent {
+        WebhookEvent::new("orders", "order.created", serde_json::json!({ "id": 7 }))
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let now = Utc::now().timestamp();
+        let signature = sign("secret", now, b"{}");
+        assert!(signature.starts_with(&format!("t={now},v1=")));
+
+        let tolerance = Duration::from_secs(300);
+        assert!(verify_signature("secret", &signature, b"{}", tolerance));
+        assert!(!verify_signature("other", &signature, b"{}", tolerance));
+        assert!(!verify_signature(
+            "secret",
+            &signature,
+            b"{\"x\":1}",
+            tolerance
+        ));
+        assert!(!verify_signature("secret", "v1=00", b"{}", tolerance));
+
+        let stale = sign("secret", now - 600, b"{}");
+        assert!(!verify_signature("secret", &stale, b"{}", tolerance));
+    }
+
+    #[test]
+    fn test_backoff_grows_and_caps() {
+        let policy = RetryPolicy::new()
+            .with_initial_backoff(Duration::from_millis(100))
+            .with_max_backoff(Duration::from_millis(350));
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(350));
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_millis(350));
+        assert_eq!(RetryPolicy::new().with_max_attempts(0).max_attempts(), 1);
+    }
+
+    #[test]
+    fn test_retryable_errors() {
+        assert!(DeliveryError::Status(StatusCode::SERVICE_UNAVAILABLE).is_retryable());
+        assert!(DeliveryError::Timeout.is_retryable());
+        assert!(!DeliveryError::Status(StatusCode::BAD_REQUEST).is_retryable());
+        assert!(!DeliveryError::Transport("refused".into()).is_retryable());
+    }
+
+    #[tokio::test]
+    async fn test_delivers_signed_event() {
+        let receiver = MockReceiver::start(vec![]).await;
+        let (dispatcher, _dead) = dispatcher(&receiver);
+
+        let id = dispatcher.dispatch(event()).unwrap();
+        drain(&dispatcher).await;
+
+        let received = receiver.received.lock().unwrap();
+        let request = &received[0];
+        assert_eq!(request.body, b"{\"id\":7}");
+        assert_eq!(request.headers[ID_HEADER], id.to_string());
+        assert_eq!(request.headers[EVENT_HEADER], "order.created");
+        assert_eq!(request.headers["content-type"], "application/json");
+        let signature = &request.headers[SIGNATURE_HEADER];
+        let timestamp = &request.headers[TIMESTAMP_HEADER];
+        assert!(signature.starts_with(&format!("t={timestamp},")));
+        assert!(verify_signature(
+            "secret",
+            signature,
+            &request.body,
+            Duration::from_secs(60)
+        ));
+
+        let stats = dispatcher.stats("orders").unwrap();
+        assert_eq!(stats.delivered, 1);
+        assert_eq!(stats.attempts, 1);
+        assert!(stats.last_success.is_some());
+        assert!(stats.last_failure.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_retries_server_errors_until_delivered() {
+        let receiver =
+            MockReceiver::start(vec![(503, Duration::ZERO), (500, Duration::ZERO)]).await;
+        let (dispatcher, mut dead) = dispatcher(&receiver);
+
+        dispatcher.dispatch(event()).unwrap();
+        drain(&dispatcher).await;
+
+        assert_eq!(receiver.received(), 3);
+        let ids: Vec<_> = receiver
+            .received
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|request| request.headers[ID_HEADER].clone())
+            .collect();
+        assert!(ids.iter().all(|id| *id == ids[0]));
+
+        let stats = dispatcher.stats("orders").unwrap();
+        assert_eq!(stats.delivered, 1);
+        assert_eq!(stats.attempts, 3);
+        assert_eq!(stats.failed_attempts, 2);
+        let failure = stats.last_failure.unwrap();
+        assert_eq!(failure.attempt, 2);
+        assert_eq!(
+            failure.error,
+            DeliveryError::Status(StatusCode::INTERNAL_SERVER_ERROR)
+        );
+        assert!(dead.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_client_error_dead_letters_without_retry() {
+        let receiver = MockReceiver::start(vec![(422, Duration::ZERO)]).await;
+        let (dispatcher, mut dead) = dispatcher(&receiver);
+
+        let id = dispatcher.dispatch(event()).unwrap();
+        drain(&dispatcher).await;
+
+        assert_eq!(receiver.received(), 1);
+        let letter = dead.try_recv().unwrap();
+        assert_eq!(letter.id, id);
+        assert_eq!(letter.attempts, 1);
+        assert_eq!(letter.event, event());
+        assert_eq!(
+            letter.error,
+            DeliveryError::Status(StatusCode::UNPROCESSABLE_ENTITY)
+        );
+        assert_eq!(dispatcher.stats("orders").unwrap().dead_lettered, 1);
+    }
+
+    #[tokio::test]
+    async fn test_timeouts_retry_then_dead_letter() {
+        let slow = (200, Duration::from_millis(500));
+        let receiver = MockReceiver::start(vec![slow; 3]).await;
+        let (dispatcher, mut dead) = dispatcher(&receiver);
+        dispatcher
+            .update_endpoint("orders", |endpoint| {
+                endpoint.with_timeout(Duration::from_millis(50))
+            })
+            .unwrap();
+
+        dispatcher.dispatch(event()).unwrap();
+        drain(&dispatcher).await;
+
+        assert_eq!(receiver.received(), 3);
+        let letter = dead.try_recv().unwrap();
+        assert_eq!(letter.attempts, 3);
+        assert_eq!(letter.error, DeliveryError::Timeout);
+
+        let stats = dispatcher.stats("orders").unwrap();
+        assert_eq!(stats.failed_attempts, 3);
+        assert_eq!(stats.delivered, 0);
+        assert_eq!(stats.dead_lettered, 1);
+    }
+
+    #[tokio::test]
+    async fn test_endpoint_registry_updates_at_runtime() {
+        let receiver = MockReceiver::start(vec![]).await;
+        let (dispatcher, mut dead) = dispatcher(&receiver);
+
+        let error = dispatcher
+            .dispatch(WebhookEvent::new("missing", "x", serde_json::Value::Null))
+            .unwrap_err();
+        assert!(matches!(error, TaskError::InvalidConfig(_)));
+        assert!(dispatcher.set_enabled("missing", false).is_err());
+
+        // Disabled endpoints dead-letter without sending
+        dispatcher.set_enabled("orders", false).unwrap();
+        assert!(!dispatcher.endpoint("orders").unwrap().is_enabled());
+        dispatcher.dispatch(event()).unwrap();
+
+        // Re-registering keeps the statistics
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        dispatcher.register_endpoint("orders", WebhookEndpoint::new(&receiver.url, "rotated"));
+        dispatcher.dispatch(event()).unwrap();
+        drain(&dispatcher).await;
+
+        let letter = dead.try_recv().unwrap();
+        assert_eq!(letter.attempts, 0);
+        assert_eq!(letter.error, DeliveryError::Disabled);
+
+        let received = receiver.received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert!(verify_signature(
+            "rotated",
+            &received[0].headers[SIGNATURE_HEADER],
+            &received[0].body,
+            Duration::from_secs(60)
+        ));
+
+        let stats = dispatcher.stats("orders").unwrap();
+        assert_eq!(stats.dead_lettered, 1);
+        assert_eq!(stats.delivered, 1);
+        assert!(dispatcher.remove_endpoint("orders").is_some());
+        assert!(dispatcher.stats("orders").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_spawner_shutdown_waits_for_deliveries() {
+        let receiver = MockReceiver::start(vec![(200, Duration::from_millis(200))]).await;
+        let (dispatcher, _dead) = dispatcher(&receiver);
+
+        dispatcher.dispatch(event()).unwrap();
+        drain(&dispatcher).await;
+        assert_eq!(receiver.received(), 1);
+        assert_eq!(dispatcher.stats("orders").unwrap().delivered, 1);
+    }
+}
@@ -0,0 +1,322 @@
+//! Access logs for completed requests.
+//!
+//! The telemetry stage of `archimedes-middleware` emits one event per
+//! completed request under the [`ACCESS_LOG_TARGET`] target, carrying the
+//! request and response sizes and status it captured. [`AccessLogLayer`]
+//! renders those events as access log lines in one of three formats:
+//!
+//! - [`AccessLogFormat::Json`] - one JSON object per line
+//! - [`AccessLogFormat::Common`] - the Common Log Format of Apache and nginx
+//! - [`AccessLogFormat::Combined`] - Common Log Format plus referer and
+//!   user agent
+//!
+//! The CLF formats end each line with the request duration in milliseconds,
+//! which log parsers treat as an extra trailing field:
+//!
+//! ```text
+//! - - user:user-123 [15/Oct/2026:09:30:00 +0000] "GET /users/123 HTTP/1.1" 200 12 "-" "curl/8.0" 4.521
+//! ```
+//!
+//! The remote host and `ident` are always `-`; the user is the caller's
+//! identity as in logs, or `-` for anonymous callers.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use archimedes_telemetry::{AccessLogFormat, LogConfig, init_logging};
+//!
+//! let config = LogConfig::production().access_log(AccessLogFormat::Combined);
+//! let _provider = init_logging(&config)?;
+//! ```
+
+use crate::error::TelemetryError;
+use chrono::{DateTime, Utc};
+use std::fmt::{self, Write as _};
+use std::io::Write as _;
+use std::str::FromStr;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::Layer;
+
+/// Target of the access log events emitted by the telemetry stage.
+pub const ACCESS_LOG_TARGET: &str = "archimedes::access";
+
+/// Access log line format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AccessLogFormat {
+    /// One JSON object per line.
+    #[default]
+    Json,
+    /// Combined Log Format: Common Log Format plus referer and user agent.
+    Combined,
+    /// Common Log Format.
+    Common,
+}
+
+impl AccessLogFormat {
+    /// Returns the format name.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Combined => "combined",
+            Self::Common => "common",
+        }
+    }
+}
+
+impl FromStr for AccessLogFormat {
+    type Err = TelemetryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "combined" => Ok(Self::Combined),
+            "common" | "clf" => Ok(Self::Common),
+            other => Err(TelemetryError::InvalidConfig(format!(
+                "Unknown access log format: {other}"
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for AccessLogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Layer writing one access log line per access log event.
+///
+/// Events of other targets are ignored.
+pub struct AccessLogLayer<W> {
+    format: AccessLogFormat,
+    writer: W,
+}
+
+impl<W> AccessLogLayer<W>
+where
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    /// Creates a layer writing lines in `format` to `writer`.
+    #[must_use]
+    pub const fn new(format: AccessLogFormat, writer: W) -> Self {
+        Self { format, writer }
+    }
+}
+
+impl<S, W> Layer<S> for AccessLogLayer<W>
+where
+    S: Subscriber,
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if event.metadata().target() != ACCESS_LOG_TARGET {
+            return;
+        }
+
+        let mut entry = AccessLogEntry::default();
+        event.record(&mut entry);
+        let line = entry.render(self.format, Utc::now());
+        let _ = writeln!(self.writer.make_writer(), "{line}");
+    }
+}
+
+/// Fields of an access log event.
+#[derive(Debug, Default)]
+struct AccessLogEntry {
+    method: Option<String>,
+    path: Option<String>,
+    protocol: Option<String>,
+    status: Option<u64>,
+    bytes: Option<u64>,
+    request_bytes: Option<u64>,
+    duration_ms: Option<f64>,
+    caller: Option<String>,
+    referer: Option<String>,
+    user_agent: Option<String>,
+    request_id: Option<String>,
+    operation_id: Option<String>,
+}
+
+impl AccessLogEntry {
+    /// Renders the entry as a line in `format`, without the newline.
+    fn render(&self, format: AccessLogFormat, time: DateTime<Utc>) -> String {
+        match format {
+            AccessLogFormat::Json => self.render_json(time),
+            AccessLogFormat::Combined => self.render_clf(time, true),
+            AccessLogFormat::Common => self.render_clf(time, false),
+        }
+    }
+
+    fn render_json(&self, time: DateTime<Utc>) -> String {
+        serde_json::json!({
+            "timestamp": time.to_rfc3339(),
+            "method": self.method,
+            "path": self.path,
+            "protocol": self.protocol,
+            "status": self.status,
+            "bytes": self.bytes,
+            "request_bytes": self.request_bytes,
+            "duration_ms": self.duration_ms,
+            "caller": self.caller,
+            "referer": self.referer,
+            "user_agent": self.user_agent,
+            "request_id": self.request_id,
+            "operation_id": self.operation_id,
+        })
+        .to_string()
+    }
+
+    fn render_clf(&self, time: DateTime<Utc>, combined: bool) -> String {
+        let mut line = format!(
+            "- - {} [{}] \"{} {} {}\" {} {}",
+            self.caller.as_deref().unwrap_or("-"),
+            time.format("%d/%b/%Y:%H:%M:%S %z"),
+            self.method.as_deref().unwrap_or("-"),
+            self.path.as_deref().unwrap_or("-"),
+            self.protocol.as_deref().unwrap_or("-"),
+            self.status
+                .map_or_else(|| "-".to_string(), |s| s.to_string()),
+            self.bytes
+                .map_or_else(|| "-".to_string(), |b| b.to_string()),
+        );
+        if combined {
+            let _ = write!(
+                line,
+                " \"{}\" \"{}\"",
+                quoted(self.referer.as_deref()),
+                quoted(self.user_agent.as_deref())
+            );
+        }
+        if let Some(duration_ms) = self.duration_ms {
+            let _ = write!(line, " {duration_ms:.3}");
+        }
+        line
+    }
+}
+
+/// Escapes a header value for a quoted CLF field, `-` when absent.
+fn quoted(value: Option<&str>) -> String {
+    value.map_or_else(
+        || "-".to_string(),
+        |value| value.replace('\\', "\\\\").replace('"', "\\\""),
+    )
+}
+
+impl Visit for AccessLogEntry {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        let slot = match field.name() {
+            "method" => &mut self.method,
+            "path" => &mut self.path,
+            "protocol" => &mut self.protocol,
+            "caller" => &mut self.caller,
+            "referer" => &mut self.referer,
+            "user_agent" => &mut self.user_agent,
+            "request_id" => &mut self.request_id,
+            "operation_id" => &mut self.operation_id,
+            _ => return,
+        };
+        *slot = Some(value.to_string());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        match field.name() {
+            "status" => self.status = Some(value),
+            "bytes" => self.bytes = Some(value),
+            "request_bytes" => self.request_bytes = Some(value),
+            _ => {}
+        }
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        if let Ok(value) = u64::try_from(value) {
+            self.record_u64(field, value);
+        }
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        if field.name() == "duration_ms" {
+            self.duration_ms = Some(value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        // Display fields (`%value`) are recorded through their Debug impl
+        self.record_str(field, &format!("{value:?}"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn sample_entry() -> AccessLogEntry {
+        AccessLogEntry {
+            method: Some("GET".to_string()),
+            path: Some("/users/123".to_string()),
+            protocol: Some("HTTP/1.1".to_string()),
+            status: Some(200),
+            bytes: Some(12),
+            request_bytes: Some(0),
+            duration_ms: Some(4.5),
+            caller: Some("user:user-123".to_string()),
+            referer: None,
+            user_agent: Some("curl/8.0 \"test\"".to_string()),
+            request_id: Some("req-1".to_string()),
+            operation_id: Some("getUser".to_string()),
+        }
+    }
+
+    fn sample_time() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, 15, 9, 30, 0).unwrap()
+    }
+
+    #[test]
+    fn test_format_parsing() {
+        assert_eq!(
+            "Combined".parse::<AccessLogFormat>().unwrap(),
+            AccessLogFormat::Combined
+        );
+        assert_eq!(
+            "clf".parse::<AccessLogFormat>().unwrap(),
+            AccessLogFormat::Common
+        );
+        assert!("w3c".parse::<AccessLogFormat>().is_err());
+        assert_eq!(AccessLogFormat::default(), AccessLogFormat::Json);
+    }
+
+    #[test]
+    fn test_common_line() {
+        let line = sample_entry().render(AccessLogFormat::Common, sample_time());
+        assert_eq!(
+            line,
+            "- - user:user-123 [15/Oct/2026:09:30:00 +0000] \"GET /users/123 HTTP/1.1\" 200 12 4.500"
+        );
+    }
+
+    #[test]
+    fn test_combined_line_quotes_headers() {
+        let line = sample_entry().render(AccessLogFormat::Combined, sample_time());
+        assert!(line.ends_with(" 200 12 \"-\" \"curl/8.0 \\\"test\\\"\" 4.500"));
+    }
+
+    #[test]
+    fn test_missing_fields_render_as_dash() {
+        let line = AccessLogEntry::default().render(AccessLogFormat::Common, sample_time());
+        assert_eq!(line, "- - - [15/Oct/2026:09:30:00 +0000] \"- - -\" - -");
+    }
+
+    #[test]
+    fn test_json_line() {
+        let line = sample_entry().render(AccessLogFormat::Json, sample_time());
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(value["status"], 200);
+        assert_eq!(value["caller"], "user:user-123");
+        assert_eq!(value["referer"], serde_json::Value::Null);
+        assert_eq!(value["timestamp"], "2026-10-15T09:30:00+00:00");
+    }
+}
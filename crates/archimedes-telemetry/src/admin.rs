@@ -0,0 +1,779 @@
+//! The admin API.
+//!
+//! [`AdminApi`] is a small router for runtime introspection and control,
+//! served under `/-/` on the metrics listener (see
+//! [`MetricsEndpoint::with_admin`](crate::MetricsEndpoint::with_admin)). It
+//! never touches the contract pipeline: requests are not routed, validated
+//! or authorized against the contract, and the server's handlers never see
+//! them.
+//!
+//! | Endpoint | Answer |
+//! |---|---|
+//! | `GET /-/config` | `{"config": …}`, the effective configuration with secrets redacted |
+//! | `GET /-/routes` | `{"routes": [{"method", "path", "operation", "internal"}]}` |
+//! | `GET /-/tasks` | `{"tasks": …}`, the spawner and scheduler state |
+//! | `POST /-/log-level` | Body `{"target": "archimedes_server", "level": "debug"}`; `target` may be omitted to change the default level |
+//! | `POST /-/cache/authz/clear` | Clears the authorization decision cache |
+//! | `POST /-/reload/contract` | Reloads the contract artifact |
+//! | `POST /-/reload/policy` | Reloads the policy bundle |
+//!
+//! Mutations answer `{"status": "ok", "action": …}`, and every error
+//! answers `{"error": {"code": …, "message": …}}`. An endpoint whose hook
+//! was not provided answers `501 Not Implemented`.
+//!
+//! # Access
+//!
+//! The API is disabled unless [`AdminConfig::enabled`] is set; while
+//! disabled, `/-/` paths are unknown to the listener. Credentials are
+//! checked with the same bearer token and client certificate mechanisms as
+//! the metrics scrape (see [`MetricsAuthConfig`]), but configured
+//! separately so scrape credentials do not grant control. Reads are open
+//! when no credentials are configured; mutations are refused with
+//! `403 Forbidden` until they are.
+//!
+//! Every mutation, including a refused one, is recorded on the
+//! [`AUDIT_TARGET`] log target with the action, the caller and the outcome.
+//!
+//! ```toml
+//! [server.admin]
+//! enabled = true
+//!
+//! [server.admin.auth]
+//! bearer_token = "change-me"
+//! ```
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use archimedes_telemetry::admin::{AdminApi, AdminRoute};
+//!
+//! let admin = AdminApi::new(config.server.admin.clone())
+//!     .effective_config(move || serde_json::to_value(&config).unwrap_or_default())
+//!     .routes(move || routes.clone())
+//!     .clear_authz_cache(move || authorizer.clear_cache())
+//!     .reload_policy(move || evaluator.reload().map_err(|e| e.to_string()))
+//!     .tasks(move || serde_json::json!({
+//!         "spawner": spawner.state_json(),
+//!         "scheduler": scheduler.state_json(),
+//!     }));
+//!
+//! init_metrics_with_admin(&config.telemetry.metrics, admin)?;
+//! ```
+
+use std::fmt;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use http::{header, HeaderMap, HeaderValue, Method, Request, Response, StatusCode};
+use http_body_util::Full;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::endpoint::{client_identities, AuthOutcome, MetricsAuthConfig};
+use crate::logging;
+
+/// Path prefix of the admin API.
+pub const ADMIN_PREFIX: &str = "/-/";
+
+/// Log target of the audit records written for mutations.
+pub const AUDIT_TARGET: &str = "archimedes::admin::audit";
+
+/// Largest request body the admin API reads.
+pub const MAX_ADMIN_BODY: usize = 64 * 1024;
+
+/// The `[server.admin]` configuration section.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AdminConfig {
+    /// Serve the admin API. Off by default.
+    pub enabled: bool,
+
+    /// Credentials required by the admin API. Mutations are refused until
+    /// a bearer token or client certificate is required.
+    pub auth: MetricsAuthConfig,
+}
+
+/// A route listed by `GET /-/routes`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdminRoute {
+    /// HTTP method.
+    pub method: String,
+    /// Path pattern.
+    pub path: String,
+    /// Contract operation ID, absent for internal routes.
+    pub operation: Option<String>,
+    /// Whether the route is served outside the contract.
+    pub internal: bool,
+}
+
+impl AdminRoute {
+    /// Creates the entry for a contract operation.
+    #[must_use]
+    pub fn operation(
+        method: impl fmt::Display,
+        path: impl Into<String>,
+        operation: impl Into<String>,
+    ) -> Self {
+        Self {
+            method: method.to_string(),
+            path: path.into(),
+            operation: Some(operation.into()),
+            internal: false,
+        }
+    }
+
+    /// Creates the entry for an internal route.
+    #[must_use]
+    pub fn internal(method: impl fmt::Display, path: impl Into<String>) -> Self {
+        Self {
+            method: method.to_string(),
+            path: path.into(),
+            operation: None,
+            internal: true,
+        }
+    }
+}
+
+type Snapshot = Arc<dyn Fn() -> Value + Send + Sync>;
+type RouteList = Arc<dyn Fn() -> Vec<AdminRoute> + Send + Sync>;
+type Action = Arc<dyn Fn() -> Result<(), String> + Send + Sync>;
+type SetLogLevel = Arc<dyn Fn(Option<&str>, &str) -> Result<String, String> + Send + Sync>;
+
+/// Body of `POST /-/log-level`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LogLevelRequest {
+    target: Option<String>,
+    level: String,
+}
+
+/// The admin API router.
+///
+/// Each endpoint is backed by a hook supplied by the application, so the
+/// API does not depend on the crates it controls.
+#[derive(Clone)]
+pub struct AdminApi {
+    config: AdminConfig,
+    effective_config: Option<Snapshot>,
+    routes: Option<RouteList>,
+    tasks: Option<Snapshot>,
+    log_level: SetLogLevel,
+    clear_authz_cache: Option<Action>,
+    reload_contract: Option<Action>,
+    reload_policy: Option<Action>,
+}
+
+impl fmt::Debug for AdminApi {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AdminApi")
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AdminApi {
+    /// Creates the API with no hooks.
+    ///
+    /// `POST /-/log-level` changes the filter installed by
+    /// [`init_logging`](crate::init_logging) until replaced with
+    /// [`log_level`](Self::log_level).
+    #[must_use]
+    pub fn new(config: AdminConfig) -> Self {
+        Self {
+            config,
+            effective_config: None,
+            routes: None,
+            tasks: None,
+            log_level: Arc::new(|target, level| {
+                logging::set_log_level(target, level).map_err(|e| e.to_string())
+            }),
+            clear_authz_cache: None,
+            reload_contract: None,
+            reload_policy: None,
+        }
+    }
+
+    /// Serves `GET /-/config` from `snapshot`.
+    ///
+    /// String values under keys naming a secret (`token`, `secret`,
+    /// `password`, `credential`, `private_key`) are redacted even if the
+    /// snapshot left them in.
+    #[must_use]
+    pub fn effective_config(
+        mut self,
+        snapshot: impl Fn() -> Value + Send + Sync + 'static,
+    ) -> Self {
+        self.effective_config = Some(Arc::new(snapshot));
+        self
+    }
+
+    /// Serves `GET /-/routes` from `routes`.
+    #[must_use]
+    pub fn routes(mut self, routes: impl Fn() -> Vec<AdminRoute> + Send + Sync + 'static) -> Self {
+        self.routes = Some(Arc::new(routes));
+        self
+    }
+
+    /// Serves `GET /-/tasks` from `snapshot`.
+    #[must_use]
+    pub fn tasks(mut self, snapshot: impl Fn() -> Value + Send + Sync + 'static) -> Self {
+        self.tasks = Some(Arc::new(snapshot));
+        self
+    }
+
+    /// Replaces how `POST /-/log-level` applies a level. `set` receives the
+    /// target and level and returns the new filter.
+    #[must_use]
+    pub fn log_level(
+        mut self,
+        set: impl Fn(Option<&str>, &str) -> Result<String, String> + Send + Sync + 'static,
+    ) -> Self {
+        self.log_level = Arc::new(set);
+        self
+    }
+
+    /// Serves `POST /-/cache/authz/clear` with `clear`.
+    #[must_use]
+    pub fn clear_authz_cache(mut self, clear: impl Fn() + Send + Sync + 'static) -> Self {
+        self.clear_authz_cache = Some(Arc::new(move || {
+            clear();
+            Ok(())
+        }));
+        self
+    }
+
+    /// Serves `POST /-/reload/contract` with `reload`.
+    #[must_use]
+    pub fn reload_contract(
+        mut self,
+        reload: impl Fn() -> Result<(), String> + Send + Sync + 'static,
+    ) -> Self {
+        self.reload_contract = Some(Arc::new(reload));
+        self
+    }
+
+    /// Serves `POST /-/reload/policy` with `reload`.
+    #[must_use]
+    pub fn reload_policy(
+        mut self,
+        reload: impl Fn() -> Result<(), String> + Send + Sync + 'static,
+    ) -> Self {
+        self.reload_policy = Some(Arc::new(reload));
+        self
+    }
+
+    /// Returns `true` if the API is enabled.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Returns `true` if the API answers `path`.
+    #[must_use]
+    pub fn handles(&self, path: &str) -> bool {
+        self.config.enabled && path.starts_with(ADMIN_PREFIX)
+    }
+
+    /// Answers a request with its body already read.
+    #[must_use]
+    pub fn handle(&self, request: &Request<Bytes>) -> Response<Full<Bytes>> {
+        if !self.config.enabled {
+            return error(StatusCode::NOT_FOUND, "not_found", "not found");
+        }
+        let path = request.uri().path();
+        let headers = request.headers();
+        let Some(action) = action_name(path) else {
+            return error(StatusCode::NOT_FOUND, "not_found", "not found");
+        };
+        let mutation = matches!(
+            action,
+            "log_level" | "clear_authz_cache" | "reload_contract" | "reload_policy"
+        );
+        let caller = self.caller(headers);
+
+        match self.config.auth.check(headers) {
+            AuthOutcome::Allowed => {}
+            AuthOutcome::Unauthenticated => {
+                if mutation {
+                    audit(action, &caller, "unauthenticated");
+                }
+                let mut response = error(StatusCode::UNAUTHORIZED, "unauthorized", "unauthorized");
+                response.headers_mut().insert(
+                    header::WWW_AUTHENTICATE,
+                    HeaderValue::from_static("Bearer realm=\"admin\""),
+                );
+                return response;
+            }
+            AuthOutcome::Forbidden => {
+                if mutation {
+                    audit(action, &caller, "forbidden");
+                }
+                return error(StatusCode::FORBIDDEN, "forbidden", "forbidden");
+            }
+        }
+
+        let allowed = if mutation { Method::POST } else { Method::GET };
+        if request.method() != allowed {
+            let mut response = error(
+                StatusCode::METHOD_NOT_ALLOWED,
+                "method_not_allowed",
+                "method not allowed",
+            );
+            response
+                .headers_mut()
+                .insert(header::ALLOW, HeaderValue::from_static(allowed.as_str()));
+            return response;
+        }
+
+        if mutation && !self.config.auth.is_enabled() {
+            audit(action, &caller, "auth_not_configured");
+            return error(
+                StatusCode::FORBIDDEN,
+                "admin_auth_required",
+                "mutations require admin credentials to be configured",
+            );
+        }
+
+        match action {
+            "config" => snapshot(self.effective_config.as_ref(), "config", |mut config| {
+                redact(&mut config);
+                config
+            }),
+            "routes" => match &self.routes {
+                Some(routes) => json_response(StatusCode::OK, &json!({ "routes": routes() })),
+                None => not_configured(),
+            },
+            "tasks" => snapshot(self.tasks.as_ref(), "tasks", |tasks| tasks),
+            "log_level" => self.set_log_level(request.body(), &caller),
+            "clear_authz_cache" => run(self.clear_authz_cache.as_ref(), action, &caller),
+            "reload_contract" => run(self.reload_contract.as_ref(), action, &caller),
+            _ => run(self.reload_policy.as_ref(), action, &caller),
+        }
+    }
+
+    fn set_log_level(&self, body: &Bytes, caller: &str) -> Response<Full<Bytes>> {
+        let request: LogLevelRequest = match serde_json::from_slice(body) {
+            Ok(request) => request,
+            Err(e) => {
+                audit("log_level", caller, "invalid_body");
+                return error(StatusCode::BAD_REQUEST, "invalid_body", &e.to_string());
+            }
+        };
+        match (self.log_level)(request.target.as_deref(), &request.level) {
+            Ok(filter) => {
+                tracing::info!(
+                    target: AUDIT_TARGET,
+                    action = "log_level",
+                    caller,
+                    outcome = "ok",
+                    log_target = request.target.as_deref().unwrap_or("*"),
+                    level = %request.level,
+                    "admin action"
+                );
+                json_response(
+                    StatusCode::OK,
+                    &json!({ "status": "ok", "action": "log_level", "filter": filter }),
+                )
+            }
+            Err(message) => {
+                audit("log_level", caller, "failed");
+                error(StatusCode::BAD_REQUEST, "invalid_log_level", &message)
+            }
+        }
+    }
+
+    /// Identifies the caller for the audit log, without revealing tokens.
+    fn caller(&self, headers: &HeaderMap) -> String {
+        if let Some(identity) = headers
+            .get(self.config.auth.client_cert_header.as_str())
+            .and_then(|value| value.to_str().ok())
+            .and_then(|cert| client_identities(cert).next())
+        {
+            return identity;
+        }
+        if headers.contains_key(header::AUTHORIZATION) {
+            "bearer".to_string()
+        } else {
+            "anonymous".to_string()
+        }
+    }
+}
+
+/// Maps a path to the action it names.
+fn action_name(path: &str) -> Option<&'static str> {
+    let action = match path.strip_prefix(ADMIN_PREFIX)? {
+        "config" => "config",
+        "routes" => "routes",
+        "tasks" => "tasks",
+        "log-level" => "log_level",
+        "cache/authz/clear" => "clear_authz_cache",
+        "reload/contract" => "reload_contract",
+        "reload/policy" => "reload_policy",
+        _ => return None,
+    };
+    Some(action)
+}
+
+fn snapshot(
+    hook: Option<&Snapshot>,
+    key: &str,
+    finish: impl FnOnce(Value) -> Value,
+) -> Response<Full<Bytes>> {
+    match hook {
+        Some(hook) => {
+            let mut body = serde_json::Map::new();
+            body.insert(key.to_string(), finish(hook()));
+            json_response(StatusCode::OK, &Value::Object(body))
+        }
+        None => not_configured(),
+    }
+}
+
+fn run(hook: Option<&Action>, action: &str, caller: &str) -> Response<Full<Bytes>> {
+    let Some(hook) = hook else {
+        audit(action, caller, "not_configured");
+        return not_configured();
+    };
+    match hook() {
+        Ok(()) => {
+            audit(action, caller, "ok");
+            json_response(StatusCode::OK, &json!({ "status": "ok", "action": action }))
+        }
+        Err(message) => {
+            audit(action, caller, "failed");
+            tracing::warn!(action, error = %message, "admin action failed");
+            error(StatusCode::INTERNAL_SERVER_ERROR, "action_failed", &message)
+        }
+    }
+}
+
+fn audit(action: &str, caller: &str, outcome: &str) {
+    if outcome == "ok" {
+        tracing::info!(target: AUDIT_TARGET, action, caller, outcome, "admin action");
+    } else {
+        tracing::warn!(target: AUDIT_TARGET, action, caller, outcome, "admin action");
+    }
+}
+
+/// Replaces string values under keys that name a secret.
+fn redact(value: &mut Value) {
+    const SECRET_KEYS: &[&str] = &["token", "secret", "password", "credential", "private_key"];
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                let key = key.to_ascii_lowercase();
+                if value.is_string() && SECRET_KEYS.iter().any(|secret| key.contains(secret)) {
+                    *value = Value::String(archimedes_core::secret::REDACTED.to_string());
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+fn not_configured() -> Response<Full<Bytes>> {
+    error(
+        StatusCode::NOT_IMPLEMENTED,
+        "not_configured",
+        "this endpoint is not configured",
+    )
+}
+
+pub(crate) fn error(status: StatusCode, code: &str, message: &str) -> Response<Full<Bytes>> {
+    json_response(
+        status,
+        &json!({ "error": { "code": code, "message": message } }),
+    )
+}
+
+fn json_response(status: StatusCode, body: &Value) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(Bytes::from(body.to_string())));
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::endpoint::DEFAULT_CLIENT_CERT_HEADER;
+    use archimedes_core::Secret;
+    use http_body_util::BodyExt;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    const TOKEN: &str = "admin-token";
+
+    fn enabled() -> AdminConfig {
+        AdminConfig {
+            enabled: true,
+            auth: MetricsAuthConfig {
+                bearer_token: Some(Secret::new(TOKEN)),
+                ..MetricsAuthConfig::default()
+            },
+        }
+    }
+
+    fn request(method: Method, path: &str, token: Option<&str>, body: &str) -> Request<Bytes> {
+        let mut builder = Request::builder().method(method).uri(path);
+        if let Some(token) = token {
+            builder = builder.header("authorization", format!("Bearer {token}"));
+        }
+        builder.body(Bytes::from(body.to_string())).unwrap()
+    }
+
+    fn get(path: &str) -> Request<Bytes> {
+        request(Method::GET, path, Some(TOKEN), "")
+    }
+
+    fn post(path: &str, body: &str) -> Request<Bytes> {
+        request(Method::POST, path, Some(TOKEN), body)
+    }
+
+    async fn json(response: Response<Full<Bytes>>) -> Value {
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    fn counting(counter: &Arc<AtomicUsize>) -> impl Fn() -> Result<(), String> {
+        let counter = counter.clone();
+        move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_disabled_by_default() {
+        let api = AdminApi::new(AdminConfig::default())
+            .routes(Vec::new)
+            .reload_policy(|| Ok(()));
+        assert!(!api.is_enabled());
+        assert!(!api.handles("/-/routes"));
+
+        let response = api.handle(&get("/-/routes"));
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let response = api.handle(&post("/-/reload/policy", ""));
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_config_is_redacted() {
+        let api = AdminApi::new(enabled()).effective_config(|| {
+            json!({
+                "server": { "http_addr": "0.0.0.0:8080" },
+                "telemetry": { "metrics": { "auth": { "bearer_token": "[REDACTED]" } } },
+                "upstream": { "api_token": "plain", "password": "hunter2" },
+            })
+        });
+
+        let response = api.handle(&get("/-/config"));
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json(response).await;
+        assert_eq!(body["config"]["server"]["http_addr"], "0.0.0.0:8080");
+        assert_eq!(body["config"]["upstream"]["api_token"], "[REDACTED]");
+        assert_eq!(body["config"]["upstream"]["password"], "[REDACTED]");
+    }
+
+    #[tokio::test]
+    async fn test_routes() {
+        let api = AdminApi::new(enabled()).routes(|| {
+            vec![
+                AdminRoute::operation(Method::GET, "/users/{userId}", "getUser"),
+                AdminRoute::internal(Method::GET, "/_archimedes/health"),
+            ]
+        });
+
+        let body = json(api.handle(&get("/-/routes"))).await;
+        assert_eq!(
+            body,
+            json!({ "routes": [
+                { "method": "GET", "path": "/users/{userId}", "operation": "getUser", "internal": false },
+                { "method": "GET", "path": "/_archimedes/health", "operation": null, "internal": true },
+            ]})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tasks() {
+        let api = AdminApi::new(enabled())
+            .tasks(|| json!({ "spawner": { "running": 1 }, "scheduler": { "jobs": [] } }));
+
+        let body = json(api.handle(&get("/-/tasks"))).await;
+        assert_eq!(body["tasks"]["spawner"]["running"], 1);
+        assert_eq!(body["tasks"]["scheduler"]["jobs"], json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_log_level() {
+        let applied = Arc::new(Mutex::new(Vec::new()));
+        let recorded = applied.clone();
+        let api = AdminApi::new(enabled()).log_level(move |target, level| {
+            recorded
+                .lock()
+                .unwrap()
+                .push((target.map(ToString::to_string), level.to_string()));
+            Ok(format!("info,{}={level}", target.unwrap_or("*")))
+        });
+
+        let response = api.handle(&post(
+            "/-/log-level",
+            r#"{"target": "archimedes_server", "level": "debug"}"#,
+        ));
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            json(response).await,
+            json!({ "status": "ok", "action": "log_level", "filter": "info,archimedes_server=debug" })
+        );
+        assert_eq!(
+            applied.lock().unwrap()[0],
+            (Some("archimedes_server".to_string()), "debug".to_string())
+        );
+
+        let response = api.handle(&post("/-/log-level", r#"{"level": 3}"#));
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(json(response).await["error"]["code"], "invalid_body");
+    }
+
+    #[tokio::test]
+    async fn test_log_level_rejects_unknown_level() {
+        let api = AdminApi::new(enabled())
+            .log_level(|_, level| Err(format!("unknown log level: {level}")));
+
+        let response = api.handle(&post("/-/log-level", r#"{"level": "loud"}"#));
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(json(response).await["error"]["code"], "invalid_log_level");
+    }
+
+    #[tokio::test]
+    async fn test_clear_authz_cache() {
+        let cleared = Arc::new(AtomicUsize::new(0));
+        let counter = cleared.clone();
+        let api = AdminApi::new(enabled()).clear_authz_cache(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let response = api.handle(&post("/-/cache/authz/clear", ""));
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            json(response).await,
+            json!({ "status": "ok", "action": "clear_authz_cache" })
+        );
+        assert_eq!(cleared.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_reload_contract() {
+        let reloads = Arc::new(AtomicUsize::new(0));
+        let api = AdminApi::new(enabled()).reload_contract(counting(&reloads));
+
+        let response = api.handle(&post("/-/reload/contract", ""));
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(reloads.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_reload_policy_failure() {
+        let api = AdminApi::new(enabled()).reload_policy(|| Err("bundle does not compile".into()));
+
+        let response = api.handle(&post("/-/reload/policy", ""));
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(
+            json(response).await,
+            json!({ "error": { "code": "action_failed", "message": "bundle does not compile" } })
+        );
+    }
+
+    #[test]
+    fn test_auth_rejected() {
+        let reloads = Arc::new(AtomicUsize::new(0));
+        let api = AdminApi::new(enabled())
+            .routes(Vec::new)
+            .reload_policy(counting(&reloads));
+
+        for token in [None, Some("wrong-token")] {
+            let response = api.handle(&request(Method::POST, "/-/reload/policy", token, ""));
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+            assert!(response.headers().contains_key(header::WWW_AUTHENTICATE));
+
+            let response = api.handle(&request(Method::GET, "/-/routes", token, ""));
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+        assert_eq!(reloads.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_client_cert_rejected() {
+        let config = AdminConfig {
+            enabled: true,
+            auth: MetricsAuthConfig {
+                require_client_cert: true,
+                allowed_client_subjects: vec!["CN=operator".to_string()],
+                ..MetricsAuthConfig::default()
+            },
+        };
+        let api = AdminApi::new(config).clear_authz_cache(|| {});
+
+        let mut denied = post("/-/cache/authz/clear", "");
+        denied.headers_mut().insert(
+            DEFAULT_CLIENT_CERT_HEADER,
+            HeaderValue::from_static("Subject=\"CN=intruder\""),
+        );
+        assert_eq!(api.handle(&denied).status(), StatusCode::FORBIDDEN);
+
+        let mut allowed = post("/-/cache/authz/clear", "");
+        allowed.headers_mut().insert(
+            DEFAULT_CLIENT_CERT_HEADER,
+            HeaderValue::from_static("Hash=abc;Subject=\"CN=operator\""),
+        );
+        assert_eq!(api.handle(&allowed).status(), StatusCode::OK);
+        assert_eq!(api.caller(allowed.headers()), "CN=operator");
+    }
+
+    #[tokio::test]
+    async fn test_mutations_require_configured_auth() {
+        let reloads = Arc::new(AtomicUsize::new(0));
+        let config = AdminConfig {
+            enabled: true,
+            auth: MetricsAuthConfig::default(),
+        };
+        let api = AdminApi::new(config)
+            .routes(Vec::new)
+            .reload_contract(counting(&reloads));
+
+        let response = api.handle(&request(Method::GET, "/-/routes", None, ""));
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = api.handle(&request(Method::POST, "/-/reload/contract", None, ""));
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(json(response).await["error"]["code"], "admin_auth_required");
+        assert_eq!(reloads.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_path_method_and_missing_hook() {
+        let api = AdminApi::new(enabled());
+
+        assert_eq!(
+            api.handle(&get("/-/unknown")).status(),
+            StatusCode::NOT_FOUND
+        );
+
+        let response = api.handle(&post("/-/routes", ""));
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(response.headers()[header::ALLOW], "GET");
+
+        let response = api.handle(&get("/-/reload/policy"));
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+        let response = api.handle(&post("/-/reload/policy", ""));
+        assert_eq!(response.status(), StatusCode::NOT_IMPLEMENTED);
+        assert_eq!(json(response).await["error"]["code"], "not_configured");
+    }
+}
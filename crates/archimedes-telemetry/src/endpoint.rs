@@ -0,0 +1,771 @@
+//! The metrics listener.
+//!
+//! [`MetricsEndpoint`] serves `/metrics` and `/health` on the metrics
+//! address, and the [admin API](crate::admin) under `/-/` when one is
+//! attached. [`init_metrics`](crate::init_metrics) starts it when called
+//! inside a Tokio runtime.
+//!
+//! # Formats
+//!
+//! The scrape format is negotiated from the `Accept` header (see
+//! [`ExpositionFormat::negotiate`]):
+//!
+//! - Prometheus text 0.0.4 (`text/plain; version=0.0.4`), the default.
+//!   Exemplars are not part of this format and are stripped.
+//! - OpenMetrics 1.0.0 (`application/openmetrics-text; version=1.0.0`).
+//!   Counter families are named without their `_total` suffix, `untyped`
+//!   becomes `unknown`, exemplars (`value # {labels} value`) are kept and the
+//!   output ends with `# EOF`.
+//!
+//! # Authentication
+//!
+//! With a [`MetricsAuthConfig`], every path except `/health` requires the
+//! configured credentials, so orchestrator health probes keep working
+//! without them:
+//!
+//! - A bearer token. A missing or wrong token is answered with
+//!   `401 Unauthorized`. Tokens are compared in constant time.
+//! - A client certificate. Archimedes does not terminate TLS, so the
+//!   certificate is checked by the TLS-terminating proxy and forwarded in
+//!   the `x-forwarded-client-cert` header. A missing or disallowed
+//!   certificate is answered with `403 Forbidden`. Only enable this when
+//!   the metrics address is reachable through that proxy alone.
+//!
+//! ```toml
+//! [telemetry.metrics.auth]
+//! bearer_token = "change-me"
+//! require_client_cert = true
+//! allowed_client_subjects = ["CN=prometheus"]
+//! ```
+//!
+//! Keep the token out of the file with
+//! `ARCHIMEDES__TELEMETRY__METRICS__AUTH__BEARER_TOKEN`.
+
+use std::collections::HashSet;
+use std::convert::Infallible;
+use std::fmt;
+use std::sync::Arc;
+
+use archimedes_core::Secret;
+use bytes::Bytes;
+use http::{header, HeaderMap, HeaderValue, Method, Request, Response, StatusCode};
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper_util::rt::TokioIo;
+use serde::{Deserialize, Serialize};
+use tokio::net::TcpListener;
+
+use crate::admin::{self, AdminApi, MAX_ADMIN_BODY};
+
+/// Content type of the Prometheus text format.
+pub const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Content type of the OpenMetrics text format.
+pub const OPENMETRICS_CONTENT_TYPE: &str =
+    "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// Header carrying the client certificate verified by the TLS proxy.
+pub const DEFAULT_CLIENT_CERT_HEADER: &str = "x-forwarded-client-cert";
+
+/// A metrics exposition format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExpositionFormat {
+    /// Prometheus text format 0.0.4.
+    #[default]
+    Prometheus,
+    /// OpenMetrics text format 1.0.0.
+    OpenMetrics,
+}
+
+impl ExpositionFormat {
+    /// Picks the format for an `Accept` header.
+    ///
+    /// OpenMetrics is chosen only when the client ranks it strictly above
+    /// Prometheus text, as Prometheus servers do by default.
+    #[must_use]
+    pub fn negotiate(accept: Option<&str>) -> Self {
+        let Some(accept) = accept else {
+            return Self::Prometheus;
+        };
+        let mut openmetrics = 0.0_f32;
+        let mut text = 0.0_f32;
+        for range in accept.split(',') {
+            let mut params = range.split(';').map(str::trim);
+            let media_type = params.next().unwrap_or_default().to_ascii_lowercase();
+            let quality = params
+                .filter_map(|param| param.strip_prefix("q="))
+                .find_map(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            match media_type.as_str() {
+                "application/openmetrics-text" => openmetrics = openmetrics.max(quality),
+                "text/plain" | "text/*" | "*/*" => text = text.max(quality),
+                _ => {}
+            }
+        }
+        if openmetrics > 0.0 && openmetrics > text {
+            Self::OpenMetrics
+        } else {
+            Self::Prometheus
+        }
+    }
+
+    /// Returns the `Content-Type` of this format.
+    #[must_use]
+    pub const fn content_type(self) -> &'static str {
+        match self {
+            Self::Prometheus => PROMETHEUS_CONTENT_TYPE,
+            Self::OpenMetrics => OPENMETRICS_CONTENT_TYPE,
+        }
+    }
+
+    /// Converts Prometheus text output to this format.
+    #[must_use]
+    pub fn encode(self, text: &str) -> String {
+        match self {
+            Self::Prometheus => to_prometheus(text),
+            Self::OpenMetrics => to_openmetrics(text),
+        }
+    }
+}
+
+/// Strips exemplars, which Prometheus text does not support.
+fn to_prometheus(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for line in text.lines() {
+        if line == "# EOF" {
+            continue;
+        }
+        let line = if line.starts_with('#') {
+            line
+        } else {
+            strip_exemplar(line)
+        };
+        out.push_str(line);
+        out.push('\n');
+    }
+    out
+}
+
+fn to_openmetrics(text: &str) -> String {
+    let counters: HashSet<&str> = text
+        .lines()
+        .filter_map(|line| line.strip_prefix("# TYPE "))
+        .filter_map(|rest| rest.split_once(' '))
+        .filter(|(_, kind)| kind.trim() == "counter")
+        .map(|(name, _)| name)
+        .collect();
+
+    let mut out = String::with_capacity(text.len() + 8);
+    for line in text.lines() {
+        // OpenMetrics does not allow blank lines
+        if line.trim().is_empty() || line == "# EOF" {
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("# TYPE ") {
+            let (name, kind) = rest.split_once(' ').unwrap_or((rest, "unknown"));
+            let kind = match kind.trim() {
+                "untyped" => "unknown",
+                other => other,
+            };
+            let name = if kind == "counter" {
+                counter_family(name)
+            } else {
+                name
+            };
+            out.push_str("# TYPE ");
+            out.push_str(name);
+            out.push(' ');
+            out.push_str(kind);
+        } else if let Some(rest) = line.strip_prefix("# HELP ") {
+            let (name, help) = rest.split_once(' ').unwrap_or((rest, ""));
+            let name = if counters.contains(name) {
+                counter_family(name)
+            } else {
+                name
+            };
+            out.push_str("# HELP ");
+            out.push_str(name);
+            out.push(' ');
+            out.push_str(help);
+        } else if line.starts_with('#') {
+            out.push_str(line);
+        } else {
+            let name_end = line.find(|c| c == '{' || c == ' ').unwrap_or(line.len());
+            let name = &line[..name_end];
+            out.push_str(name);
+            // Counter samples must carry the suffix
+            if counters.contains(name) && !name.ends_with("_total") {
+                out.push_str("_total");
+            }
+            out.push_str(&line[name_end..]);
+        }
+        out.push('\n');
+    }
+    out.push_str("# EOF\n");
+    out
+}
+
+fn counter_family(name: &str) -> &str {
+    name.strip_suffix("_total").unwrap_or(name)
+}
+
+/// Removes the exemplar from a sample line.
+fn strip_exemplar(line: &str) -> &str {
+    let bytes = line.as_bytes();
+    let mut in_quotes = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if in_quotes => i += 1,
+            b'"' => in_quotes = !in_quotes,
+            b'#' if !in_quotes && i > 0 && bytes[i - 1] == b' ' => {
+                return line[..i].trim_end();
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    line
+}
+
+/// Authentication for the metrics listener.
+///
+/// Every configured requirement must be met. With the defaults the
+/// endpoint is open.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetricsAuthConfig {
+    /// Token scrapers must send as `Authorization: Bearer <token>`.
+    pub bearer_token: Option<Secret>,
+
+    /// Require a client certificate verified by the TLS-terminating proxy.
+    pub require_client_cert: bool,
+
+    /// Header the proxy forwards the verified certificate in, in Envoy's
+    /// `x-forwarded-client-cert` format.
+    pub client_cert_header: String,
+
+    /// Certificate subjects or SAN URIs allowed to scrape. Empty allows
+    /// any verified certificate.
+    pub allowed_client_subjects: Vec<String>,
+}
+
+impl Default for MetricsAuthConfig {
+    fn default() -> Self {
+        Self {
+            bearer_token: None,
+            require_client_cert: false,
+            client_cert_header: DEFAULT_CLIENT_CERT_HEADER.to_string(),
+            allowed_client_subjects: Vec::new(),
+        }
+    }
+}
+
+impl MetricsAuthConfig {
+    /// Returns `true` if any authentication is configured.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.bearer_token.is_some() || self.require_client_cert
+    }
+
+    /// Checks the credentials in `headers`.
+    #[must_use]
+    pub fn check(&self, headers: &HeaderMap) -> AuthOutcome {
+        if let Some(expected) = &self.bearer_token {
+            let authorized = headers
+                .get(header::AUTHORIZATION)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.split_once(' '))
+                .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("bearer"))
+                .is_some_and(|(_, token)| {
+                    constant_time_eq(token.trim().as_bytes(), expected.expose().as_bytes())
+                });
+            if !authorized {
+                return AuthOutcome::Unauthenticated;
+            }
+        }
+
+        if self.require_client_cert {
+            let Some(cert) = headers
+                .get(self.client_cert_header.as_str())
+                .and_then(|value| value.to_str().ok())
+                .filter(|value| !value.trim().is_empty())
+            else {
+                return AuthOutcome::Forbidden;
+            };
+            if !self.allowed_client_subjects.is_empty()
+                && !client_identities(cert).any(|identity| {
+                    self.allowed_client_subjects
+                        .iter()
+                        .any(|allowed| *allowed == identity)
+                })
+            {
+                return AuthOutcome::Forbidden;
+            }
+        }
+
+        AuthOutcome::Allowed
+    }
+}
+
+/// Result of checking a scrape's credentials.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthOutcome {
+    /// The request may scrape.
+    Allowed,
+    /// The bearer token is missing or wrong (401).
+    Unauthenticated,
+    /// The client certificate is missing or not allowed (403).
+    Forbidden,
+}
+
+/// Compares two byte strings in time independent of where they differ.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0_u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Yields the `Subject` and `URI` values of an XFCC header.
+pub(crate) fn client_identities(header: &str) -> impl Iterator<Item = String> + '_ {
+    header
+        .split(',')
+        .flat_map(|element| element.split(';'))
+        .filter_map(|pair| pair.split_once('='))
+        .filter(|(key, _)| {
+            key.trim().eq_ignore_ascii_case("subject") || key.trim().eq_ignore_ascii_case("uri")
+        })
+        .map(|(_, value)| value.trim().trim_matches('"').to_string())
+}
+
+/// Serves metrics and health checks on the metrics listener.
+#[derive(Clone)]
+pub struct MetricsEndpoint {
+    render: Arc<dyn Fn() -> String + Send + Sync>,
+    auth: MetricsAuthConfig,
+    admin: Option<AdminApi>,
+}
+
+impl fmt::Debug for MetricsEndpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MetricsEndpoint")
+            .field("auth", &self.auth)
+            .field("admin", &self.admin)
+            .finish_non_exhaustive()
+    }
+}
+
+impl MetricsEndpoint {
+    /// Creates an endpoint that renders Prometheus text with `render`.
+    #[must_use]
+    pub fn new(render: impl Fn() -> String + Send + Sync + 'static) -> Self {
+        Self {
+            render: Arc::new(render),
+            auth: MetricsAuthConfig::default(),
+            admin: None,
+        }
+    }
+
+    /// Requires the given credentials for everything except `/health`.
+    #[must_use]
+    pub fn with_auth(mut self, auth: MetricsAuthConfig) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    /// Serves `admin` under `/-/`, with its own credentials.
+    ///
+    /// Nothing is served there while the admin API is disabled.
+    #[must_use]
+    pub fn with_admin(mut self, admin: AdminApi) -> Self {
+        self.admin = Some(admin);
+        self
+    }
+
+    /// Answers a request.
+    ///
+    /// Admin API requests need their body and are answered by
+    /// [`serve`](Self::serve) instead.
+    #[must_use]
+    pub fn handle<B>(&self, request: &Request<B>) -> Response<Full<Bytes>> {
+        let path = request.uri().path();
+        if path == "/health" {
+            return text_response(
+                StatusCode::OK,
+                "text/plain; charset=utf-8",
+                "ok".to_string(),
+            );
+        }
+
+        match self.auth.check(request.headers()) {
+            AuthOutcome::Allowed => {}
+            AuthOutcome::Unauthenticated => {
+                let mut response = text_response(
+                    StatusCode::UNAUTHORIZED,
+                    "text/plain; charset=utf-8",
+                    "unauthorized".to_string(),
+                );
+                response.headers_mut().insert(
+                    header::WWW_AUTHENTICATE,
+                    HeaderValue::from_static("Bearer realm=\"metrics\""),
+                );
+                return response;
+            }
+            AuthOutcome::Forbidden => {
+                return text_response(
+                    StatusCode::FORBIDDEN,
+                    "text/plain; charset=utf-8",
+                    "forbidden".to_string(),
+                );
+            }
+        }
+
+        if path != "/metrics" {
+            return text_response(
+                StatusCode::NOT_FOUND,
+                "text/plain; charset=utf-8",
+                "not found".to_string(),
+            );
+        }
+        if request.method() != Method::GET && request.method() != Method::HEAD {
+            let mut response = text_response(
+                StatusCode::METHOD_NOT_ALLOWED,
+                "text/plain; charset=utf-8",
+                "method not allowed".to_string(),
+            );
+            response
+                .headers_mut()
+                .insert(header::ALLOW, HeaderValue::from_static("GET, HEAD"));
+            return response;
+        }
+
+        let format = ExpositionFormat::negotiate(
+            request
+                .headers()
+                .get(header::ACCEPT)
+                .and_then(|value| value.to_str().ok()),
+        );
+        text_response(
+            StatusCode::OK,
+            format.content_type(),
+            format.encode(&(self.render)()),
+        )
+    }
+
+    /// Serves connections from `listener` until the task is dropped.
+    pub async fn serve(self, listener: TcpListener) {
+        let endpoint = Arc::new(self);
+        loop {
+            let stream = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    tracing::warn!("Failed to accept metrics connection: {e}");
+                    continue;
+                }
+            };
+            let endpoint = endpoint.clone();
+            tokio::spawn(async move {
+                let service = service_fn(move |request: Request<hyper::body::Incoming>| {
+                    let endpoint = endpoint.clone();
+                    async move {
+                        let response = match &endpoint.admin {
+                            Some(admin) if admin.handles(request.uri().path()) => {
+                                let (parts, body) = request.into_parts();
+                                match Limited::new(body, MAX_ADMIN_BODY).collect().await {
+                                    Ok(body) => {
+                                        admin.handle(&Request::from_parts(parts, body.to_bytes()))
+                                    }
+                                    Err(_) => admin::error(
+                                        StatusCode::PAYLOAD_TOO_LARGE,
+                                        "body_too_large",
+                                        "request body is too large",
+                                    ),
+                                }
+                            }
+                            _ => endpoint.handle(&request),
+                        };
+                        Ok::<_, Infallible>(response)
+                    }
+                });
+                if let Err(e) = http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await
+                {
+                    tracing::debug!("Metrics connection error: {e}");
+                }
+            });
+        }
+    }
+}
+
+fn text_response(
+    status: StatusCode,
+    content_type: &'static str,
+    body: String,
+) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(Bytes::from(body)));
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_body_util::BodyExt;
+
+    const SAMPLE: &str = "\
+# HELP archimedes_requests_total Total number of HTTP requests processed
+# TYPE archimedes_requests_total counter
+archimedes_requests_total{operation=\"getUser\",status=\"200\"} 3 # {trace_id=\"abc\"} 1
+
+# TYPE archimedes_in_flight_requests gauge
+archimedes_in_flight_requests 1
+
+# TYPE legacy untyped
+legacy 7
+";
+
+    fn endpoint(auth: MetricsAuthConfig) -> MetricsEndpoint {
+        MetricsEndpoint::new(|| SAMPLE.to_string()).with_auth(auth)
+    }
+
+    fn token_auth() -> MetricsAuthConfig {
+        MetricsAuthConfig {
+            bearer_token: Some(Secret::new("scrape-token")),
+            ..MetricsAuthConfig::default()
+        }
+    }
+
+    fn get(path: &str, headers: &[(&str, &str)]) -> Request<()> {
+        let mut builder = Request::get(path);
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        builder.body(()).unwrap()
+    }
+
+    async fn body(response: Response<Full<Bytes>>) -> String {
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[test]
+    fn test_negotiate() {
+        assert_eq!(
+            ExpositionFormat::negotiate(None),
+            ExpositionFormat::Prometheus
+        );
+        assert_eq!(
+            ExpositionFormat::negotiate(Some("text/plain;version=0.0.4")),
+            ExpositionFormat::Prometheus
+        );
+        // Prometheus' default scrape Accept header
+        assert_eq!(
+            ExpositionFormat::negotiate(Some(
+                "application/openmetrics-text;version=1.0.0,application/openmetrics-text;version=0.0.1;q=0.75,text/plain;version=0.0.4;q=0.5,*/*;q=0.1"
+            )),
+            ExpositionFormat::OpenMetrics
+        );
+        assert_eq!(
+            ExpositionFormat::negotiate(Some(
+                "application/openmetrics-text;q=0.5,text/plain;q=0.9"
+            )),
+            ExpositionFormat::Prometheus
+        );
+    }
+
+    #[tokio::test]
+    async fn test_format_follows_accept_header() {
+        let endpoint = endpoint(MetricsAuthConfig::default());
+
+        let response = endpoint.handle(&get("/metrics", &[]));
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            PROMETHEUS_CONTENT_TYPE
+        );
+        let text = body(response).await;
+        assert!(
+            text.contains("archimedes_requests_total{operation=\"getUser\",status=\"200\"} 3\n")
+        );
+        assert!(!text.contains("trace_id"));
+        assert!(!text.contains("# EOF"));
+
+        let response = endpoint.handle(&get(
+            "/metrics",
+            &[("accept", "application/openmetrics-text; version=1.0.0")],
+        ));
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            OPENMETRICS_CONTENT_TYPE
+        );
+        let text = body(response).await;
+        assert!(text.contains("# TYPE archimedes_requests counter\n"));
+        assert!(text.contains("# HELP archimedes_requests Total number"));
+        assert!(text.contains("status=\"200\"} 3 # {trace_id=\"abc\"} 1\n"));
+        assert!(text.contains("# TYPE legacy unknown\n"));
+        assert!(!text.contains("\n\n"));
+        assert!(text.ends_with("# EOF\n"));
+    }
+
+    #[test]
+    fn test_counter_samples_get_total_suffix() {
+        let text = ExpositionFormat::OpenMetrics.encode("# TYPE jobs counter\njobs 2\n");
+        assert_eq!(text, "# TYPE jobs counter\njobs_total 2\n# EOF\n");
+    }
+
+    #[tokio::test]
+    async fn test_authenticated_scrape_succeeds() {
+        let endpoint = endpoint(token_auth());
+        let response = endpoint.handle(&get(
+            "/metrics",
+            &[("authorization", "Bearer scrape-token")],
+        ));
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(body(response)
+            .await
+            .contains("archimedes_in_flight_requests 1"));
+    }
+
+    #[test]
+    fn test_wrong_token_rejected() {
+        let endpoint = endpoint(token_auth());
+        for headers in [
+            &[][..],
+            &[("authorization", "Bearer wrong-token")][..],
+            &[("authorization", "Basic c2NyYXBlLXRva2Vu")][..],
+        ] {
+            let response = endpoint.handle(&get("/metrics", headers));
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+            assert!(response.headers().contains_key(header::WWW_AUTHENTICATE));
+        }
+        // Unknown paths do not reveal themselves before authentication
+        let response = endpoint.handle(&get("/other", &[]));
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_health_stays_open() {
+        let auth = MetricsAuthConfig {
+            require_client_cert: true,
+            ..token_auth()
+        };
+        let response = endpoint(auth).handle(&get("/health", &[]));
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body(response).await, "ok");
+    }
+
+    #[test]
+    fn test_client_cert() {
+        let endpoint = endpoint(MetricsAuthConfig {
+            require_client_cert: true,
+            allowed_client_subjects: vec![
+                "spiffe://cluster/ns/monitoring/sa/prometheus".to_string()
+            ],
+            ..MetricsAuthConfig::default()
+        });
+
+        let response = endpoint.handle(&get("/metrics", &[]));
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = endpoint.handle(&get(
+            "/metrics",
+            &[(
+                "x-forwarded-client-cert",
+                "Hash=abc;Subject=\"CN=other\";URI=spiffe://cluster/ns/default/sa/app",
+            )],
+        ));
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = endpoint.handle(&get(
+            "/metrics",
+            &[(
+                "x-forwarded-client-cert",
+                "Hash=abc;Subject=\"CN=prometheus\";URI=spiffe://cluster/ns/monitoring/sa/prometheus",
+            )],
+        ));
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    /// Sends a raw HTTP/1.1 request to `addr` and returns the response.
+    async fn send(addr: std::net::SocketAddr, request: &str) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_serves_admin_api_with_body() {
+        let admin = AdminApi::new(crate::admin::AdminConfig {
+            enabled: true,
+            auth: token_auth(),
+        })
+        .log_level(|target, level| Ok(format!("{}={level}", target.unwrap_or("*"))));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(
+            endpoint(MetricsAuthConfig::default())
+                .with_admin(admin)
+                .serve(listener),
+        );
+
+        let body = r#"{"target":"archimedes","level":"debug"}"#;
+        let response = send(
+            addr,
+            &format!(
+                "POST /-/log-level HTTP/1.1\r\nHost: admin\r\nAuthorization: Bearer scrape-token\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            ),
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        assert!(response.contains(r#""filter":"archimedes=debug""#));
+
+        // Metrics are still served next to the admin API
+        let response = send(
+            addr,
+            "GET /metrics HTTP/1.1\r\nHost: admin\r\nConnection: close\r\n\r\n",
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+    }
+
+    #[test]
+    fn test_admin_api_disabled_by_default() {
+        let endpoint = endpoint(MetricsAuthConfig::default())
+            .with_admin(AdminApi::new(crate::admin::AdminConfig::default()));
+        assert!(!endpoint.admin.as_ref().unwrap().handles("/-/config"));
+
+        let response = endpoint.handle(&get("/-/config", &[]));
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"token", b"token"));
+        assert!(!constant_time_eq(b"token", b"tokem"));
+        assert!(!constant_time_eq(b"token", b"token2"));
+    }
+
+    #[test]
+    fn test_auth_config_deserializes() {
+        let auth: MetricsAuthConfig = serde_json::from_value(serde_json::json!({
+            "bearer_token": "s3cr3t",
+            "require_client_cert": true
+        }))
+        .unwrap();
+        assert!(auth.is_enabled());
+        assert_eq!(auth.bearer_token.as_ref().unwrap().expose(), "s3cr3t");
+        assert_eq!(auth.client_cert_header, DEFAULT_CLIENT_CERT_HEADER);
+        assert!(!format!("{auth:?}").contains("s3cr3t"));
+    }
+}
@@ -25,6 +25,14 @@ pub enum TelemetryError {
     #[error("Invalid address: {0}")]
     InvalidAddress(String),
 
+    /// Failed to flush buffered telemetry.
+    #[error("Failed to flush telemetry: {0}")]
+    Flush(String),
+
+    /// Flushing did not complete within the allotted time.
+    #[error("Telemetry flush timed out after {0:?}")]
+    FlushTimeout(std::time::Duration),
+
     /// IO error.
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
@@ -46,5 +54,12 @@ mod tests {
         let _ = TelemetryError::LoggingInit("log fail".to_string());
         let _ = TelemetryError::InvalidConfig("bad config".to_string());
         let _ = TelemetryError::InvalidAddress("bad addr".to_string());
+        let _ = TelemetryError::Flush("flush fail".to_string());
+    }
+
+    #[test]
+    fn test_flush_timeout_display() {
+        let err = TelemetryError::FlushTimeout(std::time::Duration::from_secs(2));
+        assert_eq!(err.to_string(), "Telemetry flush timed out after 2s");
     }
 }
@@ -4,7 +4,8 @@
 //!
 //! - **Metrics**: Prometheus-format metrics via the `metrics` crate
 //! - **Tracing**: Distributed tracing via OpenTelemetry with OTLP export
-//! - **Logging**: Structured JSON logging with trace correlation
+//! - **Logging**: Structured logging (JSON, pretty, compact or OTLP) with trace correlation
+//! - **Access logs**: One line per request as JSON or in Common or Combined Log Format
 //!
 //! # Architecture
 //!
@@ -43,6 +44,9 @@
 //! | `archimedes_request_size_bytes` | Histogram | `operation` | Request body size |
 //! | `archimedes_response_size_bytes` | Histogram | `operation` | Response body size |
 //!
+//! Process (memory, CPU, file descriptors) and Tokio runtime metrics can be
+//! enabled with [`MetricsConfig::process_metrics`].
+//!
 //! # Example
 //!
 //! ```rust,ignore
@@ -67,7 +71,10 @@
 //!
 //! # Metrics Endpoint
 //!
-//! The `/metrics` endpoint exposes Prometheus-format metrics:
+//! The `/metrics` endpoint exposes Prometheus-format metrics, or OpenMetrics
+//! when the scraper asks for it, and can require a bearer token or client
+//! certificate (see [`endpoint`]). The same listener can serve the
+//! authenticated [`admin`] API, which is disabled by default:
 //!
 //! ```text
 //! # HELP archimedes_requests_total Total number of requests
@@ -83,16 +90,29 @@
 
 #![warn(missing_docs)]
 
+pub mod access_log;
+pub mod admin;
 pub mod config;
+pub mod endpoint;
 pub mod error;
 pub mod logging;
 pub mod metrics;
+pub mod process;
+pub mod sampling;
 pub mod tracing;
 
+pub use access_log::{AccessLogFormat, AccessLogLayer};
+pub use admin::{AdminApi, AdminConfig, AdminRoute};
 pub use config::{TelemetryConfig, TelemetryConfigBuilder};
+pub use endpoint::{AuthOutcome, ExpositionFormat, MetricsAuthConfig, MetricsEndpoint};
 pub use error::TelemetryError;
-pub use logging::{init_logging, LogConfig};
-pub use metrics::{init_metrics, MetricsConfig, MetricsRegistry};
+pub use logging::{init_logging, set_log_level, LogConfig, LogFormat};
+pub use metrics::{
+    init_metrics, init_metrics_with_admin, HistogramSnapshot, MetricKey, MetricsConfig,
+    MetricsRegistry, MetricsSnapshot,
+};
+pub use process::{spawn_process_metrics, ProcessMetrics};
+pub use sampling::SamplingStrategy;
 pub use tracing::{init_tracing, TracingConfig};
 
 /// Result type for telemetry operations.
@@ -103,24 +123,87 @@ pub type TelemetryResult<T> = Result<T, TelemetryError>;
 /// This guard should be kept alive for the lifetime of the application.
 /// When dropped, it will flush any pending telemetry data and shut down
 /// the providers gracefully.
+///
+/// `Drop` does not run on `std::process::exit` or after a crash, so call
+/// [`flush`](Self::flush) (or register a [`TelemetryFlusher`] in the
+/// server's shutdown path) to export buffered data at a known point.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use std::time::Duration;
+///
+/// let guard = init_telemetry(config)?;
+/// let flusher = guard.flusher();
+///
+/// let server = Server::builder()
+///     .before_exit(move || {
+///         if let Err(e) = flusher.flush_with_timeout(Duration::from_secs(5)) {
+///             eprintln!("{e}");
+///         }
+///     })
+///     .build();
+/// ```
 pub struct TelemetryGuard {
-    /// Tracing provider shutdown handle
-    #[allow(dead_code)]
-    tracer_provider: Option<opentelemetry_sdk::trace::TracerProvider>,
+    /// Providers to flush and shut down
+    flusher: TelemetryFlusher,
 }
 
 impl TelemetryGuard {
     /// Creates a new telemetry guard.
     #[must_use]
     pub fn new(tracer_provider: Option<opentelemetry_sdk::trace::TracerProvider>) -> Self {
-        Self { tracer_provider }
+        Self {
+            flusher: TelemetryFlusher {
+                tracer_provider,
+                logger_provider: None,
+            },
+        }
+    }
+
+    /// Adds the OTLP log provider to flush and shut down on drop.
+    #[must_use]
+    pub fn with_logger_provider(
+        mut self,
+        logger_provider: Option<opentelemetry_sdk::logs::LoggerProvider>,
+    ) -> Self {
+        self.flusher.logger_provider = logger_provider;
+        self
+    }
+
+    /// Returns a cloneable handle that flushes this guard's providers.
+    ///
+    /// The handle does not shut the providers down; the guard still does
+    /// that on drop.
+    #[must_use]
+    pub fn flusher(&self) -> TelemetryFlusher {
+        self.flusher.clone()
+    }
+
+    /// Force-flushes traces, metrics and logs, blocking until done.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TelemetryError::Flush` if any provider fails to flush.
+    pub fn flush(&self) -> TelemetryResult<()> {
+        self.flusher.flush()
+    }
+
+    /// Force-flushes like [`flush`](Self::flush), waiting at most `timeout`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TelemetryError::FlushTimeout` if flushing does not finish in
+    /// time, or `TelemetryError::Flush` if any provider fails to flush.
+    pub fn flush_with_timeout(&self, timeout: std::time::Duration) -> TelemetryResult<()> {
+        self.flusher.flush_with_timeout(timeout)
     }
 }
 
 impl Drop for TelemetryGuard {
     fn drop(&mut self) {
         // Shutdown tracer provider if present
-        if let Some(provider) = self.tracer_provider.take() {
+        if let Some(provider) = self.flusher.tracer_provider.take() {
             // Force flush and shutdown
             for result in provider.force_flush() {
                 if let Err(e) = result {
@@ -131,6 +214,95 @@ impl Drop for TelemetryGuard {
                 eprintln!("Error shutting down tracer provider: {e}");
             }
         }
+
+        // Shutdown logger provider if present
+        if let Some(provider) = self.flusher.logger_provider.take() {
+            for result in provider.force_flush() {
+                if let Err(e) = result {
+                    eprintln!("Error flushing logger provider: {e}");
+                }
+            }
+            if let Err(e) = provider.shutdown() {
+                eprintln!("Error shutting down logger provider: {e}");
+            }
+        }
+    }
+}
+
+/// Cloneable handle that force-flushes telemetry providers.
+///
+/// Obtained from [`TelemetryGuard::flusher`]; it can be moved into shutdown
+/// hooks while the guard itself stays in `main`.
+#[derive(Clone)]
+pub struct TelemetryFlusher {
+    tracer_provider: Option<opentelemetry_sdk::trace::TracerProvider>,
+    logger_provider: Option<opentelemetry_sdk::logs::LoggerProvider>,
+}
+
+impl TelemetryFlusher {
+    /// Force-flushes traces, metrics and logs, blocking until done.
+    ///
+    /// Metrics are pull-based, so flushing them runs the Prometheus
+    /// exporter's upkeep to make recorded histograms visible to the next
+    /// scrape.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TelemetryError::Flush` if any provider fails to flush.
+    pub fn flush(&self) -> TelemetryResult<()> {
+        let mut errors = Vec::new();
+
+        if let Some(provider) = &self.tracer_provider {
+            errors.extend(
+                provider
+                    .force_flush()
+                    .into_iter()
+                    .filter_map(Result::err)
+                    .map(|e| format!("traces: {e}")),
+            );
+        }
+
+        if let Some(handle) = metrics::get_metrics_handle() {
+            handle.run_upkeep();
+        }
+
+        if let Some(provider) = &self.logger_provider {
+            errors.extend(
+                provider
+                    .force_flush()
+                    .into_iter()
+                    .filter_map(Result::err)
+                    .map(|e| format!("logs: {e}")),
+            );
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(TelemetryError::Flush(errors.join("; ")))
+        }
+    }
+
+    /// Force-flushes like [`flush`](Self::flush), waiting at most `timeout`.
+    ///
+    /// The flush runs on a separate thread; on timeout it keeps running in
+    /// the background and this call returns immediately.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TelemetryError::FlushTimeout` if flushing does not finish in
+    /// time, or `TelemetryError::Flush` if any provider fails to flush.
+    pub fn flush_with_timeout(&self, timeout: std::time::Duration) -> TelemetryResult<()> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let flusher = self.clone();
+        std::thread::Builder::new()
+            .name("telemetry-flush".to_string())
+            .spawn(move || {
+                let _ = tx.send(flusher.flush());
+            })?;
+
+        rx.recv_timeout(timeout)
+            .map_err(|_| TelemetryError::FlushTimeout(timeout))?
     }
 }
 
@@ -165,8 +337,12 @@ impl Drop for TelemetryGuard {
 /// let _guard = init_telemetry(config)?;
 /// ```
 pub fn init_telemetry(config: TelemetryConfig) -> TelemetryResult<TelemetryGuard> {
-    // Initialize logging first
-    init_logging(&config.logging)?;
+    // Initialize logging first, exporting over OTLP to the trace collector
+    let mut logging = config.logging.clone();
+    if logging.otlp_endpoint.is_none() {
+        logging.otlp_endpoint = Some(config.tracing.otlp_endpoint.clone());
+    }
+    let logger_provider = init_logging(&logging)?;
 
     // Initialize metrics
     init_metrics(&config.metrics)?;
@@ -174,7 +350,7 @@ pub fn init_telemetry(config: TelemetryConfig) -> TelemetryResult<TelemetryGuard
     // Initialize tracing
     let tracer_provider = init_tracing(&config.tracing)?;
 
-    Ok(TelemetryGuard::new(tracer_provider))
+    Ok(TelemetryGuard::new(tracer_provider).with_logger_provider(logger_provider))
 }
 
 #[cfg(test)]
@@ -199,4 +375,59 @@ mod tests {
         assert_eq!(config.service_version, "1.0.0");
         assert_eq!(config.environment, "test");
     }
+
+    #[test]
+    fn test_flush_without_providers() {
+        let guard = TelemetryGuard::new(None);
+        assert!(guard.flush().is_ok());
+        assert!(guard
+            .flush_with_timeout(std::time::Duration::from_secs(1))
+            .is_ok());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_flush_exports_spans_before_drop() {
+        use opentelemetry::trace::{Tracer, TracerProvider as _};
+        use opentelemetry_sdk::testing::trace::InMemorySpanExporter;
+
+        let exporter = InMemorySpanExporter::default();
+        let provider = opentelemetry_sdk::trace::TracerProvider::builder()
+            .with_batch_exporter(exporter.clone(), opentelemetry_sdk::runtime::Tokio)
+            .build();
+        provider.tracer("test").in_span("buffered", |_cx| {});
+
+        let guard = TelemetryGuard::new(Some(provider));
+        assert!(exporter.get_finished_spans().unwrap().is_empty());
+
+        guard.flush().expect("flush should succeed");
+        let spans = exporter.get_finished_spans().unwrap();
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].name, "buffered");
+
+        drop(guard);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_flush_with_timeout_exports_logs_before_drop() {
+        use opentelemetry::logs::{LogRecord as _, Logger as _, LoggerProvider as _};
+        use opentelemetry_sdk::testing::logs::InMemoryLogExporter;
+
+        let exporter = InMemoryLogExporter::default();
+        let provider = opentelemetry_sdk::logs::LoggerProvider::builder()
+            .with_batch_exporter(exporter.clone(), opentelemetry_sdk::runtime::Tokio)
+            .build();
+        let logger = provider.logger("test");
+        let mut record = logger.create_log_record();
+        record.set_body("buffered".into());
+        logger.emit(record);
+
+        let guard = TelemetryGuard::new(None).with_logger_provider(Some(provider));
+        guard
+            .flusher()
+            .flush_with_timeout(std::time::Duration::from_secs(5))
+            .expect("flush should succeed");
+        assert_eq!(exporter.get_emitted_logs().unwrap().len(), 1);
+
+        drop(guard);
+    }
 }
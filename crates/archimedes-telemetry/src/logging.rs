@@ -1,32 +1,138 @@
-//! Structured JSON logging for Archimedes.
+//! Structured logging for Archimedes.
 //!
-//! This module provides structured JSON logging with trace correlation,
+//! This module provides structured logging with trace correlation,
 //! integrating with the tracing-subscriber ecosystem.
 //!
 //! # Features
 //!
-//! - JSON-formatted log output
+//! - JSON, pretty and compact log output (see [`LogFormat`])
+//! - OTLP log export to the trace collector
 //! - Trace ID correlation in logs
 //! - Configurable log levels
 //! - Span context in structured fields
 //!
+//! The format can be overridden at startup with the
+//! `ARCHIMEDES_LOG_FORMAT` environment variable (`json`, `pretty`,
+//! `compact` or `otlp`). Every format captures the same event fields; only
+//! the rendering differs.
+//!
+//! Access log events of the telemetry stage can be rendered separately, as
+//! JSON or in Common or Combined Log Format, with [`LogConfig::access_log`]
+//! (see [`crate::access_log`]).
+//!
+//! The level filter can be changed while the service runs with
+//! [`set_log_level`], which the admin API exposes as `POST /-/log-level`.
+//!
 //! # Example
 //!
 //! ```rust,ignore
-//! use archimedes_telemetry::logging::{LogConfig, init_logging};
+//! use archimedes_telemetry::logging::{LogConfig, LogFormat, init_logging};
 //!
-//! let config = LogConfig::default();
-//! init_logging(&config)?;
+//! let config = LogConfig {
+//!     format: Some(LogFormat::Pretty),
+//!     ..LogConfig::default()
+//! };
+//! let _provider = init_logging(&config)?;
 //!
 //! tracing::info!(operation = "getUser", user_id = 123, "Processing request");
 //! ```
 
+use crate::access_log::{AccessLogFormat, AccessLogLayer, ACCESS_LOG_TARGET};
 use crate::error::TelemetryError;
 use crate::TelemetryResult;
+use opentelemetry::KeyValue;
+use opentelemetry_appender_tracing::layer::OpenTelemetryTracingBridge;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::logs::LoggerProvider;
+use opentelemetry_sdk::Resource;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Mutex, OnceLock};
+use tracing_subscriber::filter::{filter_fn, LevelFilter};
 use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::MakeWriter;
 use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
 use tracing_subscriber::util::SubscriberInitExt;
-use tracing_subscriber::{EnvFilter, Layer};
+use tracing_subscriber::{reload, EnvFilter, Layer, Registry};
+
+/// Environment variable that overrides the configured log format.
+pub const LOG_FORMAT_ENV: &str = "ARCHIMEDES_LOG_FORMAT";
+
+/// Default OTLP endpoint for log export.
+pub const DEFAULT_OTLP_ENDPOINT: &str = "http://localhost:4317";
+
+/// Targets whose events are never exported over OTLP, to avoid the exporter
+/// logging about its own exports.
+const EXPORTER_TARGETS: &[&str] = &["opentelemetry", "tonic", "h2", "hyper", "tower"];
+
+/// The installed level filter and the directives it was built from.
+static LEVEL_FILTER: OnceLock<LevelFilterHandle> = OnceLock::new();
+
+struct LevelFilterHandle {
+    handle: reload::Handle<EnvFilter, Registry>,
+    directives: Mutex<String>,
+}
+
+/// Log output format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    /// One JSON object per line.
+    #[default]
+    Json,
+    /// Multi-line, human-readable output for local development.
+    Pretty,
+    /// Single-line, human-readable output.
+    Compact,
+    /// OTLP export to the collector, correlated with the active trace.
+    ///
+    /// Events are also written to stdout as JSON so logs remain available
+    /// when the collector is unreachable.
+    Otlp,
+}
+
+impl LogFormat {
+    /// Reads the format from [`LOG_FORMAT_ENV`], if set and valid.
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        std::env::var(LOG_FORMAT_ENV)
+            .ok()
+            .and_then(|value| value.parse().ok())
+    }
+
+    /// Returns the format name.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Pretty => "pretty",
+            Self::Compact => "compact",
+            Self::Otlp => "otlp",
+        }
+    }
+}
+
+impl FromStr for LogFormat {
+    type Err = TelemetryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "pretty" => Ok(Self::Pretty),
+            "compact" => Ok(Self::Compact),
+            "otlp" => Ok(Self::Otlp),
+            other => Err(TelemetryError::InvalidConfig(format!(
+                "Unknown log format: {other}"
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
 
 /// Logging configuration.
 #[derive(Debug, Clone)]
@@ -38,8 +144,19 @@ pub struct LogConfig {
     pub level: String,
 
     /// Whether to output JSON format.
+    ///
+    /// Ignored when `format` is set.
     pub json_format: bool,
 
+    /// Log format, overriding `json_format` when set.
+    pub format: Option<LogFormat>,
+
+    /// OTLP collector endpoint used by [`LogFormat::Otlp`].
+    ///
+    /// [`init_telemetry`](crate::init_telemetry) defaults this to the
+    /// tracing endpoint so logs and traces go to the same collector.
+    pub otlp_endpoint: Option<String>,
+
     /// Whether to include span events (enter, exit, close).
     pub span_events: bool,
 
@@ -54,6 +171,11 @@ pub struct LogConfig {
 
     /// Service name for log fields.
     pub service_name: String,
+
+    /// Format of the access log, written as separate lines to stdout.
+    ///
+    /// When `None`, access log events are logged like any other event.
+    pub access_log_format: Option<AccessLogFormat>,
 }
 
 impl Default for LogConfig {
@@ -62,11 +184,14 @@ impl Default for LogConfig {
             enabled: true,
             level: "info".to_string(),
             json_format: true, // JSON by default for production
+            format: None,
+            otlp_endpoint: None,
             span_events: false,
             file_line_info: false,
             thread_ids: false,
             include_target: true,
             service_name: "archimedes".to_string(),
+            access_log_format: None,
         }
     }
 }
@@ -79,11 +204,14 @@ impl LogConfig {
             enabled: true,
             level: "debug".to_string(),
             json_format: false,
+            format: None,
+            otlp_endpoint: None,
             span_events: true,
             file_line_info: true,
             thread_ids: false,
             include_target: true,
             service_name: "archimedes".to_string(),
+            access_log_format: None,
         }
     }
 
@@ -94,33 +222,178 @@ impl LogConfig {
             enabled: true,
             level: "info".to_string(),
             json_format: true,
+            format: None,
+            otlp_endpoint: None,
             span_events: false,
             file_line_info: false,
             thread_ids: false,
             include_target: true,
             service_name: "archimedes".to_string(),
+            access_log_format: None,
         }
     }
+
+    /// Writes access log lines in `format` instead of logging access log
+    /// events like other events.
+    #[must_use]
+    pub const fn access_log(mut self, format: AccessLogFormat) -> Self {
+        self.access_log_format = Some(format);
+        self
+    }
+
+    /// Returns the effective log format.
+    #[must_use]
+    pub fn log_format(&self) -> LogFormat {
+        self.format.unwrap_or(if self.json_format {
+            LogFormat::Json
+        } else {
+            LogFormat::Pretty
+        })
+    }
 }
 
 /// Initializes the logging subsystem.
 ///
+/// The format is taken from [`LOG_FORMAT_ENV`] when set, otherwise from
+/// [`LogConfig::log_format`].
+///
 /// # Arguments
 ///
 /// * `config` - Logging configuration
 ///
+/// # Returns
+///
+/// Returns the `LoggerProvider` for later shutdown when logs are exported
+/// over OTLP.
+///
 /// # Errors
 ///
 /// Returns `TelemetryError::LoggingInit` if initialization fails.
-pub fn init_logging(config: &LogConfig) -> TelemetryResult<()> {
+pub fn init_logging(config: &LogConfig) -> TelemetryResult<Option<LoggerProvider>> {
     if !config.enabled {
-        return Ok(());
+        return Ok(None);
     }
 
     // Build env filter
     let filter = EnvFilter::try_new(&config.level)
         .map_err(|e| TelemetryError::LoggingInit(format!("Invalid log level: {e}")))?;
 
+    let format = LogFormat::from_env().unwrap_or_else(|| config.log_format());
+
+    let provider = if format == LogFormat::Otlp {
+        Some(otlp_log_provider(config)?)
+    } else {
+        None
+    };
+
+    let access_log = config.access_log_format;
+    let (filter, handle) = reload::Layer::new(filter);
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(
+            fmt_layer(config, format, std::io::stdout).with_filter(filter_fn(move |metadata| {
+                access_log.is_none() || metadata.target() != ACCESS_LOG_TARGET
+            })),
+        )
+        .with(access_log.map(|format| AccessLogLayer::new(format, std::io::stdout)))
+        .with(provider.as_ref().map(otlp_layer))
+        .try_init()
+        .map_err(|e| TelemetryError::LoggingInit(e.to_string()))?;
+
+    let _ = LEVEL_FILTER.set(LevelFilterHandle {
+        handle,
+        directives: Mutex::new(config.level.clone()),
+    });
+
+    Ok(provider)
+}
+
+/// Changes the log level of `target`, or the default level when `target`
+/// is `None`, without restarting.
+///
+/// Other directives of the filter are kept. Returns the new filter.
+///
+/// # Errors
+///
+/// Returns `TelemetryError::InvalidConfig` for an unknown level or a
+/// malformed target, and `TelemetryError::LoggingInit` if logging was not
+/// initialized by [`init_logging`].
+pub fn set_log_level(target: Option<&str>, level: &str) -> TelemetryResult<String> {
+    let installed = LEVEL_FILTER
+        .get()
+        .ok_or_else(|| TelemetryError::LoggingInit("logging is not initialized".to_string()))?;
+    let mut current = installed
+        .directives
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+    let directives = merge_directive(&current, target, level)?;
+    let filter = create_env_filter(&directives)?;
+    installed
+        .handle
+        .reload(filter)
+        .map_err(|e| TelemetryError::LoggingInit(e.to_string()))?;
+    current.clone_from(&directives);
+    Ok(directives)
+}
+
+/// Returns the active level filter, if logging was initialized.
+#[must_use]
+pub fn current_log_filter() -> Option<String> {
+    LEVEL_FILTER.get().map(|installed| {
+        installed
+            .directives
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    })
+}
+
+/// Replaces the directive for `target` in a filter string.
+fn merge_directive(filter: &str, target: Option<&str>, level: &str) -> TelemetryResult<String> {
+    let level = level.trim().to_ascii_lowercase();
+    LevelFilter::from_str(&level)
+        .map_err(|_| TelemetryError::InvalidConfig(format!("unknown log level: {level}")))?;
+    if let Some(target) = target {
+        if target.is_empty()
+            || target
+                .chars()
+                .any(|c| c.is_whitespace() || matches!(c, ',' | '=' | '[' | ']'))
+        {
+            return Err(TelemetryError::InvalidConfig(format!(
+                "invalid log target: {target:?}"
+            )));
+        }
+    }
+
+    let mut directives: Vec<String> = filter
+        .split(',')
+        .map(str::trim)
+        .filter(|directive| !directive.is_empty())
+        .filter(|directive| match (target, directive.split_once('=')) {
+            (Some(target), Some((name, _))) => name != target,
+            (Some(_), None) => true,
+            (None, found) => found.is_some(),
+        })
+        .map(ToString::to_string)
+        .collect();
+    match target {
+        Some(target) => directives.push(format!("{target}={level}")),
+        None => directives.insert(0, level),
+    }
+    Ok(directives.join(","))
+}
+
+/// Builds the formatting layer for a log format.
+fn fmt_layer<S, W>(
+    config: &LogConfig,
+    format: LogFormat,
+    writer: W,
+) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
     // Determine span events to capture
     let span_events = if config.span_events {
         FmtSpan::NEW | FmtSpan::CLOSE
@@ -128,39 +401,58 @@ pub fn init_logging(config: &LogConfig) -> TelemetryResult<()> {
         FmtSpan::NONE
     };
 
-    if config.json_format {
-        // JSON format for production
-        let fmt_layer = tracing_subscriber::fmt::layer()
-            .json()
-            .with_span_events(span_events)
-            .with_file(config.file_line_info)
-            .with_line_number(config.file_line_info)
-            .with_thread_ids(config.thread_ids)
-            .with_target(config.include_target)
-            .with_filter(filter);
-
-        tracing_subscriber::registry()
-            .with(fmt_layer)
-            .try_init()
-            .map_err(|e| TelemetryError::LoggingInit(e.to_string()))?;
-    } else {
-        // Pretty format for development
-        let fmt_layer = tracing_subscriber::fmt::layer()
-            .pretty()
-            .with_span_events(span_events)
-            .with_file(config.file_line_info)
-            .with_line_number(config.file_line_info)
-            .with_thread_ids(config.thread_ids)
-            .with_target(config.include_target)
-            .with_filter(filter);
-
-        tracing_subscriber::registry()
-            .with(fmt_layer)
-            .try_init()
-            .map_err(|e| TelemetryError::LoggingInit(e.to_string()))?;
-    }
-
-    Ok(())
+    let layer = tracing_subscriber::fmt::layer()
+        .with_writer(writer)
+        .with_span_events(span_events)
+        .with_file(config.file_line_info)
+        .with_line_number(config.file_line_info)
+        .with_thread_ids(config.thread_ids)
+        .with_target(config.include_target);
+
+    match format {
+        LogFormat::Json | LogFormat::Otlp => layer.json().boxed(),
+        LogFormat::Pretty => layer.pretty().boxed(),
+        LogFormat::Compact => layer.compact().boxed(),
+    }
+}
+
+/// Builds the layer that bridges tracing events to OpenTelemetry logs.
+///
+/// Log records pick up the trace and span IDs of the active OpenTelemetry
+/// context, so exported logs correlate with exported traces.
+fn otlp_layer<S>(provider: &LoggerProvider) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+{
+    OpenTelemetryTracingBridge::new(provider)
+        .with_filter(filter_fn(|metadata| {
+            !EXPORTER_TARGETS
+                .iter()
+                .any(|target| metadata.target().starts_with(target))
+        }))
+        .boxed()
+}
+
+/// Builds a logger provider exporting over OTLP.
+fn otlp_log_provider(config: &LogConfig) -> TelemetryResult<LoggerProvider> {
+    let endpoint = config
+        .otlp_endpoint
+        .as_deref()
+        .unwrap_or(DEFAULT_OTLP_ENDPOINT);
+
+    let exporter = opentelemetry_otlp::LogExporter::builder()
+        .with_tonic()
+        .with_endpoint(endpoint)
+        .build()
+        .map_err(|e| TelemetryError::LoggingInit(e.to_string()))?;
+
+    Ok(LoggerProvider::builder()
+        .with_resource(Resource::new([KeyValue::new(
+            opentelemetry_semantic_conventions::attribute::SERVICE_NAME,
+            config.service_name.clone(),
+        )]))
+        .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
+        .build())
 }
 
 /// Creates an env filter from a string.
@@ -307,4 +599,186 @@ mod tests {
         let result = init_logging(&config);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_log_format_parsing() {
+        assert_eq!("json".parse::<LogFormat>().unwrap(), LogFormat::Json);
+        assert_eq!("Pretty".parse::<LogFormat>().unwrap(), LogFormat::Pretty);
+        assert_eq!(
+            " compact ".parse::<LogFormat>().unwrap(),
+            LogFormat::Compact
+        );
+        assert_eq!("otlp".parse::<LogFormat>().unwrap(), LogFormat::Otlp);
+        assert!("xml".parse::<LogFormat>().is_err());
+        assert_eq!(LogFormat::Otlp.to_string(), "otlp");
+    }
+
+    #[test]
+    fn test_merge_directive() {
+        assert_eq!(
+            merge_directive("info,hyper=warn", Some("archimedes_server"), "DEBUG").unwrap(),
+            "info,hyper=warn,archimedes_server=debug"
+        );
+        assert_eq!(
+            merge_directive("info,hyper=warn", Some("hyper"), "error").unwrap(),
+            "info,hyper=error"
+        );
+        assert_eq!(
+            merge_directive("info,hyper=warn", None, "trace").unwrap(),
+            "trace,hyper=warn"
+        );
+        assert!(merge_directive("info", None, "loud").is_err());
+        assert!(merge_directive("info", Some("a,b"), "debug").is_err());
+    }
+
+    #[test]
+    fn test_effective_log_format() {
+        assert_eq!(LogConfig::default().log_format(), LogFormat::Json);
+        assert_eq!(LogConfig::development().log_format(), LogFormat::Pretty);
+
+        let config = LogConfig {
+            format: Some(LogFormat::Compact),
+            ..LogConfig::default()
+        };
+        assert_eq!(config.log_format(), LogFormat::Compact);
+    }
+
+    #[test]
+    fn test_access_log_builder() {
+        assert_eq!(LogConfig::default().access_log_format, None);
+
+        let config = LogConfig::production().access_log(AccessLogFormat::Combined);
+        assert_eq!(config.access_log_format, Some(AccessLogFormat::Combined));
+    }
+
+    /// In-memory writer capturing formatted log output.
+    #[derive(Clone, Default)]
+    struct CapturedOutput(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl CapturedOutput {
+        fn contents(&self) -> String {
+            let bytes = self.0.lock().unwrap().clone();
+            strip_ansi(&String::from_utf8(bytes).unwrap())
+        }
+    }
+
+    impl std::io::Write for CapturedOutput {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for CapturedOutput {
+        type Writer = Self;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    /// Removes ANSI color sequences from formatted output.
+    fn strip_ansi(s: &str) -> String {
+        let mut out = String::with_capacity(s.len());
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            if c == '\u{1b}' {
+                for c in chars.by_ref() {
+                    if c.is_ascii_alphabetic() {
+                        break;
+                    }
+                }
+            } else {
+                out.push(c);
+            }
+        }
+        out
+    }
+
+    fn capture(format: LogFormat) -> String {
+        let output = CapturedOutput::default();
+        let subscriber = tracing_subscriber::registry().with(fmt_layer(
+            &LogConfig::default(),
+            format,
+            output.clone(),
+        ));
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(
+                request_id = "req-1",
+                operation_id = "getUser",
+                "Request completed"
+            );
+        });
+
+        output.contents()
+    }
+
+    #[test]
+    fn test_json_format_keys() {
+        let output = capture(LogFormat::Json);
+        let line: serde_json::Value = serde_json::from_str(output.trim()).unwrap();
+
+        assert!(line.get("timestamp").is_some());
+        assert_eq!(line["level"], "INFO");
+        assert!(line.get("target").is_some());
+        assert_eq!(line["fields"]["message"], "Request completed");
+        assert_eq!(line["fields"]["request_id"], "req-1");
+        assert_eq!(line["fields"]["operation_id"], "getUser");
+    }
+
+    #[test]
+    fn test_pretty_format_is_human_readable() {
+        let output = capture(LogFormat::Pretty);
+
+        assert!(!output.trim_start().starts_with('{'));
+        assert!(output.contains("INFO"));
+        assert!(output.contains("Request completed"));
+        assert!(output.lines().count() > 1);
+    }
+
+    #[test]
+    fn test_formats_capture_same_fields() {
+        for format in [LogFormat::Json, LogFormat::Pretty, LogFormat::Compact] {
+            let output = capture(format);
+            for expected in ["request_id", "req-1", "operation_id", "getUser"] {
+                assert!(
+                    output.contains(expected),
+                    "{format} output is missing {expected}: {output}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_otlp_layer_exports_events() {
+        let exporter = opentelemetry_sdk::testing::logs::InMemoryLogExporter::default();
+        let provider = LoggerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+
+        let subscriber = tracing_subscriber::registry().with(otlp_layer(&provider));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(request_id = "req-1", "Request completed");
+            tracing::info!(target: "opentelemetry_sdk", "exporter internals");
+        });
+
+        let logs = exporter.get_emitted_logs().unwrap();
+        assert_eq!(logs.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_otlp_provider_built_when_configured() {
+        let config = LogConfig {
+            format: Some(LogFormat::Otlp),
+            otlp_endpoint: Some("http://127.0.0.1:4317".to_string()),
+            ..LogConfig::default()
+        };
+
+        assert!(otlp_log_provider(&config).is_ok());
+    }
 }
@@ -9,6 +9,11 @@
 //! | `archimedes_requests_total` | Counter | `operation`, `status` | Total requests |
 //! | `archimedes_request_duration_seconds` | Histogram | `operation` | Request latency |
 //! | `archimedes_in_flight_requests` | Gauge | - | In-flight requests |
+//! | `archimedes_open_connections` | Gauge | - | Open client connections |
+//! | `archimedes_request_phase_duration_seconds` | Histogram | `operation`, `phase` | Time per request phase, with detailed timing |
+//!
+//! Process and Tokio runtime metrics are opt-in with
+//! [`MetricsConfig::process_metrics`]; see [`process`](crate::process).
 //!
 //! # Example
 //!
@@ -18,14 +23,33 @@
 //! // Record a completed request
 //! record_request("getUser", 200, Duration::from_millis(45));
 //! ```
+//!
+//! # Snapshots
+//!
+//! [`MetricsRegistry::snapshot`] reads the current metric values in
+//! process, which lets tests assert on metrics without scraping the
+//! `/metrics` endpoint:
+//!
+//! ```rust,ignore
+//! let snapshot = registry.snapshot();
+//! assert_eq!(
+//!     snapshot.counter_value("archimedes_requests_total", &[("operation", "getUser"), ("status", "200")]),
+//!     Some(1),
+//! );
+//! ```
 
+use crate::admin::AdminApi;
+use crate::endpoint::{MetricsAuthConfig, MetricsEndpoint};
 use crate::error::TelemetryError;
+use crate::process::{spawn_process_metrics, DEFAULT_PROCESS_METRICS_INTERVAL};
 use crate::TelemetryResult;
 use metrics::{counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram};
 use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use std::collections::{BTreeMap, HashMap};
 use std::net::SocketAddr;
 use std::sync::OnceLock;
 use std::time::Duration;
+use tokio::runtime::Handle;
 
 /// Global metrics handle for rendering.
 static METRICS_HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
@@ -44,6 +68,13 @@ pub struct MetricsConfig {
 
     /// Histogram buckets for request duration.
     pub duration_buckets: Vec<f64>,
+
+    /// Whether to expose process and Tokio runtime metrics; see
+    /// [`process`](crate::process).
+    pub process_metrics: bool,
+
+    /// Credentials required to scrape; see [`endpoint`](crate::endpoint).
+    pub auth: MetricsAuthConfig,
 }
 
 impl Default for MetricsConfig {
@@ -56,6 +87,8 @@ impl Default for MetricsConfig {
             duration_buckets: vec![
                 0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
             ],
+            process_metrics: false,
+            auth: MetricsAuthConfig::default(),
         }
     }
 }
@@ -80,21 +113,247 @@ impl MetricsRegistry {
     pub fn render(&self) -> String {
         self.handle.render()
     }
+
+    /// Takes a snapshot of the current metric values.
+    ///
+    /// All values are read in a single pass over the registry, so the
+    /// snapshot is a point-in-time view that later recordings do not
+    /// change.
+    #[must_use]
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot::parse(&self.handle.render())
+    }
+
+    /// Returns the current value of a counter.
+    ///
+    /// Shorthand for `snapshot().counter_value(name, labels)`.
+    #[must_use]
+    pub fn counter_value(&self, name: &str, labels: &[(&str, &str)]) -> Option<u64> {
+        self.snapshot().counter_value(name, labels)
+    }
+}
+
+/// A metric identified by name and labels.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MetricKey {
+    /// Metric name.
+    pub name: String,
+    /// Labels, sorted by name.
+    pub labels: BTreeMap<String, String>,
+}
+
+impl MetricKey {
+    /// Creates a key from a name and label pairs.
+    #[must_use]
+    pub fn new(name: &str, labels: &[(&str, &str)]) -> Self {
+        Self {
+            name: name.to_string(),
+            labels: labels
+                .iter()
+                .map(|(name, value)| ((*name).to_string(), (*value).to_string()))
+                .collect(),
+        }
+    }
+}
+
+/// Observations recorded by a histogram (or summary).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HistogramSnapshot {
+    /// Number of observations.
+    pub count: u64,
+    /// Sum of all observations.
+    pub sum: f64,
+}
+
+/// Point-in-time values of all recorded metrics.
+///
+/// Labels are matched exactly: a lookup must give every label of the
+/// metric, in any order.
+#[derive(Debug, Clone, Default)]
+pub struct MetricsSnapshot {
+    /// Counter values.
+    pub counters: HashMap<MetricKey, u64>,
+    /// Gauge values.
+    pub gauges: HashMap<MetricKey, f64>,
+    /// Histogram observation counts and sums.
+    pub histograms: HashMap<MetricKey, HistogramSnapshot>,
+}
+
+impl MetricsSnapshot {
+    /// Returns the value of a counter.
+    #[must_use]
+    pub fn counter_value(&self, name: &str, labels: &[(&str, &str)]) -> Option<u64> {
+        self.counters.get(&MetricKey::new(name, labels)).copied()
+    }
+
+    /// Returns the value of a gauge.
+    #[must_use]
+    pub fn gauge_value(&self, name: &str, labels: &[(&str, &str)]) -> Option<f64> {
+        self.gauges.get(&MetricKey::new(name, labels)).copied()
+    }
+
+    /// Returns the observation count and sum of a histogram.
+    #[must_use]
+    pub fn histogram(&self, name: &str, labels: &[(&str, &str)]) -> Option<HistogramSnapshot> {
+        self.histograms.get(&MetricKey::new(name, labels)).copied()
+    }
+
+    /// Parses a Prometheus text exposition.
+    fn parse(text: &str) -> Self {
+        let mut snapshot = Self::default();
+        let mut types: HashMap<&str, &str> = HashMap::new();
+
+        for line in text.lines().map(str::trim) {
+            if let Some(declaration) = line.strip_prefix("# TYPE ") {
+                if let Some((name, kind)) = declaration.split_once(' ') {
+                    types.insert(name, kind.trim());
+                }
+                continue;
+            }
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((name, labels, value)) = parse_sample(line) else {
+                continue;
+            };
+
+            match types.get(name.as_str()).copied() {
+                Some("counter") => {
+                    if let Ok(value) = value.parse::<u64>() {
+                        snapshot.counters.insert(MetricKey { name, labels }, value);
+                    }
+                }
+                Some("gauge") => {
+                    if let Ok(value) = value.parse::<f64>() {
+                        snapshot.gauges.insert(MetricKey { name, labels }, value);
+                    }
+                }
+                _ => snapshot.record_histogram_sample(&types, &name, labels, value),
+            }
+        }
+
+        snapshot
+    }
+
+    /// Records the `_count` and `_sum` samples of histograms and summaries.
+    fn record_histogram_sample(
+        &mut self,
+        types: &HashMap<&str, &str>,
+        name: &str,
+        labels: BTreeMap<String, String>,
+        value: &str,
+    ) {
+        let is_histogram =
+            |base: &str| matches!(types.get(base).copied(), Some("histogram" | "summary"));
+        if let Some(base) = name
+            .strip_suffix("_count")
+            .filter(|base| is_histogram(base))
+        {
+            let key = MetricKey {
+                name: base.to_string(),
+                labels,
+            };
+            self.histograms.entry(key).or_default().count = value.parse().unwrap_or(0);
+        } else if let Some(base) = name.strip_suffix("_sum").filter(|base| is_histogram(base)) {
+            let key = MetricKey {
+                name: base.to_string(),
+                labels,
+            };
+            self.histograms.entry(key).or_default().sum = value.parse().unwrap_or(0.0);
+        }
+    }
+}
+
+/// Parses a sample line such as `name{label="value"} 1` into its name,
+/// labels and value.
+fn parse_sample(line: &str) -> Option<(String, BTreeMap<String, String>, &str)> {
+    let mut labels = BTreeMap::new();
+    let (name, rest) = match line.find(['{', ' ']) {
+        Some(i) if line.as_bytes()[i] == b'{' => {
+            let mut rest = &line[i + 1..];
+            loop {
+                rest = rest.trim_start_matches([',', ' ']);
+                if let Some(after) = rest.strip_prefix('}') {
+                    rest = after;
+                    break;
+                }
+                let (label, after) = rest.split_once("=\"")?;
+                let (value, after) = parse_label_value(after)?;
+                labels.insert(label.trim().to_string(), value);
+                rest = after;
+            }
+            (&line[..i], rest)
+        }
+        Some(i) => (&line[..i], &line[i..]),
+        None => return None,
+    };
+    // A timestamp may follow the value
+    let value = rest.split_whitespace().next()?;
+    Some((name.to_string(), labels, value))
+}
+
+/// Parses an escaped label value up to its closing quote, returning the
+/// value and the rest of the line.
+fn parse_label_value(text: &str) -> Option<(String, &str)> {
+    let mut value = String::new();
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Some((value, &text[i + 1..])),
+            '\\' => match chars.next()?.1 {
+                'n' => value.push('\n'),
+                escaped => value.push(escaped),
+            },
+            c => value.push(c),
+        }
+    }
+    None
 }
 
 /// Initializes the metrics subsystem.
 ///
+/// Inside a Tokio runtime this also binds [`MetricsConfig::addr`] and serves
+/// `/metrics` and `/health` there; see [`MetricsEndpoint`]. Outside one,
+/// metrics are only available through [`render_metrics`].
+///
+/// With [`MetricsConfig::process_metrics`] set, this must be called from
+/// within a Tokio runtime, which the metrics refresh task is spawned on.
+///
 /// # Arguments
 ///
 /// * `config` - Metrics configuration
 ///
 /// # Errors
 ///
-/// Returns `TelemetryError::MetricsInit` if initialization fails.
+/// Returns `TelemetryError::MetricsInit` if initialization fails, or if
+/// process metrics are enabled outside a Tokio runtime, and
+/// `TelemetryError::Io` if the metrics address cannot be bound.
 pub fn init_metrics(config: &MetricsConfig) -> TelemetryResult<()> {
+    install_metrics(config, None)
+}
+
+/// Initializes the metrics subsystem and serves `admin` on the metrics
+/// listener.
+///
+/// The admin API is only served inside a Tokio runtime, like the rest of
+/// the listener; see [`crate::admin`].
+///
+/// # Errors
+///
+/// Returns the same errors as [`init_metrics`].
+pub fn init_metrics_with_admin(config: &MetricsConfig, admin: AdminApi) -> TelemetryResult<()> {
+    install_metrics(config, Some(admin))
+}
+
+fn install_metrics(config: &MetricsConfig, admin: Option<AdminApi>) -> TelemetryResult<()> {
     if !config.enabled {
         return Ok(());
     }
+    if config.process_metrics && Handle::try_current().is_err() {
+        return Err(TelemetryError::MetricsInit(
+            "process metrics must be initialized within a Tokio runtime".to_string(),
+        ));
+    }
 
     // Parse address
     let addr: SocketAddr = config
@@ -102,21 +361,36 @@ pub fn init_metrics(config: &MetricsConfig) -> TelemetryResult<()> {
         .parse()
         .map_err(|e| TelemetryError::InvalidAddress(format!("{}: {e}", config.addr)))?;
 
-    // Build Prometheus exporter
-    let builder = PrometheusBuilder::new();
-
     // Install the recorder
-    let handle = builder
-        .with_http_listener(addr)
+    let handle = PrometheusBuilder::new()
         .install_recorder()
         .map_err(|e| TelemetryError::MetricsInit(e.to_string()))?;
 
+    // Serve the metrics listener
+    if let Ok(runtime) = Handle::try_current() {
+        let listener = std::net::TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        let listener = tokio::net::TcpListener::from_std(listener)?;
+        let render_handle = handle.clone();
+        let mut endpoint =
+            MetricsEndpoint::new(move || render_handle.render()).with_auth(config.auth.clone());
+        if let Some(admin) = admin {
+            endpoint = endpoint.with_admin(admin);
+        }
+        runtime.spawn(endpoint.serve(listener));
+    }
+
     // Store handle for later access
     let _ = METRICS_HANDLE.set(handle);
 
     // Register metric descriptions
     register_metric_descriptions();
 
+    // Refresh process and runtime metrics in the background
+    if config.process_metrics {
+        spawn_process_metrics(DEFAULT_PROCESS_METRICS_INTERVAL);
+    }
+
     Ok(())
 }
 
@@ -147,12 +421,24 @@ fn register_metric_descriptions() {
         "HTTP request duration in seconds"
     );
 
+    // Per-phase duration histogram, recorded with detailed timing
+    describe_histogram!(
+        "archimedes_request_phase_duration_seconds",
+        "Time spent in each phase of a request in seconds"
+    );
+
     // In-flight requests gauge
     describe_gauge!(
         "archimedes_in_flight_requests",
         "Number of HTTP requests currently being processed"
     );
 
+    // Open connections gauge
+    describe_gauge!(
+        "archimedes_open_connections",
+        "Number of client connections currently open"
+    );
+
     // Request size histogram
     describe_histogram!(
         "archimedes_request_size_bytes",
@@ -220,6 +506,14 @@ pub fn decrement_in_flight() {
     gauge!("archimedes_in_flight_requests").decrement(1.0);
 }
 
+/// Sets the open connections gauge.
+///
+/// Typically fed from the server's connection tracker
+/// (`Server::connections().active_connections()`).
+pub fn set_open_connections(count: usize) {
+    gauge!("archimedes_open_connections").set(count as f64);
+}
+
 /// Records request body size.
 ///
 /// # Arguments
@@ -316,6 +610,7 @@ mod tests {
         assert!(config.enabled);
         assert_eq!(config.addr, "0.0.0.0:9090");
         assert!(!config.duration_buckets.is_empty());
+        assert!(!config.process_metrics);
     }
 
     #[test]
@@ -333,6 +628,7 @@ mod tests {
         record_response_size("test", 2048);
         record_authz_decision(true, "allowed");
         record_validation_failure("request", "missing_field");
+        set_open_connections(3);
     }
 
     #[test]
@@ -342,11 +638,85 @@ mod tests {
             addr: "127.0.0.1:8080".to_string(),
             service_name: "test".to_string(),
             duration_buckets: vec![0.1, 0.5, 1.0],
+            process_metrics: true,
+            auth: MetricsAuthConfig::default(),
         };
         assert_eq!(config.addr, "127.0.0.1:8080");
         assert_eq!(config.duration_buckets.len(), 3);
     }
 
+    #[test]
+    fn test_snapshot_reads_recorded_request() {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let registry = MetricsRegistry::new(recorder.handle());
+
+        metrics::with_local_recorder(&recorder, || {
+            record_request("getUser", 200, Duration::from_millis(10));
+            record_request("getUser", 200, Duration::from_millis(30));
+            record_request("getUser", 404, Duration::from_millis(5));
+            set_open_connections(2);
+        });
+
+        let snapshot = registry.snapshot();
+        let ok = [("operation", "getUser"), ("status", "200")];
+        assert_eq!(
+            snapshot.counter_value("archimedes_requests_total", &ok),
+            Some(2)
+        );
+        // Label order does not matter, but every label must be given
+        assert_eq!(
+            snapshot.counter_value(
+                "archimedes_requests_total",
+                &[("status", "404"), ("operation", "getUser")]
+            ),
+            Some(1)
+        );
+        assert_eq!(
+            snapshot.counter_value("archimedes_requests_total", &[("operation", "getUser")]),
+            None
+        );
+        assert_eq!(
+            snapshot.gauge_value("archimedes_open_connections", &[]),
+            Some(2.0)
+        );
+        let duration = snapshot
+            .histogram(
+                "archimedes_request_duration_seconds",
+                &[("operation", "getUser")],
+            )
+            .unwrap();
+        assert_eq!(duration.count, 3);
+        assert!((duration.sum - 0.045).abs() < 1e-9);
+
+        // Later recordings do not change an existing snapshot
+        metrics::with_local_recorder(&recorder, || {
+            record_request("getUser", 200, Duration::from_millis(10));
+        });
+        assert_eq!(
+            snapshot.counter_value("archimedes_requests_total", &ok),
+            Some(2)
+        );
+        assert_eq!(
+            registry.counter_value("archimedes_requests_total", &ok),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn test_parse_sample_escapes() {
+        let (name, labels, value) =
+            parse_sample(r#"errors_total{path="/a\"b\\c",kind="x"} 7 1700000000"#).unwrap();
+        assert_eq!(name, "errors_total");
+        assert_eq!(labels["path"], r#"/a"b\c"#);
+        assert_eq!(labels["kind"], "x");
+        assert_eq!(value, "7");
+
+        let (name, labels, value) = parse_sample("in_flight 3").unwrap();
+        assert_eq!(name, "in_flight");
+        assert!(labels.is_empty());
+        assert_eq!(value, "3");
+    }
+
     #[test]
     fn test_render_metrics_without_init() {
         // Should return None when not initialized
@@ -0,0 +1,277 @@
+//! Process and Tokio runtime metrics.
+//!
+//! Enabled with [`MetricsConfig::process_metrics`](crate::MetricsConfig),
+//! these gauges are refreshed in the background and exposed on `/metrics`
+//! next to the request metrics.
+//!
+//! # Process Metrics
+//!
+//! | Metric | Description |
+//! |--------|-------------|
+//! | `process_resident_memory_bytes` | Resident set size |
+//! | `process_virtual_memory_bytes` | Virtual memory size |
+//! | `process_cpu_seconds_total` | User and system CPU time |
+//! | `process_open_fds` | Open file descriptors |
+//! | `process_max_fds` | Soft limit on open file descriptors |
+//!
+//! Process metrics are read from `/proc` and are only available on Linux;
+//! elsewhere they are omitted.
+//!
+//! # Runtime Metrics
+//!
+//! | Metric | Description |
+//! |--------|-------------|
+//! | `tokio_workers` | Worker threads of the runtime |
+//! | `tokio_busy_workers` | Average number of busy workers since the last collection |
+//! | `tokio_alive_tasks` | Tasks that have been spawned and not yet completed |
+//! | `tokio_global_queue_depth` | Tasks waiting in the runtime's global queue |
+//!
+//! Runtime metrics describe the runtime the collector runs on and are
+//! omitted when collected outside one.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use archimedes_telemetry::process::ProcessMetrics;
+//!
+//! let process = ProcessMetrics::new();
+//! process.collect();
+//! ```
+
+use metrics::{describe_gauge, gauge};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tokio::runtime::Handle;
+use tokio::task::JoinHandle;
+
+/// How often [`spawn_process_metrics`] refreshes the gauges by default.
+pub const DEFAULT_PROCESS_METRICS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Collects process and runtime metrics into the installed recorder.
+#[derive(Debug, Default)]
+pub struct ProcessMetrics {
+    /// Wall time and total worker busy time at the previous collection.
+    last_busy: Mutex<Option<(Instant, Duration)>>,
+}
+
+impl ProcessMetrics {
+    /// Creates a collector.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers descriptions for the process and runtime metrics.
+    pub fn describe() {
+        describe_gauge!(
+            "process_resident_memory_bytes",
+            "Resident memory size in bytes"
+        );
+        describe_gauge!(
+            "process_virtual_memory_bytes",
+            "Virtual memory size in bytes"
+        );
+        describe_gauge!(
+            "process_cpu_seconds_total",
+            "Total user and system CPU time spent in seconds"
+        );
+        describe_gauge!("process_open_fds", "Number of open file descriptors");
+        describe_gauge!("process_max_fds", "Maximum number of open file descriptors");
+        describe_gauge!("tokio_workers", "Number of Tokio worker threads");
+        describe_gauge!(
+            "tokio_busy_workers",
+            "Average number of busy Tokio workers since the last collection"
+        );
+        describe_gauge!(
+            "tokio_alive_tasks",
+            "Number of Tokio tasks spawned and not yet completed"
+        );
+        describe_gauge!(
+            "tokio_global_queue_depth",
+            "Number of tasks in the Tokio global queue"
+        );
+    }
+
+    /// Reads the current values and sets the gauges.
+    ///
+    /// Metrics the platform or context cannot provide are skipped.
+    pub fn collect(&self) {
+        let stats = read_process_stats();
+        if let Some(bytes) = stats.resident_memory_bytes {
+            gauge!("process_resident_memory_bytes").set(bytes as f64);
+        }
+        if let Some(bytes) = stats.virtual_memory_bytes {
+            gauge!("process_virtual_memory_bytes").set(bytes as f64);
+        }
+        if let Some(seconds) = stats.cpu_seconds {
+            gauge!("process_cpu_seconds_total").set(seconds);
+        }
+        if let Some(count) = stats.open_fds {
+            gauge!("process_open_fds").set(count as f64);
+        }
+        if let Some(count) = stats.max_fds {
+            gauge!("process_max_fds").set(count as f64);
+        }
+
+        if let Ok(runtime) = Handle::try_current() {
+            self.collect_runtime(&runtime);
+        }
+    }
+
+    fn collect_runtime(&self, runtime: &Handle) {
+        let metrics = runtime.metrics();
+        let workers = metrics.num_workers();
+        gauge!("tokio_workers").set(workers as f64);
+        gauge!("tokio_alive_tasks").set(metrics.num_alive_tasks() as f64);
+        gauge!("tokio_global_queue_depth").set(metrics.global_queue_depth() as f64);
+
+        let busy: Duration = (0..workers)
+            .map(|worker| metrics.worker_total_busy_duration(worker))
+            .sum();
+        let now = Instant::now();
+        let mut last_busy = self
+            .last_busy
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some((then, busy_then)) = last_busy.replace((now, busy)) {
+            let elapsed = now.duration_since(then).as_secs_f64();
+            if elapsed > 0.0 {
+                let busy_workers = busy.saturating_sub(busy_then).as_secs_f64() / elapsed;
+                gauge!("tokio_busy_workers").set(busy_workers);
+            }
+        }
+    }
+}
+
+/// Spawns a task on the current runtime that refreshes the process and
+/// runtime metrics every `interval`.
+///
+/// # Panics
+///
+/// Panics if called outside a Tokio runtime.
+pub fn spawn_process_metrics(interval: Duration) -> JoinHandle<()> {
+    ProcessMetrics::describe();
+    let process = ProcessMetrics::new();
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            process.collect();
+        }
+    })
+}
+
+/// Process statistics; fields the platform does not provide are `None`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct ProcessStats {
+    resident_memory_bytes: Option<u64>,
+    virtual_memory_bytes: Option<u64>,
+    cpu_seconds: Option<f64>,
+    open_fds: Option<u64>,
+    max_fds: Option<u64>,
+}
+
+#[cfg(target_os = "linux")]
+fn read_process_stats() -> ProcessStats {
+    use std::fs;
+
+    let status = fs::read_to_string("/proc/self/status").unwrap_or_default();
+    let stat = fs::read_to_string("/proc/self/stat").unwrap_or_default();
+    let limits = fs::read_to_string("/proc/self/limits").unwrap_or_default();
+
+    ProcessStats {
+        resident_memory_bytes: status_kib(&status, "VmRSS:").map(|kib| kib * 1024),
+        virtual_memory_bytes: status_kib(&status, "VmSize:").map(|kib| kib * 1024),
+        cpu_seconds: cpu_seconds(&stat),
+        open_fds: fs::read_dir("/proc/self/fd")
+            .ok()
+            .map(|entries| entries.count() as u64),
+        max_fds: max_open_files(&limits),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_process_stats() -> ProcessStats {
+    ProcessStats::default()
+}
+
+/// Reads a `kB` value such as `VmRSS:     1234 kB` from `/proc/self/status`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn status_kib(status: &str, key: &str) -> Option<u64> {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix(key))
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|value| value.parse().ok())
+}
+
+/// Reads user plus system CPU time from `/proc/self/stat`.
+///
+/// The kernel reports both in clock ticks of `USER_HZ`, which is 100 on
+/// every Linux architecture Tokio supports.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn cpu_seconds(stat: &str) -> Option<f64> {
+    const USER_HZ: f64 = 100.0;
+
+    // The command name may contain spaces, so count fields after its `)`;
+    // `utime` and `stime` are fields 14 and 15, the state is field 3
+    let mut fields = stat.rsplit_once(')')?.1.split_whitespace().skip(11);
+    let utime: u64 = fields.next()?.parse().ok()?;
+    let stime: u64 = fields.next()?.parse().ok()?;
+    Some((utime + stime) as f64 / USER_HZ)
+}
+
+/// Reads the soft limit on open files from `/proc/self/limits`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn max_open_files(limits: &str) -> Option<u64> {
+    limits
+        .lines()
+        .find_map(|line| line.strip_prefix("Max open files"))
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|value| value.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metrics_exporter_prometheus::PrometheusBuilder;
+
+    #[test]
+    fn test_parse_proc_files() {
+        let status = "Name:\tserver\nVmSize:\t  204800 kB\nVmRSS:\t   10240 kB\n";
+        assert_eq!(status_kib(status, "VmRSS:"), Some(10240));
+        assert_eq!(status_kib(status, "VmSize:"), Some(204_800));
+        assert_eq!(status_kib(status, "VmSwap:"), None);
+
+        let stat = "4242 (my server) S 1 4242 4242 0 -1 4194560 900 0 0 0 250 50 0 0 20 0";
+        assert_eq!(cpu_seconds(stat), Some(3.0));
+        assert_eq!(cpu_seconds("garbage"), None);
+
+        let limits = "Limit                     Soft Limit           Hard Limit           Units\n\
+                      Max open files            1024                 524288               files\n";
+        assert_eq!(max_open_files(limits), Some(1024));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_collect_exposes_process_and_runtime_metrics() {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+        let process = ProcessMetrics::new();
+
+        metrics::with_local_recorder(&recorder, || {
+            ProcessMetrics::describe();
+            process.collect();
+            process.collect();
+        });
+
+        let exposition = handle.render();
+        #[cfg(target_os = "linux")]
+        {
+            assert!(exposition.contains("process_resident_memory_bytes"));
+            assert!(exposition.contains("process_open_fds"));
+        }
+        assert!(exposition.contains("tokio_workers 2"));
+        assert!(exposition.contains("tokio_alive_tasks"));
+        assert!(exposition.contains("tokio_busy_workers"));
+    }
+}
@@ -0,0 +1,445 @@
+//! Trace sampling strategies.
+//!
+//! This module provides the samplers used by [`init_tracing`](crate::init_tracing):
+//!
+//! - **Always on / off**: sample every trace or none
+//! - **Trace ID ratio**: sample a fixed fraction of traces
+//! - **Parent based**: follow the upstream sampling decision, using a ratio
+//!   for root spans
+//! - **Rate limiting**: sample at most N traces per second
+//!
+//! In addition, errored requests can always be exported regardless of the
+//! sampling decision. This works by *recording* every span the strategy
+//! would have dropped and letting [`ErrorSamplingProcessor`] export only
+//! those that are sampled or ended in error.
+//!
+//! # Upstream `traceparent` Sampling
+//!
+//! The W3C `traceparent` header carries a `sampled` flag (`-01` suffix).
+//!
+//! - [`SamplingStrategy::ParentBased`] honors that flag: a sampled parent
+//!   always produces a sampled child and an unsampled parent never does.
+//!   The ratio only applies to requests arriving without a parent.
+//! - [`SamplingStrategy::TraceIdRatio`] and [`SamplingStrategy::RateLimiting`]
+//!   ignore the flag and make an independent decision, which may break
+//!   traces that span several services.
+//! - With error sampling enabled, an errored request is exported even when
+//!   its parent was not sampled, so the trace may be incomplete upstream.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use archimedes_telemetry::{SamplingStrategy, TracingConfig};
+//!
+//! let config = TracingConfig {
+//!     sampling: Some(SamplingStrategy::ParentBased(0.05)),
+//!     sample_errors: true,
+//!     ..TracingConfig::default()
+//! };
+//! ```
+
+use opentelemetry::trace::{
+    Link, SamplingDecision, SamplingResult, SpanContext, SpanKind, Status, TraceContextExt,
+    TraceId, TraceResult,
+};
+use opentelemetry::{Context, KeyValue, Value};
+use opentelemetry_sdk::export::trace::SpanData;
+use opentelemetry_sdk::trace::{Sampler, ShouldSample, Span, SpanProcessor};
+use opentelemetry_sdk::Resource;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Span attributes holding the HTTP response status code.
+const STATUS_CODE_ATTRIBUTES: [&str; 2] = ["http.response.status_code", "http.status_code"];
+
+/// Sampling strategy for traces.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SamplingStrategy {
+    /// Sample every trace.
+    AlwaysOn,
+    /// Sample no traces.
+    AlwaysOff,
+    /// Sample a fraction of traces based on the trace ID (0.0 to 1.0).
+    TraceIdRatio(f64),
+    /// Follow the parent's sampling decision; sample root spans by ratio.
+    ParentBased(f64),
+    /// Sample at most the given number of traces per second.
+    RateLimiting(f64),
+}
+
+impl SamplingStrategy {
+    /// Creates the strategy equivalent to a plain sampling ratio.
+    #[must_use]
+    pub fn from_ratio(ratio: f64) -> Self {
+        if ratio >= 1.0 {
+            Self::AlwaysOn
+        } else if ratio <= 0.0 {
+            Self::AlwaysOff
+        } else {
+            Self::TraceIdRatio(ratio)
+        }
+    }
+
+    /// Builds the OpenTelemetry sampler for this strategy.
+    fn build(&self) -> Box<dyn ShouldSample> {
+        match self {
+            Self::AlwaysOn => Box::new(Sampler::AlwaysOn),
+            Self::AlwaysOff => Box::new(Sampler::AlwaysOff),
+            Self::TraceIdRatio(ratio) => Box::new(Sampler::TraceIdRatioBased(*ratio)),
+            Self::ParentBased(ratio) => Box::new(Sampler::ParentBased(Box::new(
+                Sampler::TraceIdRatioBased(*ratio),
+            ))),
+            Self::RateLimiting(per_second) => Box::new(RateLimitingSampler::new(*per_second)),
+        }
+    }
+}
+
+/// Sampler applying a [`SamplingStrategy`].
+///
+/// When `record_unsampled` is enabled, spans the strategy would drop are
+/// recorded instead so that [`ErrorSamplingProcessor`] can export them if
+/// the request errors.
+#[derive(Debug, Clone)]
+pub struct StrategySampler {
+    inner: Box<dyn ShouldSample>,
+    record_unsampled: bool,
+}
+
+impl StrategySampler {
+    /// Creates a sampler for the given strategy.
+    #[must_use]
+    pub fn new(strategy: &SamplingStrategy) -> Self {
+        Self {
+            inner: strategy.build(),
+            record_unsampled: false,
+        }
+    }
+
+    /// Records spans that would otherwise be dropped.
+    #[must_use]
+    pub fn record_unsampled(mut self, enabled: bool) -> Self {
+        self.record_unsampled = enabled;
+        self
+    }
+}
+
+impl ShouldSample for StrategySampler {
+    fn should_sample(
+        &self,
+        parent_context: Option<&Context>,
+        trace_id: TraceId,
+        name: &str,
+        span_kind: &SpanKind,
+        attributes: &[KeyValue],
+        links: &[Link],
+    ) -> SamplingResult {
+        let mut result =
+            self.inner
+                .should_sample(parent_context, trace_id, name, span_kind, attributes, links);
+        if self.record_unsampled && result.decision == SamplingDecision::Drop {
+            result.decision = SamplingDecision::RecordOnly;
+        }
+        result
+    }
+}
+
+/// Sampler that samples at most a fixed number of traces per second.
+///
+/// Uses a token bucket refilled continuously at the configured rate, with
+/// a burst capacity of one second's worth of traces.
+#[derive(Debug, Clone)]
+pub struct RateLimitingSampler {
+    per_second: f64,
+    bucket: Arc<Mutex<TokenBucket>>,
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimitingSampler {
+    /// Creates a sampler allowing `per_second` traces per second.
+    #[must_use]
+    pub fn new(per_second: f64) -> Self {
+        let per_second = per_second.max(0.0);
+        Self {
+            per_second,
+            bucket: Arc::new(Mutex::new(TokenBucket {
+                tokens: per_second,
+                last_refill: Instant::now(),
+            })),
+        }
+    }
+
+    /// Takes a token from the bucket if one is available.
+    fn try_acquire(&self) -> bool {
+        let mut bucket = self
+            .bucket
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.per_second).min(self.per_second);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl ShouldSample for RateLimitingSampler {
+    fn should_sample(
+        &self,
+        parent_context: Option<&Context>,
+        _trace_id: TraceId,
+        _name: &str,
+        _span_kind: &SpanKind,
+        _attributes: &[KeyValue],
+        _links: &[Link],
+    ) -> SamplingResult {
+        let decision = if self.try_acquire() {
+            SamplingDecision::RecordAndSample
+        } else {
+            SamplingDecision::Drop
+        };
+        SamplingResult {
+            decision,
+            attributes: Vec::new(),
+            trace_state: parent_context
+                .map(|cx| cx.span().span_context().trace_state().clone())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Span processor that exports sampled spans plus any errored span.
+///
+/// Pair this with a [`StrategySampler`] that records unsampled spans.
+/// Unsampled spans that ended with an error status, or with an HTTP
+/// status code of 500 or above, are marked as sampled and forwarded to
+/// the inner processor; the rest are discarded.
+#[derive(Debug)]
+pub struct ErrorSamplingProcessor<P> {
+    inner: P,
+}
+
+impl<P: SpanProcessor> ErrorSamplingProcessor<P> {
+    /// Wraps the processor that performs the actual export.
+    pub fn new(inner: P) -> Self {
+        Self { inner }
+    }
+
+    /// Returns true if the span represents a failed request.
+    fn is_errored(span: &SpanData) -> bool {
+        if matches!(span.status, Status::Error { .. }) {
+            return true;
+        }
+        span.attributes.iter().any(|kv| {
+            STATUS_CODE_ATTRIBUTES.contains(&kv.key.as_str())
+                && matches!(kv.value, Value::I64(code) if code >= 500)
+        })
+    }
+}
+
+impl<P: SpanProcessor> SpanProcessor for ErrorSamplingProcessor<P> {
+    fn on_start(&self, span: &mut Span, cx: &Context) {
+        self.inner.on_start(span, cx);
+    }
+
+    fn on_end(&self, mut span: SpanData) {
+        if span.span_context.is_sampled() {
+            self.inner.on_end(span);
+        } else if Self::is_errored(&span) {
+            let ctx = &span.span_context;
+            span.span_context = SpanContext::new(
+                ctx.trace_id(),
+                ctx.span_id(),
+                ctx.trace_flags().with_sampled(true),
+                ctx.is_remote(),
+                ctx.trace_state().clone(),
+            );
+            self.inner.on_end(span);
+        }
+    }
+
+    fn force_flush(&self) -> TraceResult<()> {
+        self.inner.force_flush()
+    }
+
+    fn shutdown(&self) -> TraceResult<()> {
+        self.inner.shutdown()
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.inner.set_resource(resource);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::trace::{
+        Span as _, SpanId, TraceFlags, TraceState, Tracer as _, TracerProvider as _,
+    };
+    use opentelemetry_sdk::trace::TracerProvider;
+
+    /// Processor collecting every span it is handed.
+    #[derive(Debug, Clone, Default)]
+    struct CollectingProcessor(Arc<Mutex<Vec<SpanData>>>);
+
+    impl CollectingProcessor {
+        fn names(&self) -> Vec<String> {
+            self.0
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|s| s.name.to_string())
+                .collect()
+        }
+    }
+
+    impl SpanProcessor for CollectingProcessor {
+        fn on_start(&self, _span: &mut Span, _cx: &Context) {}
+
+        fn on_end(&self, span: SpanData) {
+            if span.span_context.is_sampled() {
+                self.0.lock().unwrap().push(span);
+            }
+        }
+
+        fn force_flush(&self) -> TraceResult<()> {
+            Ok(())
+        }
+
+        fn shutdown(&self) -> TraceResult<()> {
+            Ok(())
+        }
+    }
+
+    fn provider(
+        strategy: &SamplingStrategy,
+        sample_errors: bool,
+    ) -> (TracerProvider, CollectingProcessor) {
+        let collector = CollectingProcessor::default();
+        let provider = TracerProvider::builder()
+            .with_sampler(StrategySampler::new(strategy).record_unsampled(sample_errors))
+            .with_span_processor(ErrorSamplingProcessor::new(collector.clone()))
+            .build();
+        (provider, collector)
+    }
+
+    fn remote_parent(sampled: bool) -> Context {
+        let flags = if sampled {
+            TraceFlags::SAMPLED
+        } else {
+            TraceFlags::default()
+        };
+        Context::new().with_remote_span_context(SpanContext::new(
+            TraceId::from_hex("4bf92f3577b34da6a3ce929d0e0e4736").unwrap(),
+            SpanId::from_hex("00f067aa0ba902b7").unwrap(),
+            flags,
+            true,
+            TraceState::default(),
+        ))
+    }
+
+    #[test]
+    fn test_from_ratio() {
+        assert_eq!(
+            SamplingStrategy::from_ratio(1.0),
+            SamplingStrategy::AlwaysOn
+        );
+        assert_eq!(
+            SamplingStrategy::from_ratio(0.0),
+            SamplingStrategy::AlwaysOff
+        );
+        assert_eq!(
+            SamplingStrategy::from_ratio(0.25),
+            SamplingStrategy::TraceIdRatio(0.25)
+        );
+    }
+
+    #[test]
+    fn test_errored_request_exported_despite_low_ratio() {
+        let (provider, collector) = provider(&SamplingStrategy::TraceIdRatio(0.0), true);
+        let tracer = provider.tracer("test");
+
+        let mut ok = tracer.start("ok_request");
+        ok.end();
+
+        let mut failed = tracer.start("failed_request");
+        failed.set_status(Status::error("handler failed"));
+        failed.end();
+
+        let mut server_error = tracer.start("server_error");
+        server_error.set_attribute(KeyValue::new("http.response.status_code", 503_i64));
+        server_error.end();
+
+        assert_eq!(collector.names(), vec!["failed_request", "server_error"]);
+    }
+
+    #[test]
+    fn test_errored_request_dropped_without_error_sampling() {
+        let (provider, collector) = provider(&SamplingStrategy::TraceIdRatio(0.0), false);
+        let tracer = provider.tracer("test");
+
+        let mut failed = tracer.start("failed_request");
+        failed.set_status(Status::error("handler failed"));
+        failed.end();
+
+        assert!(collector.names().is_empty());
+    }
+
+    #[test]
+    fn test_parent_sampled_request_is_honored() {
+        let (provider, collector) = provider(&SamplingStrategy::ParentBased(0.0), false);
+        let tracer = provider.tracer("test");
+
+        let mut sampled = tracer.start_with_context("sampled_parent", &remote_parent(true));
+        sampled.end();
+
+        let mut unsampled = tracer.start_with_context("unsampled_parent", &remote_parent(false));
+        unsampled.end();
+
+        let mut root = tracer.start("root");
+        root.end();
+
+        assert_eq!(collector.names(), vec!["sampled_parent"]);
+    }
+
+    #[test]
+    fn test_rate_limiting_sampler() {
+        let sampler = RateLimitingSampler::new(2.0);
+        let trace_id = TraceId::from_hex("4bf92f3577b34da6a3ce929d0e0e4736").unwrap();
+        let decisions: Vec<SamplingDecision> = (0..3)
+            .map(|_| {
+                sampler
+                    .should_sample(None, trace_id, "op", &SpanKind::Server, &[], &[])
+                    .decision
+            })
+            .collect();
+
+        assert_eq!(
+            decisions,
+            vec![
+                SamplingDecision::RecordAndSample,
+                SamplingDecision::RecordAndSample,
+                SamplingDecision::Drop,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_strategy_sampler_records_unsampled() {
+        let sampler = StrategySampler::new(&SamplingStrategy::AlwaysOff).record_unsampled(true);
+        let trace_id = TraceId::from_hex("4bf92f3577b34da6a3ce929d0e0e4736").unwrap();
+        let result = sampler.should_sample(None, trace_id, "op", &SpanKind::Server, &[], &[]);
+        assert_eq!(result.decision, SamplingDecision::RecordOnly);
+    }
+}
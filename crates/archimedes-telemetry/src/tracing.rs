@@ -9,6 +9,7 @@
 //! - W3C Trace Context propagation
 //! - OTLP export (gRPC or HTTP)
 //! - Baggage propagation
+//! - Configurable sampling (see [`crate::sampling`])
 //!
 //! # Example
 //!
@@ -20,10 +21,11 @@
 //! ```
 
 use crate::error::TelemetryError;
+use crate::sampling::{ErrorSamplingProcessor, SamplingStrategy, StrategySampler};
 use crate::TelemetryResult;
 use opentelemetry::{global, KeyValue};
 use opentelemetry_otlp::WithExportConfig;
-use opentelemetry_sdk::trace::{RandomIdGenerator, Sampler, TracerProvider};
+use opentelemetry_sdk::trace::{BatchSpanProcessor, RandomIdGenerator, TracerProvider};
 use opentelemetry_sdk::Resource;
 
 /// Tracing configuration.
@@ -45,7 +47,15 @@ pub struct TracingConfig {
     pub environment: String,
 
     /// Sampling ratio (0.0 to 1.0).
+    ///
+    /// Ignored when `sampling` is set.
     pub sample_ratio: f64,
+
+    /// Sampling strategy, overriding `sample_ratio` when set.
+    pub sampling: Option<SamplingStrategy>,
+
+    /// Whether to export errored requests even when they were not sampled.
+    pub sample_errors: bool,
 }
 
 impl Default for TracingConfig {
@@ -57,6 +67,8 @@ impl Default for TracingConfig {
             service_version: "0.1.0".to_string(),
             environment: "development".to_string(),
             sample_ratio: 1.0, // Sample all traces by default in dev
+            sampling: None,
+            sample_errors: false,
         }
     }
 }
@@ -72,8 +84,18 @@ impl TracingConfig {
             service_version: version.to_string(),
             environment: "production".to_string(),
             sample_ratio: 0.1, // Sample 10% in production
+            sampling: None,
+            sample_errors: false,
         }
     }
+
+    /// Returns the effective sampling strategy.
+    #[must_use]
+    pub fn sampling_strategy(&self) -> SamplingStrategy {
+        self.sampling
+            .clone()
+            .unwrap_or_else(|| SamplingStrategy::from_ratio(self.sample_ratio))
+    }
 }
 
 /// Initializes the tracing subsystem.
@@ -114,18 +136,21 @@ pub fn init_tracing(config: &TracingConfig) -> TelemetryResult<Option<TracerProv
         .build()
         .map_err(|e| TelemetryError::TracingInit(e.to_string()))?;
 
-    // Build sampler based on ratio
-    let sampler = if config.sample_ratio >= 1.0 {
-        Sampler::AlwaysOn
-    } else if config.sample_ratio <= 0.0 {
-        Sampler::AlwaysOff
-    } else {
-        Sampler::TraceIdRatioBased(config.sample_ratio)
-    };
+    // Build sampler from the configured strategy. With error sampling,
+    // unsampled spans are recorded so errored ones can still be exported.
+    let sampler =
+        StrategySampler::new(&config.sampling_strategy()).record_unsampled(config.sample_errors);
 
     // Build tracer provider
-    let provider = TracerProvider::builder()
-        .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
+    let builder = TracerProvider::builder();
+    let builder = if config.sample_errors {
+        let batch =
+            BatchSpanProcessor::builder(exporter, opentelemetry_sdk::runtime::Tokio).build();
+        builder.with_span_processor(ErrorSamplingProcessor::new(batch))
+    } else {
+        builder.with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
+    };
+    let provider = builder
         .with_sampler(sampler)
         .with_id_generator(RandomIdGenerator::default())
         .with_resource(resource)
@@ -220,6 +245,19 @@ mod tests {
         assert!(config.enabled);
         assert_eq!(config.sample_ratio, 1.0);
         assert_eq!(config.environment, "development");
+        assert_eq!(config.sampling_strategy(), SamplingStrategy::AlwaysOn);
+    }
+
+    #[test]
+    fn test_sampling_overrides_ratio() {
+        let config = TracingConfig {
+            sampling: Some(SamplingStrategy::ParentBased(0.05)),
+            ..TracingConfig::production("my-service", "1.0.0")
+        };
+        assert_eq!(
+            config.sampling_strategy(),
+            SamplingStrategy::ParentBased(0.05)
+        );
     }
 
     #[test]
@@ -0,0 +1,98 @@
+//! Access log lines for requests through the telemetry stage.
+
+use std::sync::{Arc, Mutex};
+
+use archimedes_core::CallerIdentity;
+use archimedes_middleware::stages::TelemetryMiddleware;
+use archimedes_middleware::{MiddlewareContext, Pipeline};
+use archimedes_telemetry::{AccessLogFormat, AccessLogLayer};
+use bytes::Bytes;
+use http::StatusCode;
+use http_body_util::Full;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+
+/// In-memory writer capturing access log lines.
+#[derive(Clone, Default)]
+struct CapturedOutput(Arc<Mutex<Vec<u8>>>);
+
+impl CapturedOutput {
+    fn lines(&self) -> Vec<String> {
+        let bytes = self.0.lock().unwrap().clone();
+        String::from_utf8(bytes)
+            .unwrap()
+            .lines()
+            .map(ToString::to_string)
+            .collect()
+    }
+}
+
+impl std::io::Write for CapturedOutput {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for CapturedOutput {
+    type Writer = Self;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+#[tokio::test]
+async fn test_combined_line_for_sample_request() {
+    let output = CapturedOutput::default();
+    let subscriber = tracing_subscriber::registry().with(AccessLogLayer::new(
+        AccessLogFormat::Combined,
+        output.clone(),
+    ));
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let pipeline = Pipeline::builder()
+        .add_post_handler_stage(TelemetryMiddleware::new("test-service"))
+        .build();
+    let mut ctx = MiddlewareContext::new();
+    ctx.set_identity(CallerIdentity::user("user-123", "alice@example.com"));
+    let request = http::Request::builder()
+        .method("POST")
+        .uri("/users?invite=true")
+        .header(http::header::REFERER, "https://example.com/signup")
+        .header(http::header::USER_AGENT, "curl/8.0")
+        .body(Full::new(Bytes::from(r#"{"name":"alice"}"#)))
+        .unwrap();
+
+    let response = pipeline
+        .process(ctx, request, |_ctx, _req| {
+            Box::pin(async {
+                http::Response::builder()
+                    .status(StatusCode::CREATED)
+                    .body(Full::new(Bytes::from(r#"{"id":"123"}"#)))
+                    .unwrap()
+            })
+        })
+        .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let lines = output.lines();
+    assert_eq!(lines.len(), 1, "{lines:?}");
+    let line = &lines[0];
+
+    // - - user [dd/Mon/yyyy:HH:MM:SS +0000] "request" status bytes "referer" "agent" duration
+    let (prefix, rest) = line.split_once(" [").unwrap();
+    assert_eq!(prefix, "- - user:user-123");
+    let (time, rest) = rest.split_once("] ").unwrap();
+    assert_eq!(time.len(), "15/Oct/2026:09:30:00 +0000".len(), "{time}");
+    assert!(time.ends_with(" +0000"), "{time}");
+    let expected = "\"POST /users HTTP/1.1\" 201 12 \"https://example.com/signup\" \"curl/8.0\" ";
+    let duration = rest
+        .strip_prefix(expected)
+        .unwrap_or_else(|| panic!("{line}"));
+    assert!(duration.parse::<f64>().unwrap() >= 0.0, "{duration}");
+}
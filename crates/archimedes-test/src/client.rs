@@ -3,11 +3,13 @@
 use crate::error::TestError;
 use crate::request::{TestRequest, TestRequestBuilder};
 use crate::response::TestResponse;
+use archimedes_core::timing::{self, PhaseTimings, RequestTiming};
 use archimedes_middleware::context::MiddlewareContext;
 use archimedes_middleware::types::Response;
 use bytes::Bytes;
 use http::{Method, StatusCode};
 use http_body_util::Full;
+use hyper::body::Body;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
@@ -47,6 +49,8 @@ pub struct TestClient {
     handler: TestHandler,
     /// Default headers to add to all requests.
     default_headers: Vec<(String, String)>,
+    /// Whether to record phase timings for each request.
+    detailed_timing: bool,
 }
 
 impl TestClient {
@@ -62,6 +66,7 @@ impl TestClient {
         Self {
             handler: Arc::new(move |ctx, req| Box::pin(handler(ctx, req))),
             default_headers: Vec::new(),
+            detailed_timing: false,
         }
     }
 
@@ -107,6 +112,24 @@ impl TestClient {
         self
     }
 
+    /// Sends a Bearer token with every request.
+    ///
+    /// A token or Authorization header set on an individual request
+    /// replaces the default.
+    pub fn with_default_bearer(self, token: impl AsRef<str>) -> Self {
+        self.with_default_header("authorization", format!("Bearer {}", token.as_ref()))
+    }
+
+    /// Records phase timings for each request.
+    ///
+    /// The handler runs inside a timing scope, so handlers registered with a
+    /// `HandlerRegistry` and validation middleware record their phases, and
+    /// the response exposes them through [`TestResponse::phase_timings`].
+    pub fn with_detailed_timing(mut self) -> Self {
+        self.detailed_timing = true;
+        self
+    }
+
     /// Creates a GET request builder.
     pub fn get(&self, uri: impl AsRef<str>) -> TestClientRequest<'_> {
         TestClientRequest::new(self, TestRequest::get(uri))
@@ -151,7 +174,19 @@ impl TestClient {
     async fn send_internal(&self, request: TestRequest) -> Result<TestResponse, TestError> {
         let handler = Arc::clone(&self.handler);
         let ctx = MiddlewareContext::new();
-        let response = (handler)(ctx, request).await;
+        if !self.detailed_timing {
+            let response = (handler)(ctx, request).await;
+            return TestResponse::from_http(response).await;
+        }
+
+        let timing = RequestTiming::new();
+        timing.set_request_bytes(request.body.len() as u64);
+        let mut response = timing::scope(timing.clone(), (handler)(ctx, request)).await;
+        if response.extensions().get::<PhaseTimings>().is_none() {
+            let size = response.body().size_hint().exact().unwrap_or_default();
+            timing.set_response_bytes(size);
+            response.extensions_mut().insert(timing.snapshot());
+        }
         TestResponse::from_http(response).await
     }
 }
@@ -184,12 +219,30 @@ impl<'a> TestClientRequest<'a> {
         self
     }
 
+    /// Sets the Authorization header with a Bearer token.
+    pub fn bearer(mut self, token: impl AsRef<str>) -> Self {
+        self.builder = self.builder.bearer(token);
+        self
+    }
+
     /// Sets the Authorization header with a Bearer token.
     pub fn bearer_token(mut self, token: impl AsRef<str>) -> Self {
         self.builder = self.builder.bearer_token(token);
         self
     }
 
+    /// Sets an API key in the given header.
+    pub fn api_key(mut self, header_name: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+        self.builder = self.builder.api_key(header_name, value);
+        self
+    }
+
+    /// Sets the Authorization header with HTTP Basic credentials.
+    pub fn basic_auth(mut self, user: impl AsRef<str>, pass: impl AsRef<str>) -> Self {
+        self.builder = self.builder.basic_auth(user, pass);
+        self
+    }
+
     /// Sets the raw request body.
     pub fn body(mut self, body: impl Into<Bytes>) -> Self {
         self.builder = self.builder.body(body);
@@ -330,6 +383,96 @@ mod tests {
         assert_eq!(response.text().unwrap(), "default-value");
     }
 
+    #[tokio::test]
+    async fn test_request_bearer_overrides_default() {
+        let client = TestClient::new(|_ctx, req| async move {
+            let auth = req
+                .headers
+                .get("Authorization")
+                .map(|v| v.to_str().unwrap_or("none").to_string())
+                .unwrap_or_default();
+            http::Response::builder()
+                .status(StatusCode::OK)
+                .body(Full::new(Bytes::from(auth)))
+                .unwrap()
+        })
+        .with_default_bearer("default-token");
+
+        let response = client.get("/test").send().await;
+        assert_eq!(response.text().unwrap(), "Bearer default-token");
+
+        let response = client.get("/test").bearer("request-token").send().await;
+        assert_eq!(response.text().unwrap(), "Bearer request-token");
+
+        let response = client.get("/test").basic_auth("user", "pass").send().await;
+        assert_eq!(response.text().unwrap(), "Basic dXNlcjpwYXNz");
+    }
+
+    #[tokio::test]
+    #[allow(deprecated)]
+    async fn test_invalid_body_reports_field_path() {
+        use archimedes_middleware::stages::{
+            ErrorNormalizationMiddleware, FieldType, MockSchema, RequestBody, ValidationMiddleware,
+        };
+        use archimedes_middleware::{BoxFuture, Pipeline};
+
+        let pipeline = Arc::new(
+            Pipeline::builder()
+                .add_pre_handler_stage(ErrorNormalizationMiddleware::new())
+                .add_pre_handler_stage(
+                    ValidationMiddleware::with_schemas()
+                        .add_request_schema(
+                            "createUser",
+                            MockSchema::builder()
+                                .required("name")
+                                .required("email")
+                                .field("email", FieldType::String)
+                                .allow_additional(true)
+                                .build(),
+                        )
+                        .build(),
+                )
+                .build(),
+        );
+        let client = TestClient::new(move |mut ctx, req| {
+            let pipeline = pipeline.clone();
+            async move {
+                ctx.set_operation_id("createUser".to_string());
+                let body = req.body.to_vec();
+                let mut request = req.into_http_request();
+                request.extensions_mut().insert(RequestBody(body));
+                pipeline
+                    .process(ctx, request, |_ctx, _req| {
+                        Box::pin(async {
+                            http::Response::builder()
+                                .status(StatusCode::CREATED)
+                                .body(Full::new(Bytes::new()))
+                                .unwrap()
+                        }) as BoxFuture<'static, Response>
+                    })
+                    .await
+            }
+        });
+
+        let response = client
+            .post("/users")
+            .json(&json!({"name": "Alice", "email": 42}))
+            .send()
+            .await;
+
+        response.assert_status(StatusCode::BAD_REQUEST);
+        response.assert_validation_error("email");
+        assert_eq!(response.validation_errors().len(), 1);
+
+        let response = client
+            .post("/users")
+            .json(&json!({"name": "Alice", "email": "alice@example.com"}))
+            .send()
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        assert!(response.validation_errors().is_empty());
+    }
+
     #[tokio::test]
     async fn test_all_methods() {
         let client = TestClient::echo();
@@ -355,4 +498,24 @@ mod tests {
         let head = client.head("/test").send().await;
         assert!(head.json_value().unwrap()["method"] == "HEAD");
     }
+
+    #[tokio::test]
+    async fn test_detailed_timing() {
+        let client = TestClient::new(|_ctx, req| async move {
+            timing::time(timing::VALIDATION, || ());
+            let echoed = timing::time_handler(async move { req.body }).await;
+            http::Response::new(Full::new(echoed))
+        })
+        .with_detailed_timing();
+
+        let response = client.post("/test").body("hello").send().await;
+        let timings = response.phase_timings().unwrap();
+        assert_eq!(timings.request_bytes(), 5);
+        assert_eq!(timings.response_bytes(), 5);
+        assert!(response.phase(timing::VALIDATION).is_some());
+        response.assert_phase_within(timing::HANDLER, std::time::Duration::from_secs(5));
+
+        let untimed = TestClient::echo().get("/test").send().await;
+        assert!(untimed.phase_timings().is_none());
+    }
 }
@@ -15,6 +15,8 @@ pub enum TestError {
     Processing(String),
     /// Header value is invalid
     InvalidHeader(String),
+    /// A file could not be read
+    Io(std::io::Error),
 }
 
 impl fmt::Display for TestError {
@@ -25,6 +27,7 @@ impl fmt::Display for TestError {
             Self::Json(e) => write!(f, "JSON error: {e}"),
             Self::Processing(msg) => write!(f, "Processing error: {msg}"),
             Self::InvalidHeader(msg) => write!(f, "Invalid header: {msg}"),
+            Self::Io(e) => write!(f, "I/O error: {e}"),
         }
     }
 }
@@ -33,6 +36,7 @@ impl std::error::Error for TestError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             Self::Json(e) => Some(e),
+            Self::Io(e) => Some(e),
             _ => None,
         }
     }
@@ -11,8 +11,11 @@
 //! - **In-Memory Testing**: No real network connections or port binding
 //! - **Request Builder**: Fluent API for building test requests
 //! - **Response Assertions**: Helper methods for validating responses
+//! - **Validation Errors**: Structured field errors from error envelopes
 //! - **JSON Support**: Automatic serialization/deserialization of JSON bodies
 //! - **Full Middleware**: Requests go through the complete middleware pipeline
+//! - **Capture Replay**: Re-issue captured production requests with
+//!   [`replay_capture`] and diff the responses against the recording
 //!
 //! ## Example
 //!
@@ -71,10 +74,12 @@
 
 mod client;
 mod error;
+mod replay;
 mod request;
 mod response;
 
 pub use client::TestClient;
 pub use error::TestError;
+pub use replay::{replay_capture, Difference, Replay};
 pub use request::{TestRequest, TestRequestBuilder};
-pub use response::TestResponse;
+pub use response::{TestResponse, ValidationError};
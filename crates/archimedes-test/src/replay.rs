@@ -0,0 +1,388 @@
+//! Replay of captured requests.
+//!
+//! The capture stage of `archimedes-middleware` records requests and their
+//! responses as JSON records. [`replay_capture`] re-issues each recorded
+//! request through a [`TestClient`] and compares the response with the
+//! recording, so a production exchange can be reproduced locally.
+
+use crate::client::TestClient;
+use crate::error::TestError;
+use crate::response::TestResponse;
+use archimedes_core::secret::REDACTED;
+use archimedes_middleware::stages::capture::{CaptureRecord, CapturedBody};
+use http::Method;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::path::Path;
+
+/// Headers not replayed, because the client sets them itself.
+const SKIPPED_HEADERS: &[&str] = &["content-length", "host"];
+
+/// A way a replayed response differs from the recording.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Difference {
+    /// The status codes differ.
+    Status {
+        /// Recorded status.
+        recorded: u16,
+        /// Replayed status.
+        replayed: u16,
+    },
+    /// The bodies differ at a JSON pointer; `""` for the whole body.
+    Body {
+        /// JSON pointer of the difference.
+        path: String,
+        /// Recorded value, `None` if absent.
+        recorded: Option<Value>,
+        /// Replayed value, `None` if absent.
+        replayed: Option<Value>,
+    },
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show =
+            |value: Option<&Value>| value.map_or_else(|| "nothing".to_string(), Value::to_string);
+        match self {
+            Self::Status { recorded, replayed } => {
+                write!(f, "status: recorded {recorded}, replayed {replayed}")
+            }
+            Self::Body {
+                path,
+                recorded,
+                replayed,
+            } => write!(
+                f,
+                "body at '{path}': recorded {}, replayed {}",
+                show(recorded.as_ref()),
+                show(replayed.as_ref())
+            ),
+        }
+    }
+}
+
+/// A replayed capture record.
+pub struct Replay {
+    /// The record replayed.
+    pub record: CaptureRecord,
+    /// The replayed response.
+    pub response: TestResponse,
+    /// How the response differs from the recording.
+    pub differences: Vec<Difference>,
+}
+
+impl Replay {
+    /// Returns `true` if the response matches the recording.
+    #[must_use]
+    pub fn is_match(&self) -> bool {
+        self.differences.is_empty()
+    }
+
+    /// Asserts that the response matches the recording.
+    ///
+    /// # Panics
+    ///
+    /// Panics listing the differences if there are any.
+    pub fn assert_match(&self) -> &Self {
+        assert!(
+            self.is_match(),
+            "replay of request {} differs from the recording:\n{}",
+            self.record.request_id,
+            self.differences
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+        self
+    }
+}
+
+/// Replays the capture records in a file against a client.
+///
+/// The file holds JSON records, such as the JSON lines written by a
+/// `RotatingFileSink`. Redacted headers and query parameters are not
+/// replayed, and redacted body fields are sent as recorded. Redacted or
+/// left-out parts of the recorded response are not compared.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or parsed, or a recorded
+/// request cannot be rebuilt.
+pub async fn replay_capture(
+    path: impl AsRef<Path>,
+    client: &TestClient,
+) -> Result<Vec<Replay>, TestError> {
+    let contents = std::fs::read_to_string(path).map_err(TestError::Io)?;
+    let records = serde_json::Deserializer::from_str(&contents)
+        .into_iter::<CaptureRecord>()
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut replays = Vec::with_capacity(records.len());
+    for record in records {
+        let method = Method::from_bytes(record.request.method.as_bytes())
+            .map_err(|e| TestError::RequestBuild(e.to_string()))?;
+        let mut request = client.request(method, replay_url(&record.request.url));
+        for header in &record.request.headers {
+            let skipped = SKIPPED_HEADERS
+                .iter()
+                .any(|skipped| header.name.eq_ignore_ascii_case(skipped));
+            if !skipped && header.value != REDACTED {
+                request = request.header(&header.name, &header.value);
+            }
+        }
+        if let Some(body) = record.request.body.bytes() {
+            request = request.body(body);
+        }
+
+        let response = request.try_send().await?;
+        let differences = diff(&record, &response);
+        replays.push(Replay {
+            record,
+            response,
+            differences,
+        });
+    }
+    Ok(replays)
+}
+
+/// Drops redacted parameters from a recorded URL.
+fn replay_url(url: &str) -> String {
+    let Some((path, query)) = url.split_once('?') else {
+        return url.to_string();
+    };
+    let redacted = format!("={REDACTED}");
+    let query: Vec<&str> = query
+        .split('&')
+        .filter(|pair| !pair.ends_with(&redacted))
+        .collect();
+    if query.is_empty() {
+        path.to_string()
+    } else {
+        format!("{path}?{}", query.join("&"))
+    }
+}
+
+/// Compares a replayed response with a record.
+fn diff(record: &CaptureRecord, response: &TestResponse) -> Vec<Difference> {
+    let mut differences = Vec::new();
+    if record.response.status != response.status_code() {
+        differences.push(Difference::Status {
+            recorded: record.response.status,
+            replayed: response.status_code(),
+        });
+    }
+    diff_body(&record.response.body, response.body(), &mut differences);
+    differences
+}
+
+fn diff_body(recorded: &CapturedBody, replayed: &[u8], differences: &mut Vec<Difference>) {
+    let Some(recorded_bytes) = recorded.bytes() else {
+        // Left out of the recording
+        return;
+    };
+    let text = |bytes: &[u8]| Value::String(String::from_utf8_lossy(bytes).into_owned());
+
+    if recorded.is_json() {
+        if let Ok(recorded) = serde_json::from_slice::<Value>(&recorded_bytes) {
+            match serde_json::from_slice::<Value>(replayed) {
+                Ok(replayed) => diff_json(String::new(), &recorded, &replayed, differences),
+                Err(_) => differences.push(Difference::Body {
+                    path: String::new(),
+                    recorded: Some(recorded),
+                    replayed: Some(text(replayed)),
+                }),
+            }
+            return;
+        }
+    }
+    if recorded_bytes != replayed {
+        differences.push(Difference::Body {
+            path: String::new(),
+            recorded: Some(text(&recorded_bytes)),
+            replayed: Some(text(replayed)),
+        });
+    }
+}
+
+/// Compares two JSON values, skipping redacted recorded values.
+fn diff_json(path: String, recorded: &Value, replayed: &Value, differences: &mut Vec<Difference>) {
+    match (recorded, replayed) {
+        (Value::String(redacted), _) if redacted == REDACTED => {}
+        (Value::Object(recorded), Value::Object(replayed)) => {
+            let keys: BTreeSet<&String> = recorded.keys().chain(replayed.keys()).collect();
+            for key in keys {
+                let path = format!("{path}/{}", key.replace('~', "~0").replace('/', "~1"));
+                match (recorded.get(key), replayed.get(key)) {
+                    (Some(recorded), Some(replayed)) => {
+                        diff_json(path, recorded, replayed, differences);
+                    }
+                    (recorded, replayed) => differences.push(Difference::Body {
+                        path,
+                        recorded: recorded.cloned(),
+                        replayed: replayed.cloned(),
+                    }),
+                }
+            }
+        }
+        (Value::Array(recorded), Value::Array(replayed)) => {
+            for index in 0..recorded.len().max(replayed.len()) {
+                let path = format!("{path}/{index}");
+                match (recorded.get(index), replayed.get(index)) {
+                    (Some(recorded), Some(replayed)) => {
+                        diff_json(path, recorded, replayed, differences);
+                    }
+                    (recorded, replayed) => differences.push(Difference::Body {
+                        path,
+                        recorded: recorded.cloned(),
+                        replayed: replayed.cloned(),
+                    }),
+                }
+            }
+        }
+        (recorded, replayed) if recorded != replayed => differences.push(Difference::Body {
+            path,
+            recorded: Some(recorded.clone()),
+            replayed: Some(replayed.clone()),
+        }),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use archimedes_middleware::context::MiddlewareContext;
+    use archimedes_middleware::stages::capture::{
+        CaptureConfig, CaptureMiddleware, RotatingFileSink,
+    };
+    use archimedes_middleware::types::{Request, Response};
+    use archimedes_middleware::{BoxFuture, Middleware, Next};
+    use bytes::Bytes;
+    use http::StatusCode;
+    use http_body_util::Full;
+    use serde_json::json;
+
+    fn order_response(status: StatusCode, price: u32) -> Response {
+        http::Response::builder()
+            .status(status)
+            .header("content-type", "application/json")
+            .body(Full::new(Bytes::from(
+                json!({"id": "o-1", "price": price, "owner": {"email": "a@example.com"}})
+                    .to_string(),
+            )))
+            .unwrap()
+    }
+
+    /// Captures one order request into a file and returns its path.
+    async fn capture(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "archimedes-replay-{name}-{}.jsonl",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let stage = CaptureMiddleware::new(
+            CaptureConfig::new()
+                .enabled()
+                .percentage(100.0)
+                .redact_field("email"),
+            RotatingFileSink::new(&path),
+        );
+
+        let request: Request = http::Request::builder()
+            .method(Method::POST)
+            .uri("/orders?sku=b-1")
+            .header("content-type", "application/json")
+            .header("authorization", "Bearer secret-token")
+            .body(Full::new(Bytes::from(r#"{"sku":"b-1","quantity":2}"#)))
+            .unwrap();
+        let mut ctx = MiddlewareContext::new();
+        let next = Next::handler(|_ctx: &mut MiddlewareContext, _req: Request| {
+            Box::pin(async { order_response(StatusCode::CREATED, 20) })
+                as BoxFuture<'static, Response>
+        });
+        stage.process(&mut ctx, request, next).await;
+        path
+    }
+
+    /// A local server answering orders with a price.
+    fn server(status: StatusCode, price: u32) -> TestClient {
+        TestClient::new(move |_ctx, req| async move {
+            assert_eq!(req.uri.path(), "/orders");
+            assert_eq!(req.uri.query(), Some("sku=b-1"));
+            assert!(req.headers.get("authorization").is_none());
+            let body: Value = serde_json::from_slice(&req.body).unwrap();
+            assert_eq!(body["quantity"], 2);
+            order_response(status, price)
+        })
+    }
+
+    #[tokio::test]
+    async fn test_replay_reproduces_recording() {
+        let path = capture("match").await;
+        let replays = replay_capture(&path, &server(StatusCode::CREATED, 20))
+            .await
+            .unwrap();
+
+        assert_eq!(replays.len(), 1);
+        let replay = &replays[0];
+        replay.assert_match();
+        assert_eq!(replay.response.status(), StatusCode::CREATED);
+        assert_eq!(replay.record.response.status, 201);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_replay_reports_discrepancies() {
+        let path = capture("diff").await;
+        let replays = replay_capture(&path, &server(StatusCode::OK, 25))
+            .await
+            .unwrap();
+
+        let replay = &replays[0];
+        assert!(!replay.is_match());
+        assert_eq!(
+            replay.differences,
+            vec![
+                Difference::Status {
+                    recorded: 201,
+                    replayed: 200
+                },
+                Difference::Body {
+                    path: "/price".to_string(),
+                    recorded: Some(json!(20)),
+                    replayed: Some(json!(25)),
+                },
+            ]
+        );
+        assert_eq!(
+            replay.differences[1].to_string(),
+            "body at '/price': recorded 20, replayed 25"
+        );
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_diff_json_paths() {
+        let mut differences = Vec::new();
+        diff_json(
+            String::new(),
+            &json!({"items": [1, 2], "a/b": true, "gone": 1}),
+            &json!({"items": [1], "a/b": false}),
+            &mut differences,
+        );
+        let paths: Vec<String> = differences
+            .iter()
+            .map(|difference| match difference {
+                Difference::Body { path, .. } => path.clone(),
+                Difference::Status { .. } => unreachable!(),
+            })
+            .collect();
+        assert_eq!(paths, ["/a~1b", "/gone", "/items/1"]);
+        assert_eq!(replay_url("/a?x=[REDACTED]&y=1"), "/a?y=1");
+        assert_eq!(replay_url("/a?x=[REDACTED]"), "/a");
+    }
+}
@@ -1,6 +1,8 @@
 //! Test request building.
 
 use crate::error::TestError;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
 use bytes::Bytes;
 use http::{header, HeaderMap, HeaderName, HeaderValue, Method, Uri};
 use http_body_util::Full;
@@ -124,10 +126,28 @@ impl TestRequestBuilder {
     }
 
     /// Sets the Authorization header with a Bearer token.
-    pub fn bearer_token(self, token: impl AsRef<str>) -> Self {
+    pub fn bearer(self, token: impl AsRef<str>) -> Self {
         self.header(header::AUTHORIZATION.as_str(), format!("Bearer {}", token.as_ref()))
     }
 
+    /// Sets the Authorization header with a Bearer token.
+    ///
+    /// Same as [`bearer`](Self::bearer).
+    pub fn bearer_token(self, token: impl AsRef<str>) -> Self {
+        self.bearer(token)
+    }
+
+    /// Sets an API key in the given header, such as `X-API-Key`.
+    pub fn api_key(self, header_name: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+        self.header(header_name, value)
+    }
+
+    /// Sets the Authorization header with HTTP Basic credentials.
+    pub fn basic_auth(self, user: impl AsRef<str>, pass: impl AsRef<str>) -> Self {
+        let credentials = STANDARD.encode(format!("{}:{}", user.as_ref(), pass.as_ref()));
+        self.header(header::AUTHORIZATION.as_str(), format!("Basic {credentials}"))
+    }
+
     /// Sets the raw request body.
     pub fn body(mut self, body: impl Into<Bytes>) -> Self {
         self.body = Some(body.into());
@@ -268,6 +288,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_bearer() {
+        let request = TestRequest::get("/users").bearer("abc").build().unwrap();
+
+        assert_eq!(request.headers.get("Authorization").unwrap(), "Bearer abc");
+    }
+
+    #[test]
+    fn test_api_key() {
+        let request = TestRequest::get("/users")
+            .api_key("X-API-Key", "secret")
+            .build()
+            .unwrap();
+
+        assert_eq!(request.headers.get("X-API-Key").unwrap(), "secret");
+        assert!(request.headers.get("Authorization").is_none());
+    }
+
+    #[test]
+    fn test_basic_auth() {
+        let request = TestRequest::get("/users")
+            .basic_auth("Aladdin", "open sesame")
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            request.headers.get("Authorization").unwrap(),
+            "Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ=="
+        );
+    }
+
     #[test]
     fn test_json_body() {
         let request = TestRequest::post("/users")
@@ -1,10 +1,13 @@
 //! Test response wrapper.
 
 use crate::error::TestError;
+use archimedes_core::timing::PhaseTimings;
 use bytes::Bytes;
 use http::{header, HeaderMap, HeaderValue, StatusCode};
 use serde::de::DeserializeOwned;
+use serde::Deserialize;
 use std::fmt;
+use std::time::Duration;
 
 /// A test response with helper methods for assertions.
 pub struct TestResponse {
@@ -14,6 +17,8 @@ pub struct TestResponse {
     headers: HeaderMap,
     /// Response body bytes
     body: Bytes,
+    /// Phase timings, when recorded with detailed timing
+    phase_timings: Option<PhaseTimings>,
 }
 
 impl TestResponse {
@@ -34,6 +39,7 @@ impl TestResponse {
             status: parts.status,
             headers: parts.headers,
             body: body_bytes,
+            phase_timings: parts.extensions.get::<PhaseTimings>().cloned(),
         })
     }
 
@@ -43,6 +49,7 @@ impl TestResponse {
             status,
             headers,
             body,
+            phase_timings: None,
         }
     }
 
@@ -145,6 +152,46 @@ impl TestResponse {
         self.json()
     }
 
+    /// Returns the phase timings recorded for the request.
+    ///
+    /// Only present when the request ran with detailed timing, either on a
+    /// server built with `detailed_timing(true)` or through
+    /// [`TestClient::with_detailed_timing`](crate::TestClient::with_detailed_timing).
+    #[must_use]
+    pub fn phase_timings(&self) -> Option<&PhaseTimings> {
+        self.phase_timings.as_ref()
+    }
+
+    /// Returns the time spent in a phase such as
+    /// [`timing::HANDLER`](archimedes_core::timing::HANDLER).
+    #[must_use]
+    pub fn phase(&self, name: &str) -> Option<Duration> {
+        self.phase_timings.as_ref()?.phase(name)
+    }
+
+    /// Returns the field errors reported in the error envelope.
+    ///
+    /// Reads the `error.details.issues` list that validation failures carry.
+    /// Returns an empty list when the body is not an error envelope or
+    /// reports no issues.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let response = client.post("/users").json(&json!({})).send().await;
+    /// let errors = response.validation_errors();
+    /// assert_eq!(errors[0].field, "email");
+    /// ```
+    #[must_use]
+    pub fn validation_errors(&self) -> Vec<ValidationError> {
+        let Ok(json) = self.json::<serde_json::Value>() else {
+            return Vec::new();
+        };
+        json_path(&json, "error.details.issues")
+            .and_then(|issues| serde_json::from_value(issues.clone()).ok())
+            .unwrap_or_default()
+    }
+
     // Assertion methods
 
     /// Asserts that the status code equals the expected value.
@@ -291,6 +338,55 @@ impl TestResponse {
         );
         self
     }
+
+    /// Asserts that a validation error was reported for a field path.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no error in [`validation_errors`](Self::validation_errors)
+    /// is for `field_path`.
+    pub fn assert_validation_error(&self, field_path: impl AsRef<str>) -> &Self {
+        let field_path = field_path.as_ref();
+        let errors = self.validation_errors();
+        assert!(
+            errors.iter().any(|e| e.field == field_path),
+            "Expected a validation error for '{}', got: {:?}",
+            field_path,
+            errors
+        );
+        self
+    }
+
+    /// Asserts that a phase was recorded and took at most `max`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the phase wasn't recorded or took longer than `max`.
+    pub fn assert_phase_within(&self, name: impl AsRef<str>, max: Duration) -> &Self {
+        let name = name.as_ref();
+        let actual = self
+            .phase(name)
+            .unwrap_or_else(|| panic!("Phase '{}' was not recorded", name));
+        assert!(
+            actual <= max,
+            "Phase '{}' took {:?}, expected at most {:?}",
+            name,
+            actual,
+            max
+        );
+        self
+    }
+}
+
+/// A field error from an error envelope's `details.issues`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ValidationError {
+    /// Path of the failing field, empty for errors about the whole body.
+    #[serde(default)]
+    pub field: String,
+    /// Human-readable error message.
+    #[serde(default)]
+    pub message: String,
 }
 
 impl fmt::Debug for TestResponse {
@@ -335,6 +431,39 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_validation_errors() {
+        let response = create_response(
+            400,
+            r#"{"error":{"code":"FIELD_REQUIRED","message":"Missing required field: email","details":{"issues":[{"field":"email","message":"Missing required field: email","code":"FIELD_REQUIRED"},{"field":"age","message":"bad type"}]}}}"#,
+        );
+        let errors = response.validation_errors();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            errors[0],
+            ValidationError {
+                field: "email".to_string(),
+                message: "Missing required field: email".to_string(),
+            }
+        );
+        response.assert_validation_error("age");
+
+        assert!(create_response(200, "{}").validation_errors().is_empty());
+        assert!(create_response(400, "not json")
+            .validation_errors()
+            .is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected a validation error for 'name'")]
+    fn test_assert_validation_error_missing_field() {
+        let response = create_response(
+            400,
+            r#"{"error":{"details":{"issues":[{"field":"email","message":"required"}]}}}"#,
+        );
+        response.assert_validation_error("name");
+    }
+
     #[test]
     fn test_status() {
         let response = create_response(200, "{}");
@@ -0,0 +1,102 @@
+//! Fault injection observed through the test client.
+//!
+//! Requests run through a pipeline with the fault stage, so the latency
+//! and abort rates a fault file configures show up in the responses a test
+//! harness sees.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use archimedes_middleware::stages::fault::FAULT_HEADER;
+use archimedes_middleware::stages::{
+    FaultConfig, FaultConfigError, FaultInjectionMiddleware, FaultRule, Faults, LatencyFault,
+};
+use archimedes_middleware::Pipeline;
+use archimedes_test::TestClient;
+use bytes::Bytes;
+use http::StatusCode;
+use http_body_util::Full;
+
+/// A client whose requests pass through the fault stage to a handler that
+/// answers `200 OK`.
+fn client(config: FaultConfig) -> TestClient {
+    let stage = FaultInjectionMiddleware::new(Faults::new(config), "test").unwrap();
+    let pipeline = Arc::new(Pipeline::builder().add_pre_handler_stage(stage).build());
+
+    TestClient::new(move |ctx, req| {
+        let pipeline = Arc::clone(&pipeline);
+        async move {
+            pipeline
+                .process(ctx, req.into_http_request(), |_ctx, _req| {
+                    Box::pin(async {
+                        http::Response::builder()
+                            .status(StatusCode::OK)
+                            .body(Full::new(Bytes::from("ok")))
+                            .unwrap()
+                    })
+                })
+                .await
+        }
+    })
+}
+
+#[tokio::test]
+async fn test_latency_injection_is_measurable() {
+    let client = client(FaultConfig::new().enabled().rule(
+        FaultRule::path("/slow/**").latency(LatencyFault::fixed(Duration::from_millis(200))),
+    ));
+
+    let start = Instant::now();
+    let response = client.get("/slow/items").send().await;
+    let slow = start.elapsed();
+    assert_eq!(response.status_code(), 200);
+    assert_eq!(response.header(FAULT_HEADER).unwrap(), "latency");
+    assert!(slow >= Duration::from_millis(200), "took {slow:?}");
+
+    let start = Instant::now();
+    let response = client.get("/fast/items").send().await;
+    let fast = start.elapsed();
+    assert!(response.header(FAULT_HEADER).is_none());
+    assert!(fast < Duration::from_millis(200), "took {fast:?}");
+}
+
+#[tokio::test]
+async fn test_abort_percentage_approximately_honored() {
+    const REQUESTS: usize = 2000;
+    let client = client(
+        FaultConfig::new()
+            .enabled()
+            .rule(FaultRule::path("/items").abort(25.0, StatusCode::SERVICE_UNAVAILABLE)),
+    );
+
+    let mut aborted = 0;
+    for _ in 0..REQUESTS {
+        let response = client.get("/items").send().await;
+        match response.status_code() {
+            503 => {
+                assert_eq!(response.header(FAULT_HEADER).unwrap(), "abort");
+                aborted += 1;
+            }
+            status => assert_eq!(status, 200),
+        }
+    }
+
+    // 25% of 2000 is 500, with a standard deviation of about 19
+    assert!((400..600).contains(&aborted), "aborted {aborted}");
+}
+
+#[test]
+fn test_production_profile_refused() {
+    let faults = Faults::new(FaultConfig::new().enabled());
+    assert!(matches!(
+        FaultInjectionMiddleware::new(faults.clone(), "production"),
+        Err(FaultConfigError::Production)
+    ));
+
+    // Refused even while injection is disabled
+    assert!(matches!(
+        FaultInjectionMiddleware::new(Faults::default(), "production"),
+        Err(FaultConfigError::Production)
+    ));
+    assert!(FaultInjectionMiddleware::new(faults, "staging").is_ok());
+}
@@ -5,6 +5,8 @@
 
 use std::time::Duration;
 
+use crate::limits::RateLimitAction;
+
 /// Configuration for a WebSocket connection.
 #[derive(Debug, Clone)]
 pub struct WebSocketConfig {
@@ -12,6 +14,8 @@ pub struct WebSocketConfig {
     pub max_message_size: usize,
     /// Maximum frame size in bytes (default: 16 MB).
     pub max_frame_size: usize,
+    /// Maximum continuation frames in a single fragmented message (default: 1024).
+    pub max_continuation_frames: usize,
     /// Heartbeat interval for ping frames (default: 30 seconds).
     pub heartbeat_interval: Duration,
     /// Connection timeout - close if no pong received (default: 60 seconds).
@@ -22,6 +26,16 @@ pub struct WebSocketConfig {
     pub read_buffer_size: usize,
     /// Whether to accept unmasked frames from clients (default: false).
     pub accept_unmasked_frames: bool,
+    /// Whether to measure ping round-trip times (default: false).
+    ///
+    /// When enabled, `recv` also sends a heartbeat ping every
+    /// `heartbeat_interval`.
+    pub track_rtt: bool,
+    /// Maximum text and binary messages received per second (default: unlimited).
+    pub max_messages_per_sec: Option<u32>,
+    /// What to do with messages over `max_messages_per_sec`, or over the
+    /// manager's limit (default: close the connection).
+    pub rate_limit_action: RateLimitAction,
 }
 
 impl Default for WebSocketConfig {
@@ -29,11 +43,15 @@ impl Default for WebSocketConfig {
         Self {
             max_message_size: 64 * 1024 * 1024, // 64 MB
             max_frame_size: 16 * 1024 * 1024,   // 16 MB
+            max_continuation_frames: 1024,
             heartbeat_interval: Duration::from_secs(30),
             connection_timeout: Duration::from_secs(60),
             write_buffer_size: 128 * 1024, // 128 KB
             read_buffer_size: 128 * 1024,  // 128 KB
             accept_unmasked_frames: false,
+            track_rtt: false,
+            max_messages_per_sec: None,
+            rate_limit_action: RateLimitAction::Close,
         }
     }
 }
@@ -56,6 +74,12 @@ impl WebSocketConfig {
         self
     }
 
+    /// Set the maximum number of continuation frames per message.
+    pub fn max_continuation_frames(mut self, max: usize) -> Self {
+        self.max_continuation_frames = max;
+        self
+    }
+
     /// Set the heartbeat interval.
     pub fn heartbeat_interval(mut self, interval: Duration) -> Self {
         self.heartbeat_interval = interval;
@@ -85,6 +109,34 @@ impl WebSocketConfig {
         self.accept_unmasked_frames = accept;
         self
     }
+
+    /// Set whether to measure ping round-trip times.
+    pub fn track_rtt(mut self, track: bool) -> Self {
+        self.track_rtt = track;
+        self
+    }
+
+    /// Set the maximum text and binary messages received per second.
+    pub fn max_messages_per_sec(mut self, max: u32) -> Self {
+        self.max_messages_per_sec = Some(max);
+        self
+    }
+
+    /// Set what to do with messages over the rate limit.
+    pub fn rate_limit_action(mut self, action: RateLimitAction) -> Self {
+        self.rate_limit_action = action;
+        self
+    }
+
+    /// Build the protocol-level configuration enforced while reading frames.
+    pub(crate) fn protocol_config(&self) -> tungstenite::protocol::WebSocketConfig {
+        tungstenite::protocol::WebSocketConfig::default()
+            .max_message_size(Some(self.max_message_size))
+            .max_frame_size(Some(self.max_frame_size))
+            .read_buffer_size(self.read_buffer_size)
+            .write_buffer_size(self.write_buffer_size)
+            .accept_unmasked_frames(self.accept_unmasked_frames)
+    }
 }
 
 /// Configuration for the connection manager.
@@ -98,6 +150,9 @@ pub struct ConnectionManagerConfig {
     pub idle_timeout: Duration,
     /// How often to run the cleanup task (default: 30 seconds).
     pub cleanup_interval: Duration,
+    /// Maximum text and binary messages received per second across all
+    /// attached connections (default: unlimited).
+    pub max_messages_per_sec: Option<u32>,
 }
 
 impl Default for ConnectionManagerConfig {
@@ -107,6 +162,7 @@ impl Default for ConnectionManagerConfig {
             max_per_client: 100,
             idle_timeout: Duration::from_secs(300), // 5 minutes
             cleanup_interval: Duration::from_secs(30),
+            max_messages_per_sec: None,
         }
     }
 }
@@ -140,6 +196,13 @@ impl ConnectionManagerConfig {
         self.cleanup_interval = interval;
         self
     }
+
+    /// Set the maximum text and binary messages received per second across
+    /// all attached connections.
+    pub fn max_messages_per_sec(mut self, max: u32) -> Self {
+        self.max_messages_per_sec = Some(max);
+        self
+    }
 }
 
 #[cfg(test)]
@@ -151,9 +214,13 @@ mod tests {
         let config = WebSocketConfig::default();
         assert_eq!(config.max_message_size, 64 * 1024 * 1024);
         assert_eq!(config.max_frame_size, 16 * 1024 * 1024);
+        assert_eq!(config.max_continuation_frames, 1024);
         assert_eq!(config.heartbeat_interval, Duration::from_secs(30));
         assert_eq!(config.connection_timeout, Duration::from_secs(60));
         assert!(!config.accept_unmasked_frames);
+        assert!(!config.track_rtt);
+        assert_eq!(config.max_messages_per_sec, None);
+        assert_eq!(config.rate_limit_action, RateLimitAction::Close);
     }
 
     #[test]
@@ -161,15 +228,34 @@ mod tests {
         let config = WebSocketConfig::new()
             .max_message_size(1024)
             .max_frame_size(512)
+            .max_continuation_frames(8)
             .heartbeat_interval(Duration::from_secs(10))
             .connection_timeout(Duration::from_secs(20))
-            .accept_unmasked_frames(true);
+            .accept_unmasked_frames(true)
+            .track_rtt(true)
+            .max_messages_per_sec(50)
+            .rate_limit_action(RateLimitAction::Backpressure);
 
         assert_eq!(config.max_message_size, 1024);
         assert_eq!(config.max_frame_size, 512);
+        assert_eq!(config.max_continuation_frames, 8);
         assert_eq!(config.heartbeat_interval, Duration::from_secs(10));
         assert_eq!(config.connection_timeout, Duration::from_secs(20));
         assert!(config.accept_unmasked_frames);
+        assert!(config.track_rtt);
+        assert_eq!(config.max_messages_per_sec, Some(50));
+        assert_eq!(config.rate_limit_action, RateLimitAction::Backpressure);
+    }
+
+    #[test]
+    fn test_protocol_config_carries_size_limits() {
+        let config = WebSocketConfig::new()
+            .max_message_size(1024)
+            .max_frame_size(512);
+
+        let protocol = config.protocol_config();
+        assert_eq!(protocol.max_message_size, Some(1024));
+        assert_eq!(protocol.max_frame_size, Some(512));
     }
 
     #[test]
@@ -179,6 +265,7 @@ mod tests {
         assert_eq!(config.max_per_client, 100);
         assert_eq!(config.idle_timeout, Duration::from_secs(300));
         assert_eq!(config.cleanup_interval, Duration::from_secs(30));
+        assert_eq!(config.max_messages_per_sec, None);
     }
 
     #[test]
@@ -187,11 +274,13 @@ mod tests {
             .max_connections(5000)
             .max_per_client(50)
             .idle_timeout(Duration::from_secs(600))
-            .cleanup_interval(Duration::from_secs(60));
+            .cleanup_interval(Duration::from_secs(60))
+            .max_messages_per_sec(1000);
 
         assert_eq!(config.max_connections, 5000);
         assert_eq!(config.max_per_client, 50);
         assert_eq!(config.idle_timeout, Duration::from_secs(600));
         assert_eq!(config.cleanup_interval, Duration::from_secs(60));
+        assert_eq!(config.max_messages_per_sec, Some(1000));
     }
 }
@@ -7,19 +7,27 @@
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use futures_util::stream::{SplitSink, SplitStream};
 use futures_util::{SinkExt, Stream, StreamExt};
 use tokio::io::{AsyncRead, AsyncWrite};
-use tokio::sync::Mutex;
+use tokio::sync::{broadcast, Mutex};
+use tokio::time::{Interval, MissedTickBehavior};
 use tokio_tungstenite::WebSocketStream;
 use tracing::{debug, instrument, warn};
 use uuid::Uuid;
 
 use crate::config::WebSocketConfig;
+use crate::context::WebSocketContext;
 use crate::error::{CloseCode, WsError, WsResult};
+use crate::limits::{is_continuation_limit, FrameLimiter, MessageRateLimiter, RateLimitAction};
+use crate::manager::{AttachGuard, ConnectionManager};
 use crate::message::Message;
+use crate::telemetry::{message_type, ConnectionTelemetry, MessageRecord};
+
+/// The protocol stream underlying a [`WebSocket`].
+type WsStream<S> = WebSocketStream<FrameLimiter<S>>;
 
 /// A unique identifier for a WebSocket connection.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -72,6 +80,13 @@ impl From<ConnectionId> for Uuid {
 /// and receiving messages. It tracks connection state and supports
 /// automatic ping/pong handling.
 ///
+/// With [`WebSocketConfig::track_rtt`], [`recv`](Self::recv) also sends a
+/// heartbeat ping every `heartbeat_interval`, and the round-trip time of
+/// every answered ping is available from [`rtt`](Self::rtt).
+///
+/// [`recv`](Self::recv) also enforces the message rate limits of the
+/// connection and of its manager, see [`limits`](crate::limits).
+///
 /// # Example
 ///
 /// ```ignore
@@ -97,17 +112,33 @@ pub struct WebSocket<S = tokio::net::TcpStream> {
     /// The unique connection ID.
     connection_id: ConnectionId,
     /// The sender half of the WebSocket stream.
-    sender: Arc<Mutex<SplitSink<WebSocketStream<S>, tungstenite::Message>>>,
+    sender: Arc<Mutex<SplitSink<WsStream<S>, tungstenite::Message>>>,
     /// The receiver half of the WebSocket stream.
-    receiver: SplitStream<WebSocketStream<S>>,
+    receiver: SplitStream<WsStream<S>>,
     /// Configuration for this connection.
     config: WebSocketConfig,
+    /// Metadata about the request that opened this connection.
+    context: WebSocketContext,
     /// When the connection was established.
     connected_at: Instant,
     /// Last time activity was seen on this connection.
     last_activity: Instant,
     /// Whether the connection has been closed.
     closed: bool,
+    /// Metrics and the connection span.
+    telemetry: ConnectionTelemetry,
+    /// Shutdown notifications of the manager this connection is attached to.
+    shutdown: Option<broadcast::Receiver<()>>,
+    /// Whether the manager shut down before this connection was attached.
+    going_away: bool,
+    /// Heartbeat pings sent while receiving, when measuring round-trip times.
+    heartbeat: Option<Interval>,
+    /// Message rate limit of this connection.
+    rate_limiter: Option<MessageRateLimiter>,
+    /// Message rate limit shared with the other connections of the manager.
+    shared_rate_limiter: Option<Arc<MessageRateLimiter>>,
+    /// Keeps this connection counted by its manager until dropped.
+    _attached: Option<AttachGuard>,
 }
 
 impl<S> WebSocket<S>
@@ -115,36 +146,41 @@ where
     S: AsyncRead + AsyncWrite + Unpin,
 {
     /// Create a new WebSocket from an underlying stream.
-    pub fn new(stream: WebSocketStream<S>, config: WebSocketConfig) -> Self {
-        let (sender, receiver) = stream.split();
-        let now = Instant::now();
-        Self {
-            connection_id: ConnectionId::new(),
-            sender: Arc::new(Mutex::new(sender)),
-            receiver,
-            config,
-            connected_at: now,
-            last_activity: now,
-            closed: false,
-        }
+    pub fn new(stream: WsStream<S>, config: WebSocketConfig) -> Self {
+        Self::with_id(stream, config, ConnectionId::new())
     }
 
     /// Create a new WebSocket with a specific connection ID.
     pub fn with_id(
-        stream: WebSocketStream<S>,
+        stream: WsStream<S>,
         config: WebSocketConfig,
         connection_id: ConnectionId,
     ) -> Self {
         let (sender, receiver) = stream.split();
         let now = Instant::now();
+        let mut telemetry = ConnectionTelemetry::new(connection_id, now);
+        if config.track_rtt {
+            telemetry.enable_rtt();
+        }
+        let rate_limiter = config
+            .max_messages_per_sec
+            .map(MessageRateLimiter::per_second);
         Self {
             connection_id,
             sender: Arc::new(Mutex::new(sender)),
             receiver,
             config,
+            context: WebSocketContext::default(),
             connected_at: now,
             last_activity: now,
             closed: false,
+            telemetry,
+            shutdown: None,
+            going_away: false,
+            heartbeat: None,
+            rate_limiter,
+            shared_rate_limiter: None,
+            _attached: None,
         }
     }
 
@@ -153,11 +189,54 @@ where
         self.connection_id
     }
 
+    /// Attach metadata about the request that opened this connection.
+    ///
+    /// The negotiated subprotocol is recorded on the connection span.
+    pub fn with_context(mut self, context: WebSocketContext) -> Self {
+        if let Some(protocol) = context.protocol() {
+            self.telemetry.set_subprotocol(protocol);
+        }
+        self.context = context;
+        self
+    }
+
+    /// Aggregate this connection's traffic into a manager's statistics.
+    ///
+    /// If the manager tracks this connection's ID, its client ID is
+    /// recorded on the connection span. Call this before taking
+    /// [`sender`](Self::sender) handles, so their traffic is aggregated
+    /// as well.
+    ///
+    /// When the manager shuts down, [`recv`](Self::recv) closes the
+    /// connection with [`CloseCode::GoingAway`] and returns `None`, and
+    /// [`ConnectionManager::drain`] waits for this connection to be
+    /// dropped.
+    pub fn with_manager(mut self, manager: &ConnectionManager) -> Self {
+        self.telemetry.set_shared(manager.traffic());
+        self.telemetry.set_connections(manager.connections_handle());
+        self.shutdown = Some(manager.shutdown_receiver());
+        self.going_away = manager.is_shutdown();
+        self.shared_rate_limiter = manager.message_limiter();
+        self._attached = Some(manager.attach());
+        if let Some(client_id) = manager
+            .get(&self.connection_id)
+            .and_then(|info| info.client_id)
+        {
+            self.telemetry.set_client_id(&client_id);
+        }
+        self
+    }
+
     /// Get the connection configuration.
     pub fn config(&self) -> &WebSocketConfig {
         &self.config
     }
 
+    /// Get the metadata about the request that opened this connection.
+    pub fn context(&self) -> &WebSocketContext {
+        &self.context
+    }
+
     /// Get when the connection was established.
     pub fn connected_at(&self) -> Instant {
         self.connected_at
@@ -183,19 +262,57 @@ where
         self.last_activity.elapsed()
     }
 
+    /// Get the round-trip time of the last answered ping.
+    ///
+    /// Always `None` unless [`WebSocketConfig::track_rtt`] is enabled.
+    pub fn rtt(&self) -> Option<Duration> {
+        self.telemetry.rtt()
+    }
+
     /// Receive the next message from the WebSocket.
     ///
-    /// Returns `None` when the connection is closed.
-    #[instrument(skip(self), fields(connection_id = %self.connection_id))]
+    /// Returns `None` when the connection is closed. A message exceeding the
+    /// configured size or continuation frame limits closes the connection
+    /// with [`CloseCode::MessageTooBig`]; a shutdown of the attached
+    /// manager closes it with [`CloseCode::GoingAway`]. A message over the
+    /// rate limit closes it with [`CloseCode::PolicyViolation`], or is held
+    /// back until it fits with [`RateLimitAction::Backpressure`].
+    #[instrument(parent = self.telemetry.span(), skip(self))]
     pub async fn recv(&mut self) -> Option<WsResult<Message>> {
         if self.closed {
             return None;
         }
+        if self.going_away {
+            return self.go_away().await;
+        }
+
+        if self.config.track_rtt && self.heartbeat.is_none() {
+            self.heartbeat = heartbeat_interval(self.config.heartbeat_interval);
+        }
+
+        let next = loop {
+            tokio::select! {
+                next = self.receiver.next() => break next,
+                () = shutdown_requested(&mut self.shutdown) => return self.go_away().await,
+                () = heartbeat_due(&mut self.heartbeat) => {
+                    if let Err(e) = self.heartbeat().await {
+                        warn!("Failed to send heartbeat ping: {}", e);
+                    }
+                }
+            }
+        };
 
-        match self.receiver.next().await {
+        match next {
             Some(Ok(msg)) => {
                 self.last_activity = Instant::now();
                 let msg = Message::from(msg);
+                self.telemetry.received(&msg);
+
+                if msg.is_data() {
+                    if let Err(err) = self.enforce_rate_limits().await {
+                        return Some(Err(err));
+                    }
+                }
 
                 // Handle ping automatically
                 if let Message::Ping(data) = &msg {
@@ -214,18 +331,77 @@ where
                 Some(Ok(msg))
             }
             Some(Err(e)) => {
+                if let Some(reason) = limit_violation(&e) {
+                    warn!(reason = %reason, "Message exceeds limits, closing connection");
+                    if let Err(e) = self.close(CloseCode::MessageTooBig, reason.clone()).await {
+                        debug!("Failed to send close frame: {}", e);
+                    }
+                    self.closed = true;
+                    let err = message_too_big(reason);
+                    self.telemetry.error(&err);
+                    self.telemetry.closed(CloseCode::MessageTooBig.as_u16());
+                    return Some(Err(err));
+                }
                 self.closed = true;
-                Some(Err(WsError::from(e)))
+                let err = WsError::from(e);
+                self.telemetry.error(&err);
+                self.telemetry.aborted();
+                Some(Err(err))
             }
             None => {
                 self.closed = true;
+                self.telemetry.aborted();
                 None
             }
         }
     }
 
+    /// Take the rate limit budget for a received text or binary message.
+    ///
+    /// Fails once the connection was closed for exceeding a limit.
+    async fn enforce_rate_limits(&mut self) -> WsResult<()> {
+        let reserve = self.config.rate_limit_action == RateLimitAction::Backpressure;
+        let now = tokio::time::Instant::now();
+        let wait = [
+            self.rate_limiter.as_ref(),
+            self.shared_rate_limiter.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|limiter| limiter.acquire(now, reserve))
+        .max()
+        .unwrap_or_default();
+        if wait.is_zero() {
+            return Ok(());
+        }
+
+        match self.config.rate_limit_action {
+            RateLimitAction::Backpressure => {
+                debug!(wait = ?wait, "Message rate limit reached, holding message back");
+                tokio::select! {
+                    () = tokio::time::sleep(wait) => {}
+                    () = shutdown_requested(&mut self.shutdown) => self.going_away = true,
+                }
+                Ok(())
+            }
+            RateLimitAction::Close => {
+                let reason = "message rate limit exceeded";
+                warn!("Message rate limit exceeded, closing connection");
+                if let Err(e) = self.close(CloseCode::PolicyViolation, reason).await {
+                    debug!("Failed to send close frame: {}", e);
+                }
+                self.closed = true;
+                let err =
+                    WsError::connection_closed(Some(CloseCode::PolicyViolation.as_u16()), reason);
+                self.telemetry.error(&err);
+                self.telemetry.closed(CloseCode::PolicyViolation.as_u16());
+                Err(err)
+            }
+        }
+    }
+
     /// Send a message on the WebSocket.
-    #[instrument(skip(self, msg), fields(connection_id = %self.connection_id, msg_type = ?msg_type(&msg)))]
+    #[instrument(parent = self.telemetry.span(), skip(self, msg), fields(msg_type = message_type(&msg)))]
     pub async fn send(&self, msg: Message) -> WsResult<()> {
         if self.closed {
             return Err(WsError::connection_closed(
@@ -234,12 +410,7 @@ where
             ));
         }
 
-        let tungstenite_msg = tungstenite::Message::from(msg);
-        let mut sender = self.sender.lock().await;
-        sender
-            .send(tungstenite_msg)
-            .await
-            .map_err(|e| WsError::send_failed(e.to_string()))
+        send_message(&self.sender, &self.telemetry, msg).await
     }
 
     /// Send a text message.
@@ -263,6 +434,15 @@ where
         self.send(Message::ping(data)).await
     }
 
+    /// Send a heartbeat ping.
+    ///
+    /// When measuring round-trip times, each heartbeat carries a unique
+    /// payload so its pong can be told apart from others.
+    pub async fn heartbeat(&self) -> WsResult<()> {
+        self.send(Message::ping(self.telemetry.heartbeat_payload()))
+            .await
+    }
+
     /// Close the WebSocket connection.
     pub async fn close(&mut self, code: CloseCode, reason: impl Into<String>) -> WsResult<()> {
         if self.closed {
@@ -278,6 +458,19 @@ where
         Ok(())
     }
 
+    /// Close the connection because the server is shutting down.
+    async fn go_away(&mut self) -> Option<WsResult<Message>> {
+        if let Err(e) = self
+            .close(CloseCode::GoingAway, "server shutting down")
+            .await
+        {
+            debug!("Failed to send close frame: {}", e);
+            self.closed = true;
+            self.telemetry.closed(CloseCode::GoingAway.as_u16());
+        }
+        None
+    }
+
     /// Close the WebSocket with a normal close code.
     pub async fn close_normal(&mut self, reason: impl Into<String>) -> WsResult<()> {
         self.close(CloseCode::Normal, reason).await
@@ -288,6 +481,7 @@ where
         WebSocketSender {
             connection_id: self.connection_id,
             sender: Arc::clone(&self.sender),
+            telemetry: self.telemetry.clone(),
         }
     }
 }
@@ -307,6 +501,7 @@ where
             Poll::Ready(Some(Ok(msg))) => {
                 self.last_activity = Instant::now();
                 let msg = Message::from(msg);
+                self.telemetry.received(&msg);
                 if msg.is_close() {
                     self.closed = true;
                 }
@@ -314,10 +509,17 @@ where
             }
             Poll::Ready(Some(Err(e))) => {
                 self.closed = true;
-                Poll::Ready(Some(Err(WsError::from(e))))
+                let err = match limit_violation(&e) {
+                    Some(reason) => message_too_big(reason),
+                    None => WsError::from(e),
+                };
+                self.telemetry.error(&err);
+                self.telemetry.aborted();
+                Poll::Ready(Some(Err(err)))
             }
             Poll::Ready(None) => {
                 self.closed = true;
+                self.telemetry.aborted();
                 Poll::Ready(None)
             }
             Poll::Pending => Poll::Pending,
@@ -325,6 +527,13 @@ where
     }
 }
 
+impl<S> Drop for WebSocket<S> {
+    fn drop(&mut self) {
+        // A connection dropped without a close handshake ended abnormally
+        self.telemetry.aborted();
+    }
+}
+
 /// A handle for sending messages to a WebSocket from other tasks.
 ///
 /// This is a cloneable handle that can be shared across tasks to send
@@ -334,7 +543,9 @@ pub struct WebSocketSender<S = tokio::net::TcpStream> {
     /// The connection ID.
     connection_id: ConnectionId,
     /// The sender half.
-    sender: Arc<Mutex<SplitSink<WebSocketStream<S>, tungstenite::Message>>>,
+    sender: Arc<Mutex<SplitSink<WsStream<S>, tungstenite::Message>>>,
+    /// Metrics and the connection span.
+    telemetry: ConnectionTelemetry,
 }
 
 impl<S> WebSocketSender<S>
@@ -348,12 +559,7 @@ where
 
     /// Send a message.
     pub async fn send(&self, msg: Message) -> WsResult<()> {
-        let tungstenite_msg = tungstenite::Message::from(msg);
-        let mut sender = self.sender.lock().await;
-        sender
-            .send(tungstenite_msg)
-            .await
-            .map_err(|e| WsError::send_failed(e.to_string()))
+        send_message(&self.sender, &self.telemetry, msg).await
     }
 
     /// Send a text message.
@@ -373,20 +579,126 @@ where
     }
 }
 
-/// Helper function to get message type for logging.
-fn msg_type(msg: &Message) -> &'static str {
-    match msg {
-        Message::Text(_) => "text",
-        Message::Binary(_) => "binary",
-        Message::Ping(_) => "ping",
-        Message::Pong(_) => "pong",
-        Message::Close(_) => "close",
+/// Send a message on the sender half, recording it in the telemetry.
+async fn send_message<S>(
+    sender: &Mutex<SplitSink<WsStream<S>, tungstenite::Message>>,
+    telemetry: &ConnectionTelemetry,
+    msg: Message,
+) -> WsResult<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let record = MessageRecord::of(&msg);
+    telemetry.sending(&msg);
+    let result = sender
+        .lock()
+        .await
+        .send(tungstenite::Message::from(msg))
+        .await
+        .map_err(|e| WsError::send_failed(e.to_string()));
+    match &result {
+        Ok(()) => telemetry.sent(record),
+        Err(err) => telemetry.error(err),
+    }
+    result
+}
+
+/// Resolve when the manager signals shutdown; never resolve without one.
+async fn shutdown_requested(shutdown: &mut Option<broadcast::Receiver<()>>) {
+    if let Some(receiver) = shutdown {
+        // A closed channel means the manager was dropped without shutting down
+        if !matches!(
+            receiver.recv().await,
+            Err(broadcast::error::RecvError::Closed)
+        ) {
+            return;
+        }
+    }
+    std::future::pending().await
+}
+
+/// Create the heartbeat timer, first firing one period from now.
+///
+/// A zero period disables heartbeats.
+fn heartbeat_interval(period: Duration) -> Option<Interval> {
+    if period.is_zero() {
+        return None;
+    }
+    let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    Some(interval)
+}
+
+/// Resolve when a heartbeat is due; never resolve without a timer.
+async fn heartbeat_due(heartbeat: &mut Option<Interval>) {
+    match heartbeat {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => std::future::pending().await,
     }
 }
 
+/// Describe a receive error caused by exceeding the inbound message limits.
+fn limit_violation(err: &tungstenite::Error) -> Option<String> {
+    match err {
+        tungstenite::Error::Capacity(e) => Some(e.to_string()),
+        tungstenite::Error::Io(e) if is_continuation_limit(e) => Some(e.to_string()),
+        _ => None,
+    }
+}
+
+/// Error returned when the connection was closed for an oversized message.
+fn message_too_big(reason: String) -> WsError {
+    WsError::connection_closed(Some(CloseCode::MessageTooBig.as_u16()), reason)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::manager::ConnectionType;
+    use crate::telemetry::MessageCounts;
+    use crate::upgrade::{complete_upgrade, complete_upgrade_with_id};
+    use metrics_exporter_prometheus::PrometheusBuilder;
+    use std::collections::BTreeMap;
+    use tokio::io::DuplexStream;
+    use tungstenite::protocol::frame::coding::{Data, OpCode};
+    use tungstenite::protocol::frame::Frame;
+    use tungstenite::protocol::Role;
+
+    async fn pair(
+        config: WebSocketConfig,
+    ) -> (WebSocket<DuplexStream>, WebSocketStream<DuplexStream>) {
+        let (server_io, client_io) = tokio::io::duplex(64 * 1024);
+        let server = complete_upgrade(server_io, config).await;
+        let client = WebSocketStream::from_raw_socket(client_io, Role::Client, None).await;
+        (server, client)
+    }
+
+    async fn send_fragments(client: &mut WebSocketStream<DuplexStream>, fragments: &[&[u8]]) {
+        let last = fragments.len() - 1;
+        for (i, fragment) in fragments.iter().enumerate() {
+            let opcode = if i == 0 {
+                OpCode::Data(Data::Text)
+            } else {
+                OpCode::Data(Data::Continue)
+            };
+            let frame = Frame::message(fragment.to_vec(), opcode, i == last);
+            client
+                .send(tungstenite::Message::Frame(frame))
+                .await
+                .unwrap();
+        }
+    }
+
+    async fn expect_close_code(client: &mut WebSocketStream<DuplexStream>, code: CloseCode) {
+        match client.next().await {
+            Some(Ok(tungstenite::Message::Close(Some(frame)))) => {
+                assert_eq!(u16::from(frame.code), code.as_u16());
+            }
+            other => panic!("expected close frame, got {other:?}"),
+        }
+    }
 
     #[test]
     fn test_connection_id_new() {
@@ -415,4 +727,405 @@ mod tests {
         let uuid: Uuid = id.into();
         assert_eq!(uuid, id.as_uuid());
     }
+
+    #[tokio::test]
+    async fn test_fragmented_message_within_limits() {
+        let config = WebSocketConfig::new()
+            .max_message_size(1024)
+            .max_continuation_frames(4);
+        let (mut server, mut client) = pair(config).await;
+
+        send_fragments(&mut client, &[b"hello ", b"fragmented ", b"world"]).await;
+
+        let msg = server.recv().await.unwrap().unwrap();
+        assert_eq!(msg, Message::text("hello fragmented world"));
+        assert!(!server.is_closed());
+    }
+
+    #[tokio::test]
+    async fn test_oversize_message_closes_connection() {
+        let config = WebSocketConfig::new().max_message_size(1024);
+        let (mut server, mut client) = pair(config).await;
+
+        let chunk = [b'x'; 512];
+        send_fragments(&mut client, &[&chunk, &chunk, &chunk]).await;
+
+        let err = server.recv().await.unwrap().unwrap_err();
+        assert_eq!(err.close_code(), Some(CloseCode::MessageTooBig.as_u16()));
+        assert!(server.is_closed());
+        expect_close_code(&mut client, CloseCode::MessageTooBig).await;
+    }
+
+    #[tokio::test]
+    async fn test_too_many_continuation_frames_closes_connection() {
+        let config = WebSocketConfig::new().max_continuation_frames(2);
+        let (mut server, mut client) = pair(config).await;
+
+        send_fragments(&mut client, &[b"a", b"b", b"c", b"d"]).await;
+
+        let err = server.recv().await.unwrap().unwrap_err();
+        assert_eq!(err.close_code(), Some(CloseCode::MessageTooBig.as_u16()));
+        expect_close_code(&mut client, CloseCode::MessageTooBig).await;
+    }
+
+    #[tokio::test]
+    async fn test_manager_shutdown_closes_with_going_away() {
+        let manager = ConnectionManager::default_manager();
+        let id = manager.accept(ConnectionType::WebSocket, None).unwrap();
+        let (server_io, client_io) = tokio::io::duplex(64 * 1024);
+        let mut server = complete_upgrade_with_id(server_io, WebSocketConfig::default(), id)
+            .await
+            .with_manager(&manager);
+        let mut client = WebSocketStream::from_raw_socket(client_io, Role::Client, None).await;
+        assert_eq!(manager.attached_connections(), 1);
+
+        let drain = tokio::spawn({
+            let manager = Arc::clone(&manager);
+            async move { manager.drain().await }
+        });
+
+        assert!(server.recv().await.is_none());
+        assert!(server.is_closed());
+        expect_close_code(&mut client, CloseCode::GoingAway).await;
+
+        // Draining waits for the connection to be dropped
+        assert!(!drain.is_finished());
+        drop(server);
+        tokio::time::timeout(std::time::Duration::from_secs(1), drain)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(manager.attached_connections(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_attach_after_shutdown_goes_away() {
+        let manager = ConnectionManager::default_manager();
+        manager.shutdown();
+
+        let (mut server, mut client) = pair(WebSocketConfig::default()).await;
+        server = server.with_manager(&manager);
+
+        assert!(server.recv().await.is_none());
+        expect_close_code(&mut client, CloseCode::GoingAway).await;
+    }
+
+    #[test]
+    fn test_heartbeat_rtt_is_recorded() {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+        let manager = ConnectionManager::default_manager();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        metrics::with_local_recorder(&recorder, || {
+            runtime.block_on(async {
+                let id = manager.accept(ConnectionType::WebSocket, None).unwrap();
+                let (server_io, client_io) = tokio::io::duplex(64 * 1024);
+                let config = WebSocketConfig::new().track_rtt(true);
+                let mut server = complete_upgrade_with_id(server_io, config, id)
+                    .await
+                    .with_manager(&manager);
+                let mut client =
+                    WebSocketStream::from_raw_socket(client_io, Role::Client, None).await;
+
+                let started = Instant::now();
+                server.heartbeat().await.unwrap();
+                let payload = match client.next().await {
+                    Some(Ok(tungstenite::Message::Ping(payload))) => payload,
+                    other => panic!("expected ping, got {other:?}"),
+                };
+
+                // The peer answers late, twice, and sends a pong for no ping
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                for pong in [payload.clone(), payload, b"stray".to_vec().into()] {
+                    client.send(tungstenite::Message::Pong(pong)).await.unwrap();
+                }
+                client
+                    .send(tungstenite::Message::text("done"))
+                    .await
+                    .unwrap();
+
+                let mut rtt = None;
+                loop {
+                    match server.recv().await.unwrap().unwrap() {
+                        Message::Pong(_) => {
+                            let measured = *rtt.get_or_insert_with(|| server.rtt().unwrap());
+                            assert_eq!(server.rtt(), Some(measured));
+                        }
+                        Message::Text(_) => break,
+                        other => panic!("unexpected message {other:?}"),
+                    }
+                }
+
+                let rtt = rtt.unwrap();
+                assert!(rtt >= Duration::from_millis(20), "{rtt:?}");
+                assert!(rtt <= started.elapsed(), "{rtt:?}");
+                assert_eq!(manager.get(&id).unwrap().rtt, Some(rtt));
+            });
+        });
+
+        let rendered = handle.render();
+        assert!(
+            rendered.contains("archimedes_ws_ping_rtt_seconds_count 1"),
+            "{rendered}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_recv_sends_heartbeats() {
+        let config = WebSocketConfig::new()
+            .track_rtt(true)
+            .heartbeat_interval(Duration::from_millis(10));
+        let (mut server, mut client) = pair(config).await;
+        assert_eq!(server.rtt(), None);
+
+        // Reading is enough for the peer to answer each ping
+        let peer = tokio::spawn(async move { while let Some(Ok(_)) = client.next().await {} });
+
+        let msg = tokio::time::timeout(Duration::from_secs(1), server.recv())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert!(msg.is_pong());
+        assert!(server.rtt().is_some_and(|rtt| rtt < Duration::from_secs(1)));
+        peer.abort();
+    }
+
+    #[tokio::test]
+    async fn test_rtt_not_tracked_by_default() {
+        let (mut server, mut client) = pair(WebSocketConfig::default()).await;
+
+        server.heartbeat().await.unwrap();
+        let payload = match client.next().await {
+            Some(Ok(tungstenite::Message::Ping(payload))) => payload,
+            other => panic!("expected ping, got {other:?}"),
+        };
+        assert!(payload.is_empty());
+        client
+            .send(tungstenite::Message::Pong(payload))
+            .await
+            .unwrap();
+
+        assert!(server.recv().await.unwrap().unwrap().is_pong());
+        assert_eq!(server.rtt(), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_flooding_client_is_closed() {
+        let config = WebSocketConfig::new().max_messages_per_sec(5);
+        let (mut server, mut client) = pair(config).await;
+
+        // Pings do not count against the budget
+        for _ in 0..10 {
+            client
+                .send(tungstenite::Message::Ping(Vec::new().into()))
+                .await
+                .unwrap();
+        }
+        for i in 0..10 {
+            client
+                .send(tungstenite::Message::text(i.to_string()))
+                .await
+                .unwrap();
+        }
+
+        for _ in 0..10 {
+            assert!(server.recv().await.unwrap().unwrap().is_ping());
+        }
+        for i in 0..5 {
+            assert_eq!(
+                server.recv().await.unwrap().unwrap(),
+                Message::text(i.to_string())
+            );
+        }
+        let err = server.recv().await.unwrap().unwrap_err();
+        assert_eq!(err.close_code(), Some(CloseCode::PolicyViolation.as_u16()));
+        assert!(server.is_closed());
+        assert!(server.recv().await.is_none());
+
+        for _ in 0..10 {
+            assert!(matches!(
+                client.next().await,
+                Some(Ok(tungstenite::Message::Pong(_)))
+            ));
+        }
+        expect_close_code(&mut client, CloseCode::PolicyViolation).await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_client_under_rate_limit_is_unaffected() {
+        let config = WebSocketConfig::new().max_messages_per_sec(5);
+        let (mut server, mut client) = pair(config).await;
+
+        // An initial burst, then a steady 4 messages per second
+        for i in 0..50 {
+            client
+                .send(tungstenite::Message::text(i.to_string()))
+                .await
+                .unwrap();
+            if i >= 5 {
+                tokio::time::sleep(Duration::from_millis(250)).await;
+            }
+            assert_eq!(
+                server.recv().await.unwrap().unwrap(),
+                Message::text(i.to_string())
+            );
+        }
+        assert!(!server.is_closed());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_backpressure_holds_messages_back() {
+        let config = WebSocketConfig::new()
+            .max_messages_per_sec(2)
+            .rate_limit_action(RateLimitAction::Backpressure);
+        let (mut server, mut client) = pair(config).await;
+
+        for i in 0..6 {
+            client
+                .send(tungstenite::Message::text(i.to_string()))
+                .await
+                .unwrap();
+        }
+
+        let started = tokio::time::Instant::now();
+        for i in 0..6 {
+            assert_eq!(
+                server.recv().await.unwrap().unwrap(),
+                Message::text(i.to_string())
+            );
+        }
+        // A burst of two, then one message every 500ms
+        assert_eq!(started.elapsed(), Duration::from_secs(2));
+        assert!(!server.is_closed());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_manager_rate_limit_spans_connections() {
+        let manager = ConnectionManager::new(
+            crate::config::ConnectionManagerConfig::new().max_messages_per_sec(3),
+        );
+        let (server_a, mut client_a) = pair(WebSocketConfig::default()).await;
+        let (server_b, mut client_b) = pair(WebSocketConfig::default()).await;
+        let mut server_a = server_a.with_manager(&manager);
+        let mut server_b = server_b.with_manager(&manager);
+
+        for client in [&mut client_a, &mut client_b] {
+            for _ in 0..2 {
+                client.send(tungstenite::Message::text("hi")).await.unwrap();
+            }
+        }
+
+        assert!(server_a.recv().await.unwrap().is_ok());
+        assert!(server_a.recv().await.unwrap().is_ok());
+        assert!(server_b.recv().await.unwrap().is_ok());
+        let err = server_b.recv().await.unwrap().unwrap_err();
+        assert_eq!(err.close_code(), Some(CloseCode::PolicyViolation.as_u16()));
+        expect_close_code(&mut client_b, CloseCode::PolicyViolation).await;
+        assert!(!server_a.is_closed());
+    }
+
+    #[test]
+    fn test_traffic_is_recorded() {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+        let manager = ConnectionManager::default_manager();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        metrics::with_local_recorder(&recorder, || {
+            runtime.block_on(async {
+                let id = manager
+                    .accept(ConnectionType::WebSocket, Some("user1".to_string()))
+                    .unwrap();
+                let (server_io, client_io) = tokio::io::duplex(64 * 1024);
+                let mut server =
+                    complete_upgrade_with_id(server_io, WebSocketConfig::default(), id)
+                        .await
+                        .with_manager(&manager);
+                let mut client =
+                    WebSocketStream::from_raw_socket(client_io, Role::Client, None).await;
+
+                client
+                    .send(tungstenite::Message::text("hello"))
+                    .await
+                    .unwrap();
+                client
+                    .send(tungstenite::Message::binary(vec![1, 2, 3]))
+                    .await
+                    .unwrap();
+                client
+                    .send(tungstenite::Message::Ping(vec![9].into()))
+                    .await
+                    .unwrap();
+                for _ in 0..3 {
+                    server.recv().await.unwrap().unwrap();
+                }
+
+                server.send_text("hi").await.unwrap();
+                server.close_normal("bye").await.unwrap();
+                // The client sees the pong and the text before the close
+                loop {
+                    match client.next().await {
+                        Some(Ok(tungstenite::Message::Close(frame))) => {
+                            assert_eq!(u16::from(frame.unwrap().code), 1000);
+                            break;
+                        }
+                        Some(Ok(_)) => {}
+                        other => panic!("expected close frame, got {other:?}"),
+                    }
+                }
+
+                drop(server);
+                manager.remove(&id);
+            });
+        });
+
+        let stats = manager.stats();
+        assert_eq!(
+            stats.messages_received,
+            MessageCounts {
+                text: 1,
+                binary: 1,
+                ping: 1,
+                ..MessageCounts::default()
+            }
+        );
+        assert_eq!(
+            stats.messages_sent,
+            MessageCounts {
+                text: 1,
+                pong: 1,
+                close: 1,
+                ..MessageCounts::default()
+            }
+        );
+        assert_eq!(stats.bytes_received, 9);
+        assert_eq!(stats.bytes_sent, 8);
+        assert_eq!(stats.closes_by_code, BTreeMap::from([(1000, 1)]));
+
+        let rendered = handle.render();
+        for line in [
+            "archimedes_ws_messages_received_total{type=\"text\"} 1",
+            "archimedes_ws_messages_received_total{type=\"binary\"} 1",
+            "archimedes_ws_messages_received_total{type=\"ping\"} 1",
+            "archimedes_ws_messages_sent_total{type=\"text\"} 1",
+            "archimedes_ws_messages_sent_total{type=\"pong\"} 1",
+            "archimedes_ws_messages_sent_total{type=\"close\"} 1",
+            "archimedes_ws_received_bytes_total 9",
+            "archimedes_ws_sent_bytes_total 8",
+            "archimedes_ws_closes_total{code=\"1000\"} 1",
+            "archimedes_ws_connection_duration_seconds_count 1",
+            "archimedes_ws_connections 0",
+            "archimedes_ws_connections_by_type{type=\"websocket\"} 0",
+        ] {
+            assert!(rendered.contains(line), "missing {line:?} in:\n{rendered}");
+        }
+    }
 }
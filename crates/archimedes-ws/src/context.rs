@@ -0,0 +1,147 @@
+//! Connection metadata for WebSocket handlers.
+//!
+//! The HTTP request that opened a WebSocket connection is gone by the time
+//! the handler runs. [`WebSocketContext`] keeps the parts of it a handler
+//! typically needs: the remote address, whether the connection is secured
+//! by TLS, the negotiated subprotocol, the caller identity and a snapshot
+//! of selected request headers. It is built while the upgrade request is
+//! validated, so authorization can reject a caller before `101 Switching
+//! Protocols` is sent.
+
+use std::net::SocketAddr;
+
+use archimedes_core::CallerIdentity;
+use http::header::{AsHeaderName, HeaderMap};
+
+/// Metadata about the request that opened a WebSocket connection.
+#[derive(Debug, Clone)]
+pub struct WebSocketContext {
+    /// The remote peer address.
+    remote_addr: Option<SocketAddr>,
+    /// Whether the connection is secured by TLS.
+    secure: bool,
+    /// The negotiated subprotocol.
+    protocol: Option<String>,
+    /// The authenticated caller.
+    identity: CallerIdentity,
+    /// Snapshot of the captured request headers.
+    headers: HeaderMap,
+}
+
+impl Default for WebSocketContext {
+    fn default() -> Self {
+        Self {
+            remote_addr: None,
+            secure: false,
+            protocol: None,
+            identity: CallerIdentity::Anonymous,
+            headers: HeaderMap::new(),
+        }
+    }
+}
+
+impl WebSocketContext {
+    /// Create an empty context with an anonymous caller.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the remote peer address.
+    pub fn with_remote_addr(mut self, addr: SocketAddr) -> Self {
+        self.remote_addr = Some(addr);
+        self
+    }
+
+    /// Set whether the connection is secured by TLS.
+    pub fn with_secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    /// Set the negotiated subprotocol.
+    pub fn with_protocol(mut self, protocol: impl Into<String>) -> Self {
+        self.protocol = Some(protocol.into());
+        self
+    }
+
+    /// Set the caller identity.
+    pub fn with_identity(mut self, identity: CallerIdentity) -> Self {
+        self.identity = identity;
+        self
+    }
+
+    /// Set the captured request headers.
+    pub fn with_headers(mut self, headers: HeaderMap) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    /// Get the remote peer address.
+    pub fn remote_addr(&self) -> Option<SocketAddr> {
+        self.remote_addr
+    }
+
+    /// Check if the connection is secured by TLS.
+    pub fn is_secure(&self) -> bool {
+        self.secure
+    }
+
+    /// Get the negotiated subprotocol.
+    pub fn protocol(&self) -> Option<&str> {
+        self.protocol.as_deref()
+    }
+
+    /// Get the caller identity.
+    pub fn identity(&self) -> &CallerIdentity {
+        &self.identity
+    }
+
+    /// Get the captured request headers.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Get a captured header value as a string.
+    pub fn header(&self, name: impl AsHeaderName) -> Option<&str> {
+        self.headers.get(name).and_then(|v| v.to_str().ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use archimedes_core::CallerIdentityExt;
+    use http::HeaderValue;
+
+    #[test]
+    fn test_default_context_is_anonymous() {
+        let context = WebSocketContext::new();
+        assert!(context.remote_addr().is_none());
+        assert!(!context.is_secure());
+        assert!(context.protocol().is_none());
+        assert_eq!(context.identity().log_id(), "anonymous");
+        assert!(context.headers().is_empty());
+    }
+
+    #[test]
+    fn test_context_builder() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-tenant", HeaderValue::from_static("acme"));
+
+        let context = WebSocketContext::new()
+            .with_remote_addr("10.0.0.1:4242".parse().unwrap())
+            .with_secure(true)
+            .with_protocol("graphql-ws")
+            .with_identity(CallerIdentity::user("user-123", "alice@example.com"))
+            .with_headers(headers);
+
+        assert_eq!(
+            context.remote_addr(),
+            Some("10.0.0.1:4242".parse().unwrap())
+        );
+        assert!(context.is_secure());
+        assert_eq!(context.protocol(), Some("graphql-ws"));
+        assert_eq!(context.identity().log_id(), "user:user-123");
+        assert_eq!(context.header("x-tenant"), Some("acme"));
+    }
+}
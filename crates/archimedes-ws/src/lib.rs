@@ -8,10 +8,16 @@
 //!
 //! - **RFC 6455 compliant** WebSocket implementation using `tokio-tungstenite`
 //! - **Connection management** with configurable limits per client and globally
-//! - **Automatic ping/pong** handling for connection health
+//! - **Automatic ping/pong** handling for connection health, with optional
+//!   heartbeats measuring round-trip time
 //! - **Graceful shutdown** with connection notification
 //! - **Message types** including Text, Binary, Ping, Pong, and Close
 //! - **JSON serialization** support for typed messages
+//! - **Connection metadata** (remote address, identity, headers) for handlers,
+//!   with authorization before the upgrade is accepted
+//! - **Telemetry** through the `metrics` and `tracing` facades: connection
+//!   gauges, message and byte counters, close codes and a span per
+//!   connection (see [`telemetry`])
 //!
 //! # Example
 //!
@@ -102,30 +108,47 @@
 //!
 //! - Enforces global and per-client connection limits
 //! - Tracks connection metadata (client ID, connection time, etc.)
-//! - Supports graceful shutdown with notification to all connections
+//! - Supports graceful shutdown with notification to all connections:
+//!   attached WebSockets close with `GoingAway`, and
+//!   [`drain`](manager::ConnectionManager::drain) waits for them, e.g. from
+//!   a server drain hook
 //! - Automatically cleans up idle connections
 //!
 //! # Configuration
 //!
 //! - [`WebSocketConfig`](config::WebSocketConfig) - Per-connection settings
 //! - [`ConnectionManagerConfig`](config::ConnectionManagerConfig) - Manager settings
+//!
+//! Inbound messages exceeding `max_message_size`, `max_frame_size` or
+//! `max_continuation_frames` close the connection with
+//! [`CloseCode::MessageTooBig`]. Messages over `max_messages_per_sec`, per
+//! connection or per manager, close it with [`CloseCode::PolicyViolation`]
+//! or are held back, see [`limits`].
 
 pub mod config;
 pub mod connection;
+pub mod context;
 pub mod error;
+pub mod limits;
 pub mod manager;
 pub mod message;
+pub mod telemetry;
 pub mod upgrade;
 
 // Re-exports for convenience
 pub use config::{ConnectionManagerConfig, WebSocketConfig};
 pub use connection::{ConnectionId, WebSocket, WebSocketSender};
+pub use context::WebSocketContext;
 pub use error::{CloseCode, WsError, WsResult};
+pub use limits::{ContinuationLimitExceeded, FrameLimiter, RateLimitAction};
 pub use manager::{ConnectionInfo, ConnectionManager, ConnectionStats, ConnectionType};
 pub use message::{CloseFrame, Message};
+pub use telemetry::MessageCounts;
 pub use upgrade::{
-    complete_upgrade, complete_upgrade_with_id, get_websocket_protocols, is_websocket_request,
-    prepare_upgrade, validate_upgrade_request, WebSocketHandler, WebSocketUpgrade,
+    complete_upgrade, complete_upgrade_with_context, complete_upgrade_with_id,
+    get_websocket_protocols, is_websocket_request, prepare_upgrade, prepare_upgrade_with,
+    validate_upgrade_request, IdentityExtractor, UpgradeAuthorizer, UpgradeOptions,
+    WebSocketHandler, WebSocketUpgrade,
 };
 
 #[cfg(test)]
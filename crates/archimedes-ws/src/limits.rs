@@ -0,0 +1,321 @@
+//! Inbound frame limits.
+//!
+//! Message and frame sizes are enforced by the protocol layer, but it
+//! reassembles fragmented messages without bounding how many frames a
+//! message may span. A client can keep a message open indefinitely with a
+//! stream of tiny continuation frames. [`FrameLimiter`] wraps the raw IO
+//! stream, inspects inbound frame headers as they are read, and fails the
+//! read once a message exceeds the configured number of continuation frames.
+//!
+//! A client can also send small messages faster than handlers process them.
+//! [`WebSocket::recv`](crate::WebSocket::recv) enforces
+//! [`max_messages_per_sec`](crate::WebSocketConfig::max_messages_per_sec)
+//! per connection, and
+//! [`max_messages_per_sec`](crate::ConnectionManagerConfig::max_messages_per_sec)
+//! across the connections attached to a manager. Only text and binary
+//! messages count against the budget; control frames such as pings and
+//! pongs do not. What happens to a message over the limit is set by
+//! [`RateLimitAction`].
+
+use std::fmt;
+use std::io;
+use std::pin::Pin;
+use std::sync::{Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::time::Instant;
+
+/// Opcode of a continuation frame.
+const OPCODE_CONTINUATION: u8 = 0x0;
+
+/// Opcodes at or above this value are control frames.
+const OPCODE_CONTROL: u8 = 0x8;
+
+/// Maximum size of a frame header (2 bytes, 8 bytes of length, 4 bytes of mask).
+const MAX_HEADER_LEN: usize = 14;
+
+/// Error raised when a message spans more continuation frames than allowed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContinuationLimitExceeded {
+    /// The configured maximum.
+    pub max: usize,
+}
+
+impl fmt::Display for ContinuationLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "message exceeds {} continuation frames", self.max)
+    }
+}
+
+impl std::error::Error for ContinuationLimitExceeded {}
+
+/// IO wrapper counting continuation frames on the inbound side.
+///
+/// Writes pass through untouched.
+#[derive(Debug)]
+pub struct FrameLimiter<S> {
+    inner: S,
+    max_continuation_frames: usize,
+    continuations: usize,
+    header: [u8; MAX_HEADER_LEN],
+    header_len: usize,
+    payload_remaining: u64,
+}
+
+impl<S> FrameLimiter<S> {
+    /// Wrap a raw IO stream.
+    pub fn new(inner: S, max_continuation_frames: usize) -> Self {
+        Self {
+            inner,
+            max_continuation_frames,
+            continuations: 0,
+            header: [0; MAX_HEADER_LEN],
+            header_len: 0,
+            payload_remaining: 0,
+        }
+    }
+
+    /// Get a reference to the wrapped stream.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Length of the current header, once enough of it has been read.
+    fn expected_header_len(&self) -> Option<usize> {
+        if self.header_len < 2 {
+            return None;
+        }
+        let extended = match self.header[1] & 0x7F {
+            126 => 2,
+            127 => 8,
+            _ => 0,
+        };
+        let mask = if self.header[1] & 0x80 != 0 { 4 } else { 0 };
+        Some(2 + extended + mask)
+    }
+
+    /// Payload length encoded in a complete header.
+    fn payload_len(&self) -> u64 {
+        match self.header[1] & 0x7F {
+            126 => u64::from(u16::from_be_bytes([self.header[2], self.header[3]])),
+            127 => {
+                let mut bytes = [0; 8];
+                bytes.copy_from_slice(&self.header[2..10]);
+                u64::from_be_bytes(bytes)
+            }
+            len => u64::from(len),
+        }
+    }
+
+    /// Record a complete frame header.
+    fn on_header(&mut self) -> Result<(), ContinuationLimitExceeded> {
+        let opcode = self.header[0] & 0x0F;
+        if opcode == OPCODE_CONTINUATION {
+            self.continuations += 1;
+            if self.continuations > self.max_continuation_frames {
+                return Err(ContinuationLimitExceeded {
+                    max: self.max_continuation_frames,
+                });
+            }
+        } else if opcode < OPCODE_CONTROL {
+            // A new data frame starts a new message
+            self.continuations = 0;
+        }
+        self.payload_remaining = self.payload_len();
+        self.header_len = 0;
+        Ok(())
+    }
+
+    /// Advance the frame parser over freshly read bytes.
+    fn inspect(&mut self, mut bytes: &[u8]) -> Result<(), ContinuationLimitExceeded> {
+        while !bytes.is_empty() {
+            if self.payload_remaining > 0 {
+                let skip = usize::try_from(self.payload_remaining)
+                    .unwrap_or(usize::MAX)
+                    .min(bytes.len());
+                self.payload_remaining -= skip as u64;
+                bytes = &bytes[skip..];
+                continue;
+            }
+
+            self.header[self.header_len] = bytes[0];
+            self.header_len += 1;
+            bytes = &bytes[1..];
+
+            if self.expected_header_len() == Some(self.header_len) {
+                self.on_header()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<S> AsyncRead for FrameLimiter<S>
+where
+    S: AsyncRead + Unpin,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let before = buf.filled().len();
+        std::task::ready!(Pin::new(&mut self.inner).poll_read(cx, buf))?;
+
+        match self.inspect(&buf.filled()[before..]) {
+            Ok(()) => Poll::Ready(Ok(())),
+            Err(exceeded) => Poll::Ready(Err(io::Error::new(io::ErrorKind::InvalidData, exceeded))),
+        }
+    }
+}
+
+impl<S> AsyncWrite for FrameLimiter<S>
+where
+    S: AsyncWrite + Unpin,
+{
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+/// What to do with a message received over the rate limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RateLimitAction {
+    /// Close the connection with [`CloseCode::PolicyViolation`](crate::CloseCode::PolicyViolation).
+    #[default]
+    Close,
+    /// Hold the message back until it fits the budget. Nothing else is read
+    /// from the connection meanwhile, so TCP flow control slows the client
+    /// down.
+    Backpressure,
+}
+
+/// Limits how many messages are received per second.
+///
+/// A burst of up to the per-second limit is let through at once; after it,
+/// messages are spaced evenly at the sustained rate (the generic cell rate
+/// algorithm).
+#[derive(Debug)]
+pub(crate) struct MessageRateLimiter {
+    /// Time between two messages at the sustained rate.
+    interval: Duration,
+    /// How far ahead of the sustained rate a burst may run.
+    tolerance: Duration,
+    /// When the next message is due at the sustained rate.
+    next: Mutex<Option<Instant>>,
+}
+
+impl MessageRateLimiter {
+    /// Create a limiter letting `max` messages through per second.
+    ///
+    /// A limit of zero is treated as one.
+    pub(crate) fn per_second(max: u32) -> Self {
+        let max = max.max(1);
+        let interval = Duration::from_secs(1) / max;
+        Self {
+            interval,
+            tolerance: interval * (max - 1),
+            next: Mutex::new(None),
+        }
+    }
+
+    /// Take the budget for a message received at `now`.
+    ///
+    /// Returns how long the message must wait to fit the budget, zero if it
+    /// fits already. A message that does not fit only takes the budget,
+    /// delaying later messages, if `reserve` is set.
+    pub(crate) fn acquire(&self, now: Instant, reserve: bool) -> Duration {
+        let mut next = self.next.lock().unwrap_or_else(PoisonError::into_inner);
+        let due = next.map_or(now, |next| next.max(now));
+        let wait = (due - now).saturating_sub(self.tolerance);
+        if wait.is_zero() || reserve {
+            *next = Some(due + self.interval);
+        }
+        wait
+    }
+}
+
+/// Check whether an IO error was raised by a [`FrameLimiter`].
+pub(crate) fn is_continuation_limit(err: &io::Error) -> bool {
+    err.get_ref()
+        .is_some_and(|inner| inner.is::<ContinuationLimitExceeded>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(first: u8, payload: &[u8]) -> Vec<u8> {
+        let len = u8::try_from(payload.len()).unwrap();
+        let mut bytes = vec![first, 0x80 | len, 1, 2, 3, 4];
+        bytes.extend_from_slice(payload);
+        bytes
+    }
+
+    #[test]
+    fn test_counts_continuations_per_message() {
+        let mut limiter = FrameLimiter::new((), 2);
+        let mut bytes = frame(0x01, b"a");
+        bytes.extend(frame(0x00, b"b"));
+        bytes.extend(frame(0x89, b"")); // interleaved ping
+        bytes.extend(frame(0x80, b"c"));
+        assert!(limiter.inspect(&bytes).is_ok());
+
+        // A new message resets the count
+        let mut bytes = frame(0x02, b"a");
+        bytes.extend(frame(0x00, b"b"));
+        bytes.extend(frame(0x00, b"c"));
+        assert!(limiter.inspect(&bytes).is_ok());
+        assert_eq!(
+            limiter.inspect(&frame(0x80, b"d")),
+            Err(ContinuationLimitExceeded { max: 2 })
+        );
+    }
+
+    #[test]
+    fn test_rate_limiter_allows_burst_then_sustained_rate() {
+        let limiter = MessageRateLimiter::per_second(4);
+        let start = Instant::now();
+
+        for _ in 0..4 {
+            assert_eq!(limiter.acquire(start, false), Duration::ZERO);
+        }
+        assert_eq!(limiter.acquire(start, false), Duration::from_millis(250));
+
+        // The budget refills at the sustained rate
+        let later = start + Duration::from_millis(250);
+        assert_eq!(limiter.acquire(later, false), Duration::ZERO);
+        assert_eq!(limiter.acquire(later, false), Duration::from_millis(250));
+
+        // Reserving takes the budget even when waiting
+        assert_eq!(limiter.acquire(later, true), Duration::from_millis(250));
+        assert_eq!(limiter.acquire(later, true), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_headers_split_across_reads() {
+        let mut limiter = FrameLimiter::new((), 0);
+        let mut bytes = vec![0x82, 0x80 | 126, 0x01, 0x00, 1, 2, 3, 4];
+        bytes.extend(vec![0u8; 256]);
+        bytes.extend(frame(0x01, b"x"));
+
+        for chunk in bytes.chunks(3) {
+            assert!(limiter.inspect(chunk).is_ok());
+        }
+        assert!(limiter.inspect(&frame(0x80, b"y")).is_err());
+    }
+}
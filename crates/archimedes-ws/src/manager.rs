@@ -3,17 +3,20 @@
 //! This module provides a connection manager that tracks active WebSocket
 //! connections, enforces connection limits, and handles graceful shutdown.
 
+use std::collections::BTreeMap;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use dashmap::DashMap;
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, Notify};
 use tracing::{debug, info, warn};
 
 use crate::config::ConnectionManagerConfig;
 use crate::connection::ConnectionId;
 use crate::error::{WsError, WsResult};
+use crate::limits::MessageRateLimiter;
+use crate::telemetry::{self, MessageCounts, TrafficCounters};
 
 /// The type of WebSocket connection.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -48,6 +51,9 @@ pub struct ConnectionInfo {
     pub connection_type: ConnectionType,
     /// Optional metadata.
     pub metadata: Option<String>,
+    /// The last measured ping round-trip time, for attached WebSockets
+    /// tracking it.
+    pub rtt: Option<Duration>,
 }
 
 impl ConnectionInfo {
@@ -61,6 +67,7 @@ impl ConnectionInfo {
             last_activity: now,
             connection_type,
             metadata: None,
+            rtt: None,
         }
     }
 
@@ -102,7 +109,13 @@ impl ConnectionInfo {
     }
 }
 
+/// The connections tracked by a manager, by ID.
+pub(crate) type Connections = DashMap<ConnectionId, ConnectionInfo>;
+
 /// Statistics about the connection manager.
+///
+/// Traffic and close codes cover the connections attached with
+/// [`WebSocket::with_manager`](crate::WebSocket::with_manager).
 #[derive(Debug, Clone, Default)]
 pub struct ConnectionStats {
     /// Total number of active connections.
@@ -117,6 +130,16 @@ pub struct ConnectionStats {
     pub total_rejected: usize,
     /// Total connections closed.
     pub total_closed: usize,
+    /// Messages sent by type.
+    pub messages_sent: MessageCounts,
+    /// Messages received by type.
+    pub messages_received: MessageCounts,
+    /// Payload bytes sent.
+    pub bytes_sent: u64,
+    /// Payload bytes received.
+    pub bytes_received: u64,
+    /// Closed connections by close code.
+    pub closes_by_code: BTreeMap<u16, u64>,
 }
 
 /// A manager for tracking WebSocket and SSE connections.
@@ -145,7 +168,7 @@ pub struct ConnectionStats {
 /// ```
 pub struct ConnectionManager {
     /// Active connections.
-    connections: DashMap<ConnectionId, ConnectionInfo>,
+    connections: Arc<Connections>,
     /// Configuration.
     config: ConnectionManagerConfig,
     /// Total connections accepted.
@@ -158,20 +181,51 @@ pub struct ConnectionManager {
     shutdown_tx: broadcast::Sender<()>,
     /// Whether shutdown has been triggered.
     is_shutdown: AtomicBool,
+    /// Traffic of attached connections.
+    traffic: Arc<TrafficCounters>,
+    /// Connections attached with `WebSocket::with_manager`.
+    attached: Arc<AttachedConnections>,
+    /// Message rate limit shared by attached connections.
+    message_limiter: Option<Arc<MessageRateLimiter>>,
+}
+
+/// Counts the WebSockets attached to a manager until they are dropped.
+#[derive(Debug, Default)]
+pub(crate) struct AttachedConnections {
+    open: AtomicUsize,
+    closed: Notify,
+}
+
+/// Keeps an attached WebSocket counted; dropped with the WebSocket.
+#[derive(Debug)]
+pub(crate) struct AttachGuard(Arc<AttachedConnections>);
+
+impl Drop for AttachGuard {
+    fn drop(&mut self) {
+        if self.0.open.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.0.closed.notify_waiters();
+        }
+    }
 }
 
 impl ConnectionManager {
     /// Create a new connection manager.
     pub fn new(config: ConnectionManagerConfig) -> Arc<Self> {
         let (shutdown_tx, _) = broadcast::channel(1);
+        let message_limiter = config
+            .max_messages_per_sec
+            .map(|max| Arc::new(MessageRateLimiter::per_second(max)));
         Arc::new(Self {
-            connections: DashMap::new(),
+            connections: Arc::new(DashMap::new()),
             config,
             total_accepted: AtomicUsize::new(0),
             total_rejected: AtomicUsize::new(0),
             total_closed: AtomicUsize::new(0),
             shutdown_tx,
             is_shutdown: AtomicBool::new(false),
+            traffic: Arc::new(TrafficCounters::default()),
+            attached: Arc::new(AttachedConnections::default()),
+            message_limiter,
         })
     }
 
@@ -255,6 +309,7 @@ impl ConnectionManager {
 
         self.connections.insert(id, info);
         self.total_accepted.fetch_add(1, Ordering::Relaxed);
+        telemetry::connection_opened(connection_type);
 
         debug!(
             connection_id = %id,
@@ -310,6 +365,7 @@ impl ConnectionManager {
 
         self.connections.insert(id, info);
         self.total_accepted.fetch_add(1, Ordering::Relaxed);
+        telemetry::connection_opened(connection_type);
 
         Ok(())
     }
@@ -317,8 +373,9 @@ impl ConnectionManager {
     /// Remove a connection.
     pub fn remove(&self, id: &ConnectionId) -> Option<ConnectionInfo> {
         let removed = self.connections.remove(id).map(|(_, info)| info);
-        if removed.is_some() {
+        if let Some(info) = &removed {
             self.total_closed.fetch_add(1, Ordering::Relaxed);
+            telemetry::connection_removed(info.connection_type);
             debug!(connection_id = %id, "Connection removed");
         }
         removed
@@ -370,9 +427,42 @@ impl ConnectionManager {
             total_accepted: self.total_accepted.load(Ordering::Relaxed),
             total_rejected: self.total_rejected.load(Ordering::Relaxed),
             total_closed: self.total_closed.load(Ordering::Relaxed),
+            messages_sent: self.traffic.messages_sent(),
+            messages_received: self.traffic.messages_received(),
+            bytes_sent: self.traffic.bytes_sent(),
+            bytes_received: self.traffic.bytes_received(),
+            closes_by_code: self.traffic.closes_by_code(),
         }
     }
 
+    /// Get the traffic counters shared by attached connections.
+    pub(crate) fn traffic(&self) -> Arc<TrafficCounters> {
+        Arc::clone(&self.traffic)
+    }
+
+    /// Get the tracked connections, for attached WebSockets to update.
+    pub(crate) fn connections_handle(&self) -> Arc<Connections> {
+        Arc::clone(&self.connections)
+    }
+
+    /// Get the message rate limit shared by attached WebSockets, if any.
+    pub(crate) fn message_limiter(&self) -> Option<Arc<MessageRateLimiter>> {
+        self.message_limiter.clone()
+    }
+
+    /// Count a WebSocket as attached until the guard is dropped.
+    pub(crate) fn attach(&self) -> AttachGuard {
+        self.attached.open.fetch_add(1, Ordering::SeqCst);
+        AttachGuard(Arc::clone(&self.attached))
+    }
+
+    /// Get the number of WebSockets attached with
+    /// [`WebSocket::with_manager`](crate::WebSocket::with_manager) that are
+    /// still open.
+    pub fn attached_connections(&self) -> usize {
+        self.attached.open.load(Ordering::SeqCst)
+    }
+
     /// Get all connection IDs.
     pub fn connection_ids(&self) -> Vec<ConnectionId> {
         self.connections.iter().map(|e| *e.key()).collect()
@@ -423,6 +513,40 @@ impl ConnectionManager {
         count
     }
 
+    /// Trigger shutdown and wait for attached connections to close.
+    ///
+    /// Attached WebSockets answer the shutdown by sending a Close frame
+    /// with [`CloseCode::GoingAway`](crate::CloseCode::GoingAway) from
+    /// their next [`recv`](crate::WebSocket::recv). This resolves once
+    /// every attached WebSocket has been dropped; bound it with a timeout
+    /// to force shutdown past slow clients.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use archimedes_ws::ConnectionManager;
+    /// use std::time::Duration;
+    ///
+    /// # tokio_test::block_on(async {
+    /// let manager = ConnectionManager::default_manager();
+    /// let drained = tokio::time::timeout(Duration::from_secs(5), manager.drain()).await;
+    /// assert!(drained.is_ok());
+    /// # });
+    /// ```
+    pub async fn drain(&self) {
+        self.shutdown();
+
+        loop {
+            let closed = self.attached.closed.notified();
+            tokio::pin!(closed);
+            closed.as_mut().enable();
+            if self.attached_connections() == 0 {
+                return;
+            }
+            closed.await;
+        }
+    }
+
     /// Remove idle connections that have exceeded the idle timeout.
     ///
     /// Returns the number of connections removed.
@@ -439,9 +563,10 @@ impl ConnectionManager {
             .collect();
 
         for id in to_remove {
-            if self.connections.remove(&id).is_some() {
+            if let Some((_, info)) = self.connections.remove(&id) {
                 removed += 1;
                 self.total_closed.fetch_add(1, Ordering::Relaxed);
+                telemetry::connection_removed(info.connection_type);
                 debug!(connection_id = %id, "Removed idle connection");
             }
         }
@@ -465,6 +590,7 @@ mod tests {
             max_per_client: 3,
             idle_timeout: Duration::from_millis(100),
             cleanup_interval: Duration::from_millis(50),
+            max_messages_per_sec: None,
         }
     }
 
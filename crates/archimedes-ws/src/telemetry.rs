@@ -0,0 +1,526 @@
+//! Connection metrics and tracing.
+//!
+//! Metrics are recorded through the [`metrics`] facade, so they go to
+//! whichever recorder the application installs (for example the Prometheus
+//! exporter set up by `archimedes-telemetry`) and cost nothing otherwise.
+//!
+//! | Metric | Type | Labels | Description |
+//! |--------|------|--------|-------------|
+//! | `archimedes_ws_connections` | Gauge | - | Open connections |
+//! | `archimedes_ws_connections_by_type` | Gauge | `type` | Open connections per [`ConnectionType`] |
+//! | `archimedes_ws_messages_sent_total` | Counter | `type` | Messages sent |
+//! | `archimedes_ws_messages_received_total` | Counter | `type` | Messages received |
+//! | `archimedes_ws_sent_bytes_total` | Counter | - | Payload bytes sent |
+//! | `archimedes_ws_received_bytes_total` | Counter | - | Payload bytes received |
+//! | `archimedes_ws_closes_total` | Counter | `code` | Closed connections by close code |
+//! | `archimedes_ws_connection_duration_seconds` | Histogram | - | Connection lifetime |
+//! | `archimedes_ws_ping_rtt_seconds` | Histogram | - | Ping round-trip time |
+//!
+//! The connection gauges are maintained by the
+//! [`ConnectionManager`](crate::ConnectionManager), which knows the type of
+//! each connection. The other metrics are recorded by the [`WebSocket`]
+//! itself. A connection that ends without a close handshake is counted
+//! under [`CloseCode::Abnormal`].
+//!
+//! Ping round-trip times are only measured on connections configured with
+//! [`track_rtt`](crate::WebSocketConfig::track_rtt). Each outgoing ping is
+//! timestamped and matched with the pong echoing its payload; pongs that
+//! match no outstanding ping, including repeated ones, are ignored. The
+//! last round-trip time is also kept in the manager's
+//! [`ConnectionInfo`](crate::ConnectionInfo).
+//!
+//! Each [`WebSocket`] also owns a long-lived `ws.connection` span carrying
+//! the connection ID, client ID and negotiated subprotocol. Per-call spans
+//! of `recv` and `send` are its children, and message errors are recorded
+//! on it as events.
+//!
+//! [`WebSocket`]: crate::WebSocket
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use metrics::{counter, gauge, histogram};
+use tracing::{field, warn, Span};
+
+use crate::connection::ConnectionId;
+use crate::error::{CloseCode, WsError};
+use crate::manager::{ConnectionType, Connections};
+use crate::message::{CloseFrame, Message};
+
+/// Open connections.
+pub const CONNECTIONS: &str = "archimedes_ws_connections";
+/// Open connections per connection type.
+pub const CONNECTIONS_BY_TYPE: &str = "archimedes_ws_connections_by_type";
+/// Messages sent, by message type.
+pub const MESSAGES_SENT: &str = "archimedes_ws_messages_sent_total";
+/// Messages received, by message type.
+pub const MESSAGES_RECEIVED: &str = "archimedes_ws_messages_received_total";
+/// Payload bytes sent.
+pub const SENT_BYTES: &str = "archimedes_ws_sent_bytes_total";
+/// Payload bytes received.
+pub const RECEIVED_BYTES: &str = "archimedes_ws_received_bytes_total";
+/// Closed connections, by close code.
+pub const CLOSES: &str = "archimedes_ws_closes_total";
+/// Connection lifetime in seconds.
+pub const CONNECTION_DURATION: &str = "archimedes_ws_connection_duration_seconds";
+/// Ping round-trip time in seconds.
+pub const PING_RTT: &str = "archimedes_ws_ping_rtt_seconds";
+
+/// Maximum number of pings awaiting a pong; older ones are forgotten.
+const MAX_PENDING_PINGS: usize = 16;
+
+/// Message counts by message type.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MessageCounts {
+    /// Text messages.
+    pub text: u64,
+    /// Binary messages.
+    pub binary: u64,
+    /// Ping frames.
+    pub ping: u64,
+    /// Pong frames.
+    pub pong: u64,
+    /// Close frames.
+    pub close: u64,
+}
+
+impl MessageCounts {
+    /// Get the total number of messages.
+    pub fn total(&self) -> u64 {
+        self.text + self.binary + self.ping + self.pong + self.close
+    }
+}
+
+/// Get the label for a message type.
+pub(crate) fn message_type(msg: &Message) -> &'static str {
+    match msg {
+        Message::Text(_) => "text",
+        Message::Binary(_) => "binary",
+        Message::Ping(_) => "ping",
+        Message::Pong(_) => "pong",
+        Message::Close(_) => "close",
+    }
+}
+
+/// Get the code of a close frame, or `1005` if it carries none.
+fn close_code(frame: Option<&CloseFrame>) -> u16 {
+    frame.map_or(CloseCode::NoStatus.as_u16(), |frame| frame.code)
+}
+
+/// What is recorded about a message.
+///
+/// Taken before a message is handed to the sink, so the message itself
+/// does not have to be kept until the send completes.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct MessageRecord {
+    /// The message type label.
+    kind: &'static str,
+    /// The payload size in bytes.
+    bytes: u64,
+    /// The close code, for close frames.
+    close_code: Option<u16>,
+}
+
+impl MessageRecord {
+    /// Summarize a message.
+    pub(crate) fn of(msg: &Message) -> Self {
+        Self {
+            kind: message_type(msg),
+            bytes: msg.len() as u64,
+            close_code: match msg {
+                Message::Close(frame) => Some(close_code(frame.as_ref())),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Get the label for a connection type.
+fn connection_type_label(connection_type: ConnectionType) -> &'static str {
+    match connection_type {
+        ConnectionType::WebSocket => "websocket",
+        ConnectionType::ServerSentEvents => "sse",
+    }
+}
+
+/// Record a connection being opened.
+pub(crate) fn connection_opened(connection_type: ConnectionType) {
+    gauge!(CONNECTIONS).increment(1.0);
+    gauge!(CONNECTIONS_BY_TYPE, "type" => connection_type_label(connection_type)).increment(1.0);
+}
+
+/// Record a connection being removed.
+pub(crate) fn connection_removed(connection_type: ConnectionType) {
+    gauge!(CONNECTIONS).decrement(1.0);
+    gauge!(CONNECTIONS_BY_TYPE, "type" => connection_type_label(connection_type)).decrement(1.0);
+}
+
+/// Per-type message counters.
+#[derive(Debug, Default)]
+struct MessageCounters {
+    text: AtomicU64,
+    binary: AtomicU64,
+    ping: AtomicU64,
+    pong: AtomicU64,
+    close: AtomicU64,
+}
+
+impl MessageCounters {
+    fn increment(&self, kind: &str) {
+        let counter = match kind {
+            "text" => &self.text,
+            "binary" => &self.binary,
+            "ping" => &self.ping,
+            "pong" => &self.pong,
+            _ => &self.close,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> MessageCounts {
+        MessageCounts {
+            text: self.text.load(Ordering::Relaxed),
+            binary: self.binary.load(Ordering::Relaxed),
+            ping: self.ping.load(Ordering::Relaxed),
+            pong: self.pong.load(Ordering::Relaxed),
+            close: self.close.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Traffic totals shared by the connections of a manager.
+#[derive(Debug, Default)]
+pub(crate) struct TrafficCounters {
+    sent: MessageCounters,
+    received: MessageCounters,
+    bytes_sent: AtomicU64,
+    bytes_received: AtomicU64,
+    closes: Mutex<BTreeMap<u16, u64>>,
+}
+
+impl TrafficCounters {
+    /// Get the messages sent by type.
+    pub(crate) fn messages_sent(&self) -> MessageCounts {
+        self.sent.snapshot()
+    }
+
+    /// Get the messages received by type.
+    pub(crate) fn messages_received(&self) -> MessageCounts {
+        self.received.snapshot()
+    }
+
+    /// Get the payload bytes sent.
+    pub(crate) fn bytes_sent(&self) -> u64 {
+        self.bytes_sent.load(Ordering::Relaxed)
+    }
+
+    /// Get the payload bytes received.
+    pub(crate) fn bytes_received(&self) -> u64 {
+        self.bytes_received.load(Ordering::Relaxed)
+    }
+
+    /// Get the closed connections by close code.
+    pub(crate) fn closes_by_code(&self) -> BTreeMap<u16, u64> {
+        lock(&self.closes).clone()
+    }
+}
+
+/// Round-trip time measurement of pings.
+#[derive(Debug, Default)]
+pub(crate) struct RttTracker {
+    /// Payloads of pings awaiting a pong with when they were sent, oldest
+    /// first.
+    pending: Mutex<VecDeque<(Vec<u8>, Instant)>>,
+    /// The last measured round-trip time.
+    last: Mutex<Option<Duration>>,
+    /// Sequence number of the next heartbeat ping.
+    next_seq: AtomicU64,
+}
+
+impl RttTracker {
+    /// Get a payload for a heartbeat ping, unique on this connection.
+    fn next_payload(&self) -> Vec<u8> {
+        self.next_seq
+            .fetch_add(1, Ordering::Relaxed)
+            .to_be_bytes()
+            .to_vec()
+    }
+
+    /// Timestamp a ping being sent.
+    ///
+    /// A ping repeating the payload of one still awaiting its pong keeps
+    /// the earlier timestamp.
+    fn ping_sent(&self, payload: &[u8], at: Instant) {
+        let mut pending = lock(&self.pending);
+        if pending.iter().any(|(p, _)| p == payload) {
+            return;
+        }
+        if pending.len() == MAX_PENDING_PINGS {
+            pending.pop_front();
+        }
+        pending.push_back((payload.to_vec(), at));
+    }
+
+    /// Match a pong with its ping, returning the round-trip time.
+    ///
+    /// Returns `None` for a pong matching no outstanding ping.
+    fn pong_received(&self, payload: &[u8]) -> Option<Duration> {
+        let mut pending = lock(&self.pending);
+        let index = pending.iter().position(|(p, _)| p == payload)?;
+        // A peer may answer only the latest of several pings (RFC 6455,
+        // section 5.5.3), so earlier ones will not be answered anymore
+        let (_, sent_at) = pending.drain(..=index).last()?;
+        let rtt = sent_at.elapsed();
+        *lock(&self.last) = Some(rtt);
+        Some(rtt)
+    }
+
+    /// Get the last measured round-trip time.
+    fn last(&self) -> Option<Duration> {
+        *lock(&self.last)
+    }
+}
+
+/// Lock a mutex, ignoring poisoning.
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// Telemetry for a single connection.
+///
+/// Cloned into each [`WebSocketSender`](crate::WebSocketSender), so
+/// messages sent from other tasks are counted against the connection.
+#[derive(Debug, Clone)]
+pub(crate) struct ConnectionTelemetry {
+    /// The connection ID.
+    connection_id: ConnectionId,
+    /// The long-lived connection span.
+    span: Span,
+    /// When the connection was established.
+    connected_at: Instant,
+    /// Whether the close has been recorded.
+    closed: Arc<AtomicBool>,
+    /// Totals of the manager tracking this connection, if any.
+    shared: Option<Arc<TrafficCounters>>,
+    /// Connections of the manager tracking this connection, if any.
+    connections: Option<Arc<Connections>>,
+    /// Round-trip time measurement, if enabled.
+    rtt: Option<Arc<RttTracker>>,
+}
+
+impl ConnectionTelemetry {
+    /// Create telemetry for a connection established at `connected_at`.
+    pub(crate) fn new(connection_id: ConnectionId, connected_at: Instant) -> Self {
+        let span = tracing::info_span!(
+            "ws.connection",
+            connection_id = %connection_id,
+            client_id = field::Empty,
+            subprotocol = field::Empty,
+        );
+        Self {
+            connection_id,
+            span,
+            connected_at,
+            closed: Arc::new(AtomicBool::new(false)),
+            shared: None,
+            connections: None,
+            rtt: None,
+        }
+    }
+
+    /// Get the connection span.
+    pub(crate) fn span(&self) -> &Span {
+        &self.span
+    }
+
+    /// Record the client ID on the connection span.
+    pub(crate) fn set_client_id(&self, client_id: &str) {
+        self.span.record("client_id", client_id);
+    }
+
+    /// Record the negotiated subprotocol on the connection span.
+    pub(crate) fn set_subprotocol(&self, protocol: &str) {
+        self.span.record("subprotocol", protocol);
+    }
+
+    /// Aggregate this connection's traffic into a manager's totals.
+    pub(crate) fn set_shared(&mut self, shared: Arc<TrafficCounters>) {
+        self.shared = Some(shared);
+    }
+
+    /// Keep the round-trip time in a manager's connection info.
+    pub(crate) fn set_connections(&mut self, connections: Arc<Connections>) {
+        self.connections = Some(connections);
+    }
+
+    /// Measure ping round-trip times.
+    pub(crate) fn enable_rtt(&mut self) {
+        self.rtt = Some(Arc::default());
+    }
+
+    /// Get the last measured ping round-trip time.
+    pub(crate) fn rtt(&self) -> Option<Duration> {
+        self.rtt.as_ref().and_then(|rtt| rtt.last())
+    }
+
+    /// Get a payload for a heartbeat ping.
+    ///
+    /// Payloads are unique on a connection measuring round-trip times, and
+    /// empty otherwise.
+    pub(crate) fn heartbeat_payload(&self) -> Vec<u8> {
+        self.rtt
+            .as_ref()
+            .map_or_else(Vec::new, |rtt| rtt.next_payload())
+    }
+
+    /// Timestamp a message about to be sent, if it is a ping.
+    pub(crate) fn sending(&self, msg: &Message) {
+        if let (Some(rtt), Message::Ping(payload)) = (&self.rtt, msg) {
+            rtt.ping_sent(payload, Instant::now());
+        }
+    }
+
+    /// Record the round-trip time of the ping a pong answers.
+    fn pong_received(&self, payload: &[u8]) {
+        let Some(rtt) = self.rtt.as_ref().and_then(|rtt| rtt.pong_received(payload)) else {
+            return;
+        };
+        histogram!(PING_RTT).record(rtt.as_secs_f64());
+        if let Some(mut info) = self
+            .connections
+            .as_ref()
+            .and_then(|connections| connections.get_mut(&self.connection_id))
+        {
+            info.rtt = Some(rtt);
+        }
+    }
+
+    /// Record a message that was sent.
+    ///
+    /// Sending a close frame records the close.
+    pub(crate) fn sent(&self, record: MessageRecord) {
+        counter!(MESSAGES_SENT, "type" => record.kind).increment(1);
+        counter!(SENT_BYTES).increment(record.bytes);
+        if let Some(shared) = &self.shared {
+            shared.sent.increment(record.kind);
+            shared.bytes_sent.fetch_add(record.bytes, Ordering::Relaxed);
+        }
+        if let Some(code) = record.close_code {
+            self.closed(code);
+        }
+    }
+
+    /// Record a message that was received.
+    ///
+    /// Receiving a close frame records the close.
+    pub(crate) fn received(&self, msg: &Message) {
+        let record = MessageRecord::of(msg);
+        counter!(MESSAGES_RECEIVED, "type" => record.kind).increment(1);
+        counter!(RECEIVED_BYTES).increment(record.bytes);
+        if let Some(shared) = &self.shared {
+            shared.received.increment(record.kind);
+            shared
+                .bytes_received
+                .fetch_add(record.bytes, Ordering::Relaxed);
+        }
+        if let Some(code) = record.close_code {
+            self.closed(code);
+        }
+        if let Message::Pong(payload) = msg {
+            self.pong_received(payload);
+        }
+    }
+
+    /// Record a message handling error as an event on the connection span.
+    pub(crate) fn error(&self, error: &WsError) {
+        warn!(parent: &self.span, error = %error, "WebSocket message error");
+    }
+
+    /// Record the connection closing with `code`.
+    ///
+    /// Only the first close of a connection is recorded, so a close frame
+    /// sent in reply to the peer's does not count twice.
+    pub(crate) fn closed(&self, code: u16) {
+        if self.closed.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let duration = self.connected_at.elapsed();
+        counter!(CLOSES, "code" => code.to_string()).increment(1);
+        histogram!(CONNECTION_DURATION).record(duration.as_secs_f64());
+        if let Some(shared) = &self.shared {
+            *lock(&shared.closes).entry(code).or_insert(0) += 1;
+        }
+    }
+
+    /// Record the connection ending without a close handshake.
+    pub(crate) fn aborted(&self) {
+        self.closed(CloseCode::Abnormal.as_u16());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_counts_total() {
+        let counts = MessageCounts {
+            text: 3,
+            binary: 1,
+            ping: 2,
+            pong: 2,
+            close: 1,
+        };
+        assert_eq!(counts.total(), 9);
+    }
+
+    #[test]
+    fn test_close_recorded_once() {
+        let shared = Arc::new(TrafficCounters::default());
+        let mut telemetry = ConnectionTelemetry::new(ConnectionId::new(), Instant::now());
+        telemetry.set_shared(Arc::clone(&shared));
+
+        telemetry.closed(CloseCode::Normal.as_u16());
+        telemetry.clone().aborted();
+
+        assert_eq!(shared.closes_by_code(), BTreeMap::from([(1000, 1)]));
+    }
+
+    #[test]
+    fn test_rtt_tracker_matches_pongs() {
+        let tracker = RttTracker::default();
+        let first = tracker.next_payload();
+        let second = tracker.next_payload();
+        assert_ne!(first, second);
+
+        let sent_at = Instant::now();
+        tracker.ping_sent(&first, sent_at);
+        tracker.ping_sent(&second, sent_at);
+        tracker.ping_sent(&first, Instant::now());
+
+        assert_eq!(tracker.pong_received(b"unknown"), None);
+        assert!(tracker.pong_received(&first).is_some());
+        assert_eq!(tracker.pong_received(&first), None);
+        assert!(tracker.pong_received(&second).is_some());
+        assert_eq!(tracker.pong_received(&second), None);
+        assert!(tracker.last().is_some());
+    }
+
+    #[test]
+    fn test_rtt_tracker_answer_to_latest_ping_settles_earlier() {
+        let tracker = RttTracker::default();
+        for _ in 0..MAX_PENDING_PINGS + 4 {
+            tracker.ping_sent(&tracker.next_payload(), Instant::now());
+        }
+        assert_eq!(lock(&tracker.pending).len(), MAX_PENDING_PINGS);
+
+        let latest = (MAX_PENDING_PINGS as u64 + 3).to_be_bytes();
+        assert!(tracker.pong_received(&latest).is_some());
+        assert!(lock(&tracker.pending).is_empty());
+        assert_eq!(tracker.pong_received(&0u64.to_be_bytes()), None);
+    }
+}
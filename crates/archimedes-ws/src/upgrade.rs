@@ -3,10 +3,16 @@
 //! This module provides functionality for upgrading HTTP connections
 //! to WebSocket connections according to RFC 6455.
 
+use std::fmt;
 use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use archimedes_core::CallerIdentity;
 
 use base64::Engine;
-use http::{header, Request, Response, StatusCode};
+use http::header::HeaderName;
+use http::{header, Extensions, HeaderMap, Request, Response, StatusCode};
 use http_body_util::Full;
 use hyper::body::Bytes;
 use sha1::{Digest, Sha1};
@@ -16,7 +22,9 @@ use tracing::{debug, instrument};
 
 use crate::config::WebSocketConfig;
 use crate::connection::{ConnectionId, WebSocket};
+use crate::context::WebSocketContext;
 use crate::error::{WsError, WsResult};
+use crate::limits::FrameLimiter;
 
 /// The WebSocket magic GUID used in the handshake.
 const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
@@ -128,6 +136,15 @@ fn create_bad_request_response(reason: &str) -> Response<Full<Bytes>> {
         .unwrap()
 }
 
+/// Create a forbidden response for a rejected upgrade.
+fn create_forbidden_response(reason: &str) -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(StatusCode::FORBIDDEN)
+        .header(header::CONTENT_TYPE, "text/plain")
+        .body(Full::new(Bytes::from(reason.to_string())))
+        .unwrap()
+}
+
 /// A WebSocket upgrade result.
 ///
 /// This is returned from the upgrade process and contains either
@@ -139,15 +156,18 @@ pub struct WebSocketUpgrade {
     pub protocol: Option<String>,
     /// Whether the upgrade was successful.
     pub success: bool,
+    /// Connection metadata to hand to the handler, if the upgrade succeeded.
+    pub context: Option<WebSocketContext>,
 }
 
 impl WebSocketUpgrade {
     /// Create a successful upgrade.
-    fn success(response: Response<Full<Bytes>>, protocol: Option<String>) -> Self {
+    fn success(response: Response<Full<Bytes>>, context: WebSocketContext) -> Self {
         Self {
             response,
-            protocol,
+            protocol: context.protocol().map(String::from),
             success: true,
+            context: Some(context),
         }
     }
 
@@ -157,6 +177,7 @@ impl WebSocketUpgrade {
             response,
             protocol: None,
             success: false,
+            context: None,
         }
     }
 }
@@ -186,6 +207,126 @@ pub fn validate_upgrade_request<B>(request: &Request<B>) -> WsResult<String> {
     Ok(compute_accept_key(key))
 }
 
+/// Resolves the caller identity from the upgrade request.
+pub type IdentityExtractor = Arc<dyn Fn(&HeaderMap, &Extensions) -> CallerIdentity + Send + Sync>;
+
+/// Decides whether a caller may open a WebSocket connection.
+///
+/// Returning an error rejects the upgrade with `403 Forbidden` and the
+/// error as the response body.
+pub type UpgradeAuthorizer = Arc<dyn Fn(&WebSocketContext) -> Result<(), String> + Send + Sync>;
+
+/// Options controlling how an upgrade request is validated and what
+/// connection metadata is captured for the handler.
+///
+/// By default the caller identity is taken from a [`CallerIdentity`] in the
+/// request extensions (as left by the identity middleware), falling back to
+/// anonymous.
+#[derive(Clone, Default)]
+pub struct UpgradeOptions {
+    /// Allowed subprotocols, in order of preference.
+    allowed_protocols: Option<Vec<String>>,
+    /// The remote peer address.
+    remote_addr: Option<SocketAddr>,
+    /// Whether the connection is secured by TLS.
+    secure: bool,
+    /// Request headers copied into the connection context.
+    captured_headers: Vec<HeaderName>,
+    /// Custom identity extraction.
+    identity: Option<IdentityExtractor>,
+    /// Authorization check run before the upgrade is accepted.
+    authorizer: Option<UpgradeAuthorizer>,
+}
+
+impl fmt::Debug for UpgradeOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UpgradeOptions")
+            .field("allowed_protocols", &self.allowed_protocols)
+            .field("remote_addr", &self.remote_addr)
+            .field("secure", &self.secure)
+            .field("captured_headers", &self.captured_headers)
+            .field("identity", &self.identity.is_some())
+            .field("authorizer", &self.authorizer.is_some())
+            .finish()
+    }
+}
+
+impl UpgradeOptions {
+    /// Create default upgrade options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the allowed subprotocols.
+    pub fn allowed_protocols<I, P>(mut self, protocols: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<String>,
+    {
+        self.allowed_protocols = Some(protocols.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Set the remote peer address.
+    pub fn remote_addr(mut self, addr: SocketAddr) -> Self {
+        self.remote_addr = Some(addr);
+        self
+    }
+
+    /// Set whether the connection is secured by TLS.
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    /// Copy a request header into the connection context.
+    pub fn capture_header(mut self, name: HeaderName) -> Self {
+        self.captured_headers.push(name);
+        self
+    }
+
+    /// Set a custom identity extractor.
+    pub fn identity<F>(mut self, extractor: F) -> Self
+    where
+        F: Fn(&HeaderMap, &Extensions) -> CallerIdentity + Send + Sync + 'static,
+    {
+        self.identity = Some(Arc::new(extractor));
+        self
+    }
+
+    /// Set the authorization check run before the upgrade is accepted.
+    pub fn authorize<F>(mut self, authorizer: F) -> Self
+    where
+        F: Fn(&WebSocketContext) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.authorizer = Some(Arc::new(authorizer));
+        self
+    }
+
+    /// Resolve the caller identity for a request.
+    fn resolve_identity<B>(&self, request: &Request<B>) -> CallerIdentity {
+        match &self.identity {
+            Some(extractor) => extractor(request.headers(), request.extensions()),
+            None => request
+                .extensions()
+                .get::<CallerIdentity>()
+                .cloned()
+                .unwrap_or(CallerIdentity::Anonymous),
+        }
+    }
+
+    /// Copy the captured headers out of a request.
+    fn snapshot_headers<B>(&self, request: &Request<B>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for name in &self.captured_headers {
+            for value in request.headers().get_all(name) {
+                headers.append(name.clone(), value.clone());
+            }
+        }
+        headers
+    }
+}
+
 /// Prepare a WebSocket upgrade.
 ///
 /// This validates the request and prepares the upgrade response.
@@ -206,6 +347,22 @@ pub fn prepare_upgrade<B>(
     request: &Request<B>,
     allowed_protocols: Option<&[&str]>,
 ) -> WebSocketUpgrade {
+    let mut options = UpgradeOptions::new();
+    if let Some(allowed) = allowed_protocols {
+        options = options.allowed_protocols(allowed.iter().copied());
+    }
+    prepare_upgrade_with(request, &options)
+}
+
+/// Prepare a WebSocket upgrade with connection metadata and authorization.
+///
+/// Like [`prepare_upgrade`], but also resolves the caller identity and
+/// builds the [`WebSocketContext`] for the handler. The authorizer, if any,
+/// runs here, so a rejected caller receives `403 Forbidden` instead of
+/// `101 Switching Protocols`. Complete a successful upgrade with
+/// [`complete_upgrade_with_context`].
+#[instrument(skip(request, options))]
+pub fn prepare_upgrade_with<B>(request: &Request<B>, options: &UpgradeOptions) -> WebSocketUpgrade {
     let accept_key = match validate_upgrade_request(request) {
         Ok(key) => key,
         Err(e) => {
@@ -215,7 +372,7 @@ pub fn prepare_upgrade<B>(
     };
 
     // Select subprotocol if requested
-    let selected_protocol = if let Some(allowed) = allowed_protocols {
+    let selected_protocol = if let Some(allowed) = &options.allowed_protocols {
         let requested = get_websocket_protocols(request);
         requested
             .iter()
@@ -225,8 +382,26 @@ pub fn prepare_upgrade<B>(
         None
     };
 
-    let response = create_upgrade_response(&accept_key, selected_protocol.as_deref());
-    WebSocketUpgrade::success(response, selected_protocol)
+    let mut context = WebSocketContext::new()
+        .with_secure(options.secure)
+        .with_identity(options.resolve_identity(request))
+        .with_headers(options.snapshot_headers(request));
+    if let Some(addr) = options.remote_addr {
+        context = context.with_remote_addr(addr);
+    }
+    if let Some(protocol) = selected_protocol {
+        context = context.with_protocol(protocol);
+    }
+
+    if let Some(authorizer) = &options.authorizer {
+        if let Err(reason) = authorizer(&context) {
+            debug!("WebSocket upgrade rejected: {}", reason);
+            return WebSocketUpgrade::failure(create_forbidden_response(&reason));
+        }
+    }
+
+    let response = create_upgrade_response(&accept_key, context.protocol());
+    WebSocketUpgrade::success(response, context)
 }
 
 /// Complete a WebSocket upgrade.
@@ -246,8 +421,13 @@ pub async fn complete_upgrade<S>(stream: S, config: WebSocketConfig) -> WebSocke
 where
     S: AsyncRead + AsyncWrite + Unpin,
 {
-    let ws_stream =
-        WebSocketStream::from_raw_socket(stream, tungstenite::protocol::Role::Server, None).await;
+    let stream = FrameLimiter::new(stream, config.max_continuation_frames);
+    let ws_stream = WebSocketStream::from_raw_socket(
+        stream,
+        tungstenite::protocol::Role::Server,
+        Some(config.protocol_config()),
+    )
+    .await;
 
     WebSocket::new(ws_stream, config)
 }
@@ -261,12 +441,33 @@ pub async fn complete_upgrade_with_id<S>(
 where
     S: AsyncRead + AsyncWrite + Unpin,
 {
-    let ws_stream =
-        WebSocketStream::from_raw_socket(stream, tungstenite::protocol::Role::Server, None).await;
+    let stream = FrameLimiter::new(stream, config.max_continuation_frames);
+    let ws_stream = WebSocketStream::from_raw_socket(
+        stream,
+        tungstenite::protocol::Role::Server,
+        Some(config.protocol_config()),
+    )
+    .await;
 
     WebSocket::with_id(ws_stream, config, connection_id)
 }
 
+/// Complete a WebSocket upgrade, attaching the connection metadata.
+///
+/// Pass the [`WebSocketUpgrade::context`] produced by
+/// [`prepare_upgrade_with`] so the handler can read it through
+/// [`WebSocket::context`].
+pub async fn complete_upgrade_with_context<S>(
+    stream: S,
+    config: WebSocketConfig,
+    context: WebSocketContext,
+) -> WebSocket<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    complete_upgrade(stream, config).await.with_context(context)
+}
+
 /// Handler type for WebSocket connections.
 ///
 /// This is the signature of a function that handles a WebSocket connection.
@@ -297,6 +498,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use archimedes_core::CallerIdentityExt;
 
     fn make_ws_request() -> Request<()> {
         Request::builder()
@@ -462,4 +664,99 @@ mod tests {
         let protocols = get_websocket_protocols(&request);
         assert_eq!(protocols, vec!["chat", "json"]);
     }
+
+    #[tokio::test]
+    async fn test_handler_sees_connection_context() {
+        let mut request = make_ws_request();
+        request
+            .headers_mut()
+            .insert("x-tenant", "acme".parse().unwrap());
+        request
+            .headers_mut()
+            .insert("x-ignored", "secret".parse().unwrap());
+        request
+            .extensions_mut()
+            .insert(CallerIdentity::user("user-123", "alice@example.com"));
+
+        let remote_addr: SocketAddr = "192.0.2.7:51000".parse().unwrap();
+        let options = UpgradeOptions::new()
+            .remote_addr(remote_addr)
+            .secure(true)
+            .capture_header(HeaderName::from_static("x-tenant"));
+
+        let upgrade = prepare_upgrade_with(&request, &options);
+        assert!(upgrade.success);
+        assert_eq!(upgrade.response.status(), StatusCode::SWITCHING_PROTOCOLS);
+
+        let (server_io, _client_io) = tokio::io::duplex(1024);
+        let ws = complete_upgrade_with_context(
+            server_io,
+            WebSocketConfig::default(),
+            upgrade.context.unwrap(),
+        )
+        .await;
+
+        let handler = move |ws: WebSocket<tokio::io::DuplexStream>| async move {
+            let context = ws.context();
+            assert_eq!(context.identity().log_id(), "user:user-123");
+            assert_eq!(context.remote_addr(), Some(remote_addr));
+            assert!(context.is_secure());
+            assert_eq!(context.header("x-tenant"), Some("acme"));
+            assert!(context.header("x-ignored").is_none());
+        };
+        handler.handle(ws).await;
+    }
+
+    #[test]
+    fn test_custom_identity_extractor() {
+        let mut request = make_ws_request();
+        request
+            .headers_mut()
+            .insert("x-api-key-id", "key-1".parse().unwrap());
+
+        let options = UpgradeOptions::new().identity(|headers, _| {
+            headers
+                .get("x-api-key-id")
+                .and_then(|v| v.to_str().ok())
+                .map_or(CallerIdentity::Anonymous, |id| {
+                    CallerIdentity::api_key(id, "test key")
+                })
+        });
+
+        let upgrade = prepare_upgrade_with(&request, &options);
+        let context = upgrade.context.unwrap();
+        assert_eq!(context.identity().log_id(), "apikey:key-1");
+    }
+
+    #[test]
+    fn test_unauthorized_upgrade_rejected_before_101() {
+        let request = make_ws_request();
+        let options = UpgradeOptions::new().authorize(|context| match context.identity() {
+            CallerIdentity::Anonymous => Err("authentication required".to_string()),
+            _ => Ok(()),
+        });
+
+        let upgrade = prepare_upgrade_with(&request, &options);
+        assert!(!upgrade.success);
+        assert!(upgrade.context.is_none());
+        assert_eq!(upgrade.response.status(), StatusCode::FORBIDDEN);
+        assert!(upgrade
+            .response
+            .headers()
+            .get("Sec-WebSocket-Accept")
+            .is_none());
+    }
+
+    #[test]
+    fn test_prepare_upgrade_with_selects_protocol() {
+        let mut request = make_ws_request();
+        request
+            .headers_mut()
+            .insert("Sec-WebSocket-Protocol", "chat, json".parse().unwrap());
+
+        let options = UpgradeOptions::new().allowed_protocols(["json"]);
+        let upgrade = prepare_upgrade_with(&request, &options);
+        assert_eq!(upgrade.protocol.as_deref(), Some("json"));
+        assert_eq!(upgrade.context.unwrap().protocol(), Some("json"));
+    }
 }
@@ -82,7 +82,7 @@ pub use archimedes_tasks as tasks;
 /// ```
 pub mod prelude {
     pub use archimedes_core::{
-        CallerIdentity, Handler, RequestContext, RequestId, ThemisError, ThemisResult,
+        CallerIdentity, Handler, IntoResponse, RequestContext, RequestId, ThemisError, ThemisResult,
     };
 
     // Re-export DI types
@@ -90,12 +90,14 @@ pub mod prelude {
 
     // Re-export common extractors
     pub use archimedes_extract::{
-        Form, Header, Headers, Inject as InjectExtract, Json, JsonWithLimit, Path, Query, RawQuery,
+        Form, Header, Headers, Inject as InjectExtract, Json, JsonWithLimit, Path, Query, RawPath,
+        RawQuery,
     };
 
     // Re-export common response builders
     pub use archimedes_extract::response::{
-        ErrorResponse, HtmlResponse, JsonResponse, NoContent, Redirect, TextResponse,
+        ErrorResponse, HtmlResponse, JsonConfig, JsonResponse, NdJson, NoContent, Redirect,
+        TextResponse,
     };
 
     // Re-export handler macro
@@ -104,8 +106,9 @@ pub mod prelude {
     // Re-export WebSocket types
     pub use archimedes_ws::{
         CloseCode, CloseFrame, ConnectionId, ConnectionInfo, ConnectionManager,
-        ConnectionManagerConfig, ConnectionStats, ConnectionType, Message, WebSocket,
-        WebSocketConfig, WebSocketSender, WsError, WsResult,
+        ConnectionManagerConfig, ConnectionStats, ConnectionType, Message, MessageCounts,
+        UpgradeOptions, WebSocket, WebSocketConfig, WebSocketContext, WebSocketSender, WsError,
+        WsResult,
     };
 
     // Re-export SSE types
@@ -113,7 +116,7 @@ pub mod prelude {
 
     // Re-export background task types
     pub use archimedes_tasks::{
-        JobId, Scheduler, SchedulerConfig, SharedSpawner, Spawner, SpawnerConfig, TaskHandle,
-        TaskId, TaskInfo, TaskStatus,
+        AcceptedJob, JobContext, JobId, JobTracker, JobTrackerConfig, Scheduler, SchedulerConfig,
+        SharedSpawner, Spawner, SpawnerConfig, TaskHandle, TaskId, TaskInfo, TaskStatus,
     };
 }
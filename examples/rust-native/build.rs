@@ -0,0 +1,16 @@
+//! Generates typed operation constants from `openapi.yaml`.
+//!
+//! The generated `operations.rs` is included by `src/main.rs` as the `ops`
+//! module, so registering a handler under an operation the contract does not
+//! declare fails to compile.
+
+use archimedes_sentinel::{codegen, ArtifactLoader};
+
+fn main() {
+    println!("cargo:rerun-if-changed=openapi.yaml");
+
+    let contract = std::fs::read_to_string("openapi.yaml").expect("read openapi.yaml");
+    let artifact = ArtifactLoader::from_document(&contract).expect("parse openapi.yaml");
+    let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    codegen::generate_operation_consts(&artifact, out_dir).expect("generate operations.rs");
+}
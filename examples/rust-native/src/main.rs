@@ -9,6 +9,7 @@
 //! - Native Archimedes server setup with `Server::builder()`
 //! - Handler registration with `HandlerRegistry`
 //! - Route configuration with `router_mut().add_route()`
+//! - Operation constants generated from `openapi.yaml` by `build.rs`
 //! - Typed request/response handlers with `RequestContext`
 //! - Shared application state via `Arc`
 //!
@@ -32,6 +33,14 @@ use std::{
 use tracing::info;
 use uuid::Uuid;
 
+/// Operation constants generated from `openapi.yaml`.
+#[allow(dead_code)]
+mod ops {
+    include!(concat!(env!("OUT_DIR"), "/operations.rs"));
+}
+
+use ops::Operation;
+
 // =============================================================================
 // Types
 // =============================================================================
@@ -410,41 +419,71 @@ async fn delete_user_handler(
 /// Each handler is wrapped in a closure that captures the shared state
 /// and adapts the 3-argument handler to the 2-argument signature expected
 /// by the Archimedes framework.
+///
+/// The `match` over the generated [`Operation`] enum is exhaustive, so an
+/// operation added to `openapi.yaml` fails the build until it is handled.
 fn register_handlers(handlers: &mut HandlerRegistry, state: Arc<AppState>) {
-    // List users
-    let state_clone = Arc::clone(&state);
-    handlers.register("listUsers", move |ctx: RequestContext, req: ListUsersRequest| {
-        let state = Arc::clone(&state_clone);
-        async move { list_users_handler(ctx, req, state).await }
-    });
-
-    // Get user
-    let state_clone = Arc::clone(&state);
-    handlers.register("getUser", move |ctx: RequestContext, req: GetUserRequest| {
-        let state = Arc::clone(&state_clone);
-        async move { get_user_handler(ctx, req, state).await }
-    });
-
-    // Create user
-    let state_clone = Arc::clone(&state);
-    handlers.register("createUser", move |ctx: RequestContext, req: CreateUserRequest| {
-        let state = Arc::clone(&state_clone);
-        async move { create_user_handler(ctx, req, state).await }
-    });
-
-    // Update user
-    let state_clone = Arc::clone(&state);
-    handlers.register("updateUser", move |ctx: RequestContext, req: UpdateUserRequest| {
-        let state = Arc::clone(&state_clone);
-        async move { update_user_handler(ctx, req, state).await }
-    });
-
-    // Delete user
-    let state_clone = Arc::clone(&state);
-    handlers.register("deleteUser", move |ctx: RequestContext, req: DeleteUserRequest| {
-        let state = Arc::clone(&state_clone);
-        async move { delete_user_handler(ctx, req, state).await }
-    });
+    for &op in Operation::ALL {
+        let state = Arc::clone(&state);
+        match op {
+            Operation::ListUsers => {
+                handlers.register(
+                    ops::LIST_USERS,
+                    move |ctx: RequestContext, req: ListUsersRequest| {
+                        let state = Arc::clone(&state);
+                        async move { list_users_handler(ctx, req, state).await }
+                    },
+                );
+            }
+            Operation::GetUser => {
+                handlers.register(
+                    ops::GET_USER,
+                    move |ctx: RequestContext, req: GetUserRequest| {
+                        let state = Arc::clone(&state);
+                        async move { get_user_handler(ctx, req, state).await }
+                    },
+                );
+            }
+            Operation::CreateUser => {
+                handlers.register(
+                    ops::CREATE_USER,
+                    move |ctx: RequestContext, req: CreateUserRequest| {
+                        let state = Arc::clone(&state);
+                        async move { create_user_handler(ctx, req, state).await }
+                    },
+                );
+            }
+            Operation::UpdateUser => {
+                handlers.register(
+                    ops::UPDATE_USER,
+                    move |ctx: RequestContext, req: UpdateUserRequest| {
+                        let state = Arc::clone(&state);
+                        async move { update_user_handler(ctx, req, state).await }
+                    },
+                );
+            }
+            Operation::DeleteUser => {
+                handlers.register(
+                    ops::DELETE_USER,
+                    move |ctx: RequestContext, req: DeleteUserRequest| {
+                        let state = Arc::clone(&state);
+                        async move { delete_user_handler(ctx, req, state).await }
+                    },
+                );
+            }
+        }
+    }
+}
+
+/// Adds a route for every operation in the contract.
+fn add_routes(server: &mut Server) {
+    for &op in Operation::ALL {
+        let method =
+            Method::from_bytes(op.method().as_bytes()).expect("contract methods are valid");
+        server
+            .router_mut()
+            .add_route(method, op.path(), op.as_str());
+    }
 }
 
 // =============================================================================
@@ -482,19 +521,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .service_name("example-rust-native")
         .service_version(env!("CARGO_PKG_VERSION"))
         .handlers(handlers)
+        .diagnostics_endpoint(true)
         .build();
 
-    // Configure routes (mapping paths to operation IDs)
-    server.router_mut().add_route(Method::GET, "/users", "listUsers");
-    server.router_mut().add_route(Method::POST, "/users", "createUser");
-    server.router_mut().add_route(Method::GET, "/users/{userId}", "getUser");
-    server.router_mut().add_route(Method::PUT, "/users/{userId}", "updateUser");
-    server.router_mut().add_route(Method::DELETE, "/users/{userId}", "deleteUser");
+    // Configure routes (mapping paths to operation IDs) from the contract
+    add_routes(&mut server);
 
     info!("Rust example service (native Archimedes) listening on {}", addr);
     info!("Endpoints:");
     info!("  GET    /health        - Health check (built-in)");
     info!("  GET    /ready         - Readiness check (built-in)");
+    info!("  GET    /-/diagnostics - Diagnostics report (built-in)");
     info!("  GET    /users         - List all users");
     info!("  POST   /users         - Create a new user");
     info!("  GET    /users/{{id}}    - Get user by ID");
@@ -514,6 +551,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use archimedes_server::HandlerCoverage;
 
     // -------------------------------------------------------------------------
     // State Tests
@@ -725,12 +763,16 @@ mod tests {
 
         register_handlers(&mut handlers, state);
 
-        assert!(handlers.contains("listUsers"));
-        assert!(handlers.contains("getUser"));
-        assert!(handlers.contains("createUser"));
-        assert!(handlers.contains("updateUser"));
-        assert!(handlers.contains("deleteUser"));
+        assert!(handlers.contains(ops::LIST_USERS));
+        assert!(handlers.contains(ops::GET_USER));
+        assert!(handlers.contains(ops::CREATE_USER));
+        assert!(handlers.contains(ops::UPDATE_USER));
+        assert!(handlers.contains(ops::DELETE_USER));
         assert_eq!(handlers.len(), 5);
+
+        let coverage = HandlerCoverage::for_operations::<Operation, _>(handlers.operation_ids());
+        assert!(coverage.missing.is_empty());
+        assert!(coverage.unknown.is_empty());
     }
 
     // -------------------------------------------------------------------------
@@ -758,6 +800,30 @@ mod tests {
         assert!(server.router().has_operation("getUser"));
     }
 
+    #[test]
+    fn test_server_diagnostics() {
+        let state = Arc::new(AppState::default());
+        let mut handlers = HandlerRegistry::new();
+        register_handlers(&mut handlers, state);
+
+        let mut server = Server::builder()
+            .http_addr("127.0.0.1:8080")
+            .service_name("example-rust-native")
+            .handlers(handlers)
+            .build();
+
+        add_routes(&mut server);
+
+        let diagnostics = serde_json::to_value(server.diagnostics()).unwrap();
+        assert_eq!(diagnostics["service"], "example-rust-native");
+        assert_eq!(diagnostics["listeners"][0]["name"], "http");
+        assert_eq!(diagnostics["listeners"][0]["address"], "127.0.0.1:8080");
+        assert_eq!(diagnostics["handlers"]["operations"], 5);
+        assert_eq!(diagnostics["handlers"]["handled"], 5);
+        assert_eq!(diagnostics["handlers"]["missing"], serde_json::json!([]));
+        assert_eq!(diagnostics["handlers"]["unknown"], serde_json::json!([]));
+    }
+
     // -------------------------------------------------------------------------
     // User Model Tests
     // -------------------------------------------------------------------------